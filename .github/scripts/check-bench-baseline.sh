@@ -0,0 +1,61 @@
+#!/bin/bash
+# Compare criterion results with the committed baseline
+# Usage: check-bench-baseline.sh [--update] [criterion_dir] [baseline_file]
+#
+# Reads the mean time of each benchmark from criterion_dir (default:
+# target/criterion, written by `cargo bench`) and fails when one is slower
+# than its baseline by more than the baseline's tolerance. With --update,
+# rewrites the baseline's times from the results instead.
+
+set -e
+
+UPDATE="false"
+if [[ "$1" == "--update" ]]; then
+    UPDATE="true"
+    shift
+fi
+
+CRITERION_DIR="${1:-target/criterion}"
+BASELINE="${2:-benches/baseline.json}"
+
+if [[ ! -d "$CRITERION_DIR" ]]; then
+    echo "No criterion results in $CRITERION_DIR (run cargo bench first)" >&2
+    exit 1
+fi
+
+# {"<benchmark id>": <mean ns>, ...} of the latest run
+RESULTS=$(find "$CRITERION_DIR" -path '*/new/benchmark.json' | sort | while read -r bench; do
+    estimates="$(dirname "$bench")/estimates.json"
+    jq -n --slurpfile b "$bench" --slurpfile e "$estimates" \
+        '{($b[0].full_id): ($e[0].mean.point_estimate | round)}'
+done | jq -s 'add // {}')
+
+if [[ "$UPDATE" == "true" ]]; then
+    jq --argjson results "$RESULTS" '.benchmarks = (.benchmarks + $results)' "$BASELINE" > "$BASELINE.tmp"
+    mv "$BASELINE.tmp" "$BASELINE"
+    echo "Updated $BASELINE with $(jq 'length' <<< "$RESULTS") benchmarks"
+    exit 0
+fi
+
+TOLERANCE=$(jq '.tolerance_percent' "$BASELINE")
+FAILED=0
+
+while IFS=$'\t' read -r id baseline_ns; do
+    current_ns=$(jq --arg id "$id" '.[$id] // empty' <<< "$RESULTS")
+    if [[ -z "$current_ns" ]]; then
+        echo "SKIP  $id (not run)"
+        continue
+    fi
+    change=$(jq -n "($current_ns - $baseline_ns) * 100 / $baseline_ns | round")
+    if (( change > TOLERANCE )); then
+        echo "FAIL  $id: ${current_ns}ns vs ${baseline_ns}ns baseline (+${change}%)"
+        FAILED=$((FAILED + 1))
+    else
+        echo "OK    $id: ${current_ns}ns vs ${baseline_ns}ns baseline (${change}%)"
+    fi
+done < <(jq -r '.benchmarks | to_entries[] | "\(.key)\t\(.value)"' "$BASELINE")
+
+if (( FAILED > 0 )); then
+    echo "$FAILED benchmarks regressed by more than ${TOLERANCE}%" >&2
+    exit 1
+fi
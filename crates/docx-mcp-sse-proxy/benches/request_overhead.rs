@@ -0,0 +1,108 @@
+//! Latency the proxy adds to an MCP request.
+//!
+//! Runs the proxy binary in front of an in-process backend that answers
+//! every POST /mcp with a small JSON-RPC result, and times the same
+//! `tools/call` request sent to the backend directly and through the proxy
+//! (API key auth, tenant header injection, session routing, response
+//! streaming). The difference between the two is the proxy's overhead.
+//!
+//! Run with `cargo bench -p docx-mcp-sse-proxy`.
+
+use std::net::TcpListener as StdTcpListener;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use axum::routing::post;
+use axum::Router;
+use criterion::{criterion_group, criterion_main, Criterion};
+use reqwest::Client;
+use tokio::runtime::Runtime;
+
+const API_KEY: &str = "bench-key";
+
+/// A `tools/call` request of typical size.
+const REQUEST: &str = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"query","arguments":{"doc_id":"0123456789ab","path":"/body/paragraph[*]"}}}"#;
+
+const RESPONSE: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"content":[{"type":"text","text":"[]"}]}}"#;
+
+/// The proxy process, killed when dropped.
+struct ProxyProcess(Child);
+
+impl Drop for ProxyProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn free_port() -> u16 {
+    StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+async fn start_backend() -> String {
+    let app = Router::new().route(
+        "/mcp",
+        post(|| async { ([("content-type", "application/json")], RESPONSE) }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    url
+}
+
+async fn start_proxy(backend_url: &str, client: &Client) -> (ProxyProcess, String) {
+    let port = free_port();
+    let child = Command::new(env!("CARGO_BIN_EXE_docx-mcp-sse-proxy"))
+        .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+        .args(["--mcp-backend-url", backend_url])
+        .args(["--auth-provider", "env", "--api-keys", &format!("acme={API_KEY}")])
+        .env("RUST_LOG", "error")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to start the proxy");
+    let process = ProxyProcess(child);
+    let url = format!("http://127.0.0.1:{port}");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if client.get(format!("{url}/health")).send().await.is_ok() {
+            return (process, url);
+        }
+        assert!(Instant::now() < deadline, "the proxy did not start");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+async fn call(client: &Client, url: &str) {
+    let response = client
+        .post(format!("{url}/mcp"))
+        .bearer_auth(API_KEY)
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .body(REQUEST)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success(), "{}", response.status());
+    response.bytes().await.unwrap();
+}
+
+fn request_overhead(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let client = Client::new();
+    let backend_url = rt.block_on(start_backend());
+    let (_proxy, proxy_url) = rt.block_on(start_proxy(&backend_url, &client));
+
+    let mut group = c.benchmark_group("proxy_overhead");
+    group.bench_function("direct", |b| b.to_async(&rt).iter(|| call(&client, &backend_url)));
+    group.bench_function("proxied", |b| b.to_async(&rt).iter(|| call(&client, &proxy_url)));
+    group.finish();
+}
+
+criterion_group!(benches, request_overhead);
+criterion_main!(benches);
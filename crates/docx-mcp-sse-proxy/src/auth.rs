@@ -3,7 +3,6 @@
 //! Validates Personal Access Tokens against a D1 database using the
 //! Cloudflare REST API. Includes a moka cache for performance.
 
-use std::sync::Arc;
 use std::time::Duration;
 
 use moka::future::Cache;
@@ -268,9 +267,6 @@ impl PatValidator {
     }
 }
 
-/// Shared validator wrapped in Arc.
-pub type SharedPatValidator = Arc<PatValidator>;
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,134 @@
+//! Request body limits and streaming uploads.
+//!
+//! Small bodies are buffered so the proxy can inspect them (initialize
+//! detection) and replay them (retries, session recovery). Bodies announced
+//! with a `Content-Length` above the streaming threshold are piped to the
+//! backend as they arrive instead, so large image-heavy documents don't sit in
+//! proxy memory. Streamed requests are sent exactly once.
+//!
+//! The maximum size is resolved per request: a tenant override wins over the
+//! longest matching route prefix, which wins over the global default.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::body::Body;
+use futures::StreamExt;
+
+use crate::config::Config;
+
+/// Body size policy for forwarded requests.
+#[derive(Debug, Clone)]
+pub struct BodyLimits {
+    default_limit: usize,
+    /// (path prefix, limit), sorted longest prefix first.
+    routes: Vec<(String, usize)>,
+    tenants: HashMap<String, usize>,
+    stream_threshold: usize,
+}
+
+impl BodyLimits {
+    pub fn from_config(config: &Config) -> Self {
+        let mut routes = config.route_body_limits.clone();
+        routes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Self {
+            default_limit: config.max_body_bytes,
+            routes,
+            tenants: config.tenant_body_limits.iter().cloned().collect(),
+            stream_threshold: config.stream_body_threshold_bytes,
+        }
+    }
+
+    /// Maximum body size accepted for a request to `path` from `tenant_id`.
+    pub fn limit_for(&self, path: &str, tenant_id: &str) -> usize {
+        if let Some(limit) = self.tenants.get(tenant_id) {
+            return *limit;
+        }
+        self.routes
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .map(|(_, limit)| *limit)
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Whether a body of the announced length should be streamed rather than buffered.
+    /// Bodies without a `Content-Length` are buffered (they are usually small).
+    pub fn should_stream(&self, content_length: Option<u64>) -> bool {
+        content_length.is_some_and(|len| len > self.stream_threshold as u64)
+    }
+}
+
+/// Wrap a client body into a backend body that aborts once more than `limit`
+/// bytes have been read. The returned flag is set when the limit was hit, so
+/// the caller can report 413 instead of a generic backend error.
+pub fn limited_stream(body: Body, limit: usize) -> (reqwest::Body, Arc<AtomicBool>) {
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&exceeded);
+    let mut total = 0usize;
+
+    let stream = body.into_data_stream().map(move |chunk| {
+        let chunk = chunk.map_err(std::io::Error::other)?;
+        total += chunk.len();
+        if total > limit {
+            flag.store(true, Ordering::Relaxed);
+            return Err(std::io::Error::other(format!(
+                "request body exceeds {} bytes",
+                limit
+            )));
+        }
+        Ok(chunk)
+    });
+
+    (reqwest::Body::wrap_stream(stream), exceeded)
+}
+
+/// Parse a `key=bytes` pair from the command line.
+pub fn parse_limit(s: &str) -> Result<(String, usize), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=BYTES, got '{}'", s))?;
+    let limit = value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid byte count '{}': {}", value, e))?;
+    Ok((key.trim().to_string(), limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> BodyLimits {
+        BodyLimits {
+            default_limit: 10,
+            routes: vec![("/mcp/upload".into(), 1000), ("/mcp".into(), 100)],
+            tenants: HashMap::from([("big".to_string(), 5000)]),
+            stream_threshold: 50,
+        }
+    }
+
+    #[test]
+    fn test_limit_resolution() {
+        let limits = limits();
+        assert_eq!(limits.limit_for("/mcp/upload/x", "t"), 1000);
+        assert_eq!(limits.limit_for("/mcp", "t"), 100);
+        assert_eq!(limits.limit_for("/other", "t"), 10);
+        assert_eq!(limits.limit_for("/mcp", "big"), 5000);
+    }
+
+    #[test]
+    fn test_should_stream() {
+        let limits = limits();
+        assert!(!limits.should_stream(None));
+        assert!(!limits.should_stream(Some(50)));
+        assert!(limits.should_stream(Some(51)));
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit("tenant-a=42"), Ok(("tenant-a".to_string(), 42)));
+        assert!(parse_limit("no-equals").is_err());
+        assert!(parse_limit("a=lots").is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Canary routing of tenants to an alternate backend.
+//!
+//! A new backend version is rolled out by sending some tenants to
+//! `CANARY_BACKEND_URL`: the tenants listed in `CANARY_TENANTS`, then a
+//! stable percentage of the others. A client can also ask for a backend with
+//! the `X-Canary` header (`1`/`true` or `0`/`false`), e.g. to test a
+//! release before any tenant is routed to it.
+//!
+//! Canary requests are tracked apart from the primary backend's. When their
+//! error rate (failed forwards and 5xx responses) goes over the threshold
+//! within a window, the canary is rolled back: every tenant goes to the
+//! primary backend again, whose 404 on the canary's sessions triggers the
+//! usual session recovery. A rolled back canary stays so until the
+//! configuration is reloaded.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::flags::rollout_bucket;
+
+/// Header letting a client pick the canary (`1`) or primary (`0`) backend.
+pub const X_CANARY: &str = "x-canary";
+
+/// Canary rules and the health of the traffic they routed.
+#[derive(Debug)]
+pub struct CanaryRouter {
+    backend_url: Option<String>,
+    tenants: HashSet<String>,
+    percent: u8,
+    /// Error rate, in percent, above which the canary is rolled back.
+    max_error_rate: u8,
+    /// Requests a window needs before its error rate is acted on.
+    min_requests: u64,
+    window: Duration,
+    health: Mutex<CanaryHealth>,
+}
+
+#[derive(Debug)]
+struct CanaryHealth {
+    window_start: Instant,
+    requests: u64,
+    errors: u64,
+    rolled_back: bool,
+}
+
+/// Canary state reported by the upstream health check.
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStatus {
+    pub rolled_back: bool,
+    /// Requests and errors of the current window.
+    pub requests: u64,
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_healthy: Option<bool>,
+}
+
+impl CanaryRouter {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            backend_url: config
+                .canary_backend_url
+                .as_deref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+            tenants: config.canary_tenants.iter().cloned().collect(),
+            percent: config.canary_percent,
+            max_error_rate: config.canary_max_error_rate,
+            min_requests: config.canary_min_requests.max(1),
+            window: Duration::from_secs(config.canary_window_secs.max(1)),
+            health: Mutex::new(CanaryHealth {
+                window_start: Instant::now(),
+                requests: 0,
+                errors: 0,
+                rolled_back: false,
+            }),
+        }
+    }
+
+    /// The canary backend URL, if one is configured.
+    pub fn backend_url(&self) -> Option<&str> {
+        self.backend_url.as_deref()
+    }
+
+    /// The canary backend URL when a request of `tenant_id` goes to it,
+    /// `None` when it goes to the primary backend.
+    pub fn route(&self, tenant_id: &str, headers: &HeaderMap) -> Option<&str> {
+        let url = self.backend_url.as_deref()?;
+        if self.health.lock().unwrap().rolled_back {
+            return None;
+        }
+        let requested = headers
+            .get(X_CANARY)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_ascii_lowercase());
+        let on_canary = match requested.as_deref() {
+            Some("1" | "true") => true,
+            Some("0" | "false") => false,
+            _ => {
+                self.tenants.contains(tenant_id)
+                    || rollout_bucket(tenant_id, "canary") < self.percent
+            }
+        };
+        on_canary.then_some(url)
+    }
+
+    /// Record the outcome of a request routed to the canary, rolling it back
+    /// when the window's error rate goes over the threshold.
+    pub fn record(&self, ok: bool) {
+        let mut health = self.health.lock().unwrap();
+        if health.rolled_back {
+            return;
+        }
+        if health.window_start.elapsed() >= self.window {
+            health.window_start = Instant::now();
+            health.requests = 0;
+            health.errors = 0;
+        }
+        health.requests += 1;
+        if !ok {
+            health.errors += 1;
+        }
+        if health.requests >= self.min_requests
+            && health.errors * 100 > health.requests * self.max_error_rate as u64
+        {
+            health.rolled_back = true;
+            warn!(
+                requests = health.requests,
+                errors = health.errors,
+                max_error_rate = self.max_error_rate,
+                "Canary error rate over threshold, routing every tenant to the primary backend"
+            );
+        }
+    }
+
+    /// Current canary state, without the backend probe.
+    pub fn status(&self) -> CanaryStatus {
+        let health = self.health.lock().unwrap();
+        CanaryStatus {
+            rolled_back: health.rolled_back,
+            requests: health.requests,
+            errors: health.errors,
+            backend_healthy: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use clap::Parser;
+
+    fn router(args: &[&str]) -> CanaryRouter {
+        let base = [
+            "docx-mcp-proxy",
+            "--mcp-backend-url=http://primary:5000",
+            "--canary-backend-url=http://canary:5000/",
+        ];
+        CanaryRouter::from_config(&Config::try_parse_from(base.iter().chain(args)).unwrap())
+    }
+
+    #[test]
+    fn test_routes_listed_tenants_and_header_overrides() {
+        let router = router(&["--canary-tenants=acme,beta"]);
+        let none = HeaderMap::new();
+        assert_eq!(router.route("acme", &none), Some("http://canary:5000"));
+        assert_eq!(router.route("other", &none), None);
+
+        let mut opt_out = HeaderMap::new();
+        opt_out.insert(X_CANARY, HeaderValue::from_static("0"));
+        assert_eq!(router.route("acme", &opt_out), None);
+        let mut opt_in = HeaderMap::new();
+        opt_in.insert(X_CANARY, HeaderValue::from_static("true"));
+        assert_eq!(router.route("other", &opt_in), Some("http://canary:5000"));
+
+        let all = self::router(&["--canary-percent=100"]);
+        assert!(all.route("anyone", &none).is_some());
+    }
+
+    #[test]
+    fn test_rolls_back_over_error_rate() {
+        let router = router(&[
+            "--canary-tenants=acme",
+            "--canary-max-error-rate=20",
+            "--canary-min-requests=10",
+        ]);
+        let none = HeaderMap::new();
+        // 2 errors in 10 requests is at the threshold, not over it
+        for i in 0..10 {
+            router.record(i >= 2);
+        }
+        assert!(router.route("acme", &none).is_some());
+
+        router.record(false);
+        assert!(router.status().rolled_back);
+        assert_eq!(router.route("acme", &none), None);
+    }
+}
@@ -1,4 +1,10 @@
 use clap::Parser;
+use docx_storage_core::LogFormat;
+
+use crate::body::parse_limit;
+use crate::mirror::parse_percent;
+use crate::provider::{parse_api_key, AuthProviderKind};
+use crate::retry::parse_retries;
 
 /// Configuration for the docx-mcp-proxy server.
 #[derive(Parser, Debug, Clone)]
@@ -17,6 +23,59 @@ pub struct Config {
     #[arg(long, env = "MCP_BACKEND_URL")]
     pub mcp_backend_url: String,
 
+    /// Secondary backend receiving a copy of sampled sessions, whose
+    /// responses are discarded (e.g. a new backend version before cutover)
+    #[arg(long, env = "MIRROR_BACKEND_URL")]
+    pub mirror_backend_url: Option<String>,
+
+    /// Percentage of client sessions mirrored to MIRROR_BACKEND_URL
+    #[arg(long, default_value = "100", env = "MIRROR_PERCENT", value_parser = parse_percent)]
+    pub mirror_percent: u8,
+
+    /// Alternate backend receiving canary tenants (e.g. a new backend version)
+    #[arg(long, env = "CANARY_BACKEND_URL")]
+    pub canary_backend_url: Option<String>,
+
+    /// Tenants always routed to the canary backend, comma-separated
+    #[arg(long, env = "CANARY_TENANTS", value_delimiter = ',')]
+    pub canary_tenants: Vec<String>,
+
+    /// Percentage of the other tenants routed to the canary backend
+    #[arg(long, default_value = "0", env = "CANARY_PERCENT", value_parser = parse_percent)]
+    pub canary_percent: u8,
+
+    /// Error rate of canary requests, in percent, above which every tenant
+    /// is routed back to the primary backend until the configuration is reloaded
+    #[arg(long, default_value = "5", env = "CANARY_MAX_ERROR_RATE", value_parser = parse_percent)]
+    pub canary_max_error_rate: u8,
+
+    /// Canary requests needed in a window before its error rate is acted on
+    #[arg(long, default_value = "20", env = "CANARY_MIN_REQUESTS")]
+    pub canary_min_requests: u64,
+
+    /// Length of the windows canary error rates are measured over, in seconds
+    #[arg(long, default_value = "300", env = "CANARY_WINDOW_SECS")]
+    pub canary_window_secs: u64,
+
+    /// Single-user desktop mode: every request goes to the local (empty)
+    /// tenant and D1 is never contacted, even when its credentials are set
+    /// (no authentication, feature flags or scheduled jobs)
+    #[arg(long, env = "LOCAL_MODE")]
+    pub local_mode: bool,
+
+    /// Where bearer tokens are checked: auto (d1 when its credentials are
+    /// set, none otherwise), d1, file, env or none
+    #[arg(long, default_value = "auto", env = "AUTH_PROVIDER")]
+    pub auth_provider: AuthProviderKind,
+
+    /// JSON file of API keys for the file auth provider
+    #[arg(long, env = "AUTH_KEYS_FILE")]
+    pub auth_keys_file: Option<std::path::PathBuf>,
+
+    /// API keys for the env auth provider as TENANT_ID=KEY, comma-separated
+    #[arg(long, env = "API_KEYS", value_delimiter = ',', value_parser = parse_api_key, hide_env_values = true)]
+    pub api_keys: Vec<(String, String)>,
+
     /// Cloudflare Account ID
     #[arg(long, env = "CLOUDFLARE_ACCOUNT_ID")]
     pub cloudflare_account_id: Option<String>,
@@ -37,6 +96,76 @@ pub struct Config {
     #[arg(long, default_value = "60", env = "PAT_NEGATIVE_CACHE_TTL_SECS")]
     pub pat_negative_cache_ttl_secs: u64,
 
+    /// Feature flag cache TTL in seconds (flags are read from D1)
+    #[arg(long, default_value = "60", env = "FEATURE_FLAG_CACHE_TTL_SECS")]
+    pub feature_flag_cache_ttl_secs: u64,
+
+    /// Default maximum request body size in bytes
+    #[arg(long, default_value = "10485760", env = "MAX_BODY_BYTES")]
+    pub max_body_bytes: usize,
+
+    /// Per-route body limits as PATH_PREFIX=BYTES, comma-separated
+    #[arg(long, env = "ROUTE_BODY_LIMITS", value_delimiter = ',', value_parser = parse_limit)]
+    pub route_body_limits: Vec<(String, usize)>,
+
+    /// Per-tenant body limits as TENANT_ID=BYTES, comma-separated (override route limits)
+    #[arg(long, env = "TENANT_BODY_LIMITS", value_delimiter = ',', value_parser = parse_limit)]
+    pub tenant_body_limits: Vec<(String, usize)>,
+
+    /// Bodies with a Content-Length above this are streamed to the backend
+    /// instead of buffered (streamed requests are not retried)
+    #[arg(long, default_value = "1048576", env = "STREAM_BODY_THRESHOLD_BYTES")]
+    pub stream_body_threshold_bytes: usize,
+
+    /// Retries for transient backend failures (502/503, connection errors).
+    /// The default budget (~27.5s) covers a .NET backend cold start.
+    #[arg(long, default_value = "8", env = "BACKEND_MAX_RETRIES")]
+    pub backend_max_retries: u32,
+
+    /// Per-method retry counts as JSONRPC_METHOD=RETRIES, comma-separated
+    /// (e.g. `tools/call=2,initialize=12`)
+    #[arg(long, env = "BACKEND_METHOD_RETRIES", value_delimiter = ',', value_parser = parse_retries)]
+    pub backend_method_retries: Vec<(String, u32)>,
+
+    /// Delay before the first backend retry, in milliseconds (doubles per attempt)
+    #[arg(long, default_value = "500", env = "BACKEND_INITIAL_BACKOFF_MS")]
+    pub backend_initial_backoff_ms: u64,
+
+    /// Cap on the delay between backend retries, in milliseconds
+    #[arg(long, default_value = "5000", env = "BACKEND_MAX_BACKOFF_MS")]
+    pub backend_max_backoff_ms: u64,
+
+    /// Timeout for each individual backend request, in seconds
+    #[arg(long, default_value = "30", env = "BACKEND_TIMEOUT_SECS")]
+    pub backend_timeout_secs: u64,
+
+    /// Seconds between SSE keepalive comments on quiet streams (0 disables)
+    #[arg(long, default_value = "15", env = "SSE_HEARTBEAT_SECS")]
+    pub sse_heartbeat_secs: u64,
+
+    /// Close a POST response stream after this many seconds without backend data (0 disables)
+    #[arg(long, default_value = "120", env = "SSE_IDLE_TIMEOUT_SECS")]
+    pub sse_idle_timeout_secs: u64,
+
+    /// File the recovered client sessions are saved to and reloaded from on
+    /// startup (in memory only when unset)
+    #[arg(long, env = "SESSION_STORE_FILE")]
+    pub session_store_file: Option<std::path::PathBuf>,
+
+    /// SSE events buffered per client session and replayed when its GET
+    /// stream reconnects with Last-Event-ID (0 leaves resumption to the backend)
+    #[arg(long, default_value = "0", env = "SSE_REPLAY_EVENTS")]
+    pub sse_replay_events: usize,
+
+    /// Directory the SSE replay buffers are persisted to (in memory only when unset)
+    #[arg(long, env = "SSE_REPLAY_DIR")]
+    pub sse_replay_dir: Option<std::path::PathBuf>,
+
+    /// Seconds a cursor, selection or agent activity stays on /presence
+    /// without being refreshed (0 disables presence)
+    #[arg(long, default_value = "60", env = "PRESENCE_TTL_SECS")]
+    pub presence_ttl_secs: u64,
+
     /// Resource server URL (for OAuth protected resource metadata)
     #[arg(long, env = "RESOURCE_URL")]
     pub resource_url: Option<String>,
@@ -44,4 +173,51 @@ pub struct Config {
     /// Authorization server URL (for OAuth discovery)
     #[arg(long, env = "AUTH_SERVER_URL")]
     pub auth_server_url: Option<String>,
+
+    /// Seconds between polls for due scheduled jobs (0 disables the scheduler; requires D1)
+    #[arg(long, default_value = "60", env = "SCHEDULER_POLL_SECS")]
+    pub scheduler_poll_secs: u64,
+
+    /// Webhook notified when a scheduled job fails, for jobs without their own
+    #[arg(long, env = "SCHEDULER_ALERT_WEBHOOK")]
+    pub scheduler_alert_webhook: Option<String>,
+
+    /// Alert once a job has failed this many times in a row
+    #[arg(long, default_value = "1", env = "SCHEDULER_ALERT_AFTER")]
+    pub scheduler_alert_after: u32,
+
+    /// Runs kept in the history of each scheduled job
+    #[arg(long, default_value = "50", env = "SCHEDULER_RUN_HISTORY")]
+    pub scheduler_run_history: u32,
+
+    /// Log output format: text or json (one object per line, with the
+    /// request, tenant and session IDs of the enclosing spans)
+    #[arg(long, default_value = "text", env = "LOG_FORMAT")]
+    pub log_format: LogFormat,
+
+    /// TOML configuration file (keys are the long option names). Flags and
+    /// environment variables take precedence over it; it is reloaded on
+    /// SIGHUP and when it changes
+    #[arg(long, env = "CONFIG_FILE")]
+    pub config: Option<std::path::PathBuf>,
+}
+
+impl Config {
+    /// Account ID, API token and database ID of D1, when all are set and the
+    /// proxy isn't in local mode.
+    pub fn d1_credentials(&self) -> Option<(String, String, String)> {
+        if self.local_mode {
+            return None;
+        }
+        match (
+            self.cloudflare_account_id.clone(),
+            self.cloudflare_api_token.clone(),
+            self.d1_database_id.clone(),
+        ) {
+            (Some(account_id), Some(api_token), Some(database_id)) => {
+                Some((account_id, api_token, database_id))
+            }
+            _ => None,
+        }
+    }
 }
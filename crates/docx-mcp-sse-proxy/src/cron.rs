@@ -0,0 +1,244 @@
+//! Minimal cron expression parser for scheduled jobs.
+//!
+//! Supports the classic five fields (minute, hour, day of month, month, day
+//! of week) with `*`, lists, ranges and steps, month and weekday names, and
+//! the `@hourly`/`@daily`/`@weekly`/`@monthly`/`@yearly` shorthands.
+//! Schedules are evaluated in UTC. As in Vixie cron, when both the day of
+//! month and the day of week are restricted, a day matching either runs.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+/// Upper bound on search steps, so impossible dates (`0 0 30 2 *`) end.
+const MAX_SEARCH_STEPS: usize = 10_000;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed cron schedule. Each field is a bitset of allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = match expr.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(format!("Unknown cron shorthand '{}'", expr))
+            }
+            _ => expr.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Cron expression '{}' must have 5 fields (minute hour day month weekday)",
+                expr
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, &WEEKDAYS)?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, &[])?,
+            hours: parse_field(fields[1], 0, 23, &[])?,
+            days_of_month: parse_field(fields[2], 1, 31, &[])?,
+            months: parse_field(fields[3], 1, 12, &MONTHS)?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    /// The first time strictly after `after` (at minute precision) that matches.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            + Duration::minutes(1);
+
+        for _ in 0..MAX_SEARCH_STEPS {
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(&t) {
+                t = Utc
+                    .with_ymd_and_hms(t.year(), t.month(), t.day(), 0, 0, 0)
+                    .single()?
+                    + Duration::days(1);
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        let dom = has(self.days_of_month, t.day());
+        let dow = has(self.days_of_week, t.weekday().num_days_from_sunday());
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse one field (`*`, `5`, `1-5`, `*/15`, `mon-fri`, `1,15`) into a bitset.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid step '{}' in cron field '{}'", step, field))?;
+                if step == 0 {
+                    return Err(format!("Step must be positive in cron field '{}'", field));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (value(a, names, min)?, value(b, names, min)?)
+        } else {
+            let v = value(range, names, min)?;
+            // "5/10" means from 5 to the end of the range
+            (v, if step > 1 { max } else { v })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!(
+                "Cron field '{}' is out of range {}-{}",
+                field, min, max
+            ));
+        }
+
+        let mut v = start;
+        while v <= end {
+            bits |= 1 << v;
+            v += step;
+        }
+    }
+    Ok(bits)
+}
+
+fn value(token: &str, names: &[&str], min: u32) -> Result<u32, String> {
+    if let Ok(v) = token.parse::<u32>() {
+        return Ok(v);
+    }
+    names
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(token))
+        .map(|i| i as u32 + min)
+        .ok_or_else(|| format!("Invalid cron value '{}'", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_every_fifteen_minutes() {
+        let cron = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-03-10T10:07:42Z")),
+            Some(at("2026-03-10T10:15:00Z"))
+        );
+        assert_eq!(
+            cron.next_after(at("2026-03-10T10:45:00Z")),
+            Some(at("2026-03-10T11:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_weekdays_with_names() {
+        // 2026-03-13 is a Friday
+        let cron = CronSchedule::parse("30 8 * * mon-fri").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-03-13T09:00:00Z")),
+            Some(at("2026-03-16T08:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_shorthands_and_month_rollover() {
+        let cron = CronSchedule::parse("@monthly").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-12-15T00:00:00Z")),
+            Some(at("2027-01-01T00:00:00Z"))
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("0 0 * * sun").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // The 1st, or any Monday
+        let cron = CronSchedule::parse("0 12 1 * 1").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-03-02T13:00:00Z")),
+            Some(at("2026-03-09T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("@often").is_err());
+        assert!(CronSchedule::parse("0 0 30 2 *")
+            .unwrap()
+            .next_after(at("2026-01-01T00:00:00Z"))
+            .is_none());
+    }
+}
@@ -25,6 +25,15 @@ pub enum ProxyError {
     #[error("Invalid JSON: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("Request body exceeds the {0} byte limit")]
+    PayloadTooLarge(usize),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Feature '{0}' is not enabled for this tenant")]
+    FeatureDisabled(String),
+
     #[error("Session recovery failed: {0}")]
     SessionRecoveryFailed(String),
 
@@ -45,15 +54,10 @@ pub fn set_resource_metadata_url(url: Option<String>) {
     });
 }
 
-impl IntoResponse for ProxyError {
-    fn into_response(self) -> Response {
-        #[derive(Serialize)]
-        struct ErrorBody {
-            error: String,
-            code: &'static str,
-        }
-
-        let (status, code) = match &self {
+impl ProxyError {
+    /// HTTP status and machine-readable code for this error.
+    pub fn status_and_code(&self) -> (StatusCode, &'static str) {
+        match self {
             ProxyError::Unauthorized => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
             ProxyError::InvalidToken => (StatusCode::UNAUTHORIZED, "INVALID_TOKEN"),
             ProxyError::D1Error(_) => (StatusCode::BAD_GATEWAY, "D1_ERROR"),
@@ -64,9 +68,40 @@ impl IntoResponse for ProxyError {
             ProxyError::SessionRecoveryFailed(_) => {
                 (StatusCode::BAD_GATEWAY, "SESSION_RECOVERY_FAILED")
             }
+            ProxyError::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE"),
+            ProxyError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "INVALID_REQUEST"),
+            ProxyError::FeatureDisabled(_) => (StatusCode::FORBIDDEN, "FEATURE_DISABLED"),
             ProxyError::JsonError(_) => (StatusCode::BAD_REQUEST, "INVALID_JSON"),
             ProxyError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
-        };
+        }
+    }
+
+    /// JSON-RPC error code used when the error is reported to an MCP client.
+    /// Server-side failures use the implementation-defined -32000..-32099 range.
+    pub fn jsonrpc_code(&self) -> i64 {
+        match self {
+            ProxyError::BackendError(_)
+            | ProxyError::BackendUnavailable(_, _)
+            | ProxyError::SessionRecoveryFailed(_) => -32000,
+            ProxyError::Unauthorized | ProxyError::InvalidToken => -32001,
+            ProxyError::D1Error(_) => -32002,
+            ProxyError::FeatureDisabled(_) => -32003,
+            ProxyError::PayloadTooLarge(_) | ProxyError::InvalidRequest(_) => -32600,
+            ProxyError::JsonError(_) => -32700,
+            ProxyError::Internal(_) => -32603,
+        }
+    }
+}
+
+impl IntoResponse for ProxyError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct ErrorBody {
+            error: String,
+            code: &'static str,
+        }
+
+        let (status, code) = self.status_and_code();
 
         let body = ErrorBody {
             error: self.to_string(),
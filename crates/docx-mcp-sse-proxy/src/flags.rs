@@ -0,0 +1,300 @@
+//! Per-tenant feature flags via Cloudflare D1 API.
+//!
+//! Flags gate new or risky document capabilities (PDF export, tracked
+//! changes, ...) so they can be rolled out gradually. D1's `feature_flag`
+//! table holds each flag's global state and rollout percentage,
+//! `tenant_feature_flag` per-tenant overrides. A flag is on for a tenant when
+//! its override says so, else when it is enabled globally, else when the
+//! tenant falls within the rollout percentage.
+//!
+//! The proxy rejects tool calls needing a disabled flag and passes the
+//! tenant's flags to the backend in `X-Feature-Flags` (`name=1,other=0`),
+//! which enforces them too. Flags are cached per tenant for a short TTL.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use moka::future::Cache;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::error::{ProxyError, Result};
+
+/// Header carrying a tenant's resolved flags to the backend.
+pub const X_FEATURE_FLAGS: &str = "x-feature-flags";
+
+/// Flag gating PDF export.
+pub const PDF_EXPORT: &str = "pdf_export";
+/// Flag gating the tracked changes tools.
+pub const TRACKED_CHANGES: &str = "tracked_changes";
+
+/// The flag a tool call needs, if any.
+pub fn required_flag(tool: &str, arguments: &Value) -> Option<&'static str> {
+    match tool {
+        "export" => arguments
+            .get("format")
+            .and_then(Value::as_str)
+            .filter(|f| f.eq_ignore_ascii_case("pdf"))
+            .map(|_| PDF_EXPORT),
+        "track_changes_enable" | "revision_accept" | "revision_reject" => Some(TRACKED_CHANGES),
+        _ => None,
+    }
+}
+
+/// A tenant's flags. Flags missing from D1 are left to the backend's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantFlags(BTreeMap<String, bool>);
+
+impl TenantFlags {
+    /// Whether `flag` is on; `None` when D1 doesn't know it.
+    pub fn get(&self, flag: &str) -> Option<bool> {
+        self.0.get(flag).copied()
+    }
+
+    /// Value of the `X-Feature-Flags` header.
+    pub fn header_value(&self) -> String {
+        self.0
+            .iter()
+            .map(|(name, on)| format!("{}={}", name, u8::from(*on)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// A flag row joined with the tenant's override.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlagRecord {
+    pub name: String,
+    pub enabled: i64,
+    #[serde(rename = "rolloutPercent")]
+    pub rollout_percent: i64,
+    #[serde(rename = "tenantEnabled")]
+    pub tenant_enabled: Option<i64>,
+}
+
+/// Resolve `records` for `tenant_id`: override, else global state, else rollout.
+pub fn resolve(tenant_id: &str, records: &[FlagRecord]) -> TenantFlags {
+    TenantFlags(
+        records
+            .iter()
+            .map(|r| {
+                let on = match r.tenant_enabled {
+                    Some(enabled) => enabled != 0,
+                    None => {
+                        r.enabled != 0
+                            || i64::from(rollout_bucket(tenant_id, &r.name)) < r.rollout_percent
+                    }
+                };
+                (r.name.clone(), on)
+            })
+            .collect(),
+    )
+}
+
+/// Stable bucket in 0..100 of a tenant for a flag. Hashing the flag name in
+/// means each rollout reaches a different slice of tenants.
+pub fn rollout_bucket(tenant_id: &str, flag: &str) -> u8 {
+    let mut hasher = Sha256::new();
+    hasher.update(flag.as_bytes());
+    hasher.update(b":");
+    hasher.update(tenant_id.as_bytes());
+    let digest = hasher.finalize();
+    let value = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    (value % 100) as u8
+}
+
+/// D1 query request body.
+#[derive(Serialize)]
+struct D1QueryRequest {
+    sql: String,
+    params: Vec<String>,
+}
+
+/// D1 API response structure.
+#[derive(Deserialize)]
+struct D1Response {
+    success: bool,
+    result: Option<Vec<D1QueryResult>>,
+    errors: Option<Vec<D1Error>>,
+}
+
+#[derive(Deserialize)]
+struct D1QueryResult {
+    results: Vec<FlagRecord>,
+}
+
+#[derive(Deserialize)]
+struct D1Error {
+    message: String,
+}
+
+/// Feature flag lookup with D1 backend and caching.
+pub struct FeatureFlags {
+    client: Client,
+    account_id: String,
+    api_token: String,
+    database_id: String,
+    cache: Cache<String, Arc<TenantFlags>>,
+}
+
+impl FeatureFlags {
+    /// Create a new feature flag lookup.
+    pub fn new(account_id: String, api_token: String, database_id: String, cache_ttl_secs: u64) -> Self {
+        let cache = Cache::builder()
+            .time_to_live(Duration::from_secs(cache_ttl_secs))
+            .max_capacity(10_000)
+            .build();
+
+        Self {
+            client: Client::new(),
+            account_id,
+            api_token,
+            database_id,
+            cache,
+        }
+    }
+
+    /// The tenant's flags. When D1 can't be reached the backend's defaults
+    /// apply (the failure isn't cached, so the next request tries again).
+    pub async fn for_tenant(&self, tenant_id: &str) -> Arc<TenantFlags> {
+        if let Some(flags) = self.cache.get(tenant_id).await {
+            return flags;
+        }
+
+        match self.query_d1(tenant_id).await {
+            Ok(records) => {
+                let flags = Arc::new(resolve(tenant_id, &records));
+                debug!("Loaded feature flags for tenant {}: {}", tenant_id, flags.header_value());
+                self.cache.insert(tenant_id.to_string(), flags.clone()).await;
+                flags
+            }
+            Err(e) => {
+                warn!("Failed to load feature flags for tenant {}: {}", tenant_id, e);
+                Arc::new(TenantFlags::default())
+            }
+        }
+    }
+
+    /// Query D1 for every flag with the tenant's override.
+    async fn query_d1(&self, tenant_id: &str) -> Result<Vec<FlagRecord>> {
+        let url = format!(
+            "https://api.cloudflare.com/client/v4/accounts/{}/d1/database/{}/query",
+            self.account_id, self.database_id
+        );
+
+        let query = D1QueryRequest {
+            sql: "SELECT f.name, f.enabled, f.rolloutPercent, t.enabled AS tenantEnabled \
+                  FROM feature_flag f \
+                  LEFT JOIN tenant_feature_flag t ON t.flag = f.name AND t.tenantId = ?1"
+                .to_string(),
+            params: vec![tenant_id.to_string()],
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header("Content-Type", "application/json")
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(ProxyError::D1Error(format!(
+                "D1 API returned {}: {}",
+                status, body
+            )));
+        }
+
+        let d1_response: D1Response =
+            serde_json::from_str(&body).map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        if !d1_response.success {
+            let error_msg = d1_response
+                .errors
+                .map(|errs| errs.into_iter().map(|e| e.message).collect::<Vec<_>>().join(", "))
+                .unwrap_or_else(|| "Unknown D1 error".to_string());
+            return Err(ProxyError::D1Error(error_msg));
+        }
+
+        Ok(d1_response
+            .result
+            .and_then(|mut results| results.pop())
+            .map(|r| r.results)
+            .unwrap_or_default())
+    }
+}
+
+/// Shared feature flag lookup wrapped in Arc.
+pub type SharedFeatureFlags = Arc<FeatureFlags>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(name: &str, enabled: i64, rollout: i64, tenant: Option<i64>) -> FlagRecord {
+        FlagRecord {
+            name: name.to_string(),
+            enabled,
+            rollout_percent: rollout,
+            tenant_enabled: tenant,
+        }
+    }
+
+    #[test]
+    fn test_resolve_prefers_tenant_override() {
+        let flags = resolve(
+            "acme",
+            &[
+                record("pdf_export", 1, 0, Some(0)),
+                record("tracked_changes", 0, 0, Some(1)),
+                record("hifi_toc", 0, 0, None),
+                record("everyone", 1, 0, None),
+                record("rolled_out", 0, 100, None),
+            ],
+        );
+        assert_eq!(flags.get("pdf_export"), Some(false));
+        assert_eq!(flags.get("tracked_changes"), Some(true));
+        assert_eq!(flags.get("hifi_toc"), Some(false));
+        assert_eq!(flags.get("everyone"), Some(true));
+        assert_eq!(flags.get("rolled_out"), Some(true));
+        assert_eq!(flags.get("unknown"), None);
+        assert_eq!(
+            flags.header_value(),
+            "everyone=1,hifi_toc=0,pdf_export=0,rolled_out=1,tracked_changes=1"
+        );
+    }
+
+    #[test]
+    fn test_rollout_bucket_is_stable_and_spread() {
+        assert_eq!(rollout_bucket("acme", "pdf_export"), rollout_bucket("acme", "pdf_export"));
+
+        let enabled = (0..1000)
+            .filter(|i| {
+                let flags = resolve(&format!("tenant-{}", i), &[record("hifi_toc", 0, 25, None)]);
+                flags.get("hifi_toc") == Some(true)
+            })
+            .count();
+        assert!((180..320).contains(&enabled), "{} of 1000 tenants enabled", enabled);
+    }
+
+    #[test]
+    fn test_required_flag() {
+        assert_eq!(required_flag("export", &json!({"format": "PDF"})), Some(PDF_EXPORT));
+        assert_eq!(required_flag("export", &json!({"format": "html"})), None);
+        assert_eq!(required_flag("track_changes_enable", &json!({})), Some(TRACKED_CHANGES));
+        assert_eq!(required_flag("query", &json!({})), None);
+    }
+}
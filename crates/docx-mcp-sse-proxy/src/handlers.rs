@@ -3,39 +3,85 @@
 //! Implements:
 //! - POST/GET/DELETE /mcp{/*rest} - Forward to .NET MCP backend
 //! - GET /health - Health check endpoint
+//! - GET/POST /presence/{doc_id} - Subscribe to and publish presence on a document
 //!
 //! Session recovery: when the backend returns 404 (session lost after restart),
 //! the proxy transparently re-initializes the MCP session and retries the request.
 
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 
 use axum::body::Body;
-use axum::extract::{Request, State};
-use axum::http::{header, HeaderMap, HeaderValue, Method};
+use axum::extract::{Path, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use futures::StreamExt;
 use axum::body::Bytes;
 use reqwest::Client as HttpClient;
 use serde::Serialize;
 use serde_json::Value;
-use tracing::{debug, info, warn};
+use docx_storage_core::{new_request_id, Reloadable, REQUEST_ID_HEADER};
+use tracing::{debug, info, warn, Instrument};
 
-use crate::auth::SharedPatValidator;
+use crate::body::{limited_stream, BodyLimits};
+use crate::canary::{CanaryRouter, CanaryStatus};
 use crate::error::{set_resource_metadata_url, ProxyError};
-use crate::oauth::{OAuthValidator, SharedOAuthValidator};
+use crate::flags::{self, SharedFeatureFlags, TenantFlags, X_FEATURE_FLAGS};
+use crate::jsonrpc::{self, RequestIds};
+use crate::mirror::SharedMirror;
+use crate::presence::{Presence, PresenceHub, AGENT_PARTICIPANT};
+use crate::provider::SharedAuthProvider;
+use crate::replay::ReplayBuffers;
+use crate::retry::RetryPolicy;
 use crate::session::SessionRegistry;
+use crate::sse::{with_keepalive, SseSettings};
 
 /// Application state shared across handlers.
 #[derive(Clone)]
 pub struct AppState {
-    pub validator: Option<SharedPatValidator>,
-    pub oauth_validator: Option<SharedOAuthValidator>,
+    /// Bearer token validation (None = auth disabled, default tenant).
+    pub auth: Option<SharedAuthProvider>,
+    /// Per-tenant feature flags (None = backend defaults for everyone).
+    pub feature_flags: Option<SharedFeatureFlags>,
     pub backend_url: String,
+    /// Canary rules, replacing `backend_url` for the tenants they route.
+    pub canary: Reloadable<CanaryRouter>,
+    /// Secondary backend receiving copies of sampled sessions (None = no mirroring).
+    pub mirror: Option<SharedMirror>,
     pub http_client: HttpClient,
     pub sessions: Arc<SessionRegistry>,
+    /// Recent SSE events per client session, replayed on reconnect (None = disabled).
+    pub replay: Option<Arc<ReplayBuffers>>,
+    /// Cursors, selections and agent activity per document (None = disabled).
+    pub presence: Option<Arc<PresenceHub>>,
     pub resource_url: Option<String>,
     pub auth_server_url: Option<String>,
+    /// Body limits, retry policy, SSE settings and canary rules are reloaded with the
+    /// configuration file; requests in flight keep the version they started with.
+    pub body_limits: Reloadable<BodyLimits>,
+    pub retry_policy: Reloadable<RetryPolicy>,
+    /// Keepalive interval and idle timeout of SSE responses (None = disabled).
+    pub sse: Reloadable<SseSettings>,
+}
+
+impl AppState {
+    /// SSE settings for a response to `method`. The standalone GET stream only
+    /// carries server notifications and may legitimately stay quiet for a long
+    /// time, so it gets heartbeats but no idle timeout.
+    fn sse_settings(&self, method: &Method) -> SseSettings {
+        let sse = self.sse.get();
+        SseSettings {
+            heartbeat: sse.heartbeat,
+            idle_timeout: if method == Method::GET {
+                None
+            } else {
+                sse.idle_timeout
+            },
+        }
+    }
 }
 
 /// Health check response.
@@ -46,6 +92,8 @@ pub struct HealthResponse {
     pub auth_enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub backend_healthy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canary: Option<CanaryStatus>,
 }
 
 /// GET /health - Liveness check (proxy only, no upstream dependency).
@@ -53,30 +101,48 @@ pub async fn health_handler(State(state): State<AppState>) -> Json<HealthRespons
     Json(HealthResponse {
         healthy: true,
         version: env!("CARGO_PKG_VERSION"),
-        auth_enabled: state.validator.is_some(),
+        auth_enabled: state.auth.is_some(),
         backend_healthy: None,
+        canary: None,
     })
 }
 
 /// GET /upstream-health - Deep health check (proxy + upstream mcp-http).
+/// The canary backend, when configured, is reported apart and doesn't
+/// affect `healthy`.
 pub async fn upstream_health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
-    let backend_ok = state
-        .http_client
-        .get(format!("{}/health", state.backend_url))
-        .timeout(Duration::from_secs(3))
-        .send()
-        .await
-        .map(|r| r.status().is_success())
-        .unwrap_or(false);
+    let backend_ok = probe_backend(&state.http_client, &state.backend_url).await;
+
+    let canary_router = state.canary.get();
+    let canary = match canary_router.backend_url() {
+        Some(url) => {
+            let mut status = canary_router.status();
+            status.backend_healthy = Some(probe_backend(&state.http_client, url).await);
+            Some(status)
+        }
+        None => None,
+    };
 
     Json(HealthResponse {
         healthy: backend_ok,
         version: env!("CARGO_PKG_VERSION"),
-        auth_enabled: state.validator.is_some(),
+        auth_enabled: state.auth.is_some(),
         backend_healthy: Some(backend_ok),
+        canary,
     })
 }
 
+/// Whether a backend answers its health check.
+async fn probe_backend(http_client: &HttpClient, backend_url: &str) -> bool {
+    http_client
+        .get(format!("{}/health", backend_url))
+        .timeout(Duration::from_secs(3))
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+}
+
 /// GET /.well-known/oauth-protected-resource - OAuth 2.0 Protected Resource Metadata.
 pub async fn oauth_metadata_handler(
     State(state): State<AppState>,
@@ -118,24 +184,28 @@ fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
         .and_then(|v| v.strip_prefix("Bearer "))
 }
 
-/// Maximum number of retry attempts for transient backend errors.
-/// Budget: 500+1000+2000+4000+5000×4 = ~27.5s (covers .NET cold start).
-const MAX_RETRIES: u32 = 8;
-/// Initial backoff delay in milliseconds.
-const INITIAL_BACKOFF_MS: u64 = 500;
-/// Maximum backoff delay in milliseconds (cap for exponential backoff).
-const MAX_BACKOFF_MS: u64 = 5_000;
-/// Timeout for each individual backend request.
-const FORWARD_TIMEOUT_SECS: u64 = 30;
-
 /// Headers to forward from the client to the backend.
-const FORWARD_HEADERS: &[header::HeaderName] = &[header::CONTENT_TYPE, header::ACCEPT];
+pub(crate) const FORWARD_HEADERS: &[header::HeaderName] = &[header::CONTENT_TYPE, header::ACCEPT];
 
 /// MCP-specific header for session tracking.
-const MCP_SESSION_ID: &str = "mcp-session-id";
+pub(crate) const MCP_SESSION_ID: &str = "mcp-session-id";
 /// SSE resumption header (client sends this to resume from a specific event).
 const LAST_EVENT_ID: &str = "last-event-id";
-const X_TENANT_ID: &str = "x-tenant-id";
+pub(crate) const X_TENANT_ID: &str = "x-tenant-id";
+/// How much of a rejected (unauthenticated) body is read to find its JSON-RPC id.
+const AUTH_ERROR_PEEK_BYTES: usize = 64 * 1024;
+/// Longest client-supplied request ID that is kept; longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The client's `X-Request-Id` if it is usable, a new one otherwise.
+fn request_id_of(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(new_request_id)
+}
 
 /// Check if a JSON body is an MCP `initialize` request.
 fn is_initialize_request(body: &[u8]) -> bool {
@@ -186,10 +256,14 @@ fn is_retryable_error(err: &ProxyError) -> bool {
 }
 
 /// Send a request to the backend with retry for transient errors.
+///
+/// Retries are logged with structured fields (`rpc_method`, `attempt`,
+/// `max_retries`, `delay_ms`, `outcome`) so the policy can be tuned from
+/// the logs.
 #[allow(clippy::too_many_arguments)]
 async fn send_to_backend_with_retry(
-    http_client: &HttpClient,
-    backend_url: &str,
+    state: &AppState,
+    rpc_method: Option<&str>,
     method: &Method,
     path: &str,
     query: &str,
@@ -198,36 +272,44 @@ async fn send_to_backend_with_retry(
     session_id_override: Option<&str>,
     body: Bytes,
 ) -> Result<BackendResponse, ProxyError> {
+    let policy = state.retry_policy.get();
+    let max_retries = policy.max_retries_for(rpc_method);
+    let rpc_method = rpc_method.unwrap_or("-");
     let started = std::time::Instant::now();
     let mut last_error = None;
-    for attempt in 0..=MAX_RETRIES {
+    for attempt in 0..=max_retries {
         if attempt > 0 {
-            let delay = (INITIAL_BACKOFF_MS * 2u64.pow(attempt - 1)).min(MAX_BACKOFF_MS);
+            let delay = policy.backoff(attempt);
             warn!(
-                "Retrying backend request ({}/{}) after {}ms",
-                attempt, MAX_RETRIES, delay
+                rpc_method,
+                tenant_id,
+                attempt,
+                max_retries,
+                delay_ms = delay.as_millis() as u64,
+                outcome = "retrying",
+                "Retrying backend request"
             );
-            tokio::time::sleep(Duration::from_millis(delay)).await;
+            tokio::time::sleep(delay).await;
         }
         match send_to_backend(
-            http_client,
-            backend_url,
+            state,
             method,
             path,
             query,
             client_headers,
             tenant_id,
             session_id_override,
-            body.clone(),
+            body.clone().into(),
         )
         .await
         {
-            Ok(resp) if is_retryable_status(resp.status) && attempt < MAX_RETRIES => {
+            Ok(resp) if is_retryable_status(resp.status) && attempt < max_retries => {
                 warn!(
-                    "Backend returned {}, will retry ({}/{})",
-                    resp.status,
-                    attempt + 1,
-                    MAX_RETRIES
+                    rpc_method,
+                    attempt = attempt + 1,
+                    max_retries,
+                    status = resp.status.as_u16(),
+                    "Backend returned a retryable status"
                 );
                 last_error = Some(ProxyError::BackendUnavailable(
                     format!("Backend returned {}", resp.status),
@@ -237,61 +319,65 @@ async fn send_to_backend_with_retry(
             Ok(resp) => {
                 if attempt > 0 {
                     info!(
-                        "Backend request succeeded after {} attempts in {:.1}s",
-                        attempt + 1,
-                        started.elapsed().as_secs_f64()
+                        rpc_method,
+                        tenant_id,
+                        attempts = attempt + 1,
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        outcome = "recovered",
+                        "Backend request succeeded after retries"
                     );
                 }
                 return Ok(resp);
             }
-            Err(e) if is_retryable_error(&e) && attempt < MAX_RETRIES => {
+            Err(e) if is_retryable_error(&e) && attempt < max_retries => {
                 warn!(
-                    "Backend error: {}, will retry ({}/{})",
-                    e,
-                    attempt + 1,
-                    MAX_RETRIES
+                    rpc_method,
+                    attempt = attempt + 1,
+                    max_retries,
+                    error = %e,
+                    "Backend request failed with a retryable error"
                 );
                 last_error = Some(e);
             }
             // Last attempt failed with retryable error → wrap as BackendUnavailable (503)
             Err(e) if is_retryable_error(&e) => {
-                return Err(ProxyError::BackendUnavailable(
-                    e.to_string(),
-                    MAX_RETRIES,
-                ));
+                last_error = Some(e);
+                break;
             }
             Err(e) => return Err(e),
         }
     }
     warn!(
-        "All {} retries exhausted after {:.1}s",
-        MAX_RETRIES,
-        started.elapsed().as_secs_f64()
+        rpc_method,
+        tenant_id,
+        max_retries,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        outcome = "exhausted",
+        "All backend retries exhausted"
     );
     Err(last_error.map_or_else(
-        || ProxyError::BackendUnavailable("All retries exhausted".into(), MAX_RETRIES),
-        |e| ProxyError::BackendUnavailable(e.to_string(), MAX_RETRIES),
+        || ProxyError::BackendUnavailable("All retries exhausted".into(), max_retries),
+        |e| ProxyError::BackendUnavailable(e.to_string(), max_retries),
     ))
 }
 
 /// Send a request to the backend, returning status + headers + body.
 #[allow(clippy::too_many_arguments)]
 async fn send_to_backend(
-    http_client: &HttpClient,
-    backend_url: &str,
+    state: &AppState,
     method: &Method,
     path: &str,
     query: &str,
     client_headers: &HeaderMap,
     tenant_id: &str,
     session_id_override: Option<&str>,
-    body: Bytes,
+    body: reqwest::Body,
 ) -> Result<BackendResponse, ProxyError> {
-    let url = format!("{}{}{}", backend_url, path, query);
+    let url = format!("{}{}{}", state.backend_url, path, query);
 
     debug!("Forwarding {} {} -> {}", method, path, url);
 
-    let mut req = http_client.request(
+    let mut req = state.http_client.request(
         reqwest::Method::from_bytes(method.as_str().as_bytes())
             .map_err(|e| ProxyError::Internal(format!("Invalid method: {}", e)))?,
         &url,
@@ -322,22 +408,39 @@ async fn send_to_backend(
         }
     }
 
+    // Forward the request ID (always set by mcp_forward_handler) and the
+    // tenant's feature flags (only ever set by handle_forward)
+    for name in [REQUEST_ID_HEADER, X_FEATURE_FLAGS] {
+        if let Some(value) = client_headers.get(name) {
+            if let Ok(s) = value.to_str() {
+                req = req.header(name, s);
+            }
+        }
+    }
+
     // Inject tenant ID
     req = req.header(X_TENANT_ID, tenant_id);
 
-    // Forward body
-    if !body.is_empty() {
-        debug!(
-            "Request body ({} bytes): {}",
-            body.len(),
-            String::from_utf8_lossy(&body[..body.len().min(2048)])
-        );
-        req = req.body(body);
+    // Forward body (buffered bodies are logged, streamed ones are passed through)
+    match body.as_bytes() {
+        Some([]) => {}
+        Some(bytes) => {
+            debug!(
+                "Request body ({} bytes): {}",
+                bytes.len(),
+                String::from_utf8_lossy(&bytes[..bytes.len().min(2048)])
+            );
+            req = req.body(body);
+        }
+        None => {
+            debug!("Streaming request body");
+            req = req.body(body);
+        }
     }
 
     // Send with timeout
     let resp = req
-        .timeout(Duration::from_secs(FORWARD_TIMEOUT_SECS))
+        .timeout(state.retry_policy.get().request_timeout)
         .send()
         .await
         .map_err(|e| ProxyError::BackendError(format!("Failed to reach backend: {}", e)))?;
@@ -407,12 +510,35 @@ async fn send_to_backend(
     }
 }
 
+/// SSE events of a client session: recorded for replay, and those replayed
+/// on a reconnect, sent ahead of the backend stream.
+struct ClientEvents {
+    replay: Arc<ReplayBuffers>,
+    tenant_id: String,
+    client_session_id: String,
+    replayed: Vec<Bytes>,
+}
+
 /// Convert a BackendResponse into an axum Response.
-fn into_response(br: BackendResponse) -> Result<Response, ProxyError> {
+fn into_response(
+    br: BackendResponse,
+    sse: SseSettings,
+    events: Option<ClientEvents>,
+) -> Result<Response, ProxyError> {
     if br.is_sse {
         let raw = br.raw_response.expect("SSE response must have raw_response");
         debug!("Starting SSE stream forwarding");
-        let stream = raw.bytes_stream();
+        let upstream = match events {
+            Some(events) => futures::stream::iter(events.replayed.into_iter().map(Ok))
+                .chain(events.replay.record(
+                    &events.tenant_id,
+                    &events.client_session_id,
+                    raw.bytes_stream(),
+                ))
+                .boxed(),
+            None => raw.bytes_stream().boxed(),
+        };
+        let stream = with_keepalive(upstream, sse);
         let body = Body::from_stream(stream);
 
         let mut response = Response::builder()
@@ -425,6 +551,14 @@ fn into_response(br: BackendResponse) -> Result<Response, ProxyError> {
             header::CONTENT_TYPE,
             HeaderValue::from_static("text/event-stream"),
         );
+        // Ask intermediaries not to buffer or cache the stream
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        response.headers_mut().insert(
+            header::HeaderName::from_static("x-accel-buffering"),
+            HeaderValue::from_static("no"),
+        );
 
         Ok(response)
     } else {
@@ -441,7 +575,7 @@ fn into_response(br: BackendResponse) -> Result<Response, ProxyError> {
     }
 }
 
-/// Extract the Mcp-Session-Id value from response headers.
+/// Extract the Mcp-Session-Id value from request or response headers.
 fn extract_session_id_from_headers(headers: &HeaderMap) -> Option<String> {
     headers
         .get("mcp-session-id")
@@ -451,7 +585,7 @@ fn extract_session_id_from_headers(headers: &HeaderMap) -> Option<String> {
 
 /// Perform a synthetic MCP initialize + notifications/initialized handshake
 /// against the backend to obtain a new session ID.
-async fn reinitialize_session(
+pub(crate) async fn reinitialize_session(
     http_client: &HttpClient,
     backend_url: &str,
     tenant_id: &str,
@@ -552,169 +686,796 @@ async fn reinitialize_session(
 /// 2. Forwards the request to {MCP_BACKEND_URL}/mcp with X-Tenant-Id header
 /// 3. If backend returns 404 (session lost), transparently re-initializes and retries
 /// 4. Streams the response back (SSE or JSON)
-pub async fn mcp_forward_handler(
-    State(state): State<AppState>,
-    req: Request,
-) -> std::result::Result<Response, ProxyError> {
+///
+/// Errors are reported as JSON-RPC errors when the request carried an id
+/// (see [`jsonrpc`]).
+///
+/// Each request runs in an `mcp_request` span with its `request_id` (the
+/// client's `X-Request-Id` or a new one), `tenant_id`, `rpc_method` and the
+/// `session_id` (document) of tool calls. The request ID is passed to the
+/// backend, which passes it on to the storage services, and returned to the
+/// client.
+pub async fn mcp_forward_handler(State(state): State<AppState>, mut req: Request) -> Response {
+    let request_id = request_id_of(req.headers());
+    let header_value =
+        HeaderValue::from_str(&request_id).unwrap_or_else(|_| HeaderValue::from_static("-"));
+    req.headers_mut().insert(REQUEST_ID_HEADER, header_value.clone());
+
+    let span = tracing::info_span!(
+        "mcp_request",
+        request_id = %request_id,
+        tenant_id = tracing::field::Empty,
+        rpc_method = tracing::field::Empty,
+        session_id = tracing::field::Empty,
+    );
+    let mut response = handle_forward(state, req).instrument(span).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header_value);
+    response
+}
+
+/// Steps 1-6 of [`mcp_forward_handler`], inside its span.
+async fn handle_forward(mut state: AppState, mut req: Request) -> Response {
     // --- 1. Authenticate (PAT or OAuth) ---
     // Set resource metadata URL for WWW-Authenticate header on 401
     set_resource_metadata_url(state.resource_url.clone());
 
-    let tenant_id = if state.validator.is_some() || state.oauth_validator.is_some() {
-        let token = extract_bearer_token(req.headers()).ok_or(ProxyError::Unauthorized)?;
+    let tenant_id = match authenticate(&state, req.headers()).await {
+        Ok(tenant_id) => {
+            if !tenant_id.is_empty() {
+                tracing::Span::current().record("tenant_id", tenant_id.as_str());
+            }
+            tenant_id
+        }
+        Err(e) => {
+            // Peek at the body so the error can be addressed to the request id
+            let body = axum::body::to_bytes(req.into_body(), AUTH_ERROR_PEEK_BYTES)
+                .await
+                .unwrap_or_default();
+            return jsonrpc::error_response(e, jsonrpc::request_ids(&body).as_ref());
+        }
+    };
 
-        if OAuthValidator::is_oauth_token(token) {
-            // Try OAuth token (oat_...)
-            let oauth_validator = state
-                .oauth_validator
-                .as_ref()
-                .ok_or(ProxyError::InvalidToken)?;
-            let validation = oauth_validator.validate(token).await?;
-            info!(
-                "Authenticated request for tenant {} (OAuth: {}...)",
-                validation.tenant_id,
-                &token[..12.min(token.len())]
-            );
-            validation.tenant_id
-        } else {
-            // Try PAT token (dxs_...)
-            let pat_validator = state.validator.as_ref().ok_or(ProxyError::InvalidToken)?;
-            let validation = pat_validator.validate(token).await?;
-            info!(
-                "Authenticated request for tenant {} (PAT: {}...)",
-                validation.tenant_id,
-                &validation.pat_id[..8.min(validation.pat_id.len())]
-            );
-            validation.tenant_id
+    // Feature flags come from D1 only, never from the client
+    req.headers_mut().remove(X_FEATURE_FLAGS);
+    let tenant_flags = match &state.feature_flags {
+        Some(feature_flags) => Some(feature_flags.for_tenant(&tenant_id).await),
+        None => None,
+    };
+    if let Some(value) = tenant_flags
+        .as_ref()
+        .and_then(|f| HeaderValue::from_str(&f.header_value()).ok())
+    {
+        req.headers_mut().insert(X_FEATURE_FLAGS, value);
+    }
+
+    // Canary tenants are served by the canary backend, recovery included
+    let canary = state.canary.get();
+    let on_canary = match canary.route(&tenant_id, req.headers()) {
+        Some(url) => {
+            debug!("Routing tenant {} to the canary backend", tenant_id);
+            state.backend_url = url.to_string();
+            true
         }
-    } else {
+        None => false,
+    };
+
+    let mut rpc_ids = None;
+    let result =
+        forward_request(&state, req, &tenant_id, tenant_flags.as_deref(), &mut rpc_ids).await;
+    if on_canary {
+        canary.record(matches!(&result, Ok(r) if !r.status().is_server_error()));
+    }
+    match result {
+        Ok(response) => response,
+        Err(e) => jsonrpc::error_response(e, rpc_ids.as_ref()),
+    }
+}
+
+/// Validate the bearer token with the auth provider and return the tenant ID.
+/// Without a provider every request maps to the default tenant.
+async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<String, ProxyError> {
+    let Some(auth) = &state.auth else {
         debug!("Auth not configured, using default tenant");
-        String::new()
+        return Ok(String::new());
+    };
+    let token = extract_bearer_token(headers).ok_or(ProxyError::Unauthorized)?;
+    let identity = auth.authenticate(token).await?;
+    info!(
+        "Authenticated request for tenant {} ({})",
+        identity.tenant_id, identity.credential
+    );
+    Ok(identity.tenant_id)
+}
+
+/// GET /presence/{doc_id} - SSE stream of the presences on a document: a
+/// `snapshot` event with the current ones and the TTL, then a `presence`
+/// event per change, the agent's tool calls included.
+pub async fn presence_subscribe_handler(
+    State(state): State<AppState>,
+    Path(doc_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    set_resource_metadata_url(state.resource_url.clone());
+    let Some(presence) = state.presence.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let tenant_id = match authenticate(&state, &headers).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return e.into_response(),
     };
 
+    let (current, rx) = presence.subscribe(&tenant_id, &doc_id);
+    let snapshot = Event::default().event("snapshot").json_data(serde_json::json!({
+        "ttl_secs": presence.ttl().as_secs(),
+        "presences": current,
+    }));
+    let changes = futures::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(change) => {
+                    return Some((Event::default().event("presence").json_data(change), rx))
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "Presence subscriber lagged behind");
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let mut keep_alive = KeepAlive::new();
+    if let Some(heartbeat) = state.sse.get().heartbeat {
+        keep_alive = keep_alive.interval(heartbeat);
+    }
+    Sse::new(futures::stream::once(async move { snapshot }).chain(changes))
+        .keep_alive(keep_alive)
+        .into_response()
+}
+
+/// POST /presence/{doc_id} - Publish the caller's cursor, selection or
+/// activity on a document, or `left` when it goes away. Clients refresh it
+/// within the TTL to keep it shown.
+pub async fn presence_publish_handler(
+    State(state): State<AppState>,
+    Path(doc_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    set_resource_metadata_url(state.resource_url.clone());
+    let Some(presence) = state.presence.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let result = async {
+        let tenant_id = authenticate(&state, &headers).await?;
+        let update: Presence = serde_json::from_slice(&body)?;
+        if update.participant.trim().is_empty() {
+            return Err(ProxyError::InvalidRequest("participant is required".to_string()));
+        }
+        if update.participant == AGENT_PARTICIPANT {
+            return Err(ProxyError::InvalidRequest(format!(
+                "participant '{}' is reserved for the agent's tool calls",
+                AGENT_PARTICIPANT
+            )));
+        }
+        presence.publish(&tenant_id, &doc_id, update);
+        Ok(())
+    }
+    .await;
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Steps 2-6 of [`mcp_forward_handler`]. Records the JSON-RPC ids of the
+/// request in `rpc_ids` as soon as the body has been read.
+///
+/// Tool calls needing a feature disabled in `tenant_flags` are rejected here;
+/// streamed bodies aren't inspected and are left to the backend's check.
+async fn forward_request(
+    state: &AppState,
+    req: Request,
+    tenant_id: &str,
+    tenant_flags: Option<&TenantFlags>,
+    rpc_ids: &mut Option<RequestIds>,
+) -> Result<Response, ProxyError> {
     // --- 2. Capture request parts ---
     let method = req.method().clone();
     let uri = req.uri().clone();
     let path = uri.path().to_string();
     let query = uri.query().map(|q| format!("?{}", q)).unwrap_or_default();
-    let client_headers = req.headers().clone();
+    let mut client_headers = req.headers().clone();
+
+    let body_limits = state.body_limits.get();
+    let limit = body_limits.limit_for(&path, tenant_id);
+    let content_length = client_headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if content_length.is_some_and(|len| len > limit as u64) {
+        return Err(ProxyError::PayloadTooLarge(limit));
+    }
+
+    if body_limits.should_stream(content_length) {
+        return forward_streaming(
+            state,
+            &method,
+            &path,
+            &query,
+            &client_headers,
+            tenant_id,
+            req.into_body(),
+            limit,
+        )
+        .await;
+    }
 
-    let body_bytes: Bytes = axum::body::to_bytes(req.into_body(), 10 * 1024 * 1024)
+    let body_bytes: Bytes = axum::body::to_bytes(req.into_body(), limit)
         .await
-        .map_err(|e| ProxyError::Internal(format!("Failed to read body: {}", e)))?;
+        .map_err(|e| {
+            let e = e.into_inner();
+            if e.is::<http_body_util::LengthLimitError>() {
+                ProxyError::PayloadTooLarge(limit)
+            } else {
+                ProxyError::Internal(format!("Failed to read body: {}", e))
+            }
+        })?;
+    *rpc_ids = jsonrpc::request_ids(&body_bytes);
+    let rpc_method = jsonrpc::request_method(&body_bytes);
+    let span = tracing::Span::current();
+    if let Some(rpc_method) = &rpc_method {
+        span.record("rpc_method", rpc_method.as_str());
+    }
+    let doc_id = jsonrpc::tool_doc_id(&body_bytes);
+    if let Some(doc_id) = &doc_id {
+        span.record("session_id", doc_id.as_str());
+    }
+    if let Some((tool, arguments)) = jsonrpc::tool_call(&body_bytes) {
+        if let Some(flag) = flags::required_flag(&tool, &arguments)
+            .filter(|flag| tenant_flags.and_then(|f| f.get(flag)) == Some(false))
+        {
+            info!(tool = %tool, flag, "Rejected tool call needing a disabled feature");
+            return Err(ProxyError::FeatureDisabled(flag.to_string()));
+        }
+        if let (Some(presence), Some(doc_id)) = (&state.presence, &doc_id) {
+            presence.publish_tool_call(tenant_id, doc_id, &tool, &arguments);
+        }
+    }
 
     let is_init = is_initialize_request(&body_bytes);
     let is_delete = method == Method::DELETE;
 
     // --- 3. Resolve session ID ---
     // For initialize: don't inject a session ID (backend creates a new one).
-    // For other requests: use registry session ID if available, else fall through
-    // to whatever the client sent.
-    let registry_session_id = if !is_init {
-        state.sessions.get_session_id(&tenant_id).await
+    // For other requests: if the client's session was recovered earlier, route
+    // to the replacement backend session; otherwise forward the client's ID.
+    let client_session_id = if !is_init {
+        extract_session_id_from_headers(&client_headers)
     } else {
         None
     };
+    let registry_session_id = match &client_session_id {
+        Some(csid) => state.sessions.get_session_id(tenant_id, csid).await,
+        None => None,
+    };
+
+    // Resume a reconnected GET stream from the proxy's buffer when it still
+    // holds the client's last event; the backend then starts afresh
+    let mut events = match (&state.replay, &client_session_id) {
+        (Some(replay), Some(csid)) => Some(ClientEvents {
+            replay: replay.clone(),
+            tenant_id: tenant_id.to_string(),
+            client_session_id: csid.clone(),
+            replayed: Vec::new(),
+        }),
+        _ => None,
+    };
+    let last_event_id = client_headers
+        .get(LAST_EVENT_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if let (Some(events), Some(last_event_id)) = (
+        events.as_mut().filter(|_| method == Method::GET),
+        last_event_id,
+    ) {
+        if let Some(missed) = events
+            .replay
+            .events_after(tenant_id, &events.client_session_id, &last_event_id)
+            .await
+        {
+            debug!(
+                "Replaying {} SSE events after {} from the proxy buffer",
+                missed.len(),
+                last_event_id
+            );
+            events.replayed = missed;
+            client_headers.remove(LAST_EVENT_ID);
+        }
+    }
+
+    // Copy the request to the mirror backend (its handshake is its own)
+    if let (Some(mirror), Some(csid)) = (&state.mirror, &client_session_id) {
+        if method != Method::GET && rpc_method.as_deref() != Some("notifications/initialized") {
+            mirror
+                .forward(
+                    &method,
+                    &format!("{}{}", path, query),
+                    &client_headers,
+                    tenant_id,
+                    csid,
+                    body_bytes.clone(),
+                )
+                .await;
+        }
+    }
 
     // --- 4. Forward to backend ---
     let backend_resp = send_to_backend_with_retry(
-        &state.http_client,
-        &state.backend_url,
+        state,
+        rpc_method.as_deref(),
         &method,
         &path,
         &query,
         &client_headers,
-        &tenant_id,
+        tenant_id,
         registry_session_id.as_deref(),
         body_bytes.clone(),
     )
     .await?;
 
+    // A new session may be sampled for mirroring
+    if let Some(mirror) = state.mirror.as_ref().filter(|_| is_init) {
+        if let Some(sid) = extract_session_id_from_headers(&backend_resp.headers)
+            .filter(|_| backend_resp.status.is_success())
+        {
+            mirror.start_session(tenant_id, &sid);
+        }
+    }
+
     // --- 5. Handle 404 → session recovery ---
-    if backend_resp.status == axum::http::StatusCode::NOT_FOUND && !is_init && !is_delete {
+    // Only sessions the client identified can be recovered.
+    if let Some(csid) = client_session_id
+        .as_deref()
+        .filter(|_| backend_resp.status == axum::http::StatusCode::NOT_FOUND && !is_delete)
+    {
         info!(
-            "Session expired for tenant {}, attempting recovery",
-            tenant_id
+            "Session {} expired for tenant {}, attempting recovery",
+            csid, tenant_id
         );
 
         // Invalidate the stale session
-        state.sessions.invalidate(&tenant_id).await;
+        state.sessions.invalidate(tenant_id, csid).await;
 
-        // Acquire per-tenant recovery lock (serializes concurrent recoveries)
-        let _guard = state.sessions.acquire_recovery_lock(&tenant_id).await;
+        // Acquire per-session recovery lock (serializes concurrent recoveries)
+        let _guard = state.sessions.acquire_recovery_lock(tenant_id, csid).await;
 
         // Double-check: another request may have already recovered
-        if let Some(new_sid) = state.sessions.get_session_id(&tenant_id).await {
-            debug!(
-                "Session already recovered by another request for tenant {}",
-                tenant_id
-            );
-            // Retry with the recovered session ID
-            let retry_resp = send_to_backend(
-                &state.http_client,
-                &state.backend_url,
-                &method,
-                &path,
-                &query,
-                &client_headers,
-                &tenant_id,
-                Some(&new_sid),
-                body_bytes,
-            )
-            .await?;
-
-            // Cache any new session ID from the retry
-            if let Some(sid) = extract_session_id_from_headers(&retry_resp.headers) {
-                state.sessions.set_session_id(&tenant_id, sid).await;
+        let new_session_id = match state.sessions.get_session_id(tenant_id, csid).await {
+            Some(new_sid) => {
+                debug!(
+                    "Session {} already recovered by another request for tenant {}",
+                    csid, tenant_id
+                );
+                new_sid
             }
-
-            return into_response(retry_resp);
-        }
-
-        // We are the first to recover: re-initialize
-        let new_session_id = reinitialize_session(
-            &state.http_client,
-            &state.backend_url,
-            &tenant_id,
-        )
-        .await?;
-
-        state
-            .sessions
-            .set_session_id(&tenant_id, new_session_id.clone())
-            .await;
+            None => {
+                // We are the first to recover: re-initialize
+                let new_sid = reinitialize_session(
+                    &state.http_client,
+                    &state.backend_url,
+                    tenant_id,
+                )
+                .await?;
+                state
+                    .sessions
+                    .set_session_id(tenant_id, csid, new_sid.clone())
+                    .await;
+                new_sid
+            }
+        };
 
         // Retry the original request with the new session ID
-        let retry_resp = send_to_backend(
-            &state.http_client,
-            &state.backend_url,
+        let mut retry_resp = send_to_backend(
+            state,
             &method,
             &path,
             &query,
             &client_headers,
-            &tenant_id,
+            tenant_id,
             Some(&new_session_id),
-            body_bytes,
+            body_bytes.into(),
         )
         .await?;
 
-        // Cache any updated session ID
-        if let Some(sid) = extract_session_id_from_headers(&retry_resp.headers) {
-            state.sessions.set_session_id(&tenant_id, sid).await;
+        // The client keeps using its original session ID
+        present_session_id(&mut retry_resp.headers, csid);
+        return into_response(retry_resp, state.sse_settings(&method), events);
+    }
+
+    // --- 6. Normal path: keep the client's session ID and return response ---
+    let mut backend_resp = backend_resp;
+    if let Some(csid) = client_session_id.as_deref() {
+        if registry_session_id.is_some() {
+            present_session_id(&mut backend_resp.headers, csid);
         }
 
-        return into_response(retry_resp);
+        // On DELETE, forget the client session
+        if is_delete && backend_resp.status.is_success() {
+            state.sessions.remove(tenant_id, csid).await;
+            if let Some(replay) = &state.replay {
+                replay.forget(tenant_id, csid).await;
+            }
+        }
     }
 
-    // --- 6. Normal path: cache session ID and return response ---
-    if let Some(sid) = extract_session_id_from_headers(&backend_resp.headers) {
-        state.sessions.set_session_id(&tenant_id, sid).await;
+    into_response(backend_resp, state.sse_settings(&method), events)
+}
+
+/// Show the client its own session ID in place of the backend one.
+fn present_session_id(headers: &mut HeaderMap, client_session_id: &str) {
+    if headers.contains_key(MCP_SESSION_ID) {
+        if let Ok(v) = HeaderValue::from_str(client_session_id) {
+            headers.insert(MCP_SESSION_ID, v);
+        }
     }
+}
 
-    // On DELETE, clear the registry entry
-    if is_delete && backend_resp.status.is_success() {
-        state.sessions.invalidate(&tenant_id).await;
+/// Forward a large request body to the backend as it arrives.
+///
+/// The body can't be replayed, so there is no retry and no transparent
+/// session recovery: on 404 the stale backend session is forgotten and the
+/// 404 is returned; the client's next request goes through recovery.
+#[allow(clippy::too_many_arguments)]
+async fn forward_streaming(
+    state: &AppState,
+    method: &Method,
+    path: &str,
+    query: &str,
+    client_headers: &HeaderMap,
+    tenant_id: &str,
+    body: Body,
+    limit: usize,
+) -> std::result::Result<Response, ProxyError> {
+    debug!("Streaming request body to backend (limit {} bytes)", limit);
+
+    let (body, exceeded) = limited_stream(body, limit);
+    let client_session_id = extract_session_id_from_headers(client_headers);
+    let registry_session_id = match &client_session_id {
+        Some(csid) => state.sessions.get_session_id(tenant_id, csid).await,
+        None => None,
+    };
+
+    let result = send_to_backend(
+        state,
+        method,
+        path,
+        query,
+        client_headers,
+        tenant_id,
+        registry_session_id.as_deref(),
+        body,
+    )
+    .await;
+    if exceeded.load(Ordering::Relaxed) {
+        return Err(ProxyError::PayloadTooLarge(limit));
     }
+    let mut backend_resp = result?;
+
+    if let Some(csid) = client_session_id.as_deref() {
+        if backend_resp.status == axum::http::StatusCode::NOT_FOUND {
+            info!(
+                "Session {} expired for tenant {} during streamed request, dropping it",
+                csid, tenant_id
+            );
+            state.sessions.invalidate(tenant_id, csid).await;
+        } else if registry_session_id.is_some() {
+            present_session_id(&mut backend_resp.headers, csid);
+        }
+    }
+
+    into_response(backend_resp, state.sse_settings(method), None)
+}
 
-    into_response(backend_resp)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use axum::http::HeaderName;
+    use axum::routing::{any, get};
+    use axum::Router;
+    use clap::Parser;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::provider::StaticKeyProvider;
+
+    /// Headers a backend may receive from the proxy.
+    const BACKEND_HEADERS: &[&str] = &[
+        "content-type",
+        "accept",
+        "content-length",
+        "host",
+        MCP_SESSION_ID,
+        REQUEST_ID_HEADER,
+        X_TENANT_ID,
+        X_FEATURE_FLAGS,
+    ];
+
+    type Received = Arc<Mutex<Vec<HeaderMap>>>;
+
+    /// A backend recording the headers of every request it gets.
+    async fn backend() -> (String, Received) {
+        let received: Received = Arc::default();
+        let sink = received.clone();
+        let app = Router::new().route(
+            "/mcp",
+            any(move |headers: HeaderMap| {
+                let sink = sink.clone();
+                async move {
+                    sink.lock().unwrap().push(headers);
+                    Json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {}}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, received)
+    }
+
+    fn proxy(backend_url: &str) -> (Router, Arc<SessionRegistry>) {
+        let config = Config::try_parse_from([
+            "docx-mcp-sse-proxy",
+            "--mcp-backend-url",
+            backend_url,
+            "--backend-max-retries",
+            "0",
+        ])
+        .unwrap();
+        let sessions = Arc::new(SessionRegistry::new());
+        let state = AppState {
+            auth: Some(Arc::new(StaticKeyProvider::from_keys(&[
+                ("acme".to_string(), "acme-key".to_string()),
+                ("globex".to_string(), "globex-key".to_string()),
+            ]))),
+            feature_flags: None,
+            backend_url: backend_url.to_string(),
+            canary: Reloadable::new(CanaryRouter::from_config(&config)),
+            mirror: None,
+            http_client: HttpClient::new(),
+            sessions: sessions.clone(),
+            replay: None,
+            presence: PresenceHub::from_config(&config),
+            resource_url: None,
+            auth_server_url: None,
+            body_limits: Reloadable::new(BodyLimits::from_config(&config)),
+            retry_policy: Reloadable::new(RetryPolicy::from_config(&config)),
+            sse: Reloadable::new(SseSettings::from_config(&config)),
+        };
+        let app = Router::new()
+            .route("/mcp", any(mcp_forward_handler))
+            .route(
+                "/presence/{doc_id}",
+                get(presence_subscribe_handler).post(presence_publish_handler),
+            )
+            .with_state(state);
+        (app, sessions)
+    }
+
+    fn request(token: Option<&str>, headers: &[(HeaderName, HeaderValue)]) -> Request {
+        let mut builder = Request::post("/mcp")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let mut request = builder
+            .body(Body::from(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#))
+            .unwrap();
+        for (name, value) in headers {
+            request.headers_mut().append(name.clone(), value.clone());
+        }
+        request
+    }
+
+    /// Deterministic header values mixing separators, traversal and encoding
+    /// tricks with random printable bytes.
+    fn forged_values(count: usize) -> Vec<HeaderValue> {
+        const SEEDS: &[&str] = &[
+            "acme",
+            "ACME",
+            " acme ",
+            "acme,globex",
+            "acme;globex",
+            "../acme",
+            "acme/../globex",
+            "%61cme",
+            "",
+            "*",
+            "acme\tglobex",
+        ];
+        let mut values: Vec<HeaderValue> = SEEDS.iter().map(|s| HeaderValue::from_str(s).unwrap()).collect();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        while values.len() < count {
+            let mut bytes = Vec::new();
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            for i in 0..(state % 24) {
+                let byte = ((state >> (i % 8 * 8)) as u8 ^ i as u8) % 95 + 32;
+                bytes.push(byte);
+            }
+            if let Ok(value) = HeaderValue::from_bytes(&bytes) {
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    #[tokio::test]
+    async fn test_forged_tenant_headers_never_reach_the_backend() {
+        let (url, received) = backend().await;
+        let (app, _) = proxy(&url);
+        let names = [
+            HeaderName::from_static(X_TENANT_ID),
+            HeaderName::from_static(X_FEATURE_FLAGS),
+            HeaderName::from_static("x-forwarded-tenant-id"),
+            HeaderName::from_static("x-tenant"),
+            HeaderName::from_static("tenant-id"),
+        ];
+
+        let values = forged_values(200);
+        for (i, value) in values.iter().enumerate() {
+            let name = &names[i % names.len()];
+            let headers = [
+                (name.clone(), value.clone()),
+                (HeaderName::from_static(X_TENANT_ID), value.clone()),
+            ];
+            let response = app
+                .clone()
+                .oneshot(request(Some("globex-key"), &headers))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200, "forged {name}: {value:?}");
+        }
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), values.len());
+        for headers in received.iter() {
+            let tenants: Vec<_> = headers.get_all(X_TENANT_ID).iter().collect();
+            assert_eq!(tenants, ["globex"]);
+            assert!(headers.get(X_FEATURE_FLAGS).is_none());
+            for name in headers.keys() {
+                assert!(BACKEND_HEADERS.contains(&name.as_str()), "forwarded {name}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tenant_headers_without_valid_credentials_are_rejected() {
+        let (url, received) = backend().await;
+        let (app, _) = proxy(&url);
+
+        for value in forged_values(50) {
+            for token in [None, Some("acme"), Some("acme-key "), Some("Globex-Key")] {
+                let headers = [(HeaderName::from_static(X_TENANT_ID), value.clone())];
+                let response = app.clone().oneshot(request(token, &headers)).await.unwrap();
+                assert_eq!(response.status(), 401, "token {token:?}, tenant {value:?}");
+            }
+        }
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_ids_are_scoped_to_the_tenant() {
+        let (url, received) = backend().await;
+        let (app, sessions) = proxy(&url);
+        // acme's client session was recovered onto a new backend session
+        sessions
+            .set_session_id("acme", "client-1", "backend-acme".to_string())
+            .await;
+
+        let session = [(
+            HeaderName::from_static(MCP_SESSION_ID),
+            HeaderValue::from_static("client-1"),
+        )];
+        for token in ["globex-key", "acme-key"] {
+            let response = app
+                .clone()
+                .oneshot(request(Some(token), &session))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+        }
+
+        let received = received.lock().unwrap();
+        let sent = |i: usize| {
+            (
+                received[i].get(X_TENANT_ID).unwrap().to_str().unwrap(),
+                received[i].get(MCP_SESSION_ID).unwrap().to_str().unwrap(),
+            )
+        };
+        assert_eq!(sent(0), ("globex", "client-1"));
+        assert_eq!(sent(1), ("acme", "backend-acme"));
+    }
+
+    /// The `snapshot` event opening a tenant's presence stream on `doc_id`.
+    async fn presence_snapshot(app: &Router, token: &str, doc_id: &str) -> Value {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get(format!("/presence/{}", doc_id))
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let frame = response
+            .into_body()
+            .into_data_stream()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        let event = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(event.starts_with("event: snapshot"), "{event}");
+        let data = event.lines().find_map(|l| l.strip_prefix("data:")).unwrap();
+        serde_json::from_str(data.trim_start()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_presence_shows_agent_tool_calls_to_the_tenant_only() {
+        let (url, _) = backend().await;
+        let (app, _) = proxy(&url);
+
+        let call = Request::post("/mcp")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .header(header::AUTHORIZATION, "Bearer acme-key")
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"replace_text","arguments":{"doc_id":"d1","path":"/body/paragraph[0]"}}}"#,
+            ))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(call).await.unwrap().status(), 200);
+
+        let publish = |token: &str, participant: &str| {
+            Request::post("/presence/d1")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::from(
+                    serde_json::json!({
+                        "participant": participant,
+                        "kind": "selection",
+                        "path": "/body/paragraph[2]",
+                        "start": 0,
+                        "end": 5,
+                    })
+                    .to_string(),
+                ))
+                .unwrap()
+        };
+        let status = |request: Request| {
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+        assert_eq!(status(publish("globex-key", "mallory")).await, 204);
+        assert_eq!(status(publish("acme-key", AGENT_PARTICIPANT)).await, 400);
+        assert_eq!(status(publish("", "alice")).await, 401);
+
+        let acme = presence_snapshot(&app, "acme-key", "d1").await;
+        assert_eq!(acme["ttl_secs"], 60);
+        let presences = acme["presences"].as_array().unwrap();
+        assert_eq!(presences.len(), 1);
+        assert_eq!(presences[0]["participant"], AGENT_PARTICIPANT);
+        assert_eq!(presences[0]["kind"], "activity");
+        assert_eq!(presences[0]["label"], "replace_text");
+        assert_eq!(presences[0]["path"], "/body/paragraph[0]");
+
+        let globex = presence_snapshot(&app, "globex-key", "d1").await;
+        assert_eq!(globex["presences"][0]["participant"], "mallory");
+        assert_eq!(globex["presences"].as_array().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,191 @@
+//! JSON-RPC aware error responses.
+//!
+//! MCP clients treat non-2xx HTTP responses as opaque transport failures.
+//! When the failed request was a JSON-RPC request with an id, the proxy
+//! answers with a JSON-RPC error for that id instead, so the client can show
+//! the actual reason (backend down, payload too large, ...).
+//!
+//! Authentication failures keep their 401 status and `WWW-Authenticate`
+//! header so OAuth discovery still works; only the body changes. Everything
+//! else is returned as HTTP 200. Notifications and non-JSON-RPC bodies fall
+//! back to the plain HTTP error.
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde_json::{json, Value};
+
+use crate::error::ProxyError;
+
+/// Ids of the JSON-RPC request(s) in a body.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestIds {
+    Single(Value),
+    Batch(Vec<Value>),
+}
+
+/// Extract request ids from a JSON-RPC body. Returns `None` when the body is
+/// not JSON-RPC or only contains notifications.
+pub fn request_ids(body: &[u8]) -> Option<RequestIds> {
+    fn id_of(message: &Value) -> Option<Value> {
+        message.get("jsonrpc")?;
+        message.get("method")?;
+        message.get("id").filter(|id| !id.is_null()).cloned()
+    }
+
+    match serde_json::from_slice::<Value>(body).ok()? {
+        Value::Array(messages) => {
+            let ids: Vec<Value> = messages.iter().filter_map(id_of).collect();
+            (!ids.is_empty()).then_some(RequestIds::Batch(ids))
+        }
+        message => id_of(&message).map(RequestIds::Single),
+    }
+}
+
+/// Method of a single JSON-RPC message. Returns `None` for batches and
+/// non-JSON-RPC bodies.
+pub fn request_method(body: &[u8]) -> Option<String> {
+    let message = serde_json::from_slice::<Value>(body).ok()?;
+    message.get("jsonrpc")?;
+    message.get("method")?.as_str().map(str::to_string)
+}
+
+/// Document a single `tools/call` message acts on (its `doc_id` argument),
+/// for correlating proxy logs with the storage services'.
+pub fn tool_doc_id(body: &[u8]) -> Option<String> {
+    let message = serde_json::from_slice::<Value>(body).ok()?;
+    if message.get("method")?.as_str()? != "tools/call" {
+        return None;
+    }
+    message
+        .get("params")?
+        .get("arguments")?
+        .get("doc_id")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Tool name and arguments of a single `tools/call` message.
+pub fn tool_call(body: &[u8]) -> Option<(String, Value)> {
+    let message = serde_json::from_slice::<Value>(body).ok()?;
+    if message.get("method")?.as_str()? != "tools/call" {
+        return None;
+    }
+    let params = message.get("params")?;
+    let name = params.get("name")?.as_str()?.to_string();
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+    Some((name, arguments))
+}
+
+/// Render `err` as a JSON-RPC error addressed to `ids`, or as the plain HTTP
+/// error when there is nothing to address it to.
+pub fn error_response(err: ProxyError, ids: Option<&RequestIds>) -> Response {
+    let Some(ids) = ids else {
+        return err.into_response();
+    };
+
+    let (status, code) = err.status_and_code();
+    let error = json!({
+        "code": err.jsonrpc_code(),
+        "message": err.to_string(),
+        "data": { "code": code },
+    });
+    let envelope = |id: &Value| json!({ "jsonrpc": "2.0", "id": id, "error": error });
+    let body = match ids {
+        RequestIds::Single(id) => envelope(id),
+        RequestIds::Batch(ids) => Value::Array(ids.iter().map(envelope).collect()),
+    };
+
+    // Start from the plain response to keep headers such as WWW-Authenticate.
+    let mut response = err.into_response();
+    if status != StatusCode::UNAUTHORIZED {
+        *response.status_mut() = StatusCode::OK;
+    }
+    response.headers_mut().remove(header::CONTENT_LENGTH);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    *response.body_mut() = axum::body::Body::from(body.to_string());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_ids() {
+        assert_eq!(
+            request_ids(br#"{"jsonrpc":"2.0","id":7,"method":"tools/call"}"#),
+            Some(RequestIds::Single(json!(7)))
+        );
+        assert_eq!(
+            request_ids(br#"[{"jsonrpc":"2.0","id":"a","method":"x"},{"jsonrpc":"2.0","method":"notifications/y"}]"#),
+            Some(RequestIds::Batch(vec![json!("a")]))
+        );
+        assert_eq!(request_ids(br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#), None);
+        assert_eq!(request_ids(b"not json"), None);
+        assert_eq!(request_ids(b""), None);
+    }
+
+    #[test]
+    fn test_request_method() {
+        assert_eq!(
+            request_method(br#"{"jsonrpc":"2.0","id":1,"method":"tools/call"}"#).as_deref(),
+            Some("tools/call")
+        );
+        assert_eq!(request_method(br#"[{"jsonrpc":"2.0","id":1,"method":"x"}]"#), None);
+        assert_eq!(request_method(b""), None);
+    }
+
+    #[test]
+    fn test_tool_doc_id() {
+        assert_eq!(
+            tool_doc_id(br#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"query","arguments":{"doc_id":"a1b2"}}}"#).as_deref(),
+            Some("a1b2")
+        );
+        assert_eq!(
+            tool_doc_id(br#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"list_documents","arguments":{}}}"#),
+            None
+        );
+        assert_eq!(
+            tool_doc_id(br#"{"jsonrpc":"2.0","id":1,"method":"resources/read","params":{"arguments":{"doc_id":"x"}}}"#),
+            None
+        );
+        assert_eq!(tool_doc_id(b"not json"), None);
+    }
+
+    #[test]
+    fn test_tool_call() {
+        assert_eq!(
+            tool_call(br#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"export","arguments":{"format":"pdf"}}}"#),
+            Some(("export".to_string(), json!({"format": "pdf"})))
+        );
+        assert_eq!(
+            tool_call(br#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_response_shapes() {
+        let ids = RequestIds::Single(json!(3));
+        let response = error_response(
+            ProxyError::BackendUnavailable("down".into(), 8),
+            Some(&ids),
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["id"], 3);
+        assert_eq!(body["error"]["code"], -32000);
+        assert_eq!(body["error"]["data"]["code"], "BACKEND_UNAVAILABLE");
+
+        let response = error_response(ProxyError::Unauthorized, Some(&ids));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = error_response(ProxyError::BackendError("x".into()), None);
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+}
@@ -2,16 +2,24 @@
 //!
 //! This proxy:
 //! - Receives MCP Streamable HTTP requests (POST/GET/DELETE /mcp)
-//! - Validates PAT tokens via Cloudflare D1
+//! - Validates bearer tokens: PATs and OAuth tokens via Cloudflare D1, or
+//!   API keys from a file or the environment for self-hosted deployments
 //! - Extracts tenant_id from validated tokens
 //! - Forwards requests to the .NET MCP HTTP backend with X-Tenant-Id header
-//! - Streams responses (SSE or JSON) back to clients
+//! - Gates tool calls on per-tenant feature flags stored in D1
+//! - Streams responses (SSE or JSON) back to clients, optionally replaying
+//!   missed SSE events when a client reconnects
+//! - Routes canary tenants to an alternate backend, rolling back on errors
+//! - Optionally mirrors a share of sessions to a secondary backend
+//! - Broadcasts cursors, selections and the agent's tool calls per document
+//!   on /presence, for UIs showing who is editing what
+//! - Runs tenants' scheduled jobs (cron tool calls and syncs) stored in D1
 
 use std::sync::Arc;
 
 use axum::routing::{any, get};
 use axum::Router;
-use clap::Parser;
+use docx_storage_core::Reloadable;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder;
 use tokio::net::TcpListener;
@@ -20,31 +28,48 @@ use tower::Service;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
-use tracing_subscriber::EnvFilter;
 
 mod auth;
+mod body;
+mod canary;
 mod config;
+mod cron;
 mod error;
+mod flags;
 mod handlers;
+mod jsonrpc;
+mod mirror;
 mod oauth;
+mod presence;
+mod provider;
+mod replay;
+mod retry;
+mod scheduler;
 mod session;
+mod sse;
 
-use auth::{PatValidator, SharedPatValidator};
+use body::BodyLimits;
+use canary::CanaryRouter;
 use config::Config;
-use handlers::{health_handler, mcp_forward_handler, oauth_metadata_handler, upstream_health_handler, AppState};
-use oauth::{OAuthValidator, SharedOAuthValidator};
+use flags::{FeatureFlags, SharedFeatureFlags};
+use handlers::{
+    health_handler, mcp_forward_handler, oauth_metadata_handler, presence_publish_handler,
+    presence_subscribe_handler, upstream_health_handler, AppState,
+};
+use mirror::Mirror;
+use presence::PresenceHub;
+use replay::ReplayBuffers;
+use retry::RetryPolicy;
+use scheduler::{Scheduler, SchedulerSettings};
 use session::SessionRegistry;
+use sse::SseSettings;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let (config, config_source) = docx_storage_core::load_config::<Config>();
 
-    let config = Config::parse();
+    // Initialize logging
+    docx_storage_core::init_tracing(config.log_format);
 
     info!(
         "Starting docx-mcp-sse-proxy v{}",
@@ -53,45 +78,51 @@ async fn main() -> anyhow::Result<()> {
     info!("  Host: {}", config.host);
     info!("  Port: {}", config.port);
     info!("  Backend: {}", config.mcp_backend_url);
+    info!(
+        "  Backend retries: {} ({}ms doubling to {}ms, {}s per request)",
+        config.backend_max_retries,
+        config.backend_initial_backoff_ms,
+        config.backend_max_backoff_ms,
+        config.backend_timeout_secs
+    );
 
-    // Create PAT and OAuth validators if D1 credentials are configured
-    let (validator, oauth_validator): (Option<SharedPatValidator>, Option<SharedOAuthValidator>) =
-        if config.cloudflare_account_id.is_some()
-            && config.cloudflare_api_token.is_some()
-            && config.d1_database_id.is_some()
-        {
-            let account_id = config.cloudflare_account_id.clone().unwrap();
-            let api_token = config.cloudflare_api_token.clone().unwrap();
-            let database_id = config.d1_database_id.clone().unwrap();
-
-            info!("  Auth: D1 PAT + OAuth validation enabled");
-            info!(
-                "  Cache TTL: {}s (negative: {}s)",
-                config.pat_cache_ttl_secs, config.pat_negative_cache_ttl_secs
-            );
+    if config.local_mode {
+        info!("  Mode: local (single tenant, D1 disabled)");
+    }
 
-            let pat = Arc::new(PatValidator::new(
-                account_id.clone(),
-                api_token.clone(),
-                database_id.clone(),
-                config.pat_cache_ttl_secs,
-                config.pat_negative_cache_ttl_secs,
-            ));
+    // Bearer token validation (D1, API key file or environment)
+    let auth = provider::auth_provider_from_config(&config)?;
+    match &auth {
+        None if config.local_mode => info!("  Auth: none (local mode)"),
+        Some(auth) => {
+            info!("  Auth: {} provider", auth.name());
+            if auth.name() == "d1" {
+                info!(
+                    "  Cache TTL: {}s (negative: {}s)",
+                    config.pat_cache_ttl_secs, config.pat_negative_cache_ttl_secs
+                );
+            }
+        }
+        None => {
+            warn!("  Auth: DISABLED (AUTH_PROVIDER={})", config.auth_provider);
+            warn!("  Set D1 credentials, or AUTH_PROVIDER=file or env, to enable auth");
+        }
+    }
 
-            let oauth = Arc::new(OAuthValidator::new(
+    // Per-tenant feature flags, read from the same D1 database
+    let feature_flags: Option<SharedFeatureFlags> =
+        config.d1_credentials().map(|(account_id, api_token, database_id)| {
+            info!(
+                "  Feature flags: enabled (cache TTL: {}s)",
+                config.feature_flag_cache_ttl_secs
+            );
+            Arc::new(FeatureFlags::new(
                 account_id,
                 api_token,
                 database_id,
-                config.pat_cache_ttl_secs,
-                config.pat_negative_cache_ttl_secs,
-            ));
-
-            (Some(pat), Some(oauth))
-        } else {
-            warn!("  Auth: DISABLED (no D1 credentials configured)");
-            warn!("  Set CLOUDFLARE_ACCOUNT_ID, CLOUDFLARE_API_TOKEN, and D1_DATABASE_ID to enable auth");
-            (None, None)
-        };
+                config.feature_flag_cache_ttl_secs,
+            ))
+        });
 
     // Create HTTP client for forwarding
     let http_client = reqwest::Client::builder()
@@ -102,6 +133,27 @@ async fn main() -> anyhow::Result<()> {
     // Normalize backend URL (strip trailing slash)
     let backend_url = config.mcp_backend_url.trim_end_matches('/').to_string();
 
+    // Canary rollout to an alternate backend
+    let canary = CanaryRouter::from_config(&config);
+    if let Some(url) = canary.backend_url() {
+        info!(
+            "  Canary backend: {} ({} listed tenants, {}% of others)",
+            url,
+            config.canary_tenants.len(),
+            config.canary_percent
+        );
+    }
+
+    // Shadow traffic to a secondary backend
+    let mirror = Mirror::from_config(&config, http_client.clone());
+    if let Some(mirror) = &mirror {
+        info!(
+            "  Mirror backend: {} ({}% of sessions)",
+            mirror.backend_url(),
+            mirror.percent()
+        );
+    }
+
     // OAuth resource metadata config
     let resource_url = config.resource_url.clone();
     let auth_server_url = config.auth_server_url.clone();
@@ -112,17 +164,110 @@ async fn main() -> anyhow::Result<()> {
         info!("  Auth Server URL: {}", url);
     }
 
+    info!(
+        "  Max body: {} bytes (streaming above {} bytes)",
+        config.max_body_bytes, config.stream_body_threshold_bytes
+    );
+
+    let sse = SseSettings::from_config(&config);
+    info!(
+        "  SSE heartbeat: {:?}, idle timeout: {:?}",
+        sse.heartbeat, sse.idle_timeout
+    );
+
+    // Start the job scheduler if D1 is configured
+    if let (Some((account_id, api_token, database_id)), Some(feature_flags)) =
+        (config.d1_credentials(), feature_flags.clone())
+    {
+        if config.scheduler_poll_secs > 0 {
+            info!(
+                "  Scheduler: polling every {}s",
+                config.scheduler_poll_secs
+            );
+            let scheduler = Arc::new(Scheduler::new(
+                http_client.clone(),
+                account_id,
+                api_token,
+                database_id,
+                backend_url.clone(),
+                feature_flags,
+                SchedulerSettings {
+                    poll_interval: std::time::Duration::from_secs(config.scheduler_poll_secs),
+                    alert_webhook: config.scheduler_alert_webhook.clone(),
+                    alert_after: config.scheduler_alert_after.max(1),
+                    run_history: config.scheduler_run_history,
+                },
+            ));
+            tokio::spawn(scheduler.run());
+        }
+    }
+
+    // Client session → backend session routes, optionally kept across restarts
+    let sessions = Arc::new(match &config.session_store_file {
+        Some(path) => {
+            info!("  Session store: {}", path.display());
+            SessionRegistry::with_store(path).await
+        }
+        None => SessionRegistry::new(),
+    });
+    sessions.spawn_persistence();
+
+    // Replay of missed SSE events on reconnect
+    let replay = ReplayBuffers::from_config(&config);
+    if replay.is_some() {
+        info!(
+            "  SSE replay: {} events per session ({})",
+            config.sse_replay_events,
+            config
+                .sse_replay_dir
+                .as_ref()
+                .map_or_else(|| "in memory".to_string(), |dir| dir.display().to_string())
+        );
+    }
+
+    // Live cursors, selections and agent activity per document
+    let presence = PresenceHub::from_config(&config);
+    if presence.is_some() {
+        info!("  Presence: enabled (TTL {}s)", config.presence_ttl_secs);
+    }
+
     // Build application state
     let state = AppState {
-        validator,
-        oauth_validator,
+        auth,
+        feature_flags,
         backend_url,
+        canary: Reloadable::new(canary),
+        mirror,
         http_client,
-        sessions: Arc::new(SessionRegistry::new()),
+        sessions: sessions.clone(),
+        replay,
+        presence,
         resource_url,
         auth_server_url,
+        body_limits: Reloadable::new(BodyLimits::from_config(&config)),
+        retry_policy: Reloadable::new(RetryPolicy::from_config(&config)),
+        sse: Reloadable::new(sse),
     };
 
+    // Reload retry, body limit, SSE and canary settings with the configuration
+    // file (a reload also re-arms a rolled back canary)
+    if let Some(path) = config_source.path() {
+        info!("  Config file: {} (reloaded on change or SIGHUP)", path.display());
+    }
+    let (body_limits, retry_policy, sse, canary) = (
+        state.body_limits.clone(),
+        state.retry_policy.clone(),
+        state.sse.clone(),
+        state.canary.clone(),
+    );
+    config_source.watch(move |config: Config| {
+        body_limits.set(BodyLimits::from_config(&config));
+        retry_policy.set(RetryPolicy::from_config(&config));
+        sse.set(SseSettings::from_config(&config));
+        canary.set(CanaryRouter::from_config(&config));
+        Ok(())
+    });
+
     // Configure CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -139,6 +284,10 @@ async fn main() -> anyhow::Result<()> {
         )
         .route("/mcp", any(mcp_forward_handler))
         .route("/mcp/{*rest}", any(mcp_forward_handler))
+        .route(
+            "/presence/{doc_id}",
+            get(presence_subscribe_handler).post(presence_publish_handler),
+        )
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -175,6 +324,10 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    if let Err(e) = sessions.save().await {
+        warn!("Failed to save session store: {}", e);
+    }
+
     info!("Server shutdown complete");
     Ok(())
 }
@@ -0,0 +1,212 @@
+//! Traffic mirroring to a secondary backend.
+//!
+//! A share of client sessions is copied to `MIRROR_BACKEND_URL` so a new
+//! backend version can be validated against production traffic before
+//! cutover. Mirrored requests are sent in the background, their responses
+//! are read and discarded, and failures are only logged: the client never
+//! waits on the mirror.
+//!
+//! Sampling is per client session, since requests are only meaningful within
+//! their MCP session. When the primary backend accepts an `initialize`, a
+//! sampled session gets its own handshake on the mirror, and the session's
+//! later requests are sent there under the mirror's session ID. Sessions
+//! started before the mirror was configured, requests that arrive before the
+//! mirror's handshake completes, streamed bodies and GET streams are not
+//! mirrored.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, Method};
+use moka::future::Cache;
+use reqwest::Client as HttpClient;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::handlers::{reinitialize_session, FORWARD_HEADERS, MCP_SESSION_ID, X_TENANT_ID};
+
+/// Forget mirrored sessions that have not been used for this long.
+const SESSION_IDLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Upper bound on tracked mirrored sessions.
+const MAX_SESSIONS: u64 = 100_000;
+
+/// Timeout of each mirrored request.
+const MIRROR_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Copies sampled client sessions to a secondary backend.
+pub struct Mirror {
+    backend_url: String,
+    percent: u8,
+    http_client: HttpClient,
+    /// (tenant, client session ID) → mirror session ID.
+    sessions: Cache<(String, String), String>,
+}
+
+pub type SharedMirror = Arc<Mirror>;
+
+impl Mirror {
+    pub fn new(backend_url: &str, percent: u8, http_client: HttpClient) -> Self {
+        Self {
+            backend_url: backend_url.trim_end_matches('/').to_string(),
+            percent: percent.min(100),
+            http_client,
+            sessions: Cache::builder()
+                .max_capacity(MAX_SESSIONS)
+                .time_to_idle(SESSION_IDLE_TTL)
+                .build(),
+        }
+    }
+
+    /// The mirror configured in `config`, if any.
+    pub fn from_config(config: &Config, http_client: HttpClient) -> Option<SharedMirror> {
+        let url = config.mirror_backend_url.as_deref()?;
+        if config.mirror_percent == 0 {
+            return None;
+        }
+        Some(Arc::new(Self::new(url, config.mirror_percent, http_client)))
+    }
+
+    pub fn backend_url(&self) -> &str {
+        &self.backend_url
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Whether a client session is mirrored. The same session always gets
+    /// the same answer.
+    pub fn samples(&self, tenant_id: &str, client_session_id: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (tenant_id, client_session_id).hash(&mut hasher);
+        hasher.finish() % 100 < self.percent as u64
+    }
+
+    /// Start mirroring a session the primary backend just initialized, if it
+    /// is sampled: run the handshake on the mirror in the background.
+    pub fn start_session(self: &Arc<Self>, tenant_id: &str, client_session_id: &str) {
+        if !self.samples(tenant_id, client_session_id) {
+            return;
+        }
+        let mirror = Arc::clone(self);
+        let key = (tenant_id.to_string(), client_session_id.to_string());
+        tokio::spawn(async move {
+            match reinitialize_session(&mirror.http_client, &mirror.backend_url, &key.0).await {
+                Ok(mirror_session_id) => {
+                    debug!(
+                        "Mirroring session {} of tenant {} as {}",
+                        key.1, key.0, mirror_session_id
+                    );
+                    mirror.sessions.insert(key, mirror_session_id).await;
+                }
+                Err(e) => warn!(error = %e, "Mirror backend initialize failed"),
+            }
+        });
+    }
+
+    /// Send a copy of a client request to the mirror, if its session is
+    /// mirrored. A DELETE also ends the mirrored session.
+    pub async fn forward(
+        self: &Arc<Self>,
+        method: &Method,
+        path_and_query: &str,
+        client_headers: &HeaderMap,
+        tenant_id: &str,
+        client_session_id: &str,
+        body: Bytes,
+    ) {
+        let key = (tenant_id.to_string(), client_session_id.to_string());
+        let Some(mirror_session_id) = self.sessions.get(&key).await else {
+            return;
+        };
+        if method == Method::DELETE {
+            self.sessions.invalidate(&key).await;
+        }
+
+        let Ok(method) = reqwest::Method::from_bytes(method.as_str().as_bytes()) else {
+            return;
+        };
+        let mut req = self
+            .http_client
+            .request(method, format!("{}{}", self.backend_url, path_and_query))
+            .timeout(MIRROR_TIMEOUT)
+            .header(MCP_SESSION_ID, mirror_session_id)
+            .header(X_TENANT_ID, tenant_id);
+        for name in FORWARD_HEADERS
+            .iter()
+            .map(|h| h.as_str())
+            .chain([docx_storage_core::REQUEST_ID_HEADER, crate::flags::X_FEATURE_FLAGS])
+        {
+            if let Some(value) = client_headers.get(name).and_then(|v| v.to_str().ok()) {
+                req = req.header(name, value);
+            }
+        }
+        if !body.is_empty() {
+            req = req.body(body);
+        }
+
+        tokio::spawn(async move {
+            match req.send().await {
+                // Read the whole response so streamed (SSE) replies run to completion
+                Ok(resp) => {
+                    let status = resp.status();
+                    let _ = resp.bytes().await;
+                    if status.is_server_error() {
+                        info!(status = status.as_u16(), "Mirror backend returned an error");
+                    }
+                }
+                Err(e) => warn!(error = %e, "Mirrored request failed"),
+            }
+        });
+    }
+}
+
+/// Parse a mirrored share of sessions, in percent (0-100).
+pub fn parse_percent(s: &str) -> Result<u8, String> {
+    let percent = s
+        .trim()
+        .parse::<u8>()
+        .map_err(|e| format!("invalid percentage '{}': {}", s, e))?;
+    if percent > 100 {
+        return Err(format!("percentage must be at most 100, got {}", percent));
+    }
+    Ok(percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror(percent: u8) -> Mirror {
+        Mirror::new("http://mirror:5000/", percent, HttpClient::new())
+    }
+
+    #[test]
+    fn test_sampling_is_per_session_and_stable() {
+        let sessions: Vec<String> = (0..1000).map(|i| format!("session-{}", i)).collect();
+
+        // Built once: each HTTP client loads the TLS roots
+        let (none, all, half) = (mirror(0), mirror(100), mirror(50));
+        assert!(sessions.iter().all(|s| !none.samples("t1", s)));
+        assert!(sessions.iter().all(|s| all.samples("t1", s)));
+
+        let sampled = sessions.iter().filter(|s| half.samples("t1", s)).count();
+        assert!((400..600).contains(&sampled), "sampled {}", sampled);
+        assert!(sessions
+            .iter()
+            .all(|s| half.samples("t1", s) == half.samples("t1", s)));
+        assert_eq!(half.backend_url(), "http://mirror:5000");
+    }
+
+    #[test]
+    fn test_parse_percent() {
+        assert_eq!(parse_percent("25"), Ok(25));
+        assert_eq!(parse_percent(" 100 "), Ok(100));
+        assert!(parse_percent("101").is_err());
+        assert!(parse_percent("-1").is_err());
+    }
+}
@@ -4,8 +4,6 @@
 //! using the Cloudflare REST API. Always queries D1 directly (no cache) so that
 //! token revocation takes effect immediately.
 
-use std::sync::Arc;
-
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -227,8 +225,6 @@ impl OAuthValidator {
     }
 }
 
-pub type SharedOAuthValidator = Arc<OAuthValidator>;
-
 #[cfg(test)]
 mod tests {
     use super::*;
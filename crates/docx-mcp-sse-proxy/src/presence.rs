@@ -0,0 +1,243 @@
+//! Live presence on a document: who is looking where, and what the agent is
+//! doing.
+//!
+//! Clients publish their cursor or selection on a document with
+//! `POST /presence/{doc_id}` and subscribe to everyone's with
+//! `GET /presence/{doc_id}`, an SSE stream starting with a `snapshot` event
+//! (the current presences) followed by a `presence` event per change. The
+//! proxy publishes the agent's activity itself: every tool call on a document
+//! it forwards becomes an `activity` presence with the tool and the path it
+//! touches, so UIs can show what the agent is editing as it happens.
+//!
+//! Presence is kept in memory per tenant and document and isn't replayed:
+//! a presence not refreshed within the TTL (sent in the snapshot) is dropped
+//! without an event, so subscribers expire them too, and a participant
+//! leaving publishes `left`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::config::Config;
+
+/// Changes buffered per subscriber; a subscriber falling further behind
+/// skips the oldest ones.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Participant name of the agent's tool calls.
+pub const AGENT_PARTICIPANT: &str = "agent";
+
+/// What a presence shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceKind {
+    /// A caret at `path` (and `start`, a character offset in it)
+    Cursor,
+    /// A selection of `path` (from `start` to `end`)
+    Selection,
+    /// What the participant is doing, e.g. the agent's tool call (`label`) on `path`
+    Activity,
+    /// The participant is gone
+    Left,
+}
+
+/// One participant's presence on a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Presence {
+    /// Who: a user or client name, or [`AGENT_PARTICIPANT`]
+    pub participant: String,
+    pub kind: PresenceKind,
+    /// Document path (e.g. `/body/paragraph[3]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+    /// Free text shown next to the presence, e.g. the tool being called
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Unix milliseconds, set by the proxy
+    #[serde(default)]
+    pub updated_at_ms: i64,
+}
+
+/// The presences of one document and its subscribers.
+struct Channel {
+    tx: broadcast::Sender<Presence>,
+    current: HashMap<String, Presence>,
+}
+
+/// In-memory presence of every tenant's documents.
+pub struct PresenceHub {
+    channels: Mutex<HashMap<(String, String), Channel>>,
+    ttl: Duration,
+}
+
+impl PresenceHub {
+    /// Create a hub dropping presences not refreshed for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// The hub configured in `config`, if enabled.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        (config.presence_ttl_secs > 0)
+            .then(|| Arc::new(Self::new(Duration::from_secs(config.presence_ttl_secs))))
+    }
+
+    /// How long a presence lasts without being refreshed.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Record `presence` on a document and send it to its subscribers.
+    pub fn publish(&self, tenant_id: &str, doc_id: &str, mut presence: Presence) {
+        let now = chrono::Utc::now().timestamp_millis();
+        presence.updated_at_ms = now;
+
+        let mut channels = self.channels.lock().unwrap();
+        self.prune(&mut channels, now);
+        let channel = channels
+            .entry((tenant_id.to_string(), doc_id.to_string()))
+            .or_insert_with(|| Channel {
+                tx: broadcast::channel(CHANNEL_CAPACITY).0,
+                current: HashMap::new(),
+            });
+        if presence.kind == PresenceKind::Left {
+            channel.current.remove(&presence.participant);
+        } else {
+            channel
+                .current
+                .insert(presence.participant.clone(), presence.clone());
+        }
+        // No subscribers is fine: the presence is still in the snapshot
+        let _ = channel.tx.send(presence);
+    }
+
+    /// Publish the agent's tool call `tool` on a document, touching the
+    /// `path` argument if it has one.
+    pub fn publish_tool_call(
+        &self,
+        tenant_id: &str,
+        doc_id: &str,
+        tool: &str,
+        arguments: &Value,
+    ) {
+        self.publish(
+            tenant_id,
+            doc_id,
+            Presence {
+                participant: AGENT_PARTICIPANT.to_string(),
+                kind: PresenceKind::Activity,
+                path: arguments
+                    .get("path")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                start: None,
+                end: None,
+                label: Some(tool.to_string()),
+                updated_at_ms: 0,
+            },
+        );
+    }
+
+    /// The current presences on a document, oldest first, and a receiver of
+    /// the changes from now on.
+    pub fn subscribe(
+        &self,
+        tenant_id: &str,
+        doc_id: &str,
+    ) -> (Vec<Presence>, broadcast::Receiver<Presence>) {
+        let mut channels = self.channels.lock().unwrap();
+        self.prune(&mut channels, chrono::Utc::now().timestamp_millis());
+        let channel = channels
+            .entry((tenant_id.to_string(), doc_id.to_string()))
+            .or_insert_with(|| Channel {
+                tx: broadcast::channel(CHANNEL_CAPACITY).0,
+                current: HashMap::new(),
+            });
+        let mut current: Vec<Presence> = channel.current.values().cloned().collect();
+        current.sort_by(|a, b| {
+            a.updated_at_ms
+                .cmp(&b.updated_at_ms)
+                .then_with(|| a.participant.cmp(&b.participant))
+        });
+        (current, channel.tx.subscribe())
+    }
+
+    /// Drop expired presences, and documents nobody is present on or
+    /// subscribed to.
+    fn prune(&self, channels: &mut HashMap<(String, String), Channel>, now_ms: i64) {
+        let ttl_ms = self.ttl.as_millis() as i64;
+        channels.retain(|_, channel| {
+            channel.current.retain(|_, p| now_ms - p.updated_at_ms < ttl_ms);
+            !channel.current.is_empty() || channel.tx.receiver_count() > 0
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cursor(participant: &str, path: &str) -> Presence {
+        Presence {
+            participant: participant.to_string(),
+            kind: PresenceKind::Cursor,
+            path: Some(path.to_string()),
+            start: Some(4),
+            end: None,
+            label: None,
+            updated_at_ms: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_get_a_snapshot_then_changes() {
+        let hub = PresenceHub::new(Duration::from_secs(60));
+        hub.publish("acme", "doc", cursor("alice", "/body/paragraph[1]"));
+        hub.publish("globex", "doc", cursor("mallory", "/body/paragraph[9]"));
+
+        let (snapshot, mut rx) = hub.subscribe("acme", "doc");
+        assert_eq!(
+            snapshot.iter().map(|p| p.participant.as_str()).collect::<Vec<_>>(),
+            ["alice"],
+            "presence must be scoped to the tenant"
+        );
+
+        hub.publish_tool_call("acme", "doc", "replace_text", &json!({"path": "/body/table[0]"}));
+        let change = rx.recv().await.unwrap();
+        assert_eq!(change.participant, AGENT_PARTICIPANT);
+        assert_eq!(change.kind, PresenceKind::Activity);
+        assert_eq!(change.path.as_deref(), Some("/body/table[0]"));
+        assert_eq!(change.label.as_deref(), Some("replace_text"));
+
+        let mut left = cursor("alice", "/body/paragraph[1]");
+        left.kind = PresenceKind::Left;
+        hub.publish("acme", "doc", left);
+        assert_eq!(rx.recv().await.unwrap().kind, PresenceKind::Left);
+        let (snapshot, _) = hub.subscribe("acme", "doc");
+        assert_eq!(
+            snapshot.iter().map(|p| p.participant.as_str()).collect::<Vec<_>>(),
+            [AGENT_PARTICIPANT]
+        );
+    }
+
+    #[test]
+    fn test_stale_presences_expire() {
+        let hub = PresenceHub::new(Duration::ZERO);
+        hub.publish("acme", "doc", cursor("alice", "/body/paragraph[1]"));
+
+        let (snapshot, _) = hub.subscribe("acme", "doc");
+        assert!(snapshot.is_empty());
+    }
+}
@@ -0,0 +1,382 @@
+//! Pluggable bearer token authentication.
+//!
+//! The proxy resolves tokens to tenants through an [`AuthProvider`]:
+//! - `d1`: PATs and OAuth access tokens stored in Cloudflare D1
+//! - `file`: API keys listed in a JSON file, for self-hosted deployments
+//! - `env`: API keys given in the `API_KEYS` environment variable
+//!
+//! `auto` (the default) picks D1 when its credentials are configured, and
+//! otherwise runs without authentication. Local mode always runs without it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::auth::PatValidator;
+use crate::config::Config;
+use crate::error::{ProxyError, Result};
+use crate::oauth::OAuthValidator;
+
+/// Tenant a token was issued to, and how to refer to the token in logs
+/// without leaking it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub tenant_id: String,
+    pub credential: String,
+}
+
+/// Resolves bearer tokens to the tenant they authenticate.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Name of the provider, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Validate a bearer token. Unknown, revoked or expired tokens fail with
+    /// [`ProxyError::InvalidToken`].
+    async fn authenticate(&self, token: &str) -> Result<Identity>;
+}
+
+/// Shared provider wrapped in Arc.
+pub type SharedAuthProvider = Arc<dyn AuthProvider>;
+
+/// Which [`AuthProvider`] the proxy runs with (`AUTH_PROVIDER`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthProviderKind {
+    /// D1 when its credentials are configured, no authentication otherwise
+    #[default]
+    Auto,
+    D1,
+    File,
+    Env,
+    /// Every request goes to the default tenant
+    None,
+}
+
+impl FromStr for AuthProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(AuthProviderKind::Auto),
+            "d1" => Ok(AuthProviderKind::D1),
+            "file" => Ok(AuthProviderKind::File),
+            "env" => Ok(AuthProviderKind::Env),
+            "none" => Ok(AuthProviderKind::None),
+            _ => Err(format!(
+                "unknown auth provider '{}' (expected auto, d1, file, env or none)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AuthProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuthProviderKind::Auto => "auto",
+            AuthProviderKind::D1 => "d1",
+            AuthProviderKind::File => "file",
+            AuthProviderKind::Env => "env",
+            AuthProviderKind::None => "none",
+        })
+    }
+}
+
+/// Build the provider selected in `config`, or `None` to run without
+/// authentication.
+pub fn auth_provider_from_config(config: &Config) -> anyhow::Result<Option<SharedAuthProvider>> {
+    if config.local_mode {
+        if !matches!(config.auth_provider, AuthProviderKind::Auto | AuthProviderKind::None) {
+            anyhow::bail!(
+                "LOCAL_MODE serves a single local tenant without authentication; unset AUTH_PROVIDER={}",
+                config.auth_provider
+            );
+        }
+        return Ok(None);
+    }
+    let d1 = config.d1_credentials();
+
+    let provider: SharedAuthProvider = match config.auth_provider {
+        AuthProviderKind::None => return Ok(None),
+        AuthProviderKind::Auto if d1.is_none() => return Ok(None),
+        AuthProviderKind::Auto | AuthProviderKind::D1 => {
+            let (account_id, api_token, database_id) = d1.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "the d1 auth provider needs CLOUDFLARE_ACCOUNT_ID, CLOUDFLARE_API_TOKEN and D1_DATABASE_ID"
+                )
+            })?;
+            let pats = PatValidator::new(
+                account_id.clone(),
+                api_token.clone(),
+                database_id.clone(),
+                config.pat_cache_ttl_secs,
+                config.pat_negative_cache_ttl_secs,
+            );
+            let oauth = OAuthValidator::new(
+                account_id,
+                api_token,
+                database_id,
+                config.pat_cache_ttl_secs,
+                config.pat_negative_cache_ttl_secs,
+            );
+            Arc::new(D1AuthProvider::new(pats, oauth))
+        }
+        AuthProviderKind::File => {
+            let path = config
+                .auth_keys_file
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("the file auth provider needs AUTH_KEYS_FILE"))?;
+            Arc::new(StaticKeyProvider::from_file(path)?)
+        }
+        AuthProviderKind::Env => {
+            if config.api_keys.is_empty() {
+                anyhow::bail!("the env auth provider needs API_KEYS");
+            }
+            Arc::new(StaticKeyProvider::from_keys(&config.api_keys))
+        }
+    };
+    Ok(Some(provider))
+}
+
+/// PATs (`dxs_...`) and OAuth access tokens (`oat_...`) validated against D1.
+pub struct D1AuthProvider {
+    pats: PatValidator,
+    oauth: OAuthValidator,
+}
+
+impl D1AuthProvider {
+    pub fn new(pats: PatValidator, oauth: OAuthValidator) -> Self {
+        Self { pats, oauth }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for D1AuthProvider {
+    fn name(&self) -> &'static str {
+        "d1"
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Identity> {
+        if OAuthValidator::is_oauth_token(token) {
+            let validation = self.oauth.validate(token).await?;
+            Ok(Identity {
+                tenant_id: validation.tenant_id,
+                credential: format!("OAuth: {}...", &token[..12.min(token.len())]),
+            })
+        } else {
+            let validation = self.pats.validate(token).await?;
+            Ok(Identity {
+                tenant_id: validation.tenant_id,
+                credential: format!(
+                    "PAT: {}...",
+                    &validation.pat_id[..8.min(validation.pat_id.len())]
+                ),
+            })
+        }
+    }
+}
+
+/// One key of an API key file.
+#[derive(Debug, Deserialize)]
+struct KeyEntry {
+    /// Hex SHA-256 of the key, so the file holds no usable secret
+    sha256: String,
+    tenant_id: String,
+    /// Shown in logs instead of the key
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyFile {
+    keys: Vec<KeyEntry>,
+}
+
+/// A known API key.
+#[derive(Debug, Clone)]
+struct ApiKey {
+    tenant_id: String,
+    name: String,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// API keys fixed at startup, from a file or the environment. Keys are held
+/// by the SHA-256 of their value only.
+pub struct StaticKeyProvider {
+    name: &'static str,
+    keys: HashMap<String, ApiKey>,
+}
+
+impl StaticKeyProvider {
+    /// Read keys from a JSON file:
+    ///
+    /// ```json
+    /// {"keys": [{"sha256": "<hex sha256 of the key>", "tenant_id": "acme",
+    ///            "name": "ci", "expires_at": "2027-01-01T00:00:00Z"}]}
+    /// ```
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("cannot read {}: {}", path.display(), e))?;
+        let file: KeyFile = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("invalid API key file {}: {}", path.display(), e))?;
+
+        let mut keys = HashMap::new();
+        for (i, entry) in file.keys.into_iter().enumerate() {
+            let hash = entry.sha256.trim().to_ascii_lowercase();
+            if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                anyhow::bail!(
+                    "key {} of {}: sha256 must be 64 hex digits",
+                    i,
+                    path.display()
+                );
+            }
+            let key = ApiKey {
+                name: entry.name.unwrap_or_else(|| format!("key {}", i)),
+                tenant_id: entry.tenant_id,
+                expires_at: entry.expires_at,
+            };
+            keys.insert(hash, key);
+        }
+        Ok(Self { name: "file", keys })
+    }
+
+    /// Keys given as `(tenant_id, key)` pairs, named after their tenant.
+    pub fn from_keys(keys: &[(String, String)]) -> Self {
+        let keys = keys
+            .iter()
+            .map(|(tenant_id, key)| {
+                let key_info = ApiKey {
+                    tenant_id: tenant_id.clone(),
+                    name: format!("{} API key", tenant_id),
+                    expires_at: None,
+                };
+                (hash_token(key), key_info)
+            })
+            .collect();
+        Self { name: "env", keys }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for StaticKeyProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Identity> {
+        let key = self
+            .keys
+            .get(&hash_token(token))
+            .ok_or(ProxyError::InvalidToken)?;
+        if key
+            .expires_at
+            .is_some_and(|expires| expires < chrono::Utc::now())
+        {
+            return Err(ProxyError::InvalidToken);
+        }
+        Ok(Identity {
+            tenant_id: key.tenant_id.clone(),
+            credential: key.name.clone(),
+        })
+    }
+}
+
+/// Parse a `TENANT_ID=KEY` pair of `API_KEYS`.
+pub fn parse_api_key(s: &str) -> std::result::Result<(String, String), String> {
+    let (tenant, key) = s
+        .split_once('=')
+        .ok_or_else(|| "expected TENANT_ID=KEY".to_string())?;
+    let (tenant, key) = (tenant.trim(), key.trim());
+    if tenant.is_empty() || key.is_empty() {
+        return Err("expected TENANT_ID=KEY with both parts set".to_string());
+    }
+    Ok((tenant.to_string(), key.to_string()))
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn test_env_keys_resolve_to_their_tenant() {
+        let provider = StaticKeyProvider::from_keys(&[
+            ("acme".to_string(), "k-acme".to_string()),
+            ("globex".to_string(), "k-globex".to_string()),
+        ]);
+
+        let identity = provider.authenticate("k-globex").await.unwrap();
+        assert_eq!(identity.tenant_id, "globex");
+        assert!(matches!(
+            provider.authenticate("k-unknown").await,
+            Err(ProxyError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_file_skips_expired_keys() {
+        let dir = std::env::temp_dir().join(format!("proxy-keys-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("keys.json");
+        let keys = serde_json::json!({"keys": [
+            {"sha256": hash_token("live"), "tenant_id": "acme", "name": "ci"},
+            {"sha256": hash_token("old"), "tenant_id": "acme", "expires_at": "2020-01-01T00:00:00Z"},
+        ]});
+        std::fs::write(&path, keys.to_string()).unwrap();
+
+        let provider = StaticKeyProvider::from_file(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(provider.keys.len(), 2);
+        assert_eq!(
+            provider.authenticate("live").await.unwrap(),
+            Identity {
+                tenant_id: "acme".to_string(),
+                credential: "ci".to_string(),
+            }
+        );
+        assert!(provider.authenticate("old").await.is_err());
+    }
+
+    #[test]
+    fn test_local_mode_ignores_d1_credentials() {
+        let parse = |args: &[&str]| {
+            let base = [
+                "docx-mcp-proxy",
+                "--mcp-backend-url=http://localhost:5000",
+                "--cloudflare-account-id=acct",
+                "--cloudflare-api-token=token",
+                "--d1-database-id=db",
+            ];
+            Config::try_parse_from(base.iter().chain(args)).unwrap()
+        };
+
+        assert!(parse(&[]).d1_credentials().is_some());
+        let local = parse(&["--local-mode"]);
+        assert!(local.d1_credentials().is_none());
+        assert!(auth_provider_from_config(&local).unwrap().is_none());
+        assert!(auth_provider_from_config(&parse(&["--local-mode", "--auth-provider=d1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_api_key() {
+        assert_eq!(
+            parse_api_key("acme=k=with=equals").unwrap(),
+            ("acme".to_string(), "k=with=equals".to_string())
+        );
+        assert!(parse_api_key("acme").is_err());
+        assert!(parse_api_key("=key").is_err());
+    }
+}
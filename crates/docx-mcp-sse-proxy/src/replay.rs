@@ -0,0 +1,311 @@
+//! Replay of missed SSE events when a client reconnects.
+//!
+//! The events of a client session's SSE responses are kept in a bounded
+//! buffer. When the client reconnects its GET stream with `Last-Event-ID`
+//! and that event is still buffered, the proxy replays the events after it
+//! and opens the backend stream without the header, instead of depending on
+//! the backend to resume. An unknown ID is passed to the backend as before.
+//!
+//! Events the backend sent without an ID get one from the proxy
+//! (`proxy-<n>`), so clients always have something to resume from. Comments
+//! (heartbeats) are neither buffered nor given IDs. With a replay directory,
+//! each session's buffer is also written to disk and survives restarts.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use futures::{Stream, StreamExt};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::session::write_atomically;
+
+/// Forget the events of sessions that have not been used for this long.
+const SESSION_IDLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Upper bound on sessions with buffered events in memory.
+const MAX_SESSIONS: u64 = 10_000;
+
+/// Per-session buffers of recent SSE events.
+pub struct ReplayBuffers {
+    sessions: Cache<(String, String), Arc<AsyncMutex<SessionEvents>>>,
+    max_events: usize,
+    /// Directory the buffers are persisted to (None = in memory only).
+    dir: Option<PathBuf>,
+}
+
+/// The buffered events of one client session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionEvents {
+    /// Number of the next proxy-assigned event ID.
+    next_id: u64,
+    /// (event ID, raw event with its terminating blank line), oldest first.
+    events: VecDeque<(String, String)>,
+}
+
+impl ReplayBuffers {
+    pub fn new(max_events: usize, dir: Option<PathBuf>) -> Self {
+        Self {
+            sessions: Cache::builder()
+                .max_capacity(MAX_SESSIONS)
+                .time_to_idle(SESSION_IDLE_TTL)
+                .build(),
+            max_events,
+            dir,
+        }
+    }
+
+    /// The replay buffers configured in `config`, if enabled.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        (config.sse_replay_events > 0).then(|| {
+            Arc::new(Self::new(
+                config.sse_replay_events,
+                config.sse_replay_dir.clone(),
+            ))
+        })
+    }
+
+    /// File persisting a session's buffer.
+    fn file(&self, tenant_id: &str, client_session_id: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        let mut hasher = Sha256::new();
+        hasher.update(tenant_id.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(client_session_id.as_bytes());
+        Some(dir.join(format!("{}.json", hex::encode(hasher.finalize()))))
+    }
+
+    /// Get the buffer of a client session, loading it from disk if persisted.
+    async fn session(
+        &self,
+        tenant_id: &str,
+        client_session_id: &str,
+    ) -> Arc<AsyncMutex<SessionEvents>> {
+        let file = self.file(tenant_id, client_session_id);
+        self.sessions
+            .get_with(
+                (tenant_id.to_string(), client_session_id.to_string()),
+                async move {
+                    let events = match file {
+                        Some(file) => tokio::fs::read(&file)
+                            .await
+                            .ok()
+                            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                            .unwrap_or_default(),
+                        None => SessionEvents::default(),
+                    };
+                    Arc::new(AsyncMutex::new(events))
+                },
+            )
+            .await
+    }
+
+    /// The events a client missed after `last_event_id`, or `None` when that
+    /// event isn't buffered.
+    pub async fn events_after(
+        &self,
+        tenant_id: &str,
+        client_session_id: &str,
+        last_event_id: &str,
+    ) -> Option<Vec<Bytes>> {
+        let session = self.session(tenant_id, client_session_id).await;
+        let session = session.lock().await;
+        let position = session.events.iter().position(|(id, _)| id == last_event_id)?;
+        Some(
+            session
+                .events
+                .iter()
+                .skip(position + 1)
+                .map(|(_, event)| Bytes::from(event.clone()))
+                .collect(),
+        )
+    }
+
+    /// Forget a client session's events (e.g. after the client deleted it).
+    pub async fn forget(&self, tenant_id: &str, client_session_id: &str) {
+        self.sessions
+            .invalidate(&(tenant_id.to_string(), client_session_id.to_string()))
+            .await;
+        if let Some(file) = self.file(tenant_id, client_session_id) {
+            let _ = tokio::fs::remove_file(file).await;
+        }
+    }
+
+    /// Buffer one event, giving it an ID if it has none. Returns the event
+    /// as it is sent to the client.
+    async fn record_event(
+        &self,
+        tenant_id: &str,
+        client_session_id: &str,
+        raw: Vec<u8>,
+    ) -> Bytes {
+        let text = String::from_utf8_lossy(&raw).into_owned();
+        let lines = || text.lines().map(|l| l.trim_end_matches('\r'));
+        if !lines().any(|l| l.starts_with("data")) {
+            return Bytes::from(raw);
+        }
+        let id = lines()
+            .filter_map(|l| l.strip_prefix("id:"))
+            .next_back()
+            .map(|id| id.strip_prefix(' ').unwrap_or(id).to_string());
+        if id.as_deref() == Some("") {
+            // An empty ID resets the client's last event ID: nothing to resume
+            return Bytes::from(raw);
+        }
+
+        let session = self.session(tenant_id, client_session_id).await;
+        let mut session = session.lock().await;
+        let (id, event) = match id {
+            Some(id) => (id, text),
+            None => {
+                let id = format!("proxy-{}", session.next_id);
+                session.next_id += 1;
+                let event = format!("id: {}\n{}", id, text);
+                (id, event)
+            }
+        };
+        session.events.push_back((id, event.clone()));
+        while session.events.len() > self.max_events {
+            session.events.pop_front();
+        }
+        if let Some(file) = self.file(tenant_id, client_session_id) {
+            let saved = match serde_json::to_vec(&*session) {
+                Ok(bytes) => write_atomically(&file, &bytes).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = saved {
+                warn!("Failed to persist SSE replay buffer {}: {}", file.display(), e);
+            }
+        }
+        Bytes::from(event)
+    }
+
+    /// Pass a backend SSE stream through, buffering its events. Events are
+    /// forwarded whole, once their terminating blank line has arrived.
+    pub fn record(
+        self: &Arc<Self>,
+        tenant_id: &str,
+        client_session_id: &str,
+        upstream: impl Stream<Item = reqwest::Result<Bytes>> + Send + 'static,
+    ) -> impl Stream<Item = reqwest::Result<Bytes>> + Send + 'static {
+        let state = RecordState {
+            buffers: Arc::clone(self),
+            tenant_id: tenant_id.to_string(),
+            client_session_id: client_session_id.to_string(),
+            upstream: upstream.boxed(),
+            pending: Vec::new(),
+            done: false,
+        };
+        futures::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(end) = event_end(&state.pending) {
+                    let raw: Vec<u8> = state.pending.drain(..end).collect();
+                    let event = state
+                        .buffers
+                        .record_event(&state.tenant_id, &state.client_session_id, raw)
+                        .await;
+                    return Some((Ok(event), state));
+                }
+                if state.done {
+                    // Pass on an unterminated trailing event as it is
+                    if state.pending.is_empty() {
+                        return None;
+                    }
+                    let rest = std::mem::take(&mut state.pending);
+                    return Some((Ok(Bytes::from(rest)), state));
+                }
+                match state.upstream.next().await {
+                    Some(Ok(bytes)) => state.pending.extend_from_slice(&bytes),
+                    Some(Err(e)) => {
+                        state.done = true;
+                        state.pending.clear();
+                        return Some((Err(e), state));
+                    }
+                    None => state.done = true,
+                }
+            }
+        })
+    }
+}
+
+struct RecordState {
+    buffers: Arc<ReplayBuffers>,
+    tenant_id: String,
+    client_session_id: String,
+    upstream: futures::stream::BoxStream<'static, reqwest::Result<Bytes>>,
+    /// Bytes received after the last complete event.
+    pending: Vec<u8>,
+    done: bool,
+}
+
+/// Length of the first complete event in `buf`, blank line included.
+fn event_end(buf: &[u8]) -> Option<usize> {
+    let find = |terminator: &[u8]| {
+        buf.windows(terminator.len())
+            .position(|w| w == terminator)
+            .map(|i| i + terminator.len())
+    };
+    match (find(b"\n\n"), find(b"\r\n\r\n")) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn record(buffers: &Arc<ReplayBuffers>, chunks: Vec<&'static [u8]>) -> Vec<Bytes> {
+        let upstream = futures::stream::iter(chunks.into_iter().map(|c| Ok(Bytes::from_static(c))));
+        buffers
+            .record("t1", "client-a", upstream)
+            .map(|c| c.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_replays_events_after_last_id() {
+        let buffers = Arc::new(ReplayBuffers::new(2, None));
+        let sent = record(
+            &buffers,
+            vec![
+                b"id: 7\ndata: a\n\n: keepalive\n\nda",
+                b"ta: b\n\n",
+                b"id: 9\ndata: c\n\n",
+            ],
+        )
+        .await;
+        assert_eq!(&sent[0][..], b"id: 7\ndata: a\n\n");
+        assert_eq!(&sent[1][..], b": keepalive\n\n");
+        assert_eq!(&sent[2][..], b"id: proxy-0\ndata: b\n\n");
+
+        let missed = buffers.events_after("t1", "client-a", "proxy-0").await.unwrap();
+        assert_eq!(missed, vec![Bytes::from_static(b"id: 9\ndata: c\n\n")]);
+        // Only the last 2 events are kept
+        assert!(buffers.events_after("t1", "client-a", "7").await.is_none());
+        assert!(buffers.events_after("t1", "client-b", "proxy-0").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_persisted_buffers_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("proxy-replay-{}", std::process::id()));
+        let buffers = Arc::new(ReplayBuffers::new(10, Some(dir.clone())));
+        record(&buffers, vec![b"data: a\n\n", b"data: b\n\n"]).await;
+
+        let restarted = ReplayBuffers::new(10, Some(dir.clone()));
+        let missed = restarted.events_after("t1", "client-a", "proxy-0").await.unwrap();
+        assert_eq!(missed, vec![Bytes::from_static(b"id: proxy-1\ndata: b\n\n")]);
+
+        restarted.forget("t1", "client-a").await;
+        assert!(restarted.events_after("t1", "client-a", "proxy-0").await.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,102 @@
+//! Retry policy for requests forwarded to the backend.
+//!
+//! Transient backend failures (502/503, connection errors) are retried with
+//! exponential backoff. The retry count can be overridden per JSON-RPC
+//! method, e.g. to let `initialize` wait longer for a cold backend while
+//! `tools/call` fails fast.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Backend retry settings.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    /// JSON-RPC method → retries, overriding `max_retries`.
+    methods: HashMap<String, u32>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// Timeout for each individual backend request.
+    pub request_timeout: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_retries: config.backend_max_retries,
+            methods: config.backend_method_retries.iter().cloned().collect(),
+            initial_backoff: Duration::from_millis(config.backend_initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.backend_max_backoff_ms),
+            request_timeout: Duration::from_secs(config.backend_timeout_secs),
+        }
+    }
+
+    /// Retries allowed for a request calling `method` (`None` for GET/DELETE
+    /// and bodies that are not a single JSON-RPC request).
+    pub fn max_retries_for(&self, method: Option<&str>) -> u32 {
+        method
+            .and_then(|m| self.methods.get(m))
+            .copied()
+            .unwrap_or(self.max_retries)
+    }
+
+    /// Delay before retry number `attempt` (starting at 1).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.initial_backoff
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_backoff)
+    }
+}
+
+/// Parse a `method=retries` pair from the command line.
+pub fn parse_retries(s: &str) -> Result<(String, u32), String> {
+    let (method, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected METHOD=RETRIES, got '{}'", s))?;
+    let retries = value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid retry count '{}': {}", value, e))?;
+    Ok((method.trim().to_string(), retries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 8,
+            methods: HashMap::from([("tools/call".to_string(), 2)]),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_millis(5_000),
+            request_timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn test_max_retries_for() {
+        let policy = policy();
+        assert_eq!(policy.max_retries_for(Some("tools/call")), 2);
+        assert_eq!(policy.max_retries_for(Some("initialize")), 8);
+        assert_eq!(policy.max_retries_for(None), 8);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let policy = policy();
+        let delays: Vec<u128> = (1..=6).map(|a| policy.backoff(a).as_millis()).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 5000, 5000]);
+        assert_eq!(policy.backoff(200), Duration::from_millis(5_000));
+    }
+
+    #[test]
+    fn test_parse_retries() {
+        assert_eq!(parse_retries("tools/call=3"), Ok(("tools/call".to_string(), 3)));
+        assert!(parse_retries("tools/call").is_err());
+        assert!(parse_retries("tools/call=-1").is_err());
+    }
+}
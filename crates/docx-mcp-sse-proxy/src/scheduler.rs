@@ -0,0 +1,662 @@
+//! Scheduled document jobs.
+//!
+//! Tenants register jobs in the D1 `scheduled_job` table: a cron expression
+//! and an action, either an MCP tool call (e.g. a recurring report
+//! generation) or a sync of a document with its source. The proxy polls for
+//! due jobs and runs each one against the .NET backend in a fresh MCP session
+//! for the job's tenant, exactly as a client would.
+//!
+//! Every run is recorded in `scheduled_job_run` (the last few per job are
+//! kept). When a job fails `alert_after` times in a row, a JSON alert is
+//! POSTed to the job's webhook, or the global one.
+//!
+//! Several proxy replicas may poll the same database: a run is only started
+//! by the replica whose conditional update of `nextRunAt` succeeds.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::Client as HttpClient;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
+
+use crate::cron::CronSchedule;
+use crate::error::{ProxyError, Result};
+use crate::flags::{SharedFeatureFlags, X_FEATURE_FLAGS};
+use crate::handlers::{reinitialize_session, MCP_SESSION_ID, X_TENANT_ID};
+
+/// JSON-RPC id of the scheduled tools/call request.
+const CALL_ID: i64 = 1;
+
+/// Longest tool output kept in the run history.
+const MAX_OUTPUT_CHARS: usize = 4_000;
+
+/// Timeout for failure alert webhooks.
+const ALERT_TIMEOUT_SECS: u64 = 10;
+
+/// What a job does when it fires.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobAction {
+    /// Call an MCP tool with fixed arguments.
+    Tool {
+        tool: String,
+        #[serde(default)]
+        arguments: Value,
+    },
+    /// Push a document to its source, or pull external changes into it.
+    Sync {
+        doc_id: String,
+        #[serde(default)]
+        direction: SyncDirection,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDirection {
+    #[default]
+    Push,
+    Pull,
+}
+
+impl JobAction {
+    /// The tool name and arguments sent to the backend.
+    pub fn tool_call(&self) -> (String, Value) {
+        match self {
+            JobAction::Tool { tool, arguments } => {
+                let arguments = if arguments.is_null() {
+                    json!({})
+                } else {
+                    arguments.clone()
+                };
+                (tool.clone(), arguments)
+            }
+            JobAction::Sync { doc_id, direction } => {
+                let tool = match direction {
+                    SyncDirection::Push => "document_save",
+                    SyncDirection::Pull => "sync_external_changes",
+                };
+                (tool.to_string(), json!({ "doc_id": doc_id }))
+            }
+        }
+    }
+}
+
+/// Result of one run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub success: bool,
+    pub output: String,
+}
+
+impl RunOutcome {
+    fn failure(output: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            output: output.into(),
+        }
+    }
+}
+
+/// Interpret the backend's answer to the scheduled tools/call, either a JSON
+/// body or an SSE stream carrying the response event.
+///
+/// Tools report validation problems as a successful result whose text starts
+/// with "Error:", so those count as failures too.
+pub fn parse_tool_response(content_type: &str, body: &str) -> RunOutcome {
+    let messages: Vec<Value> = if content_type.contains("text/event-stream") {
+        body.split("\n\n")
+            .filter_map(|event| {
+                let data: Vec<&str> = event
+                    .lines()
+                    .filter_map(|l| l.strip_prefix("data:"))
+                    .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                    .collect();
+                serde_json::from_str(&data.join("\n")).ok()
+            })
+            .collect()
+    } else {
+        serde_json::from_str(body).into_iter().collect()
+    };
+
+    let Some(response) = messages
+        .into_iter()
+        .find(|m| m.get("id").and_then(Value::as_i64) == Some(CALL_ID))
+    else {
+        return RunOutcome::failure("Backend returned no response to the tool call");
+    };
+
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown error");
+        return RunOutcome::failure(truncate(message));
+    }
+
+    let result = response.get("result").cloned().unwrap_or(Value::Null);
+    let text = result
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|c| c.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    let is_error = result.get("isError").and_then(Value::as_bool) == Some(true)
+        || text.trim_start().starts_with("Error:");
+
+    RunOutcome {
+        success: !is_error,
+        output: truncate(&text),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    // Same format as JavaScript's toISOString, so stored values compare as text
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Scheduler settings from the command line.
+#[derive(Debug, Clone)]
+pub struct SchedulerSettings {
+    pub poll_interval: Duration,
+    pub alert_webhook: Option<String>,
+    pub alert_after: u32,
+    pub run_history: u32,
+}
+
+/// A job row from D1.
+#[derive(Debug, Clone, Deserialize)]
+struct JobRecord {
+    id: String,
+    #[serde(rename = "tenantId")]
+    tenant_id: String,
+    name: String,
+    cron: String,
+    action: String,
+    #[serde(rename = "alertWebhook")]
+    alert_webhook: Option<String>,
+    #[serde(rename = "nextRunAt")]
+    next_run_at: Option<String>,
+    #[serde(rename = "consecutiveFailures")]
+    consecutive_failures: i64,
+}
+
+/// D1 query request body.
+#[derive(Serialize)]
+struct D1QueryRequest {
+    sql: String,
+    params: Vec<Value>,
+}
+
+/// D1 API response structure.
+#[derive(Deserialize)]
+struct D1Response {
+    success: bool,
+    result: Option<Vec<D1QueryResult>>,
+    errors: Option<Vec<D1Error>>,
+}
+
+#[derive(Deserialize)]
+struct D1QueryResult {
+    results: Vec<Value>,
+    meta: Option<D1Meta>,
+}
+
+#[derive(Deserialize)]
+struct D1Meta {
+    #[serde(default)]
+    changes: u64,
+}
+
+#[derive(Deserialize)]
+struct D1Error {
+    message: String,
+}
+
+/// Polls D1 for due jobs and runs them against the backend.
+pub struct Scheduler {
+    client: HttpClient,
+    account_id: String,
+    api_token: String,
+    database_id: String,
+    backend_url: String,
+    feature_flags: SharedFeatureFlags,
+    settings: SchedulerSettings,
+}
+
+impl Scheduler {
+    /// Create a new scheduler.
+    pub fn new(
+        client: HttpClient,
+        account_id: String,
+        api_token: String,
+        database_id: String,
+        backend_url: String,
+        feature_flags: SharedFeatureFlags,
+        settings: SchedulerSettings,
+    ) -> Self {
+        Self {
+            client,
+            account_id,
+            api_token,
+            database_id,
+            backend_url,
+            feature_flags,
+            settings,
+        }
+    }
+
+    /// Poll for due jobs until the process exits.
+    pub async fn run(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.settings.poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.clone().tick(Utc::now()).await {
+                warn!("Scheduler poll failed: {}", e);
+            }
+        }
+    }
+
+    /// Claim and start every job that is due at `now`.
+    async fn tick(self: Arc<Self>, now: DateTime<Utc>) -> Result<()> {
+        let (rows, _) = self
+            .query(
+                "SELECT id, tenantId, name, cron, action, alertWebhook, nextRunAt, consecutiveFailures \
+                 FROM scheduled_job WHERE enabled = 1 AND (nextRunAt IS NULL OR nextRunAt <= ?1)",
+                vec![json!(timestamp(now))],
+            )
+            .await?;
+
+        for row in rows {
+            let job: JobRecord = match serde_json::from_value(row) {
+                Ok(job) => job,
+                Err(e) => {
+                    warn!("Failed to parse scheduled job: {}", e);
+                    continue;
+                }
+            };
+
+            let next = match CronSchedule::parse(&job.cron) {
+                Ok(cron) => cron.next_after(now),
+                Err(e) => {
+                    self.disable(&job, &e).await;
+                    continue;
+                }
+            };
+            let Some(next) = next else {
+                self.disable(&job, &format!("Cron expression '{}' never fires", job.cron))
+                    .await;
+                continue;
+            };
+
+            if !self.claim(&job, next).await? {
+                debug!("Job {} was claimed by another replica", job.id);
+                continue;
+            }
+
+            // A new job is only scheduled; it first runs at its next slot
+            if job.next_run_at.is_none() {
+                debug!("Scheduled job {} first runs at {}", job.id, timestamp(next));
+                continue;
+            }
+
+            let scheduler = self.clone();
+            tokio::spawn(async move { scheduler.execute(job).await });
+        }
+        Ok(())
+    }
+
+    /// Move the job's next run forward, if no other replica did it first.
+    async fn claim(&self, job: &JobRecord, next: DateTime<Utc>) -> Result<bool> {
+        let (_, changes) = self
+            .query(
+                "UPDATE scheduled_job SET nextRunAt = ?1, updatedAt = ?2 \
+                 WHERE id = ?3 AND nextRunAt IS ?4",
+                vec![
+                    json!(timestamp(next)),
+                    json!(timestamp(Utc::now())),
+                    json!(job.id),
+                    json!(job.next_run_at),
+                ],
+            )
+            .await?;
+        Ok(changes > 0)
+    }
+
+    /// Run a job, record the run and alert on repeated failures.
+    async fn execute(&self, job: JobRecord) {
+        let started = Utc::now();
+        let outcome = match serde_json::from_str::<JobAction>(&job.action) {
+            Ok(action) => self.call_tool(&job.tenant_id, &action).await,
+            Err(e) => RunOutcome::failure(format!("Invalid job action: {}", e)),
+        };
+        let finished = Utc::now();
+
+        let failures = if outcome.success {
+            0
+        } else {
+            job.consecutive_failures + 1
+        };
+        if outcome.success {
+            info!("Scheduled job {} ({}) succeeded", job.id, job.name);
+        } else {
+            warn!(
+                "Scheduled job {} ({}) failed ({} in a row): {}",
+                job.id, job.name, failures, outcome.output
+            );
+        }
+
+        if let Err(e) = self
+            .record_run(&job, started, finished, &outcome, failures)
+            .await
+        {
+            warn!("Failed to record run of job {}: {}", job.id, e);
+        }
+
+        if failures == i64::from(self.settings.alert_after) {
+            self.alert(&job, &outcome.output, failures, finished).await;
+        }
+    }
+
+    /// Call the job's tool in a fresh backend session for its tenant, with
+    /// the tenant's feature flags.
+    async fn call_tool(&self, tenant_id: &str, action: &JobAction) -> RunOutcome {
+        let session_id =
+            match reinitialize_session(&self.client, &self.backend_url, tenant_id).await {
+                Ok(id) => id,
+                Err(e) => return RunOutcome::failure(e.to_string()),
+            };
+
+        let (tool, arguments) = action.tool_call();
+        let flags = self.feature_flags.for_tenant(tenant_id).await;
+        let url = format!("{}/mcp", self.backend_url);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": CALL_ID,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments }
+        });
+
+        let outcome = match self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .header(MCP_SESSION_ID, &session_id)
+            .header(X_TENANT_ID, tenant_id)
+            .header(X_FEATURE_FLAGS, flags.header_value())
+            .json(&request)
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => {
+                let content_type = resp
+                    .headers()
+                    .get("content-type")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                match resp.text().await {
+                    Ok(body) => parse_tool_response(&content_type, &body),
+                    Err(e) => RunOutcome::failure(format!("Failed to read response: {}", e)),
+                }
+            }
+            Ok(resp) => RunOutcome::failure(format!("Backend returned {}", resp.status())),
+            Err(e) => RunOutcome::failure(format!("Tool call failed: {}", e)),
+        };
+
+        // Close the session; the backend would otherwise keep it until it expires
+        if let Err(e) = self
+            .client
+            .delete(&url)
+            .header(MCP_SESSION_ID, &session_id)
+            .header(X_TENANT_ID, tenant_id)
+            .send()
+            .await
+        {
+            debug!("Failed to close scheduler session {}: {}", session_id, e);
+        }
+
+        outcome
+    }
+
+    /// Insert the run, update the job's status and trim old runs.
+    async fn record_run(
+        &self,
+        job: &JobRecord,
+        started: DateTime<Utc>,
+        finished: DateTime<Utc>,
+        outcome: &RunOutcome,
+        failures: i64,
+    ) -> Result<()> {
+        let status = if outcome.success { "success" } else { "failure" };
+        self.query(
+            "INSERT INTO scheduled_job_run (id, jobId, tenantId, startedAt, finishedAt, status, output) \
+             VALUES (lower(hex(randomblob(16))), ?1, ?2, ?3, ?4, ?5, ?6)",
+            vec![
+                json!(job.id),
+                json!(job.tenant_id),
+                json!(timestamp(started)),
+                json!(timestamp(finished)),
+                json!(status),
+                json!(outcome.output),
+            ],
+        )
+        .await?;
+
+        self.query(
+            "UPDATE scheduled_job SET lastRunAt = ?1, lastStatus = ?2, consecutiveFailures = ?3, \
+             updatedAt = ?1 WHERE id = ?4",
+            vec![
+                json!(timestamp(finished)),
+                json!(status),
+                json!(failures),
+                json!(job.id),
+            ],
+        )
+        .await?;
+
+        self.query(
+            "DELETE FROM scheduled_job_run WHERE jobId = ?1 AND id NOT IN \
+             (SELECT id FROM scheduled_job_run WHERE jobId = ?1 ORDER BY startedAt DESC LIMIT ?2)",
+            vec![json!(job.id), json!(self.settings.run_history)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Disable a job that can never run, recording why.
+    async fn disable(&self, job: &JobRecord, reason: &str) {
+        warn!("Disabling scheduled job {} ({}): {}", job.id, job.name, reason);
+        let now = Utc::now();
+        let outcome = RunOutcome::failure(reason);
+        if let Err(e) = self
+            .query(
+                "UPDATE scheduled_job SET enabled = 0, updatedAt = ?1 WHERE id = ?2",
+                vec![json!(timestamp(now)), json!(job.id)],
+            )
+            .await
+        {
+            warn!("Failed to disable job {}: {}", job.id, e);
+            return;
+        }
+        let failures = job.consecutive_failures + 1;
+        if let Err(e) = self.record_run(job, now, now, &outcome, failures).await {
+            warn!("Failed to record run of job {}: {}", job.id, e);
+        }
+        self.alert(job, reason, failures, now).await;
+    }
+
+    /// POST a failure alert to the job's webhook, or the global one.
+    async fn alert(&self, job: &JobRecord, error: &str, failures: i64, at: DateTime<Utc>) {
+        let Some(url) = job
+            .alert_webhook
+            .as_deref()
+            .or(self.settings.alert_webhook.as_deref())
+        else {
+            return;
+        };
+
+        let payload = json!({
+            "event": "scheduled_job.failed",
+            "job_id": job.id,
+            "tenant_id": job.tenant_id,
+            "name": job.name,
+            "error": error,
+            "consecutive_failures": failures,
+            "at": timestamp(at),
+        });
+
+        match self
+            .client
+            .post(url)
+            .timeout(Duration::from_secs(ALERT_TIMEOUT_SECS))
+            .json(&payload)
+            .send()
+            .await
+        {
+            Ok(resp) if !resp.status().is_success() => {
+                warn!("Alert webhook for job {} returned {}", job.id, resp.status())
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Alert webhook for job {} failed: {}", job.id, e),
+        }
+    }
+
+    /// Execute a D1 query, returning its rows and the number of changed rows.
+    async fn query(&self, sql: &str, params: Vec<Value>) -> Result<(Vec<Value>, u64)> {
+        let url = format!(
+            "https://api.cloudflare.com/client/v4/accounts/{}/d1/database/{}/query",
+            self.account_id, self.database_id
+        );
+
+        let query = D1QueryRequest {
+            sql: sql.to_string(),
+            params,
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header("Content-Type", "application/json")
+            .json(&query)
+            .send()
+            .await
+            .map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(ProxyError::D1Error(format!(
+                "D1 API returned {}: {}",
+                status, body
+            )));
+        }
+
+        let d1_response: D1Response =
+            serde_json::from_str(&body).map_err(|e| ProxyError::D1Error(e.to_string()))?;
+
+        if !d1_response.success {
+            let error_msg = d1_response
+                .errors
+                .map(|errs| errs.into_iter().map(|e| e.message).collect::<Vec<_>>().join(", "))
+                .unwrap_or_else(|| "Unknown D1 error".to_string());
+            return Err(ProxyError::D1Error(error_msg));
+        }
+
+        Ok(d1_response
+            .result
+            .and_then(|mut r| r.pop())
+            .map(|qr| (qr.results, qr.meta.map(|m| m.changes).unwrap_or(0)))
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_tool_call() {
+        let action: JobAction = serde_json::from_str(
+            r#"{"type":"tool","tool":"generate_report","arguments":{"month":"current"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            action.tool_call(),
+            ("generate_report".to_string(), json!({"month": "current"}))
+        );
+
+        let action: JobAction =
+            serde_json::from_str(r#"{"type":"sync","doc_id":"report"}"#).unwrap();
+        assert_eq!(
+            action.tool_call(),
+            ("document_save".to_string(), json!({"doc_id": "report"}))
+        );
+
+        let action: JobAction =
+            serde_json::from_str(r#"{"type":"sync","doc_id":"report","direction":"pull"}"#)
+                .unwrap();
+        assert_eq!(action.tool_call().0, "sync_external_changes");
+
+        assert!(serde_json::from_str::<JobAction>(r#"{"type":"email"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_json_response() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"result":{"content":[{"type":"text","text":"Saved."}]}}"#;
+        assert_eq!(
+            parse_tool_response("application/json", body),
+            RunOutcome {
+                success: true,
+                output: "Saved.".to_string()
+            }
+        );
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unknown tool"}}"#;
+        let outcome = parse_tool_response("application/json", body);
+        assert!(!outcome.success);
+        assert_eq!(outcome.output, "Unknown tool");
+    }
+
+    #[test]
+    fn test_parse_sse_response() {
+        let body = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n\
+                    event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":\
+                    {\"content\":[{\"type\":\"text\",\"text\":\"Error: Document 'x' not found.\"}]}}\n\n";
+        let outcome = parse_tool_response("text/event-stream", body);
+        assert!(!outcome.success);
+        assert_eq!(outcome.output, "Error: Document 'x' not found.");
+
+        assert!(!parse_tool_response("text/event-stream", ": keepalive\n\n").success);
+    }
+
+    #[test]
+    fn test_output_truncated_on_char_boundary() {
+        let text = "é".repeat(MAX_OUTPUT_CHARS + 10);
+        let truncated = truncate(&text);
+        assert_eq!(truncated.chars().count(), MAX_OUTPUT_CHARS + 1);
+        assert!(truncated.ends_with('…'));
+    }
+}
@@ -1,74 +1,333 @@
-//! Per-tenant MCP session registry with recovery coordination.
+//! Per-client MCP session registry with recovery coordination.
 //!
 //! The backend .NET MCP server keeps transport sessions in memory.
 //! When it restarts, those sessions are lost and clients get 404.
-//! This registry tracks the current backend session ID per tenant
-//! and coordinates recovery (re-initialize) when a 404 is detected.
+//! The proxy then re-initializes a fresh backend session on the client's
+//! behalf and keeps presenting the client's original session ID, so the
+//! client never notices.
+//!
+//! Sessions are keyed by (tenant, client session ID): one user may run
+//! several MCP clients at once, and recovering one must not touch the others.
+//! A client session with no entry maps to the backend session of the same ID.
+//!
+//! With a session store file, the recovered sessions are saved shortly after
+//! each change and reloaded on startup, so a proxy restart doesn't send
+//! every recovered client through recovery again at once. Saved sessions
+//! idle for longer than the registry's TTL are dropped on reload.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex as AsyncMutex, Notify, OwnedMutexGuard, RwLock};
+use tracing::{info, warn};
+
+/// Forget client sessions that have not been used for this long.
+const SESSION_IDLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Upper bound on tracked client sessions.
+const MAX_SESSIONS: u64 = 100_000;
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+/// How long after a change the session store is saved, so bursts of
+/// recoveries are written once.
+const STORE_SAVE_DELAY: Duration = Duration::from_secs(1);
 
-use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard, RwLock};
+/// How often the session store is saved without changes (to keep
+/// last-use times fresh).
+const STORE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
 
-/// Tracks the current backend MCP session ID for each tenant
-/// and serializes recovery attempts per tenant.
+/// Tracks the backend MCP session ID behind each client session
+/// and serializes recovery attempts per client session.
 pub struct SessionRegistry {
-    inner: Mutex<HashMap<String, Arc<TenantEntry>>>,
+    inner: Cache<(String, String), Arc<SessionEntry>>,
+    /// File the recovered sessions are saved to (None = in memory only).
+    store_path: Option<PathBuf>,
+    changed: Notify,
 }
 
-struct TenantEntry {
-    /// Current backend session ID (read-heavy, write-rare).
-    session_id: RwLock<Option<String>>,
+struct SessionEntry {
+    /// Backend session ID replacing the client's one after a recovery
+    /// (read-heavy, write-rare). `None` means the IDs are the same.
+    backend_session_id: RwLock<Option<String>>,
     /// Serializes re-initialization attempts so only one request
-    /// performs the initialize handshake per tenant.
+    /// performs the initialize handshake per client session.
     recovery_lock: Arc<AsyncMutex<()>>,
+    /// Unix time of the last lookup, saved with the session.
+    last_used: AtomicU64,
+}
+
+/// A recovered session as saved in the session store.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSession {
+    tenant_id: String,
+    client_session_id: String,
+    backend_session_id: String,
+    /// Unix time of the session's last use.
+    last_used: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 impl SessionRegistry {
     pub fn new() -> Self {
         Self {
-            inner: Mutex::new(HashMap::new()),
+            inner: Cache::builder()
+                .max_capacity(MAX_SESSIONS)
+                .time_to_idle(SESSION_IDLE_TTL)
+                .build(),
+            store_path: None,
+            changed: Notify::new(),
+        }
+    }
+
+    /// A registry saved to `path`, starting with the sessions saved there
+    /// that haven't been idle for longer than the TTL. A missing file is an
+    /// empty store; an unreadable one is logged and ignored.
+    pub async fn with_store(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let registry = Self {
+            store_path: Some(path.clone()),
+            ..Self::new()
+        };
+
+        let stored: Vec<StoredSession> = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable session store {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                warn!("Failed to read session store {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+        let cutoff = unix_now().saturating_sub(SESSION_IDLE_TTL.as_secs());
+        let mut restored = 0;
+        for session in stored.into_iter().filter(|s| s.last_used >= cutoff) {
+            let entry = registry
+                .entry(&session.tenant_id, &session.client_session_id)
+                .await;
+            *entry.backend_session_id.write().await = Some(session.backend_session_id);
+            entry.last_used.store(session.last_used, Ordering::Relaxed);
+            restored += 1;
+        }
+        info!("Restored {} recovered sessions from {}", restored, path.display());
+        registry
+    }
+
+    /// Save the session store after each change (and periodically) until
+    /// the process exits. Does nothing without a store.
+    pub fn spawn_persistence(self: &Arc<Self>) {
+        if self.store_path.is_none() {
+            return;
         }
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = registry.changed.notified() => tokio::time::sleep(STORE_SAVE_DELAY).await,
+                    _ = tokio::time::sleep(STORE_SAVE_INTERVAL) => {}
+                }
+                if let Err(e) = registry.save().await {
+                    warn!("Failed to save session store: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Write the recovered sessions to the store file, replacing it atomically.
+    pub async fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        let entries: Vec<_> = self.inner.iter().collect();
+        let mut sessions = Vec::new();
+        for (key, entry) in entries {
+            if let Some(backend_session_id) = entry.backend_session_id.read().await.clone() {
+                sessions.push(StoredSession {
+                    tenant_id: key.0.clone(),
+                    client_session_id: key.1.clone(),
+                    backend_session_id,
+                    last_used: entry.last_used.load(Ordering::Relaxed),
+                });
+            }
+        }
+        write_atomically(path, &serde_json::to_vec(&sessions)?).await
+    }
+
+    /// The entry of a client session, if it has one.
+    async fn existing(&self, tenant_id: &str, client_session_id: &str) -> Option<Arc<SessionEntry>> {
+        self.inner
+            .get(&(tenant_id.to_string(), client_session_id.to_string()))
+            .await
     }
 
-    /// Get or create the entry for a tenant.
-    fn entry(&self, tenant_id: &str) -> Arc<TenantEntry> {
-        let mut map = self.inner.lock().expect("session registry poisoned");
-        map.entry(tenant_id.to_string())
-            .or_insert_with(|| {
-                Arc::new(TenantEntry {
-                    session_id: RwLock::new(None),
-                    recovery_lock: Arc::new(AsyncMutex::new(())),
-                })
-            })
-            .clone()
+    /// Get or create the entry for a client session. Only for the recovery
+    /// path: lookups use [`Self::existing`], so that sessions which never
+    /// needed recovery don't fill the cache.
+    async fn entry(&self, tenant_id: &str, client_session_id: &str) -> Arc<SessionEntry> {
+        self.inner
+            .get_with(
+                (tenant_id.to_string(), client_session_id.to_string()),
+                async {
+                    Arc::new(SessionEntry {
+                        backend_session_id: RwLock::new(None),
+                        recovery_lock: Arc::new(AsyncMutex::new(())),
+                        last_used: AtomicU64::new(unix_now()),
+                    })
+                },
+            )
+            .await
     }
 
-    /// Get the current backend session ID for a tenant (if any).
-    pub async fn get_session_id(&self, tenant_id: &str) -> Option<String> {
-        let entry = self.entry(tenant_id);
-        let guard = entry.session_id.read().await;
+    /// Get the backend session ID replacing a client session (if any).
+    pub async fn get_session_id(&self, tenant_id: &str, client_session_id: &str) -> Option<String> {
+        let entry = self.existing(tenant_id, client_session_id).await?;
+        entry.last_used.store(unix_now(), Ordering::Relaxed);
+        let guard = entry.backend_session_id.read().await;
         guard.clone()
     }
 
-    /// Store a new backend session ID for a tenant.
-    pub async fn set_session_id(&self, tenant_id: &str, session_id: String) {
-        let entry = self.entry(tenant_id);
-        *entry.session_id.write().await = Some(session_id);
+    /// Route a client session to a new backend session.
+    pub async fn set_session_id(
+        &self,
+        tenant_id: &str,
+        client_session_id: &str,
+        backend_session_id: String,
+    ) {
+        let entry = self.entry(tenant_id, client_session_id).await;
+        *entry.backend_session_id.write().await = Some(backend_session_id);
+        self.changed.notify_one();
     }
 
-    /// Clear the session ID for a tenant (e.g. after detecting 404).
-    pub async fn invalidate(&self, tenant_id: &str) {
-        let entry = self.entry(tenant_id);
-        *entry.session_id.write().await = None;
+    /// Clear the backend session of a client session (e.g. after detecting 404).
+    pub async fn invalidate(&self, tenant_id: &str, client_session_id: &str) {
+        let Some(entry) = self.existing(tenant_id, client_session_id).await else {
+            return;
+        };
+        *entry.backend_session_id.write().await = None;
+        self.changed.notify_one();
     }
 
-    /// Acquire the recovery lock for a tenant. Only one recovery
+    /// Forget a client session entirely (e.g. after the client deleted it).
+    pub async fn remove(&self, tenant_id: &str, client_session_id: &str) {
+        self.inner
+            .invalidate(&(tenant_id.to_string(), client_session_id.to_string()))
+            .await;
+        self.changed.notify_one();
+    }
+
+    /// Acquire the recovery lock for a client session. Only one recovery
     /// attempt proceeds at a time; others wait and then check if
     /// a new session ID was already established.
-    pub async fn acquire_recovery_lock(&self, tenant_id: &str) -> OwnedMutexGuard<()> {
-        let entry = self.entry(tenant_id);
+    pub async fn acquire_recovery_lock(
+        &self,
+        tenant_id: &str,
+        client_session_id: &str,
+    ) -> OwnedMutexGuard<()> {
+        let entry = self.entry(tenant_id, client_session_id).await;
         let lock = Arc::clone(&entry.recovery_lock);
         lock.lock_owned().await
     }
 }
+
+/// Write `bytes` to a temporary file beside `path`, then rename it over `path`.
+pub(crate) async fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sessions_are_independent_per_client() {
+        let registry = SessionRegistry::new();
+        registry.set_session_id("t1", "client-a", "backend-a2".into()).await;
+        registry.set_session_id("t1", "client-b", "backend-b2".into()).await;
+
+        registry.invalidate("t1", "client-a").await;
+        assert_eq!(registry.get_session_id("t1", "client-a").await, None);
+        assert_eq!(
+            registry.get_session_id("t1", "client-b").await.as_deref(),
+            Some("backend-b2")
+        );
+        // Same client session ID under another tenant is another entry
+        assert_eq!(registry.get_session_id("t2", "client-b").await, None);
+
+        registry.remove("t1", "client-b").await;
+        assert_eq!(registry.get_session_id("t1", "client-b").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_store_restores_recovered_sessions() {
+        let path = std::env::temp_dir().join(format!(
+            "docx-proxy-sessions-{}-{}.json",
+            std::process::id(),
+            unix_now()
+        ));
+        let registry = SessionRegistry::with_store(&path).await;
+        registry.set_session_id("t1", "client-a", "backend-a2".into()).await;
+        // Unrecovered sessions map to themselves and aren't saved
+        registry.get_session_id("t1", "client-b").await;
+        registry.save().await.unwrap();
+
+        let restored = SessionRegistry::with_store(&path).await;
+        assert_eq!(
+            restored.get_session_id("t1", "client-a").await.as_deref(),
+            Some("backend-a2")
+        );
+        restored.remove("t1", "client-a").await;
+        restored.save().await.unwrap();
+
+        let stale = StoredSession {
+            tenant_id: "t1".into(),
+            client_session_id: "client-c".into(),
+            backend_session_id: "backend-c2".into(),
+            last_used: unix_now() - SESSION_IDLE_TTL.as_secs() - 60,
+        };
+        std::fs::write(&path, serde_json::to_vec(&[stale]).unwrap()).unwrap();
+        let reloaded = SessionRegistry::with_store(&path).await;
+        assert_eq!(reloaded.get_session_id("t1", "client-a").await, None);
+        assert_eq!(reloaded.get_session_id("t1", "client-c").await, None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lookups_do_not_create_entries() {
+        let registry = SessionRegistry::new();
+        for i in 0..100 {
+            assert_eq!(registry.get_session_id("t1", &format!("client-{}", i)).await, None);
+        }
+        registry.invalidate("t1", "client-0").await;
+        registry.inner.run_pending_tasks().await;
+        assert_eq!(registry.inner.entry_count(), 0);
+
+        // Recovery creates the entry
+        drop(registry.acquire_recovery_lock("t1", "client-0").await);
+        registry.set_session_id("t1", "client-0", "backend-0".into()).await;
+        registry.inner.run_pending_tasks().await;
+        assert_eq!(registry.inner.entry_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_locks_do_not_block_other_clients() {
+        let registry = SessionRegistry::new();
+        let _a = registry.acquire_recovery_lock("t1", "client-a").await;
+        // Must not deadlock while client-a is recovering
+        let _b = registry.acquire_recovery_lock("t1", "client-b").await;
+    }
+}
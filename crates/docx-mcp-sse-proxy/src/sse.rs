@@ -0,0 +1,198 @@
+//! SSE keepalive and idle-timeout handling for streamed backend responses.
+//!
+//! Buffering intermediaries (ALB, Cloudflare, nginx) drop connections that
+//! stay silent for too long, and a stalled backend otherwise leaves clients
+//! waiting on a stream that will never produce anything. The proxy therefore:
+//! - injects `: keepalive` comments when the stream has been quiet for the
+//!   heartbeat interval (only between events, never inside one);
+//! - ends the stream with an `error` event when the backend sends nothing for
+//!   the idle timeout, or when the backend stream fails.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use futures::{Stream, StreamExt};
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::config::Config;
+
+/// SSE comment sent as a heartbeat. Ignored by conforming clients.
+const HEARTBEAT: &[u8] = b": keepalive\n\n";
+
+/// Keepalive and idle-timeout settings for one SSE response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SseSettings {
+    pub heartbeat: Option<Duration>,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl SseSettings {
+    /// Settings for POST response streams from the configuration (0 disables).
+    pub fn from_config(config: &Config) -> Self {
+        let secs = |s: u64| (s > 0).then(|| Duration::from_secs(s));
+        Self {
+            heartbeat: secs(config.sse_heartbeat_secs),
+            idle_timeout: secs(config.sse_idle_timeout_secs),
+        }
+    }
+}
+
+type ByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>;
+
+struct State {
+    upstream: ByteStream,
+    settings: SseSettings,
+    /// Last time the backend produced data.
+    last_data: Instant,
+    /// Last time anything (data or heartbeat) was sent to the client.
+    last_sent: Instant,
+    /// Whether everything sent so far ends on an event boundary.
+    at_boundary: bool,
+    done: bool,
+}
+
+/// Wrap a backend SSE byte stream with heartbeats and idle-timeout termination.
+pub fn with_keepalive(
+    upstream: impl Stream<Item = reqwest::Result<Bytes>> + Send + 'static,
+    settings: SseSettings,
+) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send {
+    let now = Instant::now();
+    let state = State {
+        upstream: Box::pin(upstream),
+        settings,
+        last_data: now,
+        last_sent: now,
+        at_boundary: true,
+        done: false,
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        loop {
+            let heartbeat_at = state.settings.heartbeat.map(|d| state.last_sent + d);
+            let idle_at = state.settings.idle_timeout.map(|d| state.last_data + d);
+            let deadline = match (heartbeat_at, idle_at) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+
+            let item = match deadline {
+                Some(deadline) => {
+                    tokio::select! {
+                        item = state.upstream.next() => Some(item),
+                        _ = tokio::time::sleep_until(deadline) => None,
+                    }
+                }
+                None => Some(state.upstream.next().await),
+            };
+
+            match item {
+                Some(Some(Ok(bytes))) => {
+                    let now = Instant::now();
+                    state.last_data = now;
+                    state.last_sent = now;
+                    if !bytes.is_empty() {
+                        state.at_boundary = bytes.ends_with(b"\n\n") || bytes.ends_with(b"\r\n\r\n");
+                    }
+                    return Some((Ok(bytes), state));
+                }
+                Some(Some(Err(e))) => {
+                    warn!("Backend SSE stream failed: {}", e);
+                    state.done = true;
+                    let event = termination_event(state.at_boundary, "BACKEND_ERROR", &e.to_string());
+                    return Some((Ok(event), state));
+                }
+                Some(None) => return None,
+                None => {
+                    let now = Instant::now();
+                    if idle_at.is_some_and(|at| now >= at) {
+                        warn!(
+                            "Backend SSE stream idle for {:?}, closing",
+                            state.settings.idle_timeout.unwrap_or_default()
+                        );
+                        state.done = true;
+                        let event = termination_event(
+                            state.at_boundary,
+                            "BACKEND_IDLE_TIMEOUT",
+                            "Backend stopped responding",
+                        );
+                        return Some((Ok(event), state));
+                    }
+                    // Heartbeat due. Inside a partially sent event a comment would
+                    // corrupt it, so skip this beat and wait for the next one.
+                    state.last_sent = now;
+                    if state.at_boundary {
+                        return Some((Ok(Bytes::from_static(HEARTBEAT)), state));
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Final `error` event sent before the proxy closes a stream.
+fn termination_event(at_boundary: bool, code: &str, message: &str) -> Bytes {
+    let data = serde_json::json!({ "error": message, "code": code });
+    let prefix = if at_boundary { "" } else { "\n\n" };
+    Bytes::from(format!("{}event: error\ndata: {}\n\n", prefix, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(heartbeat_ms: u64, idle_ms: u64) -> SseSettings {
+        SseSettings {
+            heartbeat: Some(Duration::from_millis(heartbeat_ms)),
+            idle_timeout: Some(Duration::from_millis(idle_ms)),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_then_idle_timeout() {
+        let upstream = futures::stream::pending::<reqwest::Result<Bytes>>();
+        let chunks: Vec<_> = with_keepalive(upstream, settings(10, 25))
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(&chunks[0][..], HEARTBEAT);
+        assert_eq!(&chunks[1][..], HEARTBEAT);
+        let last = String::from_utf8_lossy(&chunks[2]);
+        assert!(last.starts_with("event: error\n"));
+        assert!(last.contains("BACKEND_IDLE_TIMEOUT"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_no_heartbeat_inside_event() {
+        let upstream = futures::stream::iter(vec![Ok(Bytes::from_static(b"data: partial"))])
+            .chain(futures::stream::pending());
+        let chunks: Vec<_> = with_keepalive(upstream, settings(10, 25))
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(&chunks[0][..], b"data: partial");
+        // The termination event first closes the partial event.
+        assert!(chunks[1].starts_with(b"\n\nevent: error\n"));
+    }
+
+    #[tokio::test]
+    async fn test_passthrough_ends_with_upstream() {
+        let upstream = futures::stream::iter(vec![
+            Ok(Bytes::from_static(b"data: a\n\n")),
+            Ok(Bytes::from_static(b"data: b\n\n")),
+        ]);
+        let chunks: Vec<_> = with_keepalive(upstream, SseSettings::default())
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 2);
+    }
+}
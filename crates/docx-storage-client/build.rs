@@ -0,0 +1,8 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Compile the protobuf definitions (client side only)
+    tonic_build::configure()
+        .build_server(false)
+        .build_client(true)
+        .compile_protos(&["../../proto/storage.proto"], &["../../proto"])?;
+    Ok(())
+}
@@ -0,0 +1,698 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use docx_storage_core::{
+    new_request_id, SessionIndex, SessionPageQuery, DEFAULT_SESSION_PAGE_SIZE, REQUEST_ID_HEADER,
+    TENANT_ID_HEADER,
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+use tracing::debug;
+
+use crate::error::ClientError;
+use crate::proto::storage_service_client::StorageServiceClient;
+use crate::proto::*;
+use crate::retry::RetryPolicy;
+
+/// Size of the chunks documents are uploaded in: 256KB (the server default).
+pub const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+
+type RawClient = StorageServiceClient<Channel>;
+
+/// Client of a StorageService server (local, R2, ...) acting for one tenant.
+///
+/// Every request carries the tenant's context, and the `x-tenant-id` and a
+/// fresh `x-request-id` header for the server's logs. Documents are streamed
+/// in and out in chunks, and calls the server didn't process are retried
+/// according to a [`RetryPolicy`]. RPCs without a method here are made on
+/// [`raw`](Self::raw).
+///
+/// Cloning is cheap: clones share the connection.
+#[derive(Debug, Clone)]
+pub struct StorageClient {
+    inner: RawClient,
+    tenant_id: String,
+    actor: String,
+    retry: RetryPolicy,
+    chunk_size: usize,
+}
+
+impl StorageClient {
+    /// Connect to the server at `endpoint` (e.g. `http://localhost:50051`).
+    pub async fn connect(endpoint: impl Into<String>) -> Result<Self, ClientError> {
+        let channel = Endpoint::from_shared(endpoint.into())?.connect().await?;
+        Ok(Self::new(channel))
+    }
+
+    /// Make requests over `channel`, for the local/legacy tenant until
+    /// [`with_tenant`](Self::with_tenant).
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            inner: StorageServiceClient::new(channel).max_decoding_message_size(usize::MAX),
+            tenant_id: String::new(),
+            actor: String::new(),
+            retry: RetryPolicy::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Act for `tenant_id`.
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = tenant_id.into();
+        self
+    }
+
+    /// Name `actor` as who makes the requests, in the tenant's admin log.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = actor.into();
+        self
+    }
+
+    /// Retry failed calls according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Upload documents in chunks of `bytes`.
+    pub fn with_chunk_size(mut self, bytes: usize) -> Self {
+        self.chunk_size = bytes.max(1);
+        self
+    }
+
+    /// The tenant requests are made for.
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    /// The generated client, sharing this one's connection. Its requests
+    /// need their context set by hand (see [`context`](Self::context)).
+    pub fn raw(&self) -> RawClient {
+        self.inner.clone()
+    }
+
+    /// The tenant context of this client's requests.
+    pub fn context(&self) -> Option<TenantContext> {
+        Some(TenantContext {
+            tenant_id: self.tenant_id.clone(),
+            actor: self.actor.clone(),
+        })
+    }
+
+    /// Wrap `message` in a request carrying the tenant and request ID headers.
+    pub fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        let metadata = request.metadata_mut();
+        if let Ok(value) = MetadataValue::try_from(self.tenant_id.as_str()) {
+            if !self.tenant_id.is_empty() {
+                metadata.insert(TENANT_ID_HEADER, value);
+            }
+        }
+        if let Ok(value) = MetadataValue::try_from(new_request_id()) {
+            metadata.insert(REQUEST_ID_HEADER, value);
+        }
+        request
+    }
+
+    /// Run `call` on a fresh handle of the client until it succeeds or the
+    /// retry policy gives up.
+    async fn retrying<T, F, Fut>(&self, rpc: &'static str, mut call: F) -> Result<T, ClientError>
+    where
+        F: FnMut(RawClient) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call(self.inner.clone()).await {
+                Ok(value) => return Ok(value),
+                Err(status) => match self.retry.retry_delay(attempt, &status) {
+                    Some(delay) => {
+                        debug!(
+                            rpc,
+                            attempt,
+                            code = ?status.code(),
+                            delay_ms = delay.as_millis() as u64,
+                            "Retrying storage call"
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(status.into()),
+                },
+            }
+        }
+    }
+
+    // =========================================================================
+    // Server
+    // =========================================================================
+
+    pub async fn health(&self) -> Result<HealthCheckResponse, ClientError> {
+        self.retrying("health_check", |mut c| {
+            let request = self.request(HealthCheckRequest {});
+            async move { Ok(c.health_check(request).await?.into_inner()) }
+        })
+        .await
+    }
+
+    pub async fn capabilities(&self) -> Result<GetCapabilitiesResponse, ClientError> {
+        self.retrying("get_capabilities", |mut c| {
+            let request = self.request(GetCapabilitiesRequest {});
+            async move { Ok(c.get_capabilities(request).await?.into_inner()) }
+        })
+        .await
+    }
+
+    // =========================================================================
+    // Sessions
+    // =========================================================================
+
+    /// Every stored session, with its size.
+    pub async fn list_sessions(&self) -> Result<Vec<SessionInfo>, ClientError> {
+        self.retrying("list_sessions", |mut c| {
+            let request = self.request(ListSessionsRequest {
+                context: self.context(),
+                ..Default::default()
+            });
+            async move { Ok(c.list_sessions(request).await?.into_inner().sessions) }
+        })
+        .await
+    }
+
+    /// One page of the sessions in the index `query` selects (sizes aren't
+    /// reported in pages).
+    pub async fn list_sessions_page(
+        &self,
+        query: &SessionPageQuery,
+    ) -> Result<ListSessionsResponse, ClientError> {
+        self.retrying("list_sessions", |mut c| {
+            let request = self.request(ListSessionsRequest {
+                context: self.context(),
+                stale_ok: false,
+                // A request without paging fields gets the whole listing
+                page_size: match query.page_size {
+                    0 => DEFAULT_SESSION_PAGE_SIZE,
+                    size => size,
+                },
+                page_token: query.page_token.clone().unwrap_or_default(),
+                session_id_prefix: query.id_prefix.clone().unwrap_or_default(),
+                search: query.search.clone().unwrap_or_default(),
+            });
+            async move { Ok(c.list_sessions(request).await?.into_inner()) }
+        })
+        .await
+    }
+
+    /// The sessions in the index `query` selects, read a page at a time as
+    /// the stream is polled.
+    pub fn sessions_matching(
+        &self,
+        query: SessionPageQuery,
+    ) -> impl Stream<Item = Result<SessionInfo, ClientError>> + '_ {
+        stream::try_unfold(Some(query), move |query| async move {
+            let Some(query) = query else {
+                return Ok::<_, ClientError>(None);
+            };
+            let page = self.list_sessions_page(&query).await?;
+            let next = match page.next_page_token.is_empty() {
+                true => None,
+                false => Some(SessionPageQuery {
+                    page_token: Some(page.next_page_token),
+                    ..query
+                }),
+            };
+            Ok(Some((stream::iter(page.sessions.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+
+    pub async fn session_exists(&self, session_id: &str) -> Result<bool, ClientError> {
+        self.retrying("session_exists", |mut c| {
+            let request = self.request(SessionExistsRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            });
+            async move { Ok(c.session_exists(request).await?.into_inner().exists) }
+        })
+        .await
+    }
+
+    /// Delete a session. Returns whether it existed.
+    pub async fn delete_session(&self, session_id: &str) -> Result<bool, ClientError> {
+        self.retrying("delete_session", |mut c| {
+            let request = self.request(DeleteSessionRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            });
+            async move { Ok(c.delete_session(request).await?.into_inner().existed) }
+        })
+        .await
+    }
+
+    /// The session's document, or `None` if it doesn't exist.
+    pub async fn load_session(&self, session_id: &str) -> Result<Option<Vec<u8>>, ClientError> {
+        self.retrying("load_session", |mut c| {
+            let request = self.request(LoadSessionRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            });
+            async move {
+                let mut stream = c.load_session(request).await?.into_inner();
+                let mut data = Vec::new();
+                while let Some(chunk) = stream.message().await? {
+                    if !chunk.found {
+                        return Ok(None);
+                    }
+                    data.extend(chunk.data);
+                    if chunk.is_last {
+                        break;
+                    }
+                }
+                Ok(Some(data))
+            }
+        })
+        .await
+    }
+
+    /// Write the session's document to `writer` as it downloads. Returns
+    /// `false` if the session doesn't exist. Not retried: `writer` may have
+    /// received part of the document.
+    pub async fn load_session_into<W>(&self, session_id: &str, writer: &mut W) -> Result<bool, ClientError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let request = self.request(LoadSessionRequest {
+            context: self.context(),
+            session_id: session_id.to_string(),
+        });
+        let mut stream = self.raw().load_session(request).await?.into_inner();
+        while let Some(chunk) = stream.message().await? {
+            if !chunk.found {
+                return Ok(false);
+            }
+            writer.write_all(&chunk.data).await?;
+            if chunk.is_last {
+                break;
+            }
+        }
+        writer.flush().await?;
+        Ok(true)
+    }
+
+    /// Store the session's document.
+    pub async fn save_session(&self, session_id: &str, data: &[u8]) -> Result<(), ClientError> {
+        let response = self
+            .retrying("save_session", |mut c| {
+                let chunks = self.session_chunks(session_id, split_chunks(data, self.chunk_size));
+                let request = self.request(stream::iter(chunks));
+                async move { Ok(c.save_session(request).await?.into_inner()) }
+            })
+            .await?;
+        ensure_saved(response.success)
+    }
+
+    /// Store the session's document, read from `reader` and uploaded as it
+    /// is read. Not retried: `reader` can't be read again.
+    pub async fn save_session_from<R>(&self, session_id: &str, reader: R) -> Result<(), ClientError>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let failed = Arc::new(Mutex::new(None));
+        let chunks = read_chunks(reader, self.chunk_size, failed.clone());
+        let context = self.context();
+        let session_id = session_id.to_string();
+        let messages = chunks.enumerate().map(move |(i, (data, is_last))| SaveSessionChunk {
+            context: if i == 0 { context.clone() } else { None },
+            session_id: if i == 0 { session_id.clone() } else { String::new() },
+            data,
+            is_last,
+            ..Default::default()
+        });
+
+        let result = self.raw().save_session(self.request(messages)).await;
+        // A read error ends the upload early, which the server rejects
+        if let Some(e) = failed.lock().unwrap().take() {
+            return Err(e.into());
+        }
+        ensure_saved(result?.into_inner().success)
+    }
+
+    fn session_chunks(&self, session_id: &str, chunks: Vec<&[u8]>) -> Vec<SaveSessionChunk> {
+        let last = chunks.len() - 1;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| SaveSessionChunk {
+                context: if i == 0 { self.context() } else { None },
+                session_id: if i == 0 { session_id.to_string() } else { String::new() },
+                data: chunk.to_vec(),
+                is_last: i == last,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The tenant's session index, or `None` if it has none.
+    pub async fn load_index(&self) -> Result<Option<SessionIndex>, ClientError> {
+        let response = self
+            .retrying("load_index", |mut c| {
+                let request = self.request(LoadIndexRequest {
+                    context: self.context(),
+                });
+                async move { Ok(c.load_index(request).await?.into_inner()) }
+            })
+            .await?;
+        if !response.found {
+            return Ok(None);
+        }
+        SessionIndex::from_json(&response.index_json)
+            .map(Some)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
+    // =========================================================================
+    // WAL
+    // =========================================================================
+
+    /// Up to `limit` WAL entries (0 = all) from position `from` (0 = the
+    /// first).
+    pub async fn read_wal(
+        &self,
+        session_id: &str,
+        from: u64,
+        limit: u64,
+    ) -> Result<ReadWalResponse, ClientError> {
+        self.retrying("read_wal", |mut c| {
+            let request = self.request(ReadWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                from_position: from,
+                limit,
+            });
+            async move { Ok(c.read_wal(request).await?.into_inner()) }
+        })
+        .await
+    }
+
+    /// Append entries to the session's WAL. Not retried: the entries may
+    /// have been appended before the call failed.
+    pub async fn append_wal(
+        &self,
+        session_id: &str,
+        entries: Vec<WalEntry>,
+    ) -> Result<AppendWalResponse, ClientError> {
+        let request = self.request(AppendWalRequest {
+            context: self.context(),
+            session_id: session_id.to_string(),
+            entries,
+        });
+        Ok(self.raw().append_wal(request).await?.into_inner())
+    }
+
+    /// Drop the WAL entries before `keep_from`. Returns how many were
+    /// removed.
+    pub async fn truncate_wal(&self, session_id: &str, keep_from: u64) -> Result<u64, ClientError> {
+        self.retrying("truncate_wal", |mut c| {
+            let request = self.request(TruncateWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                keep_from_position: keep_from,
+            });
+            async move { Ok(c.truncate_wal(request).await?.into_inner().entries_removed) }
+        })
+        .await
+    }
+
+    // =========================================================================
+    // Checkpoints
+    // =========================================================================
+
+    pub async fn list_checkpoints(&self, session_id: &str) -> Result<Vec<CheckpointInfo>, ClientError> {
+        self.retrying("list_checkpoints", |mut c| {
+            let request = self.request(ListCheckpointsRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                stale_ok: false,
+            });
+            async move { Ok(c.list_checkpoints(request).await?.into_inner().checkpoints) }
+        })
+        .await
+    }
+
+    /// The checkpoint at `position` (0 = the latest) and the position it
+    /// was found at, or `None` if there is none.
+    pub async fn load_checkpoint(
+        &self,
+        session_id: &str,
+        position: u64,
+    ) -> Result<Option<(Vec<u8>, u64)>, ClientError> {
+        self.retrying("load_checkpoint", |mut c| {
+            let request = self.request(LoadCheckpointRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                position,
+            });
+            async move {
+                let mut stream = c.load_checkpoint(request).await?.into_inner();
+                let mut data = Vec::new();
+                let mut found_at = None;
+                while let Some(chunk) = stream.message().await? {
+                    if found_at.is_none() {
+                        if !chunk.found {
+                            return Ok(None);
+                        }
+                        found_at = Some(chunk.position);
+                    }
+                    data.extend(chunk.data);
+                    if chunk.is_last {
+                        break;
+                    }
+                }
+                Ok(found_at.map(|position| (data, position)))
+            }
+        })
+        .await
+    }
+
+    /// Store a checkpoint of the session at WAL `position`.
+    pub async fn save_checkpoint(
+        &self,
+        session_id: &str,
+        position: u64,
+        data: &[u8],
+    ) -> Result<(), ClientError> {
+        let response = self
+            .retrying("save_checkpoint", |mut c| {
+                let chunks = split_chunks(data, self.chunk_size);
+                let last = chunks.len() - 1;
+                let messages: Vec<SaveCheckpointChunk> = chunks
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, chunk)| SaveCheckpointChunk {
+                        context: if i == 0 { self.context() } else { None },
+                        session_id: if i == 0 { session_id.to_string() } else { String::new() },
+                        position: if i == 0 { position } else { 0 },
+                        data: chunk.to_vec(),
+                        is_last: i == last,
+                    })
+                    .collect();
+                let request = self.request(stream::iter(messages));
+                async move { Ok(c.save_checkpoint(request).await?.into_inner()) }
+            })
+            .await?;
+        ensure_saved(response.success)
+    }
+}
+
+fn ensure_saved(success: bool) -> Result<(), ClientError> {
+    match success {
+        true => Ok(()),
+        false => Err(ClientError::InvalidResponse(
+            "the server didn't store the upload".to_string(),
+        )),
+    }
+}
+
+/// Split a payload into upload chunks; always yields at least one (possibly
+/// empty) chunk.
+fn split_chunks(data: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return vec![data];
+    }
+    data.chunks(chunk_size).collect()
+}
+
+/// Fill a chunk of up to `chunk_size` bytes from `reader`; shorter only at
+/// the end of the data.
+async fn read_chunk<R: AsyncRead + Unpin>(reader: &mut R, chunk_size: usize) -> std::io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(chunk_size);
+    while chunk.len() < chunk_size {
+        let read = (&mut *reader)
+            .take((chunk_size - chunk.len()) as u64)
+            .read_to_end(&mut chunk)
+            .await?;
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(chunk)
+}
+
+/// The chunks of `reader` with whether each is the last one (at least one,
+/// possibly empty). Reading one chunk ahead tells the last one apart. A read
+/// error ends the stream before the last chunk and is left in `failed`.
+fn read_chunks<R>(
+    reader: R,
+    chunk_size: usize,
+    failed: Arc<Mutex<Option<std::io::Error>>>,
+) -> impl Stream<Item = (Vec<u8>, bool)> + Send
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    stream::unfold(
+        (reader, None::<Vec<u8>>, false),
+        move |(mut reader, pending, done)| {
+            let failed = failed.clone();
+            async move {
+                if done {
+                    return None;
+                }
+                let current = match pending {
+                    Some(chunk) => chunk,
+                    None => match read_chunk(&mut reader, chunk_size).await {
+                        Ok(chunk) => chunk,
+                        Err(e) => {
+                            *failed.lock().unwrap() = Some(e);
+                            return None;
+                        }
+                    },
+                };
+                if current.len() < chunk_size {
+                    return Some(((current, true), (reader, None, true)));
+                }
+                match read_chunk(&mut reader, chunk_size).await {
+                    Ok(next) if next.is_empty() => Some(((current, true), (reader, None, true))),
+                    Ok(next) => Some(((current, false), (reader, Some(next), false))),
+                    Err(e) => {
+                        *failed.lock().unwrap() = Some(e);
+                        None
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A client whose calls never reach a server: tests fake the answers.
+    fn offline_client(policy: RetryPolicy) -> StorageClient {
+        let channel = Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+        StorageClient::new(channel).with_retry_policy(policy)
+    }
+
+    /// Answer `failures` in turn, then succeed; returns the result and the
+    /// number of attempts.
+    async fn call(
+        client: &StorageClient,
+        failures: Vec<Status>,
+    ) -> (Result<u32, ClientError>, u32) {
+        let attempts = AtomicU32::new(0);
+        let failures = Mutex::new(failures.into_iter());
+        let result = client
+            .retrying("test", |_| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                let failure = failures.lock().unwrap().next();
+                async move {
+                    match failure {
+                        Some(status) => Err(status),
+                        None => Ok(attempt),
+                    }
+                }
+            })
+            .await;
+        (result, attempts.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_until_the_server_answers() {
+        let client = offline_client(RetryPolicy::default());
+        let started = tokio::time::Instant::now();
+        let (result, attempts) = call(
+            &client,
+            vec![
+                Status::unavailable("restarting"),
+                Status::resource_exhausted("busy"),
+            ],
+        )
+        .await;
+        assert_eq!((result.unwrap(), attempts), (3, 3));
+        // Backed off between attempts: 50-100ms, then 100-200ms
+        let waited = started.elapsed();
+        assert!(
+            waited >= Duration::from_millis(150) && waited <= Duration::from_millis(300),
+            "{:?}",
+            waited
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gives_up_after_max_retries() {
+        let client = offline_client(RetryPolicy {
+            max_retries: 2,
+            ..Default::default()
+        });
+        let (result, attempts) = call(&client, vec![Status::unavailable("down"); 5]).await;
+        assert_eq!(attempts, 3);
+        assert_eq!(result.unwrap_err().code(), Some(tonic::Code::Unavailable));
+
+        let (result, attempts) = call(
+            &offline_client(RetryPolicy::none()),
+            vec![Status::unavailable("down")],
+        )
+        .await;
+        assert_eq!(attempts, 1);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_errors_the_server_processed_are_returned_at_once() {
+        let client = offline_client(RetryPolicy::default());
+        let (result, attempts) =
+            call(&client, vec![Status::not_found("Session s1 not found")]).await;
+        assert_eq!(attempts, 1);
+        let error = result.unwrap_err();
+        assert!(error.is_not_found());
+        assert!(
+            matches!(error, ClientError::Status(ref status) if status.message() == "Session s1 not found")
+        );
+    }
+
+    #[test]
+    fn test_requests_carry_the_tenant() {
+        let _runtime = tokio::runtime::Runtime::new().unwrap().enter();
+        let client = offline_client(RetryPolicy::none())
+            .with_tenant("acme")
+            .with_actor("ops");
+        let request = client.request(());
+        assert_eq!(request.metadata().get(TENANT_ID_HEADER).unwrap(), "acme");
+        assert!(request.metadata().get(REQUEST_ID_HEADER).is_some());
+        let context = client.context().unwrap();
+        assert_eq!(
+            (context.tenant_id.as_str(), context.actor.as_str()),
+            ("acme", "ops")
+        );
+
+        // The local tenant sends no header
+        let request = client.clone().with_tenant("").request(());
+        assert!(request.metadata().get(TENANT_ID_HEADER).is_none());
+    }
+}
@@ -0,0 +1,62 @@
+use thiserror::Error;
+use tonic::Code;
+
+/// Errors returned by [`StorageClient`](crate::StorageClient).
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Failed to connect to the storage server: {0}")]
+    Connect(#[from] tonic::transport::Error),
+
+    #[error("Storage server error ({}): {}", .0.code(), .0.message())]
+    Status(#[from] tonic::Status),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl ClientError {
+    /// The gRPC code the server answered with, if it answered.
+    pub fn code(&self) -> Option<Code> {
+        match self {
+            ClientError::Status(status) => Some(status.code()),
+            _ => None,
+        }
+    }
+
+    /// Whether the server said the resource doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.code() == Some(Code::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes() {
+        let error = ClientError::from(tonic::Status::not_found("Session s1 not found"));
+        assert_eq!(error.code(), Some(Code::NotFound));
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            "Storage server error (Some requested entity was not found): Session s1 not found"
+        );
+
+        let error = ClientError::from(tonic::Status::unavailable("down"));
+        assert_eq!(error.code(), Some(Code::Unavailable));
+        assert!(!error.is_not_found());
+
+        // Errors the server didn't answer have no code
+        let error = ClientError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "gone"));
+        assert_eq!(error.code(), None);
+        assert!(!error.is_not_found());
+        assert_eq!(
+            ClientError::InvalidResponse("bad index".to_string()).code(),
+            None
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Typed async client for the docx-mcp storage gRPC API.
+//!
+//! Wraps the generated tonic client for Rust consumers (CLI, tests, other services):
+//! - `StorageClient`: Tenant-scoped calls with request ID headers, and documents streamed
+//!   to and from `AsyncRead` / `AsyncWrite` in chunks
+//! - `RetryPolicy`: Backoff for calls the server didn't process, honouring its pushback
+//! - `ClientError`: Connection, server and I/O errors of a call
+//! - `proto`: The generated messages and client, for RPCs without a typed method
+
+mod client;
+mod error;
+mod retry;
+
+pub mod proto {
+    tonic::include_proto!("docx.storage");
+}
+
+pub use client::{StorageClient, DEFAULT_CHUNK_SIZE};
+pub use error::ClientError;
+pub use retry::RetryPolicy;
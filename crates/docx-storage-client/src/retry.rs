@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use docx_storage_core::RETRY_PUSHBACK_METADATA;
+use tonic::{Code, Status};
+
+/// When [`StorageClient`](crate::StorageClient) retries a failed call.
+///
+/// Only calls the server didn't process are retried: `UNAVAILABLE` (server
+/// down or restarting, connection refused) and `RESOURCE_EXHAUSTED`
+/// (throttling). A server's `grpc-retry-pushback-ms` wins over the backoff;
+/// a negative one, which quota errors carry, stops retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 = never retry)
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled before each next one
+    pub base_delay: Duration,
+    /// Longest delay between two attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait before retry `attempt` (0 for the first) of a call
+    /// that failed with `status`, or `None` to give up.
+    pub fn retry_delay(&self, attempt: u32, status: &Status) -> Option<Duration> {
+        if attempt >= self.max_retries
+            || !matches!(status.code(), Code::Unavailable | Code::ResourceExhausted)
+        {
+            return None;
+        }
+        let pushback = status
+            .metadata()
+            .get(RETRY_PUSHBACK_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        match pushback {
+            Some(millis) if millis < 0 => None,
+            Some(millis) => Some(Duration::from_millis(millis as u64).min(self.max_delay)),
+            None => {
+                let backoff = self
+                    .base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(self.max_delay);
+                // Jitter between half and all of the backoff, so clients
+                // failing together don't retry together
+                let half = backoff / 2;
+                Some(half + half.mul_f64(rand::random::<f64>()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::MetadataValue;
+
+    fn pushback(code: Code, millis: &'static str) -> Status {
+        let mut status = Status::new(code, "busy");
+        status
+            .metadata_mut()
+            .insert(RETRY_PUSHBACK_METADATA, MetadataValue::from_static(millis));
+        status
+    }
+
+    #[test]
+    fn test_only_unprocessed_calls_are_retried() {
+        let policy = RetryPolicy::default();
+        assert!(policy
+            .retry_delay(0, &Status::unavailable("down"))
+            .is_some());
+        assert!(policy
+            .retry_delay(0, &Status::resource_exhausted("busy"))
+            .is_some());
+        for code in [
+            Code::NotFound,
+            Code::Internal,
+            Code::DeadlineExceeded,
+            Code::FailedPrecondition,
+            Code::InvalidArgument,
+        ] {
+            assert_eq!(
+                policy.retry_delay(0, &Status::new(code, "")),
+                None,
+                "{:?}",
+                code
+            );
+        }
+
+        assert!(policy
+            .retry_delay(2, &Status::unavailable("down"))
+            .is_some());
+        assert_eq!(policy.retry_delay(3, &Status::unavailable("down")), None);
+        assert_eq!(
+            RetryPolicy::none().retry_delay(0, &Status::unavailable("down")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles_with_jitter_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        let status = Status::unavailable("down");
+        for (attempt, backoff) in [(0, 100), (1, 200), (2, 400), (3, 500), (9, 500)] {
+            let backoff = Duration::from_millis(backoff);
+            for _ in 0..20 {
+                let delay = policy.retry_delay(attempt, &status).unwrap();
+                assert!(
+                    delay >= backoff / 2 && delay <= backoff,
+                    "{:?} for attempt {}",
+                    delay,
+                    attempt
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_server_pushback_wins_over_backoff() {
+        let policy = RetryPolicy::default();
+        let delay = policy.retry_delay(0, &pushback(Code::ResourceExhausted, "1500"));
+        assert_eq!(delay, Some(Duration::from_millis(1500)));
+        // Capped at the longest delay
+        let delay = policy.retry_delay(0, &pushback(Code::Unavailable, "60000"));
+        assert_eq!(delay, Some(policy.max_delay));
+        // Quota errors say not to retry
+        assert_eq!(
+            policy.retry_delay(0, &pushback(Code::ResourceExhausted, "-1")),
+            None
+        );
+        // Unparsable pushback falls back to the backoff
+        let delay = policy
+            .retry_delay(0, &pushback(Code::Unavailable, "soon"))
+            .unwrap();
+        assert!(delay <= policy.base_delay);
+        // Still only for retryable codes
+        assert_eq!(policy.retry_delay(0, &pushback(Code::Internal, "10")), None);
+    }
+}
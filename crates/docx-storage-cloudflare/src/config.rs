@@ -1,4 +1,12 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::Parser;
+use docx_storage_core::{CheckpointPolicy, LogFormat, TenantLimits};
+
+use crate::storage::{
+    parse_retry_override, R2Rates, R2RetryPolicy, RetryOverride, RetrySettings,
+};
 
 /// Configuration for the docx-storage-cloudflare server.
 #[derive(Parser, Debug, Clone)]
@@ -21,6 +29,53 @@ pub struct Config {
     #[arg(long, env = "R2_BUCKET_NAME")]
     pub r2_bucket_name: String,
 
+    /// Region name of the --r2-bucket-name bucket
+    #[arg(long, default_value = "default", env = "R2_REGION")]
+    pub r2_region: String,
+
+    /// R2 jurisdiction of the --r2-bucket-name bucket (e.g. "eu"); buckets
+    /// created in a jurisdiction are only reachable through its endpoint
+    #[arg(long, env = "R2_JURISDICTION")]
+    pub r2_jurisdiction: Option<String>,
+
+    /// More buckets, one per region, as REGION=BUCKET[@JURISDICTION],
+    /// comma-separated. All share the account and credentials
+    #[arg(long = "region-bucket", env = "R2_REGION_BUCKETS", value_delimiter = ',', value_parser = parse_region_bucket)]
+    pub region_buckets: Vec<RegionBucket>,
+
+    /// Region of tenants without a pin (default: the --r2-region bucket's)
+    #[arg(long, env = "DEFAULT_REGION")]
+    pub default_region: Option<String>,
+
+    /// Per-tenant region pins, as TENANT=REGION, comma-separated
+    #[arg(long = "tenant-region", env = "TENANT_REGIONS", value_delimiter = ',', value_parser = parse_tenant_region)]
+    pub tenant_regions: Vec<(String, String)>,
+
+    /// Regions data may be stored in, comma-separated (e.g. "eu" for EU-only
+    /// storage). The server refuses to start with a bucket in another
+    /// region. All regions are allowed when unset
+    #[arg(long = "allowed-regions", env = "ALLOWED_REGIONS", value_delimiter = ',')]
+    pub allowed_regions: Vec<String>,
+
+    /// Key (32 bytes, base64) the website seals the secrets of buckets
+    /// registered by tenants with. When set, tenants with a bucket of their
+    /// own are served from it; requires --d1-database-id and
+    /// --cloudflare-api-token
+    #[arg(long, env = "BUCKET_CREDENTIALS_KEY", hide_env_values = true)]
+    pub bucket_credentials_key: Option<String>,
+
+    /// D1 database holding the buckets registered by tenants (`tenant_bucket`)
+    #[arg(long, env = "D1_DATABASE_ID")]
+    pub d1_database_id: Option<String>,
+
+    /// Cloudflare API token with D1 read permission
+    #[arg(long, env = "CLOUDFLARE_API_TOKEN", hide_env_values = true)]
+    pub cloudflare_api_token: Option<String>,
+
+    /// Seconds between reloads of the buckets registered by tenants
+    #[arg(long, default_value = "60", env = "TENANT_BUCKET_REFRESH_SECS")]
+    pub tenant_bucket_refresh_secs: u64,
+
     /// R2 access key ID (for S3-compatible API)
     #[arg(long, env = "R2_ACCESS_KEY_ID")]
     pub r2_access_key_id: String,
@@ -28,14 +83,264 @@ pub struct Config {
     /// R2 secret access key (for S3-compatible API)
     #[arg(long, env = "R2_SECRET_ACCESS_KEY")]
     pub r2_secret_access_key: String,
+
+    /// Local directory for caching session/checkpoint documents fetched from R2.
+    /// Disabled when unset. Only enable when this instance is the sole writer
+    /// for its tenants, since other writers won't invalidate this cache. Each
+    /// region's bucket caches in its own subdirectory.
+    #[arg(long, env = "DISK_CACHE_DIR")]
+    pub disk_cache_dir: Option<PathBuf>,
+
+    /// Maximum size of the disk cache in bytes
+    #[arg(long, default_value = "1073741824", env = "DISK_CACHE_MAX_BYTES")]
+    pub disk_cache_max_bytes: u64,
+
+    /// Retries for R2 requests failing with a transient error (429 / 5xx)
+    #[arg(long, default_value = "5", env = "R2_MAX_RETRIES")]
+    pub r2_max_retries: u32,
+
+    /// Retries for CAS loops (index and WAL updates) losing an ETag race
+    #[arg(long, default_value = "10", env = "R2_CAS_MAX_RETRIES")]
+    pub r2_cas_max_retries: u32,
+
+    /// Delay before the first R2 retry, in milliseconds (doubles per retry)
+    #[arg(long, default_value = "200", env = "R2_RETRY_BASE_DELAY_MS")]
+    pub r2_retry_base_delay_ms: u64,
+
+    /// Cap on any single R2 retry delay, in milliseconds
+    #[arg(long, default_value = "30000", env = "R2_RETRY_MAX_DELAY_MS")]
+    pub r2_retry_max_delay_ms: u64,
+
+    /// Per-operation overrides as OP=RETRIES[:BASE_DELAY_MS], comma-separated.
+    /// OP is one of get, put, delete, list, cas_index, cas_wal.
+    #[arg(long, env = "R2_RETRY_OVERRIDES", value_delimiter = ',', value_parser = parse_retry_override)]
+    pub r2_retry_overrides: Vec<RetryOverride>,
+
+    /// Log output format: text or json (one object per line, with the
+    /// request, tenant and session IDs of the enclosing spans)
+    #[arg(long, default_value = "text", env = "LOG_FORMAT")]
+    pub log_format: LogFormat,
+
+    /// Key (at least 16 bytes) signing tenant erasure confirmation tokens and
+    /// reports. EraseTenant is refused when unset
+    #[arg(long, env = "ERASURE_KEY", hide_env_values = true)]
+    pub erasure_key: Option<String>,
+
+    /// Key (at least 16 bytes) signing history proofs. When set, every
+    /// session's WAL appends, truncations and checkpoints are hash-chained;
+    /// GetHistoryProof is refused when unset
+    #[arg(long, env = "HISTORY_KEY", hide_env_values = true)]
+    pub history_key: Option<String>,
+
+    /// JSON file declaring the bucket's object lifecycle rules, applied with
+    /// the ApplyLifecycleRules RPC (e.g. `walctl lifecycle --apply`)
+    #[arg(long, env = "R2_LIFECYCLE_RULES")]
+    pub lifecycle_rules: Option<PathBuf>,
+
+    /// Longest, in seconds, a URL issued by CreateDownloadUrl or
+    /// CreateUploadUrl stays valid, at most 604800 (7 days), and 900 for
+    /// uploads. Presigned URLs are refused when 0
+    #[arg(long, default_value = "0", env = "PRESIGNED_URL_MAX_TTL_SECS")]
+    pub presigned_url_max_ttl_secs: u64,
+
+    /// Sessions an ExportTenantCorpus stream reads per second, so compliance
+    /// exports don't starve the tenant's editing (0 = unpaced)
+    #[arg(long, default_value = "10", env = "CORPUS_EXPORT_SESSIONS_PER_SEC")]
+    pub corpus_export_sessions_per_sec: u32,
+
+    /// Seconds between purges of expired ephemeral sessions (0 disables them)
+    #[arg(long, default_value = "60", env = "PURGE_INTERVAL_SECS")]
+    pub purge_interval_secs: u64,
+
+    /// Seconds between rebuilds of every tenant's index from its stored
+    /// sessions (0 disables them). Scheduled rebuilds report orphaned index
+    /// entries but never remove them
+    #[arg(long, default_value = "0", env = "INDEX_REBUILD_INTERVAL_SECS")]
+    pub index_rebuild_interval_secs: u64,
+
+    /// Store the images of saved sessions once per tenant by content hash,
+    /// instead of in every session and checkpoint package
+    #[arg(long, env = "MEDIA_DEDUP")]
+    pub media_dedup: bool,
+
+    /// Seconds between sweeps of media no longer referenced by any package,
+    /// when deduplicating media (0 disables them)
+    #[arg(long, default_value = "86400", env = "MEDIA_SWEEP_INTERVAL_SECS")]
+    pub media_sweep_interval_secs: u64,
+
+    /// Directory resumable SaveSession uploads are spooled to until their
+    /// last chunk arrives. Resumable uploads are refused when unset
+    #[arg(long, env = "UPLOAD_SPOOL_DIR")]
+    pub upload_spool_dir: Option<PathBuf>,
+
+    /// Seconds an interrupted resumable upload can be resumed for
+    #[arg(long, default_value = "3600", env = "UPLOAD_TTL_SECS")]
+    pub upload_ttl_secs: u64,
+
+    /// WAL entries since the last checkpoint after which clients are told to
+    /// save one (1 = after every entry, 0 = never on count)
+    #[arg(long, default_value = "1", env = "CHECKPOINT_EVERY_ENTRIES")]
+    pub checkpoint_every_entries: u64,
+
+    /// Seconds after which the next edit of a session is checkpointed, however
+    /// few entries it has since the last one (0 disables)
+    #[arg(long, default_value = "0", env = "CHECKPOINT_EVERY_SECS")]
+    pub checkpoint_every_secs: u64,
+
+    /// Patch ops checkpointed as soon as they are logged, comma-separated
+    /// (defaults to replace_text, import_xliff and optimize_document)
+    #[arg(long, env = "CHECKPOINT_OPS", value_delimiter = ',')]
+    pub checkpoint_ops: Option<Vec<String>>,
+
+    /// How old, in seconds, a listing served to ListSessions and
+    /// ListCheckpoints requests accepting stale reads may be (0 disables
+    /// stale reads: every listing hits R2)
+    #[arg(long, default_value = "30", env = "STALE_READ_MAX_AGE_SECS")]
+    pub stale_read_max_age_secs: u64,
+
+    /// Count R2 requests and bytes per tenant, day and RPC, for the
+    /// GetCostEstimate RPC. Counts are kept in memory
+    #[arg(long, env = "USAGE_METERING")]
+    pub usage_metering: bool,
+
+    /// Days of usage counts kept when metering
+    #[arg(long, default_value = "31", env = "USAGE_RETENTION_DAYS")]
+    pub usage_retention_days: u32,
+
+    /// Seconds of CAS conflicts GetContentionReport's recent rates, and the
+    /// index sharding threshold, are computed over
+    #[arg(long, default_value = "600", env = "CONTENTION_WINDOW_SECS")]
+    pub contention_window_secs: u64,
+
+    /// Share of a tenant's index writes that conflict, over the contention
+    /// window, above which an index still stored whole in index.json (by an
+    /// older server) is split into one entry object per session; 0 splits it
+    /// on its next write. New indexes are always split
+    #[arg(long, default_value = "0", env = "INDEX_SHARD_CONFLICT_RATE")]
+    pub index_shard_conflict_rate: f64,
+
+    /// Price of a million Class A requests (writes, lists), in USD
+    #[arg(long, default_value = "4.5", env = "R2_CLASS_A_USD_PER_MILLION")]
+    pub r2_class_a_usd_per_million: f64,
+
+    /// Price of a million Class B requests (reads), in USD
+    #[arg(long, default_value = "0.36", env = "R2_CLASS_B_USD_PER_MILLION")]
+    pub r2_class_b_usd_per_million: f64,
+
+    /// Requests of one tenant handled at once; more wait in the tenant's
+    /// queue (0 = unlimited)
+    #[arg(long, default_value = "16", env = "TENANT_MAX_IN_FLIGHT")]
+    pub tenant_max_in_flight: usize,
+
+    /// Requests of one tenant waiting for a slot before new ones are
+    /// rejected with RESOURCE_EXHAUSTED
+    #[arg(long, default_value = "64", env = "TENANT_MAX_QUEUED")]
+    pub tenant_max_queued: usize,
+
+    /// Milliseconds a queued request waits for a slot before it is rejected
+    #[arg(long, default_value = "5000", env = "TENANT_QUEUE_TIMEOUT_MS")]
+    pub tenant_queue_timeout_ms: u64,
+
+    /// TOML configuration file (keys are the long option names). Flags and
+    /// environment variables take precedence over it; it is reloaded on
+    /// SIGHUP and when it changes
+    #[arg(long, env = "CONFIG_FILE")]
+    pub config: Option<PathBuf>,
 }
 
 impl Config {
-    /// Get the R2 endpoint URL for S3-compatible API.
-    pub fn r2_endpoint(&self) -> String {
-        format!(
-            "https://{}.r2.cloudflarestorage.com",
-            self.cloudflare_account_id
+    /// Per-tenant concurrency limits from the tenant options.
+    pub fn tenant_limits(&self) -> TenantLimits {
+        TenantLimits {
+            max_in_flight: self.tenant_max_in_flight,
+            max_queued: self.tenant_max_queued,
+            queue_timeout: Duration::from_millis(self.tenant_queue_timeout_ms),
+        }
+    }
+
+    /// Checkpoint policy from the checkpoint options.
+    pub fn checkpoint_policy(&self) -> CheckpointPolicy {
+        let mut policy = CheckpointPolicy {
+            every_entries: self.checkpoint_every_entries,
+            every: (self.checkpoint_every_secs > 0)
+                .then(|| Duration::from_secs(self.checkpoint_every_secs)),
+            ..CheckpointPolicy::default()
+        };
+        if let Some(ops) = &self.checkpoint_ops {
+            policy.ops = ops.iter().map(|op| op.trim().to_string()).collect();
+        }
+        policy
+    }
+
+    /// Get the R2 endpoint URL for S3-compatible API, for buckets in
+    /// `jurisdiction` (or none).
+    pub fn r2_endpoint(&self, jurisdiction: Option<&str>) -> String {
+        match jurisdiction {
+            Some(jurisdiction) => format!(
+                "https://{}.{}.r2.cloudflarestorage.com",
+                self.cloudflare_account_id, jurisdiction
+            ),
+            None => format!(
+                "https://{}.r2.cloudflarestorage.com",
+                self.cloudflare_account_id
+            ),
+        }
+    }
+
+    /// Prices used to estimate the cost of metered requests.
+    pub fn r2_rates(&self) -> R2Rates {
+        R2Rates {
+            class_a_per_million: self.r2_class_a_usd_per_million,
+            class_b_per_million: self.r2_class_b_usd_per_million,
+        }
+    }
+
+    /// Retry policy for R2 calls.
+    pub fn r2_retry_policy(&self) -> R2RetryPolicy {
+        let base_delay = Duration::from_millis(self.r2_retry_base_delay_ms);
+        R2RetryPolicy::new(
+            RetrySettings {
+                max_retries: self.r2_max_retries,
+                base_delay,
+            },
+            RetrySettings {
+                max_retries: self.r2_cas_max_retries,
+                base_delay,
+            },
+            Duration::from_millis(self.r2_retry_max_delay_ms),
         )
+        .with_overrides(&self.r2_retry_overrides)
     }
 }
+
+/// A regional bucket declared on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionBucket {
+    pub region: String,
+    pub bucket: String,
+    pub jurisdiction: Option<String>,
+}
+
+/// Parse a `region=bucket[@jurisdiction]` bucket from the command line.
+fn parse_region_bucket(s: &str) -> Result<RegionBucket, String> {
+    let (region, spec) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected REGION=BUCKET[@JURISDICTION], got '{}'", s))?;
+    let (bucket, jurisdiction) = match spec.split_once('@') {
+        Some((bucket, jurisdiction)) => (bucket, Some(jurisdiction.trim().to_string())),
+        None => (spec, None),
+    };
+    Ok(RegionBucket {
+        region: region.trim().to_string(),
+        bucket: bucket.trim().to_string(),
+        jurisdiction,
+    })
+}
+
+/// Parse a `tenant=region` pin from the command line.
+fn parse_tenant_region(s: &str) -> Result<(String, String), String> {
+    let (tenant, region) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TENANT=REGION, got '{}'", s))?;
+    Ok((tenant.trim().to_string(), region.trim().to_string()))
+}
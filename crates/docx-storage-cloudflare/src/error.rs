@@ -1,18 +1,38 @@
 // Re-export from docx-storage-core
 pub use docx_storage_core::StorageError;
 
-/// Convert StorageError to tonic::Status
+/// Convert StorageError to tonic::Status, with the error kind and retry
+/// hints as metadata
 pub fn storage_error_to_status(err: StorageError) -> tonic::Status {
-    match err {
+    let metadata = err.metadata();
+    let mut status = match err {
         StorageError::Io(msg) => tonic::Status::internal(msg),
         StorageError::Serialization(msg) => tonic::Status::internal(msg),
         StorageError::NotFound(msg) => tonic::Status::not_found(msg),
+        StorageError::Throttled { message, .. } => tonic::Status::resource_exhausted(message),
+        StorageError::PreconditionFailed(msg) => tonic::Status::failed_precondition(msg),
+        StorageError::QuotaExceeded(msg) => tonic::Status::resource_exhausted(msg),
+        StorageError::Unauthenticated(msg) => tonic::Status::unauthenticated(msg),
         StorageError::Lock(msg) => tonic::Status::failed_precondition(msg),
         StorageError::InvalidArgument(msg) => tonic::Status::invalid_argument(msg),
         StorageError::Internal(msg) => tonic::Status::internal(msg),
         StorageError::Sync(msg) => tonic::Status::internal(msg),
+        StorageError::Unavailable(msg) => tonic::Status::unavailable(msg),
         StorageError::Watch(msg) => tonic::Status::internal(msg),
+        StorageError::DeadlineExceeded(msg) => tonic::Status::deadline_exceeded(msg),
+        // Keep the "Legal hold:" prefix so clients can tell it from other preconditions
+        err @ StorageError::LegalHold(_) => tonic::Status::failed_precondition(err.to_string()),
+        StorageError::Conflict(msg) => tonic::Status::aborted(msg),
+        err @ StorageError::WalPosition { .. } => {
+            tonic::Status::failed_precondition(err.to_string())
+        }
+    };
+    for (key, value) in metadata {
+        if let Ok(value) = value.parse() {
+            status.metadata_mut().insert(key, value);
+        }
     }
+    status
 }
 
 /// Extension trait for converting StorageError Result to tonic::Status Result
@@ -1,70 +1,230 @@
 mod config;
 mod error;
 mod service;
+mod service_operation;
 mod storage;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use aws_config::Region;
 use aws_sdk_s3::config::{BehaviorVersion, Credentials};
-use clap::Parser;
+use aws_smithy_http_client::tls;
 use tokio::signal;
 use tokio::sync::watch as tokio_watch;
 use tonic::transport::Server;
 use tonic_reflection::server::Builder as ReflectionBuilder;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
 
 use config::Config;
+use docx_storage_core::{
+    CorrelationLayer, DeadlineLayer, OperationRegistry, TenantLimitLayer, TenantLimiter,
+};
+use service::proto::operation_service_server::OperationServiceServer;
 use service::proto::storage_service_server::StorageServiceServer;
 use service::StorageServiceImpl;
-use storage::R2Storage;
+use service_operation::{OperationServiceImpl, OPERATION_RETENTION};
+use storage::{
+    BucketPlacement, ContentionTracker, DiskCache, LifecycleRules, PublicResolver, R2RetryPolicy,
+    R2Storage, TenantBucket, TenantBuckets, UsageMeter, MAX_PRESIGNED_URL_TTL,
+};
 
 /// File descriptor set for gRPC reflection
 pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("storage_descriptor");
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let (config, config_source) = docx_storage_core::load_config::<Config>();
 
-    let config = Config::parse();
+    // Initialize logging
+    docx_storage_core::init_tracing(config.log_format);
 
     info!("Starting docx-storage-cloudflare server");
-    info!("  R2 bucket: {}", config.r2_bucket_name);
+    info!("  R2 bucket: {} (region {})", config.r2_bucket_name, config.r2_region);
 
-    // Create S3 client for R2
-    let credentials = Credentials::new(
-        &config.r2_access_key_id,
-        &config.r2_secret_access_key,
-        None,
-        None,
-        "r2",
+    // Create storage backends (R2 only — no sync/watch, Cloudflare is just a WAL/session store)
+    let retry_policy = config.r2_retry_policy();
+    info!(
+        "  R2 retries: {} transient, {} CAS, {}ms base delay",
+        retry_policy.transient.max_retries,
+        retry_policy.cas.max_retries,
+        retry_policy.transient.base_delay.as_millis()
     );
+    // One meter for every bucket, so estimates cover all regions
+    let meter = config.usage_metering.then(|| {
+        info!("  Usage metering: enabled ({} days kept)", config.usage_retention_days);
+        Arc::new(UsageMeter::new(config.usage_retention_days))
+    });
+    // One contention tracker for every bucket too
+    let contention = Arc::new(ContentionTracker::new(Duration::from_secs(
+        config.contention_window_secs,
+    )));
+    if config.index_shard_conflict_rate > 0.0 {
+        info!(
+            "  Index sharding: whole indexes split above {:.0}% conflicting writes",
+            config.index_shard_conflict_rate * 100.0
+        );
+    }
+    let primary = r2_bucket(
+        &config,
+        &config.r2_region,
+        &config.r2_bucket_name,
+        config.r2_jurisdiction.as_deref(),
+        meter.clone(),
+        contention.clone(),
+    )?;
+    let mut retry_policies = vec![primary.retry_policy()];
+    let mut placement = BucketPlacement::new(&config.r2_region, Arc::new(primary));
+    for spec in &config.region_buckets {
+        info!("  R2 bucket: {} (region {})", spec.bucket, spec.region);
+        let storage = r2_bucket(
+            &config,
+            &spec.region,
+            &spec.bucket,
+            spec.jurisdiction.as_deref(),
+            meter.clone(),
+            contention.clone(),
+        )?;
+        retry_policies.push(storage.retry_policy());
+        placement = placement.with_bucket(&spec.region, Arc::new(storage))?;
+    }
+    if let Some(region) = &config.default_region {
+        placement = placement.with_default_region(region)?;
+    }
+    for (tenant, region) in &config.tenant_regions {
+        info!("  Tenant region: {} -> {}", tenant, region);
+        placement = placement.pin(tenant, region)?;
+    }
+    if !config.allowed_regions.is_empty() {
+        info!("  Allowed regions: {}", config.allowed_regions.join(", "));
+    }
+    if let Some(key) = &config.bucket_credentials_key {
+        let (Some(database_id), Some(api_token)) =
+            (&config.d1_database_id, &config.cloudflare_api_token)
+        else {
+            anyhow::bail!("--bucket-credentials-key requires --d1-database-id and --cloudflare-api-token");
+        };
+        let open = {
+            let config = config.clone();
+            // Tenant buckets follow the primary bucket's retry policy as reloaded
+            let retry_policy = retry_policies[0].clone();
+            let (meter, contention) = (meter.clone(), contention.clone());
+            move |bucket: &TenantBucket| {
+                tenant_bucket(
+                    &config,
+                    bucket,
+                    retry_policy.get().as_ref().clone(),
+                    meter.clone(),
+                    contention.clone(),
+                )
+            }
+        };
+        let tenant_buckets = Arc::new(TenantBuckets::new(
+            &config.cloudflare_account_id,
+            api_token,
+            database_id,
+            key,
+            Box::new(open),
+        )?
+        .with_allowed_regions(&config.allowed_regions));
+        // Loaded before serving, so no tenant with a bucket of its own is
+        // served from a shared one meanwhile
+        let count = tenant_buckets.refresh().await?;
+        info!("  Tenant buckets: {} registered", count);
+        if config.tenant_bucket_refresh_secs > 0 {
+            info!("  Tenant bucket reload: every {}s", config.tenant_bucket_refresh_secs);
+            spawn_tenant_bucket_refresh(
+                tenant_buckets.clone(),
+                Duration::from_secs(config.tenant_bucket_refresh_secs),
+            );
+        }
+        placement = placement.with_tenant_buckets(tenant_buckets);
+    }
+    let placement = Arc::new(placement.restrict_to(&config.allowed_regions)?);
 
-    let s3_config = aws_sdk_s3::Config::builder()
-        .behavior_version(BehaviorVersion::latest())
-        .credentials_provider(credentials)
-        .region(Region::new("auto"))
-        .endpoint_url(config.r2_endpoint())
-        .force_path_style(true)
-        .build();
-
-    let s3_client = aws_sdk_s3::Client::from_conf(s3_config);
-
-    // Create storage backend (R2 only — no sync/watch, Cloudflare is just a WAL/session store)
-    let storage = Arc::new(R2Storage::new(
-        s3_client,
-        config.r2_bucket_name.clone(),
-    ));
+    // Reload the retry policy with the configuration file
+    if let Some(path) = config_source.path() {
+        info!("  Config file: {} (reloaded on change or SIGHUP)", path.display());
+    }
+    config_source.watch(move |config: Config| {
+        for retry_policy in &retry_policies {
+            retry_policy.set(config.r2_retry_policy());
+        }
+        Ok(())
+    });
 
-    // Create gRPC service (StorageService only)
-    let storage_service = StorageServiceImpl::new(storage);
-    let storage_svc = StorageServiceServer::new(storage_service);
+    // Create gRPC services (StorageService and OperationService)
+    let mut storage_service = StorageServiceImpl::new(placement)
+        .with_checkpoint_policy(config.checkpoint_policy())
+        .with_corpus_export_rate(config.corpus_export_sessions_per_sec);
+    if let Some(key) = &config.erasure_key {
+        info!("  Tenant erasure: enabled");
+        storage_service = storage_service
+            .with_erasure_signer(docx_storage_core::ErasureSigner::new(key.as_bytes())?);
+    }
+    if let Some(key) = &config.history_key {
+        info!("  History chain: enabled");
+        storage_service = storage_service
+            .with_history_signer(docx_storage_core::HistorySigner::new(key.as_bytes())?);
+    }
+    if let Some(dir) = &config.upload_spool_dir {
+        info!("  Upload spool: {}", dir.display());
+        storage_service = storage_service.with_upload_spool(docx_storage_core::UploadSpool::new(
+            dir,
+            Duration::from_secs(config.upload_ttl_secs),
+        ));
+    }
+    if let Some(path) = &config.lifecycle_rules {
+        // Fail at startup rather than on the first ApplyLifecycleRules
+        let rules = LifecycleRules::load(path)?;
+        info!("  Lifecycle rules: {} ({} rules)", path.display(), rules.rules.len());
+        storage_service = storage_service.with_lifecycle_rules(path.clone());
+    }
+    if let Some(meter) = meter {
+        storage_service = storage_service.with_usage_meter(meter, config.r2_rates());
+    }
+    storage_service =
+        storage_service.with_contention_tracker(contention, config.index_shard_conflict_rate);
+    if config.stale_read_max_age_secs > 0 {
+        info!("  Stale list reads: up to {}s old", config.stale_read_max_age_secs);
+        storage_service = storage_service
+            .with_stale_reads(Duration::from_secs(config.stale_read_max_age_secs));
+    }
+    if config.presigned_url_max_ttl_secs > 0 {
+        let max_ttl = Duration::from_secs(config.presigned_url_max_ttl_secs)
+            .min(MAX_PRESIGNED_URL_TTL);
+        info!("  Presigned URLs: valid up to {}s", max_ttl.as_secs());
+        storage_service = storage_service.with_presigned_urls(max_ttl);
+    }
+    let storage_service = Arc::new(storage_service);
+    if config.purge_interval_secs > 0 {
+        info!("  Ephemeral session purge: every {}s", config.purge_interval_secs);
+        spawn_expiry_purge(
+            storage_service.clone(),
+            Duration::from_secs(config.purge_interval_secs),
+        );
+    }
+    if config.index_rebuild_interval_secs > 0 {
+        info!("  Index rebuild: every {}s", config.index_rebuild_interval_secs);
+        spawn_index_rebuild(
+            storage_service.clone(),
+            Duration::from_secs(config.index_rebuild_interval_secs),
+        );
+    }
+    if config.media_dedup {
+        info!("  Media deduplication: enabled");
+        if config.media_sweep_interval_secs > 0 {
+            info!("  Media sweep: every {}s", config.media_sweep_interval_secs);
+            spawn_media_sweep(
+                storage_service.clone(),
+                Duration::from_secs(config.media_sweep_interval_secs),
+            );
+        }
+    }
+    let storage_svc = StorageServiceServer::from_arc(storage_service);
+    let operation_svc = OperationServiceServer::new(OperationServiceImpl::new(Arc::new(
+        OperationRegistry::new(OPERATION_RETENTION),
+    )));
 
     // Create shutdown signal
     let mut shutdown_rx = create_shutdown_signal();
@@ -77,13 +237,25 @@ async fn main() -> anyhow::Result<()> {
         .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
         .build_v1()?;
 
+    let tenant_limits = config.tenant_limits();
+    if tenant_limits.max_in_flight > 0 {
+        info!(
+            "  Tenant limits: {} requests in flight, {} queued",
+            tenant_limits.max_in_flight, tenant_limits.max_queued
+        );
+    }
+
     // Start server
     let addr = format!("{}:{}", config.host, config.port).parse()?;
     info!("Listening on tcp://{}", addr);
 
     Server::builder()
+        .layer(CorrelationLayer)
+        .layer(DeadlineLayer)
+        .layer(TenantLimitLayer::new(Arc::new(TenantLimiter::new(tenant_limits))))
         .add_service(reflection_svc)
         .add_service(storage_svc)
+        .add_service(operation_svc)
         .serve_with_shutdown(addr, shutdown_future)
         .await?;
 
@@ -91,6 +263,154 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Storage for the bucket of `region`, caching in its own subdirectory of
+/// the disk cache (each cache clears its directory on startup), and counting
+/// requests in `meter` if set and CAS conflicts in `contention`.
+fn r2_bucket(
+    config: &Config,
+    region: &str,
+    bucket: &str,
+    jurisdiction: Option<&str>,
+    meter: Option<Arc<UsageMeter>>,
+    contention: Arc<ContentionTracker>,
+) -> anyhow::Result<R2Storage> {
+    let credentials = Credentials::new(
+        &config.r2_access_key_id,
+        &config.r2_secret_access_key,
+        None,
+        None,
+        "r2",
+    );
+
+    let s3_config = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .credentials_provider(credentials)
+        .region(Region::new("auto"))
+        .endpoint_url(config.r2_endpoint(jurisdiction))
+        .force_path_style(true)
+        .build();
+
+    let s3_client = aws_sdk_s3::Client::from_conf(s3_config);
+
+    let mut storage = R2Storage::new(s3_client, bucket.to_string())
+        .with_retry_policy(config.r2_retry_policy())
+        .with_media_dedup(config.media_dedup)
+        .with_history_chain(config.history_key.is_some())
+        .with_contention_tracker(contention)
+        .with_index_sharding(config.index_shard_conflict_rate);
+    if let Some(cache_dir) = &config.disk_cache_dir {
+        let cache_dir = cache_dir.join(region);
+        info!(
+            "  Disk cache: {} (max {} bytes)",
+            cache_dir.display(),
+            config.disk_cache_max_bytes
+        );
+        storage = storage.with_disk_cache(DiskCache::new(&cache_dir, config.disk_cache_max_bytes)?);
+    }
+    if let Some(meter) = meter {
+        storage = storage.with_usage_meter(meter);
+    }
+    Ok(storage)
+}
+
+/// Storage for a bucket registered by a tenant, reached with its own
+/// credentials. It isn't disk cached: the tenant's data stays in its bucket.
+fn tenant_bucket(
+    config: &Config,
+    bucket: &TenantBucket,
+    retry_policy: R2RetryPolicy,
+    meter: Option<Arc<UsageMeter>>,
+    contention: Arc<ContentionTracker>,
+) -> anyhow::Result<R2Storage> {
+    let credentials = Credentials::new(
+        &bucket.access_key_id,
+        &bucket.secret_access_key,
+        None,
+        None,
+        "tenant",
+    );
+
+    // Tenants choose the endpoint: only connect to public addresses
+    let http_client = aws_smithy_http_client::Builder::new()
+        .tls_provider(tls::Provider::Rustls(tls::rustls_provider::CryptoMode::AwsLc))
+        .build_with_resolver(PublicResolver);
+
+    let s3_config = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .http_client(http_client)
+        .credentials_provider(credentials)
+        .region(Region::new(bucket.region.clone()))
+        .endpoint_url(&bucket.endpoint)
+        .force_path_style(true)
+        .build();
+
+    let mut storage = R2Storage::new(aws_sdk_s3::Client::from_conf(s3_config), bucket.bucket.clone())
+        .with_retry_policy(retry_policy)
+        .with_media_dedup(config.media_dedup)
+        .with_history_chain(config.history_key.is_some())
+        .with_contention_tracker(contention)
+        .with_index_sharding(config.index_shard_conflict_rate);
+    if let Some(meter) = meter {
+        storage = storage.with_usage_meter(meter);
+    }
+    Ok(storage)
+}
+
+/// Periodically reload the buckets registered by tenants. A failed reload
+/// keeps the buckets already loaded.
+fn spawn_tenant_bucket_refresh(tenant_buckets: Arc<TenantBuckets>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = tenant_buckets.refresh().await {
+                warn!("Failed to reload tenant buckets: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically purge expired ephemeral sessions.
+fn spawn_expiry_purge(service: Arc<StorageServiceImpl>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match service.purge_expired(chrono::Utc::now()).await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} expired sessions", purged),
+                Err(e) => warn!("Failed to purge expired sessions: {}", e.message()),
+            }
+        }
+    });
+}
+
+/// Periodically rebuild every tenant's index from its stored sessions.
+fn spawn_index_rebuild(service: Arc<StorageServiceImpl>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match service.rebuild_indexes().await {
+                Ok(0) => {}
+                Ok(repaired) => info!("Rebuilt {} out-of-sync indexes", repaired),
+                Err(e) => warn!("Failed to rebuild indexes: {}", e.message()),
+            }
+        }
+    });
+}
+
+/// Periodically delete media blobs no longer referenced by any package.
+fn spawn_media_sweep(service: Arc<StorageServiceImpl>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match service.sweep_media().await {
+                Ok(0) => {}
+                Ok(deleted) => info!("Swept {} unreferenced media blobs", deleted),
+                Err(e) => warn!("Failed to sweep media: {}", e.message()),
+            }
+        }
+    });
+}
+
 /// Create a shutdown signal that triggers on Ctrl+C or SIGTERM.
 fn create_shutdown_signal() -> tokio_watch::Receiver<bool> {
     let (tx, rx) = tokio_watch::channel(false);
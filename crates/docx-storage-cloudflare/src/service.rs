@@ -1,13 +1,25 @@
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use docx_storage_core::{
+    copy_session_data, create_sandbox, discard_sandbox, ensure_not_held, erase_tenant, export_corpus, feature,
+    load_session_with_history, owning_tenant, prove_history, record_admin_action, sandbox_tenant_id, scan_sessions, session_health,
+    validate_session_id, validate_tenant_id, AdminAction, AdminLogEntry, Capabilities, CheckpointPolicy, ChunkStream, CorpusRecord, CircuitState, ErasureSigner, ErasureStep,
+    HistorySigner, IndexRebuildReport, LegalHold, PinnedSnapshot, SessionHealth, SessionPageQuery, SnapshotRegistry, StorageError,
+    UploadProgress, UploadSpool, CORPUS_FORMAT_JSONL, DEFAULT_ADMIN_LOG_LIMIT, DEFAULT_CORPUS_SESSIONS_PER_SEC,
+    PROTO_SCHEMA_VERSION, SYSTEM_ACTOR, WEBSITE_TENANT_DATA,
+};
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use crate::error::StorageResultExt;
-use crate::storage::{R2Storage, StorageBackend};
+use crate::storage::{
+    BucketPlacement, ContentionTracker, LifecycleRules, ListSnapshots, R2Rates, R2Storage, StorageBackend,
+    UsageMeter, MAX_PRESIGNED_UPLOAD_TTL,
+};
 
 // Include the generated protobuf code
 pub mod proto {
@@ -20,36 +32,625 @@ use proto::*;
 /// Default chunk size for streaming: 256KB
 const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
 
+/// WAL entries returned by `TailWal` when the request sets no limit.
+const DEFAULT_TAIL_LIMIT: u64 = 100;
+
+/// WAL entries per `LoadSessionWithHistory` chunk.
+const HISTORY_ENTRIES_PER_CHUNK: usize = 256;
+
+/// Sessions one `AppendWalBatch` call may append to.
+const MAX_APPEND_BATCH_SESSIONS: usize = 256;
+
 /// Implementation of the StorageService gRPC service.
 pub struct StorageServiceImpl {
-    storage: Arc<R2Storage>,
+    placement: Arc<BucketPlacement>,
     version: String,
     chunk_size: usize,
+    erasure_signer: Option<ErasureSigner>,
+    history_signer: Option<HistorySigner>,
+    uploads: Option<UploadSpool>,
+    checkpoint_policy: CheckpointPolicy,
+    lifecycle_rules: Option<PathBuf>,
+    usage: Option<(Arc<UsageMeter>, R2Rates)>,
+    contention: Option<(Arc<ContentionTracker>, f64)>,
+    session_lists: Option<ListSnapshots<String, Vec<SessionInfo>>>,
+    checkpoint_lists: Option<ListSnapshots<(String, String), Vec<CheckpointInfo>>>,
+    snapshots: SnapshotRegistry,
+    presigned_url_max_ttl: Option<std::time::Duration>,
+    corpus_sessions_per_sec: u32,
 }
 
 impl StorageServiceImpl {
-    pub fn new(storage: Arc<R2Storage>) -> Self {
+    pub fn new(placement: Arc<BucketPlacement>) -> Self {
         Self {
-            storage,
+            placement,
             version: env!("CARGO_PKG_VERSION").to_string(),
             chunk_size: DEFAULT_CHUNK_SIZE,
+            erasure_signer: None,
+            history_signer: None,
+            uploads: None,
+            checkpoint_policy: CheckpointPolicy::default(),
+            lifecycle_rules: None,
+            usage: None,
+            contention: None,
+            session_lists: None,
+            checkpoint_lists: None,
+            snapshots: SnapshotRegistry::default(),
+            presigned_url_max_ttl: None,
+            corpus_sessions_per_sec: DEFAULT_CORPUS_SESSIONS_PER_SEC,
+        }
+    }
+
+    /// Enable EraseTenant, signing tokens and reports with `signer`.
+    pub fn with_erasure_signer(mut self, signer: ErasureSigner) -> Self {
+        self.erasure_signer = Some(signer);
+        self
+    }
+
+    /// Enable GetHistoryProof, signing proofs with `signer`.
+    pub fn with_history_signer(mut self, signer: HistorySigner) -> Self {
+        self.history_signer = Some(signer);
+        self
+    }
+
+    /// Enable resumable SaveSession uploads, spooled to `spool`.
+    pub fn with_upload_spool(mut self, spool: UploadSpool) -> Self {
+        self.uploads = Some(spool);
+        self
+    }
+
+    /// Read at most `sessions_per_sec` sessions per second in ExportTenantCorpus
+    /// (0 = unpaced).
+    pub fn with_corpus_export_rate(mut self, sessions_per_sec: u32) -> Self {
+        self.corpus_sessions_per_sec = sessions_per_sec;
+        self
+    }
+
+    /// Tell clients when to checkpoint with `policy` instead of after every entry.
+    pub fn with_checkpoint_policy(mut self, policy: CheckpointPolicy) -> Self {
+        self.checkpoint_policy = policy;
+        self
+    }
+
+    /// Enable ApplyLifecycleRules with the rules declared in `path`, read on
+    /// each call so edits apply without a restart.
+    pub fn with_lifecycle_rules(mut self, path: PathBuf) -> Self {
+        self.lifecycle_rules = Some(path);
+        self
+    }
+
+    /// Enable GetCostEstimate, reporting the requests counted by `meter`
+    /// priced at `rates`.
+    pub fn with_usage_meter(mut self, meter: Arc<UsageMeter>, rates: R2Rates) -> Self {
+        self.usage = Some((meter, rates));
+        self
+    }
+
+    /// Enable GetContentionReport, reporting the conflicts counted by
+    /// `tracker` and the rate above which whole indexes are sharded.
+    pub fn with_contention_tracker(
+        mut self,
+        tracker: Arc<ContentionTracker>,
+        index_shard_conflict_rate: f64,
+    ) -> Self {
+        self.contention = Some((tracker, index_shard_conflict_rate));
+        self
+    }
+
+    /// Serve ListSessions and ListCheckpoints requests accepting stale reads
+    /// from listings up to `max_age` old.
+    pub fn with_stale_reads(mut self, max_age: std::time::Duration) -> Self {
+        self.session_lists = Some(ListSnapshots::new(max_age));
+        self.checkpoint_lists = Some(ListSnapshots::new(max_age));
+        self
+    }
+
+    /// Enable CreateDownloadUrl and CreateUploadUrl, issuing URLs valid for
+    /// up to `max_ttl`.
+    pub fn with_presigned_urls(mut self, max_ttl: std::time::Duration) -> Self {
+        self.presigned_url_max_ttl = Some(max_ttl);
+        self
+    }
+
+    /// Lifetime of a presigned URL asked to last `expires_in_secs` (0 = the
+    /// longest allowed).
+    fn presigned_url_ttl(&self, expires_in_secs: u64) -> Result<std::time::Duration, Status> {
+        let max_ttl = self.presigned_url_max_ttl.ok_or_else(|| {
+            Status::failed_precondition("presigned URLs are disabled (--presigned-url-max-ttl-secs)")
+        })?;
+        Ok(match expires_in_secs {
+            0 => max_ttl,
+            secs => std::time::Duration::from_secs(secs).min(max_ttl),
+        })
+    }
+
+    /// The bucket holding a tenant's data.
+    fn storage(&self, tenant_id: &str) -> Result<Arc<R2Storage>, Status> {
+        self.placement.bucket_for(tenant_id).map_storage_err()
+    }
+
+    /// Purge the ephemeral sessions of every tenant that expired at `now`, in
+    /// every region. Tenants that fail are logged and retried on the next
+    /// purge. Returns the number of sessions purged.
+    pub async fn purge_expired(&self, now: chrono::DateTime<chrono::Utc>) -> Result<usize, Status> {
+        let mut purged = 0;
+        for (tenant_id, bucket) in self.served_tenants().await? {
+            match Self::purge_tenant_expired(&bucket, &tenant_id, now).await {
+                Ok(count) => purged += count,
+                Err(e) => warn!(tenant_id = %tenant_id, "Failed to purge expired sessions: {}", e),
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Delete a tenant's expired sessions: objects first, then the index
+    /// entries of those still expired. There is no index lock on R2, so a
+    /// hold placed while the objects are deleted only keeps the index entry.
+    async fn purge_tenant_expired(
+        bucket: &R2Storage,
+        tenant_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, docx_storage_core::StorageError> {
+        let Some(index) = bucket.load_index(tenant_id).await? else {
+            return Ok(0);
+        };
+        let expired = index.expired(now);
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        for session_id in &expired {
+            bucket.delete_session(tenant_id, session_id).await?;
+            info!(tenant_id = %tenant_id, session_id = %session_id, "Purged expired session");
+        }
+        bucket
+            .cas_index(tenant_id, |index| {
+                for session_id in index.expired(now) {
+                    if expired.contains(&session_id) {
+                        index.remove(&session_id);
+                    }
+                }
+            })
+            .await?;
+        record_admin_action(
+            bucket,
+            tenant_id,
+            AdminLogEntry::new(AdminAction::RetentionRun, SYSTEM_ACTOR, now)
+                .with_detail(format!("purged expired sessions: {}", expired.join(", "))),
+        )
+        .await;
+        Ok(expired.len())
+    }
+
+    /// Rebuild the index of every tenant from its stored sessions, in every
+    /// region, without removing orphans. Tenants that fail are logged and
+    /// retried on the next run. Returns the number of tenants whose index was
+    /// repaired.
+    pub async fn rebuild_indexes(&self) -> Result<usize, Status> {
+        let mut repaired = 0;
+        for (tenant_id, bucket) in self.served_tenants().await? {
+            match Self::rebuild_tenant_index(&bucket, &tenant_id, false, false).await {
+                Ok(report) if !report.is_clean() => {
+                    repaired += 1;
+                    if report.changed_index() {
+                        record_admin_action(
+                            bucket.as_ref(),
+                            &tenant_id,
+                            AdminLogEntry::new(AdminAction::RebuildIndex, SYSTEM_ACTOR, chrono::Utc::now())
+                                .with_detail(Self::rebuild_detail(&report)),
+                        )
+                        .await;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!(tenant_id = %tenant_id, "Failed to rebuild index: {}", e),
+            }
+        }
+        Ok(repaired)
+    }
+
+    /// Delete every tenant's media blobs no longer referenced by its
+    /// sessions or checkpoints. Returns the number of blobs deleted.
+    pub async fn sweep_media(&self) -> Result<u64, Status> {
+        let mut deleted = 0;
+        for (tenant_id, bucket) in self.served_tenants().await? {
+            match bucket.sweep_media(&tenant_id).await {
+                Ok(n) => deleted += n,
+                Err(e) => warn!(tenant_id = %tenant_id, "Failed to sweep media: {}", e),
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Every tenant with stored data, with the bucket serving it. Data a
+    /// tenant left in another region before being pinned, or in the shared
+    /// buckets before registering its own, isn't served. Tenant buckets that
+    /// can't be listed are logged and skipped.
+    async fn served_tenants(&self) -> Result<Vec<(String, Arc<R2Storage>)>, Status> {
+        let mut served = Vec::new();
+        for (region, bucket) in self.placement.buckets() {
+            for tenant_id in bucket.list_tenants().await.map_storage_err()? {
+                if self.placement.serves(region, &tenant_id) {
+                    served.push((tenant_id, bucket.clone()));
+                }
+            }
+        }
+        for (owner, bucket) in self.placement.tenant_buckets() {
+            match bucket.list_tenants().await {
+                // The bucket may hold more than the tenant's data (and its sandbox's)
+                Ok(tenant_ids) => served.extend(
+                    tenant_ids
+                        .into_iter()
+                        .filter(|tenant_id| owning_tenant(tenant_id) == owner)
+                        .map(|tenant_id| (tenant_id, bucket.clone())),
+                ),
+                Err(e) => warn!(tenant_id = %owner, "Failed to list tenant bucket: {}", e),
+            }
+        }
+        Ok(served)
+    }
+
+    /// Reconcile a tenant's index with its stored sessions, saving it unless
+    /// `dry_run`. The index is only written when it is out of sync, and is
+    /// reconciled again on each CAS attempt so concurrent writes are kept.
+    async fn rebuild_tenant_index(
+        bucket: &R2Storage,
+        tenant_id: &str,
+        dry_run: bool,
+        remove_orphans: bool,
+    ) -> Result<IndexRebuildReport, docx_storage_core::StorageError> {
+        let scanned_at = chrono::Utc::now();
+        let scanned = scan_sessions(bucket, tenant_id).await?;
+
+        let mut index = bucket.load_index(tenant_id).await?.unwrap_or_default();
+        let mut report = index.reconcile(&scanned, scanned_at, remove_orphans);
+        if !dry_run && report.changed_index() {
+            bucket
+                .cas_index(tenant_id, |index| {
+                    report = index.reconcile(&scanned, scanned_at, remove_orphans);
+                })
+                .await?;
+        }
+
+        if !report.is_clean() {
+            info!(
+                tenant_id = %tenant_id,
+                dry_run,
+                added = ?report.added,
+                corrected = ?report.corrected,
+                orphans = ?report.orphans,
+                removed = ?report.removed,
+                "Index out of sync with stored sessions"
+            );
         }
+        Ok(report)
+    }
+
+    /// The upload spool, if resumable uploads are enabled.
+    fn uploads(&self) -> Result<&UploadSpool, Status> {
+        self.uploads
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("resumable uploads are not enabled"))
+    }
+
+    /// Spool the chunks of a resumable SaveSession upload, starting with
+    /// `first`, and store the upload once its last chunk is acknowledged.
+    /// Returns the chunk to resume from if the stream ends before that.
+    async fn save_session_resumable(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        first: SaveSessionChunk,
+        mut stream: Streaming<SaveSessionChunk>,
+    ) -> Result<SaveSessionResponse, Status> {
+        let uploads = self.uploads()?;
+        let upload_id = first.upload_id.clone();
+        let mut next = Some(Ok(first));
+        let mut progress = UploadProgress::default();
+
+        while let Some(chunk) = next {
+            let chunk = chunk?;
+            if chunk.upload_id != upload_id {
+                return Err(Status::invalid_argument(
+                    "every chunk of a resumable upload must carry its upload_id",
+                ));
+            }
+            let (sequence, crc32) = (chunk.sequence, chunk.crc32);
+            progress = uploads
+                .append(tenant_id, session_id, &upload_id, sequence, &chunk.data, crc32)
+                .await
+                .map_storage_err()?;
+
+            if chunk.is_last {
+                let chunks = uploads
+                    .read(tenant_id, session_id, &upload_id)
+                    .await
+                    .map_storage_err()?;
+                let written = self.storage(tenant_id)?
+                    .save_session_stream(tenant_id, session_id, chunks)
+                    .await
+                    .map_storage_err()?;
+                uploads.discard(tenant_id, session_id, &upload_id).await;
+                debug!(
+                    "Saved session {} for tenant {} from upload {} ({} bytes)",
+                    session_id, tenant_id, upload_id, written
+                );
+                return Ok(SaveSessionResponse {
+                    success: true,
+                    next_sequence: progress.next_sequence,
+                });
+            }
+            next = stream.next().await;
+        }
+
+        Ok(SaveSessionResponse {
+            success: false,
+            next_sequence: progress.next_sequence,
+        })
+    }
+
+    /// The snapshot a LoadSessionWithHistory request reads, checking the
+    /// request doesn't also ask for another session or position.
+    fn requested_snapshot(
+        &self,
+        tenant_id: &str,
+        req: &LoadSessionWithHistoryRequest,
+    ) -> Result<PinnedSnapshot, Status> {
+        if req.position.is_some() {
+            return Err(Status::invalid_argument(
+                "position can't be set when reading a snapshot",
+            ));
+        }
+        let snapshot = self
+            .snapshots
+            .get(tenant_id, &req.snapshot_id)
+            .map_storage_err()?;
+        if !req.session_id.is_empty() && req.session_id != snapshot.session_id {
+            return Err(Status::invalid_argument(format!(
+                "snapshot {} is of session {}, not {}",
+                req.snapshot_id, snapshot.session_id, req.session_id
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Split a session and its history into stream chunks: metadata with the
+    /// first bytes of the document, the rest of it, then the WAL entries.
+    fn history_chunks(
+        history: docx_storage_core::SessionWithHistory,
+        chunk_size: usize,
+    ) -> Vec<SessionHistoryChunk> {
+        let entries: Vec<WalEntry> = history
+            .entries
+            .into_iter()
+            .map(|e| WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp_unix: e.timestamp.timestamp(),
+            })
+            .collect();
+        let mut chunks: Vec<SessionHistoryChunk> = history
+            .document
+            .chunks(chunk_size)
+            .map(|data| SessionHistoryChunk {
+                data: data.to_vec(),
+                ..Default::default()
+            })
+            .chain(entries.chunks(HISTORY_ENTRIES_PER_CHUNK).map(|entries| {
+                SessionHistoryChunk {
+                    entries: entries.to_vec(),
+                    ..Default::default()
+                }
+            }))
+            .collect();
+        if chunks.is_empty() {
+            chunks.push(SessionHistoryChunk::default());
+        }
+
+        let first = &mut chunks[0];
+        first.found = true;
+        first.base_position = history.base_position;
+        first.target_position = history.target_position;
+        first.wal_count = history.wal_count;
+        first.source_path = history.source_path.unwrap_or_default();
+        first.total_size = history.document.len() as u64;
+        if let Some(last) = chunks.last_mut() {
+            last.is_last = true;
+        }
+        chunks
+    }
+
+    /// Append entries to one session's WAL, with whether the checkpoint
+    /// policy wants a checkpoint at the new position.
+    async fn append_session_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        entries: Vec<WalEntry>,
+    ) -> Result<AppendWalResponse, Status> {
+        let entries: Vec<crate::storage::WalEntry> = entries
+            .into_iter()
+            .map(|e| crate::storage::WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp: chrono::DateTime::from_timestamp(e.timestamp_unix, 0)
+                    .unwrap_or_else(chrono::Utc::now),
+            })
+            .collect();
+
+        let new_position = self
+            .storage(tenant_id)?
+            .append_wal(tenant_id, session_id, &entries)
+            .await
+            .map_storage_err()?;
+
+        let checkpoint = self
+            .checkpoint_policy
+            .check(
+                self.storage(tenant_id)?.as_ref(),
+                tenant_id,
+                session_id,
+                &entries,
+                new_position,
+                chrono::Utc::now(),
+            )
+            .await
+            .map_storage_err()?;
+        if let Some(reason) = &checkpoint {
+            debug!(
+                "Checkpoint due at {} for session {}: {}",
+                new_position, session_id, reason
+            );
+        }
+
+        Ok(AppendWalResponse {
+            success: true,
+            new_position,
+            skip_checkpoint: checkpoint.is_none(),
+            checkpoint_reason: checkpoint.map(|r| r.to_string()).unwrap_or_default(),
+        })
     }
 
     /// Extract tenant_id from request context.
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
+    }
+
+    /// Who makes the request, for the admin log (empty = unknown).
+    fn get_actor(context: Option<&TenantContext>) -> &str {
+        context.map_or("", |c| c.actor.as_str())
+    }
+
+    /// An admin log entry for `action`, taken now by the request's actor.
+    fn admin_entry(action: AdminAction, context: Option<&TenantContext>) -> AdminLogEntry {
+        AdminLogEntry::new(action, Self::get_actor(context), chrono::Utc::now())
+    }
+
+    /// The page a ListSessions request asks for, if it asks for one.
+    fn session_page_query(req: &ListSessionsRequest) -> Option<SessionPageQuery> {
+        let given = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let query = SessionPageQuery {
+            id_prefix: given(&req.session_id_prefix),
+            search: given(&req.search),
+            page_token: given(&req.page_token),
+            page_size: req.page_size,
+        };
+        let paged = query.page_size > 0
+            || query.page_token.is_some()
+            || query.id_prefix.is_some()
+            || query.search.is_some();
+        paged.then_some(query)
+    }
+
+    /// A session of a paged listing, from its index entry alone.
+    fn paged_session_info(entry: docx_storage_core::SessionIndexEntry) -> SessionInfo {
+        SessionInfo {
+            session_id: entry.id,
+            source_path: entry.source_path.unwrap_or_default(),
+            created_at_unix: entry.created_at.timestamp(),
+            modified_at_unix: entry.last_modified_at.timestamp(),
+            size_bytes: 0,
+            expires_at_unix: entry.expires_at.map_or(0, |at| at.timestamp()),
+        }
+    }
+
+    /// Convert a library kind from the wire.
+    fn library_kind(kind: i32) -> Result<docx_storage_core::LibraryKind, Status> {
+        match LibraryKind::try_from(kind) {
+            Ok(LibraryKind::Template) => Ok(docx_storage_core::LibraryKind::Template),
+            Ok(LibraryKind::Snippet) => Ok(docx_storage_core::LibraryKind::Snippet),
+            Ok(LibraryKind::Glossary) => Ok(docx_storage_core::LibraryKind::Glossary),
+            Ok(LibraryKind::Profile) => Ok(docx_storage_core::LibraryKind::Profile),
+            Err(_) => Err(Status::invalid_argument(format!("unknown library kind {}", kind))),
+        }
+    }
+
+    /// Convert a tenant inventory to the wire.
+    fn inventory_to_proto(inventory: &docx_storage_core::TenantInventory) -> TenantInventory {
+        TenantInventory {
+            sessions: inventory.sessions,
+            wal_entries: inventory.wal_entries,
+            checkpoints: inventory.checkpoints,
+            templates: inventory.templates,
+            snippets: inventory.snippets,
+            glossaries: inventory.glossaries,
+            profiles: inventory.profiles,
+            has_index: inventory.has_index,
+            elsewhere: inventory.elsewhere.clone(),
+        }
+    }
+
+    /// Convert an admin log entry to the wire.
+    fn admin_log_entry_to_proto(entry: AdminLogEntry) -> proto::AdminLogEntry {
+        proto::AdminLogEntry {
+            position: entry.position,
+            action: entry.action.as_str().to_string(),
+            actor: entry.actor,
+            timestamp_unix: entry.timestamp.timestamp(),
+            session_id: entry.session_id.unwrap_or_default(),
+            detail: entry.detail,
+        }
+    }
+
+    /// What an index rebuild changed, for the admin log.
+    fn rebuild_detail(report: &IndexRebuildReport) -> String {
+        format!(
+            "added {}, corrected {}, removed {}",
+            report.added.len(),
+            report.corrected.len(),
+            report.removed.len()
+        )
     }
 }
 
 type StreamResult<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
 
+/// The data of an upload, from its first chunk (already read) up to the one
+/// marked last, streamed into storage as it arrives. `split` takes a chunk's
+/// data and `is_last` flag.
+fn upload_stream<T: Send + 'static>(
+    first: Vec<u8>,
+    first_is_last: bool,
+    rest: Streaming<T>,
+    split: fn(T) -> (Vec<u8>, bool),
+) -> ChunkStream<'static> {
+    Box::pin(futures::stream::unfold(
+        (Some(first), first_is_last, rest),
+        move |(first, done, mut rest)| async move {
+            if let Some(data) = first {
+                return Some((Ok(data), (None, done, rest)));
+            }
+            if done {
+                return None;
+            }
+            match rest.next().await? {
+                Ok(chunk) => {
+                    let (data, is_last) = split(chunk);
+                    Some((Ok(data), (None, is_last, rest)))
+                }
+                Err(status) => Some((
+                    Err(StorageError::Io(format!("upload failed: {}", status.message()))),
+                    (None, true, rest),
+                )),
+            }
+        },
+    ))
+}
+
 #[tonic::async_trait]
 impl StorageService for StorageServiceImpl {
     type LoadSessionStream = StreamResult<DataChunk>;
+    type LoadSessionWithHistoryStream = StreamResult<SessionHistoryChunk>;
     type LoadCheckpointStream = StreamResult<LoadCheckpointChunk>;
+    type LoadLibraryItemStream = StreamResult<DataChunk>;
+    type ExportTenantCorpusStream = StreamResult<CorpusChunk>;
 
     // =========================================================================
     // Session Operations (Streaming)
@@ -65,7 +666,7 @@ impl StorageService for StorageServiceImpl {
         let session_id = req.session_id.clone();
 
         let result = self
-            .storage
+            .storage(&tenant_id)?
             .load_session(&tenant_id, &session_id)
             .await
             .map_storage_err()?;
@@ -113,50 +714,146 @@ impl StorageService for StorageServiceImpl {
     }
 
     #[instrument(skip(self, request), level = "debug")]
-    async fn save_session(
+    async fn load_session_with_history(
         &self,
-        request: Request<Streaming<SaveSessionChunk>>,
-    ) -> Result<Response<SaveSessionResponse>, Status> {
-        let mut stream = request.into_inner();
+        request: Request<LoadSessionWithHistoryRequest>,
+    ) -> Result<Response<Self::LoadSessionWithHistoryStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-        let mut tenant_id: Option<String> = None;
-        let mut session_id: Option<String> = None;
-        let mut data = Vec::new();
+        let history = if req.snapshot_id.is_empty() {
+            let storage = self.storage(tenant_id)?;
+            load_session_with_history(storage.as_ref(), tenant_id, &req.session_id, req.position)
+                .await
+                .map_storage_err()?
+        } else {
+            let snapshot = self.requested_snapshot(tenant_id, &req)?;
+            Some(snapshot.history.as_ref().clone())
+        };
+        let chunks = match history {
+            Some(history) => Self::history_chunks(history, self.chunk_size),
+            None => vec![SessionHistoryChunk {
+                is_last: true,
+                ..Default::default()
+            }],
+        };
+        Ok(Response::new(Box::pin(tokio_stream::iter(
+            chunks.into_iter().map(Ok::<_, Status>),
+        ))))
+    }
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
+    #[instrument(skip(self, request), level = "debug")]
+    async fn open_snapshot(
+        &self,
+        request: Request<OpenSnapshotRequest>,
+    ) -> Result<Response<OpenSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-            if tenant_id.is_none() {
-                tenant_id = chunk.context.map(|c| c.tenant_id);
-                session_id = Some(chunk.session_id);
-            }
+        let storage = self.storage(tenant_id)?;
+        let snapshot = self
+            .snapshots
+            .open(storage.as_ref(), tenant_id, &req.session_id, req.position)
+            .await
+            .map_storage_err()?;
+        let Some(snapshot) = snapshot else {
+            return Ok(Response::new(OpenSnapshotResponse::default()));
+        };
+        debug!(
+            "Opened snapshot {} of session {} at position {} for tenant {}",
+            snapshot.id, req.session_id, snapshot.history.target_position, tenant_id
+        );
 
-            data.extend(chunk.data);
+        Ok(Response::new(OpenSnapshotResponse {
+            found: true,
+            snapshot_id: snapshot.id,
+            position: snapshot.history.target_position,
+            expires_at_unix: snapshot.expires_at.timestamp(),
+        }))
+    }
 
-            if chunk.is_last {
-                break;
-            }
-        }
+    #[instrument(skip(self, request), level = "debug")]
+    async fn close_snapshot(
+        &self,
+        request: Request<CloseSnapshotRequest>,
+    ) -> Result<Response<CloseSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-        let tenant_id = tenant_id
+        let existed = self.snapshots.close(tenant_id, &req.snapshot_id);
+        Ok(Response::new(CloseSnapshotResponse { existed }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn save_session(
+        &self,
+        request: Request<Streaming<SaveSessionChunk>>,
+    ) -> Result<Response<SaveSessionResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        // Metadata comes with the first chunk; the data is written as it arrives
+        let first = stream
+            .next()
+            .await
+            .transpose()?
+            .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+
+        let tenant_id = first
+            .context
+            .as_ref()
+            .map(|c| c.tenant_id.clone())
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
-        let session_id = session_id
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let session_id = Some(first.session_id.clone())
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
 
-        debug!(
-            "Saving session {} for tenant {} ({} bytes)",
-            session_id,
-            tenant_id,
-            data.len()
-        );
+        if !first.upload_id.is_empty() {
+            let response = self
+                .save_session_resumable(&tenant_id, &session_id, first, stream)
+                .await?;
+            return Ok(Response::new(response));
+        }
 
-        self.storage
-            .save_session(&tenant_id, &session_id, &data)
+        let (data, is_last) = (first.data, first.is_last);
+        let chunks = upload_stream(data, is_last, stream, |c| (c.data, c.is_last));
+        let written = self
+            .storage(&tenant_id)?
+            .save_session_stream(&tenant_id, &session_id, chunks)
             .await
             .map_storage_err()?;
+        debug!("Saved session {} for tenant {} ({} bytes)", session_id, tenant_id, written);
+
+        Ok(Response::new(SaveSessionResponse {
+            success: true,
+            next_sequence: 0,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn resume_save_session(
+        &self,
+        request: Request<ResumeSaveSessionRequest>,
+    ) -> Result<Response<ResumeSaveSessionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let progress = self
+            .uploads()?
+            .progress(tenant_id, &req.session_id, &req.upload_id)
+            .await;
+        debug!(
+            "Upload {} of session {} for tenant {}: {:?}",
+            req.upload_id, req.session_id, tenant_id, progress
+        );
 
-        Ok(Response::new(SaveSessionResponse { success: true }))
+        let found = progress.is_some();
+        let progress = progress.unwrap_or_default();
+        Ok(Response::new(ResumeSaveSessionResponse {
+            found,
+            next_sequence: progress.next_sequence,
+            received_bytes: progress.received_bytes,
+        }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -167,15 +864,50 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        if let Some(query) = Self::session_page_query(&req) {
+            let page = self.storage(tenant_id)?
+                .list_session_page(tenant_id, &query)
+                .await
+                .map_storage_err()?;
+            return Ok(Response::new(ListSessionsResponse {
+                sessions: page.entries.into_iter().map(Self::paged_session_info).collect(),
+                snapshot_age_ms: 0,
+                next_page_token: page.next_page_token.unwrap_or_default(),
+            }));
+        }
+
+        let snapshots = self.session_lists.as_ref();
+        if let Some((sessions, age)) = snapshots
+            .filter(|_| req.stale_ok)
+            .and_then(|s| s.get(&tenant_id.to_string()))
+        {
+            return Ok(Response::new(ListSessionsResponse {
+                sessions,
+                snapshot_age_ms: age.as_millis() as i64,
+                next_page_token: String::new(),
+            }));
+        }
+
         let sessions = self
-            .storage
+            .storage(tenant_id)?
             .list_sessions(tenant_id)
             .await
             .map_storage_err()?;
 
-        let sessions = sessions
+        // Ephemeral sessions are marked with their expiry from the index
+        let index = self.storage(tenant_id)?
+            .load_index(tenant_id)
+            .await
+            .map_storage_err()?
+            .unwrap_or_default();
+
+        let sessions: Vec<SessionInfo> = sessions
             .into_iter()
             .map(|s| SessionInfo {
+                expires_at_unix: index
+                    .get(&s.session_id)
+                    .and_then(|e| e.expires_at)
+                    .map_or(0, |at| at.timestamp()),
                 session_id: s.session_id,
                 source_path: s.source_path.unwrap_or_default(),
                 created_at_unix: s.created_at.timestamp(),
@@ -184,7 +916,14 @@ impl StorageService for StorageServiceImpl {
             })
             .collect();
 
-        Ok(Response::new(ListSessionsResponse { sessions }))
+        if let Some(snapshots) = snapshots {
+            snapshots.put(tenant_id.to_string(), sessions.clone());
+        }
+        Ok(Response::new(ListSessionsResponse {
+            sessions,
+            snapshot_age_ms: 0,
+            next_page_token: String::new(),
+        }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -195,11 +934,24 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        ensure_not_held(self.storage(tenant_id)?.as_ref(), tenant_id, Some(&req.session_id))
+            .await
+            .map_storage_err()?;
+
         let existed = self
-            .storage
+            .storage(tenant_id)?
             .delete_session(tenant_id, &req.session_id)
             .await
             .map_storage_err()?;
+        if existed {
+            record_admin_action(
+                self.storage(tenant_id)?.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::DeleteSession, req.context.as_ref())
+                    .with_session(&req.session_id),
+            )
+            .await;
+        }
 
         Ok(Response::new(DeleteSessionResponse {
             success: true,
@@ -216,14 +968,14 @@ impl StorageService for StorageServiceImpl {
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
         let exists = self
-            .storage
+            .storage(tenant_id)?
             .session_exists(tenant_id, &req.session_id)
             .await
             .map_storage_err()?;
 
         // Read pending_external_change from the index
         let pending_external_change = if exists {
-            self.storage
+            self.storage(tenant_id)?
                 .load_index(tenant_id)
                 .await
                 .map_storage_err()?
@@ -236,6 +988,22 @@ impl StorageService for StorageServiceImpl {
         Ok(Response::new(SessionExistsResponse { exists, pending_external_change }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_session_health(
+        &self,
+        request: Request<GetSessionHealthRequest>,
+    ) -> Result<Response<GetSessionHealthResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        // Sync runs in its own server here, so only the storage side is reported
+        let storage = self.storage(tenant_id)?;
+        let health = session_health(storage.as_ref(), None, tenant_id, &req.session_id, chrono::Utc::now())
+            .await
+            .map_storage_err()?;
+        Ok(Response::new(health_response(health)))
+    }
+
     // =========================================================================
     // Index Operations (Atomic - ETag-based CAS, no external lock)
     // =========================================================================
@@ -249,7 +1017,7 @@ impl StorageService for StorageServiceImpl {
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
         let result = self
-            .storage
+            .storage(tenant_id)?
             .load_index(tenant_id)
             .await
             .map_storage_err()?;
@@ -282,7 +1050,7 @@ impl StorageService for StorageServiceImpl {
         let sid = session_id.clone();
         let mut already_exists = false;
 
-        self.storage
+        self.storage(&tenant_id)?
             .cas_index(&tenant_id, |index| {
                 if index.contains(&sid) {
                     already_exists = true;
@@ -308,6 +1076,11 @@ impl StorageService for StorageServiceImpl {
                         cursor_position: entry.wal_position,
                         checkpoint_positions: entry.checkpoint_positions.clone(),
                         pending_external_change: entry.pending_external_change,
+                        display_name: None,
+                        alias: None,
+                        legal_hold: None,
+                        expires_at: chrono::DateTime::from_timestamp(entry.expires_at_unix, 0)
+                            .filter(|_| entry.expires_at_unix > 0),
                     });
                 }
             })
@@ -341,7 +1114,7 @@ impl StorageService for StorageServiceImpl {
         let add_checkpoint_positions = req.add_checkpoint_positions.clone();
         let remove_checkpoint_positions = req.remove_checkpoint_positions.clone();
 
-        self.storage
+        self.storage(&tenant_id)?
             .cas_index(&tenant_id, |index| {
                 if !index.contains(&sid) {
                     not_found = true;
@@ -402,55 +1175,143 @@ impl StorageService for StorageServiceImpl {
 
         let sid = session_id.clone();
         let mut existed = false;
+        // A held session is left in place and the hold reported after the CAS
+        let mut held = Ok(());
 
-        self.storage
+        self.storage(&tenant_id)?
             .cas_index(&tenant_id, |index| {
-                existed = index.remove(&sid).is_some();
+                held = index.ensure_not_held(Some(&sid));
+                existed = held.is_ok() && index.remove(&sid).is_some();
             })
             .await
             .map_storage_err()?;
 
+        held.map_storage_err()?;
         Ok(Response::new(RemoveSessionFromIndexResponse {
             success: true,
             existed,
         }))
     }
 
-    // =========================================================================
-    // WAL Operations
-    // =========================================================================
-
-    #[instrument(skip(self, request), level = "debug", fields(entries_count = request.get_ref().entries.len()))]
-    async fn append_wal(
+    #[instrument(skip(self, request), level = "debug")]
+    async fn rename_session(
         &self,
-        request: Request<AppendWalRequest>,
-    ) -> Result<Response<AppendWalResponse>, Status> {
+        request: Request<RenameSessionRequest>,
+    ) -> Result<Response<RenameSessionResponse>, Status> {
         let req = request.into_inner();
-        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?.to_string();
 
-        let entries: Vec<crate::storage::WalEntry> = req
-            .entries
-            .into_iter()
-            .map(|e| crate::storage::WalEntry {
-                position: e.position,
-                operation: e.operation,
-                path: e.path,
-                patch_json: e.patch_json,
-                timestamp: chrono::DateTime::from_timestamp(e.timestamp_unix, 0)
-                    .unwrap_or_else(chrono::Utc::now),
-            })
-            .collect();
+        // The mutator can't abort the write; on error it leaves the index
+        // unchanged and the outcome is reported after the CAS completes
+        let mut outcome = Ok(false);
 
-        let new_position = self
-            .storage
-            .append_wal(tenant_id, &req.session_id, &entries)
+        self.storage(&tenant_id)?
+            .cas_index(&tenant_id, |index| {
+                outcome = index.rename(
+                    &req.session_id,
+                    req.display_name.as_deref(),
+                    req.alias.as_deref(),
+                );
+            })
             .await
             .map_storage_err()?;
 
-        Ok(Response::new(AppendWalResponse {
-            success: true,
-            new_position,
+        let renamed = outcome.map_storage_err()?;
+        Ok(Response::new(RenameSessionResponse {
+            success: renamed,
+            not_found: !renamed,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn resolve_session(
+        &self,
+        request: Request<ResolveSessionRequest>,
+    ) -> Result<Response<ResolveSessionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let index = self
+            .storage(tenant_id)?
+            .load_index(tenant_id)
+            .await
+            .map_storage_err()?
+            .unwrap_or_default();
+
+        let response = match index.resolve(&req.name) {
+            Some(entry) => ResolveSessionResponse {
+                found: true,
+                session_id: entry.id.clone(),
+                display_name: entry.display_name.clone().unwrap_or_default(),
+                alias: entry.alias.clone().unwrap_or_default(),
+            },
+            None => ResolveSessionResponse::default(),
+        };
+        Ok(Response::new(response))
+    }
+
+    // =========================================================================
+    // WAL Operations
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug", fields(entries_count = request.get_ref().entries.len()))]
+    async fn append_wal(
+        &self,
+        request: Request<AppendWalRequest>,
+    ) -> Result<Response<AppendWalResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let response = self
+            .append_session_wal(tenant_id, &req.session_id, req.entries)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), level = "debug", fields(sessions = request.get_ref().sessions.len()))]
+    async fn append_wal_batch(
+        &self,
+        request: Request<AppendWalBatchRequest>,
+    ) -> Result<Response<AppendWalBatchResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        if req.sessions.len() > MAX_APPEND_BATCH_SESSIONS {
+            return Err(Status::invalid_argument(format!(
+                "at most {} sessions per batch",
+                MAX_APPEND_BATCH_SESSIONS
+            )));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = req.sessions.iter().find(|s| !seen.insert(s.session_id.as_str())) {
+            return Err(Status::invalid_argument(format!(
+                "session {} appears more than once in the batch",
+                dup.session_id
+            )));
+        }
+
+        // Sessions don't share WAL objects: append to all of them at once
+        let results = futures::future::join_all(req.sessions.into_iter().map(|s| async move {
+            match self.append_session_wal(tenant_id, &s.session_id, s.entries).await {
+                Ok(appended) => SessionWalAppendResult {
+                    session_id: s.session_id,
+                    success: true,
+                    error: String::new(),
+                    new_position: appended.new_position,
+                    skip_checkpoint: appended.skip_checkpoint,
+                    checkpoint_reason: appended.checkpoint_reason,
+                },
+                Err(status) => {
+                    warn!("Batch append to session {} failed: {}", s.session_id, status.message());
+                    SessionWalAppendResult {
+                        session_id: s.session_id,
+                        error: status.message().to_string(),
+                        ..Default::default()
+                    }
+                }
+            }
         }))
+        .await;
+
+        Ok(Response::new(AppendWalBatchResponse { results }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -464,7 +1325,7 @@ impl StorageService for StorageServiceImpl {
         let limit = if req.limit > 0 { Some(req.limit) } else { None };
 
         let (entries, has_more) = self
-            .storage
+            .storage(tenant_id)?
             .read_wal(tenant_id, &req.session_id, req.from_position, limit)
             .await
             .map_storage_err()?;
@@ -483,6 +1344,37 @@ impl StorageService for StorageServiceImpl {
         Ok(Response::new(ReadWalResponse { entries, has_more }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn tail_wal(
+        &self,
+        request: Request<TailWalRequest>,
+    ) -> Result<Response<TailWalResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let before_position = (req.before_position > 0).then_some(req.before_position);
+        let limit = if req.limit > 0 { req.limit } else { DEFAULT_TAIL_LIMIT };
+
+        let (entries, has_more) = self
+            .storage(tenant_id)?
+            .tail_wal(tenant_id, &req.session_id, before_position, limit)
+            .await
+            .map_storage_err()?;
+
+        let entries = entries
+            .into_iter()
+            .map(|e| WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp_unix: e.timestamp.timestamp(),
+            })
+            .collect();
+
+        Ok(Response::new(TailWalResponse { entries, has_more }))
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn truncate_wal(
         &self,
@@ -491,11 +1383,28 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        ensure_not_held(self.storage(tenant_id)?.as_ref(), tenant_id, Some(&req.session_id))
+            .await
+            .map_storage_err()?;
+
         let entries_removed = self
-            .storage
+            .storage(tenant_id)?
             .truncate_wal(tenant_id, &req.session_id, req.keep_from_position)
             .await
             .map_storage_err()?;
+        if entries_removed > 0 {
+            record_admin_action(
+                self.storage(tenant_id)?.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::TruncateWal, req.context.as_ref())
+                    .with_session(&req.session_id)
+                    .with_detail(format!(
+                        "entries removed: {}, keep_from_position: {}",
+                        entries_removed, req.keep_from_position
+                    )),
+            )
+            .await;
+        }
 
         Ok(Response::new(TruncateWalResponse {
             success: true,
@@ -503,6 +1412,36 @@ impl StorageService for StorageServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_history_proof(
+        &self,
+        request: Request<GetHistoryProofRequest>,
+    ) -> Result<Response<GetHistoryProofResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let signer = self.history_signer.as_ref().ok_or_else(|| {
+            Status::failed_precondition("history proofs are disabled: no history key configured")
+        })?;
+
+        let (proof, proof_json, signature) = prove_history(
+            self.storage(tenant_id)?.as_ref(),
+            signer,
+            tenant_id,
+            &req.session_id,
+            chrono::Utc::now(),
+        )
+        .await
+        .map_storage_err()?;
+
+        Ok(Response::new(GetHistoryProofResponse {
+            consistent: proof.is_consistent(),
+            head_hash: proof.head_hash,
+            links: proof.links,
+            proof_json,
+            signature,
+        }))
+    }
+
     // =========================================================================
     // Checkpoint Operations (Streaming)
     // =========================================================================
@@ -514,45 +1453,32 @@ impl StorageService for StorageServiceImpl {
     ) -> Result<Response<SaveCheckpointResponse>, Status> {
         let mut stream = request.into_inner();
 
-        let mut tenant_id: Option<String> = None;
-        let mut session_id: Option<String> = None;
-        let mut position: u64 = 0;
-        let mut data = Vec::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-
-            if tenant_id.is_none() {
-                tenant_id = chunk.context.map(|c| c.tenant_id);
-                session_id = Some(chunk.session_id);
-                position = chunk.position;
-            }
-
-            data.extend(chunk.data);
-
-            if chunk.is_last {
-                break;
-            }
-        }
+        // Metadata comes with the first chunk; the data is written as it arrives
+        let first = stream.next().await.transpose()?;
+        let (tenant_id, session_id, position, data, is_last) = first
+            .map(|c| {
+                let tenant_id = c.context.map(|c| c.tenant_id);
+                (tenant_id, c.session_id, c.position, c.data, c.is_last)
+            })
+            .unwrap_or_default();
 
         let tenant_id = tenant_id
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
-        let session_id = session_id
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let session_id = Some(session_id)
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
 
-        debug!(
-            "Saving checkpoint at position {} for session {} tenant {} ({} bytes)",
-            position,
-            session_id,
-            tenant_id,
-            data.len()
-        );
-
-        self.storage
-            .save_checkpoint(&tenant_id, &session_id, position, &data)
+        let chunks = upload_stream(data, is_last, stream, |c| (c.data, c.is_last));
+        let written = self
+            .storage(&tenant_id)?
+            .save_checkpoint_stream(&tenant_id, &session_id, position, chunks)
             .await
             .map_storage_err()?;
+        debug!(
+            "Saved checkpoint at position {} for session {} tenant {} ({} bytes)",
+            position, session_id, tenant_id, written
+        );
 
         Ok(Response::new(SaveCheckpointResponse { success: true }))
     }
@@ -568,7 +1494,7 @@ impl StorageService for StorageServiceImpl {
         let position = req.position;
 
         let result = self
-            .storage
+            .storage(&tenant_id)?
             .load_checkpoint(&tenant_id, &session_id, position)
             .await
             .map_storage_err()?;
@@ -625,13 +1551,25 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        let snapshots = self.checkpoint_lists.as_ref();
+        let key = (tenant_id.to_string(), req.session_id.clone());
+        if let Some((checkpoints, age)) = snapshots
+            .filter(|_| req.stale_ok)
+            .and_then(|s| s.get(&key))
+        {
+            return Ok(Response::new(ListCheckpointsResponse {
+                checkpoints,
+                snapshot_age_ms: age.as_millis() as i64,
+            }));
+        }
+
         let checkpoints = self
-            .storage
+            .storage(tenant_id)?
             .list_checkpoints(tenant_id, &req.session_id)
             .await
             .map_storage_err()?;
 
-        let checkpoints = checkpoints
+        let checkpoints: Vec<CheckpointInfo> = checkpoints
             .into_iter()
             .map(|c| CheckpointInfo {
                 position: c.position,
@@ -640,23 +1578,1246 @@ impl StorageService for StorageServiceImpl {
             })
             .collect();
 
-        Ok(Response::new(ListCheckpointsResponse { checkpoints }))
+        if let Some(snapshots) = snapshots {
+            snapshots.put(key, checkpoints.clone());
+        }
+        Ok(Response::new(ListCheckpointsResponse {
+            checkpoints,
+            snapshot_age_ms: 0,
+        }))
     }
 
     // =========================================================================
-    // Health Check
+    // Library Operations
     // =========================================================================
 
-    #[instrument(skip(self), level = "debug")]
-    async fn health_check(
+    #[instrument(skip(self, request), level = "debug")]
+    async fn save_library_item(
         &self,
-        _request: Request<HealthCheckRequest>,
-    ) -> Result<Response<HealthCheckResponse>, Status> {
-        debug!("Health check requested");
-        Ok(Response::new(HealthCheckResponse {
-            healthy: true,
-            backend: self.storage.backend_name().to_string(),
-            version: self.version.clone(),
+        request: Request<Streaming<SaveLibraryItemChunk>>,
+    ) -> Result<Response<SaveLibraryItemResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        let mut tenant_id: Option<String> = None;
+        let mut kind = 0;
+        let mut name: Option<String> = None;
+        let mut data = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+
+            // Extract metadata from first chunk
+            if tenant_id.is_none() {
+                tenant_id = chunk.context.map(|c| c.tenant_id);
+                kind = chunk.kind;
+                name = Some(chunk.name);
+            }
+
+            data.extend(chunk.data);
+
+            if chunk.is_last {
+                break;
+            }
+        }
+
+        let tenant_id = tenant_id
+            .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let kind = Self::library_kind(kind)?;
+        let name = name
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| Status::invalid_argument("name is required in first chunk"))?;
+
+        debug!("Saving {:?} {} for tenant {} ({} bytes)", kind, name, tenant_id, data.len());
+
+        self.storage(&tenant_id)?
+            .save_library_item(&tenant_id, kind, &name, &data)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(SaveLibraryItemResponse { success: true }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn load_library_item(
+        &self,
+        request: Request<LoadLibraryItemRequest>,
+    ) -> Result<Response<Self::LoadLibraryItemStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        let result = self
+            .storage(tenant_id)?
+            .load_library_item(tenant_id, kind, &req.name)
+            .await
+            .map_storage_err()?;
+
+        // Items are small (a document or a few paragraphs); chunk them eagerly
+        let chunks: Vec<Result<DataChunk, Status>> = match result {
+            Some(data) if !data.is_empty() => {
+                let total_size = data.len() as u64;
+                let total_chunks = data.len().div_ceil(self.chunk_size);
+                data.chunks(self.chunk_size)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        Ok(DataChunk {
+                            data: chunk.to_vec(),
+                            is_last: i == total_chunks - 1,
+                            found: i == 0,
+                            total_size: if i == 0 { total_size } else { 0 },
+                        })
+                    })
+                    .collect()
+            }
+            found => vec![Ok(DataChunk {
+                data: vec![],
+                is_last: true,
+                found: found.is_some(),
+                total_size: 0,
+            })],
+        };
+
+        Ok(Response::new(Box::pin(tokio_stream::iter(chunks))))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_library_items(
+        &self,
+        request: Request<ListLibraryItemsRequest>,
+    ) -> Result<Response<ListLibraryItemsResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        let items = self
+            .storage(tenant_id)?
+            .list_library_items(tenant_id, kind)
+            .await
+            .map_storage_err()?
+            .into_iter()
+            .map(|item| LibraryItemInfo {
+                name: item.name,
+                modified_at_unix: item.modified_at.timestamp(),
+                size_bytes: item.size_bytes as i64,
+            })
+            .collect();
+
+        Ok(Response::new(ListLibraryItemsResponse { items }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn delete_library_item(
+        &self,
+        request: Request<DeleteLibraryItemRequest>,
+    ) -> Result<Response<DeleteLibraryItemResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        ensure_not_held(self.storage(tenant_id)?.as_ref(), tenant_id, None)
+            .await
+            .map_storage_err()?;
+
+        let existed = self
+            .storage(tenant_id)?
+            .delete_library_item(tenant_id, kind, &req.name)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(DeleteLibraryItemResponse {
+            success: true,
+            existed,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_session_from_template(
+        &self,
+        request: Request<CreateSessionFromTemplateRequest>,
+    ) -> Result<Response<CreateSessionFromTemplateResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?.to_string();
+        let session_id = req.session_id;
+        if session_id.is_empty() {
+            return Err(Status::invalid_argument("session_id is required"));
+        }
+
+        let Some(data) = self
+            .storage(&tenant_id)?
+            .load_library_item(
+                &tenant_id,
+                docx_storage_core::LibraryKind::Template,
+                &req.template_name,
+            )
+            .await
+            .map_storage_err()?
+        else {
+            return Ok(Response::new(CreateSessionFromTemplateResponse::default()));
+        };
+
+        let storage = self.storage(&tenant_id)?;
+        if storage
+            .session_exists(&tenant_id, &session_id)
+            .await
+            .map_storage_err()?
+        {
+            return Err(Status::already_exists(format!(
+                "Session {} already exists",
+                session_id
+            )));
+        }
+
+        // Claim the id in the index before writing the document, so two
+        // creations of the same session can't both store theirs
+        let now = chrono::Utc::now();
+        let mut claimed = false;
+        storage
+            .cas_index(&tenant_id, |index| {
+                claimed = !index.contains(&session_id);
+                if claimed {
+                    index.upsert(crate::storage::SessionIndexEntry {
+                        id: session_id.clone(),
+                        source_path: None,
+                        auto_sync: true,
+                        created_at: now,
+                        last_modified_at: now,
+                        docx_file: Some(format!("{}.docx", session_id)),
+                        wal_count: 0,
+                        cursor_position: 0,
+                        checkpoint_positions: vec![],
+                        pending_external_change: false,
+                        display_name: None,
+                        alias: None,
+                        legal_hold: None,
+                        expires_at: chrono::DateTime::from_timestamp(req.expires_at_unix, 0)
+                            .filter(|_| req.expires_at_unix > 0),
+                    });
+                }
+            })
+            .await
+            .map_storage_err()?;
+        if !claimed {
+            return Err(Status::already_exists(format!(
+                "Session {} already exists",
+                session_id
+            )));
+        }
+
+        if let Err(e) = storage.save_session(&tenant_id, &session_id, &data).await {
+            // Release the claim so the id can be created again
+            let released = storage
+                .cas_index(&tenant_id, |index| {
+                    if index.get(&session_id).is_some_and(|entry| entry.created_at == now) {
+                        index.remove(&session_id);
+                    }
+                })
+                .await;
+            if let Err(release_error) = released {
+                warn!(
+                    "Failed to release session {} of tenant {}: {}",
+                    session_id, tenant_id, release_error
+                );
+            }
+            return Err(e).map_storage_err();
+        }
+
+        debug!(
+            "Created session {} from template {} for tenant {}",
+            session_id, req.template_name, tenant_id
+        );
+        Ok(Response::new(CreateSessionFromTemplateResponse {
+            found: true,
+            session_id,
+            size_bytes: data.len() as i64,
+        }))
+    }
+
+    // =========================================================================
+    // Tenant Erasure
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn erase_tenant(
+        &self,
+        request: Request<EraseTenantRequest>,
+    ) -> Result<Response<EraseTenantResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let signer = self.erasure_signer.as_ref().ok_or_else(|| {
+            Status::failed_precondition("tenant erasure is disabled: no erasure key configured")
+        })?;
+
+        let step = erase_tenant(
+            self.storage(tenant_id)?.as_ref(),
+            signer,
+            tenant_id,
+            Self::get_actor(req.context.as_ref()),
+            WEBSITE_TENANT_DATA,
+            Some(req.confirmation_token.as_str()),
+            chrono::Utc::now(),
+        )
+        .await
+        .map_storage_err()?;
+
+        let response = match step {
+            ErasureStep::ConfirmationRequired {
+                token,
+                expires_at,
+                inventory,
+            } => EraseTenantResponse {
+                erased: false,
+                inventory: Some(Self::inventory_to_proto(&inventory)),
+                confirmation_token: token,
+                token_expires_at_unix: expires_at.timestamp(),
+                ..Default::default()
+            },
+            ErasureStep::Erased {
+                report,
+                report_json,
+                signature,
+            } => {
+                // Not in the tenant's admin log: writing it would recreate the
+                // tenant just erased. The signed report names the actor.
+                info!(
+                    "Erased tenant {} for {} ({} objects)",
+                    tenant_id, report.erased_by, report.objects_deleted
+                );
+                EraseTenantResponse {
+                    erased: true,
+                    inventory: Some(Self::inventory_to_proto(&report.erased)),
+                    report_json,
+                    signature,
+                    ..Default::default()
+                }
+            }
+        };
+        Ok(Response::new(response))
+    }
+
+    // =========================================================================
+    // Index Rebuild
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn rebuild_index(
+        &self,
+        request: Request<RebuildIndexRequest>,
+    ) -> Result<Response<RebuildIndexResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let storage = self.storage(tenant_id)?;
+        let report = Self::rebuild_tenant_index(
+            &storage,
+            tenant_id,
+            req.dry_run,
+            req.remove_orphans,
+        )
+        .await
+        .map_storage_err()?;
+        if !req.dry_run && report.changed_index() {
+            record_admin_action(
+                self.storage(tenant_id)?.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::RebuildIndex, req.context.as_ref())
+                    .with_detail(Self::rebuild_detail(&report)),
+            )
+            .await;
+        }
+        Ok(Response::new(RebuildIndexResponse {
+            applied: !req.dry_run && report.changed_index(),
+            added: report.added,
+            corrected: report.corrected,
+            orphans: report.orphans,
+            removed: report.removed,
         }))
     }
+
+    // =========================================================================
+    // Legal Hold
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn set_legal_hold(
+        &self,
+        request: Request<SetLegalHoldRequest>,
+    ) -> Result<Response<SetLegalHoldResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?.to_string();
+        let session_id = (!req.session_id.is_empty()).then_some(req.session_id.as_str());
+        if req.hold && req.reason.trim().is_empty() {
+            return Err(Status::invalid_argument("a reason is required to place a legal hold"));
+        }
+
+        let hold = req
+            .hold
+            .then(|| LegalHold::new(req.reason.trim(), chrono::Utc::now()));
+        let mut found = false;
+        let mut was_held = false;
+
+        self.storage(&tenant_id)?
+            .cas_index(&tenant_id, |index| {
+                was_held = match session_id {
+                    None => index.legal_hold.is_some(),
+                    Some(id) => index.get(id).is_some_and(|e| e.legal_hold.is_some()),
+                };
+                found = index.set_legal_hold(session_id, hold.clone());
+            })
+            .await
+            .map_storage_err()?;
+
+        if found {
+            info!(
+                "Legal hold {} on tenant {} session {}",
+                if req.hold { "placed" } else { "lifted" },
+                tenant_id,
+                session_id.unwrap_or("*")
+            );
+            let mut entry = Self::admin_entry(AdminAction::LegalHold, req.context.as_ref())
+                .with_detail(if req.hold {
+                    format!("placed: {}", req.reason.trim())
+                } else {
+                    "lifted".to_string()
+                });
+            if let Some(id) = session_id {
+                entry = entry.with_session(id);
+            }
+            record_admin_action(self.storage(&tenant_id)?.as_ref(), &tenant_id, entry).await;
+        }
+        Ok(Response::new(SetLegalHoldResponse {
+            success: found,
+            not_found: !found,
+            was_held,
+        }))
+    }
+
+    // =========================================================================
+    // Sandbox
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_sandbox(
+        &self,
+        request: Request<CreateSandboxRequest>,
+    ) -> Result<Response<CreateSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let now = chrono::Utc::now();
+        let expires_at =
+            (req.ttl_seconds > 0).then(|| now + chrono::Duration::seconds(req.ttl_seconds));
+
+        let (sandbox_tenant_id, session_ids) = create_sandbox(
+            self.storage(tenant_id)?.as_ref(),
+            tenant_id,
+            &req.session_ids,
+            req.replace,
+            expires_at,
+            now,
+        )
+        .await
+        .map_storage_err()?;
+
+        info!(
+            "Created sandbox {} with {} sessions",
+            sandbox_tenant_id,
+            session_ids.len()
+        );
+        Ok(Response::new(CreateSandboxResponse {
+            sandbox_tenant_id,
+            session_ids,
+        }))
+    }
+
+    /// There is no index lock on R2: sessions are checked for conflicts before
+    /// their data is copied, so an edit landing during the copy is overwritten.
+    #[instrument(skip(self, request), level = "debug")]
+    async fn promote_sandbox(
+        &self,
+        request: Request<PromoteSandboxRequest>,
+    ) -> Result<Response<PromoteSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let sandbox_id = sandbox_tenant_id(tenant_id).map_storage_err()?;
+        let storage = self.storage(tenant_id)?;
+
+        let sandbox = storage
+            .load_index(&sandbox_id)
+            .await
+            .map_storage_err()?
+            .ok_or_else(|| Status::not_found(format!("tenant '{}' has no sandbox", tenant_id)))?;
+        let origin = sandbox.sandbox_origin(tenant_id).map_storage_err()?;
+        let entries = sandbox.select_sessions(&req.session_ids).map_storage_err()?;
+
+        storage
+            .load_index(tenant_id)
+            .await
+            .map_storage_err()?
+            .unwrap_or_default()
+            .check_promotion(origin, &entries, req.force)
+            .map_storage_err()?;
+
+        for entry in &entries {
+            copy_session_data(storage.as_ref(), &sandbox_id, tenant_id, &entry.id)
+                .await
+                .map_storage_err()?;
+        }
+        let index = storage
+            .cas_index(tenant_id, |index| index.promote(&entries))
+            .await
+            .map_storage_err()?;
+
+        // Record the promoted versions, so promoting again isn't a conflict
+        let promoted: Vec<_> = entries
+            .iter()
+            .filter_map(|e| index.get(&e.id).cloned())
+            .collect();
+        storage
+            .cas_index(&sandbox_id, |sandbox| {
+                if let Some(origin) = sandbox.sandbox.as_mut() {
+                    origin.rebase(&promoted);
+                }
+            })
+            .await
+            .map_storage_err()?;
+
+        let session_ids: Vec<String> = entries.into_iter().map(|e| e.id).collect();
+        info!(
+            "Promoted {} sessions from sandbox {} to tenant {}",
+            session_ids.len(),
+            sandbox_id,
+            tenant_id
+        );
+        record_admin_action(
+            storage.as_ref(),
+            tenant_id,
+            Self::admin_entry(AdminAction::PromoteSandbox, req.context.as_ref())
+                .with_detail(format!("promoted sessions: {}", session_ids.join(", "))),
+        )
+        .await;
+
+        let discarded = req.discard;
+        if discarded {
+            let objects_deleted = discard_sandbox(storage.as_ref(), tenant_id)
+                .await
+                .map_storage_err()?;
+            record_admin_action(
+                storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::DiscardSandbox, req.context.as_ref())
+                    .with_detail(format!("deleted {} objects", objects_deleted)),
+            )
+            .await;
+        }
+        Ok(Response::new(PromoteSandboxResponse {
+            session_ids,
+            discarded,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn discard_sandbox(
+        &self,
+        request: Request<DiscardSandboxRequest>,
+    ) -> Result<Response<DiscardSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let sandbox_id = sandbox_tenant_id(tenant_id).map_storage_err()?;
+        let storage = self.storage(tenant_id)?;
+
+        let existed = storage
+            .load_index(&sandbox_id)
+            .await
+            .map_storage_err()?
+            .is_some();
+        let objects_deleted = discard_sandbox(storage.as_ref(), tenant_id)
+            .await
+            .map_storage_err()?;
+
+        if existed {
+            info!("Discarded sandbox {} ({} objects)", sandbox_id, objects_deleted);
+            record_admin_action(
+                storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::DiscardSandbox, req.context.as_ref())
+                    .with_detail(format!("deleted {} objects", objects_deleted)),
+            )
+            .await;
+        }
+        Ok(Response::new(DiscardSandboxResponse {
+            existed,
+            objects_deleted,
+        }))
+    }
+
+    // =========================================================================
+    // Bucket Lifecycle
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn apply_lifecycle_rules(
+        &self,
+        request: Request<ApplyLifecycleRulesRequest>,
+    ) -> Result<Response<ApplyLifecycleRulesResponse>, Status> {
+        let req = request.into_inner();
+        let path = self.lifecycle_rules.as_ref().ok_or_else(|| {
+            Status::failed_precondition("no lifecycle rules file configured (--lifecycle-rules)")
+        })?;
+
+        let storage = self.placement.bucket(&req.region).map_storage_err()?;
+
+        let declared = LifecycleRules::load(path).map_storage_err()?;
+        let current = storage.lifecycle_rules().await.map_storage_err()?;
+        let changed = declared != current;
+        let applied = changed && !req.dry_run;
+        if applied {
+            storage.set_lifecycle_rules(&declared).await.map_storage_err()?;
+        }
+
+        let to_json = |rules: &LifecycleRules| {
+            serde_json::to_string_pretty(rules).map_err(|e| Status::internal(e.to_string()))
+        };
+        Ok(Response::new(ApplyLifecycleRulesResponse {
+            applied,
+            changed,
+            current_json: to_json(&current)?,
+            declared_json: to_json(&declared)?,
+        }))
+    }
+
+    // =========================================================================
+    // Cost Estimate
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_cost_estimate(
+        &self,
+        request: Request<GetCostEstimateRequest>,
+    ) -> Result<Response<GetCostEstimateResponse>, Status> {
+        let req = request.into_inner();
+        let (meter, rates) = self.usage.as_ref().ok_or_else(|| {
+            Status::failed_precondition("usage metering is disabled (--usage-metering)")
+        })?;
+        let day = |value: &str| {
+            (!value.is_empty())
+                .then(|| {
+                    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| {
+                        Status::invalid_argument(format!("invalid day '{}': {}", value, e))
+                    })
+                })
+                .transpose()
+        };
+        let from = day(&req.from_day)?;
+        let to = day(&req.to_day)?;
+        let tenant_id = (!req.tenant_id.is_empty()).then_some(req.tenant_id.as_str());
+
+        let usage: Vec<ObjectStoreUsage> = meter
+            .usage(tenant_id, from, to, req.by_rpc)
+            .into_iter()
+            .map(|(key, counts)| ObjectStoreUsage {
+                day: key.day.format("%Y-%m-%d").to_string(),
+                tenant_id: key.tenant_id,
+                rpc: key.rpc,
+                class_a_ops: counts.class_a_ops,
+                class_b_ops: counts.class_b_ops,
+                free_ops: counts.free_ops,
+                bytes_read: counts.bytes_read,
+                bytes_written: counts.bytes_written,
+                estimated_cost_usd: rates.cost(&counts),
+            })
+            .collect();
+
+        Ok(Response::new(GetCostEstimateResponse {
+            total_cost_usd: usage.iter().map(|u| u.estimated_cost_usd).sum(),
+            usage,
+            metering_since_unix: meter.since().timestamp(),
+            class_a_usd_per_million: rates.class_a_per_million,
+            class_b_usd_per_million: rates.class_b_per_million,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_contention_report(
+        &self,
+        request: Request<GetContentionReportRequest>,
+    ) -> Result<Response<GetContentionReportResponse>, Status> {
+        let req = request.into_inner();
+        let (tracker, index_shard_conflict_rate) = self
+            .contention
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("contention tracking is disabled"))?;
+        let tenant_id = (!req.tenant_id.is_empty()).then_some(req.tenant_id.as_str());
+
+        let objects = tracker
+            .report(tenant_id)
+            .into_iter()
+            .map(|row| CasContention {
+                tenant_id: row.tenant_id,
+                object: row.object.as_str().to_string(),
+                recent_operations: row.recent.operations,
+                recent_conflicts: row.recent.conflicts,
+                recent_exhausted: row.recent.exhausted,
+                recent_conflict_rate: row.recent.conflict_rate(),
+                total_operations: row.total.operations,
+                total_conflicts: row.total.conflicts,
+                total_exhausted: row.total.exhausted,
+                index_sharded: row.index_sharded,
+            })
+            .collect();
+
+        Ok(Response::new(GetContentionReportResponse {
+            objects,
+            tracking_since_unix: tracker.since().timestamp(),
+            window_secs: tracker.window().as_secs(),
+            index_shard_conflict_rate: *index_shard_conflict_rate,
+        }))
+    }
+
+    // =========================================================================
+    // Tenant Snapshot
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn snapshot_tenant(
+        &self,
+        request: Request<SnapshotTenantRequest>,
+    ) -> Result<Response<SnapshotTenantResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let snapshot = self
+            .storage(tenant_id)?
+            .snapshot_tenant(tenant_id, req.copy)
+            .await
+            .map_storage_err()?;
+        let manifest_json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| Status::internal(format!("Failed to serialize manifest: {}", e)))?;
+        Ok(Response::new(SnapshotTenantResponse {
+            object_count: snapshot.objects.len() as u64,
+            total_bytes: snapshot.total_bytes(),
+            taken_at_unix: snapshot.taken_at.timestamp(),
+            copy_prefix: snapshot.copy_prefix.unwrap_or_default(),
+            snapshot_id: snapshot.snapshot_id,
+            manifest_json,
+        }))
+    }
+
+    // =========================================================================
+    // Presigned URLs
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_download_url(
+        &self,
+        request: Request<CreateDownloadUrlRequest>,
+    ) -> Result<Response<CreateDownloadUrlResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let expires_in = self.presigned_url_ttl(req.expires_in_secs)?;
+        validate_session_id(&req.session_id).map_storage_err()?;
+
+        let presigned = self
+            .storage(tenant_id)?
+            .presign_download(tenant_id, &req.session_id, req.checkpoint_position, expires_in)
+            .await
+            .map_storage_err()?;
+        Ok(Response::new(match presigned {
+            Some(presigned) => CreateDownloadUrlResponse {
+                found: true,
+                url: presigned.url,
+                expires_at_unix: presigned.expires_at.timestamp(),
+            },
+            None => CreateDownloadUrlResponse::default(),
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_upload_url(
+        &self,
+        request: Request<CreateUploadUrlRequest>,
+    ) -> Result<Response<CreateUploadUrlResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let expires_in = self
+            .presigned_url_ttl(req.expires_in_secs)?
+            .min(MAX_PRESIGNED_UPLOAD_TTL);
+        validate_session_id(&req.session_id).map_storage_err()?;
+
+        let presigned = self
+            .storage(tenant_id)?
+            .presign_upload(
+                tenant_id,
+                &req.session_id,
+                req.checkpoint_position,
+                req.size_bytes,
+                expires_in,
+            )
+            .await
+            .map_storage_err()?;
+        Ok(Response::new(CreateUploadUrlResponse {
+            url: presigned.url,
+            headers: presigned.headers.into_iter().collect(),
+            expires_at_unix: presigned.expires_at.timestamp(),
+            upload_id: presigned.upload_id.unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn commit_upload(
+        &self,
+        request: Request<CommitUploadRequest>,
+    ) -> Result<Response<CommitUploadResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        self.presigned_url_ttl(0)?;
+        validate_session_id(&req.session_id).map_storage_err()?;
+
+        let size_bytes = self
+            .storage(tenant_id)?
+            .commit_upload(tenant_id, &req.session_id, req.checkpoint_position, &req.upload_id)
+            .await
+            .map_storage_err()?;
+        Ok(Response::new(CommitUploadResponse { size_bytes }))
+    }
+
+    // =========================================================================
+    // Corpus Export
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn export_tenant_corpus(
+        &self,
+        request: Request<ExportTenantCorpusRequest>,
+    ) -> Result<Response<Self::ExportTenantCorpusStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?.to_string();
+        check_corpus_format(&req.format)?;
+        let sessions_per_sec =
+            corpus_pace(self.corpus_sessions_per_sec, req.max_sessions_per_sec);
+        let after = (!req.resume_token.is_empty()).then_some(req.resume_token);
+
+        info!(tenant_id, ?after, sessions_per_sec, "Exporting tenant corpus");
+        let storage = self.storage(&tenant_id)?;
+        let records = export_corpus(storage, tenant_id, after, sessions_per_sec);
+        Ok(Response::new(Box::pin(records.map(corpus_chunk))))
+    }
+
+    // =========================================================================
+    // Storage Report
+    // =========================================================================
+
+    async fn get_storage_report(
+        &self,
+        _request: Request<GetStorageReportRequest>,
+    ) -> Result<Response<GetStorageReportResponse>, Status> {
+        Err(Status::unimplemented(
+            "storage reports are only supported by the local storage server",
+        ))
+    }
+
+    // =========================================================================
+    // Admin Log
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn read_admin_log(
+        &self,
+        request: Request<ReadAdminLogRequest>,
+    ) -> Result<Response<ReadAdminLogResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let limit = if req.limit == 0 { DEFAULT_ADMIN_LOG_LIMIT } else { req.limit };
+
+        // One more than asked tells whether there are more
+        let mut entries = self
+            .storage(tenant_id)?
+            .read_admin_log(tenant_id, req.from_position, Some(limit.saturating_add(1)))
+            .await
+            .map_storage_err()?;
+        let has_more = entries.len() as u64 > limit;
+        entries.truncate(limit as usize);
+
+        Ok(Response::new(ReadAdminLogResponse {
+            entries: entries.into_iter().map(Self::admin_log_entry_to_proto).collect(),
+            has_more,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn record_admin_action(
+        &self,
+        request: Request<RecordAdminActionRequest>,
+    ) -> Result<Response<RecordAdminActionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        if Self::get_actor(req.context.as_ref()).trim().is_empty() {
+            return Err(Status::invalid_argument("an actor is required to record an admin action"));
+        }
+        // The server records the other actions itself
+        let action = AdminAction::parse(&req.action).map_storage_err()?;
+        if !matches!(action, AdminAction::Migration | AdminAction::KeyRotation) {
+            return Err(Status::invalid_argument(format!(
+                "'{}' is recorded by the server, only migration and key_rotation can be recorded",
+                req.action
+            )));
+        }
+
+        let mut entry = Self::admin_entry(action, req.context.as_ref()).with_detail(req.detail);
+        if !req.session_id.is_empty() {
+            entry = entry.with_session(&req.session_id);
+        }
+        let position = self
+            .storage(tenant_id)?
+            .append_admin_log(tenant_id, entry)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(RecordAdminActionResponse { position }))
+    }
+
+    // =========================================================================
+    // Health Check
+    // =========================================================================
+
+    #[instrument(skip(self), level = "debug")]
+    async fn health_check(
+        &self,
+        _request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        debug!("Health check requested");
+
+        // Unhealthy while a bucket is down and requests are being failed fast
+        let mut healthy = true;
+        for (region, storage) in self.placement.buckets() {
+            if let Some(stats) = storage.circuit_stats() {
+                debug!(region, ?stats, "R2 circuit breaker");
+                healthy &= stats.state != CircuitState::Open;
+            }
+        }
+        Ok(Response::new(HealthCheckResponse {
+            healthy,
+            backend: "r2".to_string(),
+            version: self.version.clone(),
+        }))
+    }
+
+    // =========================================================================
+    // Capabilities
+    // =========================================================================
+
+    #[instrument(skip(self), level = "debug")]
+    async fn get_capabilities(
+        &self,
+        _request: Request<GetCapabilitiesRequest>,
+    ) -> Result<Response<GetCapabilitiesResponse>, Status> {
+        // Every region's bucket is an R2 bucket with the same capabilities
+        let caps = self
+            .storage("")?
+            .capabilities()
+            .with_feature(feature::TENANT_SNAPSHOTS)
+            .with_feature(feature::SNAPSHOT_HANDLES)
+            .with_feature(feature::SANDBOXES)
+            .with_feature(feature::LEGAL_HOLDS)
+            .with_feature(feature::WAL_BATCHES)
+            .with_feature(feature::CORPUS_EXPORT)
+            .with_feature_if(feature::RESUMABLE_UPLOADS, self.uploads.is_some())
+            .with_feature_if(feature::TENANT_ERASURE, self.erasure_signer.is_some())
+            .with_feature_if(feature::LIFECYCLE_RULES, self.lifecycle_rules.is_some())
+            .with_feature_if(feature::COST_ESTIMATES, self.usage.is_some())
+            .with_feature_if(feature::CONTENTION_REPORT, self.contention.is_some())
+            .with_feature_if(feature::PRESIGNED_URLS, self.presigned_url_max_ttl.is_some())
+            .with_feature_if(
+                feature::PRESIGNED_UPLOADS,
+                self.presigned_url_max_ttl.is_some()
+                    && self
+                        .placement
+                        .bucket("")
+                        .is_ok_and(|bucket| bucket.direct_upload_refusal(false).is_none()),
+            );
+        Ok(Response::new(capabilities_response(caps, &self.version)))
+    }
+}
+
+/// Refuse export formats other than JSONL (the default).
+fn check_corpus_format(format: &str) -> Result<(), Status> {
+    match format {
+        "" | CORPUS_FORMAT_JSONL => Ok(()),
+        other => Err(Status::invalid_argument(format!(
+            "unsupported corpus format '{}' (only {} is)",
+            other, CORPUS_FORMAT_JSONL
+        ))),
+    }
+}
+
+/// Sessions a corpus export reads per second: the slower of the server's
+/// and the client's pace (0 = unpaced on either side).
+fn corpus_pace(server: u32, requested: u32) -> u32 {
+    match (server, requested) {
+        (0, requested) => requested,
+        (server, 0) => server,
+        (server, requested) => server.min(requested),
+    }
+}
+
+/// One corpus record as a stream chunk, resuming after it.
+fn corpus_chunk(record: Result<CorpusRecord, StorageError>) -> Result<CorpusChunk, Status> {
+    let record = record.map_storage_err()?;
+    Ok(CorpusChunk {
+        data: record.to_jsonl().map_storage_err()?,
+        resume_token: record.session_id,
+    })
+}
+
+/// Convert [`Capabilities`] into their protobuf response.
+fn capabilities_response(caps: Capabilities, version: &str) -> GetCapabilitiesResponse {
+    GetCapabilitiesResponse {
+        backend: caps.backend,
+        version: version.to_string(),
+        schema_version: PROTO_SCHEMA_VERSION,
+        features: caps.features.into_iter().collect(),
+        max_object_bytes: caps.max_object_bytes,
+        max_wal_entry_bytes: caps.max_wal_entry_bytes,
+    }
+}
+
+/// Flatten a [`SessionHealth`] into its protobuf response.
+fn health_response(health: Option<SessionHealth>) -> GetSessionHealthResponse {
+    let Some(health) = health else {
+        return GetSessionHealthResponse::default();
+    };
+    let sync = health.sync.as_ref();
+    GetSessionHealthResponse {
+        found: true,
+        size_bytes: health.size_bytes,
+        wal_count: health.wal_count,
+        cursor_position: health.cursor_position,
+        last_checkpoint: health.last_checkpoint,
+        wal_since_checkpoint: health.wal_since_checkpoint,
+        last_modified_at_unix: health.last_modified_at.map_or(0, |t| t.timestamp()),
+        pending_external_change: health.pending_external_change,
+        sync_registered: sync.is_some(),
+        auto_sync_enabled: sync.is_some_and(|s| s.auto_sync_enabled),
+        last_synced_at_unix: sync.and_then(|s| s.last_synced_at).map_or(0, |t| t.timestamp()),
+        since_last_sync_secs: sync.and_then(|s| s.since_last_sync_secs).unwrap_or(-1),
+        sync_pending: sync.is_some_and(|s| s.has_pending_changes),
+        sync_error: sync.and_then(|s| s.last_error.clone()).unwrap_or_default(),
+        legal_hold: health.legal_hold.is_some(),
+        legal_hold_reason: health.legal_hold.as_ref().map(|h| h.reason.clone()).unwrap_or_default(),
+        expires_at_unix: health.expires_at.map_or(0, |t| t.timestamp()),
+        valid: health.is_valid(),
+        problems: health.problems,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::fake_s3::FakeS3;
+
+    fn service(s3: &FakeS3) -> StorageServiceImpl {
+        let placement = BucketPlacement::new("eu", Arc::new(s3.storage()));
+        StorageServiceImpl::new(Arc::new(placement))
+    }
+
+    fn from_template(session_id: &str) -> Request<CreateSessionFromTemplateRequest> {
+        Request::new(CreateSessionFromTemplateRequest {
+            context: context(),
+            template_name: "letterhead".to_string(),
+            session_id: session_id.to_string(),
+            expires_at_unix: 0,
+        })
+    }
+
+    async fn with_template(s3: &FakeS3) -> StorageServiceImpl {
+        let service = service(s3);
+        service
+            .storage("t")
+            .unwrap()
+            .save_library_item(
+                "t",
+                docx_storage_core::LibraryKind::Template,
+                "letterhead",
+                b"template",
+            )
+            .await
+            .unwrap();
+        service
+    }
+
+    fn context() -> Option<TenantContext> {
+        Some(TenantContext {
+            tenant_id: "t".to_string(),
+            actor: String::new(),
+        })
+    }
+
+    fn rename(session_id: &str, alias: &str) -> Request<RenameSessionRequest> {
+        Request::new(RenameSessionRequest {
+            context: context(),
+            session_id: session_id.to_string(),
+            display_name: Some(format!("Renamed {}", session_id)),
+            alias: Some(alias.to_string()),
+        })
+    }
+
+    async fn resolve(service: &StorageServiceImpl, name: &str) -> ResolveSessionResponse {
+        let request = Request::new(ResolveSessionRequest {
+            context: context(),
+            name: name.to_string(),
+        });
+        service.resolve_session(request).await.unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_rename_session_sets_an_alias_sessions_resolve_by() {
+        let s3 = FakeS3::start().await;
+        let service = with_template(&s3).await;
+        service
+            .create_session_from_template(from_template("s1"))
+            .await
+            .unwrap();
+
+        let response = service.rename_session(rename("s1", "board")).await.unwrap();
+
+        assert!(response.into_inner().success);
+        let resolved = resolve(&service, "Board").await;
+        assert!(resolved.found);
+        assert_eq!(resolved.session_id, "s1");
+        assert_eq!(resolved.display_name, "Renamed s1");
+        let missing = service.rename_session(rename("s9", "other")).await.unwrap();
+        assert!(missing.into_inner().not_found);
+    }
+
+    #[tokio::test]
+    async fn test_rename_session_refuses_a_duplicate_alias() {
+        let s3 = FakeS3::start().await;
+        let service = with_template(&s3).await;
+        for session_id in ["s1", "s2"] {
+            service
+                .create_session_from_template(from_template(session_id))
+                .await
+                .unwrap();
+        }
+        service.rename_session(rename("s1", "board")).await.unwrap();
+
+        let status = service
+            .rename_session(rename("s2", "BOARD"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(resolve(&service, "board").await.session_id, "s1");
+        // The refused rename left the display name alone too
+        let index = service
+            .storage("t")
+            .unwrap()
+            .load_index("t")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(index.get("s2").unwrap().display_name.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_session_from_template_stores_and_indexes_it() {
+        let s3 = FakeS3::start().await;
+        let service = with_template(&s3).await;
+
+        let response = service
+            .create_session_from_template(from_template("s1"))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(response.found);
+        assert_eq!(response.size_bytes, 8);
+        let storage = service.storage("t").unwrap();
+        assert_eq!(
+            storage.load_session("t", "s1").await.unwrap().unwrap(),
+            b"template"
+        );
+        let index = storage.load_index("t").await.unwrap().unwrap();
+        assert!(index.contains("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_from_template_refuses_existing_sessions() {
+        let s3 = FakeS3::start().await;
+        let service = with_template(&s3).await;
+        service
+            .create_session_from_template(from_template("s1"))
+            .await
+            .unwrap();
+
+        let status = service
+            .create_session_from_template(from_template("s1"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn test_create_session_from_template_refuses_ids_claimed_in_the_index() {
+        let s3 = FakeS3::start().await;
+        let service = with_template(&s3).await;
+        let storage = service.storage("t").unwrap();
+        // Another creation has claimed the id but not stored its document yet
+        storage
+            .cas_index("t", |index| {
+                let now = chrono::Utc::now();
+                index.upsert(crate::storage::SessionIndexEntry {
+                    id: "s1".to_string(),
+                    source_path: None,
+                    auto_sync: true,
+                    created_at: now,
+                    last_modified_at: now,
+                    docx_file: Some("s1.docx".to_string()),
+                    wal_count: 0,
+                    cursor_position: 0,
+                    checkpoint_positions: vec![],
+                    pending_external_change: false,
+                    display_name: None,
+                    alias: None,
+                    legal_hold: None,
+                    expires_at: None,
+                });
+            })
+            .await
+            .unwrap();
+
+        let status = service
+            .create_session_from_template(from_template("s1"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+        assert!(storage.load_session("t", "s1").await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_creations_of_the_same_session_succeed_once() {
+        let s3 = FakeS3::start().await;
+        let service = Arc::new(with_template(&s3).await);
+
+        let creations: Vec<_> = (0..4)
+            .map(|_| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    service
+                        .create_session_from_template(from_template("s1"))
+                        .await
+                })
+            })
+            .collect();
+        let mut created = 0;
+        for creation in creations {
+            match creation.await.unwrap() {
+                Ok(_) => created += 1,
+                Err(status) => assert_eq!(status.code(), tonic::Code::AlreadyExists),
+            }
+        }
+
+        assert_eq!(created, 1);
+    }
 }
@@ -0,0 +1,205 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use docx_storage_core::{validate_tenant_id, OperationRegistry};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{Request, Response, Status};
+use tracing::{debug, instrument};
+
+use crate::error::StorageResultExt;
+use crate::service::proto;
+use proto::operation_service_server::OperationService;
+use proto::*;
+
+/// How long finished operations stay readable.
+pub const OPERATION_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Implementation of the OperationService gRPC service.
+pub struct OperationServiceImpl {
+    registry: Arc<OperationRegistry>,
+}
+
+impl OperationServiceImpl {
+    pub fn new(registry: Arc<OperationRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// Extract tenant_id from request context.
+    fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
+        let tenant_id = context
+            .map(|c| c.tenant_id.as_str())
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
+    }
+
+    /// Convert a core Operation to a proto Operation.
+    fn to_proto(op: &docx_storage_core::Operation) -> Operation {
+        Operation {
+            id: op.id.clone(),
+            kind: op.kind.clone(),
+            description: op.description.clone(),
+            session_id: op.session_id.clone().unwrap_or_default(),
+            state: Self::to_proto_state(op.state),
+            progress: op.progress,
+            message: op.message.clone().unwrap_or_default(),
+            result: op.result.clone().unwrap_or_default(),
+            error: op.error.clone().unwrap_or_default(),
+            created_at_unix: op.created_at,
+            updated_at_unix: op.updated_at,
+        }
+    }
+
+    /// Convert core OperationState to proto OperationState.
+    fn to_proto_state(state: docx_storage_core::OperationState) -> i32 {
+        match state {
+            docx_storage_core::OperationState::Running => 1,
+            docx_storage_core::OperationState::Succeeded => 2,
+            docx_storage_core::OperationState::Failed => 3,
+            docx_storage_core::OperationState::Cancelled => 4,
+        }
+    }
+
+    fn respond(op: docx_storage_core::Operation) -> Response<OperationResponse> {
+        Response::new(OperationResponse {
+            operation: Some(Self::to_proto(&op)),
+        })
+    }
+}
+
+type WatchOperationStream = Pin<Box<dyn Stream<Item = Result<Operation, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl OperationService for OperationServiceImpl {
+    type WatchOperationStream = WatchOperationStream;
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn start_operation(
+        &self,
+        request: Request<StartOperationRequest>,
+    ) -> Result<Response<OperationResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let op = self
+            .registry
+            .start(tenant_id, &req.kind, &req.description, Some(&req.session_id))
+            .map_storage_err()?;
+        debug!("Started operation {} ({}) for tenant {}", op.id, op.kind, tenant_id);
+        Ok(Self::respond(op))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn report_progress(
+        &self,
+        request: Request<ReportProgressRequest>,
+    ) -> Result<Response<OperationResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let op = self
+            .registry
+            .report_progress(tenant_id, &req.operation_id, req.progress, Some(&req.message))
+            .map_storage_err()?;
+        Ok(Self::respond(op))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn finish_operation(
+        &self,
+        request: Request<FinishOperationRequest>,
+    ) -> Result<Response<OperationResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let outcome = if req.success {
+            Ok(req.result)
+        } else {
+            Err(req.error)
+        };
+        let op = self
+            .registry
+            .finish(tenant_id, &req.operation_id, outcome)
+            .map_storage_err()?;
+        debug!("Operation {} finished: {:?}", op.id, op.state);
+        Ok(Self::respond(op))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn cancel_operation(
+        &self,
+        request: Request<CancelOperationRequest>,
+    ) -> Result<Response<OperationResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let op = self
+            .registry
+            .cancel(tenant_id, &req.operation_id)
+            .map_storage_err()?;
+        Ok(Self::respond(op))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_operation(
+        &self,
+        request: Request<GetOperationRequest>,
+    ) -> Result<Response<OperationResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let op = self
+            .registry
+            .get(tenant_id, &req.operation_id)
+            .map_storage_err()?;
+        Ok(Self::respond(op))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_operations(
+        &self,
+        request: Request<ListOperationsRequest>,
+    ) -> Result<Response<ListOperationsResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let operations = self
+            .registry
+            .list(tenant_id, req.include_finished)
+            .iter()
+            .map(Self::to_proto)
+            .collect();
+        Ok(Response::new(ListOperationsResponse { operations }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn watch_operation(
+        &self,
+        request: Request<WatchOperationRequest>,
+    ) -> Result<Response<Self::WatchOperationStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let mut rx = self
+            .registry
+            .subscribe(tenant_id, &req.operation_id)
+            .map_storage_err()?;
+
+        let (tx, out) = mpsc::channel(16);
+        tokio::spawn(async move {
+            loop {
+                let op = rx.borrow_and_update().clone();
+                if tx.send(Ok(Self::to_proto(&op))).await.is_err() {
+                    return; // Client disconnected
+                }
+                if op.state.is_finished() || rx.changed().await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(out))))
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use docx_storage_core::StorageError;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+/// Local disk cache for session and checkpoint objects fetched from R2.
+///
+/// Documents are loaded many times during a conversation while they rarely
+/// change, so keeping the last-used ones on local disk avoids an R2 round-trip
+/// on every load. The cache is write-through: callers store into R2 first and
+/// then into the cache, so a cached object is never newer than R2.
+///
+/// - Bounded by `max_bytes`; least recently used entries are evicted first.
+/// - Every entry records the SHA-256 of its content. A file whose content no
+///   longer matches (truncated write, disk corruption, manual edit) is dropped
+///   and reported as a miss.
+/// - Entry metadata lives in memory only; files left over from a previous run
+///   are wiped on startup. The cache only ever touches its own subdirectory of
+///   the directory it is given, so pointing it at a shared directory is safe.
+/// - Loads fill the cache through [`DiskCache::start_fill`]: a `put` or
+///   `remove` of the key while the load is reading R2 cancels the fill, so a
+///   load racing a save can't cache the bytes it read before the save.
+///
+/// Layout on disk:
+/// ```
+/// {cache_dir}/
+///   docx-storage-cache/
+///     {sha256(object_key)}.bin
+/// ```
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+    /// Monotonic access counter used for LRU ordering.
+    clock: u64,
+    /// Keys being loaded from R2, with the number of loads in flight and a
+    /// generation bumped by every write of the key.
+    loading: HashMap<String, Loading>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Loading {
+    loads: usize,
+    generation: u64,
+}
+
+#[derive(Clone, Copy)]
+struct CacheEntry {
+    size: u64,
+    digest: [u8; 32],
+    last_access: u64,
+}
+
+impl DiskCache {
+    /// Create a cache in a subdirectory of `dir`, removing any files it left
+    /// there in a previous run. Nothing else in `dir` is touched.
+    pub fn new(dir: impl AsRef<Path>, max_bytes: u64) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().join(CACHE_SUBDIR);
+
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| {
+                StorageError::Io(format!(
+                    "Failed to clear cache dir {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+        }
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            StorageError::Io(format!(
+                "Failed to create cache dir {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            dir,
+            max_bytes,
+            state: Mutex::new(CacheState::default()),
+        })
+    }
+
+    /// Path of the cache file for an object key.
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.bin", hex::encode(digest(key.as_bytes()))))
+    }
+
+    /// Return the cached content for `key`, or `None` on miss or validation failure.
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let expected = {
+            let mut state = self.state.lock().unwrap();
+            state.clock += 1;
+            let clock = state.clock;
+            let entry = state.entries.get_mut(key)?;
+            entry.last_access = clock;
+            entry.digest
+        };
+
+        let path = self.entry_path(key);
+        match tokio::fs::read(&path).await {
+            Ok(data) if digest(&data) == expected => {
+                debug!(key, bytes = data.len(), "Disk cache hit");
+                Some(data)
+            }
+            Ok(_) => {
+                warn!(key, "Disk cache entry failed hash validation, dropping");
+                self.drop_entry(key, Some(expected)).await;
+                None
+            }
+            Err(e) => {
+                warn!(key, "Failed to read disk cache entry: {}", e);
+                self.drop_entry(key, Some(expected)).await;
+                None
+            }
+        }
+    }
+
+    /// Store `data` under `key`, evicting least recently used entries as needed.
+    ///
+    /// Failures are logged and swallowed: the cache is an optimization and must
+    /// never fail a write that already reached R2.
+    pub async fn put(&self, key: &str, data: &[u8]) {
+        self.store(key, data, None).await;
+    }
+
+    /// Register a load of `key` from R2. Call before reading R2, and hand what
+    /// was read to [`Fill::finish`].
+    pub fn start_fill(&self, key: &str) -> Fill<'_> {
+        let mut state = self.state.lock().unwrap();
+        let loading = state.loading.entry(key.to_string()).or_default();
+        loading.loads += 1;
+        Fill {
+            cache: self,
+            key: key.to_string(),
+            generation: loading.generation,
+        }
+    }
+
+    /// Write `data` and commit it as the entry for `key`. With `fill_generation`,
+    /// the entry is only committed if the key wasn't written since the fill
+    /// started.
+    async fn store(&self, key: &str, data: &[u8], fill_generation: Option<u64>) {
+        let size = data.len() as u64;
+        if size > self.max_bytes {
+            debug!(key, size, "Object larger than disk cache, not caching");
+            if fill_generation.is_none() {
+                self.remove(key).await;
+            }
+            return;
+        }
+
+        let path = self.entry_path(key);
+        let temp_path = path.with_extension(format!("tmp.{}", temp_suffix()));
+        if let Err(e) = tokio::fs::write(&temp_path, data).await {
+            warn!(key, "Failed to write disk cache entry: {}", e);
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return;
+        }
+
+        let evicted = match self.commit(key, &temp_path, &path, data, fill_generation) {
+            Ok(Some(evicted)) => evicted,
+            Ok(None) => {
+                debug!(key, "Key written while loading, not caching the load");
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return;
+            }
+            Err(e) => {
+                warn!(key, "Failed to commit disk cache entry: {}", e);
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                self.drop_entry(key, None).await;
+                return;
+            }
+        };
+
+        for victim in evicted {
+            debug!(key = %victim, "Evicting disk cache entry");
+            let _ = tokio::fs::remove_file(self.entry_path(&victim)).await;
+        }
+    }
+
+    /// Move the written `temp_path` into place and record the entry, returning
+    /// the keys evicted to make room. `None` when a fill was outdated by a write.
+    ///
+    /// The rename happens under the lock so that the file and the entry always
+    /// change together, whichever of two racing stores wins.
+    fn commit(
+        &self,
+        key: &str,
+        temp_path: &Path,
+        path: &Path,
+        data: &[u8],
+        fill_generation: Option<u64>,
+    ) -> std::io::Result<Option<Vec<String>>> {
+        let mut state = self.state.lock().unwrap();
+        let current = state.loading.get(key).map_or(0, |l| l.generation);
+        if fill_generation.is_some_and(|generation| generation != current) {
+            return Ok(None);
+        }
+        std::fs::rename(temp_path, path)?;
+        if fill_generation.is_none() {
+            Self::bump_generation(&mut state, key);
+        }
+
+        state.clock += 1;
+        let size = data.len() as u64;
+        let entry = CacheEntry {
+            size,
+            digest: digest(data),
+            last_access: state.clock,
+        };
+        if let Some(old) = state.entries.insert(key.to_string(), entry) {
+            state.total_bytes -= old.size;
+        }
+        state.total_bytes += size;
+        Ok(Some(Self::evict(&mut state, self.max_bytes)))
+    }
+
+    /// Remove `key` from the cache if present.
+    pub async fn remove(&self, key: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            Self::bump_generation(&mut state, key);
+        }
+        self.drop_entry(key, None).await;
+    }
+
+    /// Cancel the fills in flight for `key`.
+    fn bump_generation(state: &mut CacheState, key: &str) {
+        if let Some(loading) = state.loading.get_mut(key) {
+            loading.generation += 1;
+        }
+    }
+
+    /// Pop least recently used entries until the cache fits in `max_bytes`.
+    fn evict(state: &mut CacheState, max_bytes: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while state.total_bytes > max_bytes {
+            let Some(victim) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_access)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = state.entries.remove(&victim) {
+                state.total_bytes -= entry.size;
+            }
+            evicted.push(victim);
+        }
+        evicted
+    }
+
+    /// Forget an entry and delete its file.
+    ///
+    /// When `only_if_digest` is set, the entry is only dropped if it still has
+    /// that digest, so a concurrent `put` of fresh content is not discarded.
+    async fn drop_entry(&self, key: &str, only_if_digest: Option<[u8; 32]>) {
+        let removed = {
+            let mut state = self.state.lock().unwrap();
+            let matches = match (state.entries.get(key), only_if_digest) {
+                (Some(entry), Some(expected)) => entry.digest == expected,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            if matches {
+                if let Some(entry) = state.entries.remove(key) {
+                    state.total_bytes -= entry.size;
+                }
+            }
+            matches
+        };
+
+        if removed {
+            let _ = tokio::fs::remove_file(self.entry_path(key)).await;
+        }
+    }
+}
+
+/// A load of one key from R2, started by [`DiskCache::start_fill`].
+pub struct Fill<'a> {
+    cache: &'a DiskCache,
+    key: String,
+    generation: u64,
+}
+
+impl Fill<'_> {
+    /// Cache what the load read, unless the key was written in the meantime.
+    pub async fn finish(self, data: &[u8]) {
+        self.cache.store(&self.key, data, Some(self.generation)).await;
+    }
+}
+
+impl Drop for Fill<'_> {
+    fn drop(&mut self) {
+        let mut state = self.cache.state.lock().unwrap();
+        if let Some(loading) = state.loading.get_mut(&self.key) {
+            loading.loads -= 1;
+            if loading.loads == 0 {
+                state.loading.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Subdirectory of the configured directory that the cache owns.
+const CACHE_SUBDIR: &str = "docx-storage-cache";
+
+fn digest(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Cheap unique suffix for temp files (process id + nanoseconds).
+fn temp_suffix() -> String {
+    use std::time::SystemTime;
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("{}.{}", std::process::id(), nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_new_only_clears_its_own_subdirectory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("operator.txt"), b"keep me").unwrap();
+
+        let cache = DiskCache::new(dir.path(), 1024).unwrap();
+        cache.put("a", b"alpha").await;
+        drop(cache);
+
+        let cache = DiskCache::new(dir.path(), 1024).unwrap();
+        assert_eq!(cache.get("a").await, None);
+        assert!(dir.path().join("operator.txt").exists());
+        assert_eq!(std::fs::read_dir(dir.path().join(CACHE_SUBDIR)).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let cache = DiskCache::new(dir.path(), 10).unwrap();
+
+        cache.put("a", b"aaaa").await;
+        cache.put("b", b"bbbb").await;
+        // Touch "a" so that "b" is the least recently used
+        assert_eq!(cache.get("a").await.as_deref(), Some(&b"aaaa"[..]));
+        cache.put("c", b"cccc").await;
+
+        assert_eq!(cache.get("b").await, None);
+        assert_eq!(cache.get("a").await.as_deref(), Some(&b"aaaa"[..]));
+        assert_eq!(cache.get("c").await.as_deref(), Some(&b"cccc"[..]));
+        assert!(!cache.entry_path("b").exists());
+        assert_eq!(cache.state.lock().unwrap().total_bytes, 8);
+
+        // Too large to ever fit: not cached, and the stale entry is dropped
+        cache.put("a", &[0; 11]).await;
+        assert_eq!(cache.get("a").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_drops_entries_failing_hash_validation() {
+        let dir = TempDir::new().unwrap();
+        let cache = DiskCache::new(dir.path(), 1024).unwrap();
+
+        cache.put("a", b"original").await;
+        std::fs::write(cache.entry_path("a"), b"tampered").unwrap();
+
+        assert_eq!(cache.get("a").await, None);
+        assert!(!cache.entry_path("a").exists());
+        assert_eq!(cache.state.lock().unwrap().total_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fill_racing_a_write_is_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let cache = DiskCache::new(dir.path(), 1024).unwrap();
+
+        // A load reads the old bytes, then a save stores new ones before the
+        // load fills the cache.
+        let fill = cache.start_fill("a");
+        cache.put("a", b"new").await;
+        fill.finish(b"old").await;
+        assert_eq!(cache.get("a").await.as_deref(), Some(&b"new"[..]));
+
+        // Same with a save that only invalidates (streamed uploads)
+        let fill = cache.start_fill("a");
+        cache.remove("a").await;
+        fill.finish(b"old").await;
+        assert_eq!(cache.get("a").await, None);
+
+        // An undisturbed fill is cached, and finished fills are forgotten
+        let fill = cache.start_fill("a");
+        fill.finish(b"fresh").await;
+        assert_eq!(cache.get("a").await.as_deref(), Some(&b"fresh"[..]));
+        assert!(cache.state.lock().unwrap().loading.is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Object updated by read-modify-write with an ETag condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CasObject {
+    /// The tenant's session index
+    Index,
+    /// Any of the tenant's session WALs
+    Wal,
+}
+
+impl CasObject {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CasObject::Index => "index",
+            CasObject::Wal => "wal",
+        }
+    }
+}
+
+/// Read-modify-write operations counted for one tenant and object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentionCounts {
+    pub operations: u64,
+    /// Conditional writes rejected because the object changed (412)
+    pub conflicts: u64,
+    /// Operations that gave up once their retries were exhausted
+    pub exhausted: u64,
+}
+
+impl ContentionCounts {
+    fn add(&mut self, other: &ContentionCounts) {
+        self.operations += other.operations;
+        self.conflicts += other.conflicts;
+        self.exhausted += other.exhausted;
+    }
+
+    /// Share of the conditional writes attempted that conflicted.
+    pub fn conflict_rate(&self) -> f64 {
+        let attempts = self.conflicts + self.operations - self.exhausted;
+        match attempts {
+            0 => 0.0,
+            attempts => self.conflicts as f64 / attempts as f64,
+        }
+    }
+}
+
+/// One row of [`ContentionTracker::report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentionRow {
+    pub tenant_id: String,
+    pub object: CasObject,
+    /// Counts over the tracker's window
+    pub recent: ContentionCounts,
+    /// Counts since the tracker started
+    pub total: ContentionCounts,
+    /// Whether the tenant's index is split into per-session objects
+    pub index_sharded: bool,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    /// Per minute (Unix time / 60), kept for the window
+    recent: BTreeMap<(i64, String, CasObject), ContentionCounts>,
+    total: BTreeMap<(String, CasObject), ContentionCounts>,
+    sharded: HashSet<String>,
+}
+
+/// In-memory counts of the CAS conflicts each tenant's index and WAL writes
+/// run into, so hot spots show up in GetContentionReport before they turn
+/// into exhausted retries. Counts are lost on restart.
+#[derive(Debug)]
+pub struct ContentionTracker {
+    since: DateTime<Utc>,
+    window: Duration,
+    counts: Mutex<Counts>,
+}
+
+impl Default for ContentionTracker {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(600))
+    }
+}
+
+impl ContentionTracker {
+    /// Track conflicts, reporting recent rates over `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            since: Utc::now(),
+            window,
+            counts: Mutex::new(Counts::default()),
+        }
+    }
+
+    /// When tracking started.
+    pub fn since(&self) -> DateTime<Utc> {
+        self.since
+    }
+
+    /// Window recent counts cover.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    fn minute(&self) -> i64 {
+        Utc::now().timestamp().div_euclid(60)
+    }
+
+    fn oldest_minute(&self) -> i64 {
+        self.minute() - (self.window.as_secs() / 60).max(1) as i64 + 1
+    }
+
+    /// Count a finished read-modify-write of `object` that ran into
+    /// `conflicts` conflicts and succeeded, or gave up when `exhausted`.
+    pub fn record(&self, tenant_id: &str, object: CasObject, conflicts: u32, exhausted: bool) {
+        let update = ContentionCounts {
+            operations: 1,
+            conflicts: u64::from(conflicts),
+            exhausted: u64::from(exhausted),
+        };
+        let minute = self.minute();
+        let oldest = self.oldest_minute();
+        let mut counts = self.counts.lock().unwrap();
+        let key = (minute, tenant_id.to_string(), object);
+        if !counts.recent.contains_key(&key) {
+            // First count of a new minute: a good time to drop expired ones
+            counts.recent.retain(|(m, _, _), _| *m >= oldest);
+        }
+        counts.recent.entry(key).or_default().add(&update);
+        counts
+            .total
+            .entry((tenant_id.to_string(), object))
+            .or_default()
+            .add(&update);
+    }
+
+    /// Counts of `tenant_id`'s `object` over the window.
+    pub fn recent(&self, tenant_id: &str, object: CasObject) -> ContentionCounts {
+        let oldest = self.oldest_minute();
+        let counts = self.counts.lock().unwrap();
+        let mut recent = ContentionCounts::default();
+        for ((minute, tenant, o), value) in counts.recent.iter() {
+            if *minute >= oldest && tenant == tenant_id && *o == object {
+                recent.add(value);
+            }
+        }
+        recent
+    }
+
+    /// Remember that `tenant_id`'s index is split into per-session entries.
+    pub fn mark_sharded(&self, tenant_id: &str) {
+        self.counts.lock().unwrap().sharded.insert(tenant_id.to_string());
+    }
+
+    /// Counts of `tenant_id` (every tenant when `None`), busiest conflicts
+    /// first.
+    pub fn report(&self, tenant_id: Option<&str>) -> Vec<ContentionRow> {
+        let oldest = self.oldest_minute();
+        let counts = self.counts.lock().unwrap();
+        let mut recent: BTreeMap<(&str, CasObject), ContentionCounts> = BTreeMap::new();
+        for ((minute, tenant, object), value) in counts.recent.iter() {
+            if *minute >= oldest {
+                recent.entry((tenant.as_str(), *object)).or_default().add(value);
+            }
+        }
+        let mut rows: Vec<ContentionRow> = counts
+            .total
+            .iter()
+            .filter(|((tenant, _), _)| tenant_id.is_none_or(|t| t == tenant))
+            .map(|((tenant, object), total)| ContentionRow {
+                tenant_id: tenant.clone(),
+                object: *object,
+                recent: recent.get(&(tenant.as_str(), *object)).copied().unwrap_or_default(),
+                total: *total,
+                index_sharded: counts.sharded.contains(tenant),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.recent
+                .conflicts
+                .cmp(&a.recent.conflicts)
+                .then(b.total.conflicts.cmp(&a.total.conflicts))
+                .then(a.tenant_id.cmp(&b.tenant_id))
+                .then(a.object.cmp(&b.object))
+        });
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_rate_counts_every_attempt() {
+        // Two operations, the second retried twice before succeeding
+        let counts = ContentionCounts {
+            operations: 2,
+            conflicts: 2,
+            exhausted: 0,
+        };
+        assert_eq!(counts.conflict_rate(), 0.5);
+
+        // An operation that gave up after its retries made no final attempt
+        let counts = ContentionCounts {
+            operations: 1,
+            conflicts: 3,
+            exhausted: 1,
+        };
+        assert_eq!(counts.conflict_rate(), 1.0);
+
+        assert_eq!(ContentionCounts::default().conflict_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_record_adds_to_recent_and_total_counts() {
+        let tracker = ContentionTracker::default();
+        tracker.record("t1", CasObject::Index, 2, false);
+        tracker.record("t1", CasObject::Index, 3, true);
+        tracker.record("t1", CasObject::Wal, 0, false);
+
+        let expected = ContentionCounts {
+            operations: 2,
+            conflicts: 5,
+            exhausted: 1,
+        };
+        assert_eq!(tracker.recent("t1", CasObject::Index), expected);
+        assert_eq!(
+            tracker.recent("t2", CasObject::Index),
+            ContentionCounts::default()
+        );
+        let report = tracker.report(Some("t1"));
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].total, expected);
+    }
+
+    #[test]
+    fn test_a_window_under_a_minute_still_covers_the_current_one() {
+        let tracker = ContentionTracker::new(Duration::from_secs(1));
+        tracker.record("t1", CasObject::Index, 1, false);
+
+        assert_eq!(tracker.recent("t1", CasObject::Index).operations, 1);
+    }
+
+    #[test]
+    fn test_report_puts_the_busiest_conflicts_first() {
+        let tracker = ContentionTracker::default();
+        tracker.record("quiet", CasObject::Index, 0, false);
+        tracker.record("busy", CasObject::Wal, 4, false);
+        tracker.record("busy", CasObject::Index, 1, false);
+        tracker.mark_sharded("busy");
+
+        let report = tracker.report(None);
+        let rows: Vec<_> = report
+            .iter()
+            .map(|row| (row.tenant_id.as_str(), row.object, row.index_sharded))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("busy", CasObject::Wal, true),
+                ("busy", CasObject::Index, true),
+                ("quiet", CasObject::Index, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! In-process S3 server for tests: enough of the API (path-style object
+//! GET/HEAD/PUT/DELETE with ETag conditions, ListObjectsV2) to run
+//! [`R2Storage`] against.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+use axum::body::Bytes;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use sha2::{Digest, Sha256};
+
+use super::R2Storage;
+
+const BUCKET: &str = "test-bucket";
+
+/// Objects by key, with their ETags.
+type Objects = BTreeMap<String, (Vec<u8>, String)>;
+
+/// A running fake S3 server holding one bucket.
+#[derive(Clone)]
+pub struct FakeS3 {
+    endpoint: String,
+    objects: Arc<Mutex<Objects>>,
+}
+
+impl FakeS3 {
+    /// Start a server on a free local port.
+    pub async fn start() -> Self {
+        let objects = Arc::new(Mutex::new(Objects::new()));
+        let state = objects.clone();
+        let app = Router::new().fallback(
+            move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+                let state = state.clone();
+                async move { handle(&state, method, uri, headers, body) }
+            },
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        Self { endpoint, objects }
+    }
+
+    /// A client of the server.
+    pub fn client(&self) -> aws_sdk_s3::Client {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+            .region(Region::new("auto"))
+            .endpoint_url(&self.endpoint)
+            .force_path_style(true)
+            .build();
+        aws_sdk_s3::Client::from_conf(config)
+    }
+
+    /// Storage on the server's bucket.
+    pub fn storage(&self) -> R2Storage {
+        R2Storage::new(self.client(), BUCKET.to_string())
+    }
+
+    /// The object stored at `key`.
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|(data, _)| data.clone())
+    }
+
+    /// Store `data` at `key`, as another client would.
+    pub fn put(&self, key: &str, data: &[u8]) {
+        self.objects
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (data.to_vec(), etag(data)));
+    }
+
+    /// Keys stored under `prefix`.
+    pub fn keys(&self, prefix: &str) -> Vec<String> {
+        self.objects
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+/// ETag of `data`: like R2's, the same for the same content.
+fn etag(data: &[u8]) -> String {
+    format!("\"{}\"", &hex::encode(Sha256::digest(data))[..32])
+}
+
+fn error(status: StatusCode, code: &str) -> Response {
+    let body = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>{code}</Code><Message>{code}</Message></Error>");
+    (status, [("content-type", "application/xml")], body).into_response()
+}
+
+fn query_param(uri: &Uri, name: &str) -> Option<String> {
+    uri.query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| percent_decode(value))
+    })
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+/// The body of a PUT, decoded if the SDK sent it aws-chunked with a
+/// trailing checksum.
+fn put_body(headers: &HeaderMap, body: Bytes) -> Vec<u8> {
+    let chunked = headers
+        .get("content-encoding")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("aws-chunked"));
+    if !chunked {
+        return body.to_vec();
+    }
+    let mut data = Vec::new();
+    let mut rest = &body[..];
+    loop {
+        let line_end = rest.windows(2).position(|w| w == b"\r\n").unwrap();
+        let size_field = std::str::from_utf8(&rest[..line_end]).unwrap();
+        let size = usize::from_str_radix(size_field.split(';').next().unwrap(), 16).unwrap();
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            return data;
+        }
+        data.extend_from_slice(&rest[..size]);
+        rest = &rest[size + 2..];
+    }
+}
+
+fn list(objects: &Objects, uri: &Uri) -> Response {
+    let prefix = query_param(uri, "prefix").unwrap_or_default();
+    let start_after = query_param(uri, "continuation-token")
+        .or_else(|| query_param(uri, "start-after"))
+        .unwrap_or_default();
+    let max_keys: usize = query_param(uri, "max-keys")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1000);
+
+    let mut matching = objects
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix) && key.as_str() > start_after.as_str());
+    let page: Vec<_> = matching.by_ref().take(max_keys).collect();
+    let truncated = matching.next().is_some();
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">",
+    );
+    xml.push_str(&format!("<Name>{BUCKET}</Name><Prefix>{prefix}</Prefix>"));
+    xml.push_str(&format!(
+        "<KeyCount>{}</KeyCount><MaxKeys>{max_keys}</MaxKeys><IsTruncated>{truncated}</IsTruncated>",
+        page.len()
+    ));
+    if truncated {
+        let last = page.last().map(|(key, _)| key.as_str()).unwrap_or_default();
+        xml.push_str(&format!(
+            "<NextContinuationToken>{last}</NextContinuationToken>"
+        ));
+    }
+    for (key, (data, etag)) in page {
+        xml.push_str(&format!(
+            "<Contents><Key>{key}</Key><LastModified>2026-01-01T00:00:00.000Z</LastModified><ETag>{}</ETag><Size>{}</Size></Contents>",
+            etag.replace('"', "&quot;"),
+            data.len()
+        ));
+    }
+    xml.push_str("</ListBucketResult>");
+    (StatusCode::OK, [("content-type", "application/xml")], xml).into_response()
+}
+
+fn handle(
+    objects: &Mutex<Objects>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = percent_decode(uri.path().trim_start_matches('/'));
+    let key = match path.split_once('/') {
+        Some((_, key)) if !key.is_empty() => key.to_string(),
+        _ => {
+            return match method {
+                Method::GET => list(&objects.lock().unwrap(), &uri),
+                _ => error(StatusCode::NOT_IMPLEMENTED, "NotImplemented"),
+            }
+        }
+    };
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let mut objects = objects.lock().unwrap();
+    let current = objects.get(&key).map(|(_, etag)| etag.clone());
+
+    match method {
+        Method::GET | Method::HEAD => {
+            let Some((data, etag)) = objects.get(&key) else {
+                return error(StatusCode::NOT_FOUND, "NoSuchKey");
+            };
+            let total = data.len();
+            let range = header("range").and_then(|range| {
+                let (first, last) = range.strip_prefix("bytes=")?.split_once('-')?;
+                let first: usize = first.parse().ok()?;
+                let last = last
+                    .parse()
+                    .unwrap_or(usize::MAX)
+                    .min(total.saturating_sub(1));
+                Some((first, last))
+            });
+            let (status, body, content_range) = match range {
+                Some((first, _)) if first >= total => {
+                    return error(StatusCode::RANGE_NOT_SATISFIABLE, "InvalidRange");
+                }
+                Some((first, last)) => (
+                    StatusCode::PARTIAL_CONTENT,
+                    data[first..=last].to_vec(),
+                    Some(format!("bytes {first}-{last}/{total}")),
+                ),
+                None => (StatusCode::OK, data.clone(), None),
+            };
+            let mut response = Response::builder()
+                .status(status)
+                .header("etag", etag)
+                .header("content-length", body.len());
+            if let Some(content_range) = content_range {
+                response = response.header("content-range", content_range);
+            }
+            let body = match method {
+                Method::HEAD => Vec::new(),
+                _ => body,
+            };
+            response.body(body.into()).unwrap()
+        }
+        Method::PUT => {
+            if header("if-match").is_some_and(|expected| current.as_deref() != Some(expected))
+                || (header("if-none-match") == Some("*") && current.is_some())
+            {
+                return error(StatusCode::PRECONDITION_FAILED, "PreconditionFailed");
+            }
+            if let Some(source) = header("x-amz-copy-source") {
+                let source = percent_decode(source.trim_start_matches('/'));
+                let source_key = source
+                    .split_once('/')
+                    .map(|(_, key)| key)
+                    .unwrap_or_default();
+                let Some((data, etag)) = objects.get(source_key).cloned() else {
+                    return error(StatusCode::NOT_FOUND, "NoSuchKey");
+                };
+                objects.insert(key, (data, etag.clone()));
+                let xml = format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\"?><CopyObjectResult><ETag>{}</ETag><LastModified>2026-01-01T00:00:00.000Z</LastModified></CopyObjectResult>",
+                    etag.replace('"', "&quot;")
+                );
+                return (StatusCode::OK, [("content-type", "application/xml")], xml)
+                    .into_response();
+            }
+            let data = put_body(&headers, body);
+            let etag = etag(&data);
+            objects.insert(key, (data, etag.clone()));
+            (StatusCode::OK, [("etag", etag)]).into_response()
+        }
+        Method::DELETE => {
+            if header("if-match").is_some_and(|expected| current.as_deref() != Some(expected)) {
+                return error(StatusCode::PRECONDITION_FAILED, "PreconditionFailed");
+            }
+            objects.remove(&key);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        _ => error(StatusCode::NOT_IMPLEMENTED, "NotImplemented"),
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use aws_sdk_s3::types::{
+    AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, ExpirationStatus,
+    LifecycleExpiration, LifecycleRule, LifecycleRuleFilter, Transition, TransitionStorageClass,
+};
+use docx_storage_core::StorageError;
+use serde::{Deserialize, Serialize};
+
+/// Most rules R2 accepts on one bucket.
+const MAX_RULES: usize = 1000;
+
+/// Declarative object lifecycle rules for the R2 bucket, read from a JSON file:
+///
+/// ```json
+/// {
+///   "rules": [
+///     { "id": "wal-to-ia", "prefix": "acme/sessions/", "transition_days": 30 },
+///     { "id": "stale-uploads", "abort_multipart_days": 7 }
+///   ]
+/// }
+/// ```
+///
+/// The rules replace whatever the bucket has; an empty list removes them all.
+/// Prefixes match keys from the start, and every key starts with the tenant
+/// (see [`super::R2Storage`]), so rules are per tenant unless the prefix is
+/// empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleRules {
+    #[serde(default)]
+    pub rules: Vec<LifecycleRuleSpec>,
+}
+
+/// One lifecycle rule: objects under `prefix` move to Infrequent Access after
+/// `transition_days` and/or are deleted after `expire_days`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleRuleSpec {
+    pub id: String,
+    /// Key prefix the rule applies to (empty: the whole bucket)
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Days after creation before objects move to Infrequent Access
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transition_days: Option<i32>,
+    /// Days after creation before objects are deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_days: Option<i32>,
+    /// Days after initiation before incomplete multipart uploads are aborted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_multipart_days: Option<i32>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Prefix of rules written before filters existed.
+#[allow(deprecated)]
+fn legacy_prefix(rule: &LifecycleRule) -> Option<&str> {
+    rule.prefix()
+}
+
+impl LifecycleRules {
+    /// Read and validate rules from a JSON file.
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let data = std::fs::read(path).map_err(|e| {
+            StorageError::Io(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let rules: Self = serde_json::from_slice(&data).map_err(|e| {
+            StorageError::InvalidArgument(format!(
+                "Invalid lifecycle rules in {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Check the rules the way R2 would, so a bad file fails before anything
+    /// is replaced.
+    pub fn validate(&self) -> Result<(), StorageError> {
+        let invalid = |msg: String| Err(StorageError::InvalidArgument(msg));
+        if self.rules.len() > MAX_RULES {
+            return invalid(format!("at most {} lifecycle rules are allowed", MAX_RULES));
+        }
+
+        let mut ids = std::collections::HashSet::new();
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() {
+                return invalid("lifecycle rule IDs must not be empty".to_string());
+            }
+            if !ids.insert(rule.id.as_str()) {
+                return invalid(format!("duplicate lifecycle rule ID '{}'", rule.id));
+            }
+            let days = [rule.transition_days, rule.expire_days, rule.abort_multipart_days];
+            if days.iter().all(Option::is_none) {
+                return invalid(format!("lifecycle rule '{}' has no action", rule.id));
+            }
+            if days.iter().flatten().any(|d| *d < 1) {
+                return invalid(format!("lifecycle rule '{}': days must be at least 1", rule.id));
+            }
+            if let (Some(transition), Some(expire)) = (rule.transition_days, rule.expire_days) {
+                if expire <= transition {
+                    return invalid(format!(
+                        "lifecycle rule '{}': expire_days must be after transition_days",
+                        rule.id
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Enabled rules that delete objects.
+    pub fn expiring(&self) -> impl Iterator<Item = &LifecycleRuleSpec> {
+        self.rules.iter().filter(|r| r.enabled && r.expire_days.is_some())
+    }
+
+    /// The bucket configuration for these rules, `None` when there are none.
+    pub fn to_s3(&self) -> Result<Option<BucketLifecycleConfiguration>, StorageError> {
+        if self.rules.is_empty() {
+            return Ok(None);
+        }
+        let rules = self
+            .rules
+            .iter()
+            .map(LifecycleRuleSpec::to_s3)
+            .collect::<Result<Vec<_>, _>>()?;
+        BucketLifecycleConfiguration::builder()
+            .set_rules(Some(rules))
+            .build()
+            .map(Some)
+            .map_err(|e| StorageError::Internal(format!("Invalid lifecycle configuration: {}", e)))
+    }
+
+    /// Rules of a bucket configuration. Actions these rules can't express
+    /// (dated transitions, noncurrent versions, tag filters) are left out.
+    pub fn from_s3(rules: &[LifecycleRule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .map(|rule| LifecycleRuleSpec {
+                    id: rule.id().unwrap_or_default().to_string(),
+                    prefix: rule
+                        .filter()
+                        .and_then(LifecycleRuleFilter::prefix)
+                        .or(legacy_prefix(rule))
+                        .unwrap_or_default()
+                        .to_string(),
+                    enabled: *rule.status() == ExpirationStatus::Enabled,
+                    transition_days: rule.transitions().iter().find_map(Transition::days),
+                    expire_days: rule.expiration().and_then(LifecycleExpiration::days),
+                    abort_multipart_days: rule
+                        .abort_incomplete_multipart_upload()
+                        .and_then(AbortIncompleteMultipartUpload::days_after_initiation),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl LifecycleRuleSpec {
+    fn to_s3(&self) -> Result<LifecycleRule, StorageError> {
+        let mut rule = LifecycleRule::builder()
+            .id(&self.id)
+            .filter(LifecycleRuleFilter::builder().prefix(&self.prefix).build())
+            .status(if self.enabled {
+                ExpirationStatus::Enabled
+            } else {
+                ExpirationStatus::Disabled
+            });
+        if let Some(days) = self.transition_days {
+            rule = rule.transitions(
+                Transition::builder()
+                    .days(days)
+                    .storage_class(TransitionStorageClass::StandardIa)
+                    .build(),
+            );
+        }
+        if let Some(days) = self.expire_days {
+            rule = rule.expiration(LifecycleExpiration::builder().days(days).build());
+        }
+        if let Some(days) = self.abort_multipart_days {
+            rule = rule.abort_incomplete_multipart_upload(
+                AbortIncompleteMultipartUpload::builder()
+                    .days_after_initiation(days)
+                    .build(),
+            );
+        }
+        rule.build().map_err(|e| {
+            StorageError::Internal(format!("Invalid lifecycle rule '{}': {}", self.id, e))
+        })
+    }
+
+    /// Whether the rule can reach keys starting with `prefix`.
+    pub fn overlaps(&self, prefix: &str) -> bool {
+        self.prefix.starts_with(prefix) || prefix.starts_with(&self.prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, transition_days: Option<i32>, expire_days: Option<i32>) -> LifecycleRuleSpec {
+        LifecycleRuleSpec {
+            id: id.to_string(),
+            prefix: "acme/".to_string(),
+            enabled: true,
+            transition_days,
+            expire_days,
+            abort_multipart_days: None,
+        }
+    }
+
+    fn validate(rules: Vec<LifecycleRuleSpec>) -> Result<(), StorageError> {
+        LifecycleRules { rules }.validate()
+    }
+
+    #[test]
+    fn test_expiry_must_come_after_the_transition() {
+        assert!(validate(vec![rule("r", Some(30), Some(30))]).is_err());
+        assert!(validate(vec![rule("r", Some(30), Some(29))]).is_err());
+        assert!(validate(vec![rule("r", Some(30), Some(31))]).is_ok());
+    }
+
+    #[test]
+    fn test_days_start_at_one() {
+        assert!(validate(vec![rule("r", None, Some(0))]).is_err());
+        assert!(validate(vec![rule("r", None, Some(1))]).is_ok());
+        assert!(validate(vec![rule("r", Some(0), None)]).is_err());
+        assert!(validate(vec![rule("r", Some(1), None)]).is_ok());
+    }
+
+    #[test]
+    fn test_rules_need_an_action_and_a_unique_id() {
+        assert!(validate(vec![rule("r", None, None)]).is_err());
+        assert!(validate(vec![rule(" ", None, Some(1))]).is_err());
+        assert!(validate(vec![rule("r", None, Some(1)), rule("r", None, Some(2))]).is_err());
+    }
+
+    #[test]
+    fn test_at_most_max_rules_are_allowed() {
+        let rules = |count: usize| {
+            (0..count)
+                .map(|i| rule(&format!("r{}", i), None, Some(1)))
+                .collect()
+        };
+
+        assert!(validate(rules(MAX_RULES)).is_ok());
+        assert!(validate(rules(MAX_RULES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_rules_round_trip_through_the_bucket_configuration() {
+        let mut disabled = rule("old-uploads", None, None);
+        disabled.enabled = false;
+        disabled.prefix = String::new();
+        disabled.abort_multipart_days = Some(7);
+        let rules = LifecycleRules {
+            rules: vec![rule("archive", Some(30), Some(365)), disabled],
+        };
+
+        let configuration = rules.to_s3().unwrap().unwrap();
+
+        assert_eq!(LifecycleRules::from_s3(configuration.rules()), rules);
+        assert!(LifecycleRules::default().to_s3().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_expiring_lists_enabled_rules_that_delete() {
+        let mut disabled = rule("disabled", None, Some(10));
+        disabled.enabled = false;
+        let rules = LifecycleRules {
+            rules: vec![
+                rule("archive", Some(30), None),
+                disabled,
+                rule("expire", None, Some(5)),
+            ],
+        };
+
+        let ids: Vec<_> = rules.expiring().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["expire"]);
+    }
+
+    #[test]
+    fn test_overlaps_matches_prefixes_either_way() {
+        let spec = rule("r", None, Some(1));
+
+        assert!(spec.overlaps("acme/sessions/"));
+        assert!(spec.overlaps("ac"));
+        assert!(spec.overlaps(""));
+        assert!(!spec.overlaps("globex/"));
+    }
+}
@@ -1,6 +1,27 @@
+mod cache;
+mod contention;
+#[cfg(test)]
+pub(crate) mod fake_s3;
+mod lifecycle;
+mod placement;
+mod public_endpoint;
 mod r2;
+mod retry;
+mod snapshot;
+mod tenant_buckets;
+mod tenant_snapshot;
+mod usage;
 
-pub use r2::R2Storage;
+pub use cache::DiskCache;
+pub use contention::ContentionTracker;
+pub use lifecycle::LifecycleRules;
+pub use placement::BucketPlacement;
+pub use public_endpoint::PublicResolver;
+pub use r2::{R2Storage, MAX_PRESIGNED_UPLOAD_TTL, MAX_PRESIGNED_URL_TTL};
+pub use retry::{parse_retry_override, R2RetryPolicy, RetryOverride, RetrySettings};
+pub use snapshot::ListSnapshots;
+pub use tenant_buckets::{TenantBucket, TenantBuckets};
+pub use usage::{R2Rates, UsageMeter};
 
 // Re-export from core
 pub use docx_storage_core::{SessionIndexEntry, StorageBackend, WalEntry};
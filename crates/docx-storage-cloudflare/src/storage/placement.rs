@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use docx_storage_core::{owning_tenant, StorageError};
+
+use super::{R2Storage, TenantBuckets};
+
+/// Where each tenant's data lives: one R2 bucket per region, tenants pinned
+/// to a region, and the rest in the default region. Tenants that registered
+/// their own bucket are served from it instead.
+///
+/// Every key operation resolves its bucket here. Pins are fixed at startup:
+/// pinning a tenant that already has data elsewhere does not move it.
+pub struct BucketPlacement {
+    buckets: BTreeMap<String, Arc<R2Storage>>,
+    default_region: String,
+    pins: HashMap<String, String>,
+    tenant_buckets: Option<Arc<TenantBuckets>>,
+}
+
+impl BucketPlacement {
+    /// Place every tenant in `default_region`, served by `bucket`.
+    pub fn new(default_region: &str, bucket: Arc<R2Storage>) -> Self {
+        Self {
+            buckets: BTreeMap::from([(default_region.to_string(), bucket)]),
+            default_region: default_region.to_string(),
+            pins: HashMap::new(),
+            tenant_buckets: None,
+        }
+    }
+
+    /// Add the bucket serving `region`.
+    pub fn with_bucket(mut self, region: &str, bucket: Arc<R2Storage>) -> Result<Self, StorageError> {
+        if self.buckets.insert(region.to_string(), bucket).is_some() {
+            return Err(StorageError::InvalidArgument(format!(
+                "region '{}' has more than one bucket",
+                region
+            )));
+        }
+        Ok(self)
+    }
+
+    /// Tenants without a pin go to `region` instead of the first bucket's.
+    pub fn with_default_region(mut self, region: &str) -> Result<Self, StorageError> {
+        self.check_region(region)?;
+        self.default_region = region.to_string();
+        Ok(self)
+    }
+
+    /// Keep `tenant_id`'s data in `region`.
+    pub fn pin(mut self, tenant_id: &str, region: &str) -> Result<Self, StorageError> {
+        self.check_region(region)?;
+        self.pins.insert(tenant_id.to_string(), region.to_string());
+        Ok(self)
+    }
+
+    /// Serve tenants that registered their own bucket from it instead of
+    /// their region's.
+    pub fn with_tenant_buckets(mut self, tenant_buckets: Arc<TenantBuckets>) -> Self {
+        self.tenant_buckets = Some(tenant_buckets);
+        self
+    }
+
+    /// Refuse any bucket outside `allowed` (e.g. only "eu" for EU-only
+    /// storage). An empty list allows every region. Buckets registered by
+    /// tenants are checked against the same list when they are loaded (see
+    /// [`TenantBuckets::with_allowed_regions`]).
+    pub fn restrict_to(self, allowed: &[String]) -> Result<Self, StorageError> {
+        if allowed.is_empty() {
+            return Ok(self);
+        }
+        match self.buckets.keys().find(|r| !allowed.contains(r)) {
+            Some(region) => Err(StorageError::InvalidArgument(format!(
+                "region '{}' is not allowed (allowed: {})",
+                region,
+                allowed.join(", ")
+            ))),
+            None => Ok(self),
+        }
+    }
+
+    /// The region holding `tenant_id`'s data. Sandboxes stay with their tenant.
+    pub fn region_for(&self, tenant_id: &str) -> &str {
+        self.pins
+            .get(owning_tenant(tenant_id))
+            .unwrap_or(&self.default_region)
+    }
+
+    /// The bucket holding `tenant_id`'s data: its own when it registered
+    /// one, refused when that bucket can't be opened.
+    pub fn bucket_for(&self, tenant_id: &str) -> Result<Arc<R2Storage>, StorageError> {
+        if let Some(own) = self.tenant_buckets.as_ref().and_then(|b| b.get(tenant_id)) {
+            return own;
+        }
+        Ok(self.buckets[self.region_for(tenant_id)].clone())
+    }
+
+    /// Whether the `region` bucket serves `tenant_id`'s data: the tenant is
+    /// placed in that region and has no bucket of its own.
+    pub fn serves(&self, region: &str, tenant_id: &str) -> bool {
+        self.region_for(tenant_id) == region
+            && self
+                .tenant_buckets
+                .as_ref()
+                .is_none_or(|b| b.get(tenant_id).is_none())
+    }
+
+    /// Buckets registered by tenants, by tenant.
+    pub fn tenant_buckets(&self) -> Vec<(String, Arc<R2Storage>)> {
+        self.tenant_buckets.as_ref().map(|b| b.all()).unwrap_or_default()
+    }
+
+    /// The bucket of `region`; the default region's for an empty name.
+    pub fn bucket(&self, region: &str) -> Result<&Arc<R2Storage>, StorageError> {
+        let region = if region.is_empty() { &self.default_region } else { region };
+        self.buckets.get(region).ok_or_else(|| self.unknown_region(region))
+    }
+
+    /// Buckets by region, sorted by region.
+    pub fn buckets(&self) -> impl Iterator<Item = (&str, &Arc<R2Storage>)> {
+        self.buckets.iter().map(|(region, bucket)| (region.as_str(), bucket))
+    }
+
+    fn check_region(&self, region: &str) -> Result<(), StorageError> {
+        match self.buckets.contains_key(region) {
+            true => Ok(()),
+            false => Err(self.unknown_region(region)),
+        }
+    }
+
+    fn unknown_region(&self, region: &str) -> StorageError {
+        StorageError::InvalidArgument(format!(
+            "no bucket for region '{}' (regions: {})",
+            region,
+            self.buckets.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::config::{BehaviorVersion, Region};
+
+    fn bucket(name: &str) -> Arc<R2Storage> {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("auto"))
+            .build();
+        Arc::new(R2Storage::new(
+            aws_sdk_s3::Client::from_conf(config),
+            name.to_string(),
+        ))
+    }
+
+    fn two_regions() -> (BucketPlacement, Arc<R2Storage>, Arc<R2Storage>) {
+        let (eu, us) = (bucket("docs-eu"), bucket("docs-us"));
+        let placement = BucketPlacement::new("eu", eu.clone())
+            .with_bucket("us", us.clone())
+            .unwrap()
+            .pin("acme", "us")
+            .unwrap();
+        (placement, eu, us)
+    }
+
+    #[test]
+    fn test_tenants_go_to_their_pinned_region_or_the_default_one() {
+        let (placement, eu, us) = two_regions();
+
+        assert_eq!(placement.region_for("acme"), "us");
+        assert!(Arc::ptr_eq(&placement.bucket_for("acme").unwrap(), &us));
+        assert_eq!(placement.region_for("globex"), "eu");
+        assert!(Arc::ptr_eq(&placement.bucket_for("globex").unwrap(), &eu));
+        assert!(placement.serves("us", "acme"));
+        assert!(!placement.serves("eu", "acme"));
+    }
+
+    #[test]
+    fn test_sandboxes_stay_with_their_tenant() {
+        let (placement, _, us) = two_regions();
+        let sandbox = format!("acme{}", docx_storage_core::SANDBOX_SUFFIX);
+
+        assert_eq!(placement.region_for(&sandbox), "us");
+        assert!(Arc::ptr_eq(&placement.bucket_for(&sandbox).unwrap(), &us));
+    }
+
+    #[test]
+    fn test_default_region_can_be_moved() {
+        let (placement, _, us) = two_regions();
+        let placement = placement.with_default_region("us").unwrap();
+
+        assert_eq!(placement.region_for("globex"), "us");
+        assert!(Arc::ptr_eq(placement.bucket("").unwrap(), &us));
+    }
+
+    #[test]
+    fn test_unknown_regions_are_refused() {
+        let (placement, _, _) = two_regions();
+
+        assert!(placement.bucket("ap").is_err());
+        assert!(placement.pin("globex", "ap").is_err());
+        let (placement, _, _) = two_regions();
+        assert!(placement.with_default_region("ap").is_err());
+    }
+
+    #[test]
+    fn test_a_region_has_one_bucket() {
+        let result = BucketPlacement::new("eu", bucket("a")).with_bucket("eu", bucket("b"));
+
+        assert!(matches!(result, Err(StorageError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_restrict_to_refuses_buckets_outside_the_allowed_regions() {
+        let (placement, _, _) = two_regions();
+        assert!(placement.restrict_to(&["eu".to_string()]).is_err());
+
+        let (placement, _, _) = two_regions();
+        let placement = placement.restrict_to(&[]).unwrap();
+        assert_eq!(
+            placement
+                .buckets()
+                .map(|(region, _)| region)
+                .collect::<Vec<_>>(),
+            ["eu", "us"]
+        );
+    }
+}
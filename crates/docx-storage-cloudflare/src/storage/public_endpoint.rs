@@ -0,0 +1,139 @@
+//! Guards for endpoints supplied by tenants.
+//!
+//! A tenant registering its own bucket chooses the endpoint the storage
+//! server sends signed S3 requests to. Only public HTTPS hosts are accepted:
+//! [`check_endpoint`] refuses local names and non-public IP literals when the
+//! bucket is opened, and [`PublicResolver`] refuses names resolving to
+//! non-public addresses when connecting, so DNS can't be used to point the
+//! server at its own network.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns, ResolveDnsError};
+
+/// Host names that never leave the deployment.
+const LOCAL_SUFFIXES: &[&str] = &[".localhost", ".local", ".internal", ".localdomain"];
+
+/// Fail unless `endpoint` is an HTTPS URL on a public host.
+pub fn check_endpoint(endpoint: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(endpoint).map_err(|e| format!("invalid endpoint: {}", e))?;
+    if url.scheme() != "https" {
+        return Err("endpoint must use https".to_string());
+    }
+    let host = url.host_str().ok_or("endpoint has no host")?;
+    if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        return check_ip(ip);
+    }
+    let name = host.trim_end_matches('.').to_ascii_lowercase();
+    if name == "localhost"
+        || !name.contains('.')
+        || LOCAL_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+    {
+        return Err(format!("endpoint host '{}' is not public", name));
+    }
+    Ok(())
+}
+
+fn check_ip(ip: IpAddr) -> Result<(), String> {
+    match is_public(ip) {
+        true => Ok(()),
+        false => Err(format!("endpoint address {} is not public", ip)),
+    }
+}
+
+/// Whether `ip` is routable on the internet: not loopback, private (RFC 1918,
+/// unique local), link-local (including cloud metadata), CGNAT, multicast or
+/// unspecified.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..=127).contains(&b)))
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // unique local
+        || (first & 0xffc0) == 0xfe80 // link-local
+        || (first & 0xffc0) == 0xfec0) // site-local
+}
+
+/// DNS resolver keeping only public addresses, for the S3 clients of
+/// tenant buckets. IP literals don't go through it: [`check_endpoint`]
+/// covers them.
+#[derive(Debug, Clone, Default)]
+pub struct PublicResolver;
+
+impl ResolveDns for PublicResolver {
+    fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+        DnsFuture::new(async move {
+            let addresses = tokio::net::lookup_host((name, 0))
+                .await
+                .map_err(ResolveDnsError::new)?
+                .map(|addr| addr.ip())
+                .collect::<Vec<_>>();
+            let public: Vec<_> = addresses.iter().copied().filter(|ip| is_public(*ip)).collect();
+            if public.is_empty() {
+                return Err(ResolveDnsError::new(format!(
+                    "{} resolves to no public address ({:?})",
+                    name, addresses
+                )));
+            }
+            Ok(public)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_endpoint_refuses_local_hosts() {
+        assert!(check_endpoint("https://s3.eu-west-1.amazonaws.com").is_ok());
+        assert!(check_endpoint("https://abc.r2.cloudflarestorage.com/").is_ok());
+        assert!(check_endpoint("https://8.8.8.8").is_ok());
+
+        for endpoint in [
+            "http://s3.amazonaws.com",
+            "https://localhost:9000",
+            "https://minio",
+            "https://metadata.google.internal",
+            "https://storage.local",
+            "https://127.0.0.1",
+            "https://10.0.0.5:9000",
+            "https://169.254.169.254",
+            "https://100.64.0.1",
+            "https://[::1]",
+            "https://[fd00::1]",
+            "https://[::ffff:192.168.1.1]",
+            "not a url",
+        ] {
+            assert!(check_endpoint(endpoint).is_err(), "{} was accepted", endpoint);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_refuses_names_resolving_locally() {
+        let err = PublicResolver.resolve_dns("localhost").await.unwrap_err();
+        assert!(std::error::Error::source(&err).unwrap().to_string().contains("no public address"));
+    }
+}
@@ -1,19 +1,75 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
+use aws_sdk_s3::error::{DisplayErrorContext, SdkError};
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::Client as S3Client;
 use docx_storage_core::{
-    CheckpointInfo, SessionIndex, SessionInfo, StorageBackend, StorageError, WalEntry,
+    admin_log_jsonl, admin_log_page, buffer_chunks, chain_events, check_wal_positions, delete_unreferenced_media,
+    digest_chunks, document_digest, ensure_not_held, feature, history_jsonl, media_references, parse_admin_log,
+    parse_history, parse_retry_after, parse_wal_entries, prepare_wal_entries, restore_media, sleep_before_retry,
+    store_media, tail_page, wal_jsonl, AdminLogEntry, BufferedChunks, Capabilities, CheckpointInfo, ChunkStream,
+    CircuitBreaker, CircuitBreakerStats, HistoryEvent, HistoryLink, LegalHold, LibraryItemInfo, LibraryKind,
+    Reloadable, SessionIndex, SessionIndexEntry, SessionInfo, SessionPage, SessionPageQuery, StorageBackend,
+    StorageError, WalEntry, WalOffsetIndex, MEDIA_MAX_BUFFERED_BYTES,
 };
-use tracing::{debug, instrument, warn};
-
-/// Maximum retries for transient errors (429 / 5xx).
-const MAX_RETRIES: u32 = 5;
-/// Base delay for exponential backoff.
-const BASE_DELAY_MS: u64 = 200;
-/// Maximum retries for CAS (compare-and-swap) loops.
-const CAS_MAX_RETRIES: u32 = 10;
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, instrument, warn};
+
+use super::cache::DiskCache;
+use super::contention::{CasObject, ContentionTracker};
+use super::lifecycle::LifecycleRules;
+use super::retry::{R2Operation, R2RetryPolicy};
+use super::tenant_snapshot::{SnapshotObject, TenantSnapshot, MAX_SNAPSHOT_ROUNDS};
+use super::usage::{OpClass, UsageMeter};
+
+/// Size of the parts streamed uploads are sent in. Uploads that fit in one
+/// part are sent with a plain PUT; R2 requires parts but the last to have
+/// the same size, of at least 5 MiB.
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Index writes counted over the contention window before a conflict rate
+/// is trusted enough to shard a tenant's whole index.
+const MIN_SHARD_OPERATIONS: u64 = 20;
+
+/// Largest object a streamed upload can store: R2 allows 10,000 parts per
+/// multipart upload.
+const MAX_OBJECT_BYTES: u64 = 10_000 * MULTIPART_PART_SIZE as u64;
+
+/// Longest a presigned URL can stay valid: SigV4 caps it at 7 days.
+pub const MAX_PRESIGNED_URL_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Longest a presigned upload URL stays valid: whoever holds it can store a
+/// document until then, so it only needs to outlast one transfer.
+pub const MAX_PRESIGNED_UPLOAD_TTL: Duration = Duration::from_secs(15 * 60);
+
+const DOCX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Largest object a presigned PUT can store (R2's single-part limit).
+const MAX_PUT_BYTES: u64 = 5 * 1024 * 1024 * 1024 - 5 * 1024 * 1024;
+
+/// Aliases kept in an index manifest, by session ID.
+type Aliases = BTreeMap<String, String>;
+
+/// URL granting one request on one object, see
+/// [`R2Storage::presign_download`] and [`R2Storage::presign_upload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresignedUrl {
+    pub url: String,
+    /// Headers the request must carry as they are
+    pub headers: Vec<(String, String)>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// Set on uploads: what [`R2Storage::commit_upload`] takes once the PUT
+    /// succeeded
+    pub upload_id: Option<String>,
+}
 
 /// R2 storage backend using Cloudflare R2 (S3-compatible) with ETag-based optimistic locking.
 ///
@@ -21,16 +77,64 @@ const CAS_MAX_RETRIES: u32 = 10;
 /// ```
 /// {bucket}/
 ///   {tenant_id}/
-///     index.json                     # Session index (was in KV, now in R2)
+///     index.json                     # Index manifest: the tenant-wide fields and aliases
+///     admin.log                      # Admin actions (JSONL), see append_admin_log
+///     index/
+///       {session_id}.json            # Index entry of each session
 ///     sessions/
 ///       {session_id}.docx            # Session document
 ///       {session_id}.wal             # WAL file (JSONL format)
+///       {session_id}.wal.idx         # Sparse WAL offset index (JSON)
+///       {session_id}.chain           # History chain (JSONL), see with_history_chain
 ///       {session_id}.ckpt.{pos}.docx # Checkpoint files
+///     templates/
+///       {name}.docx                  # Template library
+///     snippets/
+///       {name}.docx                  # Snippet library
+///     glossaries/
+///       {name}.json                  # Glossaries (terminology rules)
+///     profiles/
+///       {name}.json                  # Tenant profile (template variables)
+///     media/
+///       {sha256}.bin                 # Images shared by sessions, see with_media_dedup
+///     snapshots/
+///       {snapshot_id}/               # Tenant snapshot copies + manifest.json
+///     uploads/
+///       {session_id}[.ckpt.{pos}].{upload_id}.docx  # Presigned uploads awaiting commit_upload
 /// ```
+///
+/// The session index is split so that CAS writes to different sessions don't
+/// contend: `index.json` only holds the tenant-wide fields and the session
+/// aliases (unique across sessions, so changed under its ETag) and is
+/// rewritten when they change, each entry lives in its own object. Indexes stored
+/// whole in `index.json` by older servers are still read, and split once
+/// their writes conflict enough (see [`R2Storage::with_index_sharding`]).
+///
+/// Session and checkpoint documents can optionally be cached on local disk
+/// (see [`DiskCache`]). The index and WAL are never cached since they are
+/// updated through CAS and must always be read fresh.
+///
+/// Transient errors and CAS conflicts are retried according to an
+/// [`R2RetryPolicy`]; every retry is logged with `operation`, `attempt`,
+/// `max_retries`, `delay_ms` and `outcome` fields.
 #[derive(Clone)]
 pub struct R2Storage {
     s3_client: S3Client,
     bucket_name: String,
+    cache: Option<Arc<DiskCache>>,
+    breaker: Arc<CircuitBreaker>,
+    retry: Reloadable<R2RetryPolicy>,
+    meter: Option<Arc<UsageMeter>>,
+    /// Move images out of saved packages into `media/` (loads always put them back)
+    media_dedup: bool,
+    /// Record WAL appends, truncations and checkpoints in a hash chain
+    history_chain: bool,
+    /// CAS conflicts per tenant
+    contention: Arc<ContentionTracker>,
+    /// Conflict rate of index writes above which a whole index is split
+    index_shard_conflict_rate: f64,
+    /// Index entries by object key, with their ETags
+    index_entries: Arc<Mutex<HashMap<String, (String, SessionIndexEntry)>>>,
 }
 
 impl R2Storage {
@@ -39,9 +143,103 @@ impl R2Storage {
         Self {
             s3_client,
             bucket_name,
+            cache: None,
+            breaker: Arc::new(CircuitBreaker::new("R2")),
+            retry: Reloadable::default(),
+            meter: None,
+            media_dedup: false,
+            history_chain: false,
+            contention: Arc::new(ContentionTracker::default()),
+            index_shard_conflict_rate: 0.0,
+            index_entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Store the images of saved sessions and checkpoints once per tenant,
+    /// under `media/`, instead of in every package (see [`store_media`]).
+    /// Packages saved with deduplicated media load fine either way.
+    pub fn with_media_dedup(mut self, enabled: bool) -> Self {
+        self.media_dedup = enabled;
+        self
+    }
+
+    /// Keep a tamper-evident chain of each session's WAL appends,
+    /// truncations and checkpoints beside its WAL (see
+    /// [`prove_history`](docx_storage_core::prove_history)).
+    pub fn with_history_chain(mut self, enabled: bool) -> Self {
+        self.history_chain = enabled;
+        self
+    }
+
+    /// Count CAS conflicts in `tracker` (shared by every bucket, so reports
+    /// cover all regions).
+    pub fn with_contention_tracker(mut self, tracker: Arc<ContentionTracker>) -> Self {
+        self.contention = tracker;
+        self
+    }
+
+    /// Split a tenant's index still stored whole in `index.json` (by an
+    /// older server) once its writes conflict at `conflict_rate` or more
+    /// over the contention window; at 0 (the default) it is split on its
+    /// next write. Indexes written by this server are always split.
+    pub fn with_index_sharding(mut self, conflict_rate: f64) -> Self {
+        self.index_shard_conflict_rate = conflict_rate;
+        self
+    }
+
+    /// Whether a whole index of `tenant_id` should be split now.
+    fn split_due(&self, tenant_id: &str) -> bool {
+        if self.index_shard_conflict_rate <= 0.0 {
+            return true;
+        }
+        let recent = self.contention.recent(tenant_id, CasObject::Index);
+        recent.operations >= MIN_SHARD_OPERATIONS
+            && recent.conflict_rate() >= self.index_shard_conflict_rate
+    }
+
+    /// The package to write for `data`: with its media moved out when
+    /// deduplication is on.
+    async fn package_to_save(
+        &self,
+        tenant_id: &str,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        if !self.media_dedup {
+            return Ok(None);
+        }
+        store_media(self, tenant_id, data).await
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, policy: R2RetryPolicy) -> Self {
+        self.retry = Reloadable::new(policy);
+        self
+    }
+
+    /// Handle to replace the retry policy while the server runs.
+    pub fn retry_policy(&self) -> Reloadable<R2RetryPolicy> {
+        self.retry.clone()
+    }
+
+    /// Count requests and bytes per tenant, day and RPC in `meter`.
+    pub fn with_usage_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    /// Count bytes transferred to or from `key`, when metering.
+    fn meter_bytes(&self, key: &str, read: u64, written: u64) {
+        if let Some(meter) = &self.meter {
+            meter.record_bytes(key, read, written);
         }
     }
 
+    /// Serve session and checkpoint loads from a local disk cache.
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
     /// Get the S3 key for a session document.
     fn session_key(&self, tenant_id: &str, session_id: &str) -> String {
         format!("{}/sessions/{}.docx", tenant_id, session_id)
@@ -52,25 +250,168 @@ impl R2Storage {
         format!("{}/sessions/{}.wal", tenant_id, session_id)
     }
 
+    /// Get the S3 key for a session WAL's offset index.
+    fn wal_index_key(&self, tenant_id: &str, session_id: &str) -> String {
+        format!("{}/sessions/{}.wal.idx", tenant_id, session_id)
+    }
+
+    /// Get the S3 key for a session's history chain.
+    fn history_key(&self, tenant_id: &str, session_id: &str) -> String {
+        format!("{}/sessions/{}.chain", tenant_id, session_id)
+    }
+
     /// Get the S3 key for a checkpoint.
     fn checkpoint_key(&self, tenant_id: &str, session_id: &str, position: u64) -> String {
         format!("{}/sessions/{}.ckpt.{}.docx", tenant_id, session_id, position)
     }
 
+    /// Get the S3 key a presigned upload of a session's document (or its
+    /// checkpoint) is staged at.
+    fn upload_key(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        checkpoint: Option<u64>,
+        upload_id: &str,
+    ) -> String {
+        match checkpoint {
+            Some(position) => format!(
+                "{}/uploads/{}.ckpt.{}.{}.docx",
+                tenant_id, session_id, position, upload_id
+            ),
+            None => format!("{}/uploads/{}.{}.docx", tenant_id, session_id, upload_id),
+        }
+    }
+
+    /// Get the S3 key for a library item, after validating its name.
+    fn library_item_key(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<String, StorageError> {
+        kind.validate_name(name)?;
+        Ok(format!(
+            "{}/{}/{}.{}",
+            tenant_id,
+            kind.dir_name(),
+            name,
+            kind.extension()
+        ))
+    }
+
+    /// Get the R2 key for a tenant's admin log.
+    fn admin_log_key(&self, tenant_id: &str) -> String {
+        format!("{}/admin.log", tenant_id)
+    }
+
     /// Get the R2 key for a tenant's index.
     fn index_key(&self, tenant_id: &str) -> String {
         format!("{}/index.json", tenant_id)
     }
 
+    /// Get the R2 key prefix of the index entries.
+    fn index_entry_prefix(&self, tenant_id: &str) -> String {
+        format!("{}/index/", tenant_id)
+    }
+
+    /// Get the R2 key for the index entry of a session.
+    fn index_entry_key(&self, tenant_id: &str, session_id: &str) -> String {
+        format!("{}/index/{}.json", tenant_id, session_id)
+    }
+
     // =========================================================================
     // Retry helper
     // =========================================================================
 
-    /// Sleep with exponential backoff + jitter.
-    async fn backoff_sleep(attempt: u32) {
-        let base = Duration::from_millis(BASE_DELAY_MS * 2u64.pow(attempt));
-        let jitter = Duration::from_millis(rand_jitter());
-        tokio::time::sleep(base + jitter).await;
+    /// Retry budget of `operation` under the current policy.
+    fn max_retries(&self, operation: R2Operation) -> u32 {
+        self.retry.get().settings(operation).max_retries
+    }
+
+    /// Decide whether to retry `operation` on `key` after failed attempt
+    /// `attempt` (from 0). Sleeps with exponential backoff + jitter and
+    /// returns `true`, or returns `false` once the retries are exhausted.
+    /// Fails instead when the caller's deadline would pass first, so
+    /// abandoned requests stop retrying.
+    async fn retry_after_backoff(
+        &self,
+        operation: R2Operation,
+        attempt: u32,
+        key: &str,
+        reason: &str,
+    ) -> Result<bool, StorageError> {
+        let max_retries = self.max_retries(operation);
+        if attempt >= max_retries {
+            warn!(
+                operation = operation.as_str(),
+                key,
+                reason,
+                max_retries,
+                outcome = "exhausted",
+                "R2 retries exhausted"
+            );
+            return Ok(false);
+        }
+
+        let delay = self.retry.get().delay(operation, attempt) + Duration::from_millis(rand_jitter());
+        warn!(
+            operation = operation.as_str(),
+            key,
+            reason,
+            attempt,
+            max_retries,
+            delay_ms = delay.as_millis() as u64,
+            outcome = "retrying",
+            "R2 call failed, retrying"
+        );
+        if let Err(e) = sleep_before_retry(delay).await {
+            warn!(
+                operation = operation.as_str(),
+                key,
+                attempt,
+                outcome = "deadline_exceeded",
+                "R2 retry abandoned: {}",
+                e
+            );
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Log an `operation` that succeeded after at least one retry.
+    fn retry_recovered(&self, operation: R2Operation, attempt: u32, key: &str) {
+        if attempt > 0 {
+            info!(
+                operation = operation.as_str(),
+                key,
+                attempts = attempt + 1,
+                outcome = "recovered",
+                "R2 call succeeded after retries"
+            );
+        }
+    }
+
+    /// Send an S3 request of billing `class` on `key` (or prefix) through
+    /// the R2 circuit breaker: fail fast with `Unavailable` while R2 is down,
+    /// and count transient errors towards opening the circuit. Other errors
+    /// (404, 412, ...) mean R2 answered. Requests sent are metered.
+    async fn guarded<T, E: std::fmt::Debug>(
+        &self,
+        class: OpClass,
+        key: &str,
+        request: impl Future<Output = Result<T, SdkError<E>>>,
+    ) -> Result<Result<T, SdkError<E>>, StorageError> {
+        self.breaker.check()?;
+        if let Some(meter) = &self.meter {
+            meter.record(key, class);
+        }
+        let result = request.await;
+        match &result {
+            Err(e) if Self::is_retryable_s3_error(e) => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+        Ok(result)
     }
 
     /// Check if an S3 error is retryable (429 or 5xx).
@@ -101,23 +442,121 @@ impl R2Storage {
         }
     }
 
+    /// Classify a failed S3 call by its HTTP status, so throttling, auth and
+    /// precondition failures reach clients as such rather than as I/O errors.
+    fn s3_error(operation: &str, err: &SdkError<impl std::error::Error + 'static>) -> StorageError {
+        let raw = match err {
+            SdkError::ServiceError(e) => Some(e.raw()),
+            SdkError::ResponseError(e) => Some(e.raw()),
+            _ => None,
+        };
+        let message = format!("R2 {} error: {}", operation, DisplayErrorContext(err));
+        let Some(raw) = raw else {
+            return StorageError::Io(message);
+        };
+        match raw.status().as_u16() {
+            401 | 403 => StorageError::Unauthenticated(message),
+            404 => StorageError::NotFound(message),
+            412 => StorageError::PreconditionFailed(message),
+            // R2 answers 429, S3-style SlowDown is a 503
+            429 | 503 => StorageError::Throttled {
+                message,
+                retry_after: raw.headers().get("retry-after").and_then(parse_retry_after),
+            },
+            _ => StorageError::Io(message),
+        }
+    }
+
+    /// Check if an S3 error is a 416 Range Not Satisfiable.
+    fn is_range_not_satisfiable(err: &aws_sdk_s3::error::SdkError<impl std::fmt::Debug>) -> bool {
+        use aws_sdk_s3::error::SdkError;
+        match err {
+            SdkError::ServiceError(e) => e.raw().status().as_u16() == 416,
+            SdkError::ResponseError(e) => e.raw().status().as_u16() == 416,
+            _ => false,
+        }
+    }
+
+    // =========================================================================
+    // Disk cache helpers
+    // =========================================================================
+
+    /// Get a document object, going through the disk cache when enabled.
+    async fn get_object_cached(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        let Some(cache) = &self.cache else {
+            return self.get_object(key).await;
+        };
+        if let Some(data) = cache.get(key).await {
+            return Ok(Some(data));
+        }
+
+        // Registered before reading R2, so a save landing meanwhile keeps
+        // the bytes read here out of the cache.
+        let fill = cache.start_fill(key);
+        let result = self.get_object(key).await?;
+        if let Some(data) = &result {
+            fill.finish(data).await;
+        }
+        Ok(result)
+    }
+
+    /// Put a document object to R2, then refresh the disk cache.
+    async fn put_object_cached(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        if let Some(cache) = &self.cache {
+            // Drop the stale copy first so a failed upload can't leave it behind.
+            cache.remove(key).await;
+        }
+        self.put_object(key, data).await?;
+        if let Some(cache) = &self.cache {
+            cache.put(key, data).await;
+        }
+        Ok(())
+    }
+
+    /// Stream a document object to R2. The disk cache entry is dropped
+    /// rather than refreshed, since the data is never held whole; the next
+    /// load caches it again.
+    async fn put_object_stream_cached(
+        &self,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        if let Some(cache) = &self.cache {
+            cache.remove(key).await;
+        }
+        self.put_object_stream(key, chunks).await
+    }
+
+    /// Delete a document object from R2 and the disk cache.
+    async fn delete_object_cached(&self, key: &str) -> Result<(), StorageError> {
+        if let Some(cache) = &self.cache {
+            cache.remove(key).await;
+        }
+        self.delete_object(key).await
+    }
+
     // =========================================================================
     // R2 primitives with retry
     // =========================================================================
 
     /// Get an object from R2, with retry on transient errors.
     async fn get_object(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
-        for attempt in 0..=MAX_RETRIES {
+        for attempt in 0..=self.max_retries(R2Operation::Get) {
             let result = self
-                .s3_client
-                .get_object()
-                .bucket(&self.bucket_name)
-                .key(key)
-                .send()
-                .await;
+                .guarded(
+                    OpClass::B,
+                    key,
+                    self.s3_client
+                        .get_object()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .send(),
+                )
+                .await?;
 
             match result {
                 Ok(output) => {
+                    self.retry_recovered(R2Operation::Get, attempt, key);
                     let bytes = output
                         .body
                         .collect()
@@ -126,22 +565,22 @@ impl R2Storage {
                             StorageError::Io(format!("Failed to read R2 object body: {}", e))
                         })?
                         .into_bytes();
+                    self.meter_bytes(key, bytes.len() as u64, 0);
                     return Ok(Some(bytes.to_vec()));
                 }
                 Err(e) => {
-                    if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                        warn!(attempt, key, "R2 get_object retryable error, retrying");
-                        Self::backoff_sleep(attempt).await;
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Get, attempt, key, "transient error")
+                            .await?
+                    {
                         continue;
                     }
-                    let service_error = e.into_service_error();
-                    if service_error.is_no_such_key() {
+                    let error = Self::s3_error("get_object", &e);
+                    if e.into_service_error().is_no_such_key() {
                         return Ok(None);
                     }
-                    return Err(StorageError::Io(format!(
-                        "R2 get_object error: {}",
-                        service_error
-                    )));
+                    return Err(error);
                 }
             }
         }
@@ -154,21 +593,48 @@ impl R2Storage {
         &self,
         key: &str,
     ) -> Result<Option<(Vec<u8>, String)>, StorageError> {
-        for attempt in 0..=MAX_RETRIES {
+        Ok(self
+            .get_object_part(key, None)
+            .await?
+            .map(|(data, etag, _)| (data, etag)))
+    }
+
+    /// Get an object, or only its bytes `first..=last`, along with its ETag
+    /// and total size, with retry on transient errors. Returns `None` if the
+    /// object does not exist or no longer covers the range.
+    async fn get_object_part(
+        &self,
+        key: &str,
+        range: Option<(u64, u64)>,
+    ) -> Result<Option<(Vec<u8>, String, u64)>, StorageError> {
+        for attempt in 0..=self.max_retries(R2Operation::Get) {
             let result = self
-                .s3_client
-                .get_object()
-                .bucket(&self.bucket_name)
-                .key(key)
-                .send()
-                .await;
+                .guarded(
+                    OpClass::B,
+                    key,
+                    self.s3_client
+                        .get_object()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .set_range(range.map(|(first, last)| format!("bytes={}-{}", first, last)))
+                        .send(),
+                )
+                .await?;
 
             match result {
                 Ok(output) => {
+                    self.retry_recovered(R2Operation::Get, attempt, key);
                     let etag = output
                         .e_tag()
                         .unwrap_or("")
                         .to_string();
+                    // Content-Range: bytes {first}-{last}/{total}
+                    let total = output
+                        .content_range()
+                        .and_then(|r| r.rsplit('/').next())
+                        .and_then(|total| total.parse().ok())
+                        .or(output.content_length().map(|len| len as u64))
+                        .unwrap_or(0);
                     let bytes = output
                         .body
                         .collect()
@@ -177,22 +643,25 @@ impl R2Storage {
                             StorageError::Io(format!("Failed to read R2 object body: {}", e))
                         })?
                         .into_bytes();
-                    return Ok(Some((bytes.to_vec(), etag)));
+                    self.meter_bytes(key, bytes.len() as u64, 0);
+                    return Ok(Some((bytes.to_vec(), etag, total)));
                 }
                 Err(e) => {
-                    if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                        warn!(attempt, key, "R2 get_object_with_etag retryable error, retrying");
-                        Self::backoff_sleep(attempt).await;
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Get, attempt, key, "transient error")
+                            .await?
+                    {
                         continue;
                     }
-                    let service_error = e.into_service_error();
-                    if service_error.is_no_such_key() {
+                    if range.is_some() && Self::is_range_not_satisfiable(&e) {
                         return Ok(None);
                     }
-                    return Err(StorageError::Io(format!(
-                        "R2 get_object_with_etag error: {}",
-                        service_error
-                    )));
+                    let error = Self::s3_error("get_object", &e);
+                    if e.into_service_error().is_no_such_key() {
+                        return Ok(None);
+                    }
+                    return Err(error);
                 }
             }
         }
@@ -201,31 +670,268 @@ impl R2Storage {
 
     /// Put an object to R2, with retry on transient errors.
     async fn put_object(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
-        for attempt in 0..=MAX_RETRIES {
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
             let result = self
-                .s3_client
-                .put_object()
-                .bucket(&self.bucket_name)
-                .key(key)
-                .body(ByteStream::from(data.to_vec()))
-                .send()
-                .await;
+                .guarded(
+                    OpClass::A,
+                    key,
+                    self.s3_client
+                        .put_object()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .body(ByteStream::from(data.to_vec()))
+                        .send(),
+                )
+                .await?;
+
+            match result {
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    self.meter_bytes(key, 0, data.len() as u64);
+                    return Ok(());
+                }
+                Err(e) => {
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
+                        continue;
+                    }
+                    return Err(Self::s3_error("put_object", &e));
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    // =========================================================================
+    // Streamed uploads
+    // =========================================================================
+
+    /// Put an object from `chunks` with at most one part in memory. Small
+    /// objects go up with a single PUT; larger ones as a multipart upload,
+    /// which is aborted if the stream or a part fails so R2 keeps no
+    /// orphaned parts. Returns the size written.
+    async fn put_object_stream(
+        &self,
+        key: &str,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let mut buffer = Vec::new();
+        while buffer.len() < MULTIPART_PART_SIZE {
+            match chunks.next().await {
+                Some(chunk) => buffer.extend_from_slice(&chunk?),
+                None => {
+                    self.put_object(key, &buffer).await?;
+                    return Ok(buffer.len() as u64);
+                }
+            }
+        }
+
+        let upload_id = self.create_multipart_upload(key).await?;
+        let result = self.upload_parts(key, &upload_id, buffer, chunks).await;
+        match result {
+            Ok((parts, written)) => {
+                match self.complete_multipart_upload(key, &upload_id, parts).await {
+                    Ok(()) => Ok(written),
+                    Err(e) => {
+                        self.abort_multipart_upload(key, &upload_id).await;
+                        Err(e)
+                    }
+                }
+            }
+            Err(e) => {
+                self.abort_multipart_upload(key, &upload_id).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Upload `buffer` and the rest of `chunks` as parts of `upload_id`.
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        mut buffer: Vec<u8>,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<(Vec<CompletedPart>, u64), StorageError> {
+        let mut parts = Vec::new();
+        let mut written = 0u64;
+        let mut done = false;
+        while !done {
+            while buffer.len() < MULTIPART_PART_SIZE {
+                match chunks.next().await {
+                    Some(chunk) => buffer.extend_from_slice(&chunk?),
+                    None => {
+                        done = true;
+                        break;
+                    }
+                }
+            }
+            if buffer.is_empty() {
+                break;
+            }
+            let rest = buffer.split_off(buffer.len().min(MULTIPART_PART_SIZE));
+            let part_number = parts.len() as i32 + 1;
+            written += buffer.len() as u64;
+            parts.push(self.upload_part(key, upload_id, part_number, buffer).await?);
+            buffer = rest;
+        }
+        Ok((parts, written))
+    }
+
+    /// Start a multipart upload of `key`, returning its upload ID.
+    async fn create_multipart_upload(&self, key: &str) -> Result<String, StorageError> {
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
+            let result = self
+                .guarded(
+                    OpClass::A,
+                    key,
+                    self.s3_client
+                        .create_multipart_upload()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .send(),
+                )
+                .await?;
+
+            match result {
+                Ok(output) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    return output.upload_id().map(str::to_string).ok_or_else(|| {
+                        StorageError::Io("R2 create_multipart_upload returned no upload ID".into())
+                    });
+                }
+                Err(e) => {
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
+                        continue;
+                    }
+                    return Err(Self::s3_error("create_multipart_upload", &e));
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    /// Upload part `part_number` (from 1) of `upload_id`.
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<CompletedPart, StorageError> {
+        let size = data.len() as u64;
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
+            let result = self
+                .guarded(
+                    OpClass::A,
+                    key,
+                    self.s3_client
+                        .upload_part()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .upload_id(upload_id)
+                        .part_number(part_number)
+                        .body(ByteStream::from(data.clone()))
+                        .send(),
+                )
+                .await?;
+
+            match result {
+                Ok(output) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    self.meter_bytes(key, 0, size);
+                    return Ok(CompletedPart::builder()
+                        .set_e_tag(output.e_tag().map(str::to_string))
+                        .part_number(part_number)
+                        .build());
+                }
+                Err(e) => {
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
+                        continue;
+                    }
+                    return Err(Self::s3_error("upload_part", &e));
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    /// Assemble the uploaded `parts` of `upload_id` into the object.
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+    ) -> Result<(), StorageError> {
+        let upload = CompletedMultipartUpload::builder().set_parts(Some(parts)).build();
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
+            let result = self
+                .guarded(
+                    OpClass::A,
+                    key,
+                    self.s3_client
+                        .complete_multipart_upload()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .upload_id(upload_id)
+                        .multipart_upload(upload.clone())
+                        .send(),
+                )
+                .await?;
 
             match result {
-                Ok(_) => return Ok(()),
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    return Ok(());
+                }
                 Err(e) => {
-                    if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                        warn!(attempt, key, "R2 put_object retryable error, retrying");
-                        Self::backoff_sleep(attempt).await;
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
                         continue;
                     }
-                    return Err(StorageError::Io(format!("R2 put_object error: {}", e)));
+                    return Err(Self::s3_error("complete_multipart_upload", &e));
                 }
             }
         }
         unreachable!()
     }
 
+    /// Abort `upload_id`, dropping its parts. Best effort: R2 also expires
+    /// abandoned uploads on its own.
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) {
+        let result = self
+            .guarded(
+                OpClass::Free,
+                key,
+                self.s3_client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .send(),
+            )
+            .await;
+        match result {
+            Ok(Ok(_)) => debug!("Aborted multipart upload of {}", key),
+            Ok(Err(e)) => warn!("Failed to abort multipart upload of {}: {}", key, e),
+            Err(e) => warn!("Failed to abort multipart upload of {}: {}", key, e),
+        }
+    }
+
     /// Conditionally put an object using ETag.
     ///
     /// - If `expected_etag` is `Some(etag)`: uses `If-Match` (update existing).
@@ -239,7 +945,7 @@ impl R2Storage {
         data: &[u8],
         expected_etag: Option<&str>,
     ) -> Result<String, StorageError> {
-        for attempt in 0..=MAX_RETRIES {
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
             let mut req = self
                 .s3_client
                 .put_object()
@@ -253,10 +959,12 @@ impl R2Storage {
                 req = req.if_none_match("*");
             }
 
-            let result = req.send().await;
+            let result = self.guarded(OpClass::A, key, req.send()).await?;
 
             match result {
                 Ok(output) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    self.meter_bytes(key, 0, data.len() as u64);
                     let new_etag = output
                         .e_tag()
                         .unwrap_or("")
@@ -269,18 +977,14 @@ impl R2Storage {
                             "ETag mismatch: object was modified concurrently".to_string(),
                         ));
                     }
-                    if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                        warn!(
-                            attempt,
-                            key, "R2 put_object_conditional retryable error, retrying"
-                        );
-                        Self::backoff_sleep(attempt).await;
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
                         continue;
                     }
-                    return Err(StorageError::Io(format!(
-                        "R2 put_object_conditional error: {}",
-                        e
-                    )));
+                    return Err(Self::s3_error("put_object_conditional", &e));
                 }
             }
         }
@@ -289,108 +993,262 @@ impl R2Storage {
 
     /// Delete an object from R2, with retry on transient errors.
     async fn delete_object(&self, key: &str) -> Result<(), StorageError> {
-        for attempt in 0..=MAX_RETRIES {
+        for attempt in 0..=self.max_retries(R2Operation::Delete) {
             let result = self
-                .s3_client
-                .delete_object()
-                .bucket(&self.bucket_name)
-                .key(key)
-                .send()
-                .await;
+                .guarded(
+                    OpClass::Free,
+                    key,
+                    self.s3_client
+                        .delete_object()
+                        .bucket(&self.bucket_name)
+                        .key(key)
+                        .send(),
+                )
+                .await?;
 
             match result {
-                Ok(_) => return Ok(()),
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::Delete, attempt, key);
+                    return Ok(());
+                }
                 Err(e) => {
-                    if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                        warn!(attempt, key, "R2 delete_object retryable error, retrying");
-                        Self::backoff_sleep(attempt).await;
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Delete, attempt, key, "transient error")
+                            .await?
+                    {
                         continue;
                     }
-                    return Err(StorageError::Io(format!("R2 delete_object error: {}", e)));
+                    return Err(Self::s3_error("delete_object", &e));
                 }
             }
         }
         unreachable!()
     }
 
-    /// List objects with a prefix, with retry on transient errors.
-    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
-        let mut keys = Vec::new();
-        let mut continuation_token: Option<String> = None;
-
-        loop {
-            let mut request = self
-                .s3_client
-                .list_objects_v2()
-                .bucket(&self.bucket_name)
-                .prefix(prefix);
-
-            if let Some(token) = continuation_token.take() {
-                request = request.continuation_token(token);
-            }
+    /// Copy an object within the bucket, with retry on transient errors.
+    async fn copy_object(&self, source: &str, key: &str) -> Result<(), StorageError> {
+        for attempt in 0..=self.max_retries(R2Operation::Put) {
+            let result = self
+                .guarded(
+                    OpClass::A,
+                    key,
+                    self.s3_client
+                        .copy_object()
+                        .bucket(&self.bucket_name)
+                        .copy_source(format!("{}/{}", self.bucket_name, source))
+                        .key(key)
+                        .send(),
+                )
+                .await?;
 
-            let output = {
-                let mut last_err = None;
-                let mut result = None;
-                for attempt in 0..=MAX_RETRIES {
-                    match request.clone().send().await {
-                        Ok(o) => {
-                            result = Some(o);
-                            break;
-                        }
-                        Err(e) => {
-                            if Self::is_retryable_s3_error(&e) && attempt < MAX_RETRIES {
-                                warn!(
-                                    attempt,
-                                    prefix, "R2 list_objects retryable error, retrying"
-                                );
-                                Self::backoff_sleep(attempt).await;
-                                last_err = Some(e);
-                                continue;
-                            }
-                            return Err(StorageError::Io(format!(
-                                "R2 list_objects error: {}",
-                                e
-                            )));
-                        }
-                    }
+            match result {
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::Put, attempt, key);
+                    return Ok(());
                 }
-                result.ok_or_else(|| {
-                    StorageError::Io(format!(
-                        "R2 list_objects exhausted retries: {:?}",
-                        last_err
-                    ))
-                })?
-            };
-
-            if let Some(contents) = output.contents {
-                for obj in contents {
-                    if let Some(key) = obj.key {
-                        keys.push(key);
+                Err(e) => {
+                    if Self::is_retryable_s3_error(&e)
+                        && self
+                            .retry_after_backoff(R2Operation::Put, attempt, key, "transient error")
+                            .await?
+                    {
+                        continue;
                     }
+                    return Err(Self::s3_error("copy_object", &e));
                 }
             }
+        }
+        unreachable!()
+    }
 
-            if output.is_truncated.unwrap_or(false) {
-                continuation_token = output.next_continuation_token;
-            } else {
+    /// List objects with a prefix, with retry on transient errors.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .list_objects_with_etags(prefix)
+            .await?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    }
+
+    /// List objects with a prefix along with their ETags, with retry on
+    /// transient errors.
+    async fn list_objects_with_etags(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<(String, String)>, StorageError> {
+        let mut keys: Vec<(String, String)> = Vec::new();
+        loop {
+            let start_after = keys.last().map(|(key, _)| key.clone());
+            let (page, truncated) = self
+                .list_objects_page(prefix, start_after.as_deref())
+                .await?;
+            keys.extend(page);
+            if !truncated {
                 break;
             }
         }
-
         Ok(keys)
     }
 
+    /// List one page of objects with a prefix, after `start_after`, along
+    /// with their ETags and whether more follow, with retry on transient
+    /// errors.
+    async fn list_objects_page(
+        &self,
+        prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<(Vec<(String, String)>, bool), StorageError> {
+        let request = self
+            .s3_client
+            .list_objects_v2()
+            .bucket(&self.bucket_name)
+            .prefix(prefix)
+            .set_start_after(start_after.map(str::to_string));
+
+        let output = {
+            let mut last_err = None;
+            let mut result = None;
+            for attempt in 0..=self.max_retries(R2Operation::List) {
+                match self
+                    .guarded(OpClass::A, prefix, request.clone().send())
+                    .await?
+                {
+                    Ok(o) => {
+                        self.retry_recovered(R2Operation::List, attempt, prefix);
+                        result = Some(o);
+                        break;
+                    }
+                    Err(e) => {
+                        if Self::is_retryable_s3_error(&e)
+                            && self
+                                .retry_after_backoff(
+                                    R2Operation::List,
+                                    attempt,
+                                    prefix,
+                                    "transient error",
+                                )
+                                .await?
+                        {
+                            last_err = Some(e);
+                            continue;
+                        }
+                        return Err(Self::s3_error("list_objects", &e));
+                    }
+                }
+            }
+            result.ok_or_else(|| {
+                StorageError::Io(format!(
+                    "R2 list_objects exhausted retries: {:?}",
+                    last_err
+                ))
+            })?
+        };
+
+        let keys = output
+            .contents
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|obj| Some((obj.key?, obj.e_tag.unwrap_or_default())))
+            .collect();
+        Ok((keys, output.is_truncated.unwrap_or(false)))
+    }
+
+    // =========================================================================
+    // WAL offset index
+    // =========================================================================
+
+    /// Store the offset index of a WAL just written with ETag `etag`. Best
+    /// effort: a missing or stale index only makes reads fetch the whole WAL.
+    async fn save_wal_index(&self, tenant_id: &str, session_id: &str, wal: &[u8], etag: String) {
+        let mut index = WalOffsetIndex::build(wal_jsonl(wal));
+        index.version = Some(etag);
+        let key = self.wal_index_key(tenant_id, session_id);
+        let result = match serde_json::to_vec(&index) {
+            Ok(json) => self.put_object(&key, &json).await,
+            Err(e) => Err(StorageError::Serialization(e.to_string())),
+        };
+        if let Err(e) = result {
+            warn!(key, "Failed to save WAL index: {}", e);
+        }
+    }
+
+    async fn load_wal_index(&self, tenant_id: &str, session_id: &str) -> Option<WalOffsetIndex> {
+        let key = self.wal_index_key(tenant_id, session_id);
+        match self.get_object(&key).await {
+            Ok(Some(data)) => serde_json::from_slice(&data)
+                .inspect_err(|e| warn!(key, "Ignoring corrupt WAL index: {}", e))
+                .ok(),
+            Ok(None) => None,
+            Err(e) => {
+                warn!(key, "Failed to load WAL index: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Read the WAL entries selected by `window`, which maps the offset index
+    /// to a `(from_position, limit)` read. When the stored index matches the
+    /// WAL (same ETag and size) only the selected byte range is fetched;
+    /// otherwise the whole WAL is read and its index rewritten.
+    async fn read_wal_window(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        window: impl Fn(&WalOffsetIndex) -> (u64, Option<u64>),
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        let key = self.wal_key(tenant_id, session_id);
+
+        if let Some(index) = self.load_wal_index(tenant_id, session_id).await {
+            let (from_position, limit) = window(&index);
+            let (first_position, start, end) = index.byte_range(from_position, limit);
+            // An empty range still needs a request to check the index is current
+            let range = if end > start {
+                (8 + start, 8 + end - 1)
+            } else {
+                (0, 7)
+            };
+            if let Some((data, etag, total)) = self.get_object_part(&key, Some(range)).await? {
+                if index.version.as_deref() == Some(etag.as_str())
+                    && total == 8 + index.data_len
+                {
+                    let jsonl = if end > start { &data[..] } else { &[] };
+                    return parse_wal_entries(jsonl, first_position, from_position, limit);
+                }
+            }
+            debug!(key, "WAL index is stale, reading the whole WAL");
+        }
+
+        let Some((raw, etag)) = self.get_object_with_etag(&key).await? else {
+            return Ok((vec![], false));
+        };
+        self.save_wal_index(tenant_id, session_id, &raw, etag).await;
+
+        let jsonl = wal_jsonl(&raw);
+        let (from_position, limit) = window(&WalOffsetIndex::build(jsonl));
+        parse_wal_entries(jsonl, 1, from_position, limit)
+    }
+
     // =========================================================================
     // CAS (Compare-And-Swap) operations
     // =========================================================================
 
     /// Atomically read-modify-write the session index using ETag-based CAS.
     ///
-    /// 1. GET index with ETag
-    /// 2. Apply `mutator` to the deserialized index
-    /// 3. PUT with If-Match (or If-None-Match: * for new)
-    /// 4. On 412, retry from step 1 (up to `CAS_MAX_RETRIES`)
+    /// 1. GET the manifest with its ETag, and the entries with theirs
+    /// 2. Apply `mutator` to the assembled index
+    /// 3. PUT the manifest if the tenant-wide fields or the aliases changed,
+    ///    then the entries that changed, each with If-Match (or
+    ///    If-None-Match: * for new ones)
+    /// 4. On 412, retry from step 1 (per the `cas_index` retry settings)
+    ///
+    /// Aliases live in the manifest, so changes that must hold across
+    /// sessions (no two sessions share an alias) commit with its ETag; the
+    /// entries only carry what concerns their own session. An index stored
+    /// whole by an older server is split first once due (see
+    /// [`R2Storage::with_index_sharding`]); until then, or if the split loses
+    /// a race, it is written back whole.
     pub async fn cas_index<F>(
         &self,
         tenant_id: &str,
@@ -400,32 +1258,55 @@ impl R2Storage {
         F: FnMut(&mut SessionIndex),
     {
         let key = self.index_key(tenant_id);
-
-        for attempt in 0..CAS_MAX_RETRIES {
-            // Step 1: Read current index + ETag
-            let (mut index, etag) = match self.get_object_with_etag(&key).await? {
-                Some((data, etag)) => {
-                    let index: SessionIndex = serde_json::from_slice(&data).map_err(|e| {
-                        StorageError::Serialization(format!("Failed to parse index: {}", e))
-                    })?;
-                    (index, Some(etag))
+        let max_retries = self.max_retries(R2Operation::CasIndex);
+        let mut conflicts = 0;
+
+        for attempt in 0..=max_retries {
+            // Step 1: Read current manifest + ETag, then the entries
+            let (mut index, mut aliases, mut etag) = self.get_index_with_etag(&key).await?;
+            if etag.is_some() && !index.sharded && self.split_due(tenant_id) {
+                self.split_index(tenant_id).await?;
+                (index, aliases, etag) = self.get_index_with_etag(&key).await?;
+            }
+            let entries = match index.sharded || etag.is_none() {
+                true => {
+                    self.contention.mark_sharded(tenant_id);
+                    let entries = self.load_index_entries(tenant_id).await?;
+                    // A new tenant's manifest is always written
+                    let manifest = match etag {
+                        Some(_) => Self::manifest_json(&index, &aliases)?,
+                        None => Vec::new(),
+                    };
+                    index.sharded = true;
+                    index.sessions = Self::with_aliases(&entries, &aliases);
+                    Some((entries, manifest))
                 }
-                None => (SessionIndex::default(), None),
+                false => None,
             };
 
             // Step 2: Apply mutation
             mutator(&mut index);
 
             // Step 3: Serialize and conditional write
-            let json = serde_json::to_vec(&index).map_err(|e| {
-                StorageError::Serialization(format!("Failed to serialize index: {}", e))
-            })?;
+            let written = match &entries {
+                Some((entries, manifest)) => {
+                    self.put_split_index(tenant_id, &index, entries, manifest, etag.as_deref())
+                        .await
+                }
+                None => {
+                    let json = serde_json::to_vec(&index).map_err(|e| {
+                        StorageError::Serialization(format!("Failed to serialize index: {}", e))
+                    })?;
+                    self.put_object_conditional(&key, &json, etag.as_deref())
+                        .await
+                        .map(|_| ())
+                }
+            };
 
-            match self
-                .put_object_conditional(&key, &json, etag.as_deref())
-                .await
-            {
-                Ok(_) => {
+            match written {
+                Ok(()) => {
+                    self.retry_recovered(R2Operation::CasIndex, attempt, &key);
+                    self.contention.record(tenant_id, CasObject::Index, conflicts, false);
                     debug!(
                         attempt,
                         tenant_id,
@@ -435,24 +1316,273 @@ impl R2Storage {
                     return Ok(index);
                 }
                 Err(StorageError::Lock(_)) => {
-                    // Step 4: ETag mismatch — retry with jitter
-                    warn!(
-                        attempt,
-                        tenant_id, "CAS index conflict (412), retrying"
-                    );
-                    Self::backoff_sleep(attempt).await;
-                    continue;
+                    // Step 4: ETag mismatch — retry with backoff
+                    conflicts += 1;
+                    if !self
+                        .retry_after_backoff(R2Operation::CasIndex, attempt, &key, "ETag conflict (412)")
+                        .await
+                        .inspect_err(|_| {
+                            self.contention.record(tenant_id, CasObject::Index, conflicts, true)
+                        })?
+                    {
+                        break;
+                    }
                 }
                 Err(e) => return Err(e),
             }
         }
 
+        self.contention.record(tenant_id, CasObject::Index, conflicts, true);
         Err(StorageError::Lock(format!(
             "CAS index exhausted {} retries for tenant {}",
-            CAS_MAX_RETRIES, tenant_id
+            max_retries, tenant_id
         )))
     }
 
+    /// The stored index (just the manifest once split) with the aliases of
+    /// a manifest and its ETag, or a new index without one.
+    async fn get_index_with_etag(
+        &self,
+        key: &str,
+    ) -> Result<(SessionIndex, Aliases, Option<String>), StorageError> {
+        Ok(match self.get_object_with_etag(key).await? {
+            Some((data, etag)) => {
+                let (index, aliases) = Self::parse_index(&data)?;
+                (index, aliases, Some(etag))
+            }
+            None => (SessionIndex::default(), Aliases::new(), None),
+        })
+    }
+
+    /// A stored index, and its aliases if it is a manifest.
+    fn parse_index(data: &[u8]) -> Result<(SessionIndex, Aliases), StorageError> {
+        #[derive(serde::Deserialize)]
+        struct Manifest {
+            #[serde(default)]
+            aliases: Aliases,
+        }
+
+        let index = SessionIndex::from_json(data)?;
+        let aliases = match index.sharded {
+            true => serde_json::from_slice::<Manifest>(data)
+                .map_err(|e| StorageError::Serialization(format!("Failed to parse index: {}", e)))?
+                .aliases,
+            false => Aliases::new(),
+        };
+        Ok((index, aliases))
+    }
+
+    /// The stored form of a split index: everything but its entries, plus
+    /// the alias of each session that has one.
+    fn manifest_json(index: &SessionIndex, aliases: &Aliases) -> Result<Vec<u8>, StorageError> {
+        let serialization_error = |e: serde_json::Error| {
+            StorageError::Serialization(format!("Failed to serialize index: {}", e))
+        };
+        let mut manifest = serde_json::to_value(index).map_err(serialization_error)?;
+        if let Some(fields) = manifest.as_object_mut() {
+            fields.remove("sessions");
+            if !aliases.is_empty() {
+                fields.insert(
+                    "aliases".to_string(),
+                    serde_json::to_value(aliases).map_err(serialization_error)?,
+                );
+            }
+        }
+        serde_json::to_vec(&manifest).map_err(serialization_error)
+    }
+
+    /// Aliases of `sessions`, by session ID.
+    fn aliases_of(sessions: &[SessionIndexEntry]) -> Aliases {
+        sessions
+            .iter()
+            .filter_map(|entry| Some((entry.id.clone(), entry.alias.clone()?)))
+            .collect()
+    }
+
+    /// Stored `entries` with their aliases from the manifest.
+    fn with_aliases(
+        entries: &[(SessionIndexEntry, String)],
+        aliases: &Aliases,
+    ) -> Vec<SessionIndexEntry> {
+        entries
+            .iter()
+            .map(|(entry, _)| SessionIndexEntry {
+                alias: aliases.get(&entry.id).cloned(),
+                ..entry.clone()
+            })
+            .collect()
+    }
+
+    /// The stored form of an index entry: without its alias, which the
+    /// manifest holds.
+    fn entry_json(entry: &SessionIndexEntry) -> Result<Vec<u8>, StorageError> {
+        serde_json::to_vec(&SessionIndexEntry {
+            alias: None,
+            ..entry.clone()
+        })
+        .map_err(|e| StorageError::Serialization(format!("Failed to serialize index entry: {}", e)))
+    }
+
+    /// Entries of a split index as stored (without aliases) with their
+    /// ETags, oldest session first. Entries whose ETag didn't change since
+    /// they were last read come from memory, so only the listing and the
+    /// changed entries hit R2.
+    async fn load_index_entries(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Vec<(SessionIndexEntry, String)>, StorageError> {
+        let listed = self
+            .list_objects_with_etags(&self.index_entry_prefix(tenant_id))
+            .await?;
+        let mut entries = Vec::with_capacity(listed.len());
+        for (key, etag) in listed {
+            // An entry deleted since the listing is skipped
+            if let Some(entry) = self.load_index_entry(key, etag).await? {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|(a, _), (b, _)| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        Ok(entries)
+    }
+
+    /// The index entry stored at `key`, listed with `etag`, with its current
+    /// ETag: from memory if it didn't change since it was last read.
+    async fn load_index_entry(
+        &self,
+        key: String,
+        etag: String,
+    ) -> Result<Option<(SessionIndexEntry, String)>, StorageError> {
+        let cached = self
+            .index_entries
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|(cached_etag, _)| *cached_etag == etag)
+            .map(|(_, entry)| entry.clone());
+        if let Some(entry) = cached {
+            return Ok(Some((entry, etag)));
+        }
+        let Some((data, etag)) = self.get_object_with_etag(&key).await? else {
+            return Ok(None);
+        };
+        let entry: SessionIndexEntry = serde_json::from_slice(&data).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse index entry {}: {}", key, e))
+        })?;
+        self.index_entries
+            .lock()
+            .unwrap()
+            .insert(key, (etag.clone(), entry.clone()));
+        Ok(Some((entry, etag)))
+    }
+
+    /// Write the entries of a split index that differ from `before`, each
+    /// conditional on the ETag it was read with, and delete the removed
+    /// ones. Returns `StorageError::Lock` on the first conflict; entries
+    /// written before it stay written and the retry sees them.
+    async fn write_index_entries(
+        &self,
+        tenant_id: &str,
+        before: &[(SessionIndexEntry, String)],
+        after: &[SessionIndexEntry],
+    ) -> Result<(), StorageError> {
+        let before: HashMap<&str, (&SessionIndexEntry, &str)> = before
+            .iter()
+            .map(|(entry, etag)| (entry.id.as_str(), (entry, etag.as_str())))
+            .collect();
+
+        for entry in after {
+            let json = Self::entry_json(entry)?;
+            let etag = match before.get(entry.id.as_str()) {
+                Some((old, _)) if Self::entry_json(old)? == json => continue,
+                Some((_, etag)) => Some(*etag),
+                None => None,
+            };
+            let key = self.index_entry_key(tenant_id, &entry.id);
+            let new_etag = self.put_object_conditional(&key, &json, etag).await?;
+            let stored = SessionIndexEntry {
+                alias: None,
+                ..entry.clone()
+            };
+            self.index_entries
+                .lock()
+                .unwrap()
+                .insert(key, (new_etag, stored));
+        }
+
+        let kept: HashSet<&str> = after.iter().map(|entry| entry.id.as_str()).collect();
+        for session_id in before.keys().filter(|id| !kept.contains(*id)) {
+            let key = self.index_entry_key(tenant_id, session_id);
+            self.delete_object(&key).await?;
+            self.index_entries.lock().unwrap().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Write a mutated split index: `index.json` if the tenant-wide fields
+    /// or the aliases changed from `manifest` (as read with `manifest_etag`),
+    /// then the changed entries. The manifest goes first so that a change
+    /// it refuses (another writer got there first) leaves no entry written.
+    async fn put_split_index(
+        &self,
+        tenant_id: &str,
+        index: &SessionIndex,
+        entries: &[(SessionIndexEntry, String)],
+        manifest: &[u8],
+        manifest_etag: Option<&str>,
+    ) -> Result<(), StorageError> {
+        let new_manifest = Self::manifest_json(index, &Self::aliases_of(&index.sessions))?;
+        if new_manifest != manifest {
+            self.put_object_conditional(&self.index_key(tenant_id), &new_manifest, manifest_etag)
+                .await?;
+        }
+        self.write_index_entries(tenant_id, entries, &index.sessions)
+            .await
+    }
+
+    /// Move the entries of an index stored whole in `index.json` into their
+    /// own objects, leaving the manifest with their aliases. Returns the
+    /// number of entries moved, or `None` if the index is already split,
+    /// doesn't exist or changed while its entries were copied.
+    async fn split_index(&self, tenant_id: &str) -> Result<Option<usize>, StorageError> {
+        let key = self.index_key(tenant_id);
+        let Some((data, etag)) = self.get_object_with_etag(&key).await? else {
+            return Ok(None);
+        };
+        let mut index = SessionIndex::from_json(&data)?;
+        if index.sharded {
+            return Ok(None);
+        }
+
+        // Entries left by an attempt whose manifest write lost a race
+        let kept: HashSet<String> = index
+            .sessions
+            .iter()
+            .map(|entry| self.index_entry_key(tenant_id, &entry.id))
+            .collect();
+        for stale in self.list_objects(&self.index_entry_prefix(tenant_id)).await? {
+            if !kept.contains(&stale) {
+                self.delete_object(&stale).await?;
+            }
+        }
+        for entry in &index.sessions {
+            self.put_object(&self.index_entry_key(tenant_id, &entry.id), &Self::entry_json(entry)?)
+                .await?;
+        }
+
+        let sessions = index.sessions.len();
+        index.sharded = true;
+        let manifest = Self::manifest_json(&index, &Self::aliases_of(&index.sessions))?;
+        match self.put_object_conditional(&key, &manifest, Some(&etag)).await {
+            Ok(_) => {
+                self.contention.mark_sharded(tenant_id);
+                info!(tenant_id, sessions, "Split the session index into per-session entries");
+                Ok(Some(sessions))
+            }
+            Err(StorageError::Lock(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Atomically append WAL entries using ETag-based CAS.
     async fn cas_append_wal(
         &self,
@@ -463,10 +1593,13 @@ impl R2Storage {
         if entries.is_empty() {
             return Ok(0);
         }
+        let lines = prepare_wal_entries(entries)?;
 
         let key = self.wal_key(tenant_id, session_id);
+        let max_retries = self.max_retries(R2Operation::CasWal);
+        let mut conflicts = 0;
 
-        for attempt in 0..CAS_MAX_RETRIES {
+        for attempt in 0..=max_retries {
             // Read current WAL + ETag
             let (mut wal_data, etag) = match self.get_object_with_etag(&key).await? {
                 Some((data, etag)) if data.len() >= 8 => {
@@ -482,14 +1615,15 @@ impl R2Storage {
                 }
             };
 
+            // Checked against the WAL as read, so a concurrent append that
+            // wins the CAS is seen on retry
+            let existing = WalOffsetIndex::build(&wal_data[8..]).entries;
+            let last_position = check_wal_positions(existing, entries)?;
+
             // Append new entries as JSONL
-            let mut last_position = 0u64;
-            for entry in entries {
-                wal_data.extend_from_slice(&entry.patch_json);
-                if !entry.patch_json.ends_with(b"\n") {
-                    wal_data.push(b'\n');
-                }
-                last_position = entry.position;
+            for line in &lines {
+                wal_data.extend_from_slice(line);
+                wal_data.push(b'\n');
             }
 
             // Update header with data length
@@ -501,7 +1635,17 @@ impl R2Storage {
                 .put_object_conditional(&key, &wal_data, etag.as_deref())
                 .await
             {
-                Ok(_) => {
+                Ok(new_etag) => {
+                    self.retry_recovered(R2Operation::CasWal, attempt, &key);
+                    self.contention.record(tenant_id, CasObject::Wal, conflicts, false);
+                    self.save_wal_index(tenant_id, session_id, &wal_data, new_etag)
+                        .await;
+                    let first_position = last_position + 1 - lines.len() as u64;
+                    let events: Vec<_> = (first_position..)
+                        .zip(&lines)
+                        .map(|(position, line)| HistoryEvent::wal(position, line))
+                        .collect();
+                    self.cas_extend_history(tenant_id, session_id, &events).await?;
                     debug!(
                         "Appended {} WAL entries, last position: {}",
                         entries.len(),
@@ -510,20 +1654,81 @@ impl R2Storage {
                     return Ok(last_position);
                 }
                 Err(StorageError::Lock(_)) => {
-                    warn!(
-                        attempt,
-                        session_id, "WAL append conflict (412), retrying"
-                    );
-                    Self::backoff_sleep(attempt).await;
-                    continue;
+                    conflicts += 1;
+                    if !self
+                        .retry_after_backoff(R2Operation::CasWal, attempt, &key, "ETag conflict (412)")
+                        .await
+                        .inspect_err(|_| {
+                            self.contention.record(tenant_id, CasObject::Wal, conflicts, true)
+                        })?
+                    {
+                        break;
+                    }
                 }
                 Err(e) => return Err(e),
             }
         }
 
+        self.contention.record(tenant_id, CasObject::Wal, conflicts, true);
         Err(StorageError::Lock(format!(
             "WAL append exhausted {} retries for session {}",
-            CAS_MAX_RETRIES, session_id
+            max_retries, session_id
+        )))
+    }
+
+    /// Atomically append links recording `events` to the session's history
+    /// chain using ETag-based CAS.
+    async fn cas_extend_history(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        events: &[HistoryEvent],
+    ) -> Result<(), StorageError> {
+        if !self.history_chain || events.is_empty() {
+            return Ok(());
+        }
+        let key = self.history_key(tenant_id, session_id);
+        let max_retries = self.max_retries(R2Operation::CasWal);
+
+        for attempt in 0..=max_retries {
+            let (mut jsonl, etag) = match self.get_object_with_etag(&key).await? {
+                Some((data, etag)) => (data, Some(etag)),
+                None => (Vec::new(), None),
+            };
+            let links = parse_history(&jsonl)?;
+            // Drop a torn last line before appending
+            jsonl = history_jsonl(&links)?;
+            jsonl.extend(history_jsonl(&chain_events(
+                links.last(),
+                tenant_id,
+                session_id,
+                events,
+                chrono::Utc::now(),
+            ))?);
+
+            match self
+                .put_object_conditional(&key, &jsonl, etag.as_deref())
+                .await
+            {
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::CasWal, attempt, &key);
+                    return Ok(());
+                }
+                Err(StorageError::Lock(_)) => {
+                    if !self
+                        .retry_after_backoff(R2Operation::CasWal, attempt, &key, "ETag conflict (412)")
+                        .await?
+                    {
+                        break;
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(StorageError::Lock(format!(
+            "History chain append exhausted {} retries for session {}",
+            max_retries, session_id
         )))
     }
 
@@ -544,8 +1749,10 @@ impl R2Storage {
         }
 
         let key = self.wal_key(tenant_id, session_id);
+        let max_retries = self.max_retries(R2Operation::CasWal);
+        let mut conflicts = 0;
 
-        for attempt in 0..CAS_MAX_RETRIES {
+        for attempt in 0..=max_retries {
             // Get current ETag
             let etag = match self.get_object_with_etag(&key).await? {
                 Some((_, etag)) => Some(etag),
@@ -569,7 +1776,13 @@ impl R2Storage {
                 .put_object_conditional(&key, &wal_data, etag.as_deref())
                 .await
             {
-                Ok(_) => {
+                Ok(new_etag) => {
+                    self.retry_recovered(R2Operation::CasWal, attempt, &key);
+                    self.contention.record(tenant_id, CasObject::Wal, conflicts, false);
+                    self.save_wal_index(tenant_id, session_id, &wal_data, new_etag)
+                        .await;
+                    self.cas_extend_history(tenant_id, session_id, &[HistoryEvent::truncate(keep_count)])
+                        .await?;
                     debug!(
                         "Truncated WAL, removed {} entries, kept {}",
                         removed_count,
@@ -578,97 +1791,617 @@ impl R2Storage {
                     return Ok(removed_count);
                 }
                 Err(StorageError::Lock(_)) => {
-                    warn!(
-                        attempt,
-                        session_id, "WAL truncate conflict (412), retrying"
-                    );
-                    Self::backoff_sleep(attempt).await;
-                    continue;
+                    conflicts += 1;
+                    if !self
+                        .retry_after_backoff(R2Operation::CasWal, attempt, &key, "ETag conflict (412)")
+                        .await
+                        .inspect_err(|_| {
+                            self.contention.record(tenant_id, CasObject::Wal, conflicts, true)
+                        })?
+                    {
+                        break;
+                    }
                 }
                 Err(e) => return Err(e),
             }
         }
 
+        self.contention.record(tenant_id, CasObject::Wal, conflicts, true);
         Err(StorageError::Lock(format!(
             "WAL truncate exhausted {} retries for session {}",
-            CAS_MAX_RETRIES, session_id
+            max_retries, session_id
         )))
     }
-}
-
-/// Simple jitter: random-ish value 0..50ms using timestamp nanos.
-fn rand_jitter() -> u64 {
-    use std::time::SystemTime;
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|d| d.subsec_nanos() as u64 % 50)
-        .unwrap_or(0)
-}
-
-#[async_trait]
-impl StorageBackend for R2Storage {
-    fn backend_name(&self) -> &'static str {
-        "r2"
-    }
 
     // =========================================================================
-    // Session Operations
+    // Bucket lifecycle
     // =========================================================================
 
-    #[instrument(skip(self), level = "debug")]
-    async fn load_session(
-        &self,
-        tenant_id: &str,
-        session_id: &str,
-    ) -> Result<Option<Vec<u8>>, StorageError> {
-        let key = self.session_key(tenant_id, session_id);
-        let result = self.get_object(&key).await?;
-        if result.is_some() {
-            debug!("Loaded session {} from R2", session_id);
+    /// The bucket's lifecycle rules (empty when it has none).
+    pub async fn lifecycle_rules(&self) -> Result<LifecycleRules, StorageError> {
+        let result = self
+            .guarded(
+                OpClass::B,
+                "",
+                self.s3_client
+                    .get_bucket_lifecycle_configuration()
+                    .bucket(&self.bucket_name)
+                    .send(),
+            )
+            .await?;
+        match result {
+            Ok(output) => Ok(LifecycleRules::from_s3(output.rules())),
+            // NoSuchLifecycleConfiguration
+            Err(SdkError::ServiceError(e)) if e.raw().status().as_u16() == 404 => {
+                Ok(LifecycleRules::default())
+            }
+            Err(e) => Err(StorageError::Io(format!(
+                "R2 get_bucket_lifecycle_configuration error: {}",
+                e
+            ))),
         }
-        Ok(result)
     }
 
-    #[instrument(skip(self, data), level = "debug", fields(data_len = data.len()))]
-    async fn save_session(
-        &self,
-        tenant_id: &str,
-        session_id: &str,
-        data: &[u8],
-    ) -> Result<(), StorageError> {
-        let key = self.session_key(tenant_id, session_id);
-        self.put_object(&key, data).await?;
-        debug!("Saved session {} to R2 ({} bytes)", session_id, data.len());
+    /// Replace the bucket's lifecycle rules; no rules removes the configuration.
+    ///
+    /// Refused when an enabled expiration rule reaches data under legal hold,
+    /// since R2 would then delete it behind the storage server's back.
+    pub async fn set_lifecycle_rules(&self, rules: &LifecycleRules) -> Result<(), StorageError> {
+        rules.validate()?;
+        if rules.expiring().next().is_some() {
+            for (prefix, hold) in self.held_prefixes().await? {
+                if let Some(rule) = rules.expiring().find(|r| r.overlaps(&prefix)) {
+                    return Err(StorageError::LegalHold(format!(
+                        "expiration rule '{}' reaches {}, held since {} ({})",
+                        rule.id,
+                        prefix,
+                        hold.placed_at.to_rfc3339(),
+                        hold.reason
+                    )));
+                }
+            }
+        }
+
+        let result = match rules.to_s3()? {
+            Some(configuration) => self
+                .guarded(
+                    OpClass::A,
+                    "",
+                    self.s3_client
+                        .put_bucket_lifecycle_configuration()
+                        .bucket(&self.bucket_name)
+                        .lifecycle_configuration(configuration)
+                        .send(),
+                )
+                .await?
+                .map(|_| ())
+                .map_err(|e| format!("put_bucket_lifecycle_configuration error: {}", e)),
+            None => self
+                .guarded(
+                    OpClass::A,
+                    "",
+                    self.s3_client
+                        .delete_bucket_lifecycle()
+                        .bucket(&self.bucket_name)
+                        .send(),
+                )
+                .await?
+                .map(|_| ())
+                .map_err(|e| format!("delete_bucket_lifecycle error: {}", e)),
+        };
+        result.map_err(|e| StorageError::Io(format!("R2 {}", e)))?;
+
+        info!(
+            bucket = %self.bucket_name,
+            rules = rules.rules.len(),
+            "Replaced R2 lifecycle rules"
+        );
         Ok(())
     }
 
-    #[instrument(skip(self), level = "debug")]
-    async fn delete_session(
+    // =========================================================================
+    // Tenant snapshots
+    // =========================================================================
+
+    /// Capture a point-in-time manifest of a tenant's objects, copying them
+    /// under `{tenant}/snapshots/{id}/` when `copy` (see [`TenantSnapshot`]).
+    ///
+    /// Objects are read (and copied) as listed, then the tenant is listed
+    /// again and whatever changed is read again, until a listing matches what
+    /// was read. Fails with a conflict if the tenant never holds still for
+    /// [`MAX_SNAPSHOT_ROUNDS`] listings; copies made so far are deleted.
+    pub async fn snapshot_tenant(
         &self,
         tenant_id: &str,
-        session_id: &str,
-    ) -> Result<bool, StorageError> {
-        let session_key = self.session_key(tenant_id, session_id);
-        let wal_key = self.wal_key(tenant_id, session_id);
-
-        // Check if session exists
-        let existed = self.get_object(&session_key).await?.is_some();
+        copy: bool,
+    ) -> Result<TenantSnapshot, StorageError> {
+        let prefix = format!("{}/", tenant_id);
+        let snapshots_prefix = format!("{}snapshots/", prefix);
+        let snapshot_id = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+        let copy_prefix = copy.then(|| format!("{}{}/", snapshots_prefix, snapshot_id));
+
+        let mut read: BTreeMap<String, SnapshotObject> = BTreeMap::new();
+        for round in 1..=MAX_SNAPSHOT_ROUNDS {
+            let listed: BTreeMap<String, String> = self
+                .list_objects_with_etags(&prefix)
+                .await?
+                .into_iter()
+                .filter(|(key, _)| !key.starts_with(&snapshots_prefix))
+                .collect();
+            let mut changed = false;
+
+            let deleted: Vec<String> =
+                read.keys().filter(|k| !listed.contains_key(*k)).cloned().collect();
+            for key in deleted {
+                if let Some(copy_prefix) = &copy_prefix {
+                    self.delete_object(&format!("{}{}", copy_prefix, &key[prefix.len()..]))
+                        .await?;
+                }
+                read.remove(&key);
+                changed = true;
+            }
+            for (key, etag) in &listed {
+                if read.get(key).is_some_and(|o| &o.etag == etag) {
+                    continue;
+                }
+                changed = true;
+                // Deleted since listed: the next listing won't show it
+                let Some((data, etag)) = self.get_object_with_etag(key).await? else {
+                    continue;
+                };
+                let relative = &key[prefix.len()..];
+                if let Some(copy_prefix) = &copy_prefix {
+                    self.put_object(&format!("{}{}", copy_prefix, relative), &data).await?;
+                }
+                read.insert(
+                    key.clone(),
+                    SnapshotObject {
+                        key: relative.to_string(),
+                        etag,
+                        sha256: hex::encode(Sha256::digest(&data)),
+                        size: data.len() as u64,
+                    },
+                );
+            }
 
-        // Delete session file
-        if let Err(e) = self.delete_object(&session_key).await {
+            if !changed {
+                let snapshot = TenantSnapshot {
+                    snapshot_id,
+                    tenant_id: tenant_id.to_string(),
+                    taken_at: chrono::Utc::now(),
+                    copy_prefix,
+                    rounds: round,
+                    objects: read.into_values().collect(),
+                };
+                if let Some(copy_prefix) = &snapshot.copy_prefix {
+                    let manifest = serde_json::to_vec_pretty(&snapshot).map_err(|e| {
+                        StorageError::Serialization(format!(
+                            "Failed to serialize snapshot manifest: {}",
+                            e
+                        ))
+                    })?;
+                    self.put_object(&format!("{}manifest.json", copy_prefix), &manifest)
+                        .await?;
+                }
+                info!(
+                    tenant_id,
+                    snapshot_id = %snapshot.snapshot_id,
+                    objects = snapshot.objects.len(),
+                    rounds = round,
+                    "Snapshotted tenant"
+                );
+                return Ok(snapshot);
+            }
+        }
+
+        if let Some(copy_prefix) = &copy_prefix {
+            for key in self.list_objects(copy_prefix).await? {
+                self.delete_object(&key).await?;
+            }
+        }
+        Err(StorageError::Conflict(format!(
+            "tenant {} kept changing over {} listings; retry the snapshot later",
+            tenant_id, MAX_SNAPSHOT_ROUNDS
+        )))
+    }
+
+    // =========================================================================
+    // Presigned URLs
+    // =========================================================================
+
+    /// The object key of a session's document, or of its checkpoint at
+    /// `checkpoint`.
+    fn document_key(&self, tenant_id: &str, session_id: &str, checkpoint: Option<u64>) -> String {
+        match checkpoint {
+            Some(position) => self.checkpoint_key(tenant_id, session_id, position),
+            None => self.session_key(tenant_id, session_id),
+        }
+    }
+
+    fn presigning_config(expires_in: Duration) -> Result<PresigningConfig, StorageError> {
+        PresigningConfig::expires_in(expires_in.min(MAX_PRESIGNED_URL_TTL)).map_err(|e| {
+            StorageError::InvalidArgument(format!("Invalid presigned URL lifetime: {}", e))
+        })
+    }
+
+    /// Why documents can't be uploaded straight to the bucket, if they can't:
+    /// the server rewrites stored packages (media deduplication), keeps
+    /// copies the upload would leave stale (disk cache) or, for checkpoints,
+    /// chains their digests into the session history.
+    pub fn direct_upload_refusal(&self, checkpoint: bool) -> Option<&'static str> {
+        if self.media_dedup {
+            Some("stored packages have their media deduplicated by the server")
+        } else if self.cache.is_some() {
+            Some("the server caches documents on disk")
+        } else if checkpoint && self.history_chain {
+            Some("checkpoints are recorded in the session history chain")
+        } else {
+            None
+        }
+    }
+
+    /// Presign a GET of a session's document, or of its checkpoint at
+    /// `checkpoint`, valid for `expires_in` (at most
+    /// [`MAX_PRESIGNED_URL_TTL`]). The URL grants nothing but reading that
+    /// object, without any other credentials. Returns `None` if the object
+    /// doesn't exist.
+    ///
+    /// Refused when media is deduplicated: the stored packages lack their
+    /// images, which only loads through the server put back.
+    pub async fn presign_download(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        checkpoint: Option<u64>,
+        expires_in: Duration,
+    ) -> Result<Option<PresignedUrl>, StorageError> {
+        if self.media_dedup {
+            return Err(StorageError::PreconditionFailed(
+                "stored packages have their media deduplicated; load them through the server"
+                    .to_string(),
+            ));
+        }
+        let key = self.document_key(tenant_id, session_id, checkpoint);
+
+        // A URL of a missing object would only fail once fetched
+        let result = self
+            .guarded(
+                OpClass::B,
+                &key,
+                self.s3_client
+                    .head_object()
+                    .bucket(&self.bucket_name)
+                    .key(&key)
+                    .send(),
+            )
+            .await?;
+        if let Err(e) = result {
+            let error = Self::s3_error("head_object", &e);
+            if e.into_service_error().is_not_found() {
+                return Ok(None);
+            }
+            return Err(error);
+        }
+
+        let request = self
+            .s3_client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .response_content_type(DOCX_CONTENT_TYPE)
+            .presigned(Self::presigning_config(expires_in)?)
+            .await
+            .map_err(|e| Self::s3_error("presign get_object", &e))?;
+        info!(
+            tenant_id,
+            session_id,
+            ?checkpoint,
+            expires_in_secs = expires_in.as_secs(),
+            "Presigned document download"
+        );
+        Ok(Some(PresignedUrl {
+            url: request.uri().to_string(),
+            headers: Vec::new(),
+            expires_at: chrono::Utc::now() + expires_in.min(MAX_PRESIGNED_URL_TTL),
+            upload_id: None,
+        }))
+    }
+
+    /// Presign a PUT of a session's document, or its checkpoint at
+    /// `checkpoint`, of exactly `size_bytes`, valid for `expires_in` (at most
+    /// [`MAX_PRESIGNED_UPLOAD_TTL`]). The PUT must carry the returned headers
+    /// and lands in a staging object: [`commit_upload`](Self::commit_upload)
+    /// with the returned `upload_id` moves it into place.
+    ///
+    /// Refused when the tenant or session is under legal hold, when the
+    /// server must see what is stored (see
+    /// [`direct_upload_refusal`](Self::direct_upload_refusal)), and for
+    /// documents larger than one PUT can store.
+    pub async fn presign_upload(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        checkpoint: Option<u64>,
+        size_bytes: u64,
+        expires_in: Duration,
+    ) -> Result<PresignedUrl, StorageError> {
+        if let Some(reason) = self.direct_upload_refusal(checkpoint.is_some()) {
+            return Err(StorageError::PreconditionFailed(format!(
+                "{}; upload through the server",
+                reason
+            )));
+        }
+        if size_bytes > MAX_PUT_BYTES {
+            return Err(StorageError::InvalidArgument(format!(
+                "{} bytes is more than one PUT stores ({} bytes); upload through the server",
+                size_bytes, MAX_PUT_BYTES
+            )));
+        }
+        ensure_not_held(self, tenant_id, Some(session_id)).await?;
+
+        let mut id = [0u8; 16];
+        ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut id)
+            .map_err(|_| StorageError::Io("Failed to generate an upload ID".to_string()))?;
+        let upload_id = hex::encode(id);
+        let key = self.upload_key(tenant_id, session_id, checkpoint, &upload_id);
+        let expires_in = expires_in.min(MAX_PRESIGNED_UPLOAD_TTL);
+
+        // Signing the length makes the URL store nothing else
+        let request = self
+            .s3_client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .content_length(size_bytes as i64)
+            .content_type(DOCX_CONTENT_TYPE)
+            .presigned(Self::presigning_config(expires_in)?)
+            .await
+            .map_err(|e| Self::s3_error("presign put_object", &e))?;
+        info!(
+            tenant_id,
+            session_id,
+            ?checkpoint,
+            size_bytes,
+            upload_id,
+            expires_in_secs = expires_in.as_secs(),
+            "Presigned document upload"
+        );
+        Ok(PresignedUrl {
+            url: request.uri().to_string(),
+            headers: request
+                .headers()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            expires_at: chrono::Utc::now() + expires_in,
+            upload_id: Some(upload_id),
+        })
+    }
+
+    /// Move the document uploaded with the URL of
+    /// [`presign_upload`](Self::presign_upload) that returned `upload_id`
+    /// into place, and record it in the index: the session is marked
+    /// modified and a checkpoint's position added. Returns the size stored.
+    ///
+    /// The session must be in the index and, as when the URL was issued, not
+    /// under legal hold.
+    pub async fn commit_upload(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        checkpoint: Option<u64>,
+        upload_id: &str,
+    ) -> Result<u64, StorageError> {
+        if upload_id.len() != 32 || !upload_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(StorageError::InvalidArgument(format!(
+                "Invalid upload ID '{}'",
+                upload_id
+            )));
+        }
+        let index = self.load_index(tenant_id).await?.unwrap_or_default();
+        if !index.contains(session_id) {
+            return Err(StorageError::NotFound(format!(
+                "Session {} is not in the index",
+                session_id
+            )));
+        }
+        index.ensure_not_held(Some(session_id))?;
+
+        let staged = self.upload_key(tenant_id, session_id, checkpoint, upload_id);
+        let result = self
+            .guarded(
+                OpClass::B,
+                &staged,
+                self.s3_client
+                    .head_object()
+                    .bucket(&self.bucket_name)
+                    .key(&staged)
+                    .send(),
+            )
+            .await?;
+        let size_bytes = match result {
+            Ok(output) => output.content_length().unwrap_or(0) as u64,
+            Err(e) => {
+                let error = Self::s3_error("head_object", &e);
+                if e.into_service_error().is_not_found() {
+                    return Err(StorageError::NotFound(format!(
+                        "No upload {} of session {} (not uploaded yet, or already committed)",
+                        upload_id, session_id
+                    )));
+                }
+                return Err(error);
+            }
+        };
+
+        let key = self.document_key(tenant_id, session_id, checkpoint);
+        self.copy_object(&staged, &key).await?;
+        self.delete_object(&staged).await?;
+        self.meter_bytes(&key, 0, size_bytes);
+
+        let now = chrono::Utc::now();
+        self.cas_index(tenant_id, |index| {
+            let Some(entry) = index.get_mut(session_id) else {
+                return;
+            };
+            entry.last_modified_at = now;
+            if let Some(position) = checkpoint {
+                if !entry.checkpoint_positions.contains(&position) {
+                    entry.checkpoint_positions.push(position);
+                    entry.checkpoint_positions.sort_unstable();
+                }
+            }
+        })
+        .await?;
+        info!(tenant_id, session_id, ?checkpoint, size_bytes, upload_id, "Committed document upload");
+        Ok(size_bytes)
+    }
+
+    /// Key prefixes under legal hold: `{tenant}/` for tenant holds and
+    /// `{tenant}/sessions/{session}.` for session holds.
+    async fn held_prefixes(&self) -> Result<Vec<(String, LegalHold)>, StorageError> {
+        let mut held = Vec::new();
+        for tenant_id in self.list_tenants().await? {
+            let Some(index) = self.load_index(&tenant_id).await? else {
+                continue;
+            };
+            if let Some(hold) = index.legal_hold {
+                held.push((format!("{}/", tenant_id), hold));
+                continue;
+            }
+            for entry in index.sessions {
+                if let Some(hold) = entry.legal_hold {
+                    held.push((format!("{}/sessions/{}.", tenant_id, entry.id), hold));
+                }
+            }
+        }
+        Ok(held)
+    }
+}
+
+/// Simple jitter: random-ish value 0..50ms using timestamp nanos.
+fn rand_jitter() -> u64 {
+    use std::time::SystemTime;
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64 % 50)
+        .unwrap_or(0)
+}
+
+#[async_trait]
+impl StorageBackend for R2Storage {
+    fn backend_name(&self) -> &'static str {
+        "r2"
+    }
+
+    fn circuit_stats(&self) -> Option<CircuitBreakerStats> {
+        Some(self.breaker.stats())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(self.backend_name())
+            .with_feature(feature::CAS)
+            .with_feature_if(feature::MEDIA_DEDUP, self.media_dedup)
+            .with_feature_if(feature::HISTORY_CHAIN, self.history_chain)
+            .with_feature(feature::ADMIN_LOG)
+            .with_max_object_bytes(MAX_OBJECT_BYTES)
+    }
+
+    // =========================================================================
+    // Session Operations
+    // =========================================================================
+
+    #[instrument(skip(self), level = "debug")]
+    async fn load_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let key = self.session_key(tenant_id, session_id);
+        match self.get_object_cached(&key).await? {
+            Some(data) => {
+                debug!("Loaded session {} from R2", session_id);
+                Ok(Some(restore_media(self, tenant_id, data).await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self, data), level = "debug", fields(data_len = data.len()))]
+    async fn save_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        let key = self.session_key(tenant_id, session_id);
+        let package = self.package_to_save(tenant_id, data).await?;
+        let data = package.as_deref().unwrap_or(data);
+        self.put_object_cached(&key, data).await?;
+        debug!("Saved session {} to R2 ({} bytes)", session_id, data.len());
+        Ok(())
+    }
+
+    #[instrument(skip(self, chunks), level = "debug")]
+    async fn save_session_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let chunks = match self.media_dedup {
+            true => match buffer_chunks(chunks, MEDIA_MAX_BUFFERED_BYTES).await? {
+                BufferedChunks::Complete(data) => {
+                    self.save_session(tenant_id, session_id, &data).await?;
+                    return Ok(data.len() as u64);
+                }
+                BufferedChunks::Partial(chunks) => chunks,
+            },
+            false => chunks,
+        };
+        let key = self.session_key(tenant_id, session_id);
+        let written = self.put_object_stream_cached(&key, chunks).await?;
+        debug!("Saved session {} to R2 ({} bytes, streamed)", session_id, written);
+        Ok(written)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn delete_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<bool, StorageError> {
+        let session_key = self.session_key(tenant_id, session_id);
+        let wal_key = self.wal_key(tenant_id, session_id);
+
+        // Check if session exists
+        let existed = self.get_object(&session_key).await?.is_some();
+
+        // Delete session file
+        if let Err(e) = self.delete_object_cached(&session_key).await {
             warn!("Failed to delete session file: {}", e);
         }
 
-        // Delete WAL
+        // Delete WAL and its offset index
         if let Err(e) = self.delete_object(&wal_key).await {
             warn!("Failed to delete WAL file: {}", e);
         }
+        if let Err(e) = self
+            .delete_object(&self.wal_index_key(tenant_id, session_id))
+            .await
+        {
+            warn!("Failed to delete WAL index: {}", e);
+        }
+        if let Err(e) = self
+            .delete_object(&self.history_key(tenant_id, session_id))
+            .await
+        {
+            warn!("Failed to delete history chain: {}", e);
+        }
 
         // Delete all checkpoints
         let checkpoints = self.list_checkpoints(tenant_id, session_id).await?;
         for ckpt in checkpoints {
             let ckpt_key = self.checkpoint_key(tenant_id, session_id, ckpt.position);
-            if let Err(e) = self.delete_object(&ckpt_key).await {
+            if let Err(e) = self.delete_object_cached(&ckpt_key).await {
                 warn!("Failed to delete checkpoint: {}", e);
             }
         }
@@ -695,12 +2428,16 @@ impl StorageBackend for R2Storage {
                 if !session_id.is_empty() {
                     // Get object metadata for size/timestamps
                     let head = self
-                        .s3_client
-                        .head_object()
-                        .bucket(&self.bucket_name)
-                        .key(&key)
-                        .send()
-                        .await;
+                        .guarded(
+                            OpClass::B,
+                            &key,
+                            self.s3_client
+                                .head_object()
+                                .bucket(&self.bucket_name)
+                                .key(&key)
+                                .send(),
+                        )
+                        .await?;
 
                     let (size_bytes, modified_at) = match head {
                         Ok(output) => {
@@ -743,24 +2480,25 @@ impl StorageBackend for R2Storage {
     ) -> Result<bool, StorageError> {
         let key = self.session_key(tenant_id, session_id);
         let result = self
-            .s3_client
-            .head_object()
-            .bucket(&self.bucket_name)
-            .key(&key)
-            .send()
-            .await;
+            .guarded(
+                OpClass::B,
+                &key,
+                self.s3_client
+                    .head_object()
+                    .bucket(&self.bucket_name)
+                    .key(&key)
+                    .send(),
+            )
+            .await?;
 
         match result {
             Ok(_) => Ok(true),
             Err(e) => {
-                let service_error = e.into_service_error();
-                if service_error.is_not_found() {
+                let error = Self::s3_error("head_object", &e);
+                if e.into_service_error().is_not_found() {
                     Ok(false)
                 } else {
-                    Err(StorageError::Io(format!(
-                        "R2 head_object error: {}",
-                        service_error
-                    )))
+                    Err(error)
                 }
             }
         }
@@ -775,9 +2513,13 @@ impl StorageBackend for R2Storage {
         let key = self.index_key(tenant_id);
         match self.get_object(&key).await? {
             Some(data) => {
-                let index: SessionIndex = serde_json::from_slice(&data).map_err(|e| {
-                    StorageError::Serialization(format!("Failed to parse index: {}", e))
-                })?;
+                let (mut index, aliases) = Self::parse_index(&data)?;
+                // A manifest; indexes stored whole by older servers are read as is
+                if index.sharded {
+                    self.contention.mark_sharded(tenant_id);
+                    let entries = self.load_index_entries(tenant_id).await?;
+                    index.sessions = Self::with_aliases(&entries, &aliases);
+                }
                 debug!(
                     "Loaded index with {} sessions from R2",
                     index.sessions.len()
@@ -788,17 +2530,75 @@ impl StorageBackend for R2Storage {
         }
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn list_session_page(
+        &self,
+        tenant_id: &str,
+        query: &SessionPageQuery,
+    ) -> Result<SessionPage, StorageError> {
+        let (index, aliases, etag) = self.get_index_with_etag(&self.index_key(tenant_id)).await?;
+        // Indexes stored whole (or not at all) are paged in memory
+        if etag.is_none() || !index.sharded {
+            return Ok(index.page(query));
+        }
+
+        // Only the listed entries that could make the page are read
+        let prefix = format!(
+            "{}{}",
+            self.index_entry_prefix(tenant_id),
+            query.id_prefix.as_deref().unwrap_or_default()
+        );
+        let size = query.effective_page_size();
+        let mut start_after = query
+            .page_token
+            .as_deref()
+            .map(|session_id| self.index_entry_key(tenant_id, session_id));
+        let mut entries = Vec::with_capacity(size);
+        loop {
+            let (listed, truncated) = self
+                .list_objects_page(&prefix, start_after.as_deref())
+                .await?;
+            let mut listed = listed.into_iter().peekable();
+            while let Some((key, etag)) = listed.next() {
+                start_after = Some(key.clone());
+                let Some((mut entry, _)) = self.load_index_entry(key, etag).await? else {
+                    continue;
+                };
+                entry.alias = aliases.get(&entry.id).cloned();
+                if !query.matches(&entry) {
+                    continue;
+                }
+                entries.push(entry);
+                if entries.len() == size {
+                    let more = truncated || listed.peek().is_some();
+                    let next_page_token = more.then(|| entries[size - 1].id.clone());
+                    return Ok(SessionPage { entries, next_page_token });
+                }
+            }
+            if !truncated {
+                return Ok(SessionPage {
+                    entries,
+                    next_page_token: None,
+                });
+            }
+        }
+    }
+
     #[instrument(skip(self, index), level = "debug", fields(sessions = index.sessions.len()))]
     async fn save_index(
         &self,
         tenant_id: &str,
         index: &SessionIndex,
     ) -> Result<(), StorageError> {
-        let key = self.index_key(tenant_id);
-        let json = serde_json::to_vec(index).map_err(|e| {
-            StorageError::Serialization(format!("Failed to serialize index: {}", e))
-        })?;
-        self.put_object(&key, &json).await?;
+        let mut manifest = index.clone();
+        manifest.sharded = true;
+        let manifest = Self::manifest_json(&manifest, &Self::aliases_of(&index.sessions))?;
+        self.put_object(&self.index_key(tenant_id), &manifest).await?;
+        // Entries left beside an index stored whole are replaced too
+        let entries = self.load_index_entries(tenant_id).await?;
+        self.write_index_entries(tenant_id, &entries, &index.sessions)
+            .await?;
+        self.contention.mark_sharded(tenant_id);
         debug!("Saved index with {} sessions to R2", index.sessions.len());
         Ok(())
     }
@@ -825,79 +2625,38 @@ impl StorageBackend for R2Storage {
         from_position: u64,
         limit: Option<u64>,
     ) -> Result<(Vec<WalEntry>, bool), StorageError> {
-        let key = self.wal_key(tenant_id, session_id);
-
-        let raw_data = match self.get_object(&key).await? {
-            Some(data) => data,
-            None => return Ok((vec![], false)),
-        };
-
-        if raw_data.len() < 8 {
-            return Ok((vec![], false));
-        }
-
-        // Parse header
-        let data_len = i64::from_le_bytes(raw_data[..8].try_into().unwrap()) as usize;
-        if data_len == 0 {
-            return Ok((vec![], false));
-        }
-
-        // Extract JSONL portion
-        let end = (8 + data_len).min(raw_data.len());
-        let jsonl_data = &raw_data[8..end];
-
-        let content = std::str::from_utf8(jsonl_data).map_err(|e| {
-            StorageError::Io(format!("WAL is not valid UTF-8: {}", e))
-        })?;
-
-        // Parse JSONL - each line is a .NET WalEntry JSON
-        let mut entries = Vec::new();
-        let limit = limit.unwrap_or(u64::MAX);
-        let mut position = 1u64;
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-
-            if position >= from_position {
-                let value: serde_json::Value = serde_json::from_str(line).map_err(|e| {
-                    StorageError::Serialization(format!(
-                        "Failed to parse WAL entry at position {}: {}",
-                        position, e
-                    ))
-                })?;
-
-                let timestamp = value
-                    .get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                    .unwrap_or_else(chrono::Utc::now);
-
-                entries.push(WalEntry {
-                    position,
-                    operation: String::new(),
-                    path: String::new(),
-                    patch_json: line.as_bytes().to_vec(),
-                    timestamp,
-                });
-
-                if entries.len() as u64 >= limit {
-                    return Ok((entries, true));
-                }
-            }
-
-            position += 1;
-        }
+        let (entries, has_more) = self
+            .read_wal_window(tenant_id, session_id, |_| (from_position, limit))
+            .await?;
 
         debug!(
             "Read {} WAL entries from position {}",
             entries.len(),
             from_position
         );
-        Ok((entries, false))
+        Ok((entries, has_more))
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn tail_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        before_position: Option<u64>,
+        limit: u64,
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        let (mut entries, _) = self
+            .read_wal_window(tenant_id, session_id, |index| {
+                match tail_page(index.entries, before_position, limit) {
+                    (_, 0) => (index.entries + 1, None),
+                    (from_position, count) => (from_position, Some(count)),
+                }
+            })
+            .await?;
+
+        let has_more = entries.first().is_some_and(|e| e.position > 1);
+        entries.reverse();
+        Ok((entries, has_more))
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -925,7 +2684,12 @@ impl StorageBackend for R2Storage {
         data: &[u8],
     ) -> Result<(), StorageError> {
         let key = self.checkpoint_key(tenant_id, session_id, position);
-        self.put_object(&key, data).await?;
+        let digest = document_digest(data);
+        let package = self.package_to_save(tenant_id, data).await?;
+        let data = package.as_deref().unwrap_or(data);
+        self.put_object_cached(&key, data).await?;
+        self.cas_extend_history(tenant_id, session_id, &[HistoryEvent::checkpoint(position, digest)])
+            .await?;
         debug!(
             "Saved checkpoint at position {} ({} bytes)",
             position,
@@ -934,6 +2698,37 @@ impl StorageBackend for R2Storage {
         Ok(())
     }
 
+    #[instrument(skip(self, chunks), level = "debug")]
+    async fn save_checkpoint_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let chunks = match self.media_dedup {
+            true => match buffer_chunks(chunks, MEDIA_MAX_BUFFERED_BYTES).await? {
+                BufferedChunks::Complete(data) => {
+                    self.save_checkpoint(tenant_id, session_id, position, &data).await?;
+                    return Ok(data.len() as u64);
+                }
+                BufferedChunks::Partial(chunks) => chunks,
+            },
+            false => chunks,
+        };
+        let key = self.checkpoint_key(tenant_id, session_id, position);
+        let (chunks, digest) = digest_chunks(chunks);
+        let written = self.put_object_stream_cached(&key, chunks).await?;
+        self.cas_extend_history(
+            tenant_id,
+            session_id,
+            &[HistoryEvent::checkpoint(position, digest.hex())],
+        )
+        .await?;
+        debug!("Saved checkpoint at position {} ({} bytes, streamed)", position, written);
+        Ok(written)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn load_checkpoint(
         &self,
@@ -946,12 +2741,13 @@ impl StorageBackend for R2Storage {
             let checkpoints = self.list_checkpoints(tenant_id, session_id).await?;
             if let Some(latest) = checkpoints.last() {
                 let key = self.checkpoint_key(tenant_id, session_id, latest.position);
-                if let Some(data) = self.get_object(&key).await? {
+                if let Some(data) = self.get_object_cached(&key).await? {
                     debug!(
                         "Loaded latest checkpoint at position {} ({} bytes)",
                         latest.position,
                         data.len()
                     );
+                    let data = restore_media(self, tenant_id, data).await?;
                     return Ok(Some((data, latest.position)));
                 }
             }
@@ -959,14 +2755,14 @@ impl StorageBackend for R2Storage {
         }
 
         let key = self.checkpoint_key(tenant_id, session_id, position);
-        match self.get_object(&key).await? {
+        match self.get_object_cached(&key).await? {
             Some(data) => {
                 debug!(
                     "Loaded checkpoint at position {} ({} bytes)",
                     position,
                     data.len()
                 );
-                Ok(Some((data, position)))
+                Ok(Some((restore_media(self, tenant_id, data).await?, position)))
             }
             None => Ok(None),
         }
@@ -993,12 +2789,16 @@ impl StorageBackend for R2Storage {
                 if let Ok(position) = position_str.parse::<u64>() {
                     // Get object metadata
                     let head = self
-                        .s3_client
-                        .head_object()
-                        .bucket(&self.bucket_name)
-                        .key(&key)
-                        .send()
-                        .await;
+                        .guarded(
+                            OpClass::B,
+                            &key,
+                            self.s3_client
+                                .head_object()
+                                .bucket(&self.bucket_name)
+                                .key(&key)
+                                .send(),
+                        )
+                        .await?;
 
                     let (size_bytes, created_at) = match head {
                         Ok(output) => {
@@ -1033,4 +2833,690 @@ impl StorageBackend for R2Storage {
         );
         Ok(checkpoints)
     }
+
+    // =========================================================================
+    // Library Operations
+    // =========================================================================
+
+    #[instrument(skip(self, data), level = "debug", fields(data_len = data.len()))]
+    async fn save_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        let key = self.library_item_key(tenant_id, kind, name)?;
+        self.put_object(&key, data).await?;
+        debug!("Saved {:?} {} to R2 ({} bytes)", kind, name, data.len());
+        Ok(())
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn load_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let key = self.library_item_key(tenant_id, kind, name)?;
+        self.get_object(&key).await
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_library_items(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+    ) -> Result<Vec<LibraryItemInfo>, StorageError> {
+        let prefix = format!("{}/{}/", tenant_id, kind.dir_name());
+        let suffix = format!(".{}", kind.extension());
+        let keys = self.list_objects(&prefix).await?;
+
+        let mut items = Vec::new();
+        for key in keys {
+            let Some(name) = key
+                .strip_prefix(&prefix)
+                .and_then(|s| s.strip_suffix(suffix.as_str()))
+                .filter(|s| !s.is_empty())
+            else {
+                continue;
+            };
+
+            let head = self
+                .guarded(
+                    OpClass::B,
+                    &key,
+                    self.s3_client
+                        .head_object()
+                        .bucket(&self.bucket_name)
+                        .key(&key)
+                        .send(),
+                )
+                .await?;
+
+            let (size_bytes, modified_at) = match head {
+                Ok(output) => {
+                    let size = output.content_length.unwrap_or(0) as u64;
+                    let modified = output
+                        .last_modified
+                        .and_then(|dt| {
+                            chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos())
+                        })
+                        .unwrap_or_else(chrono::Utc::now);
+                    (size, modified)
+                }
+                Err(_) => (0, chrono::Utc::now()),
+            };
+
+            items.push(LibraryItemInfo {
+                name: name.to_string(),
+                modified_at,
+                size_bytes,
+            });
+        }
+
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        debug!("Listed {} {:?} items for tenant {}", items.len(), kind, tenant_id);
+        Ok(items)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn delete_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<bool, StorageError> {
+        let key = self.library_item_key(tenant_id, kind, name)?;
+        let existed = self.get_object(&key).await?.is_some();
+        if existed {
+            self.delete_object(&key).await?;
+        }
+        debug!("Deleted {:?} {} (existed: {})", kind, name, existed);
+        Ok(existed)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError> {
+        // Tenants are the top-level key prefixes
+        let mut tenants = Vec::new();
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let mut request = self
+                .s3_client
+                .list_objects_v2()
+                .bucket(&self.bucket_name)
+                .delimiter("/");
+            if let Some(token) = continuation_token.take() {
+                request = request.continuation_token(token);
+            }
+            let output = self
+                .guarded(OpClass::A, "", request.send())
+                .await?
+                .map_err(|e| Self::s3_error("list_objects", &e))?;
+
+            tenants.extend(
+                output
+                    .common_prefixes()
+                    .iter()
+                    .filter_map(|p| p.prefix())
+                    .map(|p| p.trim_end_matches('/').to_string()),
+            );
+            if output.is_truncated.unwrap_or(false) {
+                continuation_token = output.next_continuation_token;
+            } else {
+                break;
+            }
+        }
+        Ok(tenants)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn erase_tenant(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        if tenant_id.is_empty() {
+            return Err(StorageError::InvalidArgument(
+                "cannot erase the bucket root".to_string(),
+            ));
+        }
+
+        let keys = self.list_objects(&format!("{}/", tenant_id)).await?;
+        for key in &keys {
+            self.delete_object_cached(key).await?;
+        }
+
+        debug!("Erased tenant {} ({} objects)", tenant_id, keys.len());
+        Ok(keys.len() as u64)
+    }
+
+    /// Objects can't be appended to, so the log is rewritten with ETag-based
+    /// CAS, like the history chain.
+    #[instrument(skip(self, entry), level = "debug")]
+    async fn append_admin_log(
+        &self,
+        tenant_id: &str,
+        mut entry: AdminLogEntry,
+    ) -> Result<u64, StorageError> {
+        let key = self.admin_log_key(tenant_id);
+        let max_retries = self.max_retries(R2Operation::CasWal);
+
+        for attempt in 0..=max_retries {
+            let (jsonl, etag) = match self.get_object_with_etag(&key).await? {
+                Some((data, etag)) => (data, Some(etag)),
+                None => (Vec::new(), None),
+            };
+            let mut entries = parse_admin_log(&jsonl)?;
+            entry.position = entries.last().map_or(0, |e| e.position) + 1;
+            entries.push(entry.clone());
+
+            match self
+                .put_object_conditional(&key, &admin_log_jsonl(&entries)?, etag.as_deref())
+                .await
+            {
+                Ok(_) => {
+                    self.retry_recovered(R2Operation::CasWal, attempt, &key);
+                    debug!("Recorded admin action {} at {}", entry.action.as_str(), entry.position);
+                    return Ok(entry.position);
+                }
+                Err(StorageError::Lock(_)) => {
+                    if !self
+                        .retry_after_backoff(R2Operation::CasWal, attempt, &key, "ETag conflict (412)")
+                        .await?
+                    {
+                        break;
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(StorageError::Lock(format!(
+            "Admin log append exhausted {} retries for tenant {}",
+            max_retries, tenant_id
+        )))
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn read_admin_log(
+        &self,
+        tenant_id: &str,
+        from_position: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<AdminLogEntry>, StorageError> {
+        match self.get_object(&self.admin_log_key(tenant_id)).await? {
+            Some(jsonl) => Ok(admin_log_page(parse_admin_log(&jsonl)?, from_position, limit)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn read_history(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Vec<HistoryLink>, StorageError> {
+        match self.get_object(&self.history_key(tenant_id, session_id)).await? {
+            Some(jsonl) => parse_history(&jsonl),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn sweep_media(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        // Snapshot copies carry their own media, only live packages count
+        let mut referenced = HashSet::new();
+        for key in self.list_objects(&format!("{}/sessions/", tenant_id)).await? {
+            if !key.ends_with(".docx") {
+                continue;
+            }
+            // A package deleted since the listing references nothing
+            if let Some(data) = self.get_object_cached(&key).await? {
+                referenced.extend(media_references(&data));
+            }
+        }
+
+        let deleted = delete_unreferenced_media(self, tenant_id, &referenced).await?;
+        debug!(
+            "Swept {} media blobs of tenant {} ({} referenced)",
+            deleted,
+            tenant_id,
+            referenced.len()
+        );
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Barrier;
+
+    use super::super::fake_s3::FakeS3;
+    use super::*;
+
+    fn entry(id: &str, alias: Option<&str>) -> SessionIndexEntry {
+        let created_at = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        SessionIndexEntry {
+            id: id.to_string(),
+            source_path: None,
+            auto_sync: true,
+            created_at,
+            last_modified_at: created_at,
+            docx_file: Some(format!("{}.docx", id)),
+            wal_count: 0,
+            cursor_position: 0,
+            checkpoint_positions: vec![],
+            pending_external_change: false,
+            display_name: None,
+            alias: alias.map(str::to_string),
+            legal_hold: None,
+            expires_at: None,
+        }
+    }
+
+    /// Store an index whole in `index.json`, as servers before the split did.
+    fn put_whole_index(s3: &FakeS3, tenant_id: &str, sessions: Vec<SessionIndexEntry>) {
+        let index = SessionIndex {
+            version: 3,
+            sessions,
+            ..Default::default()
+        };
+        s3.put(
+            &format!("{}/index.json", tenant_id),
+            &serde_json::to_vec(&index).unwrap(),
+        );
+    }
+
+    fn aliases(sessions: &[SessionIndexEntry]) -> Vec<(String, Option<String>)> {
+        sessions
+            .iter()
+            .map(|e| (e.id.clone(), e.alias.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_split_index_moves_entries_and_aliases() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        put_whole_index(
+            &s3,
+            "t1",
+            vec![entry("a", Some("report")), entry("b", None)],
+        );
+
+        assert_eq!(storage.split_index("t1").await.unwrap(), Some(2));
+        assert_eq!(
+            s3.keys("t1/index/"),
+            vec!["t1/index/a.json".to_string(), "t1/index/b.json".to_string()]
+        );
+
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&s3.get("t1/index.json").unwrap()).unwrap();
+        assert_eq!(manifest["sharded"], true);
+        assert_eq!(manifest["aliases"], serde_json::json!({ "a": "report" }));
+        assert!(manifest.get("sessions").is_none());
+        let stored: SessionIndexEntry =
+            serde_json::from_slice(&s3.get("t1/index/a.json").unwrap()).unwrap();
+        assert_eq!(stored.alias, None);
+
+        // Already split
+        assert_eq!(storage.split_index("t1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_index_reads_whole_and_split_layouts_alike() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        put_whole_index(
+            &s3,
+            "t1",
+            vec![entry("a", Some("report")), entry("b", None)],
+        );
+
+        let whole = storage.load_index("t1").await.unwrap().unwrap();
+        storage.split_index("t1").await.unwrap();
+        let split = storage.load_index("t1").await.unwrap().unwrap();
+
+        assert!(split.sharded);
+        assert_eq!(aliases(&split.sessions), aliases(&whole.sessions));
+        assert_eq!(split.resolve("REPORT").map(|e| e.id.as_str()), Some("a"));
+    }
+
+    #[tokio::test]
+    async fn test_cas_index_splits_a_whole_index_on_write() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        put_whole_index(&s3, "t1", vec![entry("a", Some("report"))]);
+
+        storage
+            .cas_index("t1", |index| index.upsert(entry("b", Some("memo"))))
+            .await
+            .unwrap();
+
+        assert_eq!(s3.keys("t1/index/").len(), 2);
+        let index = storage.load_index("t1").await.unwrap().unwrap();
+        assert_eq!(
+            aliases(&index.sessions),
+            vec![
+                ("a".to_string(), Some("report".to_string())),
+                ("b".to_string(), Some("memo".to_string())),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_whole_index_is_split_once_its_writes_conflict_enough() {
+        let s3 = FakeS3::start().await;
+        let tracker = Arc::new(ContentionTracker::default());
+        let storage = s3
+            .storage()
+            .with_contention_tracker(tracker.clone())
+            .with_index_sharding(0.5);
+        put_whole_index(&s3, "t1", vec![entry("a", None)]);
+
+        storage
+            .cas_index("t1", |index| index.upsert(entry("b", None)))
+            .await
+            .unwrap();
+        assert!(s3.keys("t1/index/").is_empty());
+        let index = storage.load_index("t1").await.unwrap().unwrap();
+        assert!(!index.sharded);
+        assert_eq!(index.sessions.len(), 2);
+
+        // Other writers of the tenant kept running into conflicts
+        for _ in 0..MIN_SHARD_OPERATIONS {
+            tracker.record("t1", CasObject::Index, 2, false);
+        }
+        storage
+            .cas_index("t1", |index| index.upsert(entry("c", None)))
+            .await
+            .unwrap();
+        assert_eq!(s3.keys("t1/index/").len(), 3);
+        assert!(storage.load_index("t1").await.unwrap().unwrap().sharded);
+    }
+
+    #[tokio::test]
+    async fn test_split_is_due_from_the_minimum_operations_at_the_conflict_rate() {
+        let s3 = FakeS3::start().await;
+        let tracker = Arc::new(ContentionTracker::default());
+        let storage = s3
+            .storage()
+            .with_contention_tracker(tracker.clone())
+            .with_index_sharding(0.5);
+
+        // Half the attempts conflicting, one write short of the minimum
+        for _ in 1..MIN_SHARD_OPERATIONS {
+            tracker.record("t1", CasObject::Index, 1, false);
+        }
+        assert!(!storage.split_due("t1"));
+        // The minimum, with just under half the attempts conflicting
+        tracker.record("t1", CasObject::Index, 0, false);
+        assert!(!storage.split_due("t1"));
+        // The minimum, with exactly half the attempts conflicting
+        for _ in 0..MIN_SHARD_OPERATIONS {
+            tracker.record("t2", CasObject::Index, 1, false);
+        }
+        assert!(storage.split_due("t2"));
+
+        // Rate 0 splits on the next write, whatever the counts
+        let storage = s3.storage().with_index_sharding(0.0);
+        assert!(storage.split_due("t4"));
+    }
+
+    #[tokio::test]
+    async fn test_list_session_page_reads_split_entries_with_aliases() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        put_whole_index(
+            &s3,
+            "t1",
+            vec![
+                entry("a", Some("report")),
+                entry("b", None),
+                entry("c", None),
+            ],
+        );
+        storage.split_index("t1").await.unwrap();
+
+        let mut query = SessionPageQuery {
+            page_size: 2,
+            ..Default::default()
+        };
+        let first = storage.list_session_page("t1", &query).await.unwrap();
+        assert_eq!(
+            aliases(&first.entries),
+            vec![
+                ("a".to_string(), Some("report".to_string())),
+                ("b".to_string(), None),
+            ]
+        );
+        query.page_token = first.next_page_token;
+        let second = storage.list_session_page("t1", &query).await.unwrap();
+        assert_eq!(second.entries.len(), 1);
+        assert_eq!(second.next_page_token, None);
+
+        let search = SessionPageQuery {
+            search: Some("REP".to_string()),
+            ..Default::default()
+        };
+        let found = storage.list_session_page("t1", &search).await.unwrap();
+        assert_eq!(found.entries.len(), 1);
+        assert_eq!(found.entries[0].id, "a");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_renames_to_the_same_alias_commit_once() {
+        let s3 = FakeS3::start().await;
+        s3.storage()
+            .cas_index("t1", |index| {
+                index.upsert(entry("a", None));
+                index.upsert(entry("b", None));
+            })
+            .await
+            .unwrap();
+
+        // Both renames read the index before either writes
+        let barrier = Arc::new(Barrier::new(2));
+        let rename = |session_id: &'static str| {
+            let storage = s3.storage();
+            let barrier = barrier.clone();
+            tokio::spawn(async move {
+                let waited = AtomicBool::new(false);
+                let mut outcome = Ok(false);
+                storage
+                    .cas_index("t1", |index| {
+                        if !waited.swap(true, Ordering::SeqCst) {
+                            barrier.wait();
+                        }
+                        outcome = index.rename(session_id, Some(session_id), Some("report"));
+                    })
+                    .await
+                    .unwrap();
+                outcome
+            })
+        };
+        let (a, b) = tokio::join!(rename("a"), rename("b"));
+        let (a, b) = (a.unwrap(), b.unwrap());
+
+        assert!(
+            a.is_ok() != b.is_ok(),
+            "exactly one rename wins: {:?} {:?}",
+            a,
+            b
+        );
+        let loser = if a.is_ok() { "b" } else { "a" };
+        assert!(matches!(
+            if a.is_ok() { b } else { a },
+            Err(StorageError::InvalidArgument(_))
+        ));
+
+        let index = s3.storage().load_index("t1").await.unwrap().unwrap();
+        let named: Vec<_> = index
+            .sessions
+            .iter()
+            .filter(|e| e.alias.as_deref() == Some("report"))
+            .collect();
+        assert_eq!(named.len(), 1);
+        assert_ne!(named[0].id, loser);
+        // The refused rename left the loser's display name alone too
+        assert_eq!(index.get(loser).unwrap().display_name, None);
+    }
+
+    #[tokio::test]
+    async fn test_presign_upload_refuses_held_sessions() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        let hold = LegalHold::new("litigation", chrono::Utc::now());
+        storage
+            .cas_index("t1", |index| {
+                index.upsert(entry("a", None));
+                index.upsert(SessionIndexEntry {
+                    legal_hold: Some(hold.clone()),
+                    ..entry("b", None)
+                });
+            })
+            .await
+            .unwrap();
+        let ttl = Duration::from_secs(60);
+
+        assert!(storage
+            .presign_upload("t1", "a", None, 10, ttl)
+            .await
+            .is_ok());
+        let held = storage.presign_upload("t1", "b", Some(3), 10, ttl).await;
+        assert!(
+            matches!(held, Err(StorageError::LegalHold(_))),
+            "{:?}",
+            held
+        );
+
+        storage
+            .cas_index("t1", |index| index.legal_hold = Some(hold.clone()))
+            .await
+            .unwrap();
+        let held = storage.presign_upload("t1", "a", None, 10, ttl).await;
+        assert!(
+            matches!(held, Err(StorageError::LegalHold(_))),
+            "{:?}",
+            held
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presign_upload_caps_its_lifetime() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+
+        let presigned = storage
+            .presign_upload("t1", "a", None, 10, MAX_PRESIGNED_URL_TTL)
+            .await
+            .unwrap();
+
+        assert!(
+            presigned.url.contains("X-Amz-Expires=900"),
+            "{}",
+            presigned.url
+        );
+        assert!(presigned.expires_at <= chrono::Utc::now() + MAX_PRESIGNED_UPLOAD_TTL);
+        let upload_id = presigned.upload_id.unwrap();
+        assert!(presigned
+            .url
+            .contains(&format!("/t1/uploads/a.{}.docx", upload_id)));
+    }
+
+    /// PUT `data` to a presigned URL, as a browser would.
+    async fn upload(presigned: &PresignedUrl, data: &[u8]) {
+        let mut request = reqwest::Client::new()
+            .put(&presigned.url)
+            .body(data.to_vec());
+        for (name, value) in &presigned.headers {
+            if !name.eq_ignore_ascii_case("content-length") {
+                request = request.header(name, value);
+            }
+        }
+        assert!(request.send().await.unwrap().status().is_success());
+    }
+
+    #[tokio::test]
+    async fn test_commit_upload_moves_the_document_into_place() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        storage
+            .cas_index("t1", |index| index.upsert(entry("a", None)))
+            .await
+            .unwrap();
+        let ttl = Duration::from_secs(60);
+
+        let document = storage
+            .presign_upload("t1", "a", None, 4, ttl)
+            .await
+            .unwrap();
+        upload(&document, b"docx").await;
+        let checkpoint = storage
+            .presign_upload("t1", "a", Some(7), 4, ttl)
+            .await
+            .unwrap();
+        upload(&checkpoint, b"ckpt").await;
+        // Not moved into place before the commit
+        assert_eq!(s3.get("t1/sessions/a.docx"), None);
+
+        let upload_id = document.upload_id.unwrap();
+        assert_eq!(
+            storage
+                .commit_upload("t1", "a", None, &upload_id)
+                .await
+                .unwrap(),
+            4
+        );
+        let checkpoint_id = checkpoint.upload_id.unwrap();
+        assert_eq!(
+            storage
+                .commit_upload("t1", "a", Some(7), &checkpoint_id)
+                .await
+                .unwrap(),
+            4
+        );
+
+        assert_eq!(s3.get("t1/sessions/a.docx").unwrap(), b"docx");
+        assert_eq!(s3.get("t1/sessions/a.ckpt.7.docx").unwrap(), b"ckpt");
+        assert!(s3.keys("t1/uploads/").is_empty());
+        let index = storage.load_index("t1").await.unwrap().unwrap();
+        let a = index.get("a").unwrap();
+        assert!(a.last_modified_at > entry("a", None).last_modified_at);
+        assert_eq!(a.checkpoint_positions, vec![7]);
+
+        // Each upload commits once
+        let again = storage.commit_upload("t1", "a", None, &upload_id).await;
+        assert!(
+            matches!(again, Err(StorageError::NotFound(_))),
+            "{:?}",
+            again
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cas_index_writes_only_changed_entries() {
+        let s3 = FakeS3::start().await;
+        let storage = s3.storage();
+        storage
+            .cas_index("t1", |index| {
+                index.upsert(entry("a", None));
+                index.upsert(entry("b", None));
+            })
+            .await
+            .unwrap();
+        let manifest = s3.get("t1/index.json").unwrap();
+        let b = s3.get("t1/index/b.json").unwrap();
+
+        storage
+            .cas_index("t1", |index| index.get_mut("a").unwrap().wal_count = 3)
+            .await
+            .unwrap();
+
+        assert_eq!(s3.get("t1/index.json").unwrap(), manifest);
+        assert_eq!(s3.get("t1/index/b.json").unwrap(), b);
+        let a: SessionIndexEntry =
+            serde_json::from_slice(&s3.get("t1/index/a.json").unwrap()).unwrap();
+        assert_eq!(a.wal_count, 3);
+    }
 }
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Class of R2 call with its own retry settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum R2Operation {
+    /// Object reads, including ETag reads for CAS
+    Get,
+    /// Plain and conditional object writes
+    Put,
+    Delete,
+    List,
+    /// Read-modify-write of a tenant's session index (retried on 412)
+    CasIndex,
+    /// Read-modify-write of a session WAL (retried on 412)
+    CasWal,
+}
+
+impl R2Operation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            R2Operation::Get => "get",
+            R2Operation::Put => "put",
+            R2Operation::Delete => "delete",
+            R2Operation::List => "list",
+            R2Operation::CasIndex => "cas_index",
+            R2Operation::CasWal => "cas_wal",
+        }
+    }
+
+    fn is_cas(self) -> bool {
+        matches!(self, R2Operation::CasIndex | R2Operation::CasWal)
+    }
+}
+
+impl fmt::Display for R2Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for R2Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "get" => Ok(R2Operation::Get),
+            "put" => Ok(R2Operation::Put),
+            "delete" => Ok(R2Operation::Delete),
+            "list" => Ok(R2Operation::List),
+            "cas_index" => Ok(R2Operation::CasIndex),
+            "cas_wal" => Ok(R2Operation::CasWal),
+            _ => Err(format!(
+                "unknown R2 operation '{}' (expected get, put, delete, list, cas_index or cas_wal)",
+                s
+            )),
+        }
+    }
+}
+
+/// Retry budget of one operation class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetrySettings {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each further retry
+    pub base_delay: Duration,
+}
+
+/// Per-operation override from the command line: `OP=RETRIES[:BASE_DELAY_MS]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOverride {
+    pub operation: R2Operation,
+    pub max_retries: u32,
+    pub base_delay_ms: Option<u64>,
+}
+
+/// Parse an `OP=RETRIES[:BASE_DELAY_MS]` override.
+pub fn parse_retry_override(s: &str) -> Result<RetryOverride, String> {
+    let (operation, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected OP=RETRIES[:BASE_DELAY_MS], got '{}'", s))?;
+    let (retries, base_delay_ms) = match value.split_once(':') {
+        Some((retries, delay)) => (retries, Some(delay)),
+        None => (value, None),
+    };
+    Ok(RetryOverride {
+        operation: operation.trim().parse()?,
+        max_retries: retries
+            .trim()
+            .parse()
+            .map_err(|e| format!("invalid retry count '{}': {}", retries, e))?,
+        base_delay_ms: base_delay_ms
+            .map(|d| {
+                d.trim()
+                    .parse()
+                    .map_err(|e| format!("invalid base delay '{}': {}", d, e))
+            })
+            .transpose()?,
+    })
+}
+
+/// Retry policy for R2 calls: transient errors (429 / 5xx) on single
+/// requests, and ETag conflicts (412) on CAS loops.
+#[derive(Debug, Clone)]
+pub struct R2RetryPolicy {
+    /// Defaults for get, put, delete and list
+    pub transient: RetrySettings,
+    /// Defaults for CAS loops
+    pub cas: RetrySettings,
+    /// Cap on any single backoff delay
+    pub max_delay: Duration,
+    overrides: HashMap<R2Operation, RetrySettings>,
+}
+
+impl Default for R2RetryPolicy {
+    fn default() -> Self {
+        let base_delay = Duration::from_millis(200);
+        Self::new(
+            RetrySettings {
+                max_retries: 5,
+                base_delay,
+            },
+            RetrySettings {
+                max_retries: 10,
+                base_delay,
+            },
+            Duration::from_secs(30),
+        )
+    }
+}
+
+impl R2RetryPolicy {
+    pub fn new(transient: RetrySettings, cas: RetrySettings, max_delay: Duration) -> Self {
+        Self {
+            transient,
+            cas,
+            max_delay,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Apply per-operation overrides. An override without a base delay
+    /// keeps the operation's default one.
+    pub fn with_overrides(mut self, overrides: &[RetryOverride]) -> Self {
+        for o in overrides {
+            let default = self.settings(o.operation);
+            self.overrides.insert(
+                o.operation,
+                RetrySettings {
+                    max_retries: o.max_retries,
+                    base_delay: o
+                        .base_delay_ms
+                        .map_or(default.base_delay, Duration::from_millis),
+                },
+            );
+        }
+        self
+    }
+
+    /// Effective settings for `operation`.
+    pub fn settings(&self, operation: R2Operation) -> RetrySettings {
+        self.overrides.get(&operation).copied().unwrap_or(if operation.is_cas() {
+            self.cas
+        } else {
+            self.transient
+        })
+    }
+
+    /// Backoff before the retry following failed attempt `attempt` (from 0),
+    /// without jitter.
+    pub fn delay(&self, operation: R2Operation, attempt: u32) -> Duration {
+        self.settings(operation)
+            .base_delay
+            .saturating_mul(1u32 << attempt.min(31))
+            .min(self.max_delay)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// In-memory snapshots of listings, served to clients that accept stale
+/// reads (dashboards polling ListSessions, ...) instead of listing R2 again.
+///
+/// A snapshot is served until it is `max_age` old; the next stale read then
+/// lists R2 and replaces it. Fresh reads also replace it, so polling clients
+/// see their own writes once they read fresh. Expired snapshots are dropped
+/// whenever one is stored.
+#[derive(Debug)]
+pub struct ListSnapshots<K, V> {
+    max_age: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> ListSnapshots<K, V> {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The snapshot of `key` and its age, unless it is missing or too old.
+    pub fn get(&self, key: &K) -> Option<(V, Duration)> {
+        let entries = self.entries.lock().unwrap();
+        let (taken_at, value) = entries.get(key)?;
+        let age = taken_at.elapsed();
+        (age < self.max_age).then(|| (value.clone(), age))
+    }
+
+    /// Store a listing of `key` taken just now.
+    pub fn put(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (taken_at, _)| taken_at.elapsed() < self.max_age);
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_then_get_returns_the_listing() {
+        let snapshots = ListSnapshots::new(Duration::from_secs(60));
+        snapshots.put("t1", vec!["s1", "s2"]);
+
+        let (listing, age) = snapshots.get(&"t1").unwrap();
+        assert_eq!(listing, vec!["s1", "s2"]);
+        assert!(age < Duration::from_secs(60));
+        assert!(snapshots.get(&"t2").is_none());
+    }
+
+    #[test]
+    fn test_put_replaces_the_previous_listing() {
+        let snapshots = ListSnapshots::new(Duration::from_secs(60));
+        snapshots.put("t1", vec!["s1"]);
+        snapshots.put("t1", vec!["s1", "s2"]);
+
+        assert_eq!(snapshots.get(&"t1").unwrap().0, vec!["s1", "s2"]);
+    }
+
+    #[test]
+    fn test_snapshots_expire_at_their_max_age() {
+        let snapshots = ListSnapshots::new(Duration::from_millis(20));
+        snapshots.put("t1", vec!["s1"]);
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(snapshots.get(&"t1").is_none());
+    }
+
+    #[test]
+    fn test_put_drops_expired_snapshots() {
+        let snapshots = ListSnapshots::new(Duration::from_millis(20));
+        snapshots.put("t1", vec!["s1"]);
+        std::thread::sleep(Duration::from_millis(30));
+        snapshots.put("t2", vec!["s2"]);
+
+        assert_eq!(snapshots.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_a_zero_max_age_serves_nothing() {
+        let snapshots = ListSnapshots::new(Duration::ZERO);
+        snapshots.put("t1", vec!["s1"]);
+
+        assert!(snapshots.get(&"t1").is_none());
+    }
+}
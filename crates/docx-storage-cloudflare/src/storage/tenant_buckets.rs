@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use base64::Engine;
+use docx_storage_core::{owning_tenant, StorageError};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::public_endpoint::check_endpoint;
+use super::R2Storage;
+
+/// A bucket registered by a tenant, with its secret decrypted.
+pub struct TenantBucket {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// A row of D1's `tenant_bucket` table, secret still sealed.
+#[derive(Deserialize)]
+struct TenantBucketRow {
+    #[serde(rename = "tenantId")]
+    tenant_id: String,
+    endpoint: String,
+    region: String,
+    bucket: String,
+    #[serde(rename = "dataRegion")]
+    data_region: String,
+    #[serde(rename = "accessKeyId")]
+    access_key_id: String,
+    #[serde(rename = "secretAccessKey")]
+    secret_access_key: String,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+/// D1 query request body.
+#[derive(Serialize)]
+struct D1QueryRequest {
+    sql: &'static str,
+    params: Vec<String>,
+}
+
+/// D1 API response structure.
+#[derive(Deserialize)]
+struct D1Response {
+    success: bool,
+    result: Option<Vec<D1QueryResult>>,
+    errors: Option<Vec<D1Error>>,
+}
+
+#[derive(Deserialize)]
+struct D1QueryResult {
+    results: Vec<TenantBucketRow>,
+}
+
+#[derive(Deserialize)]
+struct D1Error {
+    message: String,
+}
+
+/// Builds the storage of a registered bucket.
+pub type OpenBucket = dyn Fn(&TenantBucket) -> anyhow::Result<R2Storage> + Send + Sync;
+
+/// A pooled bucket: its storage, or why it can't be opened.
+#[derive(Clone)]
+struct PooledBucket {
+    updated_at: String,
+    storage: Result<Arc<R2Storage>, String>,
+}
+
+/// Buckets tenants registered to keep their data in their own cloud account,
+/// read from D1 (`tenant_bucket`, written by the website) with one S3 client
+/// per tenant.
+///
+/// The pool is reloaded by `refresh`; a client is only rebuilt when its row
+/// changed. Registering a bucket does not move data already stored in the
+/// shared buckets, and removing it sends the tenant back to them. A tenant
+/// whose bucket can't be opened is refused rather than served from a shared
+/// bucket.
+///
+/// Only buckets on public HTTPS hosts are opened, and with an allow-list of
+/// regions, only buckets declaring one of those regions.
+pub struct TenantBuckets {
+    http: reqwest::Client,
+    query_url: String,
+    api_token: String,
+    key: LessSafeKey,
+    open: Box<OpenBucket>,
+    allowed_regions: Vec<String>,
+    pool: RwLock<HashMap<String, PooledBucket>>,
+}
+
+impl TenantBuckets {
+    /// Read the buckets from D1 `database_id`, decrypting secrets with `key`
+    /// (32 bytes, base64) and opening each bucket with `open`.
+    pub fn new(
+        account_id: &str,
+        api_token: &str,
+        database_id: &str,
+        key: &str,
+        open: Box<OpenBucket>,
+    ) -> Result<Self, StorageError> {
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(key.trim())
+            .ok()
+            .and_then(|key| UnboundKey::new(&AES_256_GCM, &key).ok())
+            .ok_or_else(|| {
+                StorageError::InvalidArgument(
+                    "bucket credentials key must be 32 bytes, base64-encoded".to_string(),
+                )
+            })?;
+        Ok(Self {
+            http: reqwest::Client::new(),
+            query_url: format!(
+                "https://api.cloudflare.com/client/v4/accounts/{}/d1/database/{}/query",
+                account_id, database_id
+            ),
+            api_token: api_token.to_string(),
+            key: LessSafeKey::new(key),
+            open,
+            allowed_regions: Vec::new(),
+            pool: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Refuse buckets whose declared region isn't in `allowed` (e.g. only
+    /// "eu" for EU-only storage). An empty list allows every region.
+    pub fn with_allowed_regions(mut self, allowed: &[String]) -> Self {
+        self.allowed_regions = allowed.to_vec();
+        self
+    }
+
+    /// The bucket `tenant_id` registered, if any. Sandboxes stay with their
+    /// tenant.
+    pub fn get(&self, tenant_id: &str) -> Option<Result<Arc<R2Storage>, StorageError>> {
+        let tenant_id = owning_tenant(tenant_id);
+        let pool = self.pool.read().unwrap_or_else(|e| e.into_inner());
+        pool.get(tenant_id).map(|pooled| {
+            pooled.storage.clone().map_err(|reason| {
+                StorageError::PreconditionFailed(format!(
+                    "the bucket registered by tenant '{}' can't be used: {}",
+                    tenant_id, reason
+                ))
+            })
+        })
+    }
+
+    /// Every tenant with a usable bucket, with that bucket.
+    pub fn all(&self) -> Vec<(String, Arc<R2Storage>)> {
+        let pool = self.pool.read().unwrap_or_else(|e| e.into_inner());
+        pool.iter()
+            .filter_map(|(tenant_id, pooled)| {
+                let storage = pooled.storage.as_ref().ok()?;
+                Some((tenant_id.clone(), storage.clone()))
+            })
+            .collect()
+    }
+
+    /// Reload the registered buckets from D1. Returns the number of tenants
+    /// with a bucket.
+    pub async fn refresh(&self) -> anyhow::Result<usize> {
+        let rows = self.query().await?;
+        Ok(self.reload(rows))
+    }
+
+    /// Replace the pool with the buckets of `rows`.
+    fn reload(&self, rows: Vec<TenantBucketRow>) -> usize {
+        // Clients are opened outside the lock; tenants keep their current
+        // bucket until the new pool replaces it
+        let mut previous = self.pool.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut pool = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let pooled = match previous.remove(&row.tenant_id) {
+                Some(pooled) if pooled.updated_at == row.updated_at => pooled,
+                _ => {
+                    let storage = self
+                        .open_row(&row)
+                        .map(Arc::new)
+                        .map_err(|e| e.to_string());
+                    match &storage {
+                        Ok(_) => info!(tenant_id = %row.tenant_id, bucket = %row.bucket, "Opened tenant bucket"),
+                        Err(e) => warn!(tenant_id = %row.tenant_id, "Failed to open tenant bucket: {}", e),
+                    }
+                    PooledBucket {
+                        updated_at: row.updated_at,
+                        storage,
+                    }
+                }
+            };
+            pool.insert(row.tenant_id, pooled);
+        }
+        for tenant_id in previous.keys() {
+            info!(tenant_id = %tenant_id, "Tenant bucket removed");
+        }
+
+        let count = pool.len();
+        *self.pool.write().unwrap_or_else(|e| e.into_inner()) = pool;
+        count
+    }
+
+    fn open_row(&self, row: &TenantBucketRow) -> anyhow::Result<R2Storage> {
+        if row.data_region.is_empty() {
+            anyhow::bail!("the bucket has no declared region");
+        }
+        if !self.allowed_regions.is_empty() && !self.allowed_regions.contains(&row.data_region) {
+            anyhow::bail!(
+                "region '{}' is not allowed (allowed: {})",
+                row.data_region,
+                self.allowed_regions.join(", ")
+            );
+        }
+        check_endpoint(&row.endpoint).map_err(anyhow::Error::msg)?;
+        let secret_access_key = open_secret(&self.key, &row.tenant_id, &row.secret_access_key)?;
+        (self.open)(&TenantBucket {
+            endpoint: row.endpoint.clone(),
+            region: row.region.clone(),
+            bucket: row.bucket.clone(),
+            access_key_id: row.access_key_id.clone(),
+            secret_access_key,
+        })
+    }
+
+    async fn query(&self) -> anyhow::Result<Vec<TenantBucketRow>> {
+        let query = D1QueryRequest {
+            sql: r#"SELECT "tenantId", "endpoint", "region", "dataRegion", "bucket", "accessKeyId", "secretAccessKey", "updatedAt" FROM "tenant_bucket""#,
+            params: Vec::new(),
+        };
+
+        let response = self
+            .http
+            .post(&self.query_url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .json(&query)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            anyhow::bail!("D1 API returned {}: {}", status, body);
+        }
+
+        let d1_response: D1Response = serde_json::from_str(&body)?;
+        if !d1_response.success {
+            let error_msg = d1_response
+                .errors
+                .map(|errs| errs.into_iter().map(|e| e.message).collect::<Vec<_>>().join(", "))
+                .unwrap_or_else(|| "Unknown D1 error".to_string());
+            anyhow::bail!("D1 query failed: {}", error_msg);
+        }
+
+        Ok(d1_response
+            .result
+            .and_then(|mut r| r.pop())
+            .map(|qr| qr.results)
+            .unwrap_or_default())
+    }
+}
+
+/// Decrypt a secret sealed by the website: base64 of the nonce, then the
+/// AES-256-GCM ciphertext and tag, with the tenant ID as associated data.
+fn open_secret(key: &LessSafeKey, tenant_id: &str, sealed: &str) -> anyhow::Result<String> {
+    let sealed = base64::engine::general_purpose::STANDARD.decode(sealed)?;
+    if sealed.len() < NONCE_LEN {
+        anyhow::bail!("sealed secret is too short");
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow::anyhow!("invalid nonce"))?;
+    let mut in_out = ciphertext.to_vec();
+    let secret = key
+        .open_in_place(nonce, Aad::from(tenant_id.as_bytes()), &mut in_out)
+        .map_err(|_| anyhow::anyhow!("secret doesn't decrypt with the bucket credentials key"))?;
+    Ok(String::from_utf8(secret.to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use aws_sdk_s3::config::{BehaviorVersion, Region};
+    use ring::aead::{UnboundKey, AES_256_GCM};
+
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn key() -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &KEY).unwrap())
+    }
+
+    /// Seal like the website does.
+    fn seal(tenant_id: &str, secret: &str) -> String {
+        let nonce = [3u8; NONCE_LEN];
+        let mut in_out = secret.as_bytes().to_vec();
+        key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(tenant_id.as_bytes()),
+                &mut in_out,
+            )
+            .unwrap();
+        base64::engine::general_purpose::STANDARD.encode([nonce.as_slice(), &in_out].concat())
+    }
+
+    fn row(tenant_id: &str, updated_at: &str) -> TenantBucketRow {
+        TenantBucketRow {
+            tenant_id: tenant_id.to_string(),
+            endpoint: "https://s3.eu-central-1.amazonaws.com".to_string(),
+            region: "eu-central-1".to_string(),
+            data_region: "eu".to_string(),
+            bucket: format!("{}-docs", tenant_id),
+            access_key_id: "AKIA".to_string(),
+            secret_access_key: seal(tenant_id, "secret"),
+            updated_at: updated_at.to_string(),
+        }
+    }
+
+    /// Tenant buckets opening offline clients, counting the opens.
+    fn buckets(opens: Arc<AtomicUsize>) -> TenantBuckets {
+        let open = move |bucket: &TenantBucket| {
+            assert_eq!(bucket.secret_access_key, "secret");
+            opens.fetch_add(1, Ordering::SeqCst);
+            let config = aws_sdk_s3::Config::builder()
+                .behavior_version(BehaviorVersion::latest())
+                .region(Region::new(bucket.region.clone()))
+                .build();
+            Ok(R2Storage::new(aws_sdk_s3::Client::from_conf(config), bucket.bucket.clone()))
+        };
+        let key = base64::engine::general_purpose::STANDARD.encode(KEY);
+        TenantBuckets::new("account", "token", "db", &key, Box::new(open)).unwrap()
+    }
+
+    #[test]
+    fn test_open_secret_round_trip() {
+        let sealed = seal("acme", "s3cr3t");
+        assert_eq!(open_secret(&key(), "acme", &sealed).unwrap(), "s3cr3t");
+    }
+
+    #[test]
+    fn test_open_secret_is_bound_to_the_tenant() {
+        let sealed = seal("acme", "s3cr3t");
+        let err = open_secret(&key(), "globex", &sealed).unwrap_err();
+        assert!(err.to_string().contains("doesn't decrypt"));
+    }
+
+    #[test]
+    fn test_open_secret_rejects_short_input() {
+        let short = base64::engine::general_purpose::STANDARD.encode([1u8; 5]);
+        assert!(open_secret(&key(), "acme", &short).unwrap_err().to_string().contains("too short"));
+        // A nonce without ciphertext or tag doesn't decrypt
+        let nonce_only = base64::engine::general_purpose::STANDARD.encode([1u8; NONCE_LEN]);
+        assert!(open_secret(&key(), "acme", &nonce_only).is_err());
+        assert!(open_secret(&key(), "acme", "not base64!").is_err());
+    }
+
+    #[test]
+    fn test_reload_reuses_unchanged_reopens_changed_and_drops_removed() {
+        let opens = Arc::new(AtomicUsize::new(0));
+        let buckets = buckets(opens.clone());
+
+        assert_eq!(buckets.reload(vec![row("acme", "t1"), row("globex", "t1")]), 2);
+        assert_eq!(opens.load(Ordering::SeqCst), 2);
+        let acme = buckets.get("acme").unwrap().unwrap();
+        let globex = buckets.get("globex").unwrap().unwrap();
+
+        // acme unchanged, globex updated
+        assert_eq!(buckets.reload(vec![row("acme", "t1"), row("globex", "t2")]), 2);
+        assert_eq!(opens.load(Ordering::SeqCst), 3);
+        assert!(Arc::ptr_eq(&acme, &buckets.get("acme").unwrap().unwrap()));
+        assert!(!Arc::ptr_eq(&globex, &buckets.get("globex").unwrap().unwrap()));
+
+        assert_eq!(buckets.reload(vec![row("globex", "t2")]), 1);
+        assert!(buckets.get("acme").is_none());
+        // Sandboxes follow their tenant
+        assert!(buckets.get(&format!("globex{}", docx_storage_core::SANDBOX_SUFFIX)).is_some());
+        assert_eq!(buckets.all().len(), 1);
+    }
+
+    #[test]
+    fn test_reload_refuses_disallowed_regions_and_local_endpoints() {
+        let buckets = buckets(Arc::new(AtomicUsize::new(0))).with_allowed_regions(&["eu".to_string()]);
+
+        let mut us = row("acme", "t1");
+        us.data_region = "us".to_string();
+        let mut undeclared = row("globex", "t1");
+        undeclared.data_region = String::new();
+        let mut metadata = row("initech", "t1");
+        metadata.endpoint = "https://169.254.169.254".to_string();
+        buckets.reload(vec![us, undeclared, metadata, row("umbrella", "t1")]);
+
+        for (tenant_id, reason) in [
+            ("acme", "region 'us' is not allowed"),
+            ("globex", "no declared region"),
+            ("initech", "not public"),
+        ] {
+            let Some(Err(err)) = buckets.get(tenant_id) else {
+                panic!("{} was opened", tenant_id);
+            };
+            assert!(err.to_string().contains(reason), "{}: {}", tenant_id, err);
+        }
+        assert!(buckets.get("umbrella").unwrap().is_ok());
+        assert_eq!(buckets.all().len(), 1);
+    }
+}
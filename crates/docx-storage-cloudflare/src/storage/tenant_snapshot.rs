@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Listings of the tenant a snapshot may take before giving up on the tenant
+/// ever holding still.
+pub const MAX_SNAPSHOT_ROUNDS: u32 = 5;
+
+/// Point-in-time manifest of a tenant's objects.
+///
+/// Objects are read until a listing of the tenant shows exactly the ETags
+/// already read, so the manifest is the state of that last listing even if
+/// writes went on meanwhile. When copied, the objects and `manifest.json`
+/// are stored under `{tenant}/snapshots/{snapshot_id}/`, which later
+/// snapshots skip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantSnapshot {
+    pub snapshot_id: String,
+    pub tenant_id: String,
+    /// When the listing the manifest matches completed
+    pub taken_at: DateTime<Utc>,
+    /// Prefix the objects were copied under, if they were
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_prefix: Option<String>,
+    /// Listings it took for the tenant to hold still
+    pub rounds: u32,
+    pub objects: Vec<SnapshotObject>,
+}
+
+/// One object of a tenant snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotObject {
+    /// Key relative to the tenant prefix (`index.json`, `sessions/...`)
+    pub key: String,
+    pub etag: String,
+    /// Hex SHA-256 of the content
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl TenantSnapshot {
+    /// Total size of the objects, in bytes.
+    pub fn total_bytes(&self) -> u64 {
+        self.objects.iter().map(|o| o.size).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::fake_s3::FakeS3;
+
+    #[test]
+    fn test_manifest_round_trips_through_json() {
+        let snapshot = TenantSnapshot {
+            snapshot_id: "20260101T000000.000Z".to_string(),
+            tenant_id: "t".to_string(),
+            taken_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+            copy_prefix: None,
+            rounds: 1,
+            objects: vec![SnapshotObject {
+                key: "index.json".to_string(),
+                etag: "\"abc\"".to_string(),
+                sha256: "00".repeat(32),
+                size: 2,
+            }],
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("copy_prefix"));
+        assert_eq!(
+            serde_json::from_str::<TenantSnapshot>(&json).unwrap(),
+            snapshot
+        );
+        assert_eq!(snapshot.total_bytes(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_copied_snapshot_round_trips_objects_and_manifest() {
+        let s3 = FakeS3::start().await;
+        s3.put("t/index.json", b"{}");
+        s3.put("t/sessions/s1.docx", b"document");
+        s3.put("other/index.json", b"{}");
+        let storage = s3.storage();
+
+        let snapshot = storage.snapshot_tenant("t", true).await.unwrap();
+
+        // One listing to read the objects, one to see they held still
+        assert_eq!(snapshot.rounds, 2);
+        let keys: Vec<_> = snapshot.objects.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, ["index.json", "sessions/s1.docx"]);
+        assert_eq!(snapshot.total_bytes(), 10);
+        let copy_prefix = snapshot.copy_prefix.clone().unwrap();
+        assert_eq!(
+            s3.get(&format!("{}sessions/s1.docx", copy_prefix)).unwrap(),
+            b"document"
+        );
+        let manifest = s3.get(&format!("{}manifest.json", copy_prefix)).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<TenantSnapshot>(&manifest).unwrap(),
+            snapshot
+        );
+
+        // Later snapshots skip the copies
+        let again = storage.snapshot_tenant("t", false).await.unwrap();
+        assert_eq!(again.objects, snapshot.objects);
+        assert!(again.copy_prefix.is_none());
+    }
+}
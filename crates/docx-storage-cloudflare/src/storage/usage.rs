@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// R2 billing class of an object-store request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpClass {
+    /// Writes and listings (PUT, LIST, bucket configuration changes)
+    A,
+    /// Reads (GET, HEAD, bucket configuration reads)
+    B,
+    /// Requests R2 doesn't bill (DELETE)
+    Free,
+}
+
+/// Object-store requests and bytes counted for one tenant, day and RPC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounts {
+    pub class_a_ops: u64,
+    pub class_b_ops: u64,
+    pub free_ops: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+}
+
+impl UsageCounts {
+    fn add(&mut self, other: &UsageCounts) {
+        self.class_a_ops += other.class_a_ops;
+        self.class_b_ops += other.class_b_ops;
+        self.free_ops += other.free_ops;
+        self.bytes_read += other.bytes_read;
+        self.bytes_written += other.bytes_written;
+    }
+}
+
+/// Where counted requests are attributed: UTC day, tenant and the RPC that
+/// caused them (`background` for work outside any RPC, like purges).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UsageKey {
+    pub day: NaiveDate,
+    pub tenant_id: String,
+    pub rpc: String,
+}
+
+/// Prices of R2 requests, in USD per million.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct R2Rates {
+    pub class_a_per_million: f64,
+    pub class_b_per_million: f64,
+}
+
+impl Default for R2Rates {
+    /// Cloudflare's list prices for the Standard storage class.
+    fn default() -> Self {
+        Self {
+            class_a_per_million: 4.50,
+            class_b_per_million: 0.36,
+        }
+    }
+}
+
+impl R2Rates {
+    /// Request cost of `counts`, in USD. Storage and the monthly free tier
+    /// aren't included.
+    pub fn cost(&self, counts: &UsageCounts) -> f64 {
+        (counts.class_a_ops as f64 * self.class_a_per_million
+            + counts.class_b_ops as f64 * self.class_b_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// In-memory counts of the R2 requests made on behalf of each tenant, per
+/// UTC day and RPC, to estimate what they cost. Every attempt is counted,
+/// retries included, since R2 bills them all. Counts are lost on restart
+/// and days older than the retention are dropped.
+#[derive(Debug)]
+pub struct UsageMeter {
+    since: DateTime<Utc>,
+    retention_days: u32,
+    counts: Mutex<BTreeMap<UsageKey, UsageCounts>>,
+}
+
+impl UsageMeter {
+    pub fn new(retention_days: u32) -> Self {
+        Self {
+            since: Utc::now(),
+            retention_days,
+            counts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// When counting started.
+    pub fn since(&self) -> DateTime<Utc> {
+        self.since
+    }
+
+    /// Count a request of `class` on object `key` (the tenant is its first
+    /// segment), made by the current RPC.
+    pub fn record(&self, key: &str, class: OpClass) {
+        self.add(key, |counts| match class {
+            OpClass::A => counts.class_a_ops += 1,
+            OpClass::B => counts.class_b_ops += 1,
+            OpClass::Free => counts.free_ops += 1,
+        });
+    }
+
+    /// Count bytes transferred to or from object `key`.
+    pub fn record_bytes(&self, key: &str, read: u64, written: u64) {
+        self.add(key, |counts| {
+            counts.bytes_read += read;
+            counts.bytes_written += written;
+        });
+    }
+
+    fn add(&self, key: &str, update: impl FnOnce(&mut UsageCounts)) {
+        let now = Utc::now();
+        let key = UsageKey {
+            day: now.date_naive(),
+            tenant_id: key.split_once('/').map_or("", |(tenant, _)| tenant).to_string(),
+            rpc: docx_storage_core::current_rpc().unwrap_or_else(|| "background".to_string()),
+        };
+        let mut counts = self.counts.lock().unwrap();
+        if !counts.contains_key(&key) {
+            // First count of a new key: a good time to drop expired days
+            let oldest = key.day - chrono::Days::new(u64::from(self.retention_days));
+            counts.retain(|k, _| k.day > oldest);
+        }
+        update(counts.entry(key).or_default());
+    }
+
+    /// Counts of `tenant_id` (every tenant when `None`) between the days
+    /// `from` and `to`, inclusive. Without `by_rpc`, the RPCs of a tenant and
+    /// day are summed and reported with an empty RPC.
+    pub fn usage(
+        &self,
+        tenant_id: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        by_rpc: bool,
+    ) -> Vec<(UsageKey, UsageCounts)> {
+        let counts = self.counts.lock().unwrap();
+        let mut usage: BTreeMap<UsageKey, UsageCounts> = BTreeMap::new();
+        for (key, value) in counts.iter() {
+            if tenant_id.is_some_and(|t| t != key.tenant_id)
+                || from.is_some_and(|from| key.day < from)
+                || to.is_some_and(|to| key.day > to)
+            {
+                continue;
+            }
+            let key = if by_rpc {
+                key.clone()
+            } else {
+                UsageKey {
+                    rpc: String::new(),
+                    ..key.clone()
+                }
+            };
+            usage.entry(key).or_default().add(value);
+        }
+        usage.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::fake_s3::FakeS3;
+    use docx_storage_core::{StorageBackend, WalEntry};
+    use std::sync::Arc;
+
+    fn wal_entry(position: u64) -> WalEntry {
+        WalEntry {
+            position,
+            operation: "add".to_string(),
+            path: String::new(),
+            patch_json: br#"{"patches":"[]","timestamp":"2026-01-01T00:00:00Z"}"#.to_vec(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn totals(meter: &UsageMeter, tenant_id: &str) -> UsageCounts {
+        let usage = meter.usage(Some(tenant_id), None, None, false);
+        assert!(usage.len() <= 1, "one day and tenant expected: {:?}", usage);
+        usage.first().map(|(_, counts)| *counts).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_counts_are_attributed_to_the_tenant_of_the_key() {
+        let meter = UsageMeter::new(7);
+        meter.record("t1/sessions/s.docx", OpClass::A);
+        meter.record("t1/sessions/s.docx", OpClass::B);
+        meter.record("t2/index.json", OpClass::Free);
+        meter.record_bytes("t1/sessions/s.docx", 10, 20);
+
+        let counts = totals(&meter, "t1");
+        assert_eq!(
+            (counts.class_a_ops, counts.class_b_ops, counts.free_ops),
+            (1, 1, 0)
+        );
+        assert_eq!((counts.bytes_read, counts.bytes_written), (10, 20));
+        assert_eq!(totals(&meter, "t2").free_ops, 1);
+        assert_eq!(meter.usage(None, None, None, false).len(), 2);
+    }
+
+    #[test]
+    fn test_usage_groups_by_rpc_only_when_asked() {
+        let meter = UsageMeter::new(7);
+        meter.record("t/index.json", OpClass::A);
+
+        let by_rpc = meter.usage(None, None, None, true);
+        assert_eq!(by_rpc[0].0.rpc, "background");
+        let summed = meter.usage(None, None, None, false);
+        assert_eq!(summed[0].0.rpc, "");
+    }
+
+    #[test]
+    fn test_usage_filters_days() {
+        let meter = UsageMeter::new(7);
+        meter.record("t/index.json", OpClass::A);
+        let today = Utc::now().date_naive();
+
+        assert_eq!(meter.usage(None, Some(today), Some(today), false).len(), 1);
+        assert!(meter.usage(None, today.succ_opt(), None, false).is_empty());
+        assert!(meter.usage(None, None, today.pred_opt(), false).is_empty());
+    }
+
+    #[test]
+    fn test_cost_applies_the_rate_of_each_class() {
+        let counts = UsageCounts {
+            class_a_ops: 2_000_000,
+            class_b_ops: 1_000_000,
+            free_ops: 5_000_000,
+            ..Default::default()
+        };
+
+        assert!((R2Rates::default().cost(&counts) - 9.36).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_totals_after_save_truncate_and_delete() {
+        let s3 = FakeS3::start().await;
+        let meter = Arc::new(UsageMeter::new(7));
+        let storage = s3.storage().with_usage_meter(meter.clone());
+
+        // One PUT of the document
+        storage.save_session("t", "s1", b"document").await.unwrap();
+        let saved = totals(&meter, "t");
+        assert_eq!(
+            (saved.class_a_ops, saved.class_b_ops, saved.free_ops),
+            (1, 0, 0)
+        );
+        assert_eq!((saved.bytes_read, saved.bytes_written), (0, 8));
+
+        let entries: Vec<_> = (1..=3).map(wal_entry).collect();
+        storage.append_wal("t", "s1", &entries).await.unwrap();
+        let appended = totals(&meter, "t");
+        // Reading the WAL back and writing the shorter one
+        storage.truncate_wal("t", "s1", 1).await.unwrap();
+        let truncated = totals(&meter, "t");
+        assert!(truncated.class_a_ops > appended.class_a_ops);
+        assert!(truncated.class_b_ops > appended.class_b_ops);
+        assert!(truncated.bytes_read > appended.bytes_read);
+        assert!(truncated.bytes_written > appended.bytes_written);
+        assert_eq!(truncated.free_ops, 0);
+
+        // A GET of the document, a listing of its checkpoints and one free
+        // DELETE each for the document, WAL, WAL index and history
+        storage.delete_session("t", "s1").await.unwrap();
+        let deleted = totals(&meter, "t");
+        assert_eq!(deleted.class_a_ops - truncated.class_a_ops, 1);
+        assert_eq!(deleted.class_b_ops - truncated.class_b_ops, 1);
+        assert_eq!(deleted.free_ops, 4);
+        assert_eq!(deleted.bytes_read - truncated.bytes_read, 8);
+        assert_eq!(deleted.bytes_written, truncated.bytes_written);
+
+        assert_eq!(totals(&meter, "other"), UsageCounts::default());
+    }
+}
@@ -0,0 +1,75 @@
+//! Conformance suite for docx-mcp storage backends.
+//!
+//! Any `StorageBackend` / `LockManager` implementation can prove it behaves like
+//! the reference backends by running this suite from its own tests:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn conformance() {
+//!     let backend = MyBackend::new(...);
+//!     docx_storage_conformance::run_storage_suite(&backend).await;
+//! }
+//! ```
+//!
+//! Each check runs in its own freshly generated tenant, so the suite can be
+//! pointed at a shared bucket or database. Failures panic with the backend
+//! name and the check that failed.
+//!
+//! Checks that require atomic read-modify-write (e.g. concurrent WAL appends)
+//! are not part of `run_storage_suite` since single-writer backends don't
+//! provide them; run them explicitly for backends that claim CAS semantics.
+
+mod lock;
+mod storage;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use docx_storage_core::{LockManager, StorageBackend};
+
+pub use lock::{lock_conflicts, lock_reacquire_after_release, lock_tenant_isolation};
+pub use storage::{
+    admin_log, checkpoint_edge_cases, index_round_trip, index_schema_round_trip, library_crud, session_crud,
+    session_delete_cascades, session_with_history, streamed_saves, tenant_isolation,
+    tenant_listing, unicode_session_ids, wal_concurrent_appends, wal_ordering, wal_positions,
+    wal_schema, wal_tail, wal_truncate,
+};
+
+/// Run every storage check that all backends must pass.
+pub async fn run_storage_suite(backend: &dyn StorageBackend) {
+    session_crud(backend).await;
+    session_delete_cascades(backend).await;
+    unicode_session_ids(backend).await;
+    streamed_saves(backend).await;
+    tenant_isolation(backend).await;
+    tenant_listing(backend).await;
+    index_round_trip(backend).await;
+    index_schema_round_trip(backend).await;
+    library_crud(backend).await;
+    wal_ordering(backend).await;
+    wal_truncate(backend).await;
+    wal_tail(backend).await;
+    wal_schema(backend).await;
+    wal_positions(backend).await;
+    checkpoint_edge_cases(backend).await;
+    session_with_history(backend).await;
+    admin_log(backend).await;
+}
+
+/// Run every lock manager check.
+pub async fn run_lock_suite(locks: &dyn LockManager) {
+    lock_conflicts(locks).await;
+    lock_reacquire_after_release(locks).await;
+    lock_tenant_isolation(locks).await;
+}
+
+/// Generate a tenant ID that no other check (or concurrent run) uses.
+pub(crate) fn unique_tenant(check: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "conformance-{}-{}-{}-{}",
+        check,
+        std::process::id(),
+        chrono::Utc::now().timestamp_micros(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
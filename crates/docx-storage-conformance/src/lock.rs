@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use docx_storage_core::LockManager;
+
+use crate::unique_tenant;
+
+const TTL: Duration = Duration::from_secs(30);
+
+/// A held lock rejects other holders, even after they try to release it.
+pub async fn lock_conflicts(locks: &dyn LockManager) {
+    let tenant = unique_tenant("lock-conflict");
+    let resource = "index";
+
+    assert!(locks.acquire(&tenant, resource, "holder-a", TTL).await.unwrap().acquired);
+    assert!(
+        !locks.acquire(&tenant, resource, "holder-b", TTL).await.unwrap().acquired,
+        "a second holder must not acquire a held lock"
+    );
+    assert!(
+        locks.acquire(&tenant, resource, "holder-a", TTL).await.unwrap().acquired,
+        "the current holder must be able to re-acquire its own lock"
+    );
+
+    locks.release(&tenant, resource, "holder-b").await.unwrap();
+    assert!(
+        !locks.acquire(&tenant, resource, "holder-b", TTL).await.unwrap().acquired,
+        "releasing with the wrong holder must not free the lock"
+    );
+
+    locks.release(&tenant, resource, "holder-a").await.unwrap();
+}
+
+/// Once released, a lock can be taken by someone else; releasing twice is harmless.
+pub async fn lock_reacquire_after_release(locks: &dyn LockManager) {
+    let tenant = unique_tenant("lock-release");
+    let resource = "index";
+
+    assert!(locks.acquire(&tenant, resource, "holder-a", TTL).await.unwrap().acquired);
+    locks.release(&tenant, resource, "holder-a").await.unwrap();
+    locks.release(&tenant, resource, "holder-a").await.unwrap();
+
+    assert!(
+        locks.acquire(&tenant, resource, "holder-b", TTL).await.unwrap().acquired,
+        "a released lock must be acquirable by another holder"
+    );
+    locks.release(&tenant, resource, "holder-b").await.unwrap();
+
+    // Releasing a lock that was never taken must not fail.
+    locks.release(&tenant, "never-locked", "holder-c").await.unwrap();
+}
+
+/// The same resource name in two tenants is two independent locks.
+pub async fn lock_tenant_isolation(locks: &dyn LockManager) {
+    let tenant_a = unique_tenant("lock-tenant-a");
+    let tenant_b = unique_tenant("lock-tenant-b");
+    let resource = "index";
+
+    assert!(locks.acquire(&tenant_a, resource, "holder-a", TTL).await.unwrap().acquired);
+    assert!(
+        locks.acquire(&tenant_b, resource, "holder-b", TTL).await.unwrap().acquired,
+        "locks must be scoped per tenant"
+    );
+
+    locks.release(&tenant_a, resource, "holder-a").await.unwrap();
+    locks.release(&tenant_b, resource, "holder-b").await.unwrap();
+}
@@ -0,0 +1,1012 @@
+use docx_storage_core::{
+    feature, load_session_with_history, AdminAction, AdminLogEntry, ChunkStream, LibraryKind,
+    SessionIndex, SessionIndexEntry, SessionWithHistory, StorageBackend, StorageError, WalEntry,
+    WalRecord, SESSION_INDEX_VERSION, WAL_SCHEMA_VERSION,
+};
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+
+use crate::unique_tenant;
+
+/// Minimal payload that looks like a DOCX (ZIP magic) to every backend.
+fn docx_bytes(tag: &str) -> Vec<u8> {
+    let mut data = b"PK\x03\x04".to_vec();
+    data.extend_from_slice(tag.as_bytes());
+    data
+}
+
+/// Build a WAL entry whose raw JSON carries `marker`, so order can be checked on read.
+fn wal_entry(position: u64, marker: &str) -> WalEntry {
+    let timestamp = chrono::Utc::now();
+    raw_wal_entry(
+        position,
+        &format!(
+            r#"{{"version":{},"patches":"[]","marker":"{}","timestamp":"{}"}}"#,
+            WAL_SCHEMA_VERSION,
+            marker,
+            timestamp.to_rfc3339()
+        ),
+    )
+}
+
+fn raw_wal_entry(position: u64, json: &str) -> WalEntry {
+    WalEntry {
+        position,
+        operation: "add".to_string(),
+        path: format!("/body/paragraph[{}]", position),
+        patch_json: json.as_bytes().to_vec(),
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn marker_of(entry: &WalEntry) -> String {
+    let text = String::from_utf8_lossy(&entry.patch_json);
+    text.split(r#""marker":""#)
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn index_entry(id: &str) -> SessionIndexEntry {
+    SessionIndexEntry {
+        id: id.to_string(),
+        source_path: Some(format!("/docs/{}.docx", id)),
+        auto_sync: true,
+        created_at: chrono::Utc::now(),
+        last_modified_at: chrono::Utc::now(),
+        docx_file: Some(format!("{}.docx", id)),
+        wal_count: 0,
+        cursor_position: 0,
+        checkpoint_positions: vec![],
+        pending_external_change: false,
+        display_name: None,
+        alias: None,
+        legal_hold: None,
+        expires_at: None,
+    }
+}
+
+// =========================================================================
+// Session Operations
+// =========================================================================
+
+/// Save, load, overwrite, list and delete a single session.
+pub async fn session_crud(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("session-crud");
+    let session = "session-crud";
+
+    assert!(
+        !backend.session_exists(&tenant, session).await.unwrap(),
+        "[{name}] session must not exist before save"
+    );
+    assert!(
+        backend.load_session(&tenant, session).await.unwrap().is_none(),
+        "[{name}] loading a missing session must return None"
+    );
+    assert!(
+        backend.list_sessions(&tenant).await.unwrap().is_empty(),
+        "[{name}] a fresh tenant must have no sessions"
+    );
+
+    let v1 = docx_bytes("v1");
+    backend.save_session(&tenant, session, &v1).await.unwrap();
+    assert!(
+        backend.session_exists(&tenant, session).await.unwrap(),
+        "[{name}] session must exist after save"
+    );
+    assert_eq!(
+        backend.load_session(&tenant, session).await.unwrap(),
+        Some(v1),
+        "[{name}] loaded bytes must match saved bytes"
+    );
+
+    let v2 = docx_bytes("second version, longer than the first");
+    backend.save_session(&tenant, session, &v2).await.unwrap();
+    assert_eq!(
+        backend.load_session(&tenant, session).await.unwrap(),
+        Some(v2),
+        "[{name}] saving again must overwrite the session"
+    );
+
+    let sessions = backend.list_sessions(&tenant).await.unwrap();
+    assert_eq!(sessions.len(), 1, "[{name}] expected exactly one listed session");
+    assert_eq!(sessions[0].session_id, session);
+
+    assert!(
+        backend.delete_session(&tenant, session).await.unwrap(),
+        "[{name}] deleting an existing session must return true"
+    );
+    assert!(
+        !backend.session_exists(&tenant, session).await.unwrap(),
+        "[{name}] session must not exist after delete"
+    );
+    assert!(
+        !backend.delete_session(&tenant, session).await.unwrap(),
+        "[{name}] deleting a missing session must return false"
+    );
+}
+
+fn chunked(data: &[u8], size: usize) -> ChunkStream<'static> {
+    let chunks: Vec<_> = data.chunks(size).map(|c| Ok(c.to_vec())).collect();
+    stream::iter(chunks).boxed()
+}
+
+/// Streamed saves store the concatenated chunks, and a failing stream
+/// leaves the previous document in place.
+pub async fn streamed_saves(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("streamed");
+    let session = "streamed";
+
+    let v1 = docx_bytes(&"streamed document ".repeat(100));
+    let written = backend
+        .save_session_stream(&tenant, session, chunked(&v1, 64))
+        .await
+        .unwrap();
+    assert_eq!(written, v1.len() as u64, "[{name}] streamed save must report its size");
+    assert_eq!(
+        backend.load_session(&tenant, session).await.unwrap(),
+        Some(v1.clone()),
+        "[{name}] streamed chunks must be stored in order"
+    );
+
+    let failing = stream::iter(vec![
+        Ok(docx_bytes("partial")),
+        Err(StorageError::Io("client went away".to_string())),
+    ])
+    .boxed();
+    assert!(
+        backend.save_session_stream(&tenant, session, failing).await.is_err(),
+        "[{name}] a failing stream must fail the save"
+    );
+    assert_eq!(
+        backend.load_session(&tenant, session).await.unwrap(),
+        Some(v1),
+        "[{name}] a failed streamed save must keep the previous document"
+    );
+
+    let checkpoint = docx_bytes("streamed checkpoint");
+    backend
+        .save_checkpoint_stream(&tenant, session, 3, chunked(&checkpoint, 5))
+        .await
+        .unwrap();
+    assert_eq!(
+        backend.load_checkpoint(&tenant, session, 3).await.unwrap(),
+        Some((checkpoint, 3)),
+        "[{name}] streamed checkpoint must round-trip"
+    );
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Deleting a session removes its WAL and checkpoints but leaves other sessions alone.
+pub async fn session_delete_cascades(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("delete-cascade");
+
+    for session in ["doomed", "survivor"] {
+        backend
+            .save_session(&tenant, session, &docx_bytes(session))
+            .await
+            .unwrap();
+        backend
+            .append_wal(&tenant, session, &[wal_entry(1, session)])
+            .await
+            .unwrap();
+        backend
+            .save_checkpoint(&tenant, session, 1, &docx_bytes(session))
+            .await
+            .unwrap();
+    }
+
+    backend.delete_session(&tenant, "doomed").await.unwrap();
+
+    let (wal, _) = backend.read_wal(&tenant, "doomed", 0, None).await.unwrap();
+    assert!(wal.is_empty(), "[{name}] WAL must be deleted with its session");
+    assert!(
+        backend.list_checkpoints(&tenant, "doomed").await.unwrap().is_empty(),
+        "[{name}] checkpoints must be deleted with their session"
+    );
+
+    assert!(backend.session_exists(&tenant, "survivor").await.unwrap());
+    let (wal, _) = backend.read_wal(&tenant, "survivor", 0, None).await.unwrap();
+    assert_eq!(wal.len(), 1, "[{name}] other sessions' WAL must be untouched");
+    assert_eq!(
+        backend.list_checkpoints(&tenant, "survivor").await.unwrap().len(),
+        1,
+        "[{name}] other sessions' checkpoints must be untouched"
+    );
+
+    backend.delete_session(&tenant, "survivor").await.unwrap();
+}
+
+/// Session IDs with non-ASCII characters round-trip through every operation.
+pub async fn unicode_session_ids(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("unicode");
+    let ids = ["résumé-2024", "отчёт", "報告書", "emoji-📄"];
+
+    for id in ids {
+        backend.save_session(&tenant, id, &docx_bytes(id)).await.unwrap();
+        backend
+            .append_wal(&tenant, id, &[wal_entry(1, id)])
+            .await
+            .unwrap();
+        backend
+            .save_checkpoint(&tenant, id, 1, &docx_bytes(id))
+            .await
+            .unwrap();
+    }
+
+    let mut listed: Vec<String> = backend
+        .list_sessions(&tenant)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.session_id)
+        .collect();
+    listed.sort();
+    let mut expected: Vec<String> = ids.iter().map(|s| s.to_string()).collect();
+    expected.sort();
+    assert_eq!(listed, expected, "[{name}] unicode session IDs must list verbatim");
+
+    for id in ids {
+        assert_eq!(
+            backend.load_session(&tenant, id).await.unwrap(),
+            Some(docx_bytes(id)),
+            "[{name}] session {id:?} must round-trip"
+        );
+        let (wal, _) = backend.read_wal(&tenant, id, 0, None).await.unwrap();
+        assert_eq!(wal.len(), 1, "[{name}] WAL of {id:?} must round-trip");
+        assert_eq!(marker_of(&wal[0]), id);
+        let (ckpt, pos) = backend.load_checkpoint(&tenant, id, 0).await.unwrap().unwrap();
+        assert_eq!((ckpt, pos), (docx_bytes(id), 1));
+    }
+
+    for id in ids {
+        assert!(backend.delete_session(&tenant, id).await.unwrap());
+    }
+}
+
+/// Data written for one tenant is invisible to another.
+pub async fn tenant_isolation(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant_a = unique_tenant("isolation-a");
+    let tenant_b = unique_tenant("isolation-b");
+    let session = "shared-name";
+
+    backend
+        .save_session(&tenant_a, session, &docx_bytes("a"))
+        .await
+        .unwrap();
+    backend
+        .append_wal(&tenant_a, session, &[wal_entry(1, "a")])
+        .await
+        .unwrap();
+
+    assert!(
+        !backend.session_exists(&tenant_b, session).await.unwrap(),
+        "[{name}] sessions must not leak across tenants"
+    );
+    let (wal, _) = backend.read_wal(&tenant_b, session, 0, None).await.unwrap();
+    assert!(wal.is_empty(), "[{name}] WAL must not leak across tenants");
+    assert!(backend.load_index(&tenant_b).await.unwrap().is_none());
+
+    backend.delete_session(&tenant_a, session).await.unwrap();
+}
+
+/// Tenants with sessions are listed.
+pub async fn tenant_listing(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("listing");
+
+    backend
+        .save_session(&tenant, "listed", &docx_bytes("listed"))
+        .await
+        .unwrap();
+    assert!(
+        backend.list_tenants().await.unwrap().contains(&tenant),
+        "[{name}] tenants with sessions must be listed"
+    );
+
+    backend.delete_session(&tenant, "listed").await.unwrap();
+}
+
+// =========================================================================
+// Index Operations
+// =========================================================================
+
+/// The session index is absent for new tenants and round-trips every field.
+pub async fn index_round_trip(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("index");
+
+    assert!(
+        backend.load_index(&tenant).await.unwrap().is_none(),
+        "[{name}] a fresh tenant must have no index"
+    );
+
+    let mut entry = index_entry("indexed");
+    entry.wal_count = 7;
+    entry.cursor_position = 5;
+    entry.checkpoint_positions = vec![2, 4];
+    entry.pending_external_change = true;
+    entry.auto_sync = false;
+    entry.expires_at = chrono::DateTime::from_timestamp(1_900_000_000, 0);
+
+    let mut index = SessionIndex::default();
+    index.upsert(entry);
+    index.upsert(index_entry("other"));
+    assert!(index
+        .rename("indexed", Some("Q3 board report"), Some("q3-board"))
+        .unwrap());
+    assert!(
+        index.rename("other", None, Some("Q3-Board")).is_err(),
+        "[{name}] aliases must be unique regardless of case"
+    );
+    backend.save_index(&tenant, &index).await.unwrap();
+
+    let loaded = backend.load_index(&tenant).await.unwrap().unwrap();
+    assert_eq!(loaded.sessions.len(), 2, "[{name}] index must keep every entry");
+    let got = loaded.get("indexed").unwrap();
+    assert_eq!(got.source_path.as_deref(), Some("/docs/indexed.docx"));
+    assert_eq!(got.wal_count, 7);
+    assert_eq!(got.cursor_position, 5);
+    assert_eq!(got.checkpoint_positions, vec![2, 4]);
+    assert!(got.pending_external_change);
+    assert!(!got.auto_sync);
+    assert_eq!(got.display_name.as_deref(), Some("Q3 board report"));
+    assert_eq!(got.alias.as_deref(), Some("q3-board"));
+    assert_eq!(got.expires_at, chrono::DateTime::from_timestamp(1_900_000_000, 0));
+    assert_eq!(
+        loaded.resolve("Q3-BOARD").map(|e| e.id.as_str()),
+        Some("indexed"),
+        "[{name}] sessions must resolve by alias"
+    );
+    assert!(loaded.get("other").unwrap().alias.is_none());
+
+    let mut index = loaded;
+    index.remove("other");
+    backend.save_index(&tenant, &index).await.unwrap();
+    let loaded = backend.load_index(&tenant).await.unwrap().unwrap();
+    assert!(
+        !loaded.contains("other"),
+        "[{name}] saving the index must replace, not merge"
+    );
+}
+
+/// Indexes of every older schema version, once migrated, round-trip through
+/// the backend at the current version.
+pub async fn index_schema_round_trip(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("index-schema");
+
+    let legacy = [
+        r#"{"sessions":[{"id":"s1","source_path":"/docs/s1.docx","created_at":"2026-01-01T00:00:00Z","modified_at":"2026-01-02T00:00:00Z","wal_position":3}]}"#,
+        r#"{"version":0,"sessions":[{"id":"s1","source_path":"/docs/s1.docx","created_at":"2026-01-01T00:00:00Z","last_modified_at":"2026-01-02T00:00:00Z","wal_count":3}]}"#,
+        r#"{"version":1,"sessions":[{"id":"s1","source_path":"/docs/s1.docx","created_at":"2026-01-01T00:00:00Z","modified_at":"2026-01-02T00:00:00Z","wal_position":3}]}"#,
+    ];
+    for json in legacy {
+        let index = SessionIndex::from_json(json.as_bytes()).unwrap();
+        assert_eq!(index.version, SESSION_INDEX_VERSION);
+        backend.save_index(&tenant, &index).await.unwrap();
+
+        let loaded = backend.load_index(&tenant).await.unwrap().unwrap();
+        assert_eq!(loaded.version, SESSION_INDEX_VERSION, "[{name}] {json}");
+        let entry = loaded.get("s1").unwrap();
+        assert_eq!(entry.wal_count, 3, "[{name}] {json}");
+        assert_eq!(entry.source_path.as_deref(), Some("/docs/s1.docx"));
+        assert_eq!(entry.last_modified_at.to_rfc3339(), "2026-01-02T00:00:00+00:00");
+        assert!(entry.auto_sync, "[{name}] auto_sync must default to on");
+    }
+
+    assert!(
+        SessionIndex::from_json(br#"{"version":999,"sessions":[]}"#).is_err(),
+        "indexes from a newer schema must be refused"
+    );
+    assert_eq!(SessionIndex::default().version, SESSION_INDEX_VERSION);
+}
+
+// =========================================================================
+// Library Operations
+// =========================================================================
+
+/// Library items round-trip, list sorted by name, and are kept apart by kind
+/// and from sessions.
+pub async fn library_crud(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("library");
+    let (template, snippet) = (LibraryKind::Template, LibraryKind::Snippet);
+
+    assert!(
+        backend.list_library_items(&tenant, template).await.unwrap().is_empty(),
+        "[{name}] a fresh tenant must have no templates"
+    );
+    assert!(backend
+        .load_library_item(&tenant, template, "letterhead")
+        .await
+        .unwrap()
+        .is_none());
+
+    backend
+        .save_library_item(&tenant, template, "letterhead", &docx_bytes("v1"))
+        .await
+        .unwrap();
+    backend
+        .save_library_item(&tenant, template, "board report", &docx_bytes("report"))
+        .await
+        .unwrap();
+    backend
+        .save_library_item(&tenant, template, "letterhead", &docx_bytes("v2"))
+        .await
+        .unwrap();
+    backend
+        .save_library_item(&tenant, snippet, "letterhead", &docx_bytes("clause"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend
+            .load_library_item(&tenant, template, "letterhead")
+            .await
+            .unwrap(),
+        Some(docx_bytes("v2")),
+        "[{name}] saving a library item must replace it"
+    );
+    assert_eq!(
+        backend
+            .load_library_item(&tenant, snippet, "letterhead")
+            .await
+            .unwrap(),
+        Some(docx_bytes("clause")),
+        "[{name}] templates and snippets must not share names"
+    );
+    let listed: Vec<(String, u64)> = backend
+        .list_library_items(&tenant, template)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.name, t.size_bytes))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("board report".to_string(), docx_bytes("report").len() as u64),
+            ("letterhead".to_string(), docx_bytes("v2").len() as u64),
+        ],
+        "[{name}] library items must list by name with their sizes"
+    );
+    assert!(
+        backend.list_sessions(&tenant).await.unwrap().is_empty(),
+        "[{name}] library items must not show up as sessions"
+    );
+
+    let glossary = br#"{"entries":[]}"#;
+    backend
+        .save_library_item(&tenant, LibraryKind::Glossary, "default", glossary)
+        .await
+        .unwrap();
+    let glossaries: Vec<String> = backend
+        .list_library_items(&tenant, LibraryKind::Glossary)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|g| g.name)
+        .collect();
+    assert_eq!(
+        glossaries,
+        vec!["default".to_string()],
+        "[{name}] glossaries must list by name"
+    );
+    assert!(backend
+        .delete_library_item(&tenant, LibraryKind::Glossary, "default")
+        .await
+        .unwrap());
+    assert!(
+        backend
+            .save_library_item(&tenant, template, "../escape", b"x")
+            .await
+            .is_err(),
+        "[{name}] library item names must not contain path separators"
+    );
+
+    assert!(backend
+        .delete_library_item(&tenant, template, "letterhead")
+        .await
+        .unwrap());
+    assert!(!backend
+        .delete_library_item(&tenant, template, "letterhead")
+        .await
+        .unwrap());
+    assert!(backend
+        .delete_library_item(&tenant, template, "board report")
+        .await
+        .unwrap());
+    assert!(backend
+        .delete_library_item(&tenant, snippet, "letterhead")
+        .await
+        .unwrap());
+    assert!(backend
+        .list_library_items(&tenant, template)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+// =========================================================================
+// WAL Operations
+// =========================================================================
+
+/// Appends keep insertion order, positions are 1-indexed, and paging works.
+pub async fn wal_ordering(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-order");
+    let session = "wal-order";
+
+    let (wal, has_more) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert!(wal.is_empty() && !has_more, "[{name}] missing WAL must read as empty");
+
+    let first: Vec<_> = (1..=3).map(|i| wal_entry(i, &format!("e{i}"))).collect();
+    let last = backend.append_wal(&tenant, session, &first).await.unwrap();
+    assert_eq!(last, 3, "[{name}] append must return the last position");
+
+    let second: Vec<_> = (4..=5).map(|i| wal_entry(i, &format!("e{i}"))).collect();
+    let last = backend.append_wal(&tenant, session, &second).await.unwrap();
+    assert_eq!(last, 5);
+
+    assert_eq!(
+        backend.append_wal(&tenant, session, &[]).await.unwrap(),
+        0,
+        "[{name}] appending nothing must return 0"
+    );
+
+    let (wal, has_more) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert!(!has_more);
+    let positions: Vec<u64> = wal.iter().map(|e| e.position).collect();
+    assert_eq!(positions, vec![1, 2, 3, 4, 5], "[{name}] positions must be 1..=n");
+    let markers: Vec<String> = wal.iter().map(marker_of).collect();
+    assert_eq!(
+        markers,
+        vec!["e1", "e2", "e3", "e4", "e5"],
+        "[{name}] entries must keep append order"
+    );
+
+    let (page, has_more) = backend.read_wal(&tenant, session, 2, Some(2)).await.unwrap();
+    assert!(has_more, "[{name}] a full page must report has_more");
+    assert_eq!(page.iter().map(|e| e.position).collect::<Vec<_>>(), vec![2, 3]);
+
+    let (tail, _) = backend.read_wal(&tenant, session, 5, None).await.unwrap();
+    assert_eq!(tail.len(), 1);
+    assert_eq!(marker_of(&tail[0]), "e5");
+
+    let (past_end, has_more) = backend.read_wal(&tenant, session, 6, None).await.unwrap();
+    assert!(past_end.is_empty() && !has_more);
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Truncation keeps the first N entries and later appends continue from there.
+pub async fn wal_truncate(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-truncate");
+    let session = "wal-truncate";
+
+    let entries: Vec<_> = (1..=4).map(|i| wal_entry(i, &format!("e{i}"))).collect();
+    backend.append_wal(&tenant, session, &entries).await.unwrap();
+
+    assert_eq!(
+        backend.truncate_wal(&tenant, session, 10).await.unwrap(),
+        0,
+        "[{name}] truncating beyond the end must remove nothing"
+    );
+    assert_eq!(backend.truncate_wal(&tenant, session, 2).await.unwrap(), 2);
+
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert_eq!(
+        wal.iter().map(marker_of).collect::<Vec<_>>(),
+        vec!["e1", "e2"],
+        "[{name}] truncate must keep positions <= keep_count"
+    );
+
+    backend
+        .append_wal(&tenant, session, &[wal_entry(3, "after")])
+        .await
+        .unwrap();
+    let (wal, _) = backend.read_wal(&tenant, session, 3, None).await.unwrap();
+    assert_eq!(wal.len(), 1);
+    assert_eq!(wal[0].position, 3, "[{name}] appends after truncate must reuse positions");
+    assert_eq!(marker_of(&wal[0]), "after");
+
+    assert_eq!(backend.truncate_wal(&tenant, session, 0).await.unwrap(), 3);
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert!(wal.is_empty(), "[{name}] keep_count = 0 must clear the WAL");
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Tail reads return the newest entries first and page backwards, across
+/// WALs longer than one offset-index stride.
+pub async fn wal_tail(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-tail");
+    let session = "wal-tail";
+
+    let (tail, has_more) = backend.tail_wal(&tenant, session, None, 10).await.unwrap();
+    assert!(tail.is_empty() && !has_more, "[{name}] missing WAL must tail as empty");
+
+    let entries: Vec<_> = (1..=100).map(|i| wal_entry(i, &format!("e{i}"))).collect();
+    backend.append_wal(&tenant, session, &entries).await.unwrap();
+
+    let (tail, has_more) = backend.tail_wal(&tenant, session, None, 3).await.unwrap();
+    assert!(has_more);
+    assert_eq!(
+        tail.iter().map(marker_of).collect::<Vec<_>>(),
+        vec!["e100", "e99", "e98"],
+        "[{name}] tail must return the newest entries first"
+    );
+
+    let (page, has_more) = backend.tail_wal(&tenant, session, Some(66), 2).await.unwrap();
+    assert!(has_more);
+    assert_eq!(page.iter().map(|e| e.position).collect::<Vec<_>>(), vec![65, 64]);
+
+    let (page, has_more) = backend.tail_wal(&tenant, session, Some(3), 10).await.unwrap();
+    assert!(!has_more, "[{name}] the oldest page must not report has_more");
+    assert_eq!(page.iter().map(|e| e.position).collect::<Vec<_>>(), vec![2, 1]);
+
+    let (page, has_more) = backend.read_wal(&tenant, session, 70, Some(2)).await.unwrap();
+    assert!(has_more);
+    assert_eq!(page.iter().map(marker_of).collect::<Vec<_>>(), vec!["e70", "e71"]);
+
+    backend.truncate_wal(&tenant, session, 10).await.unwrap();
+    let (tail, _) = backend.tail_wal(&tenant, session, None, 1).await.unwrap();
+    assert_eq!(
+        tail.iter().map(|e| e.position).collect::<Vec<_>>(),
+        vec![10],
+        "[{name}] tail must follow truncation"
+    );
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Appends are validated against the WAL entry schema: a batch with an
+/// invalid entry is rejected as a whole, and older versions are upgraded.
+pub async fn wal_schema(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-schema");
+    let session = "wal-schema";
+
+    let invalid = [
+        r#"not json"#,
+        r#"["not", "an", "object"]"#,
+        r#"{"version":1,"timestamp":"2026-01-01T00:00:00Z"}"#,
+        r#"{"version":1,"patches":"{}","timestamp":"2026-01-01T00:00:00Z"}"#,
+        r#"{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:00Z","entry_type":1}"#,
+        r#"{"version":999,"patches":"[]","timestamp":"2026-01-01T00:00:00Z"}"#,
+    ];
+    for json in invalid {
+        let batch = [wal_entry(1, "valid"), raw_wal_entry(2, json)];
+        assert!(
+            matches!(
+                backend.append_wal(&tenant, session, &batch).await,
+                Err(StorageError::InvalidArgument(_))
+            ),
+            "[{name}] append must reject {json}"
+        );
+    }
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert!(wal.is_empty(), "[{name}] a rejected batch must not be partly appended");
+
+    // Current entries are stored byte for byte
+    let current = [wal_entry(1, "current")];
+    backend.append_wal(&tenant, session, &current).await.unwrap();
+
+    // Unversioned entries with offset-less timestamps are upgraded
+    let legacy = r#"{"patches":"[]","timestamp":"2026-01-02T03:04:05.5","description":"legacy","entry_type":2,"sync_meta":{"source_path":"/docs/a.docx","previous_hash":"a","new_hash":"b","summary":{"total_changes":0},"document_snapshot":"UEsDBA=="}}"#;
+    backend
+        .append_wal(&tenant, session, &[raw_wal_entry(2, legacy)])
+        .await
+        .unwrap();
+
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert_eq!(wal.len(), 2);
+    assert_eq!(wal[0].patch_json, current[0].patch_json, "[{name}] current entry altered");
+    assert_eq!(wal[0].operation, "Patch");
+
+    let record: WalRecord = serde_json::from_slice(&wal[1].patch_json).unwrap();
+    assert_eq!(record.version, WAL_SCHEMA_VERSION, "[{name}] legacy entry not upgraded");
+    assert_eq!(record.description.as_deref(), Some("legacy"));
+    assert_eq!(record.timestamp.to_rfc3339(), "2026-01-02T03:04:05.500+00:00");
+    assert_eq!(wal[1].operation, "Import");
+    assert_eq!(wal[1].timestamp, record.timestamp);
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Appended positions must continue the WAL: gaps and duplicates are
+/// rejected without appending anything, and entries at position 0 are
+/// numbered by the backend.
+pub async fn wal_positions(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-positions");
+    let session = "wal-positions";
+
+    backend
+        .append_wal(&tenant, session, &[wal_entry(1, "e1"), wal_entry(2, "e2")])
+        .await
+        .unwrap();
+
+    for (batch, expected, found) in [
+        (vec![wal_entry(2, "duplicate")], 3, 2),
+        (vec![wal_entry(5, "gap")], 3, 5),
+        (vec![wal_entry(3, "e3"), wal_entry(3, "e3 again")], 4, 3),
+    ] {
+        let result = backend.append_wal(&tenant, session, &batch).await;
+        assert!(
+            matches!(
+                result,
+                Err(StorageError::WalPosition { expected: e, found: f })
+                    if (e, f) == (expected, found)
+            ),
+            "[{name}] append at {found} must be rejected, got {result:?}"
+        );
+    }
+
+    let last = backend
+        .append_wal(&tenant, session, &[wal_entry(0, "e3"), wal_entry(4, "e4")])
+        .await
+        .unwrap();
+    assert_eq!(last, 4, "[{name}] append must return the position assigned last");
+
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    assert_eq!(
+        wal.iter().map(marker_of).collect::<Vec<_>>(),
+        vec!["e1", "e2", "e3", "e4"],
+        "[{name}] rejected appends must leave the WAL unchanged"
+    );
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+/// Concurrent appends to the same WAL must all land, none lost or duplicated.
+/// Writers don't know where their entry lands, so they leave positions to
+/// the backend.
+///
+/// Only backends with atomic appends (e.g. R2 with ETag CAS) pass this; it is
+/// not part of [`run_storage_suite`](crate::run_storage_suite).
+pub async fn wal_concurrent_appends(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("wal-cas");
+    let session = "wal-cas";
+    let writers = 8;
+
+    let appends = (0..writers).map(|i| {
+        let tenant = tenant.clone();
+        async move {
+            backend
+                .append_wal(&tenant, session, &[wal_entry(0, &format!("w{i}"))])
+                .await
+        }
+    });
+    for result in join_all(appends).await {
+        result.unwrap();
+    }
+
+    let (wal, _) = backend.read_wal(&tenant, session, 0, None).await.unwrap();
+    let mut markers: Vec<String> = wal.iter().map(marker_of).collect();
+    markers.sort();
+    let mut expected: Vec<String> = (0..writers).map(|i| format!("w{i}")).collect();
+    expected.sort();
+    assert_eq!(markers, expected, "[{name}] concurrent appends must not be lost");
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+// =========================================================================
+// Checkpoint Operations
+// =========================================================================
+
+/// Checkpoint listing is numeric, per-session, and "latest" resolves correctly.
+pub async fn checkpoint_edge_cases(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("checkpoints");
+    let session = "doc";
+    // Shares a prefix with `session`; its checkpoints must never show up there.
+    let neighbour = "doc2";
+
+    assert!(backend.list_checkpoints(&tenant, session).await.unwrap().is_empty());
+    assert!(
+        backend.load_checkpoint(&tenant, session, 0).await.unwrap().is_none(),
+        "[{name}] latest checkpoint of an empty session must be None"
+    );
+
+    // Saved out of order, and with positions that sort differently as strings.
+    for position in [100, 9, 10] {
+        backend
+            .save_checkpoint(&tenant, session, position, &docx_bytes(&position.to_string()))
+            .await
+            .unwrap();
+    }
+    backend
+        .save_checkpoint(&tenant, neighbour, 500, &docx_bytes("neighbour"))
+        .await
+        .unwrap();
+    backend
+        .save_session(&tenant, session, &docx_bytes("base"))
+        .await
+        .unwrap();
+
+    let positions: Vec<u64> = backend
+        .list_checkpoints(&tenant, session)
+        .await
+        .unwrap()
+        .iter()
+        .map(|c| c.position)
+        .collect();
+    assert_eq!(
+        positions,
+        vec![9, 10, 100],
+        "[{name}] checkpoints must be listed in numeric order, per session"
+    );
+
+    let (data, pos) = backend.load_checkpoint(&tenant, session, 0).await.unwrap().unwrap();
+    assert_eq!(pos, 100, "[{name}] position 0 must load the latest checkpoint");
+    assert_eq!(data, docx_bytes("100"));
+
+    let (data, pos) = backend.load_checkpoint(&tenant, session, 10).await.unwrap().unwrap();
+    assert_eq!((data, pos), (docx_bytes("10"), 10));
+
+    assert!(
+        backend.load_checkpoint(&tenant, session, 11).await.unwrap().is_none(),
+        "[{name}] a missing position must return None, not the nearest"
+    );
+
+    backend
+        .save_checkpoint(&tenant, session, 10, &docx_bytes("10-rewritten"))
+        .await
+        .unwrap();
+    let (data, _) = backend.load_checkpoint(&tenant, session, 10).await.unwrap().unwrap();
+    assert_eq!(data, docx_bytes("10-rewritten"), "[{name}] checkpoints must be overwritable");
+
+    let sessions: Vec<String> = backend
+        .list_sessions(&tenant)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.session_id)
+        .collect();
+    assert_eq!(
+        sessions,
+        vec![session.to_string()],
+        "[{name}] checkpoints must not be listed as sessions"
+    );
+
+    backend.delete_session(&tenant, session).await.unwrap();
+    backend.delete_session(&tenant, neighbour).await.unwrap();
+}
+
+/// Open a session in one call from the nearest checkpoint and the WAL entries after it.
+pub async fn session_with_history(backend: &dyn StorageBackend) {
+    let name = backend.backend_name();
+    let tenant = unique_tenant("history");
+    let session = "doc";
+
+    assert!(
+        load_session_with_history(backend, &tenant, session, None).await.unwrap().is_none(),
+        "[{name}] a missing session must load no history"
+    );
+
+    backend.save_session(&tenant, session, &docx_bytes("base")).await.unwrap();
+    let entries: Vec<WalEntry> = (1..=5).map(|p| wal_entry(p, &format!("e{}", p))).collect();
+    backend.append_wal(&tenant, session, &entries).await.unwrap();
+    backend.save_checkpoint(&tenant, session, 2, &docx_bytes("2")).await.unwrap();
+    backend.save_checkpoint(&tenant, session, 5, &docx_bytes("5")).await.unwrap();
+    let mut index = SessionIndex::default();
+    index.upsert(SessionIndexEntry {
+        wal_count: 5,
+        cursor_position: 4,
+        checkpoint_positions: vec![2, 5],
+        ..index_entry(session)
+    });
+    backend.save_index(&tenant, &index).await.unwrap();
+
+    let markers = |history: &SessionWithHistory| -> Vec<String> {
+        history.entries.iter().map(marker_of).collect()
+    };
+
+    // The cursor is behind the newest checkpoint: start from the one before it
+    let history = load_session_with_history(backend, &tenant, session, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (history.base_position, history.target_position, history.wal_count),
+        (2, 4, 5),
+        "[{name}] history must start at the newest checkpoint at or before the cursor"
+    );
+    assert_eq!(history.document, docx_bytes("2"));
+    assert_eq!(markers(&history), ["e3", "e4"]);
+    assert_eq!(history.source_path.as_deref(), Some("/docs/doc.docx"));
+
+    let history = load_session_with_history(backend, &tenant, session, Some(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(history.base_position, 0, "[{name}] before any checkpoint, start from the baseline");
+    assert_eq!(history.document, docx_bytes("base"));
+    assert_eq!(markers(&history), ["e1"]);
+
+    let history = load_session_with_history(backend, &tenant, session, Some(99))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (history.base_position, history.target_position),
+        (5, 5),
+        "[{name}] positions past the WAL must open at its end"
+    );
+    assert!(history.entries.is_empty());
+
+    let snapshot = backend
+        .load_session_snapshot(&tenant, session, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (snapshot.base_position, snapshot.target_position, snapshot.wal_count),
+        (2, 4, 5),
+        "[{name}] a snapshot load must open the session like a live one"
+    );
+    assert_eq!(markers(&snapshot), ["e3", "e4"]);
+
+    backend.delete_session(&tenant, session).await.unwrap();
+}
+
+// =========================================================================
+// Admin Log
+// =========================================================================
+
+/// Admin log entries are numbered in append order, kept per tenant, and read
+/// back from a position. Skipped by backends without the `admin_log` feature.
+pub async fn admin_log(backend: &dyn StorageBackend) {
+    if !backend.capabilities().supports(feature::ADMIN_LOG) {
+        return;
+    }
+    let name = backend.backend_name();
+    let tenant = unique_tenant("admin-log");
+    let other = unique_tenant("admin-log-other");
+    let entry = |action| AdminLogEntry::new(action, "ops", chrono::Utc::now());
+
+    assert!(
+        backend.read_admin_log(&tenant, 0, None).await.unwrap().is_empty(),
+        "[{name}] a new tenant's admin log must be empty"
+    );
+    for action in [AdminAction::DeleteSession, AdminAction::TruncateWal, AdminAction::Migration] {
+        backend.append_admin_log(&tenant, entry(action)).await.unwrap();
+    }
+    assert_eq!(
+        backend.append_admin_log(&other, entry(AdminAction::KeyRotation)).await.unwrap(),
+        1,
+        "[{name}] positions must be counted per tenant"
+    );
+
+    let log = backend.read_admin_log(&tenant, 0, None).await.unwrap();
+    assert_eq!(
+        log.iter().map(|e| (e.position, e.action)).collect::<Vec<_>>(),
+        [
+            (1, AdminAction::DeleteSession),
+            (2, AdminAction::TruncateWal),
+            (3, AdminAction::Migration)
+        ],
+        "[{name}] entries must be read back in append order"
+    );
+    assert_eq!(log[0].actor, "ops");
+
+    let page = backend.read_admin_log(&tenant, 2, Some(1)).await.unwrap();
+    assert_eq!(
+        page.iter().map(|e| e.position).collect::<Vec<_>>(),
+        [2],
+        "[{name}] reads must start at from_position and stop at the limit"
+    );
+}
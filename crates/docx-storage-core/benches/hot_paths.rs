@@ -0,0 +1,174 @@
+//! Benchmarks of the storage hot paths that grow with document size.
+//!
+//! - `package_rebuild`: rewriting a package on save (media moved out) and on
+//!   load (media inlined back), the ZIP rebuild every R2 save and load with
+//!   media deduplication pays
+//! - `r2_wal_append`: the CPU side of an R2 WAL append (validating the
+//!   entries, indexing the WAL as read, checking positions, building the new
+//!   object), against WALs of growing length; the object store round trips
+//!   are left out
+//! - `extract_text`: plain text of large documents, as exported to corpora
+//!
+//! Run with `cargo bench -p docx-storage-core`; `.github/scripts/check-bench-baseline.sh`
+//! compares the results with `benches/baseline.json`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::io::{Cursor, Write};
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use docx_storage_core::{
+    check_wal_positions, extract_media, extract_text, inline_media, prepare_wal_entries, WalEntry,
+    WalOffsetIndex, MEDIA_MIN_BYTES,
+};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Document sizes, in paragraphs.
+const PARAGRAPHS: [usize; 3] = [100, 1_000, 10_000];
+
+/// Bytes of each image; one image per 100 paragraphs.
+const IMAGE_BYTES: usize = 32 * 1024;
+
+/// Deterministic bytes that don't compress, standing in for image data.
+fn noise(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+/// A package with `paragraphs` paragraphs of body text and one image per
+/// hundred of them.
+fn package(paragraphs: usize) -> Vec<u8> {
+    let mut document = String::from(r#"<?xml version="1.0"?><w:document><w:body>"#);
+    for i in 0..paragraphs {
+        document.push_str(&format!(
+            "<w:p><w:pPr><w:pStyle w:val=\"Normal\"/></w:pPr><w:r><w:rPr><w:b/></w:rPr>\
+             <w:t>Clause {i}.</w:t></w:r><w:r><w:t xml:space=\"preserve\"> The parties agree \
+             that the terms &amp; conditions of this agreement apply to clause {i}.</w:t></w:r></w:p>"
+        ));
+    }
+    document.push_str("</w:body></w:document>");
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    writer.start_file("[Content_Types].xml", deflated).unwrap();
+    writer.write_all(b"<Types/>").unwrap();
+    writer.start_file("word/document.xml", deflated).unwrap();
+    writer.write_all(document.as_bytes()).unwrap();
+    writer.start_file("word/styles.xml", deflated).unwrap();
+    writer.write_all(b"<w:styles/>").unwrap();
+    for i in 0..paragraphs.div_ceil(100) {
+        writer.start_file(format!("word/media/image{i}.png"), stored).unwrap();
+        writer.write_all(&noise(i as u64, IMAGE_BYTES)).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// A WAL entry as the .NET server appends it, with a patch of `ops` operations.
+fn wal_entry(ops: usize) -> WalEntry {
+    let patches: Vec<_> = (0..ops)
+        .map(|i| {
+            serde_json::json!({
+                "op": "replace_text",
+                "path": format!("/body/paragraph[{i}]"),
+                "find": "terms",
+                "replace": "terms and conditions",
+            })
+        })
+        .collect();
+    let record = serde_json::json!({
+        "version": 1,
+        "patches": serde_json::to_string(&patches).unwrap(),
+        "timestamp": "2025-06-01T12:00:00Z",
+    });
+    WalEntry {
+        position: 0,
+        operation: String::new(),
+        path: String::new(),
+        patch_json: serde_json::to_vec(&record).unwrap(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// The JSONL data of an R2 WAL object (after its 8-byte header) holding
+/// `entries` entries.
+fn wal_data(entries: usize) -> Vec<u8> {
+    let line = prepare_wal_entries(&[wal_entry(3)]).unwrap().remove(0);
+    let mut data = Vec::with_capacity(entries * (line.len() + 1));
+    for _ in 0..entries {
+        data.extend_from_slice(&line);
+        data.push(b'\n');
+    }
+    data
+}
+
+fn package_rebuild(c: &mut Criterion) {
+    let mut group = c.benchmark_group("package_rebuild");
+    for paragraphs in PARAGRAPHS {
+        let original = package(paragraphs);
+        let (stripped, blobs) = extract_media(&original, MEDIA_MIN_BYTES).unwrap().unwrap();
+        let blobs: HashMap<_, _> = blobs.into_iter().map(|b| (b.hash, b.data)).collect();
+
+        group.throughput(Throughput::Bytes(original.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("extract_media", paragraphs),
+            &original,
+            |b, original| b.iter(|| extract_media(black_box(original), MEDIA_MIN_BYTES).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("inline_media", paragraphs),
+            &stripped,
+            |b, stripped| b.iter(|| inline_media(black_box(stripped), &blobs).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn r2_wal_append(c: &mut Criterion) {
+    let mut group = c.benchmark_group("r2_wal_append");
+    let batch = vec![wal_entry(3); 10];
+    group.throughput(Throughput::Elements(batch.len() as u64));
+    for existing in [0, 1_000, 10_000] {
+        let mut object = vec![0u8; 8];
+        object.extend_from_slice(&wal_data(existing));
+
+        group.bench_with_input(BenchmarkId::from_parameter(existing), &object, |b, object| {
+            b.iter(|| {
+                let lines = prepare_wal_entries(black_box(&batch)).unwrap();
+                let mut wal_data = object.clone();
+                let existing = WalOffsetIndex::build(&wal_data[8..]).entries;
+                let last_position = check_wal_positions(existing, &batch).unwrap();
+                for line in &lines {
+                    wal_data.extend_from_slice(line);
+                    wal_data.push(b'\n');
+                }
+                let data_len = (wal_data.len() - 8) as i64;
+                wal_data[..8].copy_from_slice(&data_len.to_le_bytes());
+                (last_position, WalOffsetIndex::build(&wal_data[8..]))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn text_extraction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extract_text");
+    for paragraphs in PARAGRAPHS {
+        let data = package(paragraphs);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(paragraphs), &data, |b, data| {
+            b.iter(|| extract_text(black_box(data)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, package_rebuild, r2_wal_append, text_extraction);
+criterion_main!(benches);
@@ -0,0 +1,287 @@
+//! Digests of the activity on a tenant's synced documents.
+//!
+//! External changes to a document's source, and syncs of the document to it,
+//! mostly happen while nobody is looking at an MCP client. [`ActivityDigests`]
+//! collects them per tenant and per session between two digests, so a
+//! notifier can tell document owners what changed in one message per tenant
+//! rather than one per event.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::watch::ExternalChangeType;
+
+/// Something that happened to a session's source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Activity {
+    /// The source was changed outside docx-mcp
+    ExternalChange(ExternalChangeType),
+    /// The session was written to its source
+    Synced,
+    /// The source was unreachable; the sync is queued for retry
+    SyncQueued,
+    /// The sync failed, with the error
+    SyncFailed(String),
+}
+
+/// What happened to one session's source since the last digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionActivity {
+    pub session_id: String,
+    pub modified: u32,
+    pub deleted: u32,
+    pub renamed: u32,
+    pub permission_changed: u32,
+    /// Opened from a new source (e.g. a file dropped into the inbox)
+    pub created: u32,
+    pub synced: u32,
+    pub queued: u32,
+    pub failed: u32,
+    pub last_error: Option<String>,
+    pub first_at: DateTime<Utc>,
+    pub last_at: DateTime<Utc>,
+}
+
+impl SessionActivity {
+    fn new(session_id: &str, at: DateTime<Utc>) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            modified: 0,
+            deleted: 0,
+            renamed: 0,
+            permission_changed: 0,
+            created: 0,
+            synced: 0,
+            queued: 0,
+            failed: 0,
+            last_error: None,
+            first_at: at,
+            last_at: at,
+        }
+    }
+
+    /// Whether the source changed outside docx-mcp.
+    pub fn changed_externally(&self) -> bool {
+        self.modified + self.deleted + self.renamed + self.permission_changed > 0
+    }
+
+    /// One line describing the activity, e.g. "modified externally 2 times, 1 failed sync".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        for (count, what) in [
+            (self.modified, "modified externally"),
+            (self.deleted, "deleted at the source"),
+            (self.renamed, "renamed at the source"),
+            (self.permission_changed, "permissions changed"),
+            (self.created, "added"),
+        ] {
+            match count {
+                0 => {}
+                1 => parts.push(what.to_string()),
+                n => parts.push(format!("{what} {n} times")),
+            }
+        }
+        for (count, one, many) in [
+            (self.synced, "sync", "syncs"),
+            (self.queued, "sync queued for retry", "syncs queued for retry"),
+            (self.failed, "failed sync", "failed syncs"),
+        ] {
+            match count {
+                0 => {}
+                1 => parts.push(format!("1 {one}")),
+                n => parts.push(format!("{n} {many}")),
+            }
+        }
+        let mut line = parts.join(", ");
+        if let Some(error) = &self.last_error {
+            line.push_str(&format!(" (last error: {error})"));
+        }
+        line
+    }
+}
+
+/// The activity of a tenant's sessions between two digests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TenantDigest {
+    pub tenant_id: String,
+    /// Sessions with activity, external changes first
+    pub sessions: Vec<SessionActivity>,
+    /// Sessions left out because the digest was full
+    pub omitted_sessions: u64,
+}
+
+impl TenantDigest {
+    /// Subject line, e.g. "2 documents changed at their source, 1 document failed to sync".
+    pub fn subject(&self) -> String {
+        let changed = self.sessions.iter().filter(|s| s.changed_externally()).count();
+        let failed = self.sessions.iter().filter(|s| s.failed > 0).count();
+        let added = self.sessions.iter().filter(|s| s.created > 0).count();
+        let mut parts = Vec::new();
+        if added > 0 {
+            parts.push(format!(
+                "{added} document{} added",
+                if added == 1 { "" } else { "s" }
+            ));
+        }
+        if changed > 0 {
+            parts.push(format!(
+                "{changed} document{} changed at {} source",
+                if changed == 1 { "" } else { "s" },
+                if changed == 1 { "its" } else { "their" }
+            ));
+        }
+        if failed > 0 {
+            parts.push(format!(
+                "{failed} document{} failed to sync",
+                if failed == 1 { "" } else { "s" }
+            ));
+        }
+        if parts.is_empty() {
+            let synced = self.sessions.len() as u64 + self.omitted_sessions;
+            parts.push(format!(
+                "{synced} document{} synced",
+                if synced == 1 { "" } else { "s" }
+            ));
+        }
+        parts.join(", ")
+    }
+
+    /// Plain-text body: one line per session.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for session in &self.sessions {
+            text.push_str(&format!("- {}: {}\n", session.session_id, session.describe()));
+        }
+        if self.omitted_sessions > 0 {
+            text.push_str(&format!(
+                "... and {} more documents\n",
+                self.omitted_sessions
+            ));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Default)]
+struct TenantActivity {
+    sessions: BTreeMap<String, SessionActivity>,
+    omitted: BTreeSet<String>,
+}
+
+/// Activity collected per tenant until the next [`drain`](Self::drain).
+#[derive(Debug)]
+pub struct ActivityDigests {
+    tenants: BTreeMap<String, TenantActivity>,
+    max_sessions: usize,
+}
+
+impl ActivityDigests {
+    /// Sessions listed per tenant digest by default; activity of further
+    /// sessions is only counted.
+    pub const DEFAULT_MAX_SESSIONS: usize = 50;
+
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            tenants: BTreeMap::new(),
+            max_sessions,
+        }
+    }
+
+    /// Record activity on a session's source.
+    pub fn record(&mut self, tenant_id: &str, session_id: &str, activity: Activity, at: DateTime<Utc>) {
+        let tenant = self.tenants.entry(tenant_id.to_string()).or_default();
+        if !tenant.sessions.contains_key(session_id) && tenant.sessions.len() >= self.max_sessions {
+            tenant.omitted.insert(session_id.to_string());
+            return;
+        }
+
+        let session = tenant
+            .sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionActivity::new(session_id, at));
+        session.last_at = session.last_at.max(at);
+        match activity {
+            Activity::ExternalChange(ExternalChangeType::Modified) => session.modified += 1,
+            Activity::ExternalChange(ExternalChangeType::Deleted) => session.deleted += 1,
+            Activity::ExternalChange(ExternalChangeType::Renamed) => session.renamed += 1,
+            Activity::ExternalChange(ExternalChangeType::PermissionChanged) => {
+                session.permission_changed += 1
+            }
+            Activity::ExternalChange(ExternalChangeType::Created) => session.created += 1,
+            Activity::Synced => session.synced += 1,
+            Activity::SyncQueued => session.queued += 1,
+            Activity::SyncFailed(error) => {
+                session.failed += 1;
+                session.last_error = Some(error);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+
+    /// The digest of every tenant with activity, emptying the collection.
+    pub fn drain(&mut self) -> Vec<TenantDigest> {
+        std::mem::take(&mut self.tenants)
+            .into_iter()
+            .map(|(tenant_id, activity)| {
+                let mut sessions: Vec<_> = activity.sessions.into_values().collect();
+                sessions.sort_by_key(|s| (!s.changed_externally(), s.failed == 0, s.first_at));
+                TenantDigest {
+                    tenant_id,
+                    sessions,
+                    omitted_sessions: activity.omitted.len() as u64,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for ActivityDigests {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_SESSIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_groups_activity_by_tenant_and_session() {
+        let at = Utc::now();
+        let mut digests = ActivityDigests::new(2);
+        digests.record("t1", "contract", Activity::Synced, at);
+        digests.record("t1", "contract", Activity::Synced, at);
+        digests.record("t1", "report", Activity::ExternalChange(ExternalChangeType::Modified), at);
+        digests.record("t1", "report", Activity::ExternalChange(ExternalChangeType::Modified), at);
+        digests.record("t1", "memo", Activity::SyncFailed("disk full".into()), at);
+        digests.record("t2", "notes", Activity::SyncFailed("unreachable".into()), at);
+
+        let drained = digests.drain();
+        assert!(digests.is_empty());
+        assert_eq!(drained.len(), 2);
+
+        let t1 = &drained[0];
+        assert_eq!(t1.tenant_id, "t1");
+        assert_eq!(
+            t1.sessions.iter().map(|s| s.session_id.as_str()).collect::<Vec<_>>(),
+            ["report", "contract"]
+        );
+        assert_eq!(t1.omitted_sessions, 1);
+        assert_eq!(t1.subject(), "1 document changed at its source");
+        assert_eq!(
+            t1.text(),
+            "- report: modified externally 2 times\n- contract: 2 syncs\n... and 1 more documents\n"
+        );
+
+        assert_eq!(drained[1].subject(), "1 document failed to sync");
+        assert_eq!(
+            drained[1].sessions[0].describe(),
+            "1 failed sync (last error: unreachable)"
+        );
+    }
+}
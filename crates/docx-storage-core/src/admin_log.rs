@@ -0,0 +1,233 @@
+//! Append-only audit log of administrative actions.
+//!
+//! The WAL only records content edits. Deleting a session, truncating its
+//! WAL, purging expired sessions, erasing a tenant, migrating it to another
+//! server or rotating keys changes what is stored too, and compliance asks who
+//! did it and when. Servers append an [`AdminLogEntry`] for each such action to
+//! a per-tenant log that is never rewritten, read back with `ReadAdminLog`.
+//!
+//! Erasing a tenant deletes its log with everything else; the erasure itself is
+//! then the first entry of a new log.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{StorageBackend, StorageError};
+
+/// Actor of actions the server takes on its own (e.g. purging expired sessions).
+pub const SYSTEM_ACTOR: &str = "system";
+
+/// Actor of requests that don't say who makes them.
+pub const UNKNOWN_ACTOR: &str = "unknown";
+
+/// Entries `ReadAdminLog` returns when the request sets no limit.
+pub const DEFAULT_ADMIN_LOG_LIMIT: u64 = 100;
+
+/// An administrative action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminAction {
+    /// DeleteSession
+    DeleteSession,
+    /// TruncateWal
+    TruncateWal,
+    /// Expired ephemeral sessions purged by the retention task
+    RetentionRun,
+    /// EraseTenant (the confirmed step)
+    EraseTenant,
+    /// RebuildIndex, unless a dry run
+    RebuildIndex,
+    /// SetLegalHold
+    LegalHold,
+    /// PromoteSandbox
+    PromoteSandbox,
+    /// DiscardSandbox
+    DiscardSandbox,
+    /// GetStorageReport deleting leftover files
+    StorageCleanup,
+    /// Sessions copied to or from another server (walctl migrate)
+    Migration,
+    /// Signing, encryption or storage credentials replaced by the operator
+    KeyRotation,
+}
+
+impl AdminAction {
+    /// Every action, in declaration order.
+    pub const ALL: [AdminAction; 11] = [
+        AdminAction::DeleteSession,
+        AdminAction::TruncateWal,
+        AdminAction::RetentionRun,
+        AdminAction::EraseTenant,
+        AdminAction::RebuildIndex,
+        AdminAction::LegalHold,
+        AdminAction::PromoteSandbox,
+        AdminAction::DiscardSandbox,
+        AdminAction::StorageCleanup,
+        AdminAction::Migration,
+        AdminAction::KeyRotation,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminAction::DeleteSession => "delete_session",
+            AdminAction::TruncateWal => "truncate_wal",
+            AdminAction::RetentionRun => "retention_run",
+            AdminAction::EraseTenant => "erase_tenant",
+            AdminAction::RebuildIndex => "rebuild_index",
+            AdminAction::LegalHold => "legal_hold",
+            AdminAction::PromoteSandbox => "promote_sandbox",
+            AdminAction::DiscardSandbox => "discard_sandbox",
+            AdminAction::StorageCleanup => "storage_cleanup",
+            AdminAction::Migration => "migration",
+            AdminAction::KeyRotation => "key_rotation",
+        }
+    }
+
+    /// The action named `name` (as in [`Self::as_str`]).
+    pub fn parse(name: &str) -> Result<Self, StorageError> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.as_str() == name)
+            .ok_or_else(|| StorageError::InvalidArgument(format!("unknown admin action '{}'", name)))
+    }
+}
+
+/// One entry of a tenant's admin log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminLogEntry {
+    /// Position in the log (1-indexed, assigned by the backend on append)
+    #[serde(default)]
+    pub position: u64,
+    pub action: AdminAction,
+    /// Who asked for it: the request's actor, [`SYSTEM_ACTOR`] or [`UNKNOWN_ACTOR`]
+    pub actor: String,
+    pub timestamp: DateTime<Utc>,
+    /// Session acted on, when the action is about one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// What was done, e.g. "deleted 42 objects"
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+impl AdminLogEntry {
+    /// An entry for `action` taken by `actor` (empty = [`UNKNOWN_ACTOR`]) at `now`.
+    pub fn new(action: AdminAction, actor: &str, now: DateTime<Utc>) -> Self {
+        let actor = actor.trim();
+        Self {
+            position: 0,
+            action,
+            actor: if actor.is_empty() { UNKNOWN_ACTOR } else { actor }.to_string(),
+            timestamp: now,
+            session_id: None,
+            detail: String::new(),
+        }
+    }
+
+    pub fn with_session(mut self, session_id: &str) -> Self {
+        self.session_id = Some(session_id.to_string());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+}
+
+/// Serialize entries as JSONL, one per line.
+pub fn admin_log_jsonl(entries: &[AdminLogEntry]) -> Result<Vec<u8>, StorageError> {
+    let mut out = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut out, entry).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize admin log entry: {}", e))
+        })?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+/// Parse a stored admin log. A torn last line (a crash mid-append) is dropped.
+pub fn parse_admin_log(jsonl: &[u8]) -> Result<Vec<AdminLogEntry>, StorageError> {
+    let lines: Vec<&[u8]> = jsonl
+        .split(|b| *b == b'\n')
+        .map(|l| l.trim_ascii())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut entries = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_slice(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if i + 1 == lines.len() && !jsonl.ends_with(b"\n") => break,
+            Err(e) => {
+                return Err(StorageError::Serialization(format!(
+                    "Failed to parse admin log entry {}: {}",
+                    i + 1,
+                    e
+                )))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// The entries from `from_position` on (0 or 1 = from the start), at most `limit`.
+pub fn admin_log_page(
+    entries: Vec<AdminLogEntry>,
+    from_position: u64,
+    limit: Option<u64>,
+) -> Vec<AdminLogEntry> {
+    let limit = limit.map_or(usize::MAX, |l| l as usize);
+    entries
+        .into_iter()
+        .filter(|e| e.position >= from_position)
+        .take(limit)
+        .collect()
+}
+
+/// Append `entry` to the tenant's admin log once its action is done. The
+/// action can't be undone by then, so a failure is logged rather than
+/// returned.
+pub async fn record_admin_action<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    entry: AdminLogEntry,
+) {
+    let action = entry.action.as_str();
+    if let Err(e) = storage.append_admin_log(tenant_id, entry).await {
+        error!(tenant_id, action, "Failed to record admin action: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_round_trip_drops_torn_last_line() {
+        let now = Utc::now();
+        let mut entry = AdminLogEntry::new(AdminAction::TruncateWal, "alice", now)
+            .with_session("s1")
+            .with_detail("kept 2 entries, removed 1");
+        entry.position = 1;
+        let mut jsonl = admin_log_jsonl(&[entry.clone()]).unwrap();
+        jsonl.extend_from_slice(b"{\"position\":2,\"act");
+
+        assert_eq!(parse_admin_log(&jsonl).unwrap(), vec![entry]);
+        assert!(parse_admin_log(b"{\"bad\":\n{}\n").is_err());
+    }
+
+    #[test]
+    fn test_actions_round_trip_by_name() {
+        for action in AdminAction::ALL {
+            assert_eq!(AdminAction::parse(action.as_str()).unwrap(), action);
+            assert_eq!(
+                serde_json::to_value(action).unwrap(),
+                serde_json::Value::from(action.as_str())
+            );
+        }
+        assert!(AdminAction::parse("format_disk").is_err());
+        assert_eq!(AdminLogEntry::new(AdminAction::Migration, " ", Utc::now()).actor, UNKNOWN_ACTOR);
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
 
 use crate::error::StorageError;
@@ -14,6 +17,9 @@ pub struct ConnectionInfo {
     pub display_name: String,
     /// Provider account identifier (email for GDrive, empty for local)
     pub provider_account_id: Option<String>,
+    /// Tag of the backend serving this connection ("local", "gdrive", ...).
+    /// Set by `AggregateBrowsableBackend`, `None` otherwise.
+    pub backend: Option<String>,
 }
 
 /// A file or folder entry from a connection.
@@ -42,6 +48,35 @@ pub struct FileListResult {
     pub next_page_token: Option<String>,
 }
 
+/// MIME type of .docx documents, the default search filter.
+pub const DOCX_MIME_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Parameters for `BrowsableBackend::search_files`.
+#[derive(Debug, Clone, Default)]
+pub struct FileSearchQuery {
+    /// Case-insensitive text to find in file names
+    pub query: String,
+    /// Folder to search from (empty = connection root)
+    pub path: String,
+    /// Also search subfolders
+    pub recursive: bool,
+    /// Only return files of this MIME type (`None` = .docx documents)
+    pub mime_filter: Option<String>,
+    pub page_token: Option<String>,
+    pub page_size: u32,
+}
+
+impl FileSearchQuery {
+    /// The MIME type results must have.
+    pub fn effective_mime_type(&self) -> &str {
+        self.mime_filter
+            .as_deref()
+            .filter(|m| !m.is_empty())
+            .unwrap_or(DOCX_MIME_TYPE)
+    }
+}
+
 /// Backend trait for browsing storage connections and their files.
 #[async_trait]
 pub trait BrowsableBackend: Send + Sync {
@@ -58,6 +93,14 @@ pub trait BrowsableBackend: Send + Sync {
         page_size: u32,
     ) -> Result<FileListResult, StorageError>;
 
+    /// Find files by name in a connection. Only files are returned, never folders.
+    async fn search_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        query: &FileSearchQuery,
+    ) -> Result<FileListResult, StorageError>;
+
     /// Download a file from a connection.
     async fn download_file(
         &self,
@@ -67,3 +110,143 @@ pub trait BrowsableBackend: Send + Sync {
         file_id: Option<&str>,
     ) -> Result<Vec<u8>, StorageError>;
 }
+
+/// Browsable backend that fans out to several backends and presents their
+/// connections as one list, so a client can offer a single "open from" picker.
+///
+/// - `list_connections` queries every backend concurrently and tags each
+///   connection with the name of the backend it came from. A failing backend
+///   is skipped (and logged) unless all of them fail.
+/// - `list_files` / `download_file` are routed to the backend that listed the
+///   connection. Routes are learned from `list_connections` and refreshed on
+///   a miss. When two backends report the same connection ID, the one listed
+///   first wins.
+pub struct AggregateBrowsableBackend {
+    backends: Vec<(String, Arc<dyn BrowsableBackend>)>,
+    /// (tenant_id, connection_id) → index into `backends`.
+    routes: Mutex<HashMap<(String, String), usize>>,
+}
+
+impl AggregateBrowsableBackend {
+    /// Create an aggregator over `(tag, backend)` pairs, in precedence order.
+    pub fn new(backends: Vec<(String, Arc<dyn BrowsableBackend>)>) -> Self {
+        Self {
+            backends,
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lookup(&self, tenant_id: &str, connection_id: &str) -> Option<usize> {
+        self.routes
+            .lock()
+            .unwrap()
+            .get(&(tenant_id.to_string(), connection_id.to_string()))
+            .copied()
+    }
+
+    /// Find the backend serving a connection, re-listing connections on a miss.
+    async fn route(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+    ) -> Result<&Arc<dyn BrowsableBackend>, StorageError> {
+        let index = match self.lookup(tenant_id, connection_id) {
+            Some(index) => index,
+            None => {
+                self.list_connections(tenant_id).await?;
+                self.lookup(tenant_id, connection_id).ok_or_else(|| {
+                    StorageError::NotFound(format!("Unknown connection '{}'", connection_id))
+                })?
+            }
+        };
+        Ok(&self.backends[index].1)
+    }
+}
+
+#[async_trait]
+impl BrowsableBackend for AggregateBrowsableBackend {
+    async fn list_connections(&self, tenant_id: &str) -> Result<Vec<ConnectionInfo>, StorageError> {
+        let results = futures::future::join_all(
+            self.backends
+                .iter()
+                .map(|(_, backend)| backend.list_connections(tenant_id)),
+        )
+        .await;
+
+        let mut connections = Vec::new();
+        let mut routes = HashMap::new();
+        let mut first_error = None;
+        let mut any_ok = false;
+
+        for (index, ((tag, _), result)) in self.backends.iter().zip(results).enumerate() {
+            match result {
+                Ok(listed) => {
+                    any_ok = true;
+                    for mut connection in listed {
+                        routes
+                            .entry(connection.connection_id.clone())
+                            .or_insert(index);
+                        connection.backend.get_or_insert_with(|| tag.clone());
+                        connections.push(connection);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(backend = %tag, "Failed to list connections: {}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if !any_ok {
+            if let Some(e) = first_error {
+                return Err(e);
+            }
+        }
+
+        let mut table = self.routes.lock().unwrap();
+        for (connection_id, index) in routes {
+            table.insert((tenant_id.to_string(), connection_id), index);
+        }
+
+        Ok(connections)
+    }
+
+    async fn list_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        path: &str,
+        page_token: Option<&str>,
+        page_size: u32,
+    ) -> Result<FileListResult, StorageError> {
+        self.route(tenant_id, connection_id)
+            .await?
+            .list_files(tenant_id, connection_id, path, page_token, page_size)
+            .await
+    }
+
+    async fn search_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        query: &FileSearchQuery,
+    ) -> Result<FileListResult, StorageError> {
+        self.route(tenant_id, connection_id)
+            .await?
+            .search_files(tenant_id, connection_id, query)
+            .await
+    }
+
+    async fn download_file(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        path: &str,
+        file_id: Option<&str>,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.route(tenant_id, connection_id)
+            .await?
+            .download_file(tenant_id, connection_id, path, file_id)
+            .await
+    }
+}
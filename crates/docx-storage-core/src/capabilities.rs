@@ -0,0 +1,129 @@
+//! What a storage server supports, reported by `GetCapabilities`.
+//!
+//! Backends differ (R2 has conditional writes and lifecycle rules, local
+//! storage has neither), and optional server features depend on
+//! configuration. Clients read the capabilities once instead of assuming
+//! parity or probing for `Unimplemented`.
+
+use std::collections::BTreeSet;
+
+/// Version of the storage protobuf schema. Bumped when RPCs or fields that
+/// clients need to detect are added, and on incompatible changes.
+pub const PROTO_SCHEMA_VERSION: u32 = 1;
+
+/// Largest gRPC message the servers decode (tonic's default), which bounds
+/// unary payloads such as WAL entries.
+pub const MAX_GRPC_MESSAGE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Feature names reported in [`Capabilities::features`].
+pub mod feature {
+    /// Conditional (compare-and-swap) index updates.
+    pub const CAS: &str = "cas";
+    /// Archiving of cold sessions to cheaper storage.
+    pub const ARCHIVES: &str = "archives";
+    /// Encryption of stored objects by the server.
+    pub const ENCRYPTION: &str = "encryption";
+    /// Compression of stored objects by the server.
+    pub const COMPRESSION: &str = "compression";
+    /// Resumable SaveSession uploads.
+    pub const RESUMABLE_UPLOADS: &str = "resumable_uploads";
+    /// EraseTenant.
+    pub const TENANT_ERASURE: &str = "tenant_erasure";
+    /// ApplyLifecycleRules.
+    pub const LIFECYCLE_RULES: &str = "lifecycle_rules";
+    /// GetCostEstimate.
+    pub const COST_ESTIMATES: &str = "cost_estimates";
+    /// SnapshotTenant.
+    pub const TENANT_SNAPSHOTS: &str = "tenant_snapshots";
+    /// OpenSnapshot / CloseSnapshot.
+    pub const SNAPSHOT_HANDLES: &str = "snapshot_handles";
+    /// CreateSandbox / PromoteSandbox / DiscardSandbox.
+    pub const SANDBOXES: &str = "sandboxes";
+    /// SetLegalHold.
+    pub const LEGAL_HOLDS: &str = "legal_holds";
+    /// AppendWalBatch.
+    pub const WAL_BATCHES: &str = "wal_batches";
+    /// Images stored once per tenant instead of in every session.
+    pub const MEDIA_DEDUP: &str = "media_dedup";
+    /// Hash-chained session history and GetHistoryProof.
+    pub const HISTORY_CHAIN: &str = "history_chain";
+    /// GetContentionReport.
+    pub const CONTENTION_REPORT: &str = "contention_report";
+    /// CreateDownloadUrl.
+    pub const PRESIGNED_URLS: &str = "presigned_urls";
+    /// CreateUploadUrl.
+    pub const PRESIGNED_UPLOADS: &str = "presigned_uploads";
+    /// ExportTenantCorpus.
+    pub const CORPUS_EXPORT: &str = "corpus_export";
+    /// GetStorageReport.
+    pub const STORAGE_REPORTS: &str = "storage_reports";
+    /// ReadAdminLog.
+    pub const ADMIN_LOG: &str = "admin_log";
+}
+
+/// A storage server's backend, features and limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub backend: String,
+    pub features: BTreeSet<String>,
+    /// Largest session, checkpoint or library object stored (0 = no limit).
+    pub max_object_bytes: u64,
+    pub max_wal_entry_bytes: u64,
+}
+
+impl Capabilities {
+    /// Capabilities of `backend` with no optional feature and no object limit.
+    pub fn new(backend: &str) -> Self {
+        Self {
+            backend: backend.to_string(),
+            features: BTreeSet::new(),
+            max_object_bytes: 0,
+            max_wal_entry_bytes: MAX_GRPC_MESSAGE_BYTES,
+        }
+    }
+
+    /// Add a feature (see [`feature`]).
+    pub fn with_feature(mut self, feature: &str) -> Self {
+        self.features.insert(feature.to_string());
+        self
+    }
+
+    /// Add `feature` when `enabled`.
+    pub fn with_feature_if(self, feature: &str, enabled: bool) -> Self {
+        if enabled {
+            self.with_feature(feature)
+        } else {
+            self
+        }
+    }
+
+    pub fn with_max_object_bytes(mut self, max_object_bytes: u64) -> Self {
+        self.max_object_bytes = max_object_bytes;
+        self
+    }
+
+    /// Whether `feature` is supported.
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features_are_opt_in() {
+        let caps = Capabilities::new("local")
+            .with_feature(feature::SANDBOXES)
+            .with_feature_if(feature::TENANT_ERASURE, false)
+            .with_feature_if(feature::RESUMABLE_UPLOADS, true);
+
+        assert!(caps.supports(feature::SANDBOXES));
+        assert!(caps.supports(feature::RESUMABLE_UPLOADS));
+        assert!(!caps.supports(feature::TENANT_ERASURE));
+        assert!(!caps.supports(feature::CAS));
+        assert_eq!(caps.max_object_bytes, 0);
+        assert_eq!(caps.max_wal_entry_bytes, MAX_GRPC_MESSAGE_BYTES);
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::StorageError;
+use crate::watch::{ExternalChangeEvent, ExternalChangeType, SourceMetadata};
+
+/// An external change waiting to be acknowledged by the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedChange {
+    /// Sequence number, bumped every time the entry absorbs a new change.
+    /// Acknowledging an older sequence leaves the newer change queued.
+    pub sequence: u64,
+    /// Coalesced event: `old_metadata` from the first change, the rest from the latest
+    pub event: ExternalChangeEvent,
+    /// Number of raw changes merged into this entry
+    pub coalesced: u32,
+}
+
+/// Per-tenant queue of external changes, at most one entry per session.
+///
+/// Bursts of changes to the same source are merged into a single entry.
+/// Entries stay queued until acknowledged, so they are redelivered after
+/// a disconnect or restart (at-least-once delivery).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeQueue {
+    next_sequence: u64,
+    #[serde(default)]
+    entries: Vec<QueuedChange>,
+    /// Last acknowledged change per session, so polling backends that keep
+    /// reporting the same change don't queue it again.
+    #[serde(default)]
+    acked: HashMap<String, (ExternalChangeType, Option<SourceMetadata>)>,
+}
+
+impl ChangeQueue {
+    /// Queue a change, merging it into the session's pending entry if any.
+    ///
+    /// Returns `None` when the change is already queued or acknowledged.
+    pub fn push(&mut self, event: ExternalChangeEvent) -> Option<&QueuedChange> {
+        let signature = (event.change_type, event.new_metadata.clone());
+        if self.acked.get(&event.session_id) == Some(&signature) {
+            return None;
+        }
+
+        let index = match self
+            .entries
+            .iter()
+            .position(|e| e.event.session_id == event.session_id)
+        {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                if (entry.event.change_type, entry.event.new_metadata.clone()) == signature {
+                    return None;
+                }
+                self.next_sequence += 1;
+                entry.sequence = self.next_sequence;
+                entry.coalesced += 1;
+                entry.event = ExternalChangeEvent {
+                    old_metadata: entry
+                        .event
+                        .old_metadata
+                        .take()
+                        .or(event.old_metadata.clone()),
+                    ..event
+                };
+                index
+            }
+            None => {
+                self.next_sequence += 1;
+                self.entries.push(QueuedChange {
+                    sequence: self.next_sequence,
+                    event,
+                    coalesced: 1,
+                });
+                self.entries.len() - 1
+            }
+        };
+
+        self.entries.get(index)
+    }
+
+    /// Pending changes for the given sessions (all sessions if empty), oldest first.
+    pub fn pending(&self, session_ids: &[String]) -> Vec<QueuedChange> {
+        let mut pending: Vec<QueuedChange> = self
+            .entries
+            .iter()
+            .filter(|e| session_ids.is_empty() || session_ids.contains(&e.event.session_id))
+            .cloned()
+            .collect();
+        pending.sort_by_key(|e| e.sequence);
+        pending
+    }
+
+    /// Acknowledge a session's change up to `sequence`.
+    ///
+    /// Returns `false` if nothing was removed (unknown session, or the entry
+    /// has absorbed a newer change since).
+    pub fn ack(&mut self, session_id: &str, sequence: u64) -> bool {
+        let Some(index) = self
+            .entries
+            .iter()
+            .position(|e| e.event.session_id == session_id && e.sequence <= sequence)
+        else {
+            return false;
+        };
+
+        let entry = self.entries.remove(index);
+        self.acked.insert(
+            session_id.to_string(),
+            (entry.event.change_type, entry.event.new_metadata),
+        );
+        true
+    }
+
+    /// Drop everything queued or remembered for a session.
+    ///
+    /// Returns `true` if the queue changed.
+    pub fn remove_session(&mut self, session_id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.event.session_id != session_id);
+        let acked = self.acked.remove(session_id).is_some();
+        acked || self.entries.len() != before
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Persistence for per-tenant change queues.
+#[async_trait]
+pub trait ChangeQueueStore: Send + Sync {
+    /// Load a tenant's queue (empty if none was saved).
+    async fn load(&self, tenant_id: &str) -> Result<ChangeQueue, StorageError>;
+
+    /// Save a tenant's queue.
+    async fn save(&self, tenant_id: &str, queue: &ChangeQueue) -> Result<(), StorageError>;
+}
+
+/// Change queues backed by a [`ChangeQueueStore`].
+///
+/// Every mutation is written through before returning, so queued changes
+/// survive a restart. Queues are cached after the first load; updates are
+/// serialized, which is fine for the low volume of watch events.
+pub struct DurableChangeQueue {
+    store: Arc<dyn ChangeQueueStore>,
+    queues: Mutex<HashMap<String, ChangeQueue>>,
+}
+
+impl DurableChangeQueue {
+    pub fn new(store: Arc<dyn ChangeQueueStore>) -> Self {
+        Self {
+            store,
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a change. Returns the resulting entry, or `None` if it was a duplicate.
+    pub async fn enqueue(
+        &self,
+        tenant_id: &str,
+        event: ExternalChangeEvent,
+    ) -> Result<Option<QueuedChange>, StorageError> {
+        self.update(tenant_id, |queue| queue.push(event).cloned())
+            .await
+    }
+
+    /// Pending changes for the given sessions (all sessions if empty).
+    pub async fn pending(
+        &self,
+        tenant_id: &str,
+        session_ids: &[String],
+    ) -> Result<Vec<QueuedChange>, StorageError> {
+        let mut queues = self.queues.lock().await;
+        let queue = self.loaded(&mut queues, tenant_id).await?;
+        Ok(queue.pending(session_ids))
+    }
+
+    /// Acknowledge a session's change up to `sequence`.
+    pub async fn ack(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        sequence: u64,
+    ) -> Result<bool, StorageError> {
+        self.update(tenant_id, |queue| {
+            queue.ack(session_id, sequence).then_some(())
+        })
+        .await
+        .map(|acked| acked.is_some())
+    }
+
+    /// Drop everything queued for a session (e.g. when its watch stops).
+    pub async fn remove_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<(), StorageError> {
+        self.update(tenant_id, |queue| {
+            queue.remove_session(session_id).then_some(())
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// Apply `f` to a tenant's queue and save it if `f` returns `Some`.
+    async fn update<T>(
+        &self,
+        tenant_id: &str,
+        f: impl FnOnce(&mut ChangeQueue) -> Option<T>,
+    ) -> Result<Option<T>, StorageError> {
+        let mut queues = self.queues.lock().await;
+        let queue = self.loaded(&mut queues, tenant_id).await?;
+
+        let mut updated = queue.clone();
+        let Some(result) = f(&mut updated) else {
+            return Ok(None);
+        };
+
+        self.store.save(tenant_id, &updated).await?;
+        *queue = updated;
+        Ok(Some(result))
+    }
+
+    async fn loaded<'a>(
+        &self,
+        queues: &'a mut HashMap<String, ChangeQueue>,
+        tenant_id: &str,
+    ) -> Result<&'a mut ChangeQueue, StorageError> {
+        match queues.entry(tenant_id.to_string()) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(self.store.load(tenant_id).await?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn metadata(size_bytes: u64) -> SourceMetadata {
+        SourceMetadata {
+            size_bytes,
+            modified_at: size_bytes as i64,
+            etag: None,
+            version_id: None,
+            content_hash: None,
+        }
+    }
+
+    fn modified(session_id: &str, from: u64, to: u64) -> ExternalChangeEvent {
+        ExternalChangeEvent {
+            session_id: session_id.to_string(),
+            change_type: ExternalChangeType::Modified,
+            old_metadata: Some(metadata(from)),
+            new_metadata: Some(metadata(to)),
+            detected_at: to as i64,
+            new_uri: None,
+        }
+    }
+
+    #[test]
+    fn test_push_coalesces_per_session() {
+        let mut queue = ChangeQueue::default();
+        assert_eq!(queue.push(modified("s1", 1, 2)).unwrap().sequence, 1);
+        assert_eq!(queue.push(modified("s2", 5, 6)).unwrap().sequence, 2);
+        // Same change again is a duplicate
+        assert!(queue.push(modified("s1", 1, 2)).is_none());
+
+        let merged = queue.push(modified("s1", 2, 3)).unwrap().clone();
+        assert_eq!((merged.sequence, merged.coalesced), (3, 2));
+        // Old metadata from the first change, new metadata from the latest
+        assert_eq!(merged.event.old_metadata, Some(metadata(1)));
+        assert_eq!(merged.event.new_metadata, Some(metadata(3)));
+
+        assert_eq!(queue.len(), 2);
+        let pending = queue.pending(&[]);
+        assert_eq!(
+            pending.iter().map(|c| c.event.session_id.as_str()).collect::<Vec<_>>(),
+            ["s2", "s1"]
+        );
+        assert_eq!(queue.pending(&["s1".to_string()]).len(), 1);
+    }
+
+    #[test]
+    fn test_ack_keeps_newer_changes_and_ignores_acked_repeats() {
+        let mut queue = ChangeQueue::default();
+        let first = queue.push(modified("s1", 1, 2)).unwrap().sequence;
+        queue.push(modified("s1", 2, 3));
+
+        // The entry absorbed a newer change: acking the first one is stale
+        assert!(!queue.ack("s1", first));
+        assert!(queue.ack("s1", first + 1));
+        assert!(queue.is_empty());
+        assert!(!queue.ack("unknown", 99));
+
+        // A polling backend reporting the acked change again doesn't requeue it
+        assert!(queue.push(modified("s1", 2, 3)).is_none());
+        assert!(queue.push(modified("s1", 3, 4)).is_some());
+
+        assert!(queue.remove_session("s1"));
+        assert!(!queue.remove_session("s1"));
+        assert!(queue.is_empty());
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        saved: std::sync::Mutex<HashMap<String, ChangeQueue>>,
+        fail_saves: AtomicBool,
+    }
+
+    #[async_trait]
+    impl ChangeQueueStore for MemoryStore {
+        async fn load(&self, tenant_id: &str) -> Result<ChangeQueue, StorageError> {
+            Ok(self.saved.lock().unwrap().get(tenant_id).cloned().unwrap_or_default())
+        }
+
+        async fn save(&self, tenant_id: &str, queue: &ChangeQueue) -> Result<(), StorageError> {
+            if self.fail_saves.load(Ordering::SeqCst) {
+                return Err(StorageError::Io("disk full".to_string()));
+            }
+            self.saved.lock().unwrap().insert(tenant_id.to_string(), queue.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_durable_queue_writes_through_and_survives_restart() {
+        let store = Arc::new(MemoryStore::default());
+        let queue = DurableChangeQueue::new(store.clone());
+        queue.enqueue("t1", modified("s1", 1, 2)).await.unwrap().unwrap();
+        queue.enqueue("t2", modified("s1", 1, 2)).await.unwrap().unwrap();
+        assert!(queue.enqueue("t1", modified("s1", 1, 2)).await.unwrap().is_none());
+
+        // A new instance over the same store redelivers until acked
+        let restarted = DurableChangeQueue::new(store.clone());
+        let pending = restarted.pending("t1", &[]).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(restarted.ack("t1", "s1", pending[0].sequence).await.unwrap());
+        assert!(DurableChangeQueue::new(store.clone())
+            .pending("t1", &[])
+            .await
+            .unwrap()
+            .is_empty());
+        // Tenants are separate queues
+        assert_eq!(restarted.pending("t2", &[]).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_leaves_the_queue_unchanged() {
+        let store = Arc::new(MemoryStore::default());
+        let queue = DurableChangeQueue::new(store.clone());
+        queue.enqueue("t1", modified("s1", 1, 2)).await.unwrap();
+
+        store.fail_saves.store(true, Ordering::SeqCst);
+        assert!(queue.enqueue("t1", modified("s2", 1, 2)).await.is_err());
+        assert!(queue.remove_session("t1", "s1").await.is_err());
+
+        let pending = queue.pending("t1", &[]).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event.session_id, "s1");
+    }
+}
@@ -0,0 +1,137 @@
+//! When clients should save a checkpoint.
+//!
+//! Only clients can render a document at a WAL position, so the storage
+//! service can't checkpoint on its own. Instead it answers every `AppendWal`
+//! with whether a checkpoint is due at the new position, following the
+//! server's [`CheckpointPolicy`], and clients save one when told to. This
+//! keeps replay short without each client hard-coding its own rules.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::{StorageBackend, StorageError, WalEntry, WalRecord};
+
+/// Ops whose entries are checkpointed by default: slow to replay, and the
+/// ones most often undone.
+pub const DEFAULT_CHECKPOINT_OPS: &[&str] = &["replace_text", "import_xliff", "optimize_document"];
+
+/// Triggers for checkpoints. Any trigger makes a checkpoint due.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointPolicy {
+    /// Entries appended since the last checkpoint before a new one is due
+    /// (1 = after every entry, 0 = never on count)
+    pub every_entries: u64,
+    /// Age of the last checkpoint after which the next append is checkpointed,
+    /// so a checkpoint is due every so often while the session is being edited
+    pub every: Option<Duration>,
+    /// Patch ops checkpointed as soon as they are logged, so replay never
+    /// has to go through them again
+    pub ops: Vec<String>,
+}
+
+impl Default for CheckpointPolicy {
+    /// A checkpoint after every entry, what clients did before policies.
+    fn default() -> Self {
+        Self {
+            every_entries: 1,
+            every: None,
+            ops: DEFAULT_CHECKPOINT_OPS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// Why a checkpoint is due.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointReason {
+    /// `every_entries` entries were appended since the last checkpoint
+    EntryCount(u64),
+    /// The last checkpoint is older than `every`
+    Interval,
+    /// An appended entry has one of the policy's ops
+    Op(String),
+}
+
+impl std::fmt::Display for CheckpointReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EntryCount(n) => write!(f, "{} entries since the last checkpoint", n),
+            Self::Interval => write!(f, "the last checkpoint is too old"),
+            Self::Op(op) => write!(f, "{} entry", op),
+        }
+    }
+}
+
+impl CheckpointPolicy {
+    /// Whether a checkpoint is due after `appended` brought the WAL to
+    /// `new_position`, given the newest checkpoint position and when it was
+    /// saved (`None` for the baseline).
+    pub fn due(
+        &self,
+        appended: &[WalEntry],
+        new_position: u64,
+        last_checkpoint: u64,
+        last_checkpoint_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<CheckpointReason> {
+        if let Some(op) = appended.iter().flat_map(entry_ops).find(|op| self.ops.contains(op)) {
+            return Some(CheckpointReason::Op(op));
+        }
+        let since = new_position.saturating_sub(last_checkpoint);
+        if self.every_entries > 0 && since >= self.every_entries {
+            return Some(CheckpointReason::EntryCount(since));
+        }
+        // A session with only its baseline is checkpointed on its first append
+        let stale = |every: Duration| {
+            last_checkpoint_at.is_none_or(|at| (now - at).to_std().is_ok_and(|age| age >= every))
+        };
+        match self.every {
+            Some(every) if since > 0 && stale(every) => Some(CheckpointReason::Interval),
+            _ => None,
+        }
+    }
+
+    /// [`due`](Self::due), reading the newest checkpoint from `storage` when
+    /// the entries alone don't decide.
+    pub async fn check(
+        &self,
+        storage: &dyn StorageBackend,
+        tenant_id: &str,
+        session_id: &str,
+        appended: &[WalEntry],
+        new_position: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<CheckpointReason>, StorageError> {
+        if self.every_entries == 1 && !appended.is_empty() {
+            return Ok(Some(CheckpointReason::EntryCount(1)));
+        }
+        let checkpoints = storage.list_checkpoints(tenant_id, session_id).await?;
+        let last = checkpoints
+            .iter()
+            .filter(|c| c.position > 0 && c.position <= new_position)
+            .max_by_key(|c| c.position);
+        Ok(self.due(
+            appended,
+            new_position,
+            last.map_or(0, |c| c.position),
+            last.map(|c| c.created_at),
+            now,
+        ))
+    }
+}
+
+/// The `op` of each patch in a WAL entry; empty when the payload isn't a
+/// readable [`WalRecord`].
+fn entry_ops(entry: &WalEntry) -> Vec<String> {
+    let Ok(record) = serde_json::from_slice::<WalRecord>(&entry.patch_json) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Value>(&record.patches) {
+        Ok(Value::Array(patches)) => patches
+            .iter()
+            .filter_map(|p| p.get("op")?.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
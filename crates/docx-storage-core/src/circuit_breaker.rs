@@ -0,0 +1,309 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use crate::error::StorageError;
+
+/// Consecutive failures that open the circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit rejects calls before letting a probe through.
+pub const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// Returned instead of calling a dependency whose circuit is open.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "{dependency} is unavailable: circuit open after {failures} consecutive failures, \
+     next probe in {retry_in:?}"
+)]
+pub struct CircuitOpen {
+    pub dependency: &'static str,
+    pub failures: u32,
+    pub retry_in: Duration,
+}
+
+impl From<CircuitOpen> for StorageError {
+    fn from(e: CircuitOpen) -> Self {
+        StorageError::Unavailable(e.to_string())
+    }
+}
+
+/// Public view of a circuit's state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through.
+    Closed,
+    /// Calls fail fast until the next probe.
+    Open,
+    /// One probe call is in flight; its outcome closes or reopens the circuit.
+    HalfOpen,
+}
+
+/// Counters for monitoring a circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerStats {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub successes: u64,
+    pub failures: u64,
+    /// Calls rejected without reaching the dependency
+    pub rejected: u64,
+    pub times_opened: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant, failures: u32 },
+    HalfOpen { probe_started: Instant, failures: u32 },
+}
+
+/// Circuit breaker around an external dependency (R2, D1, Google APIs).
+///
+/// After `failure_threshold` consecutive failures the circuit opens and
+/// [`check`](Self::check) fails fast, so an outage costs callers one error
+/// instead of a full retry/backoff budget each. After `open_for`, one call
+/// is let through as a probe: success closes the circuit, failure reopens
+/// it. A probe that never reports (its caller gave up) is replaced after
+/// another `open_for`.
+///
+/// Callers report only failures that say the dependency is unhealthy
+/// (timeouts, 429, 5xx); a 404 or a precondition failure is a success.
+pub struct CircuitBreaker {
+    dependency: &'static str,
+    failure_threshold: u32,
+    open_for: Duration,
+    state: Mutex<State>,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    rejected: AtomicU64,
+    times_opened: AtomicU64,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker with the default threshold and open duration.
+    pub fn new(dependency: &'static str) -> Self {
+        Self::with_thresholds(dependency, DEFAULT_FAILURE_THRESHOLD, DEFAULT_OPEN_DURATION)
+    }
+
+    pub fn with_thresholds(
+        dependency: &'static str,
+        failure_threshold: u32,
+        open_for: Duration,
+    ) -> Self {
+        Self {
+            dependency,
+            failure_threshold: failure_threshold.max(1),
+            open_for,
+            state: Mutex::new(State::Closed { failures: 0 }),
+            successes: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            times_opened: AtomicU64::new(0),
+        }
+    }
+
+    /// Ask to call the dependency. Fails fast while the circuit is open or a
+    /// probe is already in flight.
+    pub fn check(&self) -> Result<(), CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        let (wait_until, failures) = match *state {
+            State::Closed { .. } => return Ok(()),
+            State::Open { until, failures } if now >= until => {
+                info!(dependency = self.dependency, "Circuit half-open, probing");
+                *state = State::HalfOpen {
+                    probe_started: now,
+                    failures,
+                };
+                return Ok(());
+            }
+            State::HalfOpen {
+                probe_started,
+                failures,
+            } if now >= probe_started + self.open_for => {
+                *state = State::HalfOpen {
+                    probe_started: now,
+                    failures,
+                };
+                return Ok(());
+            }
+            State::Open { until, failures } => (until, failures),
+            State::HalfOpen {
+                probe_started,
+                failures,
+            } => (probe_started + self.open_for, failures),
+        };
+
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        Err(CircuitOpen {
+            dependency: self.dependency,
+            failures,
+            retry_in: wait_until.saturating_duration_since(now),
+        })
+    }
+
+    /// Record a call that reached a healthy dependency.
+    pub fn record_success(&self) {
+        self.successes.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, State::Closed { .. }) {
+            info!(dependency = self.dependency, "Circuit closed, dependency recovered");
+        }
+        *state = State::Closed { failures: 0 };
+    }
+
+    /// Record a call that failed because the dependency is unhealthy.
+    pub fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap();
+        let failures = match *state {
+            State::Closed { failures } if failures + 1 < self.failure_threshold => {
+                *state = State::Closed {
+                    failures: failures + 1,
+                };
+                return;
+            }
+            State::Closed { failures } | State::HalfOpen { failures, .. } => failures + 1,
+            // A call started before the circuit opened
+            State::Open { .. } => return,
+        };
+
+        let times_opened = self.times_opened.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            dependency = self.dependency,
+            failures,
+            times_opened,
+            rejected = self.rejected.load(Ordering::Relaxed),
+            "Circuit open for {:?}",
+            self.open_for
+        );
+        *state = State::Open {
+            until: Instant::now() + self.open_for,
+            failures,
+        };
+    }
+
+    pub fn stats(&self) -> CircuitBreakerStats {
+        let (state, consecutive_failures) = match *self.state.lock().unwrap() {
+            State::Closed { failures } => (CircuitState::Closed, failures),
+            State::Open { failures, .. } => (CircuitState::Open, failures),
+            State::HalfOpen { failures, .. } => (CircuitState::HalfOpen, failures),
+        };
+        CircuitBreakerStats {
+            state,
+            consecutive_failures,
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            times_opened: self.times_opened.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN_FOR: Duration = Duration::from_millis(100);
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::with_thresholds("r2", 3, OPEN_FOR)
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker();
+        breaker.record_failure();
+        breaker.record_failure();
+        // A success resets the count
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.stats().consecutive_failures, 2);
+
+        breaker.record_failure();
+        let err = breaker.check().unwrap_err();
+        assert_eq!((err.dependency, err.failures), ("r2", 3));
+        assert!(err.retry_in <= OPEN_FOR);
+        assert!(matches!(
+            StorageError::from(err),
+            StorageError::Unavailable(_)
+        ));
+
+        // Late reports from calls started before it opened change nothing
+        breaker.record_failure();
+        assert_eq!(
+            breaker.stats(),
+            CircuitBreakerStats {
+                state: CircuitState::Open,
+                consecutive_failures: 3,
+                successes: 1,
+                failures: 6,
+                rejected: 1,
+                times_opened: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_half_open_probe_closes_or_reopens() {
+        let breaker = breaker();
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+
+        // One probe after the open duration, others keep failing fast
+        std::thread::sleep(OPEN_FOR);
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.stats().state, CircuitState::HalfOpen);
+        assert!(breaker.check().is_err());
+
+        // A failed probe reopens at once
+        breaker.record_failure();
+        let stats = breaker.stats();
+        assert_eq!(
+            (stats.state, stats.consecutive_failures, stats.times_opened),
+            (CircuitState::Open, 4, 2)
+        );
+        assert!(breaker.check().is_err());
+
+        // A successful probe closes it
+        std::thread::sleep(OPEN_FOR);
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        let stats = breaker.stats();
+        assert_eq!(
+            (stats.state, stats.consecutive_failures),
+            (CircuitState::Closed, 0)
+        );
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_abandoned_probe_is_replaced() {
+        let breaker = breaker();
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        std::thread::sleep(OPEN_FOR);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+
+        // The probe's caller never reports: another probe goes through
+        std::thread::sleep(OPEN_FOR);
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.stats().state, CircuitState::HalfOpen);
+    }
+
+    #[test]
+    fn test_threshold_is_at_least_one() {
+        let breaker = CircuitBreaker::with_thresholds("d1", 0, OPEN_FOR);
+        breaker.record_failure();
+        assert_eq!(breaker.stats().state, CircuitState::Open);
+    }
+}
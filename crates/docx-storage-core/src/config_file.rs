@@ -0,0 +1,508 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, FromArgMatches};
+use tracing::{info, warn};
+
+/// Flag naming the configuration file.
+const CONFIG_FLAG: &str = "--config";
+/// Environment variable naming the configuration file.
+pub const CONFIG_FILE_ENV: &str = "CONFIG_FILE";
+/// How often the configuration file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A value that can be replaced while the service runs. Readers take a
+/// snapshot with [`get`](Self::get), so one request sees one version.
+#[derive(Debug)]
+pub struct Reloadable<T>(Arc<RwLock<Arc<T>>>);
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Reloadable<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(value))))
+    }
+
+    /// The current value.
+    pub fn get(&self) -> Arc<T> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the value for every holder of this handle.
+    pub fn set(&self, value: T) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(value);
+    }
+}
+
+impl<T: Default> Default for Reloadable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Where a service's configuration came from, to load it again on reload.
+#[derive(Debug, Clone)]
+pub struct ConfigSource {
+    args: Vec<OsString>,
+    path: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    /// The configuration file, if one was given.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Reload the configuration on SIGHUP and whenever the file changes,
+    /// passing each new version to `apply`. A version that fails to parse
+    /// or that `apply` rejects is logged and the running one kept.
+    ///
+    /// Flags and environment variables keep precedence over the file, so
+    /// only values set in the file can change.
+    pub fn watch<C, F>(self, apply: F)
+    where
+        C: CommandFactory + FromArgMatches + Send + 'static,
+        F: Fn(C) -> Result<(), String> + Send + 'static,
+    {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            let mut modified = modified_at(&path);
+            let mut hangup = hangup_signal();
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                let trigger = tokio::select! {
+                    _ = interval.tick() => {
+                        let current = modified_at(&path);
+                        if current == modified {
+                            continue;
+                        }
+                        modified = current;
+                        "file changed"
+                    }
+                    _ = recv_hangup(&mut hangup) => "SIGHUP",
+                };
+
+                match load_config_from::<C, _>(self.args.clone()) {
+                    Ok((config, _)) => match apply(config) {
+                        Ok(()) => info!(path = %path.display(), trigger, "Configuration reloaded"),
+                        Err(e) => warn!(
+                            path = %path.display(),
+                            trigger,
+                            error = %e,
+                            "Rejected reloaded configuration, keeping the current one"
+                        ),
+                    },
+                    Err(e) => warn!(
+                        path = %path.display(),
+                        trigger,
+                        error = %e.to_string().trim_end(),
+                        "Failed to reload configuration, keeping the current one"
+                    ),
+                }
+            }
+        });
+    }
+}
+
+/// Parse a service's configuration from its command line, environment and
+/// configuration file (`--config` or `CONFIG_FILE`), in that order of
+/// precedence. Exits with a usage error like `Parser::parse` on failure.
+pub fn load_config<C: CommandFactory + FromArgMatches>() -> (C, ConfigSource) {
+    load_config_from(std::env::args_os()).unwrap_or_else(|e| e.exit())
+}
+
+/// [`load_config`] with explicit arguments (the first is the program name).
+///
+/// The file holds `key = value` lines in TOML syntax, keys being the long
+/// flag names (`backend_max_retries` or `backend-max-retries`). Arrays are
+/// joined with commas for list options.
+pub fn load_config_from<C, I>(args: I) -> Result<(C, ConfigSource), clap::Error>
+where
+    C: CommandFactory + FromArgMatches,
+    I: IntoIterator,
+    I::Item: Into<OsString>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let path = config_path(&args);
+
+    let mut command = C::command();
+    if let Some(path) = &path {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            command.error(
+                ErrorKind::Io,
+                format!("Failed to read config file {}: {}", path.display(), e),
+            )
+        })?;
+        let values = parse_config_file(&text).map_err(|e| {
+            command.error(
+                ErrorKind::InvalidValue,
+                format!("Invalid config file {}: {}", path.display(), e),
+            )
+        })?;
+
+        for (key, value) in values {
+            let name = key.replace('_', "-");
+            let id = command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(name.as_str()) && name != "config")
+                .map(|a| a.get_id().clone());
+            let Some(id) = id else {
+                return Err(command.error(
+                    ErrorKind::UnknownArgument,
+                    format!("Unknown key '{}' in config file {}", key, path.display()),
+                ));
+            };
+            // Defaults are 'static; configuration is loaded once at startup
+            // and on the occasional reload, so the leak stays small.
+            let value: &'static str = Box::leak(value.into_boxed_str());
+            command = command.mut_arg(id, |arg| arg.default_value(value).required(false));
+        }
+    }
+
+    let matches = command.try_get_matches_from_mut(args.clone())?;
+    let config = C::from_arg_matches(&matches).map_err(|e| e.format(&mut command))?;
+    Ok((config, ConfigSource { args, path }))
+}
+
+/// The `--config` flag's value, else `CONFIG_FILE`.
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let arg = arg.to_string_lossy();
+        if arg == CONFIG_FLAG {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    std::env::var_os(CONFIG_FILE_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Parse the subset of TOML used by configuration files: top-level
+/// `key = value` pairs whose values are strings, integers, floats, booleans
+/// or single-line arrays of those. Returns each value as the command-line
+/// text clap parses.
+pub fn parse_config_file(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line_error = |e: String| format!("line {}: {}", number + 1, e);
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(line_error("tables are not supported".to_string()));
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| line_error("expected key = value".to_string()))?;
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(line_error(format!("invalid key '{}'", key)));
+        }
+
+        let value = value.trim();
+        let value = match value.strip_prefix('[') {
+            Some(items) => {
+                let items = items
+                    .strip_suffix(']')
+                    .ok_or_else(|| line_error("unterminated array".to_string()))?;
+                split_array(items)
+                    .into_iter()
+                    .map(parse_scalar)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(line_error)?
+                    .join(",")
+            }
+            None => parse_scalar(value).map_err(line_error)?,
+        };
+
+        if values.insert(key.to_string(), value).is_some() {
+            return Err(line_error(format!("duplicate key '{}'", key)));
+        }
+    }
+    Ok(values)
+}
+
+/// The line up to a `#` outside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Split array items on commas outside strings; a trailing comma is allowed.
+fn split_array(items: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in items.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ',') => {
+                parts.push(items[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    let last = items[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+fn parse_scalar(value: &str) -> Result<String, String> {
+    if let Some(inner) = value.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .ok_or_else(|| format!("unterminated string {}", value))?;
+        return unescape(inner);
+    }
+    if let Some(inner) = value.strip_prefix('\'') {
+        return inner
+            .strip_suffix('\'')
+            .map(str::to_string)
+            .ok_or_else(|| format!("unterminated string {}", value));
+    }
+    if value == "true" || value == "false" {
+        return Ok(value.to_string());
+    }
+
+    let number = value.replace('_', "");
+    if number.parse::<i64>().is_ok() || number.parse::<f64>().is_ok() {
+        return Ok(number);
+    }
+    Err(format!("invalid value '{}' (strings must be quoted)", value))
+}
+
+fn unescape(value: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => return Err(format!("unsupported escape \\{}", other)),
+            None => return Err("unterminated escape".to_string()),
+        }
+    }
+    Ok(out)
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(unix)]
+type Hangup = Option<tokio::signal::unix::Signal>;
+#[cfg(not(unix))]
+type Hangup = ();
+
+#[cfg(unix)]
+fn hangup_signal() -> Hangup {
+    use tokio::signal::unix::{signal, SignalKind};
+    signal(SignalKind::hangup())
+        .inspect_err(|e| warn!("Failed to listen for SIGHUP: {}", e))
+        .ok()
+}
+
+#[cfg(not(unix))]
+fn hangup_signal() -> Hangup {}
+
+#[cfg(unix)]
+async fn recv_hangup(hangup: &mut Hangup) {
+    match hangup {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn recv_hangup(_: &mut Hangup) {
+    std::future::pending::<()>().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Parser)]
+    struct Config {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long, default_value = "local")]
+        backend: String,
+        #[arg(long, default_value_t = 3)]
+        backend_max_retries: u32,
+        #[arg(long, value_delimiter = ',')]
+        regions: Vec<String>,
+        #[arg(long, env = "DOCX_TEST_CONFIG_UNSET_BUCKET")]
+        bucket: String,
+    }
+
+    #[test]
+    fn test_parse_config_file() {
+        let values = parse_config_file(
+            r#"
+            # Storage
+            backend = "r2"   # trailing comment
+            backend-max-retries = 1_000
+            ratio = 0.5
+            verbose = true
+            path = 'C:\data # not a comment'
+            name = "a \"quoted\" \\ name"
+            regions = ["weur", 'eeur', "a,b",]
+            "#,
+        )
+        .unwrap();
+        let expected = [
+            ("backend", "r2"),
+            ("backend-max-retries", "1000"),
+            ("name", r#"a "quoted" \ name"#),
+            ("path", r"C:\data # not a comment"),
+            ("ratio", "0.5"),
+            ("regions", "weur,eeur,a,b"),
+            ("verbose", "true"),
+        ];
+        let values: Vec<(&str, &str)> = values
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_parse_config_file_errors() {
+        for (text, error) in [
+            ("[storage]", "line 1: tables are not supported"),
+            ("\nbackend", "line 2: expected key = value"),
+            ("bad key = 1", "line 1: invalid key 'bad key'"),
+            (
+                "backend = r2",
+                "line 1: invalid value 'r2' (strings must be quoted)",
+            ),
+            ("backend = \"r2", "line 1: unterminated string \"r2"),
+            ("regions = [\"a\"", "line 1: unterminated array"),
+            ("name = \"\\x\"", "line 1: unsupported escape \\x"),
+            ("a = 1\na = 2", "line 2: duplicate key 'a'"),
+        ] {
+            assert_eq!(parse_config_file(text).unwrap_err(), error, "{}", text);
+        }
+    }
+
+    fn write_config(dir: &TempDir, text: &str) -> String {
+        let path = dir.path().join("storage.toml");
+        std::fs::write(&path, text).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_flags_take_precedence_over_the_file() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(
+            &dir,
+            "backend = \"r2\"\nbackend_max_retries = 7\nregions = [\"weur\", \"eeur\"]\nbucket = \"docs\"",
+        );
+
+        let (config, source) =
+            load_config_from::<Config, _>(["server", "--config", &path, "--backend", "local"])
+                .unwrap();
+        assert_eq!(config.backend, "local");
+        assert_eq!(config.backend_max_retries, 7);
+        assert_eq!(config.regions, ["weur", "eeur"]);
+        assert_eq!(config.bucket, "docs");
+        assert_eq!(source.path(), Some(Path::new(&path)));
+
+        // Without the file, the flag it satisfied is missing again
+        let err = load_config_from::<Config, _>(["server"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn test_config_file_errors() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(&dir, "bucket = \"docs\"\nbuckets = \"docs\"");
+        let err =
+            load_config_from::<Config, _>(["server", &format!("--config={}", path)]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownArgument);
+        assert!(err.to_string().contains("Unknown key 'buckets'"));
+
+        let path = write_config(&dir, "config = \"other.toml\"");
+        let err = load_config_from::<Config, _>(["server", "--config", &path]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownArgument);
+
+        let path = write_config(&dir, "backend_max_retries = \"many\"\nbucket = \"docs\"");
+        let err = load_config_from::<Config, _>(["server", "--config", &path]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+
+        let missing = dir.path().join("missing.toml");
+        let err = load_config_from::<Config, _>([
+            "server".as_ref(),
+            "--config".as_ref(),
+            missing.as_os_str(),
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Io);
+    }
+
+    #[test]
+    fn test_reloadable_snapshots() {
+        let config = Reloadable::new(1);
+        let handle = config.clone();
+        let before = config.get();
+        handle.set(2);
+        assert_eq!((*before, *config.get()), (1, 2));
+    }
+}
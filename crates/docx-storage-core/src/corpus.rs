@@ -0,0 +1,342 @@
+//! Text of every session of a tenant, for eDiscovery and compliance search.
+//!
+//! [`export_corpus`] walks the tenant's index by session ID and yields one
+//! [`CorpusRecord`] per session: its metadata and the plain text of the
+//! newest document stored for it (the latest checkpoint at or before its
+//! cursor, or the baseline). Edits logged since that document aren't applied
+//! (the server doesn't replay patches); records say how many there are.
+//!
+//! Exports are paced so they don't starve the tenant's interactive traffic,
+//! and resumable: each record's session ID, passed back as `after`, continues
+//! the export with the next session.
+
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::error::StorageError;
+use crate::session_page::{SessionPageQuery, MAX_SESSION_PAGE_SIZE};
+use crate::storage::{SessionIndexEntry, StorageBackend};
+
+/// The only export format: one JSON record per line.
+pub const CORPUS_FORMAT_JSONL: &str = "jsonl";
+
+/// Sessions exported per second when the server isn't configured otherwise.
+pub const DEFAULT_CORPUS_SESSIONS_PER_SEC: u32 = 10;
+
+/// One session of a tenant corpus export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusRecord {
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub modified_at: DateTime<Utc>,
+    /// WAL position of the document the text was extracted from (0 for the
+    /// baseline)
+    pub position: u64,
+    /// Edits between `position` and the session's cursor the text doesn't show
+    pub pending_entries: u64,
+    pub text: String,
+    /// Why no text was extracted (document missing or not a package)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CorpusRecord {
+    /// The record as one JSONL line, newline included.
+    pub fn to_jsonl(&self) -> Result<Vec<u8>, StorageError> {
+        let mut line = serde_json::to_vec(self).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize corpus record: {}", e))
+        })?;
+        line.push(b'\n');
+        Ok(line)
+    }
+}
+
+/// Whether a package part holds text worth exporting: the body, then
+/// headers, footers, footnotes, endnotes and comments.
+fn text_part_rank(name: &str) -> Option<u8> {
+    let stem = name.strip_prefix("word/")?.strip_suffix(".xml")?;
+    match stem.trim_end_matches(|c: char| c.is_ascii_digit()) {
+        "document" => Some(0),
+        "header" => Some(1),
+        "footer" => Some(2),
+        "footnotes" => Some(3),
+        "endnotes" => Some(4),
+        "comments" => Some(5),
+        _ => None,
+    }
+}
+
+/// Plain text of a .docx package: paragraphs on their own lines, parts
+/// separated by a blank line.
+pub fn extract_text(package: &[u8]) -> Result<String, StorageError> {
+    let zip_error = |e: zip::result::ZipError| {
+        StorageError::Serialization(format!("Not a document package: {}", e))
+    };
+    let mut archive = ZipArchive::new(Cursor::new(package)).map_err(zip_error)?;
+    let mut parts: Vec<(u8, String)> = archive
+        .file_names()
+        .filter_map(|name| text_part_rank(name).map(|rank| (rank, name.to_string())))
+        .collect();
+    if !parts.iter().any(|(rank, _)| *rank == 0) {
+        return Err(StorageError::Serialization(
+            "Not a document package: no word/document.xml".to_string(),
+        ));
+    }
+    parts.sort();
+
+    let mut texts = Vec::new();
+    for (_, name) in parts {
+        let mut xml = String::new();
+        archive
+            .by_name(&name)
+            .map_err(zip_error)?
+            .read_to_string(&mut xml)
+            .map_err(|e| StorageError::Serialization(format!("Failed to read {}: {}", name, e)))?;
+        let text = xml_text(&xml);
+        let text = text.trim();
+        if !text.is_empty() {
+            texts.push(text.to_string());
+        }
+    }
+    Ok(texts.join("\n\n"))
+}
+
+/// Text runs of a WordprocessingML part. Tabs and breaks only count inside
+/// runs (`w:tabs` in paragraph properties are tab stops).
+fn xml_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut in_run = false;
+    let mut in_text = false;
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        if in_text {
+            unescape_into(&rest[..start], &mut text);
+        }
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        let closing = tag.starts_with('/');
+        let empty = tag.ends_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "w:r" => in_run = !closing && !empty,
+            "w:t" => in_text = !closing && !empty,
+            "w:tab" if in_run && !closing => text.push('\t'),
+            "w:br" | "w:cr" if in_run && !closing => text.push('\n'),
+            "w:p" if closing || empty => text.push('\n'),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Append `raw` with its XML entity and character references decoded.
+fn unescape_into(raw: &str, out: &mut String) {
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').and_then(|end| {
+            let c = match &after[..end] {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+/// The corpus record of the session `entry` describes. Storage errors fail;
+/// a missing or unreadable document is reported in the record.
+pub async fn corpus_record<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    entry: &SessionIndexEntry,
+) -> Result<CorpusRecord, StorageError> {
+    let checkpoint = entry
+        .checkpoint_positions
+        .iter()
+        .copied()
+        .filter(|&p| p > 0 && p <= entry.cursor_position)
+        .max();
+    let mut document = None;
+    if let Some(position) = checkpoint {
+        document = storage.load_checkpoint(tenant_id, &entry.id, position).await?;
+    }
+    if document.is_none() {
+        document = storage
+            .load_session(tenant_id, &entry.id)
+            .await?
+            .map(|data| (data, 0));
+    }
+
+    let (position, text) = match document {
+        Some((data, position)) => (position, extract_text(&data)),
+        None => (
+            0,
+            Err(StorageError::NotFound(format!(
+                "No document stored for session {}",
+                entry.id
+            ))),
+        ),
+    };
+    let (text, error) = match text {
+        Ok(text) => (text, None),
+        Err(e) => (String::new(), Some(e.to_string())),
+    };
+    Ok(CorpusRecord {
+        session_id: entry.id.clone(),
+        source_path: entry.source_path.clone(),
+        display_name: entry.display_name.clone(),
+        alias: entry.alias.clone(),
+        created_at: entry.created_at,
+        modified_at: entry.last_modified_at,
+        position,
+        pending_entries: entry.cursor_position.saturating_sub(position),
+        text,
+        error,
+    })
+}
+
+struct ExportState {
+    queue: std::vec::IntoIter<SessionIndexEntry>,
+    next_page: Option<Option<String>>,
+    first: bool,
+}
+
+/// Corpus records of every session of `tenant_id`, by session ID, starting
+/// after session `after` (from the first when `None`). At most
+/// `sessions_per_sec` are read per second (0 = unpaced). The stream ends at
+/// the first storage error; resume after the last record received.
+pub fn export_corpus<S: StorageBackend + ?Sized + 'static>(
+    storage: Arc<S>,
+    tenant_id: String,
+    after: Option<String>,
+    sessions_per_sec: u32,
+) -> impl Stream<Item = Result<CorpusRecord, StorageError>> + Send {
+    let pace = (sessions_per_sec > 0).then(|| Duration::from_secs(1) / sessions_per_sec);
+    let state = ExportState {
+        queue: Vec::new().into_iter(),
+        next_page: Some(after),
+        first: true,
+    };
+    stream::try_unfold(state, move |mut state| {
+        let storage = storage.clone();
+        let tenant_id = tenant_id.clone();
+        async move {
+            loop {
+                if let Some(entry) = state.queue.next() {
+                    if let (Some(pace), false) = (pace, state.first) {
+                        tokio::time::sleep(pace).await;
+                    }
+                    state.first = false;
+                    let record = corpus_record(storage.as_ref(), &tenant_id, &entry).await?;
+                    return Ok(Some((record, state)));
+                }
+                let Some(page_token) = state.next_page.take() else {
+                    return Ok(None);
+                };
+                let page = storage
+                    .list_session_page(
+                        &tenant_id,
+                        &SessionPageQuery {
+                            page_token,
+                            page_size: MAX_SESSION_PAGE_SIZE,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                state.next_page = page.next_page_token.map(Some);
+                state.queue = page.entries.into_iter();
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn package(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_text() {
+        let document = r#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:pPr><w:tabs><w:tab w:val="left" w:pos="720"/></w:tabs></w:pPr>
+              <w:r><w:t>Terms</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">&amp; Conditions </w:t></w:r></w:p>
+            <w:p/>
+            <w:p><w:r><w:t>Line one</w:t><w:br/><w:t>caf&#xE9; &lt;draft&gt;</w:t></w:r></w:p>
+            </w:body></w:document>"#;
+        let header = "<w:hdr><w:p><w:r><w:t>Confidential</w:t></w:r></w:p></w:hdr>";
+        let data = package(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("word/header1.xml", header),
+            ("word/document.xml", document),
+            ("word/styles.xml", "<w:styles><w:t>not text</w:t></w:styles>"),
+        ]);
+
+        assert_eq!(
+            extract_text(&data).unwrap(),
+            "Terms\t& Conditions \n\nLine one\ncafé <draft>\n\nConfidential"
+        );
+        assert!(extract_text(b"not a zip").is_err());
+        assert!(extract_text(&package(&[("word/styles.xml", "<w:styles/>")])).is_err());
+    }
+
+    #[test]
+    fn test_unescape_keeps_unknown_entities() {
+        let mut out = String::new();
+        unescape_into("a &bogus; b & c &#65;", &mut out);
+        assert_eq!(out, "a &bogus; b & c A");
+    }
+}
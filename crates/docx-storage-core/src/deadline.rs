@@ -0,0 +1,118 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::error::StorageError;
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Run `future` with the caller's deadline in scope.
+///
+/// Storage code reads it through [`time_remaining`] and
+/// [`sleep_before_retry`], so retry loops give up as soon as the caller
+/// would no longer wait for the answer. Without a deadline `future` runs
+/// unchanged.
+pub async fn with_deadline<F: Future>(deadline: Option<Instant>, future: F) -> F::Output {
+    match deadline {
+        Some(deadline) => DEADLINE.scope(deadline, future).await,
+        None => future.await,
+    }
+}
+
+/// Time left before the current request's deadline, if it has one.
+pub fn time_remaining() -> Option<Duration> {
+    DEADLINE
+        .try_with(|deadline| deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+/// Sleep `delay` before retrying, unless the caller's deadline passes first:
+/// then fail right away rather than retrying for nobody.
+pub async fn sleep_before_retry(delay: Duration) -> Result<(), StorageError> {
+    if let Some(remaining) = time_remaining() {
+        if remaining <= delay {
+            return Err(StorageError::DeadlineExceeded(format!(
+                "{:?} left before the caller's deadline, retry would wait {:?}",
+                remaining, delay
+            )));
+        }
+    }
+    tokio::time::sleep(delay).await;
+    Ok(())
+}
+
+/// Parse a `grpc-timeout` header value: up to 8 digits and a unit
+/// (`H`, `M`, `S`, `m`, `u`, `n`).
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || !value.is_ascii() {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    if digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u64 = digits.parse().ok()?;
+
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+/// Tower layer putting each gRPC request's deadline (its `grpc-timeout`
+/// header) in scope for the handler; see [`with_deadline`].
+///
+/// Tonic already drops a handler when its deadline passes or the client
+/// cancels; this lets storage code stop earlier, before sleeping through a
+/// backoff the caller will never see the end of.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeadlineLayer;
+
+impl<S> tower::Layer<S> for DeadlineLayer {
+    type Service = DeadlineService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeadlineService { inner }
+    }
+}
+
+/// Service produced by [`DeadlineLayer`].
+#[derive(Debug, Clone)]
+pub struct DeadlineService<S> {
+    inner: S,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for DeadlineService<S>
+where
+    S: tower::Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let deadline = request
+            .headers()
+            .get("grpc-timeout")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_grpc_timeout)
+            .map(|timeout| Instant::now() + timeout);
+
+        Box::pin(with_deadline(deadline, self.inner.call(request)))
+    }
+}
@@ -0,0 +1,92 @@
+//! Ephemeral sessions.
+//!
+//! A session added to the index with an expiry (`expires_at`) is ephemeral:
+//! unlike every other session, storage deletes it without being asked once it
+//! expires. The storage servers run a purge task that deletes the session's
+//! files, then its index entry, so an interrupted purge is finished on the
+//! next run. Legal holds win over expiry: held sessions, and every session of
+//! a held tenant, are kept until the hold is lifted.
+
+use chrono::{DateTime, Utc};
+
+use crate::storage::SessionIndex;
+
+impl SessionIndex {
+    /// IDs of the ephemeral sessions expired at `now` that may be purged.
+    pub fn expired(&self, now: DateTime<Utc>) -> Vec<String> {
+        if self.legal_hold.is_some() {
+            return Vec::new();
+        }
+        self.sessions
+            .iter()
+            .filter(|s| s.legal_hold.is_none() && s.expires_at.is_some_and(|at| at <= now))
+            .map(|s| s.id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SessionIndexEntry;
+    use crate::LegalHold;
+    use chrono::Duration;
+
+    fn session(id: &str, expires_at: Option<DateTime<Utc>>) -> SessionIndexEntry {
+        let created_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        SessionIndexEntry {
+            id: id.to_string(),
+            source_path: None,
+            auto_sync: true,
+            created_at,
+            last_modified_at: created_at,
+            docx_file: None,
+            wal_count: 0,
+            cursor_position: 0,
+            checkpoint_positions: vec![],
+            pending_external_change: false,
+            display_name: None,
+            alias: None,
+            legal_hold: None,
+            expires_at,
+        }
+    }
+
+    fn index(sessions: Vec<SessionIndexEntry>) -> SessionIndex {
+        let mut index = SessionIndex::default();
+        for session in sessions {
+            index.upsert(session);
+        }
+        index
+    }
+
+    #[test]
+    fn test_sessions_expire_exactly_at_their_expiry() {
+        let at = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let index = index(vec![session("s1", Some(at))]);
+
+        assert!(index.expired(at - Duration::seconds(1)).is_empty());
+        assert_eq!(index.expired(at), ["s1"]);
+        assert_eq!(index.expired(at + Duration::seconds(1)), ["s1"]);
+    }
+
+    #[test]
+    fn test_sessions_without_expiry_are_kept() {
+        let index = index(vec![session("kept", None)]);
+
+        assert!(index.expired(DateTime::<Utc>::MAX_UTC).is_empty());
+    }
+
+    #[test]
+    fn test_held_sessions_and_tenants_are_kept() {
+        let at = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let hold = LegalHold::new("matter 42", at);
+        let mut held = session("held", Some(at));
+        held.legal_hold = Some(hold.clone());
+        let mut index = index(vec![held, session("free", Some(at))]);
+
+        assert_eq!(index.expired(at), ["free"]);
+        index.legal_hold = Some(hold);
+        assert!(index.expired(at).is_empty());
+    }
+}
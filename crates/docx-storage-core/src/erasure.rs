@@ -0,0 +1,376 @@
+//! Tenant erasure (right to be forgotten).
+//!
+//! Apart from expired ephemeral sessions, storage never deletes data on its
+//! own; erasing a tenant is an explicit, two-step operator action:
+//!
+//! 1. [`erase_tenant`] without a token counts what the tenant has stored and
+//!    returns a confirmation token, valid for [`CONFIRMATION_TTL_SECS`].
+//! 2. Called again with that token, it deletes every object of the tenant and
+//!    returns an [`ErasureReport`] signed with the operator's erasure key
+//!    (HMAC-SHA256), which can be archived as proof of erasure.
+//!
+//! Tokens are stateless: they carry their expiry and are bound to the tenant by
+//! the same key, so any replica of the storage server can complete the erasure.
+//!
+//! Only the storage backend is erased. Tenant data kept by other services
+//! (e.g. [`WEBSITE_TENANT_DATA`]) is listed in the inventory and in the
+//! report's `remaining`, so the report never claims more than was done.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::admin_log::UNKNOWN_ACTOR;
+use crate::sandbox::sandbox_tenant_id;
+use crate::{LibraryKind, StorageBackend, StorageError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a confirmation token stays valid.
+pub const CONFIRMATION_TTL_SECS: i64 = 600;
+
+/// Tenant data kept outside storage when it runs with the website: rows of
+/// the website's D1 tables (removed by deleting the tenant on the website,
+/// which cascades) and the SSE proxy's session store (expires when idle).
+pub const WEBSITE_TENANT_DATA: &[&str] = &[
+    "D1 tenant_feature_flag",
+    "D1 scheduled_job",
+    "D1 scheduled_job_run",
+    "D1 tenant_bucket",
+    "D1 recent_file",
+    "D1 watch_change_queue",
+    "D1 oauth_connection",
+    "SSE proxy session store",
+];
+
+/// Objects a tenant has stored, by kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantInventory {
+    pub sessions: u64,
+    pub wal_entries: u64,
+    pub checkpoints: u64,
+    pub templates: u64,
+    pub snippets: u64,
+    pub glossaries: u64,
+    #[serde(default)]
+    pub profiles: u64,
+    /// Shared media blobs (not part of the protobuf inventory)
+    #[serde(default)]
+    pub media: u64,
+    pub has_index: bool,
+    /// Tenant data held by other services, which storage can't erase
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elsewhere: Vec<String>,
+}
+
+impl TenantInventory {
+    /// Count what `tenant_id` has stored in `storage`.
+    pub async fn collect(
+        storage: &dyn StorageBackend,
+        tenant_id: &str,
+    ) -> Result<Self, StorageError> {
+        let mut inventory = Self::default();
+
+        for session in storage.list_sessions(tenant_id).await? {
+            inventory.sessions += 1;
+            let (entries, _) = storage
+                .read_wal(tenant_id, &session.session_id, 0, None)
+                .await?;
+            inventory.wal_entries += entries.len() as u64;
+            inventory.checkpoints += storage
+                .list_checkpoints(tenant_id, &session.session_id)
+                .await?
+                .len() as u64;
+        }
+
+        inventory.templates = storage
+            .list_library_items(tenant_id, LibraryKind::Template)
+            .await?
+            .len() as u64;
+        inventory.snippets = storage
+            .list_library_items(tenant_id, LibraryKind::Snippet)
+            .await?
+            .len() as u64;
+        inventory.glossaries = storage
+            .list_library_items(tenant_id, LibraryKind::Glossary)
+            .await?
+            .len() as u64;
+        inventory.profiles = storage
+            .list_library_items(tenant_id, LibraryKind::Profile)
+            .await?
+            .len() as u64;
+        inventory.media = storage
+            .list_library_items(tenant_id, LibraryKind::Media)
+            .await?
+            .len() as u64;
+        inventory.has_index = storage.load_index(tenant_id).await?.is_some();
+
+        Ok(inventory)
+    }
+
+    /// Whether nothing is stored.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Record of a completed erasure.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureReport {
+    pub tenant_id: String,
+    pub backend: String,
+    pub erased_at: DateTime<Utc>,
+    /// Who confirmed the erasure. The tenant's admin log goes with its data,
+    /// so the signed report is the only record of the action.
+    #[serde(default)]
+    pub erased_by: String,
+    /// What the tenant had stored before the erasure.
+    pub erased: TenantInventory,
+    /// Objects (files or keys) deleted, including backend bookkeeping.
+    pub objects_deleted: u64,
+    /// What was left afterwards: data held elsewhere, and anything written
+    /// while the erasure ran. The erasure is complete only if this is empty.
+    pub remaining: TenantInventory,
+}
+
+/// Outcome of one [`erase_tenant`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErasureStep {
+    /// First step: nothing was deleted; confirm with `token` before `expires_at`.
+    ConfirmationRequired {
+        token: String,
+        expires_at: DateTime<Utc>,
+        inventory: TenantInventory,
+    },
+    /// Second step: the tenant was erased.
+    Erased {
+        report: Box<ErasureReport>,
+        /// JSON of the report, as signed.
+        report_json: String,
+        /// Hex HMAC-SHA256 of `report_json` under the erasure key.
+        signature: String,
+    },
+}
+
+/// Issues confirmation tokens and signs erasure reports with the operator's key.
+#[derive(Clone)]
+pub struct ErasureSigner {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ErasureSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErasureSigner").finish_non_exhaustive()
+    }
+}
+
+impl ErasureSigner {
+    pub fn new(key: impl Into<Vec<u8>>) -> Result<Self, StorageError> {
+        let key = key.into();
+        if key.len() < 16 {
+            return Err(StorageError::InvalidArgument(
+                "erasure key must be at least 16 bytes".to_string(),
+            ));
+        }
+        Ok(Self { key })
+    }
+
+    /// Token confirming the erasure of `tenant_id`, as `{expiry}.{mac}`.
+    pub fn issue_token(&self, tenant_id: &str, expires_at: DateTime<Utc>) -> String {
+        let expiry = expires_at.timestamp();
+        format!("{}.{}", expiry, self.sign(&Self::token_message(tenant_id, expiry)))
+    }
+
+    /// Check that `token` confirms the erasure of `tenant_id` and has not expired.
+    pub fn verify_token(
+        &self,
+        tenant_id: &str,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), StorageError> {
+        let invalid = || {
+            StorageError::InvalidArgument(format!(
+                "invalid confirmation token for tenant '{}'",
+                tenant_id
+            ))
+        };
+
+        let (expiry, mac) = token.split_once('.').ok_or_else(invalid)?;
+        let expiry: i64 = expiry.parse().map_err(|_| invalid())?;
+        let mac = hex::decode(mac).map_err(|_| invalid())?;
+
+        self.mac(&Self::token_message(tenant_id, expiry))
+            .verify_slice(&mac)
+            .map_err(|_| invalid())?;
+        if now.timestamp() > expiry {
+            return Err(StorageError::InvalidArgument(
+                "confirmation token expired; request a new one".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Hex HMAC-SHA256 of `data`.
+    pub fn sign(&self, data: &[u8]) -> String {
+        hex::encode(self.mac(data).finalize().into_bytes())
+    }
+
+    fn mac(&self, data: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(data);
+        mac
+    }
+
+    fn token_message(tenant_id: &str, expiry: i64) -> Vec<u8> {
+        format!("erase-tenant:{}:{}", tenant_id, expiry).into_bytes()
+    }
+}
+
+/// Run one step of the erasure of `tenant_id`: without a token, count its data
+/// and issue a token; with a valid token, delete everything and sign a report.
+/// The tenant's sandbox is erased along with it. Refused while the tenant, its
+/// sandbox or any of their sessions is under legal hold. `actor` is recorded in
+/// the report: nothing is written to the erased tenant afterwards. `elsewhere`
+/// names tenant data held by other services, reported as not erased.
+pub async fn erase_tenant(
+    storage: &dyn StorageBackend,
+    signer: &ErasureSigner,
+    tenant_id: &str,
+    actor: &str,
+    elsewhere: &[&str],
+    confirmation_token: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<ErasureStep, StorageError> {
+    // The legacy empty tenant lives at the storage root
+    if tenant_id.is_empty() {
+        return Err(StorageError::InvalidArgument(
+            "cannot erase the empty tenant".to_string(),
+        ));
+    }
+
+    if let Some(index) = storage.load_index(tenant_id).await? {
+        index.ensure_nothing_held()?;
+    }
+    // A tenant's sandbox holds copies of its data and goes with it. Sandboxes
+    // and tenants whose ID can't take the suffix have none.
+    let sandbox_id = sandbox_tenant_id(tenant_id).ok();
+    if let Some(sandbox_id) = &sandbox_id {
+        if let Some(index) = storage.load_index(sandbox_id).await? {
+            index.ensure_nothing_held()?;
+        }
+    }
+
+    let elsewhere: Vec<String> = elsewhere.iter().map(|s| s.to_string()).collect();
+    let inventory = TenantInventory {
+        elsewhere: elsewhere.clone(),
+        ..TenantInventory::collect(storage, tenant_id).await?
+    };
+
+    let Some(token) = confirmation_token.filter(|t| !t.is_empty()) else {
+        let expires_at = now + chrono::Duration::seconds(CONFIRMATION_TTL_SECS);
+        return Ok(ErasureStep::ConfirmationRequired {
+            token: signer.issue_token(tenant_id, expires_at),
+            expires_at,
+            inventory,
+        });
+    };
+    signer.verify_token(tenant_id, token, now)?;
+
+    let mut objects_deleted = storage.erase_tenant(tenant_id).await?;
+    if let Some(sandbox_id) = &sandbox_id {
+        objects_deleted += storage.erase_tenant(sandbox_id).await?;
+    }
+    let report = ErasureReport {
+        tenant_id: tenant_id.to_string(),
+        backend: storage.backend_name().to_string(),
+        erased_at: now,
+        erased_by: match actor.trim() {
+            "" => UNKNOWN_ACTOR.to_string(),
+            actor => actor.to_string(),
+        },
+        erased: inventory,
+        objects_deleted,
+        remaining: TenantInventory {
+            elsewhere,
+            ..TenantInventory::collect(storage, tenant_id).await?
+        },
+    };
+    let report_json = serde_json::to_string(&report)
+        .map_err(|e| StorageError::Serialization(e.to_string()))?;
+    let signature = signer.sign(report_json.as_bytes());
+
+    Ok(ErasureStep::Erased {
+        report: Box::new(report),
+        report_json,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer() -> ErasureSigner {
+        ErasureSigner::new("0123456789abcdef-erasure").unwrap()
+    }
+
+    #[test]
+    fn test_signer_requires_a_long_enough_key() {
+        assert!(ErasureSigner::new("too short").is_err());
+        assert!(ErasureSigner::new("0123456789abcdef").is_ok());
+    }
+
+    #[test]
+    fn test_token_is_bound_to_tenant_key_and_expiry() {
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::seconds(CONFIRMATION_TTL_SECS);
+        let token = signer().issue_token("acme", expires_at);
+
+        assert!(signer().verify_token("acme", &token, now).is_ok());
+        assert!(signer().verify_token("other", &token, now).is_err());
+        let other_key = ErasureSigner::new("another-erasure-key").unwrap();
+        assert!(other_key.verify_token("acme", &token, now).is_err());
+
+        // Pushing the expiry out invalidates the MAC
+        let (_, mac) = token.split_once('.').unwrap();
+        let extended = format!("{}.{}", expires_at.timestamp() + 3600, mac);
+        assert!(signer().verify_token("acme", &extended, now).is_err());
+
+        let err = signer()
+            .verify_token("acme", &token, expires_at + chrono::Duration::seconds(1))
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        for garbage in ["", "nodot", "x.abcd", "123.not-hex"] {
+            assert!(signer().verify_token("acme", garbage, now).is_err());
+        }
+    }
+
+    #[test]
+    fn test_data_held_elsewhere_keeps_the_report_incomplete() {
+        let remaining = TenantInventory {
+            elsewhere: vec!["D1 scheduled_job".to_string()],
+            ..Default::default()
+        };
+        assert!(!remaining.is_empty());
+        assert!(TenantInventory::default().is_empty());
+        assert!(WEBSITE_TENANT_DATA.iter().any(|d| d.contains("tenant_bucket")));
+    }
+
+    #[test]
+    fn test_reports_signed_before_actor_and_elsewhere_still_parse() {
+        let json = r#"{"tenant_id":"acme","backend":"local","erased_at":"2026-01-01T00:00:00Z",
+            "erased":{"sessions":1,"wal_entries":2,"checkpoints":0,"templates":0,"snippets":0,
+            "glossaries":0,"has_index":true},"objects_deleted":4,
+            "remaining":{"sessions":0,"wal_entries":0,"checkpoints":0,"templates":0,"snippets":0,
+            "glossaries":0,"has_index":false}}"#;
+        let report: ErasureReport = serde_json::from_str(json).unwrap();
+        assert_eq!(report.erased_by, "");
+        assert!(report.remaining.is_empty());
+
+        // Nothing held elsewhere: the field is left out of what gets signed
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("elsewhere"));
+    }
+}
@@ -1,5 +1,19 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
+/// gRPC metadata naming the error variant (see [`StorageError::kind`]), so
+/// clients don't have to parse messages.
+pub const ERROR_KIND_METADATA: &str = "x-storage-error";
+
+/// gRPC metadata with the delay, in milliseconds, after which a throttled
+/// call may be retried.
+pub const RETRY_AFTER_METADATA: &str = "retry-after-ms";
+
+/// Standard gRPC retry pushback: clients with a retry policy wait this many
+/// milliseconds before retrying, and don't retry when it is negative.
+pub const RETRY_PUSHBACK_METADATA: &str = "grpc-retry-pushback-ms";
+
 /// Errors that can occur in the storage layer.
 #[derive(Error, Debug)]
 pub enum StorageError {
@@ -12,6 +26,27 @@ pub enum StorageError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// The backend is rate limiting us. Retry after `retry_after` when the
+    /// backend said how long to wait.
+    #[error("Throttled: {message}")]
+    Throttled {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
+    /// A condition of the request (e.g. an If-Match ETag) doesn't hold.
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
+    /// Storage or API quota used up; retrying won't help until it is raised
+    /// or space is freed.
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// The backend rejected our credentials (expired or revoked token, bad key).
+    #[error("Unauthenticated: {0}")]
+    Unauthenticated(String),
+
     #[error("Lock error: {0}")]
     Lock(String),
 
@@ -24,6 +59,103 @@ pub enum StorageError {
     #[error("Sync error: {0}")]
     Sync(String),
 
+    /// The external source is unreachable (network down, rate limited, 5xx).
+    /// Operations failing this way are worth retrying later.
+    #[error("Source unavailable: {0}")]
+    Unavailable(String),
+
     #[error("Watch error: {0}")]
     Watch(String),
+
+    /// The caller's deadline passes before the operation could complete.
+    #[error("Deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+
+    /// The data is under legal hold and can't be deleted until the hold is lifted.
+    #[error("Legal hold: {0}")]
+    LegalHold(String),
+
+    /// The data changed since the caller read it, and the operation would
+    /// overwrite those changes.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// An appended WAL entry's position doesn't follow the stored WAL:
+    /// `found` below `expected` repeats an entry already logged, above it
+    /// leaves a gap.
+    #[error("WAL position mismatch: expected entry {expected}, got {found}")]
+    WalPosition { expected: u64, found: u64 },
+}
+
+impl StorageError {
+    /// Stable name of the variant, sent as [`ERROR_KIND_METADATA`].
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StorageError::Io(_) => "io",
+            StorageError::Serialization(_) => "serialization",
+            StorageError::NotFound(_) => "not_found",
+            StorageError::Throttled { .. } => "throttled",
+            StorageError::PreconditionFailed(_) => "precondition_failed",
+            StorageError::QuotaExceeded(_) => "quota_exceeded",
+            StorageError::Unauthenticated(_) => "unauthenticated",
+            StorageError::Lock(_) => "lock",
+            StorageError::InvalidArgument(_) => "invalid_argument",
+            StorageError::Internal(_) => "internal",
+            StorageError::Sync(_) => "sync",
+            StorageError::Unavailable(_) => "unavailable",
+            StorageError::Watch(_) => "watch",
+            StorageError::DeadlineExceeded(_) => "deadline_exceeded",
+            StorageError::LegalHold(_) => "legal_hold",
+            StorageError::Conflict(_) => "conflict",
+            StorageError::WalPosition { .. } => "wal_position",
+        }
+    }
+
+    /// Whether the same call may succeed later without any change.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            StorageError::Unavailable(_) | StorageError::Throttled { .. }
+        )
+    }
+
+    /// How long the backend asked us to wait before retrying, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            StorageError::Throttled { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// gRPC metadata describing the error: its kind, and for throttling the
+    /// delay before a retry. Quota errors, which share throttling's gRPC
+    /// code, tell retry policies not to retry.
+    pub fn metadata(&self) -> Vec<(&'static str, String)> {
+        let mut metadata = vec![(ERROR_KIND_METADATA, self.kind().to_string())];
+        if let Some(retry_after) = self.retry_after() {
+            let millis = retry_after.as_millis().to_string();
+            metadata.push((RETRY_AFTER_METADATA, millis.clone()));
+            metadata.push((RETRY_PUSHBACK_METADATA, millis));
+        } else if matches!(self, StorageError::QuotaExceeded(_)) {
+            metadata.push((RETRY_PUSHBACK_METADATA, "-1".to_string()));
+        }
+        metadata
+    }
+
+    /// An I/O failure, as [`StorageError::QuotaExceeded`] when the disk or
+    /// the user's quota is full.
+    pub fn from_io(context: impl std::fmt::Display, e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::StorageFull | std::io::ErrorKind::QuotaExceeded => {
+                StorageError::QuotaExceeded(format!("{}: {}", context, e))
+            }
+            _ => StorageError::Io(format!("{}: {}", context, e)),
+        }
+    }
+}
+
+/// Parse an HTTP `Retry-After` header given in seconds. (HTTP dates aren't
+/// sent by the APIs we call.)
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
 }
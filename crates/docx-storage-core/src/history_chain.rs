@@ -0,0 +1,387 @@
+//! Tamper-evident session history.
+//!
+//! Backends with a history chain record every WAL append, WAL truncation and
+//! checkpoint of a session as a [`HistoryLink`] in an append-only log: each
+//! link holds the SHA-256 digest of what was written and the hash of the link
+//! before it, so rewriting any past entry changes every hash after it.
+//! Truncations (undo followed by a new edit, compaction) are links too; the
+//! chain itself is never truncated.
+//!
+//! [`prove_history`] checks the chain, replays it to check the stored WAL
+//! still holds what was chained, and signs the chain head with the operator's
+//! history key (HMAC-SHA256). A user who archived a proof can later ask for a
+//! new one: if the history was rewritten, the new chain no longer extends the
+//! archived head.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{ChunkStream, StorageBackend, StorageError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What a link records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEventKind {
+    /// A WAL entry appended at `position`
+    Wal,
+    /// A checkpoint saved at `position`
+    Checkpoint,
+    /// The WAL truncated to its first `position` entries
+    Truncate,
+}
+
+impl HistoryEventKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            HistoryEventKind::Wal => "wal",
+            HistoryEventKind::Checkpoint => "checkpoint",
+            HistoryEventKind::Truncate => "truncate",
+        }
+    }
+}
+
+/// A change to a session's history, before it is chained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEvent {
+    pub kind: HistoryEventKind,
+    pub position: u64,
+    /// Hex SHA-256 of what was written; empty for truncations
+    pub digest: String,
+}
+
+impl HistoryEvent {
+    /// A WAL entry stored as `line` (see [`wal_line_digest`]).
+    pub fn wal(position: u64, line: &[u8]) -> Self {
+        Self {
+            kind: HistoryEventKind::Wal,
+            position,
+            digest: wal_line_digest(line),
+        }
+    }
+
+    /// A checkpoint whose document has the hex SHA-256 `digest`.
+    pub fn checkpoint(position: u64, digest: String) -> Self {
+        Self {
+            kind: HistoryEventKind::Checkpoint,
+            position,
+            digest,
+        }
+    }
+
+    /// The WAL truncated to its first `keep_count` entries.
+    pub fn truncate(keep_count: u64) -> Self {
+        Self {
+            kind: HistoryEventKind::Truncate,
+            position: keep_count,
+            digest: String::new(),
+        }
+    }
+}
+
+/// One link of a session's history chain, stored as a JSON line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryLink {
+    /// 1 for the first link
+    pub seq: u64,
+    pub kind: HistoryEventKind,
+    pub position: u64,
+    pub digest: String,
+    pub at: DateTime<Utc>,
+    /// Hash of the previous link, or the session's genesis hash
+    pub prev: String,
+    pub hash: String,
+}
+
+/// Hash the chain of `session_id` starts from.
+pub fn genesis_hash(tenant_id: &str, session_id: &str) -> String {
+    hex::encode(Sha256::digest(
+        format!("docx-mcp-history:v1:{}/{}", tenant_id, session_id).as_bytes(),
+    ))
+}
+
+/// Hex SHA-256 of a stored WAL line, as read back by `read_wal`.
+pub fn wal_line_digest(line: &[u8]) -> String {
+    hex::encode(Sha256::digest(line.trim_ascii()))
+}
+
+/// Hex SHA-256 of a checkpointed document, as given to `save_checkpoint`.
+pub fn document_digest(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn link_hash(
+    prev: &str,
+    seq: u64,
+    kind: HistoryEventKind,
+    position: u64,
+    digest: &str,
+    at: DateTime<Utc>,
+) -> String {
+    let message = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        prev,
+        seq,
+        kind.as_str(),
+        position,
+        digest,
+        at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+    );
+    hex::encode(Sha256::digest(message.as_bytes()))
+}
+
+/// Links recording `events` after `last` (the chain's current head, if any).
+pub fn chain_events(
+    last: Option<&HistoryLink>,
+    tenant_id: &str,
+    session_id: &str,
+    events: &[HistoryEvent],
+    at: DateTime<Utc>,
+) -> Vec<HistoryLink> {
+    let mut prev = last.map_or_else(|| genesis_hash(tenant_id, session_id), |l| l.hash.clone());
+    let mut seq = last.map_or(0, |l| l.seq);
+    events
+        .iter()
+        .map(|event| {
+            seq += 1;
+            let hash = link_hash(&prev, seq, event.kind, event.position, &event.digest, at);
+            HistoryLink {
+                seq,
+                kind: event.kind,
+                position: event.position,
+                digest: event.digest.clone(),
+                at,
+                prev: std::mem::replace(&mut prev, hash.clone()),
+                hash,
+            }
+        })
+        .collect()
+}
+
+/// JSON lines for `links`, each ending with a newline.
+pub fn history_jsonl(links: &[HistoryLink]) -> Result<Vec<u8>, StorageError> {
+    let mut out = Vec::new();
+    for link in links {
+        serde_json::to_writer(&mut out, link)
+            .map_err(|e| StorageError::Serialization(format!("Failed to serialize history link: {}", e)))?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+/// Parse a stored chain. A torn last line (a crash mid-append) is dropped.
+pub fn parse_history(jsonl: &[u8]) -> Result<Vec<HistoryLink>, StorageError> {
+    let lines: Vec<&[u8]> = jsonl
+        .split(|b| *b == b'\n')
+        .map(|l| l.trim_ascii())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut links = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_slice(line) {
+            Ok(link) => links.push(link),
+            Err(_) if i + 1 == lines.len() && !jsonl.ends_with(b"\n") => break,
+            Err(e) => {
+                return Err(StorageError::Serialization(format!(
+                    "Failed to parse history link {}: {}",
+                    i + 1,
+                    e
+                )))
+            }
+        }
+    }
+    Ok(links)
+}
+
+/// Pass `chunks` through while hashing them; the digest of everything that
+/// went through is read from the returned handle once the stream is consumed.
+pub fn digest_chunks(chunks: ChunkStream<'_>) -> (ChunkStream<'_>, ChunkDigest) {
+    let digest = ChunkDigest(Arc::new(Mutex::new(Sha256::new())));
+    let hasher = digest.0.clone();
+    let chunks = chunks
+        .inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                hasher.lock().unwrap().update(chunk);
+            }
+        })
+        .boxed();
+    (chunks, digest)
+}
+
+/// Digest of a stream passed through [`digest_chunks`].
+pub struct ChunkDigest(Arc<Mutex<Sha256>>);
+
+impl ChunkDigest {
+    /// Hex SHA-256 of the chunks seen so far.
+    pub fn hex(&self) -> String {
+        hex::encode(self.0.lock().unwrap().clone().finalize())
+    }
+}
+
+/// Signs history proofs with the operator's history key.
+#[derive(Clone)]
+pub struct HistorySigner {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for HistorySigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistorySigner").finish_non_exhaustive()
+    }
+}
+
+impl HistorySigner {
+    pub fn new(key: impl Into<Vec<u8>>) -> Result<Self, StorageError> {
+        let key = key.into();
+        if key.len() < 16 {
+            return Err(StorageError::InvalidArgument(
+                "history key must be at least 16 bytes".to_string(),
+            ));
+        }
+        Ok(Self { key })
+    }
+
+    /// Hex HMAC-SHA256 of `data`.
+    pub fn sign(&self, data: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(data);
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// What [`prove_history`] found, signed as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryProof {
+    pub tenant_id: String,
+    pub session_id: String,
+    /// Hash of the last link (the genesis hash when nothing was chained)
+    pub head_hash: String,
+    pub links: u64,
+    /// Whether every link hashes to its successor's `prev`
+    pub chain_valid: bool,
+    /// First link that doesn't, when the chain isn't valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broken_at_seq: Option<u64>,
+    /// Position of the last stored WAL entry
+    pub wal_position: u64,
+    /// Stored WAL entries whose digest differs from the chained one, or
+    /// chained entries the WAL no longer holds
+    pub mismatched_positions: Vec<u64>,
+    /// Stored WAL entries written before the chain was kept
+    pub unchained_entries: u64,
+    pub checkpoints: u64,
+    pub issued_at: DateTime<Utc>,
+}
+
+impl HistoryProof {
+    /// Whether the chain is intact and matches the stored WAL.
+    pub fn is_consistent(&self) -> bool {
+        self.chain_valid && self.mismatched_positions.is_empty()
+    }
+}
+
+/// Check the history chain of a session against its stored WAL and sign the
+/// result. Returns the proof, its JSON and the JSON's signature.
+pub async fn prove_history(
+    storage: &dyn StorageBackend,
+    signer: &HistorySigner,
+    tenant_id: &str,
+    session_id: &str,
+    now: DateTime<Utc>,
+) -> Result<(HistoryProof, String, String), StorageError> {
+    if !storage.session_exists(tenant_id, session_id).await? {
+        return Err(StorageError::NotFound(format!("session {}", session_id)));
+    }
+    let links = storage.read_history(tenant_id, session_id).await?;
+
+    let mut prev = genesis_hash(tenant_id, session_id);
+    let mut broken_at_seq = None;
+    // Chained WAL digests by position, as the truncations left them
+    let mut chained: BTreeMap<u64, &str> = BTreeMap::new();
+    let mut checkpoints = 0;
+    for (i, link) in links.iter().enumerate() {
+        let expected = link_hash(&prev, link.seq, link.kind, link.position, &link.digest, link.at);
+        if broken_at_seq.is_none()
+            && (link.seq != i as u64 + 1 || link.prev != prev || link.hash != expected)
+        {
+            broken_at_seq = Some(link.seq);
+        }
+        prev = link.hash.clone();
+        match link.kind {
+            HistoryEventKind::Wal => {
+                chained.insert(link.position, &link.digest);
+            }
+            HistoryEventKind::Truncate => {
+                chained.split_off(&(link.position + 1));
+            }
+            HistoryEventKind::Checkpoint => checkpoints += 1,
+        }
+    }
+
+    let (entries, _) = storage.read_wal(tenant_id, session_id, 0, None).await?;
+    let mut mismatched_positions = Vec::new();
+    let mut unchained_entries = 0;
+    for entry in &entries {
+        match chained.remove(&entry.position) {
+            Some(digest) if digest == wal_line_digest(&entry.patch_json) => {}
+            Some(_) => mismatched_positions.push(entry.position),
+            None => unchained_entries += 1,
+        }
+    }
+    mismatched_positions.extend(chained.keys());
+    mismatched_positions.sort_unstable();
+
+    let proof = HistoryProof {
+        tenant_id: tenant_id.to_string(),
+        session_id: session_id.to_string(),
+        head_hash: links.last().map_or_else(|| genesis_hash(tenant_id, session_id), |l| l.hash.clone()),
+        links: links.len() as u64,
+        chain_valid: broken_at_seq.is_none(),
+        broken_at_seq,
+        wal_position: entries.last().map_or(0, |e| e.position),
+        mismatched_positions,
+        unchained_entries,
+        checkpoints,
+        issued_at: now,
+    };
+    let proof_json =
+        serde_json::to_string(&proof).map_err(|e| StorageError::Serialization(e.to_string()))?;
+    let signature = signer.sign(proof_json.as_bytes());
+    Ok((proof, proof_json, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_links_to_previous_hash() {
+        let at = Utc::now();
+        let first = chain_events(None, "t1", "s1", &[HistoryEvent::wal(1, b"{\"a\":1}\n")], at);
+        let more = chain_events(
+            first.last(),
+            "t1",
+            "s1",
+            &[HistoryEvent::truncate(0), HistoryEvent::checkpoint(1, "ab".into())],
+            at,
+        );
+
+        assert_eq!(first[0].prev, genesis_hash("t1", "s1"));
+        assert_eq!(more[0].prev, first[0].hash);
+        assert_eq!(more[1].seq, 3);
+        assert_eq!(first[0].digest, wal_line_digest(b"{\"a\":1}"));
+
+        let all: Vec<_> = first.into_iter().chain(more).collect();
+        let jsonl = history_jsonl(&all).unwrap();
+        assert_eq!(parse_history(&jsonl).unwrap(), all);
+        // A torn last line is dropped
+        assert_eq!(parse_history(&jsonl[..jsonl.len() - 10]).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+//! Rebuilding a tenant's session index from its stored objects.
+//!
+//! The index can drift from what is stored, e.g. when a server crashes after
+//! saving a session but before indexing it. [`scan_sessions`] lists what is
+//! stored and [`SessionIndex::reconcile`] brings the index in line:
+//!
+//! - Stored sessions missing from the index are added back.
+//! - WAL counts and checkpoint positions behind storage are caught up. They
+//!   are never lowered, since a write racing the scan makes the index look
+//!   ahead of storage.
+//! - Entries without a stored document are reported as orphans, and only
+//!   removed when asked. Entries indexed after the scan started and held
+//!   entries are never removed.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::storage::{SessionIndex, SessionIndexEntry, SessionInfo};
+use crate::{StorageBackend, StorageError};
+
+/// A session found in storage.
+#[derive(Debug, Clone)]
+pub struct ScannedSession {
+    pub info: SessionInfo,
+    pub wal_count: u64,
+    pub checkpoint_positions: Vec<u64>,
+}
+
+/// What a rebuild found wrong with the index, by session ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexRebuildReport {
+    /// Stored sessions that were missing from the index
+    pub added: Vec<String>,
+    /// Entries whose WAL count or checkpoints were behind storage
+    pub corrected: Vec<String>,
+    /// Entries without a stored document
+    pub orphans: Vec<String>,
+    /// Orphans removed from the index
+    pub removed: Vec<String>,
+}
+
+impl IndexRebuildReport {
+    /// Whether the index matched storage.
+    pub fn is_clean(&self) -> bool {
+        self.added.is_empty() && self.corrected.is_empty() && self.orphans.is_empty()
+    }
+
+    /// Whether reconciling changed the index.
+    pub fn changed_index(&self) -> bool {
+        !(self.added.is_empty() && self.corrected.is_empty() && self.removed.is_empty())
+    }
+}
+
+/// List the sessions stored for `tenant_id`, with their WAL length and
+/// checkpoints.
+pub async fn scan_sessions(
+    storage: &dyn StorageBackend,
+    tenant_id: &str,
+) -> Result<Vec<ScannedSession>, StorageError> {
+    let mut scanned = Vec::new();
+    for info in storage.list_sessions(tenant_id).await? {
+        let (last, _) = storage.tail_wal(tenant_id, &info.session_id, None, 1).await?;
+        let checkpoints = storage.list_checkpoints(tenant_id, &info.session_id).await?;
+        scanned.push(ScannedSession {
+            wal_count: last.first().map_or(0, |e| e.position),
+            checkpoint_positions: checkpoints.iter().map(|c| c.position).collect(),
+            info,
+        });
+    }
+    Ok(scanned)
+}
+
+impl SessionIndex {
+    /// Bring the index in line with the sessions `scanned` from storage by a
+    /// scan started at `scanned_at`, removing orphans if `remove_orphans`.
+    pub fn reconcile(
+        &mut self,
+        scanned: &[ScannedSession],
+        scanned_at: DateTime<Utc>,
+        remove_orphans: bool,
+    ) -> IndexRebuildReport {
+        let mut report = IndexRebuildReport::default();
+
+        for session in scanned {
+            let id = &session.info.session_id;
+            let Some(entry) = self.get_mut(id) else {
+                self.upsert(SessionIndexEntry {
+                    id: id.clone(),
+                    source_path: session.info.source_path.clone(),
+                    auto_sync: true,
+                    created_at: session.info.created_at,
+                    last_modified_at: session.info.modified_at,
+                    docx_file: Some(format!("{}.docx", id)),
+                    wal_count: session.wal_count,
+                    cursor_position: session.wal_count,
+                    checkpoint_positions: session.checkpoint_positions.clone(),
+                    pending_external_change: false,
+                    display_name: None,
+                    alias: None,
+                    legal_hold: None,
+                    expires_at: None,
+                });
+                report.added.push(id.clone());
+                continue;
+            };
+
+            let mut corrected = false;
+            if entry.wal_count < session.wal_count {
+                // The cursor was at the end of the WAL the index knew about
+                if entry.cursor_position == entry.wal_count {
+                    entry.cursor_position = session.wal_count;
+                }
+                entry.wal_count = session.wal_count;
+                corrected = true;
+            }
+            for &position in &session.checkpoint_positions {
+                if !entry.checkpoint_positions.contains(&position) {
+                    entry.checkpoint_positions.push(position);
+                    corrected = true;
+                }
+            }
+            if corrected {
+                entry.checkpoint_positions.sort_unstable();
+                report.corrected.push(id.clone());
+            }
+        }
+
+        let tenant_held = self.legal_hold.is_some();
+        for entry in &self.sessions {
+            if scanned.iter().any(|s| s.info.session_id == entry.id) {
+                continue;
+            }
+            report.orphans.push(entry.id.clone());
+            if remove_orphans
+                && !tenant_held
+                && entry.legal_hold.is_none()
+                && entry.created_at < scanned_at
+            {
+                report.removed.push(entry.id.clone());
+            }
+        }
+        for id in &report.removed {
+            self.remove(id);
+        }
+
+        report
+    }
+}
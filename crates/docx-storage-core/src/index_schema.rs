@@ -0,0 +1,103 @@
+use serde_json::{Map, Value};
+
+use crate::error::StorageError;
+use crate::storage::SessionIndex;
+
+/// Version of the session index schema written by this server.
+///
+/// - 0: indexes first created by the Rust server before versions were set
+///   (same layout as 1)
+/// - 1: .NET layout; also assumed when `version` is missing. Entries may
+///   name `last_modified_at` `modified_at` and `wal_count` `wal_position`
+/// - 2: canonical entry field names only
+/// - 3: may carry legal holds; servers that predate them must not rewrite
+///   (and so drop) them
+/// - 4: may be a manifest (`sharded`) whose entries are stored one object
+///   per session; servers that predate them would read no sessions
+pub const SESSION_INDEX_VERSION: u32 = 4;
+
+/// Upgrades from each older version to the next, indexed by the version
+/// they upgrade from.
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+const MIGRATIONS: [Migration; SESSION_INDEX_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+/// Version assumed for indexes without a `version` field.
+const UNVERSIONED: u32 = 1;
+
+impl SessionIndex {
+    /// Parse a stored index, upgrading older schema versions.
+    ///
+    /// Indexes from a newer schema are refused: saving them back would drop
+    /// the fields this server doesn't know about.
+    pub fn from_json(data: &[u8]) -> Result<Self, StorageError> {
+        let mut value: Value = serde_json::from_slice(data)
+            .map_err(|e| StorageError::Serialization(format!("Failed to parse index: {}", e)))?;
+        migrate_index_value(&mut value).map_err(|e| {
+            StorageError::Serialization(format!("Failed to migrate index: {}", e))
+        })?;
+        serde_json::from_value(value)
+            .map_err(|e| StorageError::Serialization(format!("Failed to parse index: {}", e)))
+    }
+}
+
+/// Bring a parsed index up to [`SESSION_INDEX_VERSION`]. Returns whether it
+/// changed.
+pub fn migrate_index_value(value: &mut Value) -> Result<bool, String> {
+    let Value::Object(map) = value else {
+        return Err("index must be a JSON object".to_string());
+    };
+    let version = match map.get("version") {
+        None => UNVERSIONED,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("invalid version {}", v))?,
+    };
+    if version > SESSION_INDEX_VERSION {
+        return Err(format!(
+            "version {} is newer than the supported version {}",
+            version, SESSION_INDEX_VERSION
+        ));
+    }
+
+    for from in version..SESSION_INDEX_VERSION {
+        MIGRATIONS[from as usize](map)
+            .map_err(|e| format!("migrating from version {}: {}", from, e))?;
+    }
+    map.insert("version".to_string(), Value::from(SESSION_INDEX_VERSION));
+    Ok(version < SESSION_INDEX_VERSION)
+}
+
+fn migrate_v0_to_v1(_: &mut Map<String, Value>) -> Result<(), String> {
+    Ok(())
+}
+
+/// v1 → v2: rename the .NET entry field names.
+fn migrate_v1_to_v2(map: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(sessions) = map.get_mut("sessions") else {
+        return Ok(());
+    };
+    let Value::Array(sessions) = sessions else {
+        return Err("sessions must be an array".to_string());
+    };
+    for session in sessions {
+        let Value::Object(entry) = session else {
+            return Err("session entries must be objects".to_string());
+        };
+        for (old, new) in [("modified_at", "last_modified_at"), ("wal_position", "wal_count")] {
+            if let Some(value) = entry.remove(old) {
+                entry.entry(new).or_insert(value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v2_to_v3(_: &mut Map<String, Value>) -> Result<(), String> {
+    Ok(())
+}
+
+fn migrate_v3_to_v4(_: &mut Map<String, Value>) -> Result<(), String> {
+    Ok(())
+}
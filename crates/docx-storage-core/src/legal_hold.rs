@@ -0,0 +1,104 @@
+//! Legal holds (litigation hold).
+//!
+//! A hold on a tenant, or on one of its sessions, makes destructive operations
+//! on the held data fail with [`StorageError::LegalHold`] until an administrator
+//! lifts it: deleting sessions and library items, truncating WALs, removing
+//! sessions from the index and erasing the tenant. Holds are kept in the
+//! tenant's session index, so every replica sees them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::SessionIndex;
+use crate::{StorageBackend, StorageError};
+
+/// A hold placed on a tenant or a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegalHold {
+    /// Why the data is held (e.g. the matter or case reference)
+    pub reason: String,
+    /// When the hold was placed
+    pub placed_at: DateTime<Utc>,
+}
+
+impl LegalHold {
+    pub fn new(reason: impl Into<String>, placed_at: DateTime<Utc>) -> Self {
+        Self {
+            reason: reason.into(),
+            placed_at,
+        }
+    }
+}
+
+impl SessionIndex {
+    /// The hold protecting `session_id`: the tenant's, else the session's own.
+    /// With `None`, only the tenant's hold.
+    pub fn legal_hold(&self, session_id: Option<&str>) -> Option<&LegalHold> {
+        self.legal_hold.as_ref().or_else(|| {
+            session_id
+                .and_then(|id| self.get(id))
+                .and_then(|entry| entry.legal_hold.as_ref())
+        })
+    }
+
+    /// Fail if `session_id` (or, with `None`, the tenant) is under legal hold.
+    pub fn ensure_not_held(&self, session_id: Option<&str>) -> Result<(), StorageError> {
+        if let Some(hold) = self.legal_hold.as_ref() {
+            return Err(held("tenant", hold));
+        }
+        let entry = session_id.and_then(|id| self.get(id));
+        match entry.and_then(|e| e.legal_hold.as_ref().map(|hold| (e, hold))) {
+            Some((entry, hold)) => Err(held(&format!("session {}", entry.id), hold)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fail if the tenant or any of its sessions is under legal hold.
+    pub fn ensure_nothing_held(&self) -> Result<(), StorageError> {
+        self.ensure_not_held(None)?;
+        match self.sessions.iter().find(|s| s.legal_hold.is_some()) {
+            Some(entry) => self.ensure_not_held(Some(&entry.id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Place (`Some`) or lift (`None`) the hold on `session_id`, or on the
+    /// tenant with `None`. Returns false if the session doesn't exist.
+    pub fn set_legal_hold(&mut self, session_id: Option<&str>, hold: Option<LegalHold>) -> bool {
+        match session_id {
+            None => {
+                self.legal_hold = hold;
+                true
+            }
+            Some(id) => match self.get_mut(id) {
+                Some(entry) => {
+                    entry.legal_hold = hold;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+fn held(what: &str, hold: &LegalHold) -> StorageError {
+    StorageError::LegalHold(format!(
+        "{} is under legal hold since {} ({})",
+        what,
+        hold.placed_at.to_rfc3339(),
+        hold.reason
+    ))
+}
+
+/// Fail if `session_id` (or, with `None`, the tenant) is under legal hold in
+/// the stored index.
+pub async fn ensure_not_held(
+    storage: &dyn StorageBackend,
+    tenant_id: &str,
+    session_id: Option<&str>,
+) -> Result<(), StorageError> {
+    match storage.load_index(tenant_id).await? {
+        Some(index) => index.ensure_not_held(session_id),
+        None => Ok(()),
+    }
+}
@@ -1,24 +1,203 @@
 //! Core traits and types for docx-mcp storage backends.
 //!
 //! This crate defines the abstractions shared between local and cloud storage implementations:
-//! - `StorageBackend`: Session, index, WAL, and checkpoint operations
+//! - `StorageBackend`: Session, index, WAL, checkpoint, and library (templates, snippets) operations
 //! - `SyncBackend`: Auto-save and source synchronization
+//! - `RetryingSyncBackend`: Durable retry queue for syncs to unreachable sources
 //! - `WatchBackend`: External change detection
 //! - `BrowsableBackend`: Connection browsing and file listing
+//! - `DurableChangeQueue`: Coalesced, acknowledged external change delivery
+//! - `ActivityDigests`: External changes and syncs collected per tenant, for digests sent
+//!   to document owners
 //! - `LockManager`: Distributed locking for atomic operations
+//! - `OperationRegistry`: Progress and cancellation of long-running operations
+//! - `CheckpointPolicy`: Server-side rules telling clients when a checkpoint is due
+//! - `CircuitBreaker`: Fail fast while a backend dependency (R2, D1, Google APIs) is down
+//! - `DeadlineLayer` / `with_deadline`: Caller deadlines that retry loops give up on
+//! - `TenantLimitLayer`: Per-tenant caps on concurrent requests, so one tenant's burst
+//!   can't starve the others
+//! - `load_config` / `Reloadable`: Layered configuration (flags, env, TOML file) and live
+//!   reload of tunables
+//! - `init_tracing` / `CorrelationLayer`: Shared log setup (text or JSON) and per-request
+//!   spans carrying request, tenant and session IDs
+//! - `WalOffsetIndex`: Sparse position → byte-offset index for paging through a WAL
+//! - `WalRecord`: Versioned schema of WAL entry payloads, validated on append
+//! - `SessionIndex::from_json`: Versioned session index parsing with schema migrations
+//! - `StorageBackendRegistry`: Runtime backend construction and per-tenant routing
+//! - `erase_tenant` / `ErasureSigner`: Two-step, signed erasure of all of a tenant's data
+//! - `prove_history` / `HistorySigner`: Append-only hash chain over a session's WAL and
+//!   checkpoints, with signed proofs its history wasn't rewritten
+//! - `AdminLogEntry` / `record_admin_action`: Per-tenant append-only log of administrative
+//!   actions (deletes, truncations, retention runs, migrations, key rotations)
+//! - `LegalHold` / `ensure_not_held`: Litigation holds blocking destructive operations
+//! - `SessionIndex::expired`: Ephemeral sessions purged once their TTL runs out
+//! - `SessionIndex::page` / `SessionPageQuery`: Filtered pages of a tenant's sessions, for
+//!   tenants too large to list at once
+//! - `scan_sessions` / `SessionIndex::reconcile`: Index rebuild from the stored sessions
+//! - `StorageReport`: A tenant's usage by area and the orphaned and temporary files left
+//!   behind by crashes
+//! - `load_session_with_history`: A session's checkpoint and the WAL entries to replay on it,
+//!   read in one call
+//! - `export_corpus`: Paced, resumable export of every session's text and metadata, for
+//!   eDiscovery and compliance search
+//! - `SnapshotRegistry`: Read-only session snapshots pinned by ID, for exports that must not
+//!   see concurrent edits
+//! - `session_health`: WAL, checkpoint, sync, hold and consistency state of a session in one
+//!   call, for dashboards
+//! - `create_sandbox` / `discard_sandbox`: Staging copies of a tenant's sessions to
+//!   experiment on, promoted back with conflict checks
+//! - `UploadSpool`: Spooled, CRC-checked SaveSession chunks that interrupted uploads resume from
+//! - `store_media` / `restore_media`: Content-addressed images shared by a tenant's sessions,
+//!   stored once instead of in every package
+//! - `SourceMetadataCache`: Shared metadata cache for polling watch backends
+//! - `validate_tenant_id` / `validate_session_id`: ID checks before IDs become paths or keys
+//! - `Capabilities`: Backend, features, limits and schema version reported by GetCapabilities
+//! - `SimulatedWatchBackend` (feature `simulation`): Scripted external changes on a simulated
+//!   clock, for testing the layers above watch backends
 
+mod activity_digest;
+mod admin_log;
 mod browse;
+mod capabilities;
+mod change_queue;
+mod checkpoint_policy;
+mod circuit_breaker;
+mod config_file;
+mod corpus;
+mod deadline;
+mod ephemeral;
+mod erasure;
 mod error;
+mod history_chain;
+mod index_rebuild;
+mod index_schema;
+mod legal_hold;
+mod library;
 mod lock;
+mod logging;
+mod media;
+mod metadata_cache;
+mod operation;
+mod registry;
+mod sandbox;
+mod session_health;
+mod session_history;
+mod session_page;
+#[cfg(any(test, feature = "simulation"))]
+mod simulation;
+mod snapshot_registry;
 mod storage;
+mod storage_report;
 mod sync;
+mod sync_queue;
+mod tenant_limit;
+mod upload_spool;
+mod validation;
+mod wal;
+mod wal_schema;
 mod watch;
 
-pub use browse::{BrowsableBackend, ConnectionInfo, FileEntry, FileListResult};
-pub use error::StorageError;
+pub use activity_digest::{Activity, ActivityDigests, SessionActivity, TenantDigest};
+pub use admin_log::{
+    admin_log_jsonl, admin_log_page, parse_admin_log, record_admin_action, AdminAction,
+    AdminLogEntry, DEFAULT_ADMIN_LOG_LIMIT, SYSTEM_ACTOR, UNKNOWN_ACTOR,
+};
+pub use browse::{
+    AggregateBrowsableBackend, BrowsableBackend, ConnectionInfo, FileEntry, FileListResult,
+    FileSearchQuery, DOCX_MIME_TYPE,
+};
+pub use capabilities::{feature, Capabilities, MAX_GRPC_MESSAGE_BYTES, PROTO_SCHEMA_VERSION};
+pub use change_queue::{ChangeQueue, ChangeQueueStore, DurableChangeQueue, QueuedChange};
+pub use checkpoint_policy::{CheckpointPolicy, CheckpointReason, DEFAULT_CHECKPOINT_OPS};
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerStats, CircuitOpen, CircuitState, DEFAULT_FAILURE_THRESHOLD,
+    DEFAULT_OPEN_DURATION,
+};
+pub use config_file::{
+    load_config, load_config_from, parse_config_file, ConfigSource, Reloadable, CONFIG_FILE_ENV,
+};
+pub use corpus::{
+    corpus_record, export_corpus, extract_text, CorpusRecord, CORPUS_FORMAT_JSONL,
+    DEFAULT_CORPUS_SESSIONS_PER_SEC,
+};
+pub use deadline::{
+    parse_grpc_timeout, sleep_before_retry, time_remaining, with_deadline, DeadlineLayer,
+    DeadlineService,
+};
+pub use erasure::{
+    erase_tenant, ErasureReport, ErasureSigner, ErasureStep, TenantInventory,
+    CONFIRMATION_TTL_SECS, WEBSITE_TENANT_DATA,
+};
+pub use error::{
+    parse_retry_after, StorageError, ERROR_KIND_METADATA, RETRY_AFTER_METADATA,
+    RETRY_PUSHBACK_METADATA,
+};
+pub use history_chain::{
+    chain_events, digest_chunks, document_digest, genesis_hash, history_jsonl, parse_history, prove_history,
+    wal_line_digest, ChunkDigest, HistoryEvent, HistoryEventKind, HistoryLink, HistoryProof,
+    HistorySigner,
+};
+pub use index_rebuild::{scan_sessions, IndexRebuildReport, ScannedSession};
+pub use index_schema::{migrate_index_value, SESSION_INDEX_VERSION};
+pub use legal_hold::{ensure_not_held, LegalHold};
+pub use library::{LibraryItemInfo, LibraryKind};
 pub use lock::{LockAcquireResult, LockManager};
+pub use logging::{
+    current_rpc, init_tracing, new_request_id, request_span, CorrelationLayer, CorrelationService,
+    LogFormat, REQUEST_ID_HEADER, SESSION_ID_HEADER, TENANT_ID_HEADER,
+};
+pub use media::{
+    buffer_chunks, delete_unreferenced_media, extract_media, inline_media, media_hash,
+    media_references, restore_media, store_media, BufferedChunks, ExtractedMedia, MediaBlob, MEDIA_MAX_BUFFERED_BYTES,
+    MEDIA_MIN_BYTES, MEDIA_REFRESH_AGE, MEDIA_SWEEP_AGE,
+};
+pub use metadata_cache::SourceMetadataCache;
+pub use operation::{
+    Operation, OperationRegistry, OperationState, MAX_OPERATION_RESULT_BYTES,
+};
+pub use registry::{
+    BackendOptions, StorageBackendFactory, StorageBackendRegistry, TenantRoutedStorage,
+};
+pub use sandbox::{
+    copy_session_data, create_sandbox, discard_sandbox, is_sandbox, owning_tenant,
+    sandbox_tenant_id, SandboxOrigin, SessionVersion, SANDBOX_SUFFIX,
+};
+pub use session_health::{
+    session_health, SessionHealth, SessionSyncHealth, HEALTH_WAL_SCAN_LIMIT,
+};
+pub use session_history::{load_session_with_history, SessionWithHistory};
+pub use session_page::{
+    SessionPage, SessionPageQuery, DEFAULT_SESSION_PAGE_SIZE, MAX_SESSION_PAGE_SIZE,
+};
+#[cfg(any(test, feature = "simulation"))]
+pub use simulation::{SimulatedChange, SimulatedWatchBackend};
+pub use snapshot_registry::{
+    PinnedSnapshot, SnapshotRegistry, DEFAULT_SNAPSHOT_TTL, MAX_SNAPSHOTS_PER_TENANT,
+};
 pub use storage::{
-    CheckpointInfo, SessionIndex, SessionIndexEntry, SessionInfo, StorageBackend, WalEntry,
+    collect_chunks, CheckpointInfo, ChunkStream, SessionIndex, SessionIndexEntry, SessionInfo,
+    StorageBackend, WalEntry,
+};
+pub use storage_report::{StorageReport, StorageUsage, StoredFile, STALE_FILE_AGE};
+pub use sync::{
+    RecentFile, SourceDescriptor, SourceType, SyncBackend, SyncStatus, MAX_RECENT_FILES,
+};
+pub use sync_queue::{
+    FileSyncQueueStore, PendingSync, RetryingSyncBackend, SyncQueueStore, SyncRetryPolicy,
+};
+pub use tenant_limit::{
+    Overload, TenantLimitLayer, TenantLimitService, TenantLimitStats, TenantLimiter, TenantLimits,
+    TenantPermit, OVERLOAD_RETRY_AFTER,
+};
+pub use upload_spool::{UploadProgress, UploadSpool};
+pub use validation::{
+    ensure_within, tenant_dir, validate_alias, validate_session_id, validate_tenant_id, MAX_ID_LEN,
+};
+pub use wal::{
+    check_wal_positions, parse_wal_entries, tail_page, wal_jsonl, WalOffsetIndex, WAL_INDEX_STRIDE,
+};
+pub use wal_schema::{
+    migrate_wal_value, prepare_wal_entries, WalEntryType, WalRecord, WalSyncMeta,
+    WAL_SCHEMA_VERSION,
 };
-pub use sync::{SourceDescriptor, SourceType, SyncBackend, SyncStatus};
 pub use watch::{ExternalChangeEvent, ExternalChangeType, SourceMetadata, WatchBackend};
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::StorageError;
+
+/// Kinds of reusable items kept in a tenant's library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryKind {
+    /// Whole documents that new sessions start from
+    Template,
+    /// Ranges of content inserted into other documents
+    Snippet,
+    /// Terminology rules (JSON) that documents are checked against
+    Glossary,
+    /// Tenant profile (JSON): company details and defaults resolved as
+    /// `{{company.name}}`-style template variables
+    Profile,
+    /// Images shared by the tenant's sessions, named by content hash (see
+    /// the `media` module); not exposed to clients
+    Media,
+}
+
+impl LibraryKind {
+    /// Directory (or key prefix) holding this kind under a tenant.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            LibraryKind::Template => "templates",
+            LibraryKind::Snippet => "snippets",
+            LibraryKind::Glossary => "glossaries",
+            LibraryKind::Profile => "profiles",
+            LibraryKind::Media => "media",
+        }
+    }
+
+    /// File extension of stored items, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            LibraryKind::Template | LibraryKind::Snippet => "docx",
+            LibraryKind::Glossary | LibraryKind::Profile => "json",
+            LibraryKind::Media => "bin",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LibraryKind::Template => "Template",
+            LibraryKind::Snippet => "Snippet",
+            LibraryKind::Glossary => "Glossary",
+            LibraryKind::Profile => "Profile",
+            LibraryKind::Media => "Media",
+        }
+    }
+
+    /// Validate an item name; same rules as session IDs.
+    pub fn validate_name(self, name: &str) -> Result<(), StorageError> {
+        crate::validation::validate_name(&format!("{} name", self.label()), name)
+    }
+}
+
+/// Information about an item in a tenant's library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryItemInfo {
+    pub name: String,
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+    pub size_bytes: u64,
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Instrument, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Correlation ID of a request, set by the proxy (or the first service that
+/// sees the request) and passed along on every hop.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Tenant the request is made for.
+pub const TENANT_ID_HEADER: &str = "x-tenant-id";
+/// Document session the request is about, when there is one.
+pub const SESSION_ID_HEADER: &str = "x-session-id";
+
+/// Log output format of a service (`LOG_FORMAT`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines, span fields in front of the message
+    #[default]
+    Text,
+    /// One JSON object per line, span fields flattened into it
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("unknown log format '{}' (expected text or json)", s)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// Install the global subscriber shared by every service: `RUST_LOG`
+/// filtering (default `info`) and the given format.
+pub fn init_tracing(format: LogFormat) {
+    let builder = tracing_subscriber::fmt().with_env_filter(
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    );
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.fmt_fields(JsonFields).event_format(JsonFormat).init(),
+    }
+}
+
+/// A new request ID, for requests that arrive without one.
+pub fn new_request_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+/// Collects fields into a JSON object.
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0
+            .insert(field.name().to_string(), Value::from(value.to_string()));
+    }
+}
+
+/// Stores span fields as a JSON object, so [`JsonFormat`] can merge them
+/// into each event.
+struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(&self, mut writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        write!(writer, "{}", Value::Object(visitor.0))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor(serde_json::from_str(&current.fields).unwrap_or_default());
+        fields.record(&mut visitor);
+        current.fields = Value::Object(visitor.0).to_string();
+        Ok(())
+    }
+}
+
+/// One JSON object per event: timestamp, level, target, the names of the
+/// enclosing spans, their fields (inner spans win) and the event's fields.
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::from(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        );
+        line.insert("level".to_string(), Value::from(metadata.level().as_str()));
+        line.insert("target".to_string(), Value::from(metadata.target()));
+
+        if let Some(scope) = ctx.event_scope() {
+            let mut spans = Vec::new();
+            for span in scope.from_root() {
+                spans.push(Value::from(span.name()));
+                if let Some(fields) = span.extensions().get::<FormattedFields<N>>() {
+                    if let Ok(Value::Object(fields)) = serde_json::from_str(&fields.fields) {
+                        line.extend(fields);
+                    }
+                }
+            }
+            line.insert("spans".to_string(), Value::Array(spans));
+        }
+
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        line.extend(visitor.0);
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+tokio::task_local! {
+    static RPC_METHOD: String;
+}
+
+/// Name of the gRPC method being handled (e.g. `AppendWal`), when running
+/// under [`CorrelationLayer`].
+pub fn current_rpc() -> Option<String> {
+    RPC_METHOD.try_with(String::clone).ok()
+}
+
+/// Tower layer running each gRPC request in a `grpc_request` span carrying
+/// its method, `request_id`, `tenant_id` and `session_id`, read from the
+/// correlation headers. Requests without a request ID get a new one. The
+/// method name is also in scope for [`current_rpc`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CorrelationLayer;
+
+impl<S> tower::Layer<S> for CorrelationLayer {
+    type Service = CorrelationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CorrelationService { inner }
+    }
+}
+
+/// Service produced by [`CorrelationLayer`].
+#[derive(Debug, Clone)]
+pub struct CorrelationService<S> {
+    inner: S,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for CorrelationService<S>
+where
+    S: tower::Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let path = request.uri().path();
+        let method = path.rsplit('/').next().unwrap_or(path).to_string();
+        let span = request_span(path, request.headers());
+        let future = {
+            let _entered = span.enter();
+            self.inner.call(request)
+        };
+        Box::pin(RPC_METHOD.scope(method, future.instrument(span)))
+    }
+}
+
+/// The `grpc_request` span of a request; see [`CorrelationLayer`].
+pub fn request_span(method: &str, headers: &http::HeaderMap) -> tracing::Span {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    };
+    let request_id = header(REQUEST_ID_HEADER)
+        .map(str::to_string)
+        .unwrap_or_else(new_request_id);
+
+    tracing::info_span!(
+        "grpc_request",
+        method,
+        request_id = %request_id,
+        tenant_id = header(TENANT_ID_HEADER),
+        session_id = header(SESSION_ID_HEADER),
+    )
+}
@@ -0,0 +1,395 @@
+//! Content-addressed media shared by a tenant's sessions.
+//!
+//! Generated documents tend to embed the same logos and screenshots over and
+//! over. Backends with media deduplication enabled store each image once per
+//! tenant, as a [`LibraryKind::Media`] item named by the SHA-256 of its
+//! content, and save packages with every large `…/media/…` part replaced by a
+//! short pointer to it ([`store_media`]). Loading puts the images back
+//! ([`restore_media`]), so clients always get complete packages; sessions,
+//! checkpoints and tenant snapshots only carry the pointers.
+//!
+//! Blobs are shared, so deleting a session leaves its images behind;
+//! [`delete_unreferenced_media`] deletes the ones nothing references any more. A blob is
+//! only swept once it was left unreferenced for [`MEDIA_SWEEP_AGE`], and saves
+//! write again the blobs older than [`MEDIA_REFRESH_AGE`] that they reuse, so
+//! a save racing a sweep never ends up pointing at a deleted blob.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+
+use chrono::Utc;
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::StorageError;
+use crate::library::LibraryKind;
+use crate::storage::{ChunkStream, StorageBackend};
+
+/// Media parts smaller than this stay in the package.
+pub const MEDIA_MIN_BYTES: u64 = 4 * 1024;
+
+/// Streamed saves larger than this are stored as they come, without
+/// deduplication, so they keep being written with bounded memory.
+pub const MEDIA_MAX_BUFFERED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Unreferenced blobs older than this are deleted by [`delete_unreferenced_media`].
+pub const MEDIA_SWEEP_AGE: chrono::Duration = chrono::Duration::hours(24);
+
+/// Reused blobs older than this are written again by [`store_media`], so
+/// they can't be swept while the package referencing them is being saved.
+pub const MEDIA_REFRESH_AGE: chrono::Duration = chrono::Duration::hours(12);
+
+/// Content of a part moved to the media store, followed by the hex SHA-256.
+const POINTER_PREFIX: &[u8] = b"docx-mcp-media:sha256:";
+
+/// Length of a pointer part.
+const POINTER_LEN: usize = POINTER_PREFIX.len() + 64;
+
+/// A media part taken out of a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaBlob {
+    /// Hex SHA-256 of `data`
+    pub hash: String,
+    pub data: Vec<u8>,
+}
+
+/// Hex SHA-256 naming a blob.
+pub fn media_hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn is_media_part(name: &str) -> bool {
+    name.contains("/media/") && !name.ends_with('/')
+}
+
+/// Parts we can read and write again (packages only use these two).
+fn supported(method: CompressionMethod) -> bool {
+    matches!(method, CompressionMethod::Stored | CompressionMethod::Deflated)
+}
+
+fn open(package: &[u8]) -> Option<ZipArchive<Cursor<&[u8]>>> {
+    ZipArchive::new(Cursor::new(package)).ok()
+}
+
+fn zip_error(context: &str, e: impl std::fmt::Display) -> StorageError {
+    StorageError::Serialization(format!("{}: {}", context, e))
+}
+
+/// The blob hash a part points to, if it is a pointer.
+fn pointer_hash(content: &[u8]) -> Option<String> {
+    let hash = content.strip_prefix(POINTER_PREFIX)?;
+    (hash.len() == 64 && hash.iter().all(u8::is_ascii_hexdigit))
+        .then(|| String::from_utf8_lossy(hash).into_owned())
+}
+
+/// Hashes of the blobs a package points to, without duplicates. Empty for
+/// packages holding all their media, and for data that isn't a ZIP package.
+pub fn media_references(package: &[u8]) -> Vec<String> {
+    let Some(mut archive) = open(package) else {
+        return vec![];
+    };
+    let mut hashes = Vec::new();
+    for i in 0..archive.len() {
+        let Ok(mut part) = archive.by_index(i) else {
+            continue;
+        };
+        if part.size() != POINTER_LEN as u64 || !is_media_part(part.name()) {
+            continue;
+        }
+        let mut content = Vec::with_capacity(POINTER_LEN);
+        if part.read_to_end(&mut content).is_ok() {
+            if let Some(hash) = pointer_hash(&content) {
+                if !hashes.contains(&hash) {
+                    hashes.push(hash);
+                }
+            }
+        }
+    }
+    hashes
+}
+
+/// A package with its media replaced by pointers, and the blobs taken out.
+pub type ExtractedMedia = (Vec<u8>, Vec<MediaBlob>);
+
+/// Replace the media parts of at least `min_bytes` with pointers. Returns
+/// the new package and the blobs taken out (once each), or `None` when there
+/// is nothing to take out or `package` isn't a ZIP package.
+pub fn extract_media(
+    package: &[u8],
+    min_bytes: u64,
+) -> Result<Option<ExtractedMedia>, StorageError> {
+    let Some(mut archive) = open(package) else {
+        return Ok(None);
+    };
+    let movable = |name: &str, size: u64, method: CompressionMethod| {
+        is_media_part(name) && size >= min_bytes && supported(method)
+    };
+    let mut any = false;
+    for i in 0..archive.len() {
+        let part = archive.by_index_raw(i).map_err(|e| zip_error("Invalid package", e))?;
+        any |= movable(part.name(), part.size(), part.compression());
+    }
+    if !any {
+        return Ok(None);
+    }
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(package.len() / 2)));
+    let mut blobs: Vec<MediaBlob> = Vec::new();
+    for i in 0..archive.len() {
+        let part = archive.by_index_raw(i).map_err(|e| zip_error("Invalid package", e))?;
+        if !movable(part.name(), part.size(), part.compression()) {
+            writer
+                .raw_copy_file(part)
+                .map_err(|e| zip_error("Failed to copy package part", e))?;
+            continue;
+        }
+        let (name, method) = (part.name().to_string(), part.compression());
+        drop(part);
+
+        let mut data = Vec::new();
+        archive
+            .by_index(i)
+            .and_then(|mut part| Ok(part.read_to_end(&mut data)?))
+            .map_err(|e| zip_error(&format!("Failed to read {}", name), e))?;
+        let hash = media_hash(&data);
+        writer
+            .start_file(name, SimpleFileOptions::default().compression_method(method))
+            .and_then(|_| {
+                writer.write_all(POINTER_PREFIX)?;
+                Ok(writer.write_all(hash.as_bytes())?)
+            })
+            .map_err(|e| zip_error("Failed to write media pointer", e))?;
+        if !blobs.iter().any(|b| b.hash == hash) {
+            blobs.push(MediaBlob { hash, data });
+        }
+    }
+    let stripped = writer
+        .finish()
+        .map_err(|e| zip_error("Failed to write package", e))?
+        .into_inner();
+    Ok(Some((stripped, blobs)))
+}
+
+/// Replace the pointers of a package with the blobs they point to.
+pub fn inline_media(
+    package: &[u8],
+    blobs: &HashMap<String, Vec<u8>>,
+) -> Result<Vec<u8>, StorageError> {
+    let mut archive = open(package)
+        .ok_or_else(|| StorageError::Serialization("Invalid package".to_string()))?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(
+        package.len() + blobs.values().map(Vec::len).sum::<usize>(),
+    )));
+    for i in 0..archive.len() {
+        let mut part = archive.by_index(i).map_err(|e| zip_error("Invalid package", e))?;
+        let hash = if part.size() == POINTER_LEN as u64 && is_media_part(part.name()) {
+            let mut content = Vec::with_capacity(POINTER_LEN);
+            part.read_to_end(&mut content)
+                .map_err(|e| zip_error("Invalid package", e))?;
+            pointer_hash(&content)
+        } else {
+            None
+        };
+        let Some(hash) = hash else {
+            drop(part);
+            let part = archive.by_index_raw(i).map_err(|e| zip_error("Invalid package", e))?;
+            writer
+                .raw_copy_file(part)
+                .map_err(|e| zip_error("Failed to copy package part", e))?;
+            continue;
+        };
+
+        let data = blobs.get(&hash).ok_or_else(|| {
+            StorageError::NotFound(format!("Media blob {} of {}", hash, part.name()))
+        })?;
+        let options = SimpleFileOptions::default().compression_method(part.compression());
+        writer
+            .start_file(part.name(), options)
+            .and_then(|_| Ok(writer.write_all(data)?))
+            .map_err(|e| zip_error("Failed to write media part", e))?;
+    }
+    Ok(writer
+        .finish()
+        .map_err(|e| zip_error("Failed to write package", e))?
+        .into_inner())
+}
+
+/// Move the media of a package about to be saved for `tenant_id` to the
+/// tenant's media store. Returns the package to save instead, or `None` to
+/// save it as it is (nothing to move, or not a valid package).
+pub async fn store_media<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    package: &[u8],
+) -> Result<Option<Vec<u8>>, StorageError> {
+    let (stripped, blobs) = match extract_media(package, MEDIA_MIN_BYTES) {
+        Ok(Some(extracted)) => extracted,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            warn!("Saving package of tenant '{}' with its media: {}", tenant_id, e);
+            return Ok(None);
+        }
+    };
+
+    let refresh_before = Utc::now() - MEDIA_REFRESH_AGE;
+    let fresh: HashSet<String> = storage
+        .list_library_items(tenant_id, LibraryKind::Media)
+        .await?
+        .into_iter()
+        .filter(|item| item.modified_at > refresh_before)
+        .map(|item| item.name)
+        .collect();
+    for blob in blobs.iter().filter(|b| !fresh.contains(&b.hash)) {
+        storage
+            .save_library_item(tenant_id, LibraryKind::Media, &blob.hash, &blob.data)
+            .await?;
+    }
+    debug!(
+        "Moved {} media blob(s) out of a package ({} -> {} bytes)",
+        blobs.len(),
+        package.len(),
+        stripped.len()
+    );
+    Ok(Some(stripped))
+}
+
+/// Put back the media of a package loaded for `tenant_id`. Packages without
+/// pointers are returned as they are.
+pub async fn restore_media<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    package: Vec<u8>,
+) -> Result<Vec<u8>, StorageError> {
+    let hashes = media_references(&package);
+    if hashes.is_empty() {
+        return Ok(package);
+    }
+    let mut blobs = HashMap::new();
+    for hash in hashes {
+        let data = storage
+            .load_library_item(tenant_id, LibraryKind::Media, &hash)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!(
+                    "Media blob {} of tenant '{}' is missing",
+                    hash, tenant_id
+                ))
+            })?;
+        blobs.insert(hash, data);
+    }
+    inline_media(&package, &blobs)
+}
+
+/// Delete the media blobs of `tenant_id` that are not in `referenced` and
+/// are older than [`MEDIA_SWEEP_AGE`]. `referenced` must cover every stored
+/// package of the tenant (see [`media_references`]). Returns the number of
+/// blobs deleted.
+pub async fn delete_unreferenced_media<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    referenced: &HashSet<String>,
+) -> Result<u64, StorageError> {
+    let sweep_before = Utc::now() - MEDIA_SWEEP_AGE;
+    let mut deleted = 0;
+    for item in storage.list_library_items(tenant_id, LibraryKind::Media).await? {
+        if referenced.contains(&item.name) || item.modified_at > sweep_before {
+            continue;
+        }
+        if storage
+            .delete_library_item(tenant_id, LibraryKind::Media, &item.name)
+            .await?
+        {
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+/// A streamed save, read into memory unless it turned out too large.
+pub enum BufferedChunks<'a> {
+    /// The whole stream
+    Complete(Vec<u8>),
+    /// The chunks read so far followed by the rest of the stream
+    Partial(ChunkStream<'a>),
+}
+
+/// Read `chunks` into memory, up to `limit` bytes, so the media of packages
+/// saved as streams can be moved to the media store too. A failing stream
+/// fails here, before anything is saved.
+pub async fn buffer_chunks(
+    mut chunks: ChunkStream<'_>,
+    limit: usize,
+) -> Result<BufferedChunks<'_>, StorageError> {
+    let mut read: Vec<Vec<u8>> = Vec::new();
+    let mut len = 0;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        len += chunk.len();
+        read.push(chunk);
+        if len > limit {
+            let read = futures::stream::iter(read.into_iter().map(Ok));
+            return Ok(BufferedChunks::Partial(read.chain(chunks).boxed()));
+        }
+    }
+    Ok(BufferedChunks::Complete(read.concat()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A package with a large image stored twice, a small image and a document part.
+    fn package(image: &[u8]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let deflated = SimpleFileOptions::default();
+        let stored = deflated.compression_method(CompressionMethod::Stored);
+        for (name, data, options) in [
+            ("[Content_Types].xml", &b"<Types/>"[..], deflated),
+            ("word/document.xml", b"<w:document/>", deflated),
+            ("word/media/image1.png", image, stored),
+            ("word/media/image2.png", image, deflated),
+            ("word/media/icon.png", b"tiny", stored),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn parts(package: &[u8]) -> Vec<(String, CompressionMethod, Vec<u8>)> {
+        let mut archive = open(package).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut part = archive.by_index(i).unwrap();
+                let mut data = Vec::new();
+                part.read_to_end(&mut data).unwrap();
+                (part.name().to_string(), part.compression(), data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_and_inline_round_trip() {
+        let image: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let original = package(&image);
+
+        let (stripped, blobs) = extract_media(&original, MEDIA_MIN_BYTES).unwrap().unwrap();
+        assert!(stripped.len() < original.len() / 2);
+        assert_eq!(blobs, vec![MediaBlob { hash: media_hash(&image), data: image.clone() }]);
+        assert_eq!(media_references(&stripped), vec![media_hash(&image)]);
+        assert!(media_references(&original).is_empty());
+        // Nothing left to move
+        assert_eq!(extract_media(&stripped, MEDIA_MIN_BYTES).unwrap(), None);
+        assert_eq!(extract_media(b"not a package", MEDIA_MIN_BYTES).unwrap(), None);
+
+        let blobs = HashMap::from([(media_hash(&image), image)]);
+        assert_eq!(parts(&inline_media(&stripped, &blobs).unwrap()), parts(&original));
+        assert!(matches!(
+            inline_media(&stripped, &HashMap::new()),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::watch::SourceMetadata;
+
+/// Shared cache of source metadata for polling watch backends.
+///
+/// Entries are keyed by the provider's file identity (not the session), so
+/// several sessions watching the same file share one fetch. Each entry keeps
+/// the validator (ETag / revision token) of the last response so the next
+/// fetch can be a conditional request, and a refresh deadline spread by a
+/// random jitter so hundreds of sources don't all hit the API in the same
+/// second.
+pub struct SourceMetadataCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    /// Fraction of the TTL the refresh deadline is randomly moved by (0.0 – 1.0)
+    jitter: f64,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    /// `None` = the source was reported missing
+    metadata: Option<SourceMetadata>,
+    validator: Option<String>,
+    refresh_at: Instant,
+}
+
+impl SourceMetadataCache {
+    /// Create a cache whose refresh deadlines are jittered by ±`jitter` × TTL.
+    pub fn new(jitter: f64) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            jitter: jitter.clamp(0.0, 1.0),
+        }
+    }
+
+    /// The cached value, if the source isn't due for a refresh yet.
+    /// `Some(None)` means the source was cached as missing.
+    pub fn fresh(&self, key: &str) -> Option<Option<SourceMetadata>> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|entry| entry.refresh_at > Instant::now())
+            .map(|entry| entry.metadata.clone())
+    }
+
+    /// The validator to send with the next conditional request, if any.
+    pub fn validator(&self, key: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .and_then(|entry| entry.validator.clone())
+    }
+
+    /// Store a full response, to be refreshed after about `ttl`.
+    pub fn store(
+        &self,
+        key: &str,
+        metadata: Option<SourceMetadata>,
+        validator: Option<String>,
+        ttl: Duration,
+    ) {
+        let entry = CacheEntry {
+            metadata,
+            validator,
+            refresh_at: Instant::now() + self.jittered(ttl),
+        };
+        self.entries.lock().unwrap().insert(key.to_string(), entry);
+    }
+
+    /// Record a "not modified" answer: keep the cached value, reschedule the
+    /// refresh. Returns the cached value, or `None` if the entry is gone (the
+    /// caller must then fetch unconditionally).
+    pub fn revalidate(&self, key: &str, ttl: Duration) -> Option<Option<SourceMetadata>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(key)?;
+        entry.refresh_at = Instant::now() + self.jittered(ttl);
+        Some(entry.metadata.clone())
+    }
+
+    /// Drop a source, e.g. after our own write changed it.
+    pub fn invalidate(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    /// Number of cached sources.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn jittered(&self, ttl: Duration) -> Duration {
+        if self.jitter == 0.0 {
+            return ttl;
+        }
+        let factor = 1.0 + self.jitter * (rand::random::<f64>() * 2.0 - 1.0);
+        ttl.mul_f64(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn metadata(size_bytes: u64) -> SourceMetadata {
+        SourceMetadata {
+            size_bytes,
+            modified_at: 1_700_000_000,
+            etag: None,
+            version_id: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_stored_metadata_is_fresh_until_its_ttl() {
+        let cache = SourceMetadataCache::new(0.0);
+        cache.store("file", Some(metadata(1)), Some("v1".to_string()), TTL);
+
+        assert_eq!(cache.fresh("file").unwrap().unwrap().size_bytes, 1);
+        assert_eq!(cache.validator("file").as_deref(), Some("v1"));
+        assert!(cache.fresh("other").is_none());
+    }
+
+    #[test]
+    fn test_metadata_is_due_for_refresh_at_its_ttl() {
+        let cache = SourceMetadataCache::new(0.0);
+        cache.store(
+            "file",
+            Some(metadata(1)),
+            Some("v1".to_string()),
+            Duration::ZERO,
+        );
+
+        assert!(cache.fresh("file").is_none());
+        // The validator survives, so the refresh can be conditional
+        assert_eq!(cache.validator("file").as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn test_missing_sources_are_cached_as_missing() {
+        let cache = SourceMetadataCache::new(0.0);
+        cache.store("file", None, None, TTL);
+
+        assert_eq!(cache.fresh("file"), Some(None));
+    }
+
+    #[test]
+    fn test_invalidate_after_a_write_forces_an_unconditional_fetch() {
+        let cache = SourceMetadataCache::new(0.0);
+        cache.store("file", Some(metadata(1)), Some("v1".to_string()), TTL);
+
+        cache.invalidate("file");
+
+        assert!(cache.fresh("file").is_none());
+        assert!(cache.validator("file").is_none());
+        assert!(cache.revalidate("file", TTL).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_revalidate_keeps_the_value_and_reschedules_the_refresh() {
+        let cache = SourceMetadataCache::new(0.0);
+        cache.store(
+            "file",
+            Some(metadata(1)),
+            Some("v1".to_string()),
+            Duration::ZERO,
+        );
+
+        assert_eq!(
+            cache.revalidate("file", TTL).unwrap().unwrap().size_bytes,
+            1
+        );
+        assert_eq!(cache.fresh("file").unwrap().unwrap().size_bytes, 1);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_its_fraction_of_the_ttl() {
+        let cache = SourceMetadataCache::new(0.25);
+        for _ in 0..100 {
+            let ttl = cache.jittered(Duration::from_secs(100));
+            assert!(ttl >= Duration::from_secs(75) && ttl <= Duration::from_secs(125));
+        }
+        assert_eq!(SourceMetadataCache::new(5.0).jitter, 1.0);
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::error::StorageError;
+
+/// Results larger than this are rejected: operations are returned whole in
+/// gRPC messages, whose default limit is 4 MiB.
+pub const MAX_OPERATION_RESULT_BYTES: usize = 3 * 1024 * 1024;
+
+/// Lifecycle of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl OperationState {
+    pub fn is_finished(self) -> bool {
+        self != OperationState::Running
+    }
+}
+
+/// A long-running operation and its latest progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    pub id: String,
+    pub kind: String,
+    pub description: String,
+    pub session_id: Option<String>,
+    pub state: OperationState,
+    /// Fraction done, 0.0 – 1.0
+    pub progress: f64,
+    pub message: Option<String>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Unix timestamps
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// In-memory registry of long-running operations, per tenant.
+///
+/// The worker running an operation registers it, reports progress and
+/// records the outcome; clients read, cancel or watch it by ID. Cancelling
+/// finishes the operation right away: the worker learns about it from the
+/// state returned by its next progress report (or by watching) and stops.
+/// Finished operations are kept for `retention`, then dropped.
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<(String, String), watch::Sender<Operation>>>,
+    retention: Duration,
+}
+
+impl OperationRegistry {
+    /// Create a registry keeping finished operations for `retention`.
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            operations: Mutex::new(HashMap::new()),
+            retention,
+        }
+    }
+
+    /// Register a new running operation.
+    pub fn start(
+        &self,
+        tenant_id: &str,
+        kind: &str,
+        description: &str,
+        session_id: Option<&str>,
+    ) -> Result<Operation, StorageError> {
+        if kind.is_empty() {
+            return Err(StorageError::InvalidArgument(
+                "operation kind is required".to_string(),
+            ));
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let operation = Operation {
+            id: format!("op_{:016x}", rand::random::<u64>()),
+            kind: kind.to_string(),
+            description: description.to_string(),
+            session_id: session_id.filter(|s| !s.is_empty()).map(str::to_string),
+            state: OperationState::Running,
+            progress: 0.0,
+            message: None,
+            result: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut operations = self.operations.lock().unwrap();
+        self.prune(&mut operations, now);
+        let (tx, _) = watch::channel(operation.clone());
+        operations.insert((tenant_id.to_string(), operation.id.clone()), tx);
+        Ok(operation)
+    }
+
+    /// Update a running operation's progress. Returns the operation, whose
+    /// state tells the worker whether it was cancelled in the meantime.
+    pub fn report_progress(
+        &self,
+        tenant_id: &str,
+        operation_id: &str,
+        progress: f64,
+        message: Option<&str>,
+    ) -> Result<Operation, StorageError> {
+        self.update(tenant_id, operation_id, |op| {
+            op.progress = progress.clamp(0.0, 1.0);
+            if let Some(message) = message.filter(|m| !m.is_empty()) {
+                op.message = Some(message.to_string());
+            }
+        })
+    }
+
+    /// Record the outcome of a running operation.
+    pub fn finish(
+        &self,
+        tenant_id: &str,
+        operation_id: &str,
+        outcome: Result<String, String>,
+    ) -> Result<Operation, StorageError> {
+        if let Ok(result) = &outcome {
+            if result.len() > MAX_OPERATION_RESULT_BYTES {
+                return self.finish(
+                    tenant_id,
+                    operation_id,
+                    Err(format!(
+                        "Result of {} bytes exceeds the {} byte limit",
+                        result.len(),
+                        MAX_OPERATION_RESULT_BYTES
+                    )),
+                );
+            }
+        }
+
+        self.update(tenant_id, operation_id, |op| match outcome {
+            Ok(result) => {
+                op.state = OperationState::Succeeded;
+                op.progress = 1.0;
+                op.result = Some(result);
+            }
+            Err(error) => {
+                op.state = OperationState::Failed;
+                op.error = Some(error);
+            }
+        })
+    }
+
+    /// Cancel a running operation.
+    pub fn cancel(&self, tenant_id: &str, operation_id: &str) -> Result<Operation, StorageError> {
+        self.update(tenant_id, operation_id, |op| {
+            op.state = OperationState::Cancelled;
+        })
+    }
+
+    /// Get an operation by ID.
+    pub fn get(&self, tenant_id: &str, operation_id: &str) -> Result<Operation, StorageError> {
+        self.operations
+            .lock()
+            .unwrap()
+            .get(&(tenant_id.to_string(), operation_id.to_string()))
+            .map(|tx| tx.borrow().clone())
+            .ok_or_else(|| not_found(operation_id))
+    }
+
+    /// List a tenant's operations, newest first.
+    pub fn list(&self, tenant_id: &str, include_finished: bool) -> Vec<Operation> {
+        let mut operations = self.operations.lock().unwrap();
+        self.prune(&mut operations, chrono::Utc::now().timestamp());
+        let mut list: Vec<Operation> = operations
+            .iter()
+            .filter(|((tenant, _), _)| tenant == tenant_id)
+            .map(|(_, tx)| tx.borrow().clone())
+            .filter(|op| include_finished || !op.state.is_finished())
+            .collect();
+        list.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
+
+    /// Subscribe to an operation's changes. The receiver holds the current
+    /// state and is notified on every update.
+    pub fn subscribe(
+        &self,
+        tenant_id: &str,
+        operation_id: &str,
+    ) -> Result<watch::Receiver<Operation>, StorageError> {
+        self.operations
+            .lock()
+            .unwrap()
+            .get(&(tenant_id.to_string(), operation_id.to_string()))
+            .map(|tx| tx.subscribe())
+            .ok_or_else(|| not_found(operation_id))
+    }
+
+    /// Apply `change` to a running operation. Finished operations are
+    /// returned unchanged.
+    fn update(
+        &self,
+        tenant_id: &str,
+        operation_id: &str,
+        change: impl FnOnce(&mut Operation),
+    ) -> Result<Operation, StorageError> {
+        let operations = self.operations.lock().unwrap();
+        let tx = operations
+            .get(&(tenant_id.to_string(), operation_id.to_string()))
+            .ok_or_else(|| not_found(operation_id))?;
+
+        tx.send_if_modified(|op| {
+            if op.state.is_finished() {
+                return false;
+            }
+            change(op);
+            op.updated_at = chrono::Utc::now().timestamp();
+            true
+        });
+        let current = tx.borrow().clone();
+        Ok(current)
+    }
+
+    /// Drop operations that finished more than `retention` ago.
+    fn prune(&self, operations: &mut HashMap<(String, String), watch::Sender<Operation>>, now: i64) {
+        let retention = self.retention.as_secs() as i64;
+        operations.retain(|_, tx| {
+            let op = tx.borrow();
+            !op.state.is_finished() || now - op.updated_at < retention
+        });
+    }
+}
+
+fn not_found(operation_id: &str) -> StorageError {
+    StorageError::NotFound(format!("operation {}", operation_id))
+}
@@ -0,0 +1,709 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::admin_log::AdminLogEntry;
+use crate::capabilities::Capabilities;
+use crate::error::StorageError;
+use crate::history_chain::HistoryLink;
+use crate::library::{LibraryItemInfo, LibraryKind};
+use crate::sandbox::owning_tenant;
+use crate::session_history::SessionWithHistory;
+use crate::session_page::{SessionPage, SessionPageQuery};
+use crate::storage::{
+    CheckpointInfo, ChunkStream, SessionIndex, SessionInfo, StorageBackend, WalEntry,
+};
+use crate::storage_report::StorageReport;
+
+/// Options passed to a backend constructor (e.g. `dir`, `bucket`).
+pub type BackendOptions = HashMap<String, String>;
+
+/// Constructor for a kind of storage backend.
+pub type StorageBackendFactory =
+    Box<dyn Fn(&BackendOptions) -> Result<Arc<dyn StorageBackend>, StorageError> + Send + Sync>;
+
+/// Maps backend kinds (e.g. "local", "r2") to their constructors, so a
+/// server can build its backends from configuration at runtime.
+#[derive(Default)]
+pub struct StorageBackendRegistry {
+    factories: HashMap<String, StorageBackendFactory>,
+}
+
+impl StorageBackendRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a constructor for `kind`, replacing any previous one.
+    pub fn register<F>(&mut self, kind: &str, factory: F)
+    where
+        F: Fn(&BackendOptions) -> Result<Arc<dyn StorageBackend>, StorageError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.factories.insert(kind.to_string(), Box::new(factory));
+    }
+
+    /// Construct a backend of the given kind.
+    pub fn create(
+        &self,
+        kind: &str,
+        options: &BackendOptions,
+    ) -> Result<Arc<dyn StorageBackend>, StorageError> {
+        let factory = self.factories.get(kind).ok_or_else(|| {
+            StorageError::InvalidArgument(format!(
+                "Unknown storage backend '{}' (available: {})",
+                kind,
+                self.kinds().join(", ")
+            ))
+        })?;
+        factory(options)
+    }
+
+    /// Registered backend kinds, sorted.
+    pub fn kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = self.factories.keys().cloned().collect();
+        kinds.sort();
+        kinds
+    }
+}
+
+/// Storage backend that routes each call to a backend chosen by tenant.
+///
+/// Tenants without an explicit route use the default backend. Routes are
+/// fixed at construction: moving a tenant between backends does not move
+/// its existing sessions.
+pub struct TenantRoutedStorage {
+    default: Arc<dyn StorageBackend>,
+    routes: HashMap<String, Arc<dyn StorageBackend>>,
+}
+
+impl TenantRoutedStorage {
+    pub fn new(default: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            default,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Serve `tenant_id` from `backend`.
+    pub fn route(mut self, tenant_id: &str, backend: Arc<dyn StorageBackend>) -> Self {
+        self.routes.insert(tenant_id.to_string(), backend);
+        self
+    }
+
+    /// The backend serving a tenant (and its sandbox).
+    pub fn backend_for(&self, tenant_id: &str) -> &Arc<dyn StorageBackend> {
+        self.routes
+            .get(owning_tenant(tenant_id))
+            .unwrap_or(&self.default)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for TenantRoutedStorage {
+    fn backend_name(&self) -> &'static str {
+        "routed"
+    }
+
+    /// What every routed backend supports, within the smallest limits.
+    fn capabilities(&self) -> Capabilities {
+        let mut caps = self.default.capabilities();
+        for backend in self.routes.values() {
+            let other = backend.capabilities();
+            caps.features.retain(|f| other.supports(f));
+            caps.max_object_bytes = match (caps.max_object_bytes, other.max_object_bytes) {
+                (0, max) | (max, 0) => max,
+                (a, b) => a.min(b),
+            };
+            caps.max_wal_entry_bytes = caps.max_wal_entry_bytes.min(other.max_wal_entry_bytes);
+        }
+        caps
+    }
+
+    async fn load_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        self.backend_for(tenant_id)
+            .load_session(tenant_id, session_id)
+            .await
+    }
+
+    async fn save_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        self.backend_for(tenant_id)
+            .save_session(tenant_id, session_id, data)
+            .await
+    }
+
+    async fn save_session_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id)
+            .save_session_stream(tenant_id, session_id, chunks)
+            .await
+    }
+
+    async fn delete_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<bool, StorageError> {
+        self.backend_for(tenant_id)
+            .delete_session(tenant_id, session_id)
+            .await
+    }
+
+    async fn list_sessions(&self, tenant_id: &str) -> Result<Vec<SessionInfo>, StorageError> {
+        self.backend_for(tenant_id).list_sessions(tenant_id).await
+    }
+
+    async fn session_exists(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<bool, StorageError> {
+        self.backend_for(tenant_id)
+            .session_exists(tenant_id, session_id)
+            .await
+    }
+
+    async fn load_session_snapshot(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: Option<u64>,
+    ) -> Result<Option<SessionWithHistory>, StorageError> {
+        self.backend_for(tenant_id)
+            .load_session_snapshot(tenant_id, session_id, position)
+            .await
+    }
+
+    async fn load_index(&self, tenant_id: &str) -> Result<Option<SessionIndex>, StorageError> {
+        self.backend_for(tenant_id).load_index(tenant_id).await
+    }
+
+    async fn save_index(&self, tenant_id: &str, index: &SessionIndex) -> Result<(), StorageError> {
+        self.backend_for(tenant_id)
+            .save_index(tenant_id, index)
+            .await
+    }
+
+    async fn list_session_page(
+        &self,
+        tenant_id: &str,
+        query: &SessionPageQuery,
+    ) -> Result<SessionPage, StorageError> {
+        self.backend_for(tenant_id)
+            .list_session_page(tenant_id, query)
+            .await
+    }
+
+    async fn append_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        entries: &[WalEntry],
+    ) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id)
+            .append_wal(tenant_id, session_id, entries)
+            .await
+    }
+
+    async fn read_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        from_position: u64,
+        limit: Option<u64>,
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        self.backend_for(tenant_id)
+            .read_wal(tenant_id, session_id, from_position, limit)
+            .await
+    }
+
+    async fn tail_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        before_position: Option<u64>,
+        limit: u64,
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        self.backend_for(tenant_id)
+            .tail_wal(tenant_id, session_id, before_position, limit)
+            .await
+    }
+
+    async fn truncate_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        keep_count: u64,
+    ) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id)
+            .truncate_wal(tenant_id, session_id, keep_count)
+            .await
+    }
+
+    async fn save_checkpoint(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        self.backend_for(tenant_id)
+            .save_checkpoint(tenant_id, session_id, position, data)
+            .await
+    }
+
+    async fn save_checkpoint_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id)
+            .save_checkpoint_stream(tenant_id, session_id, position, chunks)
+            .await
+    }
+
+    async fn load_checkpoint(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+    ) -> Result<Option<(Vec<u8>, u64)>, StorageError> {
+        self.backend_for(tenant_id)
+            .load_checkpoint(tenant_id, session_id, position)
+            .await
+    }
+
+    async fn list_checkpoints(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Vec<CheckpointInfo>, StorageError> {
+        self.backend_for(tenant_id)
+            .list_checkpoints(tenant_id, session_id)
+            .await
+    }
+
+    async fn save_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        self.backend_for(tenant_id)
+            .save_library_item(tenant_id, kind, name, data)
+            .await
+    }
+
+    async fn load_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        self.backend_for(tenant_id)
+            .load_library_item(tenant_id, kind, name)
+            .await
+    }
+
+    async fn list_library_items(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+    ) -> Result<Vec<LibraryItemInfo>, StorageError> {
+        self.backend_for(tenant_id)
+            .list_library_items(tenant_id, kind)
+            .await
+    }
+
+    async fn delete_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<bool, StorageError> {
+        self.backend_for(tenant_id)
+            .delete_library_item(tenant_id, kind, name)
+            .await
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError> {
+        // List each backend once, keeping the tenants routed to it
+        let mut backends = vec![&self.default];
+        for backend in self.routes.values() {
+            if !backends.iter().any(|b| Arc::ptr_eq(b, backend)) {
+                backends.push(backend);
+            }
+        }
+        let mut tenants = BTreeSet::new();
+        for backend in backends {
+            for tenant_id in backend.list_tenants().await? {
+                if Arc::ptr_eq(self.backend_for(&tenant_id), backend) {
+                    tenants.insert(tenant_id);
+                }
+            }
+        }
+        Ok(tenants.into_iter().collect())
+    }
+
+    async fn erase_tenant(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id).erase_tenant(tenant_id).await
+    }
+
+    async fn sweep_media(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id).sweep_media(tenant_id).await
+    }
+
+    async fn read_history(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Vec<HistoryLink>, StorageError> {
+        self.backend_for(tenant_id).read_history(tenant_id, session_id).await
+    }
+
+    async fn storage_report(
+        &self,
+        tenant_id: &str,
+        cleanup: bool,
+    ) -> Result<Option<StorageReport>, StorageError> {
+        self.backend_for(tenant_id).storage_report(tenant_id, cleanup).await
+    }
+
+    async fn append_admin_log(
+        &self,
+        tenant_id: &str,
+        entry: AdminLogEntry,
+    ) -> Result<u64, StorageError> {
+        self.backend_for(tenant_id).append_admin_log(tenant_id, entry).await
+    }
+
+    async fn read_admin_log(
+        &self,
+        tenant_id: &str,
+        from_position: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<AdminLogEntry>, StorageError> {
+        self.backend_for(tenant_id)
+            .read_admin_log(tenant_id, from_position, limit)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::capabilities::feature;
+
+    /// Backend keeping session documents in memory; everything else is empty.
+    struct MemoryBackend {
+        capabilities: Capabilities,
+        sessions: Mutex<BTreeMap<(String, String), Vec<u8>>>,
+    }
+
+    impl MemoryBackend {
+        fn new(capabilities: Capabilities) -> Arc<Self> {
+            Arc::new(Self {
+                capabilities,
+                sessions: Mutex::default(),
+            })
+        }
+
+        fn holds(&self, tenant_id: &str, session_id: &str) -> bool {
+            let key = (tenant_id.to_string(), session_id.to_string());
+            self.sessions.lock().unwrap().contains_key(&key)
+        }
+    }
+
+    #[async_trait]
+    impl StorageBackend for MemoryBackend {
+        fn backend_name(&self) -> &'static str {
+            "memory"
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            self.capabilities.clone()
+        }
+
+        async fn load_session(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<Option<Vec<u8>>, StorageError> {
+            let key = (tenant_id.to_string(), session_id.to_string());
+            Ok(self.sessions.lock().unwrap().get(&key).cloned())
+        }
+
+        async fn save_session(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+            data: &[u8],
+        ) -> Result<(), StorageError> {
+            let key = (tenant_id.to_string(), session_id.to_string());
+            self.sessions.lock().unwrap().insert(key, data.to_vec());
+            Ok(())
+        }
+
+        async fn delete_session(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<bool, StorageError> {
+            let key = (tenant_id.to_string(), session_id.to_string());
+            Ok(self.sessions.lock().unwrap().remove(&key).is_some())
+        }
+
+        async fn list_sessions(&self, _tenant_id: &str) -> Result<Vec<SessionInfo>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn session_exists(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<bool, StorageError> {
+            Ok(self.holds(tenant_id, session_id))
+        }
+
+        async fn load_index(&self, _tenant_id: &str) -> Result<Option<SessionIndex>, StorageError> {
+            Ok(None)
+        }
+
+        async fn save_index(
+            &self,
+            _tenant_id: &str,
+            _index: &SessionIndex,
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn append_wal(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _entries: &[WalEntry],
+        ) -> Result<u64, StorageError> {
+            Ok(0)
+        }
+
+        async fn read_wal(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _from_position: u64,
+            _limit: Option<u64>,
+        ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+            Ok((Vec::new(), false))
+        }
+
+        async fn truncate_wal(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _keep_count: u64,
+        ) -> Result<u64, StorageError> {
+            Ok(0)
+        }
+
+        async fn save_checkpoint(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _position: u64,
+            _data: &[u8],
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn load_checkpoint(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _position: u64,
+        ) -> Result<Option<(Vec<u8>, u64)>, StorageError> {
+            Ok(None)
+        }
+
+        async fn list_checkpoints(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+        ) -> Result<Vec<CheckpointInfo>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn save_library_item(
+            &self,
+            _tenant_id: &str,
+            _kind: LibraryKind,
+            _name: &str,
+            _data: &[u8],
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn load_library_item(
+            &self,
+            _tenant_id: &str,
+            _kind: LibraryKind,
+            _name: &str,
+        ) -> Result<Option<Vec<u8>>, StorageError> {
+            Ok(None)
+        }
+
+        async fn list_library_items(
+            &self,
+            _tenant_id: &str,
+            _kind: LibraryKind,
+        ) -> Result<Vec<LibraryItemInfo>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn delete_library_item(
+            &self,
+            _tenant_id: &str,
+            _kind: LibraryKind,
+            _name: &str,
+        ) -> Result<bool, StorageError> {
+            Ok(false)
+        }
+
+        async fn list_tenants(&self) -> Result<Vec<String>, StorageError> {
+            let sessions = self.sessions.lock().unwrap();
+            let tenants: BTreeSet<String> = sessions
+                .keys()
+                .map(|(tenant_id, _)| tenant_id.clone())
+                .collect();
+            Ok(tenants.into_iter().collect())
+        }
+
+        async fn erase_tenant(&self, tenant_id: &str) -> Result<u64, StorageError> {
+            let mut sessions = self.sessions.lock().unwrap();
+            let before = sessions.len();
+            sessions.retain(|(tenant, _), _| tenant != tenant_id);
+            Ok((before - sessions.len()) as u64)
+        }
+    }
+
+    #[test]
+    fn test_registry_creates_registered_kinds() {
+        let mut registry = StorageBackendRegistry::new();
+        registry.register("memory", |_options| {
+            Ok(MemoryBackend::new(Capabilities::new("memory")) as Arc<dyn StorageBackend>)
+        });
+        registry.register("failing", |options| {
+            Err(StorageError::InvalidArgument(format!(
+                "missing dir ({} options)",
+                options.len()
+            )))
+        });
+
+        assert_eq!(registry.kinds(), ["failing", "memory"]);
+        let backend = registry.create("memory", &BackendOptions::new()).unwrap();
+        assert_eq!(backend.backend_name(), "memory");
+        assert!(matches!(
+            registry.create("failing", &BackendOptions::new()),
+            Err(StorageError::InvalidArgument(msg)) if msg == "missing dir (0 options)"
+        ));
+        let Err(StorageError::InvalidArgument(msg)) = registry.create("s3", &BackendOptions::new())
+        else {
+            panic!("unknown kind was created");
+        };
+        assert_eq!(
+            msg,
+            "Unknown storage backend 's3' (available: failing, memory)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenants_and_their_sandboxes_follow_routes() {
+        let shared = MemoryBackend::new(Capabilities::new("memory"));
+        let dedicated = MemoryBackend::new(Capabilities::new("memory"));
+        let routed = TenantRoutedStorage::new(shared.clone()).route("acme", dedicated.clone());
+
+        routed.save_session("acme", "s1", b"a").await.unwrap();
+        routed
+            .save_session("acme~sandbox", "s1", b"b")
+            .await
+            .unwrap();
+        routed.save_session("globex", "s1", b"c").await.unwrap();
+
+        assert!(dedicated.holds("acme", "s1") && dedicated.holds("acme~sandbox", "s1"));
+        assert!(shared.holds("globex", "s1") && !shared.holds("acme", "s1"));
+        assert_eq!(
+            routed
+                .load_session("acme~sandbox", "s1")
+                .await
+                .unwrap()
+                .unwrap(),
+            b"b"
+        );
+        assert_eq!(routed.erase_tenant("acme").await.unwrap(), 1);
+        assert!(dedicated.holds("acme~sandbox", "s1"));
+    }
+
+    #[tokio::test]
+    async fn test_list_tenants_keeps_each_tenant_from_its_backend() {
+        let shared = MemoryBackend::new(Capabilities::new("memory"));
+        let dedicated = MemoryBackend::new(Capabilities::new("memory"));
+        let routed = TenantRoutedStorage::new(shared.clone())
+            .route("acme", dedicated.clone())
+            .route("initech", shared.clone());
+
+        routed.save_session("acme", "s1", b"a").await.unwrap();
+        routed.save_session("globex", "s1", b"b").await.unwrap();
+        routed.save_session("initech", "s1", b"c").await.unwrap();
+        // Left behind on the shared backend before acme got its own
+        shared.save_session("acme", "old", b"d").await.unwrap();
+        // Routed elsewhere: not served from here
+        dedicated
+            .save_session("globex", "stray", b"e")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            routed.list_tenants().await.unwrap(),
+            ["acme", "globex", "initech"]
+        );
+    }
+
+    #[test]
+    fn test_capabilities_are_the_common_subset() {
+        let full = Capabilities::new("r2")
+            .with_feature(feature::CAS)
+            .with_feature(feature::SANDBOXES)
+            .with_max_object_bytes(0);
+        let limited = Capabilities::new("local")
+            .with_feature(feature::SANDBOXES)
+            .with_max_object_bytes(100);
+        let routed = TenantRoutedStorage::new(MemoryBackend::new(full))
+            .route("acme", MemoryBackend::new(limited));
+
+        let caps = routed.capabilities();
+        assert!(caps.supports(feature::SANDBOXES));
+        assert!(!caps.supports(feature::CAS));
+        assert_eq!(caps.max_object_bytes, 100);
+    }
+}
@@ -0,0 +1,299 @@
+//! Tenant sandboxes (staging copies).
+//!
+//! A sandbox is a scratch copy of some of a tenant's sessions, stored as a
+//! tenant of its own, `{tenant}~sandbox`, where agents can edit, undo and
+//! delete freely without touching the real documents:
+//!
+//! - [`create_sandbox`] copies sessions (document, WAL, checkpoints and index
+//!   entry) into the sandbox, recording the version of each copy.
+//! - Promoting copies sandbox sessions back over the tenant's, refused with
+//!   [`StorageError::Conflict`] for sessions that changed in the tenant since
+//!   they were copied (see [`SessionIndex::check_promotion`]). Sessions
+//!   deleted in the sandbox are left alone in the tenant.
+//! - [`discard_sandbox`] deletes the sandbox.
+//!
+//! Sandboxes are stored with their tenant (same backend, same region).
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{SessionIndex, SessionIndexEntry};
+use crate::validation::validate_tenant_id;
+use crate::{StorageBackend, StorageError};
+
+/// Appended to a tenant ID to name its sandbox. Tenant IDs can't otherwise
+/// contain `~` (see [`validate_tenant_id`]), so it can't name a real tenant.
+pub const SANDBOX_SUFFIX: &str = "~sandbox";
+
+/// The tenant a sandbox was copied from, and the version of each session
+/// when it was copied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxOrigin {
+    pub tenant_id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub sessions: BTreeMap<String, SessionVersion>,
+}
+
+/// What identifies a version of a session: its WAL length and last edit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionVersion {
+    pub wal_count: u64,
+    pub last_modified_at: DateTime<Utc>,
+}
+
+impl SessionVersion {
+    pub fn of(entry: &SessionIndexEntry) -> Self {
+        Self {
+            wal_count: entry.wal_count,
+            last_modified_at: entry.last_modified_at,
+        }
+    }
+}
+
+impl SandboxOrigin {
+    /// Record `entries` as promoted, so promoting them again isn't a conflict.
+    pub fn rebase(&mut self, entries: &[SessionIndexEntry]) {
+        for entry in entries {
+            self.sessions
+                .insert(entry.id.clone(), SessionVersion::of(entry));
+        }
+    }
+}
+
+/// The sandbox tenant of `tenant_id`.
+pub fn sandbox_tenant_id(tenant_id: &str) -> Result<String, StorageError> {
+    if tenant_id.is_empty() {
+        return Err(StorageError::InvalidArgument(
+            "the empty tenant has no sandbox".to_string(),
+        ));
+    }
+    if is_sandbox(tenant_id) {
+        return Err(StorageError::InvalidArgument(format!(
+            "'{}' is already a sandbox",
+            tenant_id
+        )));
+    }
+    let sandbox_id = format!("{}{}", tenant_id, SANDBOX_SUFFIX);
+    validate_tenant_id(&sandbox_id)?;
+    Ok(sandbox_id)
+}
+
+/// Whether `tenant_id` names a sandbox.
+pub fn is_sandbox(tenant_id: &str) -> bool {
+    tenant_id.ends_with(SANDBOX_SUFFIX)
+}
+
+/// The tenant whose data `tenant_id` belongs with: the sandbox's tenant for
+/// a sandbox, else itself.
+pub fn owning_tenant(tenant_id: &str) -> &str {
+    tenant_id.strip_suffix(SANDBOX_SUFFIX).unwrap_or(tenant_id)
+}
+
+impl SessionIndex {
+    /// Entries of `session_ids` (IDs or aliases), or every entry when empty.
+    pub fn select_sessions(
+        &self,
+        session_ids: &[String],
+    ) -> Result<Vec<SessionIndexEntry>, StorageError> {
+        if session_ids.is_empty() {
+            return Ok(self.sessions.clone());
+        }
+        session_ids
+            .iter()
+            .map(|id| {
+                self.resolve(id)
+                    .cloned()
+                    .ok_or_else(|| StorageError::NotFound(format!("Session {} not found", id)))
+            })
+            .collect()
+    }
+
+    /// Where this sandbox was copied from; fails unless it is the sandbox of
+    /// `tenant_id`.
+    pub fn sandbox_origin(&self, tenant_id: &str) -> Result<&SandboxOrigin, StorageError> {
+        self.sandbox
+            .as_ref()
+            .filter(|origin| origin.tenant_id == tenant_id)
+            .ok_or_else(|| {
+                StorageError::InvalidArgument(format!(
+                    "the index is not the sandbox of tenant '{}'",
+                    tenant_id
+                ))
+            })
+    }
+
+    /// Check that sandbox `entries` may replace this tenant's sessions: none
+    /// of the replaced sessions is under legal hold and, unless `force`, none
+    /// changed since it was copied into the sandbox.
+    pub fn check_promotion(
+        &self,
+        origin: &SandboxOrigin,
+        entries: &[SessionIndexEntry],
+        force: bool,
+    ) -> Result<(), StorageError> {
+        let mut changed = Vec::new();
+        for entry in entries {
+            let Some(current) = self.get(&entry.id) else {
+                continue;
+            };
+            self.ensure_not_held(Some(&entry.id))?;
+            if origin.sessions.get(&entry.id) != Some(&SessionVersion::of(current)) {
+                changed.push(entry.id.as_str());
+            }
+        }
+        if changed.is_empty() || force {
+            return Ok(());
+        }
+        Err(StorageError::Conflict(format!(
+            "sessions changed since they were copied into the sandbox: {} (force to overwrite)",
+            changed.join(", ")
+        )))
+    }
+
+    /// Replace this tenant's entries with promoted sandbox `entries`. Holds
+    /// and expiry stay the tenant's; aliases naming another session are dropped.
+    pub fn promote(&mut self, entries: &[SessionIndexEntry]) {
+        for entry in entries {
+            let current = self.get(&entry.id);
+            let mut promoted = entry.clone();
+            promoted.legal_hold = current.and_then(|e| e.legal_hold.clone());
+            promoted.expires_at = current.and_then(|e| e.expires_at);
+            if let Some(alias) = &promoted.alias {
+                if self
+                    .resolve(alias)
+                    .is_some_and(|other| other.id != entry.id)
+                {
+                    promoted.alias = None;
+                }
+            }
+            self.upsert(promoted);
+        }
+    }
+}
+
+/// Copy a session's document, WAL and checkpoints from one tenant to
+/// another, replacing the target's. The index is left to the caller.
+pub async fn copy_session_data(
+    storage: &dyn StorageBackend,
+    from_tenant: &str,
+    to_tenant: &str,
+    session_id: &str,
+) -> Result<(), StorageError> {
+    let Some(docx) = storage.load_session(from_tenant, session_id).await? else {
+        return Err(StorageError::NotFound(format!(
+            "Session {} not found",
+            session_id
+        )));
+    };
+    storage.delete_session(to_tenant, session_id).await?;
+    storage.save_session(to_tenant, session_id, &docx).await?;
+
+    let (entries, _) = storage.read_wal(from_tenant, session_id, 0, None).await?;
+    if !entries.is_empty() {
+        storage.append_wal(to_tenant, session_id, &entries).await?;
+    }
+    for checkpoint in storage.list_checkpoints(from_tenant, session_id).await? {
+        if let Some((data, position)) = storage
+            .load_checkpoint(from_tenant, session_id, checkpoint.position)
+            .await?
+        {
+            storage
+                .save_checkpoint(to_tenant, session_id, position, &data)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy `session_ids` (every session when empty) of `tenant_id` into its
+/// sandbox, along with the rest of its index (recent files). An existing
+/// sandbox is refused unless `replace`, which discards it first. Copies expire
+/// at `expires_at`, if set. Returns the sandbox tenant and the copied sessions.
+pub async fn create_sandbox(
+    storage: &dyn StorageBackend,
+    tenant_id: &str,
+    session_ids: &[String],
+    replace: bool,
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(String, Vec<String>), StorageError> {
+    let sandbox_id = sandbox_tenant_id(tenant_id)?;
+    let index = storage.load_index(tenant_id).await?.unwrap_or_default();
+    let entries = index.select_sessions(session_ids)?;
+
+    if storage.load_index(&sandbox_id).await?.is_some() {
+        if !replace {
+            return Err(StorageError::InvalidArgument(format!(
+                "tenant '{}' already has a sandbox; promote or discard it, or replace it",
+                tenant_id
+            )));
+        }
+        discard_sandbox(storage, tenant_id).await?;
+    }
+
+    let mut origin = SandboxOrigin {
+        tenant_id: tenant_id.to_string(),
+        created_at: now,
+        sessions: BTreeMap::new(),
+    };
+    let mut sandbox = SessionIndex {
+        sessions: Vec::new(),
+        legal_hold: None,
+        sandbox: None,
+        sharded: false,
+        ..index
+    };
+    for entry in entries {
+        copy_session_data(storage, tenant_id, &sandbox_id, &entry.id).await?;
+        origin.rebase(std::slice::from_ref(&entry));
+        sandbox.upsert(SessionIndexEntry {
+            legal_hold: None,
+            expires_at,
+            ..entry
+        });
+    }
+    let copied = sandbox.sessions.iter().map(|s| s.id.clone()).collect();
+    sandbox.sandbox = Some(origin);
+    storage.save_index(&sandbox_id, &sandbox).await?;
+
+    Ok((sandbox_id, copied))
+}
+
+/// Delete the sandbox of `tenant_id`, unless something in it is under legal
+/// hold. Returns the number of objects deleted (0 if there was no sandbox).
+pub async fn discard_sandbox(
+    storage: &dyn StorageBackend,
+    tenant_id: &str,
+) -> Result<u64, StorageError> {
+    let sandbox_id = sandbox_tenant_id(tenant_id)?;
+    if let Some(index) = storage.load_index(&sandbox_id).await? {
+        index.ensure_nothing_held()?;
+    }
+    storage.erase_tenant(&sandbox_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_ids_cannot_collide_with_tenants() {
+        assert_eq!(sandbox_tenant_id("acme").unwrap(), "acme~sandbox");
+        assert!(validate_tenant_id("acme~sandbox").is_ok());
+        assert_eq!(owning_tenant("acme~sandbox"), "acme");
+
+        // A tenant named like the old ".sandbox" scheme is just a tenant
+        assert!(!is_sandbox("acme.sandbox"));
+        assert_eq!(sandbox_tenant_id("acme.sandbox").unwrap(), "acme.sandbox~sandbox");
+
+        // `~` only appears in the suffix, and sandboxes have no sandbox
+        for tenant_id in ["~sandbox", "ac~me", "acme~sandbox~sandbox", "acme~other"] {
+            assert!(validate_tenant_id(tenant_id).is_err(), "{} was accepted", tenant_id);
+        }
+        assert!(sandbox_tenant_id("acme~sandbox").is_err());
+        assert!(sandbox_tenant_id("").is_err());
+    }
+}
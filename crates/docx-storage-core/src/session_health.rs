@@ -0,0 +1,168 @@
+//! Health summary of one session, for dashboards.
+//!
+//! Dashboards used to read the index entry, the checkpoint list, the WAL tail
+//! and the sync status separately to tell whether a session is in good shape.
+//! [`session_health`] does those reads concurrently and adds a few consistency
+//! checks between them.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::wal_schema::prepare_wal_entry;
+use crate::{LegalHold, StorageBackend, StorageError, SyncBackend};
+
+/// Most WAL entries since the last checkpoint checked against the WAL schema.
+pub const HEALTH_WAL_SCAN_LIMIT: u64 = 1000;
+
+/// State of a session at a glance.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionHealth {
+    pub session_id: String,
+    /// Size of the stored baseline document
+    pub size_bytes: u64,
+    pub wal_count: u64,
+    pub cursor_position: u64,
+    /// Newest checkpoint position (0 when only the baseline exists)
+    pub last_checkpoint: u64,
+    /// Entries a load replays on top of the newest checkpoint
+    pub wal_since_checkpoint: u64,
+    pub last_modified_at: Option<DateTime<Utc>>,
+    pub pending_external_change: bool,
+    /// `None` when the session has no registered source
+    pub sync: Option<SessionSyncHealth>,
+    /// Hold on the session or its tenant
+    pub legal_hold: Option<LegalHold>,
+    /// Lease of an ephemeral session
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Inconsistencies found; empty when the session looks valid
+    pub problems: Vec<String>,
+}
+
+impl SessionHealth {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Sync side of [`SessionHealth`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSyncHealth {
+    pub auto_sync_enabled: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Seconds since the last successful sync
+    pub since_last_sync_secs: Option<i64>,
+    pub has_pending_changes: bool,
+    pub last_error: Option<String>,
+}
+
+/// Summarize `session_id`, checked at `now`. Sync fields are filled when a
+/// `sync` backend is given. Returns `None` when the session doesn't exist.
+pub async fn session_health(
+    storage: &dyn StorageBackend,
+    sync: Option<&dyn SyncBackend>,
+    tenant_id: &str,
+    session_id: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<SessionHealth>, StorageError> {
+    let sync_status = async {
+        match sync {
+            Some(sync) => sync.get_sync_status(tenant_id, session_id).await,
+            None => Ok(None),
+        }
+    };
+    let (document, index, checkpoints, (last, _), sync_status) = tokio::try_join!(
+        storage.load_session(tenant_id, session_id),
+        storage.load_index(tenant_id),
+        storage.list_checkpoints(tenant_id, session_id),
+        storage.tail_wal(tenant_id, session_id, None, 1),
+        sync_status,
+    )?;
+    let Some(document) = document else {
+        return Ok(None);
+    };
+
+    let wal_count = last.first().map_or(0, |e| e.position);
+    let last_checkpoint = checkpoints
+        .iter()
+        .map(|c| c.position)
+        .filter(|&p| p <= wal_count)
+        .max()
+        .unwrap_or(0);
+    let mut problems = Vec::new();
+
+    if !document.starts_with(b"PK\x03\x04") {
+        problems.push("the stored document is not a DOCX (ZIP) package".to_string());
+    }
+
+    let entry = index.as_ref().and_then(|index| index.get(session_id));
+    match entry {
+        None => problems.push("the session is missing from the index".to_string()),
+        Some(entry) => {
+            if entry.wal_count != wal_count {
+                problems.push(format!(
+                    "the index records {} WAL entries, the WAL has {}",
+                    entry.wal_count, wal_count
+                ));
+            }
+            if entry.cursor_position > wal_count {
+                problems.push(format!(
+                    "the cursor ({}) is past the end of the WAL ({})",
+                    entry.cursor_position, wal_count
+                ));
+            }
+            for position in &entry.checkpoint_positions {
+                if !checkpoints.iter().any(|c| c.position == *position) {
+                    problems.push(format!("checkpoint {} is indexed but not stored", position));
+                }
+            }
+        }
+    }
+
+    let wal_since_checkpoint = wal_count - last_checkpoint;
+    if wal_since_checkpoint > 0 {
+        let (entries, _) = storage
+            .read_wal(
+                tenant_id,
+                session_id,
+                last_checkpoint + 1,
+                Some(wal_since_checkpoint.min(HEALTH_WAL_SCAN_LIMIT)),
+            )
+            .await?;
+        problems.extend(entries.iter().filter_map(|e| {
+            prepare_wal_entry(&e.patch_json)
+                .err()
+                .map(|err| format!("WAL entry {} is invalid: {}", e.position, err))
+        }));
+    }
+
+    let sync = sync_status.map(|status| {
+        let last_synced_at = status
+            .last_synced_at
+            .and_then(|t| Utc.timestamp_opt(t, 0).single());
+        SessionSyncHealth {
+            auto_sync_enabled: status.auto_sync_enabled,
+            since_last_sync_secs: last_synced_at.map(|t| (now - t).num_seconds().max(0)),
+            last_synced_at,
+            has_pending_changes: status.has_pending_changes,
+            last_error: status.last_error,
+        }
+    });
+
+    Ok(Some(SessionHealth {
+        session_id: session_id.to_string(),
+        size_bytes: document.len() as u64,
+        wal_count,
+        cursor_position: entry.map_or(wal_count, |e| e.cursor_position),
+        last_checkpoint,
+        wal_since_checkpoint,
+        last_modified_at: entry.map(|e| e.last_modified_at),
+        pending_external_change: entry.is_some_and(|e| e.pending_external_change),
+        sync,
+        legal_hold: index
+            .as_ref()
+            .and_then(|index| index.legal_hold(Some(session_id)))
+            .cloned(),
+        expires_at: entry.and_then(|e| e.expires_at),
+        problems,
+    }))
+}
@@ -0,0 +1,101 @@
+//! Opening a session in one call: the document to start from and the WAL
+//! entries to replay on it.
+//!
+//! Clients used to load the index (for the cursor), then a checkpoint or the
+//! baseline, then the WAL, one round-trip each. [`load_session_with_history`]
+//! does the same reads in two concurrent batches on the server.
+
+use crate::{StorageBackend, StorageError, WalEntry};
+
+/// A session's document at some WAL position, with the entries to replay on
+/// it to reach the target position.
+#[derive(Debug, Clone)]
+pub struct SessionWithHistory {
+    /// Newest checkpoint at or before `target_position`, or the baseline
+    pub document: Vec<u8>,
+    /// WAL position `document` represents (0 for the baseline)
+    pub base_position: u64,
+    /// Position the session opens at
+    pub target_position: u64,
+    pub wal_count: u64,
+    pub source_path: Option<String>,
+    /// Entries `base_position + 1..=target_position`, in order
+    pub entries: Vec<WalEntry>,
+}
+
+/// Load what it takes to open a session at `position`, or at its index cursor
+/// when `None`. Positions past the end of the WAL open at its end. Returns
+/// `None` when the session doesn't exist.
+pub async fn load_session_with_history<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    session_id: &str,
+    position: Option<u64>,
+) -> Result<Option<SessionWithHistory>, StorageError> {
+    let (index, checkpoints, (last, _)) = tokio::try_join!(
+        storage.load_index(tenant_id),
+        storage.list_checkpoints(tenant_id, session_id),
+        storage.tail_wal(tenant_id, session_id, None, 1),
+    )?;
+    let wal_count = last.first().map_or(0, |e| e.position);
+    let entry = index.as_ref().and_then(|index| index.get(session_id));
+    let target_position = position
+        .or(entry.map(|e| e.cursor_position))
+        .map_or(wal_count, |p| p.min(wal_count));
+    // Position 0 asks storage for the latest checkpoint, and is the baseline anyway
+    let checkpoint = checkpoints
+        .iter()
+        .map(|c| c.position)
+        .filter(|&p| p > 0 && p <= target_position)
+        .max();
+
+    let base = async {
+        if let Some(position) = checkpoint {
+            if let Some(found) = storage.load_checkpoint(tenant_id, session_id, position).await? {
+                return Ok(Some(found));
+            }
+        }
+        Ok(storage.load_session(tenant_id, session_id).await?.map(|data| (data, 0)))
+    };
+    let read_from = checkpoint.unwrap_or(0);
+    let (base, mut entries) = tokio::try_join!(
+        base,
+        read_entries(storage, tenant_id, session_id, read_from, target_position),
+    )?;
+    let Some((document, base_position)) = base else {
+        return Ok(None);
+    };
+    if base_position < read_from {
+        // The checkpoint went away meanwhile: replay from the baseline
+        let mut earlier =
+            read_entries(storage, tenant_id, session_id, base_position, read_from).await?;
+        earlier.append(&mut entries);
+        entries = earlier;
+    }
+
+    Ok(Some(SessionWithHistory {
+        document,
+        base_position,
+        target_position,
+        wal_count,
+        source_path: entry.and_then(|e| e.source_path.clone()),
+        entries,
+    }))
+}
+
+/// WAL entries `after + 1..=through`.
+async fn read_entries<S: StorageBackend + ?Sized>(
+    storage: &S,
+    tenant_id: &str,
+    session_id: &str,
+    after: u64,
+    through: u64,
+) -> Result<Vec<WalEntry>, StorageError> {
+    if through <= after {
+        return Ok(Vec::new());
+    }
+    let (entries, _) = storage
+        .read_wal(tenant_id, session_id, after + 1, Some(through - after))
+        .await?;
+    Ok(entries)
+}
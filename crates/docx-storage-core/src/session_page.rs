@@ -0,0 +1,85 @@
+use crate::storage::{SessionIndex, SessionIndexEntry};
+
+/// Entries in a page of sessions when the query doesn't say.
+pub const DEFAULT_SESSION_PAGE_SIZE: u32 = 100;
+
+/// Most entries a page of sessions holds.
+pub const MAX_SESSION_PAGE_SIZE: u32 = 1000;
+
+/// Parameters for [`StorageBackend::list_session_page`](crate::StorageBackend::list_session_page).
+#[derive(Debug, Clone, Default)]
+pub struct SessionPageQuery {
+    /// Only sessions whose ID starts with this
+    pub id_prefix: Option<String>,
+    /// Case-insensitive text to find in the source path, display name or alias
+    pub search: Option<String>,
+    /// `next_page_token` of the previous page
+    pub page_token: Option<String>,
+    /// Entries per page (0 = [`DEFAULT_SESSION_PAGE_SIZE`]), capped at
+    /// [`MAX_SESSION_PAGE_SIZE`]
+    pub page_size: u32,
+}
+
+impl SessionPageQuery {
+    /// The number of entries a page holds.
+    pub fn effective_page_size(&self) -> usize {
+        let size = match self.page_size {
+            0 => DEFAULT_SESSION_PAGE_SIZE,
+            size => size.min(MAX_SESSION_PAGE_SIZE),
+        };
+        size as usize
+    }
+
+    /// Whether `entry` passes the ID prefix and search filters.
+    pub fn matches(&self, entry: &SessionIndexEntry) -> bool {
+        if let Some(prefix) = self.id_prefix.as_deref() {
+            if !entry.id.starts_with(prefix) {
+                return false;
+            }
+        }
+        let Some(search) = self.search.as_deref().filter(|s| !s.is_empty()) else {
+            return true;
+        };
+        let search = search.to_lowercase();
+        [&entry.source_path, &entry.display_name, &entry.alias]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&search))
+    }
+}
+
+/// One page of a tenant's index entries, in a stable order: by session ID,
+/// or by object key for backends that list the entries from storage.
+#[derive(Debug, Clone, Default)]
+pub struct SessionPage {
+    pub entries: Vec<SessionIndexEntry>,
+    /// Token of the next page; `None` on the last one. With filters, the
+    /// page it leads to may turn out empty
+    pub next_page_token: Option<String>,
+}
+
+impl SessionIndex {
+    /// The page of this index's entries `query` asks for, by session ID.
+    pub fn page(&self, query: &SessionPageQuery) -> SessionPage {
+        let mut entries: Vec<&SessionIndexEntry> = self
+            .sessions
+            .iter()
+            .filter(|entry| {
+                query
+                    .page_token
+                    .as_deref()
+                    .is_none_or(|after| entry.id.as_str() > after)
+            })
+            .filter(|entry| query.matches(entry))
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let size = query.effective_page_size();
+        let next_page_token = (entries.len() > size).then(|| entries[size - 1].id.clone());
+        entries.truncate(size);
+        SessionPage {
+            entries: entries.into_iter().cloned().collect(),
+            next_page_token,
+        }
+    }
+}
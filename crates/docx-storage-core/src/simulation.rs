@@ -0,0 +1,347 @@
+//! Scriptable watch backend for tests (feature `simulation`).
+//!
+//! [`SimulatedWatchBackend`] runs on a simulated clock instead of polling a
+//! real source: a test schedules external changes at given times, advances
+//! the clock, and the changes that came due are reported by
+//! `check_for_changes` exactly like a real backend reports edits made in
+//! Drive or on disk. Outages are scripted with
+//! [`SimulatedWatchBackend::fail_next`]. Conflict handling, change queueing
+//! and sync retries can then be tested deterministically, without cloud
+//! accounts or timing-dependent sleeps.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::error::StorageError;
+use crate::sync::SourceDescriptor;
+use crate::watch::{ExternalChangeEvent, ExternalChangeType, SourceMetadata, WatchBackend};
+
+/// An external change to a simulated source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulatedChange {
+    /// The source now has this metadata.
+    Modified(SourceMetadata),
+    Deleted,
+    /// The source moved to this URI.
+    Renamed(String),
+    PermissionChanged,
+}
+
+/// State of one watched session.
+#[derive(Debug)]
+struct SimulatedSource {
+    watch_id: String,
+    source: SourceDescriptor,
+    /// The source's current metadata (`None` once deleted).
+    current: Option<SourceMetadata>,
+    /// Metadata as of the last sync.
+    known: Option<SourceMetadata>,
+    /// Changes not yet due, by time.
+    scheduled: Vec<(i64, SimulatedChange)>,
+    /// Changes that came due and weren't reported yet, oldest first.
+    detected: VecDeque<ExternalChangeEvent>,
+}
+
+#[derive(Debug, Default)]
+struct Simulation {
+    now: i64,
+    next_watch: u64,
+    /// Calls left to fail with `Unavailable`.
+    failures: u32,
+    sources: HashMap<(String, String), SimulatedSource>,
+}
+
+/// Watch backend driven by a script and a simulated clock.
+#[derive(Debug, Default)]
+pub struct SimulatedWatchBackend {
+    state: Mutex<Simulation>,
+}
+
+impl SimulatedWatchBackend {
+    /// A backend whose clock starts at `now` (Unix timestamp).
+    pub fn new(now: i64) -> Self {
+        Self {
+            state: Mutex::new(Simulation {
+                now,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// The simulated time.
+    pub fn now(&self) -> i64 {
+        self.state.lock().unwrap().now
+    }
+
+    /// Schedule an external change to a watched source at time `at`.
+    /// Changes scheduled in the past are applied on the next
+    /// [`advance`](Self::advance).
+    pub fn schedule(&self, tenant_id: &str, session_id: &str, at: i64, change: SimulatedChange) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(watched) = state
+            .sources
+            .get_mut(&Self::key(tenant_id, session_id))
+        {
+            watched.scheduled.push((at, change));
+        }
+    }
+
+    /// Move the clock forward, applying the changes that come due in order.
+    pub fn advance(&self, secs: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.now += secs;
+        let now = state.now;
+        for (key, watched) in state.sources.iter_mut() {
+            watched.scheduled.sort_by_key(|(at, _)| *at);
+            let due = watched.scheduled.partition_point(|(at, _)| *at <= now);
+            for (at, change) in watched.scheduled.drain(..due).collect::<Vec<_>>() {
+                let event = watched.apply(&key.1, at, change);
+                watched.detected.push_back(event);
+            }
+        }
+    }
+
+    /// Make the next `calls` calls fail with [`StorageError::Unavailable`],
+    /// as when the source's API is down.
+    pub fn fail_next(&self, calls: u32) {
+        self.state.lock().unwrap().failures = calls;
+    }
+
+    /// Number of watched sessions.
+    pub fn watch_count(&self) -> usize {
+        self.state.lock().unwrap().sources.len()
+    }
+
+    /// Watch ID and current path of a watched session.
+    pub fn watched(&self, tenant_id: &str, session_id: &str) -> Option<(String, String)> {
+        self.state
+            .lock()
+            .unwrap()
+            .sources
+            .get(&Self::key(tenant_id, session_id))
+            .map(|w| (w.watch_id.clone(), w.source.path.clone()))
+    }
+
+    /// Lock the state, consuming a scripted failure if any.
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Simulation>, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if state.failures > 0 {
+            state.failures -= 1;
+            return Err(StorageError::Unavailable("simulated outage".to_string()));
+        }
+        Ok(state)
+    }
+
+    fn key(tenant_id: &str, session_id: &str) -> (String, String) {
+        (tenant_id.to_string(), session_id.to_string())
+    }
+}
+
+impl SimulatedSource {
+    /// Apply a change that came due at `at`, returning its event.
+    fn apply(&mut self, session_id: &str, at: i64, change: SimulatedChange) -> ExternalChangeEvent {
+        let old_metadata = self.current.clone();
+        let mut new_uri = None;
+        let change_type = match change {
+            SimulatedChange::Modified(metadata) => {
+                self.current = Some(metadata);
+                ExternalChangeType::Modified
+            }
+            SimulatedChange::Deleted => {
+                self.current = None;
+                ExternalChangeType::Deleted
+            }
+            SimulatedChange::Renamed(uri) => {
+                self.source.path = uri.clone();
+                new_uri = Some(uri);
+                ExternalChangeType::Renamed
+            }
+            SimulatedChange::PermissionChanged => ExternalChangeType::PermissionChanged,
+        };
+        ExternalChangeEvent {
+            session_id: session_id.to_string(),
+            change_type,
+            old_metadata,
+            new_metadata: self.current.clone(),
+            detected_at: at,
+            new_uri,
+        }
+    }
+}
+
+#[async_trait]
+impl WatchBackend for SimulatedWatchBackend {
+    async fn start_watch(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        source: &SourceDescriptor,
+        _poll_interval_secs: u32,
+    ) -> Result<String, StorageError> {
+        let mut state = self.lock()?;
+        let watch_id = format!("sim-watch-{}", state.next_watch);
+        state.next_watch += 1;
+        let metadata = SourceMetadata {
+            size_bytes: 0,
+            modified_at: state.now,
+            etag: None,
+            version_id: None,
+            content_hash: None,
+        };
+        state.sources.insert(
+            Self::key(tenant_id, session_id),
+            SimulatedSource {
+                watch_id: watch_id.clone(),
+                source: source.clone(),
+                current: Some(metadata.clone()),
+                known: Some(metadata),
+                scheduled: Vec::new(),
+                detected: VecDeque::new(),
+            },
+        );
+        Ok(watch_id)
+    }
+
+    async fn stop_watch(&self, tenant_id: &str, session_id: &str) -> Result<(), StorageError> {
+        self.lock()?
+            .sources
+            .remove(&Self::key(tenant_id, session_id));
+        Ok(())
+    }
+
+    async fn check_for_changes(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<ExternalChangeEvent>, StorageError> {
+        let mut state = self.lock()?;
+        let Some(watched) = state.sources.get_mut(&Self::key(tenant_id, session_id)) else {
+            return Err(StorageError::Watch(format!(
+                "No watch for tenant {} session {}",
+                tenant_id, session_id
+            )));
+        };
+        Ok(watched.detected.pop_front())
+    }
+
+    async fn get_source_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<SourceMetadata>, StorageError> {
+        Ok(self
+            .lock()?
+            .sources
+            .get(&Self::key(tenant_id, session_id))
+            .and_then(|w| w.current.clone()))
+    }
+
+    async fn get_known_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<SourceMetadata>, StorageError> {
+        Ok(self
+            .lock()?
+            .sources
+            .get(&Self::key(tenant_id, session_id))
+            .and_then(|w| w.known.clone()))
+    }
+
+    async fn update_known_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        metadata: SourceMetadata,
+    ) -> Result<(), StorageError> {
+        let mut state = self.lock()?;
+        if let Some(watched) = state.sources.get_mut(&Self::key(tenant_id, session_id)) {
+            // A sync writes the source, so it is current too
+            watched.current = Some(metadata.clone());
+            watched.known = Some(metadata);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::change_queue::ChangeQueue;
+    use crate::sync::SourceType;
+
+    fn source() -> SourceDescriptor {
+        SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: "/docs/report.docx".to_string(),
+            file_id: None,
+        }
+    }
+
+    fn metadata(size_bytes: u64, modified_at: i64) -> SourceMetadata {
+        SourceMetadata {
+            size_bytes,
+            modified_at,
+            etag: None,
+            version_id: None,
+            content_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scripted_changes_come_due_in_order() {
+        let watch = SimulatedWatchBackend::new(1_000);
+        watch.start_watch("t1", "s1", &source(), 0).await.unwrap();
+        watch.schedule("t1", "s1", 1_120, SimulatedChange::Renamed("/docs/final.docx".into()));
+        watch.schedule("t1", "s1", 1_030, SimulatedChange::Modified(metadata(10, 1_030)));
+
+        watch.advance(60);
+        let event = watch.check_for_changes("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(event.change_type, ExternalChangeType::Modified);
+        assert_eq!(event.detected_at, 1_030);
+        assert_eq!(event.new_metadata, Some(metadata(10, 1_030)));
+        assert!(watch.check_for_changes("t1", "s1").await.unwrap().is_none());
+        assert_eq!(
+            watch.get_known_metadata("t1", "s1").await.unwrap(),
+            Some(metadata(0, 1_000))
+        );
+
+        watch.advance(60);
+        let event = watch.check_for_changes("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(event.change_type, ExternalChangeType::Renamed);
+        assert_eq!(event.new_uri.as_deref(), Some("/docs/final.docx"));
+        assert_eq!(watch.watched("t1", "s1").unwrap().1, "/docs/final.docx");
+        assert_eq!(watch.now(), 1_120);
+    }
+
+    #[tokio::test]
+    async fn test_outages_and_change_queue_coalescing() {
+        let watch = SimulatedWatchBackend::new(0);
+        watch.start_watch("t1", "s1", &source(), 0).await.unwrap();
+        watch.schedule("t1", "s1", 5, SimulatedChange::Modified(metadata(1, 5)));
+        watch.schedule("t1", "s1", 8, SimulatedChange::Modified(metadata(2, 8)));
+        watch.advance(10);
+
+        watch.fail_next(1);
+        assert!(matches!(
+            watch.check_for_changes("t1", "s1").await,
+            Err(StorageError::Unavailable(_))
+        ));
+
+        // Both edits reach the client as one queued change
+        let mut queue = ChangeQueue::default();
+        while let Some(event) = watch.check_for_changes("t1", "s1").await.unwrap() {
+            queue.push(event);
+        }
+        let pending = queue.pending(&["s1".to_string()]);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].coalesced, 2);
+        assert_eq!(pending[0].event.new_metadata, Some(metadata(2, 8)));
+
+        watch.stop_watch("t1", "s1").await.unwrap();
+        assert_eq!(watch.watch_count(), 0);
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::StorageError;
+use crate::session_history::SessionWithHistory;
+use crate::storage::StorageBackend;
+
+/// How long a snapshot stays open when its reader doesn't close it.
+pub const DEFAULT_SNAPSHOT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Snapshots a tenant can hold open at once. Each keeps a document in memory.
+pub const MAX_SNAPSHOTS_PER_TENANT: usize = 16;
+
+/// An immutable view of a session: its document and WAL entries up to the
+/// position it was opened at.
+#[derive(Debug, Clone)]
+pub struct PinnedSnapshot {
+    pub id: String,
+    pub session_id: String,
+    pub history: Arc<SessionWithHistory>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Open snapshots by tenant and snapshot ID, with when they expire.
+type Snapshots = HashMap<(String, String), (PinnedSnapshot, Instant)>;
+
+/// In-memory registry of session snapshots, per tenant.
+///
+/// Opening a snapshot reads the session once, through
+/// [`StorageBackend::load_session_snapshot`], and keeps the result under an
+/// ID. Reads by that ID see the session as it was when opened, however the
+/// session is edited, truncated or compacted meanwhile, so an export or a
+/// preview spanning several reads stays consistent. Snapshots are dropped
+/// when closed or `ttl` after they were opened.
+pub struct SnapshotRegistry {
+    snapshots: Mutex<Snapshots>,
+    ttl: Duration,
+    max_per_tenant: usize,
+}
+
+impl Default for SnapshotRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_SNAPSHOT_TTL, MAX_SNAPSHOTS_PER_TENANT)
+    }
+}
+
+impl SnapshotRegistry {
+    /// Create a registry keeping snapshots open for `ttl`, at most
+    /// `max_per_tenant` of them per tenant.
+    pub fn new(ttl: Duration, max_per_tenant: usize) -> Self {
+        Self {
+            snapshots: Mutex::new(HashMap::new()),
+            ttl,
+            max_per_tenant,
+        }
+    }
+
+    /// Pin a session at `position` (`None` = its index cursor). Returns
+    /// `None` when the session doesn't exist, and `Unavailable` when the
+    /// tenant already holds the maximum number of snapshots.
+    pub async fn open<S: StorageBackend + ?Sized>(
+        &self,
+        storage: &S,
+        tenant_id: &str,
+        session_id: &str,
+        position: Option<u64>,
+    ) -> Result<Option<PinnedSnapshot>, StorageError> {
+        self.ensure_capacity(tenant_id)?;
+        let Some(history) = storage
+            .load_session_snapshot(tenant_id, session_id, position)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let snapshot = PinnedSnapshot {
+            id: format!("snap_{:016x}", rand::random::<u64>()),
+            session_id: session_id.to_string(),
+            history: Arc::new(history),
+            expires_at: chrono::Utc::now() + self.ttl,
+        };
+        // Checked again: other snapshots may have opened during the load
+        let mut snapshots = self.snapshots.lock().unwrap();
+        Self::prune(&mut snapshots);
+        if Self::open_count(&snapshots, tenant_id) >= self.max_per_tenant {
+            return Err(too_many(tenant_id));
+        }
+        snapshots.insert(
+            (tenant_id.to_string(), snapshot.id.clone()),
+            (snapshot.clone(), Instant::now() + self.ttl),
+        );
+        Ok(Some(snapshot))
+    }
+
+    /// Get an open snapshot by ID.
+    pub fn get(&self, tenant_id: &str, snapshot_id: &str) -> Result<PinnedSnapshot, StorageError> {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        Self::prune(&mut snapshots);
+        snapshots
+            .get(&(tenant_id.to_string(), snapshot_id.to_string()))
+            .map(|(snapshot, _)| snapshot.clone())
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("snapshot {} (closed or expired)", snapshot_id))
+            })
+    }
+
+    /// Close a snapshot. Returns whether it was open.
+    pub fn close(&self, tenant_id: &str, snapshot_id: &str) -> bool {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        Self::prune(&mut snapshots);
+        snapshots
+            .remove(&(tenant_id.to_string(), snapshot_id.to_string()))
+            .is_some()
+    }
+
+    fn ensure_capacity(&self, tenant_id: &str) -> Result<(), StorageError> {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        Self::prune(&mut snapshots);
+        if Self::open_count(&snapshots, tenant_id) >= self.max_per_tenant {
+            return Err(too_many(tenant_id));
+        }
+        Ok(())
+    }
+
+    fn open_count(snapshots: &Snapshots, tenant_id: &str) -> usize {
+        snapshots.keys().filter(|(tenant, _)| tenant == tenant_id).count()
+    }
+
+    /// Drop expired snapshots.
+    fn prune(snapshots: &mut Snapshots) {
+        let now = Instant::now();
+        snapshots.retain(|_, (_, expires)| *expires > now);
+    }
+}
+
+fn too_many(tenant_id: &str) -> StorageError {
+    StorageError::Unavailable(format!(
+        "tenant {} has too many open snapshots; close one or wait for it to expire",
+        tenant_id
+    ))
+}
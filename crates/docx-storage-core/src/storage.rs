@@ -1,7 +1,22 @@
 use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
+use crate::admin_log::AdminLogEntry;
+use crate::capabilities::Capabilities;
+use crate::circuit_breaker::CircuitBreakerStats;
 use crate::error::StorageError;
+use crate::history_chain::HistoryLink;
+use crate::index_schema::SESSION_INDEX_VERSION;
+use crate::legal_hold::LegalHold;
+use crate::library::{LibraryItemInfo, LibraryKind};
+use crate::sandbox::SandboxOrigin;
+use crate::session_history::{load_session_with_history, SessionWithHistory};
+use crate::session_page::{SessionPage, SessionPageQuery};
+use crate::storage_report::StorageReport;
+use crate::sync::{RecentFile, SourceDescriptor, MAX_RECENT_FILES};
+use crate::validation::validate_alias;
 
 /// Information about a session stored in the backend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,14 +30,17 @@ pub struct SessionInfo {
 
 /// A single WAL entry representing an edit operation.
 ///
-/// The `patch_json` field contains the raw JSON bytes of the .NET WalEntry.
-/// The Rust server doesn't parse this - it just stores and retrieves raw bytes.
+/// The `patch_json` field contains the raw JSON bytes of the .NET WalEntry,
+/// a [`WalRecord`](crate::WalRecord). Backends validate it on append and
+/// upgrade entries of older schema versions on read; otherwise the bytes are
+/// stored and returned as-is.
 /// The `position` field is assigned by the server when appending.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalEntry {
     /// Position in WAL (1-indexed, assigned by server)
     pub position: u64,
-    /// Operation type (for debugging/logging only)
+    /// Entry type of the record (`Patch`, `ExternalSync` or `Import`) on read;
+    /// ignored on append
     #[serde(default)]
     pub operation: String,
     /// Target path (for debugging/logging only)
@@ -44,18 +62,44 @@ pub struct CheckpointInfo {
 }
 
 /// The session index containing metadata about all sessions for a tenant.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// Stored indexes are parsed with [`SessionIndex::from_json`], which upgrades
+/// older schema versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionIndex {
-    /// Schema version
-    #[serde(default = "default_version")]
+    /// Schema version ([`SESSION_INDEX_VERSION`] once loaded)
     pub version: u32,
     /// Array of session entries
     #[serde(default)]
     pub sessions: Vec<SessionIndexEntry>,
+    /// Recently opened/synced files and favorites, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_files: Vec<RecentFile>,
+    /// Legal hold on the whole tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_hold: Option<LegalHold>,
+    /// Set when this tenant is a sandbox: what it was copied from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxOrigin>,
+    /// Set on manifests: stored indexes whose session entries are kept by
+    /// the backend as one object per session, so writes to different
+    /// sessions don't conflict. Loaded indexes have their entries back in
+    /// `sessions`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sharded: bool,
 }
 
-fn default_version() -> u32 {
-    1
+impl Default for SessionIndex {
+    fn default() -> Self {
+        Self {
+            version: SESSION_INDEX_VERSION,
+            sessions: Vec::new(),
+            recent_files: Vec::new(),
+            legal_hold: None,
+            sandbox: None,
+            sharded: false,
+        }
+    }
 }
 
 impl SessionIndex {
@@ -92,6 +136,100 @@ impl SessionIndex {
     pub fn contains(&self, session_id: &str) -> bool {
         self.sessions.iter().any(|s| s.id == session_id)
     }
+
+    /// Find a session by ID, falling back to a case-insensitive alias match.
+    pub fn resolve(&self, name: &str) -> Option<&SessionIndexEntry> {
+        if let Some(entry) = self.get(name) {
+            return Some(entry);
+        }
+        let key = name.trim().to_lowercase();
+        self.sessions
+            .iter()
+            .find(|s| s.alias.as_deref().is_some_and(|a| a.to_lowercase() == key))
+    }
+
+    /// Set a session's display name and/or alias.
+    ///
+    /// `None` leaves a field unchanged and an empty string clears it. Returns
+    /// `Ok(false)` if the session doesn't exist, and an error if the alias is
+    /// invalid or already names another session (by alias or ID).
+    pub fn rename(
+        &mut self,
+        session_id: &str,
+        display_name: Option<&str>,
+        alias: Option<&str>,
+    ) -> Result<bool, StorageError> {
+        if !self.contains(session_id) {
+            return Ok(false);
+        }
+
+        let alias = alias.map(str::trim);
+        if let Some(alias) = alias.filter(|a| !a.is_empty()) {
+            validate_alias(alias)?;
+            let key = alias.to_lowercase();
+            if let Some(other) = self.sessions.iter().find(|s| {
+                s.id != session_id
+                    && (s.id.to_lowercase() == key
+                        || s.alias.as_deref().is_some_and(|a| a.to_lowercase() == key))
+            }) {
+                return Err(StorageError::InvalidArgument(format!(
+                    "Alias '{}' is already used by session {}",
+                    alias, other.id
+                )));
+            }
+        }
+
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let Some(entry) = self.get_mut(session_id) else {
+            return Ok(false);
+        };
+        if let Some(name) = display_name {
+            entry.display_name = non_empty(name.trim());
+        }
+        if let Some(alias) = alias {
+            entry.alias = non_empty(alias);
+        }
+        Ok(true)
+    }
+
+    /// Move a file to the front of the recent list (adding it if needed),
+    /// evicting the oldest non-favorites beyond `MAX_RECENT_FILES`.
+    pub fn touch_recent_file(&mut self, source: SourceDescriptor, used_at: i64) {
+        let favorite = match self.recent_files.iter().position(|f| f.matches(&source)) {
+            Some(pos) => self.recent_files.remove(pos).favorite,
+            None => false,
+        };
+        let mut entry = RecentFile::new(source, used_at);
+        entry.favorite = favorite;
+        self.recent_files.insert(0, entry);
+
+        let mut kept = 0;
+        self.recent_files.retain(|f| {
+            if f.favorite {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_RECENT_FILES
+        });
+    }
+
+    /// Pin or unpin a favorite. Returns false (nothing changed) when unpinning
+    /// a file that isn't listed.
+    pub fn set_favorite(&mut self, source: SourceDescriptor, pinned: bool, now: i64) -> bool {
+        match self.recent_files.iter_mut().find(|f| f.matches(&source)) {
+            Some(entry) => {
+                entry.favorite = pinned;
+                true
+            }
+            None if pinned => {
+                let mut entry = RecentFile::new(source, now);
+                entry.favorite = true;
+                self.recent_files.insert(0, entry);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// A single session entry in the index.
@@ -107,13 +245,12 @@ pub struct SessionIndexEntry {
     /// When the session was created
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// When the session was last modified
-    #[serde(alias = "modified_at")]
     pub last_modified_at: chrono::DateTime<chrono::Utc>,
     /// The DOCX filename (e.g., "abc123.docx")
     #[serde(default)]
     pub docx_file: Option<String>,
     /// WAL entry count
-    #[serde(alias = "wal_position", default)]
+    #[serde(default)]
     pub wal_count: u64,
     /// Current cursor position in WAL
     #[serde(default)]
@@ -124,12 +261,38 @@ pub struct SessionIndexEntry {
     /// Whether there is a pending external change for this session
     #[serde(default)]
     pub pending_external_change: bool,
+    /// Human-friendly name shown in listings (e.g. "Q3 board report")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Name that can be used instead of the session ID; unique per tenant,
+    /// compared case-insensitively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Legal hold on this session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_hold: Option<LegalHold>,
+    /// When an ephemeral session expires and gets purged; `None` for sessions
+    /// kept until deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 fn default_auto_sync() -> bool {
     true
 }
 
+/// Chunks of a document streamed into storage, in order.
+pub type ChunkStream<'a> = BoxStream<'a, Result<Vec<u8>, StorageError>>;
+
+/// Concatenate every chunk of `chunks`.
+pub async fn collect_chunks(mut chunks: ChunkStream<'_>) -> Result<Vec<u8>, StorageError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        data.extend_from_slice(&chunk?);
+    }
+    Ok(data)
+}
+
 /// Storage backend abstraction for tenant-aware document storage.
 ///
 /// All methods take `tenant_id` as the first parameter to ensure isolation.
@@ -139,6 +302,18 @@ pub trait StorageBackend: Send + Sync {
     /// Returns the backend identifier (e.g., "local", "r2").
     fn backend_name(&self) -> &'static str;
 
+    /// State of the circuit breaker guarding a remote backend, for health
+    /// checks. `None` for backends without one.
+    fn circuit_stats(&self) -> Option<CircuitBreakerStats> {
+        None
+    }
+
+    /// Features and limits of the backend itself (the services add the ones
+    /// they implement). By default, none and no object size limit.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(self.backend_name())
+    }
+
     // =========================================================================
     // Session Operations
     // =========================================================================
@@ -158,6 +333,21 @@ pub trait StorageBackend: Send + Sync {
         data: &[u8],
     ) -> Result<(), StorageError>;
 
+    /// Save a session's DOCX bytes as they arrive, returning the size
+    /// written. Nothing is saved if the stream fails. The default collects
+    /// the chunks for [`Self::save_session`]; backends override it to write
+    /// with bounded memory.
+    async fn save_session_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let data = collect_chunks(chunks).await?;
+        self.save_session(tenant_id, session_id, &data).await?;
+        Ok(data.len() as u64)
+    }
+
     /// Delete a session and all associated data (WAL, checkpoints).
     async fn delete_session(
         &self,
@@ -175,6 +365,19 @@ pub trait StorageBackend: Send + Sync {
         session_id: &str,
     ) -> Result<bool, StorageError>;
 
+    /// Load what it takes to open a session, like [`load_session_with_history`],
+    /// as of a single point in time. The default reads the live data, so
+    /// writes landing between its reads can show; backends that can freeze a
+    /// session's files cheaply override it to read from a snapshot instead.
+    async fn load_session_snapshot(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: Option<u64>,
+    ) -> Result<Option<SessionWithHistory>, StorageError> {
+        load_session_with_history(self, tenant_id, session_id, position).await
+    }
+
     // =========================================================================
     // Index Operations
     // =========================================================================
@@ -189,11 +392,29 @@ pub trait StorageBackend: Send + Sync {
         index: &SessionIndex,
     ) -> Result<(), StorageError>;
 
+    /// One page of the tenant's index entries (see [`SessionPage`]). The
+    /// default loads the whole index; backends storing entries separately
+    /// override it to read only the page's.
+    async fn list_session_page(
+        &self,
+        tenant_id: &str,
+        query: &SessionPageQuery,
+    ) -> Result<SessionPage, StorageError> {
+        Ok(self.load_index(tenant_id).await?.unwrap_or_default().page(query))
+    }
+
     // =========================================================================
     // WAL Operations
     // =========================================================================
 
     /// Append entries to a session's WAL.
+    ///
+    /// Entries must be valid [`WalRecord`](crate::WalRecord)s (older versions
+    /// are upgraded); otherwise nothing is appended and `InvalidArgument` is
+    /// returned. Positions are checked with [`check_wal_positions`](crate::check_wal_positions):
+    /// entries at position 0 are numbered by the storage, the others must
+    /// continue the stored WAL or nothing is appended and `WalPosition` is
+    /// returned. Returns the position of the last entry appended.
     async fn append_wal(
         &self,
         tenant_id: &str,
@@ -210,6 +431,39 @@ pub trait StorageBackend: Send + Sync {
         limit: Option<u64>,
     ) -> Result<(Vec<WalEntry>, bool), StorageError>;
 
+    /// Read up to `limit` WAL entries before `before_position` (the newest
+    /// entries when `None`), newest first. Returns the entries and whether
+    /// older entries remain, so a UI can page backwards by passing the last
+    /// returned position as the next `before_position`.
+    async fn tail_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        before_position: Option<u64>,
+        limit: u64,
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        let mut entries = match before_position {
+            Some(before) => {
+                let start = before.saturating_sub(limit).max(1);
+                if before <= start {
+                    return Ok((vec![], false));
+                }
+                self.read_wal(tenant_id, session_id, start, Some(before - start))
+                    .await?
+                    .0
+            }
+            None => {
+                let (mut all, _) = self.read_wal(tenant_id, session_id, 0, None).await?;
+                let skip = all.len().saturating_sub(limit as usize);
+                all.drain(..skip);
+                all
+            }
+        };
+        let has_more = entries.first().is_some_and(|e| e.position > 1);
+        entries.reverse();
+        Ok((entries, has_more))
+    }
+
     /// Truncate WAL, keeping only the first N entries.
     /// - keep_count = 0: delete all entries
     /// - keep_count = N: keep entries with position <= N
@@ -233,6 +487,19 @@ pub trait StorageBackend: Send + Sync {
         data: &[u8],
     ) -> Result<(), StorageError>;
 
+    /// Save a checkpoint as it arrives, like [`Self::save_session_stream`].
+    async fn save_checkpoint_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let data = collect_chunks(chunks).await?;
+        self.save_checkpoint(tenant_id, session_id, position, &data).await?;
+        Ok(data.len() as u64)
+    }
+
     /// Load a checkpoint. If position is 0, load the latest.
     async fn load_checkpoint(
         &self,
@@ -247,4 +514,213 @@ pub trait StorageBackend: Send + Sync {
         tenant_id: &str,
         session_id: &str,
     ) -> Result<Vec<CheckpointInfo>, StorageError>;
+
+    // =========================================================================
+    // Library Operations
+    // =========================================================================
+
+    /// Save a library item (template or snippet), replacing any item of the
+    /// same kind and name.
+    async fn save_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError>;
+
+    /// Load a library item's DOCX bytes.
+    async fn load_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// List a tenant's library items of one kind, sorted by name.
+    async fn list_library_items(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+    ) -> Result<Vec<LibraryItemInfo>, StorageError>;
+
+    /// Delete a library item. Returns whether it existed.
+    async fn delete_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<bool, StorageError>;
+
+    // =========================================================================
+    // Tenant Operations
+    // =========================================================================
+
+    /// IDs of the tenants with sessions stored, for maintenance tasks that
+    /// visit every tenant. May include tenants that only have other data.
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Permanently delete everything stored for a tenant: sessions, WALs,
+    /// checkpoints, library items, the index and backend bookkeeping (locks,
+    /// queues). Returns the number of objects deleted. Callers go through
+    /// [`erase_tenant`](crate::erase_tenant), which guards this with a
+    /// confirmation token.
+    async fn erase_tenant(&self, tenant_id: &str) -> Result<u64, StorageError>;
+
+    /// Delete the tenant's shared media blobs that its stored sessions and
+    /// checkpoints no longer reference (see
+    /// [`delete_unreferenced_media`](crate::delete_unreferenced_media)).
+    /// Returns the number deleted. By default there are none: the backend
+    /// doesn't deduplicate media.
+    async fn sweep_media(&self, _tenant_id: &str) -> Result<u64, StorageError> {
+        Ok(0)
+    }
+
+    /// The session's history chain, oldest link first (see
+    /// [`prove_history`](crate::prove_history)). Empty when the backend
+    /// doesn't keep one.
+    async fn read_history(
+        &self,
+        _tenant_id: &str,
+        _session_id: &str,
+    ) -> Result<Vec<HistoryLink>, StorageError> {
+        Ok(Vec::new())
+    }
+
+    /// Append `entry` to the tenant's admin log (see
+    /// [`record_admin_action`](crate::record_admin_action)), numbering it
+    /// after the last entry. Returns its position; 0 when the backend
+    /// doesn't keep the log.
+    async fn append_admin_log(
+        &self,
+        _tenant_id: &str,
+        _entry: AdminLogEntry,
+    ) -> Result<u64, StorageError> {
+        Ok(0)
+    }
+
+    /// Up to `limit` entries of the tenant's admin log from `from_position`
+    /// on, oldest first. Empty when the backend doesn't keep the log.
+    async fn read_admin_log(
+        &self,
+        _tenant_id: &str,
+        _from_position: u64,
+        _limit: Option<u64>,
+    ) -> Result<Vec<AdminLogEntry>, StorageError> {
+        Ok(Vec::new())
+    }
+
+    /// The tenant's usage and the files crashes left behind (see
+    /// [`StorageReport`]), deleting those when `cleanup`. `None` when the
+    /// backend has no files of its own to report on.
+    async fn storage_report(
+        &self,
+        _tenant_id: &str,
+        _cleanup: bool,
+    ) -> Result<Option<StorageReport>, StorageError> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, alias: Option<&str>) -> SessionIndexEntry {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        SessionIndexEntry {
+            id: id.to_string(),
+            source_path: None,
+            auto_sync: true,
+            created_at: now,
+            last_modified_at: now,
+            docx_file: None,
+            wal_count: 0,
+            cursor_position: 0,
+            checkpoint_positions: vec![],
+            pending_external_change: false,
+            display_name: None,
+            alias: alias.map(str::to_string),
+            legal_hold: None,
+            expires_at: None,
+        }
+    }
+
+    fn index() -> SessionIndex {
+        let mut index = SessionIndex::default();
+        index.upsert(entry("s1", None));
+        index.upsert(entry("s2", Some("q3-board")));
+        index
+    }
+
+    #[test]
+    fn test_rename_sets_display_name_and_alias() {
+        let mut index = index();
+
+        assert!(index
+            .rename("s1", Some(" Annual report "), Some(" annual "))
+            .unwrap());
+
+        let renamed = index.get("s1").unwrap();
+        assert_eq!(renamed.display_name.as_deref(), Some("Annual report"));
+        assert_eq!(renamed.alias.as_deref(), Some("annual"));
+        assert_eq!(index.resolve("ANNUAL").unwrap().id, "s1");
+    }
+
+    #[test]
+    fn test_rename_refuses_an_alias_already_in_use() {
+        let mut index = index();
+
+        // Another session's alias, whatever its case
+        let result = index.rename("s1", Some("Board"), Some("Q3-Board"));
+        assert!(matches!(result, Err(StorageError::InvalidArgument(_))));
+        // Another session's ID
+        assert!(index.rename("s1", None, Some("S2")).is_err());
+        // An invalid one
+        assert!(index.rename("s1", None, Some("bad\nalias")).is_err());
+
+        // Nothing changed
+        let refused = index.get("s1").unwrap();
+        assert!(refused.display_name.is_none() && refused.alias.is_none());
+        assert_eq!(index.resolve("q3-board").unwrap().id, "s2");
+    }
+
+    #[test]
+    fn test_rename_keeps_a_sessions_own_alias() {
+        let mut index = index();
+
+        assert!(index.rename("s2", None, Some("Q3-BOARD")).unwrap());
+        assert_eq!(index.get("s2").unwrap().alias.as_deref(), Some("Q3-BOARD"));
+    }
+
+    #[test]
+    fn test_rename_clears_with_empty_strings_and_keeps_with_none() {
+        let mut index = index();
+        index.rename("s2", Some("Board"), None).unwrap();
+
+        index.rename("s2", None, Some("")).unwrap();
+
+        let entry = index.get("s2").unwrap();
+        assert_eq!(entry.display_name.as_deref(), Some("Board"));
+        assert!(entry.alias.is_none());
+        assert!(index.resolve("q3-board").is_none());
+    }
+
+    #[test]
+    fn test_rename_of_an_unknown_session_changes_nothing() {
+        let mut index = index();
+
+        assert!(!index.rename("missing", Some("x"), Some("x")).unwrap());
+        assert!(index.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_resolve_prefers_ids_over_aliases() {
+        let mut index = index();
+        index.upsert(entry("q3-board", None));
+
+        assert_eq!(index.resolve("q3-board").unwrap().id, "q3-board");
+        assert_eq!(index.resolve("s1").unwrap().id, "s1");
+        assert!(index.resolve("unknown").is_none());
+    }
 }
@@ -0,0 +1,82 @@
+//! What a tenant's files occupy, and the ones nothing uses any more.
+//!
+//! Crashes and interrupted writes leave files behind that no RPC ever reads
+//! again: the WAL or checkpoints of a session whose document and index entry
+//! are gone, or the temporary file a write was renaming into place. A
+//! [`StorageReport`] lists them next to the tenant's usage, so operators
+//! don't have to go through the data directory by hand.
+//!
+//! Only files untouched for [`STALE_FILE_AGE`] count as leftovers: a session
+//! is saved before it is indexed, and a temporary file is renamed away as
+//! soon as its write completes.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Files modified more recently than this may belong to a write in progress,
+/// and are never reported as leftovers.
+pub const STALE_FILE_AGE: chrono::Duration = chrono::Duration::hours(1);
+
+/// Files and bytes of one area of a tenant's storage (session documents,
+/// WALs, checkpoints, a library kind, ...).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StorageUsage {
+    pub area: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// A file of a tenant, by path relative to the tenant's storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredFile {
+    pub path: String,
+    pub bytes: u64,
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// Usage and leftover files of a tenant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StorageReport {
+    /// Usage by area, largest first
+    pub usage: Vec<StorageUsage>,
+    /// Session documents no index entry lists (RebuildIndex adds them back)
+    pub unindexed_sessions: Vec<String>,
+    /// WAL, checkpoint and other session files of sessions with neither a
+    /// document nor an index entry
+    pub orphaned_files: Vec<StoredFile>,
+    /// Temporary files left by interrupted writes
+    pub temp_files: Vec<StoredFile>,
+    /// Whether the orphaned and temporary files were deleted
+    pub cleaned: bool,
+}
+
+impl StorageReport {
+    /// Bytes of all the tenant's files.
+    pub fn total_bytes(&self) -> u64 {
+        self.usage.iter().map(|u| u.bytes).sum()
+    }
+
+    /// Bytes of the orphaned and temporary files.
+    pub fn leftover_bytes(&self) -> u64 {
+        self.orphaned_files
+            .iter()
+            .chain(&self.temp_files)
+            .map(|f| f.bytes)
+            .sum()
+    }
+
+    /// Count a file of `bytes` in `area`.
+    pub fn add_usage(&mut self, area: &str, bytes: u64) {
+        match self.usage.iter_mut().find(|u| u.area == area) {
+            Some(usage) => {
+                usage.files += 1;
+                usage.bytes += bytes;
+            }
+            None => self.usage.push(StorageUsage {
+                area: area.to_string(),
+                files: 1,
+                bytes,
+            }),
+        }
+    }
+}
@@ -2,11 +2,13 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::error::StorageError;
+use crate::sync_queue::PendingSync;
 
 /// Source types supported by the sync service.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SourceType {
+    #[default]
     LocalFile,
     SharePoint,
     OneDrive,
@@ -15,12 +17,6 @@ pub enum SourceType {
     GoogleDrive,
 }
 
-impl Default for SourceType {
-    fn default() -> Self {
-        Self::LocalFile
-    }
-}
-
 /// Typed descriptor for an external source.
 ///
 /// Resolution rule: for API operations, use `file_id` if non-empty, else `path`.
@@ -69,6 +65,50 @@ pub struct SyncStatus {
     pub last_error: Option<String>,
 }
 
+/// A file the tenant recently opened or synced, or pinned as a favorite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFile {
+    /// Where the file lives
+    pub source: SourceDescriptor,
+    /// File name, for display
+    pub display_name: String,
+    /// Unix timestamp of the last open or sync
+    pub last_used_at: i64,
+    /// Pinned as a favorite (favorites are never evicted)
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+/// Maximum number of non-favorite entries kept in a recent-files list.
+pub const MAX_RECENT_FILES: usize = 50;
+
+impl RecentFile {
+    /// Create an entry named after the last segment of the source path.
+    pub fn new(source: SourceDescriptor, last_used_at: i64) -> Self {
+        let display_name = source
+            .path
+            .rsplit(['/', '\\'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&source.path)
+            .to_string();
+        Self {
+            source,
+            display_name,
+            last_used_at,
+            favorite: false,
+        }
+    }
+
+    /// Whether this entry refers to the same file as `source`.
+    pub fn matches(&self, source: &SourceDescriptor) -> bool {
+        self.source.source_type == source.source_type
+            && self.source.connection_id.as_deref().unwrap_or_default()
+                == source.connection_id.as_deref().unwrap_or_default()
+            && self.source.effective_id() == source.effective_id()
+    }
+}
+
 /// Sync backend abstraction for syncing session changes to external sources.
 ///
 /// This handles the auto-save functionality for various source types:
@@ -147,4 +187,26 @@ pub trait SyncBackend: Send + Sync {
         tenant_id: &str,
         session_id: &str,
     ) -> Result<bool, StorageError>;
+
+    /// List the tenant's recent files (most recent first), favorites included.
+    ///
+    /// Files are recorded by `register_source` and `sync_to_source`.
+    async fn list_recent_files(&self, tenant_id: &str) -> Result<Vec<RecentFile>, StorageError>;
+
+    /// Pin or unpin a file as a favorite. Pinning a file that isn't in the
+    /// recent list adds it.
+    async fn pin_favorite(
+        &self,
+        tenant_id: &str,
+        source: SourceDescriptor,
+        pinned: bool,
+    ) -> Result<(), StorageError>;
+
+    /// List syncs waiting to be retried because the source was unreachable.
+    ///
+    /// Backends without a retry queue have none.
+    async fn list_pending_syncs(&self, tenant_id: &str) -> Result<Vec<PendingSync>, StorageError> {
+        let _ = tenant_id;
+        Ok(Vec::new())
+    }
 }
@@ -0,0 +1,926 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::error::StorageError;
+use crate::sync::{RecentFile, SourceDescriptor, SyncBackend, SyncStatus};
+use crate::validation::{tenant_dir, validate_session_id};
+
+/// Backoff and expiry of queued syncs.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncRetryPolicy {
+    /// Delay before the first retry
+    pub initial_backoff_secs: u64,
+    /// Cap on the delay between retries
+    pub max_backoff_secs: u64,
+    /// Queued syncs older than this are dropped
+    pub max_age_secs: u64,
+}
+
+impl Default for SyncRetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff_secs: 5,
+            max_backoff_secs: 15 * 60,
+            max_age_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+impl SyncRetryPolicy {
+    /// Delay before the next retry after `attempts` failed attempts.
+    pub fn backoff_secs(&self, attempts: u32) -> u64 {
+        let exponent = attempts.saturating_sub(1).min(32);
+        self.initial_backoff_secs
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_backoff_secs)
+    }
+
+    /// Time of the next retry after `attempts` failed attempts, no sooner
+    /// than the source asked for.
+    fn next_attempt_at(&self, now: i64, attempts: u32, retry_after: Option<Duration>) -> i64 {
+        let delay = self
+            .backoff_secs(attempts)
+            .max(retry_after.map_or(0, |d| d.as_secs()));
+        now + delay as i64
+    }
+
+    fn is_expired(&self, pending: &PendingSync, now: i64) -> bool {
+        now - pending.queued_at > self.max_age_secs as i64
+    }
+}
+
+/// A sync that failed because the source was unreachable, waiting for a retry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSync {
+    /// Session ID
+    pub session_id: String,
+    /// Where the document goes
+    pub source: SourceDescriptor,
+    /// Unix timestamp of the first failed attempt (kept when newer data replaces the payload)
+    pub queued_at: i64,
+    /// Failed attempts so far
+    pub attempts: u32,
+    /// Unix timestamp of the next retry
+    pub next_attempt_at: i64,
+    /// Error from the last attempt
+    pub last_error: String,
+    /// Size of the queued document
+    pub size_bytes: u64,
+}
+
+/// Persistence for queued syncs: one entry (metadata + document) per session.
+#[async_trait]
+pub trait SyncQueueStore: Send + Sync {
+    /// Save an entry. `data` replaces the queued document; `None` keeps it.
+    async fn put(
+        &self,
+        tenant_id: &str,
+        pending: &PendingSync,
+        data: Option<&[u8]>,
+    ) -> Result<(), StorageError>;
+
+    /// Get a session's entry.
+    async fn get(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<PendingSync>, StorageError>;
+
+    /// Get a session's queued document.
+    async fn load_data(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// List a tenant's entries.
+    async fn list(&self, tenant_id: &str) -> Result<Vec<PendingSync>, StorageError>;
+
+    /// List tenants that have entries.
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Remove a session's entry (no-op if absent).
+    async fn remove(&self, tenant_id: &str, session_id: &str) -> Result<(), StorageError>;
+}
+
+/// Sync queue kept on disk, next to the session storage.
+///
+/// Layout:
+/// ```text
+/// {base_dir}/{tenant_id}/sync_queue/{session_id}.json  # PendingSync
+/// {base_dir}/{tenant_id}/sync_queue/{session_id}.docx  # queued document
+/// ```
+pub struct FileSyncQueueStore {
+    base_dir: PathBuf,
+}
+
+impl FileSyncQueueStore {
+    pub fn new(base_dir: impl AsRef<Path>) -> Self {
+        Self {
+            base_dir: base_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn queue_dir(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join("sync_queue"))
+    }
+
+    fn entry_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self.queue_dir(tenant_id)?.join(format!("{}.json", session_id)))
+    }
+
+    fn data_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self.queue_dir(tenant_id)?.join(format!("{}.docx", session_id)))
+    }
+
+    fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), StorageError> {
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        std::fs::write(&temp_path, contents).map_err(|e| {
+            StorageError::Io(format!("Failed to write {}: {}", temp_path.display(), e))
+        })?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| StorageError::Io(format!("Failed to rename to {}: {}", path.display(), e)))
+    }
+
+    fn read_entry(path: &Path) -> Result<Option<PendingSync>, StorageError> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map(Some).map_err(|e| {
+                StorageError::Serialization(format!(
+                    "Failed to parse queued sync {}: {}",
+                    path.display(),
+                    e
+                ))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to read queued sync {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl SyncQueueStore for FileSyncQueueStore {
+    async fn put(
+        &self,
+        tenant_id: &str,
+        pending: &PendingSync,
+        data: Option<&[u8]>,
+    ) -> Result<(), StorageError> {
+        let dir = self.queue_dir(tenant_id)?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| StorageError::Io(format!("Failed to create {}: {}", dir.display(), e)))?;
+
+        // Document first, so an entry never points at a missing payload
+        if let Some(data) = data {
+            Self::write_atomic(&self.data_path(tenant_id, &pending.session_id)?, data)?;
+        }
+
+        let json = serde_json::to_vec_pretty(pending).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize queued sync: {}", e))
+        })?;
+        Self::write_atomic(&self.entry_path(tenant_id, &pending.session_id)?, &json)
+    }
+
+    async fn get(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<PendingSync>, StorageError> {
+        Self::read_entry(&self.entry_path(tenant_id, session_id)?)
+    }
+
+    async fn load_data(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let path = self.data_path(tenant_id, session_id)?;
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    async fn list(&self, tenant_id: &str) -> Result<Vec<PendingSync>, StorageError> {
+        let dir = self.queue_dir(tenant_id)?;
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(StorageError::Io(format!(
+                    "Failed to list {}: {}",
+                    dir.display(),
+                    e
+                )))
+            }
+        };
+
+        let mut pending = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match Self::read_entry(&path) {
+                    Ok(Some(p)) => pending.push(p),
+                    Ok(None) => {}
+                    Err(e) => warn!("Skipping queued sync: {}", e),
+                }
+            }
+        }
+        pending.sort_by_key(|p| p.queued_at);
+        Ok(pending)
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError> {
+        let entries = match std::fs::read_dir(&self.base_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(StorageError::Io(format!(
+                    "Failed to list {}: {}",
+                    self.base_dir.display(),
+                    e
+                )))
+            }
+        };
+
+        Ok(entries
+            .flatten()
+            .filter(|e| e.path().join("sync_queue").is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect())
+    }
+
+    async fn remove(&self, tenant_id: &str, session_id: &str) -> Result<(), StorageError> {
+        for path in [
+            self.entry_path(tenant_id, session_id)?,
+            self.data_path(tenant_id, session_id)?,
+        ] {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(StorageError::Io(format!(
+                        "Failed to remove {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Message of a retryable failure, without the variant's prefix.
+fn retry_message(e: &StorageError) -> String {
+    match e {
+        StorageError::Unavailable(msg) => msg.clone(),
+        StorageError::Throttled { message, .. } => message.clone(),
+        e => e.to_string(),
+    }
+}
+
+type SessionLock = Arc<futures::lock::Mutex<()>>;
+
+/// Sync backend that queues syncs failing with a retryable error
+/// ([`StorageError::Unavailable`] or [`StorageError::Throttled`])
+/// and retries them with exponential backoff.
+///
+/// - `sync_to_source` always tries the source first. When it is unreachable
+///   the document is queued (replacing any older queued version) and the
+///   `Unavailable` error is returned with a "queued for retry" note.
+/// - `retry_due` must be called periodically; it re-sends queued documents
+///   whose backoff has elapsed and drops those older than the max age or
+///   failing with a non-transient error.
+/// - Sync status reports queued sessions as having pending changes.
+pub struct RetryingSyncBackend {
+    inner: Arc<dyn SyncBackend>,
+    queue: Arc<dyn SyncQueueStore>,
+    policy: SyncRetryPolicy,
+    /// (tenant_id, session_id) → lock ordering direct syncs and retries,
+    /// so a retry never overwrites a newer document.
+    session_locks: Mutex<HashMap<(String, String), SessionLock>>,
+}
+
+impl RetryingSyncBackend {
+    pub fn new(
+        inner: Arc<dyn SyncBackend>,
+        queue: Arc<dyn SyncQueueStore>,
+        policy: SyncRetryPolicy,
+    ) -> Self {
+        Self {
+            inner,
+            queue,
+            policy,
+            session_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn session_lock(&self, tenant_id: &str, session_id: &str) -> SessionLock {
+        self.session_locks
+            .lock()
+            .unwrap()
+            .entry((tenant_id.to_string(), session_id.to_string()))
+            .or_default()
+            .clone()
+    }
+
+    /// Retry every queued sync whose backoff has elapsed.
+    ///
+    /// Returns the number of syncs that completed.
+    pub async fn retry_due(&self) -> Result<usize, StorageError> {
+        let mut completed = 0;
+
+        for tenant_id in self.queue.list_tenants().await? {
+            let now = chrono::Utc::now().timestamp();
+            for pending in self.queue.list(&tenant_id).await? {
+                if pending.next_attempt_at > now {
+                    continue;
+                }
+                if self.retry(&tenant_id, &pending.session_id, now).await? {
+                    completed += 1;
+                }
+            }
+        }
+
+        Ok(completed)
+    }
+
+    /// Retry one queued sync. Returns `true` if it completed.
+    async fn retry(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        now: i64,
+    ) -> Result<bool, StorageError> {
+        let lock = self.session_lock(tenant_id, session_id);
+        let _guard = lock.lock().await;
+
+        // A direct sync may have landed (or re-queued) while we waited
+        let Some(mut pending) = self.queue.get(tenant_id, session_id).await? else {
+            return Ok(false);
+        };
+
+        if self.policy.is_expired(&pending, now) {
+            warn!(
+                "Dropping sync for tenant {} session {} queued at {} after {} attempts: {}",
+                tenant_id, session_id, pending.queued_at, pending.attempts, pending.last_error
+            );
+            self.queue.remove(tenant_id, session_id).await?;
+            return Ok(false);
+        }
+
+        let Some(data) = self.queue.load_data(tenant_id, session_id).await? else {
+            warn!(
+                "Dropping sync for tenant {} session {}: queued document is missing",
+                tenant_id, session_id
+            );
+            self.queue.remove(tenant_id, session_id).await?;
+            return Ok(false);
+        };
+
+        // The source registration may be gone (session closed, server restarted)
+        let reregistered = self
+            .inner
+            .get_sync_status(tenant_id, session_id)
+            .await?
+            .is_none();
+        if reregistered {
+            self.inner
+                .register_source(tenant_id, session_id, pending.source.clone(), false)
+                .await?;
+        }
+
+        let result = self
+            .inner
+            .sync_to_source(tenant_id, session_id, &data)
+            .await;
+
+        if reregistered {
+            if let Err(e) = self.inner.unregister_source(tenant_id, session_id).await {
+                warn!(
+                    "Failed to unregister source for tenant {} session {}: {}",
+                    tenant_id, session_id, e
+                );
+            }
+        }
+
+        match result {
+            Ok(_) => {
+                info!(
+                    "Completed queued sync for tenant {} session {} after {} attempts",
+                    tenant_id, session_id, pending.attempts
+                );
+                self.queue.remove(tenant_id, session_id).await?;
+                Ok(true)
+            }
+            Err(e) if e.is_retryable() => {
+                pending.attempts += 1;
+                pending.next_attempt_at =
+                    self.policy.next_attempt_at(now, pending.attempts, e.retry_after());
+                pending.last_error = retry_message(&e);
+                debug!(
+                    "Queued sync for tenant {} session {} still failing, next attempt at {}",
+                    tenant_id, session_id, pending.next_attempt_at
+                );
+                self.queue.put(tenant_id, &pending, None).await?;
+                Ok(false)
+            }
+            Err(e) => {
+                warn!(
+                    "Dropping sync for tenant {} session {}: {}",
+                    tenant_id, session_id, e
+                );
+                self.queue.remove(tenant_id, session_id).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Mark a status as having changes waiting in the queue.
+    fn with_pending(mut status: SyncStatus, pending: Option<&PendingSync>) -> SyncStatus {
+        if let Some(pending) = pending {
+            status.has_pending_changes = true;
+            status.last_error = Some(pending.last_error.clone());
+        }
+        status
+    }
+}
+
+#[async_trait]
+impl SyncBackend for RetryingSyncBackend {
+    async fn register_source(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        source: SourceDescriptor,
+        auto_sync: bool,
+    ) -> Result<(), StorageError> {
+        self.inner
+            .register_source(tenant_id, session_id, source, auto_sync)
+            .await
+    }
+
+    async fn unregister_source(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<(), StorageError> {
+        // Queued syncs are kept: they still carry the session's last save
+        self.session_locks
+            .lock()
+            .unwrap()
+            .remove(&(tenant_id.to_string(), session_id.to_string()));
+        self.inner.unregister_source(tenant_id, session_id).await
+    }
+
+    async fn update_source(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        source: Option<SourceDescriptor>,
+        auto_sync: Option<bool>,
+    ) -> Result<(), StorageError> {
+        self.inner
+            .update_source(tenant_id, session_id, source, auto_sync)
+            .await
+    }
+
+    async fn sync_to_source(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        data: &[u8],
+    ) -> Result<i64, StorageError> {
+        let lock = self.session_lock(tenant_id, session_id);
+        let _guard = lock.lock().await;
+
+        let failure = match self.inner.sync_to_source(tenant_id, session_id, data).await {
+            Ok(synced_at) => {
+                // This document supersedes anything still queued
+                if let Err(e) = self.queue.remove(tenant_id, session_id).await {
+                    warn!(
+                        "Failed to clear queued sync for tenant {} session {}: {}",
+                        tenant_id, session_id, e
+                    );
+                }
+                return Ok(synced_at);
+            }
+            Err(e) if e.is_retryable() => e,
+            Err(e) => return Err(e),
+        };
+        let msg = retry_message(&failure);
+
+        let Some(status) = self.inner.get_sync_status(tenant_id, session_id).await? else {
+            return Err(failure);
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let previous = self.queue.get(tenant_id, session_id).await.ok().flatten();
+        let attempts = previous.as_ref().map_or(0, |p| p.attempts) + 1;
+        let pending = PendingSync {
+            session_id: session_id.to_string(),
+            source: status.source,
+            queued_at: previous.map_or(now, |p| p.queued_at),
+            attempts,
+            next_attempt_at: self
+                .policy
+                .next_attempt_at(now, attempts, failure.retry_after()),
+            last_error: msg.clone(),
+            size_bytes: data.len() as u64,
+        };
+
+        match self.queue.put(tenant_id, &pending, Some(data)).await {
+            Ok(()) => {
+                debug!(
+                    "Queued sync for tenant {} session {}, next attempt at {}",
+                    tenant_id, session_id, pending.next_attempt_at
+                );
+                Err(StorageError::Unavailable(format!(
+                    "{}; queued for retry",
+                    msg
+                )))
+            }
+            Err(e) => Err(StorageError::Sync(format!(
+                "{}; failed to queue for retry: {}",
+                msg, e
+            ))),
+        }
+    }
+
+    async fn get_sync_status(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<SyncStatus>, StorageError> {
+        let status = self.inner.get_sync_status(tenant_id, session_id).await?;
+        let Some(status) = status else {
+            return Ok(None);
+        };
+        let pending = self.queue.get(tenant_id, session_id).await?;
+        Ok(Some(Self::with_pending(status, pending.as_ref())))
+    }
+
+    async fn list_sources(&self, tenant_id: &str) -> Result<Vec<SyncStatus>, StorageError> {
+        let pending = self.queue.list(tenant_id).await?;
+        Ok(self
+            .inner
+            .list_sources(tenant_id)
+            .await?
+            .into_iter()
+            .map(|status| {
+                let queued = pending.iter().find(|p| p.session_id == status.session_id);
+                Self::with_pending(status, queued)
+            })
+            .collect())
+    }
+
+    async fn is_auto_sync_enabled(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<bool, StorageError> {
+        self.inner.is_auto_sync_enabled(tenant_id, session_id).await
+    }
+
+    async fn list_recent_files(&self, tenant_id: &str) -> Result<Vec<RecentFile>, StorageError> {
+        self.inner.list_recent_files(tenant_id).await
+    }
+
+    async fn pin_favorite(
+        &self,
+        tenant_id: &str,
+        source: SourceDescriptor,
+        pinned: bool,
+    ) -> Result<(), StorageError> {
+        self.inner.pin_favorite(tenant_id, source, pinned).await
+    }
+
+    async fn list_pending_syncs(&self, tenant_id: &str) -> Result<Vec<PendingSync>, StorageError> {
+        self.queue.list(tenant_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::SourceType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let policy = SyncRetryPolicy {
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+            max_age_secs: 3600,
+        };
+        let delays: Vec<u64> = (1..=6)
+            .map(|attempts| policy.backoff_secs(attempts))
+            .collect();
+        assert_eq!(delays, [5, 10, 20, 40, 60, 60]);
+        assert_eq!(policy.backoff_secs(0), 5);
+        assert_eq!(policy.backoff_secs(u32::MAX), 60);
+
+        // A source asking for longer is obeyed
+        assert_eq!(policy.next_attempt_at(1000, 1, None), 1005);
+        assert_eq!(
+            policy.next_attempt_at(1000, 1, Some(Duration::from_secs(90))),
+            1090
+        );
+    }
+
+    fn source() -> SourceDescriptor {
+        SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: "/docs/report.docx".to_string(),
+            file_id: None,
+        }
+    }
+
+    fn pending(session_id: &str, queued_at: i64) -> PendingSync {
+        PendingSync {
+            session_id: session_id.to_string(),
+            source: source(),
+            queued_at,
+            attempts: 1,
+            next_attempt_at: queued_at,
+            last_error: "offline".to_string(),
+            size_bytes: 4,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store = FileSyncQueueStore::new(dir.path());
+        assert!(store.list_tenants().await.unwrap().is_empty());
+
+        store
+            .put("acme", &pending("s2", 20), Some(b"two"))
+            .await
+            .unwrap();
+        store
+            .put("acme", &pending("s1", 10), Some(b"one"))
+            .await
+            .unwrap();
+        // Metadata only: the queued document is kept
+        let mut retried = pending("s1", 10);
+        retried.attempts = 2;
+        store.put("acme", &retried, None).await.unwrap();
+
+        assert_eq!(store.list_tenants().await.unwrap(), ["acme"]);
+        let listed = store.list("acme").await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|p| p.session_id.as_str())
+                .collect::<Vec<_>>(),
+            ["s1", "s2"]
+        );
+        assert_eq!(store.get("acme", "s1").await.unwrap().unwrap().attempts, 2);
+        assert_eq!(
+            store.load_data("acme", "s1").await.unwrap().unwrap(),
+            b"one"
+        );
+
+        store.remove("acme", "s1").await.unwrap();
+        store.remove("acme", "s1").await.unwrap();
+        assert!(store.get("acme", "s1").await.unwrap().is_none());
+        assert!(store.load_data("acme", "s1").await.unwrap().is_none());
+        assert!(store.get("acme", "../s1").await.is_err());
+    }
+
+    /// Sync backend whose source is reachable only when `online`.
+    #[derive(Default)]
+    struct FlakySource {
+        online: std::sync::atomic::AtomicBool,
+        registered: Mutex<HashMap<String, SourceDescriptor>>,
+        synced: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl SyncBackend for FlakySource {
+        async fn register_source(
+            &self,
+            _tenant_id: &str,
+            session_id: &str,
+            source: SourceDescriptor,
+            _auto_sync: bool,
+        ) -> Result<(), StorageError> {
+            self.registered
+                .lock()
+                .unwrap()
+                .insert(session_id.to_string(), source);
+            Ok(())
+        }
+
+        async fn unregister_source(
+            &self,
+            _tenant_id: &str,
+            session_id: &str,
+        ) -> Result<(), StorageError> {
+            self.registered.lock().unwrap().remove(session_id);
+            Ok(())
+        }
+
+        async fn update_source(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            _source: Option<SourceDescriptor>,
+            _auto_sync: Option<bool>,
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn sync_to_source(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+            data: &[u8],
+        ) -> Result<i64, StorageError> {
+            if !self.online.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(StorageError::Unavailable("source offline".to_string()));
+            }
+            self.synced.lock().unwrap().push(data.to_vec());
+            Ok(1)
+        }
+
+        async fn get_sync_status(
+            &self,
+            _tenant_id: &str,
+            session_id: &str,
+        ) -> Result<Option<SyncStatus>, StorageError> {
+            Ok(self
+                .registered
+                .lock()
+                .unwrap()
+                .get(session_id)
+                .map(|source| SyncStatus {
+                    session_id: session_id.to_string(),
+                    source: source.clone(),
+                    auto_sync_enabled: false,
+                    last_synced_at: None,
+                    has_pending_changes: false,
+                    last_error: None,
+                }))
+        }
+
+        async fn list_sources(&self, _tenant_id: &str) -> Result<Vec<SyncStatus>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn is_auto_sync_enabled(
+            &self,
+            _tenant_id: &str,
+            _session_id: &str,
+        ) -> Result<bool, StorageError> {
+            Ok(false)
+        }
+
+        async fn list_recent_files(
+            &self,
+            _tenant_id: &str,
+        ) -> Result<Vec<RecentFile>, StorageError> {
+            Ok(Vec::new())
+        }
+
+        async fn pin_favorite(
+            &self,
+            _tenant_id: &str,
+            _source: SourceDescriptor,
+            _pinned: bool,
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+    }
+
+    fn retrying(
+        dir: &TempDir,
+        policy: SyncRetryPolicy,
+    ) -> (
+        Arc<FlakySource>,
+        Arc<FileSyncQueueStore>,
+        RetryingSyncBackend,
+    ) {
+        let source = Arc::new(FlakySource::default());
+        let queue = Arc::new(FileSyncQueueStore::new(dir.path()));
+        let backend = RetryingSyncBackend::new(source.clone(), queue.clone(), policy);
+        (source, queue, backend)
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_source_is_queued_then_retried() {
+        let dir = TempDir::new().unwrap();
+        let (flaky, queue, backend) = retrying(
+            &dir,
+            SyncRetryPolicy {
+                initial_backoff_secs: 0,
+                ..Default::default()
+            },
+        );
+        backend
+            .register_source("acme", "s1", source(), false)
+            .await
+            .unwrap();
+
+        let err = backend
+            .sync_to_source("acme", "s1", b"v1")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, StorageError::Unavailable(ref msg) if msg.ends_with("queued for retry"))
+        );
+        // A newer save replaces the queued document but keeps the first failure time
+        let first = queue.get("acme", "s1").await.unwrap().unwrap();
+        backend
+            .sync_to_source("acme", "s1", b"v2")
+            .await
+            .unwrap_err();
+        let queued = queue.get("acme", "s1").await.unwrap().unwrap();
+        assert_eq!((queued.attempts, queued.queued_at), (2, first.queued_at));
+        assert_eq!(queue.load_data("acme", "s1").await.unwrap().unwrap(), b"v2");
+
+        let status = backend
+            .get_sync_status("acme", "s1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(status.has_pending_changes);
+        assert_eq!(status.last_error.as_deref(), Some("source offline"));
+
+        // Still offline: stays queued with one more attempt
+        assert_eq!(backend.retry_due().await.unwrap(), 0);
+        assert_eq!(queue.get("acme", "s1").await.unwrap().unwrap().attempts, 3);
+
+        // Back online, even after the session closed: sent and dequeued
+        backend.unregister_source("acme", "s1").await.unwrap();
+        flaky
+            .online
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(backend.retry_due().await.unwrap(), 1);
+        assert_eq!(*flaky.synced.lock().unwrap(), [b"v2".to_vec()]);
+        assert!(queue.get("acme", "s1").await.unwrap().is_none());
+        assert!(flaky.registered.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retries_wait_for_backoff_and_expire() {
+        let dir = TempDir::new().unwrap();
+        let (flaky, queue, backend) = retrying(
+            &dir,
+            SyncRetryPolicy {
+                initial_backoff_secs: 3600,
+                max_backoff_secs: 3600,
+                max_age_secs: 60,
+            },
+        );
+        flaky
+            .online
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let now = chrono::Utc::now().timestamp();
+
+        let mut not_due = pending("later", now);
+        not_due.next_attempt_at = now + 3600;
+        queue.put("acme", &not_due, Some(b"data")).await.unwrap();
+        queue
+            .put("acme", &pending("stale", now - 120), Some(b"data"))
+            .await
+            .unwrap();
+
+        assert_eq!(backend.retry_due().await.unwrap(), 0);
+        assert!(queue.get("acme", "later").await.unwrap().is_some());
+        assert!(queue.get("acme", "stale").await.unwrap().is_none());
+        assert!(flaky.synced.lock().unwrap().is_empty());
+
+        // A direct sync supersedes what is queued
+        backend
+            .register_source("acme", "later", source(), false)
+            .await
+            .unwrap();
+        backend
+            .sync_to_source("acme", "later", b"fresh")
+            .await
+            .unwrap();
+        assert!(queue.get("acme", "later").await.unwrap().is_none());
+    }
+}
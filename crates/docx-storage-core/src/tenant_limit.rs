@@ -0,0 +1,456 @@
+//! Per-tenant caps on concurrent gRPC requests.
+//!
+//! Tenants share a storage process: without a cap, one tenant saving a burst
+//! of checkpoints takes every blocking thread and backend connection, and
+//! everybody else's requests wait behind it. [`TenantLimitLayer`] lets each
+//! tenant (from the `x-tenant-id` header) run at most
+//! [`TenantLimits::max_in_flight`] requests at once. Extra requests wait in a
+//! short per-tenant queue; when that queue is full, or a request waited
+//! [`TenantLimits::queue_timeout`] (or its deadline) without getting a slot,
+//! it fails with `RESOURCE_EXHAUSTED` and the same retry hints as
+//! [`StorageError::Throttled`], so clients back off instead of piling on.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+use crate::deadline::time_remaining;
+use crate::error::StorageError;
+use crate::logging::TENANT_ID_HEADER;
+
+/// How long rejected clients are told to wait before retrying.
+pub const OVERLOAD_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// gRPC status code `RESOURCE_EXHAUSTED`.
+const GRPC_RESOURCE_EXHAUSTED: &str = "8";
+
+/// Concurrency limits applied to each tenant separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TenantLimits {
+    /// Requests of one tenant handled at once (0 = unlimited).
+    pub max_in_flight: usize,
+    /// Requests of one tenant waiting for a slot; more are rejected.
+    pub max_queued: usize,
+    /// Longest wait for a slot.
+    pub queue_timeout: Duration,
+}
+
+impl Default for TenantLimits {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 16,
+            max_queued: 64,
+            queue_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Slots of one tenant.
+struct TenantSlots {
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+}
+
+/// Request counts across tenants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantLimitStats {
+    /// Tenants with requests in flight or queued.
+    pub tenants: usize,
+    /// Requests waiting for a slot.
+    pub queued: usize,
+    /// Requests rejected since startup.
+    pub rejected: u64,
+}
+
+/// Why a request didn't get a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overload {
+    /// The tenant's queue was full.
+    QueueFull,
+    /// No slot freed up in time.
+    TimedOut,
+}
+
+/// A request slot, released on drop.
+pub struct TenantPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+    _slots: Option<Arc<TenantSlots>>,
+}
+
+/// Per-tenant request slots and queues, shared by every service of a server.
+pub struct TenantLimiter {
+    limits: TenantLimits,
+    tenants: Mutex<HashMap<String, Arc<TenantSlots>>>,
+    rejected: AtomicU64,
+}
+
+impl TenantLimiter {
+    pub fn new(limits: TenantLimits) -> Self {
+        Self {
+            limits,
+            tenants: Mutex::new(HashMap::new()),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    pub fn limits(&self) -> TenantLimits {
+        self.limits
+    }
+
+    /// Wait for one of `tenant_id`'s slots, queueing behind its other
+    /// requests. Gives up when the queue is full, after the queue timeout,
+    /// or when the caller's deadline (see [`time_remaining`]) comes first.
+    pub async fn acquire(&self, tenant_id: &str) -> Result<TenantPermit, Overload> {
+        if self.limits.max_in_flight == 0 {
+            return Ok(TenantPermit {
+                _permit: None,
+                _slots: None,
+            });
+        }
+
+        let slots = self.slots(tenant_id);
+        if let Ok(permit) = slots.semaphore.clone().try_acquire_owned() {
+            return Ok(TenantPermit {
+                _permit: Some(permit),
+                _slots: Some(slots),
+            });
+        }
+
+        if slots.queued.fetch_add(1, Ordering::AcqRel) >= self.limits.max_queued {
+            slots.queued.fetch_sub(1, Ordering::AcqRel);
+            return Err(self.reject(tenant_id, Overload::QueueFull));
+        }
+        let wait = match time_remaining() {
+            Some(remaining) => remaining.min(self.limits.queue_timeout),
+            None => self.limits.queue_timeout,
+        };
+        let acquired =
+            tokio::time::timeout(wait, slots.semaphore.clone().acquire_owned()).await;
+        slots.queued.fetch_sub(1, Ordering::AcqRel);
+
+        match acquired {
+            // The semaphore is never closed
+            Ok(Ok(permit)) => Ok(TenantPermit {
+                _permit: Some(permit),
+                _slots: Some(slots),
+            }),
+            _ => Err(self.reject(tenant_id, Overload::TimedOut)),
+        }
+    }
+
+    fn slots(&self, tenant_id: &str) -> Arc<TenantSlots> {
+        let mut tenants = self.tenants.lock().unwrap();
+        if let Some(slots) = tenants.get(tenant_id) {
+            return slots.clone();
+        }
+        // Forget tenants with nothing in flight or queued
+        tenants.retain(|_, slots| Arc::strong_count(slots) > 1);
+        let slots = Arc::new(TenantSlots {
+            semaphore: Arc::new(Semaphore::new(self.limits.max_in_flight)),
+            queued: AtomicUsize::new(0),
+        });
+        tenants.insert(tenant_id.to_string(), slots.clone());
+        slots
+    }
+
+    fn reject(&self, tenant_id: &str, overload: Overload) -> Overload {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            "Rejecting request of tenant '{}': {:?} ({} in flight allowed, {} queued)",
+            tenant_id, overload, self.limits.max_in_flight, self.limits.max_queued
+        );
+        overload
+    }
+
+    /// Current queue depth.
+    pub fn stats(&self) -> TenantLimitStats {
+        let tenants = self.tenants.lock().unwrap();
+        let mut stats = TenantLimitStats {
+            rejected: self.rejected.load(Ordering::Relaxed),
+            ..Default::default()
+        };
+        for slots in tenants.values().filter(|s| Arc::strong_count(s) > 1) {
+            stats.tenants += 1;
+            stats.queued += slots.queued.load(Ordering::Acquire);
+        }
+        stats
+    }
+}
+
+impl Overload {
+    /// The error reported to the client.
+    pub fn to_error(self, limits: &TenantLimits) -> StorageError {
+        let message = match self {
+            Overload::QueueFull => format!(
+                "Too many concurrent requests for this tenant ({} running, {} queued)",
+                limits.max_in_flight, limits.max_queued
+            ),
+            Overload::TimedOut => format!(
+                "No request slot for this tenant freed up within {:?}",
+                limits.queue_timeout
+            ),
+        };
+        StorageError::Throttled {
+            message,
+            retry_after: Some(OVERLOAD_RETRY_AFTER),
+        }
+    }
+}
+
+/// Tower layer applying [`TenantLimits`] to gRPC requests, by their
+/// `x-tenant-id` header (requests without one share the empty tenant).
+///
+/// A request holds its slot until its response starts; a streamed response
+/// is not counted while it streams. Add it inside [`DeadlineLayer`] so
+/// queued requests give up at their deadline.
+///
+/// [`DeadlineLayer`]: crate::DeadlineLayer
+#[derive(Clone)]
+pub struct TenantLimitLayer {
+    limiter: Arc<TenantLimiter>,
+}
+
+impl TenantLimitLayer {
+    pub fn new(limiter: Arc<TenantLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> tower::Layer<S> for TenantLimitLayer {
+    type Service = TenantLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Service produced by [`TenantLimitLayer`].
+#[derive(Clone)]
+pub struct TenantLimitService<S> {
+    inner: S,
+    limiter: Arc<TenantLimiter>,
+}
+
+impl<S, B, R> tower::Service<http::Request<B>> for TenantLimitService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<R>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+    R: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let tenant_id = request
+            .headers()
+            .get(TENANT_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        // Call the service that was polled ready, leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            match limiter.acquire(&tenant_id).await {
+                Ok(_permit) => inner.call(request).await,
+                Err(overload) => Ok(overloaded_response(
+                    &overload.to_error(&limiter.limits()),
+                )),
+            }
+        })
+    }
+}
+
+/// A trailers-only gRPC response failing with `RESOURCE_EXHAUSTED`.
+fn overloaded_response<R: Default>(error: &StorageError) -> http::Response<R> {
+    let mut response = http::Response::new(R::default());
+    let headers = response.headers_mut();
+    headers.insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("application/grpc"),
+    );
+    headers.insert(
+        "grpc-status",
+        http::HeaderValue::from_static(GRPC_RESOURCE_EXHAUSTED),
+    );
+    // grpc-message is percent-encoded; the messages above are plain ASCII
+    if let Ok(message) = http::HeaderValue::from_str(&error.to_string()) {
+        headers.insert("grpc-message", message);
+    }
+    for (key, value) in error.metadata() {
+        if let Ok(value) = http::HeaderValue::from_str(&value) {
+            headers.insert(key, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tenants_are_limited_separately() {
+        let limiter = TenantLimiter::new(TenantLimits {
+            max_in_flight: 1,
+            max_queued: 1,
+            queue_timeout: Duration::from_secs(2),
+        });
+
+        let busy = limiter.acquire("t1").await.unwrap();
+        // Another tenant is not held up
+        let _other = limiter.acquire("t2").await.unwrap();
+
+        let queued = limiter.acquire("t1");
+        let full = limiter.acquire("t1");
+        let (queued, full) = tokio::join!(
+            async {
+                let result = queued.await;
+                assert_eq!(limiter.stats().queued, 0);
+                result
+            },
+            async {
+                tokio::task::yield_now().await;
+                assert_eq!(limiter.stats().queued, 1);
+                let result = full.await;
+                drop(busy);
+                result
+            }
+        );
+        assert!(queued.is_ok());
+        assert_eq!(full.err(), Some(Overload::QueueFull));
+
+        // While the slot is taken, a queued request times out
+        let _taken = queued.unwrap();
+        let started = tokio::time::Instant::now();
+        assert_eq!(limiter.acquire("t1").await.err(), Some(Overload::TimedOut));
+        assert!(started.elapsed() >= Duration::from_secs(2));
+        assert_eq!(limiter.stats().rejected, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_ends_at_the_callers_deadline() {
+        let limiter = TenantLimiter::new(TenantLimits {
+            max_in_flight: 1,
+            max_queued: 1,
+            queue_timeout: Duration::from_secs(5),
+        });
+        let _busy = limiter.acquire("t1").await.unwrap();
+
+        let started = tokio::time::Instant::now();
+        let deadline = Some(started + Duration::from_millis(300));
+        let result = crate::deadline::with_deadline(deadline, limiter.acquire("t1")).await;
+        assert_eq!(result.err(), Some(Overload::TimedOut));
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_and_idle_tenants() {
+        let unlimited = TenantLimiter::new(TenantLimits {
+            max_in_flight: 0,
+            ..Default::default()
+        });
+        let permits: Vec<_> =
+            futures::future::join_all((0..100).map(|_| unlimited.acquire("t1"))).await;
+        assert!(permits.iter().all(Result::is_ok));
+        assert_eq!(unlimited.stats(), TenantLimitStats::default());
+
+        // Tenants are forgotten once their requests are done
+        let limiter = TenantLimiter::new(TenantLimits::default());
+        let permit = limiter.acquire("t1").await.unwrap();
+        assert_eq!(limiter.stats().tenants, 1);
+        drop(permit);
+        let _other = limiter.acquire("t2").await.unwrap();
+        assert_eq!(limiter.stats().tenants, 1);
+        assert_eq!(limiter.tenants.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_overload_errors_ask_clients_to_back_off() {
+        let limits = TenantLimits::default();
+        let error = Overload::QueueFull.to_error(&limits);
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(OVERLOAD_RETRY_AFTER));
+        assert_eq!(
+            error.to_string(),
+            StorageError::Throttled {
+                message: "Too many concurrent requests for this tenant (16 running, 64 queued)"
+                    .to_string(),
+                retry_after: Some(OVERLOAD_RETRY_AFTER),
+            }
+            .to_string()
+        );
+        assert!(Overload::TimedOut
+            .to_error(&limits)
+            .to_string()
+            .contains("within 5s"));
+    }
+
+    #[tokio::test]
+    async fn test_layer_rejects_with_resource_exhausted() {
+        use tower::{Layer, Service, ServiceExt};
+
+        let limiter = Arc::new(TenantLimiter::new(TenantLimits {
+            max_in_flight: 1,
+            max_queued: 0,
+            queue_timeout: Duration::from_secs(5),
+        }));
+        let mut service = TenantLimitLayer::new(limiter.clone()).layer(tower::service_fn(
+            |_request: http::Request<()>| async {
+                Ok::<_, std::convert::Infallible>(http::Response::new("served".to_string()))
+            },
+        ));
+        let request = |tenant_id: &str| {
+            http::Request::builder()
+                .header(TENANT_ID_HEADER, tenant_id)
+                .body(())
+                .unwrap()
+        };
+
+        let _busy = limiter.acquire("t1").await.unwrap();
+        let response = service
+            .ready()
+            .await
+            .unwrap()
+            .call(request("t1"))
+            .await
+            .unwrap();
+        assert_eq!(response.body(), "");
+        let headers = response.headers();
+        assert_eq!(headers["grpc-status"], GRPC_RESOURCE_EXHAUSTED);
+        assert_eq!(headers["content-type"], "application/grpc");
+        assert!(headers["grpc-message"]
+            .to_str()
+            .unwrap()
+            .contains("Too many concurrent requests"));
+        assert_eq!(headers[crate::error::RETRY_AFTER_METADATA], "1000");
+
+        let response = service
+            .ready()
+            .await
+            .unwrap()
+            .call(request("t2"))
+            .await
+            .unwrap();
+        assert_eq!(response.body(), "served");
+        assert_eq!(limiter.stats().rejected, 1);
+    }
+}
@@ -0,0 +1,355 @@
+//! Resumable uploads: the chunks of a SaveSession stream are spooled to disk
+//! as they are acknowledged, so a client whose connection drops can ask where
+//! the upload stopped and send the rest instead of starting over.
+//!
+//! Each chunk carries its sequence number (from 0) and a CRC32 of its data.
+//! A chunk is acknowledged once its CRC matched and it was appended to the
+//! spool file. Chunks already acknowledged are ignored when resent, so the
+//! client may resume from any chunk at or before the last acknowledged one.
+//! Progress is kept in memory: after a restart, uploads start over.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::storage::ChunkStream;
+use crate::validation::{tenant_dir, validate_session_id};
+use crate::StorageError;
+
+/// Size of the chunks a finished upload is read back in.
+const READ_CHUNK_SIZE: usize = 256 * 1024;
+
+/// How far an upload got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Sequence number of the next chunk the upload expects
+    pub next_sequence: u64,
+    pub received_bytes: u64,
+}
+
+/// Identifies an upload: the client picks `upload_id`, unique per session.
+type UploadKey = (String, String, String);
+
+/// Uploads in progress, spooled under `{base_dir}/{tenant_id}/`.
+///
+/// Uploads not appended to for `ttl` are dropped with their spool file the
+/// next time an upload starts.
+#[derive(Debug)]
+pub struct UploadSpool {
+    base_dir: PathBuf,
+    ttl: Duration,
+    uploads: Mutex<HashMap<UploadKey, (UploadProgress, Instant)>>,
+}
+
+impl UploadSpool {
+    pub fn new(base_dir: impl AsRef<Path>, ttl: Duration) -> Self {
+        Self {
+            base_dir: base_dir.as_ref().to_path_buf(),
+            ttl,
+            uploads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn spool_path(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        upload_id: &str,
+    ) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        validate_session_id(upload_id).map_err(|_| {
+            StorageError::InvalidArgument(format!("invalid upload ID {upload_id:?}"))
+        })?;
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join(format!("{session_id}.{upload_id}.part")))
+    }
+
+    fn key(tenant_id: &str, session_id: &str, upload_id: &str) -> UploadKey {
+        (tenant_id.to_string(), session_id.to_string(), upload_id.to_string())
+    }
+
+    /// Progress of an upload, or `None` if it is unknown or expired.
+    pub async fn progress(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        upload_id: &str,
+    ) -> Option<UploadProgress> {
+        let uploads = self.uploads.lock().await;
+        let (progress, touched) = uploads.get(&Self::key(tenant_id, session_id, upload_id))?;
+        (touched.elapsed() < self.ttl).then_some(*progress)
+    }
+
+    /// Acknowledge chunk `sequence` of an upload once `crc32` matches its
+    /// data. Sequence 0 starts the upload over; other chunks must follow the
+    /// last acknowledged one, and chunks already acknowledged are skipped.
+    pub async fn append(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        upload_id: &str,
+        sequence: u64,
+        data: &[u8],
+        crc32: u32,
+    ) -> Result<UploadProgress, StorageError> {
+        let actual = crc32fast::hash(data);
+        if actual != crc32 {
+            return Err(StorageError::InvalidArgument(format!(
+                "chunk {sequence} failed its CRC check (expected {crc32:08x}, got {actual:08x})"
+            )));
+        }
+
+        let path = self.spool_path(tenant_id, session_id, upload_id)?;
+        let key = Self::key(tenant_id, session_id, upload_id);
+        let mut uploads = self.uploads.lock().await;
+        if sequence == 0 {
+            self.drop_expired(&mut uploads).await;
+        }
+        let progress = match uploads.get(&key) {
+            _ if sequence == 0 => UploadProgress::default(),
+            Some((progress, touched)) if touched.elapsed() < self.ttl => *progress,
+            _ => {
+                return Err(StorageError::NotFound(format!(
+                    "upload {upload_id} is unknown or expired; start over from chunk 0"
+                )))
+            }
+        };
+        if sequence < progress.next_sequence {
+            return Ok(progress);
+        }
+        if sequence > progress.next_sequence {
+            return Err(StorageError::InvalidArgument(format!(
+                "upload {upload_id} expects chunk {}, got {sequence}",
+                progress.next_sequence
+            )));
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await.map_err(|e| {
+                StorageError::Io(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(sequence > 0)
+            .truncate(sequence == 0)
+            .open(&path)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to open {}: {}", path.display(), e)))?;
+        let write_error = |e: std::io::Error| {
+            StorageError::Io(format!("Failed to write {}: {}", path.display(), e))
+        };
+        file.write_all(data).await.map_err(write_error)?;
+        file.flush().await.map_err(write_error)?;
+
+        let progress = UploadProgress {
+            next_sequence: sequence + 1,
+            received_bytes: progress.received_bytes + data.len() as u64,
+        };
+        uploads.insert(key, (progress, Instant::now()));
+        Ok(progress)
+    }
+
+    /// The data of an upload, read back from its spool file. The upload is
+    /// kept until [`Self::discard`], so storing it can be retried by resending
+    /// the last chunk.
+    pub async fn read(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        upload_id: &str,
+    ) -> Result<ChunkStream<'static>, StorageError> {
+        let path = self.spool_path(tenant_id, session_id, upload_id)?;
+        let file = fs::File::open(&path)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to open {}: {}", path.display(), e)))?;
+
+        Ok(futures::stream::unfold((file, path), |(mut file, path)| async move {
+            let mut buffer = vec![0; READ_CHUNK_SIZE];
+            match file.read(&mut buffer).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buffer.truncate(n);
+                    Some((Ok(buffer), (file, path)))
+                }
+                Err(e) => {
+                    let error =
+                        StorageError::Io(format!("Failed to read {}: {}", path.display(), e));
+                    Some((Err(error), (file, path)))
+                }
+            }
+        })
+        .boxed())
+    }
+
+    /// Forget an upload and delete its spool file.
+    pub async fn discard(&self, tenant_id: &str, session_id: &str, upload_id: &str) {
+        let mut uploads = self.uploads.lock().await;
+        uploads.remove(&Self::key(tenant_id, session_id, upload_id));
+        if let Ok(path) = self.spool_path(tenant_id, session_id, upload_id) {
+            let _ = fs::remove_file(&path).await;
+        }
+    }
+
+    async fn drop_expired(&self, uploads: &mut HashMap<UploadKey, (UploadProgress, Instant)>) {
+        let expired: Vec<UploadKey> = uploads
+            .iter()
+            .filter(|(_, (_, touched))| touched.elapsed() >= self.ttl)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for (tenant_id, session_id, upload_id) in expired {
+            if let Ok(path) = self.spool_path(&tenant_id, &session_id, &upload_id) {
+                let _ = fs::remove_file(&path).await;
+            }
+            uploads.remove(&(tenant_id, session_id, upload_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::collect_chunks;
+    use tempfile::TempDir;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    async fn send(
+        spool: &UploadSpool,
+        sequence: u64,
+        data: &[u8],
+    ) -> Result<UploadProgress, StorageError> {
+        spool
+            .append("acme", "s1", "up1", sequence, data, crc32fast::hash(data))
+            .await
+    }
+
+    async fn uploaded(spool: &UploadSpool) -> Vec<u8> {
+        collect_chunks(spool.read("acme", "s1", "up1").await.unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_chunks_failing_their_crc_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let spool = UploadSpool::new(dir.path(), HOUR);
+        send(&spool, 0, b"PK").await.unwrap();
+
+        let err = spool
+            .append(
+                "acme",
+                "s1",
+                "up1",
+                1,
+                b"corrupted",
+                crc32fast::hash(b"original"),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, StorageError::InvalidArgument(msg) if msg.starts_with("chunk 1 failed its CRC check"))
+        );
+        let progress = spool.progress("acme", "s1", "up1").await.unwrap();
+        assert_eq!(
+            progress,
+            UploadProgress {
+                next_sequence: 1,
+                received_bytes: 2
+            }
+        );
+
+        send(&spool, 1, b"original").await.unwrap();
+        assert_eq!(uploaded(&spool).await, b"PKoriginal");
+    }
+
+    #[tokio::test]
+    async fn test_upload_resumes_after_the_last_acknowledged_chunk() {
+        let dir = TempDir::new().unwrap();
+        let spool = UploadSpool::new(dir.path(), HOUR);
+        assert_eq!(spool.progress("acme", "s1", "up1").await, None);
+
+        send(&spool, 0, b"aa").await.unwrap();
+        send(&spool, 1, b"bbb").await.unwrap();
+        // A chunk resent after a dropped connection is acknowledged again, not appended
+        let progress = send(&spool, 1, b"bbb").await.unwrap();
+        assert_eq!(
+            progress,
+            UploadProgress {
+                next_sequence: 2,
+                received_bytes: 5
+            }
+        );
+        let err = send(&spool, 3, b"d").await.unwrap_err();
+        assert!(
+            matches!(err, StorageError::InvalidArgument(msg) if msg == "upload up1 expects chunk 2, got 3")
+        );
+        send(&spool, 2, b"c").await.unwrap();
+        assert_eq!(uploaded(&spool).await, b"aabbbc");
+
+        // Reading it back keeps it, so storing it can be retried
+        assert_eq!(uploaded(&spool).await, b"aabbbc");
+
+        // Chunk 0 starts over
+        let progress = send(&spool, 0, b"z").await.unwrap();
+        assert_eq!(
+            progress,
+            UploadProgress {
+                next_sequence: 1,
+                received_bytes: 1
+            }
+        );
+        assert_eq!(uploaded(&spool).await, b"z");
+
+        spool.discard("acme", "s1", "up1").await;
+        assert_eq!(spool.progress("acme", "s1", "up1").await, None);
+        assert!(spool.read("acme", "s1", "up1").await.is_err());
+        assert!(matches!(
+            send(&spool, 1, b"b").await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_expired_uploads_start_over() {
+        let dir = TempDir::new().unwrap();
+        let spool = UploadSpool::new(dir.path(), Duration::ZERO);
+        send(&spool, 0, b"aa").await.unwrap();
+        assert_eq!(spool.progress("acme", "s1", "up1").await, None);
+        assert!(matches!(
+            send(&spool, 1, b"b").await,
+            Err(StorageError::NotFound(_))
+        ));
+
+        // Starting another upload drops the expired one's spool file
+        let path = dir.path().join("acme/s1.up1.part");
+        assert!(path.exists());
+        spool
+            .append("acme", "s1", "up2", 0, b"x", crc32fast::hash(b"x"))
+            .await
+            .unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_upload_ids_are_validated() {
+        let dir = TempDir::new().unwrap();
+        let spool = UploadSpool::new(dir.path(), HOUR);
+        let err = spool
+            .append("acme", "s1", "../up", 0, b"x", crc32fast::hash(b"x"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, StorageError::InvalidArgument(msg) if msg == "invalid upload ID \"../up\"")
+        );
+        assert!(spool
+            .append("acme", "../s1", "up1", 0, b"x", crc32fast::hash(b"x"))
+            .await
+            .is_err());
+    }
+}
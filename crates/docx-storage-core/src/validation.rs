@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::StorageError;
+use crate::sandbox::SANDBOX_SUFFIX;
+
+/// Maximum length of a tenant or session ID, in bytes.
+pub const MAX_ID_LEN: usize = 128;
+
+/// Checks shared by all IDs used as a single path segment or object key
+/// component: bounded length and no leading `.` (rules out `.` and `..`).
+fn validate_segment(kind: &str, id: &str) -> Result<(), StorageError> {
+    if id.len() > MAX_ID_LEN {
+        return Err(StorageError::InvalidArgument(format!(
+            "{} is longer than {} bytes",
+            kind, MAX_ID_LEN
+        )));
+    }
+    if id.starts_with('.') {
+        return Err(StorageError::InvalidArgument(format!(
+            "{} must not start with '.': {:?}",
+            kind, id
+        )));
+    }
+    Ok(())
+}
+
+fn invalid_character(kind: &str, id: &str, c: char) -> StorageError {
+    StorageError::InvalidArgument(format!(
+        "{} contains invalid character {:?}: {:?}",
+        kind, c, id
+    ))
+}
+
+/// Validate a tenant ID: ASCII letters, digits, `-`, `_` and `.`, optionally
+/// followed by [`SANDBOX_SUFFIX`] for a tenant's sandbox. The suffix's `~` is
+/// allowed nowhere else, so no tenant can be named like another's sandbox.
+/// The empty ID is the local (single-user) tenant.
+pub fn validate_tenant_id(tenant_id: &str) -> Result<(), StorageError> {
+    validate_segment("Tenant ID", tenant_id)?;
+    let base = tenant_id.strip_suffix(SANDBOX_SUFFIX).unwrap_or(tenant_id);
+    if base.is_empty() && !tenant_id.is_empty() {
+        return Err(StorageError::InvalidArgument(
+            "the empty tenant has no sandbox".to_string(),
+        ));
+    }
+    if let Some(c) = base
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+    {
+        return Err(invalid_character("Tenant ID", tenant_id, c));
+    }
+    Ok(())
+}
+
+/// Validate a session ID (or another per-tenant resource name).
+///
+/// Any Unicode is allowed except path separators, control characters and
+/// characters reserved in Windows file names. IDs that would name another
+/// session's files are refused: those containing `.ckpt.` (checkpoints are
+/// `{session}.ckpt.{position}.docx`) or ending in `.wal` or `.docx`, in any case.
+pub fn validate_session_id(session_id: &str) -> Result<(), StorageError> {
+    validate_name("Session ID", session_id)?;
+    let lower = session_id.to_ascii_lowercase();
+    if lower.contains(".ckpt.") || RESERVED_SESSION_SUFFIXES.iter().any(|s| lower.ends_with(s)) {
+        return Err(StorageError::InvalidArgument(format!(
+            "Session ID collides with the names of session files: {:?}",
+            session_id
+        )));
+    }
+    Ok(())
+}
+
+/// Extensions of the files stored for a session.
+const RESERVED_SESSION_SUFFIXES: &[&str] = &[".wal", ".docx"];
+
+pub(crate) fn validate_name(kind: &str, name: &str) -> Result<(), StorageError> {
+    if name.is_empty() {
+        return Err(StorageError::InvalidArgument(format!("{} is required", kind)));
+    }
+    validate_segment(kind, name)?;
+    if let Some(c) = name.chars().find(|c| {
+        c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+    }) {
+        return Err(invalid_character(kind, name, c));
+    }
+    Ok(())
+}
+
+/// Validate a session alias: bounded length and no control characters.
+/// Aliases are never used as paths, so other characters are allowed.
+pub fn validate_alias(alias: &str) -> Result<(), StorageError> {
+    if alias.len() > MAX_ID_LEN {
+        return Err(StorageError::InvalidArgument(format!(
+            "Alias is longer than {} bytes",
+            MAX_ID_LEN
+        )));
+    }
+    if let Some(c) = alias.chars().find(|c| c.is_control()) {
+        return Err(invalid_character("Alias", alias, c));
+    }
+    Ok(())
+}
+
+/// Directory of a tenant under `base_dir`, after validating the tenant ID
+/// and checking that the directory doesn't resolve outside `base_dir`.
+pub fn tenant_dir(base_dir: &Path, tenant_id: &str) -> Result<PathBuf, StorageError> {
+    validate_tenant_id(tenant_id)?;
+    let dir = base_dir.join(tenant_id);
+    ensure_within(base_dir, &dir)?;
+    Ok(dir)
+}
+
+/// Fail if `path` resolves (following symlinks) outside `base_dir`.
+///
+/// Paths that don't exist yet can't be resolved; they are accepted, since
+/// validated IDs can't contain separators or `..`.
+pub fn ensure_within(base_dir: &Path, path: &Path) -> Result<(), StorageError> {
+    let (Ok(base), Ok(resolved)) = (base_dir.canonicalize(), path.canonicalize()) else {
+        return Ok(());
+    };
+    if !resolved.starts_with(&base) {
+        return Err(StorageError::InvalidArgument(format!(
+            "{} resolves outside the storage directory",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_tenant_id() {
+        for tenant_id in ["", "acme", "acme-corp_2", "acme.eu", "acme~sandbox"] {
+            assert!(validate_tenant_id(tenant_id).is_ok(), "{} was refused", tenant_id);
+        }
+        for tenant_id in [".", "..", ".hidden", "a/b", "a\\b", "caf\u{e9}", "a b", "a~b"] {
+            assert!(validate_tenant_id(tenant_id).is_err(), "{} was accepted", tenant_id);
+        }
+        assert!(validate_tenant_id(&"a".repeat(MAX_ID_LEN)).is_ok());
+        assert!(validate_tenant_id(&"a".repeat(MAX_ID_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_session_id() {
+        for session_id in ["s1", "Contrat de bail (v2)", "r\u{e9}sum\u{e9}.final", "a.ckpt", "notes.docx.bak"] {
+            assert!(validate_session_id(session_id).is_ok(), "{} was refused", session_id);
+        }
+        for session_id in [
+            "", "..", ".s1", "a/b", "a\\b", "a:b", "a?", "a\nb",
+            // Would name another session's files
+            "a.ckpt.3", "a.CKPT.3", "a.wal", "a.docx", "a.DOCX",
+        ] {
+            assert!(validate_session_id(session_id).is_err(), "{:?} was accepted", session_id);
+        }
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        for alias in ["q3-board", "Rapport annuel / 2026", "a:b?"] {
+            assert!(validate_alias(alias).is_ok(), "{} was refused", alias);
+        }
+        assert!(validate_alias("a\nb").is_err());
+        assert!(validate_alias(&"a".repeat(MAX_ID_LEN)).is_ok());
+        assert!(validate_alias(&"a".repeat(MAX_ID_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_ensure_within() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().join("base");
+        std::fs::create_dir_all(base.join("acme")).unwrap();
+
+        assert!(ensure_within(&base, &base.join("acme")).is_ok());
+        // Not created yet: can't be resolved, accepted
+        assert!(ensure_within(&base, &base.join("new")).is_ok());
+        assert!(ensure_within(&base, &base.join("acme/../../")).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path(), base.join("escape")).unwrap();
+            assert!(ensure_within(&base, &base.join("escape")).is_err());
+            assert!(tenant_dir(&base, "escape").is_err());
+        }
+        assert_eq!(tenant_dir(&base, "acme").unwrap(), base.join("acme"));
+        assert!(tenant_dir(&base, "..").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::StorageError;
+use crate::storage::WalEntry;
+use crate::wal_schema::{migrate_wal_value, WalRecord};
+
+/// Entries between two indexed positions in a [`WalOffsetIndex`].
+pub const WAL_INDEX_STRIDE: u64 = 64;
+
+/// Sparse position → byte-offset index of a WAL's JSONL data, stored next to
+/// the WAL so reads can start at an entry instead of scanning from the start.
+///
+/// The index records the data length (and, where the backend has one, the
+/// object version) it was built for. A reader seeing a different length
+/// treats the index as stale and falls back to a full scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalOffsetIndex {
+    /// Length of the JSONL data (without the 8-byte header) when indexed
+    pub data_len: u64,
+    /// Backend version of the WAL object when indexed (R2 ETag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Number of entries
+    pub entries: u64,
+    /// `offsets[i]` is the byte offset of position `i * WAL_INDEX_STRIDE + 1`
+    pub offsets: Vec<u64>,
+}
+
+impl WalOffsetIndex {
+    /// Index JSONL data. Blank lines are not entries, as in [`parse_wal_entries`].
+    pub fn build(jsonl: &[u8]) -> Self {
+        let mut index = Self {
+            data_len: jsonl.len() as u64,
+            ..Self::default()
+        };
+        let mut offset = 0usize;
+        for line in jsonl.split_inclusive(|&b| b == b'\n') {
+            if !line.trim_ascii().is_empty() {
+                if index.entries % WAL_INDEX_STRIDE == 0 {
+                    index.offsets.push(offset as u64);
+                }
+                index.entries += 1;
+            }
+            offset += line.len();
+        }
+        index
+    }
+
+    /// Byte range of the JSONL data holding `limit` entries from
+    /// `from_position` (1-indexed, 0 = from the beginning): the position of
+    /// the first entry in the range, and its start and end offsets. The range
+    /// starts at the closest indexed entry and may hold a few extra entries on
+    /// both ends.
+    pub fn byte_range(&self, from_position: u64, limit: Option<u64>) -> (u64, u64, u64) {
+        let from = from_position.max(1);
+        let slot = ((from - 1) / WAL_INDEX_STRIDE) as usize;
+        let Some(&start) = self.offsets.get(slot) else {
+            return (self.entries + 1, self.data_len, self.data_len);
+        };
+
+        let end = limit
+            .map(|limit| from.saturating_add(limit) - 1)
+            .map(|last| last.div_ceil(WAL_INDEX_STRIDE) as usize)
+            .and_then(|end_slot| self.offsets.get(end_slot).copied())
+            .unwrap_or(self.data_len)
+            .max(start);
+
+        (slot as u64 * WAL_INDEX_STRIDE + 1, start, end)
+    }
+}
+
+/// Split a WAL file into its JSONL data. The 8-byte little-endian header
+/// holds the data length; bytes past it are padding.
+pub fn wal_jsonl(raw: &[u8]) -> &[u8] {
+    if raw.len() < 8 {
+        return &[];
+    }
+    let data_len = i64::from_le_bytes(raw[..8].try_into().unwrap()).max(0) as usize;
+    &raw[8..(8 + data_len).min(raw.len())]
+}
+
+/// Parse JSONL WAL data whose first entry is at `first_position`, keeping up
+/// to `limit` entries from `from_position`. Returns the entries and whether
+/// the limit was reached (more entries may follow).
+///
+/// Entries from older schema versions are upgraded to the current one.
+/// Entries that don't match the schema are returned as stored, without an
+/// operation.
+pub fn parse_wal_entries(
+    jsonl: &[u8],
+    first_position: u64,
+    from_position: u64,
+    limit: Option<u64>,
+) -> Result<(Vec<WalEntry>, bool), StorageError> {
+    let content = std::str::from_utf8(jsonl)
+        .map_err(|e| StorageError::Io(format!("WAL is not valid UTF-8: {}", e)))?;
+
+    let mut entries = Vec::new();
+    let limit = limit.unwrap_or(u64::MAX);
+    let mut position = first_position;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if position >= from_position {
+            let mut value: serde_json::Value = serde_json::from_str(line).map_err(|e| {
+                StorageError::Serialization(format!(
+                    "Failed to parse WAL entry at position {}: {}",
+                    position, e
+                ))
+            })?;
+
+            let migrated = migrate_wal_value(&mut value).unwrap_or_else(|e| {
+                debug!("Leaving WAL entry at position {} as stored: {}", position, e);
+                false
+            });
+            let record = WalRecord::deserialize(&value).ok();
+
+            let timestamp = match &record {
+                Some(record) => record.timestamp,
+                None => value
+                    .get("timestamp")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(chrono::Utc::now),
+            };
+            let patch_json = if migrated {
+                serde_json::to_vec(&value)
+                    .map_err(|e| StorageError::Serialization(e.to_string()))?
+            } else {
+                line.as_bytes().to_vec()
+            };
+
+            entries.push(WalEntry {
+                position,
+                operation: record
+                    .map(|r| r.entry_type.as_str().to_string())
+                    .unwrap_or_default(),
+                path: String::new(),
+                patch_json,
+                timestamp,
+            });
+
+            if entries.len() as u64 >= limit {
+                return Ok((entries, true));
+            }
+        }
+
+        position += 1;
+    }
+
+    Ok((entries, false))
+}
+
+/// Check the positions of entries appended to a WAL holding `existing`
+/// entries, and return the position of the last one.
+///
+/// Entries at position 0 leave the numbering to the storage. Any other
+/// position must be the one the entry gets, so a client that lost track of
+/// the WAL (a retried append, a missed truncation) is refused instead of
+/// silently shifting replay order.
+pub fn check_wal_positions(existing: u64, entries: &[WalEntry]) -> Result<u64, StorageError> {
+    for (i, entry) in entries.iter().enumerate() {
+        let expected = existing + i as u64 + 1;
+        if entry.position != 0 && entry.position != expected {
+            return Err(StorageError::WalPosition {
+                expected,
+                found: entry.position,
+            });
+        }
+    }
+    Ok(existing + entries.len() as u64)
+}
+
+/// Page of a backwards read over `total` entries: the first position and
+/// the number of the (up to) `limit` entries before `before_position`
+/// (`None` = the newest entries).
+pub fn tail_page(total: u64, before_position: Option<u64>, limit: u64) -> (u64, u64) {
+    let end = before_position.map_or(total + 1, |before| before.min(total + 1));
+    let start = end.saturating_sub(limit).max(1);
+    (start, end.saturating_sub(start))
+}
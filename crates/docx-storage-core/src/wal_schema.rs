@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::StorageError;
+use crate::storage::WalEntry;
+
+/// Version of the WAL entry schema written by current clients.
+///
+/// - 0: entries written before the schema was versioned (no `version` field)
+/// - 1: `version` field; timestamps always carry an offset
+pub const WAL_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades from each older version to the next, indexed by the version
+/// they upgrade from.
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+const MIGRATIONS: [Migration; WAL_SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Kind of WAL entry, serialized as its number as the .NET `WalEntryType` is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum WalEntryType {
+    /// Patch operations applied by the LLM/user
+    #[default]
+    Patch,
+    /// The document was reloaded from its external source
+    ExternalSync,
+    /// Initial sync when watching starts
+    Import,
+}
+
+impl WalEntryType {
+    /// Name used for `WalEntry::operation`, matching the .NET enum names.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WalEntryType::Patch => "Patch",
+            WalEntryType::ExternalSync => "ExternalSync",
+            WalEntryType::Import => "Import",
+        }
+    }
+}
+
+impl From<WalEntryType> for u8 {
+    fn from(t: WalEntryType) -> Self {
+        match t {
+            WalEntryType::Patch => 0,
+            WalEntryType::ExternalSync => 1,
+            WalEntryType::Import => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for WalEntryType {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(WalEntryType::Patch),
+            1 => Ok(WalEntryType::ExternalSync),
+            2 => Ok(WalEntryType::Import),
+            _ => Err(format!("unknown entry_type {}", value)),
+        }
+    }
+}
+
+/// Metadata of an external sync or import entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalSyncMeta {
+    pub source_path: String,
+    pub previous_hash: String,
+    pub new_hash: String,
+    /// Change counts of the main body
+    pub summary: Value,
+    /// Changes outside the main body (headers, footers, images, ...)
+    #[serde(default)]
+    pub uncovered_changes: Vec<Value>,
+    /// Base64 document bytes at this sync point
+    pub document_snapshot: String,
+}
+
+/// Payload of a WAL entry (`WalEntry::patch_json`), as written by the .NET
+/// server. Unknown fields are allowed so older servers can read entries
+/// from newer clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalRecord {
+    pub version: u32,
+    /// JSON array of patch operations, serialized as a string
+    pub patches: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub entry_type: WalEntryType,
+    /// Present on external sync and import entries only
+    #[serde(default)]
+    pub sync_meta: Option<WalSyncMeta>,
+}
+
+impl WalRecord {
+    /// Checks the structure can't express.
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(serde_json::from_str(&self.patches), Ok(Value::Array(_))) {
+            return Err("patches must hold a JSON array".to_string());
+        }
+        match (self.entry_type, &self.sync_meta) {
+            (WalEntryType::Patch, Some(_)) => {
+                Err("sync_meta is only allowed on sync entries".to_string())
+            }
+            (WalEntryType::ExternalSync | WalEntryType::Import, None) => Err(format!(
+                "{} entries require sync_meta",
+                self.entry_type.as_str()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Bring a parsed entry up to [`WAL_SCHEMA_VERSION`]. Returns whether it
+/// changed. Entries from a newer schema are refused.
+pub fn migrate_wal_value(value: &mut Value) -> Result<bool, String> {
+    let Value::Object(map) = value else {
+        return Err("WAL entry must be a JSON object".to_string());
+    };
+    let version = match map.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("invalid version {}", v))?,
+    };
+    if version > WAL_SCHEMA_VERSION {
+        return Err(format!(
+            "version {} is newer than the supported version {}",
+            version, WAL_SCHEMA_VERSION
+        ));
+    }
+
+    for from in version..WAL_SCHEMA_VERSION {
+        MIGRATIONS[from as usize](map)
+            .map_err(|e| format!("migrating from version {}: {}", from, e))?;
+        map.insert("version".to_string(), Value::from(from + 1));
+    }
+    Ok(version < WAL_SCHEMA_VERSION)
+}
+
+/// v0 → v1: .NET wrote `DateTime`s of unspecified kind without an offset;
+/// they were always UTC.
+fn migrate_v0_to_v1(map: &mut Map<String, Value>) -> Result<(), String> {
+    if let Some(Value::String(timestamp)) = map.get_mut("timestamp") {
+        if chrono::DateTime::parse_from_rfc3339(timestamp).is_err() {
+            let naive =
+                chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map_err(|e| format!("invalid timestamp '{}': {}", timestamp, e))?;
+            *timestamp = naive
+                .and_utc()
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+        }
+    }
+    Ok(())
+}
+
+/// Validate entries before they are appended, upgrading older versions.
+/// Returns the JSON line to store for each entry: its own bytes when they
+/// are already current and on a single line, re-serialized otherwise.
+pub fn prepare_wal_entries(entries: &[WalEntry]) -> Result<Vec<Vec<u8>>, StorageError> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            prepare_wal_entry(&entry.patch_json).map_err(|e| {
+                StorageError::InvalidArgument(format!(
+                    "Invalid WAL entry {} (position {}): {}",
+                    i, entry.position, e
+                ))
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn prepare_wal_entry(patch_json: &[u8]) -> Result<Vec<u8>, String> {
+    let line = patch_json.trim_ascii();
+    let mut value: Value = serde_json::from_slice(line).map_err(|e| e.to_string())?;
+    let migrated = migrate_wal_value(&mut value)?;
+    WalRecord::deserialize(&value)
+        .map_err(|e| e.to_string())?
+        .validate()?;
+
+    if migrated || line.contains(&b'\n') {
+        serde_json::to_vec(&value).map_err(|e| e.to_string())
+    } else {
+        Ok(line.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_v0_entries_are_upgraded() {
+        let mut value = json!({"patches": "[]", "timestamp": "2025-03-01T10:20:30.1234567"});
+        assert_eq!(migrate_wal_value(&mut value), Ok(true));
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["timestamp"], "2025-03-01T10:20:30.123456700Z");
+        let record = WalRecord::deserialize(&value).unwrap();
+        assert_eq!(
+            record.timestamp.to_rfc3339(),
+            "2025-03-01T10:20:30.123456700+00:00"
+        );
+
+        // Timestamps that already carry an offset are kept
+        let mut value = json!({"patches": "[]", "timestamp": "2025-03-01T10:20:30+02:00"});
+        assert_eq!(migrate_wal_value(&mut value), Ok(true));
+        assert_eq!(value["timestamp"], "2025-03-01T10:20:30+02:00");
+
+        let mut value = json!({"patches": "[]", "timestamp": "yesterday"});
+        let err = migrate_wal_value(&mut value).unwrap_err();
+        assert!(
+            err.starts_with("migrating from version 0: invalid timestamp 'yesterday'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_current_and_newer_versions() {
+        let current = json!({"version": 1, "patches": "[]", "timestamp": "2025-03-01T10:20:30Z"});
+        let mut value = current.clone();
+        assert_eq!(migrate_wal_value(&mut value), Ok(false));
+        assert_eq!(value, current);
+
+        let mut value = json!({"version": 2, "patches": "[]"});
+        assert_eq!(
+            migrate_wal_value(&mut value).unwrap_err(),
+            "version 2 is newer than the supported version 1"
+        );
+        assert!(migrate_wal_value(&mut json!({"version": "1"})).is_err());
+        assert!(migrate_wal_value(&mut json!({"version": -1})).is_err());
+        assert!(migrate_wal_value(&mut json!([])).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let record = |patches: &str, entry_type, sync_meta| WalRecord {
+            version: 1,
+            patches: patches.to_string(),
+            timestamp: chrono::Utc::now(),
+            description: None,
+            entry_type,
+            sync_meta,
+        };
+        let meta = WalSyncMeta {
+            source_path: "/docs/a.docx".to_string(),
+            previous_hash: "a".to_string(),
+            new_hash: "b".to_string(),
+            summary: json!({}),
+            uncovered_changes: Vec::new(),
+            document_snapshot: String::new(),
+        };
+
+        assert!(record("[]", WalEntryType::Patch, None).validate().is_ok());
+        assert!(record("[]", WalEntryType::Import, Some(meta.clone()))
+            .validate()
+            .is_ok());
+        assert!(record("{}", WalEntryType::Patch, None).validate().is_err());
+        assert!(record("[", WalEntryType::Patch, None).validate().is_err());
+        assert_eq!(
+            record("[]", WalEntryType::ExternalSync, None).validate(),
+            Err("ExternalSync entries require sync_meta".to_string())
+        );
+        assert!(record("[]", WalEntryType::Patch, Some(meta))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_entry_type_is_serialized_as_a_number() {
+        assert_eq!(
+            serde_json::to_value(WalEntryType::ExternalSync).unwrap(),
+            json!(1)
+        );
+        assert_eq!(
+            serde_json::from_value::<WalEntryType>(json!(2)).unwrap(),
+            WalEntryType::Import
+        );
+        assert!(serde_json::from_value::<WalEntryType>(json!(3)).is_err());
+    }
+
+    fn entry(position: u64, patch_json: &str) -> WalEntry {
+        WalEntry {
+            position,
+            operation: String::new(),
+            path: String::new(),
+            patch_json: patch_json.as_bytes().to_vec(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_wal_entries() {
+        // Current single-line entries are stored byte for byte
+        let current =
+            r#"{"version":1, "patches":"[]", "timestamp":"2025-03-01T10:20:30Z", "future":true}"#;
+        let stored = prepare_wal_entries(&[entry(1, &format!("  {}\n", current))]).unwrap();
+        assert_eq!(stored, [current.as_bytes()]);
+
+        // Upgraded or multi-line entries are re-serialized on one line
+        let stored = prepare_wal_entries(&[
+            entry(1, r#"{"patches":"[]","timestamp":"2025-03-01T10:20:30"}"#),
+            entry(
+                2,
+                "{\"version\":1,\n\"patches\":\"[]\",\"timestamp\":\"2025-03-01T10:20:30Z\"}",
+            ),
+        ])
+        .unwrap();
+        for line in &stored {
+            assert!(!line.contains(&b'\n'));
+            let record: WalRecord = serde_json::from_slice(line).unwrap();
+            assert_eq!(record.version, 1);
+        }
+
+        let err =
+            prepare_wal_entries(&[entry(1, current), entry(7, r#"{"version":1}"#)]).unwrap_err();
+        assert!(matches!(
+            err,
+            StorageError::InvalidArgument(msg) if msg.starts_with("Invalid WAL entry 1 (position 7): missing field")
+        ));
+        assert!(prepare_wal_entries(&[entry(1, "not json")]).is_err());
+    }
+}
@@ -12,10 +12,13 @@ pub enum ExternalChangeType {
     Deleted,
     Renamed,
     PermissionChanged,
+    /// A new source appeared (e.g. a file dropped into the inbox folder)
+    /// and was opened as the session
+    Created,
 }
 
 /// Metadata about a source file for comparison.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceMetadata {
     /// File size in bytes
     pub size_bytes: u64,
@@ -0,0 +1,33 @@
+//! Circuit breakers around the HTTP dependencies (Google APIs, D1).
+
+use docx_storage_core::CircuitBreaker;
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+/// Send `request` through `breaker`: fail fast with [`CircuitOpen`] while
+/// the dependency is down, and count connection failures, timeouts, 429 and
+/// 5xx responses towards opening the circuit.
+///
+/// [`CircuitOpen`]: docx_storage_core::CircuitOpen
+pub async fn send_guarded(
+    breaker: &CircuitBreaker,
+    request: RequestBuilder,
+) -> anyhow::Result<Response> {
+    breaker.check()?;
+    match request.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                breaker.record_failure();
+            } else {
+                breaker.record_success();
+            }
+            Ok(resp)
+        }
+        Err(e) => {
+            if e.is_connect() || e.is_timeout() {
+                breaker.record_failure();
+            }
+            Err(e.into())
+        }
+    }
+}
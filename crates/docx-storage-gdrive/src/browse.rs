@@ -6,14 +6,18 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use docx_storage_core::{
-    BrowsableBackend, ConnectionInfo, FileEntry, FileListResult, SourceType, StorageError,
+    BrowsableBackend, ConnectionInfo, FileEntry, FileListResult, FileSearchQuery, SourceType,
+    StorageError,
 };
 use tracing::{debug, instrument};
 
 use crate::d1_client::D1Client;
-use crate::gdrive::GDriveClient;
+use crate::gdrive::{DriveFileEntry, GDriveClient, FOLDER_MIME_TYPE};
 use crate::token_manager::TokenManager;
 
+/// Maximum number of folders a recursive search from a subfolder covers.
+const MAX_SEARCH_FOLDERS: usize = 100;
+
 /// Google Drive browsable backend (multi-tenant, token per-connection).
 pub struct GDriveBrowsableBackend {
     d1: Arc<D1Client>,
@@ -35,6 +39,31 @@ impl GDriveBrowsableBackend {
     }
 }
 
+fn to_file_entry(e: DriveFileEntry) -> FileEntry {
+    let is_folder = e.mime_type == FOLDER_MIME_TYPE;
+    let size_bytes = e
+        .size
+        .as_ref()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
+    let modified_at = e
+        .modified_time
+        .as_ref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0);
+
+    FileEntry {
+        name: e.name,
+        path: e.id.clone(), // For Google Drive, path = file ID (used for navigation)
+        file_id: Some(e.id),
+        is_folder,
+        size_bytes,
+        modified_at,
+        mime_type: Some(e.mime_type),
+    }
+}
+
 #[async_trait]
 impl BrowsableBackend for GDriveBrowsableBackend {
     #[instrument(skip(self), level = "debug")]
@@ -55,6 +84,7 @@ impl BrowsableBackend for GDriveBrowsableBackend {
                 source_type: SourceType::GoogleDrive,
                 display_name: c.display_name,
                 provider_account_id: c.provider_account_id,
+                backend: None,
             })
             .collect::<Vec<_>>();
 
@@ -91,33 +121,7 @@ impl BrowsableBackend for GDriveBrowsableBackend {
             .await
             .map_err(|e| StorageError::Sync(format!("Google Drive list error: {}", e)))?;
 
-        let files = entries
-            .into_iter()
-            .map(|e| {
-                let is_folder = e.mime_type == "application/vnd.google-apps.folder";
-                let size_bytes = e
-                    .size
-                    .as_ref()
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(0);
-                let modified_at = e
-                    .modified_time
-                    .as_ref()
-                    .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
-                    .map(|dt| dt.timestamp())
-                    .unwrap_or(0);
-
-                FileEntry {
-                    name: e.name,
-                    path: e.id.clone(), // For Google Drive, path = file ID (used for navigation)
-                    file_id: Some(e.id),
-                    is_folder,
-                    size_bytes,
-                    modified_at,
-                    mime_type: Some(e.mime_type),
-                }
-            })
-            .collect();
+        let files = entries.into_iter().map(to_file_entry).collect();
 
         Ok(FileListResult {
             files,
@@ -125,6 +129,56 @@ impl BrowsableBackend for GDriveBrowsableBackend {
         })
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn search_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        query: &FileSearchQuery,
+    ) -> Result<FileListResult, StorageError> {
+        let token = self
+            .token_manager
+            .get_valid_token(tenant_id, connection_id)
+            .await
+            .map_err(|e| StorageError::Sync(format!("Token error: {}", e)))?;
+
+        let parent_id = if query.path.is_empty() { "root" } else { query.path.as_str() };
+
+        // A recursive search from the root covers the whole drive. From a
+        // subfolder, Drive can't filter on ancestors, so gather the folder tree
+        // (capped) and match on direct parents.
+        let parent_ids = if !query.recursive {
+            Some(vec![parent_id.to_string()])
+        } else if parent_id == "root" {
+            None
+        } else {
+            Some(
+                self.client
+                    .list_folder_tree(&token, parent_id, MAX_SEARCH_FOLDERS)
+                    .await
+                    .map_err(|e| StorageError::Sync(format!("Google Drive list error: {}", e)))?,
+            )
+        };
+
+        let (entries, next_page_token) = self
+            .client
+            .search_files(
+                &token,
+                &query.query,
+                query.effective_mime_type(),
+                parent_ids.as_deref(),
+                query.page_token.as_deref(),
+                query.page_size,
+            )
+            .await
+            .map_err(|e| StorageError::Sync(format!("Google Drive search error: {}", e)))?;
+
+        Ok(FileListResult {
+            files: entries.into_iter().map(to_file_entry).collect(),
+            next_page_token,
+        })
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn download_file(
         &self,
@@ -0,0 +1,48 @@
+//! D1-backed persistence for external change queues.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use docx_storage_core::{ChangeQueue, ChangeQueueStore, StorageError};
+
+use crate::d1_client::D1Client;
+
+/// Change queue store keeping one JSON row per tenant in D1.
+pub struct D1ChangeQueueStore {
+    d1: Arc<D1Client>,
+}
+
+impl D1ChangeQueueStore {
+    pub fn new(d1: Arc<D1Client>) -> Self {
+        Self { d1 }
+    }
+}
+
+#[async_trait]
+impl ChangeQueueStore for D1ChangeQueueStore {
+    async fn load(&self, tenant_id: &str) -> Result<ChangeQueue, StorageError> {
+        let json = self
+            .d1
+            .load_change_queue(tenant_id)
+            .await
+            .map_err(|e| StorageError::Watch(format!("Failed to load change queue: {}", e)))?;
+
+        match json {
+            Some(json) => serde_json::from_str(&json).map_err(|e| {
+                StorageError::Serialization(format!("Failed to parse change queue: {}", e))
+            }),
+            None => Ok(ChangeQueue::default()),
+        }
+    }
+
+    async fn save(&self, tenant_id: &str, queue: &ChangeQueue) -> Result<(), StorageError> {
+        let json = serde_json::to_string(queue).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize change queue: {}", e))
+        })?;
+
+        self.d1
+            .save_change_queue(tenant_id, &json)
+            .await
+            .map_err(|e| StorageError::Watch(format!("Failed to save change queue: {}", e)))
+    }
+}
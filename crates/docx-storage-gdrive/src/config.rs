@@ -1,4 +1,5 @@
 use clap::Parser;
+use docx_storage_core::LogFormat;
 
 /// Configuration for the docx-storage-gdrive server.
 #[derive(Parser, Debug, Clone)]
@@ -36,4 +37,51 @@ pub struct Config {
     /// Polling interval for external watch (seconds)
     #[arg(long, default_value = "60", env = "WATCH_POLL_INTERVAL")]
     pub watch_poll_interval_secs: u32,
+
+    /// Random spread of metadata refreshes, as a fraction of the poll interval
+    /// (0.2 = ±20%), so many watched files don't refresh in bursts
+    #[arg(long, default_value = "0.2", env = "METADATA_CACHE_JITTER")]
+    pub metadata_cache_jitter: f64,
+
+    /// Maximum concurrent uploads per tenant
+    #[arg(long, default_value = "4", env = "MAX_CONCURRENT_UPLOADS")]
+    pub max_concurrent_uploads: usize,
+
+    /// Upload bandwidth cap per tenant, in bytes per second (0 = unlimited)
+    #[arg(long, default_value = "0", env = "UPLOAD_BANDWIDTH_LIMIT")]
+    pub upload_bandwidth_bytes_per_sec: u64,
+
+    /// Uploads per second per Google Drive account (shared by every session
+    /// using it; 0 = unlimited). Waiting uploads go most recent first
+    #[arg(long, default_value = "3", env = "DRIVE_UPLOADS_PER_SEC")]
+    pub drive_uploads_per_sec: f64,
+
+    /// Waiting uploads released together per Drive account (also the burst
+    /// allowed after a quiet period)
+    #[arg(long, default_value = "5", env = "DRIVE_UPLOAD_BATCH_SIZE")]
+    pub drive_upload_batch_size: usize,
+
+    /// Directory for syncs queued while Google Drive is unreachable
+    /// (mount a volume here so they survive restarts)
+    #[arg(long, default_value = "./sync-queue", env = "SYNC_QUEUE_DIR")]
+    pub sync_queue_dir: std::path::PathBuf,
+
+    /// Longest delay between retries of a queued sync (seconds)
+    #[arg(long, default_value = "900", env = "SYNC_RETRY_MAX_BACKOFF")]
+    pub sync_retry_max_backoff_secs: u64,
+
+    /// Queued syncs older than this are dropped (seconds)
+    #[arg(long, default_value = "86400", env = "SYNC_RETRY_MAX_AGE")]
+    pub sync_retry_max_age_secs: u64,
+
+    /// Log output format: text or json (one object per line, with the
+    /// request, tenant and session IDs of the enclosing spans)
+    #[arg(long, default_value = "text", env = "LOG_FORMAT")]
+    pub log_format: LogFormat,
+
+    /// TOML configuration file (keys are the long option names). Flags and
+    /// environment variables take precedence over it; it is reloaded on
+    /// SIGHUP and when it changes
+    #[arg(long, env = "CONFIG_FILE")]
+    pub config: Option<std::path::PathBuf>,
 }
@@ -2,10 +2,13 @@
 //!
 //! Mirrors the pattern from `docx-mcp-sse-proxy/src/auth.rs`.
 
+use docx_storage_core::CircuitBreaker;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
+use crate::breaker::send_guarded;
+
 /// An OAuth connection record from D1.
 #[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
@@ -27,6 +30,21 @@ pub struct OAuthConnection {
     pub scopes: String,
 }
 
+/// A recent or favorite file record from D1.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecentFileRow {
+    #[serde(rename = "connectionId")]
+    pub connection_id: String,
+    #[serde(rename = "fileId")]
+    pub file_id: String,
+    pub path: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: i64,
+    pub favorite: i64,
+}
+
 /// D1 query request body.
 #[derive(Serialize)]
 struct D1QueryRequest {
@@ -52,12 +70,13 @@ struct D1Error {
     message: String,
 }
 
-/// Client for querying D1 oauth_connection table via Cloudflare REST API.
+/// Client for querying D1 (oauth_connection, recent_file, watch_change_queue) via Cloudflare REST API.
 pub struct D1Client {
     http: Client,
     account_id: String,
     api_token: String,
     database_id: String,
+    breaker: CircuitBreaker,
 }
 
 impl D1Client {
@@ -67,6 +86,7 @@ impl D1Client {
             account_id,
             api_token,
             database_id,
+            breaker: CircuitBreaker::new("D1"),
         }
     }
 
@@ -88,14 +108,13 @@ impl D1Client {
             params,
         };
 
-        let response = self
+        let request = self
             .http
-            .post(&self.query_url())
+            .post(self.query_url())
             .header("Authorization", format!("Bearer {}", self.api_token))
             .header("Content-Type", "application/json")
-            .json(&query)
-            .send()
-            .await?;
+            .json(&query);
+        let response = send_guarded(&self.breaker, request).await?;
 
         let status = response.status();
         let body = response.text().await?;
@@ -198,4 +217,129 @@ impl D1Client {
 
         Ok(())
     }
+
+    /// Record a file as used at `file.last_used_at`, then prune non-favorites
+    /// beyond `keep`. `file.favorite` is ignored (an existing pin is kept).
+    pub async fn touch_recent_file(
+        &self,
+        tenant_id: &str,
+        file: &RecentFileRow,
+        keep: usize,
+    ) -> anyhow::Result<()> {
+        self.execute_query(
+            "INSERT INTO recent_file \
+             (tenantId, connectionId, fileId, path, displayName, lastUsedAt, favorite) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0) \
+             ON CONFLICT (tenantId, connectionId, fileId) DO UPDATE \
+             SET path = excluded.path, displayName = excluded.displayName, \
+             lastUsedAt = excluded.lastUsedAt",
+            vec![
+                tenant_id.to_string(),
+                file.connection_id.clone(),
+                file.file_id.clone(),
+                file.path.clone(),
+                file.display_name.clone(),
+                file.last_used_at.to_string(),
+            ],
+        )
+        .await?;
+
+        self.execute_query(
+            "DELETE FROM recent_file WHERE tenantId = ?1 AND favorite = 0 AND rowid NOT IN \
+             (SELECT rowid FROM recent_file WHERE tenantId = ?1 AND favorite = 0 \
+             ORDER BY lastUsedAt DESC LIMIT ?2)",
+            vec![tenant_id.to_string(), keep.to_string()],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// List a tenant's recent files, most recent first.
+    pub async fn list_recent_files(&self, tenant_id: &str) -> anyhow::Result<Vec<RecentFileRow>> {
+        let results = self
+            .execute_query(
+                "SELECT connectionId, fileId, path, displayName, lastUsedAt, favorite \
+                 FROM recent_file WHERE tenantId = ?1 ORDER BY lastUsedAt DESC",
+                vec![tenant_id.to_string()],
+            )
+            .await?;
+
+        let mut files = Vec::new();
+        for row in results {
+            match serde_json::from_value(row) {
+                Ok(file) => files.push(file),
+                Err(e) => warn!("Failed to parse recent file: {}", e),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Pin a file as a favorite (adding it if needed) or unpin it.
+    pub async fn set_favorite(
+        &self,
+        tenant_id: &str,
+        file: &RecentFileRow,
+        pinned: bool,
+    ) -> anyhow::Result<()> {
+        if pinned {
+            self.execute_query(
+                "INSERT INTO recent_file \
+                 (tenantId, connectionId, fileId, path, displayName, lastUsedAt, favorite) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1) \
+                 ON CONFLICT (tenantId, connectionId, fileId) DO UPDATE SET favorite = 1",
+                vec![
+                    tenant_id.to_string(),
+                    file.connection_id.clone(),
+                    file.file_id.clone(),
+                    file.path.clone(),
+                    file.display_name.clone(),
+                    file.last_used_at.to_string(),
+                ],
+            )
+            .await?;
+        } else {
+            self.execute_query(
+                "UPDATE recent_file SET favorite = 0 \
+                 WHERE tenantId = ?1 AND connectionId = ?2 AND fileId = ?3",
+                vec![
+                    tenant_id.to_string(),
+                    file.connection_id.clone(),
+                    file.file_id.clone(),
+                ],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a tenant's serialized change queue, if one was saved.
+    pub async fn load_change_queue(&self, tenant_id: &str) -> anyhow::Result<Option<String>> {
+        let results = self
+            .execute_query(
+                "SELECT queue FROM watch_change_queue WHERE tenantId = ?1",
+                vec![tenant_id.to_string()],
+            )
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .next()
+            .and_then(|row| row.get("queue").and_then(|q| q.as_str()).map(String::from)))
+    }
+
+    /// Save a tenant's serialized change queue.
+    pub async fn save_change_queue(&self, tenant_id: &str, queue: &str) -> anyhow::Result<()> {
+        let now = chrono::Utc::now().to_rfc3339();
+        self.execute_query(
+            "INSERT INTO watch_change_queue (tenantId, queue, updatedAt) VALUES (?1, ?2, ?3) \
+             ON CONFLICT (tenantId) DO UPDATE SET queue = excluded.queue, updatedAt = excluded.updatedAt",
+            vec![tenant_id.to_string(), queue.to_string(), now],
+        )
+        .await?;
+
+        Ok(())
+    }
 }
@@ -2,9 +2,114 @@
 //!
 //! Token is passed per-call by the caller (TokenManager resolves it from D1).
 
-use reqwest::Client;
+use std::time::Duration;
+
+use md5::{Digest, Md5};
+use docx_storage_core::{parse_retry_after, CircuitBreaker, CircuitOpen, StorageError};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
 use serde::Deserialize;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
+
+use crate::breaker::send_guarded;
+
+/// MIME type Google Drive uses for folders.
+pub const FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+
+const DOCX_MIME_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Uploads of at least this many bytes use a resumable session instead of
+/// a single request.
+pub const RESUMABLE_THRESHOLD: usize = 5 * 1024 * 1024;
+
+/// Chunk size for resumable uploads (Drive requires a multiple of 256 KiB).
+const RESUMABLE_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Retries of a single resumable chunk before giving up.
+const RESUMABLE_MAX_RETRIES: u32 = 5;
+
+/// Non-success response to an upload (create or update).
+#[derive(Debug, thiserror::Error)]
+#[error("Google Drive {operation} error {status}: {body}")]
+pub struct UploadError {
+    operation: &'static str,
+    status: StatusCode,
+    body: String,
+    /// Delay Drive asked for with `Retry-After`
+    retry_after: Option<Duration>,
+}
+
+impl UploadError {
+    async fn from_response(operation: &'static str, resp: Response) -> Self {
+        let status = resp.status();
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = resp.text().await.unwrap_or_default();
+        Self {
+            operation,
+            status,
+            body,
+            retry_after,
+        }
+    }
+}
+
+/// The content stored by Drive doesn't match what was uploaded.
+#[derive(Debug, thiserror::Error)]
+#[error("Checksum mismatch after uploading file {file_id}: sent {expected}, Drive has {actual}")]
+pub struct ChecksumMismatch {
+    file_id: String,
+    expected: String,
+    actual: String,
+}
+
+/// Whether an error from this client is worth retrying later: the network
+/// is unreachable, Drive is rate limiting (429), failing (5xx) or behind an
+/// open circuit, or the upload arrived corrupted.
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        if cause.is::<ChecksumMismatch>() || cause.is::<CircuitOpen>() {
+            return true;
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return err.is_connect() || err.is_timeout() || err.is_request();
+        }
+        if let Some(err) = cause.downcast_ref::<UploadError>() {
+            return err.status == StatusCode::TOO_MANY_REQUESTS || err.status.is_server_error();
+        }
+        false
+    })
+}
+
+/// The storage error for a failure Drive explained: throttling, expired or
+/// revoked credentials, a full Drive, a missing file. `None` for other
+/// failures.
+pub fn classify_error(context: &str, e: &anyhow::Error) -> Option<StorageError> {
+    let err = e.chain().find_map(|cause| cause.downcast_ref::<UploadError>())?;
+    let message = format!("{}: {}", context, e);
+    // Drive reports most quota and rate errors as 403, told apart by reason
+    let rate_limited = err.body.contains("rateLimitExceeded");
+    match err.status {
+        StatusCode::TOO_MANY_REQUESTS => Some(StorageError::Throttled {
+            message,
+            retry_after: err.retry_after,
+        }),
+        StatusCode::FORBIDDEN if rate_limited => Some(StorageError::Throttled {
+            message,
+            retry_after: err.retry_after,
+        }),
+        StatusCode::FORBIDDEN if err.body.contains("storageQuotaExceeded") => {
+            Some(StorageError::QuotaExceeded(message))
+        }
+        StatusCode::UNAUTHORIZED => Some(StorageError::Unauthenticated(message)),
+        StatusCode::NOT_FOUND => Some(StorageError::NotFound(message)),
+        StatusCode::PRECONDITION_FAILED => Some(StorageError::PreconditionFailed(message)),
+        _ => None,
+    }
+}
 
 /// Metadata returned by Google Drive API.
 #[derive(Debug, Clone, Deserialize)]
@@ -22,6 +127,17 @@ pub struct FileMetadata {
     pub head_revision_id: Option<String>,
 }
 
+/// Result of a (possibly conditional) metadata request.
+#[derive(Debug, Clone)]
+pub enum MetadataFetch {
+    /// Metadata changed (or no validator was sent), with the response ETag.
+    Modified(FileMetadata, Option<String>),
+    /// The ETag sent with `If-None-Match` still matches (HTTP 304).
+    NotModified,
+    /// The file doesn't exist (or is no longer accessible).
+    NotFound,
+}
+
 /// A file entry from Drive API files.list.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -45,34 +161,90 @@ struct FileListResponse {
     next_page_token: Option<String>,
 }
 
+/// File fields returned by an upload.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadedFile {
+    id: String,
+    #[serde(default)]
+    md5_checksum: Option<String>,
+}
+
+/// Check the uploaded content against the MD5 Drive computed.
+fn verify_checksum(uploaded: &UploadedFile, data: &[u8]) -> anyhow::Result<()> {
+    let Some(actual) = &uploaded.md5_checksum else {
+        debug!("No checksum returned for file {}, skipping verification", uploaded.id);
+        return Ok(());
+    };
+    let expected = hex::encode(Md5::digest(data));
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(ChecksumMismatch {
+            file_id: uploaded.id.clone(),
+            expected,
+            actual: actual.clone(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Bytes committed by Drive, from the `Range` header of a 308 response.
+/// No header means nothing was received yet.
+fn committed_bytes(resp: &reqwest::Response) -> usize {
+    resp.headers()
+        .get(reqwest::header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|range| range.rsplit('-').next())
+        .and_then(|last| last.parse::<usize>().ok())
+        .map_or(0, |last| last + 1)
+}
+
 /// Google Drive API client (stateless — token provided per-call).
 pub struct GDriveClient {
     http: Client,
+    breaker: CircuitBreaker,
 }
 
 impl GDriveClient {
     pub fn new() -> Self {
         Self {
             http: Client::new(),
+            breaker: CircuitBreaker::new("Google Drive"),
         }
     }
 
-    /// Get file metadata from Google Drive.
+    /// Send a Drive API request through the circuit breaker.
+    async fn send(&self, request: RequestBuilder) -> anyhow::Result<Response> {
+        send_guarded(&self.breaker, request).await
+    }
+
+    /// Get file metadata from Google Drive. With `if_none_match`, Drive answers
+    /// 304 (no quota-heavy body) when the file is unchanged.
     #[instrument(skip(self, token), level = "debug")]
     pub async fn get_metadata(
         &self,
         token: &str,
         file_id: &str,
-    ) -> anyhow::Result<Option<FileMetadata>> {
+        if_none_match: Option<&str>,
+    ) -> anyhow::Result<MetadataFetch> {
         let url = format!(
             "https://www.googleapis.com/drive/v3/files/{}?fields=id,size,modifiedTime,md5Checksum,headRevisionId",
             file_id
         );
 
-        let resp = self.http.get(&url).bearer_auth(token).send().await?;
+        let mut request = self.http.get(&url).bearer_auth(token);
+        if let Some(etag) = if_none_match {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let resp = self.send(request).await?;
+
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("Metadata for file {} not modified", file_id);
+            return Ok(MetadataFetch::NotModified);
+        }
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
-            return Ok(None);
+            return Ok(MetadataFetch::NotFound);
         }
 
         if !resp.status().is_success() {
@@ -81,12 +253,20 @@ impl GDriveClient {
             anyhow::bail!("Google Drive API error {}: {}", status, body);
         }
 
+        let etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
         let metadata: FileMetadata = resp.json().await?;
         debug!("Got metadata for file {}: {:?}", file_id, metadata);
-        Ok(Some(metadata))
+        Ok(MetadataFetch::Modified(metadata, etag))
     }
 
     /// Download file content from Google Drive.
+    ///
+    /// Google Docs have no binary content: they are exported to DOCX instead
+    /// (Drive limits exports to 10 MB).
     #[instrument(skip(self, token), level = "debug")]
     pub async fn download_file(
         &self,
@@ -98,7 +278,7 @@ impl GDriveClient {
             file_id
         );
 
-        let resp = self.http.get(&url).bearer_auth(token).send().await?;
+        let resp = self.send(self.http.get(&url).bearer_auth(token)).await?;
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
             return Ok(None);
@@ -107,6 +287,9 @@ impl GDriveClient {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
+            if status == reqwest::StatusCode::FORBIDDEN && body.contains("fileNotDownloadable") {
+                return self.export_docx(token, file_id).await;
+            }
             anyhow::bail!("Google Drive download error {}: {}", status, body);
         }
 
@@ -115,43 +298,90 @@ impl GDriveClient {
         Ok(Some(bytes.to_vec()))
     }
 
-    /// Upload (update) file content on Google Drive.
-    #[instrument(skip(self, token, data), level = "debug", fields(data_len = data.len()))]
-    pub async fn update_file(
-        &self,
-        token: &str,
-        file_id: &str,
-        data: &[u8],
-    ) -> anyhow::Result<()> {
+    /// Export a Google Doc as DOCX.
+    async fn export_docx(&self, token: &str, file_id: &str) -> anyhow::Result<Option<Vec<u8>>> {
         let url = format!(
-            "https://www.googleapis.com/upload/drive/v3/files/{}?uploadType=media",
+            "https://www.googleapis.com/drive/v3/files/{}/export",
             file_id
         );
 
         let resp = self
-            .http
-            .patch(&url)
-            .bearer_auth(token)
-            .header(
-                "Content-Type",
-                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            .send(
+                self.http
+                    .get(&url)
+                    .query(&[("mimeType", DOCX_MIME_TYPE)])
+                    .bearer_auth(token),
             )
-            .body(data.to_vec())
-            .send()
             .await?;
 
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Google Drive upload error {}: {}", status, body);
+            anyhow::bail!("Google Drive export error {}: {}", status, body);
         }
 
+        let bytes = resp.bytes().await?;
+        debug!("Exported {} bytes of DOCX for Google Doc {}", bytes.len(), file_id);
+        Ok(Some(bytes.to_vec()))
+    }
+
+    /// Upload (update) file content on Google Drive.
+    ///
+    /// Files of at least [`RESUMABLE_THRESHOLD`] bytes go through a resumable
+    /// upload session. The content is verified against Drive's MD5 afterwards,
+    /// except for Google Docs, which Drive converts from the DOCX and which
+    /// have no MD5.
+    #[instrument(skip(self, token, data), level = "debug", fields(data_len = data.len()))]
+    pub async fn update_file(
+        &self,
+        token: &str,
+        file_id: &str,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        let uploaded = if data.len() >= RESUMABLE_THRESHOLD {
+            let url = format!(
+                "https://www.googleapis.com/upload/drive/v3/files/{}?uploadType=resumable&fields=id,md5Checksum",
+                file_id
+            );
+            self.resumable_upload(token, Method::PATCH, &url, None, data, "upload")
+                .await?
+        } else {
+            let url = format!(
+                "https://www.googleapis.com/upload/drive/v3/files/{}?uploadType=media&fields=id,md5Checksum",
+                file_id
+            );
+
+            let resp = self
+                .send(
+                    self.http
+                        .patch(&url)
+                        .bearer_auth(token)
+                        .header("Content-Type", DOCX_MIME_TYPE)
+                        .body(data.to_vec()),
+                )
+                .await?;
+
+            if !resp.status().is_success() {
+                return Err(UploadError::from_response("upload", resp).await.into());
+            }
+
+            resp.json::<UploadedFile>().await?
+        };
+
+        verify_checksum(&uploaded, data)?;
         debug!("Updated file {} ({} bytes)", file_id, data.len());
         Ok(())
     }
 
     /// Create a new file on Google Drive.
     /// Returns the new file's ID.
+    ///
+    /// Like [`Self::update_file`], large files use a resumable upload and
+    /// the content is checksum-verified.
     #[instrument(skip(self, token, data), level = "debug", fields(data_len = data.len()))]
     pub async fn create_file(
         &self,
@@ -160,70 +390,204 @@ impl GDriveClient {
         parent_id: Option<&str>,
         data: &[u8],
     ) -> anyhow::Result<String> {
-        // Build multipart/related body manually:
-        // Google Drive v3 uploadType=multipart expects a multipart/related body
-        // with a JSON metadata part and a file content part.
-        let boundary = "docx_mcp_boundary";
-        let mime_type =
-            "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
-
-        let parents_json = match parent_id {
-            Some(pid) => format!(r#","parents":["{}"]"#, pid),
-            None => String::new(),
+        let mut metadata = serde_json::json!({
+            "name": name,
+            "mimeType": DOCX_MIME_TYPE,
+        });
+        if let Some(pid) = parent_id {
+            metadata["parents"] = serde_json::json!([pid]);
+        }
+        let metadata = metadata.to_string();
+
+        let created = if data.len() >= RESUMABLE_THRESHOLD {
+            let url = "https://www.googleapis.com/upload/drive/v3/files?uploadType=resumable&fields=id,md5Checksum";
+            self.resumable_upload(token, Method::POST, url, Some(&metadata), data, "create")
+                .await?
+        } else {
+            // Build multipart/related body manually:
+            // Google Drive v3 uploadType=multipart expects a multipart/related body
+            // with a JSON metadata part and a file content part.
+            let boundary = "docx_mcp_boundary";
+
+            let mut body = Vec::new();
+            // Metadata part
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            body.extend_from_slice(b"Content-Type: application/json; charset=UTF-8\r\n\r\n");
+            body.extend_from_slice(metadata.as_bytes());
+            body.extend_from_slice(b"\r\n");
+            // File content part
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", DOCX_MIME_TYPE).as_bytes());
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+            // Closing boundary
+            body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+            let url = "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&fields=id,md5Checksum";
+
+            let resp = self
+                .send(
+                    self.http
+                        .post(url)
+                        .bearer_auth(token)
+                        .header(
+                            "Content-Type",
+                            format!("multipart/related; boundary={}", boundary),
+                        )
+                        .body(body),
+                )
+                .await?;
+
+            if !resp.status().is_success() {
+                return Err(UploadError::from_response("create", resp).await.into());
+            }
+
+            resp.json::<UploadedFile>().await?
         };
 
-        let metadata = format!(
-            r#"{{"name":"{}","mimeType":"{}"{}}}"#,
-            name, mime_type, parents_json
+        verify_checksum(&created, data)?;
+        debug!(
+            "Created file '{}' with ID {} ({} bytes)",
+            name,
+            created.id,
+            data.len()
         );
+        Ok(created.id)
+    }
 
-        let mut body = Vec::new();
-        // Metadata part
-        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
-        body.extend_from_slice(b"Content-Type: application/json; charset=UTF-8\r\n\r\n");
-        body.extend_from_slice(metadata.as_bytes());
-        body.extend_from_slice(b"\r\n");
-        // File content part
-        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
-        body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", mime_type).as_bytes());
-        body.extend_from_slice(data);
-        body.extend_from_slice(b"\r\n");
-        // Closing boundary
-        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
-
-        let url =
-            "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&fields=id";
+    /// Upload `data` through a resumable upload session.
+    ///
+    /// The content is sent in [`RESUMABLE_CHUNK_SIZE`] chunks. After a
+    /// transient failure, Drive is asked how many bytes it committed and the
+    /// upload resumes from there; an expired session is restarted from scratch.
+    async fn resumable_upload(
+        &self,
+        token: &str,
+        method: Method,
+        url: &str,
+        metadata: Option<&str>,
+        data: &[u8],
+        operation: &'static str,
+    ) -> anyhow::Result<UploadedFile> {
+        let total = data.len();
+        let mut session = self
+            .start_resumable_session(token, method.clone(), url, metadata, total, operation)
+            .await?;
+        let mut offset = 0;
+        let mut attempt = 0;
+
+        loop {
+            let end = (offset + RESUMABLE_CHUNK_SIZE).min(total);
+            let sent = self
+                .send(
+                    self.http
+                        .put(&session)
+                        .bearer_auth(token)
+                        .header(
+                            reqwest::header::CONTENT_RANGE,
+                            format!("bytes {}-{}/{}", offset, end - 1, total),
+                        )
+                        .body(data[offset..end].to_vec()),
+                )
+                .await;
+
+            let error: anyhow::Error = match sent {
+                Ok(resp) if resp.status().is_success() => return Ok(resp.json().await?),
+                Ok(resp) if resp.status() == StatusCode::PERMANENT_REDIRECT => {
+                    // 308 Resume Incomplete: the chunk was committed
+                    offset = committed_bytes(&resp);
+                    attempt = 0;
+                    continue;
+                }
+                Ok(resp) => UploadError::from_response(operation, resp).await.into(),
+                Err(e) => e,
+            };
+
+            let expired = error
+                .downcast_ref::<UploadError>()
+                .is_some_and(|e| matches!(e.status, StatusCode::NOT_FOUND | StatusCode::GONE));
+            // Drive is down: retrying now would only be rejected again
+            if error.is::<CircuitOpen>() {
+                return Err(error);
+            }
+            if attempt >= RESUMABLE_MAX_RETRIES || !(expired || is_transient(&error)) {
+                return Err(error);
+            }
+            attempt += 1;
+
+            let delay = Duration::from_millis(500 << attempt);
+            warn!(
+                "Resumable {} interrupted at {}/{} bytes (attempt {}/{}), retrying in {:?}: {}",
+                operation, offset, total, attempt, RESUMABLE_MAX_RETRIES, delay, error
+            );
+            // Stop here if whoever asked for the upload has given up
+            docx_storage_core::sleep_before_retry(delay).await?;
+
+            if expired {
+                session = self
+                    .start_resumable_session(token, method.clone(), url, metadata, total, operation)
+                    .await?;
+                offset = 0;
+                continue;
+            }
+
+            // Ask Drive how much it received before resuming; if the query
+            // fails too, the next chunk attempt fails and retries again
+            let status = self
+                .send(
+                    self.http
+                        .put(&session)
+                        .bearer_auth(token)
+                        .header(reqwest::header::CONTENT_RANGE, format!("bytes */{}", total)),
+                )
+                .await;
+            match status {
+                Ok(resp) if resp.status().is_success() => return Ok(resp.json().await?),
+                Ok(resp) if resp.status() == StatusCode::PERMANENT_REDIRECT => {
+                    offset = committed_bytes(&resp);
+                }
+                Ok(resp) => debug!("Upload status query returned {}", resp.status()),
+                Err(e) => debug!("Upload status query failed: {}", e),
+            }
+        }
+    }
 
-        let resp = self
+    /// Open a resumable upload session; returns the session URI.
+    async fn start_resumable_session(
+        &self,
+        token: &str,
+        method: Method,
+        url: &str,
+        metadata: Option<&str>,
+        total: usize,
+        operation: &'static str,
+    ) -> anyhow::Result<String> {
+        let mut request = self
             .http
-            .post(url)
+            .request(method, url)
             .bearer_auth(token)
-            .header(
-                "Content-Type",
-                format!("multipart/related; boundary={}", boundary),
-            )
-            .body(body)
-            .send()
-            .await?;
+            .header("X-Upload-Content-Type", DOCX_MIME_TYPE)
+            .header("X-Upload-Content-Length", total.to_string());
+        request = match metadata {
+            Some(metadata) => request
+                .header("Content-Type", "application/json; charset=UTF-8")
+                .body(metadata.to_string()),
+            None => request.header(reqwest::header::CONTENT_LENGTH, 0),
+        };
+        let resp = self.send(request).await?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Google Drive create error {}: {}", status, body);
+            return Err(UploadError::from_response(operation, resp).await.into());
         }
 
-        #[derive(Deserialize)]
-        struct CreateResponse {
-            id: String,
-        }
-        let created: CreateResponse = resp.json().await?;
-        debug!(
-            "Created file '{}' with ID {} ({} bytes)",
-            name,
-            created.id,
-            data.len()
-        );
-        Ok(created.id)
+        let session = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow::anyhow!("Resumable {} session has no Location header", operation))?
+            .to_string();
+        debug!("Opened resumable {} session for {} bytes", operation, total);
+        Ok(session)
     }
 
     /// List files in a folder on Google Drive.
@@ -241,22 +605,111 @@ impl GDriveClient {
             parent_id
         );
 
+        let (files, next_page_token) = self
+            .query_files(token, &query, Some("folder,name"), page_token, page_size)
+            .await?;
+        debug!("Listed {} files in folder {}", files.len(), parent_id);
+
+        Ok((files, next_page_token))
+    }
+
+    /// Search files whose name contains `name` and whose MIME type is `mime_type`.
+    /// `parent_ids` restricts results to direct children of these folders;
+    /// `None` searches the whole drive.
+    pub async fn search_files(
+        &self,
+        token: &str,
+        name: &str,
+        mime_type: &str,
+        parent_ids: Option<&[String]>,
+        page_token: Option<&str>,
+        page_size: u32,
+    ) -> anyhow::Result<(Vec<DriveFileEntry>, Option<String>)> {
+        let mut query = format!(
+            "name contains '{}' and trashed=false and mimeType='{}'",
+            escape_query(name),
+            escape_query(mime_type)
+        );
+        if let Some(parents) = parent_ids {
+            let clause = parents
+                .iter()
+                .map(|id| format!("'{}' in parents", escape_query(id)))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            query.push_str(&format!(" and ({})", clause));
+        }
+
+        let (files, next_page_token) = self
+            .query_files(token, &query, Some("name"), page_token, page_size)
+            .await?;
+        debug!("Found {} files matching '{}'", files.len(), name);
+
+        Ok((files, next_page_token))
+    }
+
+    /// Collect `root_id` and the IDs of the folders below it, breadth-first,
+    /// stopping at `limit` folders.
+    pub async fn list_folder_tree(
+        &self,
+        token: &str,
+        root_id: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut folders = vec![root_id.to_string()];
+        let mut next = 0;
+
+        while next < folders.len() && folders.len() < limit {
+            let query = format!(
+                "'{}' in parents and trashed=false and mimeType='{}'",
+                escape_query(&folders[next]),
+                FOLDER_MIME_TYPE
+            );
+            next += 1;
+
+            let mut page_token: Option<String> = None;
+            loop {
+                let (children, next_page_token) = self
+                    .query_files(token, &query, None, page_token.as_deref(), 1000)
+                    .await?;
+                folders.extend(children.into_iter().map(|f| f.id));
+                if folders.len() >= limit || next_page_token.is_none() {
+                    break;
+                }
+                page_token = next_page_token;
+            }
+        }
+
+        folders.truncate(limit);
+        Ok(folders)
+    }
+
+    /// Run a Drive files.list query and return one page of results.
+    async fn query_files(
+        &self,
+        token: &str,
+        query: &str,
+        order_by: Option<&str>,
+        page_token: Option<&str>,
+        page_size: u32,
+    ) -> anyhow::Result<(Vec<DriveFileEntry>, Option<String>)> {
         let mut request = self
             .http
             .get("https://www.googleapis.com/drive/v3/files")
             .bearer_auth(token)
             .query(&[
-                ("q", query.as_str()),
+                ("q", query),
                 ("fields", "nextPageToken,files(id,name,mimeType,size,modifiedTime)"),
                 ("pageSize", &page_size.to_string()),
-                ("orderBy", "folder,name"),
             ]);
 
+        if let Some(order_by) = order_by {
+            request = request.query(&[("orderBy", order_by)]);
+        }
         if let Some(pt) = page_token {
             request = request.query(&[("pageToken", pt)]);
         }
 
-        let resp = request.send().await?;
+        let resp = self.send(request).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -265,12 +718,186 @@ impl GDriveClient {
         }
 
         let list_response: FileListResponse = resp.json().await?;
-        debug!(
-            "Listed {} files in folder {}",
-            list_response.files.len(),
-            parent_id
+        Ok((list_response.files, list_response.next_page_token))
+    }
+}
+
+/// Escape a value for use inside a single-quoted Drive query string.
+fn escape_query(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// A Drive resumable upload session receiving chunks. With `fail_at`, the
+    /// chunk starting there is cut short: half of it is committed and the
+    /// request fails with a 503.
+    struct Session {
+        received: Mutex<Vec<u8>>,
+        fail_at: Mutex<Option<usize>>,
+        md5_checksum: Option<String>,
+    }
+
+    impl Session {
+        fn new(fail_at: Option<usize>, md5_checksum: Option<String>) -> Self {
+            Self {
+                received: Mutex::new(Vec::new()),
+                fail_at: Mutex::new(fail_at),
+                md5_checksum,
+            }
+        }
+    }
+
+    impl Respond for Session {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let range = request.headers["content-range"]
+                .to_str()
+                .unwrap()
+                .to_string();
+            let (span, total) = range
+                .strip_prefix("bytes ")
+                .unwrap()
+                .split_once('/')
+                .unwrap();
+            let total: usize = total.parse().unwrap();
+            let mut received = self.received.lock().unwrap();
+
+            if span != "*" {
+                let first: usize = span.split_once('-').unwrap().0.parse().unwrap();
+                assert_eq!(
+                    first,
+                    received.len(),
+                    "chunk doesn't resume where Drive stopped"
+                );
+                let mut fail_at = self.fail_at.lock().unwrap();
+                if *fail_at == Some(first) {
+                    *fail_at = None;
+                    received.extend_from_slice(&request.body[..request.body.len() / 2]);
+                    return ResponseTemplate::new(503);
+                }
+                received.extend_from_slice(&request.body);
+            }
+
+            if received.len() == total {
+                let md5 = self
+                    .md5_checksum
+                    .clone()
+                    .unwrap_or_else(|| hex::encode(Md5::digest(&*received)));
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"id": "f1", "md5Checksum": md5}))
+            } else {
+                ResponseTemplate::new(308)
+                    .insert_header("Range", format!("bytes=0-{}", received.len() - 1))
+            }
+        }
+    }
+
+    async fn drive(session: Session) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Location", format!("{}/session", server.uri())),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/session"))
+            .respond_with(session)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn document(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    async fn upload(server: &MockServer, data: &[u8]) -> anyhow::Result<UploadedFile> {
+        let url = format!("{}/upload", server.uri());
+        GDriveClient::new()
+            .resumable_upload("token", Method::POST, &url, Some("{}"), data, "create")
+            .await
+    }
+
+    fn uploaded(md5_checksum: Option<&str>) -> UploadedFile {
+        UploadedFile {
+            id: "f1".to_string(),
+            md5_checksum: md5_checksum.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_sends_every_chunk_and_verifies_the_checksum() {
+        let data = document(RESUMABLE_CHUNK_SIZE + 1000);
+        let server = drive(Session::new(None, None)).await;
+
+        let uploaded = upload(&server, &data).await.unwrap();
+
+        assert_eq!(uploaded.id, "f1");
+        verify_checksum(&uploaded, &data).unwrap();
+        let puts = server.received_requests().await.unwrap();
+        assert_eq!(
+            puts.iter().filter(|r| r.url.path() == "/session").count(),
+            2
         );
+    }
 
-        Ok((list_response.files, list_response.next_page_token))
+    #[tokio::test]
+    async fn test_resumable_upload_resumes_from_the_bytes_drive_committed() {
+        let data = document(RESUMABLE_CHUNK_SIZE + 1000);
+        let server = drive(Session::new(Some(RESUMABLE_CHUNK_SIZE), None)).await;
+
+        let uploaded = upload(&server, &data).await.unwrap();
+
+        verify_checksum(&uploaded, &data).unwrap();
+        let requests = server.received_requests().await.unwrap();
+        let ranges: Vec<_> = requests
+            .iter()
+            .filter_map(|r| r.headers.get("content-range"))
+            .map(|range| range.to_str().unwrap().to_string())
+            .collect();
+        let total = data.len();
+        let resumed_at = RESUMABLE_CHUNK_SIZE + 500;
+        assert_eq!(
+            ranges,
+            [
+                format!("bytes 0-{}/{}", RESUMABLE_CHUNK_SIZE - 1, total),
+                format!("bytes {}-{}/{}", RESUMABLE_CHUNK_SIZE, total - 1, total),
+                format!("bytes */{}", total),
+                format!("bytes {}-{}/{}", resumed_at, total - 1, total),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resumable_upload_reports_a_corrupted_upload() {
+        let data = document(1000);
+        let server = drive(Session::new(None, Some("0".repeat(32)))).await;
+
+        let uploaded = upload(&server, &data).await.unwrap();
+        let error = verify_checksum(&uploaded, &data).unwrap_err();
+
+        assert!(error.is::<ChecksumMismatch>());
+        assert!(is_transient(&error));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let data = b"document";
+        let md5 = hex::encode(Md5::digest(data));
+
+        assert!(verify_checksum(&uploaded(Some(&md5)), data).is_ok());
+        assert!(verify_checksum(&uploaded(Some(&md5.to_uppercase())), data).is_ok());
+        assert!(verify_checksum(&uploaded(Some(&md5)), b"other").is_err());
+        // Google Docs have no MD5
+        assert!(verify_checksum(&uploaded(None), data).is_ok());
     }
 }
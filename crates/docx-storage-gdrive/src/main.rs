@@ -1,30 +1,36 @@
+mod breaker;
 mod browse;
+mod change_queue;
 mod config;
 mod d1_client;
 mod gdrive;
+mod scheduler;
 mod service_sync;
 mod service_watch;
 mod sync;
+mod throttle;
 mod token_manager;
 mod watch;
 
 use std::sync::Arc;
 
-use clap::Parser;
+use docx_storage_core::{CorrelationLayer, DeadlineLayer, FileSyncQueueStore, RetryingSyncBackend, SyncRetryPolicy};
 use tokio::signal;
 use tokio::sync::watch as tokio_watch;
 use tonic::transport::Server;
 use tonic_reflection::server::Builder as ReflectionBuilder;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
 
 use browse::GDriveBrowsableBackend;
+use change_queue::D1ChangeQueueStore;
 use config::Config;
 use d1_client::D1Client;
 use gdrive::GDriveClient;
+use scheduler::DriveRateLimiter;
 use service_sync::SourceSyncServiceImpl;
 use service_watch::ExternalWatchServiceImpl;
 use sync::GDriveSyncBackend;
+use throttle::UploadThrottle;
 use token_manager::TokenManager;
 use watch::GDriveWatchBackend;
 
@@ -38,17 +44,23 @@ pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("stor
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let (config, config_source) = docx_storage_core::load_config::<Config>();
 
-    let config = Config::parse();
+    // Initialize logging
+    docx_storage_core::init_tracing(config.log_format);
 
     info!("Starting docx-storage-gdrive server (multi-tenant)");
     info!("  Poll interval: {} secs", config.watch_poll_interval_secs);
+    info!("  Metadata cache jitter: {}", config.metadata_cache_jitter);
+    info!("  Sync queue dir: {}", config.sync_queue_dir.display());
+    info!(
+        "  Upload limits per tenant: {} concurrent, {} bytes/s (0 = unlimited)",
+        config.max_concurrent_uploads, config.upload_bandwidth_bytes_per_sec
+    );
+    info!(
+        "  Drive upload rate per account: {}/s in batches of {} (0 = unlimited)",
+        config.drive_uploads_per_sec, config.drive_upload_batch_size
+    );
 
     // Create D1 client for OAuth token storage
     let d1_client = Arc::new(D1Client::new(
@@ -69,14 +81,40 @@ async fn main() -> anyhow::Result<()> {
     // Create Google Drive API client (stateless — tokens provided per-call)
     let gdrive_client = Arc::new(GDriveClient::new());
 
-    // Create sync backend
-    let sync_backend: Arc<dyn docx_storage_core::SyncBackend> = Arc::new(
-        GDriveSyncBackend::new(gdrive_client.clone(), token_manager.clone()),
-    );
+    // Uploads of every session wait their turn per Drive account
+    let drive_limiter = Arc::new(DriveRateLimiter::new(
+        config.drive_uploads_per_sec,
+        config.drive_upload_batch_size,
+    ));
+    spawn_queue_metrics(drive_limiter.clone());
+
+    // Create sync backend; syncs failing while Drive is unreachable are queued
+    // on disk and retried with backoff
+    let retry_policy = SyncRetryPolicy {
+        max_backoff_secs: config.sync_retry_max_backoff_secs,
+        max_age_secs: config.sync_retry_max_age_secs,
+        ..Default::default()
+    };
+    let retrying_sync = Arc::new(RetryingSyncBackend::new(
+        Arc::new(GDriveSyncBackend::new(
+            d1_client.clone(),
+            gdrive_client.clone(),
+            token_manager.clone(),
+            Arc::new(UploadThrottle::new(
+                config.max_concurrent_uploads,
+                config.upload_bandwidth_bytes_per_sec,
+            )),
+            drive_limiter,
+        )),
+        Arc::new(FileSyncQueueStore::new(&config.sync_queue_dir)),
+        retry_policy,
+    ));
+    spawn_sync_retry(retrying_sync.clone());
+    let sync_backend: Arc<dyn docx_storage_core::SyncBackend> = retrying_sync;
 
     // Create browse backend
     let browse_backend: Arc<dyn docx_storage_core::BrowsableBackend> = Arc::new(
-        GDriveBrowsableBackend::new(d1_client, gdrive_client.clone(), token_manager.clone()),
+        GDriveBrowsableBackend::new(d1_client.clone(), gdrive_client.clone(), token_manager.clone()),
     );
 
     // Create watch backend
@@ -84,13 +122,32 @@ async fn main() -> anyhow::Result<()> {
         gdrive_client,
         token_manager,
         config.watch_poll_interval_secs,
+        config.metadata_cache_jitter,
     ));
 
+    // Reload the default poll interval with the configuration file
+    if let Some(path) = config_source.path() {
+        info!("  Config file: {} (reloaded on change or SIGHUP)", path.display());
+    }
+    let reloaded_watch = watch_backend.clone();
+    config_source.watch(move |config: Config| {
+        if config.watch_poll_interval_secs == 0 {
+            return Err("watch_poll_interval_secs must be positive".to_string());
+        }
+        reloaded_watch.set_default_poll_interval(config.watch_poll_interval_secs);
+        Ok(())
+    });
+
     // Create gRPC services (sync + watch only — no StorageService)
     let sync_service = SourceSyncServiceImpl::new(sync_backend, browse_backend);
     let sync_svc = proto::source_sync_service_server::SourceSyncServiceServer::new(sync_service);
 
-    let watch_service = ExternalWatchServiceImpl::new(watch_backend);
+    // Unacknowledged change events are kept in D1 so they survive restarts
+    let change_queue = Arc::new(docx_storage_core::DurableChangeQueue::new(Arc::new(
+        D1ChangeQueueStore::new(d1_client),
+    )));
+
+    let watch_service = ExternalWatchServiceImpl::new(watch_backend, change_queue);
     let watch_svc =
         proto::external_watch_service_server::ExternalWatchServiceServer::new(watch_service);
 
@@ -110,6 +167,8 @@ async fn main() -> anyhow::Result<()> {
     info!("Listening on tcp://{}", addr);
 
     Server::builder()
+        .layer(CorrelationLayer)
+        .layer(DeadlineLayer)
         .add_service(reflection_svc)
         .add_service(sync_svc)
         .add_service(watch_svc)
@@ -120,6 +179,43 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Periodically retry syncs queued while Google Drive was unreachable.
+fn spawn_sync_retry(sync: Arc<RetryingSyncBackend>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            match sync.retry_due().await {
+                Ok(0) => {}
+                Ok(completed) => info!("Completed {} queued syncs", completed),
+                Err(e) => warn!("Failed to retry queued syncs: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically report the depth of the Drive upload queue.
+fn spawn_queue_metrics(limiter: Arc<DriveRateLimiter>) {
+    tokio::spawn(async move {
+        let mut idle = true;
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(15)).await;
+            let stats = limiter.stats();
+            // Report once more when the queue drains, then stay quiet
+            if stats.queued == 0 && idle {
+                continue;
+            }
+            idle = stats.queued == 0;
+            info!(
+                queued = stats.queued,
+                accounts = stats.accounts,
+                oldest_wait_secs = stats.oldest_wait.as_secs(),
+                dispatched = stats.dispatched,
+                "Drive upload queue"
+            );
+        }
+    });
+}
+
 /// Create a shutdown signal that triggers on Ctrl+C or SIGTERM.
 fn create_shutdown_signal() -> tokio_watch::Receiver<bool> {
     let (tx, rx) = tokio_watch::channel(false);
@@ -0,0 +1,256 @@
+//! Prioritized, rate-limited dispatch of Drive uploads.
+//!
+//! Drive limits writes per user account (about 3 per second sustained).
+//! When many sessions auto-sync at once, firing every upload gets them
+//! throttled, and the rejected ones are queued and retried in a storm.
+//! Instead, uploads wait in a queue per Drive account (OAuth connection,
+//! shared by every session and tenant using it) and are released in batches
+//! as a token bucket refills:
+//! - the most recently requested sync goes first, since auto-sync runs on
+//!   edits and the document being worked on shouldn't wait behind a backlog;
+//! - a sync that has waited longer than [`MAX_PRIORITY_WAIT`] goes before
+//!   newer ones, so a steady stream of edits can't starve it.
+//!
+//! Queue depth is reported by [`DriveRateLimiter::stats`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Waiting longer than this takes precedence over recency.
+pub const MAX_PRIORITY_WAIT: Duration = Duration::from_secs(30);
+
+/// One upload waiting for its turn.
+struct Waiter {
+    seq: u64,
+    queued_at: Instant,
+    tx: oneshot::Sender<()>,
+}
+
+/// Token bucket and waiting uploads of one Drive account.
+struct UserQueue {
+    tokens: f64,
+    refilled_at: Instant,
+    waiting: Vec<Waiter>,
+    /// Whether a dispatcher task is releasing this queue.
+    dispatching: bool,
+}
+
+/// Queue depth metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Uploads waiting for a slot.
+    pub queued: usize,
+    /// Drive accounts with waiting uploads.
+    pub accounts: usize,
+    /// Longest current wait.
+    pub oldest_wait: Duration,
+    /// Uploads released since startup.
+    pub dispatched: u64,
+}
+
+/// Upload rate limiter shared by every session of a Drive account.
+pub struct DriveRateLimiter {
+    /// Uploads per second per account (0 = unlimited)
+    requests_per_sec: f64,
+    /// Uploads released together, and the bucket's capacity
+    batch_size: usize,
+    users: Mutex<HashMap<String, UserQueue>>,
+    next_seq: AtomicU64,
+    dispatched: AtomicU64,
+}
+
+impl DriveRateLimiter {
+    pub fn new(requests_per_sec: f64, batch_size: usize) -> Self {
+        Self {
+            requests_per_sec: requests_per_sec.max(0.0),
+            batch_size: batch_size.max(1),
+            users: Mutex::new(HashMap::new()),
+            next_seq: AtomicU64::new(0),
+            dispatched: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait until an upload to the Drive account `user` may start.
+    pub async fn acquire(self: &Arc<Self>, user: &str) {
+        if self.requests_per_sec == 0.0 {
+            return;
+        }
+
+        let rx = {
+            let mut users = self.users.lock().unwrap();
+            let queue = users.entry(user.to_string()).or_insert_with(|| UserQueue {
+                tokens: self.batch_size as f64,
+                refilled_at: Instant::now(),
+                waiting: Vec::new(),
+                dispatching: false,
+            });
+            self.refill(queue);
+            if queue.waiting.is_empty() && queue.tokens >= 1.0 {
+                queue.tokens -= 1.0;
+                self.dispatched.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+
+            let (tx, rx) = oneshot::channel();
+            queue.waiting.push(Waiter {
+                seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+                queued_at: Instant::now(),
+                tx,
+            });
+            if !queue.dispatching {
+                queue.dispatching = true;
+                tokio::spawn(Arc::clone(self).dispatch(user.to_string()));
+            }
+            rx
+        };
+
+        // The dispatcher never drops a waiter without releasing it
+        let _ = rx.await;
+    }
+
+    /// Release an account's waiting uploads as tokens become available, in
+    /// batches of up to `batch_size`.
+    async fn dispatch(self: Arc<Self>, user: String) {
+        loop {
+            let wait = {
+                let mut users = self.users.lock().unwrap();
+                let Some(queue) = users.get_mut(&user) else {
+                    return;
+                };
+                self.refill(queue);
+
+                // Uploads whose caller gave up (e.g. deadline) don't take a slot
+                queue.waiting.retain(|w| !w.tx.is_closed());
+                if queue.waiting.is_empty() {
+                    queue.dispatching = false;
+                    return;
+                }
+
+                let batch = queue.waiting.len().min(self.batch_size);
+                if queue.tokens >= batch as f64 {
+                    for _ in 0..batch {
+                        let index = next_waiter(&queue.waiting);
+                        let waiter = queue.waiting.swap_remove(index);
+                        queue.tokens -= 1.0;
+                        if waiter.tx.send(()).is_ok() {
+                            self.dispatched.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    debug!(
+                        "Released {} Drive uploads for connection {}, {} still queued",
+                        batch,
+                        user,
+                        queue.waiting.len()
+                    );
+                    continue;
+                }
+                Duration::from_secs_f64((batch as f64 - queue.tokens) / self.requests_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn refill(&self, queue: &mut UserQueue) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(queue.refilled_at).as_secs_f64();
+        queue.tokens =
+            (queue.tokens + elapsed * self.requests_per_sec).min(self.batch_size as f64);
+        queue.refilled_at = now;
+    }
+
+    /// Current queue depth.
+    pub fn stats(&self) -> QueueStats {
+        let users = self.users.lock().unwrap();
+        let now = Instant::now();
+        let mut stats = QueueStats {
+            dispatched: self.dispatched.load(Ordering::Relaxed),
+            ..Default::default()
+        };
+        for queue in users.values().filter(|q| !q.waiting.is_empty()) {
+            stats.accounts += 1;
+            stats.queued += queue.waiting.len();
+            for waiter in &queue.waiting {
+                stats.oldest_wait = stats.oldest_wait.max(now - waiter.queued_at);
+            }
+        }
+        stats
+    }
+}
+
+/// Index of the upload to release next: the oldest one past
+/// [`MAX_PRIORITY_WAIT`], otherwise the most recent.
+fn next_waiter(waiting: &[Waiter]) -> usize {
+    let starved = waiting
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| w.queued_at.elapsed() >= MAX_PRIORITY_WAIT)
+        .min_by_key(|(_, w)| w.seq);
+    let (index, _) = starved
+        .or_else(|| waiting.iter().enumerate().max_by_key(|(_, w)| w.seq))
+        .expect("waiting is not empty");
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_releases_most_recent_first_at_rate() {
+        let limiter = Arc::new(DriveRateLimiter::new(2.0, 1));
+        // The bucket starts with one token
+        limiter.acquire("conn-1").await;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for i in 0..3 {
+            let limiter = limiter.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                limiter.acquire("conn-1").await;
+                tx.send(i).unwrap();
+            });
+            tokio::task::yield_now().await;
+        }
+        tokio::task::yield_now().await;
+        assert_eq!(limiter.stats().queued, 3);
+        assert_eq!(limiter.stats().accounts, 1);
+
+        // Another account is not held up
+        limiter.acquire("conn-2").await;
+
+        let started = Instant::now();
+        let order = vec![
+            rx.recv().await.unwrap(),
+            rx.recv().await.unwrap(),
+            rx.recv().await.unwrap(),
+        ];
+        assert_eq!(order, vec![2, 1, 0]);
+        assert!(started.elapsed() >= Duration::from_millis(1500));
+        assert_eq!(limiter.stats().queued, 0);
+        assert_eq!(limiter.stats().dispatched, 5);
+    }
+
+    #[test]
+    fn test_starved_uploads_go_first() {
+        let now = Instant::now();
+        let waiter = |seq, waited| Waiter {
+            seq,
+            queued_at: now - waited,
+            tx: oneshot::channel().0,
+        };
+        let waiting = vec![
+            waiter(1, MAX_PRIORITY_WAIT * 2),
+            waiter(2, MAX_PRIORITY_WAIT + Duration::from_secs(1)),
+            waiter(3, Duration::ZERO),
+        ];
+        assert_eq!(next_waiter(&waiting), 0);
+        assert_eq!(next_waiter(&waiting[2..]), 0);
+        assert_eq!(next_waiter(&[waiter(4, Duration::ZERO), waiter(5, Duration::ZERO)]), 1);
+    }
+}
@@ -1,7 +1,10 @@
 use std::pin::Pin;
 use std::sync::Arc;
 
-use docx_storage_core::{BrowsableBackend, SourceDescriptor, SourceType, SyncBackend};
+use docx_storage_core::{
+    BrowsableBackend, FileListResult, FileSearchQuery, SourceDescriptor, SourceType, SyncBackend,
+    validate_session_id, validate_tenant_id,
+};
 use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
 use tracing::{debug, instrument};
@@ -28,9 +31,11 @@ impl SourceSyncServiceImpl {
     }
 
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
     }
 
     fn convert_source_type(proto_type: i32) -> SourceType {
@@ -94,6 +99,25 @@ impl SourceSyncServiceImpl {
             last_error: status.last_error.clone().unwrap_or_default(),
         }
     }
+
+    fn to_proto_file_list(result: FileListResult) -> ListConnectionFilesResponse {
+        ListConnectionFilesResponse {
+            files: result
+                .files
+                .into_iter()
+                .map(|f| proto::FileEntry {
+                    name: f.name,
+                    path: f.path,
+                    file_id: f.file_id.unwrap_or_default(),
+                    is_folder: f.is_folder,
+                    size_bytes: f.size_bytes as i64,
+                    modified_at_unix: f.modified_at,
+                    mime_type: f.mime_type.unwrap_or_default(),
+                })
+                .collect(),
+            next_page_token: result.next_page_token.unwrap_or_default(),
+        }
+    }
 }
 
 type DownloadFromSourceStream = Pin<Box<dyn Stream<Item = Result<DataChunk, Status>> + Send>>;
@@ -109,6 +133,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
     ) -> Result<Response<RegisterSourceResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        validate_session_id(&req.session_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
 
         let source = Self::convert_source_descriptor(req.source.as_ref())
             .ok_or_else(|| Status::invalid_argument("source is required"))?;
@@ -210,6 +235,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
 
         let tenant_id = tenant_id
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
         let session_id = session_id
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
@@ -223,9 +249,11 @@ impl SourceSyncService for SourceSyncServiceImpl {
                 success: true,
                 error: String::new(),
                 synced_at_unix: synced_at,
+                queued: false,
             })),
             Err(e) => Ok(Response::new(SyncToSourceResponse {
                 success: false,
+                queued: matches!(e, docx_storage_core::StorageError::Unavailable(_)),
                 error: e.to_string(),
                 synced_at_unix: 0,
             })),
@@ -274,6 +302,93 @@ impl SourceSyncService for SourceSyncServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_pending_syncs(
+        &self,
+        request: Request<ListPendingSyncsRequest>,
+    ) -> Result<Response<ListPendingSyncsResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let pending = self
+            .sync_backend
+            .list_pending_syncs(tenant_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let syncs = pending
+            .iter()
+            .map(|p| proto::PendingSync {
+                session_id: p.session_id.clone(),
+                source: Some(Self::to_proto_source_descriptor(&p.source)),
+                queued_at_unix: p.queued_at,
+                attempts: p.attempts as i32,
+                next_attempt_at_unix: p.next_attempt_at,
+                last_error: p.last_error.clone(),
+                size_bytes: p.size_bytes as i64,
+            })
+            .collect();
+
+        Ok(Response::new(ListPendingSyncsResponse { syncs }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_recent_files(
+        &self,
+        request: Request<ListRecentFilesRequest>,
+    ) -> Result<Response<ListRecentFilesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let recent = self
+            .sync_backend
+            .list_recent_files(tenant_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let limit = if req.limit > 0 { req.limit as usize } else { usize::MAX };
+        let files = recent
+            .iter()
+            .filter(|f| f.favorite || !req.favorites_only)
+            .take(limit)
+            .map(|f| proto::RecentFile {
+                source: Some(Self::to_proto_source_descriptor(&f.source)),
+                display_name: f.display_name.clone(),
+                last_used_at_unix: f.last_used_at,
+                favorite: f.favorite,
+            })
+            .collect();
+
+        Ok(Response::new(ListRecentFilesResponse { files }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn pin_favorite(
+        &self,
+        request: Request<PinFavoriteRequest>,
+    ) -> Result<Response<PinFavoriteResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let source = Self::convert_source_descriptor(req.source.as_ref())
+            .ok_or_else(|| Status::invalid_argument("source is required"))?;
+
+        match self
+            .sync_backend
+            .pin_favorite(tenant_id, source, req.pinned)
+            .await
+        {
+            Ok(()) => Ok(Response::new(PinFavoriteResponse {
+                success: true,
+                error: String::new(),
+            })),
+            Err(e) => Ok(Response::new(PinFavoriteResponse {
+                success: false,
+                error: e.to_string(),
+            })),
+        }
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn list_connections(
         &self,
@@ -295,6 +410,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
                 r#type: Self::to_proto_source_type(c.source_type),
                 display_name: c.display_name,
                 provider_account_id: c.provider_account_id.unwrap_or_default(),
+                backend: c.backend.unwrap_or_default(),
             })
             .collect();
 
@@ -335,24 +451,34 @@ impl SourceSyncService for SourceSyncServiceImpl {
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        let proto_files = result
-            .files
-            .into_iter()
-            .map(|f| proto::FileEntry {
-                name: f.name,
-                path: f.path,
-                file_id: f.file_id.unwrap_or_default(),
-                is_folder: f.is_folder,
-                size_bytes: f.size_bytes as i64,
-                modified_at_unix: f.modified_at,
-                mime_type: f.mime_type.unwrap_or_default(),
-            })
-            .collect();
+        Ok(Response::new(Self::to_proto_file_list(result)))
+    }
 
-        Ok(Response::new(ListConnectionFilesResponse {
-            files: proto_files,
-            next_page_token: result.next_page_token.unwrap_or_default(),
-        }))
+    #[instrument(skip(self, request), level = "debug")]
+    async fn search_connection_files(
+        &self,
+        request: Request<SearchConnectionFilesRequest>,
+    ) -> Result<Response<ListConnectionFilesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        let query = FileSearchQuery {
+            query: req.query,
+            path: req.path,
+            recursive: req.recursive,
+            mime_filter: non_empty(req.mime_filter),
+            page_token: non_empty(req.page_token),
+            page_size: if req.page_size > 0 { req.page_size as u32 } else { 50 },
+        };
+
+        let result = self
+            .browse_backend
+            .search_files(tenant_id, &req.connection_id, &query)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(Self::to_proto_file_list(result)))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -1,7 +1,12 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use docx_storage_core::{SourceDescriptor, SourceType, WatchBackend};
+use docx_storage_core::{
+    DurableChangeQueue, QueuedChange, SourceDescriptor, SourceType, WatchBackend,
+    validate_session_id, validate_tenant_id,
+};
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tonic::{Request, Response, Status};
@@ -12,20 +17,35 @@ use crate::watch::GDriveWatchBackend;
 use proto::external_watch_service_server::ExternalWatchService;
 use proto::*;
 
+/// Number of change checks per poll interval in `WatchChanges` streams.
+const POLL_TICKS_PER_INTERVAL: u32 = 4;
+
+/// How long a `WatchChanges` stream waits for an ack before resending an event.
+const REDELIVERY_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Implementation of the ExternalWatchService gRPC service for Google Drive.
 pub struct ExternalWatchServiceImpl {
     watch_backend: Arc<GDriveWatchBackend>,
+    change_queue: Arc<DurableChangeQueue>,
 }
 
 impl ExternalWatchServiceImpl {
-    pub fn new(watch_backend: Arc<GDriveWatchBackend>) -> Self {
-        Self { watch_backend }
+    pub fn new(
+        watch_backend: Arc<GDriveWatchBackend>,
+        change_queue: Arc<DurableChangeQueue>,
+    ) -> Self {
+        Self {
+            watch_backend,
+            change_queue,
+        }
     }
 
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
     }
 
     fn convert_source_type(proto_type: i32) -> SourceType {
@@ -77,6 +97,27 @@ impl ExternalWatchServiceImpl {
             docx_storage_core::ExternalChangeType::Deleted => 2,
             docx_storage_core::ExternalChangeType::Renamed => 3,
             docx_storage_core::ExternalChangeType::PermissionChanged => 4,
+            docx_storage_core::ExternalChangeType::Created => 5,
+        }
+    }
+
+    fn to_proto_event(queued: &QueuedChange) -> ExternalChangeEvent {
+        let change = &queued.event;
+        ExternalChangeEvent {
+            session_id: change.session_id.clone(),
+            change_type: Self::to_proto_change_type(change.change_type),
+            old_metadata: change
+                .old_metadata
+                .as_ref()
+                .map(Self::to_proto_source_metadata),
+            new_metadata: change
+                .new_metadata
+                .as_ref()
+                .map(Self::to_proto_source_metadata),
+            detected_at_unix: change.detected_at,
+            new_uri: change.new_uri.clone().unwrap_or_default(),
+            sequence: queued.sequence,
+            coalesced_count: queued.coalesced,
         }
     }
 }
@@ -94,6 +135,7 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
     ) -> Result<Response<StartWatchResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        validate_session_id(&req.session_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
 
         let source = Self::convert_source_descriptor(req.source.as_ref())
             .ok_or_else(|| Status::invalid_argument("source is required"))?;
@@ -140,6 +182,11 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
+        self.change_queue
+            .remove_session(tenant_id, &req.session_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
         Ok(Response::new(StopWatchResponse { success: true }))
     }
 
@@ -195,13 +242,23 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
         let (tx, rx) = mpsc::channel(100);
         let watch_backend = self.watch_backend.clone();
 
+        let change_queue = self.change_queue.clone();
+
         tokio::spawn(async move {
+            // When each queued event was last sent on this stream. The queue
+            // drops repeats of a pending or acked change, so ticks shorter than
+            // the poll interval don't resend it.
+            let mut sent_at: HashMap<(String, u64), Instant> = HashMap::new();
+
             loop {
-                // Use configured poll interval from the first watched session
+                // Use configured poll interval from the first watched session.
+                // Check several times per interval: the backend's metadata cache
+                // decides (with jitter) when each file actually hits the API.
                 let poll_secs = session_ids
                     .first()
                     .map(|sid| watch_backend.get_poll_interval(&tenant_id, sid))
                     .unwrap_or(60);
+                let tick_secs = (poll_secs / POLL_TICKS_PER_INTERVAL).max(1);
 
                 for session_id in &session_ids {
                     match watch_backend
@@ -209,23 +266,8 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
                         .await
                     {
                         Ok(Some(change)) => {
-                            let proto_event = ExternalChangeEvent {
-                                session_id: change.session_id.clone(),
-                                change_type: Self::to_proto_change_type(change.change_type),
-                                old_metadata: change
-                                    .old_metadata
-                                    .as_ref()
-                                    .map(Self::to_proto_source_metadata),
-                                new_metadata: change
-                                    .new_metadata
-                                    .as_ref()
-                                    .map(Self::to_proto_source_metadata),
-                                detected_at_unix: change.detected_at,
-                                new_uri: change.new_uri.clone().unwrap_or_default(),
-                            };
-
-                            if tx.send(Ok(proto_event)).await.is_err() {
-                                return;
+                            if let Err(e) = change_queue.enqueue(&tenant_id, change).await {
+                                warn!("Failed to queue change for session {}: {}", session_id, e);
                             }
                         }
                         Ok(None) => {}
@@ -238,13 +280,76 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
                     }
                 }
 
-                tokio::time::sleep(tokio::time::Duration::from_secs(poll_secs as u64)).await;
+                match change_queue.pending(&tenant_id, &session_ids).await {
+                    Ok(pending) => {
+                        sent_at.retain(|(session_id, sequence), _| {
+                            pending.iter().any(|p| {
+                                p.sequence == *sequence && &p.event.session_id == session_id
+                            })
+                        });
+
+                        for queued in &pending {
+                            let key = (queued.event.session_id.clone(), queued.sequence);
+                            if sent_at
+                                .get(&key)
+                                .is_some_and(|at| at.elapsed() < REDELIVERY_INTERVAL)
+                            {
+                                continue;
+                            }
+
+                            if tx.send(Ok(Self::to_proto_event(queued))).await.is_err() {
+                                return;
+                            }
+                            sent_at.insert(key, Instant::now());
+                        }
+                    }
+                    Err(e) => warn!(
+                        "Failed to read change queue for tenant {}: {}",
+                        tenant_id, e
+                    ),
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(tick_secs as u64)).await;
             }
         });
 
         Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn ack_changes(
+        &self,
+        request: Request<AckChangesRequest>,
+    ) -> Result<Response<AckChangesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let mut acknowledged = 0;
+        for ack in &req.acks {
+            match self
+                .change_queue
+                .ack(tenant_id, &ack.session_id, ack.sequence)
+                .await
+            {
+                Ok(true) => acknowledged += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    return Ok(Response::new(AckChangesResponse {
+                        success: false,
+                        acknowledged,
+                        error: e.to_string(),
+                    }))
+                }
+            }
+        }
+
+        Ok(Response::new(AckChangesResponse {
+            success: true,
+            acknowledged,
+            error: String::new(),
+        }))
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn get_source_metadata(
         &self,
@@ -7,10 +7,16 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use dashmap::DashMap;
-use docx_storage_core::{SourceDescriptor, SourceType, StorageError, SyncBackend, SyncStatus};
+use docx_storage_core::{
+    RecentFile, SourceDescriptor, SourceType, StorageError, SyncBackend, SyncStatus,
+    MAX_RECENT_FILES,
+};
 use tracing::{debug, instrument, warn};
 
-use crate::gdrive::GDriveClient;
+use crate::d1_client::{D1Client, RecentFileRow};
+use crate::gdrive::{classify_error, is_transient, GDriveClient};
+use crate::scheduler::DriveRateLimiter;
+use crate::throttle::UploadThrottle;
 use crate::token_manager::TokenManager;
 
 /// Transient sync state (in-memory only).
@@ -25,17 +31,32 @@ struct TransientSyncState {
 
 /// Google Drive sync backend (multi-tenant, token per-connection).
 pub struct GDriveSyncBackend {
+    /// D1 client for the persisted recent/favorite files
+    d1: Arc<D1Client>,
     client: Arc<GDriveClient>,
     token_manager: Arc<TokenManager>,
+    /// Per-tenant upload concurrency and bandwidth limits
+    throttle: Arc<UploadThrottle>,
+    /// Per-Drive-account upload rate, shared with every session of the account
+    limiter: Arc<DriveRateLimiter>,
     /// Transient state: (tenant_id, session_id) -> TransientSyncState
     state: DashMap<(String, String), TransientSyncState>,
 }
 
 impl GDriveSyncBackend {
-    pub fn new(client: Arc<GDriveClient>, token_manager: Arc<TokenManager>) -> Self {
+    pub fn new(
+        d1: Arc<D1Client>,
+        client: Arc<GDriveClient>,
+        token_manager: Arc<TokenManager>,
+        throttle: Arc<UploadThrottle>,
+        limiter: Arc<DriveRateLimiter>,
+    ) -> Self {
         Self {
+            d1,
             client,
             token_manager,
+            throttle,
+            limiter,
             state: DashMap::new(),
         }
     }
@@ -43,6 +64,43 @@ impl GDriveSyncBackend {
     fn key(tenant_id: &str, session_id: &str) -> (String, String) {
         (tenant_id.to_string(), session_id.to_string())
     }
+
+    /// Record a file in the tenant's recent list. Failures are logged, not
+    /// returned: a D1 hiccup must not fail an open or a save.
+    async fn record_recent_file(&self, tenant_id: &str, source: &SourceDescriptor, used_at: i64) {
+        let row = to_row(RecentFile::new(source.clone(), used_at));
+        if let Err(e) = self
+            .d1
+            .touch_recent_file(tenant_id, &row, MAX_RECENT_FILES)
+            .await
+        {
+            warn!("Failed to record recent file for tenant {}: {}", tenant_id, e);
+        }
+    }
+}
+
+/// Map a Drive or token failure to a sync error. Failures Drive explained
+/// keep their kind (throttling is queued for retry with Drive's delay);
+/// other transient ones are `Unavailable` so the sync is queued for retry.
+fn sync_error(context: &str, e: anyhow::Error) -> StorageError {
+    if let Some(err) = classify_error(context, &e) {
+        err
+    } else if is_transient(&e) {
+        StorageError::Unavailable(format!("{}: {}", context, e))
+    } else {
+        StorageError::Sync(format!("{}: {}", context, e))
+    }
+}
+
+fn to_row(file: RecentFile) -> RecentFileRow {
+    RecentFileRow {
+        connection_id: file.source.connection_id.clone().unwrap_or_default(),
+        file_id: file.source.effective_id().to_string(),
+        path: file.source.path,
+        display_name: file.display_name,
+        last_used_at: file.last_used_at,
+        favorite: file.favorite as i64,
+    }
 }
 
 #[async_trait]
@@ -77,6 +135,9 @@ impl SyncBackend for GDriveSyncBackend {
             auto_sync
         );
 
+        self.record_recent_file(tenant_id, &source, chrono::Utc::now().timestamp())
+            .await;
+
         self.state.insert(
             key,
             TransientSyncState {
@@ -183,7 +244,12 @@ impl SyncBackend for GDriveSyncBackend {
             .token_manager
             .get_valid_token(tenant_id, &connection_id)
             .await
-            .map_err(|e| StorageError::Sync(format!("Token error: {}", e)))?;
+            .map_err(|e| sync_error("Token error", e))?;
+
+        // Wait for the Drive account's turn, then for the tenant's upload
+        // slot (held until the upload completes)
+        self.limiter.acquire(&connection_id).await;
+        let _permit = self.throttle.acquire(tenant_id, data.len()).await;
 
         let effective_file_id = if has_real_file_id {
             // Existing file → update in place
@@ -194,9 +260,7 @@ impl SyncBackend for GDriveSyncBackend {
             self.client
                 .update_file(&token, &file_id_or_path, data)
                 .await
-                .map_err(|e| {
-                    StorageError::Sync(format!("Google Drive upload failed: {}", e))
-                })?;
+                .map_err(|e| sync_error("Google Drive upload failed", e))?;
             file_id_or_path
         } else {
             // New file → create on Google Drive, then remember the new file_id
@@ -210,9 +274,7 @@ impl SyncBackend for GDriveSyncBackend {
                 self.client
                     .create_file(&token, &name, None, data)
                     .await
-                    .map_err(|e| {
-                        StorageError::Sync(format!("Google Drive create failed: {}", e))
-                    })?;
+                    .map_err(|e| sync_error("Google Drive create failed", e))?;
 
             // Update transient state with the newly assigned file_id
             if let Some(mut entry) = self.state.get_mut(&key) {
@@ -227,10 +289,15 @@ impl SyncBackend for GDriveSyncBackend {
         let synced_at = chrono::Utc::now().timestamp();
 
         // Update transient state
-        if let Some(mut entry) = self.state.get_mut(&key) {
+        let source = self.state.get_mut(&key).and_then(|mut entry| {
             entry.last_synced_at = Some(synced_at);
             entry.has_pending_changes = false;
             entry.last_error = None;
+            entry.source.clone()
+        });
+
+        if let Some(source) = source {
+            self.record_recent_file(tenant_id, &source, synced_at).await;
         }
 
         debug!(
@@ -317,6 +384,50 @@ impl SyncBackend for GDriveSyncBackend {
             .map(|e| e.auto_sync && e.source.is_some())
             .unwrap_or(false))
     }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_recent_files(&self, tenant_id: &str) -> Result<Vec<RecentFile>, StorageError> {
+        let rows = self
+            .d1
+            .list_recent_files(tenant_id)
+            .await
+            .map_err(|e| StorageError::Sync(format!("D1 error listing recent files: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| RecentFile {
+                source: SourceDescriptor {
+                    source_type: SourceType::GoogleDrive,
+                    connection_id: Some(row.connection_id),
+                    path: row.path,
+                    file_id: Some(row.file_id),
+                },
+                display_name: row.display_name,
+                last_used_at: row.last_used_at,
+                favorite: row.favorite != 0,
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn pin_favorite(
+        &self,
+        tenant_id: &str,
+        source: SourceDescriptor,
+        pinned: bool,
+    ) -> Result<(), StorageError> {
+        if source.connection_id.is_none() {
+            return Err(StorageError::Sync(
+                "Google Drive source requires a connection_id".to_string(),
+            ));
+        }
+
+        let row = to_row(RecentFile::new(source, chrono::Utc::now().timestamp()));
+        self.d1
+            .set_favorite(tenant_id, &row, pinned)
+            .await
+            .map_err(|e| StorageError::Sync(format!("D1 error updating favorite: {}", e)))
+    }
 }
 
 impl GDriveSyncBackend {
@@ -0,0 +1,161 @@
+//! Per-tenant upload throttling.
+//!
+//! Bulk operations (e.g. a batch job syncing hundreds of documents) would
+//! otherwise start every upload at once, saturating the network and tripping
+//! Drive's rate limits. Each tenant gets:
+//! - a cap on concurrent uploads (a semaphore), and
+//! - an optional bandwidth cap (a token bucket refilled at the configured
+//!   rate, holding at most one second's worth of bytes).
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+/// Token bucket state for one tenant.
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes that can be sent right away; negative when uploads ran ahead
+    available: f64,
+    refilled_at: Instant,
+}
+
+/// Upload limits applied per tenant.
+pub struct UploadThrottle {
+    max_concurrent: usize,
+    /// Bytes per second per tenant (0 = unlimited)
+    bytes_per_sec: u64,
+    permits: DashMap<String, Arc<Semaphore>>,
+    buckets: DashMap<String, Bucket>,
+}
+
+impl UploadThrottle {
+    pub fn new(max_concurrent: usize, bytes_per_sec: u64) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            bytes_per_sec,
+            permits: DashMap::new(),
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// Wait until the tenant may upload `bytes`.
+    ///
+    /// Hold the returned permit for the duration of the upload. Bandwidth is
+    /// accounted up front: an upload larger than the bucket runs once the
+    /// bucket is full, and later uploads wait for the overdraft to refill.
+    pub async fn acquire(&self, tenant_id: &str, bytes: usize) -> OwnedSemaphorePermit {
+        let semaphore = self
+            .permits
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_concurrent)))
+            .clone();
+        let permit = semaphore
+            .acquire_owned()
+            .await
+            .expect("upload semaphore is never closed");
+
+        let wait = self.reserve(tenant_id, bytes as f64);
+        if !wait.is_zero() {
+            debug!(
+                "Throttling {} byte upload for tenant {} by {:?}",
+                bytes, tenant_id, wait
+            );
+            tokio::time::sleep(wait).await;
+        }
+
+        permit
+    }
+
+    /// Take `bytes` from the tenant's bucket; returns how long to wait before sending.
+    fn reserve(&self, tenant_id: &str, bytes: f64) -> Duration {
+        if self.bytes_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        let rate = self.bytes_per_sec as f64;
+        let now = Instant::now();
+
+        let mut bucket = self
+            .buckets
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| Bucket {
+                available: rate,
+                refilled_at: now,
+            });
+
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.available = (bucket.available + elapsed * rate).min(rate);
+        bucket.refilled_at = now;
+
+        // A full bucket lets any single upload through; otherwise wait for
+        // the bytes (or the overdraft) to refill
+        let wait = if bucket.available >= bytes.min(rate) {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((bytes.min(rate) - bucket.available) / rate)
+        };
+        bucket.available -= bytes;
+        wait
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_bandwidth_never_waits() {
+        let throttle = UploadThrottle::new(1, 0);
+
+        assert!(throttle.reserve("t", 1e12).is_zero());
+        assert!(throttle.reserve("t", 1e12).is_zero());
+    }
+
+    #[test]
+    fn test_an_empty_bucket_waits_for_the_bytes_to_refill() {
+        let throttle = UploadThrottle::new(1, 1000);
+        assert!(throttle.reserve("t", 1000.0).is_zero());
+
+        let wait = throttle.reserve("t", 500.0);
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+        // Other tenants have their own bucket
+        assert!(throttle.reserve("other", 1000.0).is_zero());
+    }
+
+    #[test]
+    fn test_the_bucket_refills_at_the_rate() {
+        let throttle = UploadThrottle::new(1, 1000);
+        assert!(throttle.reserve("t", 1000.0).is_zero());
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(throttle.reserve("t", 90.0).is_zero());
+    }
+
+    #[test]
+    fn test_refill_stops_at_one_second_of_bytes() {
+        let throttle = UploadThrottle::new(1, 1000);
+        assert!(throttle.reserve("t", 1.0).is_zero());
+        std::thread::sleep(Duration::from_millis(50));
+
+        // A full bucket lets an upload larger than itself through, then the
+        // overdraft has to refill too
+        assert!(throttle.reserve("t", 2000.0).is_zero());
+        assert!(throttle.reserve("t", 1.0) > Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_uploads_are_capped_per_tenant() {
+        let throttle = UploadThrottle::new(1, 0);
+        let permit = throttle.acquire("t", 10).await;
+
+        let blocked = tokio::time::timeout(Duration::from_millis(50), throttle.acquire("t", 10));
+        assert!(blocked.await.is_err());
+        let _other = throttle.acquire("other", 10).await;
+
+        drop(permit);
+        let _next = throttle.acquire("t", 10).await;
+    }
+}
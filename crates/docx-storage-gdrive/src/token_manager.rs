@@ -6,8 +6,10 @@
 use std::sync::Arc;
 
 use dashmap::DashMap;
+use docx_storage_core::CircuitBreaker;
 use tracing::{debug, info, warn};
 
+use crate::breaker::send_guarded;
 use crate::d1_client::D1Client;
 
 /// Cached token with expiration.
@@ -33,6 +35,7 @@ pub struct TokenManager {
     google_client_id: String,
     google_client_secret: String,
     cache: DashMap<String, CachedToken>,
+    breaker: CircuitBreaker,
 }
 
 impl TokenManager {
@@ -47,6 +50,7 @@ impl TokenManager {
             google_client_id,
             google_client_secret,
             cache: DashMap::new(),
+            breaker: CircuitBreaker::new("Google OAuth"),
         }
     }
 
@@ -107,7 +111,7 @@ impl TokenManager {
         refresh_token: &str,
         connection_id: &str,
     ) -> anyhow::Result<String> {
-        let resp = self
+        let request = self
             .http
             .post("https://oauth2.googleapis.com/token")
             .form(&[
@@ -115,9 +119,8 @@ impl TokenManager {
                 ("client_secret", self.google_client_secret.as_str()),
                 ("refresh_token", refresh_token),
                 ("grant_type", "refresh_token"),
-            ])
-            .send()
-            .await?;
+            ]);
+        let resp = send_guarded(&self.breaker, request).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -2,17 +2,23 @@
 //!
 //! Polling-based change detection using `headRevisionId` from Drive API.
 //! Resolves OAuth tokens per-connection via TokenManager.
+//!
+//! Metadata goes through a shared `SourceMetadataCache`: sessions watching the
+//! same file share one fetch, refreshes are jittered around the poll interval,
+//! and refreshes send `If-None-Match` so unchanged files cost a 304.
 
 use async_trait::async_trait;
 use dashmap::DashMap;
 use docx_storage_core::{
-    ExternalChangeEvent, ExternalChangeType, SourceDescriptor, SourceMetadata, SourceType,
-    StorageError, WatchBackend,
+    ExternalChangeEvent, ExternalChangeType, SourceDescriptor, SourceMetadata,
+    SourceMetadataCache, SourceType, StorageError, WatchBackend,
 };
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, instrument};
 
-use crate::gdrive::GDriveClient;
+use crate::gdrive::{FileMetadata, GDriveClient, MetadataFetch};
 use crate::token_manager::TokenManager;
 
 /// State for a watched Google Drive file.
@@ -33,8 +39,11 @@ pub struct GDriveWatchBackend {
     sources: DashMap<(String, String), WatchedSource>,
     /// Pending change events
     pending_changes: DashMap<(String, String), ExternalChangeEvent>,
-    /// Default poll interval (seconds)
-    default_poll_interval: u32,
+    /// Default poll interval (seconds), for watches started without one;
+    /// reloaded with the configuration file
+    default_poll_interval: AtomicU32,
+    /// Metadata shared by all sessions, keyed by file
+    cache: SourceMetadataCache,
 }
 
 impl GDriveWatchBackend {
@@ -42,13 +51,15 @@ impl GDriveWatchBackend {
         client: Arc<GDriveClient>,
         token_manager: Arc<TokenManager>,
         default_poll_interval: u32,
+        cache_jitter: f64,
     ) -> Self {
         Self {
             client,
             token_manager,
             sources: DashMap::new(),
             pending_changes: DashMap::new(),
-            default_poll_interval,
+            default_poll_interval: AtomicU32::new(default_poll_interval),
+            cache: SourceMetadataCache::new(cache_jitter),
         }
     }
 
@@ -56,45 +67,89 @@ impl GDriveWatchBackend {
         (tenant_id.to_string(), session_id.to_string())
     }
 
-    /// Fetch metadata from Google Drive and convert to SourceMetadata.
+    /// Cache key for a source: the same file watched by several sessions of a
+    /// tenant (through the same connection) shares one entry.
+    fn cache_key(tenant_id: &str, source: &SourceDescriptor) -> String {
+        format!(
+            "{}/{}/{}",
+            tenant_id,
+            source.connection_id.as_deref().unwrap_or_default(),
+            source.effective_id()
+        )
+    }
+
+    /// Get a source's metadata, from the shared cache when it isn't due for a
+    /// refresh (unless `refresh` is set), otherwise with a conditional request.
     async fn fetch_metadata(
         &self,
-        token: &str,
-        file_id: &str,
+        tenant_id: &str,
+        source: &SourceDescriptor,
+        poll_interval_secs: u32,
+        refresh: bool,
     ) -> Result<Option<SourceMetadata>, StorageError> {
-        let metadata = self
-            .client
-            .get_metadata(token, file_id)
-            .await
-            .map_err(|e| StorageError::Watch(format!("Google Drive API error: {}", e)))?;
-
-        Ok(metadata.map(|m| {
-            let size_bytes = m
-                .size
-                .as_ref()
-                .and_then(|s| s.parse::<u64>().ok())
-                .unwrap_or(0);
-
-            let modified_at = m
-                .modified_time
-                .as_ref()
-                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
-                .map(|dt| dt.timestamp())
-                .unwrap_or(0);
-
-            let content_hash = m
-                .md5_checksum
-                .as_ref()
-                .and_then(|h| hex::decode(h).ok());
-
-            SourceMetadata {
-                size_bytes,
-                modified_at,
-                etag: None,
-                version_id: m.head_revision_id.clone(),
-                content_hash,
+        let key = Self::cache_key(tenant_id, source);
+        if !refresh {
+            if let Some(cached) = self.cache.fresh(&key) {
+                return Ok(cached);
             }
-        }))
+        }
+
+        let (token, file_id) = self.get_token_for_source(tenant_id, source).await?;
+        let ttl = Duration::from_secs(poll_interval_secs.max(1) as u64);
+        let mut validator = self.cache.validator(&key);
+
+        loop {
+            let fetch = self
+                .client
+                .get_metadata(&token, &file_id, validator.as_deref())
+                .await
+                .map_err(|e| StorageError::Watch(format!("Google Drive API error: {}", e)))?;
+
+            let (metadata, etag) = match fetch {
+                MetadataFetch::NotModified => match self.cache.revalidate(&key, ttl) {
+                    Some(cached) => return Ok(cached),
+                    None => {
+                        // Entry evicted meanwhile: fetch the full metadata
+                        validator = None;
+                        continue;
+                    }
+                },
+                MetadataFetch::NotFound => (None, None),
+                MetadataFetch::Modified(m, etag) => (Some(Self::to_source_metadata(m, etag.clone())), etag),
+            };
+
+            self.cache.store(&key, metadata.clone(), etag, ttl);
+            return Ok(metadata);
+        }
+    }
+
+    /// Convert Drive file metadata to SourceMetadata.
+    fn to_source_metadata(m: FileMetadata, etag: Option<String>) -> SourceMetadata {
+        let size_bytes = m
+            .size
+            .as_ref()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let modified_at = m
+            .modified_time
+            .as_ref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0);
+
+        let content_hash = m
+            .md5_checksum
+            .as_ref()
+            .and_then(|h| hex::decode(h).ok());
+
+        SourceMetadata {
+            size_bytes,
+            modified_at,
+            etag,
+            version_id: m.head_revision_id,
+            content_hash,
+        }
     }
 
     /// Get a valid token for a source, using its connection_id (tenant-scoped).
@@ -133,13 +188,23 @@ impl GDriveWatchBackend {
         old.size_bytes != new.size_bytes || old.modified_at != new.modified_at
     }
 
+    /// Poll interval of watches started without one.
+    pub fn default_poll_interval(&self) -> u32 {
+        self.default_poll_interval.load(Ordering::Relaxed)
+    }
+
+    /// Change the poll interval of watches started from now on without one.
+    pub fn set_default_poll_interval(&self, secs: u32) {
+        self.default_poll_interval.store(secs, Ordering::Relaxed);
+    }
+
     /// Get the configured poll interval for a watched source.
     pub fn get_poll_interval(&self, tenant_id: &str, session_id: &str) -> u32 {
         let key = Self::key(tenant_id, session_id);
         self.sources
             .get(&key)
             .map(|w| w.poll_interval_secs)
-            .unwrap_or(self.default_poll_interval)
+            .unwrap_or_else(|| self.default_poll_interval())
     }
 }
 
@@ -160,20 +225,20 @@ impl WatchBackend for GDriveWatchBackend {
             )));
         }
 
-        let (token, file_id) = self.get_token_for_source(tenant_id, source).await?;
-
         let watch_id = uuid::Uuid::new_v4().to_string();
         let map_key = Self::key(tenant_id, session_id);
 
-        // Get initial metadata
-        let known_metadata = self.fetch_metadata(&token, &file_id).await?;
-
         let poll_interval = if poll_interval_secs > 0 {
             poll_interval_secs
         } else {
-            self.default_poll_interval
+            self.default_poll_interval()
         };
 
+        // Get initial metadata (bypass the cache: this is the baseline)
+        let known_metadata = self
+            .fetch_metadata(tenant_id, source, poll_interval, true)
+            .await?;
+
         self.sources.insert(
             map_key,
             WatchedSource {
@@ -186,7 +251,10 @@ impl WatchBackend for GDriveWatchBackend {
 
         debug!(
             "Started watching Google Drive file {} (tenant {} session {}, interval {} secs)",
-            file_id, tenant_id, session_id, poll_interval
+            source.effective_id(),
+            tenant_id,
+            session_id,
+            poll_interval
         );
 
         Ok(watch_id)
@@ -197,6 +265,16 @@ impl WatchBackend for GDriveWatchBackend {
         let key = Self::key(tenant_id, session_id);
 
         if let Some((_, watched)) = self.sources.remove(&key) {
+            // Drop the cached metadata once no session watches the file
+            let cache_key = Self::cache_key(tenant_id, &watched.source);
+            let still_watched = self.sources.iter().any(|entry| {
+                entry.key().0 == tenant_id
+                    && Self::cache_key(tenant_id, &entry.value().source) == cache_key
+            });
+            if !still_watched {
+                self.cache.invalidate(&cache_key);
+            }
+
             debug!(
                 "Stopped watching {} for tenant {} session {}",
                 watched.source.effective_id(),
@@ -228,10 +306,11 @@ impl WatchBackend for GDriveWatchBackend {
             None => return Ok(None),
         };
 
-        let (token, file_id) = self.get_token_for_source(tenant_id, &watched.source).await?;
-
-        // Get current metadata
-        let current_metadata = match self.fetch_metadata(&token, &file_id).await? {
+        // Get current metadata (cached between refreshes)
+        let current_metadata = match self
+            .fetch_metadata(tenant_id, &watched.source, watched.poll_interval_secs, false)
+            .await?
+        {
             Some(m) => m,
             None => {
                 // File was deleted
@@ -289,8 +368,9 @@ impl WatchBackend for GDriveWatchBackend {
             None => return Ok(None),
         };
 
-        let (token, file_id) = self.get_token_for_source(tenant_id, &watched.source).await?;
-        self.fetch_metadata(&token, &file_id).await
+        // Always revalidate: callers use this right after their own writes
+        self.fetch_metadata(tenant_id, &watched.source, watched.poll_interval_secs, true)
+            .await
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -318,6 +398,9 @@ impl WatchBackend for GDriveWatchBackend {
 
         if let Some(mut watched) = self.sources.get_mut(&key) {
             watched.known_metadata = Some(metadata);
+            // Our own write changed the file: other sessions watching it
+            // must not be served the metadata cached before it
+            self.cache.invalidate(&Self::cache_key(tenant_id, &watched.source));
             debug!(
                 "Updated known metadata for tenant {} session {}",
                 tenant_id, session_id
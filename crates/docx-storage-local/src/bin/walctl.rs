@@ -0,0 +1,1692 @@
+//! walctl — administration CLI for docx-mcp storage servers.
+//!
+//! Talks to any StorageService implementation over gRPC (local, R2, ...), so
+//! operators don't have to hand-write grpcurl invocations.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand};
+use docx_storage_client::StorageClient;
+use docx_storage_core::SessionIndex;
+use tonic::transport::Channel;
+
+use docx_storage_client::proto::storage_service_client::StorageServiceClient;
+use docx_storage_client::proto::*;
+
+#[derive(Parser, Debug)]
+#[command(name = "walctl")]
+#[command(about = "Administration CLI for docx-mcp storage servers")]
+struct Cli {
+    /// gRPC endpoint of the storage server
+    #[arg(
+        long,
+        default_value = "http://localhost:50051",
+        env = "STORAGE_GRPC_URL"
+    )]
+    server: String,
+
+    /// Tenant to operate on (empty = local/legacy tenant)
+    #[arg(long, default_value = "", env = "TENANT_ID")]
+    tenant: String,
+
+    /// Who is running the command, recorded in the tenant's admin log
+    /// (default: walctl:$USER)
+    #[arg(long, env = "WALCTL_ACTOR")]
+    actor: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check server health and report the backend in use
+    Health,
+    /// List sessions stored for the tenant
+    Sessions {
+        /// Read the index a page of this many sessions at a time (0 = list
+        /// the stored sessions at once, with their sizes)
+        #[arg(long, default_value = "0")]
+        page_size: u32,
+        /// Only sessions whose ID starts with this (read by pages)
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Only sessions whose source path, display name or alias contains
+        /// this (read by pages)
+        #[arg(long, default_value = "")]
+        search: String,
+    },
+    /// Print the tenant's session index as JSON
+    Index,
+    /// WAL inspection and maintenance
+    Wal {
+        #[command(subcommand)]
+        command: WalCommand,
+    },
+    /// List checkpoints of a session
+    Checkpoints { session_id: String },
+    /// Export a session (document, WAL, checkpoints, index entry) to a directory
+    Export { session_id: String, dir: PathBuf },
+    /// Import a session previously written by `export`
+    Import {
+        session_id: String,
+        dir: PathBuf,
+        /// Replace the session if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Cross-check the index against stored sessions, WALs and checkpoints
+    Verify {
+        /// Only verify this session
+        session_id: Option<String>,
+    },
+    /// Copy the tenant's sessions (document, WAL, checkpoints, index entry)
+    /// to another storage server and verify the copies
+    Migrate {
+        /// gRPC endpoint of the target storage server
+        #[arg(long)]
+        to: String,
+        /// Only migrate these sessions (repeatable; default: all)
+        #[arg(long = "session")]
+        sessions: Vec<String>,
+        /// Replace sessions that already exist on the target
+        #[arg(long)]
+        force: bool,
+        /// Once every copy is verified, delete the sessions from the source
+        #[arg(long)]
+        cutover: bool,
+    },
+    /// Permanently delete all of the tenant's data. Without --token, prints
+    /// what would be deleted and a confirmation token; run again with the
+    /// token to erase and print the signed erasure report
+    Erase {
+        /// Confirmation token from a previous `erase`
+        #[arg(long)]
+        token: Option<String>,
+        /// Also write the signed report to this file
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+    /// Print a session's signed history proof: whether its hash-chained WAL
+    /// and checkpoint history matches the stored WAL, and the chain head
+    HistoryProof {
+        session_id: String,
+        /// Also write the signed proof to this file
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Place a legal hold on the tenant, or on one session, blocking deletes,
+    /// truncations and erasure until it is lifted with --release
+    Hold {
+        /// Session to hold (default: the whole tenant)
+        session_id: Option<String>,
+        /// Why the data is held (required unless --release)
+        #[arg(long, required_unless_present = "release")]
+        reason: Option<String>,
+        /// Lift the hold instead
+        #[arg(long)]
+        release: bool,
+    },
+    /// Staging copies of the tenant's sessions, under the tenant `{tenant}~sandbox`
+    Sandbox {
+        #[command(subcommand)]
+        command: SandboxCommand,
+    },
+    /// Reconcile the tenant's index with its stored sessions, adding missing
+    /// sessions and reporting entries without a stored document
+    RebuildIndex {
+        /// Only report what is out of sync
+        #[arg(long)]
+        dry_run: bool,
+        /// Also remove entries without a stored document (unless held)
+        #[arg(long)]
+        remove_orphans: bool,
+    },
+    /// Print the tenant's disk usage by area and the files nothing uses any
+    /// more: orphaned WAL and checkpoint files, and temporary files of
+    /// interrupted writes (local only)
+    Report {
+        /// Delete the orphaned and temporary files (unless the tenant is held)
+        #[arg(long)]
+        cleanup: bool,
+    },
+    /// Print a point-in-time manifest of the tenant's objects (keys, ETags,
+    /// SHA-256) as JSON; --copy also copies them under the snapshot prefix
+    /// (R2 only)
+    Snapshot {
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Print an expiring URL that downloads a session's document (or one of
+    /// its checkpoints) straight from the bucket, without credentials (R2 only)
+    Url {
+        session_id: String,
+        /// Checkpoint position instead of the session document
+        #[arg(long)]
+        checkpoint: Option<u64>,
+        /// Seconds the URL stays valid (0 = the server's maximum)
+        #[arg(long, default_value = "900")]
+        expires_in: u64,
+    },
+    /// Compare the R2 bucket's object lifecycle rules with the ones declared
+    /// in the server's lifecycle file; --apply replaces them
+    Lifecycle {
+        #[arg(long)]
+        apply: bool,
+        /// Region of the bucket (default: the server's default region)
+        #[arg(long, default_value = "")]
+        region: String,
+    },
+    /// Print the R2 requests counted for the tenant per day, with their
+    /// estimated cost (the server must run with --usage-metering)
+    Cost {
+        /// Every tenant, not just --tenant
+        #[arg(long)]
+        all: bool,
+        /// First UTC day (YYYY-MM-DD)
+        #[arg(long, default_value = "")]
+        from: String,
+        /// Last UTC day (YYYY-MM-DD)
+        #[arg(long, default_value = "")]
+        to: String,
+        /// One line per RPC
+        #[arg(long)]
+        by_rpc: bool,
+    },
+    /// Print the CAS conflicts of the tenant's index and WAL writes, recent
+    /// and since the server started (R2 only)
+    Contention {
+        /// Every tenant, busiest first, not just --tenant
+        #[arg(long)]
+        all: bool,
+    },
+    /// The tenant's append-only log of administrative actions
+    AdminLog {
+        #[command(subcommand)]
+        command: AdminLogCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AdminLogCommand {
+    /// Print the logged actions, oldest first
+    List {
+        /// First position to print (1-indexed, 0 = from the beginning)
+        #[arg(long, default_value = "0")]
+        from: u64,
+        /// Maximum number of entries (0 = all)
+        #[arg(long, default_value = "0")]
+        limit: u64,
+    },
+    /// Record an action taken outside the server: `migration` or `key_rotation`
+    Record {
+        action: String,
+        /// Session the action was about
+        #[arg(long)]
+        session: Option<String>,
+        /// What was done, e.g. which key was rotated
+        #[arg(long, default_value = "")]
+        detail: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WalCommand {
+    /// Print WAL entries as JSONL (one raw entry per line)
+    Dump {
+        session_id: String,
+        /// First position to print (1-indexed, 0 = from the beginning)
+        #[arg(long, default_value = "0")]
+        from: u64,
+        /// Maximum number of entries (0 = no limit)
+        #[arg(long, default_value = "0")]
+        limit: u64,
+    },
+    /// Print the newest WAL entries as JSONL, newest first
+    Tail {
+        session_id: String,
+        /// Number of entries
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: u64,
+        /// Only entries before this position (0 = the newest)
+        #[arg(long, default_value = "0")]
+        before: u64,
+    },
+    /// Append JSONL entries (as produced by `dump`) from a file, or `-` for stdin
+    Replay { session_id: String, file: PathBuf },
+    /// Keep only the first N entries
+    Truncate { session_id: String, keep: u64 },
+}
+
+#[derive(Subcommand, Debug)]
+enum SandboxCommand {
+    /// Copy sessions (default: all of them) into the tenant's sandbox
+    Create {
+        session_ids: Vec<String>,
+        /// Discard the existing sandbox first
+        #[arg(long)]
+        replace: bool,
+        /// Purge the copies after this many seconds (0 = keep them)
+        #[arg(long, default_value = "0")]
+        ttl: i64,
+    },
+    /// Copy sandbox sessions (default: all of them) back over the tenant's
+    Promote {
+        session_ids: Vec<String>,
+        /// Overwrite sessions that changed since they were copied
+        #[arg(long)]
+        force: bool,
+        /// Discard the sandbox once promoted
+        #[arg(long)]
+        discard: bool,
+    },
+    /// Delete the sandbox
+    Discard,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let actor = cli.actor.unwrap_or_else(|| {
+        format!("walctl:{}", std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()))
+    });
+    let mut ctl = Walctl::connect(&cli.server, cli.tenant, actor).await?;
+
+    match cli.command {
+        Command::Health => ctl.health().await,
+        Command::Sessions {
+            page_size,
+            prefix,
+            search,
+        } => ctl.sessions(page_size, prefix, search).await,
+        Command::Index => ctl.index().await,
+        Command::Wal { command } => match command {
+            WalCommand::Dump {
+                session_id,
+                from,
+                limit,
+            } => ctl.wal_dump(&session_id, from, limit).await,
+            WalCommand::Tail {
+                session_id,
+                limit,
+                before,
+            } => ctl.wal_tail(&session_id, limit, before).await,
+            WalCommand::Replay { session_id, file } => ctl.wal_replay(&session_id, &file).await,
+            WalCommand::Truncate { session_id, keep } => ctl.wal_truncate(&session_id, keep).await,
+        },
+        Command::Checkpoints { session_id } => ctl.checkpoints(&session_id).await,
+        Command::Export { session_id, dir } => ctl.export(&session_id, &dir).await,
+        Command::Import {
+            session_id,
+            dir,
+            force,
+        } => ctl.import(&session_id, &dir, force).await,
+        Command::Verify { session_id } => ctl.verify(session_id.as_deref()).await,
+        Command::Migrate {
+            to,
+            sessions,
+            force,
+            cutover,
+        } => {
+            let mut target = Walctl::connect(&to, ctl.tenant.clone(), ctl.actor.clone()).await?;
+            ctl.migrate(&mut target, &sessions, force, cutover).await
+        }
+        Command::Erase { token, report } => ctl.erase(token, report.as_deref()).await,
+        Command::HistoryProof { session_id, out } => {
+            ctl.history_proof(&session_id, out.as_deref()).await
+        }
+        Command::Hold {
+            session_id,
+            reason,
+            release,
+        } => ctl.hold(session_id, reason, !release).await,
+        Command::Sandbox { command } => match command {
+            SandboxCommand::Create {
+                session_ids,
+                replace,
+                ttl,
+            } => ctl.sandbox_create(session_ids, replace, ttl).await,
+            SandboxCommand::Promote {
+                session_ids,
+                force,
+                discard,
+            } => ctl.sandbox_promote(session_ids, force, discard).await,
+            SandboxCommand::Discard => ctl.sandbox_discard().await,
+        },
+        Command::RebuildIndex {
+            dry_run,
+            remove_orphans,
+        } => ctl.rebuild_index(dry_run, remove_orphans).await,
+        Command::Report { cleanup } => ctl.report(cleanup).await,
+        Command::Snapshot { copy } => ctl.snapshot(copy).await,
+        Command::Url {
+            session_id,
+            checkpoint,
+            expires_in,
+        } => ctl.url(&session_id, checkpoint, expires_in).await,
+        Command::Lifecycle { apply, region } => ctl.lifecycle(apply, region).await,
+        Command::Cost {
+            all,
+            from,
+            to,
+            by_rpc,
+        } => ctl.cost(all, from, to, by_rpc).await,
+        Command::Contention { all } => ctl.contention(all).await,
+        Command::AdminLog { command } => match command {
+            AdminLogCommand::List { from, limit } => ctl.admin_log(from, limit).await,
+            AdminLogCommand::Record {
+                action,
+                session,
+                detail,
+            } => {
+                let position = ctl
+                    .record_admin_action(&action, session.as_deref(), detail)
+                    .await?;
+                eprintln!("Recorded {} at position {}", action, position);
+                Ok(())
+            }
+        },
+    }
+}
+
+/// Everything copied for one session, kept to verify the target against.
+struct SessionCopy {
+    docx: Vec<u8>,
+    wal: Vec<Vec<u8>>,
+    checkpoints: Vec<(u64, Vec<u8>)>,
+    entry: Option<docx_storage_core::SessionIndexEntry>,
+}
+
+struct Walctl {
+    storage: StorageClient,
+    /// Generated client of `storage`, for RPCs it has no method for
+    client: StorageServiceClient<Channel>,
+    server: String,
+    tenant: String,
+    actor: String,
+}
+
+impl Walctl {
+    async fn connect(server: &str, tenant: String, actor: String) -> anyhow::Result<Self> {
+        let storage = StorageClient::connect(server)
+            .await
+            .with_context(|| format!("Failed to connect to {}", server))?
+            .with_tenant(tenant.clone())
+            .with_actor(actor.clone());
+        Ok(Self {
+            client: storage.raw(),
+            storage,
+            server: server.to_string(),
+            tenant,
+            actor,
+        })
+    }
+
+    fn context(&self) -> Option<TenantContext> {
+        Some(TenantContext {
+            tenant_id: self.tenant.clone(),
+            actor: self.actor.clone(),
+        })
+    }
+
+    // =========================================================================
+    // Commands
+    // =========================================================================
+
+    async fn health(&mut self) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .health_check(HealthCheckRequest {})
+            .await?
+            .into_inner();
+        println!(
+            "healthy={} backend={} version={}",
+            resp.healthy, resp.backend, resp.version
+        );
+        if !resp.healthy {
+            bail!("server reports unhealthy");
+        }
+        Ok(())
+    }
+
+    async fn sessions(
+        &mut self,
+        page_size: u32,
+        prefix: String,
+        search: String,
+    ) -> anyhow::Result<()> {
+        let mut page_token = String::new();
+        loop {
+            let resp = self
+                .client
+                .list_sessions(ListSessionsRequest {
+                    context: self.context(),
+                    stale_ok: false,
+                    page_size,
+                    page_token,
+                    session_id_prefix: prefix.clone(),
+                    search: search.clone(),
+                })
+                .await?
+                .into_inner();
+
+            for s in resp.sessions {
+                let modified = chrono::DateTime::from_timestamp(s.modified_at_unix, 0)
+                    .map(|d| d.to_rfc3339())
+                    .unwrap_or_default();
+                print!(
+                    "{}\t{}\t{}\t{}",
+                    s.session_id, s.size_bytes, modified, s.source_path
+                );
+                match chrono::DateTime::from_timestamp(s.expires_at_unix, 0) {
+                    Some(expires) if s.expires_at_unix > 0 => {
+                        println!("\tephemeral, expires {}", expires.to_rfc3339())
+                    }
+                    _ => println!(),
+                }
+            }
+            if resp.next_page_token.is_empty() {
+                return Ok(());
+            }
+            page_token = resp.next_page_token;
+        }
+    }
+
+    async fn index(&mut self) -> anyhow::Result<()> {
+        let index = self.load_index().await?;
+        println!("{}", serde_json::to_string_pretty(&index)?);
+        Ok(())
+    }
+
+    async fn wal_dump(&mut self, session_id: &str, from: u64, limit: u64) -> anyhow::Result<()> {
+        let entries = self.read_wal(session_id, from, limit).await?;
+        let mut out = std::io::stdout().lock();
+        for entry in entries {
+            out.write_all(trim_newline(&entry.patch_json))?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    async fn wal_tail(&mut self, session_id: &str, limit: u64, before: u64) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .tail_wal(TailWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                before_position: before,
+                limit,
+            })
+            .await?
+            .into_inner();
+        let mut out = std::io::stdout().lock();
+        for entry in resp.entries {
+            out.write_all(trim_newline(&entry.patch_json))?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    async fn wal_replay(&mut self, session_id: &str, file: &Path) -> anyhow::Result<()> {
+        let lines: Vec<String> = if file == Path::new("-") {
+            std::io::stdin().lock().lines().collect::<Result<_, _>>()?
+        } else {
+            std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?
+                .lines()
+                .map(str::to_string)
+                .collect()
+        };
+
+        let existing = self.read_wal(session_id, 0, 0).await?.len() as u64;
+        let appended = self.append_lines(session_id, existing, &lines).await?;
+        eprintln!(
+            "Appended {} entries to {} (positions {}..={})",
+            appended,
+            session_id,
+            existing + 1,
+            existing + appended
+        );
+        Ok(())
+    }
+
+    async fn wal_truncate(&mut self, session_id: &str, keep: u64) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .truncate_wal(TruncateWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                keep_from_position: keep,
+            })
+            .await?
+            .into_inner();
+        eprintln!(
+            "Removed {} entries from {}",
+            resp.entries_removed, session_id
+        );
+        Ok(())
+    }
+
+    async fn erase(&mut self, token: Option<String>, report: Option<&Path>) -> anyhow::Result<()> {
+        if self.tenant.is_empty() {
+            bail!("--tenant is required to erase a tenant");
+        }
+        let resp = self
+            .client
+            .erase_tenant(EraseTenantRequest {
+                context: self.context(),
+                confirmation_token: token.unwrap_or_default(),
+            })
+            .await?
+            .into_inner();
+
+        let inventory = resp.inventory.unwrap_or_default();
+        eprintln!(
+            "{} {}: {} sessions, {} WAL entries, {} checkpoints, {} templates, {} snippets, {} glossaries, {} profiles{}",
+            if resp.erased { "Erased" } else { "Tenant" },
+            self.tenant,
+            inventory.sessions,
+            inventory.wal_entries,
+            inventory.checkpoints,
+            inventory.templates,
+            inventory.snippets,
+            inventory.glossaries,
+            inventory.profiles,
+            if inventory.has_index { ", index" } else { "" }
+        );
+        if !inventory.elsewhere.is_empty() {
+            eprintln!(
+                "Held by other services, not erased here: {}",
+                inventory.elsewhere.join(", ")
+            );
+        }
+
+        if !resp.erased {
+            let expires = chrono::DateTime::from_timestamp(resp.token_expires_at_unix, 0)
+                .map(|d| d.to_rfc3339())
+                .unwrap_or_default();
+            eprintln!("Nothing deleted yet. To erase, run again before {} with:", expires);
+            println!("--token {}", resp.confirmation_token);
+            return Ok(());
+        }
+
+        let signed = serde_json::json!({
+            "report": serde_json::from_str::<serde_json::Value>(&resp.report_json)?,
+            "report_json": resp.report_json,
+            "signature": resp.signature,
+        });
+        let text = serde_json::to_string_pretty(&signed)?;
+        if let Some(path) = report {
+            std::fs::write(path, &text)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        println!("{}", text);
+        Ok(())
+    }
+
+    async fn history_proof(&mut self, session_id: &str, out: Option<&Path>) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .get_history_proof(GetHistoryProofRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            })
+            .await?
+            .into_inner();
+
+        eprintln!(
+            "Session {}: {} links, head {}, {}",
+            session_id,
+            resp.links,
+            resp.head_hash,
+            if resp.consistent { "consistent" } else { "INCONSISTENT" }
+        );
+
+        let signed = serde_json::json!({
+            "proof": serde_json::from_str::<serde_json::Value>(&resp.proof_json)?,
+            "proof_json": resp.proof_json,
+            "signature": resp.signature,
+        });
+        let text = serde_json::to_string_pretty(&signed)?;
+        if let Some(path) = out {
+            std::fs::write(path, &text)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        println!("{}", text);
+        if !resp.consistent {
+            bail!("history of session {} doesn't match its chain", session_id);
+        }
+        Ok(())
+    }
+
+    async fn hold(
+        &mut self,
+        session_id: Option<String>,
+        reason: Option<String>,
+        hold: bool,
+    ) -> anyhow::Result<()> {
+        let target = match &session_id {
+            Some(id) => format!("session {}", id),
+            None => format!("tenant '{}'", self.tenant),
+        };
+        let resp = self
+            .client
+            .set_legal_hold(SetLegalHoldRequest {
+                context: self.context(),
+                session_id: session_id.unwrap_or_default(),
+                hold,
+                reason: reason.unwrap_or_default(),
+            })
+            .await?
+            .into_inner();
+        if resp.not_found {
+            bail!("{} not found", target);
+        }
+        eprintln!(
+            "{} legal hold on {}{}",
+            if hold { "Placed" } else { "Lifted" },
+            target,
+            match (hold, resp.was_held) {
+                (true, true) => " (replacing the previous hold)",
+                (false, false) => " (it was not held)",
+                _ => "",
+            }
+        );
+        Ok(())
+    }
+
+    async fn sandbox_create(
+        &mut self,
+        session_ids: Vec<String>,
+        replace: bool,
+        ttl_seconds: i64,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .create_sandbox(CreateSandboxRequest {
+                context: self.context(),
+                session_ids,
+                replace,
+                ttl_seconds,
+            })
+            .await?
+            .into_inner();
+        eprintln!(
+            "Copied {} sessions into sandbox '{}'",
+            resp.session_ids.len(),
+            resp.sandbox_tenant_id
+        );
+        for id in resp.session_ids {
+            println!("{}", id);
+        }
+        Ok(())
+    }
+
+    async fn sandbox_promote(
+        &mut self,
+        session_ids: Vec<String>,
+        force: bool,
+        discard: bool,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .promote_sandbox(PromoteSandboxRequest {
+                context: self.context(),
+                session_ids,
+                force,
+                discard,
+            })
+            .await?
+            .into_inner();
+        eprintln!(
+            "Promoted {} sessions to tenant '{}'{}",
+            resp.session_ids.len(),
+            self.tenant,
+            if resp.discarded { " and discarded the sandbox" } else { "" }
+        );
+        for id in resp.session_ids {
+            println!("{}", id);
+        }
+        Ok(())
+    }
+
+    async fn sandbox_discard(&mut self) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .discard_sandbox(DiscardSandboxRequest {
+                context: self.context(),
+            })
+            .await?
+            .into_inner();
+        if resp.existed {
+            eprintln!("Discarded the sandbox ({} objects)", resp.objects_deleted);
+        } else {
+            eprintln!("Tenant '{}' has no sandbox", self.tenant);
+        }
+        Ok(())
+    }
+
+    async fn rebuild_index(&mut self, dry_run: bool, remove_orphans: bool) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .rebuild_index(RebuildIndexRequest {
+                context: self.context(),
+                dry_run,
+                remove_orphans,
+            })
+            .await?
+            .into_inner();
+        for (status, ids) in [
+            ("added", &resp.added),
+            ("corrected", &resp.corrected),
+            ("orphan", &resp.orphans),
+            ("removed", &resp.removed),
+        ] {
+            for id in ids {
+                println!("{}\t{}", status, id);
+            }
+        }
+        if resp.added.is_empty() && resp.corrected.is_empty() && resp.orphans.is_empty() {
+            eprintln!("Index of tenant '{}' matches its stored sessions", self.tenant);
+        } else if resp.applied {
+            eprintln!("Rebuilt the index of tenant '{}'", self.tenant);
+        } else if dry_run {
+            eprintln!("Nothing changed. Run again without --dry-run to repair the index");
+        }
+        Ok(())
+    }
+
+    async fn report(&mut self, cleanup: bool) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .get_storage_report(GetStorageReportRequest {
+                context: self.context(),
+                cleanup,
+            })
+            .await?
+            .into_inner();
+        println!("area\tfiles\tbytes");
+        for u in &resp.usage {
+            println!("{}\t{}\t{}", u.area, u.files, u.bytes);
+        }
+        for id in &resp.unindexed_sessions {
+            println!("unindexed\t{}", id);
+        }
+        for (status, files) in [("orphan", &resp.orphaned_files), ("temp", &resp.temp_files)] {
+            for file in files {
+                println!("{}\t{}\t{}", status, file.path, file.bytes);
+            }
+        }
+        let leftovers = resp.orphaned_files.len() + resp.temp_files.len();
+        eprintln!(
+            "Tenant '{}' uses {} bytes; {} leftover files, {} bytes{}",
+            self.tenant,
+            resp.total_bytes,
+            leftovers,
+            resp.leftover_bytes,
+            if resp.cleaned {
+                " (deleted)"
+            } else if leftovers > 0 {
+                ". Run again with --cleanup to delete them"
+            } else {
+                ""
+            }
+        );
+        if !resp.unindexed_sessions.is_empty() {
+            eprintln!("Run rebuild-index to index the unindexed sessions");
+        }
+        Ok(())
+    }
+
+    async fn snapshot(&mut self, copy: bool) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .snapshot_tenant(SnapshotTenantRequest {
+                context: self.context(),
+                copy,
+            })
+            .await?
+            .into_inner();
+        println!("{}", resp.manifest_json);
+        eprintln!(
+            "Snapshot {} of tenant '{}': {} objects, {} bytes{}",
+            resp.snapshot_id,
+            self.tenant,
+            resp.object_count,
+            resp.total_bytes,
+            if resp.copy_prefix.is_empty() {
+                String::new()
+            } else {
+                format!(", copied under {}", resp.copy_prefix)
+            }
+        );
+        Ok(())
+    }
+
+    async fn url(
+        &mut self,
+        session_id: &str,
+        checkpoint: Option<u64>,
+        expires_in: u64,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .create_download_url(CreateDownloadUrlRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                checkpoint_position: checkpoint,
+                expires_in_secs: expires_in,
+            })
+            .await?
+            .into_inner();
+        if !resp.found {
+            match checkpoint {
+                Some(position) => bail!("no checkpoint {} of session {}", position, session_id),
+                None => bail!("session {} not found", session_id),
+            }
+        }
+        println!("{}", resp.url);
+        let expires_at = chrono::DateTime::from_timestamp(resp.expires_at_unix, 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+        eprintln!("Valid until {}", expires_at);
+        Ok(())
+    }
+
+    async fn lifecycle(&mut self, apply: bool, region: String) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .apply_lifecycle_rules(ApplyLifecycleRulesRequest {
+                dry_run: !apply,
+                region,
+            })
+            .await?
+            .into_inner();
+        if !resp.changed {
+            eprintln!("Bucket lifecycle rules match the declared rules");
+            println!("{}", resp.current_json);
+            return Ok(());
+        }
+
+        eprintln!("Current rules:");
+        println!("{}", resp.current_json);
+        eprintln!("Declared rules:");
+        println!("{}", resp.declared_json);
+        if resp.applied {
+            eprintln!("Replaced the bucket's lifecycle rules with the declared rules");
+        } else {
+            eprintln!("Nothing changed. Run again with --apply to replace them");
+        }
+        Ok(())
+    }
+
+    async fn cost(
+        &mut self,
+        all: bool,
+        from_day: String,
+        to_day: String,
+        by_rpc: bool,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .get_cost_estimate(GetCostEstimateRequest {
+                tenant_id: if all { String::new() } else { self.tenant.clone() },
+                from_day,
+                to_day,
+                by_rpc,
+            })
+            .await?
+            .into_inner();
+        println!("day\ttenant\trpc\tclass_a\tclass_b\tfree\tbytes_read\tbytes_written\tusd");
+        for u in &resp.usage {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.6}",
+                u.day,
+                u.tenant_id,
+                u.rpc,
+                u.class_a_ops,
+                u.class_b_ops,
+                u.free_ops,
+                u.bytes_read,
+                u.bytes_written,
+                u.estimated_cost_usd
+            );
+        }
+        let since = chrono::DateTime::from_timestamp(resp.metering_since_unix, 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+        eprintln!(
+            "Total ${:.4} for requests since {} (Class A ${}/M, Class B ${}/M)",
+            resp.total_cost_usd,
+            since,
+            resp.class_a_usd_per_million,
+            resp.class_b_usd_per_million
+        );
+        eprintln!("Storage and the monthly free tier are not included");
+        Ok(())
+    }
+
+    async fn contention(&mut self, all: bool) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .get_contention_report(GetContentionReportRequest {
+                tenant_id: if all { String::new() } else { self.tenant.clone() },
+            })
+            .await?
+            .into_inner();
+        println!("tenant\tobject\tops\tconflicts\texhausted\trate\ttotal_ops\ttotal_conflicts\ttotal_exhausted\tsharded");
+        for c in &resp.objects {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{:.3}\t{}\t{}\t{}\t{}",
+                c.tenant_id,
+                c.object,
+                c.recent_operations,
+                c.recent_conflicts,
+                c.recent_exhausted,
+                c.recent_conflict_rate,
+                c.total_operations,
+                c.total_conflicts,
+                c.total_exhausted,
+                if c.object == "index" { c.index_sharded.to_string() } else { String::new() }
+            );
+        }
+        let since = chrono::DateTime::from_timestamp(resp.tracking_since_unix, 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+        eprintln!(
+            "Recent counts cover the last {}s; totals since {}",
+            resp.window_secs, since
+        );
+        if resp.index_shard_conflict_rate > 0.0 {
+            eprintln!(
+                "Whole indexes are sharded above a {:.0}% recent conflict rate",
+                resp.index_shard_conflict_rate * 100.0
+            );
+        }
+        Ok(())
+    }
+
+    async fn admin_log(&mut self, from: u64, limit: u64) -> anyhow::Result<()> {
+        let mut from_position = from;
+        let mut printed = 0;
+        loop {
+            let resp = self
+                .client
+                .read_admin_log(ReadAdminLogRequest {
+                    context: self.context(),
+                    from_position,
+                    limit: if limit == 0 { 0 } else { limit - printed },
+                })
+                .await?
+                .into_inner();
+            for e in &resp.entries {
+                let at = chrono::DateTime::from_timestamp(e.timestamp_unix, 0)
+                    .map(|d| d.to_rfc3339())
+                    .unwrap_or_default();
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    e.position, at, e.actor, e.action, e.session_id, e.detail
+                );
+                from_position = e.position + 1;
+            }
+            printed += resp.entries.len() as u64;
+            if !resp.has_more || (limit > 0 && printed >= limit) {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn record_admin_action(
+        &mut self,
+        action: &str,
+        session_id: Option<&str>,
+        detail: String,
+    ) -> anyhow::Result<u64> {
+        let resp = self
+            .client
+            .record_admin_action(RecordAdminActionRequest {
+                context: self.context(),
+                action: action.to_string(),
+                session_id: session_id.unwrap_or_default().to_string(),
+                detail,
+            })
+            .await?
+            .into_inner();
+        Ok(resp.position)
+    }
+
+    async fn checkpoints(&mut self, session_id: &str) -> anyhow::Result<()> {
+        for c in self.list_checkpoints(session_id).await? {
+            let created = chrono::DateTime::from_timestamp(c.created_at_unix, 0)
+                .map(|d| d.to_rfc3339())
+                .unwrap_or_default();
+            println!("{}\t{}\t{}", c.position, c.size_bytes, created);
+        }
+        Ok(())
+    }
+
+    /// Layout written by `export`:
+    /// ```text
+    /// {dir}/
+    ///   session.docx            # baseline document
+    ///   wal.jsonl               # raw WAL entries, one per line
+    ///   index-entry.json        # the session's index entry (if indexed)
+    ///   checkpoints/{pos}.docx  # checkpoint documents
+    /// ```
+    async fn export(&mut self, session_id: &str, dir: &Path) -> anyhow::Result<()> {
+        let Some(docx) = self.storage.load_session(session_id).await? else {
+            bail!("session {} not found", session_id);
+        };
+
+        std::fs::create_dir_all(dir.join("checkpoints"))
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::write(dir.join("session.docx"), &docx)?;
+
+        let entries = self.read_wal(session_id, 0, 0).await?;
+        let mut wal = Vec::new();
+        for entry in &entries {
+            wal.extend_from_slice(trim_newline(&entry.patch_json));
+            wal.push(b'\n');
+        }
+        std::fs::write(dir.join("wal.jsonl"), wal)?;
+
+        let index = self.load_index().await?;
+        if let Some(entry) = index.get(session_id) {
+            std::fs::write(
+                dir.join("index-entry.json"),
+                serde_json::to_string_pretty(entry)?,
+            )?;
+        }
+
+        let checkpoints = self.list_checkpoints(session_id).await?;
+        for c in &checkpoints {
+            if let Some((data, position)) =
+                self.storage.load_checkpoint(session_id, c.position).await?
+            {
+                std::fs::write(
+                    dir.join("checkpoints").join(format!("{}.docx", position)),
+                    data,
+                )?;
+            }
+        }
+
+        eprintln!(
+            "Exported {} ({} bytes, {} WAL entries, {} checkpoints) to {}",
+            session_id,
+            docx.len(),
+            entries.len(),
+            checkpoints.len(),
+            dir.display()
+        );
+        Ok(())
+    }
+
+    async fn import(&mut self, session_id: &str, dir: &Path, force: bool) -> anyhow::Result<()> {
+        let exists = self
+            .client
+            .session_exists(SessionExistsRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            })
+            .await?
+            .into_inner()
+            .exists;
+        if exists {
+            if !force {
+                bail!(
+                    "session {} already exists (use --force to replace it)",
+                    session_id
+                );
+            }
+            self.remove_session(session_id).await?;
+        }
+
+        let docx = std::fs::read(dir.join("session.docx"))
+            .with_context(|| format!("Failed to read session.docx in {}", dir.display()))?;
+        self.storage.save_session(session_id, &docx).await?;
+
+        let wal_path = dir.join("wal.jsonl");
+        let lines: Vec<String> = if wal_path.exists() {
+            std::fs::read_to_string(&wal_path)?
+                .lines()
+                .map(str::to_string)
+                .collect()
+        } else {
+            vec![]
+        };
+        let wal_count = self.append_lines(session_id, 0, &lines).await?;
+
+        let mut checkpoint_positions = Vec::new();
+        let ckpt_dir = dir.join("checkpoints");
+        if ckpt_dir.exists() {
+            for entry in std::fs::read_dir(&ckpt_dir)? {
+                let path = entry?.path();
+                let Some(position) = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| s.parse::<u64>().ok())
+                else {
+                    continue;
+                };
+                self.storage
+                    .save_checkpoint(session_id, position, &std::fs::read(&path)?)
+                    .await?;
+                checkpoint_positions.push(position);
+            }
+        }
+        checkpoint_positions.sort();
+
+        let now = chrono::Utc::now().timestamp();
+        let mut entry = SessionIndexEntry {
+            source_path: String::new(),
+            created_at_unix: now,
+            modified_at_unix: now,
+            wal_position: wal_count,
+            checkpoint_positions: checkpoint_positions.clone(),
+            pending_external_change: false,
+            expires_at_unix: 0,
+        };
+        let mut cursor_position = wal_count;
+        let mut exported_entry = None;
+        let entry_path = dir.join("index-entry.json");
+        if entry_path.exists() {
+            let exported: docx_storage_core::SessionIndexEntry =
+                serde_json::from_str(&std::fs::read_to_string(&entry_path)?)
+                    .context("Failed to parse index-entry.json")?;
+            entry.source_path = exported.source_path.clone().unwrap_or_default();
+            entry.created_at_unix = exported.created_at.timestamp();
+            entry.modified_at_unix = exported.last_modified_at.timestamp();
+            entry.expires_at_unix = exported.expires_at.map_or(0, |at| at.timestamp());
+            cursor_position = exported.cursor_position.min(wal_count);
+            exported_entry = Some(exported);
+        }
+
+        self.client
+            .add_session_to_index(AddSessionToIndexRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                entry: Some(entry),
+            })
+            .await?;
+        self.client
+            .update_session_in_index(UpdateSessionInIndexRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                cursor_position: Some(cursor_position),
+                ..Default::default()
+            })
+            .await?;
+        if let Some(exported) = &exported_entry {
+            self.restore_names(session_id, exported).await?;
+        }
+
+        eprintln!(
+            "Imported {} ({} bytes, {} WAL entries, {} checkpoints)",
+            session_id,
+            docx.len(),
+            wal_count,
+            checkpoint_positions.len()
+        );
+        Ok(())
+    }
+
+    async fn verify(&mut self, only: Option<&str>) -> anyhow::Result<()> {
+        let index = self.load_index().await?;
+        let stored: Vec<String> = self
+            .client
+            .list_sessions(ListSessionsRequest {
+                context: self.context(),
+                stale_ok: false,
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .sessions
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+
+        let mut problems = Vec::new();
+
+        for entry in &index.sessions {
+            if only.is_some_and(|id| id != entry.id) {
+                continue;
+            }
+            if !stored.contains(&entry.id) {
+                problems.push(format!("{}: in index but no stored document", entry.id));
+                continue;
+            }
+
+            let wal_len = self.read_wal(&entry.id, 0, 0).await?.len() as u64;
+            if wal_len != entry.wal_count {
+                problems.push(format!(
+                    "{}: index wal_count={} but WAL has {} entries",
+                    entry.id, entry.wal_count, wal_len
+                ));
+            }
+            if entry.cursor_position > wal_len {
+                problems.push(format!(
+                    "{}: cursor_position={} is past the end of the WAL ({})",
+                    entry.id, entry.cursor_position, wal_len
+                ));
+            }
+
+            let stored_ckpts: Vec<u64> = self
+                .list_checkpoints(&entry.id)
+                .await?
+                .into_iter()
+                .map(|c| c.position)
+                .collect();
+            for pos in &entry.checkpoint_positions {
+                if !stored_ckpts.contains(pos) {
+                    problems.push(format!(
+                        "{}: checkpoint {} indexed but missing",
+                        entry.id, pos
+                    ));
+                }
+            }
+            for pos in &stored_ckpts {
+                if !entry.checkpoint_positions.contains(pos) {
+                    problems.push(format!(
+                        "{}: checkpoint {} stored but not indexed",
+                        entry.id, pos
+                    ));
+                }
+            }
+        }
+
+        for id in &stored {
+            if only.is_some_and(|only| only != id.as_str()) {
+                continue;
+            }
+            if !index.contains(id) {
+                problems.push(format!("{}: stored document missing from index", id));
+            }
+        }
+
+        if problems.is_empty() {
+            eprintln!("OK: index and storage are consistent");
+            return Ok(());
+        }
+        for p in &problems {
+            println!("{}", p);
+        }
+        bail!("{} problem(s) found", problems.len());
+    }
+
+    /// Copy sessions to `target`, verify every copy, and with `cutover`
+    /// delete the originals. Nothing is deleted unless all copies verify,
+    /// so a failed run can be retried with `--force`.
+    async fn migrate(
+        &mut self,
+        target: &mut Walctl,
+        only: &[String],
+        force: bool,
+        cutover: bool,
+    ) -> anyhow::Result<()> {
+        let index = self.load_index().await?;
+        let stored: Vec<String> = self
+            .client
+            .list_sessions(ListSessionsRequest {
+                context: self.context(),
+                stale_ok: false,
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .sessions
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        if let Some(missing) = only.iter().find(|id| !stored.contains(id)) {
+            bail!("session {} not found", missing);
+        }
+        let sessions: Vec<&String> = stored
+            .iter()
+            .filter(|id| only.is_empty() || only.contains(id))
+            .collect();
+
+        let mut problems = Vec::new();
+        for session_id in &sessions {
+            let copy = self
+                .copy_session(target, session_id, index.get(session_id).cloned(), force)
+                .await?;
+            problems.extend(target.verify_copy(session_id, &copy).await?);
+            eprintln!(
+                "Copied {} ({} bytes, {} WAL entries, {} checkpoints)",
+                session_id,
+                copy.docx.len(),
+                copy.wal.len(),
+                copy.checkpoints.len()
+            );
+        }
+
+        if !problems.is_empty() {
+            for p in &problems {
+                println!("{}", p);
+            }
+            bail!(
+                "{} problem(s) found; the source was left untouched",
+                problems.len()
+            );
+        }
+        eprintln!("Verified {} session(s)", sessions.len());
+
+        let migrated = sessions.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ");
+        target
+            .record_admin_action(
+                "migration",
+                None,
+                format!("copied from {}: {}", self.server, migrated),
+            )
+            .await?;
+
+        if cutover {
+            for session_id in &sessions {
+                self.remove_session(session_id).await?;
+            }
+            eprintln!(
+                "Cut over: removed {} session(s) from the source",
+                sessions.len()
+            );
+        }
+        self.record_admin_action(
+            "migration",
+            None,
+            format!(
+                "copied to {}{}: {}",
+                target.server,
+                if cutover { " and removed" } else { "" },
+                migrated
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn copy_session(
+        &mut self,
+        target: &mut Walctl,
+        session_id: &str,
+        entry: Option<docx_storage_core::SessionIndexEntry>,
+        force: bool,
+    ) -> anyhow::Result<SessionCopy> {
+        let exists = target
+            .client
+            .session_exists(SessionExistsRequest {
+                context: target.context(),
+                session_id: session_id.to_string(),
+            })
+            .await?
+            .into_inner()
+            .exists;
+        if exists {
+            if !force {
+                bail!(
+                    "session {} already exists on the target (use --force to replace it)",
+                    session_id
+                );
+            }
+            target.remove_session(session_id).await?;
+        }
+
+        let Some(docx) = self.storage.load_session(session_id).await? else {
+            bail!("session {} not found", session_id);
+        };
+        target.storage.save_session(session_id, &docx).await?;
+
+        // Entries keep their operation, path and timestamp
+        let entries = self.read_wal(session_id, 0, 0).await?;
+        let wal: Vec<Vec<u8>> = entries.iter().map(|e| e.patch_json.clone()).collect();
+        if !entries.is_empty() {
+            target
+                .client
+                .append_wal(AppendWalRequest {
+                    context: target.context(),
+                    session_id: session_id.to_string(),
+                    entries,
+                })
+                .await?;
+        }
+
+        let mut checkpoints = Vec::new();
+        for c in self.list_checkpoints(session_id).await? {
+            if let Some((data, position)) =
+                self.storage.load_checkpoint(session_id, c.position).await?
+            {
+                target.storage.save_checkpoint(session_id, position, &data).await?;
+                checkpoints.push((position, data));
+            }
+        }
+
+        if let Some(entry) = &entry {
+            target
+                .client
+                .add_session_to_index(AddSessionToIndexRequest {
+                    context: target.context(),
+                    session_id: session_id.to_string(),
+                    entry: Some(SessionIndexEntry {
+                        source_path: entry.source_path.clone().unwrap_or_default(),
+                        created_at_unix: entry.created_at.timestamp(),
+                        modified_at_unix: entry.last_modified_at.timestamp(),
+                        wal_position: entry.wal_count,
+                        checkpoint_positions: entry.checkpoint_positions.clone(),
+                        pending_external_change: entry.pending_external_change,
+                        expires_at_unix: entry.expires_at.map_or(0, |at| at.timestamp()),
+                    }),
+                })
+                .await?;
+            target
+                .client
+                .update_session_in_index(UpdateSessionInIndexRequest {
+                    context: target.context(),
+                    session_id: session_id.to_string(),
+                    cursor_position: Some(entry.cursor_position),
+                    ..Default::default()
+                })
+                .await?;
+            target.restore_names(session_id, entry).await?;
+        }
+
+        Ok(SessionCopy {
+            docx,
+            wal,
+            checkpoints,
+            entry,
+        })
+    }
+
+    /// Carry a session's display name and alias over from an exported or
+    /// source index entry (they can't be set through AddSessionToIndex).
+    async fn restore_names(
+        &mut self,
+        session_id: &str,
+        entry: &docx_storage_core::SessionIndexEntry,
+    ) -> anyhow::Result<()> {
+        if entry.display_name.is_none() && entry.alias.is_none() {
+            return Ok(());
+        }
+        self.client
+            .rename_session(RenameSessionRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                display_name: entry.display_name.clone(),
+                alias: entry.alias.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Compare a migrated session on this server with what was copied.
+    async fn verify_copy(
+        &mut self,
+        session_id: &str,
+        copy: &SessionCopy,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut problems = Vec::new();
+
+        if self.storage.load_session(session_id).await?.as_ref() != Some(&copy.docx) {
+            problems.push(format!("{}: document differs on the target", session_id));
+        }
+
+        let wal: Vec<Vec<u8>> = self
+            .read_wal(session_id, 0, 0)
+            .await?
+            .into_iter()
+            .map(|e| e.patch_json)
+            .collect();
+        if wal != copy.wal {
+            problems.push(format!(
+                "{}: WAL differs on the target ({} entries, expected {})",
+                session_id,
+                wal.len(),
+                copy.wal.len()
+            ));
+        }
+
+        for (position, data) in &copy.checkpoints {
+            match self.storage.load_checkpoint(session_id, *position).await? {
+                Some((stored, _)) if &stored == data => {}
+                _ => problems.push(format!(
+                    "{}: checkpoint {} differs on the target",
+                    session_id, position
+                )),
+            }
+        }
+
+        if let Some(expected) = &copy.entry {
+            let index = self.load_index().await?;
+            match index.get(session_id) {
+                Some(entry)
+                    if entry.source_path == expected.source_path
+                        && entry.wal_count == expected.wal_count
+                        && entry.cursor_position == expected.cursor_position
+                        && entry.checkpoint_positions == expected.checkpoint_positions => {}
+                Some(_) => problems.push(format!(
+                    "{}: index entry differs on the target",
+                    session_id
+                )),
+                None => problems.push(format!(
+                    "{}: missing from the target index",
+                    session_id
+                )),
+            }
+        }
+
+        Ok(problems)
+    }
+
+    // =========================================================================
+    // RPC helpers
+    // =========================================================================
+
+    /// Delete a session and its index entry.
+    async fn remove_session(&mut self, session_id: &str) -> anyhow::Result<()> {
+        self.client
+            .delete_session(DeleteSessionRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            })
+            .await?;
+        self.client
+            .remove_session_from_index(RemoveSessionFromIndexRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn load_index(&mut self) -> anyhow::Result<SessionIndex> {
+        let resp = self
+            .client
+            .load_index(LoadIndexRequest {
+                context: self.context(),
+            })
+            .await?
+            .into_inner();
+        if !resp.found {
+            return Ok(SessionIndex::default());
+        }
+        SessionIndex::from_json(&resp.index_json).context("Failed to parse session index")
+    }
+
+    async fn read_wal(
+        &mut self,
+        session_id: &str,
+        from: u64,
+        limit: u64,
+    ) -> anyhow::Result<Vec<WalEntry>> {
+        let resp = self
+            .client
+            .read_wal(ReadWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                from_position: from,
+                limit,
+            })
+            .await?
+            .into_inner();
+        Ok(resp.entries)
+    }
+
+    /// Append raw JSONL lines, numbering them after `existing` entries.
+    async fn append_lines(
+        &mut self,
+        session_id: &str,
+        existing: u64,
+        lines: &[String],
+    ) -> anyhow::Result<u64> {
+        let entries: Vec<WalEntry> = lines
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .enumerate()
+            .map(|(i, line)| WalEntry {
+                position: existing + i as u64 + 1,
+                operation: String::new(),
+                path: String::new(),
+                patch_json: line.as_bytes().to_vec(),
+                timestamp_unix: chrono::Utc::now().timestamp(),
+            })
+            .collect();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            serde_json::from_slice::<serde_json::Value>(&entry.patch_json)
+                .with_context(|| format!("Line {} is not valid JSON", i + 1))?;
+        }
+
+        let count = entries.len() as u64;
+        self.client
+            .append_wal(AppendWalRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                entries,
+            })
+            .await?;
+        Ok(count)
+    }
+
+    async fn list_checkpoints(&mut self, session_id: &str) -> anyhow::Result<Vec<CheckpointInfo>> {
+        let resp = self
+            .client
+            .list_checkpoints(ListCheckpointsRequest {
+                context: self.context(),
+                session_id: session_id.to_string(),
+                stale_ok: false,
+            })
+            .await?
+            .into_inner();
+        Ok(resp.checkpoints)
+    }
+}
+
+fn trim_newline(bytes: &[u8]) -> &[u8] {
+    bytes.strip_suffix(b"\n").unwrap_or(bytes)
+}
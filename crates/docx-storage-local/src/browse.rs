@@ -1,13 +1,18 @@
+use std::path::{Path, PathBuf};
+
 use async_trait::async_trait;
 use docx_storage_core::{
-    BrowsableBackend, ConnectionInfo, FileEntry, FileListResult, SourceType, StorageError,
+    BrowsableBackend, ConnectionInfo, FileEntry, FileListResult, FileSearchQuery, SourceType,
+    StorageError, DOCX_MIME_TYPE,
 };
 use tracing::debug;
+use walkdir::WalkDir;
 
 /// Local filesystem browsable backend.
 ///
 /// Lists .docx files and folders on the local filesystem.
 /// Returns a single "Local filesystem" connection.
+#[derive(Default)]
 pub struct LocalBrowsableBackend;
 
 impl LocalBrowsableBackend {
@@ -24,6 +29,7 @@ impl BrowsableBackend for LocalBrowsableBackend {
             source_type: SourceType::LocalFile,
             display_name: "Local filesystem".to_string(),
             provider_account_id: None,
+            backend: None,
         }])
     }
 
@@ -95,7 +101,7 @@ impl BrowsableBackend for LocalBrowsableBackend {
             let mime_type = if is_folder {
                 None
             } else {
-                Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document".to_string())
+                Some(DOCX_MIME_TYPE.to_string())
             };
 
             entries.push(FileEntry {
@@ -118,35 +124,63 @@ impl BrowsableBackend for LocalBrowsableBackend {
             }
         });
 
-        // Pagination: offset-based (page_token = offset as string)
-        let offset: usize = page_token
-            .and_then(|t| t.parse().ok())
-            .unwrap_or(0);
-
-        let page_size = page_size as usize;
         let total = entries.len();
-        let end = std::cmp::min(offset + page_size, total);
-        let page = entries[offset..end].to_vec();
-
-        let next_page_token = if end < total {
-            Some(end.to_string())
-        } else {
-            None
-        };
+        let result = paginate(entries, page_token, page_size);
 
         debug!(
-            "Listed {} files in {} (total {}, offset {}, page_size {})",
-            page.len(),
+            "Listed {} files in {} (total {}, page_token {:?}, page_size {})",
+            result.files.len(),
             dir_path.display(),
             total,
-            offset,
+            page_token,
             page_size
         );
 
-        Ok(FileListResult {
-            files: page,
-            next_page_token,
+        Ok(result)
+    }
+
+    async fn search_files(
+        &self,
+        _tenant_id: &str,
+        _connection_id: &str,
+        query: &FileSearchQuery,
+    ) -> Result<FileListResult, StorageError> {
+        let root = if query.path.is_empty() {
+            dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
+        } else {
+            PathBuf::from(&query.path)
+        };
+
+        if !root.is_dir() {
+            return Err(StorageError::Sync(format!(
+                "Path is not a directory: {}",
+                root.display()
+            )));
+        }
+
+        let needle = query.query.to_lowercase();
+        let mime_type = query.effective_mime_type().to_string();
+        let max_depth = if query.recursive { usize::MAX } else { 1 };
+
+        let search_root = root.clone();
+        let matches = tokio::task::spawn_blocking(move || {
+            search_dir(&search_root, &needle, &mime_type, max_depth)
         })
+        .await
+        .map_err(|e| StorageError::Internal(format!("Search task failed: {}", e)))?;
+
+        let total = matches.len();
+        let result = paginate(matches, query.page_token.as_deref(), query.page_size);
+
+        debug!(
+            "Found {} files matching '{}' under {} (returning {})",
+            total,
+            query.query,
+            root.display(),
+            result.files.len()
+        );
+
+        Ok(result)
     }
 
     async fn download_file(
@@ -174,3 +208,239 @@ impl BrowsableBackend for LocalBrowsableBackend {
         })
     }
 }
+
+/// Return one page of `entries` (page_token = offset as string).
+fn paginate(entries: Vec<FileEntry>, page_token: Option<&str>, page_size: u32) -> FileListResult {
+    let offset: usize = page_token
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(0)
+        .min(entries.len());
+    let page_size = if page_size == 0 { 50 } else { page_size as usize };
+    let end = (offset + page_size).min(entries.len());
+
+    let next_page_token = if end < entries.len() {
+        Some(end.to_string())
+    } else {
+        None
+    };
+
+    FileListResult {
+        files: entries[offset..end].to_vec(),
+        next_page_token,
+    }
+}
+
+/// MIME type of a local file, derived from its extension.
+fn mime_type_for(path: &Path) -> Option<&'static str> {
+    match path
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase()
+        .as_str()
+    {
+        "docx" => Some(DOCX_MIME_TYPE),
+        "doc" => Some("application/msword"),
+        "pdf" => Some("application/pdf"),
+        "txt" => Some("text/plain"),
+        "md" => Some("text/markdown"),
+        _ => None,
+    }
+}
+
+/// Walk `root` (skipping hidden entries) for files whose name contains `needle`
+/// and whose MIME type is `mime_type`. Results are sorted by path.
+fn search_dir(root: &Path, needle: &str, mime_type: &str, max_depth: usize) -> Vec<FileEntry> {
+    let mut matches: Vec<FileEntry> = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(needle)
+        })
+        .filter(|e| mime_type_for(e.path()) == Some(mime_type))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified_at = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            Some(FileEntry {
+                name: e.file_name().to_string_lossy().to_string(),
+                path: e.path().to_string_lossy().to_string(),
+                file_id: None,
+                is_folder: false,
+                size_bytes: metadata.len(),
+                modified_at,
+                mime_type: Some(mime_type.to_string()),
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.path.cmp(&b.path));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use docx_storage_core::AggregateBrowsableBackend;
+
+    use super::*;
+
+    /// A cloud-like backend with one fixed connection, or one that always fails.
+    struct FakeBackend {
+        connection_id: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl BrowsableBackend for FakeBackend {
+        async fn list_connections(&self, _tenant_id: &str) -> Result<Vec<ConnectionInfo>, StorageError> {
+            if self.fail {
+                return Err(StorageError::Sync("upstream down".to_string()));
+            }
+            Ok(vec![ConnectionInfo {
+                connection_id: self.connection_id.to_string(),
+                source_type: SourceType::GoogleDrive,
+                display_name: "Drive".to_string(),
+                provider_account_id: Some("me@example.com".to_string()),
+                backend: None,
+            }])
+        }
+
+        async fn list_files(
+            &self,
+            _tenant_id: &str,
+            connection_id: &str,
+            _path: &str,
+            _page_token: Option<&str>,
+            _page_size: u32,
+        ) -> Result<FileListResult, StorageError> {
+            Ok(FileListResult {
+                files: vec![FileEntry {
+                    name: format!("{}.docx", connection_id),
+                    path: "/".to_string(),
+                    file_id: None,
+                    is_folder: false,
+                    size_bytes: 1,
+                    modified_at: 0,
+                    mime_type: None,
+                }],
+                next_page_token: None,
+            })
+        }
+
+        async fn download_file(
+            &self,
+            _tenant_id: &str,
+            _connection_id: &str,
+            _path: &str,
+            _file_id: Option<&str>,
+        ) -> Result<Vec<u8>, StorageError> {
+            Ok(b"remote".to_vec())
+        }
+
+        async fn search_files(
+            &self,
+            tenant_id: &str,
+            connection_id: &str,
+            _query: &FileSearchQuery,
+        ) -> Result<FileListResult, StorageError> {
+            self.list_files(tenant_id, connection_id, "", None, 0).await
+        }
+    }
+
+    fn aggregate(remote_fails: bool) -> AggregateBrowsableBackend {
+        AggregateBrowsableBackend::new(vec![
+            ("local".to_string(), Arc::new(LocalBrowsableBackend::new()) as Arc<dyn BrowsableBackend>),
+            (
+                "gdrive".to_string(),
+                Arc::new(FakeBackend { connection_id: "conn-1", fail: remote_fails }),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_tags_and_routes() {
+        let backend = aggregate(false);
+
+        let connections = backend.list_connections("t1").await.unwrap();
+        let tags: Vec<_> = connections.iter().map(|c| c.backend.as_deref()).collect();
+        assert_eq!(tags, vec![Some("local"), Some("gdrive")]);
+
+        // Routed without a prior list_connections for this tenant
+        let files = backend.list_files("t2", "conn-1", "", None, 10).await.unwrap();
+        assert_eq!(files.files[0].name, "conn-1.docx");
+        assert_eq!(
+            backend.download_file("t2", "conn-1", "/x", None).await.unwrap(),
+            b"remote"
+        );
+
+        assert!(matches!(
+            backend.list_files("t1", "unknown", "", None, 10).await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_skips_failing_backend() {
+        let backend = aggregate(true);
+        let connections = backend.list_connections("t1").await.unwrap();
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].backend.as_deref(), Some("local"));
+    }
+
+    #[tokio::test]
+    async fn test_search_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("reports/2024")).unwrap();
+        std::fs::create_dir_all(root.join(".hidden")).unwrap();
+        std::fs::write(root.join("Report-top.docx"), b"x").unwrap();
+        std::fs::write(root.join("report.pdf"), b"x").unwrap();
+        std::fs::write(root.join("reports/2024/annual REPORT.docx"), b"x").unwrap();
+        std::fs::write(root.join("reports/notes.docx"), b"x").unwrap();
+        std::fs::write(root.join(".hidden/report.docx"), b"x").unwrap();
+
+        let backend = LocalBrowsableBackend::new();
+        let mut query = FileSearchQuery {
+            query: "report".to_string(),
+            path: root.to_string_lossy().to_string(),
+            recursive: true,
+            ..Default::default()
+        };
+
+        let names = |r: FileListResult| r.files.into_iter().map(|f| f.name).collect::<Vec<_>>();
+
+        let found = backend.search_files("t", "", &query).await.unwrap();
+        assert_eq!(names(found), vec!["Report-top.docx", "annual REPORT.docx"]);
+
+        query.recursive = false;
+        let found = backend.search_files("t", "", &query).await.unwrap();
+        assert_eq!(names(found), vec!["Report-top.docx"]);
+
+        query.mime_filter = Some("application/pdf".to_string());
+        let found = backend.search_files("t", "", &query).await.unwrap();
+        assert_eq!(names(found), vec!["report.pdf"]);
+
+        // Pagination
+        query.recursive = true;
+        query.mime_filter = None;
+        query.page_size = 1;
+        let first = backend.search_files("t", "", &query).await.unwrap();
+        assert_eq!(first.next_page_token.as_deref(), Some("1"));
+        query.page_token = first.next_page_token;
+        let second = backend.search_files("t", "", &query).await.unwrap();
+        assert_eq!(names(second.clone()), vec!["annual REPORT.docx"]);
+        assert!(second.next_page_token.is_none());
+    }
+}
@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use docx_storage_core::{
+    BrowsableBackend, ConnectionInfo, FileEntry, FileListResult, FileSearchQuery, SourceType,
+    StorageError,
+};
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::service::proto;
+use proto::source_sync_service_client::SourceSyncServiceClient;
+
+/// Browsable backend served by another storage server's SourceSyncService
+/// (e.g. docx-storage-gdrive), so its connections can be aggregated with the
+/// local ones.
+pub struct RemoteBrowsableBackend {
+    client: SourceSyncServiceClient<Channel>,
+}
+
+impl RemoteBrowsableBackend {
+    /// Create a backend for the server at `url`. The connection is established
+    /// lazily, so an unavailable upstream doesn't prevent startup.
+    pub fn new(url: &str) -> Result<Self, StorageError> {
+        let endpoint = Endpoint::from_shared(url.to_string())
+            .map_err(|e| StorageError::InvalidArgument(format!("Invalid upstream URL {}: {}", url, e)))?;
+        Ok(Self {
+            client: SourceSyncServiceClient::new(endpoint.connect_lazy()),
+        })
+    }
+
+    fn context(tenant_id: &str) -> Option<proto::TenantContext> {
+        Some(proto::TenantContext {
+            tenant_id: tenant_id.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+fn status_to_error(status: tonic::Status) -> StorageError {
+    match status.code() {
+        tonic::Code::NotFound => StorageError::NotFound(status.message().to_string()),
+        tonic::Code::InvalidArgument => StorageError::InvalidArgument(status.message().to_string()),
+        _ => StorageError::Sync(format!("Upstream error: {}", status.message())),
+    }
+}
+
+fn from_proto_source_type(value: i32) -> SourceType {
+    match value {
+        2 => SourceType::SharePoint,
+        3 => SourceType::OneDrive,
+        6 => SourceType::GoogleDrive,
+        _ => SourceType::LocalFile,
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn from_proto_files(response: proto::ListConnectionFilesResponse) -> FileListResult {
+    FileListResult {
+        files: response
+            .files
+            .into_iter()
+            .map(|f| FileEntry {
+                name: f.name,
+                path: f.path,
+                file_id: non_empty(f.file_id),
+                is_folder: f.is_folder,
+                size_bytes: f.size_bytes.max(0) as u64,
+                modified_at: f.modified_at_unix,
+                mime_type: non_empty(f.mime_type),
+            })
+            .collect(),
+        next_page_token: non_empty(response.next_page_token),
+    }
+}
+
+#[async_trait]
+impl BrowsableBackend for RemoteBrowsableBackend {
+    async fn list_connections(&self, tenant_id: &str) -> Result<Vec<ConnectionInfo>, StorageError> {
+        let response = self
+            .client
+            .clone()
+            .list_connections(proto::ListConnectionsRequest {
+                context: Self::context(tenant_id),
+                filter_type: 0,
+            })
+            .await
+            .map_err(status_to_error)?;
+
+        Ok(response
+            .into_inner()
+            .connections
+            .into_iter()
+            .map(|c| ConnectionInfo {
+                connection_id: c.connection_id,
+                source_type: from_proto_source_type(c.r#type),
+                display_name: c.display_name,
+                provider_account_id: non_empty(c.provider_account_id),
+                backend: non_empty(c.backend),
+            })
+            .collect())
+    }
+
+    async fn list_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        path: &str,
+        page_token: Option<&str>,
+        page_size: u32,
+    ) -> Result<FileListResult, StorageError> {
+        // Servers route by connection ID; the source type is informational.
+        let response = self
+            .client
+            .clone()
+            .list_connection_files(proto::ListConnectionFilesRequest {
+                context: Self::context(tenant_id),
+                r#type: 0,
+                connection_id: connection_id.to_string(),
+                path: path.to_string(),
+                page_token: page_token.unwrap_or_default().to_string(),
+                page_size: page_size as i32,
+            })
+            .await
+            .map_err(status_to_error)?
+            .into_inner();
+
+        Ok(from_proto_files(response))
+    }
+
+    async fn search_files(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        query: &FileSearchQuery,
+    ) -> Result<FileListResult, StorageError> {
+        let response = self
+            .client
+            .clone()
+            .search_connection_files(proto::SearchConnectionFilesRequest {
+                context: Self::context(tenant_id),
+                r#type: 0,
+                connection_id: connection_id.to_string(),
+                query: query.query.clone(),
+                path: query.path.clone(),
+                recursive: query.recursive,
+                mime_filter: query.mime_filter.clone().unwrap_or_default(),
+                page_token: query.page_token.clone().unwrap_or_default(),
+                page_size: query.page_size as i32,
+            })
+            .await
+            .map_err(status_to_error)?
+            .into_inner();
+
+        Ok(from_proto_files(response))
+    }
+
+    async fn download_file(
+        &self,
+        tenant_id: &str,
+        connection_id: &str,
+        path: &str,
+        file_id: Option<&str>,
+    ) -> Result<Vec<u8>, StorageError> {
+        let mut stream = self
+            .client
+            .clone()
+            .download_from_source(proto::DownloadFromSourceRequest {
+                context: Self::context(tenant_id),
+                r#type: 0,
+                connection_id: connection_id.to_string(),
+                path: path.to_string(),
+                file_id: file_id.unwrap_or_default().to_string(),
+            })
+            .await
+            .map_err(status_to_error)?
+            .into_inner();
+
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(&chunk.map_err(status_to_error)?.data);
+        }
+        Ok(data)
+    }
+}
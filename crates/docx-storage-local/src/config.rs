@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use docx_storage_core::{BackendOptions, CheckpointPolicy, LogFormat, TenantLimits};
+
+use crate::notifier::{DigestEmail, DigestRoute, NotifierSettings};
 
 /// Configuration for the docx-storage-local server.
 #[derive(Parser, Debug, Clone)]
@@ -19,25 +22,232 @@ pub struct Config {
     #[arg(long, default_value = "50051", env = "GRPC_PORT")]
     pub port: u16,
 
+    /// Also accept gRPC-Web (HTTP/1.1) requests on the TCP port
+    #[arg(long, env = "GRPC_WEB")]
+    pub grpc_web: bool,
+
+    /// Serve the HTTP/JSON gateway and web dashboard on this port (disabled when unset)
+    #[arg(long, env = "GATEWAY_PORT")]
+    pub gateway_port: Option<u16>,
+
+    /// Origins allowed to call gRPC-Web and the JSON gateway from a browser
+    #[arg(long, env = "CORS_ALLOWED_ORIGINS", value_delimiter = ',')]
+    pub cors_allowed_origins: Vec<String>,
+
     /// Unix socket path (only used with --transport unix)
     #[arg(long, env = "GRPC_UNIX_SOCKET")]
     pub unix_socket: Option<PathBuf>,
 
-    /// Storage backend (always local for this binary)
+    /// Single-user desktop mode: everything is kept in the local storage dir,
+    /// whose local (empty) tenant is provisioned at startup. Extra backends,
+    /// tenant routes and browse upstreams are refused
+    #[arg(long, env = "LOCAL_MODE")]
+    pub local_mode: bool,
+
+    /// Name of the storage backend serving tenants without a route: "local"
+    /// (the built-in local storage dir) or a backend declared with --backend
     #[arg(long, default_value = "local", env = "STORAGE_BACKEND")]
-    pub storage_backend: StorageBackend,
+    pub storage_backend: String,
+
+    /// Additional storage backends, as NAME=KIND[?OPTION=VALUE&...]
+    /// (e.g. archive=local?dir=/mnt/archive), comma-separated
+    #[arg(long = "backend", env = "STORAGE_BACKENDS", value_delimiter = ',', value_parser = parse_backend)]
+    pub backends: Vec<BackendSpec>,
+
+    /// Per-tenant backend routes, as TENANT=BACKEND, comma-separated
+    #[arg(long = "tenant-backend", env = "TENANT_BACKENDS", value_delimiter = ',', value_parser = parse_tenant_backend)]
+    pub tenant_backends: Vec<(String, String)>,
 
     /// Base directory for local storage
     #[arg(long, env = "LOCAL_STORAGE_DIR")]
     pub local_storage_dir: Option<PathBuf>,
 
+    /// Other storage servers whose connections are aggregated with the local
+    /// ones, as NAME=GRPC_URL (e.g. gdrive=http://localhost:50052), comma-separated
+    #[arg(long = "browse-upstream", env = "BROWSE_UPSTREAMS", value_delimiter = ',', value_parser = parse_upstream)]
+    pub browse_upstreams: Vec<(String, String)>,
+
     /// Parent process PID to watch. If set, server will exit when parent dies.
     /// This enables fork/join semantics where the child server follows the parent lifecycle.
     #[arg(long)]
     pub parent_pid: Option<u32>,
+
+    /// Log output format: text or json (one object per line, with the
+    /// request, tenant and session IDs of the enclosing spans)
+    #[arg(long, default_value = "text", env = "LOG_FORMAT")]
+    pub log_format: LogFormat,
+
+    /// Key (at least 16 bytes) signing tenant erasure confirmation tokens and
+    /// reports. EraseTenant is refused when unset
+    #[arg(long, env = "ERASURE_KEY", hide_env_values = true)]
+    pub erasure_key: Option<String>,
+
+    /// Key (at least 16 bytes) signing history proofs. When set, every
+    /// session's WAL appends, truncations and checkpoints are hash-chained;
+    /// GetHistoryProof is refused when unset
+    #[arg(long, env = "HISTORY_KEY", hide_env_values = true)]
+    pub history_key: Option<String>,
+
+    /// Sessions an ExportTenantCorpus stream reads per second, so compliance
+    /// exports don't starve the tenant's editing (0 = unpaced)
+    #[arg(long, default_value = "10", env = "CORPUS_EXPORT_SESSIONS_PER_SEC")]
+    pub corpus_export_sessions_per_sec: u32,
+
+    /// Seconds between purges of expired ephemeral sessions (0 disables them)
+    #[arg(long, default_value = "60", env = "PURGE_INTERVAL_SECS")]
+    pub purge_interval_secs: u64,
+
+    /// Seconds between rebuilds of every tenant's index from its stored
+    /// sessions (0 disables them). Scheduled rebuilds report orphaned index
+    /// entries but never remove them
+    #[arg(long, default_value = "0", env = "INDEX_REBUILD_INTERVAL_SECS")]
+    pub index_rebuild_interval_secs: u64,
+
+    /// Store the images of saved sessions and checkpoints once per tenant,
+    /// by content hash, instead of in every package
+    #[arg(long, env = "MEDIA_DEDUP")]
+    pub media_dedup: bool,
+
+    /// Seconds between sweeps of the stored images no session or checkpoint
+    /// references any more, with media deduplication on (0 disables them)
+    #[arg(long, default_value = "86400", env = "MEDIA_SWEEP_INTERVAL_SECS")]
+    pub media_sweep_interval_secs: u64,
+
+    /// Directory resumable SaveSession uploads are spooled to until their
+    /// last chunk arrives (defaults next to the local storage directory)
+    #[arg(long, env = "UPLOAD_SPOOL_DIR")]
+    pub upload_spool_dir: Option<PathBuf>,
+
+    /// Seconds an interrupted resumable upload can be resumed for
+    #[arg(long, default_value = "3600", env = "UPLOAD_TTL_SECS")]
+    pub upload_ttl_secs: u64,
+
+    /// WAL entries since the last checkpoint after which clients are told to
+    /// save one (1 = after every entry, 0 = never on count)
+    #[arg(long, default_value = "1", env = "CHECKPOINT_EVERY_ENTRIES")]
+    pub checkpoint_every_entries: u64,
+
+    /// Seconds after which the next edit of a session is checkpointed, however
+    /// few entries it has since the last one (0 disables)
+    #[arg(long, default_value = "0", env = "CHECKPOINT_EVERY_SECS")]
+    pub checkpoint_every_secs: u64,
+
+    /// Patch ops checkpointed as soon as they are logged, comma-separated
+    /// (defaults to replace_text, import_xliff and optimize_document)
+    #[arg(long, env = "CHECKPOINT_OPS", value_delimiter = ',')]
+    pub checkpoint_ops: Option<Vec<String>>,
+
+    /// Requests of one tenant handled at once; more wait in the tenant's
+    /// queue (0 = unlimited)
+    #[arg(long, default_value = "16", env = "TENANT_MAX_IN_FLIGHT")]
+    pub tenant_max_in_flight: usize,
+
+    /// Requests of one tenant waiting for a slot before new ones are
+    /// rejected with RESOURCE_EXHAUSTED
+    #[arg(long, default_value = "64", env = "TENANT_MAX_QUEUED")]
+    pub tenant_max_queued: usize,
+
+    /// Milliseconds a queued request waits for a slot before it is rejected
+    #[arg(long, default_value = "5000", env = "TENANT_QUEUE_TIMEOUT_MS")]
+    pub tenant_queue_timeout_ms: u64,
+
+    /// Folder watched for dropped DOCX files, each opened as a session synced
+    /// back to the file and announced on the tenant's change stream
+    /// (disabled when unset)
+    #[arg(long, env = "INBOX_DIR")]
+    pub inbox_dir: Option<PathBuf>,
+
+    /// Tenant the sessions opened from the inbox belong to (the local
+    /// tenant by default)
+    #[arg(long, default_value = "", env = "INBOX_TENANT")]
+    pub inbox_tenant: String,
+
+    /// Seconds between scans of the inbox folder
+    #[arg(long, default_value = "2", env = "INBOX_INTERVAL_SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub inbox_interval_secs: u64,
+
+    /// Seconds between digests of each tenant's external changes and syncs
+    /// (0 disables notifications)
+    #[arg(long, default_value = "0", env = "NOTIFY_DIGEST_INTERVAL_SECS")]
+    pub notify_digest_interval_secs: u64,
+
+    /// Slack incoming webhooks digests are posted to, comma-separated
+    #[arg(long, env = "NOTIFY_SLACK_WEBHOOKS", value_delimiter = ',', hide_env_values = true)]
+    pub notify_slack_webhooks: Vec<String>,
+
+    /// Email addresses digests are sent to, comma-separated
+    #[arg(long, env = "NOTIFY_EMAIL_TO", value_delimiter = ',')]
+    pub notify_email_to: Vec<String>,
+
+    /// HTTP email API digests are sent through, taking a JSON
+    /// {from, to, subject, text} POST (e.g. https://api.resend.com/emails)
+    #[arg(long, env = "NOTIFY_EMAIL_ENDPOINT")]
+    pub notify_email_endpoint: Option<String>,
+
+    /// Bearer token of the email API
+    #[arg(long, env = "NOTIFY_EMAIL_API_KEY", hide_env_values = true)]
+    pub notify_email_api_key: Option<String>,
+
+    /// Sender address of digest emails
+    #[arg(long, default_value = "docx-mcp@localhost", env = "NOTIFY_EMAIL_FROM")]
+    pub notify_email_from: String,
+
+    /// JSON file of per-tenant digest destinations, keyed by tenant ID
+    /// (e.g. {"acme": {"slack_webhooks": [...], "email_to": [...]}}); other
+    /// tenants use the destinations above
+    #[arg(long, env = "NOTIFY_TENANTS_FILE")]
+    pub notify_tenants_file: Option<PathBuf>,
+
+    /// TOML configuration file (keys are the long option names). Flags and
+    /// environment variables take precedence over it
+    #[arg(long, env = "CONFIG_FILE")]
+    pub config: Option<PathBuf>,
 }
 
 impl Config {
+    /// Per-tenant concurrency limits from the tenant options.
+    pub fn tenant_limits(&self) -> TenantLimits {
+        TenantLimits {
+            max_in_flight: self.tenant_max_in_flight,
+            max_queued: self.tenant_max_queued,
+            queue_timeout: std::time::Duration::from_millis(self.tenant_queue_timeout_ms),
+        }
+    }
+
+    /// Checkpoint policy from the checkpoint options.
+    pub fn checkpoint_policy(&self) -> CheckpointPolicy {
+        let mut policy = CheckpointPolicy {
+            every_entries: self.checkpoint_every_entries,
+            every: (self.checkpoint_every_secs > 0)
+                .then(|| std::time::Duration::from_secs(self.checkpoint_every_secs)),
+            ..CheckpointPolicy::default()
+        };
+        if let Some(ops) = &self.checkpoint_ops {
+            policy.ops = ops.iter().map(|op| op.trim().to_string()).collect();
+        }
+        policy
+    }
+
+    /// Digest destinations from the notify options.
+    pub fn notifier_settings(&self) -> anyhow::Result<NotifierSettings> {
+        let tenants = match &self.notify_tenants_file {
+            Some(path) => NotifierSettings::load_tenants(path)?,
+            None => Default::default(),
+        };
+        Ok(NotifierSettings {
+            default_route: DigestRoute {
+                slack_webhooks: self.notify_slack_webhooks.clone(),
+                email_to: self.notify_email_to.clone(),
+            },
+            tenants,
+            email: self.notify_email_endpoint.clone().map(|endpoint| DigestEmail {
+                endpoint,
+                api_key: self.notify_email_api_key.clone(),
+                from: self.notify_email_from.clone(),
+            }),
+        })
+    }
+
     /// Get the effective local storage directory.
     pub fn effective_local_storage_dir(&self) -> PathBuf {
         self.local_storage_dir.clone().unwrap_or_else(|| {
@@ -48,6 +258,36 @@ impl Config {
         })
     }
 
+    /// Get the effective upload spool directory.
+    pub fn effective_upload_spool_dir(&self) -> PathBuf {
+        self.upload_spool_dir.clone().unwrap_or_else(|| {
+            dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("docx-mcp")
+                .join("uploads")
+        })
+    }
+
+    /// CORS layer for browser clients. Without allowed origins, no
+    /// cross-origin request is permitted.
+    pub fn cors_layer(&self) -> tower_http::cors::CorsLayer {
+        use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+        let origins: Vec<_> = self
+            .cors_allowed_origins
+            .iter()
+            .filter_map(|o| o.parse().ok())
+            .collect();
+        if origins.is_empty() {
+            return CorsLayer::new();
+        }
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_headers(Any)
+            .allow_methods(Any)
+            .expose_headers(Any)
+    }
+
     /// Get the effective Unix socket path.
     #[cfg(unix)]
     pub fn effective_unix_socket(&self) -> PathBuf {
@@ -60,6 +300,51 @@ impl Config {
     }
 }
 
+/// Parse a `name=url` upstream from the command line.
+fn parse_upstream(s: &str) -> Result<(String, String), String> {
+    let (name, url) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=URL, got '{}'", s))?;
+    Ok((name.trim().to_string(), url.trim().to_string()))
+}
+
+/// A named storage backend declared on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendSpec {
+    pub name: String,
+    /// Registered backend kind (see `server::storage_registry`)
+    pub kind: String,
+    pub options: BackendOptions,
+}
+
+/// Parse a `name=kind?key=value&...` backend from the command line.
+fn parse_backend(s: &str) -> Result<BackendSpec, String> {
+    let (name, spec) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=KIND[?OPTION=VALUE&...], got '{}'", s))?;
+    let (kind, query) = spec.split_once('?').unwrap_or((spec, ""));
+    let mut options = BackendOptions::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("expected OPTION=VALUE in backend '{}', got '{}'", name, pair))?;
+        options.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    Ok(BackendSpec {
+        name: name.trim().to_string(),
+        kind: kind.trim().to_string(),
+        options,
+    })
+}
+
+/// Parse a `tenant=backend` route from the command line.
+fn parse_tenant_backend(s: &str) -> Result<(String, String), String> {
+    let (tenant, backend) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TENANT=BACKEND, got '{}'", s))?;
+    Ok((tenant.trim().to_string(), backend.trim().to_string()))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum Transport {
     Tcp,
@@ -74,16 +359,3 @@ impl std::fmt::Display for Transport {
         }
     }
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
-pub enum StorageBackend {
-    Local,
-}
-
-impl std::fmt::Display for StorageBackend {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            StorageBackend::Local => write!(f, "local"),
-        }
-    }
-}
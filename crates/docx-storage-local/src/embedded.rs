@@ -1,21 +1,57 @@
+//! In-process gRPC server for the staticlib, reached through an in-memory pipe.
+//!
+//! The NativeAOT host reads and writes the client half of a `DuplexStream`
+//! through FFI; the server half is served by tonic on its own runtime. A
+//! watchdog thread replaces the server with a warm standby (a second server
+//! already started on a fresh pipe and runtime) when it is wedged:
+//! - its connection ended, so the host would reconnect to nothing;
+//! - its runtime missed heartbeats for [`STALL_TIMEOUT`] (executor starved);
+//! - a pipe write waited [`STALL_TIMEOUT`] for the server to take it.
+//!
+//! Services and backends are shared by every server generation; watches are
+//! replayed on a fresh watch backend. Pending pipe calls of the replaced
+//! server return EOF or an error, so the host reconnects, now to the new
+//! server. [`health`] reports the watchdog's state.
+
 use std::path::Path;
 use std::pin::Pin;
-use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
+use docx_storage_core::{CorrelationLayer, DeadlineLayer, OperationRegistry};
+use serde::Serialize;
 use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf, ReadHalf, WriteHalf};
 use tokio::runtime::Runtime;
-use tokio::task::AbortHandle;
+use tokio::sync::watch as tokio_watch;
+use tokio::task::JoinHandle;
 use tonic::transport::server::Connected;
 use tonic::transport::Server;
 
 use crate::server;
 use crate::service::proto::external_watch_service_server::ExternalWatchServiceServer;
+use crate::service::proto::operation_service_server::OperationServiceServer;
 use crate::service::proto::source_sync_service_server::SourceSyncServiceServer;
 use crate::service::proto::storage_service_server::StorageServiceServer;
 use crate::service::StorageServiceImpl;
+use crate::service_operation::{OperationServiceImpl, OPERATION_RETENTION};
 use crate::service_sync::SourceSyncServiceImpl;
 use crate::service_watch::ExternalWatchServiceImpl;
+use crate::watch::{NotifyWatchBackend, ReplayingWatchBackend};
+
+/// How often the watchdog checks the active server.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often a server runtime shows it still runs tasks.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A server missing heartbeats, or not taking the bytes written to it, for
+/// this long is wedged and gets replaced.
+const STALL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// In-memory transport buffer (256KB — matches StorageClient chunk size).
+const PIPE_BUFFER: usize = 256 * 1024;
 
 /// Returns true if DEBUG environment variable is set.
 fn is_debug() -> bool {
@@ -64,22 +100,167 @@ impl AsyncWrite for InMemoryStream {
     }
 }
 
+/// gRPC services, shared by every server generation.
+#[derive(Clone)]
+struct Services {
+    storage: StorageServiceServer<StorageServiceImpl>,
+    sync: SourceSyncServiceServer<SourceSyncServiceImpl>,
+    watch: ExternalWatchServiceServer<ExternalWatchServiceImpl>,
+    operation: OperationServiceServer<OperationServiceImpl>,
+}
+
+/// One run of the tonic server, on its own runtime so that a starved
+/// executor is abandoned along with it.
+struct ServerGeneration {
+    number: u64,
+    runtime: Runtime,
+    server: JoinHandle<()>,
+    /// Last heartbeat of the runtime, in ms since `EmbeddedState::started`
+    heartbeat: Arc<AtomicU64>,
+}
+
+impl ServerGeneration {
+    /// Start a server on a fresh runtime and pipe. Returns the server and
+    /// the client half of its pipe.
+    fn start(
+        number: u64,
+        services: Services,
+        started: Instant,
+    ) -> Result<(Self, DuplexStream), String> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name(format!("docx-storage-server-{number}"))
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let (client, server_stream) = tokio::io::duplex(PIPE_BUFFER);
+
+        let heartbeat = Arc::new(AtomicU64::new(millis_since(started)));
+        let beat = heartbeat.clone();
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                beat.store(millis_since(started), Ordering::Relaxed);
+            }
+        });
+
+        // Serving with a shutdown signal (which never comes) makes the task
+        // last as long as the connection, so the watchdog sees it end
+        let server = runtime.spawn(async move {
+            if is_debug() {
+                eprintln!("[embedded] server {number}: starting serve_with_incoming...");
+            }
+            let result = Server::builder()
+                .layer(CorrelationLayer)
+                .layer(DeadlineLayer)
+                .add_service(services.storage)
+                .add_service(services.sync)
+                .add_service(services.watch)
+                .add_service(services.operation)
+                .serve_with_incoming_shutdown(
+                    tokio_stream::once(Ok::<_, std::io::Error>(InMemoryStream(server_stream))),
+                    std::future::pending::<()>(),
+                )
+                .await;
+            if is_debug() {
+                eprintln!("[embedded] server {number}: serve_with_incoming ended: {result:?}");
+            }
+        });
+
+        Ok((
+            Self {
+                number,
+                runtime,
+                server,
+                heartbeat,
+            },
+            client,
+        ))
+    }
+
+    /// Why this server is wedged, if it is. `write_pending_since` is when
+    /// the pending pipe write started (ms + 1), or 0.
+    fn wedged(&self, now_ms: u64, write_pending_since: u64) -> Option<String> {
+        let stall_ms = STALL_TIMEOUT.as_millis() as u64;
+        if self.server.is_finished() {
+            return Some("connection closed".to_string());
+        }
+        let heartbeat_age = now_ms.saturating_sub(self.heartbeat.load(Ordering::Relaxed));
+        if heartbeat_age > stall_ms {
+            return Some(format!("executor starved, no heartbeat for {heartbeat_age}ms"));
+        }
+        if write_pending_since > 0 {
+            let waited = now_ms.saturating_sub(write_pending_since - 1);
+            if waited > stall_ms {
+                return Some(format!("pipe stalled, a write waited {waited}ms"));
+            }
+        }
+        None
+    }
+
+    /// Stop the server without waiting for its tasks, which may never yield.
+    fn retire(self) {
+        self.server.abort();
+        self.runtime.shutdown_background();
+    }
+}
+
+/// One half of the client pipe, tagged with the server generation it
+/// talks to.
+struct PipeHalf<T> {
+    generation: u64,
+    half: T,
+}
+
 /// Global state for the embedded gRPC server.
 /// Read and write halves have separate mutexes so HTTP/2 full-duplex works
 /// (one .NET thread reads, another writes, concurrently).
 struct EmbeddedState {
+    /// Runs the backends' background tasks and the FFI pipe calls
     runtime: Runtime,
-    read_half: Mutex<ReadHalf<DuplexStream>>,
-    write_half: Mutex<WriteHalf<DuplexStream>>,
-    server_abort: AbortHandle,
+    started: Instant,
+    services: Services,
+    watch: Arc<ReplayingWatchBackend>,
+    read_half: Mutex<PipeHalf<ReadHalf<DuplexStream>>>,
+    write_half: Mutex<PipeHalf<WriteHalf<DuplexStream>>>,
+    active: Mutex<ServerGeneration>,
+    /// Started server and its client pipe, ready to take over
+    standby: Mutex<Option<(ServerGeneration, DuplexStream)>>,
+    /// Generation of the active server; pipe calls on an older one end early
+    generation: tokio_watch::Sender<u64>,
+    /// When the pending pipe write started (ms + 1), 0 when none
+    write_pending_since: AtomicU64,
+    restarts: AtomicU64,
+    last_restart_reason: Mutex<Option<String>>,
+    stopped: AtomicBool,
 }
 
 static STATE: OnceLock<EmbeddedState> = OnceLock::new();
 
+/// Milliseconds from `started` to now.
+fn millis_since(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+/// Health of the embedded server, returned by [`health`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedHealth {
+    /// "serving", "wedged" (about to be replaced) or "stopped"
+    pub status: &'static str,
+    /// Server generation, from 1; bumped on each restart
+    pub generation: u64,
+    pub restarts: u64,
+    pub last_restart_reason: Option<String>,
+    pub heartbeat_age_ms: u64,
+    pub standby_ready: bool,
+    pub watches: usize,
+}
+
 /// Initialize the embedded gRPC server with in-memory DuplexStream transport.
 ///
-/// Creates storage backends, starts tonic server on a background tokio task,
-/// and splits the client half of the DuplexStream for FFI read/write access.
+/// Creates storage backends, starts the tonic server on its own runtime, and
+/// splits the client half of the DuplexStream for FFI read/write access. A
+/// watchdog thread then keeps a standby server ready and fails over to it.
 pub fn init(storage_dir: &Path) -> Result<(), String> {
     let debug = is_debug();
     if debug {
@@ -93,53 +274,176 @@ pub fn init(storage_dir: &Path) -> Result<(), String> {
 
     // Create backends (shared with main.rs via server module)
     let (storage, lock, sync, watch, browse) = server::create_backends(storage_dir);
+    let watch = Arc::new(ReplayingWatchBackend::new(watch));
 
     // Create gRPC services
-    let storage_svc = StorageServiceServer::new(StorageServiceImpl::new(storage, lock));
-    let sync_svc = SourceSyncServiceServer::new(SourceSyncServiceImpl::new(sync, browse));
-    let watch_svc = ExternalWatchServiceServer::new(ExternalWatchServiceImpl::new(watch));
-
-    // Create in-memory transport (256KB buffer — matches StorageClient chunk size)
-    if debug {
-        eprintln!("[embedded] init: creating DuplexStream...");
-    }
-    let (client, server_stream) = tokio::io::duplex(256 * 1024);
+    let storage_service =
+        Arc::new(StorageServiceImpl::new(storage, lock).with_sync_backend(sync.clone()));
+    server::spawn_expiry_purge(storage_service.clone(), server::DEFAULT_PURGE_INTERVAL);
+    let services = Services {
+        storage: StorageServiceServer::from_arc(storage_service),
+        sync: SourceSyncServiceServer::new(SourceSyncServiceImpl::new(sync, browse)),
+        watch: ExternalWatchServiceServer::new(ExternalWatchServiceImpl::new(
+            watch.clone(),
+            server::create_change_queue(storage_dir),
+        )),
+        operation: OperationServiceServer::new(OperationServiceImpl::new(Arc::new(
+            OperationRegistry::new(OPERATION_RETENTION),
+        ))),
+    };
 
-    // Start tonic server on the server half (runs on tokio worker threads)
+    // Start tonic server on the server half of a new pipe
     if debug {
         eprintln!("[embedded] init: spawning tonic server...");
     }
-    let server_handle = runtime.spawn(async move {
-        if is_debug() {
-            eprintln!("[embedded] server task: starting serve_with_incoming...");
-        }
-        let result = Server::builder()
-            .add_service(storage_svc)
-            .add_service(sync_svc)
-            .add_service(watch_svc)
-            .serve_with_incoming(tokio_stream::once(Ok::<_, std::io::Error>(
-                InMemoryStream(server_stream),
-            )))
-            .await;
-        if is_debug() {
-            eprintln!("[embedded] server task: serve_with_incoming ended: {result:?}");
-        }
-    });
+    let started = Instant::now();
+    let (active, client) = ServerGeneration::start(1, services.clone(), started)?;
 
     // Split client for concurrent read/write (HTTP/2 is full-duplex)
     if debug {
         eprintln!("[embedded] init: splitting client DuplexStream...");
     }
     let (read_half, write_half) = tokio::io::split(client);
+    let (generation, _) = tokio_watch::channel(active.number);
 
     STATE
         .set(EmbeddedState {
             runtime,
-            read_half: Mutex::new(read_half),
-            write_half: Mutex::new(write_half),
-            server_abort: server_handle.abort_handle(),
+            started,
+            services,
+            watch,
+            read_half: Mutex::new(PipeHalf {
+                generation: active.number,
+                half: read_half,
+            }),
+            write_half: Mutex::new(PipeHalf {
+                generation: active.number,
+                half: write_half,
+            }),
+            active: Mutex::new(active),
+            standby: Mutex::new(None),
+            generation,
+            write_pending_since: AtomicU64::new(0),
+            restarts: AtomicU64::new(0),
+            last_restart_reason: Mutex::new(None),
+            stopped: AtomicBool::new(false),
         })
-        .map_err(|_| "Already initialized".to_string())
+        .map_err(|_| "Already initialized".to_string())?;
+
+    std::thread::Builder::new()
+        .name("docx-storage-watchdog".to_string())
+        .spawn(watchdog)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Keep a standby server ready and fail over to it when the active one is
+/// wedged. Runs on its own thread: the runtimes it watches may be starved.
+fn watchdog() {
+    let Some(state) = STATE.get() else {
+        return;
+    };
+    while !state.stopped.load(Ordering::Relaxed) {
+        state.ensure_standby();
+        std::thread::sleep(WATCHDOG_INTERVAL);
+        if state.stopped.load(Ordering::Relaxed) {
+            return;
+        }
+        let reason = state.active.lock().unwrap().wedged(
+            millis_since(state.started),
+            state.write_pending_since.load(Ordering::Relaxed),
+        );
+        if let Some(reason) = reason {
+            state.failover(&reason);
+        }
+    }
+}
+
+impl EmbeddedState {
+    /// Start the standby server unless one is ready.
+    fn ensure_standby(&self) {
+        let mut standby = self.standby.lock().unwrap();
+        if standby.is_some() {
+            return;
+        }
+        let number = *self.generation.borrow() + 1;
+        match ServerGeneration::start(number, self.services.clone(), self.started) {
+            Ok(ready) => *standby = Some(ready),
+            Err(e) => eprintln!("[embedded] watchdog: failed to start standby server: {e}"),
+        }
+    }
+
+    /// Replace the active server with the standby (started now if needed).
+    fn failover(&self, reason: &str) {
+        eprintln!("[embedded] watchdog: replacing server ({reason})");
+        let next = self.standby.lock().unwrap().take();
+        let (next, client) = match next {
+            Some(ready) => ready,
+            None => {
+                let number = *self.generation.borrow() + 1;
+                match ServerGeneration::start(number, self.services.clone(), self.started) {
+                    Ok(ready) => ready,
+                    Err(e) => {
+                        eprintln!("[embedded] watchdog: failed to start server: {e}");
+                        return;
+                    }
+                }
+            }
+        };
+
+        // The old watch backend's event task may be starved with the rest;
+        // start its watches again on a new one before clients come back
+        let replayed = {
+            let _guard = self.runtime.enter();
+            let fresh = Arc::new(NotifyWatchBackend::new());
+            self.runtime.block_on(self.watch.replace(fresh))
+        };
+
+        // Wake pipe calls blocked on the old server, then swap the pipe
+        let number = next.number;
+        self.generation.send_replace(number);
+        let old = std::mem::replace(&mut *self.active.lock().unwrap(), next);
+        let (read_half, write_half) = tokio::io::split(client);
+        *self.read_half.lock().unwrap() = PipeHalf {
+            generation: number,
+            half: read_half,
+        };
+        *self.write_half.lock().unwrap() = PipeHalf {
+            generation: number,
+            half: write_half,
+        };
+        old.retire();
+
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+        *self.last_restart_reason.lock().unwrap() = Some(reason.to_string());
+        eprintln!("[embedded] watchdog: server {number} serving, {replayed} watches replayed");
+    }
+}
+
+/// Health of the embedded server, or `None` before [`init`].
+pub fn health() -> Option<EmbeddedHealth> {
+    let state = STATE.get()?;
+    let now_ms = millis_since(state.started);
+    let active = state.active.lock().unwrap();
+    let status = if state.stopped.load(Ordering::Relaxed) {
+        "stopped"
+    } else if active
+        .wedged(now_ms, state.write_pending_since.load(Ordering::Relaxed))
+        .is_some()
+    {
+        "wedged"
+    } else {
+        "serving"
+    };
+    Some(EmbeddedHealth {
+        status,
+        generation: active.number,
+        restarts: state.restarts.load(Ordering::Relaxed),
+        last_restart_reason: state.last_restart_reason.lock().unwrap().clone(),
+        heartbeat_age_ms: now_ms.saturating_sub(active.heartbeat.load(Ordering::Relaxed)),
+        standby_ready: state.standby.lock().unwrap().is_some(),
+        watches: state.watch.watch_count(),
+    })
 }
 
 /// Read from the client side of the in-memory gRPC transport.
@@ -151,25 +455,39 @@ pub fn pipe_read(buf: &mut [u8]) -> i64 {
         None => return -1,
     };
     let debug = is_debug();
+    let mut restarted = state.generation.subscribe();
     if debug {
         eprintln!("[embedded] pipe_read: waiting for lock (buf_len={})...", buf.len());
     }
     let mut reader = state.read_half.lock().unwrap();
+    // The server is being replaced: end this connection so the host
+    // reconnects to the new one
+    if reader.generation != *restarted.borrow_and_update() {
+        return 0;
+    }
     if debug {
         eprintln!("[embedded] pipe_read: got lock, calling block_on...");
     }
     state.runtime.block_on(async {
         use tokio::io::AsyncReadExt;
-        match reader.read(buf).await {
-            Ok(n) => {
+        tokio::select! {
+            result = reader.half.read(buf) => match result {
+                Ok(n) => {
+                    if debug {
+                        eprintln!("[embedded] pipe_read: read {n} bytes");
+                    }
+                    n as i64
+                }
+                Err(e) => {
+                    eprintln!("[embedded] pipe_read: error: {e}");
+                    -1
+                }
+            },
+            _ = restarted.changed() => {
                 if debug {
-                    eprintln!("[embedded] pipe_read: read {n} bytes");
+                    eprintln!("[embedded] pipe_read: server replaced, returning EOF");
                 }
-                n as i64
-            }
-            Err(e) => {
-                eprintln!("[embedded] pipe_read: error: {e}");
-                -1
+                0
             }
         }
     })
@@ -184,6 +502,7 @@ pub fn pipe_write(data: &[u8]) -> i64 {
         None => return -1,
     };
     let debug = is_debug();
+    let mut restarted = state.generation.subscribe();
     if debug {
         eprintln!(
             "[embedded] pipe_write: waiting for lock (data_len={})...",
@@ -191,24 +510,38 @@ pub fn pipe_write(data: &[u8]) -> i64 {
         );
     }
     let mut writer = state.write_half.lock().unwrap();
+    if writer.generation != *restarted.borrow_and_update() {
+        return -1;
+    }
     if debug {
         eprintln!("[embedded] pipe_write: got lock, calling block_on...");
     }
-    state.runtime.block_on(async {
+    state
+        .write_pending_since
+        .store(millis_since(state.started) + 1, Ordering::Relaxed);
+    let result = state.runtime.block_on(async {
         use tokio::io::AsyncWriteExt;
-        match writer.write_all(data).await {
-            Ok(()) => {
-                if debug {
-                    eprintln!("[embedded] pipe_write: wrote {} bytes", data.len());
+        tokio::select! {
+            result = writer.half.write_all(data) => match result {
+                Ok(()) => {
+                    if debug {
+                        eprintln!("[embedded] pipe_write: wrote {} bytes", data.len());
+                    }
+                    data.len() as i64
                 }
-                data.len() as i64
-            }
-            Err(e) => {
-                eprintln!("[embedded] pipe_write: error: {e}");
+                Err(e) => {
+                    eprintln!("[embedded] pipe_write: error: {e}");
+                    -1
+                }
+            },
+            _ = restarted.changed() => {
+                eprintln!("[embedded] pipe_write: server replaced, write dropped");
                 -1
             }
         }
-    })
+    });
+    state.write_pending_since.store(0, Ordering::Relaxed);
+    result
 }
 
 /// Flush the write side of the transport.
@@ -221,7 +554,7 @@ pub fn pipe_flush() -> i32 {
     let mut writer = state.write_half.lock().unwrap();
     state.runtime.block_on(async {
         use tokio::io::AsyncWriteExt;
-        match writer.flush().await {
+        match writer.half.flush().await {
             Ok(()) => 0,
             Err(_) => -1,
         }
@@ -229,10 +562,12 @@ pub fn pipe_flush() -> i32 {
 }
 
 /// Shutdown the embedded gRPC server.
-/// Aborts the server task. The runtime and pipe state remain in memory
-/// (leaked via OnceLock) but the process is expected to exit shortly after.
+/// Stops the watchdog and aborts the server task. The runtimes and pipe
+/// state remain in memory (leaked via OnceLock) but the process is expected
+/// to exit shortly after.
 pub fn shutdown() {
     if let Some(state) = STATE.get() {
-        state.server_abort.abort();
+        state.stopped.store(true, Ordering::Relaxed);
+        state.active.lock().unwrap().server.abort();
     }
 }
@@ -0,0 +1,687 @@
+//! JSON gateway over the storage and sync backends.
+//!
+//! Exposes the read-side of `StorageService` and `SourceSyncService` as plain
+//! HTTP/JSON so browser dashboards and shell scripts can inspect sessions
+//! without generating protobuf clients. Mutations stay on gRPC (or gRPC-Web).
+//!
+//! The tenant is selected with the `tenant` query parameter on every route and
+//! defaults to the empty (local) tenant.
+//!
+//! Routes:
+//! ```text
+//! GET /api/health
+//! GET /api/index
+//! GET /api/sessions
+//! GET /api/sessions/{session_id}
+//! GET /api/sessions/{session_id}/document
+//! GET /api/sessions/{session_id}/health
+//! GET /api/sessions/{session_id}/wal?from=&limit=
+//! GET /api/sessions/{session_id}/wal/tail?before=&limit=
+//! GET /api/sessions/{session_id}/checkpoints
+//! GET /api/sessions/{session_id}/checkpoints/{position}
+//! GET /api/sessions/{session_id}/sync
+//! GET /api/sources
+//! GET /api/sources/pending
+//! GET /api/recent?favorites_only=
+//! GET /api/connections
+//! GET /api/connections/files?connection_id=&path=&page_token=&page_size=
+//! GET /api/connections/search?connection_id=&q=&path=&recursive=&mime=&page_token=&page_size=
+//! ```
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use docx_storage_core::{
+    BrowsableBackend, CheckpointInfo, FileListResult, FileSearchQuery, PendingSync, RecentFile,
+    SessionHealth, SessionIndex, SessionIndexEntry, SessionInfo, StorageBackend, StorageError,
+    SyncBackend, SyncStatus, WalEntry,
+};
+use serde::{Deserialize, Serialize};
+
+const DOCX_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+/// Default and maximum number of WAL entries returned per request.
+const DEFAULT_WAL_LIMIT: u64 = 100;
+const MAX_WAL_LIMIT: u64 = 1000;
+
+/// Default page size for file listings.
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Backends served by the gateway.
+#[derive(Clone)]
+pub struct GatewayState {
+    pub storage: Arc<dyn StorageBackend>,
+    pub sync: Arc<dyn SyncBackend>,
+    pub browse: Arc<dyn BrowsableBackend>,
+}
+
+/// Build the JSON gateway router.
+pub fn router(state: GatewayState) -> Router {
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/api/index", get(index))
+        .route("/api/sessions", get(list_sessions))
+        .route("/api/sessions/{session_id}", get(get_session))
+        .route("/api/sessions/{session_id}/document", get(download_session))
+        .route("/api/sessions/{session_id}/health", get(session_health))
+        .route("/api/sessions/{session_id}/wal", get(read_wal))
+        .route("/api/sessions/{session_id}/wal/tail", get(tail_wal))
+        .route("/api/sessions/{session_id}/checkpoints", get(list_checkpoints))
+        .route(
+            "/api/sessions/{session_id}/checkpoints/{position}",
+            get(download_checkpoint),
+        )
+        .route("/api/sessions/{session_id}/sync", get(sync_status))
+        .route("/api/sources", get(list_sources))
+        .route("/api/sources/pending", get(list_pending_syncs))
+        .route("/api/recent", get(list_recent_files))
+        .route("/api/connections", get(list_connections))
+        .route("/api/connections/files", get(list_files))
+        .route("/api/connections/search", get(search_files))
+        .with_state(state)
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Storage error rendered as a JSON body with a matching HTTP status.
+pub struct GatewayError(StorageError);
+
+impl From<StorageError> for GatewayError {
+    fn from(err: StorageError) -> Self {
+        Self(err)
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct ErrorBody {
+            error: String,
+            code: &'static str,
+        }
+
+        let (status, code) = match &self.0 {
+            StorageError::NotFound(_) => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+            StorageError::Throttled { .. } => (StatusCode::TOO_MANY_REQUESTS, "THROTTLED"),
+            StorageError::PreconditionFailed(_) => {
+                (StatusCode::PRECONDITION_FAILED, "PRECONDITION_FAILED")
+            }
+            StorageError::QuotaExceeded(_) => (StatusCode::INSUFFICIENT_STORAGE, "QUOTA_EXCEEDED"),
+            StorageError::Unauthenticated(_) => (StatusCode::UNAUTHORIZED, "UNAUTHENTICATED"),
+            StorageError::InvalidArgument(_) => (StatusCode::BAD_REQUEST, "INVALID_ARGUMENT"),
+            StorageError::Lock(_) => (StatusCode::CONFLICT, "LOCKED"),
+            StorageError::Conflict(_) => (StatusCode::CONFLICT, "CONFLICT"),
+            StorageError::WalPosition { .. } => (StatusCode::CONFLICT, "WAL_POSITION"),
+            StorageError::Sync(_) => (StatusCode::BAD_GATEWAY, "SYNC_ERROR"),
+            StorageError::Unavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "UNAVAILABLE"),
+            StorageError::DeadlineExceeded(_) => (StatusCode::GATEWAY_TIMEOUT, "DEADLINE_EXCEEDED"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
+        };
+
+        let body = ErrorBody {
+            error: self.0.to_string(),
+            code,
+        };
+        let mut response = (status, Json(body)).into_response();
+        if let Some(retry_after) = self.0.retry_after() {
+            // Round up so clients never retry early
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, header::HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+type GatewayResult<T> = Result<T, GatewayError>;
+
+// =============================================================================
+// Query parameters and DTOs
+// =============================================================================
+
+#[derive(Deserialize)]
+struct TenantQuery {
+    #[serde(default)]
+    tenant: String,
+}
+
+#[derive(Deserialize)]
+struct WalQuery {
+    #[serde(default)]
+    tenant: String,
+    #[serde(default)]
+    from: u64,
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct WalTailQuery {
+    #[serde(default)]
+    tenant: String,
+    before: Option<u64>,
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RecentQuery {
+    #[serde(default)]
+    tenant: String,
+    #[serde(default)]
+    favorites_only: bool,
+}
+
+#[derive(Deserialize)]
+struct FilesQuery {
+    #[serde(default)]
+    tenant: String,
+    #[serde(default)]
+    connection_id: String,
+    #[serde(default)]
+    path: String,
+    page_token: Option<String>,
+    page_size: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    tenant: String,
+    #[serde(default)]
+    connection_id: String,
+    #[serde(default)]
+    q: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    recursive: bool,
+    mime: Option<String>,
+    page_token: Option<String>,
+    page_size: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    healthy: bool,
+    backend: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct WalEntryView {
+    position: u64,
+    operation: String,
+    path: String,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    /// The stored patch, parsed as JSON when possible.
+    patch: serde_json::Value,
+}
+
+impl From<WalEntry> for WalEntryView {
+    fn from(e: WalEntry) -> Self {
+        Self {
+            position: e.position,
+            operation: e.operation,
+            path: e.path,
+            timestamp: e.timestamp,
+            patch: serde_json::from_slice(&e.patch_json).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&e.patch_json).into_owned())
+            }),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WalResponse {
+    entries: Vec<WalEntryView>,
+    has_more: bool,
+}
+
+#[derive(Serialize)]
+struct ConnectionView {
+    connection_id: String,
+    source_type: docx_storage_core::SourceType,
+    display_name: String,
+    provider_account_id: Option<String>,
+    backend: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FileView {
+    name: String,
+    path: String,
+    file_id: Option<String>,
+    is_folder: bool,
+    size_bytes: u64,
+    modified_at: i64,
+    mime_type: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FileListResponse {
+    files: Vec<FileView>,
+    next_page_token: Option<String>,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+async fn health(State(state): State<GatewayState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        healthy: true,
+        backend: state.storage.backend_name(),
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+async fn index(
+    State(state): State<GatewayState>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<SessionIndex>> {
+    let index = state.storage.load_index(&q.tenant).await?.unwrap_or_default();
+    Ok(Json(index))
+}
+
+async fn list_sessions(
+    State(state): State<GatewayState>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<Vec<SessionInfo>>> {
+    Ok(Json(state.storage.list_sessions(&q.tenant).await?))
+}
+
+async fn get_session(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<SessionIndexEntry>> {
+    state
+        .storage
+        .load_index(&q.tenant)
+        .await?
+        .and_then(|index| index.sessions.into_iter().find(|s| s.id == session_id))
+        .map(Json)
+        .ok_or_else(|| StorageError::NotFound(format!("session {}", session_id)).into())
+}
+
+async fn download_session(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Response> {
+    let data = state
+        .storage
+        .load_session(&q.tenant, &session_id)
+        .await?
+        .ok_or_else(|| StorageError::NotFound(format!("session {}", session_id)))?;
+    Ok(docx_response(data, &format!("{}.docx", session_id)))
+}
+
+/// WAL, checkpoint, sync and hold state of the session, with consistency checks.
+async fn session_health(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<SessionHealth>> {
+    docx_storage_core::session_health(
+        state.storage.as_ref(),
+        Some(state.sync.as_ref()),
+        &q.tenant,
+        &session_id,
+        chrono::Utc::now(),
+    )
+    .await?
+    .map(Json)
+    .ok_or_else(|| StorageError::NotFound(format!("session {}", session_id)).into())
+}
+
+async fn read_wal(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<WalQuery>,
+) -> GatewayResult<Json<WalResponse>> {
+    let limit = q.limit.unwrap_or(DEFAULT_WAL_LIMIT).clamp(1, MAX_WAL_LIMIT);
+    let (entries, has_more) = state
+        .storage
+        .read_wal(&q.tenant, &session_id, q.from, Some(limit))
+        .await?;
+
+    Ok(Json(WalResponse {
+        entries: entries.into_iter().map(WalEntryView::from).collect(),
+        has_more,
+    }))
+}
+
+/// Newest entries first; `has_more` means older entries remain.
+async fn tail_wal(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<WalTailQuery>,
+) -> GatewayResult<Json<WalResponse>> {
+    let limit = q.limit.unwrap_or(DEFAULT_WAL_LIMIT).clamp(1, MAX_WAL_LIMIT);
+    let (entries, has_more) = state
+        .storage
+        .tail_wal(&q.tenant, &session_id, q.before, limit)
+        .await?;
+
+    Ok(Json(WalResponse {
+        entries: entries.into_iter().map(WalEntryView::from).collect(),
+        has_more,
+    }))
+}
+
+async fn list_checkpoints(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<Vec<CheckpointInfo>>> {
+    Ok(Json(
+        state.storage.list_checkpoints(&q.tenant, &session_id).await?,
+    ))
+}
+
+async fn download_checkpoint(
+    State(state): State<GatewayState>,
+    Path((session_id, position)): Path<(String, u64)>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Response> {
+    let (data, position) = state
+        .storage
+        .load_checkpoint(&q.tenant, &session_id, position)
+        .await?
+        .ok_or_else(|| {
+            StorageError::NotFound(format!("checkpoint {} of session {}", position, session_id))
+        })?;
+    Ok(docx_response(
+        data,
+        &format!("{}-checkpoint-{}.docx", session_id, position),
+    ))
+}
+
+async fn sync_status(
+    State(state): State<GatewayState>,
+    Path(session_id): Path<String>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<SyncStatus>> {
+    state
+        .sync
+        .get_sync_status(&q.tenant, &session_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| StorageError::NotFound(format!("source for session {}", session_id)).into())
+}
+
+async fn list_sources(
+    State(state): State<GatewayState>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<Vec<SyncStatus>>> {
+    Ok(Json(state.sync.list_sources(&q.tenant).await?))
+}
+
+async fn list_pending_syncs(
+    State(state): State<GatewayState>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<Vec<PendingSync>>> {
+    Ok(Json(state.sync.list_pending_syncs(&q.tenant).await?))
+}
+
+async fn list_recent_files(
+    State(state): State<GatewayState>,
+    Query(q): Query<RecentQuery>,
+) -> GatewayResult<Json<Vec<RecentFile>>> {
+    let mut files = state.sync.list_recent_files(&q.tenant).await?;
+    if q.favorites_only {
+        files.retain(|f| f.favorite);
+    }
+    Ok(Json(files))
+}
+
+async fn list_connections(
+    State(state): State<GatewayState>,
+    Query(q): Query<TenantQuery>,
+) -> GatewayResult<Json<Vec<ConnectionView>>> {
+    let connections = state
+        .browse
+        .list_connections(&q.tenant)
+        .await?
+        .into_iter()
+        .map(|c| ConnectionView {
+            connection_id: c.connection_id,
+            source_type: c.source_type,
+            display_name: c.display_name,
+            provider_account_id: c.provider_account_id,
+            backend: c.backend,
+        })
+        .collect();
+    Ok(Json(connections))
+}
+
+async fn list_files(
+    State(state): State<GatewayState>,
+    Query(q): Query<FilesQuery>,
+) -> GatewayResult<Json<FileListResponse>> {
+    let result = state
+        .browse
+        .list_files(
+            &q.tenant,
+            &q.connection_id,
+            &q.path,
+            q.page_token.as_deref().filter(|t| !t.is_empty()),
+            q.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await?;
+
+    Ok(Json(result.into()))
+}
+
+async fn search_files(
+    State(state): State<GatewayState>,
+    Query(q): Query<SearchQuery>,
+) -> GatewayResult<Json<FileListResponse>> {
+    let query = FileSearchQuery {
+        query: q.q,
+        path: q.path,
+        recursive: q.recursive,
+        mime_filter: q.mime.filter(|m| !m.is_empty()),
+        page_token: q.page_token.filter(|t| !t.is_empty()),
+        page_size: q.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+    };
+
+    let result = state
+        .browse
+        .search_files(&q.tenant, &q.connection_id, &query)
+        .await?;
+    Ok(Json(result.into()))
+}
+
+impl From<FileListResult> for FileListResponse {
+    fn from(result: FileListResult) -> Self {
+        let files = result
+            .files
+            .into_iter()
+            .map(|f| FileView {
+                name: f.name,
+                path: f.path,
+                file_id: f.file_id,
+                is_folder: f.is_folder,
+                size_bytes: f.size_bytes,
+                modified_at: f.modified_at,
+                mime_type: f.mime_type,
+            })
+            .collect();
+
+        Self {
+            files,
+            next_page_token: result.next_page_token,
+        }
+    }
+}
+
+/// Wrap DOCX bytes in an attachment response.
+fn docx_response(data: Vec<u8>, filename: &str) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, DOCX_CONTENT_TYPE.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename.replace('"', "")),
+            ),
+        ],
+        data,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::create_backends;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    fn setup() -> (TempDir, GatewayState) {
+        let temp_dir = TempDir::new().unwrap();
+        let (storage, _, sync, _, browse) = create_backends(temp_dir.path());
+        (temp_dir, GatewayState { storage, sync, browse })
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_sessions_and_wal() {
+        let (_dir, state) = setup();
+        state
+            .storage
+            .save_session("t1", "s1", b"PK docx")
+            .await
+            .unwrap();
+        state
+            .storage
+            .append_wal(
+                "t1",
+                "s1",
+                &[docx_storage_core::WalEntry {
+                    position: 0,
+                    operation: "add".to_string(),
+                    path: "/body/children/0".to_string(),
+                    patch_json: br#"{"version":1,"patches":"[{\"op\":\"add\"}]","timestamp":"2026-01-01T00:00:00Z"}"#.to_vec(),
+                    timestamp: chrono::Utc::now(),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let (status, body) = get_json(router(state.clone()), "/api/sessions?tenant=t1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["session_id"], "s1");
+
+        let (status, body) = get_json(router(state.clone()), "/api/sessions/s1/wal?tenant=t1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["entries"][0]["patch"]["patches"], r#"[{"op":"add"}]"#);
+        assert_eq!(body["entries"][0]["operation"], "Patch");
+        assert_eq!(body["has_more"], false);
+
+        let (status, body) =
+            get_json(router(state.clone()), "/api/sessions/s1/wal/tail?tenant=t1&limit=5").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["entries"][0]["position"], 1);
+        assert_eq!(body["has_more"], false);
+
+        // Other tenants don't see the session
+        let (_, body) = get_json(router(state), "/api/sessions?tenant=t2").await;
+        assert_eq!(body.as_array().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_health() {
+        let (_dir, state) = setup();
+        state
+            .storage
+            .save_session("t1", "s1", b"PK\x03\x04 docx")
+            .await
+            .unwrap();
+        state
+            .storage
+            .append_wal(
+                "t1",
+                "s1",
+                &[docx_storage_core::WalEntry {
+                    position: 0,
+                    operation: "add".to_string(),
+                    path: "/body/children/0".to_string(),
+                    patch_json: br#"{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:00Z"}"#.to_vec(),
+                    timestamp: chrono::Utc::now(),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let (status, body) =
+            get_json(router(state.clone()), "/api/sessions/s1/health?tenant=t1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["wal_count"], 1);
+        assert_eq!(body["wal_since_checkpoint"], 1);
+        assert_eq!(body["size_bytes"], 9);
+        assert!(body["sync"].is_null());
+        // Saved without going through the index
+        assert_eq!(body["problems"][0], "the session is missing from the index");
+
+        let (status, _) = get_json(router(state), "/api/sessions/s2/health?tenant=t1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_missing_session_is_404() {
+        let (_dir, state) = setup();
+        let (status, body) = get_json(router(state), "/api/sessions/nope?tenant=t1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_document_download() {
+        let (_dir, state) = setup();
+        state
+            .storage
+            .save_session("t1", "s1", b"PK docx")
+            .await
+            .unwrap();
+
+        let response = router(state)
+            .oneshot(
+                Request::get("/api/sessions/s1/document?tenant=t1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            DOCX_CONTENT_TYPE
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"PK docx");
+    }
+}
@@ -0,0 +1,350 @@
+//! Inbox folder ("hot directory") ingestion.
+//!
+//! Every DOCX file dropped into the inbox folder is opened as a session of
+//! the inbox tenant, synced back to the dropped file, and announced with a
+//! `Created` event on the tenant's external change queue, so a client
+//! watching all of its sessions (`WatchChanges` without session IDs) picks it
+//! up. Desktop users integrate by drag-and-drop instead of explicit opens.
+//!
+//! The folder is scanned on an interval. A file is ingested once its size and
+//! modification time hold still between two scans, so copies in progress are
+//! left alone. Ingested files are recorded per tenant in `inbox.json` and
+//! never ingested twice; once a file leaves the folder, dropping it again
+//! opens a new session.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use docx_storage_core::{
+    tenant_dir, DurableChangeQueue, ExternalChangeEvent, ExternalChangeType, SourceDescriptor,
+    SourceMetadata, SourceType, StorageError, SyncBackend,
+};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::service::StorageServiceImpl;
+
+/// Size and modification time of a file, compared between scans.
+type Stamp = (u64, SystemTime);
+
+/// A file of the inbox folder opened as a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestedFile {
+    pub path: PathBuf,
+    pub session_id: String,
+}
+
+#[derive(Debug, Default)]
+struct InboxState {
+    /// Whether `ingested` was read from the ledger
+    loaded: bool,
+    /// Ingested files still in the folder: path -> session ID
+    ingested: BTreeMap<String, String>,
+    /// Files seen on the last scan, not ingested yet
+    settling: HashMap<PathBuf, Stamp>,
+    /// Files that aren't DOCX packages, skipped until they change
+    rejected: HashMap<PathBuf, Stamp>,
+}
+
+/// Opens the DOCX files dropped into a folder as sessions.
+pub struct Inbox {
+    dir: PathBuf,
+    tenant_id: String,
+    ledger_path: PathBuf,
+    service: Arc<StorageServiceImpl>,
+    sync: Arc<dyn SyncBackend>,
+    changes: Arc<DurableChangeQueue>,
+    state: Mutex<InboxState>,
+}
+
+impl Inbox {
+    /// Watch `dir` (created if missing) for files to open as sessions of
+    /// `tenant_id`. The ledger of ingested files is kept in the tenant's
+    /// directory under `storage_dir`.
+    pub fn new(
+        dir: &Path,
+        tenant_id: &str,
+        storage_dir: &Path,
+        service: Arc<StorageServiceImpl>,
+        sync: Arc<dyn SyncBackend>,
+        changes: Arc<DurableChangeQueue>,
+    ) -> Result<Self, StorageError> {
+        let ledger_path = tenant_dir(storage_dir, tenant_id)?.join("inbox.json");
+        std::fs::create_dir_all(dir).map_err(|e| {
+            StorageError::Io(format!("Failed to create inbox {}: {}", dir.display(), e))
+        })?;
+        // Sources are synced back by absolute path
+        let dir = std::fs::canonicalize(dir).map_err(|e| {
+            StorageError::Io(format!("Failed to resolve inbox {}: {}", dir.display(), e))
+        })?;
+        Ok(Self {
+            dir,
+            tenant_id: tenant_id.to_string(),
+            ledger_path,
+            service,
+            sync,
+            changes,
+            state: Mutex::new(InboxState::default()),
+        })
+    }
+
+    /// Scan the folder once, opening the files that settled since the last
+    /// scan. Returns the files opened.
+    pub async fn scan(&self) -> Result<Vec<IngestedFile>, StorageError> {
+        let mut state = self.state.lock().await;
+        if !state.loaded {
+            state.ingested = self.load_ledger().await?;
+            state.loaded = true;
+        }
+
+        let files = self.list_files().await?;
+        let present: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+        let before = state.ingested.len();
+        state
+            .ingested
+            .retain(|path, _| present.contains(&PathBuf::from(path)));
+        let mut ledger_changed = state.ingested.len() != before;
+        state.settling.retain(|path, _| present.contains(path));
+        state.rejected.retain(|path, _| present.contains(path));
+
+        let mut opened = Vec::new();
+        for (path, stamp) in files {
+            let key = path.to_string_lossy().into_owned();
+            if state.ingested.contains_key(&key) || state.rejected.get(&path) == Some(&stamp) {
+                continue;
+            }
+            // Wait for the file to hold still for a whole interval
+            if state.settling.insert(path.clone(), stamp) != Some(stamp) {
+                continue;
+            }
+            state.settling.remove(&path);
+
+            match self.ingest(&path, stamp).await {
+                Ok(Some(session_id)) => {
+                    info!(
+                        "Opened {} from the inbox as session {}",
+                        path.display(),
+                        session_id
+                    );
+                    state.ingested.insert(key, session_id.clone());
+                    ledger_changed = true;
+                    opened.push(IngestedFile { path, session_id });
+                }
+                Ok(None) => {
+                    warn!("Skipping {} in the inbox: not a DOCX package", path.display());
+                    state.rejected.insert(path, stamp);
+                }
+                Err(e) => warn!("Failed to open {} from the inbox: {}", path.display(), e),
+            }
+        }
+
+        if ledger_changed {
+            self.save_ledger(&state.ingested).await?;
+        }
+        Ok(opened)
+    }
+
+    /// Scan every `interval`, in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.scan().await {
+                    warn!("Failed to scan the inbox {}: {}", self.dir.display(), e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
+    /// The `.docx` files of the folder, with their stamps. Hidden files and
+    /// Word's `~$` lock files are skipped.
+    async fn list_files(&self) -> Result<Vec<(PathBuf, Stamp)>, StorageError> {
+        let io_err =
+            |e: std::io::Error| StorageError::Io(format!("Failed to list {}: {}", self.dir.display(), e));
+        let mut entries = tokio::fs::read_dir(&self.dir).await.map_err(io_err)?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(io_err)? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.')
+                || name.starts_with("~$")
+                || !name.to_ascii_lowercase().ends_with(".docx")
+            {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), (metadata.len(), modified)));
+        }
+        Ok(files)
+    }
+
+    /// Open a file as a new session. Returns `None` if it isn't a DOCX package.
+    async fn ingest(&self, path: &Path, stamp: Stamp) -> Result<Option<String>, StorageError> {
+        let data = tokio::fs::read(path).await.map_err(|e| {
+            StorageError::Io(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        if !data.starts_with(b"PK\x03\x04") {
+            return Ok(None);
+        }
+
+        let session_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+        let display_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned());
+        self.service
+            .create_session(&self.tenant_id, &session_id, &data, display_name, None)
+            .await
+            .map_err(|status| StorageError::Internal(status.message().to_string()))?;
+
+        let source_path = path.to_string_lossy().into_owned();
+        self.sync
+            .register_source(
+                &self.tenant_id,
+                &session_id,
+                SourceDescriptor {
+                    source_type: SourceType::LocalFile,
+                    connection_id: None,
+                    path: source_path.clone(),
+                    file_id: None,
+                },
+                true,
+            )
+            .await?;
+
+        let modified_at = stamp
+            .1
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        self.changes
+            .enqueue(
+                &self.tenant_id,
+                ExternalChangeEvent {
+                    session_id: session_id.clone(),
+                    change_type: ExternalChangeType::Created,
+                    old_metadata: None,
+                    new_metadata: Some(SourceMetadata {
+                        size_bytes: stamp.0,
+                        modified_at,
+                        etag: None,
+                        version_id: None,
+                        content_hash: Some(Sha256::digest(&data).to_vec()),
+                    }),
+                    detected_at: chrono::Utc::now().timestamp(),
+                    new_uri: Some(source_path),
+                },
+            )
+            .await?;
+
+        Ok(Some(session_id))
+    }
+
+    async fn load_ledger(&self) -> Result<BTreeMap<String, String>, StorageError> {
+        match tokio::fs::read(&self.ledger_path).await {
+            Ok(json) => serde_json::from_slice(&json).map_err(|e| {
+                StorageError::Serialization(format!(
+                    "Failed to parse {}: {}",
+                    self.ledger_path.display(),
+                    e
+                ))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to read {}: {}",
+                self.ledger_path.display(),
+                e
+            ))),
+        }
+    }
+
+    async fn save_ledger(&self, ingested: &BTreeMap<String, String>) -> Result<(), StorageError> {
+        let json = serde_json::to_vec_pretty(ingested)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let io_err = |e: std::io::Error| {
+            StorageError::Io(format!("Failed to write {}: {}", self.ledger_path.display(), e))
+        };
+        if let Some(parent) = self.ledger_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_err)?;
+        }
+        let temp_path = self.ledger_path.with_extension("json.tmp");
+        tokio::fs::write(&temp_path, json).await.map_err(io_err)?;
+        tokio::fs::rename(&temp_path, &self.ledger_path)
+            .await
+            .map_err(io_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{create_backends, create_change_queue};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_dropped_files_become_synced_sessions_once() {
+        let storage_dir = TempDir::new().unwrap();
+        let inbox_dir = TempDir::new().unwrap();
+        let (storage, lock, sync, _, _) = create_backends(storage_dir.path());
+        let service = Arc::new(StorageServiceImpl::new(storage.clone(), lock));
+        let changes = create_change_queue(storage_dir.path());
+        let new_inbox = || {
+            Inbox::new(
+                inbox_dir.path(),
+                "t1",
+                storage_dir.path(),
+                service.clone(),
+                sync.clone(),
+                changes.clone(),
+            )
+            .unwrap()
+        };
+        let inbox = new_inbox();
+
+        let report = inbox_dir.path().join("Q3 report.docx");
+        std::fs::write(&report, b"PK\x03\x04 docx").unwrap();
+        std::fs::write(inbox_dir.path().join("notes.docx"), b"plain text").unwrap();
+        std::fs::write(inbox_dir.path().join("~$Q3 report.docx"), b"PK\x03\x04 lock").unwrap();
+
+        // Seen once, then opened when it held still
+        assert!(inbox.scan().await.unwrap().is_empty());
+        let opened = inbox.scan().await.unwrap();
+        assert_eq!(opened.len(), 1);
+        let session_id = &opened[0].session_id;
+        assert_eq!(opened[0].path, std::fs::canonicalize(&report).unwrap());
+
+        assert_eq!(storage.load_session("t1", session_id).await.unwrap().unwrap(), b"PK\x03\x04 docx");
+        let index = storage.load_index("t1").await.unwrap().unwrap();
+        let entry = index.get(session_id).unwrap();
+        assert_eq!(entry.display_name.as_deref(), Some("Q3 report"));
+        assert_eq!(entry.source_path.as_deref(), opened[0].path.to_str());
+        assert!(sync.is_auto_sync_enabled("t1", session_id).await.unwrap());
+
+        let pending = changes.pending("t1", &[]).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event.change_type, ExternalChangeType::Created);
+        assert_eq!(&pending[0].event.session_id, session_id);
+
+        // Not opened again, even after a restart
+        assert!(inbox.scan().await.unwrap().is_empty());
+        let restarted = new_inbox();
+        assert!(restarted.scan().await.unwrap().is_empty());
+        assert!(restarted.scan().await.unwrap().is_empty());
+
+        // Dropping it again after it left opens a new session
+        std::fs::remove_file(&report).unwrap();
+        assert!(restarted.scan().await.unwrap().is_empty());
+        std::fs::write(&report, b"PK\x03\x04 docx v2").unwrap();
+        restarted.scan().await.unwrap();
+        let reopened = restarted.scan().await.unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_ne!(&reopened[0].session_id, session_id);
+    }
+}
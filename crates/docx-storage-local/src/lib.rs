@@ -1,9 +1,15 @@
 // Shared modules (used by both the standalone binary and the embedded staticlib)
 pub mod browse;
+pub mod browse_remote;
 pub mod config;
+pub mod dashboard;
 pub mod error;
+pub mod gateway;
+pub mod inbox;
 pub mod lock;
+pub mod notifier;
 pub mod service;
+pub mod service_operation;
 pub mod service_sync;
 pub mod service_watch;
 pub mod storage;
@@ -86,6 +92,31 @@ mod ffi {
         embedded::pipe_flush()
     }
 
+    /// Health of the embedded server as null-terminated UTF-8 JSON, e.g.
+    /// {"status":"serving","generation":1,"restarts":0,...}.
+    /// Returns the JSON length written (without the terminator), -1 if not
+    /// initialized or `buf` is too small.
+    #[no_mangle]
+    pub extern "C" fn docx_storage_health(buf: *mut u8, max_len: usize) -> i64 {
+        if buf.is_null() {
+            return -1;
+        }
+        let Some(health) = embedded::health() else {
+            return -1;
+        };
+        let json = match serde_json::to_vec(&health) {
+            Ok(json) => json,
+            Err(_) => return -1,
+        };
+        if json.len() >= max_len {
+            return -1;
+        }
+        let slice = unsafe { std::slice::from_raw_parts_mut(buf, max_len) };
+        slice[..json.len()].copy_from_slice(&json);
+        slice[json.len()] = 0;
+        json.len() as i64
+    }
+
     /// Shutdown the in-memory gRPC server and cleanup.
     /// Returns 0 on success.
     #[no_mangle]
@@ -6,7 +6,9 @@ use std::sync::Mutex;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use docx_storage_core::{LockAcquireResult, LockManager, StorageError};
+use docx_storage_core::{
+    tenant_dir, validate_session_id, LockAcquireResult, LockManager, StorageError,
+};
 use fs2::FileExt;
 use tracing::{debug, instrument};
 
@@ -37,18 +39,19 @@ impl FileLock {
     }
 
     /// Get the locks directory for a tenant.
-    fn locks_dir(&self, tenant_id: &str) -> PathBuf {
-        self.base_dir.join(tenant_id).join("locks")
+    fn locks_dir(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join("locks"))
     }
 
     /// Get the path to a lock file.
-    fn lock_path(&self, tenant_id: &str, resource_id: &str) -> PathBuf {
-        self.locks_dir(tenant_id).join(format!("{}.lock", resource_id))
+    fn lock_path(&self, tenant_id: &str, resource_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(resource_id)?;
+        Ok(self.locks_dir(tenant_id)?.join(format!("{}.lock", resource_id)))
     }
 
     /// Ensure the locks directory exists.
     fn ensure_locks_dir(&self, tenant_id: &str) -> Result<(), StorageError> {
-        let dir = self.locks_dir(tenant_id);
+        let dir = self.locks_dir(tenant_id)?;
         std::fs::create_dir_all(&dir).map_err(|e| {
             StorageError::Io(format!("Failed to create locks dir {}: {}", dir.display(), e))
         })?;
@@ -67,7 +70,7 @@ impl LockManager for FileLock {
         _ttl: Duration, // TTL not needed - OS handles cleanup on process exit
     ) -> Result<LockAcquireResult, StorageError> {
         self.ensure_locks_dir(tenant_id)?;
-        let path = self.lock_path(tenant_id, resource_id);
+        let path = self.lock_path(tenant_id, resource_id)?;
         let key = (tenant_id.to_string(), resource_id.to_string());
 
         // Check if we already hold this lock
@@ -1,37 +1,47 @@
-use clap::Parser;
+use std::sync::Arc;
+
+use docx_storage_core::{
+    AggregateBrowsableBackend, BrowsableBackend, CorrelationLayer, DeadlineLayer, ErasureSigner, HistorySigner,
+    OperationRegistry, TenantLimitLayer, TenantLimiter, UploadSpool,
+};
 use tokio::signal;
 use tokio::sync::watch as tokio_watch;
 use tonic::transport::Server;
 use tonic_reflection::server::Builder as ReflectionBuilder;
 use tracing::info;
-use tracing_subscriber::EnvFilter;
 
 #[cfg(unix)]
 use tokio::net::UnixListener;
 
+use docx_storage_local::browse_remote::RemoteBrowsableBackend;
 use docx_storage_local::config::{Config, Transport};
+use docx_storage_local::dashboard;
+use docx_storage_local::gateway::{self, GatewayState};
+use docx_storage_local::inbox::Inbox;
+use docx_storage_local::notifier::Notifier;
 use docx_storage_local::service::proto::storage_service_server::StorageServiceServer;
 use docx_storage_local::service::proto::source_sync_service_server::SourceSyncServiceServer;
 use docx_storage_local::service::proto::external_watch_service_server::ExternalWatchServiceServer;
+use docx_storage_local::service::proto::operation_service_server::OperationServiceServer;
 use docx_storage_local::service::StorageServiceImpl;
+use docx_storage_local::service_operation::{OperationServiceImpl, OPERATION_RETENTION};
 use docx_storage_local::service_sync::SourceSyncServiceImpl;
 use docx_storage_local::service_watch::ExternalWatchServiceImpl;
 use docx_storage_local::FILE_DESCRIPTOR_SET;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let (config, config_source) = docx_storage_core::load_config::<Config>();
 
-    let config = Config::parse();
+    // Initialize logging
+    docx_storage_core::init_tracing(config.log_format);
 
     info!("Starting docx-storage-local server");
     info!("  Transport: {}", config.transport);
     info!("  Backend: {}", config.storage_backend);
+    if let Some(path) = config_source.path() {
+        info!("  Config file: {}", path.display());
+    }
     if let Some(ppid) = config.parent_pid {
         info!("  Parent PID: {} (will exit when parent dies)", ppid);
     }
@@ -39,13 +49,105 @@ async fn main() -> anyhow::Result<()> {
     // Create storage backends via shared helper
     let dir = config.effective_local_storage_dir();
     info!("  Local storage dir: {}", dir.display());
+    if config.local_mode {
+        info!("  Mode: local (single tenant)");
+        docx_storage_local::server::provision_local_tenant(&dir)?;
+    }
+    let storage = docx_storage_local::server::create_storage(&config)?;
     let (storage, lock_manager, sync_backend, watch_backend, browse_backend) =
-        docx_storage_local::server::create_backends(&dir);
+        docx_storage_local::server::create_backends_with_storage(&dir, storage);
+
+    // Aggregate connections from other storage servers into one picker
+    let browse_backend: Arc<dyn BrowsableBackend> = if config.browse_upstreams.is_empty() {
+        browse_backend
+    } else {
+        let mut backends = vec![("local".to_string(), browse_backend)];
+        for (name, url) in &config.browse_upstreams {
+            info!("  Browse upstream: {} -> {}", name, url);
+            let remote: Arc<dyn BrowsableBackend> = Arc::new(RemoteBrowsableBackend::new(url)?);
+            backends.push((name.clone(), remote));
+        }
+        Arc::new(AggregateBrowsableBackend::new(backends))
+    };
+
+    let gateway_state = GatewayState {
+        storage: storage.clone(),
+        sync: sync_backend.clone(),
+        browse: browse_backend.clone(),
+    };
+
+    if config.media_dedup {
+        info!("  Media deduplication: enabled");
+        if config.media_sweep_interval_secs > 0 {
+            docx_storage_local::server::spawn_media_sweep(
+                storage.clone(),
+                std::time::Duration::from_secs(config.media_sweep_interval_secs),
+            );
+        }
+    }
 
     // Create gRPC services
-    let storage_svc = StorageServiceServer::new(StorageServiceImpl::new(storage, lock_manager));
-    let sync_svc = SourceSyncServiceServer::new(SourceSyncServiceImpl::new(sync_backend, browse_backend));
-    let watch_svc = ExternalWatchServiceServer::new(ExternalWatchServiceImpl::new(watch_backend));
+    let mut storage_service = StorageServiceImpl::new(storage, lock_manager)
+        .with_sync_backend(sync_backend.clone())
+        .with_checkpoint_policy(config.checkpoint_policy())
+        .with_corpus_export_rate(config.corpus_export_sessions_per_sec);
+    if let Some(key) = &config.erasure_key {
+        info!("  Tenant erasure: enabled");
+        storage_service = storage_service.with_erasure_signer(ErasureSigner::new(key.as_bytes())?);
+    }
+    if let Some(key) = &config.history_key {
+        info!("  History chain: enabled");
+        storage_service = storage_service.with_history_signer(HistorySigner::new(key.as_bytes())?);
+    }
+    let upload_spool_dir = config.effective_upload_spool_dir();
+    info!("  Upload spool: {}", upload_spool_dir.display());
+    storage_service = storage_service.with_upload_spool(UploadSpool::new(
+        upload_spool_dir,
+        std::time::Duration::from_secs(config.upload_ttl_secs),
+    ));
+    let storage_service = Arc::new(storage_service);
+    if config.purge_interval_secs > 0 {
+        info!("  Ephemeral session purge: every {}s", config.purge_interval_secs);
+        docx_storage_local::server::spawn_expiry_purge(
+            storage_service.clone(),
+            std::time::Duration::from_secs(config.purge_interval_secs),
+        );
+    }
+    if config.index_rebuild_interval_secs > 0 {
+        info!("  Index rebuild: every {}s", config.index_rebuild_interval_secs);
+        docx_storage_local::server::spawn_index_rebuild(
+            storage_service.clone(),
+            std::time::Duration::from_secs(config.index_rebuild_interval_secs),
+        );
+    }
+    let change_queue = docx_storage_local::server::create_change_queue(&dir);
+    if let Some(inbox_dir) = &config.inbox_dir {
+        info!("  Inbox: {} (every {}s)", inbox_dir.display(), config.inbox_interval_secs);
+        Arc::new(Inbox::new(
+            inbox_dir,
+            &config.inbox_tenant,
+            &dir,
+            storage_service.clone(),
+            sync_backend.clone(),
+            change_queue.clone(),
+        )?)
+        .spawn(std::time::Duration::from_secs(config.inbox_interval_secs));
+    }
+    let storage_svc = StorageServiceServer::from_arc(storage_service);
+    let mut sync_service = SourceSyncServiceImpl::new(sync_backend, browse_backend);
+    let mut watch_service = ExternalWatchServiceImpl::new(watch_backend, change_queue);
+    if config.notify_digest_interval_secs > 0 {
+        info!("  Change digests: every {}s", config.notify_digest_interval_secs);
+        let notifier = Arc::new(Notifier::new(config.notifier_settings()?));
+        sync_service = sync_service.with_notifier(notifier.clone());
+        watch_service = watch_service.with_notifier(notifier.clone());
+        notifier.spawn(std::time::Duration::from_secs(config.notify_digest_interval_secs));
+    }
+    let sync_svc = SourceSyncServiceServer::new(sync_service);
+    let watch_svc = ExternalWatchServiceServer::new(watch_service);
+    let operation_svc = OperationServiceServer::new(OperationServiceImpl::new(Arc::new(
+        OperationRegistry::new(OPERATION_RETENTION),
+    )));
 
     // Set up parent death signal using OS-native mechanisms
     setup_parent_death_signal(config.parent_pid);
@@ -53,6 +155,30 @@ async fn main() -> anyhow::Result<()> {
     // Create shutdown signal (watches for Ctrl+C and SIGTERM)
     // Parent death is handled by OS-native signal delivery (prctl/kqueue)
     let mut shutdown_rx = create_shutdown_signal();
+
+    // Optional HTTP/JSON gateway and web dashboard on their own port
+    if let Some(gateway_port) = config.gateway_port {
+        let addr: std::net::SocketAddr = format!("{}:{}", config.host, gateway_port).parse()?;
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        info!("JSON gateway listening on http://{}", addr);
+        info!("  Dashboard: http://{}/dashboard/", addr);
+
+        let app = gateway::router(gateway_state)
+            .merge(dashboard::router())
+            .layer(config.cors_layer());
+        let mut gateway_shutdown = shutdown_rx.clone();
+        tokio::spawn(async move {
+            let result = axum::serve(listener, app)
+                .with_graceful_shutdown(async move {
+                    let _ = gateway_shutdown.wait_for(|&v| v).await;
+                })
+                .await;
+            if let Err(e) = result {
+                tracing::error!("JSON gateway failed: {}", e);
+            }
+        });
+    }
+
     let shutdown_future = async move {
         let _ = shutdown_rx.wait_for(|&v| v).await;
     };
@@ -62,17 +188,37 @@ async fn main() -> anyhow::Result<()> {
         .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
         .build_v1()?;
 
+    let tenant_limits = config.tenant_limits();
+    if tenant_limits.max_in_flight > 0 {
+        info!(
+            "  Tenant limits: {} requests in flight, {} queued",
+            tenant_limits.max_in_flight, tenant_limits.max_queued
+        );
+    }
+    let tenant_limiter = Arc::new(TenantLimiter::new(tenant_limits));
+
     // Start server based on transport
     match config.transport {
         Transport::Tcp => {
             let addr = format!("{}:{}", config.host, config.port).parse()?;
             info!("Listening on tcp://{}", addr);
+            if config.grpc_web {
+                info!("  gRPC-Web: enabled");
+            }
 
+            // gRPC-Web requests arrive over HTTP/1.1; native gRPC clients are unaffected.
             Server::builder()
+                .accept_http1(config.grpc_web)
+                .layer(config.cors_layer())
+                .layer(tonic_web::GrpcWebLayer::new())
+                .layer(CorrelationLayer)
+                .layer(DeadlineLayer)
+                .layer(TenantLimitLayer::new(tenant_limiter.clone()))
                 .add_service(reflection_svc)
                 .add_service(storage_svc)
                 .add_service(sync_svc)
                 .add_service(watch_svc)
+                .add_service(operation_svc)
                 .serve_with_shutdown(addr, shutdown_future)
                 .await?;
         }
@@ -96,10 +242,14 @@ async fn main() -> anyhow::Result<()> {
             let uds_stream = tokio_stream::wrappers::UnixListenerStream::new(uds);
 
             Server::builder()
+                .layer(CorrelationLayer)
+                .layer(DeadlineLayer)
+                .layer(TenantLimitLayer::new(tenant_limiter.clone()))
                 .add_service(reflection_svc)
                 .add_service(storage_svc)
                 .add_service(sync_svc)
                 .add_service(watch_svc)
+                .add_service(operation_svc)
                 .serve_with_incoming_shutdown(uds_stream, shutdown_future)
                 .await?;
 
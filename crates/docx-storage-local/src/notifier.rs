@@ -0,0 +1,278 @@
+//! Digests of external changes and syncs, sent to Slack and by email.
+//!
+//! The watch and sync services record what happens to each tenant's
+//! sources; every interval, the [`Notifier`] sends each tenant with activity
+//! one digest, to its Slack incoming webhooks and email recipients. Document
+//! owners learn that a collaborator changed a synced source, or that saves
+//! keep failing, without polling from an MCP client.
+//!
+//! Email goes through an HTTP send API: the endpoint receives
+//! `{"from": ..., "to": [...], "subject": ..., "text": ...}` as a JSON POST,
+//! the shape Resend-style APIs (and the .NET server's `http` email provider)
+//! accept.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use docx_storage_core::{Activity, ActivityDigests, TenantDigest};
+use reqwest::Client as HttpClient;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+/// Timeout of each webhook or email API call.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The email API digests are sent through.
+#[derive(Debug, Clone)]
+pub struct DigestEmail {
+    pub endpoint: String,
+    /// Bearer token sent to the endpoint, if any
+    pub api_key: Option<String>,
+    pub from: String,
+}
+
+/// Where one tenant's digests go, instead of the default destinations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DigestRoute {
+    #[serde(default)]
+    pub slack_webhooks: Vec<String>,
+    #[serde(default)]
+    pub email_to: Vec<String>,
+}
+
+/// Destinations of the digests.
+#[derive(Debug, Clone, Default)]
+pub struct NotifierSettings {
+    /// Destinations of tenants without a route of their own
+    pub default_route: DigestRoute,
+    /// Per-tenant destinations, by tenant ID
+    pub tenants: HashMap<String, DigestRoute>,
+    /// Needed for routes with email recipients
+    pub email: Option<DigestEmail>,
+}
+
+impl NotifierSettings {
+    /// Parse per-tenant routes: a JSON object keyed by tenant ID, e.g.
+    /// `{"acme": {"slack_webhooks": ["https://hooks.slack.com/..."], "email_to": ["legal@acme.com"]}}`.
+    pub fn parse_tenants(json: &str) -> anyhow::Result<HashMap<String, DigestRoute>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Read per-tenant routes from a JSON file (see [`parse_tenants`](Self::parse_tenants)).
+    pub fn load_tenants(path: &Path) -> anyhow::Result<HashMap<String, DigestRoute>> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("reading {}: {}", path.display(), e))?;
+        Self::parse_tenants(&json)
+    }
+
+    /// Destinations of a tenant's digests.
+    pub fn route(&self, tenant_id: &str) -> &DigestRoute {
+        self.tenants.get(tenant_id).unwrap_or(&self.default_route)
+    }
+}
+
+/// Collects activity per tenant and sends the digests.
+pub struct Notifier {
+    settings: NotifierSettings,
+    digests: Mutex<ActivityDigests>,
+    client: HttpClient,
+}
+
+impl Notifier {
+    pub fn new(settings: NotifierSettings) -> Self {
+        Self {
+            settings,
+            digests: Mutex::new(ActivityDigests::default()),
+            client: HttpClient::new(),
+        }
+    }
+
+    /// Record activity on a session's source, for the next digest.
+    pub fn record(&self, tenant_id: &str, session_id: &str, activity: Activity) {
+        // Tenants nobody is notified for aren't tracked
+        let route = self.settings.route(tenant_id);
+        if route.slack_webhooks.is_empty() && route.email_to.is_empty() {
+            return;
+        }
+        self.digests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(tenant_id, session_id, activity, Utc::now());
+    }
+
+    /// Send the digest of every tenant with activity since the last flush.
+    /// Returns the number of digests sent to at least one destination.
+    pub async fn flush(&self) -> usize {
+        let digests = self
+            .digests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain();
+
+        let mut sent = 0;
+        for digest in &digests {
+            if self.send(digest).await {
+                sent += 1;
+            }
+        }
+        sent
+    }
+
+    /// Flush every `interval`, in the background.
+    pub fn spawn(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let sent = self.flush().await;
+                if sent > 0 {
+                    info!("Sent {} change digests", sent);
+                }
+            }
+        });
+    }
+
+    async fn send(&self, digest: &TenantDigest) -> bool {
+        let route = self.settings.route(&digest.tenant_id);
+        let mut delivered = false;
+
+        for url in &route.slack_webhooks {
+            let payload = json!({
+                "text": format!("*{}* ({})\n{}", digest.subject(), digest.tenant_id, digest.text()),
+            });
+            delivered |= self.post(url, None, &payload, &digest.tenant_id).await;
+        }
+
+        if !route.email_to.is_empty() {
+            match &self.settings.email {
+                Some(email) => {
+                    let payload = json!({
+                        "from": email.from,
+                        "to": route.email_to,
+                        "subject": format!("docx-mcp: {}", digest.subject()),
+                        "text": digest.text(),
+                    });
+                    delivered |= self
+                        .post(&email.endpoint, email.api_key.as_deref(), &payload, &digest.tenant_id)
+                        .await;
+                }
+                None => warn!(
+                    "Change digest of tenant {} has email recipients but no email API is configured",
+                    digest.tenant_id
+                ),
+            }
+        }
+
+        debug!(
+            "Change digest of tenant {}: {} sessions, delivered: {}",
+            digest.tenant_id,
+            digest.sessions.len(),
+            delivered
+        );
+        delivered
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        bearer: Option<&str>,
+        payload: &serde_json::Value,
+        tenant_id: &str,
+    ) -> bool {
+        let mut request = self.client.post(url).timeout(SEND_TIMEOUT).json(payload);
+        if let Some(token) = bearer {
+            request = request.bearer_auth(token);
+        }
+        match request.send().await {
+            Ok(resp) if !resp.status().is_success() => {
+                warn!(
+                    "Change digest of tenant {} was refused with {}",
+                    tenant_id,
+                    resp.status()
+                );
+                false
+            }
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Change digest of tenant {} failed: {}", tenant_id, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use docx_storage_core::ExternalChangeType;
+
+    #[test]
+    fn test_tenant_routes_override_the_default() {
+        let tenants = NotifierSettings::parse_tenants(
+            r#"{"acme": {"email_to": ["legal@acme.com"]}}"#,
+        )
+        .unwrap();
+        let settings = NotifierSettings {
+            default_route: DigestRoute {
+                slack_webhooks: vec!["https://hooks.example/default".into()],
+                email_to: vec![],
+            },
+            tenants,
+            email: None,
+        };
+
+        assert_eq!(settings.route("acme").email_to, ["legal@acme.com"]);
+        assert!(settings.route("acme").slack_webhooks.is_empty());
+        assert_eq!(settings.route("other").slack_webhooks.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_posts_one_digest_per_tenant() {
+        let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let sink = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let sink = sink.clone();
+                async move {
+                    sink.lock().unwrap().push(body);
+                    "ok"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut tenants = HashMap::new();
+        tenants.insert(
+            "quiet".to_string(),
+            DigestRoute::default(),
+        );
+        let notifier = Notifier::new(NotifierSettings {
+            default_route: DigestRoute {
+                slack_webhooks: vec![url],
+                email_to: vec![],
+            },
+            tenants,
+            email: None,
+        });
+        notifier.record("acme", "contract", Activity::ExternalChange(ExternalChangeType::Modified));
+        notifier.record("acme", "contract", Activity::Synced);
+        notifier.record("quiet", "notes", Activity::Synced);
+
+        assert_eq!(notifier.flush().await, 1);
+        assert_eq!(notifier.flush().await, 0);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let text = received[0]["text"].as_str().unwrap();
+        assert!(text.starts_with("*1 document changed at its source* (acme)"));
+        assert!(text.contains("- contract: modified externally, 1 sync"));
+    }
+}
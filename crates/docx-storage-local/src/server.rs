@@ -1,28 +1,342 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::browse::LocalBrowsableBackend;
+use crate::config::Config;
 use crate::lock::{FileLock, LockManager};
+use crate::service::StorageServiceImpl;
 use crate::storage::{LocalStorage, StorageBackend};
 use crate::sync::LocalFileSyncBackend;
-use crate::watch::NotifyWatchBackend;
-use docx_storage_core::{BrowsableBackend, SyncBackend, WatchBackend};
+use crate::watch::{FileChangeQueueStore, NotifyWatchBackend};
+use docx_storage_core::{
+    BrowsableBackend, DurableChangeQueue, FileSyncQueueStore, RetryingSyncBackend,
+    StorageBackendRegistry, StorageError, SyncBackend, SyncRetryPolicy, TenantRoutedStorage,
+    WatchBackend,
+};
+use tracing::{info, warn};
 
-/// Create all storage backends from a base directory.
-/// Shared between the standalone server binary and the embedded staticlib.
-pub fn create_backends(
-    storage_dir: &Path,
-) -> (
+/// How often queued syncs are checked for a due retry.
+const SYNC_RETRY_TICK: Duration = Duration::from_secs(5);
+
+/// How often expired ephemeral sessions are purged, unless configured.
+pub const DEFAULT_PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// All backends served by the local server: storage, lock, sync, watch, browse.
+pub type Backends = (
     Arc<dyn StorageBackend>,
     Arc<dyn LockManager>,
     Arc<dyn SyncBackend>,
     Arc<dyn WatchBackend>,
     Arc<dyn BrowsableBackend>,
-) {
-    let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(storage_dir));
+);
+
+/// Create all storage backends from a base directory.
+/// Shared between the standalone server binary and the embedded staticlib.
+///
+/// Must be called within a tokio runtime: the sync retry worker is spawned here.
+pub fn create_backends(storage_dir: &Path) -> Backends {
+    create_backends_with_storage(storage_dir, Arc::new(LocalStorage::new(storage_dir)))
+}
+
+/// Like [`create_backends`], with sessions kept in the given storage backend.
+/// Locks and queues still live under `storage_dir`.
+pub fn create_backends_with_storage(
+    storage_dir: &Path,
+    storage: Arc<dyn StorageBackend>,
+) -> Backends {
     let lock: Arc<dyn LockManager> = Arc::new(FileLock::new(storage_dir));
-    let sync: Arc<dyn SyncBackend> = Arc::new(LocalFileSyncBackend::new(storage.clone()));
+    let sync = Arc::new(RetryingSyncBackend::new(
+        Arc::new(LocalFileSyncBackend::new(storage.clone())),
+        Arc::new(FileSyncQueueStore::new(storage_dir)),
+        SyncRetryPolicy::default(),
+    ));
+    spawn_sync_retry(sync.clone());
+    let sync: Arc<dyn SyncBackend> = sync;
     let watch: Arc<dyn WatchBackend> = Arc::new(NotifyWatchBackend::new());
     let browse: Arc<dyn BrowsableBackend> = Arc::new(LocalBrowsableBackend::new());
     (storage, lock, sync, watch, browse)
 }
+
+/// Periodically retry syncs queued while their source was unreachable.
+fn spawn_sync_retry(sync: Arc<RetryingSyncBackend>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SYNC_RETRY_TICK).await;
+            match sync.retry_due().await {
+                Ok(0) => {}
+                Ok(completed) => info!("Completed {} queued syncs", completed),
+                Err(e) => warn!("Failed to retry queued syncs: {}", e),
+            }
+        }
+    });
+}
+
+/// Periodically purge expired ephemeral sessions.
+pub fn spawn_expiry_purge(service: Arc<StorageServiceImpl>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match service.purge_expired(chrono::Utc::now()).await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} expired sessions", purged),
+                Err(e) => warn!("Failed to purge expired sessions: {}", e.message()),
+            }
+        }
+    });
+}
+
+/// Periodically rebuild every tenant's index from its stored sessions.
+pub fn spawn_index_rebuild(service: Arc<StorageServiceImpl>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match service.rebuild_indexes().await {
+                Ok(0) => {}
+                Ok(repaired) => info!("Rebuilt {} out-of-sync indexes", repaired),
+                Err(e) => warn!("Failed to rebuild indexes: {}", e.message()),
+            }
+        }
+    });
+}
+
+/// Periodically delete the stored images that no session or checkpoint of
+/// their tenant references any more.
+pub fn spawn_media_sweep(storage: Arc<dyn StorageBackend>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let tenants = match storage.list_tenants().await {
+                Ok(tenants) => tenants,
+                Err(e) => {
+                    warn!("Failed to list tenants for the media sweep: {}", e);
+                    continue;
+                }
+            };
+            for tenant_id in tenants {
+                match storage.sweep_media(&tenant_id).await {
+                    Ok(0) => {}
+                    Ok(deleted) => {
+                        info!("Deleted {} unused media blobs of tenant '{}'", deleted, tenant_id)
+                    }
+                    Err(e) => warn!("Failed to sweep media of tenant '{}': {}", tenant_id, e),
+                }
+            }
+        }
+    });
+}
+
+/// Create the durable queue of external change events, stored beside the sessions.
+pub fn create_change_queue(storage_dir: &Path) -> Arc<DurableChangeQueue> {
+    Arc::new(DurableChangeQueue::new(Arc::new(FileChangeQueueStore::new(storage_dir))))
+}
+
+/// Storage backend kinds this server can construct.
+///
+/// - `local`: sessions on local disk; options `dir` (required) and
+///   `media_dedup=true` (see [`LocalStorage::with_media_dedup`]) and
+///   `history_chain=true` (see [`LocalStorage::with_history_chain`])
+pub fn storage_registry() -> StorageBackendRegistry {
+    let mut registry = StorageBackendRegistry::new();
+    registry.register("local", |options| {
+        let dir = options.get("dir").ok_or_else(|| {
+            StorageError::InvalidArgument("local backend requires a 'dir' option".to_string())
+        })?;
+        let media_dedup = options.get("media_dedup").is_some_and(|v| v == "true");
+        let history_chain = options.get("history_chain").is_some_and(|v| v == "true");
+        Ok(Arc::new(
+            LocalStorage::new(dir)
+                .with_media_dedup(media_dedup)
+                .with_history_chain(history_chain),
+        ))
+    });
+    registry
+}
+
+/// Build the session storage from configuration: the built-in `local`
+/// backend plus any declared with `--backend`, routed per tenant.
+pub fn create_storage(config: &Config) -> Result<Arc<dyn StorageBackend>, StorageError> {
+    if config.local_mode {
+        if !config.backends.is_empty()
+            || !config.tenant_backends.is_empty()
+            || !config.browse_upstreams.is_empty()
+            || config.storage_backend != "local"
+        {
+            return Err(StorageError::InvalidArgument(
+                "local mode keeps everything in the local storage dir; remove --backend, \
+                 --tenant-backend, --browse-upstream and --storage-backend"
+                    .to_string(),
+            ));
+        }
+        return Ok(Arc::new(
+            LocalStorage::new(config.effective_local_storage_dir())
+                .with_media_dedup(config.media_dedup)
+                .with_history_chain(config.history_key.is_some()),
+        ));
+    }
+
+    let registry = storage_registry();
+
+    let mut backends: HashMap<String, Arc<dyn StorageBackend>> = HashMap::new();
+    backends.insert(
+        "local".to_string(),
+        Arc::new(
+            LocalStorage::new(config.effective_local_storage_dir())
+                .with_media_dedup(config.media_dedup)
+                .with_history_chain(config.history_key.is_some()),
+        ),
+    );
+    for spec in &config.backends {
+        info!("  Storage backend: {} ({})", spec.name, spec.kind);
+        backends.insert(spec.name.clone(), registry.create(&spec.kind, &spec.options)?);
+    }
+
+    let backend = |name: &str| {
+        backends.get(name).cloned().ok_or_else(|| {
+            StorageError::InvalidArgument(format!("Unknown storage backend name '{}'", name))
+        })
+    };
+
+    let default = backend(&config.storage_backend)?;
+    if config.tenant_backends.is_empty() {
+        return Ok(default);
+    }
+
+    let mut routed = TenantRoutedStorage::new(default);
+    for (tenant, name) in &config.tenant_backends {
+        info!("  Tenant route: {} -> {}", tenant, name);
+        routed = routed.route(tenant, backend(name)?);
+    }
+    Ok(Arc::new(routed))
+}
+
+/// Create the local (empty) tenant's sessions directory under `storage_dir`,
+/// so a fresh local-mode install starts with a tenant to list and save into.
+pub fn provision_local_tenant(storage_dir: &Path) -> Result<(), StorageError> {
+    let dir = storage_dir.join("sessions");
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        StorageError::Io(format!("Failed to provision {}: {}", dir.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_create_storage_routes_tenants() {
+        let local = TempDir::new().unwrap();
+        let archive = TempDir::new().unwrap();
+        let config = Config::try_parse_from([
+            "docx-storage-local".to_string(),
+            format!("--local-storage-dir={}", local.path().display()),
+            format!("--backend=archive=local?dir={}", archive.path().display()),
+            "--tenant-backend=acme=archive".to_string(),
+        ])
+        .unwrap();
+
+        let storage = create_storage(&config).unwrap();
+        storage.save_session("acme", "s1", b"archived").await.unwrap();
+        storage.save_session("other", "s2", b"local").await.unwrap();
+
+        let archived = LocalStorage::new(archive.path());
+        let default = LocalStorage::new(local.path());
+        assert!(archived.session_exists("acme", "s1").await.unwrap());
+        assert!(!default.session_exists("acme", "s1").await.unwrap());
+        assert!(default.session_exists("other", "s2").await.unwrap());
+    }
+
+    #[test]
+    fn test_create_storage_rejects_unknown_backend() {
+        let config = Config::try_parse_from([
+            "docx-storage-local",
+            "--backend=cold=tape",
+        ])
+        .unwrap();
+        assert!(matches!(
+            create_storage(&config),
+            Err(StorageError::InvalidArgument(_))
+        ));
+
+        let config = Config::try_parse_from([
+            "docx-storage-local",
+            "--tenant-backend=acme=missing",
+        ])
+        .unwrap();
+        assert!(matches!(
+            create_storage(&config),
+            Err(StorageError::InvalidArgument(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_mode_provisions_local_tenant() {
+        let local = TempDir::new().unwrap();
+        let dir = local.path().join("data");
+        let config = Config::try_parse_from([
+            "docx-storage-local".to_string(),
+            "--local-mode".to_string(),
+            format!("--local-storage-dir={}", dir.display()),
+        ])
+        .unwrap();
+
+        provision_local_tenant(&dir).unwrap();
+        assert!(dir.join("sessions").is_dir());
+        let storage = create_storage(&config).unwrap();
+        storage.save_session("", "s1", b"local").await.unwrap();
+        assert!(LocalStorage::new(&dir).session_exists("", "s1").await.unwrap());
+
+        let config = Config::try_parse_from([
+            "docx-storage-local",
+            "--local-mode",
+            "--tenant-backend=acme=local",
+        ])
+        .unwrap();
+        assert!(matches!(
+            create_storage(&config),
+            Err(StorageError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_file_layers_under_flags() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("storage.toml");
+        std::fs::write(
+            &path,
+            r#"
+# Archive tenants on a second backend
+port = 6000
+storage-backend = "archive"
+backend = ["archive=local?dir=/mnt/archive", "cold=local"]
+tenant_backend = ["acme=cold"]
+"#,
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let (config, source) = docx_storage_core::load_config_from::<Config, _>([
+            "docx-storage-local",
+            "--config",
+            path,
+            "--storage-backend=cold",
+        ])
+        .unwrap();
+        assert_eq!(source.path().unwrap().to_str(), Some(path));
+        assert_eq!(config.port, 6000);
+        assert_eq!(config.storage_backend, "cold");
+        assert_eq!(config.backends.len(), 2);
+        assert_eq!(config.tenant_backends, vec![("acme".to_string(), "cold".to_string())]);
+
+        std::fs::write(path, "port = 6000\nretention_days = 3\n").unwrap();
+        let err = docx_storage_core::load_config_from::<Config, _>([
+            "docx-storage-local",
+            &format!("--config={}", path),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("retention_days"));
+    }
+}
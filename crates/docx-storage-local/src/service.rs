@@ -2,10 +2,19 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use docx_storage_core::{
+    copy_session_data, create_sandbox, discard_sandbox, ensure_not_held, erase_tenant, export_corpus, feature,
+    prove_history, record_admin_action, sandbox_tenant_id, scan_sessions, session_health, sleep_before_retry,
+    validate_tenant_id, AdminAction, AdminLogEntry, Capabilities, CheckpointPolicy, ChunkStream, CorpusRecord, ErasureSigner, ErasureStep, HistorySigner, IndexRebuildReport,
+    LegalHold,
+    PinnedSnapshot, SessionHealth, SessionPageQuery, SnapshotRegistry, StorageError, SyncBackend, UploadProgress,
+    StorageReport, StoredFile, UploadSpool, CORPUS_FORMAT_JSONL, DEFAULT_ADMIN_LOG_LIMIT,
+    DEFAULT_CORPUS_SESSIONS_PER_SEC, PROTO_SCHEMA_VERSION, SYSTEM_ACTOR,
+};
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use crate::error::StorageResultExt;
 use crate::lock::LockManager;
@@ -22,12 +31,28 @@ use proto::*;
 /// Default chunk size for streaming: 256KB
 const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
 
+/// WAL entries returned by `TailWal` when the request sets no limit.
+const DEFAULT_TAIL_LIMIT: u64 = 100;
+
+/// WAL entries per `LoadSessionWithHistory` chunk.
+const HISTORY_ENTRIES_PER_CHUNK: usize = 256;
+
+/// Sessions one `AppendWalBatch` call may append to.
+const MAX_APPEND_BATCH_SESSIONS: usize = 256;
+
 /// Implementation of the StorageService gRPC service.
 pub struct StorageServiceImpl {
     storage: Arc<dyn StorageBackend>,
     lock_manager: Arc<dyn LockManager>,
     version: String,
     chunk_size: usize,
+    erasure_signer: Option<ErasureSigner>,
+    history_signer: Option<HistorySigner>,
+    uploads: Option<UploadSpool>,
+    checkpoint_policy: CheckpointPolicy,
+    sync: Option<Arc<dyn SyncBackend>>,
+    snapshots: SnapshotRegistry,
+    corpus_sessions_per_sec: u32,
 }
 
 impl StorageServiceImpl {
@@ -40,24 +65,617 @@ impl StorageServiceImpl {
             lock_manager,
             version: env!("CARGO_PKG_VERSION").to_string(),
             chunk_size: DEFAULT_CHUNK_SIZE,
+            erasure_signer: None,
+            history_signer: None,
+            uploads: None,
+            checkpoint_policy: CheckpointPolicy::default(),
+            sync: None,
+            snapshots: SnapshotRegistry::default(),
+            corpus_sessions_per_sec: DEFAULT_CORPUS_SESSIONS_PER_SEC,
+        }
+    }
+
+    /// Enable EraseTenant, signing tokens and reports with `signer`.
+    pub fn with_erasure_signer(mut self, signer: ErasureSigner) -> Self {
+        self.erasure_signer = Some(signer);
+        self
+    }
+
+    /// Enable GetHistoryProof, signing proofs with `signer`.
+    pub fn with_history_signer(mut self, signer: HistorySigner) -> Self {
+        self.history_signer = Some(signer);
+        self
+    }
+
+    /// Enable resumable SaveSession uploads, spooled to `spool`.
+    pub fn with_upload_spool(mut self, spool: UploadSpool) -> Self {
+        self.uploads = Some(spool);
+        self
+    }
+
+    /// Read at most `sessions_per_sec` sessions per second in ExportTenantCorpus
+    /// (0 = unpaced).
+    pub fn with_corpus_export_rate(mut self, sessions_per_sec: u32) -> Self {
+        self.corpus_sessions_per_sec = sessions_per_sec;
+        self
+    }
+
+    /// Tell clients when to checkpoint with `policy` instead of after every entry.
+    pub fn with_checkpoint_policy(mut self, policy: CheckpointPolicy) -> Self {
+        self.checkpoint_policy = policy;
+        self
+    }
+
+    /// Report the sync state of sessions in GetSessionHealth from `sync`.
+    pub fn with_sync_backend(mut self, sync: Arc<dyn SyncBackend>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// The upload spool, if resumable uploads are enabled.
+    fn uploads(&self) -> Result<&UploadSpool, Status> {
+        self.uploads
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("resumable uploads are not enabled"))
+    }
+
+    /// Spool the chunks of a resumable SaveSession upload, starting with
+    /// `first`, and store the upload once its last chunk is acknowledged.
+    /// Returns the chunk to resume from if the stream ends before that.
+    async fn save_session_resumable(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        first: SaveSessionChunk,
+        mut stream: Streaming<SaveSessionChunk>,
+    ) -> Result<SaveSessionResponse, Status> {
+        let uploads = self.uploads()?;
+        let upload_id = first.upload_id.clone();
+        let mut next = Some(Ok(first));
+        let mut progress = UploadProgress::default();
+
+        while let Some(chunk) = next {
+            let chunk = chunk?;
+            if chunk.upload_id != upload_id {
+                return Err(Status::invalid_argument(
+                    "every chunk of a resumable upload must carry its upload_id",
+                ));
+            }
+            let (sequence, crc32) = (chunk.sequence, chunk.crc32);
+            progress = uploads
+                .append(tenant_id, session_id, &upload_id, sequence, &chunk.data, crc32)
+                .await
+                .map_storage_err()?;
+
+            if chunk.is_last {
+                let chunks = uploads
+                    .read(tenant_id, session_id, &upload_id)
+                    .await
+                    .map_storage_err()?;
+                let written = self.storage
+                    .save_session_stream(tenant_id, session_id, chunks)
+                    .await
+                    .map_storage_err()?;
+                uploads.discard(tenant_id, session_id, &upload_id).await;
+                debug!(
+                    "Saved session {} for tenant {} from upload {} ({} bytes)",
+                    session_id, tenant_id, upload_id, written
+                );
+                return Ok(SaveSessionResponse {
+                    success: true,
+                    next_sequence: progress.next_sequence,
+                });
+            }
+            next = stream.next().await;
+        }
+
+        Ok(SaveSessionResponse {
+            success: false,
+            next_sequence: progress.next_sequence,
+        })
+    }
+
+    /// The snapshot a LoadSessionWithHistory request reads, checking the
+    /// request doesn't also ask for another session or position.
+    fn requested_snapshot(
+        &self,
+        tenant_id: &str,
+        req: &LoadSessionWithHistoryRequest,
+    ) -> Result<PinnedSnapshot, Status> {
+        if req.position.is_some() {
+            return Err(Status::invalid_argument(
+                "position can't be set when reading a snapshot",
+            ));
+        }
+        let snapshot = self
+            .snapshots
+            .get(tenant_id, &req.snapshot_id)
+            .map_storage_err()?;
+        if !req.session_id.is_empty() && req.session_id != snapshot.session_id {
+            return Err(Status::invalid_argument(format!(
+                "snapshot {} is of session {}, not {}",
+                req.snapshot_id, snapshot.session_id, req.session_id
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Split a session and its history into stream chunks: metadata with the
+    /// first bytes of the document, the rest of it, then the WAL entries.
+    fn history_chunks(
+        history: docx_storage_core::SessionWithHistory,
+        chunk_size: usize,
+    ) -> Vec<SessionHistoryChunk> {
+        let entries: Vec<WalEntry> = history
+            .entries
+            .into_iter()
+            .map(|e| WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp_unix: e.timestamp.timestamp(),
+            })
+            .collect();
+        let mut chunks: Vec<SessionHistoryChunk> = history
+            .document
+            .chunks(chunk_size)
+            .map(|data| SessionHistoryChunk {
+                data: data.to_vec(),
+                ..Default::default()
+            })
+            .chain(entries.chunks(HISTORY_ENTRIES_PER_CHUNK).map(|entries| {
+                SessionHistoryChunk {
+                    entries: entries.to_vec(),
+                    ..Default::default()
+                }
+            }))
+            .collect();
+        if chunks.is_empty() {
+            chunks.push(SessionHistoryChunk::default());
+        }
+
+        let first = &mut chunks[0];
+        first.found = true;
+        first.base_position = history.base_position;
+        first.target_position = history.target_position;
+        first.wal_count = history.wal_count;
+        first.source_path = history.source_path.unwrap_or_default();
+        first.total_size = history.document.len() as u64;
+        if let Some(last) = chunks.last_mut() {
+            last.is_last = true;
+        }
+        chunks
+    }
+
+    /// Append entries to one session's WAL, with whether the checkpoint
+    /// policy wants a checkpoint at the new position.
+    async fn append_session_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        entries: Vec<WalEntry>,
+    ) -> Result<AppendWalResponse, Status> {
+        let entries: Vec<crate::storage::WalEntry> = entries
+            .into_iter()
+            .map(|e| crate::storage::WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp: chrono::DateTime::from_timestamp(e.timestamp_unix, 0)
+                    .unwrap_or_else(chrono::Utc::now),
+            })
+            .collect();
+
+        let new_position = self
+            .storage
+            .append_wal(tenant_id, session_id, &entries)
+            .await
+            .map_storage_err()?;
+
+        let checkpoint = self
+            .checkpoint_policy
+            .check(
+                self.storage.as_ref(),
+                tenant_id,
+                session_id,
+                &entries,
+                new_position,
+                chrono::Utc::now(),
+            )
+            .await
+            .map_storage_err()?;
+        if let Some(reason) = &checkpoint {
+            debug!(
+                "Checkpoint due at {} for session {}: {}",
+                new_position, session_id, reason
+            );
         }
+
+        Ok(AppendWalResponse {
+            success: true,
+            new_position,
+            skip_checkpoint: checkpoint.is_none(),
+            checkpoint_reason: checkpoint.map(|r| r.to_string()).unwrap_or_default(),
+        })
     }
 
     /// Extract tenant_id from request context.
     /// Empty string is allowed for backward compatibility with legacy paths.
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
+    }
+
+    /// Who makes the request, for the admin log (empty = unknown).
+    fn get_actor(context: Option<&TenantContext>) -> &str {
+        context.map_or("", |c| c.actor.as_str())
+    }
+
+    /// An admin log entry for `action`, taken now by the request's actor.
+    fn admin_entry(action: AdminAction, context: Option<&TenantContext>) -> AdminLogEntry {
+        AdminLogEntry::new(action, Self::get_actor(context), chrono::Utc::now())
+    }
+
+    /// The page a ListSessions request asks for, if it asks for one.
+    fn session_page_query(req: &ListSessionsRequest) -> Option<SessionPageQuery> {
+        let given = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let query = SessionPageQuery {
+            id_prefix: given(&req.session_id_prefix),
+            search: given(&req.search),
+            page_token: given(&req.page_token),
+            page_size: req.page_size,
+        };
+        let paged = query.page_size > 0
+            || query.page_token.is_some()
+            || query.id_prefix.is_some()
+            || query.search.is_some();
+        paged.then_some(query)
+    }
+
+    /// A session of a paged listing, from its index entry alone.
+    fn paged_session_info(entry: docx_storage_core::SessionIndexEntry) -> SessionInfo {
+        SessionInfo {
+            session_id: entry.id,
+            source_path: entry.source_path.unwrap_or_default(),
+            created_at_unix: entry.created_at.timestamp(),
+            modified_at_unix: entry.last_modified_at.timestamp(),
+            size_bytes: 0,
+            expires_at_unix: entry.expires_at.map_or(0, |at| at.timestamp()),
+        }
+    }
+
+    /// Convert a library kind from the wire.
+    fn library_kind(kind: i32) -> Result<docx_storage_core::LibraryKind, Status> {
+        match LibraryKind::try_from(kind) {
+            Ok(LibraryKind::Template) => Ok(docx_storage_core::LibraryKind::Template),
+            Ok(LibraryKind::Snippet) => Ok(docx_storage_core::LibraryKind::Snippet),
+            Ok(LibraryKind::Glossary) => Ok(docx_storage_core::LibraryKind::Glossary),
+            Ok(LibraryKind::Profile) => Ok(docx_storage_core::LibraryKind::Profile),
+            Err(_) => Err(Status::invalid_argument(format!("unknown library kind {}", kind))),
+        }
+    }
+
+    /// Convert a tenant inventory to the wire.
+    fn inventory_to_proto(inventory: &docx_storage_core::TenantInventory) -> TenantInventory {
+        TenantInventory {
+            sessions: inventory.sessions,
+            wal_entries: inventory.wal_entries,
+            checkpoints: inventory.checkpoints,
+            templates: inventory.templates,
+            snippets: inventory.snippets,
+            glossaries: inventory.glossaries,
+            profiles: inventory.profiles,
+            has_index: inventory.has_index,
+            elsewhere: inventory.elsewhere.clone(),
+        }
+    }
+
+    /// Convert an index rebuild report to the wire.
+    fn storage_report_to_proto(report: StorageReport) -> GetStorageReportResponse {
+        let stored_file = |file: StoredFile| proto::StoredFile {
+            path: file.path,
+            bytes: file.bytes,
+            modified_at_unix: file.modified_at.map_or(0, |t| t.timestamp()),
+        };
+        GetStorageReportResponse {
+            total_bytes: report.total_bytes(),
+            leftover_bytes: report.leftover_bytes(),
+            cleaned: report.cleaned,
+            usage: report
+                .usage
+                .into_iter()
+                .map(|u| StorageAreaUsage {
+                    area: u.area,
+                    files: u.files,
+                    bytes: u.bytes,
+                })
+                .collect(),
+            unindexed_sessions: report.unindexed_sessions,
+            orphaned_files: report.orphaned_files.into_iter().map(stored_file).collect(),
+            temp_files: report.temp_files.into_iter().map(stored_file).collect(),
+        }
+    }
+
+    /// Convert an admin log entry to the wire.
+    fn admin_log_entry_to_proto(entry: AdminLogEntry) -> proto::AdminLogEntry {
+        proto::AdminLogEntry {
+            position: entry.position,
+            action: entry.action.as_str().to_string(),
+            actor: entry.actor,
+            timestamp_unix: entry.timestamp.timestamp(),
+            session_id: entry.session_id.unwrap_or_default(),
+            detail: entry.detail,
+        }
+    }
+
+    /// What an index rebuild changed, for the admin log.
+    fn rebuild_detail(report: &IndexRebuildReport) -> String {
+        format!(
+            "added {}, corrected {}, removed {}",
+            report.added.len(),
+            report.corrected.len(),
+            report.removed.len()
+        )
+    }
+
+    fn rebuild_report_to_proto(report: IndexRebuildReport, dry_run: bool) -> RebuildIndexResponse {
+        RebuildIndexResponse {
+            applied: !dry_run && report.changed_index(),
+            added: report.added,
+            corrected: report.corrected,
+            orphans: report.orphans,
+            removed: report.removed,
+        }
+    }
+
+    /// Acquire the tenant's index lock, retrying briefly. Returns the holder ID
+    /// to pass to [`Self::release_index_lock`].
+    async fn acquire_index_lock(&self, tenant_id: &str) -> Result<String, Status> {
+        let holder_id = uuid::Uuid::new_v4().to_string();
+        let ttl = Duration::from_secs(30);
+
+        for i in 0..10 {
+            if i > 0 {
+                sleep_before_retry(Duration::from_millis(50 * i as u64))
+                    .await
+                    .map_storage_err()?;
+            }
+            let result = self.lock_manager.acquire(tenant_id, "index", &holder_id, ttl).await
+                .map_storage_err()?;
+            if result.acquired {
+                return Ok(holder_id);
+            }
+        }
+
+        Err(Status::unavailable("Could not acquire index lock"))
+    }
+
+    async fn release_index_lock(&self, tenant_id: &str, holder_id: &str) {
+        let _ = self.lock_manager.release(tenant_id, "index", holder_id).await;
+    }
+
+    /// Store `data` as a new session and add it to the tenant's index, named
+    /// `display_name`. Fails with ALREADY_EXISTS if the session exists.
+    pub async fn create_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        data: &[u8],
+        display_name: Option<String>,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), Status> {
+        if self.storage.session_exists(tenant_id, session_id).await.map_storage_err()? {
+            return Err(Status::already_exists(format!(
+                "Session {} already exists",
+                session_id
+            )));
+        }
+
+        self.storage
+            .save_session(tenant_id, session_id, data)
+            .await
+            .map_storage_err()?;
+
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+
+        let result = async {
+            let mut index = self.storage.load_index(tenant_id).await
+                .map_storage_err()?
+                .unwrap_or_default();
+
+            let now = chrono::Utc::now();
+            index.upsert(crate::storage::SessionIndexEntry {
+                id: session_id.to_string(),
+                source_path: None,
+                auto_sync: true,
+                created_at: now,
+                last_modified_at: now,
+                docx_file: Some(format!("{}.docx", session_id)),
+                wal_count: 0,
+                cursor_position: 0,
+                checkpoint_positions: vec![],
+                pending_external_change: false,
+                display_name,
+                alias: None,
+                legal_hold: None,
+                expires_at,
+            });
+            self.storage.save_index(tenant_id, &index).await.map_storage_err()
+        }.await;
+
+        self.release_index_lock(tenant_id, &holder_id).await;
+        result
+    }
+
+    /// Purge the ephemeral sessions of every tenant that expired at `now`.
+    /// Tenants that fail are logged and retried on the next purge. Returns the
+    /// number of sessions purged.
+    pub async fn purge_expired(&self, now: chrono::DateTime<chrono::Utc>) -> Result<usize, Status> {
+        let mut purged = 0;
+        for tenant_id in self.storage.list_tenants().await.map_storage_err()? {
+            match self.purge_tenant_expired(&tenant_id, now).await {
+                Ok(count) => purged += count,
+                Err(e) => warn!(tenant_id = %tenant_id, "Failed to purge expired sessions: {}", e),
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Delete a tenant's expired sessions under the index lock, so a legal
+    /// hold can't be placed midway: files first, then the index entries.
+    async fn purge_tenant_expired(
+        &self,
+        tenant_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, Status> {
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+        let result = async {
+            let Some(mut index) = self.storage.load_index(tenant_id).await.map_storage_err()? else {
+                return Ok(0);
+            };
+            let expired = index.expired(now);
+            if expired.is_empty() {
+                return Ok(0);
+            }
+            for session_id in &expired {
+                self.storage
+                    .delete_session(tenant_id, session_id)
+                    .await
+                    .map_storage_err()?;
+                index.remove(session_id);
+                info!(tenant_id = %tenant_id, session_id = %session_id, "Purged expired session");
+            }
+            self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                AdminLogEntry::new(AdminAction::RetentionRun, SYSTEM_ACTOR, now)
+                    .with_detail(format!("purged expired sessions: {}", expired.join(", "))),
+            )
+            .await;
+            Ok::<_, Status>(expired.len())
+        }
+        .await;
+        self.release_index_lock(tenant_id, &holder_id).await;
+        result
+    }
+
+    /// Rebuild the index of every tenant from its stored sessions, without
+    /// removing orphans. Tenants that fail are logged and retried on the next
+    /// run. Returns the number of tenants whose index was repaired.
+    pub async fn rebuild_indexes(&self) -> Result<usize, Status> {
+        let mut repaired = 0;
+        for tenant_id in self.storage.list_tenants().await.map_storage_err()? {
+            match self.rebuild_tenant_index(&tenant_id, false, false).await {
+                Ok(report) if !report.is_clean() => {
+                    repaired += 1;
+                    if report.changed_index() {
+                        record_admin_action(
+                            self.storage.as_ref(),
+                            &tenant_id,
+                            AdminLogEntry::new(AdminAction::RebuildIndex, SYSTEM_ACTOR, chrono::Utc::now())
+                                .with_detail(Self::rebuild_detail(&report)),
+                        )
+                        .await;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!(tenant_id = %tenant_id, "Failed to rebuild index: {}", e),
+            }
+        }
+        Ok(repaired)
+    }
+
+    /// Reconcile a tenant's index with its stored sessions, saving it unless
+    /// `dry_run`. Storage is scanned before taking the index lock, which a
+    /// large tenant's scan would outlive.
+    async fn rebuild_tenant_index(
+        &self,
+        tenant_id: &str,
+        dry_run: bool,
+        remove_orphans: bool,
+    ) -> Result<IndexRebuildReport, Status> {
+        let scanned_at = chrono::Utc::now();
+        let scanned = scan_sessions(self.storage.as_ref(), tenant_id)
+            .await
+            .map_storage_err()?;
+
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+        let result = async {
+            let mut index = self.storage.load_index(tenant_id).await
+                .map_storage_err()?
+                .unwrap_or_default();
+            let report = index.reconcile(&scanned, scanned_at, remove_orphans);
+            if !dry_run && report.changed_index() {
+                self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
+            }
+            Ok::<_, Status>(report)
+        }
+        .await;
+        self.release_index_lock(tenant_id, &holder_id).await;
+
+        let report = result?;
+        if !report.is_clean() {
+            info!(
+                tenant_id = %tenant_id,
+                dry_run,
+                added = ?report.added,
+                corrected = ?report.corrected,
+                orphans = ?report.orphans,
+                removed = ?report.removed,
+                "Index out of sync with stored sessions"
+            );
+        }
+        Ok(report)
     }
 }
 
 type StreamResult<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
 
+/// The data of an upload, from its first chunk (already read) up to the one
+/// marked last, streamed into storage as it arrives. `split` takes a chunk's
+/// data and `is_last` flag.
+fn upload_stream<T: Send + 'static>(
+    first: Vec<u8>,
+    first_is_last: bool,
+    rest: Streaming<T>,
+    split: fn(T) -> (Vec<u8>, bool),
+) -> ChunkStream<'static> {
+    Box::pin(futures::stream::unfold(
+        (Some(first), first_is_last, rest),
+        move |(first, done, mut rest)| async move {
+            if let Some(data) = first {
+                return Some((Ok(data), (None, done, rest)));
+            }
+            if done {
+                return None;
+            }
+            match rest.next().await? {
+                Ok(chunk) => {
+                    let (data, is_last) = split(chunk);
+                    Some((Ok(data), (None, is_last, rest)))
+                }
+                Err(status) => Some((
+                    Err(StorageError::Io(format!("upload failed: {}", status.message()))),
+                    (None, true, rest),
+                )),
+            }
+        },
+    ))
+}
+
 #[tonic::async_trait]
 impl StorageService for StorageServiceImpl {
     type LoadSessionStream = StreamResult<DataChunk>;
+    type LoadSessionWithHistoryStream = StreamResult<SessionHistoryChunk>;
     type LoadCheckpointStream = StreamResult<LoadCheckpointChunk>;
+    type LoadLibraryItemStream = StreamResult<DataChunk>;
+    type ExportTenantCorpusStream = StreamResult<CorpusChunk>;
 
     // =========================================================================
     // Session Operations (Streaming)
@@ -120,46 +738,146 @@ impl StorageService for StorageServiceImpl {
     }
 
     #[instrument(skip(self, request), level = "debug")]
-    async fn save_session(
+    async fn load_session_with_history(
         &self,
-        request: Request<Streaming<SaveSessionChunk>>,
-    ) -> Result<Response<SaveSessionResponse>, Status> {
-        let mut stream = request.into_inner();
+        request: Request<LoadSessionWithHistoryRequest>,
+    ) -> Result<Response<Self::LoadSessionWithHistoryStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-        let mut tenant_id: Option<String> = None;
-        let mut session_id: Option<String> = None;
-        let mut data = Vec::new();
+        let history = if req.snapshot_id.is_empty() {
+            // Read from a snapshot so a long load doesn't race concurrent WAL appends
+            self.storage
+                .load_session_snapshot(tenant_id, &req.session_id, req.position)
+                .await
+                .map_storage_err()?
+        } else {
+            let snapshot = self.requested_snapshot(tenant_id, &req)?;
+            Some(snapshot.history.as_ref().clone())
+        };
+        let chunks = match history {
+            Some(history) => Self::history_chunks(history, self.chunk_size),
+            None => vec![SessionHistoryChunk {
+                is_last: true,
+                ..Default::default()
+            }],
+        };
+        Ok(Response::new(Box::pin(tokio_stream::iter(
+            chunks.into_iter().map(Ok::<_, Status>),
+        ))))
+    }
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
+    #[instrument(skip(self, request), level = "debug")]
+    async fn open_snapshot(
+        &self,
+        request: Request<OpenSnapshotRequest>,
+    ) -> Result<Response<OpenSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-            // Extract metadata from first chunk
-            if tenant_id.is_none() {
-                tenant_id = chunk.context.map(|c| c.tenant_id);
-                session_id = Some(chunk.session_id);
-            }
+        let snapshot = self
+            .snapshots
+            .open(self.storage.as_ref(), tenant_id, &req.session_id, req.position)
+            .await
+            .map_storage_err()?;
+        let Some(snapshot) = snapshot else {
+            return Ok(Response::new(OpenSnapshotResponse::default()));
+        };
+        debug!(
+            "Opened snapshot {} of session {} at position {} for tenant {}",
+            snapshot.id, req.session_id, snapshot.history.target_position, tenant_id
+        );
 
-            data.extend(chunk.data);
+        Ok(Response::new(OpenSnapshotResponse {
+            found: true,
+            snapshot_id: snapshot.id,
+            position: snapshot.history.target_position,
+            expires_at_unix: snapshot.expires_at.timestamp(),
+        }))
+    }
 
-            if chunk.is_last {
-                break;
-            }
-        }
+    #[instrument(skip(self, request), level = "debug")]
+    async fn close_snapshot(
+        &self,
+        request: Request<CloseSnapshotRequest>,
+    ) -> Result<Response<CloseSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-        let tenant_id = tenant_id
+        let existed = self.snapshots.close(tenant_id, &req.snapshot_id);
+        Ok(Response::new(CloseSnapshotResponse { existed }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn save_session(
+        &self,
+        request: Request<Streaming<SaveSessionChunk>>,
+    ) -> Result<Response<SaveSessionResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        // Metadata comes with the first chunk; the data is written as it arrives
+        let first = stream
+            .next()
+            .await
+            .transpose()?
+            .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+
+        let tenant_id = first
+            .context
+            .as_ref()
+            .map(|c| c.tenant_id.clone())
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
-        let session_id = session_id
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let session_id = Some(first.session_id.clone())
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
 
-        debug!("Saving session {} for tenant {} ({} bytes)", session_id, tenant_id, data.len());
+        if !first.upload_id.is_empty() {
+            let response = self
+                .save_session_resumable(&tenant_id, &session_id, first, stream)
+                .await?;
+            return Ok(Response::new(response));
+        }
 
-        self.storage
-            .save_session(&tenant_id, &session_id, &data)
+        let (data, is_last) = (first.data, first.is_last);
+        let chunks = upload_stream(data, is_last, stream, |c| (c.data, c.is_last));
+        let written = self
+            .storage
+            .save_session_stream(&tenant_id, &session_id, chunks)
             .await
             .map_storage_err()?;
+        debug!("Saved session {} for tenant {} ({} bytes)", session_id, tenant_id, written);
+
+        Ok(Response::new(SaveSessionResponse {
+            success: true,
+            next_sequence: 0,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn resume_save_session(
+        &self,
+        request: Request<ResumeSaveSessionRequest>,
+    ) -> Result<Response<ResumeSaveSessionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let progress = self
+            .uploads()?
+            .progress(tenant_id, &req.session_id, &req.upload_id)
+            .await;
+        debug!(
+            "Upload {} of session {} for tenant {}: {:?}",
+            req.upload_id, req.session_id, tenant_id, progress
+        );
 
-        Ok(Response::new(SaveSessionResponse { success: true }))
+        let found = progress.is_some();
+        let progress = progress.unwrap_or_default();
+        Ok(Response::new(ResumeSaveSessionResponse {
+            found,
+            next_sequence: progress.next_sequence,
+            received_bytes: progress.received_bytes,
+        }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -170,15 +888,39 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        if let Some(query) = Self::session_page_query(&req) {
+            let page = self
+                .storage
+                .list_session_page(tenant_id, &query)
+                .await
+                .map_storage_err()?;
+            return Ok(Response::new(ListSessionsResponse {
+                sessions: page.entries.into_iter().map(Self::paged_session_info).collect(),
+                snapshot_age_ms: 0,
+                next_page_token: page.next_page_token.unwrap_or_default(),
+            }));
+        }
+
         let sessions = self
             .storage
             .list_sessions(tenant_id)
             .await
             .map_storage_err()?;
 
+        // Ephemeral sessions are marked with their expiry from the index
+        let index = self.storage
+            .load_index(tenant_id)
+            .await
+            .map_storage_err()?
+            .unwrap_or_default();
+
         let sessions = sessions
             .into_iter()
             .map(|s| SessionInfo {
+                expires_at_unix: index
+                    .get(&s.session_id)
+                    .and_then(|e| e.expires_at)
+                    .map_or(0, |at| at.timestamp()),
                 session_id: s.session_id,
                 source_path: s.source_path.unwrap_or_default(),
                 created_at_unix: s.created_at.timestamp(),
@@ -187,7 +929,12 @@ impl StorageService for StorageServiceImpl {
             })
             .collect();
 
-        Ok(Response::new(ListSessionsResponse { sessions }))
+        // Local listings are cheap: stale reads are always served fresh
+        Ok(Response::new(ListSessionsResponse {
+            sessions,
+            snapshot_age_ms: 0,
+            next_page_token: String::new(),
+        }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -198,11 +945,24 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
-        let existed = self
+        ensure_not_held(self.storage.as_ref(), tenant_id, Some(&req.session_id))
+            .await
+            .map_storage_err()?;
+
+        let existed = self
             .storage
             .delete_session(tenant_id, &req.session_id)
             .await
             .map_storage_err()?;
+        if existed {
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::DeleteSession, req.context.as_ref())
+                    .with_session(&req.session_id),
+            )
+            .await;
+        }
 
         Ok(Response::new(DeleteSessionResponse {
             success: true,
@@ -239,6 +999,26 @@ impl StorageService for StorageServiceImpl {
         Ok(Response::new(SessionExistsResponse { exists, pending_external_change }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_session_health(
+        &self,
+        request: Request<GetSessionHealthRequest>,
+    ) -> Result<Response<GetSessionHealthResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let health = session_health(
+            self.storage.as_ref(),
+            self.sync.as_deref(),
+            tenant_id,
+            &req.session_id,
+            chrono::Utc::now(),
+        )
+        .await
+        .map_storage_err()?;
+        Ok(Response::new(health_response(health)))
+    }
+
     // =========================================================================
     // Index Operations (Atomic - with internal locking)
     // =========================================================================
@@ -287,7 +1067,9 @@ impl StorageService for StorageServiceImpl {
         let mut acquired = false;
         for i in 0..10 {
             if i > 0 {
-                tokio::time::sleep(Duration::from_millis(50 * i as u64)).await;
+                sleep_before_retry(Duration::from_millis(50 * i as u64))
+                    .await
+                    .map_storage_err()?;
             }
             let result = self.lock_manager.acquire(tenant_id, "index", &holder_id, ttl).await
                 .map_storage_err()?;
@@ -322,6 +1104,11 @@ impl StorageService for StorageServiceImpl {
                     cursor_position: entry.wal_position,
                     checkpoint_positions: entry.checkpoint_positions,
                     pending_external_change: entry.pending_external_change,
+                    display_name: None,
+                    alias: None,
+                    legal_hold: None,
+                    expires_at: chrono::DateTime::from_timestamp(entry.expires_at_unix, 0)
+                        .filter(|_| entry.expires_at_unix > 0),
                 });
                 self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
             }
@@ -356,7 +1143,9 @@ impl StorageService for StorageServiceImpl {
         let mut acquired = false;
         for i in 0..10 {
             if i > 0 {
-                tokio::time::sleep(Duration::from_millis(50 * i as u64)).await;
+                sleep_before_retry(Duration::from_millis(50 * i as u64))
+                    .await
+                    .map_storage_err()?;
             }
             let result = self.lock_manager.acquire(tenant_id, "index", &holder_id, ttl).await
                 .map_storage_err()?;
@@ -448,7 +1237,9 @@ impl StorageService for StorageServiceImpl {
         let mut acquired = false;
         for i in 0..10 {
             if i > 0 {
-                tokio::time::sleep(Duration::from_millis(50 * i as u64)).await;
+                sleep_before_retry(Duration::from_millis(50 * i as u64))
+                    .await
+                    .map_storage_err()?;
             }
             let result = self.lock_manager.acquire(tenant_id, "index", &holder_id, ttl).await
                 .map_storage_err()?;
@@ -468,6 +1259,7 @@ impl StorageService for StorageServiceImpl {
                 .map_storage_err()?
                 .unwrap_or_default();
 
+            index.ensure_not_held(Some(&session_id)).map_storage_err()?;
             let existed = index.remove(&session_id).is_some();
             if existed {
                 self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
@@ -486,6 +1278,66 @@ impl StorageService for StorageServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn rename_session(
+        &self,
+        request: Request<RenameSessionRequest>,
+    ) -> Result<Response<RenameSessionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let session_id = req.session_id;
+
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+
+        // Perform atomic operation
+        let result = async {
+            let mut index = self.storage.load_index(tenant_id).await
+                .map_storage_err()?
+                .unwrap_or_default();
+
+            let renamed = index
+                .rename(&session_id, req.display_name.as_deref(), req.alias.as_deref())
+                .map_storage_err()?;
+            if renamed {
+                self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
+            }
+
+            Ok::<_, Status>(renamed)
+        }.await;
+
+        self.release_index_lock(tenant_id, &holder_id).await;
+
+        let renamed = result?;
+        Ok(Response::new(RenameSessionResponse {
+            success: renamed,
+            not_found: !renamed,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn resolve_session(
+        &self,
+        request: Request<ResolveSessionRequest>,
+    ) -> Result<Response<ResolveSessionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let index = self.storage.load_index(tenant_id).await
+            .map_storage_err()?
+            .unwrap_or_default();
+
+        let response = match index.resolve(&req.name) {
+            Some(entry) => ResolveSessionResponse {
+                found: true,
+                session_id: entry.id.clone(),
+                display_name: entry.display_name.clone().unwrap_or_default(),
+                alias: entry.alias.clone().unwrap_or_default(),
+            },
+            None => ResolveSessionResponse::default(),
+        };
+        Ok(Response::new(response))
+    }
+
     // =========================================================================
     // WAL Operations
     // =========================================================================
@@ -497,30 +1349,57 @@ impl StorageService for StorageServiceImpl {
     ) -> Result<Response<AppendWalResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let response = self
+            .append_session_wal(tenant_id, &req.session_id, req.entries)
+            .await?;
+        Ok(Response::new(response))
+    }
 
-        let entries: Vec<crate::storage::WalEntry> = req
-            .entries
-            .into_iter()
-            .map(|e| crate::storage::WalEntry {
-                position: e.position,
-                operation: e.operation,
-                path: e.path,
-                patch_json: e.patch_json,
-                timestamp: chrono::DateTime::from_timestamp(e.timestamp_unix, 0)
-                    .unwrap_or_else(chrono::Utc::now),
-            })
-            .collect();
-
-        let new_position = self
-            .storage
-            .append_wal(tenant_id, &req.session_id, &entries)
-            .await
-            .map_storage_err()?;
+    #[instrument(skip(self, request), level = "debug", fields(sessions = request.get_ref().sessions.len()))]
+    async fn append_wal_batch(
+        &self,
+        request: Request<AppendWalBatchRequest>,
+    ) -> Result<Response<AppendWalBatchResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        if req.sessions.len() > MAX_APPEND_BATCH_SESSIONS {
+            return Err(Status::invalid_argument(format!(
+                "at most {} sessions per batch",
+                MAX_APPEND_BATCH_SESSIONS
+            )));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = req.sessions.iter().find(|s| !seen.insert(s.session_id.as_str())) {
+            return Err(Status::invalid_argument(format!(
+                "session {} appears more than once in the batch",
+                dup.session_id
+            )));
+        }
 
-        Ok(Response::new(AppendWalResponse {
-            success: true,
-            new_position,
+        // Sessions don't share WAL files: append to all of them at once
+        let results = futures::future::join_all(req.sessions.into_iter().map(|s| async move {
+            match self.append_session_wal(tenant_id, &s.session_id, s.entries).await {
+                Ok(appended) => SessionWalAppendResult {
+                    session_id: s.session_id,
+                    success: true,
+                    error: String::new(),
+                    new_position: appended.new_position,
+                    skip_checkpoint: appended.skip_checkpoint,
+                    checkpoint_reason: appended.checkpoint_reason,
+                },
+                Err(status) => {
+                    warn!("Batch append to session {} failed: {}", s.session_id, status.message());
+                    SessionWalAppendResult {
+                        session_id: s.session_id,
+                        error: status.message().to_string(),
+                        ..Default::default()
+                    }
+                }
+            }
         }))
+        .await;
+
+        Ok(Response::new(AppendWalBatchResponse { results }))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -553,6 +1432,37 @@ impl StorageService for StorageServiceImpl {
         Ok(Response::new(ReadWalResponse { entries, has_more }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn tail_wal(
+        &self,
+        request: Request<TailWalRequest>,
+    ) -> Result<Response<TailWalResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let before_position = (req.before_position > 0).then_some(req.before_position);
+        let limit = if req.limit > 0 { req.limit } else { DEFAULT_TAIL_LIMIT };
+
+        let (entries, has_more) = self
+            .storage
+            .tail_wal(tenant_id, &req.session_id, before_position, limit)
+            .await
+            .map_storage_err()?;
+
+        let entries = entries
+            .into_iter()
+            .map(|e| WalEntry {
+                position: e.position,
+                operation: e.operation,
+                path: e.path,
+                patch_json: e.patch_json,
+                timestamp_unix: e.timestamp.timestamp(),
+            })
+            .collect();
+
+        Ok(Response::new(TailWalResponse { entries, has_more }))
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn truncate_wal(
         &self,
@@ -561,11 +1471,28 @@ impl StorageService for StorageServiceImpl {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
 
+        ensure_not_held(self.storage.as_ref(), tenant_id, Some(&req.session_id))
+            .await
+            .map_storage_err()?;
+
         let entries_removed = self
             .storage
             .truncate_wal(tenant_id, &req.session_id, req.keep_from_position)
             .await
             .map_storage_err()?;
+        if entries_removed > 0 {
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::TruncateWal, req.context.as_ref())
+                    .with_session(&req.session_id)
+                    .with_detail(format!(
+                        "entries removed: {}, keep_from_position: {}",
+                        entries_removed, req.keep_from_position
+                    )),
+            )
+            .await;
+        }
 
         Ok(Response::new(TruncateWalResponse {
             success: true,
@@ -573,6 +1500,36 @@ impl StorageService for StorageServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_history_proof(
+        &self,
+        request: Request<GetHistoryProofRequest>,
+    ) -> Result<Response<GetHistoryProofResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let signer = self.history_signer.as_ref().ok_or_else(|| {
+            Status::failed_precondition("history proofs are disabled: no history key configured")
+        })?;
+
+        let (proof, proof_json, signature) = prove_history(
+            self.storage.as_ref(),
+            signer,
+            tenant_id,
+            &req.session_id,
+            chrono::Utc::now(),
+        )
+        .await
+        .map_storage_err()?;
+
+        Ok(Response::new(GetHistoryProofResponse {
+            consistent: proof.is_consistent(),
+            head_hash: proof.head_hash,
+            links: proof.links,
+            proof_json,
+            signature,
+        }))
+    }
+
     // =========================================================================
     // Checkpoint Operations (Streaming)
     // =========================================================================
@@ -584,43 +1541,32 @@ impl StorageService for StorageServiceImpl {
     ) -> Result<Response<SaveCheckpointResponse>, Status> {
         let mut stream = request.into_inner();
 
-        let mut tenant_id: Option<String> = None;
-        let mut session_id: Option<String> = None;
-        let mut position: u64 = 0;
-        let mut data = Vec::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-
-            // Extract metadata from first chunk
-            if tenant_id.is_none() {
-                tenant_id = chunk.context.map(|c| c.tenant_id);
-                session_id = Some(chunk.session_id);
-                position = chunk.position;
-            }
-
-            data.extend(chunk.data);
-
-            if chunk.is_last {
-                break;
-            }
-        }
+        // Metadata comes with the first chunk; the data is written as it arrives
+        let first = stream.next().await.transpose()?;
+        let (tenant_id, session_id, position, data, is_last) = first
+            .map(|c| {
+                let tenant_id = c.context.map(|c| c.tenant_id);
+                (tenant_id, c.session_id, c.position, c.data, c.is_last)
+            })
+            .unwrap_or_default();
 
         let tenant_id = tenant_id
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
-        let session_id = session_id
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let session_id = Some(session_id)
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
 
-        debug!(
-            "Saving checkpoint at position {} for session {} tenant {} ({} bytes)",
-            position, session_id, tenant_id, data.len()
-        );
-
-        self.storage
-            .save_checkpoint(&tenant_id, &session_id, position, &data)
+        let chunks = upload_stream(data, is_last, stream, |c| (c.data, c.is_last));
+        let written = self
+            .storage
+            .save_checkpoint_stream(&tenant_id, &session_id, position, chunks)
             .await
             .map_storage_err()?;
+        debug!(
+            "Saved checkpoint at position {} for session {} tenant {} ({} bytes)",
+            position, session_id, tenant_id, written
+        );
 
         Ok(Response::new(SaveCheckpointResponse { success: true }))
     }
@@ -707,23 +1653,1684 @@ impl StorageService for StorageServiceImpl {
             })
             .collect();
 
-        Ok(Response::new(ListCheckpointsResponse { checkpoints }))
+        Ok(Response::new(ListCheckpointsResponse {
+            checkpoints,
+            snapshot_age_ms: 0,
+        }))
     }
 
     // =========================================================================
-    // Health Check
+    // Library Operations
     // =========================================================================
 
-    #[instrument(skip(self), level = "debug")]
-    async fn health_check(
+    #[instrument(skip(self, request), level = "debug")]
+    async fn save_library_item(
         &self,
-        _request: Request<HealthCheckRequest>,
-    ) -> Result<Response<HealthCheckResponse>, Status> {
-        debug!("Health check requested");
-        Ok(Response::new(HealthCheckResponse {
-            healthy: true,
-            backend: self.storage.backend_name().to_string(),
-            version: self.version.clone(),
+        request: Request<Streaming<SaveLibraryItemChunk>>,
+    ) -> Result<Response<SaveLibraryItemResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        let mut tenant_id: Option<String> = None;
+        let mut kind = 0;
+        let mut name: Option<String> = None;
+        let mut data = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+
+            // Extract metadata from first chunk
+            if tenant_id.is_none() {
+                tenant_id = chunk.context.map(|c| c.tenant_id);
+                kind = chunk.kind;
+                name = Some(chunk.name);
+            }
+
+            data.extend(chunk.data);
+
+            if chunk.is_last {
+                break;
+            }
+        }
+
+        let tenant_id = tenant_id
+            .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let kind = Self::library_kind(kind)?;
+        let name = name
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| Status::invalid_argument("name is required in first chunk"))?;
+
+        debug!("Saving {:?} {} for tenant {} ({} bytes)", kind, name, tenant_id, data.len());
+
+        self.storage
+            .save_library_item(&tenant_id, kind, &name, &data)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(SaveLibraryItemResponse { success: true }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn load_library_item(
+        &self,
+        request: Request<LoadLibraryItemRequest>,
+    ) -> Result<Response<Self::LoadLibraryItemStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        let result = self
+            .storage
+            .load_library_item(tenant_id, kind, &req.name)
+            .await
+            .map_storage_err()?;
+
+        // Items are small (a document or a few paragraphs); chunk them eagerly
+        let chunks: Vec<Result<DataChunk, Status>> = match result {
+            Some(data) if !data.is_empty() => {
+                let total_size = data.len() as u64;
+                let total_chunks = data.len().div_ceil(self.chunk_size);
+                data.chunks(self.chunk_size)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        Ok(DataChunk {
+                            data: chunk.to_vec(),
+                            is_last: i == total_chunks - 1,
+                            found: i == 0,
+                            total_size: if i == 0 { total_size } else { 0 },
+                        })
+                    })
+                    .collect()
+            }
+            found => vec![Ok(DataChunk {
+                data: vec![],
+                is_last: true,
+                found: found.is_some(),
+                total_size: 0,
+            })],
+        };
+
+        Ok(Response::new(Box::pin(tokio_stream::iter(chunks))))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_library_items(
+        &self,
+        request: Request<ListLibraryItemsRequest>,
+    ) -> Result<Response<ListLibraryItemsResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        let items = self
+            .storage
+            .list_library_items(tenant_id, kind)
+            .await
+            .map_storage_err()?
+            .into_iter()
+            .map(|item| LibraryItemInfo {
+                name: item.name,
+                modified_at_unix: item.modified_at.timestamp(),
+                size_bytes: item.size_bytes as i64,
+            })
+            .collect();
+
+        Ok(Response::new(ListLibraryItemsResponse { items }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn delete_library_item(
+        &self,
+        request: Request<DeleteLibraryItemRequest>,
+    ) -> Result<Response<DeleteLibraryItemResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let kind = Self::library_kind(req.kind)?;
+
+        ensure_not_held(self.storage.as_ref(), tenant_id, None)
+            .await
+            .map_storage_err()?;
+
+        let existed = self
+            .storage
+            .delete_library_item(tenant_id, kind, &req.name)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(DeleteLibraryItemResponse {
+            success: true,
+            existed,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_session_from_template(
+        &self,
+        request: Request<CreateSessionFromTemplateRequest>,
+    ) -> Result<Response<CreateSessionFromTemplateResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let session_id = req.session_id;
+        if session_id.is_empty() {
+            return Err(Status::invalid_argument("session_id is required"));
+        }
+
+        let Some(data) = self
+            .storage
+            .load_library_item(tenant_id, docx_storage_core::LibraryKind::Template, &req.template_name)
+            .await
+            .map_storage_err()?
+        else {
+            return Ok(Response::new(CreateSessionFromTemplateResponse::default()));
+        };
+
+        let expires_at = chrono::DateTime::from_timestamp(req.expires_at_unix, 0)
+            .filter(|_| req.expires_at_unix > 0);
+        self.create_session(tenant_id, &session_id, &data, None, expires_at)
+            .await?;
+
+        debug!(
+            "Created session {} from template {} for tenant {}",
+            session_id, req.template_name, tenant_id
+        );
+        Ok(Response::new(CreateSessionFromTemplateResponse {
+            found: true,
+            session_id,
+            size_bytes: data.len() as i64,
         }))
     }
+
+    // =========================================================================
+    // Tenant Erasure
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn erase_tenant(
+        &self,
+        request: Request<EraseTenantRequest>,
+    ) -> Result<Response<EraseTenantResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let signer = self.erasure_signer.as_ref().ok_or_else(|| {
+            Status::failed_precondition("tenant erasure is disabled: no erasure key configured")
+        })?;
+
+        let step = erase_tenant(
+            self.storage.as_ref(),
+            signer,
+            tenant_id,
+            Self::get_actor(req.context.as_ref()),
+            &[],
+            Some(req.confirmation_token.as_str()),
+            chrono::Utc::now(),
+        )
+        .await
+        .map_storage_err()?;
+
+        let response = match step {
+            ErasureStep::ConfirmationRequired {
+                token,
+                expires_at,
+                inventory,
+            } => EraseTenantResponse {
+                erased: false,
+                inventory: Some(Self::inventory_to_proto(&inventory)),
+                confirmation_token: token,
+                token_expires_at_unix: expires_at.timestamp(),
+                ..Default::default()
+            },
+            ErasureStep::Erased {
+                report,
+                report_json,
+                signature,
+            } => {
+                // Not in the tenant's admin log: writing it would recreate the
+                // tenant just erased. The signed report names the actor.
+                info!(
+                    "Erased tenant {} for {} ({} objects)",
+                    tenant_id, report.erased_by, report.objects_deleted
+                );
+                EraseTenantResponse {
+                    erased: true,
+                    inventory: Some(Self::inventory_to_proto(&report.erased)),
+                    report_json,
+                    signature,
+                    ..Default::default()
+                }
+            }
+        };
+        Ok(Response::new(response))
+    }
+
+    // =========================================================================
+    // Index Rebuild
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn rebuild_index(
+        &self,
+        request: Request<RebuildIndexRequest>,
+    ) -> Result<Response<RebuildIndexResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let report = self
+            .rebuild_tenant_index(tenant_id, req.dry_run, req.remove_orphans)
+            .await?;
+        if !req.dry_run && report.changed_index() {
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::RebuildIndex, req.context.as_ref())
+                    .with_detail(Self::rebuild_detail(&report)),
+            )
+            .await;
+        }
+        Ok(Response::new(Self::rebuild_report_to_proto(report, req.dry_run)))
+    }
+
+    // =========================================================================
+    // Legal Hold
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn set_legal_hold(
+        &self,
+        request: Request<SetLegalHoldRequest>,
+    ) -> Result<Response<SetLegalHoldResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let session_id = (!req.session_id.is_empty()).then_some(req.session_id.as_str());
+        if req.hold && req.reason.trim().is_empty() {
+            return Err(Status::invalid_argument("a reason is required to place a legal hold"));
+        }
+
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+
+        let result = async {
+            let mut index = self.storage.load_index(tenant_id).await
+                .map_storage_err()?
+                .unwrap_or_default();
+
+            let was_held = match session_id {
+                None => index.legal_hold.is_some(),
+                Some(id) => index.get(id).is_some_and(|e| e.legal_hold.is_some()),
+            };
+            let hold = req
+                .hold
+                .then(|| LegalHold::new(req.reason.trim(), chrono::Utc::now()));
+            let found = index.set_legal_hold(session_id, hold);
+            if found {
+                self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
+            }
+
+            Ok::<_, Status>((found, was_held))
+        }.await;
+
+        self.release_index_lock(tenant_id, &holder_id).await;
+
+        let (found, was_held) = result?;
+        if found {
+            info!(
+                "Legal hold {} on tenant {} session {}",
+                if req.hold { "placed" } else { "lifted" },
+                tenant_id,
+                session_id.unwrap_or("*")
+            );
+            let mut entry = Self::admin_entry(AdminAction::LegalHold, req.context.as_ref())
+                .with_detail(if req.hold {
+                    format!("placed: {}", req.reason.trim())
+                } else {
+                    "lifted".to_string()
+                });
+            if let Some(id) = session_id {
+                entry = entry.with_session(id);
+            }
+            record_admin_action(self.storage.as_ref(), tenant_id, entry).await;
+        }
+        Ok(Response::new(SetLegalHoldResponse {
+            success: found,
+            not_found: !found,
+            was_held,
+        }))
+    }
+
+    // =========================================================================
+    // Sandbox
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn create_sandbox(
+        &self,
+        request: Request<CreateSandboxRequest>,
+    ) -> Result<Response<CreateSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let sandbox_id = sandbox_tenant_id(tenant_id).map_storage_err()?;
+        let now = chrono::Utc::now();
+        let expires_at =
+            (req.ttl_seconds > 0).then(|| now + chrono::Duration::seconds(req.ttl_seconds));
+
+        let holder_id = self.acquire_index_lock(&sandbox_id).await?;
+        let result = create_sandbox(
+            self.storage.as_ref(),
+            tenant_id,
+            &req.session_ids,
+            req.replace,
+            expires_at,
+            now,
+        )
+        .await
+        .map_storage_err();
+        self.release_index_lock(&sandbox_id, &holder_id).await;
+
+        let (sandbox_tenant_id, session_ids) = result?;
+        info!(
+            "Created sandbox {} with {} sessions",
+            sandbox_tenant_id,
+            session_ids.len()
+        );
+        Ok(Response::new(CreateSandboxResponse {
+            sandbox_tenant_id,
+            session_ids,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn promote_sandbox(
+        &self,
+        request: Request<PromoteSandboxRequest>,
+    ) -> Result<Response<PromoteSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let sandbox_id = sandbox_tenant_id(tenant_id).map_storage_err()?;
+
+        // Always lock the tenant before its sandbox
+        let holder_id = self.acquire_index_lock(tenant_id).await?;
+        let sandbox_holder_id = match self.acquire_index_lock(&sandbox_id).await {
+            Ok(id) => id,
+            Err(e) => {
+                self.release_index_lock(tenant_id, &holder_id).await;
+                return Err(e);
+            }
+        };
+
+        let result = async {
+            let mut sandbox = self.storage.load_index(&sandbox_id).await
+                .map_storage_err()?
+                .ok_or_else(|| {
+                    Status::not_found(format!("tenant '{}' has no sandbox", tenant_id))
+                })?;
+            let mut origin = sandbox.sandbox_origin(tenant_id).map_storage_err()?.clone();
+            let entries = sandbox.select_sessions(&req.session_ids).map_storage_err()?;
+
+            let mut index = self.storage.load_index(tenant_id).await
+                .map_storage_err()?
+                .unwrap_or_default();
+            index.check_promotion(&origin, &entries, req.force).map_storage_err()?;
+
+            for entry in &entries {
+                copy_session_data(self.storage.as_ref(), &sandbox_id, tenant_id, &entry.id)
+                    .await
+                    .map_storage_err()?;
+            }
+            index.promote(&entries);
+            self.storage.save_index(tenant_id, &index).await.map_storage_err()?;
+
+            // Record the promoted versions, so promoting again isn't a conflict
+            let promoted: Vec<_> = entries
+                .iter()
+                .filter_map(|e| index.get(&e.id).cloned())
+                .collect();
+            origin.rebase(&promoted);
+            sandbox.sandbox = Some(origin);
+            self.storage.save_index(&sandbox_id, &sandbox).await.map_storage_err()?;
+
+            Ok::<_, Status>(entries.into_iter().map(|e| e.id).collect::<Vec<_>>())
+        }.await;
+
+        self.release_index_lock(&sandbox_id, &sandbox_holder_id).await;
+        self.release_index_lock(tenant_id, &holder_id).await;
+
+        let session_ids = result?;
+        info!(
+            "Promoted {} sessions from sandbox {} to tenant {}",
+            session_ids.len(),
+            sandbox_id,
+            tenant_id
+        );
+        record_admin_action(
+            self.storage.as_ref(),
+            tenant_id,
+            Self::admin_entry(AdminAction::PromoteSandbox, req.context.as_ref())
+                .with_detail(format!("promoted sessions: {}", session_ids.join(", "))),
+        )
+        .await;
+
+        let discarded = req.discard;
+        if discarded {
+            self.discard_sandbox(Request::new(DiscardSandboxRequest {
+                context: req.context,
+            }))
+            .await?;
+        }
+        Ok(Response::new(PromoteSandboxResponse {
+            session_ids,
+            discarded,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn discard_sandbox(
+        &self,
+        request: Request<DiscardSandboxRequest>,
+    ) -> Result<Response<DiscardSandboxResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let sandbox_id = sandbox_tenant_id(tenant_id).map_storage_err()?;
+
+        let holder_id = self.acquire_index_lock(&sandbox_id).await?;
+        let result = async {
+            let existed = self.storage.load_index(&sandbox_id).await
+                .map_storage_err()?
+                .is_some();
+            let objects_deleted = discard_sandbox(self.storage.as_ref(), tenant_id)
+                .await
+                .map_storage_err()?;
+            Ok::<_, Status>((existed, objects_deleted))
+        }.await;
+        self.release_index_lock(&sandbox_id, &holder_id).await;
+
+        let (existed, objects_deleted) = result?;
+        if existed {
+            info!("Discarded sandbox {} ({} objects)", sandbox_id, objects_deleted);
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::DiscardSandbox, req.context.as_ref())
+                    .with_detail(format!("deleted {} objects", objects_deleted)),
+            )
+            .await;
+        }
+        Ok(Response::new(DiscardSandboxResponse {
+            existed,
+            objects_deleted,
+        }))
+    }
+
+    // =========================================================================
+    // Bucket Lifecycle
+    // =========================================================================
+
+    async fn apply_lifecycle_rules(
+        &self,
+        _request: Request<ApplyLifecycleRulesRequest>,
+    ) -> Result<Response<ApplyLifecycleRulesResponse>, Status> {
+        Err(Status::unimplemented(
+            "lifecycle rules are only supported by the R2 storage server",
+        ))
+    }
+
+    async fn get_cost_estimate(
+        &self,
+        _request: Request<GetCostEstimateRequest>,
+    ) -> Result<Response<GetCostEstimateResponse>, Status> {
+        Err(Status::unimplemented(
+            "cost estimates are only supported by the R2 storage server",
+        ))
+    }
+
+    async fn get_contention_report(
+        &self,
+        _request: Request<GetContentionReportRequest>,
+    ) -> Result<Response<GetContentionReportResponse>, Status> {
+        Err(Status::unimplemented(
+            "CAS contention is only tracked by the R2 storage server",
+        ))
+    }
+
+    // =========================================================================
+    // Tenant Snapshot
+    // =========================================================================
+
+    async fn snapshot_tenant(
+        &self,
+        _request: Request<SnapshotTenantRequest>,
+    ) -> Result<Response<SnapshotTenantResponse>, Status> {
+        Err(Status::unimplemented(
+            "tenant snapshots are only supported by the R2 storage server",
+        ))
+    }
+
+    // =========================================================================
+    // Presigned URLs
+    // =========================================================================
+
+    async fn create_download_url(
+        &self,
+        _request: Request<CreateDownloadUrlRequest>,
+    ) -> Result<Response<CreateDownloadUrlResponse>, Status> {
+        Err(Status::unimplemented(
+            "presigned URLs are only supported by the R2 storage server",
+        ))
+    }
+
+    async fn create_upload_url(
+        &self,
+        _request: Request<CreateUploadUrlRequest>,
+    ) -> Result<Response<CreateUploadUrlResponse>, Status> {
+        Err(Status::unimplemented(
+            "presigned URLs are only supported by the R2 storage server",
+        ))
+    }
+
+    async fn commit_upload(
+        &self,
+        _request: Request<CommitUploadRequest>,
+    ) -> Result<Response<CommitUploadResponse>, Status> {
+        Err(Status::unimplemented(
+            "presigned URLs are only supported by the R2 storage server",
+        ))
+    }
+
+    // =========================================================================
+    // Corpus Export
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn export_tenant_corpus(
+        &self,
+        request: Request<ExportTenantCorpusRequest>,
+    ) -> Result<Response<Self::ExportTenantCorpusStream>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?.to_string();
+        check_corpus_format(&req.format)?;
+        let sessions_per_sec =
+            corpus_pace(self.corpus_sessions_per_sec, req.max_sessions_per_sec);
+        let after = (!req.resume_token.is_empty()).then_some(req.resume_token);
+
+        info!(tenant_id, ?after, sessions_per_sec, "Exporting tenant corpus");
+        let storage = self.storage.clone();
+        let records = export_corpus(storage, tenant_id, after, sessions_per_sec);
+        Ok(Response::new(Box::pin(records.map(corpus_chunk))))
+    }
+
+    // =========================================================================
+    // Storage Report
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn get_storage_report(
+        &self,
+        request: Request<GetStorageReportRequest>,
+    ) -> Result<Response<GetStorageReportResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let report = if req.cleanup {
+            // A held tenant keeps everything, and the index lock keeps a
+            // session from being indexed while its files are deleted
+            ensure_not_held(self.storage.as_ref(), tenant_id, None)
+                .await
+                .map_storage_err()?;
+            let holder_id = self.acquire_index_lock(tenant_id).await?;
+            let result = self.storage.storage_report(tenant_id, true).await;
+            self.release_index_lock(tenant_id, &holder_id).await;
+            result
+        } else {
+            self.storage.storage_report(tenant_id, false).await
+        }
+        .map_storage_err()?
+        .ok_or_else(|| {
+            Status::unimplemented("storage reports are not supported by this tenant's backend")
+        })?;
+
+        if report.cleaned {
+            info!(
+                tenant_id,
+                orphaned_files = report.orphaned_files.len(),
+                temp_files = report.temp_files.len(),
+                bytes = report.leftover_bytes(),
+                "Deleted leftover files"
+            );
+            record_admin_action(
+                self.storage.as_ref(),
+                tenant_id,
+                Self::admin_entry(AdminAction::StorageCleanup, req.context.as_ref()).with_detail(
+                    format!(
+                        "deleted {} orphaned and {} temporary files ({} bytes)",
+                        report.orphaned_files.len(),
+                        report.temp_files.len(),
+                        report.leftover_bytes()
+                    ),
+                ),
+            )
+            .await;
+        }
+        Ok(Response::new(Self::storage_report_to_proto(report)))
+    }
+
+    // =========================================================================
+    // Admin Log
+    // =========================================================================
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn read_admin_log(
+        &self,
+        request: Request<ReadAdminLogRequest>,
+    ) -> Result<Response<ReadAdminLogResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        let limit = if req.limit == 0 { DEFAULT_ADMIN_LOG_LIMIT } else { req.limit };
+
+        // One more than asked tells whether there are more
+        let mut entries = self
+            .storage
+            .read_admin_log(tenant_id, req.from_position, Some(limit.saturating_add(1)))
+            .await
+            .map_storage_err()?;
+        let has_more = entries.len() as u64 > limit;
+        entries.truncate(limit as usize);
+
+        Ok(Response::new(ReadAdminLogResponse {
+            entries: entries.into_iter().map(Self::admin_log_entry_to_proto).collect(),
+            has_more,
+        }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn record_admin_action(
+        &self,
+        request: Request<RecordAdminActionRequest>,
+    ) -> Result<Response<RecordAdminActionResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        if Self::get_actor(req.context.as_ref()).trim().is_empty() {
+            return Err(Status::invalid_argument("an actor is required to record an admin action"));
+        }
+        // The server records the other actions itself
+        let action = AdminAction::parse(&req.action).map_storage_err()?;
+        if !matches!(action, AdminAction::Migration | AdminAction::KeyRotation) {
+            return Err(Status::invalid_argument(format!(
+                "'{}' is recorded by the server, only migration and key_rotation can be recorded",
+                req.action
+            )));
+        }
+
+        let mut entry = Self::admin_entry(action, req.context.as_ref()).with_detail(req.detail);
+        if !req.session_id.is_empty() {
+            entry = entry.with_session(&req.session_id);
+        }
+        let position = self
+            .storage
+            .append_admin_log(tenant_id, entry)
+            .await
+            .map_storage_err()?;
+
+        Ok(Response::new(RecordAdminActionResponse { position }))
+    }
+
+    // =========================================================================
+    // Health Check
+    // =========================================================================
+
+    #[instrument(skip(self), level = "debug")]
+    async fn health_check(
+        &self,
+        _request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        debug!("Health check requested");
+        Ok(Response::new(HealthCheckResponse {
+            healthy: true,
+            backend: self.storage.backend_name().to_string(),
+            version: self.version.clone(),
+        }))
+    }
+
+    // =========================================================================
+    // Capabilities
+    // =========================================================================
+
+    #[instrument(skip(self), level = "debug")]
+    async fn get_capabilities(
+        &self,
+        _request: Request<GetCapabilitiesRequest>,
+    ) -> Result<Response<GetCapabilitiesResponse>, Status> {
+        let caps = self
+            .storage
+            .capabilities()
+            .with_feature(feature::SNAPSHOT_HANDLES)
+            .with_feature(feature::SANDBOXES)
+            .with_feature(feature::LEGAL_HOLDS)
+            .with_feature(feature::WAL_BATCHES)
+            .with_feature(feature::CORPUS_EXPORT)
+            .with_feature_if(feature::RESUMABLE_UPLOADS, self.uploads.is_some())
+            .with_feature_if(feature::TENANT_ERASURE, self.erasure_signer.is_some());
+        Ok(Response::new(capabilities_response(caps, &self.version)))
+    }
+}
+
+/// Refuse export formats other than JSONL (the default).
+fn check_corpus_format(format: &str) -> Result<(), Status> {
+    match format {
+        "" | CORPUS_FORMAT_JSONL => Ok(()),
+        other => Err(Status::invalid_argument(format!(
+            "unsupported corpus format '{}' (only {} is)",
+            other, CORPUS_FORMAT_JSONL
+        ))),
+    }
+}
+
+/// Sessions a corpus export reads per second: the slower of the server's
+/// and the client's pace (0 = unpaced on either side).
+fn corpus_pace(server: u32, requested: u32) -> u32 {
+    match (server, requested) {
+        (0, requested) => requested,
+        (server, 0) => server,
+        (server, requested) => server.min(requested),
+    }
+}
+
+/// One corpus record as a stream chunk, resuming after it.
+fn corpus_chunk(record: Result<CorpusRecord, StorageError>) -> Result<CorpusChunk, Status> {
+    let record = record.map_storage_err()?;
+    Ok(CorpusChunk {
+        data: record.to_jsonl().map_storage_err()?,
+        resume_token: record.session_id,
+    })
+}
+
+/// Convert [`Capabilities`] into their protobuf response.
+fn capabilities_response(caps: Capabilities, version: &str) -> GetCapabilitiesResponse {
+    GetCapabilitiesResponse {
+        backend: caps.backend,
+        version: version.to_string(),
+        schema_version: PROTO_SCHEMA_VERSION,
+        features: caps.features.into_iter().collect(),
+        max_object_bytes: caps.max_object_bytes,
+        max_wal_entry_bytes: caps.max_wal_entry_bytes,
+    }
+}
+
+/// Flatten a [`SessionHealth`] into its protobuf response.
+fn health_response(health: Option<SessionHealth>) -> GetSessionHealthResponse {
+    let Some(health) = health else {
+        return GetSessionHealthResponse::default();
+    };
+    let sync = health.sync.as_ref();
+    GetSessionHealthResponse {
+        found: true,
+        size_bytes: health.size_bytes,
+        wal_count: health.wal_count,
+        cursor_position: health.cursor_position,
+        last_checkpoint: health.last_checkpoint,
+        wal_since_checkpoint: health.wal_since_checkpoint,
+        last_modified_at_unix: health.last_modified_at.map_or(0, |t| t.timestamp()),
+        pending_external_change: health.pending_external_change,
+        sync_registered: sync.is_some(),
+        auto_sync_enabled: sync.is_some_and(|s| s.auto_sync_enabled),
+        last_synced_at_unix: sync.and_then(|s| s.last_synced_at).map_or(0, |t| t.timestamp()),
+        since_last_sync_secs: sync.and_then(|s| s.since_last_sync_secs).unwrap_or(-1),
+        sync_pending: sync.is_some_and(|s| s.has_pending_changes),
+        sync_error: sync.and_then(|s| s.last_error.clone()).unwrap_or_default(),
+        legal_hold: health.legal_hold.is_some(),
+        legal_hold_reason: health.legal_hold.as_ref().map(|h| h.reason.clone()).unwrap_or_default(),
+        expires_at_unix: health.expires_at.map_or(0, |t| t.timestamp()),
+        valid: health.is_valid(),
+        problems: health.problems,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lock::FileLock;
+    use crate::storage::LocalStorage;
+    use docx_storage_core::with_deadline;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_index_lock_retries_stop_at_the_callers_deadline() {
+        let dir = TempDir::new().unwrap();
+        let lock_manager = Arc::new(FileLock::new(dir.path()));
+        let svc = StorageServiceImpl::new(
+            Arc::new(LocalStorage::new(dir.path())),
+            lock_manager.clone(),
+        );
+
+        // Someone else holds the index lock for the whole test
+        lock_manager
+            .acquire("acme", "index", "other", Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(100);
+        let err = with_deadline(
+            Some(deadline),
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: Some(TenantContext {
+                    tenant_id: "acme".to_string(),
+                    ..Default::default()
+                }),
+                session_id: "s1".to_string(),
+                entry: Some(SessionIndexEntry::default()),
+            })),
+        )
+        .await
+        .unwrap_err();
+
+        // Without the deadline the retries would run for over two seconds
+        assert_eq!(err.code(), tonic::Code::DeadlineExceeded);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_follow_configuration() {
+        let dir = TempDir::new().unwrap();
+        let svc = StorageServiceImpl::new(
+            Arc::new(LocalStorage::new(dir.path())),
+            Arc::new(FileLock::new(dir.path())),
+        );
+        let caps = svc
+            .get_capabilities(Request::new(GetCapabilitiesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(caps.backend, "local");
+        assert_eq!(caps.schema_version, PROTO_SCHEMA_VERSION);
+        assert_eq!(caps.max_object_bytes, 0);
+        assert!(caps.features.iter().any(|f| f == feature::SANDBOXES));
+        assert!(!caps.features.iter().any(|f| f == feature::CAS));
+        assert!(!caps.features.iter().any(|f| f == feature::TENANT_ERASURE));
+
+        let svc = svc.with_erasure_signer(ErasureSigner::new("0123456789abcdef-erasure").unwrap());
+        let caps = svc
+            .get_capabilities(Request::new(GetCapabilitiesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(caps.features.iter().any(|f| f == feature::TENANT_ERASURE));
+    }
+
+    fn erase_request(tenant_id: &str, token: &str) -> Request<EraseTenantRequest> {
+        Request::new(EraseTenantRequest {
+            context: Some(TenantContext {
+                tenant_id: tenant_id.to_string(),
+                actor: "ops@example.com".to_string(),
+            }),
+            confirmation_token: token.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_erase_tenant_requires_confirmation_and_signs_report() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let lock_manager = Arc::new(FileLock::new(dir.path()));
+        for tenant in ["acme", "other"] {
+            storage.save_session(tenant, "s1", b"PK\x03\x04doc").await.unwrap();
+            storage.save_checkpoint(tenant, "s1", 1, b"PK\x03\x04ckpt").await.unwrap();
+        }
+
+        // Disabled without an erasure key
+        let disabled = StorageServiceImpl::new(storage.clone(), lock_manager.clone());
+        let err = disabled.erase_tenant(erase_request("acme", "")).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+
+        let signer = ErasureSigner::new("0123456789abcdef-erasure").unwrap();
+        let svc = StorageServiceImpl::new(storage.clone(), lock_manager)
+            .with_erasure_signer(signer.clone());
+
+        // Step 1: inventory and token, nothing deleted
+        let first = svc.erase_tenant(erase_request("acme", "")).await.unwrap().into_inner();
+        assert!(!first.erased);
+        let inventory = first.inventory.unwrap();
+        assert_eq!((inventory.sessions, inventory.checkpoints), (1, 1));
+        assert!(storage.session_exists("acme", "s1").await.unwrap());
+
+        // A token for another tenant is refused
+        let other = svc.erase_tenant(erase_request("other", "")).await.unwrap().into_inner();
+        let err = svc
+            .erase_tenant(erase_request("acme", &other.confirmation_token))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        // Step 2: erased, with a report signed by the key
+        let second = svc
+            .erase_tenant(erase_request("acme", &first.confirmation_token))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(second.erased);
+        assert_eq!(second.signature, signer.sign(second.report_json.as_bytes()));
+        let report: docx_storage_core::ErasureReport =
+            serde_json::from_str(&second.report_json).unwrap();
+        assert_eq!(report.erased.sessions, 1);
+        assert_eq!(report.erased_by, "ops@example.com");
+        assert!(report.remaining.is_empty());
+        assert!(report.objects_deleted >= 2);
+
+        assert!(!dir.path().join("acme").exists());
+        assert!(storage.session_exists("other", "s1").await.unwrap());
+    }
+
+    fn hold_request(session_id: &str, hold: bool, reason: &str) -> Request<SetLegalHoldRequest> {
+        Request::new(SetLegalHoldRequest {
+            context: Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            }),
+            session_id: session_id.to_string(),
+            hold,
+            reason: reason.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_legal_hold_blocks_destructive_operations_until_lifted() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())))
+            .with_erasure_signer(ErasureSigner::new("0123456789abcdef-erasure").unwrap());
+        let context = || {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            })
+        };
+        for session in ["s1", "s2"] {
+            storage.save_session("acme", session, b"PK\x03\x04doc").await.unwrap();
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: context(),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry::default()),
+            }))
+            .await
+            .unwrap();
+        }
+
+        let err = svc.set_legal_hold(hold_request("s1", true, " ")).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        let resp = svc.set_legal_hold(hold_request("missing", true, "case 42")).await.unwrap();
+        assert!(resp.into_inner().not_found);
+        svc.set_legal_hold(hold_request("s1", true, "case 42")).await.unwrap();
+
+        let assert_held = |err: Status| {
+            assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+            assert!(err.message().starts_with("Legal hold:"), "{}", err.message());
+            assert!(err.message().contains("case 42"), "{}", err.message());
+        };
+        let delete = |session: &str| {
+            Request::new(DeleteSessionRequest {
+                context: context(),
+                session_id: session.to_string(),
+            })
+        };
+        assert_held(svc.delete_session(delete("s1")).await.unwrap_err());
+        assert_held(
+            svc.truncate_wal(Request::new(TruncateWalRequest {
+                context: context(),
+                session_id: "s1".to_string(),
+                keep_from_position: 0,
+            }))
+            .await
+            .unwrap_err(),
+        );
+        assert_held(
+            svc.remove_session_from_index(Request::new(RemoveSessionFromIndexRequest {
+                context: context(),
+                session_id: "s1".to_string(),
+            }))
+            .await
+            .unwrap_err(),
+        );
+        assert_held(svc.erase_tenant(erase_request("acme", "")).await.unwrap_err());
+        assert!(storage.session_exists("acme", "s1").await.unwrap());
+
+        // Other sessions aren't covered by a session hold
+        svc.delete_session(delete("s2")).await.unwrap();
+
+        // A tenant hold covers library items and every session
+        svc.set_legal_hold(hold_request("", true, "case 42")).await.unwrap();
+        assert_held(
+            svc.delete_library_item(Request::new(DeleteLibraryItemRequest {
+                context: context(),
+                kind: LibraryKind::Template as i32,
+                name: "nda".to_string(),
+            }))
+            .await
+            .unwrap_err(),
+        );
+
+        // Lifting both holds allows deletes again
+        let lifted = svc.set_legal_hold(hold_request("", false, "")).await.unwrap().into_inner();
+        assert!(lifted.was_held);
+        assert_held(svc.delete_session(delete("s1")).await.unwrap_err());
+        svc.set_legal_hold(hold_request("s1", false, "")).await.unwrap();
+        assert!(svc.delete_session(delete("s1")).await.unwrap().into_inner().existed);
+    }
+
+    #[tokio::test]
+    async fn test_expired_ephemeral_sessions_are_purged_unless_held() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let context = || {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            })
+        };
+        let now = chrono::Utc::now();
+        let expires = now.timestamp() + 3600;
+        for (session, expires_at_unix) in [("kept", 0), ("temp", expires), ("held", expires)] {
+            storage.save_session("acme", session, b"PK\x03\x04doc").await.unwrap();
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: context(),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry {
+                    expires_at_unix,
+                    ..Default::default()
+                }),
+            }))
+            .await
+            .unwrap();
+        }
+        svc.set_legal_hold(hold_request("held", true, "case 42")).await.unwrap();
+
+        let listed = svc
+            .list_sessions(Request::new(ListSessionsRequest {
+                context: context(),
+                stale_ok: false,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .sessions;
+        let expiry = |id: &str| listed.iter().find(|s| s.session_id == id).unwrap().expires_at_unix;
+        assert_eq!(expiry("kept"), 0);
+        assert_eq!(expiry("temp"), expires);
+
+        // Nothing has expired yet
+        assert_eq!(svc.purge_expired(now).await.unwrap(), 0);
+
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(svc.purge_expired(later).await.unwrap(), 1);
+        assert!(!storage.session_exists("acme", "temp").await.unwrap());
+        assert!(storage.session_exists("acme", "kept").await.unwrap());
+        assert!(storage.session_exists("acme", "held").await.unwrap());
+        let index = storage.load_index("acme").await.unwrap().unwrap();
+        assert!(!index.contains("temp"));
+        assert!(index.contains("held"));
+
+        // Once the hold is lifted, the expired session goes too
+        svc.set_legal_hold(hold_request("held", false, "")).await.unwrap();
+        assert_eq!(svc.purge_expired(later).await.unwrap(), 1);
+        assert!(!storage.session_exists("acme", "held").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_by_page() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let context = || {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            })
+        };
+        for session in ["d-1", "a-1", "c-2", "b-2", "e-1"] {
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: context(),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry {
+                    source_path: format!("/Reports/{}.docx", session),
+                    ..Default::default()
+                }),
+            }))
+            .await
+            .unwrap();
+        }
+        let page = |page_token: &str, session_id_prefix: &str, search: &str| {
+            svc.list_sessions(Request::new(ListSessionsRequest {
+                context: context(),
+                page_size: 2,
+                page_token: page_token.to_string(),
+                session_id_prefix: session_id_prefix.to_string(),
+                search: search.to_string(),
+                ..Default::default()
+            }))
+        };
+        let ids = |resp: &ListSessionsResponse| {
+            resp.sessions.iter().map(|s| s.session_id.as_str()).collect::<Vec<_>>().join(",")
+        };
+
+        let mut listed = Vec::new();
+        let mut token = String::new();
+        loop {
+            let resp = page(&token, "", "").await.unwrap().into_inner();
+            listed.push(ids(&resp));
+            if resp.next_page_token.is_empty() {
+                break;
+            }
+            token = resp.next_page_token;
+        }
+        assert_eq!(listed, ["a-1,b-2", "c-2,d-1", "e-1"]);
+
+        let resp = page("", "", "REPORTS/B").await.unwrap().into_inner();
+        assert_eq!(ids(&resp), "b-2");
+        assert_eq!(resp.sessions[0].source_path, "/Reports/b-2.docx");
+        let resp = page("a-1", "", "-2").await.unwrap().into_inner();
+        assert_eq!((ids(&resp).as_str(), resp.next_page_token.as_str()), ("b-2,c-2", ""));
+        let resp = page("", "d", "").await.unwrap().into_inner();
+        assert_eq!(ids(&resp), "d-1");
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_promotion_refuses_sessions_changed_in_the_tenant() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let context = |tenant_id: &str| {
+            Some(TenantContext {
+                tenant_id: tenant_id.to_string(),
+                ..Default::default()
+            })
+        };
+        for session in ["s1", "s2"] {
+            storage.save_session("acme", session, b"PK\x03\x04doc").await.unwrap();
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: context("acme"),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry::default()),
+            }))
+            .await
+            .unwrap();
+        }
+        let edit = |tenant_id: &str, session: &str, wal_position: u64| {
+            Request::new(UpdateSessionInIndexRequest {
+                context: context(tenant_id),
+                session_id: session.to_string(),
+                wal_position: Some(wal_position),
+                ..Default::default()
+            })
+        };
+        let promote = |force: bool| {
+            Request::new(PromoteSandboxRequest {
+                context: context("acme"),
+                session_ids: Vec::new(),
+                force,
+                discard: false,
+            })
+        };
+
+        let created = svc
+            .create_sandbox(Request::new(CreateSandboxRequest {
+                context: context("acme"),
+                session_ids: vec!["s1".to_string(), "s2".to_string()],
+                replace: false,
+                ttl_seconds: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(created.sandbox_tenant_id, "acme~sandbox");
+        assert!(storage.session_exists("acme~sandbox", "s1").await.unwrap());
+
+        // Edits in the sandbox are promoted, replacing the tenant's document
+        storage.save_session("acme~sandbox", "s1", b"PK\x03\x04edited").await.unwrap();
+        svc.update_session_in_index(edit("acme~sandbox", "s1", 3)).await.unwrap();
+        let promoted = svc.promote_sandbox(promote(false)).await.unwrap().into_inner();
+        assert_eq!(promoted.session_ids, ["s1", "s2"]);
+        assert_eq!(
+            storage.load_session("acme", "s1").await.unwrap().unwrap(),
+            b"PK\x03\x04edited"
+        );
+        let wal_count =
+            |index: docx_storage_core::SessionIndex, id: &str| index.get(id).unwrap().wal_count;
+        assert_eq!(wal_count(storage.load_index("acme").await.unwrap().unwrap(), "s1"), 3);
+
+        // A session edited in the tenant since isn't overwritten unless forced
+        svc.update_session_in_index(edit("acme", "s2", 5)).await.unwrap();
+        let err = svc.promote_sandbox(promote(false)).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Aborted);
+        assert!(err.message().contains("s2"), "{}", err.message());
+        svc.promote_sandbox(promote(true)).await.unwrap();
+        assert_eq!(wal_count(storage.load_index("acme").await.unwrap().unwrap(), "s2"), 0);
+
+        let discarded = svc
+            .discard_sandbox(Request::new(DiscardSandboxRequest {
+                context: context("acme"),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(discarded.existed);
+        assert!(storage.load_index("acme~sandbox").await.unwrap().is_none());
+        assert!(storage.session_exists("acme", "s2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_index_adds_stored_sessions_and_reports_orphans() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let rebuild = |dry_run: bool, remove_orphans: bool| {
+            Request::new(RebuildIndexRequest {
+                context: Some(TenantContext {
+                    tenant_id: "acme".to_string(),
+                    ..Default::default()
+                }),
+                dry_run,
+                remove_orphans,
+            })
+        };
+
+        // "kept" is stored and indexed, "lost" was saved but never indexed,
+        // and "gone" is indexed without a document
+        for session in ["kept", "lost"] {
+            storage.save_session("acme", session, b"PK\x03\x04doc").await.unwrap();
+        }
+        storage.save_checkpoint("acme", "kept", 0, b"PK\x03\x04ckpt").await.unwrap();
+        for session in ["kept", "gone"] {
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: Some(TenantContext {
+                    tenant_id: "acme".to_string(),
+                    ..Default::default()
+                }),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry::default()),
+            }))
+            .await
+            .unwrap();
+        }
+
+        let report = svc.rebuild_index(rebuild(true, true)).await.unwrap().into_inner();
+        assert_eq!(report.added, ["lost"]);
+        assert_eq!(report.corrected, ["kept"]);
+        assert_eq!(report.orphans, ["gone"]);
+        assert_eq!(report.removed, ["gone"]);
+        assert!(!report.applied);
+        assert!(!storage.load_index("acme").await.unwrap().unwrap().contains("lost"));
+
+        // Orphans are only reported unless asked to remove them
+        let report = svc.rebuild_index(rebuild(false, false)).await.unwrap().into_inner();
+        assert!(report.applied);
+        assert!(report.removed.is_empty());
+        let index = storage.load_index("acme").await.unwrap().unwrap();
+        assert!(index.contains("lost"));
+        assert!(index.contains("gone"));
+        assert_eq!(index.get("kept").unwrap().checkpoint_positions, [0]);
+
+        let report = svc.rebuild_index(rebuild(false, true)).await.unwrap().into_inner();
+        assert_eq!(report.removed, ["gone"]);
+        assert!(!storage.load_index("acme").await.unwrap().unwrap().contains("gone"));
+
+        let report = svc.rebuild_index(rebuild(false, true)).await.unwrap().into_inner();
+        assert!(report.orphans.is_empty() && !report.applied);
+    }
+
+    #[tokio::test]
+    async fn test_append_wal_reports_when_the_checkpoint_policy_wants_one() {
+        let dir = TempDir::new().unwrap();
+        let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())))
+            .with_checkpoint_policy(CheckpointPolicy {
+                every_entries: 3,
+                every: None,
+                ops: vec!["replace_text".to_string()],
+            });
+        storage.save_session("acme", "s1", b"PK\x03\x04doc").await.unwrap();
+
+        let append = |position: u64, op: &str| {
+            let patch = format!(
+                r#"{{"version":1,"patches":"[{{\"op\":\"{}\"}}]","timestamp":"2026-01-01T00:00:00Z"}}"#,
+                op
+            );
+            svc.append_wal(Request::new(AppendWalRequest {
+                context: Some(TenantContext {
+                    tenant_id: "acme".to_string(),
+                    ..Default::default()
+                }),
+                session_id: "s1".to_string(),
+                entries: vec![WalEntry {
+                    position,
+                    patch_json: patch.into_bytes(),
+                    ..Default::default()
+                }],
+            }))
+        };
+
+        assert!(append(1, "add").await.unwrap().into_inner().skip_checkpoint);
+        assert!(append(2, "add").await.unwrap().into_inner().skip_checkpoint);
+        let third = append(3, "add").await.unwrap().into_inner();
+        assert!(!third.skip_checkpoint);
+        assert_eq!(third.checkpoint_reason, "3 entries since the last checkpoint");
+
+        storage.save_checkpoint("acme", "s1", 3, b"PK\x03\x04ckpt").await.unwrap();
+        assert!(append(4, "add").await.unwrap().into_inner().skip_checkpoint);
+        let replace = append(5, "replace_text").await.unwrap().into_inner();
+        assert!(!replace.skip_checkpoint);
+        assert_eq!(replace.checkpoint_reason, "replace_text entry");
+    }
+
+    #[tokio::test]
+    async fn test_append_wal_batch_reports_each_session() {
+        let dir = TempDir::new().unwrap();
+        let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+
+        let entry = |position: u64, patch_json: &str| WalEntry {
+            position,
+            patch_json: patch_json.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        let record = r#"{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:00Z"}"#;
+        let batch = |sessions: Vec<SessionWalAppend>| {
+            Request::new(AppendWalBatchRequest {
+                context: Some(TenantContext {
+                    tenant_id: "acme".to_string(),
+                    ..Default::default()
+                }),
+                sessions,
+            })
+        };
+
+        let response = svc
+            .append_wal_batch(batch(vec![
+                SessionWalAppend {
+                    session_id: "s1".to_string(),
+                    entries: vec![entry(1, record), entry(2, record)],
+                },
+                SessionWalAppend {
+                    session_id: "s2".to_string(),
+                    entries: vec![entry(1, "not json")],
+                },
+                SessionWalAppend {
+                    session_id: "s3".to_string(),
+                    entries: vec![entry(1, record)],
+                },
+            ]))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let outcomes: Vec<_> = response
+            .results
+            .iter()
+            .map(|r| (r.session_id.as_str(), r.success, r.new_position))
+            .collect();
+        assert_eq!(outcomes, [("s1", true, 2), ("s2", false, 0), ("s3", true, 1)]);
+        assert!(!response.results[1].error.is_empty());
+        assert!(storage.read_wal("acme", "s2", 0, None).await.unwrap().0.is_empty());
+        assert_eq!(storage.read_wal("acme", "s1", 0, None).await.unwrap().0.len(), 2);
+
+        let duplicate = SessionWalAppend {
+            session_id: "s1".to_string(),
+            entries: vec![entry(3, record)],
+        };
+        let status = svc
+            .append_wal_batch(batch(vec![duplicate.clone(), duplicate]))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_reads_ignore_later_edits() {
+        let dir = TempDir::new().unwrap();
+        let storage: Arc<dyn StorageBackend> = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let context = || {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            })
+        };
+        let entry = |marker: &str| docx_storage_core::WalEntry {
+            position: 0,
+            operation: String::new(),
+            path: String::new(),
+            patch_json: format!(
+                r#"{{"version":1,"patches":"[]","marker":"{}","timestamp":"2026-01-01T00:00:00Z"}}"#,
+                marker
+            )
+            .into_bytes(),
+            timestamp: chrono::Utc::now(),
+        };
+        storage.save_session("acme", "s1", b"PK\x03\x04v1").await.unwrap();
+        storage.append_wal("acme", "s1", &[entry("a"), entry("b")]).await.unwrap();
+
+        let opened = svc
+            .open_snapshot(Request::new(OpenSnapshotRequest {
+                context: context(),
+                session_id: "s1".to_string(),
+                position: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(opened.found);
+        assert_eq!(opened.position, 2);
+
+        // The session moves on: an undo, another edit, a new baseline
+        storage.truncate_wal("acme", "s1", 1).await.unwrap();
+        storage.append_wal("acme", "s1", &[entry("c")]).await.unwrap();
+        storage.save_session("acme", "s1", b"PK\x03\x04v2").await.unwrap();
+
+        let read = |session_id: &str, snapshot_id: &str| {
+            svc.load_session_with_history(Request::new(LoadSessionWithHistoryRequest {
+                context: context(),
+                session_id: session_id.to_string(),
+                position: None,
+                snapshot_id: snapshot_id.to_string(),
+            }))
+        };
+        let chunks: Vec<SessionHistoryChunk> = read("", &opened.snapshot_id)
+            .await
+            .unwrap()
+            .into_inner()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let data: Vec<u8> = chunks.iter().flat_map(|c| c.data.clone()).collect();
+        let patches: Vec<String> = chunks
+            .iter()
+            .flat_map(|c| &c.entries)
+            .map(|e| String::from_utf8(e.patch_json.clone()).unwrap())
+            .collect();
+        assert_eq!(data, b"PK\x03\x04v1");
+        assert_eq!(chunks[0].target_position, 2);
+        assert_eq!(patches.len(), 2);
+        assert!(patches[1].contains(r#""marker":"b""#));
+
+        let other = read("s2", &opened.snapshot_id).await.err().unwrap();
+        assert_eq!(other.code(), tonic::Code::InvalidArgument);
+
+        let closed = svc
+            .close_snapshot(Request::new(CloseSnapshotRequest {
+                context: context(),
+                snapshot_id: opened.snapshot_id.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(closed.existed);
+        let gone = read("s1", &opened.snapshot_id).await.err().unwrap();
+        assert_eq!(gone.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_export_tenant_corpus_resumes_after_the_token() {
+        use std::io::Write;
+
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())))
+            .with_corpus_export_rate(0);
+        let context = || {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            })
+        };
+
+        let mut package = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        package
+            .start_file("word/document.xml", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        package
+            .write_all(b"<w:document><w:p><w:r><w:t>Quarterly report</w:t></w:r></w:p></w:document>")
+            .unwrap();
+        let package = package.finish().unwrap().into_inner();
+        storage.save_session("acme", "a", &package).await.unwrap();
+        storage.save_session("acme", "b", b"not a package").await.unwrap();
+        for session in ["b", "a"] {
+            svc.add_session_to_index(Request::new(AddSessionToIndexRequest {
+                context: context(),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry::default()),
+            }))
+            .await
+            .unwrap();
+        }
+
+        let export = |format: &str, resume_token: &str| {
+            svc.export_tenant_corpus(Request::new(ExportTenantCorpusRequest {
+                context: context(),
+                format: format.to_string(),
+                resume_token: resume_token.to_string(),
+                max_sessions_per_sec: 0,
+            }))
+        };
+        let chunks: Vec<CorpusChunk> = export("jsonl", "")
+            .await
+            .unwrap()
+            .into_inner()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let records: Vec<CorpusRecord> = chunks
+            .iter()
+            .map(|c| serde_json::from_slice(&c.data).unwrap())
+            .collect();
+        assert_eq!(chunks[0].resume_token, "a");
+        assert_eq!(records[0].text, "Quarterly report");
+        assert!(records[0].error.is_none());
+        assert_eq!(records[1].session_id, "b");
+        assert!(records[1].error.is_some());
+
+        let rest: Vec<CorpusChunk> = export("", "a")
+            .await
+            .unwrap()
+            .into_inner()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].resume_token, "b");
+
+        let err = export("csv", "").await.err().unwrap();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_admin_actions_are_logged_with_their_actor() {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(LocalStorage::new(dir.path()));
+        let svc = StorageServiceImpl::new(storage.clone(), Arc::new(FileLock::new(dir.path())));
+        let context = |actor: &str| {
+            Some(TenantContext {
+                tenant_id: "acme".to_string(),
+                actor: actor.to_string(),
+            })
+        };
+        let entry = docx_storage_core::WalEntry {
+            position: 0,
+            operation: String::new(),
+            path: String::new(),
+            patch_json: br#"{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:00Z"}"#.to_vec(),
+            timestamp: chrono::Utc::now(),
+        };
+        storage.save_session("acme", "s1", b"PK\x03\x04doc").await.unwrap();
+        storage.append_wal("acme", "s1", &[entry.clone(), entry]).await.unwrap();
+
+        svc.truncate_wal(Request::new(TruncateWalRequest {
+            context: context("alice"),
+            session_id: "s1".to_string(),
+            keep_from_position: 1,
+        }))
+        .await
+        .unwrap();
+        for _ in 0..2 {
+            // Deleting a missing session isn't an action
+            svc.delete_session(Request::new(DeleteSessionRequest {
+                context: context(""),
+                session_id: "s1".to_string(),
+            }))
+            .await
+            .unwrap();
+        }
+        let err = svc
+            .record_admin_action(Request::new(RecordAdminActionRequest {
+                context: context("ops"),
+                action: "delete_session".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        let recorded = svc
+            .record_admin_action(Request::new(RecordAdminActionRequest {
+                context: context("ops"),
+                action: "key_rotation".to_string(),
+                detail: "rotated erasure key".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(recorded.position, 3);
+
+        let read = |from_position: u64, limit: u64| {
+            svc.read_admin_log(Request::new(ReadAdminLogRequest {
+                context: context("auditor"),
+                from_position,
+                limit,
+            }))
+        };
+        let log = read(0, 0).await.unwrap().into_inner();
+        assert!(!log.has_more);
+        let summary: Vec<_> = log
+            .entries
+            .iter()
+            .map(|e| (e.position, e.action.as_str(), e.actor.as_str(), e.session_id.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, "truncate_wal", "alice", "s1"),
+                (2, "delete_session", "unknown", "s1"),
+                (3, "key_rotation", "ops", ""),
+            ]
+        );
+        assert_eq!(log.entries[0].detail, "entries removed: 1, keep_from_position: 1");
+
+        let page = read(2, 1).await.unwrap().into_inner();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].position, 2);
+        assert!(page.has_more);
+    }
+
+    #[test]
+    fn test_corpus_pace_is_the_slower_of_server_and_client() {
+        assert_eq!(corpus_pace(10, 0), 10);
+        assert_eq!(corpus_pace(0, 4), 4);
+        assert_eq!(corpus_pace(10, 4), 4);
+        assert_eq!(corpus_pace(0, 0), 0);
+    }
 }
@@ -1,11 +1,15 @@
 use std::pin::Pin;
 use std::sync::Arc;
 
-use docx_storage_core::{BrowsableBackend, SourceDescriptor, SourceType, SyncBackend};
+use docx_storage_core::{
+    Activity, BrowsableBackend, FileListResult, FileSearchQuery, SourceDescriptor, SourceType, SyncBackend,
+    validate_session_id, validate_tenant_id,
+};
 use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
 use tracing::{debug, instrument};
 
+use crate::notifier::Notifier;
 use crate::service::proto;
 use proto::source_sync_service_server::SourceSyncService;
 use proto::*;
@@ -16,18 +20,27 @@ type DownloadStream = Pin<Box<dyn Stream<Item = Result<DataChunk, Status>> + Sen
 pub struct SourceSyncServiceImpl {
     sync_backend: Arc<dyn SyncBackend>,
     browse_backend: Arc<dyn BrowsableBackend>,
+    notifier: Option<Arc<Notifier>>,
 }
 
 impl SourceSyncServiceImpl {
     pub fn new(sync_backend: Arc<dyn SyncBackend>, browse_backend: Arc<dyn BrowsableBackend>) -> Self {
-        Self { sync_backend, browse_backend }
+        Self { sync_backend, browse_backend, notifier: None }
+    }
+
+    /// Report the outcome of SyncToSource calls to `notifier`.
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
     }
 
     /// Extract tenant_id from request context.
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
     }
 
     /// Convert proto SourceType to core SourceType.
@@ -86,6 +99,26 @@ impl SourceSyncServiceImpl {
             last_error: status.last_error.clone().unwrap_or_default(),
         }
     }
+
+    /// Convert core FileListResult to proto ListConnectionFilesResponse.
+    fn to_proto_file_list(result: FileListResult) -> ListConnectionFilesResponse {
+        ListConnectionFilesResponse {
+            files: result
+                .files
+                .into_iter()
+                .map(|f| proto::FileEntry {
+                    name: f.name,
+                    path: f.path,
+                    file_id: f.file_id.unwrap_or_default(),
+                    is_folder: f.is_folder,
+                    size_bytes: f.size_bytes as i64,
+                    modified_at_unix: f.modified_at,
+                    mime_type: f.mime_type.unwrap_or_default(),
+                })
+                .collect(),
+            next_page_token: result.next_page_token.unwrap_or_default(),
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -99,6 +132,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
     ) -> Result<Response<RegisterSourceResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        validate_session_id(&req.session_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
 
         let source = Self::convert_source_descriptor(req.source.as_ref())
             .ok_or_else(|| Status::invalid_argument("source is required"))?;
@@ -214,6 +248,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
 
         let tenant_id = tenant_id
             .ok_or_else(|| Status::invalid_argument("tenant context is required in first chunk"))?;
+        validate_tenant_id(&tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
         let session_id = session_id
             .filter(|s| !s.is_empty())
             .ok_or_else(|| Status::invalid_argument("session_id is required in first chunk"))?;
@@ -225,18 +260,30 @@ impl SourceSyncService for SourceSyncServiceImpl {
             session_id
         );
 
-        match self
+        let result = self
             .sync_backend
             .sync_to_source(&tenant_id, &session_id, &data)
-            .await
-        {
+            .await;
+
+        if let Some(notifier) = &self.notifier {
+            let activity = match &result {
+                Ok(_) => Activity::Synced,
+                Err(docx_storage_core::StorageError::Unavailable(_)) => Activity::SyncQueued,
+                Err(e) => Activity::SyncFailed(e.to_string()),
+            };
+            notifier.record(&tenant_id, &session_id, activity);
+        }
+
+        match result {
             Ok(synced_at) => Ok(Response::new(SyncToSourceResponse {
                 success: true,
                 error: String::new(),
                 synced_at_unix: synced_at,
+                queued: false,
             })),
             Err(e) => Ok(Response::new(SyncToSourceResponse {
                 success: false,
+                queued: matches!(e, docx_storage_core::StorageError::Unavailable(_)),
                 error: e.to_string(),
                 synced_at_unix: 0,
             })),
@@ -287,6 +334,93 @@ impl SourceSyncService for SourceSyncServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_pending_syncs(
+        &self,
+        request: Request<ListPendingSyncsRequest>,
+    ) -> Result<Response<ListPendingSyncsResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let pending = self
+            .sync_backend
+            .list_pending_syncs(tenant_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let syncs = pending
+            .iter()
+            .map(|p| proto::PendingSync {
+                session_id: p.session_id.clone(),
+                source: Some(Self::to_proto_source_descriptor(&p.source)),
+                queued_at_unix: p.queued_at,
+                attempts: p.attempts as i32,
+                next_attempt_at_unix: p.next_attempt_at,
+                last_error: p.last_error.clone(),
+                size_bytes: p.size_bytes as i64,
+            })
+            .collect();
+
+        Ok(Response::new(ListPendingSyncsResponse { syncs }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn list_recent_files(
+        &self,
+        request: Request<ListRecentFilesRequest>,
+    ) -> Result<Response<ListRecentFilesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let recent = self
+            .sync_backend
+            .list_recent_files(tenant_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let limit = if req.limit > 0 { req.limit as usize } else { usize::MAX };
+        let files = recent
+            .iter()
+            .filter(|f| f.favorite || !req.favorites_only)
+            .take(limit)
+            .map(|f| proto::RecentFile {
+                source: Some(Self::to_proto_source_descriptor(&f.source)),
+                display_name: f.display_name.clone(),
+                last_used_at_unix: f.last_used_at,
+                favorite: f.favorite,
+            })
+            .collect();
+
+        Ok(Response::new(ListRecentFilesResponse { files }))
+    }
+
+    #[instrument(skip(self, request), level = "debug")]
+    async fn pin_favorite(
+        &self,
+        request: Request<PinFavoriteRequest>,
+    ) -> Result<Response<PinFavoriteResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let source = Self::convert_source_descriptor(req.source.as_ref())
+            .ok_or_else(|| Status::invalid_argument("source is required"))?;
+
+        match self
+            .sync_backend
+            .pin_favorite(tenant_id, source, req.pinned)
+            .await
+        {
+            Ok(()) => Ok(Response::new(PinFavoriteResponse {
+                success: true,
+                error: String::new(),
+            })),
+            Err(e) => Ok(Response::new(PinFavoriteResponse {
+                success: false,
+                error: e.to_string(),
+            })),
+        }
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn list_connections(
         &self,
@@ -308,6 +442,7 @@ impl SourceSyncService for SourceSyncServiceImpl {
                 r#type: Self::to_proto_source_type(c.source_type),
                 display_name: c.display_name,
                 provider_account_id: c.provider_account_id.unwrap_or_default(),
+                backend: c.backend.unwrap_or_default(),
             })
             .collect();
 
@@ -339,24 +474,34 @@ impl SourceSyncService for SourceSyncServiceImpl {
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        let proto_files: Vec<proto::FileEntry> = result
-            .files
-            .into_iter()
-            .map(|f| proto::FileEntry {
-                name: f.name,
-                path: f.path,
-                file_id: f.file_id.unwrap_or_default(),
-                is_folder: f.is_folder,
-                size_bytes: f.size_bytes as i64,
-                modified_at_unix: f.modified_at,
-                mime_type: f.mime_type.unwrap_or_default(),
-            })
-            .collect();
+        Ok(Response::new(Self::to_proto_file_list(result)))
+    }
 
-        Ok(Response::new(ListConnectionFilesResponse {
-            files: proto_files,
-            next_page_token: result.next_page_token.unwrap_or_default(),
-        }))
+    #[instrument(skip(self, request), level = "debug")]
+    async fn search_connection_files(
+        &self,
+        request: Request<SearchConnectionFilesRequest>,
+    ) -> Result<Response<ListConnectionFilesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        let query = FileSearchQuery {
+            query: req.query,
+            path: req.path,
+            recursive: req.recursive,
+            mime_filter: non_empty(req.mime_filter),
+            page_token: non_empty(req.page_token),
+            page_size: if req.page_size > 0 { req.page_size as u32 } else { 50 },
+        };
+
+        let result = self
+            .browse_backend
+            .search_files(tenant_id, &req.connection_id, &query)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(Self::to_proto_file_list(result)))
     }
 
     #[instrument(skip(self, request), level = "debug")]
@@ -1,31 +1,54 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use docx_storage_core::{SourceDescriptor, SourceType, WatchBackend};
+use docx_storage_core::{
+    Activity, DurableChangeQueue, QueuedChange, SourceDescriptor, SourceType, WatchBackend,
+    validate_session_id, validate_tenant_id,
+};
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tonic::{Request, Response, Status};
 use tracing::{debug, instrument, warn};
 
+use crate::notifier::Notifier;
 use crate::service::proto;
 use proto::external_watch_service_server::ExternalWatchService;
 use proto::*;
 
+/// How long a `WatchChanges` stream waits for an ack before resending an event.
+const REDELIVERY_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Implementation of the ExternalWatchService gRPC service.
 pub struct ExternalWatchServiceImpl {
     watch_backend: Arc<dyn WatchBackend>,
+    change_queue: Arc<DurableChangeQueue>,
+    notifier: Option<Arc<Notifier>>,
 }
 
 impl ExternalWatchServiceImpl {
-    pub fn new(watch_backend: Arc<dyn WatchBackend>) -> Self {
-        Self { watch_backend }
+    pub fn new(watch_backend: Arc<dyn WatchBackend>, change_queue: Arc<DurableChangeQueue>) -> Self {
+        Self {
+            watch_backend,
+            change_queue,
+            notifier: None,
+        }
+    }
+
+    /// Report the external changes found by WatchChanges to `notifier`.
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
     }
 
     /// Extract tenant_id from request context.
     fn get_tenant_id(context: Option<&TenantContext>) -> Result<&str, Status> {
-        context
+        let tenant_id = context
             .map(|c| c.tenant_id.as_str())
-            .ok_or_else(|| Status::invalid_argument("tenant context is required"))
+            .ok_or_else(|| Status::invalid_argument("tenant context is required"))?;
+        validate_tenant_id(tenant_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(tenant_id)
     }
 
     /// Convert proto SourceType to core SourceType.
@@ -74,6 +97,22 @@ impl ExternalWatchServiceImpl {
             docx_storage_core::ExternalChangeType::Deleted => 2,
             docx_storage_core::ExternalChangeType::Renamed => 3,
             docx_storage_core::ExternalChangeType::PermissionChanged => 4,
+            docx_storage_core::ExternalChangeType::Created => 5,
+        }
+    }
+
+    /// Convert a queued change to a proto ExternalChangeEvent.
+    fn to_proto_event(queued: &QueuedChange) -> ExternalChangeEvent {
+        let change = &queued.event;
+        ExternalChangeEvent {
+            session_id: change.session_id.clone(),
+            change_type: Self::to_proto_change_type(change.change_type),
+            old_metadata: change.old_metadata.as_ref().map(Self::to_proto_source_metadata),
+            new_metadata: change.new_metadata.as_ref().map(Self::to_proto_source_metadata),
+            detected_at_unix: change.detected_at,
+            new_uri: change.new_uri.clone().unwrap_or_default(),
+            sequence: queued.sequence,
+            coalesced_count: queued.coalesced,
         }
     }
 }
@@ -91,6 +130,7 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
     ) -> Result<Response<StartWatchResponse>, Status> {
         let req = request.into_inner();
         let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+        validate_session_id(&req.session_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
 
         let source = Self::convert_source_descriptor(req.source.as_ref())
             .ok_or_else(|| Status::invalid_argument("source is required"))?;
@@ -132,6 +172,12 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
+        // Nobody will handle queued changes for a session that is no longer watched
+        self.change_queue
+            .remove_session(tenant_id, &req.session_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
         debug!(
             "Stopped watching for tenant {} session {}",
             tenant_id, req.session_id
@@ -190,32 +236,34 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
 
         let (tx, rx) = mpsc::channel(100);
         let watch_backend = self.watch_backend.clone();
+        let change_queue = self.change_queue.clone();
+        let notifier = self.notifier.clone();
 
-        // Spawn a task that polls for changes
+        // Spawn a task that polls for changes, queues them (coalescing bursts
+        // per session) and sends whatever is still unacknowledged
         tokio::spawn(async move {
+            // When each queued event was last sent on this stream
+            let mut sent_at: HashMap<(String, u64), Instant> = HashMap::new();
+
             loop {
                 // Check each session for changes
                 for session_id in &session_ids {
                     match watch_backend.check_for_changes(&tenant_id, session_id).await {
                         Ok(Some(change)) => {
-                            let proto_event = ExternalChangeEvent {
-                                session_id: change.session_id.clone(),
-                                change_type: Self::to_proto_change_type(change.change_type),
-                                old_metadata: change
-                                    .old_metadata
-                                    .as_ref()
-                                    .map(Self::to_proto_source_metadata),
-                                new_metadata: change
-                                    .new_metadata
-                                    .as_ref()
-                                    .map(Self::to_proto_source_metadata),
-                                detected_at_unix: change.detected_at,
-                                new_uri: change.new_uri.clone().unwrap_or_default(),
-                            };
-
-                            if tx.send(Ok(proto_event)).await.is_err() {
-                                // Client disconnected
-                                return;
+                            match change_queue.enqueue(&tenant_id, change).await {
+                                Ok(Some(queued)) => {
+                                    if let Some(notifier) = &notifier {
+                                        notifier.record(
+                                            &tenant_id,
+                                            session_id,
+                                            Activity::ExternalChange(queued.event.change_type),
+                                        );
+                                    }
+                                }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    warn!("Failed to queue change for session {}: {}", session_id, e);
+                                }
                             }
                         }
                         Ok(None) => {}
@@ -228,6 +276,33 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
                     }
                 }
 
+                match change_queue.pending(&tenant_id, &session_ids).await {
+                    Ok(pending) => {
+                        sent_at.retain(|(session_id, sequence), _| {
+                            pending
+                                .iter()
+                                .any(|p| p.sequence == *sequence && &p.event.session_id == session_id)
+                        });
+
+                        for queued in &pending {
+                            let key = (queued.event.session_id.clone(), queued.sequence);
+                            if sent_at
+                                .get(&key)
+                                .is_some_and(|at| at.elapsed() < REDELIVERY_INTERVAL)
+                            {
+                                continue;
+                            }
+
+                            if tx.send(Ok(Self::to_proto_event(queued))).await.is_err() {
+                                // Client disconnected; unacked events stay queued
+                                return;
+                            }
+                            sent_at.insert(key, Instant::now());
+                        }
+                    }
+                    Err(e) => warn!("Failed to read change queue for tenant {}: {}", tenant_id, e),
+                }
+
                 // Sleep before next poll cycle
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
@@ -236,6 +311,46 @@ impl ExternalWatchService for ExternalWatchServiceImpl {
         Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
     }
 
+    #[instrument(skip(self, request), level = "debug")]
+    async fn ack_changes(
+        &self,
+        request: Request<AckChangesRequest>,
+    ) -> Result<Response<AckChangesResponse>, Status> {
+        let req = request.into_inner();
+        let tenant_id = Self::get_tenant_id(req.context.as_ref())?;
+
+        let mut acknowledged = 0;
+        for ack in &req.acks {
+            match self
+                .change_queue
+                .ack(tenant_id, &ack.session_id, ack.sequence)
+                .await
+            {
+                Ok(true) => acknowledged += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    return Ok(Response::new(AckChangesResponse {
+                        success: false,
+                        acknowledged,
+                        error: e.to_string(),
+                    }))
+                }
+            }
+        }
+
+        debug!(
+            "Acknowledged {} of {} changes for tenant {}",
+            acknowledged,
+            req.acks.len(),
+            tenant_id
+        );
+        Ok(Response::new(AckChangesResponse {
+            success: true,
+            acknowledged,
+            error: String::new(),
+        }))
+    }
+
     #[instrument(skip(self, request), level = "debug")]
     async fn get_source_metadata(
         &self,
@@ -2,11 +2,22 @@ use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
 use docx_storage_core::{
-    CheckpointInfo, SessionIndex, SessionInfo, StorageBackend, StorageError, WalEntry,
+    admin_log_jsonl, admin_log_page, buffer_chunks, chain_events, check_wal_positions,
+    delete_unreferenced_media, digest_chunks, document_digest, feature, history_jsonl,
+    load_session_with_history, media_references, parse_admin_log, parse_history,
+    parse_wal_entries, prepare_wal_entries, restore_media, store_media, tail_page, tenant_dir,
+    validate_session_id, validate_tenant_id, AdminLogEntry, BufferedChunks, Capabilities,
+    CheckpointInfo, ChunkStream, HistoryEvent, HistoryLink, LibraryItemInfo, LibraryKind,
+    SessionIndex, SessionInfo, SessionWithHistory, StorageBackend, StorageError, StorageReport,
+    StoredFile, WalEntry, WalOffsetIndex, MEDIA_MAX_BUFFERED_BYTES, STALE_FILE_AGE,
 };
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 #[cfg(test)]
 use docx_storage_core::SessionIndexEntry;
+use futures::StreamExt;
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tracing::{debug, instrument, warn};
 
 /// Local filesystem storage backend.
@@ -17,13 +28,83 @@ use tracing::{debug, instrument, warn};
 ///   {tenant_id}/
 ///     sessions/
 ///       index.json
+///       admin.log                     # admin actions (JSONL), see append_admin_log
 ///       {session_id}.docx
 ///       {session_id}.wal
+///       {session_id}.wal.idx          # sparse WAL offset index
+///       {session_id}.chain            # history chain, see with_history_chain
 ///       {session_id}.ckpt.{position}.docx
+///     templates/
+///       {name}.docx
+///     snippets/
+///       {name}.docx
+///     glossaries/
+///       {name}.json
+///     profiles/
+///       {name}.json                   # tenant profile (template variables)
+///     media/
+///       {sha256}.bin                  # images shared by sessions, see with_media_dedup
+///     snapshots/
+///       {uuid}/sessions/              # see SessionSnapshot
 /// ```
 #[derive(Debug, Clone)]
 pub struct LocalStorage {
     base_dir: PathBuf,
+    /// Move images out of saved packages into `media/` (loads always put them back)
+    media_dedup: bool,
+    /// Record WAL appends, truncations and checkpoints in a hash chain
+    history_chain: bool,
+    /// Media directory of the tenant a [`SessionSnapshot`] was taken from
+    snapshot_media: Option<PathBuf>,
+    /// Serializes admin log appends, which number entries after the last one
+    admin_log_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+/// Directory of a tenant holding [`SessionSnapshot`]s.
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Times a snapshot is taken before settling for one a write raced with.
+const SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// A session's files frozen at one point in time, readable through
+/// [`Self::storage`] under the empty tenant ID.
+///
+/// The files are hard-linked (copied where links aren't supported) into a
+/// private directory. Writers never modify session files in place, they
+/// rename a new file over the old one, so linked files don't change while the
+/// snapshot is read: reads take no lock and writers never wait for them. The
+/// directory is removed when the snapshot is dropped.
+#[derive(Debug)]
+pub struct SessionSnapshot {
+    storage: LocalStorage,
+}
+
+impl SessionSnapshot {
+    /// Storage holding only the frozen session, under tenant `""`.
+    pub fn storage(&self) -> &LocalStorage {
+        &self.storage
+    }
+}
+
+impl Drop for SessionSnapshot {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.storage.base_dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove snapshot {}: {}", self.storage.base_dir.display(), e);
+            }
+        }
+    }
+}
+
+/// What tells one version of a file from the next. Every write replaces the
+/// file, which gives it a new inode on Unix; elsewhere size and mtime have to do.
+async fn file_version(path: &Path) -> Option<(u64, Option<std::time::SystemTime>, u64)> {
+    let metadata = fs::metadata(path).await.ok()?;
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    Some((metadata.len(), metadata.modified().ok(), inode))
 }
 
 /// ZIP file signature (PK\x03\x04)
@@ -38,9 +119,78 @@ impl LocalStorage {
     pub fn new(base_dir: impl AsRef<Path>) -> Self {
         Self {
             base_dir: base_dir.as_ref().to_path_buf(),
+            media_dedup: false,
+            history_chain: false,
+            snapshot_media: None,
+            admin_log_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
+    /// Store the images of saved sessions and checkpoints once per tenant,
+    /// in `media/`, instead of in every package (see [`store_media`]).
+    /// Packages saved with deduplicated media load fine either way.
+    pub fn with_media_dedup(mut self, enabled: bool) -> Self {
+        self.media_dedup = enabled;
+        self
+    }
+
+    /// Keep a tamper-evident chain of each session's WAL appends,
+    /// truncations and checkpoints beside its WAL (see
+    /// [`prove_history`](docx_storage_core::prove_history)).
+    pub fn with_history_chain(mut self, enabled: bool) -> Self {
+        self.history_chain = enabled;
+        self
+    }
+
+    /// Append links recording `events` to the session's history chain.
+    /// Callers hold the session lock, like for the WAL writes they record.
+    async fn extend_history(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        events: &[HistoryEvent],
+    ) -> Result<(), StorageError> {
+        if !self.history_chain || events.is_empty() {
+            return Ok(());
+        }
+        let path = self.history_path(tenant_id, session_id)?;
+        let links = self.read_history(tenant_id, session_id).await?;
+        let jsonl = history_jsonl(&chain_events(
+            links.last(),
+            tenant_id,
+            session_id,
+            events,
+            chrono::Utc::now(),
+        ))?;
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to open history chain: {}", e)))?;
+        file.write_all(&jsonl)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to write history chain: {}", e)))?;
+        file.sync_data()
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to sync history chain: {}", e)))?;
+        Ok(())
+    }
+
+    /// The package to write for `data`: with its media moved out when
+    /// deduplication is on.
+    async fn package_to_save(
+        &self,
+        tenant_id: &str,
+        data: &[u8],
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        if !self.media_dedup {
+            return Ok(None);
+        }
+        store_media(self, tenant_id, data).await
+    }
+
     /// Strip the .NET header prefix if present.
     ///
     /// The .NET code writes session/checkpoint files with an 8-byte length prefix
@@ -72,36 +222,287 @@ impl LocalStorage {
     }
 
     /// Get the sessions directory for a tenant.
-    fn sessions_dir(&self, tenant_id: &str) -> PathBuf {
-        self.base_dir.join(tenant_id).join("sessions")
+    fn sessions_dir(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join("sessions"))
     }
 
     /// Get the path to a session file.
-    fn session_path(&self, tenant_id: &str, session_id: &str) -> PathBuf {
-        self.sessions_dir(tenant_id)
-            .join(format!("{}.docx", session_id))
+    fn session_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self
+            .sessions_dir(tenant_id)?
+            .join(format!("{}.docx", session_id)))
     }
 
     /// Get the path to a session's WAL file.
-    fn wal_path(&self, tenant_id: &str, session_id: &str) -> PathBuf {
-        self.sessions_dir(tenant_id)
-            .join(format!("{}.wal", session_id))
+    fn wal_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self
+            .sessions_dir(tenant_id)?
+            .join(format!("{}.wal", session_id)))
+    }
+
+    /// Get the path to a session's WAL offset index.
+    fn wal_index_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self
+            .sessions_dir(tenant_id)?
+            .join(format!("{}.wal.idx", session_id)))
+    }
+
+    /// Get the path to a session's history chain.
+    fn history_path(&self, tenant_id: &str, session_id: &str) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self
+            .sessions_dir(tenant_id)?
+            .join(format!("{}.chain", session_id)))
     }
 
     /// Get the path to a checkpoint file.
-    fn checkpoint_path(&self, tenant_id: &str, session_id: &str, position: u64) -> PathBuf {
-        self.sessions_dir(tenant_id)
-            .join(format!("{}.ckpt.{}.docx", session_id, position))
+    fn checkpoint_path(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+    ) -> Result<PathBuf, StorageError> {
+        validate_session_id(session_id)?;
+        Ok(self
+            .sessions_dir(tenant_id)?
+            .join(format!("{}.ckpt.{}.docx", session_id, position)))
+    }
+
+    /// Get the path to the tenant's admin log.
+    fn admin_log_path(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(self.sessions_dir(tenant_id)?.join("admin.log"))
     }
 
     /// Get the path to the index file.
-    fn index_path(&self, tenant_id: &str) -> PathBuf {
-        self.sessions_dir(tenant_id).join("index.json")
+    fn index_path(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(self.sessions_dir(tenant_id)?.join("index.json"))
+    }
+
+    /// Get the library directory for a tenant and item kind.
+    fn library_dir(&self, tenant_id: &str, kind: LibraryKind) -> Result<PathBuf, StorageError> {
+        if let (LibraryKind::Media, Some(dir)) = (kind, &self.snapshot_media) {
+            return Ok(dir.clone());
+        }
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join(kind.dir_name()))
+    }
+
+    /// Get the path to a library item file.
+    fn library_item_path(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<PathBuf, StorageError> {
+        kind.validate_name(name)?;
+        Ok(self
+            .library_dir(tenant_id, kind)?
+            .join(format!("{}.{}", name, kind.extension())))
+    }
+
+    /// Write the offset index of freshly written WAL data. Best effort: a
+    /// missing or stale index only makes the next read rebuild it.
+    async fn save_wal_index(&self, path: &Path, jsonl: &[u8]) {
+        let index = WalOffsetIndex::build(jsonl);
+        let json = match serde_json::to_vec(&index) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize WAL index: {}", e);
+                return;
+            }
+        };
+        let temp_path = path.with_extension("idx.tmp");
+        if let Err(e) = fs::write(&temp_path, &json).await {
+            warn!("Failed to write WAL index {}: {}", path.display(), e);
+            return;
+        }
+        if let Err(e) = fs::rename(&temp_path, path).await {
+            warn!("Failed to rename WAL index {}: {}", path.display(), e);
+        }
+    }
+
+    /// Open a session's WAL along with its offset index, rebuilding the
+    /// index when it is missing or was built for other data. Returns `None`
+    /// when the WAL doesn't exist.
+    ///
+    /// WAL writes replace the file, so reading through the returned handle
+    /// stays consistent with the index even if the WAL is rewritten meanwhile.
+    async fn open_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<(fs::File, WalOffsetIndex)>, StorageError> {
+        let path = self.wal_path(tenant_id, session_id)?;
+        let io_err =
+            |e: std::io::Error| StorageError::Io(format!("Failed to read WAL {}: {}", path.display(), e));
+
+        let mut file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_err(e)),
+        };
+        let file_len = file.metadata().await.map_err(io_err)?.len();
+        if file_len < 8 {
+            return Ok(Some((file, WalOffsetIndex::default())));
+        }
+
+        // .NET MappedWal header: little-endian i64 data length, then JSONL
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).await.map_err(io_err)?;
+        let data_len = (i64::from_le_bytes(header).max(0) as u64).min(file_len - 8);
+
+        let index_path = self.wal_index_path(tenant_id, session_id)?;
+        if let Ok(bytes) = fs::read(&index_path).await {
+            match serde_json::from_slice::<WalOffsetIndex>(&bytes) {
+                Ok(index) if index.data_len == data_len => return Ok(Some((file, index))),
+                Ok(_) => debug!("WAL index {} is stale, rebuilding", index_path.display()),
+                Err(e) => warn!("Ignoring corrupt WAL index {}: {}", index_path.display(), e),
+            }
+        }
+
+        let mut jsonl = vec![0u8; data_len as usize];
+        file.read_exact(&mut jsonl).await.map_err(io_err)?;
+        self.save_wal_index(&index_path, &jsonl).await;
+        Ok(Some((file, WalOffsetIndex::build(&jsonl))))
+    }
+
+    /// Read bytes `start..end` of the JSONL data of a WAL opened by [`Self::open_wal`].
+    async fn read_wal_range(
+        file: &mut fs::File,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        let io_err = |e: std::io::Error| StorageError::Io(format!("Failed to read WAL: {}", e));
+        file.seek(std::io::SeekFrom::Start(8 + start))
+            .await
+            .map_err(io_err)?;
+        let mut data = vec![0u8; (end - start) as usize];
+        file.read_exact(&mut data).await.map_err(io_err)?;
+        Ok(data)
+    }
+
+    /// Write `chunks` to `path` as they arrive, through a temp file renamed
+    /// into place once complete. Returns the size written; on failure the
+    /// temp file is removed and `path` is left as it was.
+    async fn write_chunks_atomically(
+        path: &Path,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let temp_path = path.with_extension("docx.tmp");
+        let written = async {
+            let mut file = fs::File::create(&temp_path).await.map_err(|e| {
+                StorageError::from_io(format!("Failed to create {}", temp_path.display()), e)
+            })?;
+            let mut written = 0u64;
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await.map_err(|e| {
+                    StorageError::from_io(format!("Failed to write {}", temp_path.display()), e)
+                })?;
+                written += chunk.len() as u64;
+            }
+            file.flush().await.map_err(|e| {
+                StorageError::from_io(format!("Failed to write {}", temp_path.display()), e)
+            })?;
+            Ok::<_, StorageError>(written)
+        }
+        .await;
+
+        let written = match written {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path).await;
+                return Err(e);
+            }
+        };
+        fs::rename(&temp_path, path).await.map_err(|e| {
+            StorageError::Io(format!("Failed to rename to {}: {}", path.display(), e))
+        })?;
+        Ok(written)
+    }
+
+    /// Freeze a session's index, WAL, checkpoints and baseline for reading.
+    ///
+    /// The files are linked one after the other, not atomically. The WAL and
+    /// the baseline are checked again once all are linked: if either was
+    /// replaced meanwhile, the other files may not match it, and the snapshot
+    /// is taken again.
+    pub async fn snapshot_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<SessionSnapshot, StorageError> {
+        let wal_path = self.wal_path(tenant_id, session_id)?;
+        let session_path = self.session_path(tenant_id, session_id)?;
+        let root = tenant_dir(&self.base_dir, tenant_id)?.join(SNAPSHOTS_DIR);
+
+        // Blobs are never modified, so the snapshot reads them from the tenant
+        let media = self.library_dir(tenant_id, LibraryKind::Media)?;
+
+        let mut attempt = 1;
+        loop {
+            let snapshot = SessionSnapshot {
+                storage: LocalStorage {
+                    snapshot_media: Some(media.clone()),
+                    ..LocalStorage::new(root.join(uuid::Uuid::new_v4().to_string()))
+                },
+            };
+            let dir = snapshot.storage.sessions_dir("")?;
+            fs::create_dir_all(&dir).await.map_err(|e| {
+                StorageError::Io(format!("Failed to create snapshot dir {}: {}", dir.display(), e))
+            })?;
+
+            let before = (file_version(&wal_path).await, file_version(&session_path).await);
+            let mut sources = vec![
+                self.index_path(tenant_id)?,
+                wal_path.clone(),
+                self.wal_index_path(tenant_id, session_id)?,
+            ];
+            for checkpoint in self.list_checkpoints(tenant_id, session_id).await? {
+                sources.push(self.checkpoint_path(tenant_id, session_id, checkpoint.position)?);
+            }
+            sources.push(session_path.clone());
+            for source in &sources {
+                Self::freeze(source, &dir).await?;
+            }
+
+            let after = (file_version(&wal_path).await, file_version(&session_path).await);
+            if after == before || attempt == SNAPSHOT_ATTEMPTS {
+                debug!("Snapshot of session {} taken in {} attempt(s)", session_id, attempt);
+                return Ok(snapshot);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Hard-link `source` into `dir`, or copy it where links aren't supported.
+    /// A missing file is left out of the snapshot, as it was of the live data.
+    async fn freeze(source: &Path, dir: &Path) -> Result<(), StorageError> {
+        let Some(name) = source.file_name() else {
+            return Ok(());
+        };
+        let target = dir.join(name);
+        match fs::hard_link(source, &target).await {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => debug!("Cannot link {}, copying it: {}", source.display(), e),
+        }
+        match fs::copy(source, &target).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to snapshot {}: {}",
+                source.display(),
+                e
+            ))),
+        }
     }
 
     /// Ensure the sessions directory exists.
     async fn ensure_sessions_dir(&self, tenant_id: &str) -> Result<(), StorageError> {
-        let dir = self.sessions_dir(tenant_id);
+        let dir = self.sessions_dir(tenant_id)?;
         fs::create_dir_all(&dir).await.map_err(|e| {
             StorageError::Io(format!("Failed to create sessions dir {}: {}", dir.display(), e))
         })?;
@@ -109,12 +510,105 @@ impl LocalStorage {
     }
 }
 
+/// Directories of a tenant's storage, relative to its directory. Walked one by
+/// one: the empty tenant's directory is the storage root, which also holds the
+/// other tenants'.
+const TENANT_AREAS: [&str; 7] = [
+    "sessions",
+    "templates",
+    "snippets",
+    "glossaries",
+    "profiles",
+    "media",
+    SNAPSHOTS_DIR,
+];
+
+/// The files in the areas of a tenant's directory `root`, by path relative to it.
+fn tenant_files(root: &Path) -> Result<Vec<(PathBuf, StoredFile)>, StorageError> {
+    let mut files = Vec::new();
+    for area in TENANT_AREAS {
+        for entry in walkdir::WalkDir::new(root.join(area)) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // Missing area, or deleted while walking
+                Err(e) if e.io_error().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound) => {
+                    continue
+                }
+                Err(e) => return Err(StorageError::Io(format!("Failed to walk {}: {}", area, e))),
+            };
+            if entry.file_type().is_dir() {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
+            files.push((
+                relative.clone(),
+                StoredFile {
+                    path: relative.to_string_lossy().into_owned(),
+                    bytes: metadata.len(),
+                    modified_at: metadata.modified().ok().map(chrono::DateTime::from),
+                },
+            ));
+        }
+    }
+    Ok(files)
+}
+
+/// Usage area of the file at `relative` (to the tenant's directory), and the
+/// session it belongs to if it is a session file.
+fn classify_file(relative: &Path) -> (&'static str, Option<String>) {
+    let mut components = relative.components();
+    let top = components.next().map(|c| c.as_os_str().to_string_lossy().into_owned());
+    let name = relative.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    match top.as_deref() {
+        Some(SNAPSHOTS_DIR) => return ("snapshots", None),
+        _ if name.ends_with(".tmp") => return ("temp", None),
+        Some("sessions") if components.count() == 1 => {}
+        Some(area) => {
+            let area = TENANT_AREAS.into_iter().find(|a| *a == area).unwrap_or("other");
+            return (area, None);
+        }
+        None => return ("other", None),
+    }
+
+    if name == "index.json" {
+        return ("index", None);
+    }
+    if name == "admin.log" {
+        return ("admin_log", None);
+    }
+    let session = |id: &str| Some(id.to_string());
+    if let Some(stem) = name.strip_suffix(".docx") {
+        return match stem.split_once(".ckpt.") {
+            Some((id, _)) => ("checkpoints", session(id)),
+            None => ("documents", session(stem)),
+        };
+    }
+    if let Some(id) = name.strip_suffix(".wal").or(name.strip_suffix(".wal.idx")) {
+        return ("wal", session(id));
+    }
+    if let Some(id) = name.strip_suffix(".chain") {
+        return ("history", session(id));
+    }
+    ("other", None)
+}
+
 #[async_trait]
 impl StorageBackend for LocalStorage {
     fn backend_name(&self) -> &'static str {
         "local"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(self.backend_name())
+            .with_feature_if(feature::MEDIA_DEDUP, self.media_dedup)
+            .with_feature_if(feature::HISTORY_CHAIN, self.history_chain)
+            .with_feature(feature::STORAGE_REPORTS)
+            .with_feature(feature::ADMIN_LOG)
+    }
+
     // =========================================================================
     // Session Operations
     // =========================================================================
@@ -125,7 +619,7 @@ impl StorageBackend for LocalStorage {
         tenant_id: &str,
         session_id: &str,
     ) -> Result<Option<Vec<u8>>, StorageError> {
-        let path = self.session_path(tenant_id, session_id);
+        let path = self.session_path(tenant_id, session_id)?;
         match fs::read(&path).await {
             Ok(data) => {
                 let original_len = data.len();
@@ -136,7 +630,7 @@ impl StorageBackend for LocalStorage {
                     data.len(),
                     original_len - data.len()
                 );
-                Ok(Some(data))
+                Ok(Some(restore_media(self, tenant_id, data).await?))
             }
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
             Err(e) => Err(StorageError::Io(format!(
@@ -155,12 +649,14 @@ impl StorageBackend for LocalStorage {
         data: &[u8],
     ) -> Result<(), StorageError> {
         self.ensure_sessions_dir(tenant_id).await?;
-        let path = self.session_path(tenant_id, session_id);
+        let path = self.session_path(tenant_id, session_id)?;
+        let package = self.package_to_save(tenant_id, data).await?;
+        let data = package.as_deref().unwrap_or(data);
 
         // Write atomically via temp file
         let temp_path = path.with_extension("docx.tmp");
         fs::write(&temp_path, data).await.map_err(|e| {
-            StorageError::Io(format!("Failed to write {}: {}", temp_path.display(), e))
+            StorageError::from_io(format!("Failed to write {}", temp_path.display()), e)
         })?;
         fs::rename(&temp_path, &path).await.map_err(|e| {
             StorageError::Io(format!("Failed to rename to {}: {}", path.display(), e))
@@ -170,14 +666,39 @@ impl StorageBackend for LocalStorage {
         Ok(())
     }
 
+    #[instrument(skip(self, chunks), level = "debug")]
+    async fn save_session_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let chunks = match self.media_dedup {
+            true => match buffer_chunks(chunks, MEDIA_MAX_BUFFERED_BYTES).await? {
+                BufferedChunks::Complete(data) => {
+                    self.save_session(tenant_id, session_id, &data).await?;
+                    return Ok(data.len() as u64);
+                }
+                BufferedChunks::Partial(chunks) => chunks,
+            },
+            false => chunks,
+        };
+        self.ensure_sessions_dir(tenant_id).await?;
+        let path = self.session_path(tenant_id, session_id)?;
+        let written = Self::write_chunks_atomically(&path, chunks).await?;
+
+        debug!("Saved session {} ({} bytes, streamed)", session_id, written);
+        Ok(written)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn delete_session(
         &self,
         tenant_id: &str,
         session_id: &str,
     ) -> Result<bool, StorageError> {
-        let session_path = self.session_path(tenant_id, session_id);
-        let wal_path = self.wal_path(tenant_id, session_id);
+        let session_path = self.session_path(tenant_id, session_id)?;
+        let wal_path = self.wal_path(tenant_id, session_id)?;
 
         let existed = session_path.exists();
 
@@ -188,17 +709,27 @@ impl StorageBackend for LocalStorage {
             }
         }
 
-        // Delete WAL
+        // Delete WAL and its offset index
         if let Err(e) = fs::remove_file(&wal_path).await {
             if e.kind() != std::io::ErrorKind::NotFound {
                 warn!("Failed to delete WAL file: {}", e);
             }
         }
+        if let Err(e) = fs::remove_file(self.wal_index_path(tenant_id, session_id)?).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to delete WAL index: {}", e);
+            }
+        }
+        if let Err(e) = fs::remove_file(self.history_path(tenant_id, session_id)?).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to delete history chain: {}", e);
+            }
+        }
 
         // Delete all checkpoints
         let checkpoints = self.list_checkpoints(tenant_id, session_id).await?;
         for ckpt in checkpoints {
-            let ckpt_path = self.checkpoint_path(tenant_id, session_id, ckpt.position);
+            let ckpt_path = self.checkpoint_path(tenant_id, session_id, ckpt.position)?;
             if let Err(e) = fs::remove_file(&ckpt_path).await {
                 if e.kind() != std::io::ErrorKind::NotFound {
                     warn!("Failed to delete checkpoint: {}", e);
@@ -212,7 +743,7 @@ impl StorageBackend for LocalStorage {
 
     #[instrument(skip(self), level = "debug")]
     async fn list_sessions(&self, tenant_id: &str) -> Result<Vec<SessionInfo>, StorageError> {
-        let dir = self.sessions_dir(tenant_id);
+        let dir = self.sessions_dir(tenant_id)?;
         if !dir.exists() {
             return Ok(vec![]);
         }
@@ -269,22 +800,31 @@ impl StorageBackend for LocalStorage {
         tenant_id: &str,
         session_id: &str,
     ) -> Result<bool, StorageError> {
-        let path = self.session_path(tenant_id, session_id);
+        let path = self.session_path(tenant_id, session_id)?;
         Ok(path.exists())
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn load_session_snapshot(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: Option<u64>,
+    ) -> Result<Option<SessionWithHistory>, StorageError> {
+        let snapshot = self.snapshot_session(tenant_id, session_id).await?;
+        load_session_with_history(snapshot.storage(), "", session_id, position).await
+    }
+
     // =========================================================================
     // Index Operations
     // =========================================================================
 
     #[instrument(skip(self), level = "debug")]
     async fn load_index(&self, tenant_id: &str) -> Result<Option<SessionIndex>, StorageError> {
-        let path = self.index_path(tenant_id);
+        let path = self.index_path(tenant_id)?;
         match fs::read_to_string(&path).await {
             Ok(json) => {
-                let index: SessionIndex = serde_json::from_str(&json).map_err(|e| {
-                    StorageError::Serialization(format!("Failed to parse index: {}", e))
-                })?;
+                let index = SessionIndex::from_json(json.as_bytes())?;
                 debug!("Loaded index with {} sessions", index.sessions.len());
                 Ok(Some(index))
             }
@@ -304,7 +844,7 @@ impl StorageBackend for LocalStorage {
         index: &SessionIndex,
     ) -> Result<(), StorageError> {
         self.ensure_sessions_dir(tenant_id).await?;
-        let path = self.index_path(tenant_id);
+        let path = self.index_path(tenant_id)?;
 
         let json = serde_json::to_string_pretty(index).map_err(|e| {
             StorageError::Serialization(format!("Failed to serialize index: {}", e))
@@ -337,9 +877,11 @@ impl StorageBackend for LocalStorage {
         if entries.is_empty() {
             return Ok(0);
         }
+        // Reject the whole batch before touching the WAL
+        let lines = prepare_wal_entries(entries)?;
 
         self.ensure_sessions_dir(tenant_id).await?;
-        let path = self.wal_path(tenant_id, session_id);
+        let path = self.wal_path(tenant_id, session_id)?;
 
         // .NET MappedWal format:
         // - 8 bytes: little-endian i64 = data length (NOT including header)
@@ -364,16 +906,13 @@ impl StorageBackend for LocalStorage {
             }
         };
 
+        let existing = WalOffsetIndex::build(&wal_data[8..]).entries;
+        let last_position = check_wal_positions(existing, entries)?;
+
         // Append new entries as JSONL (each line ends with \n)
-        let mut last_position = 0u64;
-        for entry in entries {
-            // Write the raw .NET WalEntry JSON bytes
-            wal_data.extend_from_slice(&entry.patch_json);
-            // Ensure line ends with newline
-            if !entry.patch_json.ends_with(b"\n") {
-                wal_data.push(b'\n');
-            }
-            last_position = entry.position;
+        for line in &lines {
+            wal_data.extend_from_slice(line);
+            wal_data.push(b'\n');
         }
 
         // Update header with data length (excluding header itself)
@@ -388,6 +927,15 @@ impl StorageBackend for LocalStorage {
         fs::rename(&temp_path, &path).await.map_err(|e| {
             StorageError::Io(format!("Failed to rename WAL: {}", e))
         })?;
+        self.save_wal_index(&self.wal_index_path(tenant_id, session_id)?, &wal_data[8..])
+            .await;
+
+        let first_position = last_position + 1 - lines.len() as u64;
+        let events: Vec<_> = (first_position..)
+            .zip(&lines)
+            .map(|(position, line)| HistoryEvent::wal(position, line))
+            .collect();
+        self.extend_history(tenant_id, session_id, &events).await?;
 
         debug!(
             "Appended {} WAL entries, last position: {}, data_len: {}",
@@ -406,106 +954,48 @@ impl StorageBackend for LocalStorage {
         from_position: u64,
         limit: Option<u64>,
     ) -> Result<(Vec<WalEntry>, bool), StorageError> {
-        let path = self.wal_path(tenant_id, session_id);
-
-        // Read raw bytes
-        let raw_data = match fs::read(&path).await {
-            Ok(data) => data,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                return Ok((vec![], false));
-            }
-            Err(e) => {
-                return Err(StorageError::Io(format!(
-                    "Failed to read WAL {}: {}",
-                    path.display(),
-                    e
-                )));
-            }
+        let Some((mut file, index)) = self.open_wal(tenant_id, session_id).await? else {
+            return Ok((vec![], false));
         };
 
-        // Need at least 8 bytes for header
-        if raw_data.len() < 8 {
-            return Ok((vec![], false));
-        }
+        // Start at the indexed entry closest to from_position
+        let (first_position, start, end) = index.byte_range(from_position, limit);
+        let jsonl = Self::read_wal_range(&mut file, start, end).await?;
+        let (entries, has_more) = parse_wal_entries(&jsonl, first_position, from_position, limit)?;
 
-        // .NET MappedWal format:
-        // - 8 bytes: little-endian i64 = data length (NOT including header)
-        // - JSONL data: each entry is a JSON line ending with \n
-        let data_len = i64::from_le_bytes(raw_data[..8].try_into().unwrap()) as usize;
+        debug!(
+            "Read {} WAL entries from position {} (scanned from {}, total_entries={})",
+            entries.len(),
+            from_position,
+            first_position,
+            index.entries
+        );
+        Ok((entries, has_more))
+    }
 
-        // Sanity check
-        if data_len == 0 {
+    #[instrument(skip(self), level = "debug")]
+    async fn tail_wal(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        before_position: Option<u64>,
+        limit: u64,
+    ) -> Result<(Vec<WalEntry>, bool), StorageError> {
+        let Some((mut file, index)) = self.open_wal(tenant_id, session_id).await? else {
             return Ok((vec![], false));
-        }
-        if 8 + data_len > raw_data.len() {
-            debug!(
-                "WAL {} has invalid header (data_len={}, file_size={}), using file size",
-                path.display(),
-                data_len,
-                raw_data.len()
-            );
-        }
-
-        // Extract JSONL portion
-        let end = (8 + data_len).min(raw_data.len());
-        let jsonl_data = &raw_data[8..end];
-
-        // Parse as UTF-8
-        let content = std::str::from_utf8(jsonl_data).map_err(|e| {
-            StorageError::Io(format!("WAL {} is not valid UTF-8: {}", path.display(), e))
-        })?;
-
-        // Parse JSONL - each line is a .NET WalEntry JSON
-        // Position is 1-indexed (line 1 = position 1)
-        let mut entries = Vec::new();
-        let limit = limit.unwrap_or(u64::MAX);
-        let mut position = 1u64;
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-
-            if position >= from_position {
-                // Parse to extract timestamp
-                let value: serde_json::Value = serde_json::from_str(line).map_err(|e| {
-                    StorageError::Serialization(format!(
-                        "Failed to parse WAL entry at position {}: {}",
-                        position, e
-                    ))
-                })?;
-
-                let timestamp = value.get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                    .unwrap_or_else(chrono::Utc::now);
-
-                entries.push(WalEntry {
-                    position,
-                    operation: String::new(),
-                    path: String::new(),
-                    patch_json: line.as_bytes().to_vec(),
-                    timestamp,
-                });
-
-                if entries.len() as u64 >= limit {
-                    return Ok((entries, true)); // might have more
-                }
-            }
+        };
 
-            position += 1;
+        let (from_position, count) = tail_page(index.entries, before_position, limit);
+        if count == 0 {
+            return Ok((vec![], false));
         }
+        let (first_position, start, end) = index.byte_range(from_position, Some(count));
+        let jsonl = Self::read_wal_range(&mut file, start, end).await?;
+        let (mut entries, _) =
+            parse_wal_entries(&jsonl, first_position, from_position, Some(count))?;
 
-        debug!(
-            "Read {} WAL entries from position {} (data_len={}, total_entries={})",
-            entries.len(),
-            from_position,
-            data_len,
-            position - 1
-        );
-        Ok((entries, false))
+        entries.reverse();
+        Ok((entries, from_position > 1))
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -532,7 +1022,7 @@ impl StorageBackend for LocalStorage {
 
         // Rewrite WAL with only kept entries in .NET JSONL format
         // Format: 8-byte header (data length NOT including header) + JSONL data
-        let path = self.wal_path(tenant_id, session_id);
+        let path = self.wal_path(tenant_id, session_id)?;
 
         let mut wal_data = vec![0u8; 8]; // Header placeholder
 
@@ -557,6 +1047,10 @@ impl StorageBackend for LocalStorage {
         fs::rename(&temp_path, &path).await.map_err(|e| {
             StorageError::Io(format!("Failed to rename WAL: {}", e))
         })?;
+        self.save_wal_index(&self.wal_index_path(tenant_id, session_id)?, &wal_data[8..])
+            .await;
+        self.extend_history(tenant_id, session_id, &[HistoryEvent::truncate(keep_count)])
+            .await?;
 
         debug!("Truncated WAL, removed {} entries, kept {}", removed_count, to_keep.len());
         Ok(removed_count)
@@ -575,7 +1069,10 @@ impl StorageBackend for LocalStorage {
         data: &[u8],
     ) -> Result<(), StorageError> {
         self.ensure_sessions_dir(tenant_id).await?;
-        let path = self.checkpoint_path(tenant_id, session_id, position);
+        let path = self.checkpoint_path(tenant_id, session_id, position)?;
+        let digest = document_digest(data);
+        let package = self.package_to_save(tenant_id, data).await?;
+        let data = package.as_deref().unwrap_or(data);
 
         // Write atomically
         let temp_path = path.with_extension("docx.tmp");
@@ -585,6 +1082,8 @@ impl StorageBackend for LocalStorage {
         fs::rename(&temp_path, &path).await.map_err(|e| {
             StorageError::Io(format!("Failed to rename checkpoint: {}", e))
         })?;
+        self.extend_history(tenant_id, session_id, &[HistoryEvent::checkpoint(position, digest)])
+            .await?;
 
         debug!(
             "Saved checkpoint at position {} ({} bytes)",
@@ -594,6 +1093,39 @@ impl StorageBackend for LocalStorage {
         Ok(())
     }
 
+    #[instrument(skip(self, chunks), level = "debug")]
+    async fn save_checkpoint_stream(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        position: u64,
+        chunks: ChunkStream<'_>,
+    ) -> Result<u64, StorageError> {
+        let chunks = match self.media_dedup {
+            true => match buffer_chunks(chunks, MEDIA_MAX_BUFFERED_BYTES).await? {
+                BufferedChunks::Complete(data) => {
+                    self.save_checkpoint(tenant_id, session_id, position, &data).await?;
+                    return Ok(data.len() as u64);
+                }
+                BufferedChunks::Partial(chunks) => chunks,
+            },
+            false => chunks,
+        };
+        self.ensure_sessions_dir(tenant_id).await?;
+        let path = self.checkpoint_path(tenant_id, session_id, position)?;
+        let (chunks, digest) = digest_chunks(chunks);
+        let written = Self::write_chunks_atomically(&path, chunks).await?;
+        self.extend_history(
+            tenant_id,
+            session_id,
+            &[HistoryEvent::checkpoint(position, digest.hex())],
+        )
+        .await?;
+
+        debug!("Saved checkpoint at position {} ({} bytes, streamed)", position, written);
+        Ok(written)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn load_checkpoint(
         &self,
@@ -605,7 +1137,7 @@ impl StorageBackend for LocalStorage {
             // Load latest checkpoint
             let checkpoints = self.list_checkpoints(tenant_id, session_id).await?;
             if let Some(latest) = checkpoints.last() {
-                let path = self.checkpoint_path(tenant_id, session_id, latest.position);
+                let path = self.checkpoint_path(tenant_id, session_id, latest.position)?;
                 let data = fs::read(&path).await.map_err(|e| {
                     StorageError::Io(format!("Failed to read checkpoint: {}", e))
                 })?;
@@ -617,12 +1149,13 @@ impl StorageBackend for LocalStorage {
                     data.len(),
                     original_len - data.len()
                 );
+                let data = restore_media(self, tenant_id, data).await?;
                 return Ok(Some((data, latest.position)));
             }
             return Ok(None);
         }
 
-        let path = self.checkpoint_path(tenant_id, session_id, position);
+        let path = self.checkpoint_path(tenant_id, session_id, position)?;
         match fs::read(&path).await {
             Ok(data) => {
                 let original_len = data.len();
@@ -633,7 +1166,7 @@ impl StorageBackend for LocalStorage {
                     data.len(),
                     original_len - data.len()
                 );
-                Ok(Some((data, position)))
+                Ok(Some((restore_media(self, tenant_id, data).await?, position)))
             }
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
             Err(e) => Err(StorageError::Io(format!(
@@ -649,7 +1182,7 @@ impl StorageBackend for LocalStorage {
         tenant_id: &str,
         session_id: &str,
     ) -> Result<Vec<CheckpointInfo>, StorageError> {
-        let dir = self.sessions_dir(tenant_id);
+        let dir = self.sessions_dir(tenant_id)?;
         if !dir.exists() {
             return Ok(vec![]);
         }
@@ -704,6 +1237,348 @@ impl StorageBackend for LocalStorage {
         );
         Ok(checkpoints)
     }
+
+    // =========================================================================
+    // Library Operations
+    // =========================================================================
+
+    #[instrument(skip(self, data), level = "debug", fields(data_len = data.len()))]
+    async fn save_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), StorageError> {
+        let path = self.library_item_path(tenant_id, kind, name)?;
+        let dir = self.library_dir(tenant_id, kind)?;
+        fs::create_dir_all(&dir).await.map_err(|e| {
+            StorageError::Io(format!("Failed to create library dir {}: {}", dir.display(), e))
+        })?;
+
+        // Write atomically via temp file
+        let temp_path = path.with_extension("docx.tmp");
+        fs::write(&temp_path, data).await.map_err(|e| {
+            StorageError::from_io(format!("Failed to write {}", temp_path.display()), e)
+        })?;
+        fs::rename(&temp_path, &path).await.map_err(|e| {
+            StorageError::Io(format!("Failed to rename to {}: {}", path.display(), e))
+        })?;
+
+        debug!("Saved {:?} {} ({} bytes)", kind, name, data.len());
+        Ok(())
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn load_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let path = self.library_item_path(tenant_id, kind, name)?;
+        match fs::read(&path).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_library_items(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+    ) -> Result<Vec<LibraryItemInfo>, StorageError> {
+        let dir = self.library_dir(tenant_id, kind)?;
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let suffix = format!(".{}", kind.extension());
+        let mut items = Vec::new();
+        let mut entries = fs::read_dir(&dir).await.map_err(|e| {
+            StorageError::Io(format!("Failed to read dir {}: {}", dir.display(), e))
+        })?;
+
+        while let Some(entry) = entries.next_entry().await.map_err(|e| {
+            StorageError::Io(format!("Failed to read dir entry: {}", e))
+        })? {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name) = file_name.strip_suffix(suffix.as_str()) else {
+                continue;
+            };
+
+            let metadata = entry.metadata().await.map_err(|e| {
+                StorageError::Io(format!("Failed to get metadata: {}", e))
+            })?;
+
+            items.push(LibraryItemInfo {
+                name: name.to_string(),
+                modified_at: metadata
+                    .modified()
+                    .map(chrono::DateTime::from)
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                size_bytes: metadata.len(),
+            });
+        }
+
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        debug!("Listed {} {:?} items for tenant {}", items.len(), kind, tenant_id);
+        Ok(items)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn delete_library_item(
+        &self,
+        tenant_id: &str,
+        kind: LibraryKind,
+        name: &str,
+    ) -> Result<bool, StorageError> {
+        let path = self.library_item_path(tenant_id, kind, name)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to delete {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_tenants(&self) -> Result<Vec<String>, StorageError> {
+        // The empty tenant keeps its sessions directly under the base directory
+        let mut tenants = Vec::new();
+        if self.sessions_dir("")?.join("index.json").exists() {
+            tenants.push(String::new());
+        }
+        if !self.base_dir.exists() {
+            return Ok(tenants);
+        }
+
+        let mut entries = fs::read_dir(&self.base_dir).await.map_err(|e| {
+            StorageError::Io(format!("Failed to read dir {}: {}", self.base_dir.display(), e))
+        })?;
+        while let Some(entry) = entries.next_entry().await.map_err(|e| {
+            StorageError::Io(format!("Failed to read dir entry: {}", e))
+        })? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if validate_tenant_id(&name).is_ok() && entry.path().join("sessions").is_dir() {
+                tenants.push(name);
+            }
+        }
+        tenants.sort();
+        Ok(tenants)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn erase_tenant(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        let dir = tenant_dir(&self.base_dir, tenant_id)?;
+        if tenant_id.is_empty() || dir == self.base_dir {
+            return Err(StorageError::InvalidArgument(
+                "cannot erase the storage root".to_string(),
+            ));
+        }
+        if !dir.exists() {
+            return Ok(0);
+        }
+
+        let files = walkdir::WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| !e.file_type().is_dir())
+            .count() as u64;
+        fs::remove_dir_all(&dir).await.map_err(|e| {
+            StorageError::Io(format!("Failed to erase {}: {}", dir.display(), e))
+        })?;
+
+        debug!("Erased tenant {} ({} files)", tenant_id, files);
+        Ok(files)
+    }
+
+    #[instrument(skip(self, entry), level = "debug")]
+    async fn append_admin_log(
+        &self,
+        tenant_id: &str,
+        mut entry: AdminLogEntry,
+    ) -> Result<u64, StorageError> {
+        let _guard = self.admin_log_lock.lock().await;
+        self.ensure_sessions_dir(tenant_id).await?;
+        let path = self.admin_log_path(tenant_id)?;
+        let existing = match fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(StorageError::Io(format!("Failed to read admin log: {}", e))),
+        };
+        entry.position = parse_admin_log(&existing)?.last().map_or(0, |e| e.position) + 1;
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to open admin log: {}", e)))?;
+        // Cut a torn last line (a crash mid-append) before appending
+        let complete = existing.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        file.set_len(complete as u64)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to write admin log: {}", e)))?;
+        file.seek(std::io::SeekFrom::End(0))
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to write admin log: {}", e)))?;
+        file.write_all(&admin_log_jsonl(std::slice::from_ref(&entry))?)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to write admin log: {}", e)))?;
+        file.sync_data()
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to sync admin log: {}", e)))?;
+
+        debug!("Recorded admin action {} at {}", entry.action.as_str(), entry.position);
+        Ok(entry.position)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn read_admin_log(
+        &self,
+        tenant_id: &str,
+        from_position: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<AdminLogEntry>, StorageError> {
+        match fs::read(self.admin_log_path(tenant_id)?).await {
+            Ok(jsonl) => Ok(admin_log_page(parse_admin_log(&jsonl)?, from_position, limit)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(StorageError::Io(format!("Failed to read admin log: {}", e))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn read_history(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Vec<HistoryLink>, StorageError> {
+        match fs::read(self.history_path(tenant_id, session_id)?).await {
+            Ok(jsonl) => parse_history(&jsonl),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(StorageError::Io(format!("Failed to read history chain: {}", e))),
+        }
+    }
+
+    async fn sweep_media(&self, tenant_id: &str) -> Result<u64, StorageError> {
+        // Sessions and checkpoints, and the snapshots being read
+        let mut dirs = vec![self.sessions_dir(tenant_id)?];
+        let snapshots = tenant_dir(&self.base_dir, tenant_id)?.join(SNAPSHOTS_DIR);
+        if let Ok(mut entries) = fs::read_dir(&snapshots).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                dirs.push(entry.path().join("sessions"));
+            }
+        }
+
+        let mut referenced = HashSet::new();
+        for dir in dirs {
+            let Ok(mut entries) = fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Some(entry) = entries.next_entry().await.map_err(|e| {
+                StorageError::Io(format!("Failed to read dir entry: {}", e))
+            })? {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "docx") {
+                    continue;
+                }
+                // A package deleted since the listing references nothing
+                if let Ok(data) = fs::read(&path).await {
+                    referenced.extend(media_references(&Self::strip_dotnet_header(data)));
+                }
+            }
+        }
+
+        let deleted = delete_unreferenced_media(self, tenant_id, &referenced).await?;
+        debug!(
+            "Swept {} media blobs of tenant {} ({} referenced)",
+            deleted,
+            tenant_id,
+            referenced.len()
+        );
+        Ok(deleted)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn storage_report(
+        &self,
+        tenant_id: &str,
+        cleanup: bool,
+    ) -> Result<Option<StorageReport>, StorageError> {
+        let root = tenant_dir(&self.base_dir, tenant_id)?;
+        let indexed: HashSet<String> = self
+            .load_index(tenant_id)
+            .await?
+            .map(|index| index.sessions.into_iter().map(|entry| entry.id).collect())
+            .unwrap_or_default();
+        let files = {
+            let root = root.clone();
+            tokio::task::spawn_blocking(move || tenant_files(&root))
+                .await
+                .map_err(|e| StorageError::Internal(format!("Storage report task failed: {}", e)))??
+        };
+
+        let stale_before = chrono::Utc::now() - STALE_FILE_AGE;
+        let is_stale = |file: &StoredFile| file.modified_at.is_some_and(|t| t < stale_before);
+        let mut report = StorageReport::default();
+        let mut documents = HashSet::new();
+        let mut session_files: HashMap<String, Vec<StoredFile>> = HashMap::new();
+        for (relative, file) in files {
+            let (area, session) = classify_file(&relative);
+            report.add_usage(area, file.bytes);
+            match (area, session) {
+                ("temp", _) if is_stale(&file) => report.temp_files.push(file),
+                ("documents", Some(id)) => {
+                    documents.insert(id);
+                }
+                (_, Some(id)) => session_files.entry(id).or_default().push(file),
+                _ => {}
+            }
+        }
+
+        report.unindexed_sessions = documents.iter().filter(|id| !indexed.contains(*id)).cloned().collect();
+        report.unindexed_sessions.sort();
+        for (id, files) in session_files {
+            // A session's other files are written before it is indexed
+            if !indexed.contains(&id) && !documents.contains(&id) && files.iter().all(is_stale) {
+                report.orphaned_files.extend(files);
+            }
+        }
+        report.orphaned_files.sort_by(|a, b| a.path.cmp(&b.path));
+        report.temp_files.sort_by(|a, b| a.path.cmp(&b.path));
+        report.usage.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.area.cmp(&b.area)));
+
+        if cleanup {
+            for file in report.orphaned_files.iter().chain(&report.temp_files) {
+                match fs::remove_file(root.join(&file.path)).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        return Err(StorageError::Io(format!("Failed to delete {}: {}", file.path, e)))
+                    }
+                }
+            }
+            report.cleaned = true;
+            debug!(
+                "Deleted {} leftover files of tenant {} ({} bytes)",
+                report.orphaned_files.len() + report.temp_files.len(),
+                tenant_id,
+                report.leftover_bytes()
+            );
+        }
+        Ok(Some(report))
+    }
 }
 
 #[cfg(test)]
@@ -717,6 +1592,15 @@ mod tests {
         (storage, temp_dir)
     }
 
+    /// Minimal valid WAL record payload.
+    fn record(description: &str) -> Vec<u8> {
+        format!(
+            r#"{{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:00Z","description":"{}"}}"#,
+            description
+        )
+        .into_bytes()
+    }
+
     #[tokio::test]
     async fn test_session_crud() {
         let (storage, _temp) = setup().await;
@@ -760,14 +1644,14 @@ mod tests {
                 position: 1,
                 operation: "add".to_string(),
                 path: "/body/paragraph[0]".to_string(),
-                patch_json: b"{}".to_vec(),
+                patch_json: record("edit"),
                 timestamp: chrono::Utc::now(),
             },
             WalEntry {
                 position: 2,
                 operation: "replace".to_string(),
                 path: "/body/paragraph[0]/run[0]".to_string(),
-                patch_json: b"{}".to_vec(),
+                patch_json: record("edit"),
                 timestamp: chrono::Utc::now(),
             },
         ];
@@ -795,6 +1679,155 @@ mod tests {
         assert_eq!(read_entries[0].position, 1);
     }
 
+    #[tokio::test]
+    async fn test_snapshot_is_not_affected_by_later_writes() {
+        let (storage, temp) = setup().await;
+        let tenant = "test-tenant";
+        let session = "test-session";
+        let entry = |position| WalEntry {
+            position,
+            operation: "add".to_string(),
+            path: "/body/paragraph[0]".to_string(),
+            patch_json: record("edit"),
+            timestamp: chrono::Utc::now(),
+        };
+
+        storage.save_session(tenant, session, b"baseline").await.unwrap();
+        storage.append_wal(tenant, session, &[entry(1), entry(2)]).await.unwrap();
+        storage.save_checkpoint(tenant, session, 1, b"checkpoint 1").await.unwrap();
+
+        let snapshot = storage.snapshot_session(tenant, session).await.unwrap();
+
+        // The live session moves on: an undo, new edits, a new baseline
+        storage.truncate_wal(tenant, session, 1).await.unwrap();
+        storage.append_wal(tenant, session, &[entry(2), entry(3)]).await.unwrap();
+        storage.save_session(tenant, session, b"compacted").await.unwrap();
+
+        let history = load_session_with_history(snapshot.storage(), "", session, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (history.base_position, history.target_position, history.wal_count),
+            (1, 2, 2)
+        );
+        assert_eq!(history.document, b"checkpoint 1");
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(
+            snapshot.storage().load_session("", session).await.unwrap(),
+            Some(b"baseline".to_vec())
+        );
+
+        // Dropping the snapshot removes its files, not the session's
+        let snapshots = temp.path().join(tenant).join(SNAPSHOTS_DIR);
+        drop(snapshot);
+        assert_eq!(std::fs::read_dir(&snapshots).unwrap().count(), 0);
+        let live = storage
+            .load_session_snapshot(tenant, session, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(live.wal_count, 3);
+        assert_eq!(std::fs::read_dir(&snapshots).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wal_paging_and_tail() {
+        let (storage, temp) = setup().await;
+        let tenant = "test-tenant";
+        let session = "test-session";
+
+        let entries: Vec<WalEntry> = (1..=150)
+            .map(|position| WalEntry {
+                position,
+                operation: String::new(),
+                path: String::new(),
+                patch_json: record(&position.to_string()),
+                timestamp: chrono::Utc::now(),
+            })
+            .collect();
+        storage.append_wal(tenant, session, &entries).await.unwrap();
+
+        let (page, has_more) = storage.read_wal(tenant, session, 100, Some(5)).await.unwrap();
+        let positions: Vec<u64> = page.iter().map(|e| e.position).collect();
+        assert_eq!(positions, vec![100, 101, 102, 103, 104]);
+        assert_eq!(page[0].patch_json, record("100"));
+        assert!(has_more);
+
+        let (tail, has_more) = storage.tail_wal(tenant, session, None, 3).await.unwrap();
+        let positions: Vec<u64> = tail.iter().map(|e| e.position).collect();
+        assert_eq!(positions, vec![150, 149, 148]);
+        assert!(has_more);
+
+        let (tail, has_more) = storage.tail_wal(tenant, session, Some(3), 5).await.unwrap();
+        let positions: Vec<u64> = tail.iter().map(|e| e.position).collect();
+        assert_eq!(positions, vec![2, 1]);
+        assert!(!has_more);
+
+        // A stale index (WAL rewritten behind its back) is rebuilt
+        storage.truncate_wal(tenant, session, 70).await.unwrap();
+        let index_path = temp
+            .path()
+            .join(tenant)
+            .join("sessions")
+            .join(format!("{}.wal.idx", session));
+        let stale = WalOffsetIndex::build(b"{}\n");
+        std::fs::write(&index_path, serde_json::to_vec(&stale).unwrap()).unwrap();
+
+        let (tail, _) = storage.tail_wal(tenant, session, None, 2).await.unwrap();
+        let positions: Vec<u64> = tail.iter().map(|e| e.position).collect();
+        assert_eq!(positions, vec![70, 69]);
+        let (page, _) = storage.read_wal(tenant, session, 65, None).await.unwrap();
+        assert_eq!(page.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_wal_schema_versions() {
+        let (storage, temp) = setup().await;
+        let tenant = "test-tenant";
+        let session = "test-session";
+
+        // A WAL written before entries were versioned
+        let legacy = concat!(
+            r#"{"patches":"[]","timestamp":"2025-06-01T10:00:00","entry_type":0}"#,
+            "\n",
+            r#"{"patches":"[]","timestamp":"2025-06-01T10:05:00Z","description":"second"}"#,
+            "\n",
+        );
+        let mut wal = (legacy.len() as i64).to_le_bytes().to_vec();
+        wal.extend_from_slice(legacy.as_bytes());
+        let dir = temp.path().join(tenant).join("sessions");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("{}.wal", session)), wal).unwrap();
+
+        let (entries, _) = storage.read_wal(tenant, session, 0, None).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        let first: serde_json::Value = serde_json::from_slice(&entries[0].patch_json).unwrap();
+        assert_eq!(first["version"], 1);
+        assert_eq!(first["timestamp"], "2025-06-01T10:00:00Z");
+        assert_eq!(entries[0].operation, "Patch");
+        assert_eq!(entries[1].timestamp.to_rfc3339(), "2025-06-01T10:05:00+00:00");
+
+        // Pretty-printed entries are stored on a single line
+        let pretty = WalEntry {
+            position: 3,
+            operation: String::new(),
+            path: String::new(),
+            patch_json: b"{\n  \"version\": 1,\n  \"patches\": \"[]\",\n  \"timestamp\": \"2026-01-01T00:00:00Z\"\n}".to_vec(),
+            timestamp: chrono::Utc::now(),
+        };
+        storage.append_wal(tenant, session, &[pretty]).await.unwrap();
+        let (entries, _) = storage.read_wal(tenant, session, 3, None).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].patch_json.contains(&b'\n'));
+
+        // Rewriting the WAL stores the upgraded entries
+        storage.truncate_wal(tenant, session, 2).await.unwrap();
+        let raw = std::fs::read(dir.join(format!("{}.wal", session))).unwrap();
+        let jsonl = String::from_utf8(raw[8..].to_vec()).unwrap();
+        assert!(jsonl.lines().all(|l| l.contains(r#""version":1"#)));
+    }
+
     #[tokio::test]
     async fn test_checkpoint_operations() {
         let (storage, _temp) = setup().await;
@@ -857,6 +1890,10 @@ mod tests {
             cursor_position: 5,
             checkpoint_positions: vec![],
             pending_external_change: false,
+            display_name: None,
+            alias: None,
+            legal_hold: None,
+            expires_at: None,
         });
 
         storage.save_index(tenant, &index).await.unwrap();
@@ -892,6 +1929,10 @@ mod tests {
                 cursor_position: 0,
                 checkpoint_positions: vec![],
                 pending_external_change: false,
+                display_name: None,
+                alias: None,
+                legal_hold: None,
+                expires_at: None,
             });
 
             // Save
@@ -980,6 +2021,10 @@ mod tests {
                     cursor_position: 0,
                     checkpoint_positions: vec![],
                     pending_external_change: false,
+                    display_name: None,
+                    alias: None,
+                    legal_hold: None,
+                    expires_at: None,
                 });
 
                 // Save - ensure this completes before releasing lock
@@ -1042,9 +2087,9 @@ mod tests {
   ]
 }"#;
 
-        let index: SessionIndex = serde_json::from_str(index_json).expect("Failed to parse index");
+        let index = SessionIndex::from_json(index_json.as_bytes()).expect("Failed to parse index");
 
-        assert_eq!(index.version, 1);
+        assert_eq!(index.version, docx_storage_core::SESSION_INDEX_VERSION);
         assert_eq!(index.sessions.len(), 1);
 
         let session = index.get("a5fea612f066").expect("Session not found");
@@ -1056,6 +2101,45 @@ mod tests {
         assert_eq!(session.checkpoint_positions.len(), 10);
     }
 
+    #[tokio::test]
+    async fn test_index_schema_migrations() {
+        let (storage, temp) = setup().await;
+        let dir = temp.path().join("test-tenant").join("sessions");
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_index = |json: &str| std::fs::write(dir.join("index.json"), json).unwrap();
+
+        // Every older layout loads into the current schema
+        let entry = r#"{"id":"s1","source_path":null,"created_at":"2026-01-01T00:00:00Z","#;
+        for index_json in [
+            format!(r#"{{"sessions":[{}"modified_at":"2026-01-02T00:00:00Z","wal_position":7}}]}}"#, entry),
+            format!(r#"{{"version":0,"sessions":[{}"last_modified_at":"2026-01-02T00:00:00Z","wal_count":7}}]}}"#, entry),
+            format!(r#"{{"version":1,"sessions":[{}"modified_at":"2026-01-02T00:00:00Z","wal_position":7}}]}}"#, entry),
+        ] {
+            write_index(&index_json);
+            let index = storage.load_index("test-tenant").await.unwrap().unwrap();
+            assert_eq!(index.version, docx_storage_core::SESSION_INDEX_VERSION);
+            let session = index.get("s1").unwrap();
+            assert_eq!(session.wal_count, 7, "{}", index_json);
+            assert_eq!(session.last_modified_at.to_rfc3339(), "2026-01-02T00:00:00+00:00");
+
+            // Saving writes the current version, which loads back unchanged
+            storage.save_index("test-tenant", &index).await.unwrap();
+            let saved: serde_json::Value =
+                serde_json::from_slice(&std::fs::read(dir.join("index.json")).unwrap()).unwrap();
+            assert_eq!(saved["version"], docx_storage_core::SESSION_INDEX_VERSION);
+            assert_eq!(saved["sessions"][0]["wal_count"], 7);
+            let reloaded = storage.load_index("test-tenant").await.unwrap().unwrap();
+            assert_eq!(reloaded.get("s1").unwrap().wal_count, 7);
+        }
+
+        // Indexes from a newer schema are refused rather than truncated on save
+        write_index(r#"{"version":999,"sessions":[]}"#);
+        assert!(matches!(
+            storage.load_index("test-tenant").await,
+            Err(StorageError::Serialization(_))
+        ));
+    }
+
     #[test]
     fn test_strip_dotnet_header_with_prefix() {
         // Simulate .NET format: 8-byte length prefix + DOCX data
@@ -1114,7 +2198,7 @@ mod tests {
 
         // Write a file with .NET header prefix
         storage.ensure_sessions_dir(tenant).await.unwrap();
-        let path = storage.session_path(tenant, session);
+        let path = storage.session_path(tenant, session).unwrap();
 
         let mut data_with_header = vec![0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]; // 8-byte header
         data_with_header.extend_from_slice(&[0x50, 0x4B, 0x03, 0x04]); // PK signature
@@ -1136,7 +2220,7 @@ mod tests {
 
         // Write a checkpoint with .NET header prefix
         storage.ensure_sessions_dir(tenant).await.unwrap();
-        let path = storage.checkpoint_path(tenant, session, 10);
+        let path = storage.checkpoint_path(tenant, session, 10).unwrap();
 
         let mut data_with_header = vec![0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]; // 8-byte header
         data_with_header.extend_from_slice(&[0x50, 0x4B, 0x03, 0x04]); // PK signature
@@ -1150,4 +2234,233 @@ mod tests {
         assert_eq!(&loaded[0..4], &[0x50, 0x4B, 0x03, 0x04]);
         assert_eq!(loaded.len(), 4 + 15); // PK + "checkpoint data"
     }
+
+    #[tokio::test]
+    async fn test_rejects_ids_escaping_tenant_namespace() {
+        let (storage, temp) = setup().await;
+
+        for tenant in ["../other", "a/b", "..", ".hidden", "a\\b"] {
+            let result = storage.save_session(tenant, "s1", b"data").await;
+            assert!(
+                matches!(result, Err(StorageError::InvalidArgument(_))),
+                "tenant {:?} should be rejected",
+                tenant
+            );
+        }
+        for session in ["../s1", "x/y", "", ".."] {
+            let result = storage.save_session("tenant", session, b"data").await;
+            assert!(
+                matches!(result, Err(StorageError::InvalidArgument(_))),
+                "session {:?} should be rejected",
+                session
+            );
+        }
+        assert!(storage.list_sessions("../..").await.is_err());
+        assert!(!temp.path().join("other").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_rejects_tenant_dir_symlinked_outside_base() {
+        let (storage, temp) = setup().await;
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("linked")).unwrap();
+
+        let result = storage.save_session("linked", "s1", b"data").await;
+        assert!(matches!(result, Err(StorageError::InvalidArgument(_))));
+        assert!(!outside.path().join("sessions").exists());
+    }
+    /// A package holding `image` as its only media part.
+    fn package_with_image(image: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, data) in [("word/document.xml", &b"<w:document/>"[..]), ("word/media/image1.png", image)] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_media_dedup_shares_images_across_sessions() {
+        let (storage, temp) = setup().await;
+        let storage = storage.with_media_dedup(true);
+        let image = vec![7u8; 64 * 1024];
+        let package = package_with_image(&image);
+
+        storage.save_session("t1", "s1", &package).await.unwrap();
+        storage.save_checkpoint("t1", "s1", 3, &package).await.unwrap();
+        storage.save_session("t1", "s2", &package).await.unwrap();
+
+        // One blob, referenced from packages that no longer hold the image
+        let media = storage.list_library_items("t1", LibraryKind::Media).await.unwrap();
+        assert_eq!(media.len(), 1);
+        let saved = std::fs::read(temp.path().join("t1/sessions/s1.docx")).unwrap();
+        assert!(saved.len() < image.len());
+        assert_eq!(media_references(&saved), vec![media[0].name.clone()]);
+
+        assert_eq!(storage.load_session("t1", "s2").await.unwrap().unwrap(), package);
+        let (checkpoint, _) = storage.load_checkpoint("t1", "s1", 3).await.unwrap().unwrap();
+        assert_eq!(checkpoint, package);
+
+        // Old enough to sweep once unreferenced
+        let blob = temp.path().join(format!("t1/media/{}.bin", media[0].name));
+        let two_days_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 86400);
+        std::fs::File::options().write(true).open(&blob).unwrap().set_modified(two_days_ago).unwrap();
+
+        storage.delete_session("t1", "s1").await.unwrap();
+        assert_eq!(storage.sweep_media("t1").await.unwrap(), 0);
+        storage.delete_session("t1", "s2").await.unwrap();
+        assert_eq!(storage.sweep_media("t1").await.unwrap(), 1);
+        assert!(!blob.exists());
+    }
+
+    #[tokio::test]
+    async fn test_history_chain_proves_unrewritten_wal() {
+        let (storage, temp) = setup().await;
+        let storage = storage.with_history_chain(true);
+        let signer = docx_storage_core::HistorySigner::new(vec![1u8; 32]).unwrap();
+        let entry = |position, description: &str| WalEntry {
+            position,
+            operation: "add".to_string(),
+            path: "/body/paragraph[0]".to_string(),
+            patch_json: record(description),
+            timestamp: chrono::Utc::now(),
+        };
+
+        storage.save_session("t1", "s1", b"PK\x03\x04docx").await.unwrap();
+        storage
+            .append_wal("t1", "s1", &[entry(1, "one"), entry(2, "two")])
+            .await
+            .unwrap();
+        // Undo followed by a new edit
+        storage.truncate_wal("t1", "s1", 1).await.unwrap();
+        storage.append_wal("t1", "s1", &[entry(2, "three")]).await.unwrap();
+        storage.save_checkpoint("t1", "s1", 2, b"PK\x03\x04ckpt").await.unwrap();
+
+        let (proof, proof_json, signature) =
+            docx_storage_core::prove_history(&storage, &signer, "t1", "s1", chrono::Utc::now())
+                .await
+                .unwrap();
+        assert!(proof.is_consistent());
+        assert_eq!((proof.links, proof.wal_position, proof.checkpoints), (5, 2, 1));
+        assert_eq!(signature, signer.sign(proof_json.as_bytes()));
+
+        // Rewrite the second entry in place
+        let wal = temp.path().join("t1/sessions/s1.wal");
+        let data = std::fs::read(&wal).unwrap();
+        let text = String::from_utf8(data[8..].to_vec()).unwrap().replace("three", "forgd");
+        std::fs::write(&wal, [&data[..8], text.as_bytes()].concat()).unwrap();
+
+        let (proof, _, _) =
+            docx_storage_core::prove_history(&storage, &signer, "t1", "s1", chrono::Utc::now())
+                .await
+                .unwrap();
+        assert!(!proof.is_consistent());
+        assert_eq!(proof.mismatched_positions, vec![2]);
+
+        storage.delete_session("t1", "s1").await.unwrap();
+        assert!(storage.read_history("t1", "s1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admin_log_numbers_entries_and_drops_torn_line() {
+        use docx_storage_core::AdminAction;
+
+        let (storage, _temp) = setup().await;
+        let now = chrono::Utc::now();
+        let first = AdminLogEntry::new(AdminAction::DeleteSession, "alice", now).with_session("s1");
+        assert_eq!(storage.append_admin_log("t1", first).await.unwrap(), 1);
+
+        // A crash mid-append leaves a torn line, cut by the next append
+        let path = storage.admin_log_path("t1").unwrap();
+        let mut data = std::fs::read(&path).unwrap();
+        data.extend_from_slice(b"{\"position\":2,\"act");
+        std::fs::write(&path, data).unwrap();
+        let second = AdminLogEntry::new(AdminAction::RetentionRun, "", now).with_detail("purged 2 sessions");
+        assert_eq!(storage.append_admin_log("t1", second).await.unwrap(), 2);
+
+        let log = storage.read_admin_log("t1", 0, None).await.unwrap();
+        assert_eq!(
+            log.iter().map(|e| (e.position, e.action, e.actor.as_str())).collect::<Vec<_>>(),
+            vec![(1, AdminAction::DeleteSession, "alice"), (2, AdminAction::RetentionRun, "unknown")]
+        );
+        assert_eq!(storage.read_admin_log("t1", 2, Some(5)).await.unwrap().len(), 1);
+        assert!(storage.read_admin_log("t2", 0, None).await.unwrap().is_empty());
+
+        let report = storage.storage_report("t1", false).await.unwrap().unwrap();
+        assert!(report.orphaned_files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_storage_report_finds_orphans_and_stale_temp_files() {
+        let (storage, temp) = setup().await;
+        let tenant = "t1";
+        let dir = temp.path().join(tenant).join("sessions");
+        let stale = std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 3600);
+        let write = |name: &str, age: Option<std::time::SystemTime>| {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            std::io::Write::write_all(&mut &file, b"leftover").unwrap();
+            if let Some(modified) = age {
+                file.set_modified(modified).unwrap();
+            }
+        };
+
+        // "kept" is indexed, "lost" is stored but not indexed, "gone" only
+        // has a WAL and a checkpoint left, and "new" is still being created
+        for session in ["kept", "lost"] {
+            storage.save_session(tenant, session, b"PK\x03\x04doc").await.unwrap();
+        }
+        let mut index = SessionIndex::default();
+        index.upsert(SessionIndexEntry {
+            id: "kept".to_string(),
+            source_path: None,
+            auto_sync: false,
+            created_at: chrono::Utc::now(),
+            last_modified_at: chrono::Utc::now(),
+            docx_file: Some("kept.docx".to_string()),
+            wal_count: 0,
+            cursor_position: 0,
+            checkpoint_positions: vec![],
+            pending_external_change: false,
+            display_name: None,
+            alias: None,
+            legal_hold: None,
+            expires_at: None,
+        });
+        storage.save_index(tenant, &index).await.unwrap();
+        write("gone.wal", Some(stale));
+        write("gone.ckpt.3.docx", Some(stale));
+        write("new.wal", None);
+        write("kept.docx.tmp", Some(stale));
+        write("index.json.tmp", None);
+
+        let report = storage.storage_report(tenant, false).await.unwrap().unwrap();
+        assert_eq!(report.unindexed_sessions, ["lost"]);
+        let paths = |files: &[StoredFile]| {
+            files
+                .iter()
+                .map(|f| Path::new(&f.path).file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(&report.orphaned_files), ["gone.ckpt.3.docx", "gone.wal"]);
+        assert_eq!(paths(&report.temp_files), ["kept.docx.tmp"]);
+        assert_eq!(report.leftover_bytes(), 3 * 8);
+        let documents = report.usage.iter().find(|u| u.area == "documents").unwrap();
+        assert_eq!(documents.files, 2);
+        assert!(!report.cleaned);
+        assert!(dir.join("gone.wal").exists());
+
+        let report = storage.storage_report(tenant, true).await.unwrap().unwrap();
+        assert!(report.cleaned);
+        for name in ["gone.wal", "gone.ckpt.3.docx", "kept.docx.tmp"] {
+            assert!(!dir.join(name).exists(), "{}", name);
+        }
+        for name in ["kept.docx", "lost.docx", "new.wal", "index.json.tmp"] {
+            assert!(dir.join(name).exists(), "{}", name);
+        }
+        let report = storage.storage_report(tenant, false).await.unwrap().unwrap();
+        assert!(report.orphaned_files.is_empty() && report.temp_files.is_empty());
+    }
 }
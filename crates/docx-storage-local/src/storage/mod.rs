@@ -3,4 +3,4 @@ mod local;
 // Re-export from docx-storage-core
 pub use docx_storage_core::{SessionIndexEntry, StorageBackend, WalEntry};
 
-pub use local::LocalStorage;
+pub use local::{LocalStorage, SessionSnapshot};
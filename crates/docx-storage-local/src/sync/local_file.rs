@@ -4,7 +4,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use docx_storage_core::{
-    SourceDescriptor, SourceType, StorageBackend, StorageError, SyncBackend, SyncStatus,
+    RecentFile, SourceDescriptor, SourceType, StorageBackend, StorageError, SyncBackend,
+    SyncStatus,
 };
 use tokio::fs;
 use tracing::{debug, instrument, warn};
@@ -17,6 +18,24 @@ struct TransientSyncState {
     last_error: Option<String>,
 }
 
+/// Map a write failure to a sync error. Failures meaning the target is
+/// unreachable (e.g. a disconnected network share) are `Unavailable`, so the
+/// sync is queued for retry instead of failing for good.
+fn sync_io_error(context: String, e: std::io::Error) -> StorageError {
+    use std::io::ErrorKind;
+
+    let msg = format!("{}: {}", context, e);
+    match e.kind() {
+        ErrorKind::TimedOut
+        | ErrorKind::NotConnected
+        | ErrorKind::ConnectionRefused
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::BrokenPipe => StorageError::Unavailable(msg),
+        _ => StorageError::Sync(msg),
+    }
+}
+
 /// Local file sync backend.
 ///
 /// Handles syncing session data to local files (the original auto-save behavior).
@@ -105,9 +124,14 @@ impl SyncBackend for LocalFileSyncBackend {
                 cursor_position: 0,
                 checkpoint_positions: vec![],
                 pending_external_change: false,
+                display_name: None,
+                alias: None,
+                legal_hold: None,
+                expires_at: None,
             };
             index.sessions.push(entry);
         }
+        index.touch_recent_file(source.clone(), now.timestamp());
 
         self.storage.save_index(tenant_id, &index).await?;
 
@@ -215,7 +239,7 @@ impl SyncBackend for LocalFileSyncBackend {
         data: &[u8],
     ) -> Result<i64, StorageError> {
         // Get source path from index
-        let index = self.storage.load_index(tenant_id).await?.unwrap_or_default();
+        let mut index = self.storage.load_index(tenant_id).await?.unwrap_or_default();
 
         let entry = index.get(session_id).ok_or_else(|| {
             StorageError::Sync(format!(
@@ -232,38 +256,43 @@ impl SyncBackend for LocalFileSyncBackend {
         })?;
 
         let file_path = PathBuf::from(source_path);
+        let source = SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: source_path.clone(),
+            file_id: None,
+        };
 
         // Ensure parent directory exists
         if let Some(parent) = file_path.parent() {
             fs::create_dir_all(parent).await.map_err(|e| {
-                StorageError::Sync(format!(
-                    "Failed to create parent directory for {}: {}",
-                    file_path.display(),
-                    e
-                ))
+                sync_io_error(
+                    format!("Failed to create parent directory for {}", file_path.display()),
+                    e,
+                )
             })?;
         }
 
         // Write atomically via temp file
         let temp_path = file_path.with_extension("docx.sync.tmp");
         fs::write(&temp_path, data).await.map_err(|e| {
-            StorageError::Sync(format!(
-                "Failed to write temp file {}: {}",
-                temp_path.display(),
-                e
-            ))
+            sync_io_error(format!("Failed to write temp file {}", temp_path.display()), e)
         })?;
 
         fs::rename(&temp_path, &file_path).await.map_err(|e| {
-            StorageError::Sync(format!(
-                "Failed to rename temp file to {}: {}",
-                file_path.display(),
-                e
-            ))
+            sync_io_error(
+                format!("Failed to rename temp file to {}", file_path.display()),
+                e,
+            )
         })?;
 
         let synced_at = chrono::Utc::now().timestamp();
 
+        index.touch_recent_file(source, synced_at);
+        if let Err(e) = self.storage.save_index(tenant_id, &index).await {
+            warn!("Failed to record recent file for tenant {}: {}", tenant_id, e);
+        }
+
         // Update transient state
         let key = Self::key(tenant_id, session_id);
         self.transient
@@ -371,6 +400,33 @@ impl SyncBackend for LocalFileSyncBackend {
             .map(|e| e.source_path.is_some() && e.auto_sync)
             .unwrap_or(false))
     }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn list_recent_files(&self, tenant_id: &str) -> Result<Vec<RecentFile>, StorageError> {
+        let index = self.storage.load_index(tenant_id).await?.unwrap_or_default();
+        Ok(index.recent_files)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn pin_favorite(
+        &self,
+        tenant_id: &str,
+        source: SourceDescriptor,
+        pinned: bool,
+    ) -> Result<(), StorageError> {
+        if source.source_type != SourceType::LocalFile {
+            return Err(StorageError::Sync(format!(
+                "LocalFileSyncBackend only supports LocalFile sources, got {:?}",
+                source.source_type
+            )));
+        }
+
+        let mut index = self.storage.load_index(tenant_id).await?.unwrap_or_default();
+        if index.set_favorite(source, pinned, chrono::Utc::now().timestamp()) {
+            self.storage.save_index(tenant_id, &index).await?;
+        }
+        Ok(())
+    }
 }
 
 /// Mark a session as having pending changes (for auto-sync tracking).
@@ -425,6 +481,10 @@ mod tests {
             cursor_position: 0,
             checkpoint_positions: vec![],
             pending_external_change: false,
+            display_name: None,
+            alias: None,
+            legal_hold: None,
+            expires_at: None,
         });
         backend.storage.save_index(tenant, &index).await.unwrap();
     }
@@ -699,4 +759,203 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No source registered"));
     }
+
+    #[tokio::test]
+    async fn test_recent_files_and_favorites() {
+        let (backend, _storage_dir, output_dir) = setup().await;
+        let tenant = "test-tenant";
+        let source_for = |name: &str| SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: output_dir.path().join(name).to_string_lossy().to_string(),
+            file_id: None,
+        };
+
+        for (session, name) in [("s1", "a.docx"), ("s2", "b.docx")] {
+            create_session(&backend, tenant, session).await;
+            backend
+                .register_source(tenant, session, source_for(name), false)
+                .await
+                .unwrap();
+        }
+
+        // Syncing s1 moves a.docx back to the front
+        backend.sync_to_source(tenant, "s1", b"data").await.unwrap();
+        let recent = backend.list_recent_files(tenant).await.unwrap();
+        let names: Vec<_> = recent.iter().map(|f| f.display_name.as_str()).collect();
+        assert_eq!(names, vec!["a.docx", "b.docx"]);
+
+        // Pinning an unknown file adds it; pinning survives re-opening
+        backend.pin_favorite(tenant, source_for("c.docx"), true).await.unwrap();
+        backend.pin_favorite(tenant, source_for("b.docx"), true).await.unwrap();
+        backend
+            .register_source(tenant, "s2", source_for("b.docx"), false)
+            .await
+            .unwrap();
+        let recent = backend.list_recent_files(tenant).await.unwrap();
+        let favorites: Vec<_> = recent
+            .iter()
+            .filter(|f| f.favorite)
+            .map(|f| f.display_name.as_str())
+            .collect();
+        assert_eq!(favorites, vec!["b.docx", "c.docx"]);
+
+        backend.pin_favorite(tenant, source_for("c.docx"), false).await.unwrap();
+        let recent = backend.list_recent_files(tenant).await.unwrap();
+        assert_eq!(recent.iter().filter(|f| f.favorite).count(), 1);
+        assert_eq!(recent.len(), 3);
+
+        // Other tenants are isolated
+        assert!(backend.list_recent_files("other").await.unwrap().is_empty());
+    }
+
+    /// Local backend whose syncs fail as unreachable while `offline` is set.
+    struct FlakySync {
+        inner: LocalFileSyncBackend,
+        offline: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl SyncBackend for FlakySync {
+        async fn register_source(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+            source: SourceDescriptor,
+            auto_sync: bool,
+        ) -> Result<(), StorageError> {
+            self.inner
+                .register_source(tenant_id, session_id, source, auto_sync)
+                .await
+        }
+
+        async fn unregister_source(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<(), StorageError> {
+            self.inner.unregister_source(tenant_id, session_id).await
+        }
+
+        async fn update_source(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+            source: Option<SourceDescriptor>,
+            auto_sync: Option<bool>,
+        ) -> Result<(), StorageError> {
+            self.inner
+                .update_source(tenant_id, session_id, source, auto_sync)
+                .await
+        }
+
+        async fn sync_to_source(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+            data: &[u8],
+        ) -> Result<i64, StorageError> {
+            if self.offline.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(StorageError::Unavailable("share disconnected".to_string()));
+            }
+            self.inner.sync_to_source(tenant_id, session_id, data).await
+        }
+
+        async fn get_sync_status(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<Option<SyncStatus>, StorageError> {
+            self.inner.get_sync_status(tenant_id, session_id).await
+        }
+
+        async fn list_sources(&self, tenant_id: &str) -> Result<Vec<SyncStatus>, StorageError> {
+            self.inner.list_sources(tenant_id).await
+        }
+
+        async fn is_auto_sync_enabled(
+            &self,
+            tenant_id: &str,
+            session_id: &str,
+        ) -> Result<bool, StorageError> {
+            self.inner.is_auto_sync_enabled(tenant_id, session_id).await
+        }
+
+        async fn list_recent_files(
+            &self,
+            tenant_id: &str,
+        ) -> Result<Vec<RecentFile>, StorageError> {
+            self.inner.list_recent_files(tenant_id).await
+        }
+
+        async fn pin_favorite(
+            &self,
+            tenant_id: &str,
+            source: SourceDescriptor,
+            pinned: bool,
+        ) -> Result<(), StorageError> {
+            self.inner.pin_favorite(tenant_id, source, pinned).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queued_sync_retries_when_source_returns() {
+        use docx_storage_core::{FileSyncQueueStore, RetryingSyncBackend, SyncRetryPolicy};
+        use std::sync::atomic::Ordering;
+
+        let (inner, storage_dir, output_dir) = setup().await;
+        let tenant = "test-tenant";
+        let session = "test-session";
+        let file_path = output_dir.path().join("output.docx");
+        create_session(&inner, tenant, session).await;
+
+        let flaky = Arc::new(FlakySync {
+            inner,
+            offline: true.into(),
+        });
+        let policy = SyncRetryPolicy {
+            initial_backoff_secs: 0,
+            ..Default::default()
+        };
+        let backend = RetryingSyncBackend::new(
+            flaky.clone(),
+            Arc::new(FileSyncQueueStore::new(storage_dir.path())),
+            policy,
+        );
+
+        let source = SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: file_path.to_string_lossy().to_string(),
+            file_id: None,
+        };
+        backend
+            .register_source(tenant, session, source, true)
+            .await
+            .unwrap();
+
+        // Offline: both saves are queued, the newer document replaces the older
+        let err = backend.sync_to_source(tenant, session, b"v1").await.unwrap_err();
+        assert!(matches!(err, StorageError::Unavailable(_)));
+        backend.sync_to_source(tenant, session, b"v2").await.unwrap_err();
+
+        let pending = backend.list_pending_syncs(tenant).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].size_bytes, 2);
+        let status = backend.get_sync_status(tenant, session).await.unwrap().unwrap();
+        assert!(status.has_pending_changes);
+
+        // Still offline: the retry fails and stays queued
+        assert_eq!(backend.retry_due().await.unwrap(), 0);
+        assert_eq!(backend.list_pending_syncs(tenant).await.unwrap()[0].attempts, 3);
+
+        // Back online: the queued document lands even after the session closed
+        backend.unregister_source(tenant, session).await.unwrap();
+        flaky.offline.store(false, Ordering::SeqCst);
+        assert_eq!(backend.retry_due().await.unwrap(), 1);
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), b"v2");
+        assert!(backend.list_pending_syncs(tenant).await.unwrap().is_empty());
+        assert!(backend.get_sync_status(tenant, session).await.unwrap().is_none());
+    }
 }
@@ -1,3 +1,7 @@
 mod notify_watcher;
+mod queue_store;
+mod replaying;
 
 pub use notify_watcher::NotifyWatchBackend;
+pub use queue_store::FileChangeQueueStore;
+pub use replaying::ReplayingWatchBackend;
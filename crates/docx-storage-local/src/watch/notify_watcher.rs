@@ -88,6 +88,7 @@ impl NotifyWatchBackend {
                         ExternalChangeType::Deleted => "deleted",
                         ExternalChangeType::Renamed => "renamed",
                         ExternalChangeType::PermissionChanged => "permission",
+                        ExternalChangeType::Created => "created",
                     },
                     tenant_id,
                     session_id
@@ -473,8 +474,7 @@ mod tests {
 
         // Note: notify events are async and may not always be captured in tests
         // The manual check should still detect the modification
-        if change.is_some() {
-            let change = change.unwrap();
+        if let Some(change) = change {
             assert_eq!(change.change_type, ExternalChangeType::Modified);
         }
     }
@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use docx_storage_core::{tenant_dir, ChangeQueue, ChangeQueueStore, StorageError};
+use tokio::fs;
+use tracing::{debug, instrument};
+
+/// Change queue store keeping one JSON file per tenant.
+///
+/// Layout: `{base_dir}/{tenant_id}/watch_queue.json`
+pub struct FileChangeQueueStore {
+    base_dir: PathBuf,
+}
+
+impl FileChangeQueueStore {
+    pub fn new(base_dir: impl AsRef<Path>) -> Self {
+        Self {
+            base_dir: base_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn queue_path(&self, tenant_id: &str) -> Result<PathBuf, StorageError> {
+        Ok(tenant_dir(&self.base_dir, tenant_id)?.join("watch_queue.json"))
+    }
+}
+
+#[async_trait]
+impl ChangeQueueStore for FileChangeQueueStore {
+    #[instrument(skip(self), level = "debug")]
+    async fn load(&self, tenant_id: &str) -> Result<ChangeQueue, StorageError> {
+        let path = self.queue_path(tenant_id)?;
+        match fs::read_to_string(&path).await {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| {
+                StorageError::Serialization(format!(
+                    "Failed to parse change queue {}: {}",
+                    path.display(),
+                    e
+                ))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ChangeQueue::default()),
+            Err(e) => Err(StorageError::Io(format!(
+                "Failed to read change queue {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    #[instrument(skip(self, queue), level = "debug", fields(pending = queue.len()))]
+    async fn save(&self, tenant_id: &str, queue: &ChangeQueue) -> Result<(), StorageError> {
+        let path = self.queue_path(tenant_id)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await.map_err(|e| {
+                StorageError::Io(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+
+        let json = serde_json::to_string_pretty(queue).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize change queue: {}", e))
+        })?;
+
+        // Write atomically
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, &json)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to write change queue: {}", e)))?;
+        fs::rename(&temp_path, &path)
+            .await
+            .map_err(|e| StorageError::Io(format!("Failed to rename change queue: {}", e)))?;
+
+        debug!("Saved change queue with {} pending events", queue.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use docx_storage_core::{
+        DurableChangeQueue, ExternalChangeEvent, ExternalChangeType, SourceMetadata,
+    };
+    use tempfile::TempDir;
+
+    fn change(session_id: &str, size: u64) -> ExternalChangeEvent {
+        ExternalChangeEvent {
+            session_id: session_id.to_string(),
+            change_type: ExternalChangeType::Modified,
+            old_metadata: None,
+            new_metadata: Some(SourceMetadata {
+                size_bytes: size,
+                modified_at: size as i64,
+                etag: None,
+                version_id: None,
+                content_hash: None,
+            }),
+            detected_at: size as i64,
+            new_uri: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_ack_and_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let tenant = "test-tenant";
+        let sessions = vec!["s1".to_string()];
+        let queue = DurableChangeQueue::new(Arc::new(FileChangeQueueStore::new(temp_dir.path())));
+
+        // A burst of changes to one session coalesces into one event
+        let first = queue
+            .enqueue(tenant, change("s1", 1))
+            .await
+            .unwrap()
+            .unwrap();
+        let second = queue
+            .enqueue(tenant, change("s1", 2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(second.sequence > first.sequence);
+        assert_eq!(second.coalesced, 2);
+
+        // Re-reporting the same change is a no-op
+        assert!(queue
+            .enqueue(tenant, change("s1", 2))
+            .await
+            .unwrap()
+            .is_none());
+
+        let pending = queue.pending(tenant, &sessions).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[0].event.new_metadata.as_ref().unwrap().size_bytes,
+            2
+        );
+
+        // Acking a superseded sequence keeps the newer change queued
+        assert!(!queue.ack(tenant, "s1", first.sequence).await.unwrap());
+
+        // Survives a restart
+        let reopened =
+            DurableChangeQueue::new(Arc::new(FileChangeQueueStore::new(temp_dir.path())));
+        let pending = reopened.pending(tenant, &[]).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sequence, second.sequence);
+
+        // Acked changes are gone and not re-queued when reported again
+        assert!(reopened.ack(tenant, "s1", second.sequence).await.unwrap());
+        assert!(reopened
+            .pending(tenant, &sessions)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(reopened
+            .enqueue(tenant, change("s1", 2))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(reopened
+            .enqueue(tenant, change("s1", 3))
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
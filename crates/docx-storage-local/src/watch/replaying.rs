@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+use docx_storage_core::{
+    ExternalChangeEvent, SourceDescriptor, SourceMetadata, StorageError, WatchBackend,
+};
+use tracing::warn;
+
+/// Watch backend remembering the watches started through it, so they can be
+/// replayed on a fresh backend.
+///
+/// The embedded server swaps in a new watch backend when it restarts a wedged
+/// server; clients don't know and won't start their watches again.
+pub struct ReplayingWatchBackend {
+    inner: RwLock<Arc<dyn WatchBackend>>,
+    /// Active watches: (tenant_id, session_id) -> (source, poll interval)
+    watches: Mutex<HashMap<(String, String), (SourceDescriptor, u32)>>,
+}
+
+impl ReplayingWatchBackend {
+    pub fn new(inner: Arc<dyn WatchBackend>) -> Self {
+        Self {
+            inner: RwLock::new(inner),
+            watches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn current(&self) -> Arc<dyn WatchBackend> {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Number of active watches.
+    pub fn watch_count(&self) -> usize {
+        self.watches.lock().unwrap().len()
+    }
+
+    /// Start every active watch on `inner`, carrying their known metadata
+    /// over, then serve from it. Returns the number of watches replayed;
+    /// watches that fail to start are logged and dropped.
+    pub async fn replace(&self, inner: Arc<dyn WatchBackend>) -> usize {
+        let old = self.current();
+        let watches: Vec<_> = self
+            .watches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, watch)| (key.clone(), watch.clone()))
+            .collect();
+
+        let mut replayed = 0;
+        for ((tenant_id, session_id), (source, poll_interval_secs)) in watches {
+            if let Err(e) = inner
+                .start_watch(&tenant_id, &session_id, &source, poll_interval_secs)
+                .await
+            {
+                warn!(
+                    "Dropping watch of tenant {} session {}: {}",
+                    tenant_id, session_id, e
+                );
+                self.watches.lock().unwrap().remove(&(tenant_id, session_id));
+                continue;
+            }
+            // Changes made while the old backend was down are still reported
+            if let Ok(Some(known)) = old.get_known_metadata(&tenant_id, &session_id).await {
+                let _ = inner
+                    .update_known_metadata(&tenant_id, &session_id, known)
+                    .await;
+            }
+            replayed += 1;
+        }
+
+        *self.inner.write().unwrap() = inner;
+        replayed
+    }
+}
+
+#[async_trait]
+impl WatchBackend for ReplayingWatchBackend {
+    async fn start_watch(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        source: &SourceDescriptor,
+        poll_interval_secs: u32,
+    ) -> Result<String, StorageError> {
+        let watch_id = self
+            .current()
+            .start_watch(tenant_id, session_id, source, poll_interval_secs)
+            .await?;
+        self.watches.lock().unwrap().insert(
+            (tenant_id.to_string(), session_id.to_string()),
+            (source.clone(), poll_interval_secs),
+        );
+        Ok(watch_id)
+    }
+
+    async fn stop_watch(&self, tenant_id: &str, session_id: &str) -> Result<(), StorageError> {
+        self.watches
+            .lock()
+            .unwrap()
+            .remove(&(tenant_id.to_string(), session_id.to_string()));
+        self.current().stop_watch(tenant_id, session_id).await
+    }
+
+    async fn check_for_changes(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<ExternalChangeEvent>, StorageError> {
+        self.current().check_for_changes(tenant_id, session_id).await
+    }
+
+    async fn get_source_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<SourceMetadata>, StorageError> {
+        self.current().get_source_metadata(tenant_id, session_id).await
+    }
+
+    async fn get_known_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+    ) -> Result<Option<SourceMetadata>, StorageError> {
+        self.current().get_known_metadata(tenant_id, session_id).await
+    }
+
+    async fn update_known_metadata(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        metadata: SourceMetadata,
+    ) -> Result<(), StorageError> {
+        self.current()
+            .update_known_metadata(tenant_id, session_id, metadata)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_storage_core::{SimulatedChange, SimulatedWatchBackend, SourceType};
+
+    fn source(path: &str) -> SourceDescriptor {
+        SourceDescriptor {
+            source_type: SourceType::LocalFile,
+            connection_id: None,
+            path: path.to_string(),
+            file_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_replays_active_watches() {
+        let first = Arc::new(SimulatedWatchBackend::new(100));
+        let watch = ReplayingWatchBackend::new(first.clone());
+        watch.start_watch("t1", "s1", &source("/a.docx"), 5).await.unwrap();
+        watch.start_watch("t1", "s2", &source("/b.docx"), 5).await.unwrap();
+        watch.stop_watch("t1", "s2").await.unwrap();
+        first.schedule(
+            "t1",
+            "s1",
+            150,
+            SimulatedChange::Modified(SourceMetadata {
+                size_bytes: 42,
+                modified_at: 150,
+                etag: None,
+                version_id: None,
+                content_hash: None,
+            }),
+        );
+        first.advance(100);
+
+        let second = Arc::new(SimulatedWatchBackend::new(200));
+        assert_eq!(watch.replace(second.clone()).await, 1);
+        assert_eq!(second.watch_count(), 1);
+        assert_eq!(second.watched("t1", "s1").unwrap().1, "/a.docx");
+        // Known metadata is still that of the last sync, not of the restart
+        let known = watch.get_known_metadata("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(known.modified_at, 100);
+
+        // Watches that can't be started again are dropped
+        first.fail_next(1);
+        assert_eq!(watch.replace(first).await, 0);
+        assert_eq!(watch.watch_count(), 0);
+    }
+}
@@ -0,0 +1,193 @@
+// Dashboard for docx-storage-local. Reads everything from the JSON gateway.
+"use strict";
+
+const WAL_PAGE = 100;
+
+const state = {
+  tenant: new URLSearchParams(location.search).get("tenant") || "",
+  session: null,
+  walNext: 1,
+};
+
+const $ = (id) => document.getElementById(id);
+
+function api(path, params = {}) {
+  const query = new URLSearchParams({ tenant: state.tenant, ...params });
+  return `/api/${path}?${query}`;
+}
+
+async function getJson(path, params) {
+  const response = await fetch(api(path, params));
+  const body = await response.json();
+  if (!response.ok) {
+    throw new Error(body.error || response.statusText);
+  }
+  return body;
+}
+
+function showError(err) {
+  const el = $("error");
+  el.textContent = err.message || String(err);
+  el.hidden = false;
+  setTimeout(() => { el.hidden = true; }, 5000);
+}
+
+function formatDate(value) {
+  if (value === null || value === undefined) return "—";
+  const date = typeof value === "number" ? new Date(value * 1000) : new Date(value);
+  return date.toLocaleString();
+}
+
+function formatSize(bytes) {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+function cell(row, text, className) {
+  const td = row.insertCell();
+  td.textContent = text;
+  if (className) td.className = className;
+  return td;
+}
+
+function syncLabel(status) {
+  if (!status) return ["not synced", "muted"];
+  if (status.last_error) return ["error", "err"];
+  if (status.has_pending_changes) return ["pending", "warn"];
+  return [status.auto_sync_enabled ? "auto" : "manual", "ok"];
+}
+
+async function loadHealth() {
+  try {
+    const health = await getJson("health");
+    $("health").textContent = `${health.backend} v${health.version}`;
+  } catch (err) {
+    $("health").textContent = "unreachable";
+  }
+}
+
+async function loadSessions() {
+  const [index, sources] = await Promise.all([getJson("index"), getJson("sources")]);
+  const syncBySession = new Map(sources.map((s) => [s.session_id, s]));
+
+  const tbody = $("sessions").tBodies[0];
+  tbody.replaceChildren();
+  $("sessions-empty").hidden = index.sessions.length > 0;
+
+  const sessions = [...index.sessions].sort(
+    (a, b) => new Date(b.last_modified_at) - new Date(a.last_modified_at),
+  );
+  for (const session of sessions) {
+    const row = tbody.insertRow();
+    row.dataset.id = session.id;
+    cell(row, session.id);
+    cell(row, session.source_path || "—", "muted");
+    cell(row, formatDate(session.last_modified_at));
+    cell(row, `${session.cursor_position}/${session.wal_count}`);
+    const [label, className] = syncLabel(syncBySession.get(session.id));
+    cell(row, label, className);
+    row.addEventListener("click", () => selectSession(session.id));
+  }
+}
+
+async function selectSession(id) {
+  state.session = id;
+  state.walNext = 1;
+  for (const row of $("sessions").tBodies[0].rows) {
+    row.classList.toggle("selected", row.dataset.id === id);
+  }
+
+  $("detail").hidden = false;
+  $("detail-title").textContent = id;
+  $("download").href = api(`sessions/${encodeURIComponent(id)}/document`);
+  $("wal").replaceChildren();
+
+  await Promise.all([loadSync(id), loadCheckpoints(id), loadWal(id)]);
+}
+
+async function loadSync(id) {
+  const dl = $("sync");
+  dl.replaceChildren();
+  let status = null;
+  try {
+    status = await getJson(`sessions/${encodeURIComponent(id)}/sync`);
+  } catch (err) {
+    // No registered source is a normal state, not an error.
+  }
+
+  const fields = status
+    ? [
+        ["Source", status.source.path],
+        ["Type", status.source.type],
+        ["Auto-sync", status.auto_sync_enabled ? "enabled" : "disabled"],
+        ["Last synced", formatDate(status.last_synced_at)],
+        ["Pending changes", status.has_pending_changes ? "yes" : "no"],
+        ["Last error", status.last_error || "—"],
+      ]
+    : [["Source", "none registered"]];
+
+  for (const [name, value] of fields) {
+    const dt = document.createElement("dt");
+    dt.textContent = name;
+    const dd = document.createElement("dd");
+    dd.textContent = value;
+    dl.append(dt, dd);
+  }
+}
+
+async function loadCheckpoints(id) {
+  const checkpoints = await getJson(`sessions/${encodeURIComponent(id)}/checkpoints`);
+  const tbody = $("checkpoints").tBodies[0];
+  tbody.replaceChildren();
+  for (const checkpoint of checkpoints) {
+    const row = tbody.insertRow();
+    cell(row, checkpoint.position);
+    cell(row, formatDate(checkpoint.created_at));
+    cell(row, formatSize(checkpoint.size_bytes));
+    const link = document.createElement("a");
+    link.href = api(`sessions/${encodeURIComponent(id)}/checkpoints/${checkpoint.position}`);
+    link.textContent = "download";
+    row.insertCell().append(link);
+  }
+}
+
+async function loadWal(id) {
+  const page = await getJson(`sessions/${encodeURIComponent(id)}/wal`, {
+    from: state.walNext,
+    limit: WAL_PAGE,
+  });
+  if (state.session !== id) return;
+
+  const list = $("wal");
+  for (const entry of page.entries) {
+    const item = document.createElement("li");
+    const title = document.createElement("div");
+    title.textContent = `#${entry.position} ${entry.operation || "patch"} ${entry.path} · ${formatDate(entry.timestamp)}`;
+    const patch = document.createElement("code");
+    patch.textContent = JSON.stringify(entry.patch);
+    item.append(title, patch);
+    list.append(item);
+    state.walNext = entry.position + 1;
+  }
+  $("wal-more").hidden = !page.has_more;
+}
+
+$("tenant-form").addEventListener("submit", (event) => {
+  event.preventDefault();
+  state.tenant = $("tenant").value.trim();
+  state.session = null;
+  $("detail").hidden = true;
+  const url = new URL(location.href);
+  url.searchParams.set("tenant", state.tenant);
+  history.replaceState(null, "", url);
+  loadSessions().catch(showError);
+});
+
+$("wal-more").addEventListener("click", () => {
+  if (state.session) loadWal(state.session).catch(showError);
+});
+
+$("tenant").value = state.tenant;
+loadHealth();
+loadSessions().catch(showError);
@@ -0,0 +1,126 @@
+//! Runs the typed client against a local storage server.
+
+use std::sync::Arc;
+
+use docx_storage_client::proto::{AddSessionToIndexRequest, SessionIndexEntry};
+use docx_storage_client::{RetryPolicy, StorageClient};
+use docx_storage_core::SessionPageQuery;
+use docx_storage_local::lock::FileLock;
+use docx_storage_local::service::proto::storage_service_server::StorageServiceServer;
+use docx_storage_local::service::StorageServiceImpl;
+use docx_storage_local::storage::LocalStorage;
+use futures::TryStreamExt;
+use tempfile::TempDir;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+
+/// Serve a local storage in `dir` and connect a client to it.
+async fn connect(dir: &TempDir) -> StorageClient {
+    let storage = Arc::new(LocalStorage::new(dir.path()));
+    let service = StorageServiceImpl::new(storage, Arc::new(FileLock::new(dir.path())));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(StorageServiceServer::new(service))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    StorageClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap()
+        .with_retry_policy(RetryPolicy::none())
+}
+
+#[tokio::test]
+async fn sessions_stream_in_chunks() {
+    let dir = TempDir::new().unwrap();
+    let client = connect(&dir).await.with_tenant("acme").with_chunk_size(1000);
+    let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+    // Exactly ten chunks: the last one is only known by reading ahead
+    client
+        .save_session_from("s-1", std::io::Cursor::new(data.clone()))
+        .await
+        .unwrap();
+    assert_eq!(client.load_session("s-1").await.unwrap(), Some(data.clone()));
+
+    let mut downloaded = Vec::new();
+    assert!(client.load_session_into("s-1", &mut downloaded).await.unwrap());
+    assert_eq!(downloaded, data);
+
+    client.save_session("s-2", &[]).await.unwrap();
+    assert_eq!(client.load_session("s-2").await.unwrap(), Some(Vec::new()));
+    assert_eq!(client.load_session("missing").await.unwrap(), None);
+
+    // Sessions belong to the client's tenant
+    let other = client.clone().with_tenant("globex");
+    assert!(!other.session_exists("s-1").await.unwrap());
+    assert!(client.session_exists("s-1").await.unwrap());
+}
+
+#[tokio::test]
+async fn checkpoints_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let client = connect(&dir).await.with_tenant("acme").with_chunk_size(7);
+
+    client.save_checkpoint("s-1", 3, b"checkpoint three").await.unwrap();
+    client.save_checkpoint("s-1", 8, b"checkpoint eight").await.unwrap();
+    assert_eq!(
+        client.load_checkpoint("s-1", 0).await.unwrap(),
+        Some((b"checkpoint eight".to_vec(), 8))
+    );
+    assert_eq!(
+        client.load_checkpoint("s-1", 3).await.unwrap(),
+        Some((b"checkpoint three".to_vec(), 3))
+    );
+    assert_eq!(client.load_checkpoint("other", 0).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn sessions_matching_follows_pages() {
+    let dir = TempDir::new().unwrap();
+    let client = connect(&dir).await.with_tenant("acme");
+    for session in ["d-1", "a-1", "c-2", "b-2", "e-1"] {
+        client
+            .raw()
+            .add_session_to_index(client.request(AddSessionToIndexRequest {
+                context: client.context(),
+                session_id: session.to_string(),
+                entry: Some(SessionIndexEntry {
+                    source_path: format!("/Reports/{}.docx", session),
+                    ..Default::default()
+                }),
+            }))
+            .await
+            .unwrap();
+    }
+
+    let ids = |query: SessionPageQuery| async {
+        client
+            .sessions_matching(query)
+            .map_ok(|session| session.session_id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    };
+    assert_eq!(
+        ids(SessionPageQuery {
+            page_size: 2,
+            ..Default::default()
+        })
+        .await,
+        ["a-1", "b-2", "c-2", "d-1", "e-1"]
+    );
+    assert_eq!(
+        ids(SessionPageQuery {
+            search: Some("-1.DOCX".to_string()),
+            page_size: 1,
+            ..Default::default()
+        })
+        .await,
+        ["a-1", "d-1", "e-1"]
+    );
+
+    let index = client.load_index().await.unwrap().unwrap();
+    assert_eq!(index.sessions.len(), 5);
+}
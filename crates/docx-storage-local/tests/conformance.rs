@@ -0,0 +1,19 @@
+//! Runs the shared backend conformance suite against the local implementations.
+
+use docx_storage_local::lock::FileLock;
+use docx_storage_local::storage::LocalStorage;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn local_storage_conformance() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = LocalStorage::new(temp_dir.path());
+    docx_storage_conformance::run_storage_suite(&storage).await;
+}
+
+#[tokio::test]
+async fn file_lock_conformance() {
+    let temp_dir = TempDir::new().unwrap();
+    let locks = FileLock::new(temp_dir.path());
+    docx_storage_conformance::run_lock_suite(&locks).await;
+}
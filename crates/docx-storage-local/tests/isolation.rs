@@ -0,0 +1,452 @@
+//! Tenant isolation across the storage, sync and watch services: a tenant
+//! can't reach another tenant's sessions, whatever tenant header it sends,
+//! nor escape the storage directory with crafted tenant or session IDs.
+
+use std::path::Path;
+
+use docx_storage_client::proto::external_watch_service_client::ExternalWatchServiceClient;
+use docx_storage_client::proto::source_sync_service_client::SourceSyncServiceClient;
+use docx_storage_client::proto::storage_service_client::StorageServiceClient;
+use docx_storage_client::proto::*;
+use docx_storage_client::{RetryPolicy, StorageClient};
+use docx_storage_local::server::{create_backends, create_change_queue};
+use docx_storage_local::service::proto::external_watch_service_server::ExternalWatchServiceServer;
+use docx_storage_local::service::proto::source_sync_service_server::SourceSyncServiceServer;
+use docx_storage_local::service::proto::storage_service_server::StorageServiceServer;
+use docx_storage_local::service::StorageServiceImpl;
+use docx_storage_local::service_sync::SourceSyncServiceImpl;
+use docx_storage_local::service_watch::ExternalWatchServiceImpl;
+use tempfile::TempDir;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Channel;
+use tonic::{Code, Request};
+
+/// Tenant IDs and session IDs trying to leave their directory.
+const TRAVERSALS: &[&str] = &[
+    "..",
+    "../acme",
+    "../../etc/passwd",
+    "acme/../globex",
+    "s-1/../../acme/s-1",
+    "..\\acme",
+    "/etc/passwd",
+    "C:\\Windows\\win.ini",
+    "acme\0globex",
+    "acme\n",
+];
+
+struct Server {
+    endpoint: String,
+    channel: Channel,
+}
+
+impl Server {
+    async fn start(dir: &Path) -> Self {
+        let (storage, lock, sync, watch, browse) = create_backends(dir);
+        let storage_service = StorageServiceImpl::new(storage, lock).with_sync_backend(sync.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(StorageServiceServer::new(storage_service))
+                .add_service(SourceSyncServiceServer::new(SourceSyncServiceImpl::new(sync, browse)))
+                .add_service(ExternalWatchServiceServer::new(ExternalWatchServiceImpl::new(
+                    watch,
+                    create_change_queue(dir),
+                )))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        let channel = Channel::from_shared(endpoint.clone()).unwrap().connect().await.unwrap();
+        Self { endpoint, channel }
+    }
+
+    async fn client(&self, tenant_id: &str) -> StorageClient {
+        StorageClient::connect(self.endpoint.clone())
+            .await
+            .unwrap()
+            .with_tenant(tenant_id)
+            .with_retry_policy(RetryPolicy::none())
+    }
+
+    fn sync(&self) -> SourceSyncServiceClient<Channel> {
+        SourceSyncServiceClient::new(self.channel.clone())
+    }
+
+    fn watch(&self) -> ExternalWatchServiceClient<Channel> {
+        ExternalWatchServiceClient::new(self.channel.clone())
+    }
+
+    fn storage(&self) -> StorageServiceClient<Channel> {
+        StorageServiceClient::new(self.channel.clone())
+    }
+}
+
+fn context(tenant_id: &str) -> Option<TenantContext> {
+    Some(TenantContext {
+        tenant_id: tenant_id.to_string(),
+        ..Default::default()
+    })
+}
+
+/// A request of `context`, sent with a forged `x-tenant-id` header.
+fn forged<T>(message: T, header: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("x-tenant-id", header.parse().unwrap());
+    request
+}
+
+fn wal_entry(position: u64) -> WalEntry {
+    WalEntry {
+        position,
+        patch_json: br#"{"version":1,"patches":"[{\"op\":\"add\"}]","timestamp":"2026-01-01T00:00:00Z"}"#
+            .to_vec(),
+        ..Default::default()
+    }
+}
+
+fn local_source(path: &str) -> Option<SourceDescriptor> {
+    Some(SourceDescriptor {
+        r#type: 1,
+        path: path.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Every file under `dir`, relative to it.
+fn files_under(dir: &Path) -> Vec<String> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().strip_prefix(dir).unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn tenants_cannot_reach_each_others_sessions() {
+    let dir = TempDir::new().unwrap();
+    let server = Server::start(dir.path()).await;
+    let acme = server.client("acme").await;
+    let globex = server.client("globex").await;
+
+    acme.save_session("s-1", b"acme contract").await.unwrap();
+    acme.append_wal("s-1", vec![wal_entry(1)]).await.unwrap();
+    acme.save_checkpoint("s-1", 1, b"acme checkpoint").await.unwrap();
+    acme.raw()
+        .add_session_to_index(acme.request(AddSessionToIndexRequest {
+            context: acme.context(),
+            session_id: "s-1".to_string(),
+            entry: Some(SessionIndexEntry::default()),
+        }))
+        .await
+        .unwrap();
+    server
+        .sync()
+        .register_source(RegisterSourceRequest {
+            context: context("acme"),
+            session_id: "s-1".to_string(),
+            source: local_source("/tmp/acme-contract.docx"),
+            auto_sync: true,
+        })
+        .await
+        .unwrap();
+
+    // Same session ID, other tenant: nothing is there
+    assert_eq!(globex.load_session("s-1").await.unwrap(), None);
+    assert!(!globex.session_exists("s-1").await.unwrap());
+    assert!(globex.list_sessions().await.unwrap().is_empty());
+    assert!(globex
+        .load_index()
+        .await
+        .unwrap()
+        .is_none_or(|index| !index.contains("s-1")));
+    assert!(globex.read_wal("s-1", 0, 0).await.unwrap().entries.is_empty());
+    assert_eq!(globex.load_checkpoint("s-1", 0).await.unwrap(), None);
+    assert!(globex.list_checkpoints("s-1").await.unwrap().is_empty());
+    let status = server
+        .sync()
+        .get_sync_status(GetSyncStatusRequest {
+            context: context("globex"),
+            session_id: "s-1".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!status.registered);
+    let sources = server
+        .sync()
+        .list_sources(ListSourcesRequest { context: context("globex") })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(sources.sources.is_empty());
+
+    // Writes land in the writer's tenant only
+    globex.save_session("s-1", b"globex draft").await.unwrap();
+    globex.append_wal("s-1", vec![wal_entry(1), wal_entry(2)]).await.unwrap();
+    assert!(globex.delete_session("s-1").await.unwrap());
+    assert_eq!(acme.load_session("s-1").await.unwrap(), Some(b"acme contract".to_vec()));
+    assert_eq!(acme.read_wal("s-1", 0, 0).await.unwrap().entries.len(), 1);
+    assert_eq!(
+        acme.load_checkpoint("s-1", 0).await.unwrap(),
+        Some((b"acme checkpoint".to_vec(), 1))
+    );
+
+    // The tenant header doesn't pick the tenant: the request context does
+    let mut storage = server.storage();
+    let mut stream = storage
+        .load_session(forged(
+            LoadSessionRequest {
+                context: context("globex"),
+                session_id: "s-1".to_string(),
+            },
+            "acme",
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    let chunk = stream.message().await.unwrap().unwrap();
+    assert!(!chunk.found);
+    let exists = storage
+        .session_exists(forged(
+            SessionExistsRequest {
+                context: context("globex"),
+                session_id: "s-1".to_string(),
+            },
+            "acme",
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!exists.exists);
+    let status = server
+        .sync()
+        .get_sync_status(forged(
+            GetSyncStatusRequest {
+                context: context("globex"),
+                session_id: "s-1".to_string(),
+            },
+            "acme",
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!status.registered);
+    storage
+        .delete_session(forged(
+            DeleteSessionRequest {
+                context: context("globex"),
+                session_id: "s-1".to_string(),
+            },
+            "acme",
+        ))
+        .await
+        .unwrap();
+    assert!(acme.session_exists("s-1").await.unwrap());
+}
+
+#[tokio::test]
+async fn requests_without_a_tenant_context_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let server = Server::start(dir.path()).await;
+    server
+        .client("acme")
+        .await
+        .save_session("s-1", b"acme contract")
+        .await
+        .unwrap();
+
+    // A tenant header alone is not a tenant context
+    let mut storage = server.storage();
+    let codes = [
+        storage
+            .session_exists(forged(
+                SessionExistsRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        storage
+            .load_session(forged(
+                LoadSessionRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        storage
+            .list_sessions(forged(
+                ListSessionsRequest {
+                    context: None,
+                    ..Default::default()
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        storage
+            .load_index(forged(LoadIndexRequest { context: None }, "acme"))
+            .await
+            .map(|_| ()),
+        storage
+            .read_wal(forged(
+                ReadWalRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                    ..Default::default()
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        storage
+            .append_wal(forged(
+                AppendWalRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                    entries: vec![wal_entry(1)],
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        storage
+            .delete_session(forged(
+                DeleteSessionRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        server
+            .sync()
+            .get_sync_status(forged(
+                GetSyncStatusRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+        server
+            .sync()
+            .list_sources(forged(ListSourcesRequest { context: None }, "acme"))
+            .await
+            .map(|_| ()),
+        server
+            .watch()
+            .get_source_metadata(forged(
+                GetSourceMetadataRequest {
+                    context: None,
+                    session_id: "s-1".to_string(),
+                },
+                "acme",
+            ))
+            .await
+            .map(|_| ()),
+    ];
+    for (i, result) in codes.into_iter().enumerate() {
+        assert_eq!(
+            result.err().map(|status| status.code()),
+            Some(Code::InvalidArgument),
+            "call {i} succeeded without a tenant context"
+        );
+    }
+    assert!(server.client("acme").await.session_exists("s-1").await.unwrap());
+}
+
+#[tokio::test]
+async fn crafted_ids_cannot_leave_the_storage_directory() {
+    let root = TempDir::new().unwrap();
+    let dir = root.path().join("storage");
+    std::fs::create_dir(&dir).unwrap();
+    std::fs::write(root.path().join("secret.docx"), b"outside").unwrap();
+    let server = Server::start(&dir).await;
+    let acme = server.client("acme").await;
+    acme.save_session("s-1", b"acme contract").await.unwrap();
+
+    for id in TRAVERSALS {
+        // As a session ID of a legitimate tenant
+        let globex = server.client("globex").await;
+        assert_eq!(
+            globex.save_session(id, b"payload").await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "saved session {id:?}"
+        );
+        assert_eq!(
+            globex.load_session(id).await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "loaded session {id:?}"
+        );
+        assert_eq!(
+            globex.delete_session(id).await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "deleted session {id:?}"
+        );
+        assert_eq!(
+            globex.append_wal(id, vec![wal_entry(1)]).await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "appended to the WAL of {id:?}"
+        );
+        assert_eq!(
+            globex.save_checkpoint(id, 1, b"payload").await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "saved a checkpoint of {id:?}"
+        );
+        let registered = server
+            .sync()
+            .register_source(RegisterSourceRequest {
+                context: context("globex"),
+                session_id: id.to_string(),
+                source: local_source("/tmp/payload.docx"),
+                auto_sync: true,
+            })
+            .await;
+        assert!(
+            registered.map_or(true, |r| !r.into_inner().success),
+            "registered a source for {id:?}"
+        );
+
+        // As a tenant ID
+        let tenant = server.client(id).await;
+        assert_eq!(
+            tenant.save_session("s-1", b"payload").await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "saved as tenant {id:?}"
+        );
+        assert_eq!(
+            tenant.load_session("s-1").await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "loaded as tenant {id:?}"
+        );
+        assert_eq!(
+            tenant.list_sessions().await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "listed as tenant {id:?}"
+        );
+        assert_eq!(
+            tenant.delete_session("s-1").await.unwrap_err().code(),
+            Some(Code::InvalidArgument),
+            "deleted as tenant {id:?}"
+        );
+    }
+
+    // Nothing was written or deleted outside the tenants' directories
+    assert_eq!(std::fs::read(root.path().join("secret.docx")).unwrap(), b"outside");
+    assert_eq!(files_under(root.path()).len(), files_under(&dir).len() + 1);
+    assert!(files_under(&dir)
+        .iter()
+        .all(|file| file.starts_with("acme") || file.starts_with("globex") || !file.contains('/')));
+    assert_eq!(acme.load_session("s-1").await.unwrap(), Some(b"acme contract".to_vec()));
+}
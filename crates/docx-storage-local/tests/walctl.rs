@@ -0,0 +1,263 @@
+//! Runs the walctl binary against local storage servers.
+
+use std::process::Output;
+use std::sync::Arc;
+
+use docx_storage_client::proto::{AddSessionToIndexRequest, SessionIndexEntry, WalEntry};
+use docx_storage_client::{RetryPolicy, StorageClient};
+use docx_storage_local::lock::FileLock;
+use docx_storage_local::service::proto::storage_service_server::StorageServiceServer;
+use docx_storage_local::service::StorageServiceImpl;
+use docx_storage_local::storage::LocalStorage;
+use tempfile::TempDir;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+
+/// A local storage server in its own directory.
+struct Server {
+    _dir: TempDir,
+    url: String,
+    client: StorageClient,
+}
+
+/// Serve a local storage in a fresh directory and connect a client to it.
+async fn serve() -> Server {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(LocalStorage::new(dir.path()));
+    let service = StorageServiceImpl::new(storage, Arc::new(FileLock::new(dir.path())));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(StorageServiceServer::new(service))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let client = StorageClient::connect(url.clone())
+        .await
+        .unwrap()
+        .with_retry_policy(RetryPolicy::none())
+        .with_tenant("acme");
+    Server {
+        _dir: dir,
+        url,
+        client,
+    }
+}
+
+/// Run walctl on the `acme` tenant of `server`.
+async fn walctl(server: &Server, args: &[&str]) -> Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_walctl"))
+        .args(["--server", &server.url, "--tenant", "acme", "--actor", "test"])
+        .args(args)
+        .output()
+        .await
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// A WAL record, told apart by its timestamp.
+fn record(n: u64) -> String {
+    format!(
+        r#"{{"version":1,"patches":"[]","timestamp":"2026-01-01T00:00:0{}Z"}}"#,
+        n
+    )
+}
+
+fn wal_entry(position: u64) -> WalEntry {
+    WalEntry {
+        position,
+        operation: "add".to_string(),
+        path: "/body".to_string(),
+        patch_json: record(position).into_bytes(),
+        timestamp_unix: 1_700_000_000 + position as i64,
+    }
+}
+
+/// Store a session with a two-entry WAL, a checkpoint at 2 and its index entry.
+async fn seed_session(server: &Server, session_id: &str) {
+    let client = &server.client;
+    client
+        .save_session(session_id, format!("PK\x03\x04{}", session_id).as_bytes())
+        .await
+        .unwrap();
+    client
+        .append_wal(session_id, vec![wal_entry(1), wal_entry(2)])
+        .await
+        .unwrap();
+    client
+        .save_checkpoint(session_id, 2, b"PK\x03\x04checkpoint")
+        .await
+        .unwrap();
+    client
+        .raw()
+        .add_session_to_index(client.request(AddSessionToIndexRequest {
+            context: client.context(),
+            session_id: session_id.to_string(),
+            entry: Some(SessionIndexEntry {
+                source_path: format!("/Reports/{}.docx", session_id),
+                created_at_unix: 1_700_000_000,
+                modified_at_unix: 1_700_000_002,
+                wal_position: 2,
+                checkpoint_positions: vec![2],
+                ..Default::default()
+            }),
+        }))
+        .await
+        .unwrap();
+}
+
+async fn wal_patches(server: &Server, session_id: &str) -> Vec<String> {
+    server
+        .client
+        .read_wal(session_id, 0, 0)
+        .await
+        .unwrap()
+        .entries
+        .into_iter()
+        .map(|e| String::from_utf8(e.patch_json).unwrap())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_copies_verifies_and_cuts_over() {
+    let source = serve().await;
+    let target = serve().await;
+    seed_session(&source, "s-1").await;
+    seed_session(&source, "s-2").await;
+
+    // Without --cutover the source keeps its sessions
+    let output = walctl(&source, &["migrate", "--to", &target.url, "--session", "s-1"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Verified 1 session(s)"));
+    assert!(source.client.session_exists("s-1").await.unwrap());
+    assert!(!target.client.session_exists("s-2").await.unwrap());
+
+    // Existing sessions on the target are refused without --force, and
+    // nothing is removed from the source
+    let output = walctl(&source, &["migrate", "--to", &target.url, "--cutover"]).await;
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already exists on the target"));
+    assert!(source.client.session_exists("s-1").await.unwrap());
+
+    let output = walctl(&source, &["migrate", "--to", &target.url, "--force", "--cutover"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Cut over: removed 2 session(s)"));
+
+    for session_id in ["s-1", "s-2"] {
+        assert!(!source.client.session_exists(session_id).await.unwrap());
+        assert_eq!(
+            target.client.load_session(session_id).await.unwrap(),
+            Some(format!("PK\x03\x04{}", session_id).into_bytes())
+        );
+        assert_eq!(wal_patches(&target, session_id).await, [record(1), record(2)]);
+        assert_eq!(
+            target.client.load_checkpoint(session_id, 2).await.unwrap(),
+            Some((b"PK\x03\x04checkpoint".to_vec(), 2))
+        );
+    }
+    let index = target.client.load_index().await.unwrap().unwrap();
+    let entry = index.get("s-2").unwrap();
+    assert_eq!(entry.source_path.as_deref(), Some("/Reports/s-2.docx"));
+    assert_eq!((entry.wal_count, entry.checkpoint_positions.clone()), (2, vec![2]));
+    assert!(source.client.load_index().await.unwrap().unwrap().get("s-1").is_none());
+
+    // The copy verifies on the target
+    let output = walctl(&target, &["verify"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn verify_reports_index_and_storage_mismatches() {
+    let server = serve().await;
+    seed_session(&server, "s-1").await;
+    seed_session(&server, "s-2").await;
+
+    let output = walctl(&server, &["verify"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("OK: index and storage are consistent"));
+
+    // A document nobody indexed, and a checkpoint the index doesn't know
+    server.client.save_session("stray", b"PK\x03\x04stray").await.unwrap();
+    server.client.save_checkpoint("s-2", 1, b"PK\x03\x04one").await.unwrap();
+
+    let output = walctl(&server, &["verify"]).await;
+    assert!(!output.status.success());
+    let problems = String::from_utf8_lossy(&output.stdout);
+    assert!(problems.contains("stray: stored document missing from index"), "{}", problems);
+    assert!(problems.contains("s-2: checkpoint 1 stored but not indexed"), "{}", problems);
+    assert!(stderr(&output).contains("2 problem(s) found"));
+
+    // Verifying one session only looks at that one
+    let output = walctl(&server, &["verify", "s-1"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wal_replay_appends_and_truncate_drops_entries() {
+    let server = serve().await;
+    seed_session(&server, "s-1").await;
+    let dir = TempDir::new().unwrap();
+
+    let file = dir.path().join("entries.jsonl");
+    std::fs::write(&file, format!("{}\n\n{}\n", record(3), record(4))).unwrap();
+    let output = walctl(&server, &["wal", "replay", "s-1", file.to_str().unwrap()]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Appended 2 entries to s-1 (positions 3..=4)"));
+    assert_eq!(
+        wal_patches(&server, "s-1").await,
+        [record(1), record(2), record(3), record(4)]
+    );
+
+    // Invalid JSON is refused before anything is appended
+    std::fs::write(&file, format!("{}\nnot json\n", record(5))).unwrap();
+    let output = walctl(&server, &["wal", "replay", "s-1", file.to_str().unwrap()]).await;
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Line 2 is not valid JSON"));
+    assert_eq!(wal_patches(&server, "s-1").await.len(), 4);
+
+    // Keeps the first N entries
+    let output = walctl(&server, &["wal", "truncate", "s-1", "2"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Removed 2 entries from s-1"));
+    assert_eq!(wal_patches(&server, "s-1").await, [record(1), record(2)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_then_import_restores_the_session() {
+    let source = serve().await;
+    let target = serve().await;
+    seed_session(&source, "s-1").await;
+    let dir = TempDir::new().unwrap();
+    let export = dir.path().join("s-1");
+    let export = export.to_str().unwrap();
+
+    let output = walctl(&source, &["export", "s-1", export]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.path().join("s-1/checkpoints/2.docx").exists());
+
+    let output = walctl(&target, &["import", "s-1", export]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Imported s-1 (7 bytes, 2 WAL entries, 1 checkpoints)"));
+    assert_eq!(
+        target.client.load_session("s-1").await.unwrap(),
+        Some(b"PK\x03\x04s-1".to_vec())
+    );
+    assert_eq!(wal_patches(&target, "s-1").await, [record(1), record(2)]);
+    let index = target.client.load_index().await.unwrap().unwrap();
+    let entry = index.get("s-1").unwrap();
+    assert_eq!(entry.source_path.as_deref(), Some("/Reports/s-1.docx"));
+    assert_eq!((entry.wal_count, entry.checkpoint_positions.clone()), (2, vec![2]));
+    let output = walctl(&target, &["verify"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // An existing session is only replaced with --force
+    let output = walctl(&target, &["import", "s-1", export]).await;
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("already exists (use --force to replace it)"));
+    let output = walctl(&target, &["import", "s-1", export, "--force"]).await;
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(wal_patches(&target, "s-1").await.len(), 2);
+}
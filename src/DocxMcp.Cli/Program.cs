@@ -1,396 +1,622 @@
+using System.CommandLine;
 using System.Text.Json;
 using DocxMcp;
 using DocxMcp.Diff;
 using DocxMcp.ExternalChanges;
 using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
 using DocxMcp.Tools;
 using Microsoft.Extensions.Logging.Abstractions;
 
-// --- Bootstrap ---
+// --- Command line ---
 
-// Parse global --tenant flag first
-var tenantId = TenantContextHelper.LocalTenant;
-var filteredArgs = new List<string>();
-for (int i = 0; i < args.Length; i++)
+var tenantOption = new Option<string>("--tenant")
 {
-    if (args[i] == "--tenant" && i + 1 < args.Length)
-    {
-        tenantId = args[i + 1];
-        i++; // Skip the value
-    }
-    else if (!args[i].StartsWith("--tenant="))
-    {
-        filteredArgs.Add(args[i]);
-    }
-    else
-    {
-        tenantId = args[i].Substring("--tenant=".Length);
-    }
+    Description = "Tenant ID for multi-tenant deployments",
+    DefaultValueFactory = _ => TenantContextHelper.LocalTenant,
+    Recursive = true
+};
+
+var root = new RootCommand("""
+    docx-cli — CLI for DOCX document manipulation
+
+    Most commands accept either a session ID or a file path. When using a file path,
+    an existing session is reused if one exists, otherwise a new session is auto-opened.
+
+    Sessions persist between invocations and are shared with the MCP server.
+    WAL history is preserved automatically; use 'close' to permanently delete a session.
+
+    Environment:
+      STORAGE_GRPC_URL             gRPC storage server URL (auto-launches local if not set)
+      DOCX_SESSIONS_DIR            Override sessions directory (legacy, for local storage)
+      DOCX_WAL_COMPACT_THRESHOLD   Auto-compact WAL after N entries (default: 50)
+      DOCX_CHECKPOINT_INTERVAL     Create checkpoint every N entries (default: 10)
+      DOCX_AUTO_SAVE               Auto-save to source file after each edit (default: true)
+      DOCX_DETERMINISTIC           Byte-identical output for the same edits (default: false)
+      DEBUG                        Enable debug logging for sync operations
+    """)
+{
+    tenantOption
+};
+
+var docToolsLogger = NullLogger<DocumentTools>.Instance;
+
+// --- Document commands ---
+
+{
+    var path = OptionalArg("path", "File to open; creates a new document when omitted");
+    AddCommand(new Command("open", "Open file or create new document") { path },
+        (s, r) => DocumentTools.DocumentOpen(docToolsLogger, s.Tenant, s.Sync, r.GetValue(path)));
 }
-args = filteredArgs.ToArray();
 
-// Set tenant context for all operations
-TenantContextHelper.CurrentTenantId = tenantId;
+AddCommand(new Command("new", "Create a new empty document"),
+    (s, _) => DocumentTools.DocumentOpen(docToolsLogger, s.Tenant, s.Sync));
+
+AddCommand(new Command("list", "List open sessions"),
+    (s, _) => DocumentTools.DocumentList(docToolsLogger, s.Tenant));
 
-// Create gRPC storage clients (embedded or remote)
-var isDebug = Environment.GetEnvironmentVariable("DEBUG") is not null;
-var storageOptions = StorageClientOptions.FromEnvironment();
-IHistoryStorage historyStorage;
-ISyncStorage syncStorage;
+{
+    var doc = DocArg();
+    var output = OptionalArg("output_path", "Where to save (default: the source file)");
+    AddCommand(new Command("save", "Save document to disk") { doc, output },
+        (s, r) => DocumentTools.DocumentSave(docToolsLogger, s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(output)));
+}
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path") { Description = "New source file" };
+    var noAutoSync = new Option<bool>("--no-auto-sync") { Description = "Don't save to the file after each edit" };
+    AddCommand(new Command("set-source", "Set/change save target") { doc, path, noAutoSync },
+        (s, r) => DocumentTools.DocumentSetSource(docToolsLogger, s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(path)!, auto_sync: !r.GetValue(noAutoSync)));
+}
 
-if (!string.IsNullOrEmpty(storageOptions.ServerUrl))
 {
-    // Dual mode — remote for history, local embedded for sync/watch
-    if (isDebug) Console.Error.WriteLine("[cli] Using dual mode: remote=" + storageOptions.ServerUrl);
-    var launcher = new GrpcLauncher(storageOptions, NullLogger<GrpcLauncher>.Instance);
-    historyStorage = HistoryStorageClient.CreateAsync(storageOptions, launcher, NullLogger<HistoryStorageClient>.Instance).GetAwaiter().GetResult();
+    var doc = DocArg();
+    AddCommand(new Command("inspect", "Show detailed session information") { doc },
+        (s, r) => Inspect(s, r.GetValue(doc)!));
+}
+
+// --- Administrative commands (CLI-only, not exposed to MCP) ---
+
+{
+    var doc = DocArg();
+    AddCommand(new Command("close", "Close session and delete all persisted data") { doc },
+        (s, r) => DocumentTools.DocumentClose(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!)));
+}
+
+{
+    var doc = DocArg();
+    var discardRedo = new Option<bool>("--discard-redo") { Description = "Discard redo history when compacting" };
+    AddCommand(new Command("snapshot", "Force WAL compaction into new baseline") { doc, discardRedo },
+        (s, r) => DocumentTools.DocumentSnapshot(s.Tenant, s.Resolve(r.GetValue(doc)!), r.GetValue(discardRedo)));
+}
+
+// --- Query commands ---
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path") { Description = "Typed path, e.g. /body/paragraph[*]" };
+    var format = new Option<string>("--format") { Description = "Output format", DefaultValueFactory = _ => "json" }
+        .AcceptOnlyFromAmong("json", "text", "summary");
+    var offset = new Option<int?>("--offset");
+    var limit = new Option<int?>("--limit");
+    AddCommand(new Command("query", "Query elements at a path") { doc, path, format, offset, limit },
+        (s, r) => QueryTool.Query(s.Tenant, s.Resolve(r.GetValue(doc)!), r.GetValue(path)!, r.GetValue(format)!,
+            r.GetValue(offset), r.GetValue(limit)));
+}
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path") { Description = "Typed path, e.g. /body/heading[*]" };
+    AddCommand(new Command("count", "Count elements at a path") { doc, path },
+        (s, r) => CountTool.CountElements(s.Tenant, s.Resolve(r.GetValue(doc)!), r.GetValue(path)!));
+}
+
+{
+    var doc = DocArg();
+    var index = new Option<int?>("--index") { Description = "Section index" };
+    var format = new Option<string?>("--format");
+    var offset = new Option<int?>("--offset");
+    var limit = new Option<int?>("--limit");
+    AddCommand(new Command("read-section", "Read the content of a section") { doc, index, format, offset, limit },
+        (s, r) => ReadSectionTool.ReadSection(s.Tenant, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(index), r.GetValue(format), r.GetValue(offset), r.GetValue(limit)));
+}
 
-    // Local embedded for sync/watch
-    NativeStorage.Init(storageOptions.GetEffectiveLocalStorageDir());
-    var localHandler = new System.Net.Http.SocketsHttpHandler
+{
+    var doc = DocArg();
+    var text = new Option<string?>("--text") { Description = "Heading text" };
+    var index = new Option<int?>("--index") { Description = "Heading index" };
+    var level = new Option<int?>("--level") { Description = "Heading level" };
+    var noSubHeadings = new Option<bool>("--no-sub-headings") { Description = "Leave out content under sub-headings" };
+    var format = new Option<string?>("--format");
+    var offset = new Option<int?>("--offset");
+    var limit = new Option<int?>("--limit");
+    AddCommand(new Command("read-heading", "Read the content under a heading")
+        {
+            doc, text, index, level, noSubHeadings, format, offset, limit
+        },
+        (s, r) => ReadHeadingContentTool.ReadHeadingContent(s.Tenant, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(text), r.GetValue(index), r.GetValue(level), !r.GetValue(noSubHeadings),
+            r.GetValue(format), r.GetValue(offset), r.GetValue(limit)));
+}
+
+// --- Element operations ---
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path") { Description = "Insert path, e.g. /body/children/0" };
+    var value = OptionalArg("value_json", "Element JSON (default: read from stdin)");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("add", "Add element at path") { doc, path, value, dryRun },
+        (s, r) => ElementTools.AddElement(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!), r.GetValue(path)!,
+            r.GetValue(value) ?? ReadStdin(), r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var text = OptionalArg("text", "Paragraph text (default: read from stdin)");
+    var style = new Option<string?>("--style") { Description = "Paragraph style name, e.g. Heading1" };
+    var path = new Option<string?>("--path") { Description = "Insert path (default: end of body)" };
+    var dryRun = DryRunOption();
+    AddCommand(new Command("add-paragraph", "Append a paragraph") { doc, text, style, path, dryRun },
+        (s, r) => AddParagraph(s, s.Resolve(r.GetValue(doc)!), r.GetValue(text) ?? ReadStdin(),
+            r.GetValue(style), r.GetValue(path), r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var file = OptionalArg("markdown_file", "Markdown file (default: read from stdin)");
+    var path = new Option<string?>("--path")
     {
-        ConnectCallback = (_, _) => new ValueTask<Stream>(new InMemoryPipeStream())
+        Description = "Insert path, or path to the body elements to replace (default: end of body)"
     };
-    var localChannel = Grpc.Net.Client.GrpcChannel.ForAddress("http://in-memory", new Grpc.Net.Client.GrpcChannelOptions
-    {
-        HttpHandler = localHandler
-    });
-    syncStorage = new SyncStorageClient(localChannel, NullLogger<SyncStorageClient>.Instance);
+    AddCommand(new Command("import-md", "Import Markdown as headings, paragraphs, lists, tables and code")
+        {
+            doc, file, path
+        },
+        (s, r) => ImportMarkdown(s, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(file) is { } f ? File.ReadAllText(f) : ReadStdin(), r.GetValue(path)));
 }
-else
+
 {
-    // Embedded mode — single in-memory channel for both
-    if (isDebug) Console.Error.WriteLine("[cli] Using embedded mode (in-memory gRPC)");
-    NativeStorage.Init(storageOptions.GetEffectiveLocalStorageDir());
-    if (isDebug) Console.Error.WriteLine("[cli] NativeStorage initialized, creating GrpcChannel...");
-    var handler = new System.Net.Http.SocketsHttpHandler
-    {
-        ConnectCallback = (context, ct) =>
+    var doc = DocArg();
+    var path = new Argument<string>("path");
+    var value = OptionalArg("value_json", "Element JSON (default: read from stdin)");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("replace", "Replace element") { doc, path, value, dryRun },
+        (s, r) => ElementTools.ReplaceElement(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(path)!, r.GetValue(value) ?? ReadStdin(), r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("remove", "Remove element") { doc, path, dryRun },
+        (s, r) => ElementTools.RemoveElement(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(path)!, r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var from = new Argument<string>("from");
+    var to = new Argument<string>("to");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("move", "Move element") { doc, from, to, dryRun },
+        (s, r) => ElementTools.MoveElement(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(from)!, r.GetValue(to)!, r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var from = new Argument<string>("from");
+    var to = new Argument<string>("to");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("copy", "Copy element") { doc, from, to, dryRun },
+        (s, r) => ElementTools.CopyElement(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(from)!, r.GetValue(to)!, r.GetValue(dryRun)));
+}
+
+{
+    var doc = DocArg();
+    var path = new Argument<string>("path");
+    var find = new Argument<string>("find");
+    var replace = new Argument<string>("replace");
+    var maxCount = new Option<int>("--max-count") { DefaultValueFactory = _ => 1 };
+    var dryRun = DryRunOption();
+    AddCommand(new Command("replace-text", "Replace text, keeping its formatting")
         {
-            if (isDebug) Console.Error.WriteLine($"[cli] ConnectCallback: {context.DnsEndPoint.Host}:{context.DnsEndPoint.Port}");
-            return new ValueTask<Stream>(new InMemoryPipeStream());
-        }
-    };
-    var channel = Grpc.Net.Client.GrpcChannel.ForAddress("http://in-memory", new Grpc.Net.Client.GrpcChannelOptions
-    {
-        HttpHandler = handler
-    });
-    historyStorage = new HistoryStorageClient(channel, NullLogger<HistoryStorageClient>.Instance);
-    syncStorage = new SyncStorageClient(channel, NullLogger<SyncStorageClient>.Instance);
+            doc, path, find, replace, maxCount, dryRun
+        },
+        (s, r) => TextTools.ReplaceText(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!), r.GetValue(path)!,
+            r.GetValue(find)!, r.GetValue(replace)!, r.GetValue(maxCount), r.GetValue(dryRun)));
 }
 
-var sessions = new SessionManager(historyStorage, NullLogger<SessionManager>.Instance);
-var tenant = new TenantScope(sessions);
-var syncManager = new SyncManager(syncStorage, NullLogger<SyncManager>.Instance);
-var gate = new ExternalChangeGate(historyStorage);
-var docToolsLogger = NullLogger<DocumentTools>.Instance;
+{
+    var doc = DocArg();
+    var path = new Argument<string>("table_path");
+    var column = new Argument<int>("column_index");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("remove-column", "Remove a table column") { doc, path, column, dryRun },
+        (s, r) => TableTools.RemoveTableColumn(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(path)!, r.GetValue(column), r.GetValue(dryRun)));
+}
+
+// --- Generic patch (multi-operation) ---
 
-if (args.Length == 0)
 {
-    PrintUsage();
-    return 1;
+    var doc = DocArg();
+    var patches = OptionalArg("patches_json", "JSON array of patches (default: read from stdin)");
+    var dryRun = DryRunOption();
+    AddCommand(new Command("patch", "Apply a JSON array of patches") { doc, patches, dryRun },
+        (s, r) => PatchTool.ApplyPatch(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(patches) ?? ReadStdin(), r.GetValue(dryRun)));
 }
 
-var command = args[0].ToLowerInvariant();
+// --- Style commands ---
 
-// Helper to resolve doc_id or path to session ID
-string ResolveDocId(string idOrPath)
 {
-    var session = sessions.ResolveSession(idOrPath);
-    return session.Id;
+    var doc = DocArg();
+    var style = new Argument<string>("style_json");
+    var pathArg = OptionalArg("path", "Elements to style (default: whole document)");
+    var path = new Option<string?>("--path") { Description = "Same as the path argument" };
+    AddCommand(new Command("style-element", "Apply run style to elements") { doc, style, pathArg, path },
+        (s, r) => StyleTools.StyleElement(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(style)!,
+            r.GetValue(path) ?? r.GetValue(pathArg)));
 }
 
-try
 {
-    var result = command switch
-    {
-        "open" => CmdOpen(args),
-        "list" => DocumentTools.DocumentList(docToolsLogger, tenant),
-        "close" => DocumentTools.DocumentClose(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path"))),
-        "save" => DocumentTools.DocumentSave(docToolsLogger, tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")), GetNonFlagArg(args, 2)),
-        "set-source" => DocumentTools.DocumentSetSource(docToolsLogger, tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            Require(args, 2, "path"), auto_sync: !HasFlag(args, "--no-auto-sync")),
-        "snapshot" => DocumentTools.DocumentSnapshot(tenant, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            HasFlag(args, "--discard-redo")),
-        "query" => QueryTool.Query(tenant, ResolveDocId(Require(args, 1, "doc_id_or_path")), Require(args, 2, "path"),
-            OptNamed(args, "--format") ?? "json",
-            ParseIntOpt(OptNamed(args, "--offset")),
-            ParseIntOpt(OptNamed(args, "--limit"))),
-        "count" => CountTool.CountElements(tenant, ResolveDocId(Require(args, 1, "doc_id_or_path")), Require(args, 2, "path")),
-
-        // Generic patch (multi-operation)
-        "patch" => CmdPatch(args),
-
-        // Individual element operations
-        "add" => CmdAdd(args),
-        "replace" => CmdReplace(args),
-        "remove" => CmdRemove(args),
-        "move" => CmdMove(args),
-        "copy" => CmdCopy(args),
-        "replace-text" => CmdReplaceText(args),
-        "remove-column" => CmdRemoveColumn(args),
-
-        // Style commands
-        "style-element" => CmdStyleElement(args),
-        "style-paragraph" => CmdStyleParagraph(args),
-        "style-table" => CmdStyleTable(args),
-
-        // History commands
-        "undo" => HistoryTools.DocumentUndo(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            ParseInt(GetNonFlagArg(args, 2), 1)),
-        "redo" => HistoryTools.DocumentRedo(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            ParseInt(GetNonFlagArg(args, 2), 1)),
-        "history" => HistoryTools.DocumentHistory(tenant, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            ParseInt(OptNamed(args, "--offset"), 0),
-            ParseInt(OptNamed(args, "--limit"), 20)),
-        "jump-to" => HistoryTools.DocumentJumpTo(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            int.Parse(Require(args, 2, "position"))),
-
-        // Comment commands
-        "comment-add" => CmdCommentAdd(args),
-        "comment-list" => CmdCommentList(args),
-        "comment-delete" => CmdCommentDelete(args),
-
-        // Export commands
-        "export" => CmdExport(args),
-
-        // Read commands
-        "read-section" => CmdReadSection(args),
-        "read-heading" => CmdReadHeading(args),
-
-        // Revision (Track Changes) commands
-        "revision-list" => CmdRevisionList(args),
-        "revision-accept" => RevisionTools.RevisionAccept(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            int.Parse(Require(args, 2, "revision_id"))),
-        "revision-reject" => RevisionTools.RevisionReject(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            int.Parse(Require(args, 2, "revision_id"))),
-        "track-changes-enable" => RevisionTools.TrackChangesEnable(tenant, syncManager, ResolveDocId(Require(args, 1, "doc_id_or_path")),
-            ParseBool(Require(args, 2, "enabled"))),
-
-        // Diff commands
-        "diff" => CmdDiff(args),
-        "diff-files" => CmdDiffFiles(args),
-
-        // External change commands
-        "check-external" => CmdCheckExternal(args),
-        "sync-external" => CmdSyncExternal(args),
-        "watch" => CmdWatch(args),
-
-        // Session inspection
-        "inspect" => CmdInspect(args),
-
-        "help" or "--help" or "-h" => Usage(),
-        _ => $"Unknown command: '{command}'. Run 'docx-cli help' for usage."
-    };
+    var doc = DocArg();
+    var style = new Argument<string>("style_json");
+    var pathArg = OptionalArg("path", "Elements to style (default: whole document)");
+    var path = new Option<string?>("--path") { Description = "Same as the path argument" };
+    AddCommand(new Command("style-paragraph", "Apply paragraph style to elements") { doc, style, pathArg, path },
+        (s, r) => StyleTools.StyleParagraph(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(style)!,
+            r.GetValue(path) ?? r.GetValue(pathArg)));
+}
 
-    Console.WriteLine(result);
-    return 0;
+{
+    var doc = DocArg();
+    var style = new Option<string?>("--style") { Description = "Table style JSON" };
+    var cellStyle = new Option<string?>("--cell-style") { Description = "Cell style JSON" };
+    var rowStyle = new Option<string?>("--row-style") { Description = "Row style JSON" };
+    var path = new Option<string?>("--path") { Description = "Tables to style (default: all)" };
+    AddCommand(new Command("style-table", "Apply table, row and cell style") { doc, style, cellStyle, rowStyle, path },
+        (s, r) => StyleTools.StyleTable(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(style),
+            r.GetValue(cellStyle), r.GetValue(rowStyle), r.GetValue(path)));
 }
-catch (Exception ex)
+
+// --- History commands ---
+
 {
-    Console.Error.WriteLine($"Error: {ex.Message}");
-    return 1;
+    var doc = DocArg();
+    var steps = new Argument<int>("steps") { Arity = ArgumentArity.ZeroOrOne, DefaultValueFactory = _ => 1 };
+    AddCommand(new Command("undo", "Undo edits") { doc, steps },
+        (s, r) => HistoryTools.DocumentUndo(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(steps)));
 }
 
-// --- Command handlers for complex argument parsing ---
+{
+    var doc = DocArg();
+    var steps = new Argument<int>("steps") { Arity = ArgumentArity.ZeroOrOne, DefaultValueFactory = _ => 1 };
+    AddCommand(new Command("redo", "Redo undone edits") { doc, steps },
+        (s, r) => HistoryTools.DocumentRedo(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(steps)));
+}
 
-string CmdOpen(string[] a)
 {
-    var path = GetNonFlagArg(a, 1);
-    return DocumentTools.DocumentOpen(docToolsLogger, tenant, syncManager, path);
+    var doc = DocArg();
+    var offset = new Option<int>("--offset") { DefaultValueFactory = _ => 0 };
+    var limit = new Option<int>("--limit") { DefaultValueFactory = _ => 20 };
+    AddCommand(new Command("history", "List edit history") { doc, offset, limit },
+        (s, r) => HistoryTools.DocumentHistory(s.Tenant, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(offset), r.GetValue(limit)));
 }
 
-string CmdPatch(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var dryRun = HasFlag(a, "--dry-run");
-    // patches can be arg[2] or read from stdin
-    var patches = GetNonFlagArg(a, 2) ?? ReadStdin();
-    return PatchTool.ApplyPatch(tenant, syncManager, gate, docId, patches, dryRun);
+    var doc = DocArg();
+    var position = new Argument<int>("position");
+    AddCommand(new Command("jump-to", "Move to a position in the history") { doc, position },
+        (s, r) => HistoryTools.DocumentJumpTo(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(position)));
 }
 
-string CmdAdd(string[] a)
+// --- Comment commands ---
+
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var value = GetNonFlagArg(a, 3) ?? ReadStdin();
-    var dryRun = HasFlag(a, "--dry-run");
-    return ElementTools.AddElement(tenant, syncManager, gate, docId, path, value, dryRun);
+    var doc = DocArg();
+    var path = new Argument<string>("path");
+    var text = new Argument<string>("text");
+    var anchorText = new Option<string?>("--anchor-text");
+    var author = new Option<string?>("--author");
+    var initials = new Option<string?>("--initials");
+    AddCommand(new Command("comment-add", "Add a comment") { doc, path, text, anchorText, author, initials },
+        (s, r) => CommentTools.CommentAdd(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(path)!,
+            r.GetValue(text)!, r.GetValue(anchorText), r.GetValue(author), r.GetValue(initials)));
 }
 
-string CmdReplace(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var value = GetNonFlagArg(a, 3) ?? ReadStdin();
-    var dryRun = HasFlag(a, "--dry-run");
-    return ElementTools.ReplaceElement(tenant, syncManager, gate, docId, path, value, dryRun);
+    var doc = DocArg();
+    var author = new Option<string?>("--author");
+    var offset = new Option<int?>("--offset");
+    var limit = new Option<int?>("--limit");
+    AddCommand(new Command("comment-list", "List comments") { doc, author, offset, limit },
+        (s, r) => CommentTools.CommentList(s.Tenant, s.Resolve(r.GetValue(doc)!), r.GetValue(author),
+            r.GetValue(offset), r.GetValue(limit)));
 }
 
-string CmdRemove(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var dryRun = HasFlag(a, "--dry-run");
-    return ElementTools.RemoveElement(tenant, syncManager, gate, docId, path, dryRun);
+    var doc = DocArg();
+    var id = new Option<int?>("--id");
+    var author = new Option<string?>("--author");
+    AddCommand(new Command("comment-delete", "Delete comments by ID or author") { doc, id, author },
+        (s, r) => CommentTools.CommentDelete(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(id),
+            r.GetValue(author)));
 }
 
-string CmdMove(string[] a)
+// --- Revision (Track Changes) commands ---
+
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var from = Require(a, 2, "from");
-    var to = Require(a, 3, "to");
-    var dryRun = HasFlag(a, "--dry-run");
-    return ElementTools.MoveElement(tenant, syncManager, gate, docId, from, to, dryRun);
+    var doc = DocArg();
+    var author = new Option<string?>("--author");
+    var type = new Option<string?>("--type");
+    var offset = new Option<int?>("--offset");
+    var limit = new Option<int?>("--limit");
+    AddCommand(new Command("revision-list", "List tracked changes") { doc, author, type, offset, limit },
+        (s, r) => RevisionTools.RevisionList(s.Tenant, s.Resolve(r.GetValue(doc)!), r.GetValue(author),
+            r.GetValue(type), r.GetValue(offset), r.GetValue(limit)));
 }
 
-string CmdCopy(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var from = Require(a, 2, "from");
-    var to = Require(a, 3, "to");
-    var dryRun = HasFlag(a, "--dry-run");
-    return ElementTools.CopyElement(tenant, syncManager, gate, docId, from, to, dryRun);
+    var doc = DocArg();
+    var revisionId = new Argument<int>("revision_id");
+    AddCommand(new Command("revision-accept", "Accept a single revision by ID") { doc, revisionId },
+        (s, r) => RevisionTools.RevisionAccept(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(revisionId)));
 }
 
-string CmdReplaceText(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var find = Require(a, 3, "find");
-    var replace = Require(a, 4, "replace");
-    var maxCount = ParseInt(OptNamed(a, "--max-count"), 1);
-    var dryRun = HasFlag(a, "--dry-run");
-    return TextTools.ReplaceText(tenant, syncManager, gate, docId, path, find, replace, maxCount, dryRun);
+    var doc = DocArg();
+    var revisionId = new Argument<int>("revision_id");
+    AddCommand(new Command("revision-reject", "Reject a single revision by ID") { doc, revisionId },
+        (s, r) => RevisionTools.RevisionReject(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!), r.GetValue(revisionId)));
 }
 
-string CmdRemoveColumn(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var column = int.Parse(Require(a, 3, "column"));
-    var dryRun = HasFlag(a, "--dry-run");
-    return TableTools.RemoveTableColumn(tenant, syncManager, gate, docId, path, column, dryRun);
+    var doc = DocArg();
+    var enabled = new Argument<string>("enabled") { Description = "true or false" };
+    AddCommand(new Command("track-changes-enable", "Enable/disable Track Changes") { doc, enabled },
+        (s, r) => RevisionTools.TrackChangesEnable(s.Tenant, s.Sync, s.Resolve(r.GetValue(doc)!),
+            ParseBool(r.GetValue(enabled)!)));
 }
 
-string CmdStyleElement(string[] a)
+// --- Export commands ---
+
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var style = Require(a, 2, "style");
-    var path = OptNamed(a, "--path") ?? GetNonFlagArg(a, 3);
-    return StyleTools.StyleElement(tenant, syncManager, docId, style, path);
+    var doc = DocArg();
+    var format = new Argument<string>("format").AcceptOnlyFromAmong("html", "markdown", "pdf", "docx");
+    var output = OptionalArg("output_path", "File to write (default: print to stdout)");
+    AddCommand(new Command("export", "Export as HTML, Markdown, PDF or DOCX") { doc, format, output },
+        (s, r) => Export(s, s.Resolve(r.GetValue(doc)!), r.GetValue(format)!, r.GetValue(output)));
 }
 
-string CmdStyleParagraph(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var style = Require(a, 2, "style");
-    var path = OptNamed(a, "--path") ?? GetNonFlagArg(a, 3);
-    return StyleTools.StyleParagraph(tenant, syncManager, docId, style, path);
+    var doc = DocArg();
+    var output = new Argument<string>("output_path");
+    AddCommand(new Command("export-pdf", "Export as PDF") { doc, output },
+        (s, r) => Export(s, s.Resolve(r.GetValue(doc)!), "pdf", r.GetValue(output)));
 }
 
-string CmdStyleTable(string[] a)
+// --- Diff commands ---
+
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var style = OptNamed(a, "--style");
-    var cellStyle = OptNamed(a, "--cell-style");
-    var rowStyle = OptNamed(a, "--row-style");
-    var path = OptNamed(a, "--path");
-    return StyleTools.StyleTable(tenant, syncManager, docId, style, cellStyle, rowStyle, path);
+    var doc = DocArg();
+    var file = OptionalArg("file_path", "File to compare with (default: the source file)");
+    var threshold = ThresholdOption();
+    var format = DiffFormatOption();
+    AddCommand(new Command("diff", "Compare session with file") { doc, file, threshold, format },
+        (s, r) => DiffSession(s, s.Resolve(r.GetValue(doc)!), r.GetValue(file), r.GetValue(threshold), r.GetValue(format)!));
 }
 
-string CmdCommentAdd(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var path = Require(a, 2, "path");
-    var text = Require(a, 3, "text");
-    var anchorText = OptNamed(a, "--anchor-text");
-    var author = OptNamed(a, "--author");
-    var initials = OptNamed(a, "--initials");
-    return CommentTools.CommentAdd(tenant, syncManager, docId, path, text, anchorText, author, initials);
+    var file1 = new Argument<string>("file1");
+    var file2 = new Argument<string>("file2");
+    var threshold = ThresholdOption();
+    var format = DiffFormatOption();
+    var command = new Command("diff-files", "Compare two DOCX files on disk") { file1, file2, threshold, format };
+    command.SetAction(r => Run(() => DiffFiles(r.GetValue(file1)!, r.GetValue(file2)!,
+        r.GetValue(threshold), r.GetValue(format)!)));
+    root.Subcommands.Add(command);
 }
 
-string CmdCommentList(string[] a)
+// --- External change commands ---
+
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var author = OptNamed(a, "--author");
-    var offset = ParseIntOpt(OptNamed(a, "--offset"));
-    var limit = ParseIntOpt(OptNamed(a, "--limit"));
-    return CommentTools.CommentList(tenant, docId, author, offset, limit);
+    var doc = DocArg();
+    var acknowledge = new Option<bool>("--acknowledge") { Description = "Acknowledge the changes found" };
+    AddCommand(new Command("check-external", "Check for external changes") { doc, acknowledge },
+        (s, r) => ExternalChangeTools.GetExternalChanges(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!),
+            r.GetValue(acknowledge)));
 }
 
-string CmdCommentDelete(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var commentId = ParseIntOpt(OptNamed(a, "--id"));
-    var author = OptNamed(a, "--author");
-    return CommentTools.CommentDelete(tenant, syncManager, docId, commentId, author);
+    var doc = DocArg();
+    AddCommand(new Command("sync-external", "Sync session with external file (records in WAL)") { doc },
+        (s, r) => ExternalChangeTools.SyncExternalChanges(s.Tenant, s.Sync, s.Gate, s.Resolve(r.GetValue(doc)!)));
 }
 
-string CmdExport(string[] a)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var format = Require(a, 2, "format");
-    var outputPath = a.Length > 3 ? a[3] : null;
+    // Kept so old scripts get a pointer to its replacements; its arguments are ignored
+    var command = new Command("watch") { Hidden = true, TreatUnmatchedTokensAsErrors = false };
+    command.SetAction(_ => Run(() =>
+        "Watch command removed. External change watching is now handled by the gRPC ExternalWatchService.\n" +
+        "Use 'check-external' to manually check for changes, or 'sync-external' to sync."));
+    root.Subcommands.Add(command);
+}
 
-    var content = ExportTools.Export(tenant, docId, format).GetAwaiter().GetResult();
+return root.Parse(args).Invoke();
 
-    // If an output path is given, write to file (for CLI convenience)
-    if (outputPath is not null)
+// --- Command plumbing ---
+
+// Add a command whose action runs against the storage of the tenant on its command line
+void AddCommand(Command command, Func<Services, ParseResult, string> run)
+{
+    command.SetAction(r => Run(() => run(Connect(r.GetValue(tenantOption)!), r)));
+    root.Subcommands.Add(command);
+}
+
+// Print a command's result; errors go to stderr with a non-zero exit code
+static int Run(Func<string> command)
+{
+    try
     {
-        if (format is "pdf" or "docx")
-            File.WriteAllBytes(outputPath, Convert.FromBase64String(content));
-        else
-            File.WriteAllText(outputPath, content);
-        return $"Exported to '{outputPath}'.";
+        Console.WriteLine(command());
+        return 0;
+    }
+    catch (Exception ex)
+    {
+        Console.Error.WriteLine($"Error: {ex.Message}");
+        return 1;
     }
+}
 
-    return content;
+static Argument<string> DocArg() =>
+    new("doc_id_or_path") { Description = "Session ID, alias or file path" };
+
+static Argument<string?> OptionalArg(string name, string description) =>
+    new(name) { Description = description, Arity = ArgumentArity.ZeroOrOne };
+
+static Option<bool> DryRunOption() =>
+    new("--dry-run") { Description = "Simulate operation without applying changes" };
+
+static Option<double> ThresholdOption() =>
+    new("--threshold")
+    {
+        Description = "Similarity above which elements are matched as modified",
+        DefaultValueFactory = _ => DiffEngine.DefaultSimilarityThreshold
+    };
+
+static Option<string> DiffFormatOption() =>
+    new Option<string>("--format") { DefaultValueFactory = _ => "text" }
+        .AcceptOnlyFromAmong("text", "json", "patch");
+
+// --- Storage ---
+
+static Services Connect(string tenantId)
+{
+    // Set tenant context for all operations
+    TenantContextHelper.CurrentTenantId = tenantId;
+
+    // Create gRPC storage clients (embedded or remote)
+    var isDebug = Environment.GetEnvironmentVariable("DEBUG") is not null;
+    var storageOptions = StorageClientOptions.FromEnvironment();
+    IHistoryStorage historyStorage;
+    ISyncStorage syncStorage;
+
+    if (!string.IsNullOrEmpty(storageOptions.ServerUrl))
+    {
+        // Dual mode — remote for history, local embedded for sync/watch
+        if (isDebug) Console.Error.WriteLine("[cli] Using dual mode: remote=" + storageOptions.ServerUrl);
+        var launcher = new GrpcLauncher(storageOptions, NullLogger<GrpcLauncher>.Instance);
+        historyStorage = HistoryStorageClient.CreateAsync(storageOptions, launcher, NullLogger<HistoryStorageClient>.Instance).GetAwaiter().GetResult();
+
+        // Local embedded for sync/watch
+        NativeStorage.Init(storageOptions.GetEffectiveLocalStorageDir());
+        var localHandler = new System.Net.Http.SocketsHttpHandler
+        {
+            ConnectCallback = (_, _) => new ValueTask<Stream>(new InMemoryPipeStream())
+        };
+        var localChannel = Grpc.Net.Client.GrpcChannel.ForAddress("http://in-memory", new Grpc.Net.Client.GrpcChannelOptions
+        {
+            HttpHandler = localHandler
+        });
+        syncStorage = new SyncStorageClient(localChannel, NullLogger<SyncStorageClient>.Instance);
+    }
+    else
+    {
+        // Embedded mode — single in-memory channel for both
+        if (isDebug) Console.Error.WriteLine("[cli] Using embedded mode (in-memory gRPC)");
+        NativeStorage.Init(storageOptions.GetEffectiveLocalStorageDir());
+        if (isDebug) Console.Error.WriteLine("[cli] NativeStorage initialized, creating GrpcChannel...");
+        var handler = new System.Net.Http.SocketsHttpHandler
+        {
+            ConnectCallback = (context, ct) =>
+            {
+                if (isDebug) Console.Error.WriteLine($"[cli] ConnectCallback: {context.DnsEndPoint.Host}:{context.DnsEndPoint.Port}");
+                return new ValueTask<Stream>(new InMemoryPipeStream());
+            }
+        };
+        var channel = Grpc.Net.Client.GrpcChannel.ForAddress("http://in-memory", new Grpc.Net.Client.GrpcChannelOptions
+        {
+            HttpHandler = handler
+        });
+        historyStorage = new HistoryStorageClient(channel, NullLogger<HistoryStorageClient>.Instance);
+        syncStorage = new SyncStorageClient(channel, NullLogger<SyncStorageClient>.Instance);
+    }
+
+    var sessions = new SessionManager(historyStorage, NullLogger<SessionManager>.Instance);
+    return new Services(
+        sessions,
+        new TenantScope(sessions),
+        new SyncManager(syncStorage, NullLogger<SyncManager>.Instance),
+        new ExternalChangeGate(historyStorage),
+        new OperationManager(historyStorage, NullLogger<OperationManager>.Instance));
 }
 
-string CmdReadSection(string[] a)
+// --- Command handlers ---
+
+static string AppendPath(Services s, string docId)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var sectionIndex = ParseIntOpt(OptNamed(a, "--index"));
-    var format = OptNamed(a, "--format");
-    var offset = ParseIntOpt(OptNamed(a, "--offset"));
-    var limit = ParseIntOpt(OptNamed(a, "--limit"));
-    return ReadSectionTool.ReadSection(tenant, docId, sectionIndex, format, offset, limit);
+    using var session = s.Sessions.Get(docId);
+    return PathResolver.BodyAppendPath(session.Document);
 }
 
-string CmdReadHeading(string[] a)
+static string AddParagraph(Services s, string docId, string text, string? style, string? path, bool dryRun)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var headingText = OptNamed(a, "--text");
-    var headingIndex = ParseIntOpt(OptNamed(a, "--index"));
-    var headingLevel = ParseIntOpt(OptNamed(a, "--level"));
-    var includeSubHeadings = !HasFlag(a, "--no-sub-headings");
-    var format = OptNamed(a, "--format");
-    var offset = ParseIntOpt(OptNamed(a, "--offset"));
-    var limit = ParseIntOpt(OptNamed(a, "--limit"));
-    return ReadHeadingContentTool.ReadHeadingContent(tenant, docId,
-        headingText, headingIndex, headingLevel, includeSubHeadings, format, offset, limit);
+    var value = new System.Text.Json.Nodes.JsonObject
+    {
+        ["type"] = "paragraph",
+        ["text"] = text,
+    };
+    if (style is not null)
+    {
+        // A runs array makes "style" a paragraph style name (e.g. "Heading1")
+        value["style"] = style;
+        value["runs"] = new System.Text.Json.Nodes.JsonArray(
+            new System.Text.Json.Nodes.JsonObject { ["text"] = text });
+        value.Remove("text");
+    }
+
+    return ElementTools.AddElement(s.Tenant, s.Sync, s.Gate, docId, path ?? AppendPath(s, docId),
+        value.ToJsonString(), dryRun);
 }
 
-string CmdRevisionList(string[] a)
+static string ImportMarkdown(Services s, string docId, string markdown, string? path)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var author = OptNamed(a, "--author");
-    var type = OptNamed(a, "--type");
-    var offset = ParseIntOpt(OptNamed(a, "--offset"));
-    var limit = ParseIntOpt(OptNamed(a, "--limit"));
-    return RevisionTools.RevisionList(tenant, docId, author, type, offset, limit);
+    var html = MarkdownImportHelper.ToHtml(markdown);
+    return PasteTools.PasteHtml(s.Tenant, s.Sync, s.Gate, docId, path ?? AppendPath(s, docId), html);
 }
 
-string CmdDiff(string[] a)
+static string Export(Services s, string docId, string format, string? outputPath)
 {
-    // diff <doc_id_or_path> [file_path] - compare session with file (default: source file)
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var filePath = GetNonFlagArg(a, 2);
-    var threshold = ParseDouble(OptNamed(a, "--threshold"), DiffEngine.DefaultSimilarityThreshold);
-    var format = OptNamed(a, "--format") ?? "text";
+    var content = ExportTools.Export(s.Tenant, s.Operations, docId, format).GetAwaiter().GetResult();
+
+    // If an output path is given, write to file (for CLI convenience)
+    if (outputPath is not null)
+    {
+        if (format is "pdf" or "docx")
+            File.WriteAllBytes(outputPath, Convert.FromBase64String(content));
+        else
+            File.WriteAllText(outputPath, content);
+        return $"Exported to '{outputPath}'.";
+    }
+
+    return content;
+}
 
-    using var session = sessions.Get(docId);
+static string DiffSession(Services s, string docId, string? filePath, double threshold, string format)
+{
+    using var session = s.Sessions.Get(docId);
     var targetPath = filePath ?? session.SourcePath
         ?? throw new ArgumentException("No file path specified and session has no source file.");
 
@@ -401,14 +627,8 @@ string CmdDiff(string[] a)
     return FormatDiffResult(diff, format, $"Session '{docId}'", targetPath);
 }
 
-string CmdDiffFiles(string[] a)
+static string DiffFiles(string file1, string file2, double threshold, string format)
 {
-    // diff-files <file1> <file2> - compare two files on disk
-    var file1 = Require(a, 1, "file1");
-    var file2 = Require(a, 2, "file2");
-    var threshold = ParseDouble(OptNamed(a, "--threshold"), DiffEngine.DefaultSimilarityThreshold);
-    var format = OptNamed(a, "--format") ?? "text";
-
     if (!File.Exists(file1))
         throw new ArgumentException($"File not found: {file1}");
     if (!File.Exists(file2))
@@ -418,7 +638,7 @@ string CmdDiffFiles(string[] a)
     return FormatDiffResult(diff, format, file1, file2);
 }
 
-string FormatDiffResult(DiffResult diff, string format, string original, string modified)
+static string FormatDiffResult(DiffResult diff, string format, string original, string modified)
 {
     if (format == "json")
         return diff.ToJson();
@@ -503,30 +723,10 @@ string FormatDiffResult(DiffResult diff, string format, string original, string
     return sb.ToString();
 }
 
-string CmdCheckExternal(string[] a)
-{
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    var acknowledge = HasFlag(a, "--acknowledge");
-    return ExternalChangeTools.GetExternalChanges(tenant, syncManager, gate, docId, acknowledge);
-}
-
-string CmdSyncExternal(string[] a)
+static string Inspect(Services s, string idOrPath)
 {
-    var docId = ResolveDocId(Require(a, 1, "doc_id_or_path"));
-    return ExternalChangeTools.SyncExternalChanges(tenant, syncManager, gate, docId);
-}
-
-string CmdWatch(string[] _)
-{
-    return "Watch command removed. External change watching is now handled by the gRPC ExternalWatchService.\n" +
-           "Use 'check-external' to manually check for changes, or 'sync-external' to sync.";
-}
-
-string CmdInspect(string[] a)
-{
-    var idOrPath = Require(a, 1, "doc_id_or_path");
-    using var session = sessions.ResolveSession(idOrPath);
-    var history = sessions.GetHistory(session.Id);
+    using var session = s.Sessions.ResolveSession(idOrPath);
+    var history = s.Sessions.GetHistory(session.Id);
 
     var sb = new System.Text.StringBuilder();
     sb.AppendLine($"Session: {session.Id}");
@@ -578,49 +778,9 @@ string CmdInspect(string[] a)
 
 // --- Argument helpers ---
 
-static string Require(string[] a, int idx, string name)
-{
-    if (idx >= a.Length)
-        throw new ArgumentException($"Missing required argument: <{name}>");
-    var val = a[idx];
-    if (val.StartsWith('-'))
-        throw new ArgumentException($"Missing required argument: <{name}> (got flag '{val}')");
-    return val;
-}
-
-
-static string? GetNonFlagArg(string[] a, int idx)
-{
-    if (idx >= a.Length) return null;
-    var val = a[idx];
-    return val.StartsWith('-') ? null : val;
-}
-
-static string? OptNamed(string[] a, string flag)
-{
-    for (int i = 0; i < a.Length - 1; i++)
-    {
-        if (a[i] == flag)
-            return a[i + 1];
-    }
-    return null;
-}
-
-static bool HasFlag(string[] a, string flag) =>
-    a.Any(x => x == flag);
-
-static int ParseInt(string? s, int def) =>
-    s is not null && int.TryParse(s, out var v) ? v : def;
-
-static int? ParseIntOpt(string? s) =>
-    s is not null && int.TryParse(s, out var v) ? v : null;
-
 static bool ParseBool(string s) =>
     s.ToLowerInvariant() is "true" or "1" or "yes" or "on";
 
-static double ParseDouble(string? s, double def) =>
-    s is not null && double.TryParse(s, out var v) ? v : def;
-
 static string ReadStdin()
 {
     if (Console.IsInputRedirected)
@@ -628,105 +788,14 @@ static string ReadStdin()
     throw new ArgumentException("Missing argument. Provide inline or pipe via stdin.");
 }
 
-static string Usage()
-{
-    PrintUsage();
-    return "";
-}
-
-static void PrintUsage()
+/// <summary>The storage-backed services a command runs against.</summary>
+sealed record Services(
+    SessionManager Sessions,
+    TenantScope Tenant,
+    SyncManager Sync,
+    ExternalChangeGate Gate,
+    OperationManager Operations)
 {
-    Console.Error.WriteLine("""
-    docx-cli — CLI for DOCX document manipulation
-
-    Usage: docx-cli <command> [arguments] [options]
-
-    Note: Most commands accept either a session ID or a file path.
-          When using a file path, an existing session is reused if one exists,
-          otherwise a new session is auto-opened.
-
-    Document commands:
-      open [path]                          Open file or create new document
-      list                                 List open sessions
-      save <doc_id|path> [output_path]     Save document to disk
-      set-source <doc_id|path> <path> [--no-auto-sync]  Set/change save target
-      inspect <doc_id|path>                Show detailed session information
-
-    Administrative commands (CLI-only, not exposed to MCP):
-      close <doc_id|path>                  Close session and delete all persisted data
-      snapshot <doc_id|path> [--discard-redo]   Force WAL compaction into new baseline
-
-    Query commands:
-      query <doc_id> <path> [--format json|text|summary] [--offset N] [--limit N]
-      count <doc_id> <path>
-      read-section <doc_id> [--index N] [--format fmt] [--offset N] [--limit N]
-      read-heading <doc_id> [--text str] [--index N] [--level N] [--format fmt]
-                            [--offset N] [--limit N] [--no-sub-headings]
-
-    Element operations (all support --dry-run):
-      add <doc_id> <path> <value_json>     Add element at path
-      replace <doc_id> <path> <value_json> Replace element
-      remove <doc_id> <path>               Remove element
-      move <doc_id> <from> <to>            Move element
-      copy <doc_id> <from> <to>            Copy element
-      replace-text <doc_id> <path> <find> <replace> [--max-count N]
-      remove-column <doc_id> <table_path> <column_index>
-
-    Generic patch (multi-operation):
-      patch <doc_id> <patches_json> [--dry-run]
-
-    Style commands:
-      style-element <doc_id> <style_json> [path | --path path]
-      style-paragraph <doc_id> <style_json> [path | --path path]
-      style-table <doc_id> --style json [--cell-style json] [--row-style json] [--path path]
-
-    History commands:
-      undo <doc_id> [steps]
-      redo <doc_id> [steps]
-      history <doc_id> [--offset N] [--limit N]
-      jump-to <doc_id> <position>
-
-    Comment commands:
-      comment-add <doc_id> <path> <text> [--anchor-text str] [--author name] [--initials str]
-      comment-list <doc_id> [--author name] [--offset N] [--limit N]
-      comment-delete <doc_id> [--id N] [--author name]
-
-    Revision (Track Changes) commands:
-      revision-list <doc_id> [--author name] [--type type] [--offset N] [--limit N]
-      revision-accept <doc_id> <revision_id>     Accept a single revision by ID
-      revision-reject <doc_id> <revision_id>     Reject a single revision by ID
-      track-changes-enable <doc_id> <true|false> Enable/disable Track Changes
-
-    Export commands:
-      export <doc_id> <format> [output_path]   (format: html, markdown, pdf, docx)
-
-    Diff commands:
-      diff <doc_id> [file_path] [--threshold 0.6] [--format text|json|patch]
-                                 Compare session with file (default: source file)
-      diff-files <file1> <file2> [--threshold 0.6] [--format text|json|patch]
-                                 Compare two DOCX files on disk
-
-    External change commands:
-      check-external <doc_id|path> [--acknowledge]
-                                 Check for external changes and optionally acknowledge
-      sync-external <doc_id|path> [--change-id id]
-                                 Sync session with external file (records in WAL)
-      watch <path> [--auto-sync] [--debounce ms] [--pattern *.docx] [--recursive]
-                                 Watch file or folder for changes (daemon mode)
-
-    Global options:
-      --tenant <id>  Specify tenant ID for multi-tenant deployments (optional)
-      --dry-run      Simulate operation without applying changes
-
-    Environment:
-      STORAGE_GRPC_URL             gRPC storage server URL (auto-launches local if not set)
-      DOCX_SESSIONS_DIR            Override sessions directory (legacy, for local storage)
-      DOCX_WAL_COMPACT_THRESHOLD   Auto-compact WAL after N entries (default: 50)
-      DOCX_CHECKPOINT_INTERVAL     Create checkpoint every N entries (default: 10)
-      DOCX_AUTO_SAVE               Auto-save to source file after each edit (default: true)
-      DEBUG                        Enable debug logging for sync operations
-
-    Sessions persist between invocations and are shared with the MCP server.
-    WAL history is preserved automatically; use 'close' to permanently delete a session.
-    """);
+    /// <summary>Resolve a session ID, alias or file path to a session ID.</summary>
+    public string Resolve(string idOrPath) => Sessions.ResolveSession(idOrPath).Id;
 }
@@ -0,0 +1,98 @@
+using Google.Protobuf;
+using Grpc.Core;
+using Grpc.Core.Interceptors;
+
+namespace DocxMcp.Grpc;
+
+/// <summary>
+/// Correlation ID of the request being served, passed to the storage services so their
+/// logs can be matched with the proxy's and this server's.
+/// </summary>
+public static class CorrelationContext
+{
+    /// <summary>Header carrying the request ID, set by the proxy.</summary>
+    public const string RequestIdHeader = "x-request-id";
+    public const string TenantIdHeader = "x-tenant-id";
+    public const string SessionIdHeader = "x-session-id";
+
+    private static readonly AsyncLocal<string?> _requestId = new();
+
+    /// <summary>
+    /// Request ID of the current async flow, if any.
+    /// </summary>
+    public static string? RequestId
+    {
+        get => _requestId.Value;
+        set => _requestId.Value = value;
+    }
+}
+
+/// <summary>
+/// Adds the correlation headers to every storage call: the current request ID, and the
+/// tenant and session IDs of the request message when it has them.
+/// Streamed uploads only carry the request ID, their IDs arrive with the first chunk.
+/// </summary>
+public sealed class CorrelationInterceptor : Interceptor
+{
+    public static readonly CorrelationInterceptor Instance = new();
+
+    public override TResponse BlockingUnaryCall<TRequest, TResponse>(
+        TRequest request,
+        ClientInterceptorContext<TRequest, TResponse> context,
+        BlockingUnaryCallContinuation<TRequest, TResponse> continuation)
+        => continuation(request, WithHeaders(context, request));
+
+    public override AsyncUnaryCall<TResponse> AsyncUnaryCall<TRequest, TResponse>(
+        TRequest request,
+        ClientInterceptorContext<TRequest, TResponse> context,
+        AsyncUnaryCallContinuation<TRequest, TResponse> continuation)
+        => continuation(request, WithHeaders(context, request));
+
+    public override AsyncServerStreamingCall<TResponse> AsyncServerStreamingCall<TRequest, TResponse>(
+        TRequest request,
+        ClientInterceptorContext<TRequest, TResponse> context,
+        AsyncServerStreamingCallContinuation<TRequest, TResponse> continuation)
+        => continuation(request, WithHeaders(context, request));
+
+    public override AsyncClientStreamingCall<TRequest, TResponse> AsyncClientStreamingCall<TRequest, TResponse>(
+        ClientInterceptorContext<TRequest, TResponse> context,
+        AsyncClientStreamingCallContinuation<TRequest, TResponse> continuation)
+        => continuation(WithHeaders(context, null));
+
+    public override AsyncDuplexStreamingCall<TRequest, TResponse> AsyncDuplexStreamingCall<TRequest, TResponse>(
+        ClientInterceptorContext<TRequest, TResponse> context,
+        AsyncDuplexStreamingCallContinuation<TRequest, TResponse> continuation)
+        => continuation(WithHeaders(context, null));
+
+    private static ClientInterceptorContext<TRequest, TResponse> WithHeaders<TRequest, TResponse>(
+        ClientInterceptorContext<TRequest, TResponse> context, TRequest? request)
+        where TRequest : class
+        where TResponse : class
+    {
+        var headers = new Metadata();
+        if (CorrelationContext.RequestId is { Length: > 0 } requestId)
+            headers.Add(CorrelationContext.RequestIdHeader, requestId);
+
+        if (request is IMessage message)
+        {
+            var descriptor = message.Descriptor;
+            if (descriptor.FindFieldByName("context")?.Accessor.GetValue(message)
+                is TenantContext { TenantId.Length: > 0 } tenant)
+                headers.Add(CorrelationContext.TenantIdHeader, tenant.TenantId);
+            if (descriptor.FindFieldByName("session_id")?.Accessor.GetValue(message)
+                is string { Length: > 0 } sessionId)
+                headers.Add(CorrelationContext.SessionIdHeader, sessionId);
+        }
+
+        if (headers.Count == 0)
+            return context;
+
+        if (context.Options.Headers is { } existing)
+        {
+            foreach (var entry in existing)
+                headers.Add(entry);
+        }
+        return new ClientInterceptorContext<TRequest, TResponse>(
+            context.Method, context.Host, context.Options.WithHeaders(headers));
+    }
+}
@@ -1,5 +1,6 @@
 using System.Net.Sockets;
 using Grpc.Core;
+using Grpc.Core.Interceptors;
 using Grpc.Net.Client;
 using Grpc.Net.Client.Configuration;
 using Microsoft.Extensions.Logging;
@@ -7,8 +8,8 @@ using Microsoft.Extensions.Logging;
 namespace DocxMcp.Grpc;
 
 /// <summary>
-/// gRPC client for history storage operations (StorageService).
-/// Handles sessions, index, WAL, checkpoints, and health check.
+/// gRPC client for history storage operations (StorageService, OperationService).
+/// Handles sessions, index, WAL, checkpoints, long-running operations, and health check.
 /// </summary>
 public sealed class HistoryStorageClient : IHistoryStorage
 {
@@ -25,7 +26,7 @@ public sealed class HistoryStorageClient : IHistoryStorage
     public HistoryStorageClient(GrpcChannel channel, ILogger<HistoryStorageClient>? logger = null, int chunkSize = DefaultChunkSize)
     {
         _channel = channel;
-        _client = new StorageService.StorageServiceClient(channel);
+        _client = new StorageService.StorageServiceClient(channel.Intercept(CorrelationInterceptor.Instance));
         _logger = logger;
         _chunkSize = chunkSize;
     }
@@ -45,7 +46,9 @@ public sealed class HistoryStorageClient : IHistoryStorage
 
     /// <summary>
     /// Create GrpcChannelOptions with a retry policy for transient failures.
-    /// Retries Unavailable status codes with exponential backoff (budget ~25s).
+    /// Retries Unavailable and ResourceExhausted status codes with exponential backoff
+    /// (budget ~25s). Throttled calls wait the delay the server sends as retry pushback;
+    /// quota errors carry a negative pushback and are not retried.
     /// </summary>
     public static GrpcChannelOptions CreateRetryChannelOptions(GrpcChannelOptions? baseOptions = null)
     {
@@ -55,7 +58,7 @@ public sealed class HistoryStorageClient : IHistoryStorage
             InitialBackoff = TimeSpan.FromSeconds(1),
             MaxBackoff = TimeSpan.FromSeconds(10),
             BackoffMultiplier = 2,
-            RetryableStatusCodes = { StatusCode.Unavailable }
+            RetryableStatusCodes = { StatusCode.Unavailable, StatusCode.ResourceExhausted }
         };
 
         var options = baseOptions ?? new GrpcChannelOptions();
@@ -167,12 +170,196 @@ public sealed class HistoryStorageClient : IHistoryStorage
         return (data.ToArray(), found);
     }
 
+    public Task<SessionHistoryDto?> LoadSessionWithHistoryAsync(
+        string tenantId, string sessionId, ulong? position = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new LoadSessionWithHistoryRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId
+        };
+        if (position.HasValue)
+            request.Position = position.Value;
+
+        return ReadHistoryAsync(request, cancellationToken);
+    }
+
+    public async Task<SessionSnapshotDto?> OpenSnapshotAsync(
+        string tenantId, string sessionId, ulong? position = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new OpenSnapshotRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId
+        };
+        if (position.HasValue)
+            request.Position = position.Value;
+
+        var response = await _client.OpenSnapshotAsync(request, cancellationToken: cancellationToken);
+        if (!response.Found) return null;
+
+        _logger?.LogDebug("Opened snapshot {SnapshotId} of session {SessionId} at position {Position}",
+            response.SnapshotId, sessionId, response.Position);
+        return new SessionSnapshotDto(
+            response.SnapshotId,
+            response.Position,
+            DateTimeOffset.FromUnixTimeSeconds(response.ExpiresAtUnix).UtcDateTime);
+    }
+
+    public Task<SessionHistoryDto?> LoadSnapshotAsync(
+        string tenantId, string snapshotId, CancellationToken cancellationToken = default)
+    {
+        var request = new LoadSessionWithHistoryRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SnapshotId = snapshotId
+        };
+        return ReadHistoryAsync(request, cancellationToken);
+    }
+
+    public async Task<bool> CloseSnapshotAsync(
+        string tenantId, string snapshotId, CancellationToken cancellationToken = default)
+    {
+        var request = new CloseSnapshotRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SnapshotId = snapshotId
+        };
+        var response = await _client.CloseSnapshotAsync(request, cancellationToken: cancellationToken);
+        return response.Existed;
+    }
+
+    private async Task<SessionHistoryDto?> ReadHistoryAsync(
+        LoadSessionWithHistoryRequest request, CancellationToken cancellationToken)
+    {
+        using var call = _client.LoadSessionWithHistory(request, cancellationToken: cancellationToken);
+
+        SessionHistoryChunk? first = null;
+        var data = new List<byte>();
+        var entries = new List<WalEntryDto>();
+
+        await foreach (var chunk in call.ResponseStream.ReadAllAsync(cancellationToken))
+        {
+            if (first is null)
+            {
+                first = chunk;
+                if (!chunk.Found) return null;
+            }
+            data.AddRange(chunk.Data);
+            entries.AddRange(chunk.Entries.Select(e => new WalEntryDto(
+                e.Position, e.Operation, e.Path,
+                e.PatchJson.ToByteArray(),
+                DateTimeOffset.FromUnixTimeSeconds(e.TimestampUnix).UtcDateTime
+            )));
+        }
+
+        if (first is null) return null;
+
+        _logger?.LogDebug(
+            "Loaded session {SessionId} at position {Position} from {BasePosition} ({Bytes} bytes, {Entries} WAL entries)",
+            request.SnapshotId is { Length: > 0 } ? request.SnapshotId : request.SessionId, first.TargetPosition, first.BasePosition, data.Count, entries.Count);
+
+        return new SessionHistoryDto(
+            data.ToArray(),
+            first.BasePosition,
+            first.TargetPosition,
+            first.WalCount,
+            string.IsNullOrEmpty(first.SourcePath) ? null : first.SourcePath,
+            entries);
+    }
+
+    /// <summary>
+    /// Times an interrupted SaveSession upload is resumed before giving up.
+    /// </summary>
+    private const int MaxUploadResumes = 3;
+
+    /// <summary>
+    /// Upload a session as a resumable upload: chunks carry a sequence number and
+    /// a CRC32, and when the stream breaks the upload continues from the last chunk
+    /// the server acknowledged (ResumeSaveSession) instead of starting over.
+    /// Servers without resumable uploads get a plain upload.
+    /// </summary>
     public async Task SaveSessionAsync(
         string tenantId, string sessionId, byte[] data, CancellationToken cancellationToken = default)
+    {
+        var uploadId = Guid.NewGuid().ToString("N");
+        var chunks = ChunkData(data).ToList();
+        ulong next = 0;
+
+        for (int attempt = 0; ; attempt++)
+        {
+            SaveSessionResponse response;
+            try
+            {
+                response = await SendSessionChunksAsync(
+                    tenantId, sessionId, uploadId, chunks, next, cancellationToken);
+            }
+            catch (RpcException ex) when (ex.StatusCode == StatusCode.Unimplemented && attempt == 0)
+            {
+                await SaveSessionPlainAsync(tenantId, sessionId, chunks, cancellationToken);
+                break;
+            }
+            catch (RpcException ex) when (IsInterruption(ex) && attempt < MaxUploadResumes)
+            {
+                next = await ResumeUploadAtAsync(tenantId, sessionId, uploadId, cancellationToken);
+                _logger?.LogWarning(ex, "Upload of session {SessionId} interrupted, resuming at chunk {Sequence}",
+                    sessionId, next);
+                continue;
+            }
+
+            if (response.Success)
+                break;
+            if (attempt >= MaxUploadResumes)
+                throw new InvalidOperationException($"Failed to save session {sessionId}");
+            next = response.NextSequence;
+        }
+
+        _logger?.LogDebug("Saved session {SessionId} for tenant {TenantId} ({Bytes} bytes)",
+            sessionId, tenantId, data.Length);
+    }
+
+    private async Task<SaveSessionResponse> SendSessionChunksAsync(
+        string tenantId, string sessionId, string uploadId,
+        IReadOnlyList<(byte[] Chunk, bool IsLast)> chunks, ulong from,
+        CancellationToken cancellationToken)
     {
         using var call = _client.SaveSession(cancellationToken: cancellationToken);
 
-        var chunks = ChunkData(data);
+        // With every chunk acknowledged, resending the last one stores the upload
+        from = Math.Min(from, (ulong)chunks.Count - 1);
+        for (var sequence = from; sequence < (ulong)chunks.Count; sequence++)
+        {
+            var (chunk, isLast) = chunks[(int)sequence];
+            var msg = new SaveSessionChunk
+            {
+                Data = Google.Protobuf.ByteString.CopyFrom(chunk),
+                IsLast = isLast,
+                UploadId = uploadId,
+                Sequence = sequence,
+                Crc32 = System.IO.Hashing.Crc32.HashToUInt32(chunk)
+            };
+
+            // Every stream of the upload opens with the metadata
+            if (sequence == from)
+            {
+                msg.Context = new TenantContext { TenantId = tenantId };
+                msg.SessionId = sessionId;
+            }
+
+            await call.RequestStream.WriteAsync(msg, cancellationToken);
+        }
+
+        await call.RequestStream.CompleteAsync();
+        return await call;
+    }
+
+    private async Task SaveSessionPlainAsync(
+        string tenantId, string sessionId, IReadOnlyList<(byte[] Chunk, bool IsLast)> chunks,
+        CancellationToken cancellationToken)
+    {
+        using var call = _client.SaveSession(cancellationToken: cancellationToken);
         bool isFirst = true;
 
         foreach (var (chunk, isLast) in chunks)
@@ -198,11 +385,28 @@ public sealed class HistoryStorageClient : IHistoryStorage
 
         if (!response.Success)
             throw new InvalidOperationException($"Failed to save session {sessionId}");
+    }
 
-        _logger?.LogDebug("Saved session {SessionId} for tenant {TenantId} ({Bytes} bytes)",
-            sessionId, tenantId, data.Length);
+    /// <summary>
+    /// The chunk an interrupted upload resumes at: where the server says it
+    /// stopped, or 0 when the server no longer knows the upload.
+    /// </summary>
+    private async Task<ulong> ResumeUploadAtAsync(
+        string tenantId, string sessionId, string uploadId, CancellationToken cancellationToken)
+    {
+        var response = await _client.ResumeSaveSessionAsync(new ResumeSaveSessionRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId,
+            UploadId = uploadId
+        }, cancellationToken: cancellationToken);
+        return response.Found ? response.NextSequence : 0;
     }
 
+    private static bool IsInterruption(RpcException ex) =>
+        ex.StatusCode is StatusCode.Unavailable or StatusCode.Internal or StatusCode.Aborted
+            or StatusCode.InvalidArgument or StatusCode.NotFound;
+
     public async Task<IReadOnlyList<SessionInfoDto>> ListSessionsAsync(
         string tenantId, CancellationToken cancellationToken = default)
     {
@@ -217,7 +421,8 @@ public sealed class HistoryStorageClient : IHistoryStorage
             string.IsNullOrEmpty(s.SourcePath) ? null : s.SourcePath,
             DateTimeOffset.FromUnixTimeSeconds(s.CreatedAtUnix).UtcDateTime,
             DateTimeOffset.FromUnixTimeSeconds(s.ModifiedAtUnix).UtcDateTime,
-            s.SizeBytes
+            s.SizeBytes,
+            s.ExpiresAtUnix > 0 ? DateTimeOffset.FromUnixTimeSeconds(s.ExpiresAtUnix).UtcDateTime : null
         )).ToList();
     }
 
@@ -280,7 +485,10 @@ public sealed class HistoryStorageClient : IHistoryStorage
                 SourcePath = entry.SourcePath ?? "",
                 CreatedAtUnix = new DateTimeOffset(entry.CreatedAt).ToUnixTimeSeconds(),
                 ModifiedAtUnix = new DateTimeOffset(entry.ModifiedAt).ToUnixTimeSeconds(),
-                WalPosition = entry.WalPosition
+                WalPosition = entry.WalPosition,
+                ExpiresAtUnix = entry.ExpiresAt is { } expiresAt
+                    ? new DateTimeOffset(expiresAt).ToUnixTimeSeconds()
+                    : 0
             }
         };
         request.Entry.CheckpointPositions.AddRange(entry.CheckpointPositions);
@@ -330,11 +538,45 @@ public sealed class HistoryStorageClient : IHistoryStorage
         return (response.Success, response.Existed);
     }
 
+    public async Task<(bool Success, bool NotFound)> RenameSessionAsync(
+        string tenantId, string sessionId, string? displayName = null, string? alias = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new RenameSessionRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId
+        };
+
+        if (displayName is not null) request.DisplayName = displayName;
+        if (alias is not null) request.Alias = alias;
+
+        var response = await _client.RenameSessionAsync(request, cancellationToken: cancellationToken);
+        return (response.Success, response.NotFound);
+    }
+
+    public async Task<(bool Found, string SessionId, string? DisplayName, string? Alias)> ResolveSessionAsync(
+        string tenantId, string name, CancellationToken cancellationToken = default)
+    {
+        var request = new ResolveSessionRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Name = name
+        };
+
+        var response = await _client.ResolveSessionAsync(request, cancellationToken: cancellationToken);
+        return (
+            response.Found,
+            response.SessionId,
+            string.IsNullOrEmpty(response.DisplayName) ? null : response.DisplayName,
+            string.IsNullOrEmpty(response.Alias) ? null : response.Alias);
+    }
+
     // =========================================================================
     // WAL Operations
     // =========================================================================
 
-    public async Task<ulong> AppendWalAsync(
+    public async Task<AppendWalResultDto> AppendWalAsync(
         string tenantId, string sessionId, IEnumerable<WalEntryDto> entries,
         CancellationToken cancellationToken = default)
     {
@@ -344,26 +586,59 @@ public sealed class HistoryStorageClient : IHistoryStorage
             SessionId = sessionId
         };
 
-        foreach (var entry in entries)
-        {
-            request.Entries.Add(new WalEntry
-            {
-                Position = entry.Position,
-                Operation = entry.Operation,
-                Path = entry.Path,
-                PatchJson = Google.Protobuf.ByteString.CopyFrom(entry.PatchJson),
-                TimestampUnix = new DateTimeOffset(entry.Timestamp).ToUnixTimeSeconds()
-            });
-        }
+        request.Entries.Add(entries.Select(ToProto));
 
         var response = await _client.AppendWalAsync(request, cancellationToken: cancellationToken);
 
         if (!response.Success)
             throw new InvalidOperationException($"Failed to append WAL for session {sessionId}");
 
-        return response.NewPosition;
+        // Servers without a checkpoint policy leave SkipCheckpoint unset: checkpoint every entry
+        return new AppendWalResultDto(
+            response.NewPosition,
+            !response.SkipCheckpoint,
+            string.IsNullOrEmpty(response.CheckpointReason) ? null : response.CheckpointReason);
     }
 
+    public async Task<IReadOnlyList<SessionAppendResultDto>> AppendWalBatchAsync(
+        string tenantId, IReadOnlyList<(string SessionId, IReadOnlyList<WalEntryDto> Entries)> sessions,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new AppendWalBatchRequest
+        {
+            Context = new TenantContext { TenantId = tenantId }
+        };
+        foreach (var (sessionId, entries) in sessions)
+        {
+            var append = new SessionWalAppend { SessionId = sessionId };
+            append.Entries.Add(entries.Select(ToProto));
+            request.Sessions.Add(append);
+        }
+
+        var response = await _client.AppendWalBatchAsync(request, cancellationToken: cancellationToken);
+
+        return response.Results
+            .Select(r => new SessionAppendResultDto(
+                r.SessionId,
+                r.Success
+                    ? new AppendWalResultDto(
+                        r.NewPosition,
+                        !r.SkipCheckpoint,
+                        string.IsNullOrEmpty(r.CheckpointReason) ? null : r.CheckpointReason)
+                    : null,
+                r.Success ? null : r.Error))
+            .ToList();
+    }
+
+    private static WalEntry ToProto(WalEntryDto entry) => new()
+    {
+        Position = entry.Position,
+        Operation = entry.Operation,
+        Path = entry.Path,
+        PatchJson = Google.Protobuf.ByteString.CopyFrom(entry.PatchJson),
+        TimestampUnix = new DateTimeOffset(entry.Timestamp).ToUnixTimeSeconds()
+    };
+
     public async Task<(IReadOnlyList<WalEntryDto> Entries, bool HasMore)> ReadWalAsync(
         string tenantId, string sessionId, ulong fromPosition = 0, ulong limit = 0,
         CancellationToken cancellationToken = default)
@@ -387,6 +662,29 @@ public sealed class HistoryStorageClient : IHistoryStorage
         return (entries, response.HasMore);
     }
 
+    public async Task<(IReadOnlyList<WalEntryDto> Entries, bool HasMore)> TailWalAsync(
+        string tenantId, string sessionId, ulong beforePosition = 0, ulong limit = 0,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new TailWalRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId,
+            BeforePosition = beforePosition,
+            Limit = limit
+        };
+
+        var response = await _client.TailWalAsync(request, cancellationToken: cancellationToken);
+
+        var entries = response.Entries.Select(e => new WalEntryDto(
+            e.Position, e.Operation, e.Path,
+            e.PatchJson.ToByteArray(),
+            DateTimeOffset.FromUnixTimeSeconds(e.TimestampUnix).UtcDateTime
+        )).ToList();
+
+        return (entries, response.HasMore);
+    }
+
     public async Task<ulong> TruncateWalAsync(
         string tenantId, string sessionId, ulong keepFromPosition,
         CancellationToken cancellationToken = default)
@@ -495,6 +793,323 @@ public sealed class HistoryStorageClient : IHistoryStorage
         )).ToList();
     }
 
+    // =========================================================================
+    // Presigned URLs
+    // =========================================================================
+
+    public async Task<PresignedUrlDto?> CreateDownloadUrlAsync(
+        string tenantId, string sessionId, ulong? checkpointPosition = null, TimeSpan? expiresIn = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new CreateDownloadUrlRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId,
+            ExpiresInSecs = (ulong)(expiresIn?.TotalSeconds ?? 0)
+        };
+        if (checkpointPosition.HasValue)
+            request.CheckpointPosition = checkpointPosition.Value;
+
+        var response = await _client.CreateDownloadUrlAsync(request, cancellationToken: cancellationToken);
+        if (!response.Found) return null;
+
+        return new PresignedUrlDto(
+            response.Url,
+            new Dictionary<string, string>(),
+            DateTimeOffset.FromUnixTimeSeconds(response.ExpiresAtUnix).UtcDateTime);
+    }
+
+    public async Task<PresignedUrlDto> CreateUploadUrlAsync(
+        string tenantId, string sessionId, ulong sizeBytes, ulong? checkpointPosition = null,
+        TimeSpan? expiresIn = null, CancellationToken cancellationToken = default)
+    {
+        var request = new CreateUploadUrlRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId,
+            SizeBytes = sizeBytes,
+            ExpiresInSecs = (ulong)(expiresIn?.TotalSeconds ?? 0)
+        };
+        if (checkpointPosition.HasValue)
+            request.CheckpointPosition = checkpointPosition.Value;
+
+        var response = await _client.CreateUploadUrlAsync(request, cancellationToken: cancellationToken);
+        _logger?.LogDebug("Created upload URL for session {SessionId} ({Bytes} bytes)", sessionId, sizeBytes);
+        return new PresignedUrlDto(
+            response.Url,
+            new Dictionary<string, string>(response.Headers),
+            DateTimeOffset.FromUnixTimeSeconds(response.ExpiresAtUnix).UtcDateTime,
+            response.UploadId);
+    }
+
+    public async Task<ulong> CommitUploadAsync(
+        string tenantId, string sessionId, string uploadId, ulong? checkpointPosition = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new CommitUploadRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            SessionId = sessionId,
+            UploadId = uploadId
+        };
+        if (checkpointPosition.HasValue)
+            request.CheckpointPosition = checkpointPosition.Value;
+
+        var response = await _client.CommitUploadAsync(request, cancellationToken: cancellationToken);
+        _logger?.LogDebug("Committed upload {UploadId} of session {SessionId} ({Bytes} bytes)",
+            uploadId, sessionId, response.SizeBytes);
+        return response.SizeBytes;
+    }
+
+    // =========================================================================
+    // Library Operations
+    // =========================================================================
+
+    public async Task SaveLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, byte[] data,
+        CancellationToken cancellationToken = default)
+    {
+        using var call = _client.SaveLibraryItem(cancellationToken: cancellationToken);
+
+        var chunks = ChunkData(data);
+        bool isFirst = true;
+
+        foreach (var (chunk, isLast) in chunks)
+        {
+            var msg = new SaveLibraryItemChunk
+            {
+                Data = Google.Protobuf.ByteString.CopyFrom(chunk),
+                IsLast = isLast
+            };
+
+            if (isFirst)
+            {
+                msg.Context = new TenantContext { TenantId = tenantId };
+                msg.Kind = kind;
+                msg.Name = name;
+                isFirst = false;
+            }
+
+            await call.RequestStream.WriteAsync(msg, cancellationToken);
+        }
+
+        await call.RequestStream.CompleteAsync();
+        var response = await call;
+
+        if (!response.Success)
+            throw new InvalidOperationException($"Failed to save {kind} {name}");
+
+        _logger?.LogDebug("Saved {Kind} {Name} for tenant {TenantId} ({Bytes} bytes)",
+            kind, name, tenantId, data.Length);
+    }
+
+    public async Task<(byte[]? Data, bool Found)> LoadLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, CancellationToken cancellationToken = default)
+    {
+        var request = new LoadLibraryItemRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Kind = kind,
+            Name = name
+        };
+
+        using var call = _client.LoadLibraryItem(request, cancellationToken: cancellationToken);
+
+        var data = new List<byte>();
+        bool found = false;
+        bool isFirst = true;
+
+        await foreach (var chunk in call.ResponseStream.ReadAllAsync(cancellationToken))
+        {
+            if (isFirst)
+            {
+                found = chunk.Found;
+                isFirst = false;
+                if (!found) return (null, false);
+            }
+            data.AddRange(chunk.Data);
+        }
+
+        return (data.ToArray(), found);
+    }
+
+    public async Task<IReadOnlyList<LibraryItemInfoDto>> ListLibraryItemsAsync(
+        string tenantId, LibraryKind kind, CancellationToken cancellationToken = default)
+    {
+        var request = new ListLibraryItemsRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Kind = kind
+        };
+
+        var response = await _client.ListLibraryItemsAsync(request, cancellationToken: cancellationToken);
+        return response.Items.Select(t => new LibraryItemInfoDto(
+            t.Name, DateTimeOffset.FromUnixTimeSeconds(t.ModifiedAtUnix).UtcDateTime, t.SizeBytes
+        )).ToList();
+    }
+
+    public async Task<bool> DeleteLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, CancellationToken cancellationToken = default)
+    {
+        var request = new DeleteLibraryItemRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Kind = kind,
+            Name = name
+        };
+
+        var response = await _client.DeleteLibraryItemAsync(request, cancellationToken: cancellationToken);
+        return response.Existed;
+    }
+
+    public async Task<bool> CreateSessionFromTemplateAsync(
+        string tenantId, string templateName, string sessionId, DateTime? expiresAt = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new CreateSessionFromTemplateRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            TemplateName = templateName,
+            SessionId = sessionId,
+            ExpiresAtUnix = expiresAt is { } at ? new DateTimeOffset(at).ToUnixTimeSeconds() : 0
+        };
+
+        var response = await _client.CreateSessionFromTemplateAsync(request, cancellationToken: cancellationToken);
+        return response.Found;
+    }
+
+    // =========================================================================
+    // Long-running Operations
+    // =========================================================================
+
+    private OperationService.OperationServiceClient GetOperationClient()
+        => new OperationService.OperationServiceClient(_channel.Intercept(CorrelationInterceptor.Instance));
+
+    public async Task<OperationDto> StartOperationAsync(
+        string tenantId, string kind, string description, string? sessionId = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new StartOperationRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Kind = kind,
+            Description = description,
+            SessionId = sessionId ?? ""
+        };
+
+        var response = await GetOperationClient().StartOperationAsync(request, cancellationToken: cancellationToken);
+        return ConvertOperation(response.Operation);
+    }
+
+    public async Task<OperationDto> ReportOperationProgressAsync(
+        string tenantId, string operationId, double progress, string? message = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new ReportProgressRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            OperationId = operationId,
+            Progress = progress,
+            Message = message ?? ""
+        };
+
+        var response = await GetOperationClient().ReportProgressAsync(request, cancellationToken: cancellationToken);
+        return ConvertOperation(response.Operation);
+    }
+
+    public async Task<OperationDto> FinishOperationAsync(
+        string tenantId, string operationId, bool success, string? result = null, string? error = null,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new FinishOperationRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            OperationId = operationId,
+            Success = success,
+            Result = result ?? "",
+            Error = error ?? ""
+        };
+
+        var response = await GetOperationClient().FinishOperationAsync(request, cancellationToken: cancellationToken);
+        return ConvertOperation(response.Operation);
+    }
+
+    public async Task<OperationDto> CancelOperationAsync(
+        string tenantId, string operationId, CancellationToken cancellationToken = default)
+    {
+        var request = new CancelOperationRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            OperationId = operationId
+        };
+
+        var response = await GetOperationClient().CancelOperationAsync(request, cancellationToken: cancellationToken);
+        return ConvertOperation(response.Operation);
+    }
+
+    public async Task<OperationDto?> GetOperationAsync(
+        string tenantId, string operationId, CancellationToken cancellationToken = default)
+    {
+        var request = new GetOperationRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            OperationId = operationId
+        };
+
+        try
+        {
+            var response = await GetOperationClient().GetOperationAsync(request, cancellationToken: cancellationToken);
+            return ConvertOperation(response.Operation);
+        }
+        catch (RpcException ex) when (ex.StatusCode == StatusCode.NotFound)
+        {
+            return null;
+        }
+    }
+
+    public async Task<IReadOnlyList<OperationDto>> ListOperationsAsync(
+        string tenantId, bool includeFinished = false, CancellationToken cancellationToken = default)
+    {
+        var request = new ListOperationsRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            IncludeFinished = includeFinished
+        };
+
+        var response = await GetOperationClient().ListOperationsAsync(request, cancellationToken: cancellationToken);
+        return response.Operations.Select(ConvertOperation).ToList();
+    }
+
+    public async IAsyncEnumerable<OperationDto> WatchOperationAsync(
+        string tenantId, string operationId,
+        [System.Runtime.CompilerServices.EnumeratorCancellation] CancellationToken cancellationToken = default)
+    {
+        var request = new WatchOperationRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            OperationId = operationId
+        };
+
+        using var call = GetOperationClient().WatchOperation(request, cancellationToken: cancellationToken);
+
+        await foreach (var op in call.ResponseStream.ReadAllAsync(cancellationToken))
+            yield return ConvertOperation(op);
+    }
+
+    private static OperationDto ConvertOperation(Operation op) => new(
+        op.Id,
+        op.Kind,
+        op.Description,
+        string.IsNullOrEmpty(op.SessionId) ? null : op.SessionId,
+        op.State,
+        op.Progress,
+        string.IsNullOrEmpty(op.Message) ? null : op.Message,
+        string.IsNullOrEmpty(op.Result) ? null : op.Result,
+        string.IsNullOrEmpty(op.Error) ? null : op.Error,
+        DateTimeOffset.FromUnixTimeSeconds(op.CreatedAtUnix).UtcDateTime,
+        DateTimeOffset.FromUnixTimeSeconds(op.UpdatedAtUnix).UtcDateTime
+    );
+
     // =========================================================================
     // Health Check
     // =========================================================================
@@ -506,6 +1121,18 @@ public sealed class HistoryStorageClient : IHistoryStorage
         return (response.Healthy, response.Backend, response.Version);
     }
 
+    public async Task<StorageCapabilitiesDto> GetCapabilitiesAsync(CancellationToken cancellationToken = default)
+    {
+        var response = await _client.GetCapabilitiesAsync(new GetCapabilitiesRequest(), cancellationToken: cancellationToken);
+        return new StorageCapabilitiesDto(
+            response.Backend,
+            response.Version,
+            response.SchemaVersion,
+            response.Features.ToHashSet(),
+            response.MaxObjectBytes,
+            response.MaxWalEntryBytes);
+    }
+
     // =========================================================================
     // Helpers
     // =========================================================================
@@ -1,8 +1,9 @@
 namespace DocxMcp.Grpc;
 
 /// <summary>
-/// Interface for history storage operations (sessions, index, WAL, checkpoints).
-/// Maps to the StorageService gRPC service.
+/// Interface for history storage operations (sessions, index, WAL, checkpoints),
+/// plus the registry of long-running operations served alongside it.
+/// Maps to the StorageService and OperationService gRPC services.
 /// </summary>
 public interface IHistoryStorage : IAsyncDisposable
 {
@@ -10,6 +11,35 @@ public interface IHistoryStorage : IAsyncDisposable
     Task<(byte[]? Data, bool Found)> LoadSessionAsync(
         string tenantId, string sessionId, CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Load what it takes to open a session in one call: the newest checkpoint (or the
+    /// baseline) at or before <paramref name="position"/> (null = the index cursor) and
+    /// the WAL entries to replay on it. Null when the session doesn't exist.
+    /// </summary>
+    Task<SessionHistoryDto?> LoadSessionWithHistoryAsync(
+        string tenantId, string sessionId, ulong? position = null,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Pin a read-only view of a session at <paramref name="position"/> (null = the
+    /// index cursor), for exports and previews that must not see concurrent edits.
+    /// Null when the session doesn't exist. Read it with <see cref="LoadSnapshotAsync"/>
+    /// and close it when done; it expires otherwise.
+    /// </summary>
+    Task<SessionSnapshotDto?> OpenSnapshotAsync(
+        string tenantId, string sessionId, ulong? position = null,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Load a snapshot opened with <see cref="OpenSnapshotAsync"/>, as it was when opened.
+    /// Throws NotFound once it was closed or expired.
+    /// </summary>
+    Task<SessionHistoryDto?> LoadSnapshotAsync(
+        string tenantId, string snapshotId, CancellationToken cancellationToken = default);
+
+    Task<bool> CloseSnapshotAsync(
+        string tenantId, string snapshotId, CancellationToken cancellationToken = default);
+
     Task SaveSessionAsync(
         string tenantId, string sessionId, byte[] data, CancellationToken cancellationToken = default);
 
@@ -43,15 +73,45 @@ public interface IHistoryStorage : IAsyncDisposable
     Task<(bool Success, bool Existed)> RemoveSessionFromIndexAsync(
         string tenantId, string sessionId, CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Set a session's display name and/or alias. Null leaves a field unchanged,
+    /// an empty string clears it. Throws if the alias is used by another session.
+    /// </summary>
+    Task<(bool Success, bool NotFound)> RenameSessionAsync(
+        string tenantId, string sessionId, string? displayName = null, string? alias = null,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Look up a session by ID or alias (case-insensitive).
+    /// </summary>
+    Task<(bool Found, string SessionId, string? DisplayName, string? Alias)> ResolveSessionAsync(
+        string tenantId, string name, CancellationToken cancellationToken = default);
+
     // WAL operations
-    Task<ulong> AppendWalAsync(
+    Task<AppendWalResultDto> AppendWalAsync(
         string tenantId, string sessionId, IEnumerable<WalEntryDto> entries,
         CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Append to the WALs of several sessions in one call. Each session succeeds or
+    /// fails on its own; results are in the order of <paramref name="sessions"/>.
+    /// </summary>
+    Task<IReadOnlyList<SessionAppendResultDto>> AppendWalBatchAsync(
+        string tenantId, IReadOnlyList<(string SessionId, IReadOnlyList<WalEntryDto> Entries)> sessions,
+        CancellationToken cancellationToken = default);
+
     Task<(IReadOnlyList<WalEntryDto> Entries, bool HasMore)> ReadWalAsync(
         string tenantId, string sessionId, ulong fromPosition = 0, ulong limit = 0,
         CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Read up to <paramref name="limit"/> WAL entries before <paramref name="beforePosition"/>
+    /// (0 = the newest), newest first. HasMore is true when older entries remain.
+    /// </summary>
+    Task<(IReadOnlyList<WalEntryDto> Entries, bool HasMore)> TailWalAsync(
+        string tenantId, string sessionId, ulong beforePosition = 0, ulong limit = 0,
+        CancellationToken cancellationToken = default);
+
     Task<ulong> TruncateWalAsync(
         string tenantId, string sessionId, ulong keepFromPosition,
         CancellationToken cancellationToken = default);
@@ -68,7 +128,106 @@ public interface IHistoryStorage : IAsyncDisposable
     Task<IReadOnlyList<CheckpointInfoDto>> ListCheckpointsAsync(
         string tenantId, string sessionId, CancellationToken cancellationToken = default);
 
+    // Presigned URLs (R2 only)
+
+    /// <summary>
+    /// Short-lived URL downloading a session's document (or its checkpoint at
+    /// <paramref name="checkpointPosition"/>) straight from the bucket, without proxying
+    /// the bytes through gRPC. Null when it doesn't exist. Needs the "presigned_urls"
+    /// capability; <paramref name="expiresIn"/> defaults to the server's maximum.
+    /// </summary>
+    Task<PresignedUrlDto?> CreateDownloadUrlAsync(
+        string tenantId, string sessionId, ulong? checkpointPosition = null, TimeSpan? expiresIn = null,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Short-lived URL (at most 15 minutes) storing a session's document (or its
+    /// checkpoint at <paramref name="checkpointPosition"/>) of exactly
+    /// <paramref name="sizeBytes"/> with one PUT carrying the returned headers. The
+    /// upload is staged until <see cref="CommitUploadAsync"/>. Refused for sessions
+    /// under legal hold. Needs the "presigned_uploads" capability.
+    /// </summary>
+    Task<PresignedUrlDto> CreateUploadUrlAsync(
+        string tenantId, string sessionId, ulong sizeBytes, ulong? checkpointPosition = null,
+        TimeSpan? expiresIn = null, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Move the document uploaded with the URL whose <see cref="PresignedUrlDto.UploadId"/>
+    /// is <paramref name="uploadId"/> into place and record it in the index. Returns the
+    /// size stored.
+    /// </summary>
+    Task<ulong> CommitUploadAsync(
+        string tenantId, string sessionId, string uploadId, ulong? checkpointPosition = null,
+        CancellationToken cancellationToken = default);
+
+    // Library (templates and snippets)
+    Task SaveLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, byte[] data,
+        CancellationToken cancellationToken = default);
+
+    Task<(byte[]? Data, bool Found)> LoadLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, CancellationToken cancellationToken = default);
+
+    Task<IReadOnlyList<LibraryItemInfoDto>> ListLibraryItemsAsync(
+        string tenantId, LibraryKind kind, CancellationToken cancellationToken = default);
+
+    Task<bool> DeleteLibraryItemAsync(
+        string tenantId, LibraryKind kind, string name, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Create a session (baseline + index entry) from a template, ephemeral when
+    /// expiresAt is set. Returns false if the template doesn't exist.
+    /// </summary>
+    Task<bool> CreateSessionFromTemplateAsync(
+        string tenantId, string templateName, string sessionId, DateTime? expiresAt = null,
+        CancellationToken cancellationToken = default);
+
+    // Long-running operations (OperationService)
+
+    /// <summary>
+    /// Register a running operation; returns it with its new ID.
+    /// </summary>
+    Task<OperationDto> StartOperationAsync(
+        string tenantId, string kind, string description, string? sessionId = null,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Report progress (0.0 - 1.0). The returned state is Cancelled if the
+    /// operation was cancelled in the meantime.
+    /// </summary>
+    Task<OperationDto> ReportOperationProgressAsync(
+        string tenantId, string operationId, double progress, string? message = null,
+        CancellationToken cancellationToken = default);
+
+    Task<OperationDto> FinishOperationAsync(
+        string tenantId, string operationId, bool success, string? result = null, string? error = null,
+        CancellationToken cancellationToken = default);
+
+    Task<OperationDto> CancelOperationAsync(
+        string tenantId, string operationId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Get an operation, or null if it is unknown or expired.
+    /// </summary>
+    Task<OperationDto?> GetOperationAsync(
+        string tenantId, string operationId, CancellationToken cancellationToken = default);
+
+    Task<IReadOnlyList<OperationDto>> ListOperationsAsync(
+        string tenantId, bool includeFinished = false, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Stream the operation's current state, then each change, until it finishes.
+    /// </summary>
+    IAsyncEnumerable<OperationDto> WatchOperationAsync(
+        string tenantId, string operationId, CancellationToken cancellationToken = default);
+
     // Health check
     Task<(bool Healthy, string Backend, string Version)> HealthCheckAsync(
         CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// What the storage server supports (e.g. "cas", "resumable_uploads") and its limits,
+    /// to adapt to the backend instead of assuming every one is the same.
+    /// </summary>
+    Task<StorageCapabilitiesDto> GetCapabilitiesAsync(CancellationToken cancellationToken = default);
 }
@@ -21,7 +21,11 @@ public interface ISyncStorage : IAsyncDisposable
         string? path = null, string? fileId = null, bool? autoSync = null,
         CancellationToken cancellationToken = default);
 
-    Task<(bool Success, string Error, long SyncedAtUnix)> SyncToSourceAsync(
+    /// <summary>
+    /// Sync session data to its source. Queued is true when the source was
+    /// unreachable and the sync will be retried in the background.
+    /// </summary>
+    Task<(bool Success, string Error, long SyncedAtUnix, bool Queued)> SyncToSourceAsync(
         string tenantId, string sessionId, byte[] data,
         CancellationToken cancellationToken = default);
 
@@ -50,6 +54,27 @@ public interface ISyncStorage : IAsyncDisposable
     IAsyncEnumerable<ExternalChangeEventDto> WatchChangesAsync(
         string tenantId, IEnumerable<string> sessionIds, CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Acknowledge handled events from WatchChangesAsync.
+    /// Unacknowledged events are redelivered. Returns how many queued events were removed.
+    /// </summary>
+    Task<(bool Success, int Acknowledged, string Error)> AckChangesAsync(
+        string tenantId, IEnumerable<ExternalChangeEventDto> events,
+        CancellationToken cancellationToken = default);
+
+    Task<List<PendingSyncDto>> ListPendingSyncsAsync(
+        string tenantId, CancellationToken cancellationToken = default);
+
+    // Recent files and favorites
+    Task<List<RecentFileDto>> ListRecentFilesAsync(
+        string tenantId, bool favoritesOnly = false, int limit = 0,
+        CancellationToken cancellationToken = default);
+
+    Task<(bool Success, string Error)> PinFavoriteAsync(
+        string tenantId, SourceType sourceType, string? connectionId,
+        string path, string? fileId, bool pinned,
+        CancellationToken cancellationToken = default);
+
     // Browse operations
     Task<List<ConnectionInfoDto>> ListConnectionsAsync(
         string tenantId, SourceType? filterType = null,
@@ -60,6 +85,12 @@ public interface ISyncStorage : IAsyncDisposable
         string? path = null, string? pageToken = null, int pageSize = 50,
         CancellationToken cancellationToken = default);
 
+    Task<FileListResultDto> SearchConnectionFilesAsync(
+        string tenantId, SourceType sourceType, string? connectionId,
+        string query, string? path = null, bool recursive = true, string? mimeFilter = null,
+        string? pageToken = null, int pageSize = 50,
+        CancellationToken cancellationToken = default);
+
     Task<byte[]> DownloadFromSourceAsync(
         string tenantId, SourceType sourceType, string? connectionId,
         string path, string? fileId = null,
@@ -80,6 +111,33 @@ public record SyncStatusDto(
     bool HasPendingChanges,
     string? LastError);
 
+/// <summary>
+/// Sync queued for retry because its source was unreachable.
+/// </summary>
+public record PendingSyncDto(
+    string SessionId,
+    SourceType SourceType,
+    string? ConnectionId,
+    string Path,
+    string? FileId,
+    long QueuedAtUnix,
+    int Attempts,
+    long NextAttemptAtUnix,
+    string LastError,
+    long SizeBytes);
+
+/// <summary>
+/// Recently opened/synced or favorite file DTO.
+/// </summary>
+public record RecentFileDto(
+    SourceType SourceType,
+    string? ConnectionId,
+    string Path,
+    string? FileId,
+    string DisplayName,
+    long LastUsedAtUnix,
+    bool Favorite);
+
 /// <summary>
 /// Source metadata DTO.
 /// </summary>
@@ -101,7 +159,9 @@ public record ExternalChangeEventDto(
     SourceMetadataDto? OldMetadata,
     SourceMetadataDto? NewMetadata,
     long DetectedAtUnix,
-    string? NewUri);
+    string? NewUri,
+    ulong Sequence = 0,
+    uint CoalescedCount = 1);
 
 /// <summary>
 /// Connection info DTO.
@@ -110,7 +170,8 @@ public record ConnectionInfoDto(
     string ConnectionId,
     SourceType Type,
     string DisplayName,
-    string? ProviderAccountId);
+    string? ProviderAccountId,
+    string? Backend = null);
 
 /// <summary>
 /// File entry DTO.
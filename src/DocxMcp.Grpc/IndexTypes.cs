@@ -3,25 +3,29 @@ namespace DocxMcp.Grpc;
 /// <summary>
 /// DTO for session index entry used in atomic index operations.
 /// Named with Dto suffix to avoid conflict with proto-generated SessionIndexEntry.
+/// A non-null ExpiresAt makes the session ephemeral: storage purges it once that time passes.
 /// </summary>
 public sealed record SessionIndexEntryDto(
     string? SourcePath,
     DateTime CreatedAt,
     DateTime ModifiedAt,
     ulong WalPosition,
-    IReadOnlyList<ulong> CheckpointPositions
+    IReadOnlyList<ulong> CheckpointPositions,
+    DateTime? ExpiresAt = null
 );
 
 /// <summary>
 /// DTO for session info returned by list operations.
 /// Named with Dto suffix to avoid conflict with proto-generated SessionInfo.
+/// ExpiresAt is set for ephemeral sessions.
 /// </summary>
 public sealed record SessionInfoDto(
     string SessionId,
     string? SourcePath,
     DateTime CreatedAt,
     DateTime ModifiedAt,
-    long SizeBytes
+    long SizeBytes,
+    DateTime? ExpiresAt = null
 );
 
 /// <summary>
@@ -34,6 +38,16 @@ public sealed record CheckpointInfoDto(
     long SizeBytes
 );
 
+/// <summary>
+/// DTO for a template or snippet in the tenant's library.
+/// Named with Dto suffix to avoid conflict with proto-generated LibraryItemInfo.
+/// </summary>
+public sealed record LibraryItemInfoDto(
+    string Name,
+    DateTime ModifiedAt,
+    long SizeBytes
+);
+
 /// <summary>
 /// DTO for WAL entry.
 /// Named with Dto suffix to avoid conflict with proto-generated WalEntry.
@@ -45,3 +59,93 @@ public sealed record WalEntryDto(
     byte[] PatchJson,
     DateTime Timestamp
 );
+
+/// <summary>
+/// Result of AppendWal: the WAL position after the append, and whether the server's
+/// checkpoint policy wants a checkpoint there (with the reason when it does).
+/// </summary>
+public sealed record AppendWalResultDto(
+    ulong NewPosition,
+    bool CheckpointDue,
+    string? CheckpointReason
+);
+
+/// <summary>
+/// One session's outcome in AppendWalBatch: the append result, or the error
+/// that left the session's WAL unchanged.
+/// </summary>
+public sealed record SessionAppendResultDto(
+    string SessionId,
+    AppendWalResultDto? Result,
+    string? Error
+);
+
+/// <summary>
+/// A session's document at some WAL position, with the WAL entries to replay on it
+/// to reach the target position (from LoadSessionWithHistory).
+/// </summary>
+public sealed record SessionHistoryDto(
+    byte[] Document,
+    ulong BasePosition,
+    ulong TargetPosition,
+    ulong WalCount,
+    string? SourcePath,
+    IReadOnlyList<WalEntryDto> Entries
+);
+
+/// <summary>
+/// Backend, optional features, limits and proto schema version of a storage server.
+/// A max object size of 0 means no limit.
+/// </summary>
+public sealed record StorageCapabilitiesDto(
+    string Backend,
+    string Version,
+    uint SchemaVersion,
+    IReadOnlySet<string> Features,
+    ulong MaxObjectBytes,
+    ulong MaxWalEntryBytes
+)
+{
+    public bool Supports(string feature) => Features.Contains(feature);
+}
+
+/// <summary>
+/// A read-only view of a session pinned by OpenSnapshot, and when it expires.
+/// </summary>
+public sealed record SessionSnapshotDto(
+    string SnapshotId,
+    ulong Position,
+    DateTime ExpiresAt
+);
+
+/// <summary>
+/// A presigned URL granting one request on one stored document, the headers the
+/// request must carry, and when it expires. Uploads also carry the ID to commit them with.
+/// </summary>
+public sealed record PresignedUrlDto(
+    string Url,
+    IReadOnlyDictionary<string, string> Headers,
+    DateTime ExpiresAt,
+    string? UploadId = null
+);
+
+/// <summary>
+/// DTO for a long-running operation.
+/// Named with Dto suffix to avoid conflict with proto-generated Operation.
+/// </summary>
+public sealed record OperationDto(
+    string Id,
+    string Kind,
+    string Description,
+    string? SessionId,
+    OperationState State,
+    double Progress,
+    string? Message,
+    string? Result,
+    string? Error,
+    DateTime CreatedAt,
+    DateTime UpdatedAt
+)
+{
+    public bool IsFinished => State != OperationState.Running;
+}
@@ -15,6 +15,9 @@ public static partial class NativeStorage
     [LibraryImport("*")]
     private static partial int docx_storage_shutdown();
 
+    [LibraryImport("*")]
+    private static unsafe partial long docx_storage_health(byte* buf, nuint maxLen);
+
     private static readonly bool IsDebug =
         Environment.GetEnvironmentVariable("DEBUG") is not null;
 
@@ -39,5 +42,23 @@ public static partial class NativeStorage
         if (IsDebug) Console.Error.WriteLine("[native] Init: done");
     }
 
+    /// <summary>
+    /// Health of the embedded server as JSON (status, generation, restarts, last restart
+    /// reason, heartbeat age, standby readiness), or null when it is not initialized.
+    /// The server restarts itself when wedged; restarts show up here.
+    /// </summary>
+    public static string? Health()
+    {
+        var buffer = new byte[4096];
+        unsafe
+        {
+            fixed (byte* ptr = buffer)
+            {
+                var length = docx_storage_health(ptr, (nuint)buffer.Length);
+                return length < 0 ? null : Encoding.UTF8.GetString(buffer, 0, (int)length);
+            }
+        }
+    }
+
     public static void Shutdown() => docx_storage_shutdown();
 }
@@ -1,4 +1,5 @@
 using Grpc.Core;
+using Grpc.Core.Interceptors;
 using Grpc.Net.Client;
 using Microsoft.Extensions.Logging;
 
@@ -27,10 +28,10 @@ public class SyncStorageClient : ISyncStorage
     }
 
     private SourceSyncService.SourceSyncServiceClient GetSyncClient()
-        => new SourceSyncService.SourceSyncServiceClient(_channel);
+        => new SourceSyncService.SourceSyncServiceClient(_channel.Intercept(CorrelationInterceptor.Instance));
 
     private ExternalWatchService.ExternalWatchServiceClient GetWatchClient()
-        => new ExternalWatchService.ExternalWatchServiceClient(_channel);
+        => new ExternalWatchService.ExternalWatchServiceClient(_channel.Intercept(CorrelationInterceptor.Instance));
 
     // =========================================================================
     // SourceSync Operations
@@ -105,7 +106,7 @@ public class SyncStorageClient : ISyncStorage
         return (response.Success, response.Error);
     }
 
-    public async Task<(bool Success, string Error, long SyncedAtUnix)> SyncToSourceAsync(
+    public async Task<(bool Success, string Error, long SyncedAtUnix, bool Queued)> SyncToSourceAsync(
         string tenantId, string sessionId, byte[] data,
         CancellationToken cancellationToken = default)
     {
@@ -138,7 +139,31 @@ public class SyncStorageClient : ISyncStorage
         _logger?.LogDebug("Synced session {SessionId} for tenant {TenantId} ({Bytes} bytes, success={Success})",
             sessionId, tenantId, data.Length, response.Success);
 
-        return (response.Success, response.Error, response.SyncedAtUnix);
+        return (response.Success, response.Error, response.SyncedAtUnix, response.Queued);
+    }
+
+    public async Task<List<PendingSyncDto>> ListPendingSyncsAsync(
+        string tenantId, CancellationToken cancellationToken = default)
+    {
+        var request = new ListPendingSyncsRequest
+        {
+            Context = new TenantContext { TenantId = tenantId }
+        };
+
+        var response = await GetSyncClient().ListPendingSyncsAsync(request, cancellationToken: cancellationToken);
+
+        return response.Syncs.Select(p => new PendingSyncDto(
+            p.SessionId,
+            (SourceType)(int)p.Source.Type,
+            string.IsNullOrEmpty(p.Source.ConnectionId) ? null : p.Source.ConnectionId,
+            p.Source.Path,
+            string.IsNullOrEmpty(p.Source.FileId) ? null : p.Source.FileId,
+            p.QueuedAtUnix,
+            p.Attempts,
+            p.NextAttemptAtUnix,
+            p.LastError,
+            p.SizeBytes
+        )).ToList();
     }
 
     public async Task<SyncStatusDto?> GetSyncStatusAsync(
@@ -263,15 +288,81 @@ public class SyncStorageClient : ISyncStorage
                 evt.OldMetadata is not null ? ConvertMetadata(evt.OldMetadata) : null,
                 evt.NewMetadata is not null ? ConvertMetadata(evt.NewMetadata) : null,
                 evt.DetectedAtUnix,
-                string.IsNullOrEmpty(evt.NewUri) ? null : evt.NewUri
+                string.IsNullOrEmpty(evt.NewUri) ? null : evt.NewUri,
+                evt.Sequence,
+                evt.CoalescedCount
             );
         }
     }
 
+    public async Task<(bool Success, int Acknowledged, string Error)> AckChangesAsync(
+        string tenantId, IEnumerable<ExternalChangeEventDto> events,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new AckChangesRequest
+        {
+            Context = new TenantContext { TenantId = tenantId }
+        };
+        request.Acks.AddRange(events.Select(e => new ChangeAck
+        {
+            SessionId = e.SessionId,
+            Sequence = e.Sequence
+        }));
+
+        var response = await GetWatchClient().AckChangesAsync(request, cancellationToken: cancellationToken);
+        return (response.Success, response.Acknowledged, response.Error);
+    }
+
     // =========================================================================
     // Browse Operations
     // =========================================================================
 
+    public async Task<List<RecentFileDto>> ListRecentFilesAsync(
+        string tenantId, bool favoritesOnly = false, int limit = 0,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new ListRecentFilesRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            FavoritesOnly = favoritesOnly,
+            Limit = limit
+        };
+
+        var response = await GetSyncClient().ListRecentFilesAsync(request, cancellationToken: cancellationToken);
+
+        return response.Files.Select(f => new RecentFileDto(
+            (SourceType)(int)f.Source.Type,
+            string.IsNullOrEmpty(f.Source.ConnectionId) ? null : f.Source.ConnectionId,
+            f.Source.Path,
+            string.IsNullOrEmpty(f.Source.FileId) ? null : f.Source.FileId,
+            f.DisplayName,
+            f.LastUsedAtUnix,
+            f.Favorite
+        )).ToList();
+    }
+
+    public async Task<(bool Success, string Error)> PinFavoriteAsync(
+        string tenantId, SourceType sourceType, string? connectionId,
+        string path, string? fileId, bool pinned,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new PinFavoriteRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Source = new SourceDescriptor
+            {
+                Type = sourceType,
+                ConnectionId = connectionId ?? "",
+                Path = path,
+                FileId = fileId ?? ""
+            },
+            Pinned = pinned
+        };
+
+        var response = await GetSyncClient().PinFavoriteAsync(request, cancellationToken: cancellationToken);
+        return (response.Success, response.Error);
+    }
+
     public async Task<List<ConnectionInfoDto>> ListConnectionsAsync(
         string tenantId, SourceType? filterType = null,
         CancellationToken cancellationToken = default)
@@ -288,7 +379,8 @@ public class SyncStorageClient : ISyncStorage
             c.ConnectionId,
             (SourceType)(int)c.Type,
             c.DisplayName,
-            string.IsNullOrEmpty(c.ProviderAccountId) ? null : c.ProviderAccountId
+            string.IsNullOrEmpty(c.ProviderAccountId) ? null : c.ProviderAccountId,
+            string.IsNullOrEmpty(c.Backend) ? null : c.Backend
         )).ToList();
     }
 
@@ -308,7 +400,34 @@ public class SyncStorageClient : ISyncStorage
         };
 
         var response = await GetSyncClient().ListConnectionFilesAsync(request, cancellationToken: cancellationToken);
+        return ToFileListResult(response);
+    }
 
+    public async Task<FileListResultDto> SearchConnectionFilesAsync(
+        string tenantId, SourceType sourceType, string? connectionId,
+        string query, string? path = null, bool recursive = true, string? mimeFilter = null,
+        string? pageToken = null, int pageSize = 50,
+        CancellationToken cancellationToken = default)
+    {
+        var request = new SearchConnectionFilesRequest
+        {
+            Context = new TenantContext { TenantId = tenantId },
+            Type = sourceType,
+            ConnectionId = connectionId ?? "",
+            Query = query,
+            Path = path ?? "",
+            Recursive = recursive,
+            MimeFilter = mimeFilter ?? "",
+            PageToken = pageToken ?? "",
+            PageSize = pageSize
+        };
+
+        var response = await GetSyncClient().SearchConnectionFilesAsync(request, cancellationToken: cancellationToken);
+        return ToFileListResult(response);
+    }
+
+    private static FileListResultDto ToFileListResult(ListConnectionFilesResponse response)
+    {
         var files = response.Files.Select(f => new FileEntryDto(
             f.Name,
             f.Path,
@@ -3,6 +3,7 @@ using System.Text;
 using System.Text.Json.Nodes;
 using DocumentFormat.OpenXml;
 using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
 
 namespace DocxMcp.Diff;
 
@@ -594,6 +595,26 @@ public sealed class ElementSnapshot
                 hasStyle = true;
             }
 
+            if (StyleHelper.HighlightName(rp.Highlight?.Val?.Value) is string highlight)
+            {
+                style["highlight"] = highlight;
+                hasStyle = true;
+            }
+
+            if (StyleHelper.VerticalAlignName(rp.VerticalTextAlignment?.Val?.Value) is string verticalAlign)
+            {
+                style["vertical_align"] = verticalAlign;
+                hasStyle = true;
+            }
+
+            if (rp.SmallCaps is not null) { style["small_caps"] = true; hasStyle = true; }
+
+            if (rp.Spacing?.Val?.Value is int spacing)
+            {
+                style["letter_spacing"] = spacing / 20.0;
+                hasStyle = true;
+            }
+
             if (hasStyle)
                 result["style"] = style;
         }
@@ -653,6 +674,26 @@ public sealed class ElementSnapshot
                 hasStyle = true;
             }
 
+            if (StyleHelper.HighlightName(rp.Highlight?.Val?.Value) is string highlight)
+            {
+                style["highlight"] = highlight;
+                hasStyle = true;
+            }
+
+            if (StyleHelper.VerticalAlignName(rp.VerticalTextAlignment?.Val?.Value) is string verticalAlign)
+            {
+                style["vertical_align"] = verticalAlign;
+                hasStyle = true;
+            }
+
+            if (rp.SmallCaps is not null) { style["small_caps"] = true; hasStyle = true; }
+
+            if (rp.Spacing?.Val?.Value is int spacing)
+            {
+                style["letter_spacing"] = spacing / 20.0;
+                hasStyle = true;
+            }
+
             if (hasStyle)
                 result["style"] = style;
         }
@@ -16,6 +16,9 @@ public sealed class DocxSession : IDisposable
     public WordprocessingDocument Document { get; }
     public string? SourcePath { get; private set; }
 
+    /// <summary>Element ID sequence of this session (see <see cref="DeterministicOutput"/>).</summary>
+    private readonly IDisposable _idSequence;
+
     /// <summary>
     /// Set the source path (used when setting "Save As" target for new documents).
     /// </summary>
@@ -24,12 +27,14 @@ public sealed class DocxSession : IDisposable
         SourcePath = path;
     }
 
-    private DocxSession(string id, WordprocessingDocument document, MemoryStream stream, string? sourcePath)
+    private DocxSession(string id, WordprocessingDocument document, MemoryStream stream, string? sourcePath,
+        IDisposable idSequence)
     {
         Id = id;
         Document = document;
         Stream = stream;
         SourcePath = sourcePath;
+        _idSequence = idSequence;
     }
 
     /// <summary>
@@ -45,13 +50,8 @@ public sealed class DocxSession : IDisposable
             throw new FileNotFoundException($"File not found: {absolutePath}");
 
         var bytes = File.ReadAllBytes(absolutePath);
-        var stream = new MemoryStream();
-        stream.Write(bytes);
-        stream.Position = 0;
-        var doc = WordprocessingDocument.Open(stream, isEditable: true);
-        ElementIdManager.EnsureNamespace(doc);
-        ElementIdManager.EnsureAllIds(doc);
-        return new DocxSession(Guid.NewGuid().ToString("N")[..12], doc, stream, absolutePath);
+        var (doc, stream, idSequence) = Load(bytes);
+        return new DocxSession(Guid.NewGuid().ToString("N")[..12], doc, stream, absolutePath, idSequence);
     }
 
     /// <summary>
@@ -59,13 +59,28 @@ public sealed class DocxSession : IDisposable
     /// </summary>
     public static DocxSession FromBytes(byte[] bytes, string id, string? sourcePath)
     {
-        var stream = new MemoryStream();
-        stream.Write(bytes);
-        stream.Position = 0;
-        var doc = WordprocessingDocument.Open(stream, isEditable: true);
-        ElementIdManager.EnsureNamespace(doc);
-        ElementIdManager.EnsureAllIds(doc);
-        return new DocxSession(id, doc, stream, sourcePath);
+        var (doc, stream, idSequence) = Load(bytes);
+        return new DocxSession(id, doc, stream, sourcePath, idSequence);
+    }
+
+    private static (WordprocessingDocument, MemoryStream, IDisposable) Load(byte[] bytes)
+    {
+        var idSequence = DeterministicOutput.BeginSequence(bytes);
+        try
+        {
+            var stream = new MemoryStream();
+            stream.Write(bytes);
+            stream.Position = 0;
+            var doc = WordprocessingDocument.Open(stream, isEditable: true);
+            ElementIdManager.EnsureNamespace(doc);
+            ElementIdManager.EnsureAllIds(doc);
+            return (doc, stream, idSequence);
+        }
+        catch
+        {
+            idSequence.Dispose();
+            throw;
+        }
     }
 
     /// <summary>
@@ -73,6 +88,7 @@ public sealed class DocxSession : IDisposable
     /// </summary>
     public static DocxSession Create()
     {
+        var idSequence = DeterministicOutput.BeginSequence([]);
         var stream = new MemoryStream();
         var doc = WordprocessingDocument.Create(stream, WordprocessingDocumentType.Document);
 
@@ -83,7 +99,7 @@ public sealed class DocxSession : IDisposable
 
         ElementIdManager.EnsureNamespace(doc);
         ElementIdManager.EnsureAllIds(doc);
-        return new DocxSession(Guid.NewGuid().ToString("N")[..12], doc, stream, sourcePath: null);
+        return new DocxSession(Guid.NewGuid().ToString("N")[..12], doc, stream, sourcePath: null, idSequence);
     }
 
     /// <summary>
@@ -94,18 +110,17 @@ public sealed class DocxSession : IDisposable
         var targetPath = path ?? SourcePath
             ?? throw new InvalidOperationException("No path specified and document was not opened from a file.");
 
-        Document.Save();
-        Stream.Position = 0;
-        File.WriteAllBytes(targetPath, Stream.ToArray());
+        File.WriteAllBytes(targetPath, ToBytes());
     }
 
     /// <summary>
-    /// Get the raw bytes of the document in its current state.
+    /// Get the raw bytes of the document in its current state
+    /// (normalized when <see cref="DeterministicOutput"/> is enabled).
     /// </summary>
     public byte[] ToBytes()
     {
         Document.Save();
-        return Stream.ToArray();
+        return DeterministicOutput.NormalizePackage(Stream.ToArray());
     }
 
     /// <summary>
@@ -121,5 +136,6 @@ public sealed class DocxSession : IDisposable
     {
         Document.Dispose();
         Stream.Dispose();
+        _idSequence.Dispose();
     }
 }
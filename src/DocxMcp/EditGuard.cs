@@ -0,0 +1,114 @@
+using System.Collections.Concurrent;
+using ModelContextProtocol;
+
+namespace DocxMcp;
+
+/// <summary>
+/// An edit refused by <see cref="EditGuard"/>. Limit is "edits_per_minute" or
+/// "growth_per_edit"; RetryAfter is set when waiting lets the edit through.
+/// </summary>
+public sealed class EditLimitExceededException(string limit, string message, TimeSpan? retryAfter = null)
+    : McpException(message)
+{
+    public string Limit { get; } = limit;
+    public TimeSpan? RetryAfter { get; } = retryAfter;
+}
+
+/// <summary>
+/// Stops runaway agent loops from thrashing a document: limits how many edits a session
+/// takes per minute, and how much a single edit may grow the document. Each tenant's
+/// SessionManager keeps its own guard, in memory; a restart starts the counts over.
+/// </summary>
+public sealed class EditGuard
+{
+    public const int DefaultMaxEditsPerMinute = 600;
+    public const long DefaultMaxGrowthBytes = 50L * 1024 * 1024;
+
+    private static readonly TimeSpan Window = TimeSpan.FromMinutes(1);
+
+    private readonly ConcurrentDictionary<string, SessionState> _sessions = new();
+    private readonly Func<DateTime> _clock;
+
+    /// <summary>Edits a session takes in any one minute (0 = no limit).</summary>
+    public int MaxEditsPerMinute { get; }
+
+    /// <summary>Bytes one edit may add to the document (0 = no limit).</summary>
+    public long MaxGrowthBytes { get; }
+
+    public EditGuard(int maxEditsPerMinute, long maxGrowthBytes, Func<DateTime>? clock = null)
+    {
+        MaxEditsPerMinute = Math.Max(0, maxEditsPerMinute);
+        MaxGrowthBytes = Math.Max(0, maxGrowthBytes);
+        _clock = clock ?? (() => DateTime.UtcNow);
+    }
+
+    /// <summary>
+    /// Limits from DOCX_MAX_EDITS_PER_MINUTE and DOCX_MAX_EDIT_GROWTH_BYTES.
+    /// </summary>
+    public static EditGuard FromEnvironment()
+    {
+        var edits = Environment.GetEnvironmentVariable("DOCX_MAX_EDITS_PER_MINUTE");
+        var growth = Environment.GetEnvironmentVariable("DOCX_MAX_EDIT_GROWTH_BYTES");
+        return new EditGuard(
+            int.TryParse(edits, out var e) && e >= 0 ? e : DefaultMaxEditsPerMinute,
+            long.TryParse(growth, out var g) && g >= 0 ? g : DefaultMaxGrowthBytes);
+    }
+
+    /// <summary>
+    /// Record the size of a session's document when it isn't known yet (a session opened,
+    /// or loaded for the first time since the server started).
+    /// </summary>
+    public void Observe(string sessionId, long sizeBytes)
+    {
+        var state = _sessions.GetOrAdd(sessionId, _ => new SessionState());
+        lock (state)
+            state.SizeBytes ??= sizeBytes;
+    }
+
+    /// <summary>
+    /// Admit an edit that leaves the document at <paramref name="newSizeBytes"/>, or throw
+    /// <see cref="EditLimitExceededException"/>. Refused edits aren't counted.
+    /// </summary>
+    public void Admit(string sessionId, long newSizeBytes)
+    {
+        var state = _sessions.GetOrAdd(sessionId, _ => new SessionState());
+        var now = _clock();
+        lock (state)
+        {
+            while (state.Edits.Count > 0 && now - state.Edits.Peek() >= Window)
+                state.Edits.Dequeue();
+
+            if (MaxEditsPerMinute > 0 && state.Edits.Count >= MaxEditsPerMinute)
+            {
+                var retryAfter = state.Edits.Peek() + Window - now;
+                throw new EditLimitExceededException("edits_per_minute",
+                    $"Edit rejected: session '{sessionId}' was edited {state.Edits.Count} times in the last minute " +
+                    $"(limit {MaxEditsPerMinute}). Retry in {Math.Ceiling(retryAfter.TotalSeconds)} s, " +
+                    "or batch the changes into one apply_patch call.",
+                    retryAfter);
+            }
+
+            var growth = newSizeBytes - (state.SizeBytes ?? newSizeBytes);
+            if (MaxGrowthBytes > 0 && growth > MaxGrowthBytes)
+            {
+                throw new EditLimitExceededException("growth_per_edit",
+                    $"Edit rejected: it grows session '{sessionId}' by {growth} bytes " +
+                    $"(from {state.SizeBytes} to {newSizeBytes}); one edit may add at most {MaxGrowthBytes} bytes.");
+            }
+
+            state.Edits.Enqueue(now);
+            state.SizeBytes = newSizeBytes;
+        }
+    }
+
+    /// <summary>
+    /// Drop what is tracked for a closed session.
+    /// </summary>
+    public void Forget(string sessionId) => _sessions.TryRemove(sessionId, out _);
+
+    private sealed class SessionState
+    {
+        public Queue<DateTime> Edits { get; } = new();
+        public long? SizeBytes { get; set; }
+    }
+}
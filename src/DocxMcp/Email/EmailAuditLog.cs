@@ -0,0 +1,82 @@
+using System.Text.Json.Nodes;
+using Microsoft.Extensions.Logging;
+
+namespace DocxMcp.Email;
+
+/// <summary>One email_document attempt, sent or not.</summary>
+public sealed record EmailAuditEntry(
+    DateTime Timestamp,
+    string TenantId,
+    string DocId,
+    IReadOnlyList<string> To,
+    string Subject,
+    string Format,
+    string FileName,
+    long Bytes,
+    string Sha256,
+    string Provider,
+    bool Sent,
+    string? MessageId,
+    string? Error);
+
+/// <summary>
+/// Records every email_document attempt in the server log and, when
+/// <see cref="EmailOptions.AuditLogPath"/> is set, as a JSON line in that file.
+/// The attachment itself isn't kept, only its size and SHA-256.
+/// </summary>
+public sealed class EmailAuditLog
+{
+    private readonly string? _path;
+    private readonly ILogger<EmailAuditLog> _logger;
+    private readonly Lock _lock = new();
+
+    public EmailAuditLog(EmailOptions options, ILogger<EmailAuditLog> logger)
+    {
+        _path = options.AuditLogPath;
+        _logger = logger;
+    }
+
+    public void Record(EmailAuditEntry entry)
+    {
+        if (entry.Sent)
+            _logger.LogInformation(
+                "email_document: tenant={TenantId} doc={DocId} to={To} format={Format} bytes={Bytes} provider={Provider} id={MessageId}",
+                entry.TenantId, entry.DocId, string.Join(",", entry.To), entry.Format, entry.Bytes, entry.Provider, entry.MessageId);
+        else
+            _logger.LogWarning(
+                "email_document failed: tenant={TenantId} doc={DocId} to={To} provider={Provider}: {Error}",
+                entry.TenantId, entry.DocId, string.Join(",", entry.To), entry.Provider, entry.Error);
+
+        if (_path is null)
+            return;
+
+        var line = new JsonObject
+        {
+            ["timestamp"] = entry.Timestamp.ToString("O"),
+            ["tenant_id"] = entry.TenantId,
+            ["doc_id"] = entry.DocId,
+            ["to"] = new JsonArray(entry.To.Select(t => (JsonNode)JsonValue.Create(t)!).ToArray()),
+            ["subject"] = entry.Subject,
+            ["format"] = entry.Format,
+            ["file_name"] = entry.FileName,
+            ["bytes"] = entry.Bytes,
+            ["sha256"] = entry.Sha256,
+            ["provider"] = entry.Provider,
+            ["sent"] = entry.Sent,
+            ["message_id"] = entry.MessageId,
+            ["error"] = entry.Error
+        }.ToJsonString();
+
+        lock (_lock)
+        {
+            try
+            {
+                File.AppendAllText(_path, line + "\n");
+            }
+            catch (IOException ex)
+            {
+                _logger.LogError(ex, "Could not write the email audit log to {Path}", _path);
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+using System.Text.Json.Nodes;
+
+namespace DocxMcp.Email;
+
+/// <summary>
+/// How one tenant's mail is sent by email_document.
+/// </summary>
+public sealed class EmailSettings
+{
+    /// <summary>"smtp" or "http".</summary>
+    public string Provider { get; set; } = "smtp";
+
+    /// <summary>Sender address, e.g. "Docs &lt;docs@example.com&gt;".</summary>
+    public string? From { get; set; }
+
+    public string? SmtpHost { get; set; }
+    public int SmtpPort { get; set; } = 587;
+    public string? SmtpUser { get; set; }
+    public string? SmtpPassword { get; set; }
+    public bool SmtpSsl { get; set; } = true;
+
+    /// <summary>Send endpoint of the "http" provider.</summary>
+    public string? Endpoint { get; set; }
+
+    /// <summary>Bearer token sent to the endpoint, if any.</summary>
+    public string? ApiKey { get; set; }
+
+    /// <summary>
+    /// Recipient domains mail may go to. Empty allows any domain.
+    /// </summary>
+    public List<string> AllowedDomains { get; set; } = [];
+
+    /// <summary>Largest attachment sent, in bytes.</summary>
+    public long MaxAttachmentBytes { get; set; } = 20L * 1024 * 1024;
+
+    public bool IsConfigured =>
+        From is not null && (Provider == "http" ? Endpoint is not null : SmtpHost is not null);
+
+    public bool AllowsRecipient(string address)
+    {
+        if (AllowedDomains.Count == 0)
+            return true;
+        var domain = address[(address.LastIndexOf('@') + 1)..];
+        return AllowedDomains.Any(d => string.Equals(d, domain, StringComparison.OrdinalIgnoreCase));
+    }
+
+    internal EmailSettings Clone() => new()
+    {
+        Provider = Provider,
+        From = From,
+        SmtpHost = SmtpHost,
+        SmtpPort = SmtpPort,
+        SmtpUser = SmtpUser,
+        SmtpPassword = SmtpPassword,
+        SmtpSsl = SmtpSsl,
+        Endpoint = Endpoint,
+        ApiKey = ApiKey,
+        AllowedDomains = [.. AllowedDomains],
+        MaxAttachmentBytes = MaxAttachmentBytes
+    };
+}
+
+/// <summary>
+/// Configuration for email_document: server-wide settings, per-tenant overrides
+/// and where sends are audited.
+/// </summary>
+public sealed class EmailOptions
+{
+    /// <summary>Settings of tenants without their own.</summary>
+    public EmailSettings Default { get; set; } = new();
+
+    /// <summary>Per-tenant settings, by tenant ID.</summary>
+    public Dictionary<string, EmailSettings> Tenants { get; set; } = [];
+
+    /// <summary>
+    /// JSON Lines file every send attempt is appended to. When unset, sends are
+    /// only audited in the server log.
+    /// </summary>
+    public string? AuditLogPath { get; set; }
+
+    public TimeSpan Timeout { get; set; } = TimeSpan.FromSeconds(60);
+
+    /// <summary>Settings for <paramref name="tenantId"/>, or null when it can't send mail.</summary>
+    public EmailSettings? ForTenant(string tenantId)
+    {
+        var settings = Tenants.GetValueOrDefault(tenantId) ?? Default;
+        return settings.IsConfigured ? settings : null;
+    }
+
+    /// <summary>
+    /// Read options from EMAIL_PROVIDER, EMAIL_FROM, EMAIL_SMTP_HOST, EMAIL_SMTP_PORT,
+    /// EMAIL_SMTP_USER, EMAIL_SMTP_PASSWORD, EMAIL_SMTP_SSL, EMAIL_ENDPOINT, EMAIL_API_KEY,
+    /// EMAIL_ALLOWED_DOMAINS (comma-separated), EMAIL_MAX_ATTACHMENT_BYTES,
+    /// EMAIL_TIMEOUT_SECONDS, EMAIL_AUDIT_LOG and EMAIL_TENANTS_FILE (see <see cref="ParseTenants"/>).
+    /// </summary>
+    public static EmailOptions FromEnvironment()
+    {
+        var options = new EmailOptions();
+        var settings = options.Default;
+
+        static string? Env(string name) =>
+            Environment.GetEnvironmentVariable(name) is { Length: > 0 } value ? value : null;
+
+        settings.Provider = Env("EMAIL_PROVIDER")?.ToLowerInvariant() ?? settings.Provider;
+        settings.From = Env("EMAIL_FROM");
+        settings.SmtpHost = Env("EMAIL_SMTP_HOST");
+        if (int.TryParse(Env("EMAIL_SMTP_PORT"), out var port) && port > 0)
+            settings.SmtpPort = port;
+        settings.SmtpUser = Env("EMAIL_SMTP_USER");
+        settings.SmtpPassword = Env("EMAIL_SMTP_PASSWORD");
+        if (Env("EMAIL_SMTP_SSL") is { } ssl)
+            settings.SmtpSsl = ssl is "1" || string.Equals(ssl, "true", StringComparison.OrdinalIgnoreCase);
+        settings.Endpoint = Env("EMAIL_ENDPOINT");
+        settings.ApiKey = Env("EMAIL_API_KEY");
+        if (Env("EMAIL_ALLOWED_DOMAINS") is { } domains)
+            settings.AllowedDomains = [.. domains.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)];
+        if (long.TryParse(Env("EMAIL_MAX_ATTACHMENT_BYTES"), out var maxBytes) && maxBytes > 0)
+            settings.MaxAttachmentBytes = maxBytes;
+
+        if (int.TryParse(Env("EMAIL_TIMEOUT_SECONDS"), out var timeout) && timeout > 0)
+            options.Timeout = TimeSpan.FromSeconds(timeout);
+        options.AuditLogPath = Env("EMAIL_AUDIT_LOG");
+
+        if (Env("EMAIL_TENANTS_FILE") is { } tenantsFile)
+            options.Tenants = ParseTenants(File.ReadAllText(tenantsFile), settings);
+
+        return options;
+    }
+
+    /// <summary>
+    /// Parse per-tenant settings: a JSON object keyed by tenant ID, e.g.
+    /// <c>{"acme": {"provider": "http", "from": "docs@acme.com", "endpoint": "...",
+    /// "api_key": "...", "allowed_domains": ["acme.com"]}}</c>. Fields a tenant
+    /// leaves out are taken from <paramref name="defaults"/>.
+    /// </summary>
+    public static Dictionary<string, EmailSettings> ParseTenants(string json, EmailSettings defaults)
+    {
+        var tenants = new Dictionary<string, EmailSettings>();
+        if (JsonNode.Parse(json) is not JsonObject root)
+            throw new FormatException("Email tenant settings must be a JSON object keyed by tenant ID.");
+
+        foreach (var (tenantId, node) in root)
+        {
+            if (node is not JsonObject obj)
+                throw new FormatException($"Email settings of tenant '{tenantId}' must be an object.");
+
+            var settings = defaults.Clone();
+            if (obj["provider"] is { } provider) settings.Provider = provider.GetValue<string>().ToLowerInvariant();
+            if (obj["from"] is { } from) settings.From = from.GetValue<string>();
+            if (obj["smtp_host"] is { } host) settings.SmtpHost = host.GetValue<string>();
+            if (obj["smtp_port"] is { } port) settings.SmtpPort = port.GetValue<int>();
+            if (obj["smtp_user"] is { } user) settings.SmtpUser = user.GetValue<string>();
+            if (obj["smtp_password"] is { } password) settings.SmtpPassword = password.GetValue<string>();
+            if (obj["smtp_ssl"] is { } ssl) settings.SmtpSsl = ssl.GetValue<bool>();
+            if (obj["endpoint"] is { } endpoint) settings.Endpoint = endpoint.GetValue<string>();
+            if (obj["api_key"] is { } apiKey) settings.ApiKey = apiKey.GetValue<string>();
+            if (obj["allowed_domains"] is JsonArray domains)
+                settings.AllowedDomains = [.. domains.Select(d => d!.GetValue<string>())];
+            if (obj["max_attachment_bytes"] is { } maxBytes) settings.MaxAttachmentBytes = maxBytes.GetValue<long>();
+            tenants[tenantId] = settings;
+        }
+        return tenants;
+    }
+}
@@ -0,0 +1,75 @@
+using System.Net.Http.Headers;
+using System.Text;
+using System.Text.Json.Nodes;
+
+namespace DocxMcp.Email;
+
+/// <summary>
+/// Mail through an HTTP API. The endpoint receives
+/// <c>{"from": ..., "to": [...], "subject": ..., "text": ...,
+/// "attachments": [{"filename": ..., "content_type": ..., "content": "&lt;base64&gt;"}]}</c>
+/// as a JSON POST (the shape Resend-style send APIs accept) and may answer
+/// <c>{"id": "..."}</c>.
+/// </summary>
+public sealed class HttpEmailSender : IEmailSender
+{
+    private static readonly HttpClient SharedClient = new();
+
+    private readonly EmailSettings _settings;
+    private readonly TimeSpan _timeout;
+    private readonly HttpClient _http;
+
+    public HttpEmailSender(EmailSettings settings, TimeSpan timeout, HttpClient? http = null)
+    {
+        _settings = settings;
+        _timeout = timeout;
+        _http = http ?? SharedClient;
+    }
+
+    public string Name => "http";
+
+    public async Task<string?> SendAsync(EmailMessage message, CancellationToken cancellationToken = default)
+    {
+        var payload = new JsonObject
+        {
+            ["from"] = message.From,
+            ["to"] = new JsonArray(message.To.Select(t => (JsonNode)JsonValue.Create(t)!).ToArray()),
+            ["subject"] = message.Subject,
+            ["text"] = message.Body,
+            ["attachments"] = new JsonArray(new JsonObject
+            {
+                ["filename"] = message.Attachment.FileName,
+                ["content_type"] = message.Attachment.ContentType,
+                ["content"] = Convert.ToBase64String(message.Attachment.Content)
+            })
+        };
+
+        using var request = new HttpRequestMessage(HttpMethod.Post, _settings.Endpoint)
+        {
+            Content = new StringContent(payload.ToJsonString(), Encoding.UTF8, "application/json")
+        };
+        if (_settings.ApiKey is not null)
+            request.Headers.Authorization = new AuthenticationHeaderValue("Bearer", _settings.ApiKey);
+
+        using var timeout = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        timeout.CancelAfter(_timeout);
+
+        using var response = await _http.SendAsync(request, timeout.Token);
+        var body = await response.Content.ReadAsStringAsync(timeout.Token);
+        if (!response.IsSuccessStatusCode)
+            throw new InvalidOperationException(
+                $"Email endpoint returned {(int)response.StatusCode}: {Truncate(body, 200)}");
+
+        try
+        {
+            return JsonNode.Parse(body)?["id"]?.ToString();
+        }
+        catch (System.Text.Json.JsonException)
+        {
+            return null;
+        }
+    }
+
+    private static string Truncate(string s, int maxLen) =>
+        s.Length <= maxLen ? s : s[..maxLen] + "...";
+}
@@ -0,0 +1,22 @@
+namespace DocxMcp.Email;
+
+public sealed record EmailAttachment(string FileName, string ContentType, byte[] Content);
+
+public sealed record EmailMessage(
+    string From,
+    IReadOnlyList<string> To,
+    string Subject,
+    string Body,
+    EmailAttachment Attachment);
+
+/// <summary>
+/// Extension point for outbound mail: delivers one message with its attachment.
+/// </summary>
+public interface IEmailSender
+{
+    /// <summary>Short name recorded in the audit log (e.g. "smtp", "http").</summary>
+    string Name { get; }
+
+    /// <summary>Send the message. Returns the provider's message ID when it reports one.</summary>
+    Task<string?> SendAsync(EmailMessage message, CancellationToken cancellationToken = default);
+}
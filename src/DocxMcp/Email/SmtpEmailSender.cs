@@ -0,0 +1,51 @@
+using System.Net;
+using System.Net.Mail;
+
+namespace DocxMcp.Email;
+
+/// <summary>
+/// Mail through an SMTP relay.
+/// </summary>
+public sealed class SmtpEmailSender : IEmailSender
+{
+    private readonly EmailSettings _settings;
+    private readonly TimeSpan _timeout;
+
+    public SmtpEmailSender(EmailSettings settings, TimeSpan timeout)
+    {
+        _settings = settings;
+        _timeout = timeout;
+    }
+
+    public string Name => "smtp";
+
+    public async Task<string?> SendAsync(EmailMessage message, CancellationToken cancellationToken = default)
+    {
+        using var mail = new MailMessage
+        {
+            From = new MailAddress(message.From),
+            Subject = message.Subject,
+            Body = message.Body,
+            IsBodyHtml = false
+        };
+        foreach (var to in message.To)
+            mail.To.Add(to);
+
+        var messageId = $"<{Guid.NewGuid():N}@{mail.From.Host}>";
+        mail.Headers.Add("Message-ID", messageId);
+
+        using var content = new MemoryStream(message.Attachment.Content);
+        mail.Attachments.Add(new Attachment(content, message.Attachment.FileName, message.Attachment.ContentType));
+
+        using var client = new SmtpClient(_settings.SmtpHost, _settings.SmtpPort)
+        {
+            EnableSsl = _settings.SmtpSsl,
+            Timeout = (int)_timeout.TotalMilliseconds
+        };
+        if (_settings.SmtpUser is not null)
+            client.Credentials = new NetworkCredential(_settings.SmtpUser, _settings.SmtpPassword);
+
+        await client.SendMailAsync(mail, cancellationToken);
+        return messageId;
+    }
+}
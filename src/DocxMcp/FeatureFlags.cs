@@ -0,0 +1,88 @@
+using ModelContextProtocol;
+
+namespace DocxMcp;
+
+/// <summary>
+/// Per-tenant feature flags gating new or risky capabilities.
+/// In HTTP mode the proxy resolves them from D1 and sends them in X-Feature-Flags
+/// ("pdf_export=1,tracked_changes=0"); flags it doesn't send fall back to
+/// DOCX_FEATURE_FLAGS (same syntax), then to the built-in defaults.
+/// </summary>
+public sealed class FeatureFlags
+{
+    public const string Header = "X-Feature-Flags";
+    public const string EnvironmentVariable = "DOCX_FEATURE_FLAGS";
+
+    public const string PdfExport = "pdf_export";
+    public const string TrackedChanges = "tracked_changes";
+    public const string Email = "email";
+
+    /// <summary>
+    /// Defaults of the known flags. Capabilities that already shipped stay on.
+    /// </summary>
+    private static readonly Dictionary<string, bool> Defaults = new()
+    {
+        [PdfExport] = true,
+        [TrackedChanges] = true,
+        [Email] = true,
+    };
+
+    private readonly Dictionary<string, bool> _values;
+    private readonly FeatureFlags? _fallback;
+
+    private FeatureFlags(Dictionary<string, bool> values, FeatureFlags? fallback)
+    {
+        _values = values;
+        _fallback = fallback;
+    }
+
+    /// <summary>Built-in defaults only.</summary>
+    public static FeatureFlags Default { get; } = new(new Dictionary<string, bool>(), null);
+
+    /// <summary>
+    /// Parse a flag list: comma-separated "name", "name=1" or "name=0" items
+    /// (also true/false, on/off). Unparseable items are ignored.
+    /// </summary>
+    public static FeatureFlags Parse(string? value, FeatureFlags? fallback = null)
+    {
+        var values = new Dictionary<string, bool>(StringComparer.OrdinalIgnoreCase);
+        foreach (var item in (value ?? "").Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            var separator = item.IndexOf('=');
+            var name = (separator < 0 ? item : item[..separator]).Trim();
+            if (name.Length == 0)
+                continue;
+            if (separator < 0)
+            {
+                values[name] = true;
+                continue;
+            }
+            switch (item[(separator + 1)..].Trim().ToLowerInvariant())
+            {
+                case "1" or "true" or "on": values[name] = true; break;
+                case "0" or "false" or "off": values[name] = false; break;
+            }
+        }
+        return new FeatureFlags(values, fallback ?? Default);
+    }
+
+    /// <summary>Flags from DOCX_FEATURE_FLAGS over the built-in defaults.</summary>
+    public static FeatureFlags FromEnvironment() =>
+        Parse(Environment.GetEnvironmentVariable(EnvironmentVariable));
+
+    public bool IsEnabled(string flag)
+    {
+        if (_values.TryGetValue(flag, out var enabled))
+            return enabled;
+        return _fallback?.IsEnabled(flag) ?? Defaults.GetValueOrDefault(flag);
+    }
+
+    /// <summary>
+    /// Throw an McpException when <paramref name="flag"/> is disabled.
+    /// </summary>
+    public void Require(string flag)
+    {
+        if (!IsEnabled(flag))
+            throw new McpException($"Feature '{flag}' is not enabled for this tenant.");
+    }
+}
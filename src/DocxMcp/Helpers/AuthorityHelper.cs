@@ -0,0 +1,334 @@
+using System.Globalization;
+using System.Text;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// An authority cited in the document: the long citation listed in the table, the
+/// short citation later marks use, its category, and the paragraphs citing it.
+/// </summary>
+public sealed record Authority(string LongCitation, string ShortCitation, int Category, List<Paragraph> Citing);
+
+/// <summary>
+/// One line of a table of authorities: the long citation and the pages citing it.
+/// </summary>
+public sealed record AuthorityEntry(string Citation, string Pages);
+
+/// <summary>
+/// Tables of authorities for briefs and other litigation documents.
+///
+/// Citations are marked with TA fields placed right after the cited text: the
+/// first mark of an authority carries its long citation, short citation and
+/// category (<c>TA \l "..." \s "..." \c 1</c>), later marks only the short
+/// citation. A table is one TOA field per category (<c>TOA \h \c "1" \p</c>).
+/// Word builds TOA results when fields are updated, so the field is written
+/// with a static result: the category heading and one entry per authority,
+/// with the pages estimated by <see cref="PageLayoutEstimator"/>, for viewers
+/// that never update fields.
+/// </summary>
+public static partial class AuthorityHelper
+{
+    public const string EntryStyle = "TableofAuthorities";
+    public const string HeadingStyle = "TOAHeading";
+
+    /// <summary>Pages from which an entry shows "passim" instead (Word's \p switch).</summary>
+    public const int PassimPages = 5;
+
+    /// <summary>Word's default categories, numbered from 1. Categories 8-16 have no name.</summary>
+    public static readonly string[] Categories =
+        ["Cases", "Statutes", "Other Authorities", "Rules", "Treatises", "Regulations", "Constitutional Provisions"];
+
+    private const int MaxCategory = 16;
+    private const int RightTab = 9360; // twips: 6.5in, the text width of a Letter page with 1in margins
+
+    [GeneratedRegex(@"^\s*TA\b", RegexOptions.IgnoreCase)]
+    private static partial Regex TaField();
+
+    [GeneratedRegex(@"\\(?<switch>[lsc])\s+(?:""(?<value>(?:[^""\\]|\\.)*)""|(?<value>\S+))", RegexOptions.IgnoreCase)]
+    private static partial Regex TaSwitch();
+
+    /// <summary>
+    /// Category number for a category name (e.g. "Cases") or number (1-16).
+    /// </summary>
+    public static int ParseCategory(string category)
+    {
+        if (int.TryParse(category, NumberStyles.None, CultureInfo.InvariantCulture, out var number)
+            && number is >= 1 and <= MaxCategory)
+            return number;
+
+        var index = Array.FindIndex(Categories, c => c.Equals(category.Trim(), StringComparison.OrdinalIgnoreCase));
+        if (index >= 0)
+            return index + 1;
+
+        throw new ArgumentException(
+            $"Unknown category '{category}'. Use one of: {string.Join(", ", Categories)}, or a number from 1 to {MaxCategory}.");
+    }
+
+    public static string CategoryName(int category) =>
+        category <= Categories.Length ? Categories[category - 1] : $"Category {category}";
+
+    /// <summary>
+    /// TA field instruction marking a citation. The first mark of an authority
+    /// carries its long citation and category; later marks of the same short
+    /// citation only refer to it.
+    /// </summary>
+    public static string MarkInstruction(Body body, string text, int category, string? longCitation, string? shortCitation)
+    {
+        var shortForm = shortCitation ?? text;
+        if (Collect(body).Any(a => a.ShortCitation == shortForm))
+            return $"TA \\s {Quote(shortForm)}";
+        return $"TA \\l {Quote(longCitation ?? text)} \\s {Quote(shortForm)} \\c {category}";
+    }
+
+    /// <summary>
+    /// Insert the TA field <paramref name="instruction"/> right after the first
+    /// occurrence of <paramref name="text"/> in the paragraph.
+    /// </summary>
+    public static void Mark(Paragraph paragraph, string text, string instruction)
+    {
+        var runs = CommentHelper.IsolateText(paragraph, text)
+            ?? throw new InvalidOperationException($"Text '{text}' not found in paragraph.");
+
+        OpenXmlElement anchor = runs[^1];
+        foreach (var run in new[]
+        {
+            FieldRun(new FieldChar { FieldCharType = FieldCharValues.Begin }),
+            FieldRun(new FieldCode($" {instruction} ") { Space = SpaceProcessingModeValues.Preserve }),
+            FieldRun(new FieldChar { FieldCharType = FieldCharValues.End })
+        })
+        {
+            paragraph.InsertAfter(run, anchor);
+            anchor = run;
+        }
+    }
+
+    /// <summary>
+    /// The authorities marked in the body, in the order of their first mark.
+    /// Short-only marks count for the authority with the same short citation.
+    /// </summary>
+    public static List<Authority> Collect(Body body)
+    {
+        var authorities = new List<Authority>();
+        var shortOnly = new List<(string Short, Paragraph Paragraph)>();
+
+        foreach (var (instruction, element) in FieldInstructions(body))
+        {
+            if (!TaField().IsMatch(instruction)) continue;
+            var paragraph = element as Paragraph ?? element.Ancestors<Paragraph>().FirstOrDefault();
+            if (paragraph is null) continue;
+
+            string? longCitation = null, shortCitation = null;
+            var category = 1;
+            foreach (Match m in TaSwitch().Matches(instruction))
+            {
+                var value = Regex.Replace(m.Groups["value"].Value, @"\\(.)", "$1");
+                switch (char.ToLowerInvariant(m.Groups["switch"].Value[0]))
+                {
+                    case 'l': longCitation = value; break;
+                    case 's': shortCitation = value; break;
+                    case 'c':
+                        if (int.TryParse(value, NumberStyles.None, CultureInfo.InvariantCulture, out var c)
+                            && c is >= 1 and <= MaxCategory)
+                            category = c;
+                        break;
+                }
+            }
+
+            if (longCitation is null)
+            {
+                if (shortCitation is not null)
+                    shortOnly.Add((shortCitation, paragraph));
+                continue;
+            }
+
+            var shortForm = shortCitation ?? longCitation;
+            var existing = authorities.FirstOrDefault(a => a.ShortCitation == shortForm);
+            if (existing is not null)
+                existing.Citing.Add(paragraph);
+            else
+                authorities.Add(new Authority(longCitation, shortForm, category, [paragraph]));
+        }
+
+        foreach (var (shortForm, paragraph) in shortOnly)
+            authorities.FirstOrDefault(a => a.ShortCitation == shortForm)?.Citing.Add(paragraph);
+        return authorities;
+    }
+
+    /// <summary>
+    /// Entries of the table per category, in category order, authorities sorted
+    /// alphabetically. Pages are estimated; five pages or more show "passim".
+    /// </summary>
+    public static SortedDictionary<int, List<AuthorityEntry>> BuildEntries(
+        WordprocessingDocument doc, IReadOnlySet<int>? categories)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var pageOf = PageLookup(doc, body);
+
+        var entries = new SortedDictionary<int, List<AuthorityEntry>>();
+        foreach (var group in Collect(body)
+                     .Where(a => categories is null || categories.Contains(a.Category))
+                     .GroupBy(a => a.Category))
+        {
+            entries[group.Key] = group
+                .OrderBy(a => a.LongCitation, StringComparer.OrdinalIgnoreCase)
+                .Select(a =>
+                {
+                    var pages = a.Citing.Select(pageOf).OfType<int>().Distinct().Order().ToList();
+                    return new AuthorityEntry(a.LongCitation, pages.Count >= PassimPages
+                        ? "passim"
+                        : string.Join(", ", pages.Select(p => p.ToString(CultureInfo.InvariantCulture))));
+                })
+                .ToList();
+        }
+        return entries;
+    }
+
+    /// <summary>
+    /// The paragraphs of a table of authorities: per category, a heading paragraph
+    /// opening its TOA field and one paragraph per entry, the last one closing it.
+    /// </summary>
+    public static List<Paragraph> CreateTable(MainDocumentPart mainPart, SortedDictionary<int, List<AuthorityEntry>> entries)
+    {
+        EnsureStyles(mainPart);
+
+        var paragraphs = new List<Paragraph>();
+        foreach (var (category, items) in entries)
+        {
+            if (items.Count == 0) continue;
+
+            var heading = new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = HeadingStyle }),
+                FieldRun(new FieldChar { FieldCharType = FieldCharValues.Begin }),
+                FieldRun(new FieldCode($" TOA \\h \\c \"{category}\" \\p ") { Space = SpaceProcessingModeValues.Preserve }),
+                FieldRun(new FieldChar { FieldCharType = FieldCharValues.Separate }),
+                FieldRun(new Text(CategoryName(category))));
+            ElementIdManager.AssignId(heading);
+            paragraphs.Add(heading);
+
+            Paragraph? entry = null;
+            foreach (var item in items)
+            {
+                entry = new Paragraph(
+                    new ParagraphProperties(new ParagraphStyleId { Val = EntryStyle }),
+                    FieldRun(new Text(item.Citation) { Space = SpaceProcessingModeValues.Preserve }),
+                    FieldRun(new TabChar()),
+                    FieldRun(new Text(item.Pages)));
+                ElementIdManager.AssignId(entry);
+                paragraphs.Add(entry);
+            }
+            entry!.AppendChild(FieldRun(new FieldChar { FieldCharType = FieldCharValues.End }));
+        }
+        return paragraphs;
+    }
+
+    private static string Quote(string value) => $"\"{value.Replace("\\", "\\\\").Replace("\"", "\\\"")}\"";
+
+    private static Run FieldRun(OpenXmlElement content)
+    {
+        var run = new Run(content);
+        ElementIdManager.AssignId(run);
+        return run;
+    }
+
+    /// <summary>
+    /// Instructions of the complex and simple fields under <paramref name="root"/>,
+    /// with the run or field holding each, in document order.
+    /// </summary>
+    private static IEnumerable<(string Instruction, OpenXmlElement Element)> FieldInstructions(OpenXmlElement root)
+    {
+        var open = new Stack<(StringBuilder? Code, Run Begin)>();
+        foreach (var element in root.Descendants())
+        {
+            switch (element)
+            {
+                case SimpleField simple when simple.Instruction?.Value is { } instruction:
+                    yield return (instruction, simple);
+                    break;
+                case FieldChar fieldChar when fieldChar.Parent is Run run:
+                    var type = fieldChar.FieldCharType?.Value;
+                    if (type == FieldCharValues.Begin)
+                    {
+                        open.Push((new StringBuilder(), run));
+                    }
+                    else if (open.Count > 0 && (type == FieldCharValues.Separate || type == FieldCharValues.End))
+                    {
+                        var (code, begin) = open.Pop();
+                        if (code is not null)
+                            yield return (code.ToString(), begin);
+                        if (type == FieldCharValues.Separate)
+                            open.Push((null, begin)); // the result, until the end
+                    }
+                    break;
+                case FieldCode fieldCode when open.Count > 0:
+                    open.Peek().Code?.Append(fieldCode.Text);
+                    break;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Estimated page of a paragraph: the first page its top-level body element is on.
+    /// </summary>
+    private static Func<Paragraph, int?> PageLookup(WordprocessingDocument doc, Body body)
+    {
+        var index = new Dictionary<string, int>();
+        var children = body.ChildElements.ToList();
+        for (var i = 0; i < children.Count; i++)
+        {
+            if (PageLayoutEstimator.PathOf(children[i]) is { } path)
+                index.TryAdd(path, i);
+        }
+
+        var spans = PageLayoutEstimator.Estimate(doc).Ranges
+            .Where(r => r.First is not null && r.Last is not null
+                        && index.ContainsKey(r.First) && index.ContainsKey(r.Last))
+            .Select(r => (r.Page, First: index[r.First!], Last: index[r.Last!]))
+            .ToList();
+
+        return paragraph =>
+        {
+            OpenXmlElement top = paragraph;
+            while (top.Parent is not null and not Body)
+                top = top.Parent;
+            var i = children.IndexOf(top);
+            return spans.Where(s => s.First <= i && i <= s.Last).Select(s => (int?)s.Page).FirstOrDefault();
+        };
+    }
+
+    private static void EnsureStyles(MainDocumentPart mainPart)
+    {
+        var stylesPart = mainPart.StyleDefinitionsPart
+            ?? mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles ??= new Styles();
+        var styles = stylesPart.Styles;
+
+        void Add(string id, Func<Style> create)
+        {
+            if (!styles.Elements<Style>().Any(s => s.StyleId?.Value == id))
+                styles.AppendChild(create());
+        }
+
+        // Word's built-in names, so Word uses them when it rebuilds the table
+        Add(EntryStyle, () => new Style(
+            new StyleName { Val = "table of authorities" },
+            new BasedOn { Val = "Normal" },
+            new NextParagraphStyle { Val = "Normal" },
+            new StyleParagraphProperties(
+                new Tabs(new TabStop { Val = TabStopValues.Right, Leader = TabStopLeaderCharValues.Dot, Position = RightTab }),
+                new Indentation { Left = "220", Hanging = "220" }))
+        { Type = StyleValues.Paragraph, StyleId = EntryStyle });
+
+        Add(HeadingStyle, () => new Style(
+            new StyleName { Val = "toa heading" },
+            new BasedOn { Val = "Normal" },
+            new NextParagraphStyle { Val = "Normal" },
+            new StyleParagraphProperties(new SpacingBetweenLines { Before = "120" }),
+            new StyleRunProperties(new Bold()))
+        { Type = StyleValues.Paragraph, StyleId = HeadingStyle });
+    }
+}
@@ -0,0 +1,139 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Right-to-left support shared by element creation and style merging.
+///
+/// Word reads jc/tblJc "left" and "right" as the leading and trailing edge of
+/// right-to-left paragraphs and tables, so on those the visual values are
+/// mirrored when written: "right" aligns an Arabic paragraph to the right
+/// margin. "start" and "end" follow the text direction.
+///
+/// Right-to-left runs are shaped with the complex-script properties (bCs, iCs,
+/// szCs, rFonts/@cs), which are kept in step with their Latin counterparts.
+/// </summary>
+public static class BidiHelper
+{
+    private static readonly HashSet<string> RtlLanguages = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku-Arab", "pa-Arab", "ps", "sd", "syr", "ug", "ur", "yi"
+    };
+
+    /// <summary>
+    /// Whether a BCP 47 language tag (e.g. "ar-SA", "he") is written right to left.
+    /// </summary>
+    public static bool IsRtlLanguage(string language)
+    {
+        if (RtlLanguages.Contains(language))
+            return true;
+        var parts = language.Split('-');
+        return RtlLanguages.Contains(parts[0])
+            || (parts.Length > 1 && RtlLanguages.Contains($"{parts[0]}-{parts[1]}"))
+            || parts.Contains("Arab", StringComparer.OrdinalIgnoreCase)
+            || parts.Contains("Hebr", StringComparer.OrdinalIgnoreCase);
+    }
+
+    /// <summary>
+    /// Paragraph justification for an alignment name, mirrored for bidi paragraphs.
+    /// </summary>
+    public static JustificationValues Justification(string? alignment, bool bidi) =>
+        alignment?.ToLowerInvariant() switch
+        {
+            "center" => JustificationValues.Center,
+            "justify" or "both" => JustificationValues.Both,
+            "distribute" => JustificationValues.Distribute,
+            "start" => JustificationValues.Left,
+            "end" => JustificationValues.Right,
+            "right" => bidi ? JustificationValues.Left : JustificationValues.Right,
+            "left" => bidi ? JustificationValues.Right : JustificationValues.Left,
+            _ => JustificationValues.Left
+        };
+
+    /// <summary>
+    /// Table alignment for an alignment name, mirrored for right-to-left tables.
+    /// </summary>
+    public static TableRowAlignmentValues TableAlignment(string? alignment, bool rtl) =>
+        alignment?.ToLowerInvariant() switch
+        {
+            "center" => TableRowAlignmentValues.Center,
+            "start" => TableRowAlignmentValues.Left,
+            "end" => TableRowAlignmentValues.Right,
+            "right" => rtl ? TableRowAlignmentValues.Left : TableRowAlignmentValues.Right,
+            "left" => rtl ? TableRowAlignmentValues.Right : TableRowAlignmentValues.Left,
+            _ => TableRowAlignmentValues.Left
+        };
+
+    /// <summary>
+    /// Set a run's language: w:lang/@w:bidi and the rtl flag for right-to-left
+    /// languages, w:lang/@w:val otherwise.
+    /// </summary>
+    public static void SetLanguage(RunProperties props, string language)
+    {
+        var languages = props.Languages ?? new Languages();
+        if (IsRtlLanguage(language))
+        {
+            languages.Bidi = language;
+            props.Languages = languages;
+            SetRtl(props, true);
+        }
+        else
+        {
+            languages.Val = language;
+            props.Languages = languages;
+            SetRtl(props, false);
+        }
+    }
+
+    /// <summary>
+    /// Give the paragraph's runs the paragraph's language: all of them with
+    /// <paramref name="overwrite"/>, otherwise those that have none of their own.
+    /// </summary>
+    public static void ApplyLanguage(Paragraph paragraph, string language, bool overwrite = false)
+    {
+        foreach (var run in paragraph.Descendants<Run>())
+        {
+            var props = run.RunProperties ?? run.PrependChild(new RunProperties());
+            if (overwrite || props.Languages is null)
+                SetLanguage(props, language);
+        }
+    }
+
+    /// <summary>
+    /// Set or clear a run's rtl flag, syncing the complex-script properties.
+    /// </summary>
+    public static void SetRtl(RunProperties props, bool rtl)
+    {
+        props.RightToLeftText = rtl ? new RightToLeftText() : null;
+        SyncComplexScript(props);
+    }
+
+    /// <summary>
+    /// Mirror bold, italic, size and font onto the complex-script properties of
+    /// a right-to-left run, which Word uses to render it.
+    /// </summary>
+    public static void SyncComplexScript(RunProperties props)
+    {
+        if (props.RightToLeftText is null) return;
+
+        props.BoldComplexScript = props.Bold is not null ? new BoldComplexScript() : null;
+        props.ItalicComplexScript = props.Italic is not null ? new ItalicComplexScript() : null;
+        props.FontSizeComplexScript = props.FontSize?.Val?.Value is string size
+            ? new FontSizeComplexScript { Val = size }
+            : null;
+        if (props.RunFonts?.Ascii?.Value is string font)
+            props.RunFonts.ComplexScript = font;
+    }
+
+    /// <summary>
+    /// Whether a paragraph is laid out right to left.
+    /// </summary>
+    public static bool IsBidi(ParagraphProperties? props) =>
+        props?.BiDi is { } bidi && (bidi.Val is null || bidi.Val.Value);
+
+    /// <summary>
+    /// Whether a table is laid out right to left.
+    /// </summary>
+    public static bool IsRtl(TableProperties? props) =>
+        props?.BiDiVisual is { } bidi && (bidi.Val is null || bidi.Val.Value);
+}
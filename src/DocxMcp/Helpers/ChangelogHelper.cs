@@ -0,0 +1,258 @@
+using System.Text;
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Paths;
+using DocxMcp.Persistence;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// One line of a changelog: what was done to which kind of element, in which section.
+/// Named changes ("added section 'Pricing'") aren't counted.
+/// </summary>
+public sealed record ChangelogItem(string Verb, string Noun, string? Section, bool Named = false);
+
+/// <summary>
+/// Turns WAL entries into a readable changelog ("Added section 'Pricing', edited 3 paragraphs
+/// in 'Terms'"), for commit-style messages and notifications. Patch paths are resolved against
+/// the document after the changes, or before them for removed elements, to name the section
+/// (the heading above) each change falls in.
+/// </summary>
+public static class ChangelogHelper
+{
+    /// <summary>Changes spelled out on the summary line before "and N more".</summary>
+    private const int SummaryItems = 3;
+
+    /// <summary>
+    /// Changes made by <paramref name="entries"/>, counted and in order of first appearance.
+    /// </summary>
+    public static List<(ChangelogItem Item, int Count)> Collect(
+        IEnumerable<WalEntry> entries, WordprocessingDocument before, WordprocessingDocument after)
+    {
+        var counts = new Dictionary<ChangelogItem, int>();
+        var order = new List<ChangelogItem>();
+        void Add(ChangelogItem item, int count = 1)
+        {
+            if (counts.TryAdd(item, count))
+                order.Add(item);
+            else
+                counts[item] += count;
+        }
+
+        foreach (var entry in entries)
+        {
+            if (entry.EntryType is WalEntryType.ExternalSync or WalEntryType.Import)
+            {
+                var summary = entry.SyncMeta?.Summary;
+                Add(new ChangelogItem(
+                    "synced",
+                    summary is null
+                        ? "external changes"
+                        : $"external changes (+{summary.Added} -{summary.Removed} ~{summary.Modified})",
+                    null, Named: true));
+                continue;
+            }
+
+            JsonDocument patches;
+            try { patches = JsonDocument.Parse(entry.Patches); }
+            catch (JsonException) { continue; }
+            using (patches)
+            {
+                if (patches.RootElement.ValueKind != JsonValueKind.Array)
+                    continue;
+                foreach (var patch in patches.RootElement.EnumerateArray())
+                {
+                    foreach (var (item, count) in Describe(patch, before, after))
+                        Add(item, count);
+                }
+            }
+        }
+
+        return order.Select(item => (item, counts[item])).ToList();
+    }
+
+    /// <summary>
+    /// A summary line, then one bullet per change. Empty when there are no changes.
+    /// </summary>
+    public static string Format(IReadOnlyList<(ChangelogItem Item, int Count)> changes)
+    {
+        if (changes.Count == 0)
+            return "";
+
+        var phrases = changes.Select(c => Phrase(c.Item, c.Count)).ToList();
+        var summary = string.Join(", ", phrases.Take(SummaryItems));
+        if (phrases.Count > SummaryItems)
+            summary += $" and {phrases.Count - SummaryItems} more change{(phrases.Count - SummaryItems == 1 ? "" : "s")}";
+
+        var sb = new StringBuilder(Capitalize(summary)).AppendLine().AppendLine();
+        foreach (var phrase in phrases)
+            sb.Append("- ").AppendLine(Capitalize(phrase));
+        return sb.ToString().TrimEnd();
+    }
+
+    private static string Phrase(ChangelogItem item, int count)
+    {
+        var what = item.Named
+            ? count > 1 ? $"{item.Noun} ({count}×)" : item.Noun
+            : $"{count} {(count == 1 ? item.Noun : Plural(item.Noun))}";
+        return item.Section is null
+            ? $"{item.Verb} {what}"
+            : $"{item.Verb} {what} in '{item.Section}'";
+    }
+
+    private static IEnumerable<(ChangelogItem Item, int Count)> Describe(
+        JsonElement patch, WordprocessingDocument before, WordprocessingDocument after)
+    {
+        var op = patch.TryGetProperty("op", out var opEl) ? opEl.GetString() : null;
+        var path = patch.TryGetProperty("path", out var pathEl) && pathEl.ValueKind == JsonValueKind.String
+            ? pathEl.GetString()
+            : null;
+        if (op is null)
+            yield break;
+
+        switch (op)
+        {
+            case "add":
+                var type = patch.TryGetProperty("value", out var value) && value.ValueKind == JsonValueKind.Object
+                    && value.TryGetProperty("type", out var typeEl) ? typeEl.GetString() : null;
+                if (type == "heading")
+                {
+                    yield return (new ChangelogItem("added", $"section '{ValueText(value)}'", null, Named: true), 1);
+                    break;
+                }
+                var section = path is null ? null : SectionOf(InsertedAt(path, after));
+                yield return type == "table"
+                    ? (new ChangelogItem("inserted", "table", section), 1)
+                    : (new ChangelogItem("added", (type ?? "element").Replace('_', ' '), section), 1);
+                break;
+
+            case "replace" or "replace_text" or "remove_column":
+                foreach (var (noun, where, count) in Targets(path, after, before))
+                    yield return (new ChangelogItem("edited", noun, where), count);
+                break;
+
+            case "remove":
+                foreach (var (noun, where, count) in Targets(path, before, after))
+                    yield return (new ChangelogItem("removed", noun, where), count);
+                break;
+
+            case "move" or "copy":
+                var from = patch.TryGetProperty("from", out var fromEl) ? fromEl.GetString() : null;
+                foreach (var (noun, where, count) in Targets(from, before, after))
+                    yield return (new ChangelogItem(op == "move" ? "moved" : "copied", noun, where), count);
+                break;
+
+            case "style_element" or "style_paragraph" or "style_table":
+                if (path is null)
+                {
+                    yield return (new ChangelogItem("restyled", "the whole document", null, Named: true), 1);
+                    break;
+                }
+                foreach (var (noun, where, count) in Targets(path, after, before))
+                    yield return (new ChangelogItem("restyled", noun, where), count);
+                break;
+
+            case "add_comment":
+                yield return (new ChangelogItem("added", "comment", SectionOf(First(path, after))), 1);
+                break;
+
+            case "delete_comment":
+                yield return (new ChangelogItem("deleted", "comment", null), 1);
+                break;
+
+            case "accept_revision" or "reject_revision":
+                yield return (new ChangelogItem(op == "accept_revision" ? "accepted" : "rejected", "revision", null), 1);
+                break;
+
+            default:
+                yield return (new ChangelogItem("applied", op.Replace('_', ' '), null, Named: true), 1);
+                break;
+        }
+    }
+
+    /// <summary>
+    /// Elements <paramref name="path"/> resolves to in <paramref name="doc"/> (or in
+    /// <paramref name="fallback"/> when it resolves to none), counted by kind and section.
+    /// </summary>
+    private static IEnumerable<(string Noun, string? Section, int Count)> Targets(
+        string? path, WordprocessingDocument doc, WordprocessingDocument fallback)
+    {
+        var elements = Resolve(path, doc);
+        if (elements.Count == 0)
+            elements = Resolve(path, fallback);
+        if (elements.Count == 0)
+            return [("element", null, 1)];
+        return elements
+            .GroupBy(e => (Noun: KindOf(e), Section: SectionOf(e)))
+            .Select(g => (g.Key.Noun, g.Key.Section, g.Count()));
+    }
+
+    private static List<OpenXmlElement> Resolve(string? path, WordprocessingDocument doc)
+    {
+        if (path is null)
+            return [];
+        try
+        {
+            var parsed = DocxPath.Parse(path);
+            return parsed.IsChildrenPath ? [] : PathResolver.Resolve(parsed, doc);
+        }
+        catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+        {
+            return [];
+        }
+    }
+
+    private static OpenXmlElement? First(string? path, WordprocessingDocument doc) =>
+        Resolve(path, doc).FirstOrDefault();
+
+    /// <summary>The element now at the insert position <paramref name="path"/>.</summary>
+    private static OpenXmlElement? InsertedAt(string path, WordprocessingDocument doc)
+    {
+        try
+        {
+            var (parent, index) = PathResolver.ResolveForInsert(DocxPath.Parse(path), doc);
+            var children = parent.ChildElements;
+            return children.Count == 0 ? parent : children[Math.Clamp(index, 0, children.Count - 1)];
+        }
+        catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+        {
+            return null;
+        }
+    }
+
+    private static string KindOf(OpenXmlElement element) => element switch
+    {
+        Paragraph p when p.IsHeading() => "heading",
+        Paragraph => "paragraph",
+        Table => "table",
+        TableRow => "table row",
+        TableCell => "table cell",
+        _ when element.GetFirstChild<Paragraph>() is null && element.Ancestors<Paragraph>().Any() => "paragraph",
+        _ => element.LocalName
+    };
+
+    private static string? SectionOf(OpenXmlElement? element) =>
+        HeadingHelper.HeadingAbove(element)?.InnerText.Trim();
+
+    private static string ValueText(JsonElement value)
+    {
+        if (value.TryGetProperty("text", out var text) && text.ValueKind == JsonValueKind.String)
+            return text.GetString() ?? "";
+        if (value.TryGetProperty("runs", out var runs) && runs.ValueKind == JsonValueKind.Array)
+        {
+            return string.Concat(runs.EnumerateArray()
+                .Select(r => r.ValueKind == JsonValueKind.Object && r.TryGetProperty("text", out var t)
+                    ? t.GetString()
+                    : null));
+        }
+        return "";
+    }
+
+    private static string Plural(string noun) =>
+        noun.EndsWith('y') ? noun[..^1] + "ies" : noun + "s";
+
+    private static string Capitalize(string s) =>
+        s.Length == 0 ? s : char.ToUpperInvariant(s[0]) + s[1..];
+}
@@ -0,0 +1,345 @@
+using System.Globalization;
+using System.Xml.Linq;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.CustomProperties;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.VariantTypes;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A sensitivity label found in a document. <see cref="Sources"/> says where:
+/// "custom_properties" (MSIP_Label_* properties) and/or "label_info" (the
+/// docMetadata/LabelInfo.xml part Office writes when labels support co-authoring).
+/// </summary>
+public sealed record SensitivityLabel(
+    string Id,
+    string? Name,
+    bool Enabled,
+    string? Method,
+    string? SetDate,
+    string? SiteId,
+    string? ActionId,
+    int? ContentBits,
+    List<string> Sources);
+
+/// <summary>
+/// A label as applied by set_classification, with everything replay needs.
+/// </summary>
+public sealed record AppliedLabel(
+    PolicyLabel Label,
+    string Method,
+    DateTime SetDate,
+    string ActionId);
+
+/// <summary>
+/// Reads and writes Microsoft Purview (AIP) sensitivity labels: the
+/// MSIP_Label_&lt;id&gt;_* custom document properties and, when the document has
+/// one, the LabelInfo part. Applying a label also adds its content markings —
+/// header and footer text and a diagonal watermark — in content controls tagged
+/// so the next label change can find and replace them.
+/// </summary>
+public static class ClassificationHelper
+{
+    public const string HeaderTag = "ClassificationHeader";
+    public const string FooterTag = "ClassificationFooter";
+    public const string WatermarkTag = "ClassificationWatermark";
+
+    private const string PropertyPrefix = "MSIP_Label_";
+    private const string CustomPropertyFormatId = "{D5CDD505-2E9C-101B-9397-08002B2CF9AE}";
+    private const string LabelInfoRelationshipType =
+        "http://schemas.microsoft.com/office/2020/02/relationships/classificationlabels";
+    private static readonly XNamespace Clbl = "http://schemas.microsoft.com/office/2020/mipLabelMetadata";
+
+    private static readonly string[] Tags = [HeaderTag, FooterTag, WatermarkTag];
+
+    /// <summary>Labels of the document, from its custom properties and LabelInfo part.</summary>
+    public static List<SensitivityLabel> Read(WordprocessingDocument doc)
+    {
+        var labels = new Dictionary<string, SensitivityLabel>(StringComparer.OrdinalIgnoreCase);
+
+        var byId = new Dictionary<string, Dictionary<string, string>>(StringComparer.OrdinalIgnoreCase);
+        foreach (var property in CustomProperties(doc))
+        {
+            var name = property.Name?.Value;
+            if (name is null || !name.StartsWith(PropertyPrefix, StringComparison.OrdinalIgnoreCase))
+                continue;
+            var rest = name[PropertyPrefix.Length..];
+            var separator = rest.LastIndexOf('_');
+            if (separator <= 0)
+                continue;
+            var id = rest[..separator];
+            if (!byId.TryGetValue(id, out var values))
+                byId[id] = values = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+            values[rest[(separator + 1)..]] = property.InnerText;
+        }
+        foreach (var (id, values) in byId)
+        {
+            labels[id] = new SensitivityLabel(
+                id,
+                values.GetValueOrDefault("Name"),
+                string.Equals(values.GetValueOrDefault("Enabled"), "true", StringComparison.OrdinalIgnoreCase),
+                values.GetValueOrDefault("Method"),
+                values.GetValueOrDefault("SetDate"),
+                values.GetValueOrDefault("SiteId"),
+                values.GetValueOrDefault("ActionId"),
+                int.TryParse(values.GetValueOrDefault("ContentBits"), out var bits) ? bits : null,
+                ["custom_properties"]);
+        }
+
+        if (LabelInfoPart(doc) is { } part)
+        {
+            using var stream = part.GetStream();
+            foreach (var element in XDocument.Load(stream).Root?.Elements(Clbl + "label") ?? Enumerable.Empty<XElement>())
+            {
+                if ((string?)element.Attribute("removed") == "1" || (string?)element.Attribute("id") is not { } raw)
+                    continue;
+                var id = raw.Trim('{', '}');
+                var bits = int.TryParse((string?)element.Attribute("contentBits"), out var b) ? b : (int?)null;
+                if (labels.TryGetValue(id, out var existing))
+                {
+                    existing.Sources.Add("label_info");
+                    continue;
+                }
+                labels[id] = new SensitivityLabel(
+                    id, null, (string?)element.Attribute("enabled") == "1", (string?)element.Attribute("method"),
+                    null, ((string?)element.Attribute("siteId"))?.Trim('{', '}'), null, bits, ["label_info"]);
+            }
+        }
+
+        return labels.Values.ToList();
+    }
+
+    /// <summary>
+    /// Replace the document's labels and markings with <paramref name="applied"/>,
+    /// or remove them all when it is null.
+    /// </summary>
+    public static void Apply(WordprocessingDocument doc, AppliedLabel? applied)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+
+        WriteProperties(doc, applied);
+        WriteLabelInfo(doc, applied);
+
+        RemoveMarkings(mainPart);
+        if (applied is not null)
+            AddMarkings(mainPart, applied.Label);
+    }
+
+    private static IEnumerable<CustomDocumentProperty> CustomProperties(WordprocessingDocument doc) =>
+        doc.CustomFilePropertiesPart?.Properties?.Elements<CustomDocumentProperty>() ?? Enumerable.Empty<CustomDocumentProperty>();
+
+    private static void WriteProperties(WordprocessingDocument doc, AppliedLabel? applied)
+    {
+        if (applied is null && doc.CustomFilePropertiesPart is null)
+            return;
+
+        var part = doc.CustomFilePropertiesPart ?? doc.AddCustomFilePropertiesPart();
+        part.Properties ??= new Properties();
+        var properties = part.Properties;
+
+        // A document carries one label: drop every MSIP_Label_* property first
+        foreach (var old in properties.Elements<CustomDocumentProperty>()
+                     .Where(p => p.Name?.Value?.StartsWith(PropertyPrefix, StringComparison.OrdinalIgnoreCase) == true)
+                     .ToList())
+            old.Remove();
+
+        if (applied is not null)
+        {
+            var label = applied.Label;
+            var prefix = $"{PropertyPrefix}{label.Id}_";
+            var values = new List<(string, string)>
+            {
+                ("Enabled", "true"),
+                ("SetDate", applied.SetDate.ToUniversalTime().ToString("yyyy-MM-dd'T'HH:mm:ss'Z'", CultureInfo.InvariantCulture)),
+                ("Method", applied.Method),
+                ("Name", label.Name),
+                ("SiteId", label.SiteId ?? ""),
+                ("ActionId", applied.ActionId),
+                ("ContentBits", label.ContentBits.ToString(CultureInfo.InvariantCulture)),
+            };
+            var pid = properties.Elements<CustomDocumentProperty>().Select(p => p.PropertyId?.Value ?? 1).DefaultIfEmpty(1).Max();
+            foreach (var (key, value) in values)
+            {
+                properties.Append(new CustomDocumentProperty(new VTLPWSTR(value))
+                {
+                    FormatId = CustomPropertyFormatId,
+                    PropertyId = ++pid,
+                    Name = prefix + key
+                });
+            }
+        }
+        properties.Save();
+    }
+
+    private static OpenXmlPart? LabelInfoPart(WordprocessingDocument doc) =>
+        doc.Parts.Select(p => p.OpenXmlPart).FirstOrDefault(p => p.RelationshipType == LabelInfoRelationshipType);
+
+    /// <summary>
+    /// Rewrite an existing LabelInfo part. Documents without one keep the label in
+    /// custom properties only, which every labeling client reads.
+    /// </summary>
+    private static void WriteLabelInfo(WordprocessingDocument doc, AppliedLabel? applied)
+    {
+        if (LabelInfoPart(doc) is not { } part)
+            return;
+
+        var list = new XElement(Clbl + "labelList", new XAttribute(XNamespace.Xmlns + "clbl", Clbl));
+        if (applied is not null)
+        {
+            var label = applied.Label;
+            list.Add(new XElement(Clbl + "label",
+                new XAttribute("id", $"{{{label.Id}}}"),
+                new XAttribute("enabled", "1"),
+                new XAttribute("method", applied.Method),
+                new XAttribute("siteId", $"{{{label.SiteId ?? Guid.Empty.ToString()}}}"),
+                new XAttribute("contentBits", label.ContentBits),
+                new XAttribute("removed", "0")));
+        }
+
+        using var stream = new MemoryStream();
+        new XDocument(new XDeclaration("1.0", "utf-8", "yes"), list).Save(stream);
+        stream.Position = 0;
+        part.FeedData(stream);
+    }
+
+    private static void RemoveMarkings(MainDocumentPart mainPart)
+    {
+        var roots = mainPart.HeaderParts.Select(h => (OpenXmlElement?)h.Header)
+            .Concat(mainPart.FooterParts.Select(f => (OpenXmlElement?)f.Footer));
+        foreach (var root in roots)
+        {
+            if (root is null)
+                continue;
+            foreach (var sdt in root.Descendants<SdtBlock>()
+                         .Where(s => s.SdtProperties?.GetFirstChild<Tag>()?.Val?.Value is { } tag && Tags.Contains(tag))
+                         .ToList())
+                sdt.Remove();
+            // A header or footer must keep at least one paragraph
+            if (!root.Elements<Paragraph>().Any() && !root.Elements<Table>().Any())
+                root.AppendChild(new Paragraph());
+        }
+    }
+
+    private static void AddMarkings(MainDocumentPart mainPart, PolicyLabel label)
+    {
+        if (label.Header is null && label.Footer is null && label.Watermark is null)
+            return;
+
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        if (label.Header is not null || label.Watermark is not null)
+        {
+            foreach (var header in HeaderFooterParts(mainPart, body, isHeader: true).Cast<HeaderPart>())
+            {
+                var root = header.Header!;
+                if (label.Watermark is not null)
+                    root.PrependChild(Marking(WatermarkTag, new Paragraph(new Run(Watermark(label.Watermark)))));
+                if (label.Header is not null)
+                    root.PrependChild(Marking(HeaderTag, MarkingParagraph(label.Header, label.Color)));
+            }
+        }
+        if (label.Footer is not null)
+        {
+            foreach (var footer in HeaderFooterParts(mainPart, body, isHeader: false).Cast<FooterPart>())
+                footer.Footer!.AppendChild(Marking(FooterTag, MarkingParagraph(label.Footer, label.Color)));
+        }
+    }
+
+    /// <summary>
+    /// Every header (or footer) part a section shows, after giving sections that
+    /// show none a default one.
+    /// </summary>
+    private static List<OpenXmlPart> HeaderFooterParts(MainDocumentPart mainPart, Body body, bool isHeader)
+    {
+        var sections = body.Descendants<SectionProperties>().ToList();
+        if (sections.Count == 0)
+        {
+            var last = new SectionProperties();
+            body.AppendChild(last);
+            sections.Add(last);
+        }
+
+        string? inherited = null;
+        var ids = new List<string>();
+        foreach (var sectPr in sections)
+        {
+            var references = isHeader
+                ? sectPr.Elements<HeaderReference>().Select(r => (r.Type?.Value ?? HeaderFooterValues.Default, r.Id?.Value))
+                : sectPr.Elements<FooterReference>().Select(r => (r.Type?.Value ?? HeaderFooterValues.Default, r.Id?.Value));
+            var list = references.ToList();
+            var defaultId = list.FirstOrDefault(r => r.Item1 == HeaderFooterValues.Default).Item2 ?? inherited;
+            if (defaultId is null)
+            {
+                defaultId = DeterministicOutput.NextRelationshipId(mainPart);
+                if (isHeader)
+                {
+                    mainPart.AddNewPart<HeaderPart>(defaultId).Header = new Header(new Paragraph());
+                    sectPr.PrependChild(new HeaderReference { Type = HeaderFooterValues.Default, Id = defaultId });
+                }
+                else
+                {
+                    mainPart.AddNewPart<FooterPart>(defaultId).Footer = new Footer(new Paragraph());
+                    var after = sectPr.Elements<HeaderReference>().LastOrDefault();
+                    var reference = new FooterReference { Type = HeaderFooterValues.Default, Id = defaultId };
+                    if (after is not null)
+                        sectPr.InsertAfter(reference, after);
+                    else
+                        sectPr.PrependChild(reference);
+                }
+            }
+            inherited = defaultId;
+            ids.Add(defaultId);
+            ids.AddRange(list.Select(r => r.Item2).OfType<string>());
+        }
+
+        return ids.Distinct()
+            .Select(id => mainPart.TryGetPartById(id, out var part) ? part : null)
+            .OfType<OpenXmlPart>()
+            .Where(p => isHeader ? p is HeaderPart { Header: not null } : p is FooterPart { Footer: not null })
+            .ToList();
+    }
+
+    private static SdtBlock Marking(string tag, Paragraph paragraph) => new(
+        new SdtProperties(new SdtAlias { Val = "Classification" }, new Tag { Val = tag }),
+        new SdtContentBlock(paragraph));
+
+    private static Paragraph MarkingParagraph(string text, string? color)
+    {
+        var runProperties = new RunProperties(new FontSize { Val = "20" });
+        if (color is not null)
+            runProperties.PrependChild(new Color { Val = color });
+        return new Paragraph(
+            new ParagraphProperties(new Justification { Val = JustificationValues.Center }),
+            new Run(runProperties, new Text(text) { Space = SpaceProcessingModeValues.Preserve }));
+    }
+
+    /// <summary>Word's diagonal text watermark (a VML WordArt shape behind the text).</summary>
+    private static Picture Watermark(string text)
+    {
+        var escaped = System.Security.SecurityElement.Escape(text);
+        return new Picture(
+            "<w:pict xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" " +
+            "xmlns:v=\"urn:schemas-microsoft-com:vml\" xmlns:o=\"urn:schemas-microsoft-com:office:office\">" +
+            "<v:shapetype id=\"_x0000_t136\" coordsize=\"21600,21600\" o:spt=\"136\" adj=\"10800\" " +
+            "path=\"m@7,l@8,m@5,21600l@6,21600e\"><v:formulas>" +
+            "<v:f eqn=\"sum #0 0 10800\"/><v:f eqn=\"prod #0 2 1\"/><v:f eqn=\"sum 21600 0 @1\"/>" +
+            "<v:f eqn=\"sum 0 0 @2\"/><v:f eqn=\"sum 21600 0 @3\"/><v:f eqn=\"if @0 @3 0\"/>" +
+            "<v:f eqn=\"if @0 21600 @1\"/><v:f eqn=\"if @0 0 @2\"/><v:f eqn=\"if @0 @4 21600\"/>" +
+            "<v:f eqn=\"mid @5 @6\"/><v:f eqn=\"mid @8 @5\"/><v:f eqn=\"mid @7 @8\"/><v:f eqn=\"mid @6 @7\"/>" +
+            "<v:f eqn=\"sum @6 0 @5\"/></v:formulas>" +
+            "<v:path textpathok=\"t\" o:connecttype=\"custom\" o:connectlocs=\"@9,0;@10,10800;@11,21600;@12,10800\" " +
+            "o:connectangles=\"270,180,90,0\"/><v:textpath on=\"t\" fitshape=\"t\"/>" +
+            "<o:lock v:ext=\"edit\" text=\"t\" shapetype=\"t\"/></v:shapetype>" +
+            "<v:shape id=\"ClassificationWatermark\" o:spid=\"_x0000_s1025\" type=\"#_x0000_t136\" " +
+            "style=\"position:absolute;margin-left:0;margin-top:0;width:468pt;height:117pt;rotation:315;" +
+            "z-index:-251657216;mso-position-horizontal:center;mso-position-horizontal-relative:margin;" +
+            "mso-position-vertical:center;mso-position-vertical-relative:margin\" o:allowincell=\"f\" " +
+            "fillcolor=\"silver\" stroked=\"f\"><v:fill opacity=\".5\"/>" +
+            $"<v:textpath style=\"font-family:&quot;Calibri&quot;;font-size:1pt\" string=\"{escaped}\"/>" +
+            "</v:shape></w:pict>");
+    }
+}
@@ -0,0 +1,87 @@
+using System.Text.Json.Nodes;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A sensitivity label of the policy and the markings it applies. Header, footer
+/// and watermark texts are optional; a label without any only sets metadata.
+/// </summary>
+public sealed record PolicyLabel(
+    string Name,
+    string Id,
+    string? SiteId = null,
+    string? Header = null,
+    string? Footer = null,
+    string? Watermark = null,
+    string? Color = null)
+{
+    /// <summary>MSIP content bits: 1 header, 2 footer, 4 watermark.</summary>
+    public int ContentBits =>
+        (Header is not null ? 1 : 0) | (Footer is not null ? 2 : 0) | (Watermark is not null ? 4 : 0);
+}
+
+/// <summary>
+/// Sensitivity labels set_classification can apply. Tenants using Microsoft Purview
+/// should configure their own labels (CLASSIFICATION_POLICY_FILE) so the label and
+/// site IDs match what Office and DLP tooling expect; the built-in labels only carry
+/// local IDs.
+/// </summary>
+public sealed class ClassificationPolicy
+{
+    public List<PolicyLabel> Labels { get; set; } =
+    [
+        new("Public", "2f4b3c34-6f1a-4c2e-9d0a-1a7e0b5f0001"),
+        new("General", "2f4b3c34-6f1a-4c2e-9d0a-1a7e0b5f0002", Footer: "General"),
+        new("Confidential", "2f4b3c34-6f1a-4c2e-9d0a-1a7e0b5f0003",
+            Header: "CONFIDENTIAL", Footer: "Confidential", Color: "C00000"),
+        new("Highly Confidential", "2f4b3c34-6f1a-4c2e-9d0a-1a7e0b5f0004",
+            Header: "HIGHLY CONFIDENTIAL", Footer: "Highly Confidential", Watermark: "HIGHLY CONFIDENTIAL",
+            Color: "C00000"),
+    ];
+
+    /// <summary>The label with this name or ID (case-insensitive), or null.</summary>
+    public PolicyLabel? Find(string nameOrId) =>
+        Labels.FirstOrDefault(l => string.Equals(l.Name, nameOrId, StringComparison.OrdinalIgnoreCase)
+                                   || string.Equals(l.Id, nameOrId.Trim('{', '}'), StringComparison.OrdinalIgnoreCase));
+
+    /// <summary>
+    /// Read the labels from the JSON file named by CLASSIFICATION_POLICY_FILE, or
+    /// keep the built-in ones when it isn't set.
+    /// </summary>
+    public static ClassificationPolicy FromEnvironment()
+    {
+        var path = Environment.GetEnvironmentVariable("CLASSIFICATION_POLICY_FILE");
+        return string.IsNullOrEmpty(path) ? new ClassificationPolicy() : Parse(File.ReadAllText(path));
+    }
+
+    /// <summary>
+    /// Parse <c>{"labels": [{"name": "Confidential", "id": "&lt;guid&gt;", "site_id": "&lt;guid&gt;",
+    /// "header": "...", "footer": "...", "watermark": "...", "color": "C00000"}]}</c>.
+    /// </summary>
+    public static ClassificationPolicy Parse(string json)
+    {
+        var labels = JsonNode.Parse(json)?["labels"] as JsonArray
+            ?? throw new FormatException("The classification policy must have a 'labels' array.");
+
+        var policy = new ClassificationPolicy { Labels = [] };
+        foreach (var node in labels)
+        {
+            var name = node?["name"]?.GetValue<string>()
+                ?? throw new FormatException("Every classification label needs a 'name'.");
+            var id = node["id"]?.GetValue<string>()
+                ?? throw new FormatException($"Classification label '{name}' needs an 'id'.");
+            if (!Guid.TryParse(id, out var guid))
+                throw new FormatException($"The id of classification label '{name}' is not a GUID.");
+
+            policy.Labels.Add(new PolicyLabel(
+                name,
+                guid.ToString(),
+                node["site_id"]?.GetValue<string>(),
+                node["header"]?.GetValue<string>(),
+                node["footer"]?.GetValue<string>(),
+                node["watermark"]?.GetValue<string>(),
+                node["color"]?.GetValue<string>()));
+        }
+        return policy;
+    }
+}
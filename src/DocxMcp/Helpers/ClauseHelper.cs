@@ -0,0 +1,367 @@
+using System.Text;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A numbered clause: its number (e.g. "4.2"), title, and paragraph path.
+/// Manual clauses carry their number as typed text; the others use list numbering.
+/// </summary>
+public sealed record Clause(string Number, string Title, string? Path, bool Manual);
+
+/// <summary>
+/// A problem with clause numbering or a cross-reference.
+/// Kinds: dangling_reference, title_mismatch, broken_field, stale_field,
+/// duplicate_number, numbering_gap.
+/// </summary>
+public sealed record ClauseFinding(string Kind, string? Path, string Reference, string? Target, string Message);
+
+/// <summary>
+/// Result of a clause reference check.
+/// </summary>
+public sealed record ClauseReport(List<Clause> Clauses, int References, List<ClauseFinding> Findings);
+
+/// <summary>
+/// Checks internal references of contract-style documents. Clauses are
+/// paragraphs numbered by list numbering ("1.", "4.2", "Article 3") or whose
+/// text starts with a typed number ("4.2 Termination", "Section 5").
+/// References are "Section 4.2"-style mentions in the text and REF fields
+/// pointing at bookmarks inside clauses.
+/// </summary>
+public static class ClauseHelper
+{
+    private const string Num = @"\d{1,3}(?:\.\d{1,3})*";
+
+    // "4.2 Termination", "Section 5. Fees", "§ 3 Term", "1. Definitions"
+    private static readonly Regex ManualNumber = new(
+        $@"^\s*(?:(?<kw>Section|Article|Clause)\s+|(?<kw>§)\s*)?(?<num>{Num})(?<dot>\.)?(?:\s+|$)",
+        RegexOptions.IgnoreCase);
+
+    // "Section 4.2", "Sections 3.1 and 3.2", "clause 7(b)", "§§ 2-4", "Section 4.2 (\"Termination\")"
+    private static readonly Regex Reference = new(
+        $@"(?<![\w.])(?<kw>Sections?|Clauses?|Articles?|§§?)\s*" +
+        $@"(?<nums>{Num}(?:\([a-z0-9]+\))*(?:\s*(?:,|&|-|–|and|or|to|through)\s*{Num}(?:\([a-z0-9]+\))*)*)" +
+        @"(?:\s*\(?\s*[""“](?<title>[^""”]+)[""”]\s*\)?)?",
+        RegexOptions.IgnoreCase);
+
+    private static readonly Regex ReferenceNumber = new(Num);
+
+    private static readonly Regex RefField = new(@"^\s*REF\s+(?<bookmark>[^\s\\]+)(?<switches>.*)$", RegexOptions.IgnoreCase);
+
+    /// <summary>
+    /// Collect clauses and check every reference and the numbering itself.
+    /// </summary>
+    public static ClauseReport Check(WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var numbering = new ListNumbering(mainPart);
+        var clauses = new List<Clause>();
+        var clauseByParagraph = new Dictionary<Paragraph, Clause>();
+
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var text = VisibleText(paragraph);
+            var path = TerminologyHelper.PathOf(paragraph, body);
+
+            Clause? clause = null;
+            if (numbering.Next(paragraph) is string listNumber)
+            {
+                clause = new Clause(listNumber, TitleOf(text), path, false);
+            }
+            else if (ManualNumber.Match(text) is { Success: true } m
+                     && (m.Groups["kw"].Success || m.Groups["dot"].Success || m.Groups["num"].Value.Contains('.')
+                         || paragraph.IsHeading()))
+            {
+                clause = new Clause(m.Groups["num"].Value, TitleOf(text[m.Length..]), path, true);
+            }
+
+            if (clause is not null)
+            {
+                clauses.Add(clause);
+                clauseByParagraph[paragraph] = clause;
+            }
+        }
+
+        var findings = new List<ClauseFinding>();
+        var byNumber = clauses.GroupBy(c => c.Number).ToDictionary(g => g.Key, g => g.ToList());
+
+        foreach (var (number, same) in byNumber)
+        {
+            foreach (var duplicate in same.Skip(1))
+            {
+                findings.Add(new ClauseFinding("duplicate_number", duplicate.Path, number, same[0].Path,
+                    $"Clause number {number} is used {same.Count} times; references to it are ambiguous."));
+            }
+        }
+        findings.AddRange(CheckSequence(clauses.Where(c => c.Manual)));
+
+        int references = 0;
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var path = TerminologyHelper.PathOf(paragraph, body);
+            var text = VisibleText(paragraph);
+            clauseByParagraph.TryGetValue(paragraph, out var own);
+
+            foreach (Match m in Reference.Matches(text))
+            {
+                // "Section 5. Fees" numbering its own clause is not a reference
+                if (own is { Manual: true } && text[..m.Index].Trim().Length == 0) continue;
+
+                var numbers = ReferenceNumber.Matches(m.Groups["nums"].Value).Select(n => n.Value).ToList();
+                foreach (var number in numbers)
+                {
+                    references++;
+                    if (!byNumber.TryGetValue(number, out var targets))
+                    {
+                        findings.Add(new ClauseFinding("dangling_reference", path, m.Value.Trim(), null,
+                            $"'{m.Value.Trim()}' refers to clause {number}, which does not exist."));
+                        continue;
+                    }
+
+                    var title = m.Groups["title"];
+                    if (title.Success && numbers.Count == 1 && !ReferenceEquals(targets[0], own)
+                        && !SameTitle(title.Value, targets[0].Title))
+                    {
+                        findings.Add(new ClauseFinding("title_mismatch", path, m.Value.Trim(), targets[0].Path,
+                            $"'{m.Value.Trim()}' names \"{title.Value}\" but clause {number} is \"{targets[0].Title}\"."));
+                    }
+                }
+            }
+
+            foreach (var field in Fields(paragraph))
+            {
+                var rf = RefField.Match(field.Instruction);
+                if (!rf.Success) continue;
+                references++;
+
+                var bookmark = rf.Groups["bookmark"].Value;
+                var display = $"REF {bookmark}";
+                var start = body.Descendants<BookmarkStart>().FirstOrDefault(b => b.Name?.Value == bookmark);
+                if (start is null)
+                {
+                    findings.Add(new ClauseFinding("broken_field", path, display, null,
+                        $"Cross-reference field points to bookmark '{bookmark}', which no longer exists."));
+                    continue;
+                }
+
+                // \r, \n and \w display the paragraph number of the bookmark
+                var switches = rf.Groups["switches"].Value;
+                if (!Regex.IsMatch(switches, @"\\[rnw]\b", RegexOptions.IgnoreCase)) continue;
+
+                var target = start.Ancestors<Paragraph>().FirstOrDefault()
+                    ?? start.ElementsAfter().OfType<Paragraph>().FirstOrDefault();
+                if (target is null || !clauseByParagraph.TryGetValue(target, out var targetClause))
+                {
+                    findings.Add(new ClauseFinding("broken_field", path, display, null,
+                        $"Cross-reference field points to bookmark '{bookmark}', which is not in a numbered clause."));
+                    continue;
+                }
+
+                var shown = ReferenceNumber.Match(field.Result).Value;
+                if (shown.Length > 0 && shown != targetClause.Number)
+                {
+                    findings.Add(new ClauseFinding("stale_field", path, display, targetClause.Path,
+                        $"Cross-reference shows '{field.Result.Trim()}' but its clause is now {targetClause.Number}; update fields."));
+                }
+            }
+        }
+
+        return new ClauseReport(clauses, references, findings);
+    }
+
+    /// <summary>
+    /// Manual numbers must count up one at a time under the same parent:
+    /// 3.1 followed by 3.3 means 3.2 is missing.
+    /// </summary>
+    private static IEnumerable<ClauseFinding> CheckSequence(IEnumerable<Clause> clauses)
+    {
+        var last = new Dictionary<string, int>();
+        foreach (var clause in clauses)
+        {
+            var parts = clause.Number.Split('.');
+            var parent = string.Join('.', parts[..^1]);
+            var value = int.Parse(parts[^1]);
+
+            var expected = last.GetValueOrDefault(parent) + 1;
+            if (value > expected)
+            {
+                var missing = parent.Length > 0 ? $"{parent}.{expected}" : expected.ToString();
+                yield return new ClauseFinding("numbering_gap", clause.Path, clause.Number, null,
+                    $"Clause {clause.Number} is out of sequence; {missing} is missing.");
+            }
+            last[parent] = value;
+
+            // Sub-clauses restart under each clause
+            foreach (var key in last.Keys.Where(k => k == clause.Number || k.StartsWith(clause.Number + ".")).ToList())
+                last.Remove(key);
+        }
+    }
+
+    private static bool SameTitle(string a, string b)
+    {
+        static string Norm(string s) => Regex.Replace(s.ToLowerInvariant(), @"[^\w]+", " ").Trim();
+        var (x, y) = (Norm(a), Norm(b));
+        return x.Length > 0 && y.Length > 0 && (x.Contains(y) || y.Contains(x));
+    }
+
+    /// <summary>
+    /// The clause title: its text up to the first sentence break, at most 100 characters.
+    /// </summary>
+    private static string TitleOf(string text)
+    {
+        text = text.Trim();
+        var end = Regex.Match(text, @"[.:;](\s|$)");
+        if (end.Success) text = text[..end.Index];
+        return text.Length > 100 ? text[..100].TrimEnd() + "…" : text;
+    }
+
+    /// <summary>
+    /// Displayed text of a paragraph, without field instructions or deleted text.
+    /// </summary>
+    private static string VisibleText(Paragraph paragraph) =>
+        string.Concat(paragraph.Descendants<Text>().Select(t => t.Text));
+
+    private sealed record Field(string Instruction, string Result);
+
+    /// <summary>
+    /// Simple and complex fields of a paragraph with their cached result text.
+    /// </summary>
+    private static List<Field> Fields(Paragraph paragraph)
+    {
+        var fields = new List<Field>();
+        foreach (var simple in paragraph.Descendants<SimpleField>())
+            fields.Add(new Field(simple.Instruction?.Value ?? "", simple.InnerText));
+
+        // Complex fields: begin, instruction, separate, result, end — possibly nested
+        var stack = new Stack<(StringBuilder Instruction, StringBuilder Result, bool InResult)>();
+        foreach (var element in paragraph.Descendants())
+        {
+            switch (element)
+            {
+                case FieldChar fc when fc.FieldCharType?.InnerText == "begin":
+                    stack.Push((new StringBuilder(), new StringBuilder(), false));
+                    break;
+                case FieldChar fc when fc.FieldCharType?.InnerText == "separate" && stack.Count > 0:
+                    var top = stack.Pop();
+                    stack.Push((top.Instruction, top.Result, true));
+                    break;
+                case FieldChar fc when fc.FieldCharType?.InnerText == "end" && stack.Count > 0:
+                    var done = stack.Pop();
+                    fields.Add(new Field(done.Instruction.ToString(), done.Result.ToString()));
+                    break;
+                case FieldCode code when stack.Count > 0 && !stack.Peek().InResult:
+                    stack.Peek().Instruction.Append(code.Text);
+                    break;
+                case Text t when stack.Count > 0 && stack.Peek().InResult:
+                    stack.Peek().Result.Append(t.Text);
+                    break;
+            }
+        }
+        return fields;
+    }
+
+    /// <summary>
+    /// Computes list numbers the way Word displays them, for decimal, letter
+    /// and roman formats. Counters are kept per abstract numbering definition.
+    /// </summary>
+    private sealed class ListNumbering(MainDocumentPart mainPart)
+    {
+        private readonly Numbering? _numbering = mainPart.NumberingDefinitionsPart?.Numbering;
+        private readonly Styles? _styles = mainPart.StyleDefinitionsPart?.Styles;
+        private readonly Dictionary<int, int?[]> _counters = new();
+
+        public string? Next(Paragraph paragraph)
+        {
+            if (_numbering is null) return null;
+
+            var (numId, ilvl) = Resolve(paragraph);
+            if (numId is null or 0) return null;
+
+            var abstractId = _numbering.Elements<NumberingInstance>()
+                .FirstOrDefault(n => n.NumberID?.Value == numId)?.AbstractNumId?.Val?.Value;
+            if (abstractId is null) return null;
+
+            var abstractNum = _numbering.Elements<AbstractNum>()
+                .FirstOrDefault(a => a.AbstractNumberId?.Value == abstractId);
+            if (abstractNum is null) return null;
+
+            Level? LevelOf(int i) => abstractNum.Elements<Level>().FirstOrDefault(l => l.LevelIndex?.Value == i);
+
+            if (ilvl is < 0 or > 8) return null;
+            var level = LevelOf(ilvl);
+            var format = level?.NumberingFormat?.Val?.InnerText ?? "decimal";
+            if (level is null || format is "bullet" or "none") return null;
+
+            if (!_counters.TryGetValue(abstractId.Value, out var counters))
+                _counters[abstractId.Value] = counters = new int?[9];
+
+            counters[ilvl] = counters[ilvl] is int current ? current + 1 : level.StartNumberingValue?.Val?.Value ?? 1;
+            for (int i = ilvl + 1; i < counters.Length; i++)
+                counters[i] = null;
+
+            var text = Regex.Replace(level.LevelText?.Val?.Value ?? $"%{ilvl + 1}.", @"%(\d)", m =>
+            {
+                var i = m.Groups[1].Value[0] - '1';
+                if (i is < 0 or > 8) return "";
+                var other = LevelOf(i);
+                var value = counters[i] ?? other?.StartNumberingValue?.Val?.Value ?? 1;
+                return FormatNumber(value, other?.NumberingFormat?.Val?.InnerText ?? "decimal");
+            });
+
+            // "Article 3." -> "3", "(a)" -> "a", "4.2." -> "4.2"
+            var number = Regex.Match(text, @"[\dA-Za-z]+(?:\.[\dA-Za-z]+)*(?=\W*$)").Value;
+            return number.Length > 0 ? number : null;
+        }
+
+        private (int? NumId, int Level) Resolve(Paragraph paragraph)
+        {
+            var numPr = paragraph.ParagraphProperties?.NumberingProperties;
+            var styleId = paragraph.ParagraphProperties?.ParagraphStyleId?.Val?.Value;
+
+            // Paragraph numbering wins; otherwise follow the style's basedOn chain
+            for (int depth = 0; numPr?.NumberingId is null && styleId is not null && depth < 10; depth++)
+            {
+                var style = _styles?.Elements<Style>().FirstOrDefault(s => s.StyleId?.Value == styleId);
+                numPr = style?.StyleParagraphProperties?.NumberingProperties ?? numPr;
+                styleId = style?.BasedOn?.Val?.Value;
+            }
+
+            return (numPr?.NumberingId?.Val?.Value, numPr?.NumberingLevelReference?.Val?.Value ?? 0);
+        }
+
+        private static string FormatNumber(int value, string format) => format switch
+        {
+            "lowerLetter" => Letters(value).ToLowerInvariant(),
+            "upperLetter" => Letters(value),
+            "lowerRoman" => Roman(value).ToLowerInvariant(),
+            "upperRoman" => Roman(value),
+            "decimalZero" => value.ToString("00"),
+            _ => value.ToString()
+        };
+
+        private static string Letters(int value)
+        {
+            // Word repeats the letter: a..z, aa..zz
+            var letter = (char)('A' + (value - 1) % 26);
+            return new string(letter, (value - 1) / 26 + 1);
+        }
+
+        private static string Roman(int value)
+        {
+            var sb = new StringBuilder();
+            foreach (var (n, s) in new[] { (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+                         (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I") })
+            {
+                while (value >= n) { sb.Append(s); value -= n; }
+            }
+            return sb.ToString();
+        }
+    }
+}
@@ -0,0 +1,92 @@
+using System.Text;
+using System.Text.RegularExpressions;
+using System.Web;
+using DocxMcp.Grpc;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A file in a cloud backend, recognized from a link a user copied from their
+/// browser or a sharing dialog. <see cref="FileId"/> is what the backend
+/// downloads and syncs: the Drive file ID for Google links, and the Microsoft
+/// Graph sharing ID ("u!" and the link in base64url) for OneDrive and
+/// SharePoint links, which the backend resolves to the drive item.
+/// </summary>
+public sealed record CloudLink(SourceType Type, string FileId, string Provider, bool IsGoogleDoc)
+{
+    private static readonly Regex DriveId = new(@"^[-\w]{10,}$", RegexOptions.Compiled);
+
+    /// <summary>
+    /// Recognize a Google Docs, Google Drive, OneDrive or SharePoint link.
+    /// Throws <see cref="ArgumentException"/> for anything else.
+    /// </summary>
+    public static CloudLink Parse(string url)
+    {
+        if (!Uri.TryCreate(url.Trim(), UriKind.Absolute, out var uri) || uri.Scheme != Uri.UriSchemeHttps)
+            throw new ArgumentException($"'{url}' is not an HTTPS link.");
+
+        var host = uri.Host.ToLowerInvariant();
+        var segments = uri.AbsolutePath.Split('/', StringSplitOptions.RemoveEmptyEntries);
+
+        if (host == "docs.google.com")
+        {
+            var kind = segments.FirstOrDefault();
+            if (kind is "spreadsheets" or "presentation" or "forms" or "drawings")
+                throw new ArgumentException($"This link is a Google {GoogleKind(kind)}, not a document.");
+            if (kind != "document")
+                throw new ArgumentException($"'{url}' doesn't link to a Google Doc.");
+            return new CloudLink(SourceType.GoogleDrive, GoogleId(url, segments), "Google Drive", true);
+        }
+
+        if (host == "drive.google.com")
+        {
+            if (segments.Contains("folders"))
+                throw new ArgumentException("This link is a Google Drive folder: use list_connection_files to browse it.");
+            var id = segments.Contains("d") ? GoogleId(url, segments) : HttpUtility.ParseQueryString(uri.Query)["id"];
+            if (id is null || !DriveId.IsMatch(id))
+                throw new ArgumentException($"'{url}' doesn't link to a Google Drive file.");
+            return new CloudLink(SourceType.GoogleDrive, id, "Google Drive", false);
+        }
+
+        if (host.EndsWith("-my.sharepoint.com") || host is "onedrive.live.com" or "1drv.ms")
+            return new CloudLink(SourceType.Onedrive, SharingId(uri), "OneDrive", false);
+
+        if (host.EndsWith(".sharepoint.com"))
+            return new CloudLink(SourceType.Sharepoint, SharingId(uri), "SharePoint", false);
+
+        throw new ArgumentException(
+            $"'{url}' is not a Google Docs, Google Drive, OneDrive or SharePoint link. " +
+            "Use document_open_url for direct download links.");
+    }
+
+    /// <summary>
+    /// The ID following "/d/" in Google links (e.g. /document/d/{id}/edit, /file/d/{id}/view).
+    /// </summary>
+    private static string GoogleId(string url, string[] segments)
+    {
+        var index = Array.IndexOf(segments, "d");
+        if (index < 0 || index + 1 >= segments.Length || !DriveId.IsMatch(segments[index + 1]))
+            throw new ArgumentException($"'{url}' doesn't contain a Google file ID.");
+        return segments[index + 1];
+    }
+
+    private static string GoogleKind(string kind) => kind switch
+    {
+        "spreadsheets" => "Sheets spreadsheet",
+        "presentation" => "Slides presentation",
+        "forms" => "Form",
+        _ => "Drawing"
+    };
+
+    /// <summary>
+    /// Microsoft Graph sharing ID of a link, as taken by /shares/{id}/driveItem.
+    /// </summary>
+    public static string SharingId(Uri uri)
+    {
+        var encoded = Convert.ToBase64String(Encoding.UTF8.GetBytes(uri.AbsoluteUri))
+            .TrimEnd('=')
+            .Replace('/', '_')
+            .Replace('+', '-');
+        return "u!" + encoded;
+    }
+}
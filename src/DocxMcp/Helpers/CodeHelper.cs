@@ -0,0 +1,373 @@
+using System.Text;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+public enum CodeTokenKind { Plain, Keyword, String, Comment, Number }
+
+/// <summary>
+/// A piece of source code with the same highlighting. May span lines.
+/// </summary>
+public sealed record CodeToken(CodeTokenKind Kind, string Text);
+
+/// <summary>
+/// Colors of a code block: background shading, plain text, and each highlighted
+/// token kind (null leaves the token as plain text).
+/// </summary>
+public sealed record CodeTheme(
+    string Name, string Background, string Foreground,
+    string? Keyword, string? String, string? Comment, string? Number);
+
+/// <summary>
+/// Formats source code. A code block is one "CodeBlock" paragraph per line
+/// (monospaced, no proofing, no spacing between lines) shaded with the theme's
+/// background, so consecutive lines read as one block. Tokens are colored by a
+/// small lexer per language: keywords, strings, comments and numbers. Spaces
+/// are preserved and tabs kept as tab characters.
+///
+/// Inline code is direct run formatting: the monospaced font with a light
+/// shading, so it survives copying to other documents.
+/// </summary>
+public static class CodeHelper
+{
+    public const string BlockStyle = "CodeBlock";
+    public const string MonospaceFont = "Consolas";
+    public const string InlineShading = "F2F2F2";
+
+    private sealed record CodeLanguage(
+        string Name,
+        string[] Aliases,
+        HashSet<string> Keywords,
+        string[] LineComments,
+        (string Open, string Close)? BlockComment,
+        string Quotes);
+
+    private static readonly HashSet<string> CFamilyLiterals = ["true", "false", "null"];
+
+    private static readonly CodeLanguage[] Languages =
+    [
+        new("csharp", ["c#", "cs"], [
+            "abstract", "as", "async", "await", "base", "bool", "break", "case", "catch", "class", "const",
+            "continue", "default", "delegate", "do", "double", "else", "enum", "event", "false", "finally",
+            "for", "foreach", "get", "if", "in", "int", "interface", "internal", "is", "long", "namespace",
+            "new", "null", "object", "out", "override", "private", "protected", "public", "readonly", "record",
+            "ref", "return", "sealed", "set", "static", "string", "struct", "switch", "this", "throw", "true",
+            "try", "typeof", "using", "var", "virtual", "void", "when", "where", "while", "yield"
+        ], ["//"], ("/*", "*/"), "\"'"),
+        new("java", ["kotlin"], [
+            "abstract", "boolean", "break", "case", "catch", "class", "continue", "default", "do", "double",
+            "else", "enum", "extends", "false", "final", "finally", "for", "fun", "if", "implements", "import",
+            "instanceof", "int", "interface", "long", "new", "null", "package", "private", "protected",
+            "public", "return", "static", "super", "switch", "this", "throw", "throws", "true", "try", "val",
+            "var", "void", "while"
+        ], ["//"], ("/*", "*/"), "\"'"),
+        new("javascript", ["js", "typescript", "ts", "jsx", "tsx"], [
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "delete",
+            "do", "else", "enum", "export", "extends", "false", "finally", "for", "from", "function", "if",
+            "import", "in", "instanceof", "interface", "let", "new", "null", "of", "return", "static", "super",
+            "switch", "this", "throw", "true", "try", "type", "typeof", "undefined", "var", "void", "while",
+            "yield"
+        ], ["//"], ("/*", "*/"), "\"'`"),
+        new("python", ["py"], [
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+            "else", "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is",
+            "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "self", "True", "try",
+            "while", "with", "yield"
+        ], ["#"], null, "\"'"),
+        new("rust", ["rs"], [
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false",
+            "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+            "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+            "where", "while"
+        ], ["//"], ("/*", "*/"), "\""),
+        new("go", ["golang"], [
+            "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "false",
+            "for", "func", "go", "goto", "if", "import", "interface", "map", "nil", "package", "range",
+            "return", "select", "struct", "switch", "true", "type", "var"
+        ], ["//"], ("/*", "*/"), "\"'`"),
+        new("c", ["cpp", "c++", "h", "hpp"], [
+            "auto", "bool", "break", "case", "catch", "char", "class", "const", "constexpr", "continue",
+            "default", "delete", "do", "double", "else", "enum", "extern", "false", "float", "for", "if",
+            "include", "inline", "int", "long", "namespace", "new", "nullptr", "private", "protected",
+            "public", "return", "short", "signed", "sizeof", "static", "struct", "switch", "template", "this",
+            "throw", "true", "try", "typedef", "union", "unsigned", "using", "virtual", "void", "while"
+        ], ["//"], ("/*", "*/"), "\"'"),
+        new("sql", [], [
+            "add", "all", "alter", "and", "as", "asc", "between", "by", "case", "create", "delete", "desc",
+            "distinct", "drop", "else", "end", "exists", "from", "group", "having", "in", "index", "inner",
+            "insert", "into", "is", "join", "key", "left", "like", "limit", "not", "null", "on", "or", "order",
+            "outer", "primary", "right", "select", "set", "table", "then", "union", "update", "values", "when",
+            "where", "with"
+        ], ["--"], ("/*", "*/"), "'\""),
+        new("bash", ["sh", "shell", "zsh"], [
+            "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for", "function",
+            "if", "in", "local", "return", "then", "while"
+        ], ["#"], null, "\"'"),
+        new("json", [], CFamilyLiterals, [], null, "\"")
+    ];
+
+    public static readonly CodeTheme[] Themes =
+    [
+        new("light", "F6F8FA", "24292E", "D73A49", "032F62", "6A737D", "005CC5"),
+        new("dark", "1E1E1E", "D4D4D4", "569CD6", "CE9178", "6A9955", "B5CEA8"),
+        new("plain", InlineShading, "000000", null, null, null, null)
+    ];
+
+    /// <summary>
+    /// Canonical name of a language or alias (e.g. "ts" gives "javascript"),
+    /// null when it isn't highlighted.
+    /// </summary>
+    public static string? LanguageName(string? language) => FindLanguage(language)?.Name;
+
+    /// <summary>
+    /// The theme of that name; light when omitted.
+    /// </summary>
+    public static CodeTheme Theme(string? name)
+    {
+        var key = string.IsNullOrWhiteSpace(name) ? "light" : name.Trim().ToLowerInvariant();
+        return Themes.FirstOrDefault(t => t.Name == key)
+            ?? throw new ArgumentException(
+                $"Unknown theme '{name}'. Use one of: {string.Join(", ", Themes.Select(t => t.Name))}.");
+    }
+
+    private static CodeLanguage? FindLanguage(string? language)
+    {
+        if (string.IsNullOrWhiteSpace(language)) return null;
+        var key = language.Trim().ToLowerInvariant();
+        return Languages.FirstOrDefault(l => l.Name == key || l.Aliases.Contains(key));
+    }
+
+    /// <summary>
+    /// Split code into tokens. Without a known language the code is one plain token.
+    /// </summary>
+    public static List<CodeToken> Tokenize(string code, string? language)
+    {
+        var lang = FindLanguage(language);
+        if (lang is null)
+            return [new CodeToken(CodeTokenKind.Plain, code)];
+
+        // SQL keywords are case-insensitive, the others aren't
+        var keywordComparer = lang.Name == "sql" ? StringComparer.OrdinalIgnoreCase : StringComparer.Ordinal;
+        var keywords = new HashSet<string>(lang.Keywords, keywordComparer);
+
+        var tokens = new List<CodeToken>();
+        var plain = new StringBuilder();
+        void Emit(CodeTokenKind kind, string text)
+        {
+            if (plain.Length > 0)
+            {
+                tokens.Add(new CodeToken(CodeTokenKind.Plain, plain.ToString()));
+                plain.Clear();
+            }
+            tokens.Add(new CodeToken(kind, text));
+        }
+
+        var i = 0;
+        while (i < code.Length)
+        {
+            var c = code[i];
+
+            if (lang.LineComments.Any(marker => string.CompareOrdinal(code, i, marker, 0, marker.Length) == 0))
+            {
+                var end = code.IndexOf('\n', i);
+                if (end < 0) end = code.Length;
+                Emit(CodeTokenKind.Comment, code[i..end]);
+                i = end;
+                continue;
+            }
+
+            if (lang.BlockComment is { } block &&
+                string.CompareOrdinal(code, i, block.Open, 0, block.Open.Length) == 0)
+            {
+                var end = code.IndexOf(block.Close, i + block.Open.Length, StringComparison.Ordinal);
+                end = end < 0 ? code.Length : end + block.Close.Length;
+                Emit(CodeTokenKind.Comment, code[i..end]);
+                i = end;
+                continue;
+            }
+
+            if (lang.Quotes.Contains(c))
+            {
+                // Strings end at the closing quote or, except template literals, the end of the line
+                var j = i + 1;
+                while (j < code.Length && code[j] != c && (c == '`' || code[j] != '\n'))
+                    j += code[j] == '\\' && j + 1 < code.Length ? 2 : 1;
+                var end = j < code.Length && code[j] == c ? j + 1 : j;
+                Emit(CodeTokenKind.String, code[i..end]);
+                i = end;
+                continue;
+            }
+
+            if (char.IsDigit(c))
+            {
+                var j = i + 1;
+                while (j < code.Length && (char.IsLetterOrDigit(code[j]) || code[j] is '.' or '_'))
+                    j++;
+                Emit(CodeTokenKind.Number, code[i..j]);
+                i = j;
+                continue;
+            }
+
+            if (char.IsLetter(c) || c == '_')
+            {
+                var j = i + 1;
+                while (j < code.Length && (char.IsLetterOrDigit(code[j]) || code[j] == '_'))
+                    j++;
+                var word = code[i..j];
+                if (keywords.Contains(word))
+                    Emit(CodeTokenKind.Keyword, word);
+                else
+                    plain.Append(word);
+                i = j;
+                continue;
+            }
+
+            plain.Append(c);
+            i++;
+        }
+
+        if (plain.Length > 0)
+            tokens.Add(new CodeToken(CodeTokenKind.Plain, plain.ToString()));
+        return tokens;
+    }
+
+    /// <summary>
+    /// Tokens grouped by line, tokens spanning lines being split.
+    /// </summary>
+    public static List<List<CodeToken>> Lines(IEnumerable<CodeToken> tokens)
+    {
+        var lines = new List<List<CodeToken>> { new() };
+        foreach (var token in tokens)
+        {
+            var parts = token.Text.Replace("\r\n", "\n").Replace('\r', '\n').Split('\n');
+            for (int k = 0; k < parts.Length; k++)
+            {
+                if (k > 0) lines.Add([]);
+                if (parts[k].Length > 0)
+                    lines[^1].Add(token with { Text = parts[k] });
+            }
+        }
+        return lines;
+    }
+
+    /// <summary>
+    /// Build the paragraphs of a code block, one per line.
+    /// </summary>
+    public static List<Paragraph> CreateBlock(MainDocumentPart mainPart, string code, string? language, CodeTheme theme)
+    {
+        EnsureStyles(mainPart);
+
+        var paragraphs = new List<Paragraph>();
+        foreach (var line in Lines(Tokenize(code.TrimEnd('\r', '\n'), language)))
+        {
+            var paragraph = new Paragraph(new ParagraphProperties
+            {
+                ParagraphStyleId = new ParagraphStyleId { Val = BlockStyle },
+                Shading = new Shading { Val = ShadingPatternValues.Clear, Color = "auto", Fill = theme.Background }
+            });
+            foreach (var token in line)
+            {
+                var color = token.Kind switch
+                {
+                    CodeTokenKind.Keyword => theme.Keyword,
+                    CodeTokenKind.String => theme.String,
+                    CodeTokenKind.Comment => theme.Comment,
+                    CodeTokenKind.Number => theme.Number,
+                    _ => null
+                } ?? theme.Foreground;
+                var italic = token.Kind == CodeTokenKind.Comment && theme.Comment is not null;
+
+                foreach (var run in CreateRuns(token.Text, color, italic))
+                {
+                    ElementIdManager.AssignId(run);
+                    paragraph.AppendChild(run);
+                }
+            }
+            ElementIdManager.AssignId(paragraph);
+            paragraphs.Add(paragraph);
+        }
+        return paragraphs;
+    }
+
+    /// <summary>
+    /// Runs of a token: text with spaces preserved, and a run per tab.
+    /// </summary>
+    private static IEnumerable<Run> CreateRuns(string text, string color, bool italic)
+    {
+        var pieces = text.Split('\t');
+        for (int k = 0; k < pieces.Length; k++)
+        {
+            if (k > 0)
+                yield return new Run(RunProperties(color, italic), new TabChar());
+            if (pieces[k].Length > 0)
+                yield return new Run(RunProperties(color, italic),
+                    new Text(pieces[k]) { Space = SpaceProcessingModeValues.Preserve });
+        }
+    }
+
+    private static RunProperties RunProperties(string color, bool italic) => new()
+    {
+        Italic = italic ? new Italic() : null,
+        Color = new Color { Val = color }
+    };
+
+    /// <summary>
+    /// Format a run as inline code, or remove that formatting.
+    /// </summary>
+    public static void ApplyInlineCode(RunProperties props, bool on)
+    {
+        var fonts = props.RunFonts ?? new RunFonts();
+        if (on)
+        {
+            fonts.Ascii = MonospaceFont;
+            fonts.HighAnsi = MonospaceFont;
+            fonts.ComplexScript = MonospaceFont;
+            fonts.AsciiTheme = null;
+            fonts.HighAnsiTheme = null;
+            fonts.ComplexScriptTheme = null;
+            props.Shading = new Shading { Val = ShadingPatternValues.Clear, Color = "auto", Fill = InlineShading };
+            props.NoProof = new NoProof();
+        }
+        else if (IsInlineCode(props))
+        {
+            fonts.Ascii = null;
+            fonts.HighAnsi = null;
+            fonts.ComplexScript = null;
+            props.Shading = null;
+            props.NoProof = null;
+        }
+        props.RunFonts = fonts.HasAttributes ? fonts : null;
+    }
+
+    /// <summary>
+    /// Whether a run is formatted as inline code.
+    /// </summary>
+    public static bool IsInlineCode(RunProperties props) =>
+        props.RunFonts?.Ascii?.Value == MonospaceFont && props.Shading?.Fill?.Value == InlineShading;
+
+    private static void EnsureStyles(MainDocumentPart mainPart)
+    {
+        var stylesPart = mainPart.StyleDefinitionsPart
+            ?? mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles ??= new Styles();
+        var styles = stylesPart.Styles;
+
+        if (styles.Elements<Style>().Any(s => s.StyleId?.Value == BlockStyle))
+            return;
+
+        styles.AppendChild(new Style(
+            new StyleName { Val = "Code Block" },
+            new BasedOn { Val = "Normal" },
+            new PrimaryStyle(),
+            new StyleParagraphProperties(
+                new SpacingBetweenLines { Before = "0", After = "0", Line = "240", LineRule = LineSpacingRuleValues.Auto }),
+            new StyleRunProperties(
+                new RunFonts { Ascii = MonospaceFont, HighAnsi = MonospaceFont, ComplexScript = MonospaceFont },
+                new NoProof(),
+                new FontSize { Val = "20" },
+                new FontSizeComplexScript { Val = "20" }))
+        { Type = StyleValues.Paragraph, StyleId = BlockStyle, CustomStyle = true });
+    }
+}
@@ -20,7 +20,8 @@ public static class CommentHelper
         var commentsPart = mainPart.WordprocessingCommentsPart;
         if (commentsPart is null)
         {
-            commentsPart = mainPart.AddNewPart<WordprocessingCommentsPart>();
+            commentsPart = mainPart.AddNewPart<WordprocessingCommentsPart>(
+                DeterministicOutput.NextRelationshipId(mainPart));
             commentsPart.Comments = new Comments();
         }
         else if (commentsPart.Comments is null)
@@ -144,11 +145,36 @@ public static class CommentHelper
     /// Returns true if successful.
     /// </summary>
     private static bool TryAnchorTextInParagraph(Paragraph para, int commentId, string anchorText)
+    {
+        var matched = IsolateText(para, anchorText);
+        if (matched is null) return false;
+
+        // Insert CommentRangeStart before first matching run
+        var rangeStart = new CommentRangeStart { Id = commentId.ToString() };
+        para.InsertBefore(rangeStart, matched[0]);
+
+        // Insert CommentRangeEnd after last matching run
+        var rangeEnd = new CommentRangeEnd { Id = commentId.ToString() };
+        para.InsertAfter(rangeEnd, matched[^1]);
+
+        // Insert reference run after CommentRangeEnd
+        var refRun = CreateCommentReferenceRun(commentId);
+        para.InsertAfter(refRun, rangeEnd);
+
+        return true;
+    }
+
+    /// <summary>
+    /// Find the first occurrence of <paramref name="text"/> across the paragraph's runs
+    /// and split the runs at its boundaries. Returns the runs covering exactly the
+    /// match, or null if the text isn't in the paragraph.
+    /// </summary>
+    public static List<Run>? IsolateText(Paragraph para, string text)
     {
         var runs = para.Elements<Run>().ToList();
-        if (runs.Count == 0) return false;
+        if (runs.Count == 0 || text.Length == 0) return null;
 
-        // Concatenate all run texts and find the anchor
+        // Concatenate all run texts and find the match
         var runTexts = new List<(Run Run, string Text, int Start)>();
         int pos = 0;
         foreach (var run in runs)
@@ -159,10 +185,10 @@ public static class CommentHelper
         }
 
         var allText = string.Concat(runTexts.Select(r => r.Text));
-        var matchIdx = allText.IndexOf(anchorText, StringComparison.Ordinal);
-        if (matchIdx < 0) return false;
+        var matchIdx = allText.IndexOf(text, StringComparison.Ordinal);
+        if (matchIdx < 0) return null;
 
-        var matchEnd = matchIdx + anchorText.Length;
+        var matchEnd = matchIdx + text.Length;
 
         // Find runs that overlap with the match
         var firstRunIdx = -1;
@@ -180,7 +206,7 @@ public static class CommentHelper
             lastRunIdx = i;
         }
 
-        if (firstRunIdx < 0) return false;
+        if (firstRunIdx < 0) return null;
 
         // Split first run if match doesn't start at run boundary
         var firstEntry = runTexts[firstRunIdx];
@@ -212,19 +238,9 @@ public static class CommentHelper
             }
         }
 
-        // Insert CommentRangeStart before first matching run
-        var rangeStart = new CommentRangeStart { Id = commentId.ToString() };
-        para.InsertBefore(rangeStart, runTexts[firstRunIdx].Run);
-
-        // Insert CommentRangeEnd after last matching run
-        var rangeEnd = new CommentRangeEnd { Id = commentId.ToString() };
-        para.InsertAfter(rangeEnd, runTexts[lastRunIdx].Run);
-
-        // Insert reference run after CommentRangeEnd
-        var refRun = CreateCommentReferenceRun(commentId);
-        para.InsertAfter(refRun, rangeEnd);
-
-        return true;
+        return runTexts.GetRange(firstRunIdx, lastRunIdx - firstRunIdx + 1)
+            .Select(r => r.Run)
+            .ToList();
     }
 
     /// <summary>
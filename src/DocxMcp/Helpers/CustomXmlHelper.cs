@@ -0,0 +1,258 @@
+using System.Text;
+using System.Text.RegularExpressions;
+using System.Xml;
+using System.Xml.Linq;
+using System.Xml.XPath;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Ds = DocumentFormat.OpenXml.CustomXmlDataProperties;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A custom XML data part: its datastore item ID, URI and root element.
+/// </summary>
+public sealed record CustomXmlInfo(string ItemId, string Uri, string Root, string? Namespace, int Size);
+
+/// <summary>
+/// Custom XML data parts and content-control data binding (w:dataBinding), as
+/// used by document-assembly pipelines (OpenDoPE and the like). A bound control
+/// shows the value of its XPath in the part; the value is filled in when the
+/// control is bound, and Word keeps it in sync from then on.
+/// </summary>
+public static class CustomXmlHelper
+{
+    private static readonly Regex PrefixMapping = new(@"xmlns:(?<prefix>[\w.-]+)\s*=\s*(['""])(?<uri>.*?)\1");
+
+    /// <summary>
+    /// Add a custom XML part holding <paramref name="xml"/>. The datastore item ID
+    /// is generated unless given (replay passes the original one).
+    /// </summary>
+    public static CustomXmlInfo Add(WordprocessingDocument doc, string xml, string? itemId = null)
+    {
+        var main = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+
+        XDocument data;
+        try
+        {
+            data = XDocument.Parse(xml);
+        }
+        catch (XmlException ex)
+        {
+            throw new ArgumentException($"Invalid XML — {ex.Message}");
+        }
+
+        itemId ??= "{" + DeterministicOutput.NewGuid().ToString().ToUpperInvariant() + "}";
+        if (Find(doc, itemId) is not null)
+            throw new ArgumentException($"A custom XML part with item ID {itemId} already exists.");
+
+        var part = main.AddCustomXmlPart(CustomXmlPartType.CustomXml, DeterministicOutput.NextRelationshipId(main));
+        using (var stream = new MemoryStream(Encoding.UTF8.GetBytes(data.ToString(SaveOptions.DisableFormatting))))
+            part.FeedData(stream);
+
+        var schemaRefs = new Ds.SchemaReferences();
+        if (data.Root!.Name.NamespaceName.Length > 0)
+            schemaRefs.AppendChild(new Ds.SchemaReference { Uri = data.Root.Name.NamespaceName });
+
+        var props = part.AddNewPart<CustomXmlPropertiesPart>(DeterministicOutput.NextRelationshipId(part));
+        props.DataStoreItem = new Ds.DataStoreItem(schemaRefs) { ItemId = itemId };
+
+        return Info(part)!;
+    }
+
+    /// <summary>
+    /// All custom XML parts that have a datastore item ID.
+    /// </summary>
+    public static List<CustomXmlInfo> List(WordprocessingDocument doc) =>
+        doc.MainDocumentPart?.CustomXmlParts.Select(Info).OfType<CustomXmlInfo>().ToList() ?? [];
+
+    /// <summary>
+    /// Find a custom XML part by datastore item ID (braces and case are ignored).
+    /// </summary>
+    public static CustomXmlPart? Find(WordprocessingDocument doc, string itemId) =>
+        doc.MainDocumentPart?.CustomXmlParts.FirstOrDefault(p =>
+            NormalizeId(p.CustomXmlPropertiesPart?.DataStoreItem?.ItemId?.Value) == NormalizeId(itemId));
+
+    private static string? NormalizeId(string? id) => id?.Trim('{', '}', ' ').ToUpperInvariant();
+
+    private static CustomXmlInfo? Info(CustomXmlPart part)
+    {
+        var itemId = part.CustomXmlPropertiesPart?.DataStoreItem?.ItemId?.Value;
+        if (itemId is null) return null;
+
+        var data = Load(part);
+        using var stream = part.GetStream(FileMode.Open, FileAccess.Read);
+        return new CustomXmlInfo(itemId, part.Uri.ToString(), data.Root?.Name.LocalName ?? "",
+            data.Root?.Name.NamespaceName is { Length: > 0 } ns ? ns : null,
+            (int)stream.Length);
+    }
+
+    private static XDocument Load(CustomXmlPart part)
+    {
+        using var stream = part.GetStream(FileMode.Open, FileAccess.Read);
+        return XDocument.Load(stream);
+    }
+
+    /// <summary>
+    /// Prefix mappings for a part's namespaces, in w:prefixMappings syntax.
+    /// The default namespace is mapped to ns0.
+    /// </summary>
+    public static string DefaultPrefixMappings(CustomXmlPart part)
+    {
+        var root = Load(part).Root;
+        if (root is null) return "";
+
+        var mappings = new List<string>();
+        foreach (var decl in root.Attributes().Where(a => a.IsNamespaceDeclaration))
+        {
+            var prefix = decl.Name.Namespace == XNamespace.Xmlns ? decl.Name.LocalName : "ns0";
+            mappings.Add($"xmlns:{prefix}='{decl.Value}'");
+        }
+        return string.Join(" ", mappings);
+    }
+
+    /// <summary>
+    /// The string value of <paramref name="xpath"/> in the part, or null when it matches nothing.
+    /// </summary>
+    public static string? Evaluate(CustomXmlPart part, string xpath, string prefixMappings)
+    {
+        var namespaces = new XmlNamespaceManager(new NameTable());
+        foreach (Match m in PrefixMapping.Matches(prefixMappings))
+            namespaces.AddNamespace(m.Groups["prefix"].Value, m.Groups["uri"].Value);
+
+        object result;
+        try
+        {
+            result = Load(part).XPathEvaluate(xpath, namespaces);
+        }
+        catch (XPathException ex)
+        {
+            throw new ArgumentException($"Invalid XPath — {ex.Message}");
+        }
+
+        return result switch
+        {
+            IEnumerable<object> nodes => nodes.FirstOrDefault() switch
+            {
+                XElement e => e.Value,
+                XAttribute a => a.Value,
+                XText t => t.Value,
+                _ => null
+            },
+            bool b => b ? "true" : "false",
+            double d => d.ToString(System.Globalization.CultureInfo.InvariantCulture),
+            _ => result.ToString()
+        };
+    }
+
+    /// <summary>
+    /// Content controls whose w:tag equals <paramref name="tag"/>, in the body,
+    /// headers and footers.
+    /// </summary>
+    public static List<SdtElement> FindControls(WordprocessingDocument doc, string tag)
+    {
+        var main = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+
+        var roots = new List<OpenXmlElement?> { main.Document?.Body };
+        roots.AddRange(main.HeaderParts.Select(h => (OpenXmlElement?)h.Header));
+        roots.AddRange(main.FooterParts.Select(f => (OpenXmlElement?)f.Footer));
+
+        return roots.OfType<OpenXmlElement>()
+            .SelectMany(r => r.Descendants<SdtElement>())
+            .Where(s => s.SdtProperties?.GetFirstChild<Tag>()?.Val?.Value == tag)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Replace a paragraph's content with a plain-text inline content control
+    /// tagged <paramref name="tag"/>, keeping the formatting of its first run.
+    /// </summary>
+    public static SdtRun WrapParagraph(Paragraph paragraph, string tag)
+    {
+        var runProps = paragraph.Elements<Run>().FirstOrDefault()?.RunProperties;
+
+        var control = new SdtRun(
+            new SdtProperties(
+                new SdtAlias { Val = tag },
+                new Tag { Val = tag },
+                new SdtId { Val = DeterministicOutput.Next(1, int.MaxValue) },
+                new SdtContentText()),
+            new SdtContentRun(NewRun(runProps, "")));
+
+        foreach (var child in paragraph.ChildElements.Where(c => c is not ParagraphProperties).ToList())
+            child.Remove();
+        paragraph.AppendChild(control);
+        return control;
+    }
+
+    /// <summary>
+    /// Bind a content control to an XPath in a custom XML part and show the
+    /// current value.
+    /// </summary>
+    public static void Bind(SdtElement control, string storeItemId, string xpath, string prefixMappings, string? value)
+    {
+        var sdtPr = control.SdtProperties ?? control.PrependChild(new SdtProperties());
+
+        sdtPr.RemoveAllChildren<DataBinding>();
+        sdtPr.RemoveAllChildren<ShowingPlaceholder>();
+
+        var binding = new DataBinding { XPath = xpath, StoreItemId = storeItemId };
+        if (prefixMappings.Length > 0)
+            binding.PrefixMappings = prefixMappings;
+
+        // dataBinding follows rPr, alias, tag, id, lock, placeholder and temporary
+        var before = sdtPr.ChildElements.LastOrDefault(c =>
+            c is RunProperties or SdtAlias or Tag or SdtId or Lock or SdtPlaceholder or TemporarySdt);
+        if (before is null)
+            sdtPr.PrependChild(binding);
+        else
+            sdtPr.InsertAfter(binding, before);
+
+        if (value is not null)
+            SetText(control, value);
+    }
+
+    /// <summary>
+    /// Replace the displayed content of an inline or block control with <paramref name="value"/>,
+    /// keeping the formatting of its first paragraph and run.
+    /// </summary>
+    private static void SetText(SdtElement control, string value)
+    {
+        switch (control)
+        {
+            case SdtRun run when run.SdtContentRun is { } content:
+            {
+                var runProps = content.Descendants<Run>().FirstOrDefault()?.RunProperties;
+                content.RemoveAllChildren();
+                content.AppendChild(NewRun(runProps, value));
+                break;
+            }
+            case SdtBlock block when block.SdtContentBlock is { } content:
+            {
+                var first = content.Descendants<Paragraph>().FirstOrDefault();
+                var paragraph = new Paragraph();
+                if (first?.ParagraphProperties is { } pPr)
+                    paragraph.ParagraphProperties = (ParagraphProperties)pPr.CloneNode(true);
+                paragraph.AppendChild(NewRun(first?.Descendants<Run>().FirstOrDefault()?.RunProperties, value));
+                ElementIdManager.AssignId(paragraph);
+
+                content.RemoveAllChildren();
+                content.AppendChild(paragraph);
+                break;
+            }
+        }
+    }
+
+    private static Run NewRun(RunProperties? runProps, string text)
+    {
+        var run = new Run();
+        if (runProps is not null)
+            run.RunProperties = (RunProperties)runProps.CloneNode(true);
+        run.AppendChild(new Text(text) { Space = SpaceProcessingModeValues.Preserve });
+        ElementIdManager.AssignId(run);
+        return run;
+    }
+}
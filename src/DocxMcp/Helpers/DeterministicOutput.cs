@@ -0,0 +1,164 @@
+using System.IO.Compression;
+using System.Security.Cryptography;
+using System.Xml.Linq;
+using DocumentFormat.OpenXml.Packaging;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Deterministic document output: the same operations applied to the same document
+/// produce byte-identical DOCX files, for caching, diffing and reproducible tests.
+///
+/// Enabled with DOCX_DETERMINISTIC=true (or per async flow with <see cref="Force"/>). Then:
+/// - element IDs, content control IDs and custom XML item IDs come from a sequence seeded
+///   by the bytes a session was loaded from, instead of a shared random source;
+/// - dates written into the document (comments, revisions) default to <see cref="FixedTimestamp"/>;
+/// - the ZIP package is rewritten with entries in a fixed order and timestamp, and package
+///   relationships renumbered.
+///
+/// Relationship IDs of new parts are always allocated sequentially (rId1, rId2, ...),
+/// whether or not the mode is enabled.
+/// </summary>
+public static class DeterministicOutput
+{
+    /// <summary>Timestamp of ZIP entries and default document dates (the earliest ZIP date).</summary>
+    public static readonly DateTimeOffset FixedTimestamp = new(1980, 1, 1, 0, 0, 0, TimeSpan.Zero);
+
+    private static readonly bool EnabledByEnvironment =
+        Environment.GetEnvironmentVariable("DOCX_DETERMINISTIC")?.ToLowerInvariant() is "1" or "true";
+
+    private static readonly AsyncLocal<bool?> EnabledOverride = new();
+    private static readonly AsyncLocal<Random?> Sequence = new();
+
+    public static bool Enabled => EnabledOverride.Value ?? EnabledByEnvironment;
+
+    /// <summary>
+    /// Turn deterministic output on or off for the current async flow, until disposed.
+    /// </summary>
+    public static IDisposable Force(bool enabled = true)
+    {
+        var previous = EnabledOverride.Value;
+        EnabledOverride.Value = enabled;
+        return new Restore(() => EnabledOverride.Value = previous);
+    }
+
+    /// <summary>
+    /// Start the ID sequence of a session loaded from <paramref name="documentBytes"/>.
+    /// A no-op unless deterministic output is enabled; disposing restores the outer sequence.
+    /// </summary>
+    public static IDisposable BeginSequence(ReadOnlySpan<byte> documentBytes)
+    {
+        if (!Enabled)
+            return new Restore(() => { });
+
+        var seed = BitConverter.ToInt32(SHA256.HashData(documentBytes), 0);
+        var previous = Sequence.Value;
+        Sequence.Value = new Random(seed);
+        return new Restore(() => Sequence.Value = previous);
+    }
+
+    /// <summary>
+    /// Random integer in [minValue, maxValue), from the session's sequence when one is active.
+    /// </summary>
+    public static int Next(int minValue, int maxValue) =>
+        (Sequence.Value ?? Random.Shared).Next(minValue, maxValue);
+
+    /// <summary>A new GUID, from the session's sequence when one is active.</summary>
+    public static Guid NewGuid()
+    {
+        if (Sequence.Value is not { } sequence)
+            return Guid.NewGuid();
+
+        var bytes = new byte[16];
+        sequence.NextBytes(bytes);
+        return new Guid(bytes);
+    }
+
+    /// <summary>Current time for dates written into the document.</summary>
+    public static DateTime UtcNow => Enabled ? FixedTimestamp.UtcDateTime : DateTime.UtcNow;
+
+    /// <summary>
+    /// Next free relationship ID of <paramref name="container"/>: one past the highest rIdN in use.
+    /// </summary>
+    public static string NextRelationshipId(OpenXmlPartContainer container)
+    {
+        var ids = container.Parts.Select(p => p.RelationshipId)
+            .Concat(container.ExternalRelationships.Select(r => r.Id))
+            .Concat(container.HyperlinkRelationships.Select(r => r.Id))
+            .Concat(container.DataPartReferenceRelationships.Select(r => r.Id));
+
+        var max = 0;
+        foreach (var id in ids)
+        {
+            if (id.StartsWith("rId", StringComparison.Ordinal)
+                && int.TryParse(id.AsSpan(3), out var n) && n > max)
+                max = n;
+        }
+        return $"rId{max + 1}";
+    }
+
+    /// <summary>
+    /// Rewrite a DOCX package with entries in a fixed order ([Content_Types].xml, then
+    /// _rels/.rels, then by name), a fixed timestamp, and package relationships renumbered
+    /// in order of type and target. Returns the bytes unchanged unless deterministic output
+    /// is enabled.
+    /// </summary>
+    public static byte[] NormalizePackage(byte[] docx)
+    {
+        if (!Enabled)
+            return docx;
+
+        using var input = new ZipArchive(new MemoryStream(docx), ZipArchiveMode.Read);
+        var output = new MemoryStream();
+        using (var archive = new ZipArchive(output, ZipArchiveMode.Create, leaveOpen: true))
+        {
+            var entries = input.Entries
+                .OrderBy(e => e.FullName switch
+                {
+                    "[Content_Types].xml" => 0,
+                    "_rels/.rels" => 1,
+                    _ => 2
+                })
+                .ThenBy(e => e.FullName, StringComparer.Ordinal);
+
+            foreach (var entry in entries)
+            {
+                var copy = archive.CreateEntry(entry.FullName, CompressionLevel.Optimal);
+                copy.LastWriteTime = FixedTimestamp;
+
+                using var source = entry.Open();
+                using var target = copy.Open();
+                if (entry.FullName == "_rels/.rels")
+                    RenumberRelationships(source, target);
+                else
+                    source.CopyTo(target);
+            }
+        }
+        return output.ToArray();
+    }
+
+    /// <summary>
+    /// Package-level relationship IDs are not referenced from any part, so they can be
+    /// renumbered freely.
+    /// </summary>
+    private static void RenumberRelationships(Stream source, Stream target)
+    {
+        var rels = XDocument.Load(source);
+        var root = rels.Root!;
+        var ordered = root.Elements()
+            .OrderBy(r => (string?)r.Attribute("Type"), StringComparer.Ordinal)
+            .ThenBy(r => (string?)r.Attribute("Target"), StringComparer.Ordinal)
+            .ToList();
+
+        for (var i = 0; i < ordered.Count; i++)
+            ordered[i].SetAttributeValue("Id", $"rId{i + 1}");
+
+        root.ReplaceNodes(ordered);
+        rels.Save(target, SaveOptions.DisableFormatting);
+    }
+
+    private sealed class Restore(Action restore) : IDisposable
+    {
+        public void Dispose() => restore();
+    }
+}
@@ -0,0 +1,337 @@
+using System.IO.Compression;
+using System.Security.Cryptography;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// What <see cref="DocumentOptimizer.Optimize"/> removes. Everything is on by default.
+/// </summary>
+public sealed record OptimizeOptions(
+    bool StripUnusedStyles = true,
+    bool StripUnusedNumbering = true,
+    bool StripUnusedMedia = true,
+    bool DedupeImages = true,
+    bool StripRsids = true);
+
+/// <summary>
+/// What an optimization removed.
+/// </summary>
+public sealed class OptimizeReport
+{
+    public int StylesRemoved { get; set; }
+
+    /// <summary>Numbering instances and abstract numbering definitions.</summary>
+    public int NumberingRemoved { get; set; }
+
+    /// <summary>Image parts no longer referenced by any element.</summary>
+    public int MediaRemoved { get; set; }
+
+    /// <summary>Image parts replaced by an identical image already in the package.</summary>
+    public int ImagesDeduplicated { get; set; }
+
+    /// <summary>rsid attributes and elements (revision save IDs Word adds on every save).</summary>
+    public int RsidsRemoved { get; set; }
+}
+
+/// <summary>
+/// Removes what edits leave behind in a package: unused styles, numbering and media,
+/// duplicate images and rsid bookkeeping. Content is never changed.
+/// </summary>
+public static class DocumentOptimizer
+{
+    private const string WNamespace = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+    private const string RelationshipsNamespace =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+    public static OptimizeReport Optimize(WordprocessingDocument doc, OptimizeOptions options)
+    {
+        var main = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no MainDocumentPart.");
+        var report = new OptimizeReport();
+
+        if (options.StripRsids)
+            report.RsidsRemoved = StripRsids(main);
+        // Styles first: removed styles may have been the only users of a numbering definition
+        if (options.StripUnusedStyles)
+            report.StylesRemoved = StripUnusedStyles(main);
+        if (options.StripUnusedNumbering)
+            report.NumberingRemoved = StripUnusedNumbering(main);
+        if (options.DedupeImages)
+            report.ImagesDeduplicated = DedupeImages(main);
+        if (options.StripUnusedMedia)
+            report.MediaRemoved = StripUnusedMedia(main);
+
+        return report;
+    }
+
+    /// <summary>
+    /// Recompress every entry of a package with the strongest deflate level, keeping entry
+    /// order and timestamps. Returns the original bytes if that doesn't make it smaller.
+    /// </summary>
+    public static byte[] Recompress(byte[] docx)
+    {
+        using var input = new ZipArchive(new MemoryStream(docx), ZipArchiveMode.Read);
+        var output = new MemoryStream();
+        using (var archive = new ZipArchive(output, ZipArchiveMode.Create, leaveOpen: true))
+        {
+            foreach (var entry in input.Entries)
+            {
+                var copy = archive.CreateEntry(entry.FullName, CompressionLevel.SmallestSize);
+                copy.LastWriteTime = entry.LastWriteTime;
+
+                using var source = entry.Open();
+                using var target = copy.Open();
+                source.CopyTo(target);
+            }
+        }
+        return output.Length < docx.Length ? output.ToArray() : docx;
+    }
+
+    /// <summary>
+    /// Parts holding document content (and so style, numbering and image references).
+    /// </summary>
+    private static List<OpenXmlPart> ContentParts(MainDocumentPart main)
+    {
+        var parts = new List<OpenXmlPart> { main };
+        parts.AddRange(main.HeaderParts);
+        parts.AddRange(main.FooterParts);
+        if (main.FootnotesPart is { } footnotes) parts.Add(footnotes);
+        if (main.EndnotesPart is { } endnotes) parts.Add(endnotes);
+        if (main.WordprocessingCommentsPart is { } comments) parts.Add(comments);
+        return parts;
+    }
+
+    private static IEnumerable<OpenXmlElement> Roots(IEnumerable<OpenXmlPart?> parts) =>
+        parts.Select(p => (OpenXmlElement?)p?.RootElement).OfType<OpenXmlElement>();
+
+    private static int StripRsids(MainDocumentPart main)
+    {
+        var removed = 0;
+        var parts = ContentParts(main).Append(main.StyleDefinitionsPart);
+        foreach (var root in Roots(parts))
+        {
+            foreach (var element in root.Descendants().Prepend(root))
+            {
+                foreach (var attr in element.GetAttributes())
+                {
+                    if (attr.NamespaceUri == WNamespace && attr.LocalName.StartsWith("rsid", StringComparison.Ordinal))
+                    {
+                        element.RemoveAttribute(attr.LocalName, attr.NamespaceUri);
+                        removed++;
+                    }
+                }
+            }
+
+            // <w:rsid> inside style definitions
+            foreach (var rsid in root.Descendants<Rsid>().ToList())
+            {
+                rsid.Remove();
+                removed++;
+            }
+        }
+
+        if (main.DocumentSettingsPart?.Settings?.GetFirstChild<Rsids>() is { } rsids)
+        {
+            removed += rsids.ChildElements.Count;
+            rsids.Remove();
+        }
+        return removed;
+    }
+
+    private static int StripUnusedStyles(MainDocumentPart main)
+    {
+        var styles = main.StyleDefinitionsPart?.Styles;
+        if (styles is null) return 0;
+
+        var byId = new Dictionary<string, Style>();
+        foreach (var style in styles.Elements<Style>())
+        {
+            if (style.StyleId?.Value is { } id)
+                byId.TryAdd(id, style);
+        }
+
+        var used = new HashSet<string>();
+        var roots = Roots(ContentParts(main).Append(main.NumberingDefinitionsPart)).ToList();
+        foreach (var element in roots.SelectMany(r => r.Descendants()))
+        {
+            var id = element switch
+            {
+                ParagraphStyleId p => p.Val?.Value,
+                RunStyle r => r.Val?.Value,
+                TableStyle t => t.Val?.Value,
+                NumberingStyleLink n => n.Val?.Value,
+                StyleLink l => l.Val?.Value,
+                _ => null
+            };
+            if (id is not null)
+                used.Add(id);
+        }
+
+        // Field instructions (STYLEREF, TOC \t) name styles by ID or name
+        var instructions = string.Join("\n", roots.SelectMany(r =>
+            r.Descendants<FieldCode>().Select(f => f.Text)
+                .Concat(r.Descendants<SimpleField>().Select(f => f.Instruction?.Value ?? ""))));
+
+        foreach (var (id, style) in byId)
+        {
+            if (style.Default?.Value == true
+                || (instructions.Length > 0
+                    && (instructions.Contains(id, StringComparison.OrdinalIgnoreCase)
+                        || (style.StyleName?.Val?.Value is { Length: > 0 } name
+                            && instructions.Contains(name, StringComparison.OrdinalIgnoreCase)))))
+                used.Add(id);
+        }
+
+        // Keep everything the used styles build on or link to
+        var pending = new Queue<string>(used);
+        while (pending.TryDequeue(out var id))
+        {
+            if (!byId.TryGetValue(id, out var style)) continue;
+            foreach (var related in new[] { style.BasedOn?.Val?.Value, style.NextParagraphStyle?.Val?.Value, style.LinkedStyle?.Val?.Value })
+            {
+                if (related is not null && used.Add(related))
+                    pending.Enqueue(related);
+            }
+        }
+
+        var removed = 0;
+        foreach (var style in styles.Elements<Style>().ToList())
+        {
+            if (style.StyleId?.Value is { } id && !used.Contains(id))
+            {
+                style.Remove();
+                removed++;
+            }
+        }
+        return removed;
+    }
+
+    private static int StripUnusedNumbering(MainDocumentPart main)
+    {
+        var numbering = main.NumberingDefinitionsPart?.Numbering;
+        if (numbering is null) return 0;
+
+        var usedNums = Roots(ContentParts(main).Append(main.StyleDefinitionsPart))
+            .SelectMany(r => r.Descendants<NumberingId>())
+            .Select(n => n.Val?.Value)
+            .OfType<int>()
+            .ToHashSet();
+
+        var removed = 0;
+        foreach (var num in numbering.Elements<NumberingInstance>().ToList())
+        {
+            if (num.NumberID?.Value is int id && !usedNums.Contains(id))
+            {
+                num.Remove();
+                removed++;
+            }
+        }
+
+        var usedAbstract = numbering.Elements<NumberingInstance>()
+            .Select(n => n.AbstractNumId?.Val?.Value)
+            .OfType<int>()
+            .ToHashSet();
+
+        // Abstract definitions of numbering styles are reached through the style, not a num
+        foreach (var abstractNum in numbering.Elements<AbstractNum>().ToList())
+        {
+            if (abstractNum.AbstractNumberId?.Value is int id
+                && !usedAbstract.Contains(id)
+                && abstractNum.StyleLink is null)
+            {
+                abstractNum.Remove();
+                removed++;
+            }
+        }
+        return removed;
+    }
+
+    private static int DedupeImages(MainDocumentPart main)
+    {
+        var canonical = new Dictionary<string, ImagePart>();
+        var deduplicated = 0;
+
+        foreach (var part in ContentParts(main))
+        {
+            if (part.RootElement is not { } root) continue;
+
+            foreach (var pair in part.Parts.Where(p => p.OpenXmlPart is ImagePart).ToList())
+            {
+                var image = (ImagePart)pair.OpenXmlPart;
+                string key;
+                using (var stream = image.GetStream(FileMode.Open, FileAccess.Read))
+                    key = image.ContentType + ":" + Convert.ToHexString(SHA256.HashData(stream));
+
+                if (!canonical.TryGetValue(key, out var keep))
+                {
+                    canonical[key] = image;
+                    continue;
+                }
+                if (ReferenceEquals(keep, image)) continue;
+
+                var keepId = RelationshipIdOf(part, keep)
+                    ?? part.GetIdOfPart(part.AddPart(keep, DeterministicOutput.NextRelationshipId(part)));
+                ReplaceRelationshipId(root, pair.RelationshipId, keepId);
+                part.DeletePart(pair.RelationshipId);
+                deduplicated++;
+            }
+        }
+        return deduplicated;
+    }
+
+    private static int StripUnusedMedia(MainDocumentPart main)
+    {
+        var removed = 0;
+        foreach (var part in ContentParts(main))
+        {
+            if (part.RootElement is not { } root) continue;
+
+            var referenced = root.Descendants().Prepend(root)
+                .SelectMany(e => e.GetAttributes())
+                .Where(IsRelationshipReference)
+                .Select(a => a.Value)
+                .ToHashSet();
+
+            foreach (var pair in part.Parts.Where(p => p.OpenXmlPart is ImagePart).ToList())
+            {
+                if (!referenced.Contains(pair.RelationshipId))
+                {
+                    part.DeletePart(pair.RelationshipId);
+                    removed++;
+                }
+            }
+        }
+        return removed;
+    }
+
+    /// <summary>
+    /// r:embed, r:link, r:id, ... and VML's o:relid.
+    /// </summary>
+    private static bool IsRelationshipReference(OpenXmlAttribute attr) =>
+        attr.NamespaceUri == RelationshipsNamespace || attr.LocalName == "relid";
+
+    private static void ReplaceRelationshipId(OpenXmlElement root, string from, string to)
+    {
+        foreach (var element in root.Descendants().Prepend(root))
+        {
+            foreach (var attr in element.GetAttributes())
+            {
+                if (IsRelationshipReference(attr) && attr.Value == from)
+                    element.SetAttribute(new OpenXmlAttribute(attr.Prefix, attr.LocalName, attr.NamespaceUri, to));
+            }
+        }
+    }
+
+    private static string? RelationshipIdOf(OpenXmlPart container, OpenXmlPart target)
+    {
+        foreach (var pair in container.Parts)
+        {
+            if (ReferenceEquals(pair.OpenXmlPart, target))
+                return pair.RelationshipId;
+        }
+        return null;
+    }
+}
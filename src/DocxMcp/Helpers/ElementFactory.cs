@@ -45,17 +45,20 @@ public static class ElementFactory
     {
         var props = new ParagraphProperties();
 
+        // Direction first: it decides how left/right alignment is written
+        if (style.TryGetProperty("bidi", out var bidi) && bidi.GetBoolean())
+            props.BiDi = new BiDi();
+        else if (!style.TryGetProperty("bidi", out _) &&
+                 style.TryGetProperty("language", out var language) &&
+                 BidiHelper.IsRtlLanguage(language.GetString() ?? ""))
+            props.BiDi = new BiDi();
+
         if (style.TryGetProperty("alignment", out var align))
         {
-            var justification = align.GetString()?.ToLowerInvariant() switch
+            props.Justification = new Justification
             {
-                "left" => JustificationValues.Left,
-                "center" => JustificationValues.Center,
-                "right" => JustificationValues.Right,
-                "justify" => JustificationValues.Both,
-                _ => JustificationValues.Left
+                Val = BidiHelper.Justification(align.GetString(), BidiHelper.IsBidi(props))
             };
-            props.Justification = new Justification { Val = justification };
         }
 
         if (style.TryGetProperty("style", out var styleProp))
@@ -66,7 +69,8 @@ public static class ElementFactory
         // Paragraph spacing
         if (style.TryGetProperty("spacing_before", out var spaceBefore) ||
             style.TryGetProperty("spacing_after", out var spaceAfter) ||
-            style.TryGetProperty("line_spacing", out var lineSpacing))
+            style.TryGetProperty("line_spacing", out var lineSpacing) ||
+            style.TryGetProperty("line_spacing_rule", out _))
         {
             var spacing = new SpacingBetweenLines();
             if (style.TryGetProperty("spacing_before", out spaceBefore))
@@ -75,6 +79,8 @@ public static class ElementFactory
                 spacing.After = spaceAfter.GetInt32().ToString();
             if (style.TryGetProperty("line_spacing", out lineSpacing))
                 spacing.Line = lineSpacing.GetInt32().ToString();
+            if (style.TryGetProperty("line_spacing_rule", out var lineRule))
+                spacing.LineRule = ParseLineSpacingRule(lineRule.GetString());
             props.SpacingBetweenLines = spacing;
         }
 
@@ -165,55 +171,49 @@ public static class ElementFactory
             props.FontSize = new FontSize { Val = halfPoints };
         }
 
-        if (style.TryGetProperty("font_name", out var fontName))
-        {
-            props.RunFonts = new RunFonts { Ascii = fontName.GetString() };
-        }
+        StyleHelper.MergeRunFonts(props, style);
 
         if (style.TryGetProperty("color", out var color))
         {
             props.Color = new Color { Val = color.GetString() };
         }
 
-        if (style.TryGetProperty("highlight", out var highlight))
+        if (style.TryGetProperty("highlight", out var highlight) &&
+            highlight.ValueKind == JsonValueKind.String &&
+            highlight.GetString()!.Trim().ToLowerInvariant() is not ("none" or ""))
         {
-            props.Highlight = new Highlight
-            {
-                Val = highlight.GetString()?.ToLowerInvariant() switch
-                {
-                    "yellow" => HighlightColorValues.Yellow,
-                    "green" => HighlightColorValues.Green,
-                    "cyan" => HighlightColorValues.Cyan,
-                    "magenta" => HighlightColorValues.Magenta,
-                    "blue" => HighlightColorValues.Blue,
-                    "red" => HighlightColorValues.Red,
-                    "dark_blue" => HighlightColorValues.DarkBlue,
-                    "dark_cyan" => HighlightColorValues.DarkCyan,
-                    "dark_green" => HighlightColorValues.DarkGreen,
-                    "dark_magenta" => HighlightColorValues.DarkMagenta,
-                    "dark_red" => HighlightColorValues.DarkRed,
-                    "dark_yellow" => HighlightColorValues.DarkYellow,
-                    "light_gray" => HighlightColorValues.LightGray,
-                    "dark_gray" => HighlightColorValues.DarkGray,
-                    "black" => HighlightColorValues.Black,
-                    _ => HighlightColorValues.Yellow
-                }
-            };
+            props.Highlight = new Highlight { Val = StyleHelper.HighlightColor(highlight.GetString()) };
         }
 
         if (style.TryGetProperty("vertical_align", out var vertAlign))
         {
             props.VerticalTextAlignment = new VerticalTextAlignment
             {
-                Val = vertAlign.GetString()?.ToLowerInvariant() switch
-                {
-                    "superscript" => VerticalPositionValues.Superscript,
-                    "subscript" => VerticalPositionValues.Subscript,
-                    _ => VerticalPositionValues.Baseline
-                }
+                Val = StyleHelper.VerticalPosition(vertAlign.GetString())
             };
         }
 
+        if (style.TryGetProperty("small_caps", out var smallCaps) && smallCaps.ValueKind == JsonValueKind.True)
+            props.SmallCaps = new SmallCaps();
+
+        if (style.TryGetProperty("letter_spacing", out var letterSpacing) &&
+            letterSpacing.ValueKind == JsonValueKind.Number)
+        {
+            // Letter spacing in twentieths of a point
+            props.Spacing = new Spacing { Val = StyleHelper.LetterSpacingTwips(letterSpacing.GetDouble()) };
+        }
+
+        if (style.TryGetProperty("code", out var code) && code.ValueKind == JsonValueKind.True)
+            CodeHelper.ApplyInlineCode(props, true);
+
+        if (style.TryGetProperty("language", out var language))
+            BidiHelper.SetLanguage(props, language.GetString() ?? "");
+
+        if (style.TryGetProperty("rtl", out var rtl))
+            BidiHelper.SetRtl(props, rtl.GetBoolean());
+        else
+            BidiHelper.SyncComplexScript(props);
+
         return props;
     }
 
@@ -317,11 +317,55 @@ public static class ElementFactory
         }
 
         PopulateRuns(paragraph, value);
+        ApplyParagraphLanguage(paragraph, value);
+        ApplyParagraphFont(paragraph, value);
 
         ElementIdManager.AssignId(paragraph);
         return paragraph;
     }
 
+    /// <summary>
+    /// A paragraph-level "language" also applies to the runs that don't set their own.
+    /// </summary>
+    private static void ApplyParagraphLanguage(Paragraph paragraph, JsonElement value)
+    {
+        if ((value.TryGetProperty("properties", out var props) || value.TryGetProperty("style", out props)) &&
+            props.ValueKind == JsonValueKind.Object &&
+            props.TryGetProperty("language", out var language))
+        {
+            BidiHelper.ApplyLanguage(paragraph, language.GetString() ?? "");
+        }
+    }
+
+    /// <summary>
+    /// A paragraph-level "font_name" / "font_name_east_asia" in "properties" applies to
+    /// the runs that don't set their own, and to the paragraph mark.
+    /// </summary>
+    private static void ApplyParagraphFont(Paragraph paragraph, JsonElement value)
+    {
+        if (!value.TryGetProperty("properties", out var props) || props.ValueKind != JsonValueKind.Object ||
+            !(props.TryGetProperty("font_name", out _) || props.TryGetProperty("font_name_east_asia", out _)))
+            return;
+
+        foreach (var run in paragraph.Elements<Run>())
+        {
+            var runProps = run.RunProperties ?? run.PrependChild(new RunProperties());
+            if (runProps.RunFonts is null)
+            {
+                StyleHelper.MergeRunFonts(runProps, props);
+                BidiHelper.SyncComplexScript(runProps);
+            }
+        }
+
+        var fonts = new RunProperties();
+        StyleHelper.MergeRunFonts(fonts, props);
+        if (fonts.RunFonts is null)
+            return;
+        var paragraphProps = paragraph.ParagraphProperties ??= new ParagraphProperties();
+        var markProps = paragraphProps.ParagraphMarkRunProperties ??= new ParagraphMarkRunProperties();
+        markProps.PrependChild((RunFonts)fonts.RunFonts.CloneNode(true));
+    }
+
     private static Paragraph CreateHeading(JsonElement value)
     {
         var level = value.TryGetProperty("level", out var lvl) ? lvl.GetInt32() : 1;
@@ -348,11 +392,15 @@ public static class ElementFactory
                 paragraphProps.Indentation = (Indentation)extraProps.Indentation.CloneNode(true);
             if (extraProps.Tabs is not null)
                 paragraphProps.Tabs = (Tabs)extraProps.Tabs.CloneNode(true);
+            if (extraProps.BiDi is not null)
+                paragraphProps.BiDi = (BiDi)extraProps.BiDi.CloneNode(true);
         }
 
         paragraph.ParagraphProperties = paragraphProps;
 
         PopulateRuns(paragraph, value);
+        ApplyParagraphLanguage(paragraph, value);
+        ApplyParagraphFont(paragraph, value);
 
         ElementIdManager.AssignId(paragraph);
         return paragraph;
@@ -464,18 +512,16 @@ public static class ElementFactory
             tblProps.TableStyle = new TableStyle { Val = tableStyle.GetString() };
         }
 
+        // Right-to-left column order
+        if (value.TryGetProperty("rtl", out var rtl) && rtl.GetBoolean())
+            tblProps.BiDiVisual = new BiDiVisual();
+
         // Table alignment
         if (value.TryGetProperty("table_alignment", out var tblAlign))
         {
             tblProps.TableJustification = new TableJustification
             {
-                Val = tblAlign.GetString()?.ToLowerInvariant() switch
-                {
-                    "left" => TableRowAlignmentValues.Left,
-                    "center" => TableRowAlignmentValues.Center,
-                    "right" => TableRowAlignmentValues.Right,
-                    _ => TableRowAlignmentValues.Left
-                }
+                Val = BidiHelper.TableAlignment(tblAlign.GetString(), BidiHelper.IsRtl(tblProps))
             };
         }
 
@@ -548,8 +594,10 @@ public static class ElementFactory
                 new TableRowHeight { Val = (uint)height.GetInt32() });
         }
 
-        if (rowJson.TryGetProperty("is_header", out var isH) && isH.GetBoolean())
+        var isHeader = rowJson.TryGetProperty("is_header", out var isH) && isH.GetBoolean();
+        if (isHeader)
         {
+            // Mark as header row (repeats on page breaks)
             var rowProps = tableRow.TableRowProperties ?? new TableRowProperties();
             rowProps.AppendChild(new TableHeader());
             tableRow.TableRowProperties = rowProps;
@@ -561,13 +609,15 @@ public static class ElementFactory
             {
                 if (cell.ValueKind == JsonValueKind.Object)
                 {
-                    tableRow.AppendChild(CreateRichTableCell(cell, false));
+                    tableRow.AppendChild(CreateRichTableCell(cell, isHeader));
                 }
                 else
                 {
                     var tc = new TableCell();
                     var p = new Paragraph();
                     var r = new Run();
+                    if (isHeader)
+                        r.RunProperties = new RunProperties { Bold = new Bold() };
                     r.AppendChild(new Text(cell.GetString() ?? cell.ToString())
                         { Space = SpaceProcessingModeValues.Preserve });
                     ElementIdManager.AssignId(r);
@@ -653,6 +703,13 @@ public static class ElementFactory
             hasProps = true;
         }
 
+        if (cellJson.TryGetProperty("text_direction", out var textDir) &&
+            ParseTextDirection(textDir.GetString()) is { } direction)
+        {
+            tcProps.TextDirection = new TextDirection { Val = direction };
+            hasProps = true;
+        }
+
         if (hasProps)
             tc.AppendChild(tcProps);
 
@@ -745,6 +802,35 @@ public static class ElementFactory
         };
     }
 
+    /// <summary>
+    /// Cell text direction: "top_to_bottom" (rotated 90° clockwise), "bottom_to_top"
+    /// (rotated 90° counter-clockwise), or null for horizontal text, which is the default.
+    /// </summary>
+    internal static TextDirectionValues? ParseTextDirection(string? direction)
+    {
+        return direction?.ToLowerInvariant() switch
+        {
+            "top_to_bottom" or "vertical" or "tbrl" => TextDirectionValues.TopToBottomRightToLeft,
+            "bottom_to_top" or "btlr" => TextDirectionValues.BottomToTopLeftToRight,
+            _ => null
+        };
+    }
+
+    /// <summary>
+    /// How line_spacing is read: "auto" (240ths of a line, so 360 is 1.5 lines),
+    /// "exact" or "at_least" (twips).
+    /// </summary>
+    internal static LineSpacingRuleValues ParseLineSpacingRule(string? rule)
+    {
+        return rule?.ToLowerInvariant() switch
+        {
+            "auto" => LineSpacingRuleValues.Auto,
+            "exact" => LineSpacingRuleValues.Exact,
+            "at_least" or "atleast" => LineSpacingRuleValues.AtLeast,
+            _ => throw new ArgumentException($"Unknown line_spacing_rule '{rule}'. Use auto, exact or at_least.")
+        };
+    }
+
     private static Paragraph CreateImage(JsonElement value, MainDocumentPart mainPart)
     {
         var imagePath = value.GetProperty("path").GetString()
@@ -752,6 +838,7 @@ public static class ElementFactory
         var width = value.TryGetProperty("width", out var w) ? w.GetInt64() : 200;
         var height = value.TryGetProperty("height", out var h) ? h.GetInt64() : 150;
         var alt = value.TryGetProperty("alt", out var a) ? a.GetString() ?? "" : "";
+        var title = value.TryGetProperty("title", out var t) ? t.GetString() : null;
 
         if (!File.Exists(imagePath))
             throw new FileNotFoundException($"Image file not found: {imagePath}");
@@ -768,7 +855,7 @@ public static class ElementFactory
         };
 
         // Add image part
-        var imagePart = mainPart.AddImagePart(imageType);
+        var imagePart = mainPart.AddImagePart(imageType, DeterministicOutput.NextRelationshipId(mainPart));
         using (var stream = File.OpenRead(imagePath))
         {
             imagePart.FeedData(stream);
@@ -780,6 +867,18 @@ public static class ElementFactory
         var emuWidth = width * 9525;
         var emuHeight = height * 9525;
 
+        // docPr ids must be unique across the document; name/descr/title are what
+        // screen readers and accessibility checkers read.
+        var drawingId = NextDrawingId(mainPart);
+        var name = value.TryGetProperty("name", out var n) && !string.IsNullOrEmpty(n.GetString())
+            ? n.GetString()!
+            : $"Picture {drawingId}";
+        var escapedName = System.Security.SecurityElement.Escape(name);
+        var escapedAlt = System.Security.SecurityElement.Escape(alt);
+        var titleAttribute = title is null
+            ? ""
+            : $@" title=""{System.Security.SecurityElement.Escape(title)}""";
+
         // Build the drawing element using raw XML (Open XML SDK's drawing API is verbose)
         var drawingXml = $@"<w:drawing xmlns:w=""http://schemas.openxmlformats.org/wordprocessingml/2006/main""
             xmlns:wp=""http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing""
@@ -788,12 +887,12 @@ public static class ElementFactory
             xmlns:pic=""http://schemas.openxmlformats.org/drawingml/2006/picture"">
             <wp:inline distT=""0"" distB=""0"" distL=""0"" distR=""0"">
                 <wp:extent cx=""{emuWidth}"" cy=""{emuHeight}""/>
-                <wp:docPr id=""1"" name=""Image"" descr=""{System.Security.SecurityElement.Escape(alt)}""/>
+                <wp:docPr id=""{drawingId}"" name=""{escapedName}"" descr=""{escapedAlt}""{titleAttribute}/>
                 <a:graphic>
                     <a:graphicData uri=""http://schemas.openxmlformats.org/drawingml/2006/picture"">
                         <pic:pic>
                             <pic:nvPicPr>
-                                <pic:cNvPr id=""0"" name=""Image""/>
+                                <pic:cNvPr id=""0"" name=""{escapedName}"" descr=""{escapedAlt}""{titleAttribute}/>
                                 <pic:cNvPicPr/>
                             </pic:nvPicPr>
                             <pic:blipFill>
@@ -824,30 +923,38 @@ public static class ElementFactory
         return paragraph;
     }
 
+    /// <summary>
+    /// Next free wp:docPr id, counting drawings in the body, headers and footers.
+    /// </summary>
+    private static uint NextDrawingId(MainDocumentPart mainPart)
+    {
+        var roots = new List<OpenXmlElement?> { mainPart.Document };
+        roots.AddRange(mainPart.HeaderParts.Select(h => (OpenXmlElement?)h.Header));
+        roots.AddRange(mainPart.FooterParts.Select(f => (OpenXmlElement?)f.Footer));
+
+        uint max = 0;
+        foreach (var root in roots)
+        {
+            if (root is null)
+                continue;
+            foreach (var docPr in root.Descendants<DocumentFormat.OpenXml.Drawing.Wordprocessing.DocProperties>())
+                max = Math.Max(max, docPr.Id?.Value ?? 0);
+        }
+        return max + 1;
+    }
+
     private static Paragraph CreateHyperlink(JsonElement value, MainDocumentPart mainPart)
     {
-        var url = value.GetProperty("url").GetString()
-            ?? throw new ArgumentException("Hyperlink must have a 'url' field.");
-        var text = value.TryGetProperty("text", out var t) ? t.GetString() ?? url : url;
+        var style = HyperlinkHelper.ResolveStyle(value, mainPart);
+        var hyperlink = HyperlinkHelper.CreateLink(value, mainPart);
+        var text = value.TryGetProperty("text", out var t) ? t.GetString() : null;
+        text ??= value.TryGetProperty("url", out var u) ? u.GetString()
+            : value.TryGetProperty("heading", out var hd) ? hd.GetString()
+            : value.GetProperty("bookmark").GetString();
 
-        // Add hyperlink relationship
-        var rel = mainPart.AddHyperlinkRelationship(new Uri(url), true);
+        hyperlink.AppendChild(HyperlinkHelper.CreateRun(text ?? "", style));
 
         var paragraph = new Paragraph();
-        var hyperlinkRun = new Run(
-                new RunProperties(
-                    new RunStyle { Val = "Hyperlink" },
-                    new Color { Val = "0563C1" },
-                    new Underline { Val = UnderlineValues.Single }
-                ),
-                new Text(text) { Space = SpaceProcessingModeValues.Preserve }
-            );
-        ElementIdManager.AssignId(hyperlinkRun);
-        var hyperlink = new Hyperlink(hyperlinkRun)
-        {
-            Id = rel.Id
-        };
-        ElementIdManager.AssignId(hyperlink);
         paragraph.AppendChild(hyperlink);
         ElementIdManager.AssignId(paragraph);
 
@@ -863,88 +970,105 @@ public static class ElementFactory
         return paragraph;
     }
 
+    /// <summary>
+    /// The break paragraph only; its layout depends on where it lands and is
+    /// completed by <see cref="SectionHelper.CompleteSectionBreak"/> once inserted.
+    /// </summary>
     private static Paragraph CreateSectionBreak(JsonElement value)
     {
-        var type = value.TryGetProperty("break_type", out var bt)
-            ? bt.GetString() ?? "nextPage"
-            : "nextPage";
-
-        var sectionType = type.ToLowerInvariant() switch
-        {
-            "nextpage" or "next_page" => SectionMarkValues.NextPage,
-            "continuous" => SectionMarkValues.Continuous,
-            "evenpage" or "even_page" => SectionMarkValues.EvenPage,
-            "oddpage" or "odd_page" => SectionMarkValues.OddPage,
-            _ => SectionMarkValues.NextPage
-        };
-
         var paragraph = new Paragraph(
             new ParagraphProperties(
                 new SectionProperties(
-                    new SectionType { Val = sectionType })));
+                    new SectionType { Val = SectionHelper.ParseBreakType(value) })));
         ElementIdManager.AssignId(paragraph);
         return paragraph;
     }
 
     private static OpenXmlElement CreateList(JsonElement value)
     {
-        // Lists in OOXML are just paragraphs with numbering properties
-        // We return a container that holds multiple paragraphs
-        var items = value.GetProperty("items");
-        var ordered = value.TryGetProperty("ordered", out var o) && o.GetBoolean();
-
-        // For simplicity, we return the first item as a paragraph
-        // The patch engine handles multiple items by inserting each
-        if (items.ValueKind != JsonValueKind.Array || items.GetArrayLength() == 0)
-            throw new ArgumentException("List must have at least one item.");
-
-        var paragraph = new Paragraph();
-        paragraph.ParagraphProperties = new ParagraphProperties
-        {
-            ParagraphStyleId = new ParagraphStyleId
-            {
-                Val = ordered ? "ListNumber" : "ListBullet"
-            }
-        };
-
-        var text = items[0].GetString() ?? "";
-        var run = new Run();
-        run.AppendChild(new Text(text) { Space = SpaceProcessingModeValues.Preserve });
-        ElementIdManager.AssignId(run);
-        paragraph.AppendChild(run);
-        ElementIdManager.AssignId(paragraph);
-
-        return paragraph;
+        // Lists in OOXML are just paragraphs with numbering properties.
+        // We return the first paragraph of the first item; the patch engine
+        // inserts the whole list through CreateListItems.
+        return CreateListItems(value)[0];
     }
 
     /// <summary>
     /// Create multiple list item paragraphs from a list value.
+    /// Items are strings or objects: {"text", "style"} or {"runs": [...]} for the item,
+    /// "paragraphs" for continuation paragraphs (strings or the same objects) at the
+    /// item's indent, and "checked" (true/false) to start the item with a checkbox.
     /// </summary>
     public static List<OpenXmlElement> CreateListItems(JsonElement value)
     {
         var items = value.GetProperty("items");
+        if (items.ValueKind != JsonValueKind.Array || items.GetArrayLength() == 0)
+            throw new ArgumentException("List must have at least one item.");
+
         var ordered = value.TryGetProperty("ordered", out var o) && o.GetBoolean();
         var styleName = ordered ? "ListNumber" : "ListBullet";
         var result = new List<OpenXmlElement>();
 
         foreach (var item in items.EnumerateArray())
         {
-            var paragraph = new Paragraph();
-            paragraph.ParagraphProperties = new ParagraphProperties
+            var paragraph = CreateListParagraph(item, styleName);
+
+            if (item.ValueKind == JsonValueKind.Object &&
+                item.TryGetProperty("checked", out var check) &&
+                check.ValueKind is JsonValueKind.True or JsonValueKind.False)
             {
-                ParagraphStyleId = new ParagraphStyleId { Val = styleName }
-            };
+                paragraph.InsertAfter(CreateCheckboxRun(check.GetBoolean()), paragraph.ParagraphProperties);
+            }
+
+            result.Add(paragraph);
+
+            if (item.ValueKind == JsonValueKind.Object &&
+                item.TryGetProperty("paragraphs", out var more) && more.ValueKind == JsonValueKind.Array)
+            {
+                foreach (var continuation in more.EnumerateArray())
+                    result.Add(CreateListParagraph(continuation, "ListContinue"));
+            }
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// A paragraph of a list item: a plain string, or {"text", "style"} / {"runs"}.
+    /// </summary>
+    private static Paragraph CreateListParagraph(JsonElement item, string styleName)
+    {
+        var paragraph = new Paragraph();
+        paragraph.ParagraphProperties = new ParagraphProperties
+        {
+            ParagraphStyleId = new ParagraphStyleId { Val = styleName }
+        };
 
+        if (item.ValueKind == JsonValueKind.Object)
+        {
+            PopulateRuns(paragraph, item);
+        }
+        else
+        {
             var text = item.GetString() ?? item.ToString();
             var run = new Run();
             run.AppendChild(new Text(text) { Space = SpaceProcessingModeValues.Preserve });
             ElementIdManager.AssignId(run);
             paragraph.AppendChild(run);
-            ElementIdManager.AssignId(paragraph);
-
-            result.Add(paragraph);
         }
 
-        return result;
+        ElementIdManager.AssignId(paragraph);
+        return paragraph;
+    }
+
+    /// <summary>
+    /// The ☐ / ☒ glyph (and a space) opening a checkbox list item.
+    /// </summary>
+    private static Run CreateCheckboxRun(bool isChecked)
+    {
+        var run = new Run(
+            new RunProperties(new RunFonts { Ascii = "Segoe UI Symbol", HighAnsi = "Segoe UI Symbol" }),
+            new Text(isChecked ? "\u2612 " : "\u2610 ") { Space = SpaceProcessingModeValues.Preserve });
+        ElementIdManager.AssignId(run);
+        return run;
     }
 }
@@ -31,7 +31,7 @@ public static class ElementIdManager
         string id;
         do
         {
-            id = Random.Shared.Next(1, int.MaxValue).ToString("X8");
+            id = DeterministicOutput.Next(1, int.MaxValue).ToString("X8");
         } while (existing is not null && !existing.Add(id));
 
         return id;
@@ -127,6 +127,31 @@ public static class ElementIdManager
         }
     }
 
+    /// <summary>
+    /// Replace all IDs under <paramref name="root"/> with fresh ones that don't
+    /// collide with any ID in <paramref name="target"/>. Used before importing
+    /// content from another document.
+    /// </summary>
+    public static void ReassignIds(OpenXmlElement root, WordprocessingDocument target)
+    {
+        foreach (var element in root.Descendants())
+        {
+            element.RemoveAttribute("id", DmcpNamespace);
+            if (element is Paragraph p)
+            {
+                p.ParagraphId = null;
+                p.TextId = null;
+            }
+            else if (element is TableRow tr)
+            {
+                tr.ParagraphId = null;
+                tr.TextId = null;
+            }
+        }
+
+        AssignIdsInPart(root, CollectExistingIds(target));
+    }
+
     /// <summary>
     /// Read the dmcp:id from any element. Falls back to w14:paraId for Paragraph/TableRow.
     /// </summary>
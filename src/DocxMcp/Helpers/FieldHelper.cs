@@ -0,0 +1,134 @@
+using System.Globalization;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A field ready to insert: its instruction and the cached result shown until
+/// the field is updated.
+/// </summary>
+public sealed record FieldSpec(string Instruction, string Result, string Language);
+
+/// <summary>
+/// Auto-updating DATE, TIME, SAVEDATE, CREATEDATE, FILENAME and TOC fields.
+///
+/// Date pictures use Word's notation (d, dd, ddd, dddd, M, MM, MMM, MMMM, yy,
+/// yyyy, H, HH, h, hh, mm, ss, AM/PM) and are written to the \@ switch. Word
+/// formats month and day names in the language of the field's runs, so the
+/// runs are tagged with the locale. The cached result is computed here with the
+/// same picture and locale, for viewers that never refresh fields. A TOC field has
+/// no meaningful cached result; Word builds it when fields are updated.
+/// </summary>
+public static partial class FieldHelper
+{
+    public static readonly string[] Kinds = ["date", "time", "savedate", "createdate", "filename", "toc"];
+
+    /// <summary>
+    /// Build the field for <paramref name="kind"/>. Without a format, dates use the
+    /// locale's short date pattern and times its short time pattern. For filename
+    /// the only format is "path", which includes the folder. For toc the format is the
+    /// range of heading levels listed, "1-3" by default.
+    /// </summary>
+    public static FieldSpec Create(WordprocessingDocument doc, string kind, string? format, string? locale,
+        string? fileName, DateTime now)
+    {
+        var language = locale ?? DocumentLanguage(doc) ?? "en-US";
+        CultureInfo culture;
+        try
+        {
+            culture = CultureInfo.GetCultureInfo(language);
+        }
+        catch (CultureNotFoundException)
+        {
+            throw new ArgumentException($"Unknown locale '{language}'.");
+        }
+
+        switch (kind.ToLowerInvariant())
+        {
+            case "date":
+                return DateField("DATE", format ?? ToWordPicture(culture.DateTimeFormat.ShortDatePattern), now, culture, language);
+            case "time":
+                return DateField("TIME", format ?? ToWordPicture(culture.DateTimeFormat.ShortTimePattern), now, culture, language);
+            case "savedate":
+                return DateField("SAVEDATE", format ?? ToWordPicture(culture.DateTimeFormat.ShortDatePattern),
+                    doc.PackageProperties.Modified ?? now, culture, language);
+            case "createdate":
+                return DateField("CREATEDATE", format ?? ToWordPicture(culture.DateTimeFormat.ShortDatePattern),
+                    doc.PackageProperties.Created ?? now, culture, language);
+            case "filename":
+                if (format is not null && !format.Equals("path", StringComparison.OrdinalIgnoreCase))
+                    throw new ArgumentException("The only format for filename fields is 'path'.");
+                var name = fileName ?? "Document.docx";
+                return format is null
+                    ? new FieldSpec("FILENAME", Path.GetFileName(name), language)
+                    : new FieldSpec("FILENAME \\p", name, language);
+            case "toc":
+                var levels = format ?? "1-3";
+                var range = TocLevels().Match(levels);
+                if (!range.Success || range.Groups[1].Value[0] > range.Groups[2].Value[0])
+                    throw new ArgumentException($"Invalid heading levels '{levels}'. Expected a range like \"1-3\".");
+                return new FieldSpec($"TOC \\o \"{levels}\" \\h \\z \\u",
+                    "Update fields to build the table of contents.", language);
+            default:
+                throw new ArgumentException($"Unknown field kind '{kind}'. Expected one of: {string.Join(", ", Kinds)}.");
+        }
+    }
+
+    /// <summary>
+    /// Runs of a complex field (begin, instruction, separate, cached result, end),
+    /// all tagged with the field's language.
+    /// </summary>
+    public static List<Run> CreateRuns(FieldSpec field)
+    {
+        return
+        [
+            FieldRun(field.Language, new FieldChar { FieldCharType = FieldCharValues.Begin }),
+            FieldRun(field.Language, new FieldCode($" {field.Instruction} ") { Space = SpaceProcessingModeValues.Preserve }),
+            FieldRun(field.Language, new FieldChar { FieldCharType = FieldCharValues.Separate }),
+            FieldRun(field.Language, new Text(field.Result) { Space = SpaceProcessingModeValues.Preserve }),
+            FieldRun(field.Language, new FieldChar { FieldCharType = FieldCharValues.End })
+        ];
+    }
+
+    /// <summary>
+    /// Word date picture for a .NET date pattern ("tt" becomes "AM/PM").
+    /// </summary>
+    public static string ToWordPicture(string pattern) => pattern.Replace("tt", "AM/PM");
+
+    private static FieldSpec DateField(string name, string picture, DateTime value, CultureInfo culture, string language)
+    {
+        var dotnet = AmPm().Replace(picture, "tt");
+        string result;
+        try
+        {
+            result = value.ToString(dotnet, culture);
+        }
+        catch (FormatException)
+        {
+            throw new ArgumentException($"Invalid date format '{picture}'.");
+        }
+        return new FieldSpec($"{name} \\@ \"{picture}\"", result, language);
+    }
+
+    private static string? DocumentLanguage(WordprocessingDocument doc) =>
+        doc.MainDocumentPart?.StyleDefinitionsPart?.Styles?.DocDefaults?
+            .RunPropertiesDefault?.RunPropertiesBaseStyle?.GetFirstChild<Languages>()?.Val?.Value;
+
+    private static Run FieldRun(string language, OpenXmlElement content)
+    {
+        var props = new RunProperties();
+        BidiHelper.SetLanguage(props, language);
+        var run = new Run(props, content);
+        ElementIdManager.AssignId(run);
+        return run;
+    }
+
+    [GeneratedRegex("AM/PM", RegexOptions.IgnoreCase)]
+    private static partial Regex AmPm();
+
+    [GeneratedRegex("^([1-9])-([1-9])$")]
+    private static partial Regex TocLevels();
+}
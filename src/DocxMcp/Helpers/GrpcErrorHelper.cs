@@ -11,9 +11,17 @@ public static class GrpcErrorHelper
 {
     public static McpException Wrap(RpcException ex, string context)
     {
+        var kind = ex.Trailers.GetValue("x-storage-error");
         var message = ex.StatusCode switch
         {
             StatusCode.Unavailable => $"Storage backend unavailable: {context}. The service may be restarting.",
+            StatusCode.ResourceExhausted when kind == "quota_exceeded" =>
+                $"Storage quota exceeded: {context}. Free up space or raise the quota — {ex.Status.Detail}",
+            StatusCode.ResourceExhausted =>
+                $"Storage backend is rate limiting: {context}. Retry in {RetryAfter(ex)}.",
+            StatusCode.Unauthenticated =>
+                $"Storage credentials were rejected: {context}. Reconnect the account — {ex.Status.Detail}",
+            StatusCode.FailedPrecondition => $"Storage precondition failed: {context} — {ex.Status.Detail}",
             StatusCode.DeadlineExceeded => $"Storage operation timed out: {context}.",
             StatusCode.NotFound => $"Not found: {context}.",
             StatusCode.Internal => $"Storage internal error: {context} — {ex.Status.Detail}",
@@ -22,6 +30,11 @@ public static class GrpcErrorHelper
         return new McpException(message, ex);
     }
 
+    private static string RetryAfter(RpcException ex) =>
+        long.TryParse(ex.Trailers.GetValue("retry-after-ms"), out var ms)
+            ? $"{Math.Ceiling(ms / 1000.0)}s"
+            : "a moment";
+
     public static McpException WrapNotFound(string docId)
     {
         return new McpException($"Document '{docId}' not found. Use document_list to see open sessions.");
@@ -0,0 +1,334 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A heading level change.
+/// </summary>
+public sealed record HeadingChange(string Id, string Text, int From, int To);
+
+/// <summary>
+/// The bookmark a heading can be linked to by, and whether it was just added.
+/// </summary>
+public sealed record HeadingAnchor(string? Id, int Level, string Text, string Bookmark, bool Added);
+
+/// <summary>
+/// Structural heading operations: promote/demote a range of headings (with
+/// their sub-headings), fix skipped levels, rename headings and move whole
+/// sections. Levels are changed through the HeadingN paragraph style and any
+/// direct outline level, so the navigation pane and the table of contents follow.
+/// </summary>
+public static class HeadingHelper
+{
+    /// <summary>
+    /// Level changes that shift <paramref name="targets"/> by <paramref name="delta"/>.
+    /// With <paramref name="withSubheadings"/>, the headings under each target
+    /// move along so the outline keeps its shape.
+    /// </summary>
+    public static List<HeadingChange> Shift(Body body, IEnumerable<Paragraph> targets, int delta, bool withSubheadings)
+    {
+        var headings = body.Elements<Paragraph>().Where(p => p.GetHeadingLevel() > 0).ToList();
+        var selected = new HashSet<Paragraph>();
+
+        foreach (var target in targets)
+        {
+            var index = headings.IndexOf(target);
+            if (index < 0) continue;
+            selected.Add(target);
+
+            if (!withSubheadings) continue;
+            var level = target.GetHeadingLevel();
+            for (int i = index + 1; i < headings.Count && headings[i].GetHeadingLevel() > level; i++)
+                selected.Add(headings[i]);
+        }
+
+        var changes = new List<HeadingChange>();
+        foreach (var heading in headings.Where(selected.Contains))
+        {
+            var from = heading.GetHeadingLevel();
+            var to = from + delta;
+            if (to is < 1 or > 9)
+            {
+                throw new ArgumentException(
+                    $"Cannot {(delta < 0 ? "promote" : "demote")} '{heading.InnerText}' beyond Heading{from}.");
+            }
+            changes.Add(new HeadingChange(IdOf(heading), heading.InnerText, from, to));
+        }
+        return changes;
+    }
+
+    /// <summary>
+    /// Level changes that remove skipped levels: each heading becomes at most one
+    /// level below the heading it falls under (Heading1, Heading3 becomes
+    /// Heading1, Heading2), and top-level headings become Heading1. Relative
+    /// nesting is preserved.
+    /// </summary>
+    public static List<HeadingChange> Normalize(Body body)
+    {
+        var changes = new List<HeadingChange>();
+        var stack = new Stack<(int Original, int Normalized)>();
+
+        foreach (var heading in body.Elements<Paragraph>())
+        {
+            var level = heading.GetHeadingLevel();
+            if (level == 0) continue;
+
+            while (stack.Count > 0 && stack.Peek().Original >= level)
+                stack.Pop();
+
+            var normalized = stack.Count > 0 ? stack.Peek().Normalized + 1 : 1;
+            stack.Push((level, normalized));
+
+            if (normalized != level)
+                changes.Add(new HeadingChange(IdOf(heading), heading.InnerText, level, normalized));
+        }
+        return changes;
+    }
+
+    /// <summary>
+    /// Apply level changes by heading ID, and have Word refresh the table of
+    /// contents on open when the document has one.
+    /// </summary>
+    public static void Apply(WordprocessingDocument doc, IReadOnlyList<HeadingChange> changes)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var byId = body.Elements<Paragraph>()
+            .Where(p => ElementIdManager.GetId(p) is not null)
+            .ToDictionary(p => ElementIdManager.GetId(p)!, StringComparer.OrdinalIgnoreCase);
+
+        foreach (var change in changes)
+        {
+            if (!byId.TryGetValue(change.Id, out var heading)) continue;
+
+            var pPr = heading.ParagraphProperties ??= new ParagraphProperties();
+            var current = pPr.ParagraphStyleId?.Val?.Value ?? "Heading";
+            // Keep the style ID's spelling ("heading2" stays lower-case)
+            pPr.ParagraphStyleId = new ParagraphStyleId { Val = current[.."Heading".Length] + change.To };
+            if (pPr.OutlineLevel is not null)
+                pPr.OutlineLevel = new OutlineLevel { Val = change.To - 1 };
+        }
+
+        if (changes.Count > 0)
+            RefreshTableOfContents(doc);
+    }
+
+    /// <summary>
+    /// Replace the text of <paramref name="heading"/>. The first run keeps its
+    /// formatting and takes the new text; the other text runs are removed, while
+    /// bookmarks stay so links to the heading keep working.
+    /// </summary>
+    public static void Rename(WordprocessingDocument doc, Paragraph heading, string text)
+    {
+        var runs = heading.Descendants<Run>().Where(r => r.InnerText.Length > 0).ToList();
+        var first = runs.FirstOrDefault();
+        if (first is null)
+        {
+            first = new Run();
+            ElementIdManager.AssignId(first);
+            heading.AppendChild(first);
+        }
+
+        foreach (var content in first.ChildElements.Where(c => c is not RunProperties).ToList())
+            content.Remove();
+        first.AppendChild(new Text(text) { Space = SpaceProcessingModeValues.Preserve });
+
+        foreach (var run in runs.Skip(1))
+            run.Remove();
+
+        RefreshTableOfContents(doc);
+    }
+
+    /// <summary>
+    /// Top-level body elements of the section that starts at <paramref name="heading"/>:
+    /// the heading and everything up to the next heading of the same or a higher level.
+    /// </summary>
+    public static List<OpenXmlElement> SectionOf(Body body, Paragraph heading)
+    {
+        var level = heading.GetHeadingLevel();
+        var section = new List<OpenXmlElement> { heading };
+        for (var next = heading.NextSibling(); next is not null and not SectionProperties; next = next.NextSibling())
+        {
+            if (next is Paragraph p && p.GetHeadingLevel() > 0 && p.GetHeadingLevel() <= level)
+                break;
+            section.Add(next);
+        }
+        return section;
+    }
+
+    /// <summary>
+    /// Nearest heading at or above the top-level body element holding
+    /// <paramref name="element"/>, or null before the first heading (or outside the body).
+    /// </summary>
+    public static Paragraph? HeadingAbove(OpenXmlElement? element)
+    {
+        var block = element;
+        while (block?.Parent is not null and not Body)
+            block = block.Parent;
+        if (block?.Parent is not Body)
+            return null;
+
+        for (var current = block; current is not null; current = current.PreviousSibling())
+        {
+            if (current is Paragraph p && p.IsHeading())
+                return p;
+        }
+        return null;
+    }
+
+    /// <summary>
+    /// Move the section of the heading with ID <paramref name="id"/> in front of
+    /// the heading with ID <paramref name="beforeId"/>, or to the end of the
+    /// document when <paramref name="beforeId"/> is null. Numbered headings and
+    /// lists renumber on their own; the table of contents is refreshed on open.
+    /// </summary>
+    public static void MoveSection(WordprocessingDocument doc, string id, string? beforeId)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var heading = FindHeading(body, id);
+        var section = SectionOf(body, heading);
+
+        OpenXmlElement? before = null;
+        if (beforeId is not null)
+        {
+            before = FindHeading(body, beforeId);
+            if (section.Contains(before) && before != heading)
+                throw new ArgumentException($"Cannot move '{heading.InnerText}' into its own section.");
+            if (before == heading)
+                return;
+        }
+        else
+        {
+            before = body.Elements<SectionProperties>().LastOrDefault();
+        }
+
+        foreach (var element in section)
+            element.Remove();
+        foreach (var element in section)
+        {
+            if (before is not null)
+                before.InsertBeforeSelf(element);
+            else
+                body.AppendChild(element);
+        }
+
+        RefreshTableOfContents(doc);
+    }
+
+    /// <summary>
+    /// The bookmark of every top-level heading, adding one to headings without.
+    /// A heading keeps the bookmark it has (a _Toc bookmark, or one added earlier),
+    /// so links to it survive edits, renames and moves.
+    /// </summary>
+    public static List<HeadingAnchor> EnsureAnchors(Body body)
+    {
+        var anchors = new List<HeadingAnchor>();
+        foreach (var heading in body.Elements<Paragraph>().Where(p => p.IsHeading()).ToList())
+        {
+            var existing = BookmarkOf(heading);
+            anchors.Add(new HeadingAnchor(
+                ElementIdManager.GetId(heading),
+                heading.GetHeadingLevel(),
+                heading.InnerText,
+                existing ?? EnsureBookmark(body, heading),
+                existing is null));
+        }
+        return anchors;
+    }
+
+    /// <summary>
+    /// Name of a bookmark on <paramref name="heading"/>, adding a hidden "_HeadingN"
+    /// bookmark around its content when it has none.
+    /// </summary>
+    public static string EnsureBookmark(Body body, Paragraph heading)
+    {
+        if (BookmarkOf(heading) is { } existing)
+            return existing;
+
+        var id = NextBookmarkId(body);
+        var name = $"_Heading{id}";
+        AddBookmark(heading, id, name);
+        return name;
+    }
+
+    /// <summary>
+    /// Bookmark <paramref name="heading"/> as <paramref name="name"/> unless it
+    /// already has a bookmark by that name.
+    /// </summary>
+    public static void AddBookmark(Body body, Paragraph heading, string name)
+    {
+        if (heading.Elements<BookmarkStart>().Any(b => b.Name?.Value == name))
+            return;
+        AddBookmark(heading, NextBookmarkId(body), name);
+    }
+
+    private static void AddBookmark(Paragraph heading, int id, string name)
+    {
+        var start = new BookmarkStart { Id = id.ToString(), Name = name };
+        if (heading.ParagraphProperties is { } pPr)
+            heading.InsertAfter(start, pPr);
+        else
+            heading.PrependChild(start);
+        heading.AppendChild(new BookmarkEnd { Id = id.ToString() });
+    }
+
+    private static int NextBookmarkId(Body body) =>
+        body.Descendants<BookmarkStart>()
+            .Select(b => int.TryParse(b.Id?.Value, out var n) ? n : 0)
+            .DefaultIfEmpty(0)
+            .Max() + 1;
+
+    /// <summary>
+    /// Name of the first bookmark on <paramref name="heading"/>, other than Word's _GoBack.
+    /// </summary>
+    public static string? BookmarkOf(Paragraph heading) =>
+        heading.Elements<BookmarkStart>()
+            .FirstOrDefault(b => b.Name?.Value is { } name && name != "_GoBack")
+            ?.Name!.Value;
+
+    /// <summary>
+    /// Have Word refresh the table of contents on open when the document has one,
+    /// or a table of authorities.
+    /// </summary>
+    public static void RefreshTableOfContents(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body;
+        if (body is not null && HasTableOfContents(body))
+            RequestFieldUpdate(doc);
+    }
+
+    private static Paragraph FindHeading(Body body, string id) =>
+        body.Elements<Paragraph>().FirstOrDefault(p =>
+            p.IsHeading() && string.Equals(ElementIdManager.GetId(p), id, StringComparison.OrdinalIgnoreCase))
+        ?? throw new ArgumentException($"Heading '{id}' not found.");
+
+    private static string IdOf(Paragraph heading) =>
+        ElementIdManager.GetId(heading)
+        ?? throw new InvalidOperationException($"Heading '{heading.InnerText}' has no element ID.");
+
+    private static bool HasTableOfContents(Body body) =>
+        body.Descendants<FieldCode>().Any(f => IsTableField(f.Text))
+        || body.Descendants<SimpleField>().Any(f => f.Instruction?.Value is { } i && IsTableField(i));
+
+    private static bool IsTableField(string instruction)
+    {
+        var trimmed = instruction.TrimStart();
+        return trimmed.StartsWith("TOC", StringComparison.OrdinalIgnoreCase)
+               || trimmed.StartsWith("TOA", StringComparison.OrdinalIgnoreCase);
+    }
+
+    private static void RequestFieldUpdate(WordprocessingDocument doc)
+    {
+        var main = doc.MainDocumentPart!;
+        var settingsPart = main.DocumentSettingsPart
+            ?? main.AddNewPart<DocumentSettingsPart>(DeterministicOutput.NextRelationshipId(main));
+        settingsPart.Settings ??= new Settings();
+        if (settingsPart.Settings.GetFirstChild<UpdateFieldsOnOpen>() is null)
+            settingsPart.Settings.AppendChild(new UpdateFieldsOnOpen { Val = true });
+    }
+}
@@ -0,0 +1,252 @@
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A paragraph that looks like a heading, the level it would get and why.
+/// </summary>
+public sealed record InferredHeading(string Id, string Text, int Level, string Reason);
+
+/// <summary>
+/// The headings inferred for a document, and the font size (in points) of its body text.
+/// </summary>
+public sealed record HeadingInference(double BodyFontSize, List<InferredHeading> Headings);
+
+/// <summary>
+/// Heading inference for documents whose headings are formatted by hand (big or
+/// bold paragraphs in the Normal style) instead of using Heading styles, so
+/// outline, section and table of contents tools see nothing.
+///
+/// A top-level paragraph is a candidate when it is short, doesn't end like a
+/// sentence, and stands out from the body text: a larger font than the most
+/// common one, all bold, or an outline number ("2.", "2.3", "IV.", "B."). An
+/// outline number sets the level by its depth; otherwise the candidates'
+/// looks are ranked (larger first, then bold, then all caps) and each distinct
+/// look is one level.
+/// </summary>
+public static partial class HeadingInferenceHelper
+{
+    /// <summary>Longest text, in characters, of a paragraph taken for a heading.</summary>
+    public const int MaxHeadingLength = 120;
+
+    /// <summary>Most words in a paragraph taken for a heading.</summary>
+    public const int MaxHeadingWords = 15;
+
+    /// <summary>Font size (half-points) when neither the document nor its styles set one.</summary>
+    private const int DefaultFontSize = 22;
+
+    [GeneratedRegex(@"^(?<num>\d+(\.\d+)*)\.?\s+\S")]
+    private static partial Regex DecimalNumber();
+
+    [GeneratedRegex(@"^(?<num>[IVXLC]+|[A-Z])\.\s+\S")]
+    private static partial Regex LetterNumber();
+
+    private sealed record Look(int Size, bool Bold, bool Caps);
+
+    private sealed record Candidate(Paragraph Paragraph, string Id, string Text, Look Look, int? Depth);
+
+    /// <summary>
+    /// The paragraphs of the body that look like headings, in document order.
+    /// Paragraphs that already have a heading style are left out.
+    /// </summary>
+    public static HeadingInference Infer(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var styles = StyleMap(doc);
+        var defaultSize = ParseSize(doc.MainDocumentPart?.StyleDefinitionsPart?.Styles?.DocDefaults?
+            .RunPropertiesDefault?.RunPropertiesBaseStyle?.FontSize?.Val?.Value) ?? DefaultFontSize;
+
+        // The body text size is the size most characters have
+        var sizes = new Dictionary<int, int>();
+        var looks = new List<(Paragraph Paragraph, string Text, Look Look)>();
+        foreach (var paragraph in body.Elements<Paragraph>())
+        {
+            var runs = paragraph.Elements<Run>().Where(r => r.InnerText.Trim().Length > 0).ToList();
+            var text = string.Concat(paragraph.Descendants<Text>().Select(t => t.Text)).Trim();
+            if (runs.Count == 0 || text.Length == 0) continue;
+
+            var chain = StyleChain(styles, paragraph.ParagraphProperties?.ParagraphStyleId?.Val?.Value);
+            var runSizes = runs.Select(r => (Size: FontSize(r, chain, defaultSize), r.InnerText.Length)).ToList();
+            foreach (var (size, length) in runSizes)
+                sizes[size] = sizes.GetValueOrDefault(size) + length;
+
+            var look = new Look(
+                runSizes.MaxBy(s => s.Length).Size,
+                runs.All(r => IsBold(r, chain)),
+                (text.Any(char.IsLetter) && text == text.ToUpperInvariant()) || runs.All(r => r.RunProperties?.Caps is not null));
+            looks.Add((paragraph, text, look));
+        }
+        var bodySize = sizes.Count == 0 ? defaultSize : sizes.MaxBy(kv => kv.Value).Key;
+
+        var candidates = new List<Candidate>();
+        foreach (var (paragraph, text, look) in looks)
+        {
+            if (paragraph.IsHeading() || ElementIdManager.GetId(paragraph) is not string id) continue;
+            if (text.Length > MaxHeadingLength || text.Split(' ', StringSplitOptions.RemoveEmptyEntries).Length > MaxHeadingWords)
+                continue;
+            if (text[^1] is '.' or ',' or ';' or ':' or '!' or '?') continue;
+
+            var depth = NumberDepth(text);
+            var standsOut = look.Size > bodySize || look.Bold;
+            if (!standsOut) continue;
+            // A bold line at body size is only a heading when numbered or followed by body text
+            if (look.Size <= bodySize && depth is null && !FollowedByBodyText(paragraph, looks))
+                continue;
+            candidates.Add(new Candidate(paragraph, id, text, look, depth));
+        }
+
+        var ranks = candidates
+            .Where(c => c.Depth is null)
+            .Select(c => c.Look)
+            .Distinct()
+            .OrderByDescending(l => l.Size).ThenByDescending(l => l.Bold).ThenByDescending(l => l.Caps)
+            .Select((look, i) => (look, i))
+            .ToDictionary(x => x.look, x => x.i + 1);
+
+        var headings = candidates.Select(c =>
+        {
+            var level = Math.Clamp(c.Depth ?? ranks[c.Look], 1, 9);
+            return new InferredHeading(c.Id, c.Text, level, Reason(c, bodySize));
+        }).ToList();
+        return new HeadingInference(bodySize / 2.0, headings);
+    }
+
+    /// <summary>
+    /// Give the paragraphs of <paramref name="headings"/> the HeadingN style of their
+    /// level, adding the style to the document when it lacks it. Direct formatting is
+    /// kept, so the headings look the same. Returns the number of paragraphs changed.
+    /// </summary>
+    public static int Apply(WordprocessingDocument doc, IReadOnlyList<(string Id, int Level)> headings)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var byId = body.Elements<Paragraph>()
+            .Where(p => ElementIdManager.GetId(p) is not null)
+            .ToDictionary(p => ElementIdManager.GetId(p)!, StringComparer.OrdinalIgnoreCase);
+
+        var changed = 0;
+        foreach (var (id, level) in headings)
+        {
+            if (!byId.TryGetValue(id, out var paragraph)) continue;
+
+            EnsureHeadingStyle(doc, level);
+            var pPr = paragraph.ParagraphProperties ??= new ParagraphProperties();
+            pPr.ParagraphStyleId = new ParagraphStyleId { Val = $"Heading{level}" };
+            pPr.OutlineLevel = null;
+            changed++;
+        }
+
+        if (changed > 0)
+            HeadingHelper.RefreshTableOfContents(doc);
+        return changed;
+    }
+
+    private static void EnsureHeadingStyle(WordprocessingDocument doc, int level)
+    {
+        var styles = doc.MainDocumentPart?.StyleDefinitionsPart?.Styles;
+        var styleId = $"Heading{level}";
+        if (styles is null || styles.Elements<Style>().Any(s => s.StyleId?.Value == styleId))
+            return;
+
+        styles.AppendChild(new Style(
+            new StyleName { Val = $"heading {level}" },
+            new BasedOn { Val = "Normal" },
+            new NextParagraphStyle { Val = "Normal" },
+            new UIPriority { Val = 9 },
+            new PrimaryStyle(),
+            new StyleParagraphProperties(
+                new KeepNext(),
+                new OutlineLevel { Val = level - 1 }))
+        { Type = StyleValues.Paragraph, StyleId = styleId });
+    }
+
+    private static string Reason(Candidate candidate, int bodySize)
+    {
+        var parts = new List<string>();
+        if (candidate.Depth is not null)
+            parts.Add("numbered");
+        if (candidate.Look.Size != bodySize)
+            parts.Add($"{candidate.Look.Size / 2.0}pt");
+        if (candidate.Look.Bold)
+            parts.Add("bold");
+        if (candidate.Look.Caps)
+            parts.Add("all caps");
+        return string.Join(", ", parts);
+    }
+
+    /// <summary>
+    /// Depth of the outline number the text starts with: "3 Scope" and "3. Scope" are 1,
+    /// "3.2 Terms" is 2; roman numerals and capital letters ("IV.", "B.") are 1.
+    /// </summary>
+    private static int? NumberDepth(string text)
+    {
+        if (DecimalNumber().Match(text) is { Success: true } m)
+            return m.Groups["num"].Value.Split('.').Length;
+        return LetterNumber().IsMatch(text) ? 1 : null;
+    }
+
+    private static bool FollowedByBodyText(Paragraph paragraph, List<(Paragraph Paragraph, string Text, Look Look)> looks)
+    {
+        var index = looks.FindIndex(l => l.Paragraph == paragraph);
+        if (index < 0 || index + 1 >= looks.Count) return false;
+        return !looks[index + 1].Look.Bold;
+    }
+
+    private static Dictionary<string, Style> StyleMap(WordprocessingDocument doc)
+    {
+        var map = new Dictionary<string, Style>();
+        var styles = doc.MainDocumentPart?.StyleDefinitionsPart?.Styles;
+        if (styles is null) return map;
+        foreach (var style in styles.Elements<Style>())
+        {
+            if (style.StyleId?.Value is string id)
+                map.TryAdd(id, style);
+        }
+        return map;
+    }
+
+    /// <summary>
+    /// The paragraph style <paramref name="styleId"/> (Normal when null) and its basedOn ancestors.
+    /// </summary>
+    private static List<Style> StyleChain(Dictionary<string, Style> styles, string? styleId)
+    {
+        var chain = new List<Style>();
+        var style = styles.GetValueOrDefault(styleId ?? "Normal");
+        while (style is not null && chain.Count < 10 && !chain.Contains(style))
+        {
+            chain.Add(style);
+            style = style.BasedOn?.Val?.Value is string parent ? styles.GetValueOrDefault(parent) : null;
+        }
+        return chain;
+    }
+
+    private static int FontSize(Run run, List<Style> chain, int defaultSize)
+    {
+        if (ParseSize(run.RunProperties?.FontSize?.Val?.Value) is int own)
+            return own;
+        foreach (var style in chain)
+        {
+            if (ParseSize(style.StyleRunProperties?.FontSize?.Val?.Value) is int size)
+                return size;
+        }
+        return defaultSize;
+    }
+
+    private static bool IsBold(Run run, List<Style> chain)
+    {
+        if (run.RunProperties?.Bold is Bold bold)
+            return bold.Val?.Value ?? true;
+        foreach (var style in chain)
+        {
+            if (style.StyleRunProperties?.Bold is Bold styleBold)
+                return styleBold.Val?.Value ?? true;
+        }
+        return false;
+    }
+
+    private static int? ParseSize(string? value) =>
+        int.TryParse(value, out var size) && size > 0 ? size : null;
+}
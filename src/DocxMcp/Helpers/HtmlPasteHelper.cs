@@ -0,0 +1,1063 @@
+using System.Globalization;
+using System.Net;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Turns clipboard HTML (as copied from Word, Google Docs or a browser) into element
+/// JSON for <see cref="ElementFactory"/>: paragraphs, headings, lists and tables whose
+/// runs keep the emphasis of the source.
+///
+/// Clipboard HTML is mostly noise: Word wraps every run in spans full of mso- styles
+/// and writes list bullets as hidden text, Google Docs wraps the whole fragment in a
+/// &lt;b style="font-weight:normal"&gt; and sets the font on every span. What is kept is
+/// what Word keeps when pasting with "merge formatting": bold, italic, underline,
+/// strike, super/subscript, non-black colors, highlights and links. Fonts and sizes
+/// are dropped unless asked for, so pasted text takes the document's look. Images are
+/// skipped: clipboard HTML only refers to them by local file or remote URL.
+/// </summary>
+public static class HtmlPasteHelper
+{
+    /// <summary>Element JSON values, in document order, and the number of images left out.</summary>
+    public sealed record Conversion(JsonArray Elements, int SkippedImages);
+
+    private static readonly HashSet<string> VoidElements = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "br", "img", "hr", "meta", "link", "input", "col", "wbr", "area", "base", "source"
+    };
+
+    /// <summary>Elements whose content is never document text.</summary>
+    private static readonly HashSet<string> SkippedElements = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "head", "style", "script", "title", "xml", "noscript", "template", "object", "svg"
+    };
+
+    private static readonly HashSet<string> BlockElements = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "p", "div", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ul", "ol", "table", "tr", "td", "th",
+        "blockquote", "pre", "section", "article", "header", "footer", "dl", "dt", "dd", "hr"
+    };
+
+    private static readonly HashSet<string> GenericFonts = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui", "inherit", "initial"
+    };
+
+    /// <summary>Highlight names by the color (or Word mso-highlight name) used for them.</summary>
+    private static readonly Dictionary<string, string> Highlights = new(StringComparer.OrdinalIgnoreCase)
+    {
+        ["FFFF00"] = "yellow", ["yellow"] = "yellow",
+        ["00FF00"] = "green", ["lime"] = "green", ["brightgreen"] = "green",
+        ["00FFFF"] = "cyan", ["aqua"] = "cyan", ["cyan"] = "cyan", ["turquoise"] = "cyan",
+        ["FF00FF"] = "magenta", ["fuchsia"] = "magenta", ["magenta"] = "magenta", ["pink"] = "magenta",
+        ["0000FF"] = "blue", ["blue"] = "blue",
+        ["FF0000"] = "red", ["red"] = "red",
+        ["000080"] = "dark_blue", ["navy"] = "dark_blue", ["darkblue"] = "dark_blue",
+        ["008080"] = "dark_cyan", ["teal"] = "dark_cyan",
+        ["008000"] = "dark_green", ["green"] = "dark_green",
+        ["800080"] = "dark_magenta", ["purple"] = "dark_magenta", ["violet"] = "dark_magenta",
+        ["800000"] = "dark_red", ["maroon"] = "dark_red", ["darkred"] = "dark_red",
+        ["808000"] = "dark_yellow", ["olive"] = "dark_yellow", ["darkyellow"] = "dark_yellow",
+        ["C0C0C0"] = "light_gray", ["silver"] = "light_gray", ["gray-25"] = "light_gray",
+        ["808080"] = "dark_gray", ["gray"] = "dark_gray", ["gray-50"] = "dark_gray",
+    };
+
+    private static readonly Regex Whitespace = new(@"[ \t\r\n\f]+", RegexOptions.Compiled);
+    private static readonly Regex OrderedMarker = new(@"^\(?[0-9a-zA-Z]{1,6}[.)]$", RegexOptions.Compiled);
+
+    /// <summary>
+    /// Convert clipboard HTML to element JSON values. With <paramref name="keepFonts"/>,
+    /// runs also keep their font family and size.
+    /// </summary>
+    public static Conversion Convert(string html, bool keepFonts = false)
+    {
+        var converter = new Converter(keepFonts);
+        converter.Blocks(Parse(ExtractFragment(html)), RunStyle.Default);
+        converter.Flush();
+        return new Conversion(converter.Output, converter.SkippedImages);
+    }
+
+    /// <summary>
+    /// Create the elements for values produced by <see cref="Convert"/>, turning runs
+    /// with a "url" into hyperlinks.
+    /// </summary>
+    public static List<OpenXmlElement> CreateElements(JsonArray values, MainDocumentPart mainPart)
+    {
+        var result = new List<OpenXmlElement>();
+        foreach (var node in values)
+        {
+            var value = JsonDocument.Parse(node!.ToJsonString()).RootElement;
+            if (value.GetProperty("type").GetString() == "list")
+            {
+                var items = ElementFactory.CreateListItems(value);
+                var itemValues = value.GetProperty("items").EnumerateArray().ToList();
+                for (int i = 0; i < items.Count && i < itemValues.Count; i++)
+                    LinkRuns((Paragraph)items[i], itemValues[i], mainPart);
+                result.AddRange(items);
+            }
+            else
+            {
+                var element = ElementFactory.CreateFromJson(value, mainPart);
+                if (element is Paragraph paragraph)
+                    LinkRuns(paragraph, value, mainPart);
+                result.Add(element);
+            }
+        }
+        return result;
+    }
+
+    /// <summary>
+    /// Wrap the runs of <paramref name="paragraph"/> whose JSON has a "url" in hyperlinks,
+    /// one per stretch of runs to the same URL.
+    /// </summary>
+    private static void LinkRuns(Paragraph paragraph, JsonElement value, MainDocumentPart mainPart)
+    {
+        if (!value.TryGetProperty("runs", out var runsJson))
+            return;
+        var runs = paragraph.Elements<Run>().ToList();
+        var urls = runsJson.EnumerateArray()
+            .Select(r => r.TryGetProperty("url", out var u) ? u.GetString() : null)
+            .ToList();
+        if (runs.Count != urls.Count)
+            return;
+
+        for (int i = 0; i < runs.Count;)
+        {
+            var url = urls[i];
+            int end = i + 1;
+            while (end < runs.Count && urls[end] == url)
+                end++;
+            if (url is not null)
+            {
+                var link = JsonDocument.Parse(new JsonObject { ["url"] = url }.ToJsonString()).RootElement;
+                HyperlinkHelper.Wrap(runs.GetRange(i, end - i), HyperlinkHelper.CreateLink(link, mainPart),
+                    HyperlinkHelper.ResolveStyle(link, mainPart));
+            }
+            i = end;
+        }
+    }
+
+    /// <summary>
+    /// The part of a clipboard payload that was copied: what lies between the
+    /// StartFragment/EndFragment markers, else the body, else everything after the
+    /// CF_HTML header ("Version:0.9 StartHTML:...").
+    /// </summary>
+    internal static string ExtractFragment(string html)
+    {
+        var start = html.IndexOf("<!--StartFragment", StringComparison.OrdinalIgnoreCase);
+        var end = html.IndexOf("<!--EndFragment", StringComparison.OrdinalIgnoreCase);
+        var startEnd = start < 0 ? -1 : html.IndexOf("-->", start, StringComparison.Ordinal);
+        if (startEnd >= 0 && end > startEnd)
+            return html[(startEnd + 3)..end];
+
+        var body = Regex.Match(html, @"<body[^>]*>(.*?)(</body>|$)", RegexOptions.Singleline | RegexOptions.IgnoreCase);
+        if (body.Success)
+            return body.Groups[1].Value;
+
+        var firstTag = html.IndexOf('<');
+        return html.StartsWith("Version:", StringComparison.Ordinal) && firstTag > 0 ? html[firstTag..] : html;
+    }
+
+    /// <summary>An element of the parsed tree, or a text node when Name is null.</summary>
+    internal sealed class Node
+    {
+        public string? Name { get; init; }
+        public string? Text { get; set; }
+        public Node? Parent { get; set; }
+        public Dictionary<string, string> Attributes { get; } = new(StringComparer.OrdinalIgnoreCase);
+        public List<Node> Children { get; } = [];
+
+        private Dictionary<string, string>? _style;
+
+        /// <summary>Declarations of the style attribute.</summary>
+        public Dictionary<string, string> Style => _style ??= ParseStyle(Attribute("style"));
+
+        public string? Attribute(string name) => Attributes.GetValueOrDefault(name);
+
+        public string InnerText() =>
+            Text ?? string.Concat(Children.Select(c => c.InnerText()));
+    }
+
+    /// <summary>
+    /// Build a tree from loose HTML: unquoted attributes, unclosed p/li/td, conditional
+    /// comments (&lt;![if !supportLists]&gt;) and namespaced tags (o:p, v:shape) are all expected.
+    /// </summary>
+    internal static Node Parse(string html)
+    {
+        var root = new Node { Name = "#root" };
+        var current = root;
+        int i = 0;
+
+        while (i < html.Length)
+        {
+            var lt = html.IndexOf('<', i);
+            if (lt < 0)
+            {
+                AddText(current, html[i..]);
+                break;
+            }
+            if (lt > i)
+                AddText(current, html[i..lt]);
+
+            if (string.CompareOrdinal(html, lt, "<!--", 0, 4) == 0)
+            {
+                var close = html.IndexOf("-->", lt + 4, StringComparison.Ordinal);
+                i = close < 0 ? html.Length : close + 3;
+                continue;
+            }
+            var next = lt + 1 < html.Length ? html[lt + 1] : '\0';
+            if (next is '!' or '?' or '/')
+            {
+                var close = html.IndexOf('>', lt);
+                i = close < 0 ? html.Length : close + 1;
+                // <![endif]>, <!DOCTYPE>, <?xml ...?> are dropped; end tags close elements
+                if (next == '/')
+                    current = CloseElement(current, html[(lt + 2)..(close < 0 ? html.Length : close)].Trim().ToLowerInvariant());
+                continue;
+            }
+            if (!char.IsLetter(next))
+            {
+                AddText(current, "<");
+                i = lt + 1;
+                continue;
+            }
+
+            var (node, selfClosing, end) = ReadTag(html, lt);
+            i = end;
+
+            if (SkippedElements.Contains(node.Name!))
+            {
+                if (!selfClosing)
+                {
+                    var close = html.IndexOf("</" + node.Name, i, StringComparison.OrdinalIgnoreCase);
+                    var gt = close < 0 ? -1 : html.IndexOf('>', close);
+                    i = gt < 0 ? html.Length : gt + 1;
+                }
+                continue;
+            }
+
+            current = OpenElement(current, node.Name!);
+            node.Parent = current;
+            current.Children.Add(node);
+            if (!selfClosing && !VoidElements.Contains(node.Name!))
+                current = node;
+        }
+
+        return root;
+    }
+
+    private static void AddText(Node parent, string raw)
+    {
+        var text = WebUtility.HtmlDecode(raw);
+        if (text.Length == 0)
+            return;
+        if (parent.Children.Count > 0 && parent.Children[^1].Text is { } previous)
+            parent.Children[^1].Text = previous + text;
+        else
+            parent.Children.Add(new Node { Text = text, Parent = parent });
+    }
+
+    /// <summary>Read the start tag at <paramref name="lt"/>, returning the index after it.</summary>
+    private static (Node Node, bool SelfClosing, int End) ReadTag(string html, int lt)
+    {
+        int i = lt + 1;
+        int start = i;
+        while (i < html.Length && !char.IsWhiteSpace(html[i]) && html[i] is not ('>' or '/'))
+            i++;
+        var node = new Node { Name = html[start..i].ToLowerInvariant() };
+        bool selfClosing = false;
+
+        while (i < html.Length)
+        {
+            while (i < html.Length && char.IsWhiteSpace(html[i]))
+                i++;
+            if (i >= html.Length)
+                break;
+            if (html[i] == '>')
+            {
+                i++;
+                break;
+            }
+            if (html[i] == '/')
+            {
+                selfClosing = true;
+                i++;
+                continue;
+            }
+
+            start = i;
+            while (i < html.Length && !char.IsWhiteSpace(html[i]) && html[i] is not ('=' or '>' or '/'))
+                i++;
+            var name = html[start..i];
+            if (name.Length == 0)
+            {
+                i++;
+                continue;
+            }
+            while (i < html.Length && char.IsWhiteSpace(html[i]))
+                i++;
+
+            var value = "";
+            if (i < html.Length && html[i] == '=')
+            {
+                i++;
+                while (i < html.Length && char.IsWhiteSpace(html[i]))
+                    i++;
+                if (i < html.Length && html[i] is '"' or '\'')
+                {
+                    var close = html.IndexOf(html[i], i + 1);
+                    if (close < 0)
+                        close = html.Length;
+                    value = html[(i + 1)..close];
+                    i = Math.Min(close + 1, html.Length);
+                }
+                else
+                {
+                    start = i;
+                    while (i < html.Length && !char.IsWhiteSpace(html[i]) && html[i] != '>')
+                        i++;
+                    value = html[start..i];
+                }
+            }
+            node.Attributes[name] = WebUtility.HtmlDecode(value);
+        }
+
+        return (node, selfClosing, i);
+    }
+
+    /// <summary>
+    /// Close the elements a start tag implies the end of (a p before a block, an li before
+    /// the next li, a cell before the next cell or row) and return the new parent.
+    /// </summary>
+    private static Node OpenElement(Node current, string name)
+    {
+        if (BlockElements.Contains(name) && FindOpen(current, "p", ["td", "th", "li", "div"]) is { } p)
+            current = p.Parent!;
+
+        var implied = name switch
+        {
+            "li" => FindOpen(current, "li", ["ul", "ol"]),
+            "td" or "th" => FindOpen(current, "td", ["tr", "table"]) ?? FindOpen(current, "th", ["tr", "table"]),
+            "tr" => FindOpen(current, "tr", ["table"]),
+            _ => null
+        };
+        return implied?.Parent ?? current;
+    }
+
+    /// <summary>
+    /// Close the innermost open element named <paramref name="name"/>. Stray end tags are
+    /// ignored, and only table and list end tags close a cell left open inside them.
+    /// </summary>
+    private static Node CloseElement(Node current, string name)
+    {
+        var crossesCells = name is "table" or "tbody" or "thead" or "tfoot" or "tr" or "ul" or "ol";
+        for (var node = current; node.Parent is not null; node = node.Parent)
+        {
+            if (node.Name == name)
+                return node.Parent;
+            if (!crossesCells && node.Name is "td" or "th" or "table")
+                break;
+        }
+        return current;
+    }
+
+    private static Node? FindOpen(Node current, string name, string[] stopAt)
+    {
+        for (var node = current; node.Parent is not null; node = node.Parent)
+        {
+            if (node.Name == name)
+                return node;
+            if (stopAt.Contains(node.Name))
+                return null;
+        }
+        return null;
+    }
+
+    internal static Dictionary<string, string> ParseStyle(string? style)
+    {
+        var result = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+        if (string.IsNullOrEmpty(style))
+            return result;
+        foreach (var declaration in style.Split(';'))
+        {
+            var colon = declaration.IndexOf(':');
+            if (colon <= 0)
+                continue;
+            result[declaration[..colon].Trim()] = declaration[(colon + 1)..].Replace("!important", "").Trim();
+        }
+        return result;
+    }
+
+    /// <summary>Run formatting inherited down the tree.</summary>
+    private sealed record RunStyle(
+        bool Bold, bool Italic, bool Underline, bool Strike, string? VerticalAlign,
+        double? FontSize, string? FontName, string? Color, string? Highlight, string? Url, bool Preformatted)
+    {
+        public static readonly RunStyle Default =
+            new(false, false, false, false, null, null, null, null, null, null, false);
+
+        /// <summary>The run "style" object ElementFactory reads, or null when plain.</summary>
+        public JsonObject? ToJson(bool keepFonts)
+        {
+            var style = new JsonObject();
+            if (Bold) style["bold"] = true;
+            if (Italic) style["italic"] = true;
+            if (Underline) style["underline"] = true;
+            if (Strike) style["strike"] = true;
+            if (VerticalAlign is not null) style["vertical_align"] = VerticalAlign;
+            if (keepFonts && FontSize is { } size)
+                style["font_size"] = (int)Math.Round(size, MidpointRounding.AwayFromZero);
+            if (keepFonts && FontName is not null) style["font_name"] = FontName;
+            if (Color is not null) style["color"] = Color;
+            if (Highlight is not null) style["highlight"] = Highlight;
+            return style.Count > 0 ? style : null;
+        }
+    }
+
+    /// <summary>
+    /// Walks the tree, collecting runs into the open paragraph or list item and emitting
+    /// an element each time a block ends.
+    /// </summary>
+    private sealed class Converter(bool keepFonts)
+    {
+        public JsonArray Output { get; } = [];
+        public int SkippedImages { get; private set; }
+
+        /// <summary>Runs of the open paragraph or list item, null when none is open.</summary>
+        private JsonArray? _runs;
+        /// <summary>The open paragraph; null while a list item is being built.</summary>
+        private JsonObject? _paragraph;
+        private RunStyle? _lastStyle;
+        private bool _endsWithSpace = true;
+
+        public void Blocks(Node node, RunStyle style)
+        {
+            foreach (var child in node.Children)
+            {
+                if (child.Name is null)
+                {
+                    Inline(child, style);
+                    continue;
+                }
+                if (IsHidden(child))
+                    continue;
+
+                var childStyle = Apply(child, style);
+                switch (child.Name)
+                {
+                    case "p" or "div" when ListLevel(child) is not null:
+                        // Word writes list items as paragraphs with an mso-list level
+                        AddListItem(IsOrderedWordList(child), child, childStyle);
+                        break;
+                    case "p" or "blockquote" or "dt" or "dd":
+                        Paragraph(child, childStyle, null);
+                        break;
+                    case "pre":
+                        Paragraph(child, childStyle with { Preformatted = true }, null);
+                        break;
+                    case "h1" or "h2" or "h3" or "h4" or "h5" or "h6":
+                        Paragraph(child, childStyle, child.Name[1] - '0');
+                        break;
+                    case "div" or "section" or "article" or "header" or "footer" or "dl":
+                        Flush();
+                        Blocks(child, childStyle);
+                        Flush();
+                        break;
+                    case "ul" or "ol":
+                        List(child, child.Name == "ol", childStyle);
+                        break;
+                    case "li":
+                        AddListItem(false, child, childStyle);
+                        break;
+                    case "table":
+                        Flush();
+                        Table(child, childStyle);
+                        break;
+                    case "hr":
+                        Flush();
+                        break;
+                    case "br" when _runs is null:
+                        // Google Docs puts a bare <br> between blocks for an empty paragraph
+                        Output.Add((JsonNode)new JsonObject { ["type"] = "paragraph", ["runs"] = new JsonArray() });
+                        break;
+                    default:
+                        if (ContainsBlock(child))
+                            Blocks(child, childStyle);
+                        else
+                            Inline(child, style);
+                        break;
+                }
+            }
+        }
+
+        /// <summary>
+        /// Add inline content to the open paragraph or list item, opening a paragraph
+        /// when text shows up outside of any block.
+        /// </summary>
+        private void Inline(Node node, RunStyle style)
+        {
+            if (node.Name is null)
+            {
+                var text = style.Preformatted ? node.Text!.Replace("\r\n", "\n") : Whitespace.Replace(node.Text!, " ");
+                if (_runs is null && string.IsNullOrWhiteSpace(text))
+                    return;
+                var lines = style.Preformatted ? text.Split('\n') : [text];
+                for (int i = 0; i < lines.Length; i++)
+                {
+                    if (i > 0)
+                        AddBreak(style);
+                    AddText(lines[i], style);
+                }
+                return;
+            }
+
+            if (IsHidden(node))
+                return;
+            if (node.Name == "img")
+            {
+                SkippedImages++;
+                return;
+            }
+            if (node.Name == "br")
+            {
+                AddBreak(style);
+                return;
+            }
+
+            var childStyle = Apply(node, style);
+            foreach (var child in node.Children)
+                Inline(child, childStyle);
+        }
+
+        private void Paragraph(Node node, RunStyle style, int? headingLevel)
+        {
+            Flush();
+            if (ContainsBlock(node))
+            {
+                Blocks(node, style);
+                Flush();
+                return;
+            }
+
+            Open();
+            if (headingLevel is { } level)
+            {
+                _paragraph!["type"] = "heading";
+                _paragraph["level"] = level;
+            }
+            if (Alignment(node) is { } alignment)
+                _paragraph!["properties"] = new JsonObject { ["alignment"] = alignment };
+
+            foreach (var child in node.Children)
+                Inline(child, style);
+            Flush();
+        }
+
+        private void List(Node list, bool ordered, RunStyle style)
+        {
+            foreach (var child in list.Children.Where(c => c.Name is not null && !IsHidden(c)))
+            {
+                // Google Docs nests sub-lists directly in the list; levels are flattened
+                if (child.Name is "ul" or "ol")
+                    List(child, child.Name == "ol", Apply(child, style));
+                else
+                    AddListItem(ordered, child, Apply(child, style));
+            }
+        }
+
+        /// <summary>
+        /// Add an item to the list being built, or start a list. Paragraphs in the item are
+        /// joined with line breaks; items of nested lists follow the item.
+        /// </summary>
+        private void AddListItem(bool ordered, Node item, RunStyle style)
+        {
+            Flush();
+            _runs = [];
+            foreach (var child in item.Children)
+            {
+                if (child.Name is "ul" or "ol")
+                {
+                    EndListItem(ordered);
+                    List(child, child.Name == "ol", Apply(child, style));
+                    _runs = [];
+                    continue;
+                }
+                if (child.Name is "p" or "div" && _runs.Count > 0)
+                    AddBreak(style);
+                InlineInItem(child, style);
+            }
+            EndListItem(ordered);
+        }
+
+        /// <summary>Inline content of a list item, where blocks only add their text.</summary>
+        private void InlineInItem(Node node, RunStyle style)
+        {
+            if (node.Name is null || IsHidden(node) || !ContainsBlock(node))
+            {
+                Inline(node, style);
+                return;
+            }
+            var childStyle = Apply(node, style);
+            foreach (var child in node.Children)
+                InlineInItem(child, childStyle);
+        }
+
+        private void EndListItem(bool ordered)
+        {
+            var runs = _runs;
+            _runs = null;
+            _lastStyle = null;
+            _endsWithSpace = true;
+            if (runs is null || !Trim(runs))
+                return;
+
+            var item = new JsonObject { ["runs"] = runs };
+            if (Output.Count > 0 && Output[^1] is JsonObject last
+                && last["type"]?.GetValue<string>() == "list"
+                && last["ordered"]?.GetValue<bool>() == ordered)
+            {
+                last["items"]!.AsArray().Add((JsonNode)item);
+                return;
+            }
+            Output.Add((JsonNode)new JsonObject
+            {
+                ["type"] = "list",
+                ["ordered"] = ordered,
+                ["items"] = new JsonArray((JsonNode)item)
+            });
+        }
+
+        private void Table(Node table, RunStyle style)
+        {
+            var rows = new JsonArray();
+            // Cells merged down from a rowspan: rows still covered and width, by grid column
+            var merges = new Dictionary<int, (int Rows, int Span)>();
+
+            foreach (var row in Rows(table))
+            {
+                var rowStyle = Apply(row, style);
+                var tds = row.Children.Where(c => c.Name is "td" or "th").ToList();
+                if (tds.Count == 0)
+                    continue;
+
+                var cells = new JsonArray();
+                int column = 0;
+                void FillMerged()
+                {
+                    while (merges.TryGetValue(column, out var merge) && merge.Rows > 0)
+                    {
+                        var covered = new JsonObject { ["row_span"] = "continue", ["paragraphs"] = new JsonArray((JsonNode)"") };
+                        if (merge.Span > 1)
+                            covered["col_span"] = merge.Span;
+                        cells.Add((JsonNode)covered);
+                        merges[column] = (merge.Rows - 1, merge.Span);
+                        column += merge.Span;
+                    }
+                }
+
+                foreach (var td in tds)
+                {
+                    FillMerged();
+                    var cell = Cell(td, Apply(td, rowStyle));
+                    var colSpan = PositiveInt(td.Attribute("colspan")) ?? 1;
+                    var rowSpan = PositiveInt(td.Attribute("rowspan")) ?? 1;
+                    if (colSpan > 1)
+                        cell["col_span"] = colSpan;
+                    if (rowSpan > 1)
+                    {
+                        cell["row_span"] = "restart";
+                        merges[column] = (rowSpan - 1, colSpan);
+                    }
+                    cells.Add((JsonNode)cell);
+                    column += colSpan;
+                }
+                FillMerged();
+
+                var rowJson = new JsonObject { ["cells"] = cells };
+                if (row.Parent?.Name == "thead" || tds.All(td => td.Name == "th"))
+                    rowJson["is_header"] = true;
+                rows.Add((JsonNode)rowJson);
+            }
+
+            if (rows.Count > 0)
+                Output.Add((JsonNode)new JsonObject { ["type"] = "table", ["rows"] = rows });
+        }
+
+        private static IEnumerable<Node> Rows(Node table)
+        {
+            foreach (var child in table.Children)
+            {
+                if (child.Name == "tr")
+                    yield return child;
+                else if (child.Name is "thead" or "tbody" or "tfoot")
+                    foreach (var row in child.Children.Where(c => c.Name == "tr"))
+                        yield return row;
+            }
+        }
+
+        /// <summary>
+        /// A table cell: its content converted on its own, as paragraphs. Lists become
+        /// plain paragraphs and nested tables their cells' paragraphs.
+        /// </summary>
+        private JsonObject Cell(Node td, RunStyle style)
+        {
+            var inner = new Converter(keepFonts);
+            inner.Blocks(td, style);
+            inner.Flush();
+            SkippedImages += inner.SkippedImages;
+
+            var paragraphs = new JsonArray();
+            foreach (var block in inner.Output.OfType<JsonObject>())
+                AddCellParagraphs(paragraphs, block);
+            if (paragraphs.Count == 0)
+                paragraphs.Add((JsonNode)"");
+
+            var cell = new JsonObject { ["paragraphs"] = paragraphs };
+            var fill = Fill(td.Style) ?? (td.Attribute("bgcolor") is { } bgcolor ? ParseColor(bgcolor) : null);
+            if (fill is not null and not "FFFFFF")
+                cell["shading"] = fill;
+            return cell;
+        }
+
+        private static void AddCellParagraphs(JsonArray paragraphs, JsonObject block)
+        {
+            switch (block["type"]?.GetValue<string>())
+            {
+                case "list":
+                    foreach (var item in block["items"]!.AsArray().OfType<JsonObject>())
+                        paragraphs.Add((JsonNode)new JsonObject { ["type"] = "paragraph", ["runs"] = item["runs"]!.DeepClone() });
+                    break;
+                case "table":
+                    foreach (var row in block["rows"]!.AsArray().OfType<JsonObject>())
+                        foreach (var cell in row["cells"]!.AsArray().OfType<JsonObject>())
+                            foreach (var p in cell["paragraphs"]!.AsArray().OfType<JsonObject>())
+                                AddCellParagraphs(paragraphs, p);
+                    break;
+                default:
+                    paragraphs.Add(block.DeepClone());
+                    break;
+            }
+        }
+
+        private void Open()
+        {
+            if (_runs is not null)
+                return;
+            _runs = [];
+            _paragraph = new JsonObject { ["type"] = "paragraph" };
+        }
+
+        /// <summary>End the open paragraph, if any.</summary>
+        public void Flush()
+        {
+            if (_paragraph is null)
+                return;
+            var paragraph = _paragraph;
+            var runs = _runs!;
+            _paragraph = null;
+            _runs = null;
+            _lastStyle = null;
+            _endsWithSpace = true;
+
+            Trim(runs);
+            paragraph["runs"] = runs;
+            Output.Add((JsonNode)paragraph);
+        }
+
+        private void AddText(string text, RunStyle style)
+        {
+            // Spaces collapsed from the source don't double up across runs
+            if (!style.Preformatted && _endsWithSpace && text.StartsWith(' '))
+                text = text[1..];
+            if (text.Length == 0)
+                return;
+            Open();
+            _endsWithSpace = text.EndsWith(' ');
+            text = text.Replace('\u00A0', ' ');
+
+            if (style == _lastStyle && _runs![^1]!["text"] is { } previous)
+            {
+                _runs[^1]!["text"] = previous.GetValue<string>() + text;
+                return;
+            }
+
+            var run = new JsonObject { ["text"] = text };
+            if (style.ToJson(keepFonts) is { } json)
+                run["style"] = json;
+            if (style.Url is not null)
+                run["url"] = style.Url;
+            _runs!.Add((JsonNode)run);
+            _lastStyle = style;
+        }
+
+        private void AddBreak(RunStyle style)
+        {
+            Open();
+            var run = new JsonObject { ["break"] = "line" };
+            if (style.Url is not null)
+                run["url"] = style.Url;
+            _runs!.Add((JsonNode)run);
+            _lastStyle = null;
+            _endsWithSpace = true;
+        }
+
+        /// <summary>
+        /// Trim the whitespace around a paragraph's text and drop trailing breaks.
+        /// Returns whether any run is left.
+        /// </summary>
+        private static bool Trim(JsonArray runs)
+        {
+            while (runs.Count > 0 && runs[^1]!["break"] is not null)
+                runs.RemoveAt(runs.Count - 1);
+            if (runs.Count > 0 && runs[0]!["text"] is { } first)
+                runs[0]!["text"] = first.GetValue<string>().TrimStart();
+            if (runs.Count > 0 && runs[^1]!["text"] is { } last)
+                runs[^1]!["text"] = last.GetValue<string>().TrimEnd();
+            for (int i = runs.Count - 1; i >= 0; i--)
+            {
+                if (runs[i]!["text"]?.GetValue<string>() == "")
+                    runs.RemoveAt(i);
+            }
+            return runs.Count > 0;
+        }
+    }
+
+    /// <summary>The inherited run style with the element's tag and CSS applied.</summary>
+    private static RunStyle Apply(Node node, RunStyle style)
+    {
+        style = node.Name switch
+        {
+            "b" or "strong" => style with { Bold = true },
+            "i" or "em" or "cite" or "dfn" or "var" => style with { Italic = true },
+            "u" or "ins" => style with { Underline = true },
+            "s" or "strike" or "del" => style with { Strike = true },
+            "sup" => style with { VerticalAlign = "superscript" },
+            "sub" => style with { VerticalAlign = "subscript" },
+            "code" or "kbd" or "samp" or "tt" or "pre" => style with { FontName = "Courier New" },
+            // Headings get their look from the heading style
+            "h1" or "h2" or "h3" or "h4" or "h5" or "h6" => style with { Bold = false, FontSize = null, FontName = null },
+            "a" => style with { Url = LinkTarget(node.Attribute("href")) ?? style.Url },
+            "font" => style with
+            {
+                Color = node.Attribute("color") is { } color ? TextColor(color) : style.Color,
+                FontName = FirstFont(node.Attribute("face") ?? "") ?? style.FontName
+            },
+            _ => style
+        };
+
+        var css = node.Style;
+        if (css.Count == 0)
+            return style;
+
+        if (css.TryGetValue("font-weight", out var weight))
+            style = style with { Bold = IsBold(weight) };
+        if (css.TryGetValue("font-style", out var fontStyle))
+            style = style with { Italic = fontStyle is "italic" or "oblique" };
+        if ((css.GetValueOrDefault("text-decoration") ?? css.GetValueOrDefault("text-decoration-line")) is { } decoration)
+        {
+            style = decoration.Contains("none")
+                ? style with { Underline = false, Strike = false }
+                : style with
+                {
+                    Underline = style.Underline || decoration.Contains("underline"),
+                    Strike = style.Strike || decoration.Contains("line-through")
+                };
+        }
+        if (css.TryGetValue("text-underline", out var msoUnderline))
+            style = style with { Underline = msoUnderline != "none" };
+        if (css.TryGetValue("vertical-align", out var vertical))
+        {
+            style = style with
+            {
+                VerticalAlign = vertical switch
+                {
+                    "super" => "superscript",
+                    "sub" => "subscript",
+                    "baseline" => null,
+                    _ => style.VerticalAlign
+                }
+            };
+        }
+        if (css.TryGetValue("font-size", out var size) && ParseFontSize(size) is { } points)
+            style = style with { FontSize = points };
+        if (css.TryGetValue("font-family", out var family) && FirstFont(family) is { } font)
+            style = style with { FontName = font };
+        if (css.TryGetValue("color", out var textColor))
+            style = style with { Color = TextColor(textColor) };
+
+        // Backgrounds of blocks and cells are shading, not highlight
+        var background = css.GetValueOrDefault("mso-highlight")
+            ?? css.GetValueOrDefault("background-color") ?? css.GetValueOrDefault("background");
+        if (background is not null && node.Name is "span" or "font" or "mark" or "b" or "i" or "u" or "a" or "strong" or "em")
+            style = style with { Highlight = HighlightName(background) ?? style.Highlight };
+
+        return style;
+    }
+
+    /// <summary>Word's list markers (mso-list:Ignore) and hidden content.</summary>
+    private static bool IsHidden(Node node)
+    {
+        var css = node.Style;
+        return css.TryGetValue("mso-list", out var msoList) && msoList.Equals("Ignore", StringComparison.OrdinalIgnoreCase)
+            || css.TryGetValue("display", out var display) && display == "none"
+            || css.TryGetValue("mso-hide", out var hide) && hide == "all";
+    }
+
+    private static bool ContainsBlock(Node node) =>
+        node.Children.Any(c => c.Name is { } name && (BlockElements.Contains(name) || ContainsBlock(c)));
+
+    /// <summary>Level of a Word list paragraph ("mso-list:l0 level2 lfo1"), if it is one.</summary>
+    private static int? ListLevel(Node node)
+    {
+        if (!node.Style.TryGetValue("mso-list", out var msoList))
+            return null;
+        var match = Regex.Match(msoList, @"level(\d+)");
+        return match.Success ? int.Parse(match.Groups[1].Value, CultureInfo.InvariantCulture) : null;
+    }
+
+    /// <summary>
+    /// Whether a Word list paragraph is numbered: its hidden marker reads "1." or "a)"
+    /// rather than a bullet glyph.
+    /// </summary>
+    private static bool IsOrderedWordList(Node node)
+    {
+        var marker = Descendants(node).FirstOrDefault(n =>
+            n.Style.TryGetValue("mso-list", out var v) && v.Equals("Ignore", StringComparison.OrdinalIgnoreCase));
+        if (marker is null)
+            return false;
+        var text = Whitespace.Replace(marker.InnerText().Replace('\u00A0', ' '), " ").Trim();
+        return OrderedMarker.IsMatch(text);
+    }
+
+    private static IEnumerable<Node> Descendants(Node node)
+    {
+        foreach (var child in node.Children.Where(c => c.Name is not null))
+        {
+            yield return child;
+            foreach (var descendant in Descendants(child))
+                yield return descendant;
+        }
+    }
+
+    private static string? Alignment(Node node)
+    {
+        var align = node.Style.GetValueOrDefault("text-align") ?? node.Attribute("align");
+        return align?.ToLowerInvariant() switch
+        {
+            "center" => "center",
+            "right" or "end" => "right",
+            "justify" => "justify",
+            _ => null
+        };
+    }
+
+    /// <summary>Cell shading from its CSS background, as RRGGBB.</summary>
+    private static string? Fill(Dictionary<string, string> css)
+    {
+        var background = css.GetValueOrDefault("background-color") ?? css.GetValueOrDefault("background");
+        return background is null
+            ? null
+            : ParseColor(background.Split(' ', StringSplitOptions.RemoveEmptyEntries).LastOrDefault() ?? "");
+    }
+
+    private static bool IsBold(string weight) => weight switch
+    {
+        "bold" or "bolder" => true,
+        "normal" or "lighter" => false,
+        _ => int.TryParse(weight, NumberStyles.Integer, CultureInfo.InvariantCulture, out var n) && n >= 600
+    };
+
+    /// <summary>A font size in points, from pt, px or a bare number of points.</summary>
+    internal static double? ParseFontSize(string size)
+    {
+        var match = Regex.Match(size.Trim(), @"^([0-9]*\.?[0-9]+)\s*(pt|px)?$", RegexOptions.IgnoreCase);
+        if (!match.Success)
+            return null;
+        var value = double.Parse(match.Groups[1].Value, CultureInfo.InvariantCulture);
+        if (match.Groups[2].Value.Equals("px", StringComparison.OrdinalIgnoreCase))
+            value *= 0.75;
+        return value > 0 ? value : null;
+    }
+
+    /// <summary>The first family of a font-family list that isn't generic.</summary>
+    private static string? FirstFont(string family)
+    {
+        foreach (var part in family.Split(','))
+        {
+            var name = part.Trim().Trim('"', '\'').Trim();
+            if (name.Length > 0 && !GenericFonts.Contains(name))
+                return name;
+        }
+        return null;
+    }
+
+    /// <summary>A text color worth keeping: black, like auto and windowtext, is the default.</summary>
+    private static string? TextColor(string value) =>
+        ParseColor(value) is { } hex && hex != "000000" ? hex : null;
+
+    /// <summary>A CSS color as RRGGBB, or null for auto, windowtext, transparent and the unparseable.</summary>
+    internal static string? ParseColor(string value)
+    {
+        value = value.Trim();
+        if (value.StartsWith('#'))
+        {
+            var hex = value[1..];
+            if (hex.Length == 3)
+                hex = string.Concat(hex.Select(c => $"{c}{c}"));
+            return hex.Length == 6 && hex.All(char.IsAsciiHexDigit) ? hex.ToUpperInvariant() : null;
+        }
+
+        var rgb = Regex.Match(value, @"^rgba?\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*(?:,\s*([0-9.]+)\s*)?\)$");
+        if (rgb.Success)
+        {
+            if (rgb.Groups[4].Success && double.Parse(rgb.Groups[4].Value, CultureInfo.InvariantCulture) == 0)
+                return null;
+            return string.Concat(Enumerable.Range(1, 3).Select(g =>
+                Math.Min(255, int.Parse(rgb.Groups[g].Value, CultureInfo.InvariantCulture)).ToString("X2")));
+        }
+
+        return value.ToLowerInvariant() switch
+        {
+            "black" => "000000",
+            "white" => "FFFFFF",
+            "red" => "FF0000",
+            "green" => "008000",
+            "blue" => "0000FF",
+            "yellow" => "FFFF00",
+            "gray" or "grey" => "808080",
+            "silver" => "C0C0C0",
+            "maroon" => "800000",
+            "navy" => "000080",
+            "purple" => "800080",
+            "teal" => "008080",
+            "olive" => "808000",
+            "orange" => "FFA500",
+            _ => null
+        };
+    }
+
+    private static string? HighlightName(string value)
+    {
+        value = value.Trim();
+        if (Highlights.TryGetValue(value, out var name))
+            return name;
+        return ParseColor(value) is { } hex && Highlights.TryGetValue(hex, out name) ? name : null;
+    }
+
+    /// <summary>Absolute http(s) and mailto links; anchors and file links are dropped.</summary>
+    private static string? LinkTarget(string? href)
+    {
+        if (string.IsNullOrWhiteSpace(href) || !Uri.TryCreate(href.Trim(), UriKind.Absolute, out var uri))
+            return null;
+        return uri.Scheme is "http" or "https" or "mailto" ? uri.OriginalString : null;
+    }
+
+    private static int? PositiveInt(string? value) =>
+        int.TryParse(value, NumberStyles.Integer, CultureInfo.InvariantCulture, out var n) && n > 0 ? n : null;
+}
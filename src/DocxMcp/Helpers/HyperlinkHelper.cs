@@ -0,0 +1,127 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Hyperlinks to URLs, bookmarks or headings, either as their own run or wrapped
+/// around existing text. Link look comes from a character style ("Hyperlink" by
+/// default) rather than direct formatting.
+/// </summary>
+public static class HyperlinkHelper
+{
+    public const string DefaultStyle = "Hyperlink";
+
+    /// <summary>
+    /// Create an empty w:hyperlink for the target described by <paramref name="value"/>:
+    /// exactly one of "url", "bookmark" (an existing bookmark name) or "heading"
+    /// (the text of a heading, bookmarked on demand), plus an optional "tooltip".
+    /// </summary>
+    public static Hyperlink CreateLink(JsonElement value, MainDocumentPart mainPart)
+    {
+        var url = GetString(value, "url");
+        var bookmark = GetString(value, "bookmark");
+        var heading = GetString(value, "heading");
+
+        var targets = new[] { url, bookmark, heading }.Count(t => t is not null);
+        if (targets != 1)
+            throw new ArgumentException("Hyperlink needs exactly one of 'url', 'bookmark' or 'heading'.");
+
+        var hyperlink = new Hyperlink();
+        if (url is not null)
+        {
+            var rel = mainPart.AddHyperlinkRelationship(new Uri(url), true, DeterministicOutput.NextRelationshipId(mainPart));
+            hyperlink.Id = rel.Id;
+        }
+        else
+        {
+            var body = mainPart.Document?.Body
+                ?? throw new InvalidOperationException("Document has no body.");
+            if (bookmark is not null && !body.Descendants<BookmarkStart>().Any(b => b.Name?.Value == bookmark))
+                throw new ArgumentException($"Bookmark '{bookmark}' not found.");
+            hyperlink.Anchor = bookmark ?? BookmarkHeading(body, heading!);
+            hyperlink.History = OnOffValue.FromBoolean(true);
+        }
+
+        if (GetString(value, "tooltip") is { } tooltip)
+            hyperlink.Tooltip = tooltip;
+
+        ElementIdManager.AssignId(hyperlink);
+        return hyperlink;
+    }
+
+    /// <summary>
+    /// Character style of the link runs: "character_style", or "Hyperlink", which is
+    /// added to styles.xml when missing. Other styles must already exist.
+    /// </summary>
+    public static string ResolveStyle(JsonElement value, MainDocumentPart mainPart)
+    {
+        var style = GetString(value, "character_style") ?? DefaultStyle;
+        var styles = mainPart.StyleDefinitionsPart?.Styles;
+        if (styles?.Elements<Style>().Any(s => s.StyleId?.Value == style) == true)
+            return style;
+        if (style != DefaultStyle)
+            throw new ArgumentException($"Character style '{style}' not found.");
+
+        var stylesPart = mainPart.StyleDefinitionsPart
+            ?? mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles ??= new Styles();
+        stylesPart.Styles.AppendChild(new Style(
+            new StyleName { Val = "Hyperlink" },
+            new BasedOn { Val = "DefaultParagraphFont" },
+            new UIPriority { Val = 99 },
+            new UnhideWhenUsed(),
+            new StyleRunProperties(
+                new Color { Val = "0563C1", ThemeColor = ThemeColorValues.Hyperlink },
+                new Underline { Val = UnderlineValues.Single }))
+        { Type = StyleValues.Character, StyleId = DefaultStyle });
+        return style;
+    }
+
+    /// <summary>
+    /// Create a link run with the given text and character style.
+    /// </summary>
+    public static Run CreateRun(string text, string style)
+    {
+        var run = new Run(
+            new RunProperties(new RunStyle { Val = style }),
+            new Text(text) { Space = SpaceProcessingModeValues.Preserve });
+        ElementIdManager.AssignId(run);
+        return run;
+    }
+
+    /// <summary>
+    /// Move <paramref name="runs"/> (as isolated by CommentHelper.IsolateText) into
+    /// <paramref name="hyperlink"/>, keeping the runs' own formatting.
+    /// </summary>
+    public static void Wrap(IReadOnlyList<Run> runs, Hyperlink hyperlink, string style)
+    {
+        runs[0].InsertBeforeSelf(hyperlink);
+        foreach (var run in runs)
+        {
+            run.Remove();
+            run.RunProperties ??= new RunProperties();
+            run.RunProperties.RunStyle = new RunStyle { Val = style };
+            hyperlink.AppendChild(run);
+        }
+    }
+
+    /// <summary>
+    /// Name of a bookmark on the heading whose text is <paramref name="heading"/>,
+    /// adding one when the heading has none.
+    /// </summary>
+    private static string BookmarkHeading(Body body, string heading)
+    {
+        var target = body.Descendants<Paragraph>()
+            .FirstOrDefault(p => p.IsHeading() &&
+                string.Equals(p.GetText().Trim(), heading.Trim(), StringComparison.OrdinalIgnoreCase))
+            ?? throw new ArgumentException($"Heading '{heading}' not found.");
+
+        return HeadingHelper.EnsureBookmark(body, target);
+    }
+
+    private static string? GetString(JsonElement value, string property) =>
+        value.TryGetProperty(property, out var p) && p.ValueKind == JsonValueKind.String ? p.GetString() : null;
+}
@@ -0,0 +1,224 @@
+using System.Buffers.Binary;
+using System.IO.Compression;
+using System.Security.Cryptography;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using A = DocumentFormat.OpenXml.Drawing;
+using DW = DocumentFormat.OpenXml.Drawing.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// One image in an export manifest: where it is used and the file it was saved as.
+/// Images stored in the package but not shown anywhere get a null location.
+/// </summary>
+public sealed record ExportedImage(
+    int Index,
+    string File,
+    string ContentType,
+    long Bytes,
+    string Sha256,
+    int? PixelWidth,
+    int? PixelHeight,
+    long? DisplayWidthEmu,
+    long? DisplayHeightEmu,
+    string? AltText,
+    string? Title,
+    string? Part,
+    string? Section);
+
+/// <summary>
+/// Extracts a document's images with a manifest. Files are named by order of
+/// first appearance (image-001.png, image-002.jpeg, ...), so the same document
+/// always exports to the same names, and an image shown several times is saved once.
+/// Manifest entries are one per place an image is shown, in document order
+/// (body, then headers, then footers).
+/// </summary>
+public static class ImageExportHelper
+{
+    public const string ManifestName = "manifest.json";
+
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    private static ReadOnlySpan<byte> PngSignature => [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+
+    /// <summary>The manifest, and the bytes of every file it names.</summary>
+    public static (List<ExportedImage> Manifest, Dictionary<string, byte[]> Files) Collect(WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+
+        var manifest = new List<ExportedImage>();
+        var files = new Dictionary<string, byte[]>();
+        var fileOfPart = new Dictionary<ImagePart, (string File, byte[] Data, string Sha256)>();
+
+        (string File, byte[] Data, string Sha256) Save(ImagePart part)
+        {
+            if (fileOfPart.TryGetValue(part, out var saved))
+                return saved;
+            using var stream = part.GetStream();
+            using var buffer = new MemoryStream();
+            stream.CopyTo(buffer);
+            var data = buffer.ToArray();
+            var name = $"image-{fileOfPart.Count + 1:D3}{Extension(part)}";
+            saved = (name, data, Convert.ToHexString(SHA256.HashData(data)).ToLowerInvariant());
+            fileOfPart[part] = saved;
+            files[name] = data;
+            return saved;
+        }
+
+        ExportedImage Entry(ImagePart part, string? partName, OpenXmlElement? drawing)
+        {
+            var (file, data, sha) = Save(part);
+            var (width, height) = PixelSize(data);
+            var extent = drawing?.Descendants<DW.Extent>().FirstOrDefault();
+            var docPr = drawing?.Descendants<DW.DocProperties>().FirstOrDefault();
+            return new ExportedImage(
+                manifest.Count + 1, file, part.ContentType, data.LongLength, sha, width, height,
+                extent?.Cx?.Value, extent?.Cy?.Value,
+                NullIfEmpty(docPr?.Description?.Value), NullIfEmpty(docPr?.Title?.Value),
+                partName, partName == "body" ? HeadingHelper.HeadingAbove(drawing)?.InnerText.Trim() : null);
+        }
+
+        var containers = new List<(OpenXmlPart Part, OpenXmlElement? Root, string Name)>
+        {
+            (mainPart, mainPart.Document, "body")
+        };
+        containers.AddRange(mainPart.HeaderParts.Select((h, i) => ((OpenXmlPart)h, (OpenXmlElement?)h.Header, $"header{i + 1}")));
+        containers.AddRange(mainPart.FooterParts.Select((f, i) => ((OpenXmlPart)f, (OpenXmlElement?)f.Footer, $"footer{i + 1}")));
+
+        foreach (var (part, root, name) in containers)
+        {
+            if (root is null)
+                continue;
+            foreach (var blip in root.Descendants<A.Blip>())
+            {
+                if (blip.Embed?.Value is not { } embed)
+                    continue;
+                if (!part.TryGetPartById(embed, out var found) || found is not ImagePart image)
+                    continue;
+                var drawing = blip.Ancestors<DocumentFormat.OpenXml.Wordprocessing.Drawing>().FirstOrDefault();
+                manifest.Add(Entry(image, name, drawing ?? (OpenXmlElement)blip));
+            }
+        }
+
+        // Media parts nothing shows (e.g. VML pictures, or leftovers)
+        foreach (var (part, _, _) in containers)
+        {
+            foreach (var image in part.Parts.Select(p => p.OpenXmlPart).OfType<ImagePart>())
+            {
+                if (!fileOfPart.ContainsKey(image))
+                    manifest.Add(Entry(image, null, null));
+            }
+        }
+
+        return (manifest, files);
+    }
+
+    /// <summary>
+    /// Write the files and manifest.json into <paramref name="output"/>: a ZIP
+    /// archive when it ends with .zip, otherwise a directory (created if needed).
+    /// </summary>
+    public static void Write(string output, List<ExportedImage> manifest, Dictionary<string, byte[]> files)
+    {
+        var manifestJson = ManifestJson(manifest).ToJsonString(JsonOpts);
+        if (output.EndsWith(".zip", StringComparison.OrdinalIgnoreCase))
+        {
+            var directory = Path.GetDirectoryName(Path.GetFullPath(output));
+            if (directory is not null)
+                Directory.CreateDirectory(directory);
+            using var zip = ZipFile.Open(output, ZipArchiveMode.Create);
+            foreach (var (name, data) in files.OrderBy(f => f.Key, StringComparer.Ordinal))
+            {
+                // Images are already compressed
+                using var entry = zip.CreateEntry(name, CompressionLevel.NoCompression).Open();
+                entry.Write(data);
+            }
+            using (var writer = new StreamWriter(zip.CreateEntry(ManifestName).Open()))
+                writer.Write(manifestJson);
+            return;
+        }
+
+        Directory.CreateDirectory(output);
+        foreach (var (name, data) in files)
+            File.WriteAllBytes(Path.Combine(output, name), data);
+        File.WriteAllText(Path.Combine(output, ManifestName), manifestJson);
+    }
+
+    public static JsonArray ManifestJson(List<ExportedImage> manifest)
+    {
+        var array = new JsonArray();
+        foreach (var image in manifest)
+        {
+            var obj = new JsonObject
+            {
+                ["index"] = image.Index,
+                ["file"] = image.File,
+                ["content_type"] = image.ContentType,
+                ["bytes"] = image.Bytes,
+                ["sha256"] = image.Sha256
+            };
+            if (image.PixelWidth is not null)
+            {
+                obj["width_px"] = image.PixelWidth;
+                obj["height_px"] = image.PixelHeight;
+            }
+            if (image.DisplayWidthEmu is not null)
+            {
+                obj["display_width_emu"] = image.DisplayWidthEmu;
+                obj["display_height_emu"] = image.DisplayHeightEmu;
+            }
+            if (image.AltText is not null) obj["alt_text"] = image.AltText;
+            if (image.Title is not null) obj["title"] = image.Title;
+            obj["part"] = image.Part;
+            obj["section"] = image.Section;
+            array.Add(obj);
+        }
+        return array;
+    }
+
+    /// <summary>
+    /// Pixel size read from a PNG, GIF, BMP or JPEG header; null for other formats.
+    /// </summary>
+    public static (int? Width, int? Height) PixelSize(byte[] data)
+    {
+        var span = data.AsSpan();
+        if (span.Length >= 24 && span[..8].SequenceEqual(PngSignature))
+            return (BinaryPrimitives.ReadInt32BigEndian(span[16..]), BinaryPrimitives.ReadInt32BigEndian(span[20..]));
+        if (span.Length >= 10 && span[..3].SequenceEqual("GIF"u8))
+            return (BinaryPrimitives.ReadUInt16LittleEndian(span[6..]), BinaryPrimitives.ReadUInt16LittleEndian(span[8..]));
+        if (span.Length >= 26 && span[..2].SequenceEqual("BM"u8))
+            return (BinaryPrimitives.ReadInt32LittleEndian(span[18..]), Math.Abs(BinaryPrimitives.ReadInt32LittleEndian(span[22..])));
+        if (span.Length >= 4 && span[0] == 0xFF && span[1] == 0xD8)
+        {
+            // Walk the segments to the first start-of-frame marker
+            var i = 2;
+            while (i + 9 < span.Length && span[i] == 0xFF)
+            {
+                var marker = span[i + 1];
+                var length = BinaryPrimitives.ReadUInt16BigEndian(span[(i + 2)..]);
+                if (marker is >= 0xC0 and <= 0xCF and not (0xC4 or 0xC8 or 0xCC))
+                    return (BinaryPrimitives.ReadUInt16BigEndian(span[(i + 7)..]), BinaryPrimitives.ReadUInt16BigEndian(span[(i + 5)..]));
+                i += 2 + length;
+            }
+        }
+        return (null, null);
+    }
+
+    private static string Extension(ImagePart part) => part.ContentType switch
+    {
+        "image/png" => ".png",
+        "image/jpeg" => ".jpeg",
+        "image/gif" => ".gif",
+        "image/bmp" => ".bmp",
+        "image/tiff" => ".tiff",
+        "image/svg+xml" => ".svg",
+        "image/x-emf" => ".emf",
+        "image/x-wmf" => ".wmf",
+        _ => Path.GetExtension(part.Uri.OriginalString) is { Length: > 0 } ext ? ext : ".bin"
+    };
+
+    private static string? NullIfEmpty(string? s) => string.IsNullOrEmpty(s) ? null : s;
+}
@@ -0,0 +1,296 @@
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A language found in some text: its share of the sampled letters and how
+/// sure the detection is (0-1).
+/// </summary>
+public sealed record DetectedLanguage(string Language, double Share, double Confidence);
+
+/// <summary>
+/// Languages of one part of a document, with the languages its runs declare (w:lang).
+/// </summary>
+public sealed record SectionLanguages(
+    int Index,
+    string? FirstHeading,
+    List<string> ParagraphIds,
+    int SampledChars,
+    List<DetectedLanguage> Languages,
+    List<string> Declared);
+
+/// <summary>
+/// Language detection from the text itself, so multilingual documents can be
+/// routed to the right translation workflow and given correct w:lang values
+/// (spell-check, hyphenation and field formatting all follow w:lang, which
+/// imported documents often get wrong).
+///
+/// Non-Latin scripts decide the language on their own (Cyrillic, Greek, Arabic,
+/// Hebrew, CJK, ...). Latin-script text is scored on the most frequent words and
+/// the letters specific to each language. Each paragraph is detected on its own
+/// and weighted by its length; sections longer than <see cref="MaxSampleChars"/>
+/// are sampled evenly.
+/// </summary>
+public static partial class LanguageDetectionHelper
+{
+    /// <summary>Letters sampled per section at most.</summary>
+    public const int MaxSampleChars = 20_000;
+
+    /// <summary>Paragraphs with fewer letters are too short to tell.</summary>
+    public const int MinParagraphLetters = 12;
+
+    /// <summary>Function words matched in a paragraph for full confidence.</summary>
+    private const int ConfidentWordCount = 8;
+
+    [GeneratedRegex(@"\p{L}+")]
+    private static partial Regex Word();
+
+    private static readonly Dictionary<string, HashSet<string>> FunctionWords = new()
+    {
+        ["en"] = Words("the of and to in is that for it with as was on be by this are or from at which not have an"),
+        ["fr"] = Words("le la les de des du et est en un une que qui dans pour pas sur au aux par il elle ne se ce sont avec"),
+        ["de"] = Words("der die das und ist nicht zu den von mit sich des auf für im dem ein eine eines einer als auch es wird bei"),
+        ["es"] = Words("el la los las de del y que en un una es por con para se no al lo su como más o pero sus"),
+        ["it"] = Words("il lo la gli le di del della e che è un una per in con non si da dei delle al alla sono"),
+        ["pt"] = Words("o a os as de do da dos das e que em um uma é para com não por se no na ao pelo pela são"),
+        ["nl"] = Words("de het een en van in is dat op te zijn met voor niet aan er die ook als bij door wordt"),
+        ["sv"] = Words("och i att det som en på är av för med till den har inte om ett var de kan"),
+        ["da"] = Words("og i at det en den til er som på de med af for ikke der var et har fra kan"),
+        ["pl"] = Words("i w na z nie do się że jest to o jak przez od po za dla oraz lub który które"),
+        ["cs"] = Words("a v na se je že s z do o to pro jako by ve k jsou nebo který které byl"),
+        ["ro"] = Words("și în de la a cu nu se că pe din este un o pentru care sau mai ca fi"),
+        ["tr"] = Words("ve bir bu da de için ile olarak olan gibi daha çok ne ya kadar her ama sonra")
+    };
+
+    /// <summary>Letters only one or two of the Latin-script languages use.</summary>
+    private static readonly Dictionary<string, string> SpecificLetters = new()
+    {
+        ["de"] = "ßäöü",
+        ["fr"] = "çèêëîïôœùû",
+        ["es"] = "ñ¿¡á",
+        ["it"] = "àèìòù",
+        ["pt"] = "ãõçâê",
+        ["nl"] = "ĳ",
+        ["sv"] = "åäö",
+        ["da"] = "æøå",
+        ["pl"] = "ąćęłńśźż",
+        ["cs"] = "čďěňřšťůž",
+        ["ro"] = "ăâîșțşţ",
+        ["tr"] = "çğıöşü"
+    };
+
+    private static HashSet<string> Words(string words) =>
+        words.Split(' ').ToHashSet(StringComparer.Ordinal);
+
+    /// <summary>
+    /// The language of <paramref name="text"/> and the confidence of the guess,
+    /// or null when the text has too few letters or none of the known languages fit.
+    /// </summary>
+    public static (string Language, double Confidence)? Detect(string text)
+    {
+        var letters = text.Count(char.IsLetter);
+        if (letters < MinParagraphLetters)
+            return null;
+
+        if (ScriptLanguage(text, letters) is (string scriptLanguage, double scriptConfidence))
+            return (scriptLanguage, scriptConfidence);
+
+        var words = Word().Matches(text.ToLowerInvariant()).Select(m => m.Value).ToList();
+        var scores = new Dictionary<string, double>();
+        foreach (var (language, functionWords) in FunctionWords)
+        {
+            double score = words.Count(functionWords.Contains);
+            var specific = SpecificLetters.GetValueOrDefault(language, "");
+            score += 0.5 * words.Sum(w => w.Count(specific.Contains));
+            if (score > 0)
+                scores[language] = score;
+        }
+        if (scores.Count == 0)
+            return null;
+
+        var best = scores.MaxBy(kv => kv.Value);
+        var total = scores.Values.Sum();
+        var confidence = best.Value / total * Math.Min(1, best.Value / ConfidentWordCount);
+        return (best.Key, Math.Round(confidence, 2));
+    }
+
+    /// <summary>
+    /// Languages of each section of the body: the ranges ended by section properties
+    /// (as read_section lists them), or with <paramref name="byHeading"/> the ranges
+    /// starting at each heading.
+    /// </summary>
+    public static List<SectionLanguages> Analyze(WordprocessingDocument doc, bool byHeading)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var results = new List<SectionLanguages>();
+        foreach (var section in Sections(body, byHeading))
+        {
+            var paragraphs = section.SelectMany(e => e is Paragraph p ? new[] { p } : e.Descendants<Paragraph>()).ToList();
+            var heading = paragraphs.FirstOrDefault(p => p.IsHeading())?.InnerText;
+            var texts = paragraphs.Select(p => (Paragraph: p, Text: p.InnerText)).ToList();
+
+            var totalLetters = texts.Sum(t => t.Text.Count(char.IsLetter));
+            var step = Math.Max(1, (int)Math.Ceiling(totalLetters / (double)MaxSampleChars));
+
+            var weights = new Dictionary<string, (double Chars, double Confidence)>();
+            var sampled = 0;
+            for (int i = 0; i < texts.Count; i += step)
+            {
+                if (Detect(texts[i].Text) is not (string language, double confidence))
+                    continue;
+                var chars = texts[i].Text.Count(char.IsLetter);
+                sampled += chars;
+                var (c, w) = weights.GetValueOrDefault(language);
+                weights[language] = (c + chars, w + chars * confidence);
+            }
+
+            var languages = weights
+                .OrderByDescending(kv => kv.Value.Chars)
+                .Select(kv => new DetectedLanguage(
+                    kv.Key,
+                    Math.Round(kv.Value.Chars / sampled, 2),
+                    Math.Round(kv.Value.Confidence / kv.Value.Chars, 2)))
+                .ToList();
+
+            var declared = paragraphs
+                .SelectMany(p => p.Descendants<Languages>())
+                .SelectMany(l => new[] { l.Val?.Value, l.Bidi?.Value, l.EastAsia?.Value })
+                .OfType<string>()
+                .Distinct()
+                .ToList();
+
+            var ids = paragraphs.Select(ElementIdManager.GetId).OfType<string>().ToList();
+            results.Add(new SectionLanguages(results.Count, heading, ids, sampled, languages, declared));
+        }
+        return results;
+    }
+
+    /// <summary>
+    /// The language most of the sampled text is in, across all sections.
+    /// </summary>
+    public static string? Dominant(List<SectionLanguages> sections) =>
+        sections
+            .SelectMany(s => s.Languages.Select(l => (l.Language, Chars: l.Share * s.SampledChars)))
+            .GroupBy(l => l.Language)
+            .OrderByDescending(g => g.Sum(l => l.Chars))
+            .Select(g => g.Key)
+            .FirstOrDefault();
+
+    /// <summary>
+    /// Make <paramref name="language"/> the document's default language (w:lang of the
+    /// document defaults), adding a styles part when there is none.
+    /// </summary>
+    public static void SetDefaultLanguage(WordprocessingDocument doc, string language)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var stylesPart = mainPart.StyleDefinitionsPart
+            ?? mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles ??= new Styles();
+
+        var defaults = stylesPart.Styles.DocDefaults ??= new DocDefaults();
+        var rPrDefault = defaults.RunPropertiesDefault ??= new RunPropertiesDefault();
+        var rPr = rPrDefault.RunPropertiesBaseStyle ??= new RunPropertiesBaseStyle();
+        var languages = rPr.Languages ??= new Languages();
+        if (BidiHelper.IsRtlLanguage(language))
+            languages.Bidi = language;
+        else
+            languages.Val = language;
+    }
+
+    /// <summary>
+    /// Give the runs of the paragraphs <paramref name="paragraphIds"/> that declare no
+    /// language of their own the language <paramref name="language"/>.
+    /// </summary>
+    public static void TagParagraphs(WordprocessingDocument doc, IEnumerable<string> paragraphIds, string language)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var ids = paragraphIds.ToHashSet(StringComparer.OrdinalIgnoreCase);
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            if (ElementIdManager.GetId(paragraph) is string id && ids.Contains(id))
+                BidiHelper.ApplyLanguage(paragraph, language);
+        }
+    }
+
+    /// <summary>
+    /// The language of text in a script used by one language (or one family, told
+    /// apart by its specific letters), or null for Latin-script and mixed text.
+    /// </summary>
+    private static (string, double)? ScriptLanguage(string text, int letters)
+    {
+        var counts = new Dictionary<string, int>();
+        foreach (var ch in text)
+        {
+            var script = ch switch
+            {
+                >= 'Ѐ' and <= 'ӿ' => "Cyrillic",
+                >= 'Ͱ' and <= 'Ͽ' => "Greek",
+                >= '֐' and <= '׿' => "Hebrew",
+                >= '؀' and <= 'ۿ' => "Arabic",
+                >= 'ऀ' and <= 'ॿ' => "Devanagari",
+                >= '฀' and <= '๿' => "Thai",
+                >= '぀' and <= 'ヿ' => "Kana",
+                >= '가' and <= '힯' => "Hangul",
+                >= '一' and <= '鿿' => "Han",
+                _ => null
+            };
+            if (script is not null)
+                counts[script] = counts.GetValueOrDefault(script) + 1;
+        }
+        if (counts.Count == 0)
+            return null;
+
+        var (top, count) = counts.MaxBy(kv => kv.Value);
+        var share = (double)counts.Values.Sum() / letters;
+        if (share < 0.5)
+            return null;
+
+        var language = top switch
+        {
+            "Cyrillic" => text.IndexOfAny(['і', 'ї', 'є', 'ґ']) >= 0 ? "uk"
+                : text.IndexOfAny(['ъ']) >= 0 && text.IndexOfAny(['ы', 'э']) < 0 ? "bg" : "ru",
+            "Greek" => "el",
+            "Hebrew" => "he",
+            "Arabic" => text.IndexOfAny(['پ', 'چ', 'ژ', 'گ', 'ی']) >= 0 ? "fa" : "ar",
+            "Devanagari" => "hi",
+            "Thai" => "th",
+            "Hangul" => "ko",
+            // Japanese mixes kana with kanji; Chinese has no kana at all
+            "Han" or "Kana" => counts.ContainsKey("Kana") ? "ja" : "zh",
+            _ => null
+        };
+        if (language is null)
+            return null;
+        return (language, Math.Round(Math.Min(1, share * count / counts.Values.Sum()), 2));
+    }
+
+    private static IEnumerable<List<OpenXmlElement>> Sections(Body body, bool byHeading)
+    {
+        var current = new List<OpenXmlElement>();
+        foreach (var child in body.ChildElements)
+        {
+            if (child is SectionProperties)
+                continue;
+            if (byHeading && child is Paragraph heading && heading.IsHeading() && current.Count > 0)
+            {
+                yield return current;
+                current = [];
+            }
+            current.Add(child);
+            if (!byHeading && child is Paragraph p && p.ParagraphProperties?.SectionProperties is not null)
+            {
+                yield return current;
+                current = [];
+            }
+        }
+        if (current.Count > 0)
+            yield return current;
+    }
+}
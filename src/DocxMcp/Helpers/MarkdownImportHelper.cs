@@ -0,0 +1,202 @@
+using System.Net;
+using System.Text;
+using System.Text.RegularExpressions;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Turns Markdown into HTML for <see cref="HtmlPasteHelper"/>, so imported Markdown is
+/// pasted the same way as clipboard HTML: ATX headings, paragraphs, bullet and numbered
+/// lists, fenced code blocks, block quotes and GFM pipe tables, with bold, italic,
+/// strikethrough, code spans and links inline.
+///
+/// Only the common subset is handled. Setext headings, nested lists, reference links
+/// and raw HTML are not: their text is kept as paragraph text, escaped.
+/// </summary>
+public static partial class MarkdownImportHelper
+{
+    [GeneratedRegex(@"^ {0,3}(#{1,6})(?:[ \t]+(.*?))?(?:[ \t]+#+)?[ \t]*$")]
+    private static partial Regex HeadingLine();
+
+    [GeneratedRegex(@"^ {0,3}(`{3,}|~{3,})")]
+    private static partial Regex FenceLine();
+
+    [GeneratedRegex(@"^ {0,3}(?:(?:\*[ \t]*){3,}|(?:-[ \t]*){3,}|(?:_[ \t]*){3,})$")]
+    private static partial Regex RuleLine();
+
+    [GeneratedRegex(@"^ {0,3}> ?(.*)$")]
+    private static partial Regex QuoteLine();
+
+    [GeneratedRegex(@"^ {0,3}(?:([-*+])|(\d{1,9})[.)])[ \t]+(.*)$")]
+    private static partial Regex ListLine();
+
+    [GeneratedRegex(@"^ *\|? *:?-+:? *(?:\| *:?-+:? *)*\|? *$")]
+    private static partial Regex TableDelimiterLine();
+
+    [GeneratedRegex(@"`+")]
+    private static partial Regex CodeSpanFence();
+
+    [GeneratedRegex(@"\[([^\]]+)\]\(([^)\s]+)(?:\s+&quot;[^)]*&quot;)?\)")]
+    private static partial Regex Link();
+
+    [GeneratedRegex(@"\*\*(.+?)\*\*|__(.+?)__")]
+    private static partial Regex Strong();
+
+    [GeneratedRegex(@"~~(.+?)~~")]
+    private static partial Regex Strike();
+
+    [GeneratedRegex(@"\*(.+?)\*|(?<![\w])_(.+?)_(?![\w])")]
+    private static partial Regex EmphasisSpan();
+
+    /// <summary>An HTML fragment with the Markdown's blocks in document order.</summary>
+    public static string ToHtml(string markdown)
+    {
+        var lines = markdown.Replace("\r\n", "\n").Replace('\r', '\n').Split('\n');
+        var html = new StringBuilder();
+        var paragraph = new List<string>();
+
+        void FlushParagraph()
+        {
+            if (paragraph.Count == 0)
+                return;
+            html.Append("<p>").Append(Inline(string.Join(" ", paragraph))).Append("</p>\n");
+            paragraph.Clear();
+        }
+
+        int i = 0;
+        while (i < lines.Length)
+        {
+            var line = lines[i];
+
+            if (string.IsNullOrWhiteSpace(line))
+            {
+                FlushParagraph();
+                i++;
+            }
+            else if (FenceLine().Match(line) is { Success: true } fence)
+            {
+                FlushParagraph();
+                var marker = fence.Groups[1].Value;
+                var code = new List<string>();
+                for (i++; i < lines.Length && !lines[i].TrimStart().StartsWith(marker, StringComparison.Ordinal); i++)
+                    code.Add(lines[i]);
+                i++; // closing fence, or past the end
+                html.Append("<pre>").Append(WebUtility.HtmlEncode(string.Join("\n", code))).Append("</pre>\n");
+            }
+            else if (HeadingLine().Match(line) is { Success: true } heading)
+            {
+                FlushParagraph();
+                var level = heading.Groups[1].Length;
+                html.Append($"<h{level}>").Append(Inline(heading.Groups[2].Value)).Append($"</h{level}>\n");
+                i++;
+            }
+            else if (RuleLine().IsMatch(line))
+            {
+                FlushParagraph();
+                html.Append("<hr>\n");
+                i++;
+            }
+            else if (QuoteLine().IsMatch(line))
+            {
+                FlushParagraph();
+                var quoted = new List<string>();
+                for (; i < lines.Length && QuoteLine().Match(lines[i]) is { Success: true } quote; i++)
+                    quoted.Add(quote.Groups[1].Value.Trim());
+                html.Append("<blockquote>").Append(Inline(string.Join(" ", quoted.Where(q => q.Length > 0))))
+                    .Append("</blockquote>\n");
+            }
+            else if (ListLine().Match(line) is { Success: true } first)
+            {
+                FlushParagraph();
+                var ordered = first.Groups[2].Success;
+                var items = new List<List<string>>();
+                while (i < lines.Length)
+                {
+                    if (ListLine().Match(lines[i]) is { Success: true } item && item.Groups[2].Success == ordered)
+                        items.Add([item.Groups[3].Value.Trim()]);
+                    else if (!string.IsNullOrWhiteSpace(lines[i]) && char.IsWhiteSpace(lines[i][0]))
+                        items[^1].Add(lines[i].Trim()); // continuation of the item
+                    else
+                        break;
+                    i++;
+                }
+
+                var tag = ordered ? "ol" : "ul";
+                html.Append($"<{tag}>");
+                foreach (var item in items)
+                    html.Append("<li>").Append(Inline(string.Join(" ", item))).Append("</li>");
+                html.Append($"</{tag}>\n");
+            }
+            else if (line.Contains('|') && i + 1 < lines.Length && TableDelimiterLine().IsMatch(lines[i + 1])
+                     && lines[i + 1].Contains('-'))
+            {
+                FlushParagraph();
+                html.Append("<table>");
+                AppendRow(html, line, "th");
+                for (i += 2; i < lines.Length && lines[i].Contains('|') && !string.IsNullOrWhiteSpace(lines[i]); i++)
+                    AppendRow(html, lines[i], "td");
+                html.Append("</table>\n");
+            }
+            else
+            {
+                paragraph.Add(line.Trim());
+                i++;
+            }
+        }
+
+        FlushParagraph();
+        return html.ToString();
+    }
+
+    private static void AppendRow(StringBuilder html, string line, string cellTag)
+    {
+        var row = line.Trim();
+        if (row.StartsWith('|'))
+            row = row[1..];
+        if (row.EndsWith('|') && !row.EndsWith("\\|", StringComparison.Ordinal))
+            row = row[..^1];
+
+        html.Append("<tr>");
+        foreach (var cell in Regex.Split(row, @"(?<!\\)\|"))
+            html.Append($"<{cellTag}>").Append(Inline(cell.Trim().Replace("\\|", "|"))).Append($"</{cellTag}>");
+        html.Append("</tr>");
+    }
+
+    /// <summary>
+    /// Inline Markdown as HTML. Code spans are taken out first so that nothing inside
+    /// them is read as emphasis or a link.
+    /// </summary>
+    private static string Inline(string text)
+    {
+        var html = new StringBuilder();
+        int pos = 0;
+        while (pos < text.Length)
+        {
+            var open = CodeSpanFence().Match(text, pos);
+            if (!open.Success)
+                break;
+
+            var close = text.IndexOf(open.Value, open.Index + open.Length, StringComparison.Ordinal);
+            if (close < 0)
+                break;
+
+            html.Append(Spans(text[pos..open.Index]));
+            var code = text[(open.Index + open.Length)..close].Trim();
+            html.Append("<code>").Append(WebUtility.HtmlEncode(code)).Append("</code>");
+            pos = close + open.Length;
+        }
+
+        html.Append(Spans(text[pos..]));
+        return html.ToString();
+    }
+
+    private static string Spans(string text)
+    {
+        var html = WebUtility.HtmlEncode(text);
+        html = Link().Replace(html, "<a href=\"$2\">$1</a>");
+        html = Strong().Replace(html, m => $"<strong>{m.Groups[1].Value}{m.Groups[2].Value}</strong>");
+        html = Strike().Replace(html, "<del>$1</del>");
+        html = EmphasisSpan().Replace(html, m => $"<em>{m.Groups[1].Value}{m.Groups[2].Value}</em>");
+        return html;
+    }
+}
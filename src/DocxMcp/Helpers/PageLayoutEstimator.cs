@@ -0,0 +1,579 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// The body elements (typed ID paths) that start and end on a page, and the
+/// estimated number of lines on it.
+/// </summary>
+public sealed record PageRange(int Page, string? First, string? Last, int Lines);
+
+/// <summary>
+/// Estimated pagination of a document.
+/// </summary>
+public sealed record PageEstimate(int Pages, int Sections, List<PageRange> Ranges);
+
+/// <summary>
+/// Estimates page breaks without a layout engine. Each section's page size,
+/// margins and columns give the space per page; paragraphs take lines of an
+/// average character width (half the font size) and a line height of 1.2 times
+/// the largest font, scaled by line spacing, plus spacing before and after.
+/// Tables take the height of their rows, images their extent. Explicit page
+/// breaks, page-break-before and section breaks start new pages.
+///
+/// The result is an approximation: kerning, hyphenation, widow control,
+/// floating objects, headers and footers are not modelled.
+/// </summary>
+public static class PageLayoutEstimator
+{
+    private const int TwipsPerEmu = 635;
+    private const int DefaultFontSize = 20; // half-points: 10pt when nothing is specified
+    private const int DefaultCellMargins = 216; // 0.075in left + right
+
+    public static PageEstimate Estimate(WordprocessingDocument doc)
+    {
+        var main = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var body = main.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var styles = new StyleLookup(main.StyleDefinitionsPart?.Styles);
+        var sections = SplitSections(body);
+        var layout = new Cursor();
+
+        for (int s = 0; s < sections.Count; s++)
+        {
+            var (elements, sectPr) = sections[s];
+            var page = PageGeometry.From(sectPr);
+
+            if (s > 0)
+            {
+                var type = sectPr?.GetFirstChild<SectionType>()?.Val?.InnerText ?? "nextPage";
+                layout.StartSection(page, type);
+            }
+            else
+            {
+                layout.Geometry = page;
+            }
+
+            foreach (var element in elements)
+            {
+                switch (element)
+                {
+                    case Paragraph p:
+                        PlaceParagraph(layout, styles, p, page.ColumnWidth, PathOf(p));
+                        break;
+                    case Table t:
+                        PlaceTable(layout, styles, t, page.ColumnWidth, PathOf(t));
+                        break;
+                    case SdtBlock sdt:
+                        foreach (var inner in sdt.SdtContentBlock?.Elements<Paragraph>() ?? [])
+                            PlaceParagraph(layout, styles, inner, page.ColumnWidth, PathOf(sdt));
+                        break;
+                }
+            }
+        }
+
+        return new PageEstimate(layout.Ranges.Count, sections.Count, layout.Ranges);
+    }
+
+    /// <summary>
+    /// Text column width of the section holding a top-level body element, in twips.
+    /// </summary>
+    internal static int AvailableWidth(OpenXmlElement element)
+    {
+        var sectPr = element.ElementsAfter()
+            .OfType<Paragraph>()
+            .Select(p => p.ParagraphProperties?.SectionProperties)
+            .FirstOrDefault(s => s is not null)
+            ?? element.Parent?.GetFirstChild<SectionProperties>();
+        return PageGeometry.From(sectPr).ColumnWidth;
+    }
+
+    /// <summary>
+    /// Estimated content widths of table cells, in twips, without cell margins:
+    /// the longest word (the narrowest the cell can get without breaking words)
+    /// and the longest line when nothing wraps.
+    /// </summary>
+    internal static List<(int Min, int Max)> ContentWidths(WordprocessingDocument doc, IEnumerable<TableCell> cells)
+    {
+        var styles = new StyleLookup(doc.MainDocumentPart?.StyleDefinitionsPart?.Styles);
+        var widths = new List<(int, int)>();
+        foreach (var cell in cells)
+        {
+            int min = 0, max = 0;
+            foreach (var p in cell.Descendants<Paragraph>())
+            {
+                var chain = styles.ParagraphChain(p);
+                var line = 0;
+                foreach (var run in p.Descendants<Run>())
+                {
+                    var em = styles.FontSize(run, chain) * 5; // half an em per character, in twips
+                    foreach (var child in run.ChildElements)
+                    {
+                        switch (child)
+                        {
+                            case Text t:
+                                line += t.Text.Length * em;
+                                foreach (var word in t.Text.Split(' ', StringSplitOptions.RemoveEmptyEntries))
+                                    min = Math.Max(min, word.Length * em);
+                                break;
+                            case TabChar:
+                                line += 720;
+                                break;
+                            case Break:
+                                max = Math.Max(max, line);
+                                line = 0;
+                                break;
+                            case Drawing drawing:
+                                var cx = (int)((drawing.Descendants<DocumentFormat.OpenXml.Drawing.Wordprocessing.Extent>()
+                                    .FirstOrDefault()?.Cx?.Value ?? 0) / TwipsPerEmu);
+                                line += cx;
+                                min = Math.Max(min, cx);
+                                break;
+                        }
+                    }
+                }
+                max = Math.Max(max, line);
+            }
+            widths.Add((min, Math.Max(min, max)));
+        }
+        return widths;
+    }
+
+    /// <summary>
+    /// Typed ID path of a top-level body element, as used in <see cref="PageRange"/>.
+    /// </summary>
+    internal static string? PathOf(OpenXmlElement element)
+    {
+        var id = ElementIdManager.GetId(element);
+        if (id is null) return null;
+        var kind = element switch
+        {
+            Paragraph => "paragraph",
+            Table => "table",
+            _ => element.LocalName
+        };
+        return $"/body/{kind}[id='{id}']";
+    }
+
+    /// <summary>
+    /// Body elements grouped by section. A paragraph holding a sectPr ends its
+    /// section; the body's final sectPr describes the last one.
+    /// </summary>
+    private static List<(List<OpenXmlElement> Elements, SectionProperties? SectPr)> SplitSections(Body body)
+    {
+        var sections = new List<(List<OpenXmlElement>, SectionProperties?)>();
+        var current = new List<OpenXmlElement>();
+        foreach (var element in body.ChildElements)
+        {
+            if (element is SectionProperties) continue;
+            current.Add(element);
+            if (element is Paragraph { ParagraphProperties.SectionProperties: { } sectPr })
+            {
+                sections.Add((current, sectPr));
+                current = [];
+            }
+        }
+        sections.Add((current, body.GetFirstChild<SectionProperties>()));
+        return sections;
+    }
+
+    // --- Paragraphs ---
+
+    private sealed record ParagraphMetrics(int Lines, int LineHeight, int Before, int After, int Extra, bool BreakBefore, int PageBreaks);
+
+    private static void PlaceParagraph(Cursor layout, StyleLookup styles, Paragraph p, int width, string? path)
+    {
+        var m = Measure(styles, p, width);
+
+        if (m.BreakBefore)
+            layout.NewPage();
+
+        layout.AddSpace(m.Before);
+        layout.AddLines(m.Lines, m.LineHeight, path);
+        if (m.Extra > 0)
+            layout.AddBlock(m.Extra, path);
+        layout.AddSpace(m.After);
+
+        for (int i = 0; i < m.PageBreaks; i++)
+        {
+            layout.NewPage();
+            layout.Mark(path);
+        }
+    }
+
+    private static ParagraphMetrics Measure(StyleLookup styles, Paragraph p, int width)
+    {
+        var pPr = p.ParagraphProperties;
+        var chain = styles.ParagraphChain(p);
+
+        var spacing = Resolve(pPr?.SpacingBetweenLines, chain, s => s.StyleParagraphProperties?.SpacingBetweenLines,
+            styles.DefaultSpacing);
+        var before = ParseTwips(spacing(s => s.Before)) ?? 0;
+        var after = ParseTwips(spacing(s => s.After)) ?? 0;
+        var line = ParseTwips(spacing(s => s.Line));
+        var rule = spacing(s => s.LineRule?.InnerText) ?? "auto";
+
+        var indentation = Resolve(pPr?.Indentation, chain, s => s.StyleParagraphProperties?.Indentation, null);
+        var indent = (ParseTwips(indentation(i => i.Left ?? i.Start)) ?? 0) + (ParseTwips(indentation(i => i.Right ?? i.End)) ?? 0);
+        var available = Math.Max(width - indent, 720);
+
+        long textWidth = 0;
+        int maxSize = 0, lineBreaks = 0, pageBreaks = 0, extra = 0;
+        foreach (var run in p.Descendants<Run>())
+        {
+            var size = styles.FontSize(run, chain);
+            maxSize = Math.Max(maxSize, size);
+            foreach (var child in run.ChildElements)
+            {
+                switch (child)
+                {
+                    case Text t:
+                        textWidth += (long)t.Text.Length * size * 5; // half an em per character, in twips
+                        break;
+                    case TabChar:
+                        textWidth += 720;
+                        break;
+                    case Break br when br.Type?.InnerText == "page":
+                        pageBreaks++;
+                        break;
+                    case Break:
+                        lineBreaks++;
+                        break;
+                    case Drawing drawing:
+                        extra += (int)((drawing.Descendants<DocumentFormat.OpenXml.Drawing.Wordprocessing.Extent>()
+                            .FirstOrDefault()?.Cy?.Value ?? 0) / TwipsPerEmu);
+                        break;
+                }
+            }
+        }
+        if (maxSize == 0)
+            maxSize = styles.FontSize(null, chain);
+
+        var lines = Math.Max(1, (int)Math.Ceiling(textWidth / (double)available)) + lineBreaks;
+
+        var natural = maxSize * 12; // 1.2 × font size in points, in twips
+        var lineHeight = rule switch
+        {
+            "exact" when line is int exact => exact,
+            "atLeast" when line is int least => Math.Max(least, natural),
+            _ => line is int auto ? natural * auto / 240 : natural
+        };
+
+        var breakBefore = Resolve(pPr?.PageBreakBefore, chain, s => s.StyleParagraphProperties?.PageBreakBefore, null)
+            (b => b.Val is null || b.Val.Value ? "on" : null) is not null;
+
+        return new ParagraphMetrics(lines, Math.Max(lineHeight, 1), before, after, extra, breakBefore, pageBreaks);
+    }
+
+    /// <summary>
+    /// First value found on the paragraph, then its style chain, then the default.
+    /// </summary>
+    private static Func<Func<T, string?>, string?> Resolve<T>(T? own, List<Style> chain, Func<Style, T?> fromStyle, T? fallback)
+        where T : class =>
+        read =>
+        {
+            if (own is not null && read(own) is string v) return v;
+            foreach (var style in chain)
+            {
+                if (fromStyle(style) is { } props && read(props) is string sv) return sv;
+            }
+            return fallback is not null ? read(fallback) : null;
+        };
+
+    private static int? ParseTwips(string? value) =>
+        int.TryParse(value, out var twips) ? twips : null;
+
+    // --- Tables ---
+
+    private static void PlaceTable(Cursor layout, StyleLookup styles, Table table, int width, string? path)
+    {
+        foreach (var row in table.Elements<TableRow>())
+        {
+            var height = RowHeight(styles, table, row, width);
+            var cantSplit = row.TableRowProperties?.GetFirstChild<CantSplit>() is not null;
+            layout.AddBlock(height, path, splittable: !cantSplit);
+        }
+    }
+
+    private static int TableHeight(StyleLookup styles, Table table, int width) =>
+        table.Elements<TableRow>().Sum(row => RowHeight(styles, table, row, width));
+
+    private static int RowHeight(StyleLookup styles, Table table, TableRow row, int width)
+    {
+        var cells = row.Elements<TableCell>().ToList();
+        var grid = table.GetFirstChild<TableGrid>()?.Elements<GridColumn>()
+            .Select(g => ParseTwips(g.Width?.Value) ?? 0).ToList() ?? [];
+
+        int column = 0, tallest = 0;
+        foreach (var cell in cells)
+        {
+            var span = cell.TableCellProperties?.GridSpan?.Val?.Value ?? 1;
+            var cellWidth = CellWidth(cell, grid, column, span, width, cells.Count);
+            column += span;
+
+            var inner = Math.Max(cellWidth - DefaultCellMargins, 360);
+            var height = 0;
+            foreach (var child in cell.ChildElements)
+            {
+                if (child is Paragraph p)
+                {
+                    var m = Measure(styles, p, inner);
+                    height += m.Before + m.Lines * m.LineHeight + m.Extra + m.After;
+                }
+                else if (child is Table nested)
+                {
+                    height += TableHeight(styles, nested, inner);
+                }
+            }
+            tallest = Math.Max(tallest, height);
+        }
+
+        var rowHeight = row.TableRowProperties?.GetFirstChild<TableRowHeight>();
+        if (rowHeight?.Val?.Value is uint specified)
+        {
+            return rowHeight.HeightType?.InnerText == "exact"
+                ? (int)specified
+                : Math.Max(tallest, (int)specified);
+        }
+        return tallest;
+    }
+
+    private static int CellWidth(TableCell cell, List<int> grid, int column, int span, int width, int cellCount)
+    {
+        var tcW = cell.TableCellProperties?.TableCellWidth;
+        if (tcW?.Type?.InnerText == "dxa" && ParseTwips(tcW.Width?.Value) is int dxa and > 0)
+            return dxa;
+        if (tcW?.Type?.InnerText == "pct" && ParseTwips(tcW.Width?.Value?.TrimEnd('%')) is int pct and > 0)
+            return width * pct / 5000;
+        if (column + span <= grid.Count && grid.Skip(column).Take(span).Sum() is var fromGrid and > 0)
+            return fromGrid;
+        return width / Math.Max(1, cellCount);
+    }
+
+    // --- Page geometry and flow ---
+
+    private sealed record PageGeometry(int ContentHeight, int ColumnWidth, int Columns)
+    {
+        public static PageGeometry From(SectionProperties? sectPr)
+        {
+            var size = sectPr?.GetFirstChild<PageSize>();
+            var margin = sectPr?.GetFirstChild<PageMargin>();
+            var cols = sectPr?.GetFirstChild<Columns>();
+
+            var pageWidth = (int)(size?.Width?.Value ?? 12240);
+            var pageHeight = (int)(size?.Height?.Value ?? 15840);
+            var top = Math.Abs(margin?.Top?.Value ?? 1440);
+            var bottom = Math.Abs(margin?.Bottom?.Value ?? 1440);
+            var left = (int)(margin?.Left?.Value ?? 1440);
+            var right = (int)(margin?.Right?.Value ?? 1440);
+
+            var columns = Math.Max(1, (int)(cols?.ColumnCount?.Value ?? 1));
+            var space = ParseTwips(cols?.Space?.Value) ?? 720;
+            var textWidth = pageWidth - left - right;
+            var columnWidth = (textWidth - space * (columns - 1)) / columns;
+
+            return new PageGeometry(Math.Max(pageHeight - top - bottom, 1440), Math.Max(columnWidth, 720), columns);
+        }
+    }
+
+    /// <summary>
+    /// Current position: page, column and height used in the column.
+    /// </summary>
+    private sealed class Cursor
+    {
+        public PageGeometry Geometry { get; set; } = PageGeometry.From(null);
+        public List<PageRange> Ranges { get; } = [new PageRange(1, null, null, 0)];
+
+        private int _column;
+        private int _used;
+
+        private int Remaining => Geometry.ContentHeight - _used;
+
+        public void NewPage()
+        {
+            Ranges.Add(new PageRange(Ranges.Count + 1, null, null, 0));
+            _column = 0;
+            _used = 0;
+        }
+
+        public void StartSection(PageGeometry geometry, string type)
+        {
+            Geometry = geometry;
+            switch (type)
+            {
+                case "continuous":
+                    _column = Math.Min(_column, geometry.Columns - 1);
+                    return;
+                case "nextColumn" when _column + 1 < geometry.Columns:
+                    _column++;
+                    _used = 0;
+                    return;
+                case "evenPage" or "oddPage":
+                    NewPage();
+                    // A blank page is inserted when the parity is wrong
+                    if ((Ranges.Count % 2 == 0) != (type == "evenPage"))
+                        NewPage();
+                    return;
+                default:
+                    NewPage();
+                    return;
+            }
+        }
+
+        /// <summary>Record that <paramref name="path"/> appears on the current page.</summary>
+        public void Mark(string? path)
+        {
+            if (path is null) return;
+            var current = Ranges[^1];
+            Ranges[^1] = current with { First = current.First ?? path, Last = path };
+        }
+
+        /// <summary>Spacing is dropped at the top of a column, as Word does after a break.</summary>
+        public void AddSpace(int height)
+        {
+            if (_used == 0) return;
+            _used = Math.Min(_used + height, Geometry.ContentHeight);
+        }
+
+        public void AddLines(int lines, int lineHeight, string? path)
+        {
+            while (lines > 0)
+            {
+                var fit = Remaining / lineHeight;
+                if (fit <= 0)
+                {
+                    NextColumn();
+                    fit = Math.Max(1, Remaining / lineHeight);
+                }
+
+                var placed = Math.Min(fit, lines);
+                Mark(path);
+                _used += placed * lineHeight;
+                Ranges[^1] = Ranges[^1] with { Lines = Ranges[^1].Lines + placed };
+                lines -= placed;
+            }
+        }
+
+        public void AddBlock(int height, string? path, bool splittable = false)
+        {
+            if (height <= Remaining)
+            {
+                Mark(path);
+                _used += height;
+                return;
+            }
+
+            if (!splittable && height <= Geometry.ContentHeight)
+            {
+                NextColumn();
+                Mark(path);
+                _used += height;
+                return;
+            }
+
+            // Taller than a page, or allowed to break: fill and continue
+            while (height > 0)
+            {
+                if (Remaining <= 0) NextColumn();
+                var placed = Math.Min(height, Remaining);
+                Mark(path);
+                _used += placed;
+                height -= placed;
+            }
+        }
+
+        private void NextColumn()
+        {
+            if (_column + 1 < Geometry.Columns)
+            {
+                _column++;
+                _used = 0;
+            }
+            else
+            {
+                NewPage();
+            }
+        }
+    }
+
+    // --- Styles ---
+
+    private sealed class StyleLookup
+    {
+        private readonly Dictionary<string, Style> _styles = new();
+        private readonly int? _defaultSize;
+        private readonly Style? _defaultParagraph;
+
+        public SpacingBetweenLines? DefaultSpacing { get; }
+
+        public StyleLookup(Styles? styles)
+        {
+            if (styles is null) return;
+            foreach (var style in styles.Elements<Style>())
+            {
+                if (style.StyleId?.Value is string id)
+                    _styles.TryAdd(id, style);
+                if (style.Type?.InnerText == "paragraph" && style.Default?.Value == true)
+                    _defaultParagraph = style;
+            }
+
+            var defaults = styles.DocDefaults;
+            _defaultSize = ParseTwips(defaults?.RunPropertiesDefault?.RunPropertiesBaseStyle?.FontSize?.Val?.Value);
+            DefaultSpacing = defaults?.ParagraphPropertiesDefault?.ParagraphPropertiesBaseStyle?.SpacingBetweenLines;
+        }
+
+        /// <summary>
+        /// The paragraph's style followed by its basedOn ancestors (the default
+        /// paragraph style when none is set).
+        /// </summary>
+        public List<Style> ParagraphChain(Paragraph p)
+        {
+            var id = p.ParagraphProperties?.ParagraphStyleId?.Val?.Value;
+            var start = id is not null && _styles.TryGetValue(id, out var s) ? s : _defaultParagraph;
+            return Chain(start);
+        }
+
+        private List<Style> Chain(Style? style)
+        {
+            var chain = new List<Style>();
+            while (style is not null && chain.Count < 10 && !chain.Contains(style))
+            {
+                chain.Add(style);
+                var basedOn = style.BasedOn?.Val?.Value;
+                style = basedOn is not null && _styles.TryGetValue(basedOn, out var parent) ? parent : null;
+            }
+            return chain;
+        }
+
+        /// <summary>
+        /// Font size in half-points: the run, its character style, the paragraph
+        /// style chain, then the document defaults.
+        /// </summary>
+        public int FontSize(Run? run, List<Style> paragraphChain)
+        {
+            if (ParseTwips(run?.RunProperties?.FontSize?.Val?.Value) is int own)
+                return own;
+
+            var runStyle = run?.RunProperties?.RunStyle?.Val?.Value;
+            if (runStyle is not null && _styles.TryGetValue(runStyle, out var rs))
+            {
+                foreach (var style in Chain(rs))
+                {
+                    if (ParseTwips(style.StyleRunProperties?.FontSize?.Val?.Value) is int size)
+                        return size;
+                }
+            }
+
+            foreach (var style in paragraphChain)
+            {
+                if (ParseTwips(style.StyleRunProperties?.FontSize?.Val?.Value) is int size)
+                    return size;
+            }
+            return _defaultSize ?? DefaultFontSize;
+        }
+    }
+}
@@ -0,0 +1,311 @@
+using System.Text;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using D = DocumentFormat.OpenXml.Drawing;
+using P = DocumentFormat.OpenXml.Presentation;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>One slide of an outline deck: a heading and the bullets under it.</summary>
+public sealed record OutlineSlide(string Title, List<OutlineBullet> Bullets);
+
+/// <summary>A bullet and its indent level (0 = top).</summary>
+public sealed record OutlineBullet(string Text, int Level);
+
+/// <summary>
+/// Turns a document's outline into a PPTX skeleton: one slide per Heading 1/2, its
+/// bullets taken from the list items and lower headings under it, or from the opening
+/// sentences of its paragraphs when it has none. The deck uses a plain built-in
+/// master, to be restyled in PowerPoint.
+/// </summary>
+public static class PptxOutlineHelper
+{
+    /// <summary>Paragraph sentences used for a slide without list items or sub-headings.</summary>
+    private const int FallbackSentences = 3;
+
+    /// <summary>Longest bullet, in characters.</summary>
+    private const int MaxBulletChars = 160;
+
+    /// <summary>
+    /// Slides for <paramref name="doc"/>, at most <paramref name="maxBullets"/> bullets each.
+    /// Content before the first Heading 1/2 is skipped.
+    /// </summary>
+    public static List<OutlineSlide> BuildOutline(WordprocessingDocument doc, int maxBullets)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var slides = new List<OutlineSlide>();
+        OutlineSlide? current = null;
+        var sentences = new List<string>();
+        var underSubheading = false;
+
+        void Finish()
+        {
+            if (current is not null && current.Bullets.Count == 0)
+                current.Bullets.AddRange(sentences.Take(FallbackSentences).Select(s => new OutlineBullet(s, 0)));
+            sentences.Clear();
+        }
+
+        void AddBullet(string text, int level)
+        {
+            if (current is not null && current.Bullets.Count < maxBullets && text.Length > 0)
+                current.Bullets.Add(new OutlineBullet(Shorten(text), Math.Clamp(level, 0, 4)));
+        }
+
+        foreach (var paragraph in body.Elements<Paragraph>())
+        {
+            var text = paragraph.InnerText.Trim();
+            var level = paragraph.GetHeadingLevel();
+            if (level is 1 or 2)
+            {
+                Finish();
+                current = new OutlineSlide(text, []);
+                slides.Add(current);
+                underSubheading = false;
+            }
+            else if (level > 2)
+            {
+                AddBullet(text, 0);
+                underSubheading = true;
+            }
+            else if (ListLevel(paragraph) is { } listLevel)
+            {
+                AddBullet(text, listLevel + (underSubheading ? 1 : 0));
+            }
+            else if (current is not null && sentences.Count < FallbackSentences && FirstSentence(text) is { } sentence)
+            {
+                sentences.Add(sentence);
+            }
+        }
+        Finish();
+
+        return slides;
+    }
+
+    /// <summary>
+    /// Title from the document's title property, or its first Title-styled paragraph.
+    /// </summary>
+    public static string? DocumentTitle(WordprocessingDocument doc)
+    {
+        var title = doc.PackageProperties.Title;
+        if (!string.IsNullOrWhiteSpace(title))
+            return title.Trim();
+        return doc.MainDocumentPart?.Document?.Body?.Elements<Paragraph>()
+            .FirstOrDefault(p => string.Equals(p.GetStyleId(), "Title", StringComparison.OrdinalIgnoreCase))
+            ?.InnerText.Trim() is { Length: > 0 } styled ? styled : null;
+    }
+
+    /// <summary>
+    /// A PPTX with a title slide (when <paramref name="title"/> is set) then one
+    /// title-and-content slide per outline slide.
+    /// </summary>
+    public static byte[] CreateDeck(string? title, IReadOnlyList<OutlineSlide> slides)
+    {
+        using var stream = new MemoryStream();
+        using (var pptx = PresentationDocument.Create(stream, PresentationDocumentType.Presentation))
+        {
+            var presentationPart = pptx.AddPresentationPart();
+            var masterPart = presentationPart.AddNewPart<SlideMasterPart>("rId1");
+            Feed(masterPart, SlideMasterXml);
+            var titleLayout = masterPart.AddNewPart<SlideLayoutPart>("rId1");
+            Feed(titleLayout, TitleLayoutXml);
+            titleLayout.AddPart(masterPart);
+            var contentLayout = masterPart.AddNewPart<SlideLayoutPart>("rId2");
+            Feed(contentLayout, ContentLayoutXml);
+            contentLayout.AddPart(masterPart);
+            var themePart = masterPart.AddNewPart<ThemePart>("rId3");
+            Feed(themePart, ThemeXml);
+            presentationPart.AddPart(themePart);
+
+            var slideIds = new P.SlideIdList();
+            uint nextId = 256;
+            void AddSlide(SlideLayoutPart layout, P.Slide slide)
+            {
+                var slidePart = presentationPart.AddNewPart<SlidePart>();
+                slidePart.Slide = slide;
+                slidePart.AddPart(layout);
+                slideIds.Append(new P.SlideId { Id = nextId++, RelationshipId = presentationPart.GetIdOfPart(slidePart) });
+            }
+
+            if (title is not null)
+            {
+                AddSlide(titleLayout, Slide(
+                    Placeholder(2, "Title 1", new P.PlaceholderShape { Type = P.PlaceholderValues.CenteredTitle },
+                        [TextParagraph(title, 0)])));
+            }
+            foreach (var slide in slides)
+            {
+                var shapes = new List<P.Shape>
+                {
+                    Placeholder(2, "Title 1", new P.PlaceholderShape { Type = P.PlaceholderValues.Title },
+                        [TextParagraph(slide.Title, 0)])
+                };
+                if (slide.Bullets.Count > 0)
+                {
+                    shapes.Add(Placeholder(3, "Content 2", new P.PlaceholderShape { Index = 1 },
+                        slide.Bullets.Select(b => TextParagraph(b.Text, b.Level))));
+                }
+                AddSlide(contentLayout, Slide([.. shapes]));
+            }
+
+            presentationPart.Presentation = new P.Presentation(
+                new P.SlideMasterIdList(new P.SlideMasterId
+                {
+                    Id = 2147483648U,
+                    RelationshipId = presentationPart.GetIdOfPart(masterPart)
+                }),
+                slideIds,
+                new P.SlideSize { Cx = 12192000, Cy = 6858000 },
+                new P.NotesSize { Cx = 6858000, Cy = 9144000 });
+        }
+        return stream.ToArray();
+    }
+
+    private static int? ListLevel(Paragraph paragraph)
+    {
+        var numbering = paragraph.ParagraphProperties?.NumberingProperties;
+        if (numbering is not null)
+            return numbering.NumberingLevelReference?.Val?.Value ?? 0;
+        return paragraph.GetStyleId() is "ListBullet" or "ListNumber" or "ListParagraph" ? 0 : null;
+    }
+
+    private static string? FirstSentence(string text)
+    {
+        if (text.Length == 0)
+            return null;
+        var end = text.IndexOfAny(['.', '!', '?']);
+        return Shorten(end < 0 ? text : text[..(end + 1)]);
+    }
+
+    private static string Shorten(string text) =>
+        text.Length <= MaxBulletChars ? text : text[..(MaxBulletChars - 1)].TrimEnd() + "…";
+
+    private static P.Slide Slide(params P.Shape[] shapes)
+    {
+        var tree = new P.ShapeTree(
+            new P.NonVisualGroupShapeProperties(
+                new P.NonVisualDrawingProperties { Id = 1U, Name = "" },
+                new P.NonVisualGroupShapeDrawingProperties(),
+                new P.ApplicationNonVisualDrawingProperties()),
+            new P.GroupShapeProperties(new D.TransformGroup()));
+        tree.Append(shapes);
+        return new P.Slide(new P.CommonSlideData(tree), new P.ColorMapOverride(new D.MasterColorMapping()));
+    }
+
+    private static P.Shape Placeholder(uint id, string name, P.PlaceholderShape placeholder,
+        IEnumerable<D.Paragraph> paragraphs)
+    {
+        var text = new P.TextBody(new D.BodyProperties(), new D.ListStyle());
+        text.Append(paragraphs);
+        return new P.Shape(
+            new P.NonVisualShapeProperties(
+                new P.NonVisualDrawingProperties { Id = id, Name = name },
+                new P.NonVisualShapeDrawingProperties(new D.ShapeLocks { NoGrouping = true }),
+                new P.ApplicationNonVisualDrawingProperties(placeholder)),
+            new P.ShapeProperties(),
+            text);
+    }
+
+    private static D.Paragraph TextParagraph(string text, int level)
+    {
+        var paragraph = new D.Paragraph();
+        if (level > 0)
+            paragraph.Append(new D.ParagraphProperties { Level = level });
+        paragraph.Append(new D.Run(new D.RunProperties { Language = "en-US" }, new D.Text(text)));
+        return paragraph;
+    }
+
+    private static void Feed(OpenXmlPart part, string xml)
+    {
+        using var data = new MemoryStream(Encoding.UTF8.GetBytes(xml));
+        part.FeedData(data);
+    }
+
+    private const string Namespaces =
+        "xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" " +
+        "xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\" " +
+        "xmlns:p=\"http://schemas.openxmlformats.org/presentationml/2006/main\"";
+
+    private const string GroupShape =
+        "<p:nvGrpSpPr><p:cNvPr id=\"1\" name=\"\"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr>" +
+        "<p:grpSpPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"0\" cy=\"0\"/>" +
+        "<a:chOff x=\"0\" y=\"0\"/><a:chExt cx=\"0\" cy=\"0\"/></a:xfrm></p:grpSpPr>";
+
+    private static string PlaceholderXml(int id, string name, string ph, string? xfrm, string bodyPr = "<a:bodyPr/>") =>
+        $"<p:sp><p:nvSpPr><p:cNvPr id=\"{id}\" name=\"{name}\"/><p:cNvSpPr><a:spLocks noGrp=\"1\"/></p:cNvSpPr>" +
+        $"<p:nvPr>{ph}</p:nvPr></p:nvSpPr><p:spPr>{xfrm}</p:spPr>" +
+        $"<p:txBody>{bodyPr}<a:lstStyle/><a:p><a:endParaRPr lang=\"en-US\"/></a:p></p:txBody></p:sp>";
+
+    private static string Xfrm(long x, long y, long cx, long cy) =>
+        $"<a:xfrm><a:off x=\"{x}\" y=\"{y}\"/><a:ext cx=\"{cx}\" cy=\"{cy}\"/></a:xfrm>" +
+        "<a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom>";
+
+    private static string Level(int n, long marL, int size) =>
+        $"<a:lvl{n}pPr marL=\"{marL}\" indent=\"-228600\"><a:buFont typeface=\"Arial\"/><a:buChar char=\"•\"/>" +
+        $"<a:defRPr sz=\"{size}\"><a:solidFill><a:schemeClr val=\"tx1\"/></a:solidFill>" +
+        $"<a:latin typeface=\"+mn-lt\"/></a:defRPr></a:lvl{n}pPr>";
+
+    private static readonly string SlideMasterXml =
+        $"<p:sldMaster {Namespaces}><p:cSld>" +
+        "<p:bg><p:bgRef idx=\"1001\"><a:schemeClr val=\"bg1\"/></p:bgRef></p:bg><p:spTree>" + GroupShape +
+        PlaceholderXml(2, "Title Placeholder 1", "<p:ph type=\"title\"/>",
+            Xfrm(838200, 365125, 10515600, 1325563), "<a:bodyPr anchor=\"ctr\"/>") +
+        PlaceholderXml(3, "Text Placeholder 2", "<p:ph type=\"body\" idx=\"1\"/>",
+            Xfrm(838200, 1825625, 10515600, 4351338)) +
+        "</p:spTree></p:cSld>" +
+        "<p:clrMap bg1=\"lt1\" tx1=\"dk1\" bg2=\"lt2\" tx2=\"dk2\" accent1=\"accent1\" accent2=\"accent2\" " +
+        "accent3=\"accent3\" accent4=\"accent4\" accent5=\"accent5\" accent6=\"accent6\" hlink=\"hlink\" " +
+        "folHlink=\"folHlink\"/>" +
+        "<p:sldLayoutIdLst><p:sldLayoutId id=\"2147483649\" r:id=\"rId1\"/>" +
+        "<p:sldLayoutId id=\"2147483650\" r:id=\"rId2\"/></p:sldLayoutIdLst>" +
+        "<p:txStyles><p:titleStyle><a:lvl1pPr algn=\"l\"><a:defRPr sz=\"4000\"><a:solidFill>" +
+        "<a:schemeClr val=\"tx1\"/></a:solidFill><a:latin typeface=\"+mj-lt\"/></a:defRPr></a:lvl1pPr>" +
+        "</p:titleStyle><p:bodyStyle>" +
+        Level(1, 228600, 2800) + Level(2, 685800, 2400) + Level(3, 1143000, 2000) +
+        Level(4, 1600200, 1800) + Level(5, 2057400, 1800) +
+        "</p:bodyStyle><p:otherStyle><a:defPPr><a:defRPr lang=\"en-US\"/></a:defPPr></p:otherStyle>" +
+        "</p:txStyles></p:sldMaster>";
+
+    private static readonly string TitleLayoutXml =
+        $"<p:sldLayout {Namespaces} type=\"title\" preserve=\"1\"><p:cSld name=\"Title Slide\"><p:spTree>" +
+        GroupShape +
+        PlaceholderXml(2, "Title 1", "<p:ph type=\"ctrTitle\"/>",
+            Xfrm(1524000, 1122363, 9144000, 2387600), "<a:bodyPr anchor=\"b\"/>") +
+        "</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>";
+
+    private static readonly string ContentLayoutXml =
+        $"<p:sldLayout {Namespaces} type=\"obj\" preserve=\"1\"><p:cSld name=\"Title and Content\"><p:spTree>" +
+        GroupShape +
+        PlaceholderXml(2, "Title 1", "<p:ph type=\"title\"/>", null) +
+        PlaceholderXml(3, "Content Placeholder 2", "<p:ph idx=\"1\"/>", null) +
+        "</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>";
+
+    private static string SchemeColor(string name, string rgb) =>
+        $"<a:{name}><a:srgbClr val=\"{rgb}\"/></a:{name}>";
+
+    private static string Fonts(string latin) =>
+        $"<a:latin typeface=\"{latin}\"/><a:ea typeface=\"\"/><a:cs typeface=\"\"/>";
+
+    private const string SolidFill = "<a:solidFill><a:schemeClr val=\"phClr\"/></a:solidFill>";
+
+    private static readonly string ThemeXml =
+        "<a:theme xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" name=\"Office Theme\">" +
+        "<a:themeElements><a:clrScheme name=\"Office\">" +
+        "<a:dk1><a:sysClr val=\"windowText\" lastClr=\"000000\"/></a:dk1>" +
+        "<a:lt1><a:sysClr val=\"window\" lastClr=\"FFFFFF\"/></a:lt1>" +
+        SchemeColor("dk2", "44546A") + SchemeColor("lt2", "E7E6E6") +
+        SchemeColor("accent1", "4472C4") + SchemeColor("accent2", "ED7D31") + SchemeColor("accent3", "A5A5A5") +
+        SchemeColor("accent4", "FFC000") + SchemeColor("accent5", "5B9BD5") + SchemeColor("accent6", "70AD47") +
+        SchemeColor("hlink", "0563C1") + SchemeColor("folHlink", "954F72") +
+        "</a:clrScheme><a:fontScheme name=\"Office\">" +
+        $"<a:majorFont>{Fonts("Calibri Light")}</a:majorFont><a:minorFont>{Fonts("Calibri")}</a:minorFont>" +
+        "</a:fontScheme><a:fmtScheme name=\"Office\">" +
+        $"<a:fillStyleLst>{SolidFill}{SolidFill}{SolidFill}</a:fillStyleLst>" +
+        "<a:lnStyleLst>" +
+        string.Concat(new[] { 6350, 12700, 19050 }.Select(w => $"<a:ln w=\"{w}\">{SolidFill}</a:ln>")) +
+        "</a:lnStyleLst><a:effectStyleLst>" +
+        string.Concat(Enumerable.Repeat("<a:effectStyle><a:effectLst/></a:effectStyle>", 3)) +
+        $"</a:effectStyleLst><a:bgFillStyleLst>{SolidFill}{SolidFill}{SolidFill}</a:bgFillStyleLst>" +
+        "</a:fmtScheme></a:themeElements></a:theme>";
+}
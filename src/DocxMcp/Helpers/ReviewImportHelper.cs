@@ -0,0 +1,357 @@
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>A reviewer's comment, placed on a paragraph of the live document.</summary>
+public sealed record ImportedComment(
+    string Path, string? AnchorText, string Text, string Author, string Initials, DateTime Date);
+
+/// <summary>
+/// A reviewer's tracked change in a paragraph of the live document: Delete is removed at
+/// Offset (in the paragraph's run text) and Insert goes in its place.
+/// </summary>
+public sealed record ImportedChange(
+    string Path, int Offset, string Delete, string Insert, string Author, DateTime Date);
+
+/// <summary>
+/// A comment or change of the reviewed copy that couldn't be placed in the live document.
+/// </summary>
+public sealed record UnmatchedAnnotation(string Kind, string Author, string Text, string Reason);
+
+/// <summary>
+/// What a reviewed copy adds to the live document, with each annotation mapped to a
+/// live paragraph.
+/// </summary>
+public sealed record ReviewImport(
+    List<ImportedComment> Comments,
+    List<ImportedChange> Changes,
+    int AlreadyPresent,
+    List<UnmatchedAnnotation> Unmatched);
+
+/// <summary>
+/// Brings the comments and tracked changes a reviewer made in Word on a copy of a document
+/// back into the live session. Paragraphs are matched by element ID when the copy kept
+/// them and the text still agrees, otherwise by content: the same text nearest the same
+/// position, or for comments the closest wording.
+/// </summary>
+public static partial class ReviewImportHelper
+{
+    /// <summary>Word overlap a paragraph needs to take a comment whose text has changed.</summary>
+    private const double FuzzyThreshold = 0.5;
+
+    [GeneratedRegex(@"\s+")]
+    private static partial Regex Whitespace();
+
+    [GeneratedRegex(@"\w+")]
+    private static partial Regex Word();
+
+    /// <summary>
+    /// Annotations of <paramref name="reviewed"/> that <paramref name="live"/> doesn't have yet.
+    /// Comments already in the live document (same author and text) are counted, not repeated.
+    /// </summary>
+    public static ReviewImport Collect(WordprocessingDocument live, WordprocessingDocument reviewed)
+    {
+        var liveBody = live.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var reviewedBody = reviewed.MainDocumentPart?.Document?.Body
+            ?? throw new ArgumentException("The reviewed copy has no body.");
+
+        var targets = liveBody.Descendants<Paragraph>()
+            .Select(p => (Paragraph: p, Path: TerminologyHelper.PathOf(p, liveBody), Text: RunText(p)))
+            .Where(t => t.Path is not null)
+            .Select(t => new Target(t.Paragraph, t.Path!, t.Text))
+            .ToList();
+        var reviewedParagraphs = reviewedBody.Descendants<Paragraph>().ToList();
+
+        Target? Align(Paragraph paragraph, string baseText, bool fuzzy)
+        {
+            var id = ElementIdManager.GetId(paragraph);
+            var byId = id is null ? null : targets.FirstOrDefault(t => ElementIdManager.GetId(t.Paragraph) == id);
+            if (byId is not null && Normalize(byId.Text) == Normalize(baseText))
+                return byId;
+
+            // Where the paragraph would sit in the live document if nothing moved
+            var expected = reviewedParagraphs.Count == 0
+                ? 0
+                : (double)reviewedParagraphs.IndexOf(paragraph) * targets.Count / reviewedParagraphs.Count;
+            var exact = targets
+                .Select((t, i) => (Target: t, Index: i))
+                .Where(x => Normalize(x.Target.Text) == Normalize(baseText))
+                .OrderBy(x => Math.Abs(x.Index - expected))
+                .Select(x => x.Target)
+                .FirstOrDefault();
+            if (exact is not null || !fuzzy)
+                return exact;
+
+            var words = Words(baseText);
+            return targets
+                .Select((t, i) => (Target: t, Index: i, Score: Overlap(words, Words(t.Text))))
+                .Where(x => x.Score >= FuzzyThreshold)
+                .OrderByDescending(x => x.Score)
+                .ThenBy(x => Math.Abs(x.Index - expected))
+                .Select(x => x.Target)
+                .FirstOrDefault();
+        }
+
+        var comments = new List<ImportedComment>();
+        var unmatched = new List<UnmatchedAnnotation>();
+        var alreadyPresent = 0;
+        var existing = CommentHelper.ListComments(live)
+            .Select(c => (c.Author, c.Text))
+            .ToHashSet();
+
+        foreach (var comment in CommentHelper.ListComments(reviewed))
+        {
+            if (existing.Contains((comment.Author, comment.Text)))
+            {
+                alreadyPresent++;
+                continue;
+            }
+
+            var anchor = AnchorParagraph(reviewedBody, comment.Id.ToString());
+            var target = anchor is null ? null : Align(anchor, BaseText(anchor), fuzzy: true);
+            if (target is null)
+            {
+                unmatched.Add(new UnmatchedAnnotation("comment", comment.Author, comment.Text,
+                    anchor is null
+                        ? "the comment isn't anchored in the body"
+                        : "no paragraph of the document matches the commented one"));
+                continue;
+            }
+
+            var anchorText = comment.AnchoredText is { Length: > 0 } text && target.Text.Contains(text, StringComparison.Ordinal)
+                ? text
+                : null;
+            comments.Add(new ImportedComment(target.Path, anchorText, comment.Text, comment.Author,
+                comment.Initials, comment.Date ?? DeterministicOutput.UtcNow));
+        }
+
+        var changes = new List<ImportedChange>();
+        foreach (var paragraph in reviewedParagraphs)
+        {
+            var hunks = Hunks(paragraph);
+            if (hunks.Count == 0)
+                continue;
+
+            var inserted = paragraph.ParagraphProperties?.ParagraphMarkRunProperties?.GetFirstChild<Inserted>() is not null;
+            var baseText = BaseText(paragraph);
+            var target = inserted ? null : Align(paragraph, baseText, fuzzy: false);
+            if (target is null || target.Text != baseText)
+            {
+                foreach (var hunk in hunks)
+                {
+                    unmatched.Add(new UnmatchedAnnotation("change", hunk.Author, Describe(hunk),
+                        inserted
+                            ? "the change is in a paragraph the reviewer added; paragraphs aren't imported"
+                            : target is null
+                                ? "no paragraph of the document has the text the change was made on"
+                                : "the paragraph was edited since the copy was made"));
+                }
+                continue;
+            }
+
+            // Right to left, so earlier offsets stay valid as changes are applied
+            changes.AddRange(hunks
+                .OrderByDescending(h => h.Offset)
+                .Select(h => h with { Path = target.Path }));
+        }
+
+        return new ReviewImport(comments, changes, alreadyPresent, unmatched);
+    }
+
+    /// <summary>
+    /// Apply a reviewer's change to a live paragraph as tracked changes by the reviewer.
+    /// </summary>
+    public static void ApplyChange(WordprocessingDocument doc, Paragraph paragraph, ImportedChange change)
+    {
+        var text = RunText(paragraph);
+        if (change.Offset < 0 || change.Offset + change.Delete.Length > text.Length
+            || string.CompareOrdinal(text, change.Offset, change.Delete, 0, change.Delete.Length) != 0)
+        {
+            throw new InvalidOperationException(
+                $"'{change.Delete}' is not at offset {change.Offset} of the paragraph.");
+        }
+
+        var end = change.Offset + change.Delete.Length;
+        var startRun = SplitAt(paragraph, change.Offset);
+        SplitAt(paragraph, end);
+
+        Run? template = null;
+        OpenXmlElement? last = null;
+        foreach (var (run, start, length) in Runs(paragraph).ToList())
+        {
+            if (length == 0 || start < change.Offset || start + length > end)
+                continue;
+
+            template ??= run;
+            var deleted = (Run)run.CloneNode(true);
+            foreach (var t in deleted.Elements<Text>().ToList())
+            {
+                t.InsertBeforeSelf(new DeletedText(t.Text) { Space = SpaceProcessingModeValues.Preserve });
+                t.Remove();
+            }
+            var revision = new DeletedRun
+            {
+                Id = RevisionHelper.AllocateRevisionId(doc).ToString(),
+                Author = change.Author,
+                Date = change.Date
+            };
+            revision.AppendChild(deleted);
+            paragraph.ReplaceChild(revision, run);
+            last = revision;
+        }
+
+        if (change.Insert.Length == 0)
+            return;
+
+        template ??= Runs(paragraph).LastOrDefault(r => r.Length > 0 && r.Start + r.Length <= change.Offset).Run
+            ?? startRun;
+        var insertedRun = new Run();
+        if (template?.RunProperties is { } properties)
+            insertedRun.AppendChild(properties.CloneNode(true));
+        insertedRun.AppendChild(new Text(change.Insert) { Space = SpaceProcessingModeValues.Preserve });
+        var insertion = new InsertedRun
+        {
+            Id = RevisionHelper.AllocateRevisionId(doc).ToString(),
+            Author = change.Author,
+            Date = change.Date
+        };
+        insertion.AppendChild(insertedRun);
+
+        if (last is not null)
+            last.InsertAfterSelf(insertion);
+        else if (startRun is not null)
+            startRun.InsertBeforeSelf(insertion);
+        else
+            paragraph.AppendChild(insertion);
+    }
+
+    private sealed record Target(Paragraph Paragraph, string Path, string Text);
+
+    /// <summary>
+    /// Text of a paragraph's runs, the text replace_text searches.
+    /// </summary>
+    private static string RunText(Paragraph paragraph) =>
+        string.Concat(paragraph.Elements<Run>().Select(r => r.InnerText));
+
+    /// <summary>
+    /// Text of a reviewed paragraph as it was before the reviewer's tracked changes.
+    /// </summary>
+    private static string BaseText(Paragraph paragraph) =>
+        string.Concat(paragraph.ChildElements.Select(c => c switch
+        {
+            Run or DeletedRun => c.InnerText,
+            _ => ""
+        }));
+
+    /// <summary>
+    /// Tracked changes of a reviewed paragraph, with offsets in its text before the changes.
+    /// A deletion and insertion next to each other are one change.
+    /// </summary>
+    private static List<ImportedChange> Hunks(Paragraph paragraph)
+    {
+        var hunks = new List<ImportedChange>();
+        ImportedChange? current = null;
+        var offset = 0;
+
+        foreach (var child in paragraph.ChildElements)
+        {
+            switch (child)
+            {
+                case Run run when run.InnerText.Length > 0:
+                    if (current is not null)
+                        hunks.Add(current);
+                    current = null;
+                    offset += run.InnerText.Length;
+                    break;
+                case DeletedRun deleted:
+                    current ??= NewHunk(offset, deleted.Author?.Value, deleted.Date?.Value);
+                    current = current with { Delete = current.Delete + deleted.InnerText };
+                    offset += deleted.InnerText.Length;
+                    break;
+                case InsertedRun inserted:
+                    current ??= NewHunk(offset, inserted.Author?.Value, inserted.Date?.Value);
+                    current = current with { Insert = current.Insert + inserted.InnerText };
+                    break;
+            }
+        }
+        if (current is not null)
+            hunks.Add(current);
+
+        return hunks.Where(h => h.Delete.Length > 0 || h.Insert.Length > 0).ToList();
+    }
+
+    private static ImportedChange NewHunk(int offset, string? author, DateTime? date) =>
+        new("", offset, "", "", author ?? "Reviewer", date ?? DeterministicOutput.UtcNow);
+
+    private static string Describe(ImportedChange change) =>
+        (change.Delete.Length, change.Insert.Length) switch
+        {
+            (0, _) => $"insert '{change.Insert}'",
+            (_, 0) => $"delete '{change.Delete}'",
+            _ => $"replace '{change.Delete}' with '{change.Insert}'"
+        };
+
+    /// <summary>
+    /// The paragraph a comment starts in: the one holding its range start, the next one
+    /// when the range starts between paragraphs, or the one holding its reference mark.
+    /// </summary>
+    private static Paragraph? AnchorParagraph(Body body, string commentId)
+    {
+        var start = body.Descendants<CommentRangeStart>().FirstOrDefault(s => s.Id?.Value == commentId);
+        if (start is not null)
+        {
+            return start.Ancestors<Paragraph>().FirstOrDefault()
+                ?? start.ElementsAfter()
+                    .Select(e => e as Paragraph ?? e.Descendants<Paragraph>().FirstOrDefault())
+                    .FirstOrDefault(p => p is not null);
+        }
+        return body.Descendants<CommentReference>()
+            .FirstOrDefault(r => r.Id?.Value == commentId)?
+            .Ancestors<Paragraph>().FirstOrDefault();
+    }
+
+    /// <summary>
+    /// Split the run straddling <paramref name="position"/> of the run text so a run starts
+    /// there. Returns the run starting at the position, or null at the end of the paragraph.
+    /// </summary>
+    private static Run? SplitAt(Paragraph paragraph, int position)
+    {
+        foreach (var (run, start, length) in Runs(paragraph).ToList())
+        {
+            if (length == 0 || start + length <= position)
+                continue;
+            if (start == position)
+                return run;
+
+            if (run.Elements<Text>().Count() != 1 || run.ChildElements.Any(c => c is not (RunProperties or Text)))
+                throw new InvalidOperationException("The change falls inside a run with tabs, breaks or fields.");
+            var tail = CommentHelper.SplitRun(run, position - start);
+            run.InsertAfterSelf(tail);
+            return tail;
+        }
+        return null;
+    }
+
+    private static IEnumerable<(Run Run, int Start, int Length)> Runs(Paragraph paragraph)
+    {
+        var start = 0;
+        foreach (var run in paragraph.Elements<Run>())
+        {
+            var length = run.InnerText.Length;
+            yield return (run, start, length);
+            start += length;
+        }
+    }
+
+    private static string Normalize(string text) => Whitespace().Replace(text, " ").Trim();
+
+    private static HashSet<string> Words(string text) =>
+        Word().Matches(text).Select(m => m.Value.ToLowerInvariant()).ToHashSet();
+
+    private static double Overlap(HashSet<string> a, HashSet<string> b) =>
+        a.Count == 0 && b.Count == 0 ? 0 : (double)a.Intersect(b).Count() / a.Union(b).Count();
+}
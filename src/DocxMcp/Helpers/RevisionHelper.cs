@@ -33,7 +33,7 @@ public static class RevisionHelper
         var settingsPart = mainPart.DocumentSettingsPart;
         if (settingsPart is null)
         {
-            settingsPart = mainPart.AddNewPart<DocumentSettingsPart>();
+            settingsPart = mainPart.AddNewPart<DocumentSettingsPart>(DeterministicOutput.NextRelationshipId(mainPart));
             settingsPart.Settings = new Settings();
         }
         else if (settingsPart.Settings is null)
@@ -637,7 +637,7 @@ public static class RevisionHelper
     {
         var revisionId = AllocateRevisionId(doc);
         var effectiveAuthor = author ?? DefaultAuthor;
-        var date = DateTime.UtcNow;
+        var date = DeterministicOutput.UtcNow;
 
         if (newElement is Paragraph para)
         {
@@ -698,7 +698,7 @@ public static class RevisionHelper
         string? author = null)
     {
         var effectiveAuthor = author ?? DefaultAuthor;
-        var date = DateTime.UtcNow;
+        var date = DeterministicOutput.UtcNow;
 
         if (element is Paragraph para)
         {
@@ -792,7 +792,7 @@ public static class RevisionHelper
         string? author = null)
     {
         var effectiveAuthor = author ?? DefaultAuthor;
-        var date = DateTime.UtcNow;
+        var date = DeterministicOutput.UtcNow;
 
         var paragraphs = element is Paragraph p
             ? new List<Paragraph> { p }
@@ -922,7 +922,7 @@ public static class RevisionHelper
     {
         var revisionId = AllocateRevisionId(doc);
         var effectiveAuthor = author ?? DefaultAuthor;
-        var date = DateTime.UtcNow;
+        var date = DeterministicOutput.UtcNow;
 
         var existingProps = run.RunProperties;
         var previousProps = existingProps is not null
@@ -969,7 +969,7 @@ public static class RevisionHelper
     {
         var revisionId = AllocateRevisionId(doc);
         var effectiveAuthor = author ?? DefaultAuthor;
-        var date = DateTime.UtcNow;
+        var date = DeterministicOutput.UtcNow;
 
         var existingProps = para.ParagraphProperties;
         var previousProps = existingProps is not null
@@ -0,0 +1,316 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Section breaks. In OOXML a paragraph's sectPr ends the section containing it and
+/// holds that section's layout; the body's last sectPr holds the layout of the final
+/// section. A break therefore splits the section it is inserted into: the part before
+/// it gets the break's layout, the part after it keeps the section's original one.
+/// </summary>
+public static class SectionHelper
+{
+    /// <summary>
+    /// Named page sizes, in twips (portrait).
+    /// </summary>
+    private static readonly Dictionary<string, (uint Width, uint Height)> PageSizes =
+        new(StringComparer.OrdinalIgnoreCase)
+        {
+            ["letter"] = (12240, 15840),
+            ["legal"] = (12240, 20160),
+            ["a3"] = (16838, 23811),
+            ["a4"] = (11906, 16838),
+            ["a5"] = (8391, 11906),
+        };
+
+    /// <summary>
+    /// Schema order of the sectPr children this helper writes.
+    /// </summary>
+    private static readonly Type[] ChildOrder =
+    [
+        typeof(HeaderReference), typeof(FooterReference), typeof(FootnoteProperties),
+        typeof(EndnoteProperties), typeof(SectionType), typeof(PageSize), typeof(PageMargin),
+        typeof(PaperSource), typeof(PageBorders), typeof(LineNumberType), typeof(PageNumberType),
+        typeof(Columns), typeof(FormProtection), typeof(VerticalTextAlignmentOnPage),
+        typeof(NoEndnote), typeof(TitlePage), typeof(TextDirection), typeof(BiDi),
+        typeof(GutterOnRight), typeof(DocGrid), typeof(PrinterSettingsReference),
+    ];
+
+    /// <summary>
+    /// Complete a section break paragraph after it has been inserted: start from the
+    /// layout of the section it splits (page size, margins, columns, headers and footers),
+    /// then apply the break's own options.
+    ///
+    /// Options (twips): break_type, page_size (letter, legal, a3, a4, a5), page_width,
+    /// page_height, orientation (portrait, landscape), margins {top, bottom, left, right,
+    /// header, footer, gutter}, header / footer (text, or {"default": text, "first": text}).
+    /// </summary>
+    public static void CompleteSectionBreak(Paragraph paragraph, JsonElement value, MainDocumentPart mainPart)
+    {
+        if (paragraph.Parent is not Body body)
+        {
+            paragraph.Remove();
+            throw new InvalidOperationException("Section breaks can only be added directly to the body.");
+        }
+        var pPr = paragraph.ParagraphProperties ??= new ParagraphProperties();
+
+        var following = FollowingSection(body, paragraph);
+        var sectPr = following.CloneNode(true) as SectionProperties ?? new SectionProperties();
+        sectPr.RemoveAllChildren<SectionPropertiesChange>();
+        pPr.SectionProperties = sectPr;
+
+        Set(sectPr, new SectionType { Val = ParseBreakType(value) });
+        ApplyPageSize(sectPr, value);
+        ApplyMargins(sectPr, value);
+
+        foreach (var (kind, isHeader) in new[] { ("header", true), ("footer", false) })
+        {
+            if (!value.TryGetProperty(kind, out var spec) || spec.ValueKind == JsonValueKind.Null)
+                continue;
+
+            var texts = spec.ValueKind == JsonValueKind.String
+                ? new Dictionary<HeaderFooterValues, string> { [HeaderFooterValues.Default] = spec.GetString() ?? "" }
+                : ReadHeaderFooterTexts(spec, kind);
+
+            foreach (var (type, text) in texts)
+            {
+                // Sections without their own reference inherit the previous section's,
+                // so the section after the break keeps what it showed before
+                PinReference(body, following, sectPr, type, isHeader, mainPart);
+                SetReference(sectPr, type, isHeader, CreateHeaderFooter(mainPart, isHeader, text));
+                if (type == HeaderFooterValues.First)
+                    Set(sectPr, new TitlePage());
+            }
+        }
+    }
+
+    /// <summary>
+    /// The sectPr of the section <paramref name="paragraph"/> is in, before it ends one:
+    /// the next paragraph-level sectPr, else the body's own (added if missing).
+    /// </summary>
+    private static SectionProperties FollowingSection(Body body, Paragraph paragraph)
+    {
+        var next = paragraph.ElementsAfter()
+            .OfType<Paragraph>()
+            .Select(p => p.ParagraphProperties?.SectionProperties)
+            .FirstOrDefault(s => s is not null);
+        if (next is not null)
+            return next;
+
+        var last = body.Elements<SectionProperties>().LastOrDefault();
+        if (last is null)
+        {
+            last = new SectionProperties();
+            body.AppendChild(last);
+        }
+        return last;
+    }
+
+    internal static SectionMarkValues ParseBreakType(JsonElement value)
+    {
+        var type = value.TryGetProperty("break_type", out var bt)
+            ? bt.GetString() ?? "nextPage"
+            : "nextPage";
+
+        return type.ToLowerInvariant() switch
+        {
+            "nextpage" or "next_page" => SectionMarkValues.NextPage,
+            "continuous" => SectionMarkValues.Continuous,
+            "evenpage" or "even_page" => SectionMarkValues.EvenPage,
+            "oddpage" or "odd_page" => SectionMarkValues.OddPage,
+            _ => SectionMarkValues.NextPage
+        };
+    }
+
+    private static void ApplyPageSize(SectionProperties sectPr, JsonElement value)
+    {
+        var current = sectPr.GetFirstChild<PageSize>();
+        var width = current?.Width?.Value ?? PageSizes["letter"].Width;
+        var height = current?.Height?.Value ?? PageSizes["letter"].Height;
+        var changed = false;
+
+        if (value.TryGetProperty("page_size", out var named))
+        {
+            var name = named.GetString() ?? "";
+            if (!PageSizes.TryGetValue(name, out var size))
+                throw new ArgumentException(
+                    $"Unknown page_size '{name}'. Supported: {string.Join(", ", PageSizes.Keys)}.");
+            (width, height) = width > height ? (size.Height, size.Width) : (size.Width, size.Height);
+            changed = true;
+        }
+        if (value.TryGetProperty("page_width", out var w))
+        {
+            width = w.GetUInt32();
+            changed = true;
+        }
+        if (value.TryGetProperty("page_height", out var h))
+        {
+            height = h.GetUInt32();
+            changed = true;
+        }
+
+        PageOrientationValues? orientation = current?.Orient?.Value;
+        if (value.TryGetProperty("orientation", out var o))
+        {
+            orientation = (o.GetString() ?? "").ToLowerInvariant() switch
+            {
+                "portrait" => PageOrientationValues.Portrait,
+                "landscape" => PageOrientationValues.Landscape,
+                var other => throw new ArgumentException(
+                    $"Unknown orientation '{other}'. Supported: portrait, landscape.")
+            };
+            // Only swap when the dimensions weren't given explicitly
+            var landscape = orientation == PageOrientationValues.Landscape;
+            if (!value.TryGetProperty("page_width", out _) && !value.TryGetProperty("page_height", out _)
+                && landscape != (width > height))
+                (width, height) = (height, width);
+            changed = true;
+        }
+
+        if (!changed)
+            return;
+
+        var pageSize = new PageSize { Width = width, Height = height };
+        if (orientation == PageOrientationValues.Landscape)
+            pageSize.Orient = PageOrientationValues.Landscape;
+        Set(sectPr, pageSize);
+    }
+
+    private static void ApplyMargins(SectionProperties sectPr, JsonElement value)
+    {
+        if (!value.TryGetProperty("margins", out var margins))
+            return;
+        if (margins.ValueKind != JsonValueKind.Object)
+            throw new ArgumentException("margins must be an object of twips values.");
+
+        var pgMar = sectPr.GetFirstChild<PageMargin>()?.CloneNode(true) as PageMargin
+            ?? new PageMargin { Top = 1440, Bottom = 1440, Left = 1440, Right = 1440, Header = 720, Footer = 720, Gutter = 0 };
+
+        foreach (var margin in margins.EnumerateObject())
+        {
+            var twips = margin.Value.GetInt32();
+            switch (margin.Name)
+            {
+                case "top": pgMar.Top = twips; break;
+                case "bottom": pgMar.Bottom = twips; break;
+                case "left": pgMar.Left = (uint)Math.Max(0, twips); break;
+                case "right": pgMar.Right = (uint)Math.Max(0, twips); break;
+                case "header": pgMar.Header = (uint)Math.Max(0, twips); break;
+                case "footer": pgMar.Footer = (uint)Math.Max(0, twips); break;
+                case "gutter": pgMar.Gutter = (uint)Math.Max(0, twips); break;
+                default:
+                    throw new ArgumentException(
+                        $"Unknown margin '{margin.Name}'. Supported: top, bottom, left, right, header, footer, gutter.");
+            }
+        }
+        Set(sectPr, pgMar);
+    }
+
+    private static Dictionary<HeaderFooterValues, string> ReadHeaderFooterTexts(JsonElement spec, string kind)
+    {
+        if (spec.ValueKind != JsonValueKind.Object)
+            throw new ArgumentException($"{kind} must be a string or an object with 'default' and/or 'first'.");
+
+        var texts = new Dictionary<HeaderFooterValues, string>();
+        foreach (var entry in spec.EnumerateObject())
+        {
+            var type = entry.Name switch
+            {
+                "default" => HeaderFooterValues.Default,
+                "first" => HeaderFooterValues.First,
+                _ => throw new ArgumentException($"Unknown {kind} type '{entry.Name}'. Supported: default, first.")
+            };
+            texts[type] = entry.Value.GetString() ?? "";
+        }
+        return texts;
+    }
+
+    /// <summary>
+    /// Give <paramref name="following"/> an explicit reference of <paramref name="type"/>
+    /// if it only inherited one, so a new reference on the section before it doesn't leak.
+    /// </summary>
+    private static void PinReference(Body body, SectionProperties following, SectionProperties current,
+        HeaderFooterValues type, bool isHeader, MainDocumentPart mainPart)
+    {
+        if (following == current || FindReference(following, type, isHeader) is not null)
+            return;
+
+        // What the following section showed: the closest explicit reference before it
+        var inherited = body.Descendants<SectionProperties>()
+                .TakeWhile(s => s != current)
+                .Reverse()
+                .Select(s => FindReference(s, type, isHeader))
+                .FirstOrDefault(id => id is not null);
+
+        SetReference(following, type, isHeader, inherited ?? CreateHeaderFooter(mainPart, isHeader, ""));
+    }
+
+    private static string? FindReference(SectionProperties sectPr, HeaderFooterValues type, bool isHeader) =>
+        isHeader
+            ? sectPr.Elements<HeaderReference>().FirstOrDefault(r => (r.Type?.Value ?? HeaderFooterValues.Default) == type)?.Id?.Value
+            : sectPr.Elements<FooterReference>().FirstOrDefault(r => (r.Type?.Value ?? HeaderFooterValues.Default) == type)?.Id?.Value;
+
+    private static void SetReference(SectionProperties sectPr, HeaderFooterValues type, bool isHeader, string id)
+    {
+        if (isHeader)
+        {
+            foreach (var existing in sectPr.Elements<HeaderReference>()
+                         .Where(r => (r.Type?.Value ?? HeaderFooterValues.Default) == type).ToList())
+                existing.Remove();
+            Insert(sectPr, new HeaderReference { Type = type, Id = id });
+        }
+        else
+        {
+            foreach (var existing in sectPr.Elements<FooterReference>()
+                         .Where(r => (r.Type?.Value ?? HeaderFooterValues.Default) == type).ToList())
+                existing.Remove();
+            Insert(sectPr, new FooterReference { Type = type, Id = id });
+        }
+    }
+
+    /// <summary>
+    /// Add a header or footer part holding one paragraph of <paramref name="text"/>;
+    /// returns its relationship ID.
+    /// </summary>
+    private static string CreateHeaderFooter(MainDocumentPart mainPart, bool isHeader, string text)
+    {
+        var paragraph = text.Length == 0
+            ? new Paragraph()
+            : new Paragraph(new Run(new Text(text) { Space = SpaceProcessingModeValues.Preserve }));
+        var id = DeterministicOutput.NextRelationshipId(mainPart);
+        if (isHeader)
+            mainPart.AddNewPart<HeaderPart>(id).Header = new Header(paragraph);
+        else
+            mainPart.AddNewPart<FooterPart>(id).Footer = new Footer(paragraph);
+        return id;
+    }
+
+    /// <summary>
+    /// Replace the child of the same type, or insert it in schema order.
+    /// </summary>
+    private static void Set<T>(SectionProperties sectPr, T element) where T : OpenXmlElement
+    {
+        var existing = sectPr.GetFirstChild<T>();
+        if (existing is not null)
+            sectPr.ReplaceChild(element, existing);
+        else
+            Insert(sectPr, element);
+    }
+
+    private static void Insert(SectionProperties sectPr, OpenXmlElement element)
+    {
+        var rank = Array.IndexOf(ChildOrder, element.GetType());
+        var before = sectPr.ChildElements.FirstOrDefault(c =>
+        {
+            var r = Array.IndexOf(ChildOrder, c.GetType());
+            return r > rank || (r < 0 && c is SectionPropertiesChange);
+        });
+        if (before is not null)
+            sectPr.InsertBefore(element, before);
+        else
+            sectPr.AppendChild(element);
+    }
+}
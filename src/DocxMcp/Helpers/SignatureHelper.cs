@@ -0,0 +1,214 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A party signing the document.
+/// </summary>
+public sealed record Signer(string Name, string? Title, string? Company, bool Date);
+
+/// <summary>
+/// The e-sign anchor texts placed in a signer's block (null when anchors are off).
+/// </summary>
+public sealed record SignerAnchors(string Signer, string? Signature, string? Date);
+
+/// <summary>
+/// Builds signature blocks: an optional company line, a "By:" signature line,
+/// the signer's name and title, and an optional date line. Each block is kept
+/// on one page.
+///
+/// E-sign anchors are text tags e-signature platforms search for to place
+/// their fields. They are written in white so they don't show when printed,
+/// at full size because Adobe Sign sizes its fields from the tag.
+/// </summary>
+public static class SignatureHelper
+{
+    public static readonly string[] Providers = ["none", "docusign", "adobe_sign"];
+    public static readonly string[] Layouts = ["stacked", "columns"];
+
+    private const string Line = "______________________________";
+    private const string ShortLine = "________________";
+
+    /// <summary>
+    /// Parse signers from a JSON array of {name, title?, company?, date?} objects.
+    /// </summary>
+    public static List<Signer> Parse(string json)
+    {
+        JsonNode? root;
+        try
+        {
+            root = JsonNode.Parse(json);
+        }
+        catch (JsonException ex)
+        {
+            throw new ArgumentException($"Invalid signers JSON — {ex.Message}");
+        }
+        if (root is not JsonArray array || array.Count == 0)
+            throw new ArgumentException("signers must be a non-empty JSON array of {name, title, company, date} objects.");
+
+        var signers = new List<Signer>();
+        for (int i = 0; i < array.Count; i++)
+        {
+            if (array[i] is not JsonObject obj)
+                throw new ArgumentException($"signers[{i}] must be an object.");
+
+            var name = obj["name"]?.GetValue<string>()?.Trim();
+            if (string.IsNullOrEmpty(name))
+                throw new ArgumentException($"signers[{i}] is missing 'name'.");
+
+            signers.Add(new Signer(
+                name,
+                NullIfBlank(obj["title"]?.GetValue<string>()),
+                NullIfBlank(obj["company"]?.GetValue<string>()),
+                obj["date"]?.GetValue<bool>() ?? true));
+        }
+        return signers;
+    }
+
+    private static string? NullIfBlank(string? s) => string.IsNullOrWhiteSpace(s) ? null : s.Trim();
+
+    /// <summary>
+    /// Anchor texts for the signer at <paramref name="index"/> (1-based), in the
+    /// provider's text-tag syntax.
+    /// </summary>
+    public static SignerAnchors AnchorsFor(Signer signer, int index, string provider) => provider switch
+    {
+        "docusign" => new SignerAnchors(signer.Name, $"\\s{index}\\", signer.Date ? $"\\d{index}\\" : null),
+        "adobe_sign" => new SignerAnchors(signer.Name,
+            $"{{{{Sig_es_:signer{index}:signature}}}}",
+            signer.Date ? $"{{{{Dte_es_:signer{index}:date}}}}" : null),
+        _ => new SignerAnchors(signer.Name, null, null)
+    };
+
+    /// <summary>
+    /// Insert signature blocks for <paramref name="signers"/> into <paramref name="parent"/>
+    /// at <paramref name="index"/> (or at the end of the body when parent is null).
+    /// Returns the inserted top-level elements and each signer's anchors.
+    /// </summary>
+    public static (List<OpenXmlElement> Elements, List<SignerAnchors> Anchors) Insert(
+        WordprocessingDocument doc, IReadOnlyList<Signer> signers, string provider, string layout,
+        OpenXmlElement? parent, int index)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var anchors = signers.Select((s, i) => AnchorsFor(s, i + 1, provider)).ToList();
+        var blocks = signers.Select((s, i) => BuildBlock(s, anchors[i])).ToList();
+
+        var elements = new List<OpenXmlElement>();
+        if (layout == "columns")
+        {
+            elements.Add(BuildTable(blocks));
+        }
+        else
+        {
+            foreach (var block in blocks)
+                elements.AddRange(block);
+        }
+
+        foreach (var element in elements)
+        {
+            ElementIdManager.AssignId(element);
+            ElementIdManager.ReassignIds(element, doc);
+        }
+
+        if (parent is null)
+        {
+            foreach (var element in elements)
+            {
+                if (body.GetFirstChild<SectionProperties>() is { } sectPr)
+                    body.InsertBefore(element, sectPr);
+                else
+                    body.AppendChild(element);
+            }
+        }
+        else
+        {
+            foreach (var element in elements)
+                parent.InsertChildAt(element, index++);
+        }
+
+        return (elements, anchors);
+    }
+
+    private static List<Paragraph> BuildBlock(Signer signer, SignerAnchors anchors)
+    {
+        var paragraphs = new List<Paragraph>();
+
+        if (signer.Company is not null)
+            paragraphs.Add(Para(new Run(new RunProperties(new Bold()), new Text(signer.Company.ToUpperInvariant()))));
+
+        // Leave room above the line for the handwritten signature
+        var signature = Para(Label("By: "), Anchor(anchors.Signature), new Run(new Text(Line)));
+        signature.ParagraphProperties!.SpacingBetweenLines = new SpacingBetweenLines { Before = "480" };
+        paragraphs.Add(signature);
+
+        paragraphs.Add(Para(Label("Name: "), new Run(new Text(signer.Name))));
+        if (signer.Title is not null)
+            paragraphs.Add(Para(Label("Title: "), new Run(new Text(signer.Title))));
+        if (signer.Date)
+            paragraphs.Add(Para(Label("Date: "), Anchor(anchors.Date), new Run(new Text(ShortLine))));
+
+        // Keep the block together, and leave a gap before the next one
+        foreach (var p in paragraphs.SkipLast(1))
+            p.ParagraphProperties!.KeepNext = new KeepNext();
+        var last = paragraphs[^1].ParagraphProperties!;
+        (last.SpacingBetweenLines ??= new SpacingBetweenLines()).After = "360";
+
+        return paragraphs;
+    }
+
+    private static Table BuildTable(List<List<Paragraph>> blocks)
+    {
+        const int columns = 2;
+        var table = new Table(new TableProperties(
+            new TableWidth { Width = "5000", Type = TableWidthUnitValues.Pct },
+            new TableBorders(
+                new TopBorder { Val = BorderValues.None },
+                new BottomBorder { Val = BorderValues.None },
+                new LeftBorder { Val = BorderValues.None },
+                new RightBorder { Val = BorderValues.None },
+                new InsideHorizontalBorder { Val = BorderValues.None },
+                new InsideVerticalBorder { Val = BorderValues.None }),
+            new TableLayout { Type = TableLayoutValues.Fixed }));
+
+        var grid = new TableGrid();
+        for (int i = 0; i < columns; i++)
+            grid.AppendChild(new GridColumn { Width = "4680" });
+        table.AppendChild(grid);
+
+        foreach (var chunk in blocks.Chunk(columns))
+        {
+            var row = new TableRow(new TableRowProperties(new CantSplit()));
+            for (int i = 0; i < columns; i++)
+            {
+                var cell = new TableCell(new TableCellProperties(
+                    new TableCellWidth { Width = "2500", Type = TableWidthUnitValues.Pct }));
+                if (i < chunk.Length)
+                    cell.Append(chunk[i]);
+                else
+                    cell.AppendChild(new Paragraph());
+                row.AppendChild(cell);
+            }
+            table.AppendChild(row);
+        }
+        return table;
+    }
+
+    private static Paragraph Para(params OpenXmlElement?[] runs) =>
+        new(new OpenXmlElement[] { new ParagraphProperties(new KeepLines()) }.Concat(runs.OfType<OpenXmlElement>()));
+
+    private static Run Label(string text) =>
+        new(new Text(text) { Space = SpaceProcessingModeValues.Preserve });
+
+    /// <summary>
+    /// An invisible anchor run, or null when there is no anchor.
+    /// </summary>
+    private static Run? Anchor(string? text) => text is null
+        ? null
+        : new Run(new RunProperties(new Color { Val = "FFFFFF" }), new Text(text));
+}
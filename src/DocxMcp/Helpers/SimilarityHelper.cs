@@ -0,0 +1,100 @@
+using System.Text;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Near-duplicate detection with MinHash: a document's text is cut into overlapping word
+/// shingles, and the share of equal slots in two signatures estimates the Jaccard
+/// similarity of their shingle sets. Formatting, IDs and word case don't count.
+/// </summary>
+public static partial class SimilarityHelper
+{
+    /// <summary>Words per shingle.</summary>
+    public const int ShingleSize = 5;
+
+    /// <summary>Slots per signature; the estimate is within about ±0.09 at 95% confidence.</summary>
+    public const int HashCount = 128;
+
+    private static readonly ulong[] Seeds = Enumerable.Range(1, HashCount)
+        .Select(i => Mix((ulong)i * 0x9E3779B97F4A7C15UL))
+        .ToArray();
+
+    [GeneratedRegex(@"\w+")]
+    private static partial Regex WordPattern();
+
+    /// <summary>
+    /// Displayed text of the body's paragraphs (tables included), without deleted text
+    /// or field instructions.
+    /// </summary>
+    public static string ExtractText(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body;
+        if (body is null)
+            return "";
+
+        var sb = new StringBuilder();
+        foreach (var paragraph in body.Descendants<Paragraph>())
+            sb.AppendLine(string.Concat(paragraph.Descendants<Text>().Select(t => t.Text)));
+        return sb.ToString();
+    }
+
+    /// <summary>
+    /// MinHash signature of the text's word shingles; empty when the text has no words.
+    /// Texts shorter than a shingle are one shingle.
+    /// </summary>
+    public static ulong[] Signature(string text)
+    {
+        var words = WordPattern().Matches(text).Select(m => m.Value.ToLowerInvariant()).ToList();
+        if (words.Count == 0)
+            return [];
+
+        var signature = Enumerable.Repeat(ulong.MaxValue, HashCount).ToArray();
+        var size = Math.Min(ShingleSize, words.Count);
+        for (var i = 0; i + size <= words.Count; i++)
+        {
+            var shingle = Fnv1a(string.Join(' ', words.Skip(i).Take(size)));
+            for (var k = 0; k < HashCount; k++)
+                signature[k] = Math.Min(signature[k], Mix(shingle ^ Seeds[k]));
+        }
+        return signature;
+    }
+
+    /// <summary>
+    /// Estimated Jaccard similarity of two signatures (0 to 1); 0 when either is empty.
+    /// </summary>
+    public static double Similarity(ulong[] a, ulong[] b)
+    {
+        if (a.Length == 0 || b.Length == 0 || a.Length != b.Length)
+            return 0;
+        var equal = 0;
+        for (var k = 0; k < a.Length; k++)
+        {
+            if (a[k] == b[k])
+                equal++;
+        }
+        return (double)equal / a.Length;
+    }
+
+    // Stable across processes, unlike string.GetHashCode
+    private static ulong Fnv1a(string s)
+    {
+        var hash = 0xCBF29CE484222325UL;
+        foreach (var b in Encoding.UTF8.GetBytes(s))
+        {
+            hash ^= b;
+            hash *= 0x100000001B3UL;
+        }
+        return hash;
+    }
+
+    // SplitMix64 finalizer
+    private static ulong Mix(ulong x)
+    {
+        x = (x ^ (x >> 30)) * 0xBF58476D1CE4E5B9UL;
+        x = (x ^ (x >> 27)) * 0x94D049BB133111EBUL;
+        return x ^ (x >> 31);
+    }
+}
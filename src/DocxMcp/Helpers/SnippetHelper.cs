@@ -0,0 +1,224 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using A = DocumentFormat.OpenXml.Drawing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Snippets are stored as standalone DOCX files holding only the snippet's body
+/// content, so they keep the styles, numbering and media of the document they
+/// were cut from. This class extracts snippets and imports them into other documents.
+/// </summary>
+public static class SnippetHelper
+{
+    /// <summary>
+    /// Reduce a document (a copy of the source) to the body elements
+    /// <paramref name="keep"/>. The final section properties are kept; comment
+    /// markers are dropped since comments belong to the source document.
+    /// </summary>
+    public static void Extract(WordprocessingDocument doc, IReadOnlyCollection<OpenXmlElement> keep)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        foreach (var child in body.ChildElements.ToList())
+        {
+            if (child is SectionProperties || keep.Contains(child))
+                continue;
+            child.Remove();
+        }
+
+        foreach (var marker in body.Descendants()
+            .Where(e => e is CommentRangeStart or CommentRangeEnd or CommentReference)
+            .ToList())
+        {
+            // A comment reference lives in its own run
+            if (marker is CommentReference && marker.Parent is Run run
+                && run.ChildElements.All(c => c == marker || c is RunProperties))
+                run.Remove();
+            else
+                marker.Remove();
+        }
+    }
+
+    /// <summary>
+    /// Body content of a snippet, cloned and ready to insert into <paramref name="target"/>:
+    /// images and hyperlinks are re-linked, and missing styles and numbering
+    /// definitions are copied over. Element IDs are kept as-is.
+    /// </summary>
+    public static List<OpenXmlElement> Import(WordprocessingDocument snippet, WordprocessingDocument target)
+    {
+        var sourceMain = snippet.MainDocumentPart
+            ?? throw new InvalidOperationException("Snippet has no MainDocumentPart.");
+        var sourceBody = sourceMain.Document?.Body
+            ?? throw new InvalidOperationException("Snippet has no body.");
+        var targetMain = target.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no MainDocumentPart.");
+
+        var elements = sourceBody.ChildElements
+            .Where(e => e is not SectionProperties)
+            .Select(e => e.CloneNode(true))
+            .ToList();
+
+        foreach (var element in elements)
+        {
+            CopyImages(element, sourceMain, targetMain);
+            CopyHyperlinks(element, sourceMain, targetMain);
+        }
+        CopyStyles(elements, sourceMain, targetMain);
+        CopyNumbering(elements, sourceMain, targetMain);
+
+        return elements;
+    }
+
+    private static void CopyImages(OpenXmlElement root, MainDocumentPart source, MainDocumentPart target)
+    {
+        var remapped = new Dictionary<string, string>();
+        foreach (var blip in root.Descendants<A.Blip>())
+        {
+            var embed = blip.Embed?.Value;
+            if (embed is null) continue;
+
+            if (!remapped.TryGetValue(embed, out var newId))
+            {
+                if (source.GetPartById(embed) is not ImagePart sourcePart) continue;
+
+                var targetPart = target.AddImagePart(sourcePart.ContentType, DeterministicOutput.NextRelationshipId(target));
+                using (var stream = sourcePart.GetStream())
+                {
+                    targetPart.FeedData(stream);
+                }
+                newId = target.GetIdOfPart(targetPart);
+                remapped[embed] = newId;
+            }
+            blip.Embed = newId;
+        }
+    }
+
+    private static void CopyHyperlinks(OpenXmlElement root, MainDocumentPart source, MainDocumentPart target)
+    {
+        foreach (var link in root.Descendants<Hyperlink>())
+        {
+            var id = link.Id?.Value;
+            if (id is null) continue;
+
+            var rel = source.HyperlinkRelationships.FirstOrDefault(r => r.Id == id);
+            if (rel is null) continue;
+
+            link.Id = target.AddHyperlinkRelationship(rel.Uri, rel.IsExternal,
+                DeterministicOutput.NextRelationshipId(target)).Id;
+        }
+    }
+
+    /// <summary>
+    /// Copy the styles used by the snippet (and the styles they are based on)
+    /// that the target doesn't define. Existing target styles win.
+    /// </summary>
+    private static void CopyStyles(List<OpenXmlElement> elements, MainDocumentPart source, MainDocumentPart target)
+    {
+        var sourceStyles = source.StyleDefinitionsPart?.Styles;
+        if (sourceStyles is null) return;
+
+        var pending = new Queue<string>(elements
+            .SelectMany(e => e.Descendants())
+            .Select(e => e switch
+            {
+                ParagraphStyleId p => p.Val?.Value,
+                RunStyle r => r.Val?.Value,
+                TableStyle t => t.Val?.Value,
+                _ => null
+            })
+            .OfType<string>()
+            .Distinct());
+        if (pending.Count == 0) return;
+
+        var stylesPart = target.StyleDefinitionsPart
+            ?? target.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(target));
+        stylesPart.Styles ??= new Styles();
+        var targetStyles = stylesPart.Styles;
+
+        var seen = new HashSet<string>();
+        while (pending.TryDequeue(out var styleId))
+        {
+            if (!seen.Add(styleId)) continue;
+            if (targetStyles.Elements<Style>().Any(s => s.StyleId?.Value == styleId)) continue;
+
+            var style = sourceStyles.Elements<Style>().FirstOrDefault(s => s.StyleId?.Value == styleId);
+            if (style is null) continue;
+
+            targetStyles.AppendChild((Style)style.CloneNode(true));
+            foreach (var related in new[] { style.BasedOn?.Val?.Value, style.LinkedStyle?.Val?.Value,
+                         style.NextParagraphStyle?.Val?.Value })
+            {
+                if (related is not null) pending.Enqueue(related);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Copy the numbering definitions used by the snippet under fresh IDs, so
+    /// imported lists keep their look without joining the target's lists.
+    /// </summary>
+    private static void CopyNumbering(List<OpenXmlElement> elements, MainDocumentPart source, MainDocumentPart target)
+    {
+        var sourceNumbering = source.NumberingDefinitionsPart?.Numbering;
+        if (sourceNumbering is null) return;
+
+        var numberingIds = elements
+            .SelectMany(e => e.Descendants<NumberingId>())
+            .ToList();
+        if (numberingIds.Count == 0) return;
+
+        var numberingPart = target.NumberingDefinitionsPart
+            ?? target.AddNewPart<NumberingDefinitionsPart>(DeterministicOutput.NextRelationshipId(target));
+        numberingPart.Numbering ??= new Numbering();
+        var targetNumbering = numberingPart.Numbering;
+
+        var nextNumId = targetNumbering.Elements<NumberingInstance>()
+            .Select(n => n.NumberID?.Value ?? 0).DefaultIfEmpty(0).Max() + 1;
+        var nextAbstractId = targetNumbering.Elements<AbstractNum>()
+            .Select(a => a.AbstractNumberId?.Value ?? 0).DefaultIfEmpty(-1).Max() + 1;
+
+        var numMap = new Dictionary<int, int>();
+        var abstractMap = new Dictionary<int, int>();
+        foreach (var numberingId in numberingIds)
+        {
+            var oldNumId = numberingId.Val?.Value;
+            if (oldNumId is null or 0) continue;
+
+            if (!numMap.TryGetValue(oldNumId.Value, out var newNumId))
+            {
+                var num = sourceNumbering.Elements<NumberingInstance>()
+                    .FirstOrDefault(n => n.NumberID?.Value == oldNumId);
+                if (num?.AbstractNumId?.Val?.Value is not int oldAbstractId) continue;
+
+                if (!abstractMap.TryGetValue(oldAbstractId, out var newAbstractId))
+                {
+                    var abstractNum = sourceNumbering.Elements<AbstractNum>()
+                        .FirstOrDefault(a => a.AbstractNumberId?.Value == oldAbstractId);
+                    if (abstractNum is null) continue;
+
+                    newAbstractId = nextAbstractId++;
+                    var abstractClone = (AbstractNum)abstractNum.CloneNode(true);
+                    abstractClone.AbstractNumberId = newAbstractId;
+                    // AbstractNum elements must precede all Num elements
+                    var firstNum = targetNumbering.GetFirstChild<NumberingInstance>();
+                    if (firstNum is not null)
+                        targetNumbering.InsertBefore(abstractClone, firstNum);
+                    else
+                        targetNumbering.AppendChild(abstractClone);
+                    abstractMap[oldAbstractId] = newAbstractId;
+                }
+
+                newNumId = nextNumId++;
+                var numClone = (NumberingInstance)num.CloneNode(true);
+                numClone.NumberID = newNumId;
+                numClone.AbstractNumId = new AbstractNumId { Val = newAbstractId };
+                targetNumbering.AppendChild(numClone);
+                numMap[oldNumId.Value] = newNumId;
+            }
+            numberingId.Val = newNumId;
+        }
+    }
+}
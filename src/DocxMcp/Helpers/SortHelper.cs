@@ -0,0 +1,282 @@
+using System.Globalization;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Outcome of a sort: how many rows or items were sorted, the sort key type
+/// that was detected, and the text of the duplicates that were removed.
+/// </summary>
+public sealed record SortResult(int Sorted, string KeyType, List<string> Removed);
+
+/// <summary>
+/// Sorts and deduplicates table rows and list items by moving the existing
+/// elements, so formatting, element IDs and numbering are preserved.
+/// Keys that are all numbers (currency, percentages and thousands separators
+/// allowed) or all dates sort by value; anything else sorts as text. Empty keys
+/// always go last.
+/// </summary>
+public static partial class SortHelper
+{
+    public static readonly string[] TableDedupeModes = ["none", "rows", "key"];
+
+    [GeneratedRegex(@"[\s$€£¥%,]")]
+    private static partial Regex NumberNoise();
+
+    [GeneratedRegex(@"\s+")]
+    private static partial Regex Whitespace();
+
+    /// <summary>
+    /// Sort a table's rows by a column. Leading repeat-header rows (and the first
+    /// row when <paramref name="hasHeader"/>) stay on top. <paramref name="byCol"/>
+    /// is a 0-based column index, or a header cell's text.
+    /// <paramref name="dedupe"/>: "rows" drops rows identical to an earlier one,
+    /// "key" drops rows whose sort key repeats; the first occurrence is kept.
+    /// </summary>
+    public static SortResult SortTable(Table table, string byCol, bool descending, bool hasHeader, string dedupe = "none")
+    {
+        if (!TableDedupeModes.Contains(dedupe))
+            throw new ArgumentException($"dedupe must be one of: {string.Join(", ", TableDedupeModes)}.");
+
+        var rows = table.Elements<TableRow>().ToList();
+        var headerCount = rows.TakeWhile(r => r.TableRowProperties?.GetFirstChild<TableHeader>() is not null).Count();
+        if (hasHeader)
+            headerCount = Math.Max(headerCount, 1);
+        headerCount = Math.Min(headerCount, rows.Count);
+
+        var column = ResolveColumn(rows.Take(headerCount).LastOrDefault(), byCol);
+        var dataRows = rows.Skip(headerCount).ToList();
+
+        if (dataRows.Any(r => r.Elements<TableCell>().Any(c => c.TableCellProperties?.VerticalMerge is not null)))
+            throw new ArgumentException("Cannot sort a table with vertically merged cells.");
+
+        var removed = new List<string>();
+        if (dedupe != "none")
+        {
+            var seen = new HashSet<string>(StringComparer.OrdinalIgnoreCase);
+            foreach (var row in dataRows.ToList())
+            {
+                var identity = dedupe == "key"
+                    ? Normalize(CellAt(row, column)?.InnerText)
+                    : string.Join("␟", row.Elements<TableCell>().Select(c => Normalize(c.InnerText)));
+                if (seen.Add(identity)) continue;
+
+                removed.Add(string.Join(" | ", row.Elements<TableCell>().Select(c => c.InnerText.Trim())));
+                dataRows.Remove(row);
+                row.Remove();
+            }
+        }
+
+        var keyed = dataRows.Select(r => (Row: r, Key: Normalize(CellAt(r, column)?.InnerText))).ToList();
+        var (keyType, comparison) = KeyComparison(keyed.Select(k => k.Key).ToList(), descending);
+        var sorted = keyed.OrderBy(k => k.Key, Comparer<string>.Create(comparison)).Select(k => k.Row).ToList();
+
+        Reorder(sorted);
+        return new SortResult(sorted.Count, keyType, removed);
+    }
+
+    /// <summary>
+    /// Sort consecutive list paragraphs alphabetically (or by value). Items at
+    /// the outermost level of the range are sorted; nested items and
+    /// continuation paragraphs move with the item above them. With
+    /// <paramref name="dedupe"/>, items whose text repeats an earlier item are removed.
+    /// </summary>
+    public static SortResult SortList(List<Paragraph> paragraphs, bool descending, bool dedupe)
+    {
+        if (paragraphs.Count == 0)
+            throw new ArgumentException("The list range is empty.");
+        if (paragraphs.All(p => NumberingOf(p) is null))
+            throw new ArgumentException("The range contains no list items.");
+
+        var topLevel = paragraphs.Where(p => NumberingOf(p) is not null).Min(p => LevelOf(p));
+        if (NumberingOf(paragraphs[0]) is null || LevelOf(paragraphs[0]) != topLevel)
+            throw new ArgumentException("The range must start with an outermost list item.");
+
+        var blocks = new List<List<Paragraph>>();
+        foreach (var paragraph in paragraphs)
+        {
+            if (NumberingOf(paragraph) is not null && LevelOf(paragraph) == topLevel)
+                blocks.Add([paragraph]);
+            else
+                blocks[^1].Add(paragraph);
+        }
+
+        var removed = new List<string>();
+        if (dedupe)
+        {
+            var seen = new HashSet<string>(StringComparer.OrdinalIgnoreCase);
+            foreach (var block in blocks.ToList())
+            {
+                if (seen.Add(string.Join("\n", block.Select(p => Normalize(p.InnerText))))) continue;
+
+                removed.Add(block[0].InnerText.Trim());
+                blocks.Remove(block);
+                foreach (var paragraph in block)
+                    paragraph.Remove();
+            }
+        }
+
+        var keyed = blocks.Select(b => (Block: b, Key: Normalize(b[0].InnerText))).ToList();
+        var (keyType, comparison) = KeyComparison(keyed.Select(k => k.Key).ToList(), descending);
+        var sorted = keyed.OrderBy(k => k.Key, Comparer<string>.Create(comparison))
+            .SelectMany(k => k.Block)
+            .ToList();
+
+        Reorder(sorted);
+        return new SortResult(keyed.Count, keyType, removed);
+    }
+
+    /// <summary>
+    /// The list that <paramref name="paragraph"/> belongs to: the consecutive
+    /// siblings that share its numbering, with the unnumbered paragraphs between them.
+    /// </summary>
+    public static List<Paragraph> ListAround(Paragraph paragraph)
+    {
+        var numId = NumberingOf(paragraph)
+            ?? throw new ArgumentException("The paragraph is not a list item.");
+
+        var siblings = paragraph.Parent!.ChildElements.ToList();
+        var index = siblings.IndexOf(paragraph);
+        bool InList(OpenXmlElement e) => e is Paragraph p && NumberingOf(p) == numId;
+
+        var first = index;
+        while (first > 0 && InList(siblings[first - 1]))
+            first--;
+
+        var last = index;
+        for (int i = index + 1; i < siblings.Count; i++)
+        {
+            if (InList(siblings[i]))
+                last = i;
+            else if (siblings[i] is not Paragraph p || NumberingOf(p) is not null || p.InnerText.Length == 0)
+                break;
+        }
+
+        return siblings.GetRange(first, last - first + 1).Cast<Paragraph>().ToList();
+    }
+
+    private static int ResolveColumn(TableRow? header, string byCol)
+    {
+        if (int.TryParse(byCol, out var index))
+        {
+            if (index < 0)
+                throw new ArgumentException("by_col must be a 0-based column index or a header text.");
+            return index;
+        }
+
+        if (header is null)
+            throw new ArgumentException($"Column '{byCol}' can only be found by name in a table with a header row.");
+
+        var column = 0;
+        foreach (var cell in header.Elements<TableCell>())
+        {
+            if (string.Equals(Normalize(cell.InnerText), Normalize(byCol), StringComparison.OrdinalIgnoreCase))
+                return column;
+            column += SpanOf(cell);
+        }
+        throw new ArgumentException($"No header cell named '{byCol}'.");
+    }
+
+    /// <summary>
+    /// The cell covering grid column <paramref name="column"/>, following gridSpan.
+    /// </summary>
+    private static TableCell? CellAt(TableRow row, int column)
+    {
+        var position = row.TableRowProperties?.GetFirstChild<GridBefore>()?.Val?.Value ?? 0;
+        foreach (var cell in row.Elements<TableCell>())
+        {
+            var span = SpanOf(cell);
+            if (column < position + span)
+                return column >= position ? cell : null;
+            position += span;
+        }
+        return null;
+    }
+
+    private static int SpanOf(TableCell cell) => cell.TableCellProperties?.GridSpan?.Val?.Value ?? 1;
+
+    private static string? NumberingOf(Paragraph paragraph) =>
+        paragraph.ParagraphProperties?.NumberingProperties?.NumberingId?.Val?.Value is int numId and not 0
+            ? numId.ToString(CultureInfo.InvariantCulture)
+            : null;
+
+    private static int LevelOf(Paragraph paragraph) =>
+        paragraph.ParagraphProperties?.NumberingProperties?.NumberingLevelReference?.Val?.Value ?? 0;
+
+    private static string Normalize(string? text) => Whitespace().Replace(text ?? "", " ").Trim();
+
+    /// <summary>
+    /// Move the elements so they appear in the given order, in the slots the
+    /// group already occupies.
+    /// </summary>
+    private static void Reorder<T>(List<T> ordered) where T : OpenXmlElement
+    {
+        if (ordered.Count < 2) return;
+
+        var parent = ordered[0].Parent!;
+        var anchor = ordered.Select(e => (Element: e, Index: parent.ChildElements.ToList().IndexOf(e)))
+            .MinBy(x => x.Index).Element.PreviousSibling();
+
+        foreach (var element in ordered)
+            element.Remove();
+
+        foreach (var element in ordered)
+        {
+            if (anchor is null)
+                parent.InsertAt(element, 0);
+            else
+                parent.InsertAfter(element, anchor);
+            anchor = element;
+        }
+    }
+
+    private static (string KeyType, Comparison<string> Comparison) KeyComparison(List<string> keys, bool descending)
+    {
+        var present = keys.Where(k => k.Length > 0).ToList();
+
+        Comparison<string> byValue;
+        string keyType;
+        if (present.Count > 0 && present.All(k => TryNumber(k, out _)))
+        {
+            keyType = "number";
+            byValue = (a, b) => { TryNumber(a, out var x); TryNumber(b, out var y); return x.CompareTo(y); };
+        }
+        else if (present.Count > 0 && present.All(k => TryDate(k, out _)))
+        {
+            keyType = "date";
+            byValue = (a, b) => { TryDate(a, out var x); TryDate(b, out var y); return x.CompareTo(y); };
+        }
+        else
+        {
+            keyType = "text";
+            byValue = (a, b) => string.Compare(a, b, CultureInfo.InvariantCulture, CompareOptions.IgnoreCase);
+        }
+
+        return (keyType, (a, b) =>
+        {
+            if (a.Length == 0 || b.Length == 0)
+                return (a.Length == 0).CompareTo(b.Length == 0);
+            var result = byValue(a, b);
+            return descending ? -result : result;
+        });
+    }
+
+    private static bool TryNumber(string text, out decimal value)
+    {
+        var cleaned = NumberNoise().Replace(text, "");
+        var negative = cleaned.StartsWith('(') && cleaned.EndsWith(')');
+        if (negative)
+            cleaned = cleaned[1..^1];
+
+        if (!decimal.TryParse(cleaned, NumberStyles.Float, CultureInfo.InvariantCulture, out value))
+            return false;
+        if (negative)
+            value = -value;
+        return true;
+    }
+
+    private static bool TryDate(string text, out DateTime value) =>
+        DateTime.TryParse(text, CultureInfo.InvariantCulture, DateTimeStyles.AllowWhiteSpaces, out value);
+}
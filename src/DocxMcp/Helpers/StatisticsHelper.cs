@@ -0,0 +1,139 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Headline counts for a document, as Word shows them in its statistics dialog.
+/// </summary>
+public sealed record DocumentStatistics(
+    int Words, int Characters, int Paragraphs, int Headings, int Tables, int Images, int Comments, int Pages)
+{
+    public static readonly string[] Fields =
+        ["words", "characters", "paragraphs", "headings", "tables", "images", "comments", "pages"];
+
+    public int this[string field] => field switch
+    {
+        "words" => Words,
+        "characters" => Characters,
+        "paragraphs" => Paragraphs,
+        "headings" => Headings,
+        "tables" => Tables,
+        "images" => Images,
+        "comments" => Comments,
+        "pages" => Pages,
+        _ => throw new ArgumentOutOfRangeException(nameof(field), field, null)
+    };
+}
+
+/// <summary>
+/// Counts for one top-level section: a top-level heading and everything up to the
+/// next one, or the content before the first heading (<see cref="Heading"/> null).
+/// <see cref="FirstId"/> and <see cref="LastId"/> are the IDs of the section's first
+/// and last body elements, usable as a range in other tools.
+/// </summary>
+public sealed record SectionStatistics(
+    string? Heading, int Level, string? FirstId, string? LastId,
+    int Words, int Paragraphs, int Subheadings, int Tables, int Images);
+
+/// <summary>
+/// Computes <see cref="DocumentStatistics"/> over the body, including table
+/// cells. Words are whitespace-separated tokens, characters include spaces,
+/// and only paragraphs with text count (as in Word). Pages are estimated.
+/// </summary>
+public static class StatisticsHelper
+{
+    private static readonly char[] Separators = [' ', '\t', '\n', '\r', '\u00A0'];
+
+    public static DocumentStatistics Compute(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        int words = 0, characters = 0, paragraphs = 0, headings = 0;
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var text = paragraph.InnerText;
+            if (text.Trim().Length == 0) continue;
+
+            paragraphs++;
+            characters += text.Length;
+            words += text.Split(Separators, StringSplitOptions.RemoveEmptyEntries).Length;
+            if (paragraph.GetHeadingLevel() > 0)
+                headings++;
+        }
+
+        return new DocumentStatistics(
+            words,
+            characters,
+            paragraphs,
+            headings,
+            body.Descendants<Table>().Count(),
+            body.Descendants<Drawing>().Count(),
+            doc.MainDocumentPart!.WordprocessingCommentsPart?.Comments?.Elements<Comment>().Count() ?? 0,
+            PageLayoutEstimator.Estimate(doc).Pages);
+    }
+
+    /// <summary>
+    /// Splits the body at its top-level headings (the shallowest heading level used)
+    /// and counts words, paragraphs, subheadings, tables and images in each part,
+    /// counted as in <see cref="Compute"/>.
+    /// </summary>
+    public static List<SectionStatistics> ComputeBySection(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var children = body.ChildElements.Where(e => e is not SectionProperties).ToList();
+        var levels = children.OfType<Paragraph>().Select(p => p.GetHeadingLevel()).Where(l => l > 0).ToList();
+        var topLevel = levels.Count > 0 ? levels.Min() : 0;
+
+        var sections = new List<SectionStatistics>();
+        var current = new List<OpenXmlElement>();
+        Paragraph? heading = null;
+        foreach (var child in children)
+        {
+            if (topLevel > 0 && child is Paragraph p && p.GetHeadingLevel() == topLevel)
+            {
+                if (heading is not null || current.Count > 0)
+                    sections.Add(Measure(heading, current));
+                heading = p;
+                current = [];
+            }
+            current.Add(child);
+        }
+        if (heading is not null || current.Count > 0)
+            sections.Add(Measure(heading, current));
+
+        return sections;
+    }
+
+    private static SectionStatistics Measure(Paragraph? heading, List<OpenXmlElement> elements)
+    {
+        int words = 0, paragraphs = 0, subheadings = 0;
+        var allParagraphs = elements.SelectMany(e =>
+            e is Paragraph p ? Enumerable.Repeat(p, 1) : e.Descendants<Paragraph>());
+        foreach (var paragraph in allParagraphs)
+        {
+            var text = paragraph.InnerText;
+            if (text.Trim().Length == 0) continue;
+
+            paragraphs++;
+            words += text.Split(Separators, StringSplitOptions.RemoveEmptyEntries).Length;
+            if (paragraph != heading && paragraph.GetHeadingLevel() > 0)
+                subheadings++;
+        }
+
+        return new SectionStatistics(
+            heading?.InnerText,
+            heading?.GetHeadingLevel() ?? 0,
+            ElementIdManager.GetId(elements[0]),
+            ElementIdManager.GetId(elements[^1]),
+            words,
+            paragraphs,
+            subheadings,
+            elements.Sum(e => (e is Table ? 1 : 0) + e.Descendants<Table>().Count()),
+            elements.Sum(e => e.Descendants<Drawing>().Count()));
+    }
+}
@@ -0,0 +1,82 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Models;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Records the top-level body elements before a patch and reports, afterwards,
+/// which ones were added, changed or removed along with the updated outline.
+/// Elements are matched by element ID and compared by markup.
+/// </summary>
+public sealed class StructureTracker
+{
+    private readonly Dictionary<string, string> _before;
+
+    private StructureTracker(Dictionary<string, string> before)
+    {
+        _before = before;
+    }
+
+    public static StructureTracker Capture(Body body)
+    {
+        var before = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+        foreach (var element in body.ChildElements)
+        {
+            if (ElementIdManager.GetId(element) is { } id)
+                before[id] = element.OuterXml;
+        }
+        return new StructureTracker(before);
+    }
+
+    /// <summary>
+    /// Delta between the captured state and <paramref name="body"/>, with the
+    /// outline of <paramref name="body"/>.
+    /// </summary>
+    public StructureInfo Compare(Body body)
+    {
+        var info = new StructureInfo();
+        var seen = new HashSet<string>(StringComparer.OrdinalIgnoreCase);
+        var headingIndex = 0;
+        var index = 0;
+
+        foreach (var element in body.ChildElements)
+        {
+            if (element is Paragraph p && p.IsHeading())
+            {
+                info.Outline.Add(new OutlineEntry
+                {
+                    HeadingIndex = headingIndex++,
+                    Level = p.GetHeadingLevel(),
+                    Text = p.InnerText,
+                    Id = ElementIdManager.GetId(p),
+                    BodyIndex = index
+                });
+            }
+
+            if (ElementIdManager.GetId(element) is { } id)
+            {
+                seen.Add(id);
+                if (!_before.TryGetValue(id, out var markup))
+                    AddToRange(info.Added, index, id);
+                else if (markup != element.OuterXml)
+                    AddToRange(info.Changed, index, id);
+            }
+            index++;
+        }
+
+        info.Removed = _before.Keys.Where(id => !seen.Contains(id)).ToList();
+        return info;
+    }
+
+    private static void AddToRange(List<StructureRange> ranges, int index, string id)
+    {
+        if (ranges.Count > 0 && ranges[^1].End == index - 1)
+        {
+            ranges[^1].End = index;
+            ranges[^1].Ids.Add(id);
+            return;
+        }
+        ranges.Add(new StructureRange { Start = index, End = index, Ids = [id] });
+    }
+}
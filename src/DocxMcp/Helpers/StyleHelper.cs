@@ -10,6 +10,134 @@ namespace DocxMcp.Helpers;
 /// </summary>
 public static class StyleHelper
 {
+    /// <summary>
+    /// Run style properties understood by style_element and run descriptors.
+    /// </summary>
+    public static readonly IReadOnlySet<string> RunStyleProperties = new HashSet<string>
+    {
+        "bold", "italic", "underline", "strike", "font_size", "font_name", "font_name_east_asia",
+        "color", "highlight", "vertical_align", "small_caps", "letter_spacing", "code", "language", "rtl"
+    };
+
+    private static readonly (string Name, HighlightColorValues Value)[] HighlightColors =
+    [
+        ("yellow", HighlightColorValues.Yellow),
+        ("green", HighlightColorValues.Green),
+        ("cyan", HighlightColorValues.Cyan),
+        ("magenta", HighlightColorValues.Magenta),
+        ("blue", HighlightColorValues.Blue),
+        ("red", HighlightColorValues.Red),
+        ("dark_blue", HighlightColorValues.DarkBlue),
+        ("dark_cyan", HighlightColorValues.DarkCyan),
+        ("dark_green", HighlightColorValues.DarkGreen),
+        ("dark_magenta", HighlightColorValues.DarkMagenta),
+        ("dark_red", HighlightColorValues.DarkRed),
+        ("dark_yellow", HighlightColorValues.DarkYellow),
+        ("light_gray", HighlightColorValues.LightGray),
+        ("dark_gray", HighlightColorValues.DarkGray),
+        ("black", HighlightColorValues.Black),
+        ("white", HighlightColorValues.White)
+    ];
+
+    /// <summary>
+    /// Highlight color of a name (yellow, dark_blue...). Unknown names give
+    /// yellow so old WAL entries still replay; CheckRunStyle rejects them.
+    /// </summary>
+    public static HighlightColorValues HighlightColor(string? name)
+    {
+        var key = name?.Trim().ToLowerInvariant();
+        foreach (var (n, value) in HighlightColors)
+        {
+            if (n == key)
+                return value;
+        }
+        return HighlightColorValues.Yellow;
+    }
+
+    /// <summary>
+    /// Name of a highlight color, as accepted by the highlight property.
+    /// </summary>
+    public static string? HighlightName(HighlightColorValues? value)
+    {
+        foreach (var (name, v) in HighlightColors)
+        {
+            if (v == value)
+                return name;
+        }
+        return null;
+    }
+
+    private static bool IsNoHighlight(string? name) =>
+        name?.Trim().ToLowerInvariant() is "none" or "";
+
+    /// <summary>
+    /// Vertical position of superscript, subscript or baseline (the default).
+    /// </summary>
+    public static VerticalPositionValues VerticalPosition(string? name) =>
+        name?.Trim().ToLowerInvariant() switch
+        {
+            "superscript" => VerticalPositionValues.Superscript,
+            "subscript" => VerticalPositionValues.Subscript,
+            _ => VerticalPositionValues.Baseline
+        };
+
+    /// <summary>
+    /// Name of a raised or lowered position; null for the baseline.
+    /// </summary>
+    public static string? VerticalAlignName(VerticalPositionValues? position)
+    {
+        if (position == VerticalPositionValues.Superscript) return "superscript";
+        if (position == VerticalPositionValues.Subscript) return "subscript";
+        return null;
+    }
+
+    /// <summary>
+    /// Letter spacing in points (negative condenses) as w:spacing twentieths
+    /// of a point.
+    /// </summary>
+    public static int LetterSpacingTwips(double points) =>
+        (int)Math.Round(points * 20, MidpointRounding.AwayFromZero);
+
+    /// <summary>
+    /// Check a run style before it is applied: values that can't be honored
+    /// are an error, and properties that aren't run properties are listed in
+    /// <paramref name="ignored"/> (they are left alone, not applied).
+    /// </summary>
+    public static string? CheckRunStyle(JsonElement style, out List<string> ignored)
+    {
+        ignored = style.EnumerateObject()
+            .Select(p => p.Name)
+            .Where(name => !RunStyleProperties.Contains(name))
+            .ToList();
+
+        if (style.TryGetProperty("highlight", out var highlight) && highlight.ValueKind != JsonValueKind.Null)
+        {
+            var name = highlight.ValueKind == JsonValueKind.String ? highlight.GetString() : null;
+            if (name is null || (!IsNoHighlight(name) &&
+                                 !HighlightColors.Any(c => c.Name == name.Trim().ToLowerInvariant())))
+                return $"Unknown highlight '{highlight}'. Use one of: " +
+                       string.Join(", ", HighlightColors.Select(c => c.Name)) + ", none.";
+        }
+
+        if (style.TryGetProperty("vertical_align", out var vertAlign) && vertAlign.ValueKind != JsonValueKind.Null &&
+            (vertAlign.ValueKind != JsonValueKind.String ||
+             vertAlign.GetString()?.Trim().ToLowerInvariant() is not ("superscript" or "subscript" or "baseline")))
+            return $"Unknown vertical_align '{vertAlign}'. Use superscript, subscript or baseline.";
+
+        if (style.TryGetProperty("letter_spacing", out var letterSpacing) &&
+            letterSpacing.ValueKind is not (JsonValueKind.Number or JsonValueKind.Null))
+            return "letter_spacing must be a number of points (negative condenses).";
+
+        foreach (var toggle in new[] { "bold", "italic", "underline", "strike", "small_caps", "code" })
+        {
+            if (style.TryGetProperty(toggle, out var value) &&
+                value.ValueKind is not (JsonValueKind.True or JsonValueKind.False or JsonValueKind.Null))
+                return $"{toggle} must be true or false.";
+        }
+
+        return null;
+    }
+
     // --- Run (character) properties ---
 
     public static void MergeRunProperties(Run run, JsonElement style)
@@ -58,13 +186,7 @@ public static class StyleHelper
                 props.FontSize = new FontSize { Val = (fontSize.GetInt32() * 2).ToString() };
         }
 
-        if (style.TryGetProperty("font_name", out var fontName))
-        {
-            if (fontName.ValueKind == JsonValueKind.Null)
-                props.RunFonts = null;
-            else
-                props.RunFonts = new RunFonts { Ascii = fontName.GetString() };
-        }
+        MergeRunFonts(props, style);
 
         if (style.TryGetProperty("color", out var color))
         {
@@ -76,56 +198,84 @@ public static class StyleHelper
 
         if (style.TryGetProperty("highlight", out var highlight))
         {
-            if (highlight.ValueKind == JsonValueKind.Null)
-            {
+            if (highlight.ValueKind == JsonValueKind.Null || IsNoHighlight(highlight.GetString()))
                 props.Highlight = null;
-            }
             else
-            {
-                props.Highlight = new Highlight
-                {
-                    Val = highlight.GetString()?.ToLowerInvariant() switch
-                    {
-                        "yellow" => HighlightColorValues.Yellow,
-                        "green" => HighlightColorValues.Green,
-                        "cyan" => HighlightColorValues.Cyan,
-                        "magenta" => HighlightColorValues.Magenta,
-                        "blue" => HighlightColorValues.Blue,
-                        "red" => HighlightColorValues.Red,
-                        "dark_blue" => HighlightColorValues.DarkBlue,
-                        "dark_cyan" => HighlightColorValues.DarkCyan,
-                        "dark_green" => HighlightColorValues.DarkGreen,
-                        "dark_magenta" => HighlightColorValues.DarkMagenta,
-                        "dark_red" => HighlightColorValues.DarkRed,
-                        "dark_yellow" => HighlightColorValues.DarkYellow,
-                        "light_gray" => HighlightColorValues.LightGray,
-                        "dark_gray" => HighlightColorValues.DarkGray,
-                        "black" => HighlightColorValues.Black,
-                        _ => HighlightColorValues.Yellow
-                    }
-                };
-            }
+                props.Highlight = new Highlight { Val = HighlightColor(highlight.GetString()) };
         }
 
         if (style.TryGetProperty("vertical_align", out var vertAlign))
         {
             if (vertAlign.ValueKind == JsonValueKind.Null)
-            {
                 props.VerticalTextAlignment = null;
-            }
             else
-            {
-                props.VerticalTextAlignment = new VerticalTextAlignment
-                {
-                    Val = vertAlign.GetString()?.ToLowerInvariant() switch
-                    {
-                        "superscript" => VerticalPositionValues.Superscript,
-                        "subscript" => VerticalPositionValues.Subscript,
-                        _ => VerticalPositionValues.Baseline
-                    }
-                };
-            }
+                props.VerticalTextAlignment = new VerticalTextAlignment { Val = VerticalPosition(vertAlign.GetString()) };
+        }
+
+        if (style.TryGetProperty("small_caps", out var smallCaps))
+        {
+            if (smallCaps.ValueKind == JsonValueKind.True)
+                props.SmallCaps = new SmallCaps();
+            else if (smallCaps.ValueKind is JsonValueKind.False or JsonValueKind.Null)
+                props.SmallCaps = null;
+        }
+
+        if (style.TryGetProperty("letter_spacing", out var letterSpacing))
+        {
+            if (letterSpacing.ValueKind == JsonValueKind.Null)
+                props.Spacing = null;
+            else
+                props.Spacing = new Spacing { Val = LetterSpacingTwips(letterSpacing.GetDouble()) };
+        }
+
+        if (style.TryGetProperty("code", out var code) && code.ValueKind != JsonValueKind.Undefined)
+            CodeHelper.ApplyInlineCode(props, code.ValueKind == JsonValueKind.True);
+
+        if (style.TryGetProperty("language", out var language))
+        {
+            if (language.ValueKind == JsonValueKind.Null)
+                props.Languages = null;
+            else
+                BidiHelper.SetLanguage(props, language.GetString() ?? "");
         }
+
+        if (style.TryGetProperty("rtl", out var rtl))
+            BidiHelper.SetRtl(props, rtl.ValueKind == JsonValueKind.True);
+        else
+            BidiHelper.SyncComplexScript(props);
+    }
+
+    /// <summary>
+    /// Apply font_name (Latin text: w:ascii and w:hAnsi) and font_name_east_asia
+    /// (w:eastAsia). Setting a font drops the matching theme font, which would
+    /// otherwise take precedence; JSON null removes it.
+    /// </summary>
+    public static void MergeRunFonts(RunProperties props, JsonElement style)
+    {
+        var hasLatin = style.TryGetProperty("font_name", out var latin);
+        var hasEastAsia = style.TryGetProperty("font_name_east_asia", out var eastAsia);
+        if (!hasLatin && !hasEastAsia) return;
+
+        var fonts = props.RunFonts ?? new RunFonts();
+
+        if (hasLatin)
+        {
+            var font = latin.ValueKind == JsonValueKind.Null ? null : latin.GetString();
+            fonts.Ascii = font;
+            fonts.HighAnsi = font;
+            fonts.AsciiTheme = null;
+            fonts.HighAnsiTheme = null;
+            if (font is null)
+                fonts.ComplexScript = null;
+        }
+
+        if (hasEastAsia)
+        {
+            fonts.EastAsia = eastAsia.ValueKind == JsonValueKind.Null ? null : eastAsia.GetString();
+            fonts.EastAsiaTheme = null;
+        }
+
+        props.RunFonts = fonts.HasAttributes ? fonts : null;
     }
 
     // --- Paragraph properties ---
@@ -136,6 +286,20 @@ public static class StyleHelper
         if (paragraph.ParagraphProperties is null)
             paragraph.PrependChild(props);
 
+        // Direction first: it decides how left/right alignment is written
+        if (style.TryGetProperty("bidi", out var bidi))
+        {
+            props.BiDi = bidi.ValueKind == JsonValueKind.True ? new BiDi() : null;
+        }
+
+        if (style.TryGetProperty("language", out var language) && language.ValueKind == JsonValueKind.String)
+        {
+            var lang = language.GetString() ?? "";
+            if (!style.TryGetProperty("bidi", out _))
+                props.BiDi = BidiHelper.IsRtlLanguage(lang) ? new BiDi() : null;
+            BidiHelper.ApplyLanguage(paragraph, lang, overwrite: true);
+        }
+
         if (style.TryGetProperty("alignment", out var align))
         {
             if (align.ValueKind == JsonValueKind.Null)
@@ -146,14 +310,7 @@ public static class StyleHelper
             {
                 props.Justification = new Justification
                 {
-                    Val = align.GetString()?.ToLowerInvariant() switch
-                    {
-                        "left" => JustificationValues.Left,
-                        "center" => JustificationValues.Center,
-                        "right" => JustificationValues.Right,
-                        "justify" => JustificationValues.Both,
-                        _ => JustificationValues.Left
-                    }
+                    Val = BidiHelper.Justification(align.GetString(), BidiHelper.IsBidi(props))
                 };
             }
         }
@@ -194,7 +351,8 @@ public static class StyleHelper
         bool hasSpacingProp =
             style.TryGetProperty("spacing_before", out _) ||
             style.TryGetProperty("spacing_after", out _) ||
-            style.TryGetProperty("line_spacing", out _);
+            style.TryGetProperty("line_spacing", out _) ||
+            style.TryGetProperty("line_spacing_rule", out _);
 
         if (!hasSpacingProp) return;
 
@@ -224,6 +382,18 @@ public static class StyleHelper
                 spacing.Line = ls.GetInt32().ToString();
         }
 
+        if (style.TryGetProperty("line_spacing_rule", out var lr))
+        {
+            if (lr.ValueKind == JsonValueKind.Null)
+                spacing.LineRule = null;
+            else
+                spacing.LineRule = ElementFactory.ParseLineSpacingRule(lr.GetString());
+        }
+
+        // A rule without a line value means nothing
+        if (spacing.Line is null)
+            spacing.LineRule = null;
+
         // Clean up: if all sub-fields are null, remove the element
         if (spacing.Before is null && spacing.After is null && spacing.Line is null)
         {
@@ -377,6 +547,11 @@ public static class StyleHelper
                 props.TableStyle = new TableStyle { Val = ts.GetString() };
         }
 
+        if (style.TryGetProperty("rtl", out var rtl))
+        {
+            props.BiDiVisual = rtl.ValueKind == JsonValueKind.True ? new BiDiVisual() : null;
+        }
+
         if (style.TryGetProperty("table_alignment", out var ta))
         {
             if (ta.ValueKind == JsonValueKind.Null)
@@ -387,13 +562,7 @@ public static class StyleHelper
             {
                 props.TableJustification = new TableJustification
                 {
-                    Val = ta.GetString()?.ToLowerInvariant() switch
-                    {
-                        "left" => TableRowAlignmentValues.Left,
-                        "center" => TableRowAlignmentValues.Center,
-                        "right" => TableRowAlignmentValues.Right,
-                        _ => TableRowAlignmentValues.Left
-                    }
+                    Val = BidiHelper.TableAlignment(ta.GetString(), BidiHelper.IsRtl(props))
                 };
             }
         }
@@ -460,6 +629,14 @@ public static class StyleHelper
             }
         }
 
+        if (style.TryGetProperty("text_direction", out var textDir))
+        {
+            props.TextDirection = textDir.ValueKind == JsonValueKind.String &&
+                                  ElementFactory.ParseTextDirection(textDir.GetString()) is { } direction
+                ? new TextDirection { Val = direction }
+                : null;
+        }
+
         if (style.TryGetProperty("borders", out var borders))
         {
             if (borders.ValueKind == JsonValueKind.Null)
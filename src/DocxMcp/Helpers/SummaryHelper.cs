@@ -0,0 +1,126 @@
+using System.Text;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Tools;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Splits documents into chunks for summarization and writes summaries back
+/// into a document property or a section.
+/// </summary>
+public static class SummaryHelper
+{
+    /// <summary>Core properties a summary can be written to.</summary>
+    public static readonly string[] Properties = ["description", "subject", "title", "keywords"];
+
+    /// <summary>
+    /// Body text split into chunks of at most <paramref name="maxChars"/>
+    /// characters, breaking between paragraphs where possible. Headings are
+    /// prefixed with '#' marks so the structure survives.
+    /// </summary>
+    public static List<string> Chunk(WordprocessingDocument doc, int maxChars)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var chunks = new List<string>();
+        var current = new StringBuilder();
+
+        void Flush()
+        {
+            if (current.Length > 0)
+                chunks.Add(current.ToString().TrimEnd());
+            current.Clear();
+        }
+
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var text = paragraph.InnerText.Trim();
+            if (text.Length == 0) continue;
+            if (paragraph.IsHeading())
+                text = new string('#', Math.Max(1, paragraph.GetHeadingLevel())) + " " + text;
+
+            if (current.Length > 0 && current.Length + text.Length + 1 > maxChars)
+                Flush();
+
+            // A paragraph longer than a chunk is split on its own
+            while (text.Length > maxChars)
+            {
+                chunks.Add(text[..maxChars]);
+                text = text[maxChars..];
+            }
+            current.AppendLine(text);
+        }
+        Flush();
+
+        return chunks;
+    }
+
+    /// <summary>
+    /// Store a summary in a core document property (description, subject, title or keywords).
+    /// </summary>
+    public static void WriteProperty(WordprocessingDocument doc, string property, string summary)
+    {
+        var props = doc.PackageProperties;
+        switch (property.ToLowerInvariant())
+        {
+            case "description": props.Description = summary; break;
+            case "subject": props.Subject = summary; break;
+            case "title": props.Title = summary; break;
+            case "keywords": props.Keywords = summary; break;
+            default:
+                throw new ArgumentException(
+                    $"Unknown property '{property}'. Use one of: {string.Join(", ", Properties)}.");
+        }
+    }
+
+    /// <summary>
+    /// Store a summary under the first heading whose text contains <paramref name="heading"/>,
+    /// replacing the content directly under it (sub-headings are kept). When no
+    /// such heading exists, a level-1 heading is created at the start of the body.
+    /// Blank lines in the summary separate paragraphs. Returns the new paragraphs.
+    /// </summary>
+    public static List<Paragraph> WriteSection(WordprocessingDocument doc, string heading, string summary)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var children = body.ChildElements.ToList();
+        var headingParagraph = ReadHeadingContentTool.FindHeading(children, heading, null, null);
+        if (headingParagraph is null)
+        {
+            headingParagraph = new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+                new Run(new Text(heading)));
+            ElementIdManager.AssignId(headingParagraph);
+            body.PrependChild(headingParagraph);
+        }
+        else
+        {
+            foreach (var old in ReadHeadingContentTool.CollectHeadingContent(children, headingParagraph, false).Skip(1))
+                old.Remove();
+        }
+
+        var paragraphs = summary
+            .Replace("\r\n", "\n")
+            .Split("\n\n", StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+            .Select(text =>
+            {
+                var p = new Paragraph(new Run(new Text(text) { Space = SpaceProcessingModeValues.Preserve }));
+                ElementIdManager.AssignId(p);
+                return p;
+            })
+            .ToList();
+
+        OpenXmlElement anchor = headingParagraph;
+        foreach (var p in paragraphs)
+        {
+            body.InsertAfter(p, anchor);
+            anchor = p;
+        }
+
+        return paragraphs;
+    }
+}
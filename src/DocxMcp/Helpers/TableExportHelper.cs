@@ -0,0 +1,200 @@
+using System.Text;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Flattens a table into a rectangular grid of cell texts for CSV and JSON export.
+/// Merged cells are spread over every grid position they cover: horizontally
+/// merged cells (gridSpan) across their columns and vertically merged ones
+/// (vMerge) down their rows. With "repeat" each covered position gets the merged
+/// cell's text, so every row stands on its own; with "blank" only the top-left one does.
+/// CSV is also parsed back into rows, to build tables from it.
+/// </summary>
+public static class TableExportHelper
+{
+    public static readonly string[] MergeModes = ["repeat", "blank"];
+
+    /// <summary>
+    /// The table's rows as equally long lists of cell texts. Paragraphs inside
+    /// a cell are joined with newlines.
+    /// </summary>
+    public static List<List<string>> ToGrid(Table table, string merged = "repeat")
+    {
+        if (!MergeModes.Contains(merged))
+            throw new ArgumentException($"merged must be one of: {string.Join(", ", MergeModes)}.");
+
+        var values = new List<List<string>>();
+        var origins = new List<List<bool>>();
+        foreach (var row in table.Elements<TableRow>())
+        {
+            var line = new List<string>();
+            var origin = new List<bool>();
+            var before = row.TableRowProperties?.GetFirstChild<GridBefore>()?.Val?.Value ?? 0;
+            for (int i = 0; i < before; i++)
+            {
+                line.Add("");
+                origin.Add(true);
+            }
+
+            foreach (var cell in row.Elements<TableCell>())
+            {
+                var span = Math.Max(cell.TableCellProperties?.GridSpan?.Val?.Value ?? 1, 1);
+                var vMerge = cell.TableCellProperties?.VerticalMerge;
+                var continues = vMerge is not null && (vMerge.Val is null || vMerge.Val.Value == MergedCellValues.Continue);
+
+                for (int i = 0; i < span; i++)
+                {
+                    var column = line.Count;
+                    if (continues && values.Count > 0 && column < values[^1].Count)
+                    {
+                        line.Add(values[^1][column]);
+                        origin.Add(false);
+                    }
+                    else
+                    {
+                        line.Add(CellText(cell));
+                        origin.Add(i == 0);
+                    }
+                }
+            }
+            values.Add(line);
+            origins.Add(origin);
+        }
+
+        var width = values.Count == 0 ? 0 : values.Max(r => r.Count);
+        for (int r = 0; r < values.Count; r++)
+        {
+            for (int c = 0; c < values[r].Count; c++)
+            {
+                if (merged == "blank" && !origins[r][c])
+                    values[r][c] = "";
+            }
+            while (values[r].Count < width)
+                values[r].Add("");
+        }
+        return values;
+    }
+
+    /// <summary>RFC 4180 CSV: fields with commas, quotes or line breaks are quoted.</summary>
+    public static string ToCsv(List<List<string>> grid)
+    {
+        var sb = new StringBuilder();
+        foreach (var row in grid)
+        {
+            sb.AppendJoin(',', row.Select(Quote));
+            sb.Append("\r\n");
+        }
+        return sb.ToString();
+    }
+
+    /// <summary>
+    /// Parse RFC 4180 CSV into rows of fields. Quoted fields may hold commas, doubled
+    /// quotes and line breaks; lines end with CRLF or LF, and a final line break is optional.
+    /// </summary>
+    public static List<List<string>> FromCsv(string csv)
+    {
+        var rows = new List<List<string>>();
+        var row = new List<string>();
+        var field = new StringBuilder();
+        var quoted = false;
+
+        for (int i = 0; i < csv.Length; i++)
+        {
+            var c = csv[i];
+            if (quoted)
+            {
+                if (c != '"')
+                    field.Append(c);
+                else if (i + 1 < csv.Length && csv[i + 1] == '"')
+                    field.Append(csv[++i]);
+                else
+                    quoted = false;
+            }
+            else if (c == '"' && field.Length == 0)
+            {
+                quoted = true;
+            }
+            else if (c == ',')
+            {
+                row.Add(field.ToString());
+                field.Clear();
+            }
+            else if (c is '\r' or '\n')
+            {
+                if (c == '\r' && i + 1 < csv.Length && csv[i + 1] == '\n')
+                    i++;
+                row.Add(field.ToString());
+                field.Clear();
+                rows.Add(row);
+                row = [];
+            }
+            else
+            {
+                field.Append(c);
+            }
+        }
+
+        if (quoted)
+            throw new FormatException("Unterminated quoted field in CSV.");
+        if (field.Length > 0 || row.Count > 0)
+        {
+            row.Add(field.ToString());
+            rows.Add(row);
+        }
+        return rows;
+    }
+
+    /// <summary>
+    /// With <paramref name="header"/>, one object per data row keyed by the first
+    /// row's texts (empty or repeated names are made unique); otherwise one array per row.
+    /// </summary>
+    public static JsonArray ToJson(List<List<string>> grid, bool header)
+    {
+        var result = new JsonArray();
+        if (!header)
+        {
+            foreach (var row in grid)
+                result.Add(new JsonArray(row.Select(v => (JsonNode)JsonValue.Create(v)!).ToArray()));
+            return result;
+        }
+
+        if (grid.Count == 0)
+            return result;
+        var names = ColumnNames(grid[0]);
+        foreach (var row in grid.Skip(1))
+        {
+            var obj = new JsonObject();
+            for (int c = 0; c < names.Count; c++)
+                obj[names[c]] = row[c];
+            result.Add(obj);
+        }
+        return result;
+    }
+
+    private static List<string> ColumnNames(List<string> header)
+    {
+        var names = new List<string>();
+        var used = new HashSet<string>(StringComparer.Ordinal);
+        for (int c = 0; c < header.Count; c++)
+        {
+            var name = header[c].ReplaceLineEndings(" ").Trim();
+            if (name.Length == 0)
+                name = $"column_{c + 1}";
+            var unique = name;
+            for (int n = 2; !used.Add(unique); n++)
+                unique = $"{name}_{n}";
+            names.Add(unique);
+        }
+        return names;
+    }
+
+    private static string CellText(TableCell cell) =>
+        string.Join("\n", cell.Elements<Paragraph>().Select(p => p.InnerText));
+
+    private static string Quote(string value) =>
+        value.IndexOfAny([',', '"', '\r', '\n']) < 0
+            ? value
+            : "\"" + value.Replace("\"", "\"\"") + "\"";
+}
@@ -0,0 +1,182 @@
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Result of an autofit: the layout mode, the table width and the column widths, in twips.
+/// </summary>
+public sealed record TableFit(string Mode, int Width, List<int> Columns);
+
+/// <summary>
+/// Word's three AutoFit behaviours, computed up front so the table renders the
+/// same everywhere:
+///   contents — columns as wide as their content, up to the text width;
+///   window   — the table spans the text width, extra space shared by content width;
+///   fixed    — content-based widths locked with a fixed layout.
+/// Column widths follow the HTML auto-layout idea: each column is at least as
+/// wide as its longest word and at most as wide as its longest line, and the
+/// space between is shared in proportion.
+/// </summary>
+public static class TableFitHelper
+{
+    public static readonly string[] Modes = ["contents", "window", "fixed"];
+
+    private const int MinColumnWidth = 360; // 0.25in
+    private const int DefaultCellMargin = 108; // 0.075in each side
+
+    public static TableFit Autofit(WordprocessingDocument doc, Table table, string mode)
+    {
+        mode = mode.ToLowerInvariant();
+        if (!Modes.Contains(mode))
+            throw new ArgumentException($"mode must be one of: {string.Join(", ", Modes)}.");
+
+        var rows = table.Elements<TableRow>().ToList();
+        if (rows.Count == 0)
+            throw new ArgumentException("The table has no rows.");
+
+        var columns = rows.Max(r => GridBefore(r) + r.Elements<TableCell>().Sum(SpanOf));
+        var margins = CellMargins(table);
+        var cells = rows.SelectMany(r => r.Elements<TableCell>()).ToList();
+        var content = cells.Zip(PageLayoutEstimator.ContentWidths(doc, cells))
+            .ToDictionary(x => x.First, x => x.Second);
+
+        var min = new int[columns];
+        var max = new int[columns];
+        // Single cells first, so spanning cells only widen columns that are still too narrow
+        foreach (var spanning in new[] { false, true })
+        {
+            foreach (var row in rows)
+            {
+                var column = GridBefore(row);
+                foreach (var cell in row.Elements<TableCell>())
+                {
+                    var span = SpanOf(cell);
+                    if (span > 1 == spanning)
+                    {
+                        var (cellMin, cellMax) = content[cell];
+                        Widen(min, column, span, cellMin + margins);
+                        Widen(max, column, span, cellMax + margins);
+                    }
+                    column += span;
+                }
+            }
+        }
+        for (int i = 0; i < columns; i++)
+        {
+            min[i] = Math.Max(min[i], MinColumnWidth);
+            max[i] = Math.Max(max[i], min[i]);
+        }
+
+        var available = AvailableWidth(table, margins);
+        var target = mode == "window" ? available : Math.Min(max.Sum(), available);
+        var widths = Distribute(min, max, target);
+
+        Apply(table, widths, mode);
+        return new TableFit(mode, widths.Sum(), widths);
+    }
+
+    private static void Widen(int[] widths, int column, int span, int needed)
+    {
+        var end = Math.Min(column + span, widths.Length);
+        if (column >= end) return;
+
+        var missing = needed - widths[column..end].Sum();
+        if (missing <= 0) return;
+        for (int i = column; i < end; i++)
+            widths[i] += missing / (end - column);
+        widths[end - 1] += missing % (end - column);
+    }
+
+    /// <summary>
+    /// Column widths summing to <paramref name="target"/>: between each column's
+    /// minimum and maximum when possible, scaled below the minimums when the
+    /// table can't fit, and above the maximums when it must fill the width.
+    /// </summary>
+    private static List<int> Distribute(int[] min, int[] max, int target)
+    {
+        double sumMin = min.Sum(), sumMax = max.Sum();
+        var widths = min.Select((_, i) =>
+        {
+            if (target <= sumMin)
+                return (int)(min[i] * target / sumMin);
+            if (target <= sumMax)
+                return (int)(min[i] + (max[i] - min[i]) * (target - sumMin) / Math.Max(1, sumMax - sumMin));
+            return (int)(max[i] + max[i] * (target - sumMax) / sumMax);
+        }).ToList();
+
+        widths[^1] += target - widths.Sum();
+        return widths;
+    }
+
+    private static void Apply(Table table, List<int> widths, string mode)
+    {
+        var tblPr = table.GetFirstChild<TableProperties>() ?? table.PrependChild(new TableProperties());
+        var total = widths.Sum();
+
+        tblPr.TableWidth = mode switch
+        {
+            "window" => new TableWidth { Width = "5000", Type = TableWidthUnitValues.Pct },
+            "fixed" => new TableWidth { Width = total.ToString(), Type = TableWidthUnitValues.Dxa },
+            _ => new TableWidth { Width = "0", Type = TableWidthUnitValues.Auto }
+        };
+        tblPr.TableLayout = new TableLayout
+        {
+            Type = mode == "fixed" ? TableLayoutValues.Fixed : TableLayoutValues.Autofit
+        };
+
+        var grid = table.GetFirstChild<TableGrid>() ?? table.InsertAfter(new TableGrid(), tblPr);
+        grid.RemoveAllChildren<GridColumn>();
+        var gridChange = grid.GetFirstChild<TableGridChange>();
+        foreach (var width in widths)
+        {
+            var gridCol = new GridColumn { Width = width.ToString() };
+            if (gridChange is null)
+                grid.AppendChild(gridCol);
+            else
+                grid.InsertBefore(gridCol, gridChange);
+        }
+
+        foreach (var row in table.Elements<TableRow>())
+        {
+            var column = GridBefore(row);
+            foreach (var cell in row.Elements<TableCell>())
+            {
+                var span = SpanOf(cell);
+                var width = widths.Skip(column).Take(span).Sum();
+                column += span;
+
+                var tcPr = cell.TableCellProperties ?? cell.PrependChild(new TableCellProperties());
+                tcPr.TableCellWidth = new TableCellWidth { Width = width.ToString(), Type = TableWidthUnitValues.Dxa };
+            }
+        }
+    }
+
+    /// <summary>
+    /// Text width of the table's section, or the width of the cell holding a nested table.
+    /// </summary>
+    private static int AvailableWidth(Table table, int margins)
+    {
+        if (table.Parent is TableCell cell
+            && cell.TableCellProperties?.TableCellWidth is { Type.InnerText: "dxa" } tcW
+            && int.TryParse(tcW.Width?.Value, out var cellWidth) && cellWidth > margins)
+        {
+            return cellWidth - margins;
+        }
+
+        var top = table.Parent is Body ? table : table.Ancestors<Table>().LastOrDefault() ?? table;
+        return PageLayoutEstimator.AvailableWidth(top);
+    }
+
+    private static int CellMargins(Table table)
+    {
+        var defaults = table.GetFirstChild<TableProperties>()?.TableCellMarginDefault;
+        var left = (int?)defaults?.TableCellLeftMargin?.Width?.Value ?? DefaultCellMargin;
+        var right = (int?)defaults?.TableCellRightMargin?.Width?.Value ?? DefaultCellMargin;
+        return left + right;
+    }
+
+    private static int SpanOf(TableCell cell) => cell.TableCellProperties?.GridSpan?.Val?.Value ?? 1;
+
+    private static int GridBefore(TableRow row) => row.TableRowProperties?.GetFirstChild<GridBefore>()?.Val?.Value ?? 0;
+}
@@ -0,0 +1,204 @@
+using System.Globalization;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using W14 = DocumentFormat.OpenXml.Office2010.Word;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// An action item found in a paragraph. Offset and length locate its text in the
+/// paragraph's run text, as used by replace_text. <c>Done</c> is only known for
+/// checkboxes; <c>DueDate</c> is the ISO date of <c>Due</c> when it names one
+/// unambiguously.
+/// </summary>
+public sealed record ExtractedTask(
+    string Path,
+    int Offset,
+    int Length,
+    string Text,
+    string Source,
+    bool? Done,
+    string? Assignee,
+    string? Due,
+    string? DueDate,
+    string? Section);
+
+/// <summary>
+/// Finds action items in meeting notes and similar documents: checkbox list items
+/// (checkbox content controls, checkbox bullets, ☐/☑ glyphs or "[ ]"/"[x]"), and
+/// "TODO:", "Action:" or "Follow-up:" markers, one task per marker. The owner comes
+/// from an "@name", "Owner: Name" or a leading "Name:" / "Name will", the due date
+/// from "due", "by" or "before" followed by a date, or a bare ISO date.
+/// </summary>
+public static partial class TaskHelper
+{
+    private const string Name = @"\p{Lu}[\p{L}'’-]+(?:\s+\p{Lu}[\p{L}'’-]+)?";
+
+    private const string Date =
+        @"\d{4}-\d{2}-\d{2}" +
+        @"|\d{1,2}/\d{1,2}(?:/\d{2,4})?" +
+        @"|(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]*\.?\s+\d{1,2}(?:st|nd|rd|th)?(?:,?\s+\d{4})?" +
+        @"|\d{1,2}(?:st|nd|rd|th)?\s+(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]*\.?(?:,?\s+\d{4})?" +
+        @"|(?:next\s+)?(?:Monday|Tuesday|Wednesday|Thursday|Friday|Saturday|Sunday)" +
+        @"|tomorrow|EOD|EOW|end\s+of\s+(?:the\s+)?(?:day|week|month|quarter)";
+
+    /// <summary>Glyphs of an unchecked box, in Unicode and in the Wingdings private range.</summary>
+    private static readonly HashSet<char> OpenBoxes = ['☐', '\uF0A8', '\uF06F', '\uF071'];
+
+    /// <summary>Glyphs of a checked box.</summary>
+    private static readonly HashSet<char> CheckedBoxes = ['☑', '☒', '\uF0FE', '\uF0FD'];
+
+    [GeneratedRegex(@"^\s*(?:(?<box>[☐☑☒])|\[(?<box>[ xX✓])\])\s*")]
+    private static partial Regex TypedCheckbox();
+
+    [GeneratedRegex(@"(?<![\p{L}\p{N}])(?<marker>TODO|FIXME|Action(?:\s+item)?|Follow[- ]?up)\s*[:：]\s*",
+        RegexOptions.IgnoreCase)]
+    private static partial Regex Marker();
+
+    [GeneratedRegex(@"(?<![\w.])@(?<name>\p{L}[\p{L}\p{N}._-]*[\p{L}\p{N}])")]
+    private static partial Regex Mention();
+
+    [GeneratedRegex($@"(?i:\b(?:owner|assignee|assigned\s+to|responsible))\s*[:=-]?\s*(?<name>{Name})")]
+    private static partial Regex OwnerLabel();
+
+    [GeneratedRegex($@"^(?<name>{Name})\s*(?::|\s[-–—]|\s+will\b)\s*")]
+    private static partial Regex LeadingOwner();
+
+    [GeneratedRegex($@"\b(?:due|by|before|deadline)\s*[:-]?\s*(?:on\s+)?(?<date>{Date})\b",
+        RegexOptions.IgnoreCase)]
+    private static partial Regex DuePhrase();
+
+    [GeneratedRegex(@"\b\d{4}-\d{2}-\d{2}\b")]
+    private static partial Regex IsoDate();
+
+    /// <summary>
+    /// Every task of the body, in document order.
+    /// </summary>
+    public static List<ExtractedTask> Extract(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var numbering = doc.MainDocumentPart?.NumberingDefinitionsPart?.Numbering;
+
+        var tasks = new List<ExtractedTask>();
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var path = TerminologyHelper.PathOf(paragraph, body);
+            if (path is null) continue;
+
+            var text = TerminologyHelper.RunText(paragraph);
+            var section = HeadingHelper.HeadingAbove(paragraph)?.InnerText;
+
+            bool? done = CheckboxState(paragraph, numbering);
+            var start = 0;
+            var typed = TypedCheckbox().Match(text);
+            if (typed.Success)
+            {
+                done ??= typed.Groups["box"].Value is not (" " or "☐");
+                start = typed.Length;
+            }
+
+            var markers = Marker().Matches(text, start).ToList();
+            if (done is not null)
+            {
+                // One task per checkbox; a marker right after the box is part of it
+                if (markers.Count > 0 && string.IsNullOrWhiteSpace(text[start..markers[0].Index]))
+                    start = markers[0].Index + markers[0].Length;
+                AddTask(tasks, path, text, start, text.Length, "checkbox", done, section);
+                continue;
+            }
+
+            for (var i = 0; i < markers.Count; i++)
+            {
+                var marker = markers[i].Groups["marker"].Value;
+                var source = marker.StartsWith("TODO", StringComparison.OrdinalIgnoreCase)
+                    || marker.StartsWith("FIXME", StringComparison.OrdinalIgnoreCase) ? "todo" : "action";
+                var end = i + 1 < markers.Count ? markers[i + 1].Index : text.Length;
+                AddTask(tasks, path, text, markers[i].Index + markers[i].Length, end, source, null, section);
+            }
+        }
+        return tasks;
+    }
+
+    private static void AddTask(
+        List<ExtractedTask> tasks, string path, string text, int start, int end,
+        string source, bool? done, string? section)
+    {
+        // Trim the range so it covers the task text only
+        while (start < end && char.IsWhiteSpace(text[start])) start++;
+        while (end > start && (char.IsWhiteSpace(text[end - 1]) || text[end - 1] is ';' or ',')) end--;
+        if (start == end) return;
+
+        var taskText = text[start..end];
+        var due = DuePhrase().Match(taskText) is { Success: true } phrase
+            ? phrase.Groups["date"].Value
+            : IsoDate().Match(taskText) is { Success: true } iso ? iso.Value : null;
+        tasks.Add(new ExtractedTask(path, start, end - start, taskText, source, done,
+            Assignee(taskText), due, due is null ? null : ToIsoDate(due), section));
+    }
+
+    private static string? Assignee(string taskText)
+    {
+        if (Mention().Match(taskText) is { Success: true } mention)
+            return mention.Groups["name"].Value;
+        if (OwnerLabel().Match(taskText) is { Success: true } label)
+            return label.Groups["name"].Value;
+        if (LeadingOwner().Match(taskText) is { Success: true } leading)
+            return leading.Groups["name"].Value;
+        return null;
+    }
+
+    /// <summary>
+    /// "2026-03-05", "March 5, 2026" or "5th Mar 2026" as an ISO date. Dates without
+    /// a year, relative dates and slash dates (day or month first?) stay unresolved.
+    /// </summary>
+    internal static string? ToIsoDate(string due)
+    {
+        if (!Regex.IsMatch(due, @"\b\d{4}\b") || due.Contains('/'))
+            return null;
+        var cleaned = Regex.Replace(due, @"(?<=\d)(?:st|nd|rd|th)\b|,|\.", "");
+        return DateTime.TryParse(cleaned, CultureInfo.InvariantCulture, DateTimeStyles.None, out var date)
+            ? date.ToString("yyyy-MM-dd", CultureInfo.InvariantCulture)
+            : null;
+    }
+
+    /// <summary>
+    /// Whether the paragraph is a checked (true) or unchecked (false) checkbox item:
+    /// a checkbox content control, or a list level whose bullet is a box. Null otherwise.
+    /// </summary>
+    private static bool? CheckboxState(Paragraph paragraph, Numbering? numbering)
+    {
+        var checkbox = paragraph.Elements<SdtRun>()
+            .Select(sdt => sdt.SdtProperties?.GetFirstChild<W14.SdtContentCheckBox>())
+            .FirstOrDefault(c => c is not null);
+        if (checkbox is not null)
+        {
+            var value = checkbox.Checked?.Val;
+            return value is not null
+                && (value.Value == W14.OnOffValues.One || value.Value == W14.OnOffValues.True);
+        }
+
+        var bullet = BulletOf(paragraph, numbering);
+        if (bullet is not { Length: 1 }) return null;
+        if (OpenBoxes.Contains(bullet[0])) return false;
+        if (CheckedBoxes.Contains(bullet[0])) return true;
+        return null;
+    }
+
+    private static string? BulletOf(Paragraph paragraph, Numbering? numbering)
+    {
+        var numPr = paragraph.ParagraphProperties?.NumberingProperties;
+        var numId = numPr?.NumberingId?.Val?.Value;
+        if (numbering is null || numId is null or 0) return null;
+        var ilvl = numPr?.NumberingLevelReference?.Val?.Value ?? 0;
+
+        var abstractId = numbering.Elements<NumberingInstance>()
+            .FirstOrDefault(n => n.NumberID?.Value == numId)?.AbstractNumId?.Val?.Value;
+        var level = numbering.Elements<AbstractNum>()
+            .FirstOrDefault(a => a.AbstractNumberId?.Value == abstractId)?
+            .Elements<Level>().FirstOrDefault(l => l.LevelIndex?.Value == ilvl);
+        return level?.NumberingFormat?.Val?.InnerText == "bullet" ? level.LevelText?.Val?.Value : null;
+    }
+}
@@ -0,0 +1,408 @@
+using System.Text;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A problem found in a template. Path is the paragraph path for body paragraphs (null in
+/// headers and footers); offset and length are in the paragraph's run text, as used by
+/// replace_text.
+/// </summary>
+public sealed record TemplateLintIssue(
+    string Severity,
+    string Kind,
+    string Message,
+    string Part,
+    string? Path,
+    int Offset,
+    int Length,
+    string Text,
+    string? Field);
+
+/// <summary>
+/// A fillable field of a template: a {{placeholder}}, MERGEFIELD or DOCPROPERTY field.
+/// Text is the placeholder as typed, or the result a field currently shows; Instruction
+/// is the field code. Path and offset are as in <see cref="TemplateLintIssue"/>.
+/// </summary>
+public sealed record TemplateField(
+    string Kind,
+    string Name,
+    string Part,
+    string? Path,
+    int Offset,
+    string Text,
+    string? Instruction,
+    bool SplitAcrossRuns);
+
+/// <summary>
+/// Checks the {{placeholders}} and MERGEFIELD fields of a template, so problems show up
+/// before a batch generation fails halfway through, and lists the fields a template has.
+/// </summary>
+public static partial class TemplateLintHelper
+{
+    [GeneratedRegex(@"\{\{(?<name>[^{}]*)\}\}")]
+    private static partial Regex PlaceholderPattern();
+
+    [GeneratedRegex(@"^[A-Za-z_][\w.\-]*$")]
+    private static partial Regex FieldNamePattern();
+
+    [GeneratedRegex("""^\s*MERGEFIELD\s*(?:"(?<name>[^"]*)"|(?<name>[^\s\\]*))""", RegexOptions.IgnoreCase)]
+    private static partial Regex MergeFieldPattern();
+
+    [GeneratedRegex("""^\s*DOCPROPERTY\s*(?:"(?<name>[^"]*)"|(?<name>[^\s\\]*))""", RegexOptions.IgnoreCase)]
+    private static partial Regex DocPropertyPattern();
+
+    /// <summary>
+    /// Issues in the body, headers and footers in document order, then duplicate and
+    /// inconsistent field names; and how many times each field name is used.
+    /// </summary>
+    public static (List<TemplateLintIssue> Issues, List<(string Name, int Count)> Fields) Lint(
+        WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var issues = new List<TemplateLintIssue>();
+        var uses = new List<(string Name, TemplateLintIssue At)>();
+
+        LintPart(body, "body", body, issues, uses);
+        foreach (var header in mainPart.HeaderParts)
+        {
+            if (header.Header is not null)
+                LintPart(header.Header, "header", null, issues, uses);
+        }
+        foreach (var footer in mainPart.FooterParts)
+        {
+            if (footer.Footer is not null)
+                LintPart(footer.Footer, "footer", null, issues, uses);
+        }
+
+        // Placeholders and merge fields share a name space: a batch fills both from the same record
+        foreach (var group in uses.GroupBy(u => u.Name))
+        {
+            var all = group.ToList();
+            if (all.Count > 1)
+            {
+                issues.Add(all[1].At with
+                {
+                    Severity = "info",
+                    Kind = "duplicate_field",
+                    Message = $"'{group.Key}' is used {all.Count} times; every use gets the same value."
+                });
+            }
+        }
+        foreach (var group in uses.Select(u => u.Name).Distinct()
+                     .GroupBy(n => n, StringComparer.OrdinalIgnoreCase)
+                     .Where(g => g.Count() > 1))
+        {
+            var names = group.ToList();
+            var at = uses.First(u => u.Name == names[1]).At;
+            issues.Add(at with
+            {
+                Severity = "warning",
+                Kind = "conflicting_case",
+                Message = $"{string.Join(", ", names.Select(n => $"'{n}'"))} differ only in case; " +
+                          "most data sources treat them as different fields."
+            });
+        }
+
+        var fields = uses
+            .GroupBy(u => u.Name)
+            .Select(g => (g.Key, g.Count()))
+            .ToList();
+        return (issues, fields);
+    }
+
+    /// <summary>
+    /// The placeholders, merge fields and document property fields of the body, headers
+    /// and footers, in document order. Fields are read from their instructions, which Word
+    /// often splits across several runs, and placeholders from the text of the paragraph's
+    /// runs, so tokens split by formatting or spell-check marks are found too.
+    /// </summary>
+    public static List<TemplateField> ListFields(WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var fields = new List<TemplateField>();
+        ListPartFields(body, "body", body, fields);
+        foreach (var header in mainPart.HeaderParts)
+        {
+            if (header.Header is not null)
+                ListPartFields(header.Header, "header", null, fields);
+        }
+        foreach (var footer in mainPart.FooterParts)
+        {
+            if (footer.Footer is not null)
+                ListPartFields(footer.Footer, "footer", null, fields);
+        }
+        return fields;
+    }
+
+    private static void ListPartFields(OpenXmlElement root, string part, Body? body, List<TemplateField> fields)
+    {
+        var open = new Stack<(StringBuilder Instruction, StringBuilder Result, bool InResult, string? Path, int Offset)>();
+
+        foreach (var paragraph in root.Descendants<Paragraph>())
+        {
+            var path = body is null ? null : TerminologyHelper.PathOf(paragraph, body);
+            var (text, runs) = RunText(paragraph);
+
+            foreach (var match in PlaceholderPattern().Matches(text))
+            {
+                var name = match.Groups["name"].Value.Trim();
+                if (name.Length == 0) continue;
+                var split = runs.Skip(match.Index).Take(match.Length).Distinct().Count() > 1;
+                fields.Add(new TemplateField("placeholder", name, part, path, match.Index, match.Value, null, split));
+            }
+
+            foreach (var simple in paragraph.Descendants<SimpleField>())
+                AddField(simple.Instruction?.Value ?? "", simple.InnerText, part, path, 0, fields);
+
+            foreach (var element in paragraph.Descendants())
+            {
+                switch (element)
+                {
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "begin":
+                        open.Push((new StringBuilder(), new StringBuilder(), false, path, OffsetOf(element, text)));
+                        break;
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "separate":
+                        if (open.Count > 0)
+                        {
+                            var top = open.Pop();
+                            open.Push(top with { InResult = true });
+                        }
+                        break;
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "end":
+                        if (open.Count == 0) break;
+                        var done = open.Pop();
+                        AddField(done.Instruction.ToString(), done.Result.ToString(), part, done.Path, done.Offset, fields);
+                        break;
+                    case FieldCode code when open.Count > 0 && !open.Peek().InResult:
+                        open.Peek().Instruction.Append(code.Text);
+                        break;
+                    case Text t when open.Count > 0 && open.Peek().InResult:
+                        open.Peek().Result.Append(t.Text);
+                        break;
+                }
+            }
+        }
+    }
+
+    private static void AddField(string instruction, string result, string part, string? path, int offset,
+        List<TemplateField> fields)
+    {
+        var (kind, match) = MergeFieldPattern().Match(instruction) is { Success: true } merge
+            ? ("merge_field", merge)
+            : ("doc_property", DocPropertyPattern().Match(instruction));
+        if (!match.Success) return;
+
+        var name = match.Groups["name"].Value.Trim();
+        if (name.Length == 0) return;
+        fields.Add(new TemplateField(kind, name, part, path, offset, result, instruction.Trim(), false));
+    }
+
+    /// <summary>
+    /// Text of a paragraph's runs with the run each character is in, the text replace_text
+    /// searches.
+    /// </summary>
+    private static (string Text, List<Run> Runs) RunText(Paragraph paragraph)
+    {
+        var sb = new StringBuilder();
+        var runs = new List<Run>();
+        foreach (var run in paragraph.Elements<Run>())
+        {
+            foreach (var text in run.Elements<Text>())
+            {
+                sb.Append(text.Text);
+                runs.AddRange(Enumerable.Repeat(run, text.Text.Length));
+            }
+        }
+        return (sb.ToString(), runs);
+    }
+
+    private static void LintPart(
+        OpenXmlElement root, string part, Body? body,
+        List<TemplateLintIssue> issues, List<(string Name, TemplateLintIssue At)> uses)
+    {
+        // Complex fields can span paragraphs (a TOC does), so they're tracked across the part
+        var open = new Stack<(StringBuilder Instruction, bool InResult, TemplateLintIssue At)>();
+
+        foreach (var paragraph in root.Descendants<Paragraph>())
+        {
+            var path = body is null ? null : TerminologyHelper.PathOf(paragraph, body);
+            var (text, runs) = RunText(paragraph);
+
+            TemplateLintIssue At(int offset, int length, string severity = "", string kind = "", string message = "",
+                string? field = null) =>
+                new(severity, kind, message, part, path, offset, length, text.Substring(offset, length), field);
+
+            LintPlaceholders(text, runs, At, issues, uses);
+
+            foreach (var simple in paragraph.Descendants<SimpleField>())
+                LintMergeField(simple.Instruction?.Value ?? "", At(0, 0), issues, uses);
+
+            foreach (var element in paragraph.Descendants())
+            {
+                switch (element)
+                {
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "begin":
+                        open.Push((new StringBuilder(), false, At(OffsetOf(element, text), 0)));
+                        break;
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "separate":
+                        if (open.Count > 0)
+                        {
+                            var top = open.Pop();
+                            open.Push((top.Instruction, true, top.At));
+                        }
+                        break;
+                    case FieldChar fc when fc.FieldCharType?.InnerText == "end":
+                        if (open.Count == 0)
+                        {
+                            issues.Add(At(OffsetOf(element, text), 0, "error", "stray_field_end",
+                                "A field ends here but never began; Word may drop the text around it."));
+                            break;
+                        }
+                        var done = open.Pop();
+                        LintMergeField(done.Instruction.ToString(), done.At, issues, uses);
+                        break;
+                    case FieldCode code when open.Count > 0 && !open.Peek().InResult:
+                        open.Peek().Instruction.Append(code.Text);
+                        break;
+                }
+            }
+        }
+
+        foreach (var (instruction, _, at) in open.Reverse())
+        {
+            issues.Add(at with
+            {
+                Severity = "error",
+                Kind = "unclosed_field",
+                Message = $"The field '{instruction.ToString().Trim()}' never ends.",
+                Field = MergeFieldName(instruction.ToString())
+            });
+        }
+    }
+
+    private static void LintPlaceholders(
+        string text, List<Run> runs,
+        Func<int, int, string, string, string, string?, TemplateLintIssue> at,
+        List<TemplateLintIssue> issues, List<(string Name, TemplateLintIssue At)> uses)
+    {
+        int i = 0;
+        while (i < text.Length - 1)
+        {
+            if (string.CompareOrdinal(text, i, "}}", 0, 2) == 0)
+            {
+                issues.Add(at(i, 2, "error", "stray_close",
+                    "'}}' without an opening '{{'.", null));
+                i += 2;
+                continue;
+            }
+            if (string.CompareOrdinal(text, i, "{{", 0, 2) != 0)
+            {
+                i++;
+                continue;
+            }
+
+            var close = text.IndexOf("}}", i + 2, StringComparison.Ordinal);
+            var reopen = text.IndexOf("{{", i + 2, StringComparison.Ordinal);
+            if (close < 0 || (reopen >= 0 && reopen < close))
+            {
+                var end = reopen >= 0 ? reopen : text.Length;
+                issues.Add(at(i, end - i, "error", "unclosed_placeholder",
+                    "'{{' is never closed with '}}' in this paragraph.", null));
+                i = end;
+                continue;
+            }
+
+            var length = close + 2 - i;
+            var name = text[(i + 2)..close].Trim();
+            if (name.Length == 0)
+            {
+                issues.Add(at(i, length, "error", "empty_placeholder", "The placeholder has no field name.", null));
+            }
+            else
+            {
+                var use = at(i, length, "", "", "", name);
+                uses.Add((name, use));
+                if (!FieldNamePattern().IsMatch(name))
+                {
+                    issues.Add(use with
+                    {
+                        Severity = "warning",
+                        Kind = "invalid_name",
+                        Message = $"'{name}' isn't a plain field name (letters, digits, '_', '.', '-')."
+                    });
+                }
+                var split = runs.Skip(i).Take(length).Distinct().Count();
+                if (split > 1)
+                {
+                    issues.Add(use with
+                    {
+                        Severity = "warning",
+                        Kind = "split_placeholder",
+                        Message = $"The placeholder is split across {split} runs; engines that replace " +
+                                  "text run by run won't find it. Retype it in one go to put it in one run."
+                    });
+                }
+            }
+            i = close + 2;
+        }
+    }
+
+    private static void LintMergeField(
+        string instruction, TemplateLintIssue at,
+        List<TemplateLintIssue> issues, List<(string Name, TemplateLintIssue At)> uses)
+    {
+        if (!MergeFieldPattern().IsMatch(instruction))
+            return;
+
+        var name = MergeFieldName(instruction);
+        if (string.IsNullOrWhiteSpace(name))
+        {
+            issues.Add(at with
+            {
+                Severity = "error",
+                Kind = "empty_merge_field",
+                Message = $"The field '{instruction.Trim()}' has no field name."
+            });
+            return;
+        }
+        uses.Add((name, at with { Field = name }));
+    }
+
+    private static string? MergeFieldName(string instruction)
+    {
+        var match = MergeFieldPattern().Match(instruction);
+        return match.Success ? match.Groups["name"].Value.Trim() : null;
+    }
+
+    /// <summary>
+    /// Run-text offset of the run holding <paramref name="element"/>, or 0 when that run
+    /// isn't a direct child of the paragraph.
+    /// </summary>
+    private static int OffsetOf(OpenXmlElement element, string text)
+    {
+        var run = element.Ancestors<Run>().FirstOrDefault();
+        if (run?.Parent is not Paragraph paragraph)
+            return 0;
+
+        var offset = 0;
+        foreach (var r in paragraph.Elements<Run>())
+        {
+            if (r == run)
+                return Math.Min(offset, text.Length);
+            offset += r.Elements<Text>().Sum(t => t.Text.Length);
+        }
+        return 0;
+    }
+}
@@ -0,0 +1,145 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using System.Text.RegularExpressions;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Organization data a tenant sets once (profile_set) instead of sending it with every
+/// generation call. Its fields are template variables: {{company.name}},
+/// {{company.address}}, {{company.logo}} and {{author.name}}, resolved by the pipeline's
+/// fill step and in section break headers and footers. The default author also signs
+/// comments added without an author.
+/// </summary>
+public sealed partial class TenantProfile
+{
+    /// <summary>Profile fields, as stored and as accepted by profile_set, with their variable names.</summary>
+    public static readonly IReadOnlyList<(string Field, string Variable)> Fields =
+    [
+        ("company_name", "company.name"),
+        ("company_address", "company.address"),
+        ("company_logo", "company.logo"),
+        ("default_author", "author.name"),
+    ];
+
+    [GeneratedRegex(@"\{\{\s*([^{}]*?)\s*\}\}")]
+    private static partial Regex VariablePattern();
+
+    private readonly Dictionary<string, string> _values = new(StringComparer.Ordinal);
+
+    public string? CompanyName => Get("company_name");
+    public string? CompanyAddress => Get("company_address");
+    public string? CompanyLogo => Get("company_logo");
+    public string? DefaultAuthor => Get("default_author");
+
+    public bool IsEmpty => _values.Count == 0;
+
+    public string? Get(string field) => _values.GetValueOrDefault(field);
+
+    /// <summary>Set a field; an empty value clears it.</summary>
+    public void Set(string field, string? value)
+    {
+        if (!Fields.Any(f => f.Field == field))
+            throw new ArgumentException(
+                $"Unknown profile field '{field}'. Use one of: {string.Join(", ", Fields.Select(f => f.Field))}.");
+        if (string.IsNullOrEmpty(value))
+            _values.Remove(field);
+        else
+            _values[field] = value;
+    }
+
+    /// <summary>The set fields by variable name, e.g. "company.name".</summary>
+    public Dictionary<string, string> Variables() =>
+        Fields.Where(f => _values.ContainsKey(f.Field))
+            .ToDictionary(f => f.Variable, f => _values[f.Field], StringComparer.Ordinal);
+
+    /// <summary>
+    /// Replace the {{variable}} placeholders of <paramref name="text"/> the profile sets;
+    /// other placeholders are left as they are.
+    /// </summary>
+    public string Resolve(string text)
+    {
+        if (IsEmpty || !text.Contains("{{"))
+            return text;
+        var variables = Variables();
+        return VariablePattern().Replace(text, m =>
+            variables.TryGetValue(m.Groups[1].Value, out var value) ? value : m.Value);
+    }
+
+    /// <summary>
+    /// Resolve the profile variables in the header and footer texts of a section break
+    /// add or replace patch. Returns the patch unchanged when it isn't one, so the
+    /// resolved text is what the WAL records and replays.
+    /// </summary>
+    public string ResolveSectionBreak(string patchJson)
+    {
+        if (IsEmpty || !patchJson.Contains("{{"))
+            return patchJson;
+
+        JsonNode? patch;
+        try
+        {
+            patch = JsonNode.Parse(patchJson);
+        }
+        catch (JsonException)
+        {
+            return patchJson;
+        }
+        if (patch?["value"] is not JsonObject value
+            || value["type"] is not JsonValue type || !type.TryGetValue<string>(out var typeName)
+            || !string.Equals(typeName, "section_break", StringComparison.OrdinalIgnoreCase))
+            return patchJson;
+
+        var changed = false;
+        foreach (var kind in new[] { "header", "footer" })
+        {
+            if (value[kind] is JsonObject texts)
+            {
+                // {"default": text, "first": text}
+                foreach (var name in texts.Select(kv => kv.Key).ToList())
+                    changed |= ResolveInto(texts, name);
+            }
+            else
+            {
+                changed |= ResolveInto(value, kind);
+            }
+        }
+        return changed ? patch.ToJsonString() : patchJson;
+    }
+
+    private bool ResolveInto(JsonObject parent, string name)
+    {
+        if (parent[name] is not JsonValue node || !node.TryGetValue<string>(out var text))
+            return false;
+        var resolved = Resolve(text);
+        if (resolved == text)
+            return false;
+        parent[name] = resolved;
+        return true;
+    }
+
+    /// <summary>Parse a stored profile: <c>{"company_name": "ACME", ...}</c>.</summary>
+    public static TenantProfile Parse(string json)
+    {
+        var profile = new TenantProfile();
+        if (JsonNode.Parse(json) is not JsonObject obj)
+            throw new FormatException("The tenant profile must be a JSON object.");
+        foreach (var (field, node) in obj)
+        {
+            if (Fields.Any(f => f.Field == field) && node is JsonValue v && v.TryGetValue<string>(out var value))
+                profile.Set(field, value);
+        }
+        return profile;
+    }
+
+    public JsonObject ToJson()
+    {
+        var obj = new JsonObject();
+        foreach (var (field, _) in Fields)
+        {
+            if (_values.TryGetValue(field, out var value))
+                obj[field] = value;
+        }
+        return obj;
+    }
+}
@@ -0,0 +1,257 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A glossary entry: an approved term, its unapproved variants, and its approved
+/// translations. A forbidden entry flags the term itself, optionally with a replacement.
+/// </summary>
+public sealed record GlossaryEntry(
+    string Term,
+    IReadOnlyList<string> Variants,
+    bool Forbidden,
+    string? Replacement,
+    IReadOnlyDictionary<string, string> Translations,
+    bool CaseSensitive,
+    string? Note);
+
+/// <summary>
+/// A glossary term found in a paragraph. Offset and length are in the
+/// paragraph's run text, as used by replace_text.
+/// </summary>
+public sealed record TerminologyFinding(
+    string Path,
+    int Offset,
+    string Text,
+    string Kind,
+    string Term,
+    string? Suggestion,
+    string? Note);
+
+/// <summary>
+/// Glossary parsing and terminology checks. Glossaries are stored as JSON:
+/// <c>{"entries": [{"term": "email", "variants": ["e-mail"]}, ...]}</c>.
+/// </summary>
+public static class TerminologyHelper
+{
+    /// <summary>
+    /// Parse and validate glossary JSON.
+    /// </summary>
+    public static List<GlossaryEntry> Parse(string json)
+    {
+        JsonNode? root;
+        try
+        {
+            root = JsonNode.Parse(json);
+        }
+        catch (JsonException ex)
+        {
+            throw new ArgumentException($"Invalid glossary JSON — {ex.Message}");
+        }
+
+        var entries = (root is JsonArray array ? array : root?["entries"] as JsonArray)
+            ?? throw new ArgumentException("Glossary must be a JSON object with an 'entries' array.");
+
+        var result = new List<GlossaryEntry>();
+        foreach (var node in entries)
+        {
+            if (node is not JsonObject obj)
+                throw new ArgumentException("Each glossary entry must be a JSON object.");
+
+            var term = obj["term"]?.GetValue<string>();
+            if (string.IsNullOrWhiteSpace(term))
+                throw new ArgumentException("Each glossary entry must have a non-empty 'term'.");
+
+            var variants = (obj["variants"] as JsonArray)?
+                .Select(v => v?.GetValue<string>())
+                .Where(v => !string.IsNullOrWhiteSpace(v))
+                .Cast<string>()
+                .ToList() ?? [];
+            var translations = (obj["translations"] as JsonObject)?
+                .Where(kv => kv.Value is not null)
+                .ToDictionary(kv => kv.Key.ToLowerInvariant(), kv => kv.Value!.GetValue<string>())
+                ?? new Dictionary<string, string>();
+
+            result.Add(new GlossaryEntry(
+                term,
+                variants,
+                obj["forbidden"]?.GetValue<bool>() ?? false,
+                obj["replacement"]?.GetValue<string>(),
+                translations,
+                obj["case_sensitive"]?.GetValue<bool>() ?? false,
+                obj["note"]?.GetValue<string>()));
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// Serialize glossary entries back to the stored JSON form.
+    /// </summary>
+    public static string ToJson(IEnumerable<GlossaryEntry> entries)
+    {
+        var arr = new JsonArray();
+        foreach (var e in entries)
+        {
+            var obj = new JsonObject { ["term"] = e.Term };
+            if (e.Variants.Count > 0)
+                obj["variants"] = new JsonArray(e.Variants.Select(v => (JsonNode)JsonValue.Create(v)!).ToArray());
+            if (e.Forbidden)
+                obj["forbidden"] = true;
+            if (e.Replacement is not null)
+                obj["replacement"] = e.Replacement;
+            if (e.Translations.Count > 0)
+            {
+                var tr = new JsonObject();
+                foreach (var (lang, text) in e.Translations)
+                    tr[lang] = text;
+                obj["translations"] = tr;
+            }
+            if (e.CaseSensitive)
+                obj["case_sensitive"] = true;
+            if (e.Note is not null)
+                obj["note"] = e.Note;
+            arr.Add((JsonNode)obj);
+        }
+
+        return new JsonObject { ["entries"] = arr }.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+    }
+
+    /// <summary>
+    /// Find non-compliant terms in the document body, in document order.
+    /// Without a language, variants are flagged in favour of the approved term.
+    /// With a language, terms that have an approved translation in that language
+    /// are flagged (with their variants) in favour of the translation.
+    /// Forbidden terms are always flagged.
+    /// </summary>
+    public static List<TerminologyFinding> Check(
+        WordprocessingDocument doc, IReadOnlyList<GlossaryEntry> glossary, string? language)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var rules = BuildRules(glossary, language?.ToLowerInvariant());
+        var findings = new List<TerminologyFinding>();
+
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var path = PathOf(paragraph, body);
+            if (path is null) continue;
+
+            var text = RunText(paragraph);
+            if (text.Length == 0) continue;
+
+            var matches = new List<(int Offset, int Length, Rule Rule)>();
+            foreach (var rule in rules)
+            {
+                foreach (Match m in rule.Pattern.Matches(text))
+                {
+                    // Longer or earlier rules win where matches overlap
+                    if (matches.Any(x => m.Index < x.Offset + x.Length && x.Offset < m.Index + m.Length))
+                        continue;
+                    matches.Add((m.Index, m.Length, rule));
+                }
+            }
+
+            foreach (var (offset, length, rule) in matches.OrderBy(x => x.Offset))
+            {
+                var found = text.Substring(offset, length);
+                findings.Add(new TerminologyFinding(
+                    path, offset, found, rule.Kind, rule.Term,
+                    rule.Suggestion is null ? null : MatchCase(found, rule.Suggestion, rule.CaseSensitive),
+                    rule.Note));
+            }
+        }
+
+        return findings;
+    }
+
+    /// <summary>
+    /// Text of a paragraph's runs, the same text replace_text searches.
+    /// </summary>
+    public static string RunText(Paragraph paragraph) =>
+        string.Concat(paragraph.Elements<Run>().Select(r => r.InnerText));
+
+    private sealed record Rule(Regex Pattern, string Kind, string Term, string? Suggestion, bool CaseSensitive, string? Note);
+
+    private static List<Rule> BuildRules(IReadOnlyList<GlossaryEntry> glossary, string? language)
+    {
+        var rules = new List<Rule>();
+        foreach (var entry in glossary)
+        {
+            var options = RegexOptions.CultureInvariant | (entry.CaseSensitive ? RegexOptions.None : RegexOptions.IgnoreCase);
+            Rule MakeRule(string text, string kind, string term, string? suggestion) =>
+                new(new Regex(WholeWord(text), options), kind, term, suggestion, entry.CaseSensitive, entry.Note);
+
+            if (entry.Forbidden)
+            {
+                rules.Add(MakeRule(entry.Term, "forbidden", entry.Term, entry.Replacement));
+                continue;
+            }
+
+            string? translation = null;
+            if (language is not null)
+                entry.Translations.TryGetValue(language, out translation);
+
+            var approved = translation ?? entry.Term;
+            if (translation is not null
+                && !string.Equals(translation, entry.Term, StringComparison.OrdinalIgnoreCase))
+            {
+                rules.Add(MakeRule(entry.Term, "untranslated", approved, approved));
+            }
+            foreach (var variant in entry.Variants)
+                rules.Add(MakeRule(variant, "variant", approved, approved));
+        }
+
+        // Prefer the longest pattern where matches overlap ("e-mail address" over "e-mail")
+        return rules.OrderByDescending(r => r.Pattern.ToString().Length).ToList();
+    }
+
+    private static string WholeWord(string text) => $@"(?<!\w){Regex.Escape(text)}(?!\w)";
+
+    /// <summary>
+    /// Capitalize the suggestion when a case-insensitive match starts with a capital
+    /// (e.g. at the start of a sentence).
+    /// </summary>
+    private static string MatchCase(string found, string suggestion, bool caseSensitive)
+    {
+        if (caseSensitive || suggestion.Length == 0 || !char.IsUpper(found[0]) || char.IsUpper(suggestion[0]))
+            return suggestion;
+        return char.ToUpperInvariant(suggestion[0]) + suggestion[1..];
+    }
+
+    /// <summary>
+    /// Typed path to a body paragraph (or table) using ID selectors, or null when the
+    /// element sits inside a container paths can't address (e.g. content controls).
+    /// </summary>
+    internal static string? PathOf(OpenXmlElement element, Body body)
+    {
+        var segments = new List<string>();
+        OpenXmlElement? current = element;
+        while (current is not null && current != body)
+        {
+            var name = current switch
+            {
+                Paragraph => "paragraph",
+                Table => "table",
+                TableRow => "row",
+                TableCell => "cell",
+                _ => null
+            };
+            var id = ElementIdManager.GetId(current);
+            if (name is null || id is null) return null;
+
+            segments.Add($"{name}[id='{id}']");
+            current = current.Parent;
+        }
+        if (current is null) return null;
+
+        segments.Reverse();
+        return "/body/" + string.Join("/", segments);
+    }
+}
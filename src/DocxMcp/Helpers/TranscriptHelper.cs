@@ -0,0 +1,303 @@
+using System.Globalization;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A single transcript line: who spoke, when, and what was said.
+/// The timestamp is normalized to hh:mm:ss.
+/// </summary>
+public sealed record TranscriptEntry(string Speaker, string Timestamp, string Text);
+
+/// <summary>
+/// Per-speaker statistics shown in the speaker index.
+/// </summary>
+public sealed record TranscriptSpeaker(string Name, int Entries, string FirstTimestamp);
+
+/// <summary>
+/// Formats meeting transcripts. Entries are paragraphs in the "TranscriptEntry"
+/// style: a bracketed timestamp, a tab stop that aligns every speaker name, the
+/// speaker in the "TranscriptSpeaker" style (colored per speaker), then the text,
+/// with a hanging indent so wrapped lines stay under the speaker column.
+///
+/// The speaker index is the run of "TranscriptIndex" paragraphs directly under
+/// the index heading; it is rebuilt from the entries on every append.
+/// </summary>
+public static class TranscriptHelper
+{
+    public const string EntryStyle = "TranscriptEntry";
+    public const string TimestampStyle = "TranscriptTimestamp";
+    public const string SpeakerStyle = "TranscriptSpeaker";
+    public const string IndexStyle = "TranscriptIndex";
+
+    private const int SpeakerColumn = 1440; // twips: one inch
+
+    private static readonly string[] SpeakerColors =
+        ["1F4E79", "833C0B", "385623", "7030A0", "C00000", "2E75B6", "BF8F00", "404040"];
+
+    /// <summary>
+    /// Parse entries from a JSON array of {speaker, timestamp, text} objects.
+    /// </summary>
+    public static List<TranscriptEntry> Parse(string json)
+    {
+        JsonNode? root;
+        try
+        {
+            root = JsonNode.Parse(json);
+        }
+        catch (JsonException ex)
+        {
+            throw new ArgumentException($"Invalid entries JSON — {ex.Message}");
+        }
+        if (root is not JsonArray array)
+            throw new ArgumentException("entries must be a JSON array of {speaker, timestamp, text} objects.");
+
+        var entries = new List<TranscriptEntry>();
+        for (int i = 0; i < array.Count; i++)
+        {
+            if (array[i] is not JsonObject obj)
+                throw new ArgumentException($"entries[{i}] must be an object.");
+
+            var speaker = obj["speaker"]?.GetValue<string>()?.Trim();
+            if (string.IsNullOrEmpty(speaker))
+                throw new ArgumentException($"entries[{i}] is missing 'speaker'.");
+
+            var text = obj["text"]?.GetValue<string>() ?? "";
+            var timestamp = obj["timestamp"] switch
+            {
+                null => throw new ArgumentException($"entries[{i}] is missing 'timestamp'."),
+                JsonValue v when v.TryGetValue<double>(out var seconds) => FormatSeconds(seconds),
+                JsonValue v => NormalizeTimestamp(v.GetValue<string>(), i),
+                _ => throw new ArgumentException($"entries[{i}].timestamp must be a string or a number of seconds.")
+            };
+
+            entries.Add(new TranscriptEntry(speaker, timestamp, text.Trim()));
+        }
+        return entries;
+    }
+
+    /// <summary>
+    /// Normalize "m:ss", "h:mm:ss", "hh:mm:ss.fff", a number of seconds, or an
+    /// ISO 8601 date-time (its time of day) to hh:mm:ss.
+    /// </summary>
+    public static string NormalizeTimestamp(string value, int index = 0)
+    {
+        value = value.Trim().Trim('[', ']');
+
+        if (double.TryParse(value, NumberStyles.Float, CultureInfo.InvariantCulture, out var seconds))
+            return FormatSeconds(seconds);
+
+        var parts = value.Split(':');
+        if (parts.Length is 2 or 3
+            && parts[..^1].All(p => int.TryParse(p, NumberStyles.None, CultureInfo.InvariantCulture, out _))
+            && double.TryParse(parts[^1], NumberStyles.AllowDecimalPoint, CultureInfo.InvariantCulture, out var secs))
+        {
+            var total = parts[..^1].Aggregate(0.0, (acc, p) => acc * 60 + int.Parse(p, CultureInfo.InvariantCulture));
+            return FormatSeconds(total * 60 + secs);
+        }
+
+        if (DateTimeOffset.TryParse(value, CultureInfo.InvariantCulture, DateTimeStyles.None, out var dateTime))
+            return dateTime.ToString("HH:mm:ss", CultureInfo.InvariantCulture);
+
+        throw new ArgumentException(
+            $"entries[{index}].timestamp '{value}' is not a time (use hh:mm:ss, mm:ss, seconds or an ISO date-time).");
+    }
+
+    private static string FormatSeconds(double seconds)
+    {
+        if (seconds < 0)
+            throw new ArgumentException("Timestamps cannot be negative.");
+        var span = TimeSpan.FromSeconds(Math.Floor(seconds));
+        return $"{(int)span.TotalHours:00}:{span.Minutes:00}:{span.Seconds:00}";
+    }
+
+    /// <summary>
+    /// Append entries after the last transcript entry in the body (or at the end
+    /// of the body), then rebuild the speaker index under <paramref name="indexHeading"/>
+    /// unless it is empty. Returns the new entry paragraphs.
+    /// </summary>
+    public static List<Paragraph> Append(WordprocessingDocument doc, IReadOnlyList<TranscriptEntry> entries, string? indexHeading)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        EnsureStyles(mainPart);
+
+        var speakers = ReadEntries(body).Select(e => e.Speaker).ToList();
+        var added = new List<Paragraph>();
+
+        OpenXmlElement? anchor = body.Elements<Paragraph>().LastOrDefault(IsEntry);
+        foreach (var entry in entries)
+        {
+            if (!speakers.Contains(entry.Speaker))
+                speakers.Add(entry.Speaker);
+            var color = SpeakerColors[speakers.IndexOf(entry.Speaker) % SpeakerColors.Length];
+
+            var paragraph = BuildEntry(entry, color);
+            if (anchor is not null)
+                body.InsertAfter(paragraph, anchor);
+            else if (body.GetFirstChild<SectionProperties>() is { } sectPr)
+                body.InsertBefore(paragraph, sectPr);
+            else
+                body.AppendChild(paragraph);
+
+            anchor = paragraph;
+            added.Add(paragraph);
+        }
+
+        if (!string.IsNullOrWhiteSpace(indexHeading))
+            WriteIndex(body, indexHeading);
+
+        return added;
+    }
+
+    /// <summary>
+    /// Entries currently in the body, in document order.
+    /// </summary>
+    public static List<TranscriptEntry> ReadEntries(Body body)
+    {
+        var entries = new List<TranscriptEntry>();
+        foreach (var paragraph in body.Elements<Paragraph>().Where(IsEntry))
+        {
+            var runs = paragraph.Elements<Run>().ToList();
+            var timestamp = runs.FirstOrDefault(r => RunStyleOf(r) == TimestampStyle)?.InnerText.Trim('[', ']') ?? "";
+            var speakerRun = runs.FirstOrDefault(r => RunStyleOf(r) == SpeakerStyle);
+            if (speakerRun is null) continue;
+
+            var speaker = speakerRun.InnerText.TrimEnd().TrimEnd(':');
+            var text = string.Concat(runs.SkipWhile(r => r != speakerRun).Skip(1).Select(r => r.InnerText)).Trim();
+            entries.Add(new TranscriptEntry(speaker, timestamp, text));
+        }
+        return entries;
+    }
+
+    /// <summary>
+    /// Speakers in order of first appearance, with entry counts.
+    /// </summary>
+    public static List<TranscriptSpeaker> Speakers(IEnumerable<TranscriptEntry> entries) =>
+        entries
+            .GroupBy(e => e.Speaker)
+            .Select(g => new TranscriptSpeaker(g.Key, g.Count(), g.First().Timestamp))
+            .ToList();
+
+    private static bool IsEntry(Paragraph p) =>
+        p.ParagraphProperties?.ParagraphStyleId?.Val?.Value == EntryStyle;
+
+    private static string? RunStyleOf(Run r) => r.RunProperties?.RunStyle?.Val?.Value;
+
+    private static Paragraph BuildEntry(TranscriptEntry entry, string color)
+    {
+        var paragraph = new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = EntryStyle }),
+            new Run(
+                new RunProperties(new RunStyle { Val = TimestampStyle }),
+                new Text($"[{entry.Timestamp}]")),
+            new Run(new TabChar()),
+            new Run(
+                new RunProperties(new RunStyle { Val = SpeakerStyle }, new Color { Val = color }),
+                new Text(entry.Speaker + ":")));
+
+        if (entry.Text.Length > 0)
+            paragraph.AppendChild(new Run(new Text(" " + entry.Text) { Space = SpaceProcessingModeValues.Preserve }));
+
+        ElementIdManager.AssignId(paragraph);
+        foreach (var run in paragraph.Elements<Run>())
+            ElementIdManager.AssignId(run);
+        return paragraph;
+    }
+
+    /// <summary>
+    /// Replace the index paragraphs under the heading. A missing heading is
+    /// created just before the first transcript entry.
+    /// </summary>
+    private static void WriteIndex(Body body, string indexHeading)
+    {
+        var heading = body.Elements<Paragraph>().FirstOrDefault(p =>
+            p.IsHeading() && p.InnerText.Trim().Equals(indexHeading.Trim(), StringComparison.OrdinalIgnoreCase));
+
+        if (heading is null)
+        {
+            heading = new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+                new Run(new Text(indexHeading.Trim())));
+            ElementIdManager.AssignId(heading);
+
+            var firstEntry = body.Elements<Paragraph>().First(IsEntry);
+            body.InsertBefore(heading, firstEntry);
+        }
+        else
+        {
+            var sibling = heading.NextSibling();
+            while (sibling is Paragraph p && p.ParagraphProperties?.ParagraphStyleId?.Val?.Value == IndexStyle)
+            {
+                sibling = p.NextSibling();
+                p.Remove();
+            }
+        }
+
+        OpenXmlElement anchor = heading;
+        foreach (var speaker in Speakers(ReadEntries(body)))
+        {
+            var noun = speaker.Entries == 1 ? "entry" : "entries";
+            var line = new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = IndexStyle }),
+                new Run(
+                    new RunProperties(new RunStyle { Val = SpeakerStyle }),
+                    new Text(speaker.Name)),
+                new Run(new Text($" — {speaker.Entries} {noun}, first at {speaker.FirstTimestamp}")
+                {
+                    Space = SpaceProcessingModeValues.Preserve
+                }));
+            ElementIdManager.AssignId(line);
+
+            body.InsertAfter(line, anchor);
+            anchor = line;
+        }
+    }
+
+    private static void EnsureStyles(MainDocumentPart mainPart)
+    {
+        var stylesPart = mainPart.StyleDefinitionsPart
+            ?? mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles ??= new Styles();
+        var styles = stylesPart.Styles;
+
+        void Add(string id, Func<Style> create)
+        {
+            if (!styles.Elements<Style>().Any(s => s.StyleId?.Value == id))
+                styles.AppendChild(create());
+        }
+
+        Add(EntryStyle, () => new Style(
+            new StyleName { Val = "Transcript Entry" },
+            new BasedOn { Val = "Normal" },
+            new PrimaryStyle(),
+            new StyleParagraphProperties(
+                new Tabs(new TabStop { Val = TabStopValues.Left, Position = SpeakerColumn }),
+                new SpacingBetweenLines { After = "80" },
+                new Indentation { Left = SpeakerColumn.ToString(CultureInfo.InvariantCulture), Hanging = SpeakerColumn.ToString(CultureInfo.InvariantCulture) }))
+        { Type = StyleValues.Paragraph, StyleId = EntryStyle, CustomStyle = true });
+
+        Add(IndexStyle, () => new Style(
+            new StyleName { Val = "Transcript Index" },
+            new BasedOn { Val = "Normal" },
+            new StyleParagraphProperties(new SpacingBetweenLines { After = "0" }))
+        { Type = StyleValues.Paragraph, StyleId = IndexStyle, CustomStyle = true });
+
+        Add(TimestampStyle, () => new Style(
+            new StyleName { Val = "Transcript Timestamp" },
+            new StyleRunProperties(new Color { Val = "808080" }, new FontSize { Val = "18" }))
+        { Type = StyleValues.Character, StyleId = TimestampStyle, CustomStyle = true });
+
+        Add(SpeakerStyle, () => new Style(
+            new StyleName { Val = "Transcript Speaker" },
+            new StyleRunProperties(new Bold()))
+        { Type = StyleValues.Character, StyleId = SpeakerStyle, CustomStyle = true });
+    }
+}
@@ -0,0 +1,249 @@
+using System.Text.RegularExpressions;
+using System.Xml.Linq;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A source segment and its translation, with the paragraphs they come from
+/// and the structural role they share (h1, li0, p, cell t1r2c3, ...).
+/// </summary>
+public sealed record AlignedSegment(string? SourceId, string? TargetId, string Role, string Source, string Target);
+
+/// <summary>
+/// Aligned segments, and the paragraphs of each version left without a counterpart.
+/// </summary>
+public sealed record AlignmentResult(List<AlignedSegment> Segments, int UnalignedSource, int UnalignedTarget);
+
+/// <summary>
+/// Paragraph and sentence alignment of two language versions of a document,
+/// exported as a TMX 1.4 translation memory.
+///
+/// Paragraphs are aligned with Gale–Church (dynamic programming on character
+/// lengths), but the structure decides what may pair up: headings only match
+/// headings of the same level, table cells only the cell at the same position,
+/// list items only list items. An inserted or dropped paragraph shifts nothing
+/// around it, and a paragraph split in two in the translation (or two merged)
+/// aligns as one segment.
+/// Aligned paragraphs with the same number of sentences are then split into
+/// sentence pairs.
+/// </summary>
+public static partial class TranslationMemoryHelper
+{
+    /// <summary>Variance of the translation length per source character (Gale–Church).</summary>
+    private const double LengthVariance = 6.8;
+
+    // Costs (negative log priors) of each kind of pair, from Gale–Church
+    private static readonly double MatchCost = -Math.Log(0.89);
+    private static readonly double SkipCost = -Math.Log(0.0099 / 2);
+    private static readonly double MergeCost = -Math.Log(0.089 / 2);
+
+    [GeneratedRegex(@"(?<=[.!?…。！？])\s+(?=\S)")]
+    private static partial Regex SentenceBreak();
+
+    private sealed record Unit(string? Id, string Role, string Text);
+
+    private enum Step : byte { None, Match, SkipSource, SkipTarget, MergeSource, MergeTarget }
+
+    /// <summary>
+    /// Align the paragraphs (and, with <paramref name="sentences"/>, the sentences)
+    /// of <paramref name="source"/> with those of <paramref name="target"/>.
+    /// </summary>
+    public static AlignmentResult Align(WordprocessingDocument source, WordprocessingDocument target, bool sentences)
+    {
+        var a = Units(source);
+        var b = Units(target);
+
+        int n = a.Count, m = b.Count;
+        var cost = new double[n + 1, m + 1];
+        var steps = new Step[n + 1, m + 1];
+        for (int i = 0; i <= n; i++)
+        {
+            for (int j = 0; j <= m; j++)
+            {
+                if (i == 0 && j == 0) continue;
+                var best = double.PositiveInfinity;
+                var step = Step.None;
+
+                if (i > 0)
+                    Consider(ref best, ref step, cost[i - 1, j] + SkipCost, Step.SkipSource);
+                if (j > 0)
+                    Consider(ref best, ref step, cost[i, j - 1] + SkipCost, Step.SkipTarget);
+                if (i > 0 && j > 0 && a[i - 1].Role == b[j - 1].Role)
+                    Consider(ref best, ref step, cost[i - 1, j - 1] + MatchCost +
+                             LengthCost(a[i - 1].Text.Length, b[j - 1].Text.Length),
+                        Step.Match);
+                if (i > 1 && j > 0 && Mergeable(b[j - 1], a[i - 2], a[i - 1]))
+                    Consider(ref best, ref step, cost[i - 2, j - 1] + MergeCost +
+                             LengthCost(a[i - 2].Text.Length + a[i - 1].Text.Length, b[j - 1].Text.Length),
+                        Step.MergeSource);
+                if (i > 0 && j > 1 && Mergeable(a[i - 1], b[j - 2], b[j - 1]))
+                    Consider(ref best, ref step, cost[i - 1, j - 2] + MergeCost +
+                             LengthCost(a[i - 1].Text.Length, b[j - 2].Text.Length + b[j - 1].Text.Length),
+                        Step.MergeTarget);
+
+                cost[i, j] = best;
+                steps[i, j] = step;
+            }
+        }
+
+        var segments = new List<AlignedSegment>();
+        int unalignedSource = 0, unalignedTarget = 0;
+        for (int i = n, j = m; i > 0 || j > 0;)
+        {
+            switch (steps[i, j])
+            {
+                case Step.Match:
+                    var pair = new List<AlignedSegment>();
+                    if (sentences)
+                        pair.AddRange(SplitSentences(a[i - 1], b[j - 1]));
+                    else
+                        pair.Add(Segment(a[i - 1], b[j - 1]));
+                    segments.InsertRange(0, pair);
+                    i--; j--;
+                    break;
+                case Step.MergeSource:
+                    segments.Insert(0, new AlignedSegment(a[i - 2].Id, b[j - 1].Id, b[j - 1].Role,
+                        a[i - 2].Text + " " + a[i - 1].Text, b[j - 1].Text));
+                    i -= 2; j--;
+                    break;
+                case Step.MergeTarget:
+                    segments.Insert(0, new AlignedSegment(a[i - 1].Id, b[j - 2].Id, a[i - 1].Role,
+                        a[i - 1].Text, b[j - 2].Text + " " + b[j - 1].Text));
+                    i--; j -= 2;
+                    break;
+                case Step.SkipSource:
+                    unalignedSource++;
+                    i--;
+                    break;
+                default:
+                    unalignedTarget++;
+                    j--;
+                    break;
+            }
+        }
+
+        return new AlignmentResult(segments, unalignedSource, unalignedTarget);
+    }
+
+    /// <summary>
+    /// The aligned segments as a TMX 1.4 document. Each translation unit records
+    /// the paragraph IDs it comes from and its structural role as properties.
+    /// </summary>
+    public static XDocument ToTmx(AlignmentResult alignment, string sourceLang, string targetLang, bool sentences)
+    {
+        var header = new XElement("header",
+            new XAttribute("creationtool", "docx-mcp"),
+            new XAttribute("creationtoolversion", "1"),
+            new XAttribute("segtype", sentences ? "sentence" : "paragraph"),
+            new XAttribute("o-tmf", "docx"),
+            new XAttribute("adminlang", "en"),
+            new XAttribute("srclang", sourceLang),
+            new XAttribute("datatype", "plaintext"),
+            Prop("x-unaligned-source", alignment.UnalignedSource.ToString()),
+            Prop("x-unaligned-target", alignment.UnalignedTarget.ToString()));
+
+        var body = new XElement("body");
+        var tuid = 0;
+        foreach (var segment in alignment.Segments)
+        {
+            var tu = new XElement("tu", new XAttribute("tuid", ++tuid));
+            if (segment.SourceId is not null) tu.Add(Prop("x-source-id", segment.SourceId));
+            if (segment.TargetId is not null) tu.Add(Prop("x-target-id", segment.TargetId));
+            tu.Add(Prop("x-role", segment.Role));
+            tu.Add(Variant(sourceLang, segment.Source), Variant(targetLang, segment.Target));
+            body.Add(tu);
+        }
+
+        return new XDocument(
+            new XDeclaration("1.0", "UTF-8", null),
+            new XElement("tmx", new XAttribute("version", "1.4"), header, body));
+    }
+
+    private static void Consider(ref double best, ref Step step, double cost, Step candidate)
+    {
+        if (cost < best)
+        {
+            best = cost;
+            step = candidate;
+        }
+    }
+
+    private static XElement Prop(string type, string value) =>
+        new("prop", new XAttribute("type", type), value);
+
+    private static XElement Variant(string lang, string text) =>
+        new("tuv", new XAttribute(XNamespace.Xml + "lang", lang), new XElement("seg", text));
+
+    /// <summary>
+    /// Body paragraphs with text, in document order, with their structural role.
+    /// </summary>
+    private static List<Unit> Units(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var tables = body.Descendants<Table>().Select((t, i) => (t, i)).ToDictionary(x => x.t, x => x.i + 1);
+        var units = new List<Unit>();
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var text = string.Concat(paragraph.Descendants<Text>().Select(t => t.Text)).Trim();
+            if (text.Length == 0) continue;
+            units.Add(new Unit(ElementIdManager.GetId(paragraph), Role(paragraph, tables), text));
+        }
+        return units;
+    }
+
+    private static string Role(Paragraph paragraph, Dictionary<Table, int> tables)
+    {
+        if (paragraph.Ancestors<TableCell>().FirstOrDefault() is TableCell cell
+            && cell.Parent is TableRow row && row.Parent is Table table)
+        {
+            var r = table.Elements<TableRow>().ToList().IndexOf(row) + 1;
+            var c = row.Elements<TableCell>().ToList().IndexOf(cell) + 1;
+            return $"cell t{tables[table]}r{r}c{c}";
+        }
+        var level = paragraph.GetHeadingLevel();
+        if (level > 0)
+            return $"h{level}";
+        if (paragraph.ParagraphProperties?.NumberingProperties is NumberingProperties numbering)
+            return $"li{numbering.NumberingLevelReference?.Val?.Value ?? 0}";
+        return "p";
+    }
+
+    /// <summary>
+    /// Whether <paramref name="one"/> may pair with the consecutive <paramref name="first"/>
+    /// and <paramref name="second"/>: body paragraphs or list items of one role, or
+    /// paragraphs of the same table cell.
+    /// </summary>
+    private static bool Mergeable(Unit one, Unit first, Unit second) =>
+        first.Role == one.Role && second.Role == one.Role && !one.Role.StartsWith('h');
+
+    /// <summary>
+    /// -log of the probability that texts of these lengths are translations of
+    /// each other: the length difference, normalized by its expected spread, is
+    /// taken as standard normal.
+    /// </summary>
+    private static double LengthCost(int sourceLength, int targetLength)
+    {
+        var mean = (sourceLength + targetLength) / 2.0;
+        var z = Math.Abs(targetLength - sourceLength) / Math.Sqrt(Math.Max(mean, 1) * LengthVariance) / Math.Sqrt(2);
+        // -log(erfc(z)), with erfc from Abramowitz and Stegun 7.1.26
+        var t = 1 / (1 + 0.3275911 * z);
+        var poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+        return z * z - Math.Log(poly);
+    }
+
+    private static AlignedSegment Segment(Unit source, Unit target) =>
+        new(source.Id, target.Id, source.Role, source.Text, target.Text);
+
+    private static IEnumerable<AlignedSegment> SplitSentences(Unit source, Unit target)
+    {
+        var a = SentenceBreak().Split(source.Text);
+        var b = SentenceBreak().Split(target.Text);
+        if (a.Length != b.Length)
+            return [Segment(source, target)];
+        return a.Zip(b, (s, t) => new AlignedSegment(source.Id, target.Id, source.Role, s, t));
+    }
+}
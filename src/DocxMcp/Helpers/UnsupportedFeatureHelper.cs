@@ -0,0 +1,118 @@
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using A = DocumentFormat.OpenXml.Drawing;
+using M = DocumentFormat.OpenXml.Math;
+using O = DocumentFormat.OpenXml.Vml.Office;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Where one unsupported feature sits: the part (body, header1, ...), the typed
+/// path of the body element holding it (null in headers and footers), and a detail
+/// such as the ProgID of an embedded object.
+/// </summary>
+public sealed record FeatureLocation(string Part, string? Path, string? Detail);
+
+/// <summary>
+/// A kind of content the server keeps but can't model, with every place it occurs.
+/// </summary>
+public sealed record UnsupportedFeature(string Kind, string Handling, List<FeatureLocation> Locations);
+
+/// <summary>
+/// Finds content the element factory can't rebuild: equations, SmartArt, charts,
+/// embedded OLE objects (spreadsheets, ...) and pending tracked changes.
+///
+/// Saving keeps such content byte for byte, since the package is written back
+/// through the Open XML SDK with every part it was opened with. What can lose it is
+/// an edit that rebuilds the element holding it, so <see cref="EnsureReplaceable"/>
+/// refuses those replacements instead of dropping the content silently.
+/// </summary>
+public static class UnsupportedFeatureHelper
+{
+    private const string DiagramUri = "http://schemas.openxmlformats.org/drawingml/2006/diagram";
+    private const string ChartUri = "http://schemas.openxmlformats.org/drawingml/2006/chart";
+
+    private static readonly Dictionary<string, string> Handlings = new()
+    {
+        ["equation"] = "Kept on save. Text tools don't read or write equation content; edit around the equation.",
+        ["smartart"] = "Kept on save with its data, layout and drawing parts. The diagram can't be edited.",
+        ["chart"] = "Kept on save with its chart part and embedded workbook. The chart can't be edited.",
+        ["embedded_object"] = "Kept on save with its embedded package. The object can't be edited.",
+        ["tracked_change"] = "Kept on save. Review with revision_list, then revision_accept or revision_reject."
+    };
+
+    /// <summary>
+    /// Every unsupported feature of the document, in the order of <see cref="Handlings"/>.
+    /// </summary>
+    public static List<UnsupportedFeature> Report(WordprocessingDocument doc)
+    {
+        var found = new Dictionary<string, List<FeatureLocation>>();
+        foreach (var (_, root, name) in PolicyParts.Of(doc))
+        {
+            foreach (var (kind, element, detail) in Scan(root))
+            {
+                var path = name == "body" ? PolicyParts.PathOf(element, doc) : null;
+                if (!found.TryGetValue(kind, out var locations))
+                    found[kind] = locations = [];
+                locations.Add(new FeatureLocation(name, path, detail));
+            }
+        }
+
+        return Handlings.Keys
+            .Where(found.ContainsKey)
+            .Select(kind => new UnsupportedFeature(kind, Handlings[kind], found[kind]))
+            .ToList();
+    }
+
+    /// <summary>
+    /// Throw when replacing <paramref name="target"/> would drop unsupported content.
+    /// Pending revisions are only at risk when track changes is off (a tracked
+    /// replacement keeps the old element as a deletion).
+    /// </summary>
+    public static void EnsureReplaceable(OpenXmlElement target, bool trackChanges)
+    {
+        var kinds = Scan(target)
+            .Select(f => f.Kind)
+            .Where(kind => !trackChanges || kind != "tracked_change")
+            .Distinct()
+            .ToList();
+        if (kinds.Count == 0)
+            return;
+
+        var id = ElementIdManager.GetId(target);
+        throw new InvalidOperationException(
+            $"Element{(id is null ? "" : $" '{id}'")} holds content the server can't rebuild " +
+            $"({string.Join(", ", kinds)}); replacing it would lose that content. " +
+            "Use replace_text to change its text, or remove_element if it is meant to go. " +
+            "See get_unsupported_features.");
+    }
+
+    private static IEnumerable<(string Kind, OpenXmlElement Element, string? Detail)> Scan(OpenXmlElement root)
+    {
+        foreach (var element in root.Descendants().Prepend(root))
+        {
+            switch (element)
+            {
+                // m:oMath, inline or inside a display equation (m:oMathPara)
+                case M.OfficeMath:
+                    yield return ("equation", element, null);
+                    break;
+                case A.GraphicData { Uri.Value: DiagramUri }:
+                    yield return ("smartart", element, null);
+                    break;
+                case A.GraphicData { Uri.Value: ChartUri }:
+                    yield return ("chart", element, null);
+                    break;
+                case EmbeddedObject:
+                    yield return ("embedded_object", element,
+                        element.Descendants<O.OleObject>().FirstOrDefault()?.ProgId?.Value);
+                    break;
+                case InsertedRun or DeletedRun or MoveFromRun or MoveToRun
+                    or RunPropertiesChange or ParagraphPropertiesChange:
+                    yield return ("tracked_change", element, element.LocalName);
+                    break;
+            }
+        }
+    }
+}
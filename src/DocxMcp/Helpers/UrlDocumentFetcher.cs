@@ -0,0 +1,179 @@
+using System.Net;
+using System.Net.Http.Headers;
+using System.Net.Sockets;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Downloads a DOCX from a link for document_open_url. Only HTTPS is fetched, and
+/// connections to loopback, private and link-local addresses are refused (checked
+/// on the address actually connected to, so DNS can't be used to sneak past).
+/// Redirects are followed by hand so every hop gets the same checks, and the
+/// Authorization header is only sent to the host of the original link.
+/// </summary>
+public sealed class UrlDocumentFetcher
+{
+    /// <summary>Content types a DOCX may be served with.</summary>
+    private static readonly HashSet<string> AcceptedContentTypes = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "application/octet-stream",
+        "binary/octet-stream",
+        "application/zip",
+        "application/x-zip-compressed",
+    };
+
+    private readonly UrlFetchOptions _options;
+    private readonly HttpClient _http;
+
+    public UrlDocumentFetcher(UrlFetchOptions options, HttpMessageHandler? handler = null)
+    {
+        _options = options;
+        _http = new HttpClient(handler ?? CreateHandler(options)) { Timeout = Timeout.InfiniteTimeSpan };
+    }
+
+    /// <summary>
+    /// Download the document at <paramref name="url"/>, sending <paramref name="authorization"/>
+    /// (e.g. "Bearer ...") as the Authorization header when given.
+    /// </summary>
+    public async Task<byte[]> DownloadAsync(string url, string? authorization, CancellationToken cancellationToken = default)
+    {
+        if (!Uri.TryCreate(url, UriKind.Absolute, out var uri))
+            throw new ArgumentException($"'{url}' is not an absolute URL.");
+        var origin = uri;
+
+        using var timeout = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        timeout.CancelAfter(_options.Timeout);
+
+        for (int redirects = 0; ; redirects++)
+        {
+            CheckScheme(uri);
+
+            using var request = new HttpRequestMessage(HttpMethod.Get, uri);
+            if (authorization is not null && SameOrigin(uri, origin))
+                request.Headers.TryAddWithoutValidation("Authorization", authorization);
+
+            using var response = await _http.SendAsync(request, HttpCompletionOption.ResponseHeadersRead, timeout.Token);
+
+            if (IsRedirect(response.StatusCode))
+            {
+                if (redirects >= _options.MaxRedirects)
+                    throw new InvalidOperationException($"Too many redirects fetching {origin}.");
+                var location = response.Headers.Location
+                    ?? throw new InvalidOperationException($"{uri} redirected without a Location.");
+                uri = location.IsAbsoluteUri ? location : new Uri(uri, location);
+                continue;
+            }
+
+            if (!response.IsSuccessStatusCode)
+                throw new InvalidOperationException(
+                    $"{uri} returned {(int)response.StatusCode} {response.ReasonPhrase}." +
+                    (response.StatusCode is HttpStatusCode.Unauthorized or HttpStatusCode.Forbidden
+                        ? " The link may need the auth parameter." : ""));
+
+            CheckContentType(uri, response.Content.Headers.ContentType);
+            if (response.Content.Headers.ContentLength > _options.MaxBytes)
+                throw new InvalidOperationException(
+                    $"The document is {response.Content.Headers.ContentLength} bytes, over the {_options.MaxBytes} byte limit.");
+
+            var data = await ReadLimitedAsync(response.Content, timeout.Token);
+            if (data.Length < 4 || data[0] != (byte)'P' || data[1] != (byte)'K' || data[2] != 3 || data[3] != 4)
+                throw new InvalidOperationException($"{uri} did not return a DOCX file (not a ZIP package).");
+            return data;
+        }
+    }
+
+    private void CheckScheme(Uri uri)
+    {
+        if (uri.Scheme == Uri.UriSchemeHttps)
+            return;
+        if (uri.Scheme == Uri.UriSchemeHttp && _options.AllowPrivateHosts)
+            return;
+        throw new ArgumentException($"Only HTTPS links can be opened, got {uri.Scheme}://.");
+    }
+
+    private static void CheckContentType(Uri uri, MediaTypeHeaderValue? contentType)
+    {
+        if (contentType?.MediaType is null || AcceptedContentTypes.Contains(contentType.MediaType))
+            return;
+        var hint = contentType.MediaType.StartsWith("text/html", StringComparison.OrdinalIgnoreCase)
+            ? " It is probably a sharing or login page: use the provider's direct download link."
+            : "";
+        throw new InvalidOperationException(
+            $"{uri} returned {contentType.MediaType}, not a DOCX document.{hint}");
+    }
+
+    private async Task<byte[]> ReadLimitedAsync(HttpContent content, CancellationToken cancellationToken)
+    {
+        await using var stream = await content.ReadAsStreamAsync(cancellationToken);
+        using var buffer = new MemoryStream();
+        var chunk = new byte[81920];
+        int read;
+        while ((read = await stream.ReadAsync(chunk, cancellationToken)) > 0)
+        {
+            if (buffer.Length + read > _options.MaxBytes)
+                throw new InvalidOperationException(
+                    $"The document is over the {_options.MaxBytes} byte limit.");
+            buffer.Write(chunk, 0, read);
+        }
+        return buffer.ToArray();
+    }
+
+    private static bool SameOrigin(Uri a, Uri b) =>
+        a.Scheme == b.Scheme && a.Port == b.Port
+        && string.Equals(a.IdnHost, b.IdnHost, StringComparison.OrdinalIgnoreCase);
+
+    private static bool IsRedirect(HttpStatusCode status) =>
+        status is HttpStatusCode.MovedPermanently or HttpStatusCode.Found or HttpStatusCode.SeeOther
+            or HttpStatusCode.TemporaryRedirect or HttpStatusCode.PermanentRedirect;
+
+    private static SocketsHttpHandler CreateHandler(UrlFetchOptions options) => new()
+    {
+        AllowAutoRedirect = false,
+        ConnectCallback = async (context, cancellationToken) =>
+        {
+            var addresses = await Dns.GetHostAddressesAsync(context.DnsEndPoint.Host, cancellationToken);
+            var allowed = addresses.Where(a => options.AllowPrivateHosts || IsPublic(a)).ToArray();
+            if (allowed.Length == 0)
+                throw new HttpRequestException(
+                    $"{context.DnsEndPoint.Host} resolves to a private or local address.");
+
+            var socket = new Socket(SocketType.Stream, ProtocolType.Tcp) { NoDelay = true };
+            try
+            {
+                await socket.ConnectAsync(allowed, context.DnsEndPoint.Port, cancellationToken);
+                return new NetworkStream(socket, ownsSocket: true);
+            }
+            catch
+            {
+                socket.Dispose();
+                throw;
+            }
+        }
+    };
+
+    /// <summary>
+    /// Whether <paramref name="address"/> is routable on the internet: not loopback,
+    /// private (RFC 1918, unique local), link-local, CGNAT or unspecified.
+    /// </summary>
+    internal static bool IsPublic(IPAddress address)
+    {
+        if (address.IsIPv4MappedToIPv6)
+            address = address.MapToIPv4();
+        if (IPAddress.IsLoopback(address) || address.Equals(IPAddress.Any) || address.Equals(IPAddress.IPv6Any))
+            return false;
+
+        if (address.AddressFamily == AddressFamily.InterNetwork)
+        {
+            var b = address.GetAddressBytes();
+            return !(b[0] == 10
+                || b[0] == 0
+                || (b[0] == 172 && b[1] >= 16 && b[1] <= 31)
+                || (b[0] == 192 && b[1] == 168)
+                || (b[0] == 169 && b[1] == 254)
+                || (b[0] == 100 && b[1] >= 64 && b[1] <= 127));
+        }
+
+        return !(address.IsIPv6LinkLocal || address.IsIPv6SiteLocal || address.IsIPv6UniqueLocal);
+    }
+}
@@ -0,0 +1,44 @@
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Limits applied by document_open_url when downloading a DOCX.
+/// </summary>
+public sealed class UrlFetchOptions
+{
+    /// <summary>Largest document accepted, in bytes.</summary>
+    public long MaxBytes { get; set; } = 50L * 1024 * 1024;
+
+    public TimeSpan Timeout { get; set; } = TimeSpan.FromSeconds(60);
+
+    /// <summary>Redirects followed before giving up.</summary>
+    public int MaxRedirects { get; set; } = 5;
+
+    /// <summary>
+    /// Allow plain HTTP and hosts resolving to loopback, private or link-local
+    /// addresses. Off by default so a link can't reach internal services.
+    /// </summary>
+    public bool AllowPrivateHosts { get; set; }
+
+    /// <summary>
+    /// Read options from URL_FETCH_MAX_BYTES, URL_FETCH_TIMEOUT_SECONDS and
+    /// URL_FETCH_ALLOW_PRIVATE (1/true).
+    /// </summary>
+    public static UrlFetchOptions FromEnvironment()
+    {
+        var options = new UrlFetchOptions();
+
+        if (long.TryParse(Environment.GetEnvironmentVariable("URL_FETCH_MAX_BYTES"), out var maxBytes)
+            && maxBytes > 0)
+            options.MaxBytes = maxBytes;
+
+        if (int.TryParse(Environment.GetEnvironmentVariable("URL_FETCH_TIMEOUT_SECONDS"), out var timeout)
+            && timeout > 0)
+            options.Timeout = TimeSpan.FromSeconds(timeout);
+
+        var allowPrivate = Environment.GetEnvironmentVariable("URL_FETCH_ALLOW_PRIVATE");
+        options.AllowPrivateHosts = allowPrivate is "1"
+            || string.Equals(allowPrivate, "true", StringComparison.OrdinalIgnoreCase);
+
+        return options;
+    }
+}
@@ -0,0 +1,275 @@
+using System.Xml.Linq;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// Result of importing an XLIFF file: units applied and units skipped with the reason.
+/// </summary>
+public sealed record XliffImportResult(int Units, int Updated, List<(string Id, string Reason)> Skipped);
+
+/// <summary>
+/// XLIFF 1.2 round-trip of a document's paragraphs.
+///
+/// Each paragraph with text becomes a trans-unit keyed by the paragraph's ID.
+/// Runs with the same formatting are wrapped in <c>&lt;g id="gN"&gt;</c>, and
+/// non-text content (tabs, breaks, images, hyperlinks, bookmarks, ...) becomes an
+/// <c>&lt;x id="xN"/&gt;</c> placeholder, so translators can move but not alter
+/// formatting. Import rebuilds each paragraph from its target using the
+/// formatting and placeholders of the paragraph as it is now.
+/// </summary>
+public static class XliffHelper
+{
+    public static readonly XNamespace Ns = "urn:oasis:names:tc:xliff:document:1.2";
+
+    /// <summary>
+    /// Export translatable paragraphs as an XLIFF 1.2 document.
+    /// </summary>
+    public static XDocument Export(WordprocessingDocument doc, string original, string sourceLang, string targetLang)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var units = new XElement(Ns + "body");
+        foreach (var paragraph in body.Descendants<Paragraph>())
+        {
+            var id = ElementIdManager.GetId(paragraph);
+            if (id is null) continue;
+
+            var segment = Segment(paragraph);
+            if (string.IsNullOrWhiteSpace(segment.Source.Value)) continue;
+
+            units.Add(new XElement(Ns + "trans-unit",
+                new XAttribute("id", id),
+                segment.Source));
+        }
+
+        return new XDocument(
+            new XDeclaration("1.0", "UTF-8", null),
+            new XElement(Ns + "xliff",
+                new XAttribute("version", "1.2"),
+                new XElement(Ns + "file",
+                    new XAttribute("original", original),
+                    new XAttribute("source-language", sourceLang),
+                    new XAttribute("target-language", targetLang),
+                    new XAttribute("datatype", "x-docx"),
+                    units)));
+    }
+
+    /// <summary>
+    /// Apply the targets of an XLIFF document. Units without a target, whose
+    /// paragraph no longer exists, or whose source text no longer matches the
+    /// paragraph are skipped.
+    /// </summary>
+    public static XliffImportResult Import(WordprocessingDocument doc, string xliff)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        XDocument xdoc;
+        try
+        {
+            xdoc = XDocument.Parse(xliff, LoadOptions.PreserveWhitespace);
+        }
+        catch (System.Xml.XmlException ex)
+        {
+            throw new ArgumentException($"Invalid XLIFF — {ex.Message}");
+        }
+        if (xdoc.Root?.Name != Ns + "xliff")
+            throw new ArgumentException("Not an XLIFF 1.2 document (expected <xliff> in the XLIFF 1.2 namespace).");
+
+        var paragraphs = new Dictionary<string, Paragraph>(StringComparer.OrdinalIgnoreCase);
+        foreach (var p in body.Descendants<Paragraph>())
+        {
+            if (ElementIdManager.GetId(p) is string pid)
+                paragraphs.TryAdd(pid, p);
+        }
+
+        var units = xdoc.Descendants(Ns + "trans-unit").ToList();
+        var skipped = new List<(string, string)>();
+        int updated = 0;
+
+        foreach (var unit in units)
+        {
+            var id = unit.Attribute("id")?.Value ?? "";
+            var source = unit.Element(Ns + "source");
+            var target = unit.Element(Ns + "target");
+
+            if (target is null)
+            {
+                skipped.Add((id, "no target"));
+                continue;
+            }
+            if (!paragraphs.TryGetValue(id, out var paragraph))
+            {
+                skipped.Add((id, "paragraph not found"));
+                continue;
+            }
+
+            var segment = Segment(paragraph);
+            if (source is not null && source.Value != segment.Source.Value)
+            {
+                skipped.Add((id, "source text changed since export"));
+                continue;
+            }
+
+            Rebuild(paragraph, segment, target);
+            updated++;
+        }
+
+        return new XliffImportResult(units.Count, updated, skipped);
+    }
+
+    private sealed record Placeholder(OpenXmlElement Element, string? Group);
+
+    private sealed record SegmentInfo(
+        XElement Source,
+        Dictionary<string, RunProperties?> Groups,
+        Dictionary<string, Placeholder> Placeholders);
+
+    private static SegmentInfo Segment(Paragraph paragraph)
+    {
+        var source = new XElement(Ns + "source");
+        var groups = new Dictionary<string, RunProperties?>();
+        var placeholders = new Dictionary<string, Placeholder>();
+
+        XElement? group = null;
+        string? groupFormat = null;
+
+        foreach (var child in paragraph.ChildElements)
+        {
+            if (child is ParagraphProperties) continue;
+
+            if (child is Run run)
+            {
+                var format = run.RunProperties?.OuterXml ?? "";
+                if (group is null || format != groupFormat)
+                {
+                    var groupId = $"g{groups.Count + 1}";
+                    groups[groupId] = run.RunProperties;
+                    group = new XElement(Ns + "g", new XAttribute("id", groupId));
+                    groupFormat = format;
+                    source.Add(group);
+                }
+
+                foreach (var rc in run.ChildElements)
+                {
+                    switch (rc)
+                    {
+                        case RunProperties or LastRenderedPageBreak:
+                            break;
+                        case Text t:
+                            group.Add(t.Text);
+                            break;
+                        default:
+                            group.Add(AddPlaceholder(placeholders, rc, group.Attribute("id")!.Value));
+                            break;
+                    }
+                }
+            }
+            else
+            {
+                source.Add(AddPlaceholder(placeholders, child, null));
+                group = null;
+            }
+        }
+
+        // A single uniformly formatted run needs no markup
+        if (groups.Count == 1 && placeholders.Count == 0 && source.Elements().Single() is XElement only)
+            source.ReplaceNodes(only.Nodes());
+
+        return new SegmentInfo(source, groups, placeholders);
+    }
+
+    private static XElement AddPlaceholder(Dictionary<string, Placeholder> placeholders, OpenXmlElement element, string? group)
+    {
+        var id = $"x{placeholders.Count + 1}";
+        placeholders[id] = new Placeholder(element, group);
+
+        var ctype = element switch
+        {
+            TabChar => "x-tab",
+            Break => "lb",
+            Drawing => "image",
+            Hyperlink => "link",
+            _ => "x-" + element.LocalName
+        };
+        var x = new XElement(Ns + "x", new XAttribute("id", id), new XAttribute("ctype", ctype));
+        // Show the text of opaque content (e.g. a hyperlink) to the translator
+        if (element is not Run && element.InnerText.Length > 0)
+            x.SetAttributeValue("equiv-text", element.InnerText);
+        return x;
+    }
+
+    private static void Rebuild(Paragraph paragraph, SegmentInfo segment, XElement target)
+    {
+        var defaultGroup = segment.Groups.Keys.FirstOrDefault();
+        var content = new List<OpenXmlElement>();
+        var used = new HashSet<string>();
+
+        Run NewRun(string? groupId)
+        {
+            var run = new Run();
+            if (groupId is not null && segment.Groups.TryGetValue(groupId, out var props) && props is not null)
+                run.RunProperties = (RunProperties)props.CloneNode(true);
+            ElementIdManager.AssignId(run);
+            return run;
+        }
+
+        void AddPlaceholder(string id)
+        {
+            if (!segment.Placeholders.TryGetValue(id, out var placeholder) || !used.Add(id)) return;
+
+            var clone = placeholder.Element.CloneNode(true);
+            if (placeholder.Group is null)
+            {
+                content.Add(clone);
+            }
+            else
+            {
+                var run = NewRun(placeholder.Group);
+                run.AppendChild(clone);
+                content.Add(run);
+            }
+        }
+
+        void Walk(XElement element, string? groupId)
+        {
+            foreach (var node in element.Nodes())
+            {
+                switch (node)
+                {
+                    case XText text when text.Value.Length > 0:
+                        var run = NewRun(groupId);
+                        run.AppendChild(new Text(text.Value) { Space = SpaceProcessingModeValues.Preserve });
+                        content.Add(run);
+                        break;
+                    case XElement x when x.Name == Ns + "x":
+                        AddPlaceholder(x.Attribute("id")?.Value ?? "");
+                        break;
+                    case XElement g when g.Name == Ns + "g":
+                        var id = g.Attribute("id")?.Value;
+                        Walk(g, id is not null && segment.Groups.ContainsKey(id) ? id : groupId);
+                        break;
+                    case XElement other:
+                        // mrk and other wrappers: keep their text
+                        Walk(other, groupId);
+                        break;
+                }
+            }
+        }
+
+        Walk(target, defaultGroup);
+
+        // Never drop content the translator removed by accident
+        foreach (var id in segment.Placeholders.Keys)
+            AddPlaceholder(id);
+
+        foreach (var child in paragraph.ChildElements.Where(c => c is not ParagraphProperties).ToList())
+            child.Remove();
+        foreach (var element in content)
+            paragraph.AppendChild(element);
+    }
+}
@@ -0,0 +1,225 @@
+using System.Xml;
+using System.Xml.Linq;
+using System.Xml.XPath;
+using DocumentFormat.OpenXml.Packaging;
+
+namespace DocxMcp.Helpers;
+
+/// <summary>
+/// A node matched by an XPath query over a package part.
+/// <see cref="Location"/> is an absolute XPath with positions (e.g. /w:document[1]/w:body[1]/w:p[3]).
+/// </summary>
+public sealed record XmlQueryMatch(string Type, string Location, string? Name, int Line, int Column, string Value, string? Id);
+
+/// <summary>
+/// Raw XPath queries over the XML parts of a document package. Parts are
+/// named by alias (document, styles, numbering, header2, ...) or by URI
+/// (/word/document.xml). The in-memory state is queried, including edits not
+/// saved yet.
+/// </summary>
+public static class XmlQueryHelper
+{
+    /// <summary>Part aliases and the parts they select.</summary>
+    private static readonly Dictionary<string, Func<MainDocumentPart, IEnumerable<OpenXmlPart?>>> Aliases =
+        new(StringComparer.OrdinalIgnoreCase)
+        {
+            ["document"] = m => [m],
+            ["styles"] = m => [m.StyleDefinitionsPart],
+            ["numbering"] = m => [m.NumberingDefinitionsPart],
+            ["settings"] = m => [m.DocumentSettingsPart],
+            ["comments"] = m => [m.WordprocessingCommentsPart],
+            ["footnotes"] = m => [m.FootnotesPart],
+            ["endnotes"] = m => [m.EndnotesPart],
+            ["fonts"] = m => [m.FontTablePart],
+            ["theme"] = m => [m.ThemePart],
+            ["header"] = m => m.HeaderParts,
+            ["footer"] = m => m.FooterParts,
+        };
+
+    /// <summary>Prefixes usable in every query, on top of those declared by the part.</summary>
+    private static readonly Dictionary<string, string> Prefixes = new()
+    {
+        ["w"] = "http://schemas.openxmlformats.org/wordprocessingml/2006/main",
+        ["r"] = "http://schemas.openxmlformats.org/officeDocument/2006/relationships",
+        ["wp"] = "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing",
+        ["a"] = "http://schemas.openxmlformats.org/drawingml/2006/main",
+        ["pic"] = "http://schemas.openxmlformats.org/drawingml/2006/picture",
+        ["m"] = "http://schemas.openxmlformats.org/officeDocument/2006/math",
+        ["v"] = "urn:schemas-microsoft-com:vml",
+        ["mc"] = "http://schemas.openxmlformats.org/markup-compatibility/2006",
+        ["w14"] = "http://schemas.microsoft.com/office/word/2010/wordml",
+        ["w15"] = "http://schemas.microsoft.com/office/word/2012/wordml",
+        ["cp"] = "http://schemas.openxmlformats.org/package/2006/metadata/core-properties",
+        ["dc"] = "http://purl.org/dc/elements/1.1/",
+        ["dcterms"] = "http://purl.org/dc/terms/",
+        [ElementIdManager.DmcpPrefix] = ElementIdManager.DmcpNamespace,
+    };
+
+    /// <summary>
+    /// Names of the XML parts that can be queried: aliases first, then URIs.
+    /// </summary>
+    public static List<string> ListParts(WordprocessingDocument doc)
+    {
+        var names = new List<string>();
+        if (doc.MainDocumentPart is { } main)
+        {
+            foreach (var (alias, select) in Aliases)
+            {
+                var parts = select(main).OfType<OpenXmlPart>().ToList();
+                if (parts.Count == 1 && alias is not ("header" or "footer"))
+                    names.Add(alias);
+                else
+                    names.AddRange(parts.Select((_, i) => $"{alias}{i + 1}"));
+            }
+        }
+        names.AddRange(AllParts(doc).Where(IsXml).Select(p => p.Uri.ToString()));
+        return names;
+    }
+
+    /// <summary>
+    /// Find a part by alias ("document", "header2", ...) or URI ("/word/styles.xml").
+    /// </summary>
+    public static OpenXmlPart ResolvePart(WordprocessingDocument doc, string part)
+    {
+        if (part.StartsWith('/'))
+        {
+            return AllParts(doc).FirstOrDefault(p =>
+                       string.Equals(p.Uri.ToString(), part, StringComparison.OrdinalIgnoreCase))
+                   ?? throw new ArgumentException($"No part '{part}' in the package.");
+        }
+
+        var main = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+
+        // "header2" -> alias "header", second part
+        var alias = part.TrimEnd("0123456789".ToCharArray());
+        var number = alias.Length < part.Length ? int.Parse(part[alias.Length..]) : 1;
+
+        if (!Aliases.TryGetValue(alias, out var select))
+            throw new ArgumentException(
+                $"Unknown part '{part}'. Use one of: {string.Join(", ", Aliases.Keys)} (with a number for headers and footers) or a part URI.");
+
+        return select(main).OfType<OpenXmlPart>().ElementAtOrDefault(number - 1)
+               ?? throw new ArgumentException($"The document has no {part} part.");
+    }
+
+    /// <summary>
+    /// Evaluate <paramref name="xpath"/> against a part. Node-set results are
+    /// returned as matches; number, string and boolean results as a scalar.
+    /// Element values are their outer XML, truncated to <paramref name="maxChars"/>.
+    /// </summary>
+    public static (List<XmlQueryMatch> Matches, object? Scalar) Query(OpenXmlPart part, string xpath, int maxChars)
+    {
+        var xdoc = Load(part);
+        var namespaces = new XmlNamespaceManager(new NameTable());
+        foreach (var (prefix, uri) in Prefixes)
+            namespaces.AddNamespace(prefix, uri);
+        foreach (var decl in xdoc.Root!.Attributes().Where(a => a.IsNamespaceDeclaration && a.Name.Namespace == XNamespace.Xmlns))
+        {
+            if (!Prefixes.ContainsKey(decl.Name.LocalName))
+                namespaces.AddNamespace(decl.Name.LocalName, decl.Value);
+        }
+
+        object result;
+        try
+        {
+            result = xdoc.XPathEvaluate(xpath, namespaces);
+        }
+        catch (XPathException ex)
+        {
+            throw new ArgumentException($"Invalid XPath — {ex.Message}");
+        }
+
+        if (result is not IEnumerable<object> nodes)
+            return ([], result);
+
+        var matches = new List<XmlQueryMatch>();
+        foreach (var node in nodes)
+        {
+            var lineInfo = (IXmlLineInfo)node;
+            var (line, column) = lineInfo.HasLineInfo() ? (lineInfo.LineNumber, lineInfo.LinePosition) : (0, 0);
+
+            matches.Add(node switch
+            {
+                XElement e => new XmlQueryMatch("element", Location(e, namespaces), QualifiedName(e.Name, e, namespaces),
+                    line, column, Truncate(e.ToString(SaveOptions.DisableFormatting), maxChars),
+                    e.Attribute(XName.Get("id", ElementIdManager.DmcpNamespace))?.Value),
+                XAttribute a => new XmlQueryMatch("attribute",
+                    a.Parent is null ? "@" + a.Name.LocalName : $"{Location(a.Parent, namespaces)}/@{QualifiedName(a.Name, a.Parent, namespaces)}",
+                    QualifiedName(a.Name, a.Parent, namespaces), line, column, a.Value, null),
+                XText t => new XmlQueryMatch("text", TextLocation(t, namespaces), null, line, column,
+                    Truncate(t.Value, maxChars), null),
+                XComment c => new XmlQueryMatch("comment", "", null, line, column, c.Value, null),
+                _ => new XmlQueryMatch(node.GetType().Name, "", null, line, column, node.ToString() ?? "", null)
+            });
+        }
+        return (matches, null);
+    }
+
+    private static XDocument Load(OpenXmlPart part)
+    {
+        // The DOM holds unsaved edits; parts without a typed root are read from the stream
+        string xml;
+        if (part.RootElement is { } root)
+        {
+            xml = root.OuterXml;
+        }
+        else
+        {
+            using var reader = new StreamReader(part.GetStream(FileMode.Open, FileAccess.Read));
+            xml = reader.ReadToEnd();
+        }
+
+        try
+        {
+            return XDocument.Parse(xml, LoadOptions.SetLineInfo);
+        }
+        catch (XmlException ex)
+        {
+            throw new ArgumentException($"Part {part.Uri} is not XML — {ex.Message}");
+        }
+    }
+
+    private static string Location(XElement element, IXmlNamespaceResolver namespaces)
+    {
+        var steps = element.AncestorsAndSelf().Reverse().Select(e =>
+        {
+            var position = e.Parent is null ? 1 : e.ElementsBeforeSelf(e.Name).Count() + 1;
+            return $"{QualifiedName(e.Name, e, namespaces)}[{position}]";
+        });
+        return "/" + string.Join("/", steps);
+    }
+
+    private static string TextLocation(XText text, IXmlNamespaceResolver namespaces)
+    {
+        if (text.Parent is null) return "text()";
+        var position = text.NodesBeforeSelf().OfType<XText>().Count() + 1;
+        return $"{Location(text.Parent, namespaces)}/text()[{position}]";
+    }
+
+    private static string QualifiedName(XName name, XElement? scope, IXmlNamespaceResolver namespaces)
+    {
+        if (name.Namespace == XNamespace.None) return name.LocalName;
+        var prefix = scope?.GetPrefixOfNamespace(name.Namespace) ?? namespaces.LookupPrefix(name.NamespaceName);
+        return string.IsNullOrEmpty(prefix) ? $"*[local-name()='{name.LocalName}']" : $"{prefix}:{name.LocalName}";
+    }
+
+    private static string Truncate(string value, int maxChars) =>
+        value.Length <= maxChars ? value : value[..maxChars] + "…";
+
+    private static bool IsXml(OpenXmlPart part) =>
+        part.ContentType.EndsWith("xml", StringComparison.OrdinalIgnoreCase);
+
+    private static IEnumerable<OpenXmlPart> AllParts(WordprocessingDocument doc)
+    {
+        var seen = new HashSet<OpenXmlPart>();
+        var pending = new Stack<OpenXmlPart>(doc.Parts.Select(p => p.OpenXmlPart));
+        while (pending.TryPop(out var part))
+        {
+            if (!seen.Add(part)) continue;
+            yield return part;
+            foreach (var child in part.Parts)
+                pending.Push(child.OpenXmlPart);
+        }
+    }
+}
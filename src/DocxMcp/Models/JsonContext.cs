@@ -22,6 +22,7 @@ namespace DocxMcp.Models;
 [JsonSerializable(typeof(ReplaceTextOperationResult))]
 [JsonSerializable(typeof(RemoveColumnOperationResult))]
 [JsonSerializable(typeof(UnknownOperationResult))]
+[JsonSerializable(typeof(StructureInfo))]
 [JsonSerializable(typeof(PatchOperation))]
 [JsonSerializable(typeof(List<PatchOperation>))]
 [JsonSerializable(typeof(AddPatchOperation))]
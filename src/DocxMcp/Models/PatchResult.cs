@@ -32,6 +32,10 @@ public sealed class PatchResult
     [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
     public string? Error { get; set; }
 
+    [JsonPropertyName("structure")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public StructureInfo? Structure { get; set; }
+
     public string ToJson()
     {
         return JsonSerializer.Serialize(this, DocxJsonContext.Default.PatchResult);
@@ -146,3 +150,55 @@ public sealed class UnknownOperationResult : PatchOperationResult
     [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
     public string? UnknownOp { get; set; }
 }
+
+/// <summary>
+/// Structural delta of a patch call and the outline after it, returned with
+/// include_structure so callers don't need a follow-up read.
+/// </summary>
+public sealed class StructureInfo
+{
+    [JsonPropertyName("added")]
+    public List<StructureRange> Added { get; set; } = [];
+
+    [JsonPropertyName("changed")]
+    public List<StructureRange> Changed { get; set; } = [];
+
+    [JsonPropertyName("removed")]
+    public List<string> Removed { get; set; } = [];
+
+    [JsonPropertyName("outline")]
+    public List<OutlineEntry> Outline { get; set; } = [];
+}
+
+/// <summary>A run of consecutive top-level body elements, by index after the patch.</summary>
+public sealed class StructureRange
+{
+    [JsonPropertyName("start")]
+    public int Start { get; set; }
+
+    [JsonPropertyName("end")]
+    public int End { get; set; }
+
+    [JsonPropertyName("ids")]
+    public List<string> Ids { get; set; } = [];
+}
+
+/// <summary>A heading of the outline.</summary>
+public sealed class OutlineEntry
+{
+    [JsonPropertyName("heading_index")]
+    public int HeadingIndex { get; set; }
+
+    [JsonPropertyName("level")]
+    public int Level { get; set; }
+
+    [JsonPropertyName("text")]
+    public string Text { get; set; } = "";
+
+    [JsonPropertyName("id")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public string? Id { get; set; }
+
+    [JsonPropertyName("body_index")]
+    public int BodyIndex { get; set; }
+}
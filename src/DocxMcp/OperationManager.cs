@@ -0,0 +1,173 @@
+using DocxMcp.Grpc;
+using Grpc.Core;
+using Microsoft.Extensions.Logging;
+
+namespace DocxMcp;
+
+/// <summary>
+/// Runs expensive tool calls in the background as long-running operations.
+/// The operation's state lives in the storage server's OperationService, so any
+/// client can poll, watch or cancel it by ID; this class runs the work and keeps
+/// that state up to date.
+/// </summary>
+public sealed class OperationManager
+{
+    private readonly IHistoryStorage _storage;
+    private readonly ILogger<OperationManager> _logger;
+
+    public OperationManager(IHistoryStorage storage, ILogger<OperationManager> logger)
+    {
+        _storage = storage;
+        _logger = logger;
+    }
+
+    /// <summary>
+    /// Register an operation and run <paramref name="work"/> in the background.
+    /// Returns as soon as the operation is registered. The work's return value
+    /// becomes the operation's result; an exception marks it failed.
+    /// </summary>
+    public async Task<OperationDto> StartAsync(
+        string tenantId, string kind, string description, string? sessionId,
+        Func<OperationContext, Task<string>> work)
+    {
+        var operation = await _storage.StartOperationAsync(tenantId, kind, description, sessionId);
+        var cts = new CancellationTokenSource();
+        var context = new OperationContext(_storage, tenantId, operation.Id, cts);
+
+        _ = WatchForCancellationAsync(tenantId, operation.Id, cts);
+        _ = Task.Run(async () =>
+        {
+            try
+            {
+                var result = await work(context);
+                cts.Token.ThrowIfCancellationRequested();
+                await _storage.FinishOperationAsync(tenantId, operation.Id, success: true, result: result);
+            }
+            catch (OperationCanceledException) when (cts.IsCancellationRequested)
+            {
+                _logger.LogInformation("Operation {OperationId} ({Kind}) was cancelled", operation.Id, kind);
+            }
+            catch (Exception ex)
+            {
+                _logger.LogWarning(ex, "Operation {OperationId} ({Kind}) failed", operation.Id, kind);
+                try
+                {
+                    await _storage.FinishOperationAsync(tenantId, operation.Id, success: false, error: ex.Message);
+                }
+                catch (Exception finishEx)
+                {
+                    _logger.LogError(finishEx, "Failed to record failure of operation {OperationId}", operation.Id);
+                }
+            }
+            finally
+            {
+                cts.Cancel();
+                cts.Dispose();
+            }
+        });
+
+        return operation;
+    }
+
+    public Task<OperationDto?> GetAsync(string tenantId, string operationId)
+        => _storage.GetOperationAsync(tenantId, operationId);
+
+    public Task<IReadOnlyList<OperationDto>> ListAsync(string tenantId, bool includeFinished)
+        => _storage.ListOperationsAsync(tenantId, includeFinished);
+
+    public Task<OperationDto> CancelAsync(string tenantId, string operationId)
+        => _storage.CancelOperationAsync(tenantId, operationId);
+
+    /// <summary>
+    /// Wait up to <paramref name="timeout"/> for an operation to finish.
+    /// Returns its latest state, or null if it is unknown.
+    /// </summary>
+    public async Task<OperationDto?> WaitAsync(string tenantId, string operationId, TimeSpan timeout)
+    {
+        var latest = await _storage.GetOperationAsync(tenantId, operationId);
+        if (latest is null || latest.IsFinished || timeout <= TimeSpan.Zero)
+            return latest;
+
+        using var cts = new CancellationTokenSource(timeout);
+        try
+        {
+            await foreach (var op in _storage.WatchOperationAsync(tenantId, operationId, cts.Token))
+                latest = op;
+        }
+        catch (Exception ex) when (ex is OperationCanceledException
+            || ex is RpcException { StatusCode: StatusCode.Cancelled or StatusCode.DeadlineExceeded })
+        {
+            // Timed out: return the latest state seen
+        }
+        return latest;
+    }
+
+    /// <summary>
+    /// Cancel the worker's token when a client cancels the operation.
+    /// Ends when the operation finishes or the worker completes.
+    /// </summary>
+    private async Task WatchForCancellationAsync(string tenantId, string operationId, CancellationTokenSource cts)
+    {
+        try
+        {
+            var token = cts.Token;
+            await foreach (var op in _storage.WatchOperationAsync(tenantId, operationId, token))
+            {
+                if (op.State == OperationState.Cancelled)
+                {
+                    cts.Cancel();
+                    return;
+                }
+            }
+        }
+        catch (ObjectDisposedException) { }
+        catch (OperationCanceledException) { }
+        catch (RpcException ex) when (ex.StatusCode == StatusCode.Cancelled) { }
+        catch (Exception ex)
+        {
+            // The worker still sees cancellation through its progress reports
+            _logger.LogDebug(ex, "Stopped watching operation {OperationId}", operationId);
+        }
+    }
+}
+
+/// <summary>
+/// Handle given to the work of a long-running operation.
+/// </summary>
+public sealed class OperationContext
+{
+    private readonly IHistoryStorage _storage;
+    private readonly string _tenantId;
+    private readonly CancellationTokenSource _cts;
+
+    internal OperationContext(IHistoryStorage storage, string tenantId, string operationId, CancellationTokenSource cts)
+    {
+        _storage = storage;
+        _tenantId = tenantId;
+        _cts = cts;
+        OperationId = operationId;
+        CancellationToken = cts.Token;
+    }
+
+    public string OperationId { get; }
+
+    /// <summary>
+    /// Cancelled when a client cancels the operation.
+    /// </summary>
+    public CancellationToken CancellationToken { get; }
+
+    /// <summary>
+    /// Report progress (0.0 - 1.0). Throws <see cref="OperationCanceledException"/>
+    /// if the operation was cancelled.
+    /// </summary>
+    public async Task ReportAsync(double progress, string? message = null)
+    {
+        CancellationToken.ThrowIfCancellationRequested();
+        var op = await _storage.ReportOperationProgressAsync(_tenantId, OperationId, progress, message);
+        if (op.State == OperationState.Cancelled)
+        {
+            _cts.Cancel();
+            CancellationToken.ThrowIfCancellationRequested();
+        }
+    }
+}
@@ -77,6 +77,22 @@ public static class PathResolver
         return (parents[0], childrenSeg.Index);
     }
 
+    /// <summary>
+    /// The insert path that appends to the end of the body. The body's final w:sectPr
+    /// must stay its last child, so the path points just before it when there is one.
+    /// </summary>
+    public static string BodyAppendPath(WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var index = body.ChildElements.Count;
+        if (body.LastChild is SectionProperties)
+            index--;
+
+        return $"/body/children/{index}";
+    }
+
     private static List<OpenXmlElement> ResolveHeaderFooter(
         HeaderFooterSegment seg, MainDocumentPart mainPart)
     {
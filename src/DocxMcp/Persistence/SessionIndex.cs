@@ -90,6 +90,18 @@ public sealed class SessionIndexEntry
     [JsonPropertyName("checkpoint_positions")]
     public List<int> CheckpointPositions { get; set; } = [];
 
+    [JsonPropertyName("display_name")]
+    public string? DisplayName { get; set; }
+
+    [JsonPropertyName("alias")]
+    public string? Alias { get; set; }
+
+    /// <summary>
+    /// When an ephemeral session expires and gets purged; null for sessions kept until closed.
+    /// </summary>
+    [JsonPropertyName("expires_at")]
+    public DateTime? ExpiresAt { get; set; }
+
     // Convenience property for code that uses WalPosition
     [JsonIgnore]
     public ulong WalPosition
@@ -48,6 +48,15 @@ public sealed class ExternalSyncMeta
 
 public sealed class WalEntry
 {
+    /// <summary>
+    /// Schema version written with new entries. The storage server validates entries
+    /// against this schema and upgrades older versions when reading them back.
+    /// </summary>
+    public const int CurrentVersion = 1;
+
+    /// <summary>Schema version of this entry.</summary>
+    public int Version { get; set; } = CurrentVersion;
+
     public string Patches { get; set; } = "";
     public DateTime Timestamp { get; set; }
     public string? Description { get; set; }
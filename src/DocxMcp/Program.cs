@@ -5,8 +5,11 @@ using Microsoft.Extensions.Hosting;
 using Microsoft.Extensions.Logging;
 using ModelContextProtocol.Server;
 using DocxMcp;
+using DocxMcp.Email;
 using DocxMcp.ExternalChanges;
 using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+using DocxMcp.Summaries;
 using DocxMcp.Tools;
 
 var transport = Environment.GetEnvironmentVariable("MCP_TRANSPORT") ?? "stdio";
@@ -22,10 +25,18 @@ if (transport == "http")
 
     RegisterStorageServices(builder.Services);
 
-    // Multi-tenant: pool of SessionManagers, one per tenant
+    // Multi-tenant: pool of SessionManagers, one per tenant.
+    // Write policy rules of the deployment are IWriteValidator services; the pool gives them to every tenant
     builder.Services.AddSingleton<SessionManagerPool>();
     builder.Services.AddSingleton<SyncManager>();
     builder.Services.AddSingleton<ExternalChangeGate>();
+    builder.Services.AddSingleton<OperationManager>();
+    builder.Services.AddSingleton(SummaryOptions.FromEnvironment());
+    builder.Services.AddSingleton(FeatureFlags.FromEnvironment());
+    builder.Services.AddSingleton(new UrlDocumentFetcher(UrlFetchOptions.FromEnvironment()));
+    builder.Services.AddSingleton(EmailOptions.FromEnvironment());
+    builder.Services.AddSingleton(ClassificationPolicy.FromEnvironment());
+    builder.Services.AddSingleton<EmailAuditLog>();
     builder.Services.AddHttpContextAccessor();
     builder.Services.AddScoped<TenantScope>();
 
@@ -35,20 +46,64 @@ if (transport == "http")
         .WithTools<DocumentTools>()
         .WithTools<QueryTool>()
         .WithTools<CountTool>()
+        .WithTools<ReaderTools>()
         .WithTools<ReadSectionTool>()
         .WithTools<ReadHeadingContentTool>()
         .WithTools<ElementTools>()
         .WithTools<TextTools>()
+        .WithTools<HyperlinkTools>()
+        .WithTools<FieldTools>()
         .WithTools<TableTools>()
         .WithTools<ExportTools>()
+        .WithTools<EmailTools>()
         .WithTools<HistoryTools>()
         .WithTools<CommentTools>()
+        .WithTools<ReviewTools>()
         .WithTools<StyleTools>()
         .WithTools<RevisionTools>()
         .WithTools<ExternalChangeTools>()
-        .WithTools<ConnectionTools>();
+        .WithTools<ConnectionTools>()
+        .WithTools<TemplateTools>()
+        .WithTools<SnippetTools>()
+        .WithTools<TerminologyTools>()
+        .WithTools<ProfileTools>()
+        .WithTools<SummaryTools>()
+        .WithTools<XliffTools>()
+        .WithTools<LanguageTools>()
+        .WithTools<TranscriptTools>()
+        .WithTools<PasteTools>()
+        .WithTools<ClauseTools>()
+        .WithTools<FeatureTools>()
+        .WithTools<TaskTools>()
+        .WithTools<AuthorityTools>()
+        .WithTools<CodeTools>()
+        .WithTools<SignatureTools>()
+        .WithTools<ClassificationTools>()
+        .WithTools<XmlQueryTool>()
+        .WithTools<CustomXmlTools>()
+        .WithTools<PageLayoutTool>()
+        .WithTools<HeadingTools>()
+        .WithTools<SortTools>()
+        .WithTools<AutofitTool>()
+        .WithTools<OptimizeTool>()
+        .WithTools<SimilarityTools>()
+        .WithTools<OperationTools>()
+        .WithTools<PipelineTools>();
 
     var app = builder.Build();
+
+    // Correlation ID from the proxy, passed on to the storage services and echoed back
+    app.Use(async (context, next) =>
+    {
+        var requestId = context.Request.Headers[CorrelationContext.RequestIdHeader].FirstOrDefault();
+        if (!string.IsNullOrEmpty(requestId))
+        {
+            CorrelationContext.RequestId = requestId;
+            context.Response.Headers[CorrelationContext.RequestIdHeader] = requestId;
+        }
+        await next();
+    });
+
     app.MapMcp("/mcp");
     app.Use(async (context, next) =>
     {
@@ -77,6 +132,14 @@ else
 
     builder.Services.AddSingleton<SyncManager>();
     builder.Services.AddSingleton<ExternalChangeGate>();
+    builder.Services.AddSingleton<OperationManager>();
+    builder.Services.AddSingleton(SummaryOptions.FromEnvironment());
+    builder.Services.AddSingleton(FeatureFlags.FromEnvironment());
+    builder.Services.AddSingleton(new UrlDocumentFetcher(UrlFetchOptions.FromEnvironment()));
+    builder.Services.AddSingleton(EmailOptions.FromEnvironment());
+    builder.Services.AddSingleton(ClassificationPolicy.FromEnvironment());
+    builder.Services.AddSingleton<EmailAuditLog>();
+    builder.Services.AddSingleton(sp => WritePolicy.FromEnvironment(sp.GetServices<IWriteValidator>()));
     builder.Services.AddSingleton<SessionManager>();
     builder.Services.AddScoped<TenantScope>();
 
@@ -86,18 +149,49 @@ else
         .WithTools<DocumentTools>()
         .WithTools<QueryTool>()
         .WithTools<CountTool>()
+        .WithTools<ReaderTools>()
         .WithTools<ReadSectionTool>()
         .WithTools<ReadHeadingContentTool>()
         .WithTools<ElementTools>()
         .WithTools<TextTools>()
+        .WithTools<HyperlinkTools>()
+        .WithTools<FieldTools>()
         .WithTools<TableTools>()
         .WithTools<ExportTools>()
+        .WithTools<EmailTools>()
         .WithTools<HistoryTools>()
         .WithTools<CommentTools>()
+        .WithTools<ReviewTools>()
         .WithTools<StyleTools>()
         .WithTools<RevisionTools>()
         .WithTools<ExternalChangeTools>()
-        .WithTools<ConnectionTools>();
+        .WithTools<ConnectionTools>()
+        .WithTools<TemplateTools>()
+        .WithTools<SnippetTools>()
+        .WithTools<TerminologyTools>()
+        .WithTools<ProfileTools>()
+        .WithTools<SummaryTools>()
+        .WithTools<XliffTools>()
+        .WithTools<LanguageTools>()
+        .WithTools<TranscriptTools>()
+        .WithTools<PasteTools>()
+        .WithTools<ClauseTools>()
+        .WithTools<FeatureTools>()
+        .WithTools<TaskTools>()
+        .WithTools<AuthorityTools>()
+        .WithTools<CodeTools>()
+        .WithTools<SignatureTools>()
+        .WithTools<ClassificationTools>()
+        .WithTools<XmlQueryTool>()
+        .WithTools<CustomXmlTools>()
+        .WithTools<PageLayoutTool>()
+        .WithTools<HeadingTools>()
+        .WithTools<SortTools>()
+        .WithTools<AutofitTool>()
+        .WithTools<OptimizeTool>()
+        .WithTools<SimilarityTools>()
+        .WithTools<OperationTools>()
+        .WithTools<PipelineTools>();
 
     await builder.Build().RunAsync();
 }
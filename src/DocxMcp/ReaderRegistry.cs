@@ -0,0 +1,103 @@
+using System.Collections.Concurrent;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+
+namespace DocxMcp;
+
+/// <summary>
+/// Position of a reader in a document: the ID of the next body element to read, and
+/// its index as a fallback when that element was removed since.
+/// </summary>
+public sealed class ReaderCursor(string id, string sessionId)
+{
+    public string Id { get; } = id;
+    public string SessionId { get; } = sessionId;
+    public string? NextElementId { get; set; }
+    public int NextIndex { get; set; }
+    public DateTime LastUsed { get; set; } = DateTime.UtcNow;
+}
+
+/// <summary>
+/// Server-side cursors letting an agent walk a large document a few elements or
+/// sections at a time (open_reader / read_next). Each tenant's SessionManager keeps its
+/// own readers, in memory; a restart forgets them. Cursors follow element IDs, so edits
+/// made between reads don't make a reader skip or repeat content.
+/// </summary>
+public sealed class ReaderRegistry
+{
+    /// <summary>Readers kept per tenant; opening more forgets the least recently used.</summary>
+    public const int MaxReaders = 100;
+
+    private readonly ConcurrentDictionary<string, ReaderCursor> _readers = new();
+
+    public ReaderCursor Open(string sessionId)
+    {
+        while (_readers.Count >= MaxReaders)
+        {
+            var oldest = _readers.Values.MinBy(r => r.LastUsed);
+            if (oldest is null) break;
+            _readers.TryRemove(oldest.Id, out _);
+        }
+
+        var reader = new ReaderCursor(Guid.NewGuid().ToString("N")[..12], sessionId);
+        _readers[reader.Id] = reader;
+        return reader;
+    }
+
+    public ReaderCursor Get(string readerId)
+    {
+        if (!_readers.TryGetValue(readerId, out var reader))
+            throw new KeyNotFoundException($"No reader with ID '{readerId}'. Open one with open_reader.");
+        reader.LastUsed = DateTime.UtcNow;
+        return reader;
+    }
+
+    public bool Close(string readerId) => _readers.TryRemove(readerId, out _);
+
+    /// <summary>Forget the readers of a closed session.</summary>
+    public void Forget(string sessionId)
+    {
+        foreach (var reader in _readers.Values.Where(r => r.SessionId == sessionId))
+            _readers.TryRemove(reader.Id, out _);
+    }
+
+    /// <summary>
+    /// Read the next <paramref name="count"/> units from the reader's position and move
+    /// past them. A "paragraph" unit is one body element (a table counts as one); a
+    /// "section" unit is a heading and the elements up to the next heading, or the
+    /// elements before the first heading. At most <paramref name="maxElements"/>
+    /// elements are returned; the cursor then stops mid-section.
+    /// </summary>
+    public static List<OpenXmlElement> ReadNext(ReaderCursor reader, Body body, string unit, int count, int maxElements)
+    {
+        var children = body.ChildElements.Where(e => e is not SectionProperties).ToList();
+        var index = Locate(reader, children);
+
+        var read = new List<OpenXmlElement>();
+        for (var units = 0; units < count && index < children.Count && read.Count < maxElements; units++)
+        {
+            read.Add(children[index++]);
+            if (unit != "section") continue;
+
+            while (index < children.Count && read.Count < maxElements
+                   && !(children[index] is Paragraph p && p.IsHeading()))
+                read.Add(children[index++]);
+        }
+
+        reader.NextIndex = index;
+        reader.NextElementId = index < children.Count ? ElementIdManager.GetId(children[index]) : null;
+        return read;
+    }
+
+    /// <summary>Index of the reader's next element among <paramref name="children"/>.</summary>
+    public static int Locate(ReaderCursor reader, List<OpenXmlElement> children)
+    {
+        if (reader.NextElementId is not null)
+        {
+            var found = children.FindIndex(e => ElementIdManager.GetId(e) == reader.NextElementId);
+            if (found >= 0) return found;
+        }
+        return Math.Min(reader.NextIndex, children.Count);
+    }
+}
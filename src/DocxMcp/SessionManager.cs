@@ -1,5 +1,6 @@
 using System.Text.Json;
 using DocxMcp.Grpc;
+using DocxMcp.Helpers;
 using DocxMcp.Persistence;
 using Microsoft.Extensions.Logging;
 using ModelContextProtocol;
@@ -21,6 +22,9 @@ public sealed class SessionManager
     private readonly ILogger<SessionManager> _logger;
     private readonly string _tenantId;
     private readonly int _compactThreshold;
+    private readonly TimeSpan? _sessionTtl;
+    private readonly EditGuard _editGuard;
+    private readonly WritePolicy _writePolicy;
 
     /// <summary>
     /// The tenant ID for this SessionManager instance.
@@ -28,20 +32,41 @@ public sealed class SessionManager
     /// </summary>
     public string TenantId => _tenantId;
 
+    /// <summary>
+    /// Reading cursors opened on this tenant's sessions (open_reader / read_next).
+    /// </summary>
+    public ReaderRegistry Readers { get; } = new();
+
     /// <summary>
     /// Create a SessionManager with the specified tenant ID.
     /// If tenantId is null, uses the current tenant from TenantContextHelper.
+    /// Edits are limited by <paramref name="editGuard"/>, or by the limits from the environment,
+    /// and checked against <paramref name="writePolicy"/>, or the built-in validators the
+    /// environment enables.
     /// </summary>
-    public SessionManager(IHistoryStorage history, ILogger<SessionManager> logger, string? tenantId = null)
+    public SessionManager(IHistoryStorage history, ILogger<SessionManager> logger, string? tenantId = null,
+        EditGuard? editGuard = null, WritePolicy? writePolicy = null)
     {
         _history = history;
         _logger = logger;
         _tenantId = tenantId ?? TenantContextHelper.CurrentTenantId;
+        _editGuard = editGuard ?? EditGuard.FromEnvironment();
+        _writePolicy = writePolicy ?? WritePolicy.FromEnvironment();
 
         var thresholdEnv = Environment.GetEnvironmentVariable("DOCX_WAL_COMPACT_THRESHOLD");
         _compactThreshold = int.TryParse(thresholdEnv, out var t) && t > 0 ? t : 50;
+
+        // Demo and playground deployments: new sessions are ephemeral and get
+        // purged by the storage server once their TTL runs out
+        var ttlEnv = Environment.GetEnvironmentVariable("DOCX_SESSION_TTL_SECONDS");
+        _sessionTtl = long.TryParse(ttlEnv, out var ttl) && ttl > 0 ? TimeSpan.FromSeconds(ttl) : null;
     }
 
+    /// <summary>
+    /// Expiry of sessions created now, or null when sessions are kept until closed.
+    /// </summary>
+    private DateTime? NewSessionExpiry() => DateTime.UtcNow + _sessionTtl;
+
     public DocxSession Open(string path)
     {
         var session = DocxSession.Open(path);
@@ -89,67 +114,104 @@ public sealed class SessionManager
     }
 
     /// <summary>
-    /// Load a session from gRPC checkpoint (stateless).
-    /// Fast path: exact checkpoint at cursor position (1 gRPC call).
-    /// Slow path: nearest checkpoint + WAL replay.
+    /// Create a new session from a template in the tenant's template library.
     /// The caller MUST dispose the returned session.
     /// </summary>
-    public DocxSession Get(string id)
+    public DocxSession CreateFromTemplate(string templateName)
     {
-        var walCount = GetWalEntryCountAsync(id).GetAwaiter().GetResult();
-        var cursor = LoadCursorPosition(id, walCount);
+        var id = Guid.NewGuid().ToString("N")[..12];
+        var found = _history.CreateSessionFromTemplateAsync(TenantId, templateName, id, NewSessionExpiry())
+            .GetAwaiter().GetResult();
+        if (!found)
+            throw new KeyNotFoundException($"No template named '{templateName}'.");
+        return Get(id);
+    }
 
-        // Try to load checkpoint at or before cursor position
-        var (ckptData, ckptPos, ckptFound) = _history.LoadCheckpointAsync(
-            TenantId, id, (ulong)cursor).GetAwaiter().GetResult();
+    /// <summary>
+    /// Save DOCX bytes as a named template, replacing any template with that name.
+    /// The bytes are normalized (element IDs assigned) so every session created
+    /// from the template starts from the same stable IDs.
+    /// </summary>
+    public void SaveTemplate(string name, byte[] data)
+    {
+        using var session = DocxSession.FromBytes(data, name, sourcePath: null);
+        _history.SaveLibraryItemAsync(TenantId, LibraryKind.Template, name, session.ToBytes())
+            .GetAwaiter().GetResult();
+    }
 
-        byte[] baseBytes;
-        int checkpointPosition;
+    public IReadOnlyList<LibraryItemInfoDto> ListLibraryItems(LibraryKind kind) =>
+        _history.ListLibraryItemsAsync(TenantId, kind).GetAwaiter().GetResult();
 
-        if (ckptFound && ckptData is not null && (int)ckptPos <= cursor)
-        {
-            baseBytes = ckptData;
-            checkpointPosition = (int)ckptPos;
-        }
-        else
-        {
-            // Fallback to baseline
-            var (baselineData, baselineFound) = _history.LoadSessionAsync(TenantId, id)
-                .GetAwaiter().GetResult();
-            if (!baselineFound || baselineData is null)
-                throw new KeyNotFoundException($"No document session with ID '{id}'.");
-            baseBytes = baselineData;
-            checkpointPosition = 0;
-        }
+    public bool DeleteLibraryItem(LibraryKind kind, string name) =>
+        _history.DeleteLibraryItemAsync(TenantId, kind, name).GetAwaiter().GetResult();
 
-        var sourcePath = LoadSourcePath(id);
-        var session = DocxSession.FromBytes(baseBytes, id, sourcePath);
+    /// <summary>
+    /// Save a snippet (a DOCX holding just the snippet content, with its styles and media).
+    /// </summary>
+    public void SaveSnippet(string name, byte[] data) =>
+        _history.SaveLibraryItemAsync(TenantId, LibraryKind.Snippet, name, data).GetAwaiter().GetResult();
 
-        // Replay WAL entries from checkpoint to cursor if needed
-        if (cursor > checkpointPosition)
-        {
-            var walEntries = ReadWalEntriesAsync(id).GetAwaiter().GetResult();
-            foreach (var patchJson in walEntries
-                .Skip(checkpointPosition)
-                .Take(cursor - checkpointPosition)
-                .Where(e => e.Patches is not null)
-                .Select(e => e.Patches!))
-            {
-                try { ReplayPatch(session, patchJson); }
-                catch (Exception ex)
-                {
-                    _logger.LogWarning(ex, "Failed to replay WAL entry for session {SessionId}.", id);
-                    break;
-                }
-            }
-        }
+    public byte[] LoadSnippet(string name) =>
+        LoadLibraryItem(LibraryKind.Snippet, name)
+            ?? throw new KeyNotFoundException($"No snippet named '{name}'.");
+
+    public void SaveGlossary(string name, string json) =>
+        _history.SaveLibraryItemAsync(TenantId, LibraryKind.Glossary, name, System.Text.Encoding.UTF8.GetBytes(json))
+            .GetAwaiter().GetResult();
 
+    public string LoadGlossary(string name)
+    {
+        var data = LoadLibraryItem(LibraryKind.Glossary, name)
+            ?? throw new KeyNotFoundException($"No glossary named '{name}'.");
+        return System.Text.Encoding.UTF8.GetString(data);
+    }
+
+    /// <summary>Name of the tenant's profile in its library; a tenant has one.</summary>
+    private const string ProfileName = "default";
+
+    /// <summary>
+    /// The tenant's profile (company details and defaults used as template variables);
+    /// empty when none was set.
+    /// </summary>
+    public TenantProfile LoadProfile()
+    {
+        var data = LoadLibraryItem(LibraryKind.Profile, ProfileName);
+        return data is null ? new TenantProfile() : TenantProfile.Parse(System.Text.Encoding.UTF8.GetString(data));
+    }
+
+    public void SaveProfile(TenantProfile profile) =>
+        _history.SaveLibraryItemAsync(TenantId, LibraryKind.Profile, ProfileName,
+                System.Text.Encoding.UTF8.GetBytes(profile.ToJson().ToJsonString()))
+            .GetAwaiter().GetResult();
+
+    public bool DeleteProfile() => DeleteLibraryItem(LibraryKind.Profile, ProfileName);
+
+    private byte[]? LoadLibraryItem(LibraryKind kind, string name)
+    {
+        var (data, found) = _history.LoadLibraryItemAsync(TenantId, kind, name).GetAwaiter().GetResult();
+        return found ? data : null;
+    }
+
+    /// <summary>
+    /// Load a session from gRPC checkpoint (stateless).
+    /// One gRPC call returns the nearest checkpoint at or before the cursor (or the
+    /// baseline) and the WAL entries to replay on it.
+    /// The caller MUST dispose the returned session.
+    /// </summary>
+    public DocxSession Get(string id)
+    {
+        var history = _history.LoadSessionWithHistoryAsync(TenantId, id).GetAwaiter().GetResult()
+            ?? throw new KeyNotFoundException($"No document session with ID '{id}'.");
+        _editGuard.Observe(id, history.Document.Length);
+        var session = OpenFromHistory(id, history);
+        _writePolicy.Observe(id, session.Document);
         return session;
     }
 
     /// <summary>
-    /// Resolve a session by ID or file path.
+    /// Resolve a session by ID, alias or file path.
     /// - If the input matches a session ID in the index, loads that session.
+    /// - If the input matches a session alias (case-insensitive), loads that session.
     /// - If the input is a file path, checks the index for a session with that source_path.
     /// - If no existing session found and file exists, auto-opens a new session.
     /// </summary>
@@ -160,6 +222,12 @@ public sealed class SessionManager
         if (exists)
             return Get(idOrPath);
 
+        // Then as an alias
+        var (aliasFound, aliasId, _, _) = _history.ResolveSessionAsync(TenantId, idOrPath)
+            .GetAwaiter().GetResult();
+        if (aliasFound)
+            return Get(aliasId);
+
         // Check if it looks like a file path
         var isLikelyPath = idOrPath.Contains(Path.DirectorySeparatorChar)
             || idOrPath.Contains(Path.AltDirectorySeparatorChar)
@@ -210,6 +278,29 @@ public sealed class SessionManager
             .GetAwaiter().GetResult();
     }
 
+    /// <summary>
+    /// Map a session alias to its session ID. Returns the input unchanged when it
+    /// is already an ID or matches no alias, so callers report the usual not-found error.
+    /// </summary>
+    public string ResolveId(string idOrAlias)
+    {
+        var (found, sessionId, _, _) = _history.ResolveSessionAsync(TenantId, idOrAlias)
+            .GetAwaiter().GetResult();
+        return found ? sessionId : idOrAlias;
+    }
+
+    /// <summary>
+    /// Set a session's display name and/or alias. Null leaves a field unchanged,
+    /// an empty string clears it.
+    /// </summary>
+    public void Rename(string id, string? displayName, string? alias)
+    {
+        var (_, notFound) = _history.RenameSessionAsync(TenantId, id, displayName, alias)
+            .GetAwaiter().GetResult();
+        if (notFound)
+            throw new KeyNotFoundException($"No document session with ID '{id}'.");
+    }
+
     public void Close(string id)
     {
         // Verify session exists in the index before deleting
@@ -219,23 +310,34 @@ public sealed class SessionManager
 
         _history.DeleteSessionAsync(TenantId, id).GetAwaiter().GetResult();
         _history.RemoveSessionFromIndexAsync(TenantId, id).GetAwaiter().GetResult();
+        _editGuard.Forget(id);
+        _writePolicy.Forget(id);
+        Readers.Forget(id);
     }
 
     public IReadOnlyList<(string Id, string? Path)> List()
+    {
+        return ListEntries()
+            .Select(e => (e.Id, (string?)e.SourcePath))
+            .ToList()
+            .AsReadOnly();
+    }
+
+    /// <summary>
+    /// All session index entries, including display names and aliases.
+    /// </summary>
+    public IReadOnlyList<SessionIndexEntry> ListEntries()
     {
         var (indexData, found) = _history.LoadIndexAsync(TenantId).GetAwaiter().GetResult();
         if (!found || indexData is null)
-            return Array.Empty<(string, string?)>();
+            return Array.Empty<SessionIndexEntry>();
 
         var json = System.Text.Encoding.UTF8.GetString(indexData);
         var index = JsonSerializer.Deserialize(json, SessionJsonContext.Default.SessionIndex);
         if (index is null)
-            return Array.Empty<(string, string?)>();
+            return Array.Empty<SessionIndexEntry>();
 
-        return index.Sessions
-            .Select(e => (e.Id, (string?)e.SourcePath))
-            .ToList()
-            .AsReadOnly();
+        return index.Sessions.AsReadOnly();
     }
 
     // --- WAL operations ---
@@ -243,11 +345,17 @@ public sealed class SessionManager
     /// <summary>
     /// Append a patch to the WAL after a successful mutation.
     /// If the cursor is behind the WAL tip (after undo), truncates future entries first.
-    /// Always saves a checkpoint at the new position for stateless Get().
+    /// Saves a checkpoint at the new position when the storage server's checkpoint policy
+    /// asks for one (after every entry unless the server is configured otherwise).
     /// Does NOT auto-save — caller is responsible for orchestrating sync.
     /// </summary>
     public void AppendWal(string id, string patchesJson, string? description, byte[] currentBytes)
     {
+        // Refused edits are never logged, so the next Get() doesn't see them.
+        // Policy first: an edit it refuses doesn't count against the edit limits
+        _writePolicy.Check(id, currentBytes);
+        _editGuard.Admit(id, currentBytes.Length);
+
         try
         {
             var walCount = GetWalEntryCountAsync(id).GetAwaiter().GetResult();
@@ -278,12 +386,18 @@ public sealed class SessionManager
                 Description = description
             };
 
-            AppendWalEntryAsync(id, walEntry).GetAwaiter().GetResult();
+            var appended = AppendWalEntryAsync(id, walEntry).GetAwaiter().GetResult();
             var newCursor = cursor + 1;
 
-            // Always save checkpoint at the new position (stateless pattern)
-            _history.SaveCheckpointAsync(TenantId, id, (ulong)newCursor, currentBytes)
-                .GetAwaiter().GetResult();
+            // Checkpoint when the server's policy says so; Get() replays from the previous one otherwise
+            if (appended.CheckpointDue)
+            {
+                _history.SaveCheckpointAsync(TenantId, id, (ulong)newCursor, currentBytes)
+                    .GetAwaiter().GetResult();
+                if (appended.CheckpointReason is not null)
+                    _logger.LogDebug("Checkpoint at {Position} for session {SessionId}: {Reason}.",
+                        newCursor, id, appended.CheckpointReason);
+            }
 
             // Update index with new WAL position, cursor, and checkpoint
             var newWalCount = GetWalEntryCountAsync(id).GetAwaiter().GetResult();
@@ -292,7 +406,8 @@ public sealed class SessionManager
                 modifiedAtUnix: now,
                 walPosition: (ulong)newWalCount,
                 cursorPosition: (ulong)newCursor,
-                addCheckpointPositions: new[] { (ulong)newCursor }).GetAwaiter().GetResult();
+                addCheckpointPositions: appended.CheckpointDue ? new[] { (ulong)newCursor } : null)
+                .GetAwaiter().GetResult();
 
             // Check if compaction is needed
             if ((ulong)newWalCount >= (ulong)_compactThreshold)
@@ -509,6 +624,32 @@ public sealed class SessionManager
         };
     }
 
+    /// <summary>
+    /// Rebuild the document as it was at a history position, without moving the
+    /// cursor or saving a checkpoint. The caller disposes the returned session.
+    /// </summary>
+    public DocxSession GetAtPosition(string id, int position)
+    {
+        var walCount = GetWalEntryCountAsync(id).GetAwaiter().GetResult();
+        if (position < 0 || position > walCount)
+            throw new ArgumentException($"Position {position} is outside the history (0-{walCount}).");
+
+        return RebuildDocumentAtPositionAsync(id, position).GetAwaiter().GetResult();
+    }
+
+    /// <summary>
+    /// The WAL entries taking the document from <paramref name="fromPosition"/> to
+    /// <paramref name="toPosition"/> (positions fromPosition + 1 through toPosition).
+    /// </summary>
+    public IReadOnlyList<WalEntry> GetWalEntries(string id, int fromPosition, int toPosition)
+    {
+        var walEntries = ReadWalEntriesAsync(id).GetAwaiter().GetResult();
+        if (fromPosition < 0 || toPosition > walEntries.Count || fromPosition > toPosition)
+            throw new ArgumentException(
+                $"Positions {fromPosition}-{toPosition} are outside the history (0-{walEntries.Count}).");
+        return walEntries.GetRange(fromPosition, toPosition - fromPosition);
+    }
+
     public string? GetLastExternalSyncHash(string id)
     {
         try
@@ -640,32 +781,14 @@ public sealed class SessionManager
         return walCount;
     }
 
-    /// <summary>
-    /// Load source_path from the gRPC index for a session.
-    /// </summary>
-    private string? LoadSourcePath(string sessionId)
+    private async Task<List<WalEntry>> ReadWalEntriesAsync(string sessionId)
     {
-        try
-        {
-            var (indexData, found) = _history.LoadIndexAsync(TenantId).GetAwaiter().GetResult();
-            if (found && indexData is not null)
-            {
-                var json = System.Text.Encoding.UTF8.GetString(indexData);
-                var index = JsonSerializer.Deserialize(json, SessionJsonContext.Default.SessionIndex);
-                if (index is not null && index.TryGetValue(sessionId, out var entry))
-                    return entry!.SourcePath;
-            }
-        }
-        catch (Exception ex)
-        {
-            _logger.LogDebug(ex, "Failed to load source path for session {SessionId}.", sessionId);
-        }
-        return null;
+        var (grpcEntries, _) = await _history.ReadWalAsync(TenantId, sessionId);
+        return ParseWalEntries(sessionId, grpcEntries);
     }
 
-    private async Task<List<WalEntry>> ReadWalEntriesAsync(string sessionId)
+    private List<WalEntry> ParseWalEntries(string sessionId, IEnumerable<GrpcWalEntry> grpcEntries)
     {
-        var (grpcEntries, _) = await _history.ReadWalAsync(TenantId, sessionId);
         var entries = new List<WalEntry>();
 
         foreach (var grpcEntry in grpcEntries)
@@ -692,7 +815,7 @@ public sealed class SessionManager
         return entries;
     }
 
-    private async Task AppendWalEntryAsync(string sessionId, WalEntry entry)
+    private async Task<AppendWalResultDto> AppendWalEntryAsync(string sessionId, WalEntry entry)
     {
         var json = JsonSerializer.Serialize(entry, WalJsonContext.Default.WalEntry);
         var jsonBytes = System.Text.Encoding.UTF8.GetBytes(json);
@@ -706,7 +829,7 @@ public sealed class SessionManager
             Timestamp: entry.Timestamp
         );
 
-        await _history.AppendWalAsync(TenantId, sessionId, new[] { grpcEntry });
+        return await _history.AppendWalAsync(TenantId, sessionId, new[] { grpcEntry });
     }
 
     private async Task TruncateWalAtAsync(string sessionId, int keepCount)
@@ -720,6 +843,8 @@ public sealed class SessionManager
     {
         var bytes = session.ToBytes();
         await _history.SaveSessionAsync(TenantId, session.Id, bytes);
+        _editGuard.Observe(session.Id, bytes.Length);
+        _writePolicy.Observe(session.Id, session.Document);
 
         var now = DateTime.UtcNow;
         await _history.AddSessionToIndexAsync(TenantId, session.Id,
@@ -728,7 +853,8 @@ public sealed class SessionManager
                 now,
                 now,
                 0,
-                Array.Empty<ulong>()));
+                Array.Empty<ulong>(),
+                NewSessionExpiry()));
     }
 
     /// <summary>
@@ -754,51 +880,28 @@ public sealed class SessionManager
 
     private async Task<DocxSession> RebuildDocumentAtPositionAsync(string id, int targetPosition)
     {
-        // Try to load checkpoint
-        var (ckptData, ckptPos, ckptFound) = await _history.LoadCheckpointAsync(
-            TenantId, id, (ulong)targetPosition);
-
-        byte[] baseBytes;
-        int checkpointPosition;
-
-        if (ckptFound && ckptData is not null && (int)ckptPos <= targetPosition)
-        {
-            baseBytes = ckptData;
-            checkpointPosition = (int)ckptPos;
-        }
-        else
-        {
-            // Fallback to baseline
-            var (baselineData, _) = await _history.LoadSessionAsync(TenantId, id);
-            baseBytes = baselineData ?? throw new InvalidOperationException($"No baseline found for session {id}");
-            checkpointPosition = 0;
-        }
+        var history = await _history.LoadSessionWithHistoryAsync(TenantId, id, (ulong)targetPosition)
+            ?? throw new InvalidOperationException($"No baseline found for session {id}");
+        return OpenFromHistory(id, history);
+    }
 
-        var sourcePath = LoadSourcePath(id);
-        var session = DocxSession.FromBytes(baseBytes, id, sourcePath);
+    /// <summary>
+    /// Open a session from the document loaded with its history, replaying the WAL
+    /// entries up to the target position.
+    /// </summary>
+    private DocxSession OpenFromHistory(string id, SessionHistoryDto history)
+    {
+        var session = DocxSession.FromBytes(history.Document, id, history.SourcePath);
 
-        // Replay patches from checkpoint to target
-        if (targetPosition > checkpointPosition)
+        foreach (var patchJson in ParseWalEntries(id, history.Entries)
+            .Where(e => e.Patches is not null)
+            .Select(e => e.Patches!))
         {
-            var walEntries = await ReadWalEntriesAsync(id);
-            var patchesToReplay = walEntries
-                .Skip(checkpointPosition)
-                .Take(targetPosition - checkpointPosition)
-                .Where(e => e.Patches is not null)
-                .Select(e => e.Patches!)
-                .ToList();
-
-            foreach (var patchJson in patchesToReplay)
+            try { ReplayPatch(session, patchJson); }
+            catch (Exception ex)
             {
-                try
-                {
-                    ReplayPatch(session, patchJson);
-                }
-                catch (Exception ex)
-                {
-                    _logger.LogWarning(ex, "Failed to replay WAL entry during rebuild for session {SessionId}.", id);
-                    break;
-                }
+                _logger.LogWarning(ex, "Failed to replay WAL entry for session {SessionId}.", id);
+                break;
             }
         }
 
@@ -915,6 +1018,81 @@ public sealed class SessionManager
                 case "track_changes_enable":
                     Tools.RevisionTools.ReplayTrackChangesEnable(patch, wpDoc);
                     break;
+                case "insert_snippet":
+                    Tools.SnippetTools.ReplayInsertSnippet(patch, wpDoc);
+                    break;
+                case "set_summary":
+                    Tools.SummaryTools.ReplaySetSummary(patch, wpDoc);
+                    break;
+                case "set_languages":
+                    Tools.LanguageTools.ReplaySetLanguages(patch, wpDoc);
+                    break;
+                case "import_xliff":
+                    Tools.XliffTools.ReplayImportXliff(patch, wpDoc);
+                    break;
+                case "append_transcript":
+                    Tools.TranscriptTools.ReplayAppendTranscript(patch, wpDoc);
+                    break;
+                case "paste_html":
+                    Tools.PasteTools.ReplayPasteHtml(patch, wpDoc);
+                    break;
+                case "add_hyperlink":
+                    Tools.HyperlinkTools.ReplayAddHyperlink(patch, wpDoc);
+                    break;
+                case "insert_field":
+                    Tools.FieldTools.ReplayInsertField(patch, wpDoc);
+                    break;
+                case "mark_citation":
+                    Tools.AuthorityTools.ReplayMarkCitation(patch, wpDoc);
+                    break;
+                case "insert_table_of_authorities":
+                    Tools.AuthorityTools.ReplayInsertTableOfAuthorities(patch, wpDoc);
+                    break;
+                case "add_code_block":
+                    Tools.CodeTools.ReplayAddCodeBlock(patch, wpDoc);
+                    break;
+                case "add_signature_block":
+                    Tools.SignatureTools.ReplayAddSignatureBlock(patch, wpDoc);
+                    break;
+                case "add_custom_xml_part":
+                    Tools.CustomXmlTools.ReplayAddCustomXmlPart(patch, wpDoc);
+                    break;
+                case "bind_control_to_xpath":
+                    Tools.CustomXmlTools.ReplayBindControlToXpath(patch, wpDoc);
+                    break;
+                case "set_heading_levels":
+                    Tools.HeadingTools.ReplaySetHeadingLevels(patch, wpDoc);
+                    break;
+                case "apply_inferred_headings":
+                    Tools.HeadingTools.ReplayApplyInferredHeadings(patch, wpDoc);
+                    break;
+                case "rename_heading":
+                    Tools.HeadingTools.ReplayRenameHeading(patch, wpDoc);
+                    break;
+                case "move_section":
+                    Tools.HeadingTools.ReplayMoveSection(patch, wpDoc);
+                    break;
+                case "add_heading_bookmarks":
+                    Tools.HeadingTools.ReplayAddHeadingBookmarks(patch, wpDoc);
+                    break;
+                case "sort_table":
+                    Tools.SortTools.ReplaySortTable(patch, wpDoc);
+                    break;
+                case "sort_list":
+                    Tools.SortTools.ReplaySortList(patch, wpDoc);
+                    break;
+                case "autofit_table":
+                    Tools.AutofitTool.ReplayAutofitTable(patch, wpDoc);
+                    break;
+                case "optimize_document":
+                    Tools.OptimizeTool.ReplayOptimizeDocument(patch, wpDoc);
+                    break;
+                case "set_classification":
+                    Tools.ClassificationTools.ReplaySetClassification(patch, wpDoc);
+                    break;
+                case "import_revision":
+                    Tools.ReviewTools.ReplayImportRevision(patch, wpDoc);
+                    break;
             }
         }
     }
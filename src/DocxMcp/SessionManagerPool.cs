@@ -15,11 +15,18 @@ public sealed class SessionManagerPool
     private readonly ConcurrentDictionary<string, SemaphoreSlim> _locks = new();
     private readonly IHistoryStorage _history;
     private readonly ILoggerFactory _loggerFactory;
+    private readonly IReadOnlyList<IWriteValidator> _validators;
 
-    public SessionManagerPool(IHistoryStorage history, ILoggerFactory loggerFactory)
+    /// <summary>
+    /// Every tenant's edits are checked by the built-in validators the environment enables
+    /// and by <paramref name="validators"/>, the ones registered by the deployment.
+    /// </summary>
+    public SessionManagerPool(IHistoryStorage history, ILoggerFactory loggerFactory,
+        IEnumerable<IWriteValidator>? validators = null)
     {
         _history = history;
         _loggerFactory = loggerFactory;
+        _validators = validators?.ToList() ?? [];
     }
 
     public SessionManager GetForTenant(string tenantId)
@@ -36,7 +43,8 @@ public sealed class SessionManagerPool
             if (_pool.TryGetValue(tenantId, out existing))
                 return existing;
 
-            var sm = new SessionManager(_history, _loggerFactory.CreateLogger<SessionManager>(), tenantId);
+            var sm = new SessionManager(_history, _loggerFactory.CreateLogger<SessionManager>(), tenantId,
+                writePolicy: WritePolicy.FromEnvironment(_validators));
             _pool[tenantId] = sm;
             return sm;
         }
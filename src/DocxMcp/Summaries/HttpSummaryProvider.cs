@@ -0,0 +1,61 @@
+using System.Net.Http.Headers;
+using System.Text;
+using System.Text.Json.Nodes;
+
+namespace DocxMcp.Summaries;
+
+/// <summary>
+/// Summaries from an HTTP endpoint. The endpoint receives
+/// <c>{"chunks": [...], "max_words": N, "language": ..., "instructions": ...}</c>
+/// as a JSON POST and must answer <c>{"summary": "..."}</c>.
+/// </summary>
+public sealed class HttpSummaryProvider : ISummaryProvider
+{
+    private static readonly HttpClient SharedClient = new();
+
+    private readonly SummaryOptions _options;
+    private readonly HttpClient _http;
+
+    public HttpSummaryProvider(SummaryOptions options, HttpClient? http = null)
+    {
+        _options = options;
+        _http = http ?? SharedClient;
+    }
+
+    public string Name => "http";
+
+    public async Task<string> SummarizeAsync(SummaryRequest request, CancellationToken cancellationToken = default)
+    {
+        var payload = new JsonObject
+        {
+            ["chunks"] = new JsonArray(request.Chunks.Select(c => (JsonNode)JsonValue.Create(c)!).ToArray()),
+            ["max_words"] = request.MaxWords,
+            ["language"] = request.Language,
+            ["instructions"] = request.Instructions
+        };
+
+        using var message = new HttpRequestMessage(HttpMethod.Post, _options.Endpoint)
+        {
+            Content = new StringContent(payload.ToJsonString(), Encoding.UTF8, "application/json")
+        };
+        if (_options.ApiKey is not null)
+            message.Headers.Authorization = new AuthenticationHeaderValue("Bearer", _options.ApiKey);
+
+        using var timeout = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        timeout.CancelAfter(_options.Timeout);
+
+        using var response = await _http.SendAsync(message, timeout.Token);
+        var body = await response.Content.ReadAsStringAsync(timeout.Token);
+        if (!response.IsSuccessStatusCode)
+            throw new InvalidOperationException(
+                $"Summary endpoint returned {(int)response.StatusCode}: {Truncate(body, 200)}");
+
+        var summary = JsonNode.Parse(body)?["summary"]?.GetValue<string>();
+        if (string.IsNullOrWhiteSpace(summary))
+            throw new InvalidOperationException("Summary endpoint response has no 'summary' field.");
+        return summary.Trim();
+    }
+
+    private static string Truncate(string s, int maxLen) =>
+        s.Length <= maxLen ? s : s[..maxLen] + "...";
+}
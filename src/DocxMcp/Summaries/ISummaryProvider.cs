@@ -0,0 +1,22 @@
+namespace DocxMcp.Summaries;
+
+/// <summary>
+/// What to summarize and how. Chunks are consecutive pieces of the document's
+/// text, each small enough to send to a model on its own.
+/// </summary>
+public sealed record SummaryRequest(
+    IReadOnlyList<string> Chunks,
+    int MaxWords,
+    string? Language,
+    string? Instructions);
+
+/// <summary>
+/// Extension point for summary generation: turns document chunks into a summary.
+/// </summary>
+public interface ISummaryProvider
+{
+    /// <summary>Short name reported to callers (e.g. "http", "sampling").</summary>
+    string Name { get; }
+
+    Task<string> SummarizeAsync(SummaryRequest request, CancellationToken cancellationToken = default);
+}
@@ -0,0 +1,69 @@
+using Microsoft.Extensions.AI;
+using ModelContextProtocol.Server;
+
+namespace DocxMcp.Summaries;
+
+/// <summary>
+/// Summaries from the MCP client's model, through sampling. Each chunk is
+/// summarized on its own, then the partial summaries are combined.
+/// </summary>
+public sealed class SamplingSummaryProvider : ISummaryProvider
+{
+    private readonly McpServer _server;
+
+    public SamplingSummaryProvider(McpServer server)
+    {
+        _server = server;
+    }
+
+    public string Name => "sampling";
+
+    /// <summary>True when the connected client accepts sampling requests.</summary>
+    public static bool IsSupported(McpServer server) => server.ClientCapabilities?.Sampling is not null;
+
+    public async Task<string> SummarizeAsync(SummaryRequest request, CancellationToken cancellationToken = default)
+    {
+        var client = _server.AsSamplingChatClient();
+
+        if (request.Chunks.Count == 1)
+            return await SampleAsync(client, Prompt(request, "the following document"), request.Chunks[0],
+                request.MaxWords, cancellationToken);
+
+        var partials = new List<string>();
+        for (int i = 0; i < request.Chunks.Count; i++)
+        {
+            partials.Add(await SampleAsync(client,
+                Prompt(request, $"part {i + 1} of {request.Chunks.Count} of a document"),
+                request.Chunks[i], request.MaxWords, cancellationToken));
+        }
+
+        return await SampleAsync(client,
+            Prompt(request, "the document whose part summaries follow") + " Combine them into a single summary.",
+            string.Join("\n\n", partials), request.MaxWords, cancellationToken);
+    }
+
+    private static string Prompt(SummaryRequest request, string what)
+    {
+        var prompt = $"Summarize {what} in at most {request.MaxWords} words. Reply with the summary only.";
+        if (request.Language is not null)
+            prompt += $" Write the summary in {request.Language}.";
+        if (request.Instructions is not null)
+            prompt += " " + request.Instructions;
+        return prompt;
+    }
+
+    private static async Task<string> SampleAsync(
+        IChatClient client, string prompt, string text, int maxWords, CancellationToken cancellationToken)
+    {
+        var response = await client.GetResponseAsync(
+            [new ChatMessage(ChatRole.System, prompt), new ChatMessage(ChatRole.User, text)],
+            // Roughly 2 tokens per word leaves room for longer words
+            new ChatOptions { MaxOutputTokens = Math.Max(256, maxWords * 2) },
+            cancellationToken);
+
+        var summary = response.Text.Trim();
+        if (summary.Length == 0)
+            throw new InvalidOperationException("The client returned an empty summary.");
+        return summary;
+    }
+}
@@ -0,0 +1,48 @@
+namespace DocxMcp.Summaries;
+
+/// <summary>
+/// Configuration for generate_summary.
+/// </summary>
+public sealed class SummaryOptions
+{
+    /// <summary>
+    /// HTTP endpoint that produces summaries. When unset, summaries are requested
+    /// from the MCP client through sampling.
+    /// </summary>
+    public string? Endpoint { get; set; }
+
+    /// <summary>Bearer token sent to the endpoint, if any.</summary>
+    public string? ApiKey { get; set; }
+
+    /// <summary>Maximum characters per document chunk.</summary>
+    public int ChunkChars { get; set; } = 8000;
+
+    public TimeSpan Timeout { get; set; } = TimeSpan.FromSeconds(120);
+
+    /// <summary>
+    /// Read options from SUMMARY_ENDPOINT, SUMMARY_API_KEY, SUMMARY_CHUNK_CHARS
+    /// and SUMMARY_TIMEOUT_SECONDS.
+    /// </summary>
+    public static SummaryOptions FromEnvironment()
+    {
+        var options = new SummaryOptions();
+
+        var endpoint = Environment.GetEnvironmentVariable("SUMMARY_ENDPOINT");
+        if (!string.IsNullOrEmpty(endpoint))
+            options.Endpoint = endpoint;
+
+        var apiKey = Environment.GetEnvironmentVariable("SUMMARY_API_KEY");
+        if (!string.IsNullOrEmpty(apiKey))
+            options.ApiKey = apiKey;
+
+        if (int.TryParse(Environment.GetEnvironmentVariable("SUMMARY_CHUNK_CHARS"), out var chunkChars)
+            && chunkChars > 0)
+            options.ChunkChars = chunkChars;
+
+        if (int.TryParse(Environment.GetEnvironmentVariable("SUMMARY_TIMEOUT_SECONDS"), out var timeout)
+            && timeout > 0)
+            options.Timeout = TimeSpan.FromSeconds(timeout);
+
+        return options;
+    }
+}
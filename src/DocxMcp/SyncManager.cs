@@ -166,17 +166,25 @@ public sealed class SyncManager
 
     /// <summary>
     /// Save session data to its registered source.
+    /// Returns false if the source was unreachable and the save was queued for retry.
     /// </summary>
-    public void Save(string tenantId, string sessionId, byte[] data)
+    public bool Save(string tenantId, string sessionId, byte[] data, CancellationToken cancellationToken = default)
     {
-        var status = _sync.GetSyncStatusAsync(tenantId, sessionId).GetAwaiter().GetResult();
+        var status = _sync.GetSyncStatusAsync(tenantId, sessionId, cancellationToken).GetAwaiter().GetResult();
         if (status is null)
         {
             throw new InvalidOperationException(
                 $"No save target registered for session '{sessionId}'. Use document_set_source to set a path first.");
         }
 
-        var (success, error, _) = _sync.SyncToSourceAsync(tenantId, sessionId, data).GetAwaiter().GetResult();
+        var (success, error, _, queued) = _sync.SyncToSourceAsync(tenantId, sessionId, data, cancellationToken)
+            .GetAwaiter().GetResult();
+
+        if (!success && queued)
+        {
+            _logger.LogWarning("Save of session {SessionId} queued for retry: {Error}", sessionId, error);
+            return false;
+        }
 
         if (!success)
         {
@@ -184,6 +192,7 @@ public sealed class SyncManager
         }
 
         _logger.LogDebug("Saved session {SessionId} to {Path}.", sessionId, status.Path);
+        return true;
     }
 
     /// <summary>
@@ -201,7 +210,13 @@ public sealed class SyncManager
             if (status is null || !status.AutoSyncEnabled)
                 return false;
 
-            var (success, error, syncedAt) = _sync.SyncToSourceAsync(tenantId, sessionId, data).GetAwaiter().GetResult();
+            var (success, error, syncedAt, queued) = _sync.SyncToSourceAsync(tenantId, sessionId, data).GetAwaiter().GetResult();
+
+            if (!success && queued)
+            {
+                _logger.LogInformation("Auto-save of session {SessionId} queued for retry: {Error}", sessionId, error);
+                return false;
+            }
 
             if (!success)
             {
@@ -252,18 +267,61 @@ public sealed class SyncManager
     /// List files in a connection folder.
     /// </summary>
     public FileListResultDto ListFiles(string tenantId, SourceType sourceType, string? connectionId,
-        string? path = null, string? pageToken = null, int pageSize = 50)
+        string? path = null, string? pageToken = null, int pageSize = 50, CancellationToken cancellationToken = default)
+    {
+        return _sync.ListConnectionFilesAsync(tenantId, sourceType, connectionId, path, pageToken, pageSize, cancellationToken)
+            .GetAwaiter().GetResult();
+    }
+
+    /// <summary>
+    /// List saves queued for retry because their source was unreachable.
+    /// </summary>
+    public List<PendingSyncDto> ListPendingSyncs(string tenantId)
+    {
+        return _sync.ListPendingSyncsAsync(tenantId).GetAwaiter().GetResult();
+    }
+
+    /// <summary>
+    /// List recently opened/synced files, most recent first (favorites included).
+    /// </summary>
+    public List<RecentFileDto> ListRecentFiles(string tenantId, bool favoritesOnly = false, int limit = 0)
+    {
+        return _sync.ListRecentFilesAsync(tenantId, favoritesOnly, limit).GetAwaiter().GetResult();
+    }
+
+    /// <summary>
+    /// Pin or unpin a file as a favorite.
+    /// </summary>
+    public void PinFavorite(string tenantId, SourceType sourceType, string? connectionId,
+        string path, string? fileId, bool pinned)
+    {
+        var resolvedPath = sourceType == SourceType.LocalFile ? System.IO.Path.GetFullPath(path) : path;
+        var (success, error) = _sync.PinFavoriteAsync(tenantId, sourceType, connectionId, resolvedPath, fileId, pinned)
+            .GetAwaiter().GetResult();
+
+        if (!success)
+            throw new InvalidOperationException($"Failed to update favorite: {error}");
+    }
+
+    /// <summary>
+    /// Search files by name in a connection.
+    /// </summary>
+    public FileListResultDto SearchFiles(string tenantId, SourceType sourceType, string? connectionId,
+        string query, string? path = null, bool recursive = true, string? mimeFilter = null,
+        string? pageToken = null, int pageSize = 50, CancellationToken cancellationToken = default)
     {
-        return _sync.ListConnectionFilesAsync(tenantId, sourceType, connectionId, path, pageToken, pageSize).GetAwaiter().GetResult();
+        return _sync.SearchConnectionFilesAsync(tenantId, sourceType, connectionId, query, path, recursive,
+            mimeFilter, pageToken, pageSize, cancellationToken).GetAwaiter().GetResult();
     }
 
     /// <summary>
     /// Download a file from a connection.
     /// </summary>
     public byte[] DownloadFile(string tenantId, SourceType sourceType, string? connectionId,
-        string path, string? fileId = null)
+        string path, string? fileId = null, CancellationToken cancellationToken = default)
     {
-        return _sync.DownloadFromSourceAsync(tenantId, sourceType, connectionId, path, fileId).GetAwaiter().GetResult();
+        return _sync.DownloadFromSourceAsync(tenantId, sourceType, connectionId, path, fileId, cancellationToken)
+            .GetAwaiter().GetResult();
     }
 
     /// <summary>
@@ -6,29 +6,34 @@ namespace DocxMcp;
 /// Scoped service that resolves the correct SessionManager for the current request.
 /// In stdio mode: wraps the singleton SessionManager.
 /// In HTTP mode: reads X-Tenant-Id header and resolves from SessionManagerPool.
-/// The .NET server does NO auth — X-Tenant-Id is injected by the upstream proxy.
+/// The .NET server does NO auth — X-Tenant-Id (and X-Feature-Flags) are injected by the upstream proxy.
 /// </summary>
 public sealed class TenantScope
 {
     public string TenantId { get; }
     public SessionManager Sessions { get; }
+    public FeatureFlags Features { get; }
 
     /// <summary>
-    /// HTTP mode: resolve tenant from X-Tenant-Id header via SessionManagerPool.
+    /// HTTP mode: resolve tenant from X-Tenant-Id header via SessionManagerPool,
+    /// and its feature flags from X-Feature-Flags over the server's.
     /// </summary>
-    public TenantScope(IHttpContextAccessor accessor, SessionManagerPool pool)
+    public TenantScope(IHttpContextAccessor accessor, SessionManagerPool pool, FeatureFlags features)
     {
-        TenantId = accessor.HttpContext?.Request.Headers["X-Tenant-Id"].FirstOrDefault() ?? "";
+        var headers = accessor.HttpContext?.Request.Headers;
+        TenantId = headers?["X-Tenant-Id"].FirstOrDefault() ?? "";
         Sessions = pool.GetForTenant(TenantId);
+        Features = FeatureFlags.Parse(headers?[FeatureFlags.Header].FirstOrDefault(), features);
     }
 
     /// <summary>
     /// Stdio mode: wrap the singleton SessionManager directly.
     /// </summary>
-    public TenantScope(SessionManager sessions)
+    public TenantScope(SessionManager sessions, FeatureFlags? features = null)
     {
         TenantId = sessions.TenantId;
         Sessions = sessions;
+        Features = features ?? FeatureFlags.FromEnvironment();
     }
 
     /// <summary>
@@ -0,0 +1,267 @@
+using System.ComponentModel;
+using System.Globalization;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class AuthorityTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "mark_citation"), Description(
+        "Mark a citation for the table of authorities (TA field), as Word's Mark Citation does. The field " +
+        "is placed right after the cited text and only shows when field codes are displayed.\n\n" +
+        "CATEGORIES: Cases, Statutes, Other Authorities, Rules, Treatises, Regulations, " +
+        "Constitutional Provisions (or their number, 1-7; 8-16 are custom).\n\n" +
+        "The first mark of an authority records its long citation (listed in the table; default: the text) " +
+        "and its short citation (default: the text). Later citations of the same authority, e.g. " +
+        "'Brown, 347 U.S. at 495' or 'Id.', are marked with the same short_citation and only refer to it.\n\n" +
+        "Example:\n" +
+        "  mark_citation(doc_id, \"/body/paragraph[3]\", \"Brown v. Board of Education, 347 U.S. 483 (1954)\", " +
+        "\"Cases\", short_citation=\"Brown\")")]
+    public static string MarkCitation(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Typed path to the paragraph (must resolve to exactly 1 paragraph).")] string path,
+        [Description("Cited text in the paragraph (its first occurrence is marked).")] string text,
+        [Description("Category name or number. Default: Cases.")] string category = "Cases",
+        [Description("Citation listed in the table. Default: the text.")] string? long_citation = null,
+        [Description("Short form identifying the authority in later marks. Default: the text.")] string? short_citation = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            int categoryNumber;
+            try
+            {
+                categoryNumber = AuthorityHelper.ParseCategory(category);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var body = session.GetBody();
+
+            // The instruction goes to the WAL so replay doesn't depend on the marks before it
+            var instruction = AuthorityHelper.MarkInstruction(body, text, categoryNumber, long_citation, short_citation);
+            var walObj = new JsonObject
+            {
+                ["op"] = "mark_citation",
+                ["path"] = path,
+                ["text"] = text,
+                ["instruction"] = instruction
+            };
+            var patch = JsonDocument.Parse(walObj.ToJsonString()).RootElement;
+
+            try
+            {
+                ApplyMark(patch, session.Document);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return $"Citation marked in {path} ({instruction}).";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"marking citation in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "insert_table_of_authorities"), Description(
+        "Insert a table of authorities built from the citations marked with mark_citation: one TOA field " +
+        "per category, with the category as heading and each authority with the pages citing it " +
+        "(\"passim\" from five pages).\n\n" +
+        "The fields are written with a static result so the table shows in any viewer: pages are estimated " +
+        "from the layout. Word replaces the result with exact pages when fields are updated, which it is " +
+        "asked to do when the document is opened; citations marked after the table is inserted appear then.")]
+    public static string InsertTableOfAuthorities(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Insert position (a children path, e.g. '/body/children/2'). Default: start of the document.")] string? path = null,
+        [Description("Comma-separated categories to include (names or numbers). Default: every category with citations.")] string? categories = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            HashSet<int>? wanted = null;
+            try
+            {
+                if (categories is not null)
+                    wanted = categories.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+                        .Select(AuthorityHelper.ParseCategory)
+                        .ToHashSet();
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var entries = AuthorityHelper.BuildEntries(session.Document, wanted);
+            if (entries.Count == 0)
+                return "Error: No citations marked" + (wanted is null ? "" : " in these categories") +
+                       ". Use mark_citation first.";
+
+            // The entries go to the WAL so replay doesn't depend on the page estimate
+            var walCategories = new JsonObject();
+            foreach (var (category, items) in entries)
+            {
+                var arr = new JsonArray();
+                foreach (var item in items)
+                    arr.Add((JsonNode)new JsonObject { ["citation"] = item.Citation, ["pages"] = item.Pages });
+                walCategories[category.ToString(CultureInfo.InvariantCulture)] = arr;
+            }
+            var walObj = new JsonObject
+            {
+                ["op"] = "insert_table_of_authorities",
+                ["path"] = path,
+                ["categories"] = walCategories
+            };
+            var patch = JsonDocument.Parse(walObj.ToJsonString()).RootElement;
+
+            List<Paragraph> inserted;
+            try
+            {
+                inserted = ApplyTable(patch, session.Document);
+            }
+            catch (Exception ex) when (ex is InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var ids = new JsonArray();
+            foreach (var p in inserted)
+                ids.Add((JsonNode?)ElementIdManager.GetId(p));
+
+            var categoryArr = new JsonArray();
+            foreach (var (category, items) in entries)
+            {
+                categoryArr.Add((JsonNode)new JsonObject
+                {
+                    ["category"] = AuthorityHelper.CategoryName(category),
+                    ["authorities"] = items.Count
+                });
+            }
+
+            return new JsonObject
+            {
+                ["ids"] = ids,
+                ["categories"] = categoryArr,
+                ["pages_estimated"] = true
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"inserting table of authorities in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a mark_citation WAL operation.
+    /// </summary>
+    internal static void ReplayMarkCitation(JsonElement patch, WordprocessingDocument doc)
+    {
+        ApplyMark(patch, doc);
+    }
+
+    /// <summary>
+    /// Replay an insert_table_of_authorities WAL operation.
+    /// </summary>
+    internal static void ReplayInsertTableOfAuthorities(JsonElement patch, WordprocessingDocument doc)
+    {
+        ApplyTable(patch, doc);
+    }
+
+    private static void ApplyMark(JsonElement patch, WordprocessingDocument doc)
+    {
+        var path = patch.GetProperty("path").GetString()
+            ?? throw new InvalidOperationException("mark_citation must have a 'path' field.");
+
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements.Count != 1 || elements[0] is not Paragraph paragraph)
+            throw new InvalidOperationException($"Path '{path}' must resolve to exactly 1 paragraph.");
+
+        AuthorityHelper.Mark(paragraph,
+            patch.GetProperty("text").GetString() ?? "",
+            patch.GetProperty("instruction").GetString() ?? "");
+    }
+
+    private static List<Paragraph> ApplyTable(JsonElement patch, WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no MainDocumentPart.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var entries = new SortedDictionary<int, List<AuthorityEntry>>();
+        foreach (var category in patch.GetProperty("categories").EnumerateObject())
+        {
+            entries[int.Parse(category.Name, CultureInfo.InvariantCulture)] = category.Value
+                .EnumerateArray()
+                .Select(e => new AuthorityEntry(
+                    e.GetProperty("citation").GetString() ?? "",
+                    e.GetProperty("pages").GetString() ?? ""))
+                .ToList();
+        }
+
+        OpenXmlElement parent = body;
+        var index = 0;
+        if (patch.TryGetProperty("path", out var p) && p.GetString() is { } path)
+            (parent, index) = PathResolver.ResolveForInsert(DocxPath.Parse(path), doc);
+
+        var paragraphs = AuthorityHelper.CreateTable(mainPart, entries);
+        foreach (var paragraph in paragraphs)
+            parent.InsertAt(paragraph, Math.Min(index++, parent.ChildElements.Count));
+
+        HeadingHelper.RefreshTableOfContents(doc);
+        return paragraphs;
+    }
+}
@@ -0,0 +1,127 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class AutofitTool
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "autofit_table"), Description(
+        "Compute column widths from the table's content and write them to the table grid, cell widths, " +
+        "table width and layout, like Word's AutoFit commands. Widths are estimated from text length and font size.\n\n" +
+        "Modes:\n" +
+        "  contents — columns as wide as their content, up to the page's text width (default)\n" +
+        "  window   — the table spans the text width; extra space goes to the wider columns\n" +
+        "  fixed    — content-based widths, locked with a fixed layout so they don't change as text is edited\n\n" +
+        "Omit path to autofit every table in the body.\n\n" +
+        "Examples:\n" +
+        "  autofit_table(doc_id, \"/body/table[0]\")\n" +
+        "  autofit_table(doc_id, mode=\"window\")")]
+    public static string AutofitTable(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Path to the table(s), e.g. /body/table[0]. Omit for all tables.")] string? path = null,
+        [Description("contents, window or fixed. Default: contents.")] string mode = "contents")
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            List<(Table Table, TableFit Fit)> fits;
+            try
+            {
+                fits = Apply(session.Document, path, mode);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "autofit_table",
+                ["path"] = path,
+                ["mode"] = mode
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var tables = new JsonArray();
+            foreach (var (table, fit) in fits)
+            {
+                var columns = new JsonArray();
+                foreach (var width in fit.Columns)
+                    columns.Add((JsonNode)width);
+
+                tables.Add((JsonNode)new JsonObject
+                {
+                    ["path"] = ElementIdManager.GetId(table) is string id ? $"/body/table[id='{id}']" : null,
+                    ["mode"] = fit.Mode,
+                    ["width_twips"] = fit.Width,
+                    ["column_widths_twips"] = columns
+                });
+            }
+
+            return new JsonObject { ["tables"] = tables }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"autofitting tables in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an autofit_table WAL operation.
+    /// </summary>
+    internal static void ReplayAutofitTable(JsonElement patch, WordprocessingDocument doc)
+    {
+        Apply(doc,
+            patch.TryGetProperty("path", out var p) ? p.GetString() : null,
+            patch.GetProperty("mode").GetString() ?? "contents");
+    }
+
+    private static List<(Table, TableFit)> Apply(WordprocessingDocument doc, string? path, string mode)
+    {
+        List<Table> tables;
+        if (path is null)
+        {
+            var body = doc.MainDocumentPart?.Document?.Body
+                ?? throw new InvalidOperationException("Document has no body.");
+            tables = body.Elements<Table>().ToList();
+        }
+        else
+        {
+            tables = PathResolver.Resolve(DocxPath.Parse(path), doc).OfType<Table>().ToList();
+        }
+
+        if (tables.Count == 0)
+            throw new ArgumentException(path is null ? "The document has no tables." : $"No table found at '{path}'.");
+
+        return tables.Select(t => (t, TableFitHelper.Autofit(doc, t, mode))).ToList();
+    }
+}
@@ -0,0 +1,175 @@
+using System.ComponentModel;
+using System.Globalization;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class ClassificationTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "get_classification"), Description(
+        "Read a document's sensitivity labels (Microsoft Purview / AIP): label ID, name, method, date, " +
+        "site ID and content-marking bits, from the MSIP_Label_* custom properties and the LabelInfo part. " +
+        "Also lists the labels of the server's classification policy.")]
+    public static string GetClassification(
+        TenantScope tenant,
+        ClassificationPolicy policy,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var labels = new JsonArray();
+            foreach (var label in ClassificationHelper.Read(session.Document))
+            {
+                labels.Add(new JsonObject
+                {
+                    ["id"] = label.Id,
+                    ["name"] = label.Name ?? policy.Find(label.Id)?.Name,
+                    ["enabled"] = label.Enabled,
+                    ["method"] = label.Method,
+                    ["set_date"] = label.SetDate,
+                    ["site_id"] = label.SiteId,
+                    ["action_id"] = label.ActionId,
+                    ["content_bits"] = label.ContentBits,
+                    ["sources"] = new JsonArray(label.Sources.Select(s => (JsonNode)JsonValue.Create(s)!).ToArray())
+                });
+            }
+
+            return new JsonObject
+            {
+                ["labels"] = labels,
+                ["policy_labels"] = new JsonArray(policy.Labels.Select(l => (JsonNode)JsonValue.Create(l.Name)!).ToArray())
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"reading the classification of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "set_classification"), Description(
+        "Apply a sensitivity label from the classification policy, replacing any label the document has.\n\n" +
+        "Writes the MSIP_Label_* custom properties Office and DLP tools read (and the LabelInfo part when the " +
+        "document has one), then applies the label's content markings: header text, footer text and a " +
+        "diagonal watermark, as the policy defines them. Markings of the previous label are removed.\n\n" +
+        "Pass an empty label to remove the classification.\n\n" +
+        "Examples:\n" +
+        "  set_classification(doc_id, \"Confidential\")\n" +
+        "  set_classification(doc_id, \"\")  — remove the label and its markings")]
+    public static string SetClassification(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        ClassificationPolicy policy,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Label name or ID from the policy, or empty to remove the classification.")] string label)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            AppliedLabel? applied = null;
+            if (!string.IsNullOrWhiteSpace(label))
+            {
+                var policyLabel = policy.Find(label.Trim());
+                if (policyLabel is null)
+                    return $"Error: Unknown label '{label}'. Policy labels: {string.Join(", ", policy.Labels.Select(l => l.Name))}.";
+                applied = new AppliedLabel(
+                    policyLabel, "Privileged", DeterministicOutput.UtcNow, DeterministicOutput.NewGuid().ToString());
+            }
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            ClassificationHelper.Apply(session.Document, applied);
+
+            var walObj = new JsonObject { ["op"] = "set_classification" };
+            if (applied is not null)
+            {
+                var l = applied.Label;
+                walObj["label"] = new JsonObject
+                {
+                    ["name"] = l.Name,
+                    ["id"] = l.Id,
+                    ["site_id"] = l.SiteId,
+                    ["header"] = l.Header,
+                    ["footer"] = l.Footer,
+                    ["watermark"] = l.Watermark,
+                    ["color"] = l.Color
+                };
+                walObj["method"] = applied.Method;
+                walObj["set_date"] = applied.SetDate.ToString("O", CultureInfo.InvariantCulture);
+                walObj["action_id"] = applied.ActionId;
+            }
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            if (applied is null)
+                return "Classification removed.";
+
+            var result = new JsonObject
+            {
+                ["label"] = applied.Label.Name,
+                ["id"] = applied.Label.Id,
+                ["header"] = applied.Label.Header,
+                ["footer"] = applied.Label.Footer,
+                ["watermark"] = applied.Label.Watermark
+            };
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"classifying '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a set_classification WAL operation.
+    /// </summary>
+    internal static void ReplaySetClassification(JsonElement patch, WordprocessingDocument doc)
+    {
+        if (!patch.TryGetProperty("label", out var l) || l.ValueKind != JsonValueKind.Object)
+        {
+            ClassificationHelper.Apply(doc, null);
+            return;
+        }
+
+        static string? Optional(JsonElement obj, string name) =>
+            obj.TryGetProperty(name, out var v) && v.ValueKind == JsonValueKind.String ? v.GetString() : null;
+
+        var label = new PolicyLabel(
+            Optional(l, "name") ?? throw new InvalidOperationException("set_classification label must have a 'name'."),
+            Optional(l, "id") ?? throw new InvalidOperationException("set_classification label must have an 'id'."),
+            Optional(l, "site_id"),
+            Optional(l, "header"),
+            Optional(l, "footer"),
+            Optional(l, "watermark"),
+            Optional(l, "color"));
+        var setDate = DateTime.Parse(
+            Optional(patch, "set_date") ?? throw new InvalidOperationException("set_classification must have a 'set_date'."),
+            CultureInfo.InvariantCulture, DateTimeStyles.AdjustToUniversal | DateTimeStyles.AssumeUniversal);
+
+        ClassificationHelper.Apply(doc, new AppliedLabel(
+            label, Optional(patch, "method") ?? "Privileged", setDate, Optional(patch, "action_id") ?? ""));
+    }
+}
@@ -0,0 +1,79 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class ClauseTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "verify_clause_references"), Description(
+        "Check the integrity of numbered clauses and internal cross-references (contracts, policies). " +
+        "Run it after edits that insert, delete or move clauses.\n\n" +
+        "Clauses are paragraphs with list numbering (e.g. '4.2', 'Article 3') or whose text starts with a typed " +
+        "number ('4.2 Termination', 'Section 5'). References are mentions such as 'Section 4.2', " +
+        "'Sections 3.1 and 3.2', 'clause 7(b)' or '§ 5', and REF cross-reference fields.\n\n" +
+        "FINDING KINDS:\n" +
+        "  dangling_reference — refers to a clause number that does not exist\n" +
+        "  title_mismatch     — 'Section 4.2 (\"Termination\")' where clause 4.2 has another title\n" +
+        "  broken_field       — REF field whose bookmark was deleted or is outside a clause\n" +
+        "  stale_field        — REF field showing an old clause number (fields need updating)\n" +
+        "  duplicate_number   — two clauses share a number\n" +
+        "  numbering_gap      — typed numbering skips a number (e.g. 3.1 then 3.3)")]
+    public static string VerifyClauseReferences(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Number of findings to skip. Default: 0.")] int? offset = null,
+        [Description("Maximum number of findings to return (1-100). Default: 100.")] int? limit = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var report = ClauseHelper.Check(session.Document);
+
+            var effectiveOffset = Math.Max(0, offset ?? 0);
+            var effectiveLimit = Math.Clamp(limit ?? 100, 1, 100);
+            var page = report.Findings.Skip(effectiveOffset).Take(effectiveLimit).ToList();
+
+            var arr = new JsonArray();
+            foreach (var f in page)
+            {
+                var obj = new JsonObject
+                {
+                    ["kind"] = f.Kind,
+                    ["path"] = f.Path,
+                    ["reference"] = f.Reference,
+                    ["message"] = f.Message
+                };
+                if (f.Target is not null)
+                    obj["target"] = f.Target;
+                arr.Add((JsonNode)obj);
+            }
+
+            var result = new JsonObject
+            {
+                ["clauses"] = report.Clauses.Count,
+                ["references"] = report.References,
+                ["total"] = report.Findings.Count,
+                ["offset"] = effectiveOffset,
+                ["limit"] = effectiveLimit,
+                ["count"] = page.Count,
+                ["findings"] = arr
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"verifying clause references in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
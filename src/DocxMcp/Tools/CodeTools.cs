@@ -0,0 +1,143 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class CodeTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "add_code_block"), Description(
+        "Add a code block: one monospaced paragraph per line (style \"Code Block\"), shaded with the theme's " +
+        "background. Indentation and repeated spaces are preserved, tabs are kept as tabs.\n\n" +
+        "LANGUAGES (keywords, strings, comments and numbers are colored): csharp, java, javascript " +
+        "(also typescript), python, rust, go, c (also cpp), sql, bash, json. Other languages are added " +
+        "without highlighting.\n\n" +
+        "THEMES: light (default), dark, plain (no colors).\n\n" +
+        "For code within a sentence, use style_element with {\"code\": true} on the run.")]
+    public static string AddCodeBlock(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Source code; lines are separated by newlines.")] string code,
+        [Description("Language for syntax highlighting (e.g. \"python\", \"ts\"). Omit for plain text.")] string? language = null,
+        [Description("Color theme: light, dark or plain. Default: light.")] string theme = "light",
+        [Description("Insert position (a children path, e.g. '/body/children/2'). Default: end of the document.")] string? path = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            CodeTheme codeTheme;
+            try
+            {
+                codeTheme = CodeHelper.Theme(theme);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (string.IsNullOrEmpty(code))
+                return "Error: code is empty.";
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "add_code_block",
+                ["path"] = path,
+                ["code"] = code,
+                ["language"] = language,
+                ["theme"] = codeTheme.Name
+            };
+            var patch = JsonDocument.Parse(walObj.ToJsonString()).RootElement;
+
+            List<Paragraph> inserted;
+            try
+            {
+                inserted = ApplyCodeBlock(patch, session.Document);
+            }
+            catch (Exception ex) when (ex is InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var ids = new JsonArray();
+            foreach (var p in inserted)
+                ids.Add((JsonNode?)ElementIdManager.GetId(p));
+
+            var languageName = CodeHelper.LanguageName(language);
+            return new JsonObject
+            {
+                ["ids"] = ids,
+                ["lines"] = inserted.Count,
+                ["language"] = languageName ?? language,
+                ["theme"] = codeTheme.Name,
+                ["highlighted"] = languageName is not null
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"adding code block to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an add_code_block WAL operation.
+    /// </summary>
+    internal static void ReplayAddCodeBlock(JsonElement patch, WordprocessingDocument doc)
+    {
+        ApplyCodeBlock(patch, doc);
+    }
+
+    private static List<Paragraph> ApplyCodeBlock(JsonElement patch, WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no MainDocumentPart.");
+        var body = mainPart.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var code = patch.GetProperty("code").GetString() ?? "";
+        var language = patch.TryGetProperty("language", out var l) ? l.GetString() : null;
+        var theme = CodeHelper.Theme(patch.TryGetProperty("theme", out var t) ? t.GetString() : null);
+
+        OpenXmlElement parent = body;
+        var index = body.GetFirstChild<SectionProperties>() is { } sectPr
+            ? body.ChildElements.ToList().IndexOf(sectPr)
+            : body.ChildElements.Count;
+        if (patch.TryGetProperty("path", out var p) && p.GetString() is { } path)
+            (parent, index) = PathResolver.ResolveForInsert(DocxPath.Parse(path), doc);
+
+        var paragraphs = CodeHelper.CreateBlock(mainPart, code, language, theme);
+        foreach (var paragraph in paragraphs)
+            parent.InsertAt(paragraph, Math.Min(index++, parent.ChildElements.Count));
+
+        return paragraphs;
+    }
+}
@@ -27,15 +27,16 @@ public sealed class CommentTools
     public static string CommentAdd(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Typed path to the target element (must resolve to exactly 1 element).")] string path,
         [Description("Comment text. Use \\n for multi-paragraph comments.")] string text,
         [Description("Optional text within the element to anchor the comment to. Without this, comment spans the entire element.")] string? anchor_text = null,
-        [Description("Comment author name. Default: 'AI Assistant'.")] string? author = null,
-        [Description("Author initials. Default: 'AI'.")] string? initials = null)
+        [Description("Comment author name. Default: the tenant profile's default_author, else 'AI Assistant'.")] string? author = null,
+        [Description("Author initials. Default: those of the profile's default author, else 'AI'.")] string? initials = null)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -56,9 +57,10 @@ public sealed class CommentTools
                 return $"Error: Path '{path}' resolved to {elements.Count} elements — must resolve to exactly 1.";
 
             var target = elements[0];
-            var effectiveAuthor = author ?? "AI Assistant";
-            var effectiveInitials = initials ?? "AI";
-            var date = DateTime.UtcNow;
+            var profileAuthor = author is null ? tenant.Sessions.LoadProfile().DefaultAuthor : null;
+            var effectiveAuthor = author ?? profileAuthor ?? "AI Assistant";
+            var effectiveInitials = initials ?? (profileAuthor is not null ? Initials(profileAuthor) : "AI");
+            var date = DeterministicOutput.UtcNow;
             var commentId = CommentHelper.AllocateCommentId(doc);
 
             try
@@ -111,13 +113,14 @@ public sealed class CommentTools
         "containing id, author, initials, date, text, and anchored_text.")]
     public static string CommentList(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Filter by author name (case-insensitive).")] string? author = null,
         [Description("Number of comments to skip. Default: 0.")] int? offset = null,
         [Description("Maximum number of comments to return (1-50). Default: 50.")] int? limit = null)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -174,12 +177,13 @@ public sealed class CommentTools
     public static string CommentDelete(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("ID of the specific comment to delete.")] int? comment_id = null,
         [Description("Delete all comments by this author (case-insensitive).")] string? author = null)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             if (comment_id is null && author is null)
                 return "Error: At least one of comment_id or author must be provided.";
 
@@ -255,7 +259,7 @@ public sealed class CommentTools
         var author = patch.GetProperty("author").GetString() ?? "AI Assistant";
         var initials = patch.GetProperty("initials").GetString() ?? "AI";
         var dateStr = patch.GetProperty("date").GetString();
-        var date = dateStr is not null ? DateTime.Parse(dateStr).ToUniversalTime() : DateTime.UtcNow;
+        var date = dateStr is not null ? DateTime.Parse(dateStr).ToUniversalTime() : DeterministicOutput.UtcNow;
 
         string? anchorText = null;
         if (patch.TryGetProperty("anchor_text", out var at) && at.ValueKind == JsonValueKind.String)
@@ -287,6 +291,10 @@ public sealed class CommentTools
         CommentHelper.DeleteComment(doc, commentId);
     }
 
+    /// <summary>First letter of each word of a name, e.g. "JD" for "Jane Doe".</summary>
+    private static string Initials(string name) =>
+        string.Concat(name.Split(' ', StringSplitOptions.RemoveEmptyEntries).Select(w => char.ToUpperInvariant(w[0])));
+
     private static readonly JsonSerializerOptions JsonOpts = new()
     {
         WriteIndented = true,
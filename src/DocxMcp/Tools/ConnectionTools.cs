@@ -78,7 +78,8 @@ public sealed class ConnectionTools
         [Description("Pagination token from previous response.")]
         string? page_token = null,
         [Description("Max results per page. Default 20.")]
-        int page_size = 20)
+        int page_size = 20,
+        CancellationToken cancellationToken = default)
     {
         try
         {
@@ -90,7 +91,8 @@ public sealed class ConnectionTools
                 _ => throw new ArgumentException($"Unknown source type: {source_type}. Use 'local', 'google_drive', or 'onedrive'.")
             };
 
-            var result = sync.ListFiles(tenant.TenantId, type, connection_id, path, page_token, page_size);
+            var result = sync.ListFiles(tenant.TenantId, type, connection_id, path, page_token, page_size,
+                cancellationToken);
 
             var filesArr = new JsonArray();
             foreach (var f in result.Files)
@@ -127,4 +129,160 @@ public sealed class ConnectionTools
         catch (McpException) { throw; }
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
+
+    [McpServerTool(Name = "search_connection_files"), Description(
+        "Search files by name in a storage connection, including subfolders. " +
+        "Call list_connections first to discover available source types and connection IDs. " +
+        "Matching is case-insensitive on the file name. Only files are returned, never folders. " +
+        "Defaults to .docx documents.")]
+    public static string SearchConnectionFiles(
+        TenantScope tenant,
+        SyncManager sync,
+        [Description("Source type from list_connections result (e.g. 'local', 'google_drive', 'onedrive').")]
+        string source_type,
+        [Description("Text to find in file names.")]
+        string query,
+        [Description("Connection ID from list_connections result. Required for cloud sources.")]
+        string? connection_id = null,
+        [Description("Folder path to search from. Omit for root.")]
+        string? path = null,
+        [Description("Also search subfolders. Default true.")]
+        bool recursive = true,
+        [Description("MIME type to match. Omit for .docx documents.")]
+        string? mime_type = null,
+        [Description("Pagination token from previous response.")]
+        string? page_token = null,
+        [Description("Max results per page. Default 20.")]
+        int page_size = 20,
+        CancellationToken cancellationToken = default)
+    {
+        try
+        {
+            var type = source_type switch
+            {
+                "local" => SourceType.LocalFile,
+                "google_drive" => SourceType.GoogleDrive,
+                "onedrive" => SourceType.Onedrive,
+                _ => throw new ArgumentException($"Unknown source type: {source_type}. Use 'local', 'google_drive', or 'onedrive'.")
+            };
+
+            var result = sync.SearchFiles(tenant.TenantId, type, connection_id, query, path, recursive,
+                mime_type, page_token, page_size, cancellationToken);
+
+            var filesArr = new JsonArray();
+            foreach (var f in result.Files)
+            {
+                var obj = new JsonObject
+                {
+                    ["name"] = f.Name,
+                    ["size_bytes"] = f.SizeBytes,
+                };
+                if (f.ModifiedAtUnix > 0)
+                    obj["modified_at"] = DateTimeOffset.FromUnixTimeSeconds(f.ModifiedAtUnix).ToString("o");
+                if (f.FileId is not null)
+                    obj["file_id"] = f.FileId;
+                obj["path"] = f.Path;
+                filesArr.Add((JsonNode)obj);
+            }
+
+            var response = new JsonObject
+            {
+                ["count"] = result.Files.Count,
+                ["files"] = filesArr
+            };
+
+            if (result.NextPageToken is not null)
+                response["next_page_token"] = result.NextPageToken;
+
+            return response.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "searching files"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "list_recent_files"), Description(
+        "List documents recently opened or saved, most recent first, including pinned favorites. " +
+        "Each entry has the source_type, connection_id, file_id and path to pass to document_open.")]
+    public static string ListRecentFiles(
+        TenantScope tenant,
+        SyncManager sync,
+        [Description("Only return favorites.")]
+        bool favorites_only = false,
+        [Description("Max results. Default 20.")]
+        int limit = 20)
+    {
+        try
+        {
+            var files = sync.ListRecentFiles(tenant.TenantId, favorites_only, limit);
+
+            var arr = new JsonArray();
+            foreach (var f in files)
+            {
+                var obj = new JsonObject
+                {
+                    ["name"] = f.DisplayName,
+                    ["source_type"] = f.SourceType switch
+                    {
+                        SourceType.GoogleDrive => "google_drive",
+                        SourceType.Onedrive => "onedrive",
+                        _ => "local"
+                    },
+                    ["path"] = f.Path,
+                    ["favorite"] = f.Favorite,
+                    ["last_used_at"] = DateTimeOffset.FromUnixTimeSeconds(f.LastUsedAtUnix).ToString("o")
+                };
+                if (f.ConnectionId is not null)
+                    obj["connection_id"] = f.ConnectionId;
+                if (f.FileId is not null)
+                    obj["file_id"] = f.FileId;
+                arr.Add((JsonNode)obj);
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = files.Count,
+                ["files"] = arr
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing recent files"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "pin_favorite"), Description(
+        "Pin a document as a favorite so it stays in list_recent_files, or unpin it.")]
+    public static string PinFavorite(
+        TenantScope tenant,
+        SyncManager sync,
+        [Description("Source type: 'local', 'google_drive', 'onedrive'.")]
+        string source_type,
+        [Description("Absolute path for local files, or display path for cloud files.")]
+        string path,
+        [Description("Connection ID (required for cloud sources).")]
+        string? connection_id = null,
+        [Description("Provider file ID (required for cloud sources).")]
+        string? file_id = null,
+        [Description("False to unpin. Default true.")]
+        bool pinned = true)
+    {
+        try
+        {
+            var type = source_type switch
+            {
+                "local" => SourceType.LocalFile,
+                "google_drive" => SourceType.GoogleDrive,
+                "onedrive" => SourceType.Onedrive,
+                _ => throw new ArgumentException($"Unknown source type: {source_type}. Use 'local', 'google_drive', or 'onedrive'.")
+            };
+
+            sync.PinFavorite(tenant.TenantId, type, connection_id, path, file_id, pinned);
+            return pinned ? $"Pinned '{path}' as a favorite." : $"Unpinned '{path}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "updating favorite"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
 }
@@ -18,7 +18,7 @@ public sealed class CountTool
     [McpServerTool(Name = "count_elements"), Description(
         "Count elements matching a typed path without returning their content. " +
         "Use this before querying with [*] to know the total number of elements " +
-        "and plan pagination.\n\n" +
+        "and plan pagination. For /body, also returns the estimated page count (see estimate_pages).\n\n" +
         "Examples:\n" +
         "  /body/paragraph[*] — count all paragraphs\n" +
         "  /body/table[*] — count all tables\n" +
@@ -27,11 +27,12 @@ public sealed class CountTool
         "  /body/paragraph[text~='hello'] — count paragraphs containing 'hello'")]
     public static string CountElements(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Typed path with selector (e.g. /body/paragraph[*], /body/table[0]/row[*]).")] string path)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -48,6 +49,7 @@ public sealed class CountTool
                     ["tables"] = body.Elements<Table>().Count(),
                     ["headings"] = body.Elements<Paragraph>().Count(p => p.IsHeading()),
                     ["total_children"] = body.ChildElements.Count,
+                    ["estimated_pages"] = PageLayoutEstimator.Estimate(doc).Pages,
                 };
 
                 return result.ToJsonString(JsonOpts);
@@ -70,6 +72,61 @@ public sealed class CountTool
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
 
+    [McpServerTool(Name = "analyze_structure"), Description(
+        "Word, paragraph, table and image counts per top-level section, without returning text. " +
+        "A section is a top-level heading (the shallowest heading level used) and everything up to the next one; " +
+        "content before the first heading is reported as a section with a null heading.\n\n" +
+        "Each section has the IDs of its first and last elements (range), so a section that is too long " +
+        "or missing content can then be read with query or read_heading_content.")]
+    public static string AnalyzeStructure(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            var sections = StatisticsHelper.ComputeBySection(session.Document);
+            var totalWords = sections.Sum(s => s.Words);
+
+            var arr = new JsonArray();
+            for (var i = 0; i < sections.Count; i++)
+            {
+                var section = sections[i];
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["index"] = i,
+                    ["heading"] = section.Heading,
+                    ["level"] = section.Level,
+                    ["range"] = new JsonObject
+                    {
+                        ["first_id"] = section.FirstId,
+                        ["last_id"] = section.LastId,
+                    },
+                    ["words"] = section.Words,
+                    ["word_share"] = totalWords == 0 ? 0 : Math.Round(100.0 * section.Words / totalWords, 1),
+                    ["paragraphs"] = section.Paragraphs,
+                    ["subheadings"] = section.Subheadings,
+                    ["tables"] = section.Tables,
+                    ["images"] = section.Images,
+                });
+            }
+
+            var result = new JsonObject
+            {
+                ["section_count"] = sections.Count,
+                ["total_words"] = totalWords,
+                ["sections"] = arr,
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"analyzing structure of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
     private static readonly JsonSerializerOptions JsonOpts = new()
     {
         WriteIndented = true,
@@ -0,0 +1,242 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class CustomXmlTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "add_custom_xml_part"), Description(
+        "Add a custom XML data part to a document (the data store that content controls bind to, " +
+        "e.g. for OpenDoPE-style document assembly). Returns the part's store item ID, used by bind_control_to_xpath.")]
+    public static string AddCustomXmlPart(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("XML content of the part (e.g. <contract xmlns=\"urn:acme\"><party>Acme</party></contract>).")] string xml)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            CustomXmlInfo info;
+            try
+            {
+                info = CustomXmlHelper.Add(session.Document, xml);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "add_custom_xml_part",
+                ["xml"] = xml,
+                ["item_id"] = info.ItemId
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return InfoToJson(info).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"adding a custom XML part to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "list_custom_xml_parts"), Description(
+        "List a document's custom XML data parts with their store item ID, URI, root element and namespace.")]
+    public static string ListCustomXmlParts(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var arr = new JsonArray();
+            foreach (var info in CustomXmlHelper.List(session.Document))
+                arr.Add((JsonNode)InfoToJson(info));
+
+            return new JsonObject { ["parts"] = arr }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"listing custom XML parts of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "bind_control_to_xpath"), Description(
+        "Bind content controls to a node of a custom XML part (w:dataBinding). The controls show the node's " +
+        "current value, and Word keeps them in sync with the data from then on.\n\n" +
+        "Targets every content control (body, headers, footers) whose tag equals 'tag'. " +
+        "Pass 'path' to a paragraph to create the control instead: the paragraph's content is replaced by a " +
+        "plain-text control with that tag.\n\n" +
+        "Prefix mappings default to the namespaces declared on the part's root, with the default namespace as ns0.\n\n" +
+        "Example:\n" +
+        "  bind_control_to_xpath(doc_id, \"{1A2B...}\", \"/ns0:contract[1]/ns0:party[1]\", \"party\", path=\"/body/paragraph[2]\")")]
+    public static string BindControlToXpath(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Store item ID of the custom XML part (from add_custom_xml_part).")] string store_item_id,
+        [Description("XPath of the bound node in the custom XML part.")] string xpath,
+        [Description("Tag of the content controls to bind (or of the new control when path is given).")] string tag,
+        [Description("Optional path to a paragraph to turn into a new bound control.")] string? path = null,
+        [Description("Prefix mappings, e.g. \"xmlns:ns0='urn:acme'\". Default: the part's root namespaces.")] string? prefix_mappings = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            var part = CustomXmlHelper.Find(doc, store_item_id);
+            if (part is null)
+                return $"Error: No custom XML part with store item ID '{store_item_id}'. Use list_custom_xml_parts to see them.";
+
+            prefix_mappings ??= CustomXmlHelper.DefaultPrefixMappings(part);
+            var itemId = part.CustomXmlPropertiesPart!.DataStoreItem!.ItemId!.Value!;
+
+            int bound;
+            string? value;
+            try
+            {
+                (bound, value) = Bind(doc, part, itemId, xpath, tag, path, prefix_mappings);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "bind_control_to_xpath",
+                ["store_item_id"] = itemId,
+                ["xpath"] = xpath,
+                ["tag"] = tag,
+                ["path"] = path,
+                ["prefix_mappings"] = prefix_mappings
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return new JsonObject
+            {
+                ["bound"] = bound,
+                ["store_item_id"] = itemId,
+                ["xpath"] = xpath,
+                ["prefix_mappings"] = prefix_mappings,
+                ["value"] = value
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"binding controls in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an add_custom_xml_part WAL operation.
+    /// </summary>
+    internal static void ReplayAddCustomXmlPart(JsonElement patch, WordprocessingDocument doc)
+    {
+        var xml = patch.GetProperty("xml").GetString()
+            ?? throw new InvalidOperationException("add_custom_xml_part must have an 'xml' field.");
+        var itemId = patch.GetProperty("item_id").GetString();
+        CustomXmlHelper.Add(doc, xml, itemId);
+    }
+
+    /// <summary>
+    /// Replay a bind_control_to_xpath WAL operation.
+    /// </summary>
+    internal static void ReplayBindControlToXpath(JsonElement patch, WordprocessingDocument doc)
+    {
+        var itemId = patch.GetProperty("store_item_id").GetString()
+            ?? throw new InvalidOperationException("bind_control_to_xpath must have a 'store_item_id' field.");
+        var part = CustomXmlHelper.Find(doc, itemId)
+            ?? throw new InvalidOperationException($"No custom XML part with store item ID '{itemId}'.");
+
+        Bind(doc, part, itemId,
+            patch.GetProperty("xpath").GetString() ?? "",
+            patch.GetProperty("tag").GetString() ?? "",
+            patch.TryGetProperty("path", out var p) ? p.GetString() : null,
+            patch.TryGetProperty("prefix_mappings", out var m) ? m.GetString() ?? "" : "");
+    }
+
+    private static (int Bound, string? Value) Bind(
+        WordprocessingDocument doc, CustomXmlPart part, string itemId,
+        string xpath, string tag, string? path, string prefixMappings)
+    {
+        var value = CustomXmlHelper.Evaluate(part, xpath, prefixMappings)
+            ?? throw new ArgumentException($"XPath '{xpath}' matches nothing in the custom XML part.");
+
+        List<SdtElement> controls;
+        if (path is not null)
+        {
+            var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+            if (elements is not [Paragraph paragraph])
+                throw new ArgumentException($"Path '{path}' must resolve to exactly 1 paragraph.");
+            controls = [CustomXmlHelper.WrapParagraph(paragraph, tag)];
+        }
+        else
+        {
+            controls = CustomXmlHelper.FindControls(doc, tag);
+            if (controls.Count == 0)
+                throw new ArgumentException($"No content control tagged '{tag}'. Pass path to create one.");
+        }
+
+        foreach (var control in controls)
+            CustomXmlHelper.Bind(control, itemId, xpath, prefixMappings, value);
+
+        return (controls.Count, value);
+    }
+
+    private static JsonObject InfoToJson(CustomXmlInfo info) => new()
+    {
+        ["store_item_id"] = info.ItemId,
+        ["uri"] = info.Uri,
+        ["root"] = info.Root,
+        ["namespace"] = info.Namespace,
+        ["size_bytes"] = info.Size
+    };
+}
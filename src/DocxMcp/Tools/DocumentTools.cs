@@ -17,6 +17,7 @@ public sealed class DocumentTools
         "Open an existing DOCX file or create a new empty document. " +
         "Returns a session ID to use with other tools. " +
         "If all parameters are omitted, creates a new empty document. " +
+        "Provide template to create a new document from a template (see template_list). " +
         "Use list_connections and list_connection_files to discover available files before opening. " +
         "For local files, provide path only. " +
         "For cloud files (Google Drive), provide source_type, connection_id, file_id, and path.")]
@@ -31,19 +32,32 @@ public sealed class DocumentTools
         [Description("Connection ID from list_connections (required for cloud sources).")]
         string? connection_id = null,
         [Description("Provider file ID from list_connection_files (required for cloud sources).")]
-        string? file_id = null)
+        string? file_id = null,
+        [Description("Template name from template_list. Creates a new document from that template.")]
+        string? template = null,
+        CancellationToken cancellationToken = default)
     {
         try
         {
-            logger.LogDebug("document_open: path={Path}, source_type={SourceType}, connection_id={ConnId}, file_id={FileId}",
-                path, source_type, connection_id, file_id);
+            logger.LogDebug("document_open: path={Path}, source_type={SourceType}, connection_id={ConnId}, file_id={FileId}, template={Template}",
+                path, source_type, connection_id, file_id, template);
 
             var sessions = tenant.Sessions;
 
             DocxSession session;
             string sourceDescription;
 
-            if (path is null && source_type is null && connection_id is null && file_id is null)
+            if (template is not null)
+            {
+                if (path is not null || source_type is not null || connection_id is not null || file_id is not null)
+                    throw new ArgumentException(
+                        "template cannot be combined with path, source_type, connection_id or file_id. " +
+                        "Use document_set_source afterwards to choose where to save the new document.");
+
+                session = sessions.CreateFromTemplate(template);
+                sourceDescription = $" from template '{template}'";
+            }
+            else if (path is null && source_type is null && connection_id is null && file_id is null)
             {
                 // New empty document — no sync needed, always allowed
                 session = sessions.Create();
@@ -57,7 +71,8 @@ public sealed class DocumentTools
                 if (type != SourceType.LocalFile && file_id is not null)
                 {
                     // Cloud source: download bytes, create session, register source
-                    var data = sync.DownloadFile(tenant.TenantId, type, connection_id, path ?? file_id, file_id);
+                    var data = sync.DownloadFile(tenant.TenantId, type, connection_id, path ?? file_id, file_id,
+                        cancellationToken);
                     session = sessions.OpenFromBytes(data, path ?? file_id);
 
                     // Register typed source for sync-back
@@ -88,11 +103,111 @@ public sealed class DocumentTools
             return $"Opened document{sourceDescription}. Session ID: {session.Id}";
         }
         catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "opening document"); }
+        catch (KeyNotFoundException ex) when (template is not null)
+        {
+            throw new McpException($"{ex.Message} Use template_list to see available templates.");
+        }
         catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(path ?? "new document"); }
         catch (McpException) { throw; }
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
 
+    [McpServerTool(Name = "document_open_url"), Description(
+        "Download a DOCX from an HTTPS link and open it as a new session. " +
+        "Returns a session ID to use with other tools. " +
+        "Use the file's direct download link, not a sharing or preview page. " +
+        "The document has no save target: use document_set_source to choose where to save it.")]
+    public static async Task<string> DocumentOpenUrl(
+        ILogger<DocumentTools> logger,
+        TenantScope tenant,
+        UrlDocumentFetcher fetcher,
+        [Description("HTTPS link to the .docx file.")]
+        string url,
+        [Description("Authorization header value for links that need one, e.g. 'Bearer <token>'. " +
+                     "Only sent to the link's own host, never to redirect targets elsewhere.")]
+        string? auth = null,
+        CancellationToken cancellationToken = default)
+    {
+        try
+        {
+            logger.LogDebug("document_open_url: url={Url}, auth={HasAuth}", url, auth is not null);
+
+            var data = await fetcher.DownloadAsync(url, auth, cancellationToken);
+            var session = tenant.Sessions.OpenFromBytes(data);
+
+            logger.LogDebug("document_open_url result: session={SessionId}, bytes={Bytes}", session.Id, data.Length);
+            return $"Opened document from {url} ({data.Length} bytes). Session ID: {session.Id}";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "opening document"); }
+        catch (HttpRequestException ex) { throw new McpException($"Could not download {url}: {ex.Message}", ex); }
+        catch (OperationCanceledException) when (!cancellationToken.IsCancellationRequested)
+        {
+            throw new McpException($"Timed out downloading {url}.");
+        }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "open_from_cloud_url"), Description(
+        "Open a document from a Google Docs, Google Drive, OneDrive or SharePoint link, as copied from " +
+        "the browser or a sharing dialog. Returns a session ID to use with other tools. " +
+        "The file is downloaded through the tenant's connection to that service (Google Docs are " +
+        "exported to DOCX) and registered as the document's source, so edits are synced back to it. " +
+        "Use list_connections to see which services are connected.")]
+    public static string OpenFromCloudUrl(
+        ILogger<DocumentTools> logger,
+        TenantScope tenant,
+        SyncManager sync,
+        [Description("Link to the document, e.g. https://docs.google.com/document/d/<id>/edit " +
+                     "or a OneDrive/SharePoint sharing link.")]
+        string url,
+        [Description("Connection ID from list_connections. Only needed when several connections " +
+                     "to that service exist.")]
+        string? connection_id = null,
+        CancellationToken cancellationToken = default)
+    {
+        try
+        {
+            logger.LogDebug("open_from_cloud_url: url={Url}, connection_id={ConnId}", url, connection_id);
+
+            var link = CloudLink.Parse(url);
+            connection_id ??= ResolveConnection(tenant, sync, link);
+
+            var data = sync.DownloadFile(tenant.TenantId, link.Type, connection_id, url, link.FileId,
+                cancellationToken);
+            var session = tenant.Sessions.OpenFromBytes(data, url);
+
+            sync.SetSource(tenant.TenantId, session.Id, link.Type, connection_id, url, link.FileId, autoSync: true);
+            tenant.Sessions.SetSourcePath(session.Id, url);
+
+            logger.LogDebug("open_from_cloud_url result: session={SessionId}, type={Type}, file_id={FileId}",
+                session.Id, link.Type, link.FileId);
+            return $"Opened {(link.IsGoogleDoc ? "Google Doc" : $"{link.Provider} document")} ({data.Length} bytes). " +
+                   $"Edits are synced back to it. Session ID: {session.Id}";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"opening {url}"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(url); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// The tenant's only connection to the link's service.
+    /// </summary>
+    private static string ResolveConnection(TenantScope tenant, SyncManager sync, CloudLink link)
+    {
+        var connections = sync.ListConnections(tenant.TenantId, link.Type);
+        return connections.Count switch
+        {
+            0 => throw new ArgumentException(
+                $"No {link.Provider} connection is available. Use list_connections to see the connected services."),
+            1 => connections[0].ConnectionId,
+            _ => throw new ArgumentException(
+                $"Several {link.Provider} connections are available ({string.Join(", ", connections.Select(c => $"{c.ConnectionId}: {c.DisplayName}"))}). " +
+                "Pass connection_id to choose one.")
+        };
+    }
+
     [McpServerTool(Name = "document_set_source"), Description(
         "Set or change where a document will be saved. " +
         "Use this for 'Save As' operations or to set a save target for new documents. " +
@@ -102,7 +217,7 @@ public sealed class DocumentTools
         ILogger<DocumentTools> logger,
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")]
+        [Description("Session ID or alias of the document.")]
         string doc_id,
         [Description("Path (absolute for local, display path for cloud).")]
         string path,
@@ -117,6 +232,7 @@ public sealed class DocumentTools
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             logger.LogDebug("document_set_source: doc_id={DocId}, path={Path}, source_type={SourceType}, connection_id={ConnId}, file_id={FileId}",
                 doc_id, path, source_type, connection_id, file_id);
 
@@ -142,13 +258,15 @@ public sealed class DocumentTools
         ILogger<DocumentTools> logger,
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document to save.")]
+        [Description("Session ID or alias of the document to save.")]
         string doc_id,
         [Description("Path to save the file to. If omitted, saves to the original path.")]
-        string? output_path = null)
+        string? output_path = null,
+        CancellationToken cancellationToken = default)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             logger.LogDebug("document_save: doc_id={DocId}, output_path={OutputPath}", doc_id, output_path);
 
             var sessions = tenant.Sessions;
@@ -173,9 +291,13 @@ public sealed class DocumentTools
             }
 
             var session = sessions.Get(doc_id);
-            sync.Save(tenant.TenantId, doc_id, session.ToBytes());
+            var saved = sync.Save(tenant.TenantId, doc_id, session.ToBytes(), cancellationToken);
 
             var target = output_path ?? session.SourcePath ?? "(unknown)";
+            if (!saved)
+                return $"'{target}' is unreachable. The save is queued and will be retried automatically; " +
+                       "use list_pending_syncs to follow it.";
+
             logger.LogDebug("document_save: saved to {Target}", target);
             return $"Document saved to '{target}'.";
         }
@@ -185,15 +307,55 @@ public sealed class DocumentTools
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
 
+    [McpServerTool(Name = "list_pending_syncs"), Description(
+        "List saves waiting to be retried because their source (e.g. Google Drive) was unreachable. " +
+        "They are retried automatically with backoff and dropped after a maximum age.")]
+    public static string ListPendingSyncs(TenantScope tenant, SyncManager sync)
+    {
+        try
+        {
+            var pending = sync.ListPendingSyncs(tenant.TenantId);
+            if (pending.Count == 0)
+                return "No pending saves.";
+
+            var arr = new JsonArray();
+            foreach (var p in pending)
+            {
+                var obj = new JsonObject
+                {
+                    ["doc_id"] = p.SessionId,
+                    ["path"] = p.Path,
+                    ["attempts"] = p.Attempts,
+                    ["queued_at"] = DateTimeOffset.FromUnixTimeSeconds(p.QueuedAtUnix).ToString("o"),
+                    ["next_attempt_at"] = DateTimeOffset.FromUnixTimeSeconds(p.NextAttemptAtUnix).ToString("o"),
+                    ["last_error"] = p.LastError,
+                    ["size_bytes"] = p.SizeBytes
+                };
+                arr.Add((JsonNode)obj);
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = pending.Count,
+                ["pending"] = arr
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing pending saves"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
     [McpServerTool(Name = "document_list"), Description(
-        "List all currently open document sessions with track changes status.")]
+        "List all currently open document sessions with track changes status, names and aliases.")]
     public static string DocumentList(ILogger<DocumentTools> logger, TenantScope tenant)
     {
         try
         {
             logger.LogDebug("document_list: tenant={TenantId}", tenant.TenantId);
             var sessions = tenant.Sessions;
-            var list = sessions.List();
+            var list = sessions.ListEntries();
             if (list.Count == 0)
                 return "No open documents.";
 
@@ -206,10 +368,17 @@ public sealed class DocumentTools
                 var obj = new JsonObject
                 {
                     ["id"] = s.Id,
-                    ["path"] = s.Path,
+                    ["path"] = s.SourcePath,
                     ["track_changes_enabled"] = stats.TrackChangesEnabled,
                     ["pending_revisions"] = stats.TotalCount
                 };
+                if (s.DisplayName is not null) obj["name"] = s.DisplayName;
+                if (s.Alias is not null) obj["alias"] = s.Alias;
+                if (s.ExpiresAt is { } expiresAt)
+                {
+                    obj["ephemeral"] = true;
+                    obj["expires_at"] = expiresAt.ToUniversalTime().ToString("o");
+                }
                 arr.Add((JsonNode)obj);
             }
 
@@ -226,6 +395,46 @@ public sealed class DocumentTools
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
 
+    [McpServerTool(Name = "document_rename"), Description(
+        "Give a document session a human-friendly name and/or alias. " +
+        "The alias (e.g. 'q3-board') can be used instead of the session ID in every tool; " +
+        "it must be unique and is matched case-insensitively. " +
+        "Omit a field to leave it unchanged; pass an empty string to clear it.")]
+    public static string DocumentRename(
+        ILogger<DocumentTools> logger,
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")]
+        string doc_id,
+        [Description("Display name shown in document_list (e.g. 'Q3 board report').")]
+        string? name = null,
+        [Description("Alias usable in place of the session ID.")]
+        string? alias = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            logger.LogDebug("document_rename: doc_id={DocId}, name={Name}, alias={Alias}", doc_id, name, alias);
+
+            if (name is null && alias is null)
+                throw new McpException("Provide a name and/or an alias.");
+
+            tenant.Sessions.Rename(doc_id, name, alias);
+
+            var entry = tenant.Sessions.ListEntries().FirstOrDefault(e => e.Id == doc_id);
+            var result = new JsonObject
+            {
+                ["id"] = doc_id,
+                ["name"] = entry?.DisplayName,
+                ["alias"] = entry?.Alias
+            };
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "renaming document"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
     /// <summary>
     /// Close a document session and release resources.
     /// WARNING: This operation is intentionally NOT exposed as an MCP tool.
@@ -251,7 +460,7 @@ public sealed class DocumentTools
     /// </summary>
     public static string DocumentSnapshot(
         TenantScope tenant,
-        [Description("Session ID of the document to snapshot.")]
+        [Description("Session ID or alias of the document to snapshot.")]
         string doc_id,
         [Description("If true, discard redo history when compacting. Default false.")]
         bool discard_redo = false)
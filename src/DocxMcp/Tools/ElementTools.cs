@@ -64,28 +64,49 @@ public sealed class ElementTools
         "    {\"type\": \"cell\", \"text\": \"Styled\", \"style\": {\"bold\": true}, \"shading\": \"FFFF00\"}\n\n" +
         "  list:\n" +
         "    {\"type\": \"list\", \"items\": [\"First\", \"Second\", \"Third\"], \"ordered\": false}\n" +
-        "    {\"type\": \"list\", \"items\": [\"Step 1\", \"Step 2\"], \"ordered\": true}\n\n" +
+        "    {\"type\": \"list\", \"items\": [\"Step 1\", \"Step 2\"], \"ordered\": true}\n" +
+        "    {\"type\": \"list\", \"items\": [\n" +
+        "      {\"runs\": [{\"text\": \"Must\", \"style\": {\"bold\": true}}, {\"text\": \" support SSO\"}],\n" +
+        "       \"paragraphs\": [\"Continuation paragraph at the item's indent.\"]},\n" +
+        "      {\"text\": \"Write docs\", \"checked\": false}, {\"text\": \"Ship\", \"checked\": true}]}\n" +
+        "    Items are strings or {text, style} / {runs} objects; \"checked\" adds a ☐/☒ checkbox.\n\n" +
         "  image:\n" +
         "    {\"type\": \"image\", \"path\": \"/absolute/path/to/image.png\", \"width\": 200, \"height\": 100}\n" +
-        "    {\"type\": \"image\", \"path\": \"./relative/image.jpg\"}\n\n" +
+        "    {\"type\": \"image\", \"path\": \"./relative/image.jpg\"}\n" +
+        "    {\"type\": \"image\", \"path\": \"chart.png\", \"alt\": \"Revenue by quarter\", \"title\": \"Revenue\", \"name\": \"Revenue chart\"}\n\n" +
         "  hyperlink:\n" +
-        "    {\"type\": \"hyperlink\", \"text\": \"Click here\", \"url\": \"https://example.com\"}\n\n" +
+        "    {\"type\": \"hyperlink\", \"text\": \"Click here\", \"url\": \"https://example.com\"}\n" +
+        "    {\"type\": \"hyperlink\", \"text\": \"See Pricing\", \"heading\": \"Pricing\", \"tooltip\": \"Jump to pricing\"}\n" +
+        "    {\"type\": \"hyperlink\", \"text\": \"Terms\", \"bookmark\": \"terms\", \"character_style\": \"Strong\"}\n" +
+        "    Exactly one of url, bookmark or heading. character_style defaults to Hyperlink.\n" +
+        "    To link text inside an existing paragraph use add_hyperlink_in_paragraph.\n\n" +
         "  page_break / section_break:\n" +
         "    {\"type\": \"page_break\"}\n" +
-        "    {\"type\": \"section_break\"}\n\n" +
+        "    {\"type\": \"section_break\"}\n" +
+        "    {\"type\": \"section_break\", \"break_type\": \"continuous\", \"orientation\": \"landscape\",\n" +
+        "     \"page_size\": \"a4\", \"margins\": {\"top\": 1440, \"left\": 1080},\n" +
+        "     \"header\": {\"default\": \"Chapter 1\", \"first\": \"\"}, \"footer\": \"Confidential\"}\n" +
+        "    A section break ends the section before it: its options (twips) apply to the content\n" +
+        "    above it, options left out keep that section's current layout, and the content below\n" +
+        "    keeps its page setup, headers and footers.\n\n" +
         "RUN STYLE OPTIONS:\n" +
         "  bold: true/false, italic: true/false, underline: true/false, strike: true/false\n" +
         "  font_size: integer (half-points, e.g., 24 = 12pt)\n" +
         "  font_name: string (e.g., \"Arial\", \"Times New Roman\")\n" +
+        "  font_name_east_asia: string (e.g., \"MS Mincho\")\n" +
         "  color: hex string without # (e.g., \"FF0000\" for red)\n\n" +
         "PARAGRAPH PROPERTIES:\n" +
         "  alignment: \"left\", \"center\", \"right\", \"justify\"\n" +
         "  spacing_before/spacing_after: integer (twips, 1440 = 1 inch)\n" +
+        "  font_name/font_name_east_asia: font of the runs that don't set their own\n" +
         "  tabs: array of tab stop positions\n\n" +
         "RESPONSE FORMAT:\n" +
         "  {\"success\": true, \"applied\": 1, \"total\": 1,\n" +
         "   \"operations\": [{\"op\": \"add\", \"path\": \"...\", \"status\": \"success\", \"created_id\": \"1A2B3C4D\"}]}\n\n" +
         "  The created_id is the stable ID of the new element—save it for future operations.\n\n" +
+        "  With include_structure=true the response also has a \"structure\" object: the top-level\n" +
+        "  body ranges that were added or changed ({start, end, ids}), the removed IDs, and the\n" +
+        "  updated outline ({heading_index, level, text, id, body_index}) — no follow-up read needed.\n\n" +
         "BEST PRACTICES:\n" +
         "  1. Use dry_run=true first to validate your operation\n" +
         "  2. Save the created_id from response for subsequent edits\n" +
@@ -95,14 +116,15 @@ public sealed class ElementTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path where to add the element (e.g., /body/children/0, /body/table[0]/row).")] string path,
         [Description("JSON object describing the element to add.")] string value,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patches = new[] { new AddPatchInput { Path = path, Value = JsonDocument.Parse(value).RootElement } };
         var patchJson = JsonSerializer.Serialize(patches, DocxJsonContext.Default.AddPatchInputArray);
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 
     [McpServerTool(Name = "replace_element"), Description(
@@ -146,14 +168,15 @@ public sealed class ElementTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to the element to replace.")] string path,
         [Description("JSON object describing the new element.")] string value,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patches = new[] { new ReplacePatchInput { Path = path, Value = JsonDocument.Parse(value).RootElement } };
         var patchJson = JsonSerializer.Serialize(patches, DocxJsonContext.Default.ReplacePatchInputArray);
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 
     [McpServerTool(Name = "remove_element"), Description(
@@ -201,12 +224,13 @@ public sealed class ElementTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to the element to remove.")] string path,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patchJson = $$"""[{"op": "remove", "path": "{{EscapeJson(path)}}"}]""";
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 
     [McpServerTool(Name = "move_element"), Description(
@@ -257,13 +281,14 @@ public sealed class ElementTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to the element to move.")] string from,
         [Description("Destination path (use /body/children/N for position).")] string to,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patchJson = $$"""[{"op": "move", "from": "{{EscapeJson(from)}}", "path": "{{EscapeJson(to)}}"}]""";
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 
     [McpServerTool(Name = "copy_element"), Description(
@@ -316,13 +341,14 @@ public sealed class ElementTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to the element to copy.")] string from,
         [Description("Destination path for the copy.")] string to,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patchJson = $$"""[{"op": "copy", "from": "{{EscapeJson(from)}}", "path": "{{EscapeJson(to)}}"}]""";
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 
     private static string EscapeJson(string s) => s.Replace("\\", "\\\\").Replace("\"", "\\\"");
@@ -350,6 +376,8 @@ public sealed class TextTools
         "SEARCH BEHAVIOR:\n" +
         "  - Case-sensitive exact match (\"Hello\" won't match \"hello\")\n" +
         "  - Searches across formatting runs (finds \"bold text\" even if split)\n" +
+        "  - Each run keeps its formatting: text the match and replacement share stays where it is,\n" +
+        "    the changed part takes the formatting of the run it starts in\n" +
         "  - Processes matches in document order (first occurrence first)\n\n" +
         "MAX_COUNT PARAMETER:\n" +
         "  0 = Count only (no replacements made, useful for finding occurrences)\n" +
@@ -387,16 +415,17 @@ public sealed class TextTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to element(s) to search in.")] string path,
         [Description("Text to find (case-sensitive).")] string find,
         [Description("Replacement text (cannot be empty).")] string replace,
         [Description("Maximum number of replacements (default: 1, 0 = none).")] int max_count = 1,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patches = new[] { new ReplaceTextPatchInput { Path = path, Find = find, Replace = replace, MaxCount = max_count } };
         var patchJson = JsonSerializer.Serialize(patches, DocxJsonContext.Default.ReplaceTextPatchInputArray);
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 }
 
@@ -442,12 +471,13 @@ public sealed class TableTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Path to the table.")] string path,
         [Description("Column index to remove (0-based).")] int column,
-        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates the operation without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         var patchJson = $$"""[{"op": "remove_column", "path": "{{path.Replace("\\", "\\\\").Replace("\"", "\\\"")}}", "column": {{column}}}]""";
-        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run);
+        return PatchTool.ApplyPatch(tenant, sync, gate, doc_id, patchJson, dry_run, include_structure);
     }
 }
@@ -0,0 +1,156 @@
+using System.ComponentModel;
+using System.Net.Mail;
+using System.Security.Cryptography;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Email;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class EmailTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    private const string DocxContentType = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+
+    [McpServerTool(Name = "email_document"), Description(
+        "Email a document as an attachment, so it reaches its recipients without being downloaded and forwarded.\n\n" +
+        "Mail goes through the tenant's configured SMTP relay or email API, from the tenant's sender address; " +
+        "recipients may be limited to allowed domains. Every attempt is audited (recipients, subject, " +
+        "attachment size and SHA-256).\n\n" +
+        "Formats:\n" +
+        "  docx — the document as it is now (default)\n" +
+        "  pdf — converted with LibreOffice\n\n" +
+        "Example:\n" +
+        "  email_document(doc_id, \"jane@example.com, legal@example.com\", \"Signed contract\", " +
+        "\"Please find the contract attached.\", format=\"pdf\")")]
+    public static async Task<string> EmailDocument(
+        TenantScope tenant,
+        EmailOptions options,
+        EmailAuditLog audit,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Recipient addresses, comma-separated.")] string to,
+        [Description("Subject line.")] string subject,
+        [Description("Plain-text message body.")] string body,
+        [Description("Attachment format: docx or pdf. Default: docx.")] string format = "docx",
+        [Description("Attachment file name without extension. Default: the document's file name.")] string? file_name = null,
+        CancellationToken cancellationToken = default)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            tenant.Features.Require(FeatureFlags.Email);
+
+            var settings = options.ForTenant(tenant.TenantId);
+            if (settings is null)
+                return "Error: Email is not configured for this tenant.";
+
+            IEmailSender sender = settings.Provider switch
+            {
+                "smtp" => new SmtpEmailSender(settings, options.Timeout),
+                "http" => new HttpEmailSender(settings, options.Timeout),
+                var p => throw new McpException($"Unknown email provider '{p}'. Use 'smtp' or 'http'.")
+            };
+
+            return await Send(tenant, settings, sender, audit, doc_id, to, subject, body, format, file_name,
+                cancellationToken);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"emailing '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Render the attachment, send it with <paramref name="sender"/> and audit the attempt.
+    /// </summary>
+    internal static async Task<string> Send(
+        TenantScope tenant, EmailSettings settings, IEmailSender sender, EmailAuditLog audit,
+        string doc_id, string to, string subject, string body, string format, string? file_name,
+        CancellationToken cancellationToken)
+    {
+        var recipients = new List<string>();
+        foreach (var item in to.Split([',', ';'], StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries))
+        {
+            if (!MailAddress.TryCreate(item, out var address))
+                return $"Error: '{item}' is not a valid email address.";
+            if (!settings.AllowsRecipient(address.Address))
+                return $"Error: Mail to '{address.Address}' is not allowed for this tenant.";
+            recipients.Add(address.Address);
+        }
+        if (recipients.Count == 0)
+            return "Error: No recipient given.";
+        if (string.IsNullOrWhiteSpace(subject))
+            return "Error: The subject is empty.";
+
+        format = format.ToLowerInvariant();
+        if (format is not ("docx" or "pdf"))
+            return $"Error: Unknown format '{format}'. Use 'docx' or 'pdf'.";
+
+        var session = tenant.Sessions.Get(doc_id);
+        byte[] content;
+        if (format == "pdf")
+        {
+            tenant.Features.Require(FeatureFlags.PdfExport);
+            content = await ExportTools.RenderPdf(session, cancellationToken);
+        }
+        else
+        {
+            content = session.ToBytes();
+        }
+
+        if (content.LongLength > settings.MaxAttachmentBytes)
+            return $"Error: The {format} attachment is {content.LongLength} bytes, " +
+                   $"more than the {settings.MaxAttachmentBytes} bytes allowed.";
+
+        var name = AttachmentName(file_name ?? Path.GetFileNameWithoutExtension(session.SourcePath) ?? doc_id);
+        var fileName = $"{name}.{format}";
+        var message = new EmailMessage(
+            settings.From!, recipients, subject, body,
+            new EmailAttachment(fileName, format == "pdf" ? "application/pdf" : DocxContentType, content));
+
+        string? messageId = null;
+        string? error = null;
+        try
+        {
+            messageId = await sender.SendAsync(message, cancellationToken);
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            error = ex.Message;
+        }
+
+        audit.Record(new EmailAuditEntry(
+            DateTime.UtcNow, tenant.TenantId, doc_id, recipients, subject, format, fileName,
+            content.LongLength, Convert.ToHexString(SHA256.HashData(content)).ToLowerInvariant(), sender.Name,
+            error is null, messageId, error));
+
+        if (error is not null)
+            throw new McpException($"Sending the email failed: {error}");
+
+        var result = new JsonObject
+        {
+            ["sent"] = true,
+            ["to"] = new JsonArray(recipients.Select(r => (JsonNode)JsonValue.Create(r)!).ToArray()),
+            ["file_name"] = fileName,
+            ["bytes"] = content.LongLength,
+            ["provider"] = sender.Name
+        };
+        if (messageId is not null)
+            result["message_id"] = messageId;
+        return result.ToJsonString(JsonOpts);
+    }
+
+    /// <summary>A file name safe for a MIME header, without path separators.</summary>
+    internal static string AttachmentName(string name)
+    {
+        var invalid = Path.GetInvalidFileNameChars().Concat(['/', '\\', '"', '\r', '\n']).ToHashSet();
+        var cleaned = new string(name.Select(c => invalid.Contains(c) ? '_' : c).ToArray()).Trim();
+        return cleaned.Length == 0 ? "document" : cleaned;
+    }
+}
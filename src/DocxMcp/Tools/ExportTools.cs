@@ -1,6 +1,8 @@
 using System.ComponentModel;
 using System.Diagnostics;
 using System.Text;
+using System.Text.Json;
+using System.Text.Json.Nodes;
 using DocumentFormat.OpenXml.Packaging;
 using DocumentFormat.OpenXml.Wordprocessing;
 using Grpc.Core;
@@ -13,6 +15,8 @@ namespace DocxMcp.Tools;
 [McpServerToolType]
 public sealed class ExportTools
 {
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
     [McpServerTool(Name = "export"), Description(
         "Export a document to another format. Returns the content as text (html, markdown) " +
         "or as base64-encoded binary (pdf, docx).\n\n" +
@@ -20,25 +24,54 @@ public sealed class ExportTools
         "  html — returns HTML string\n" +
         "  markdown — returns Markdown string\n" +
         "  pdf — returns base64-encoded PDF (requires LibreOffice on the server)\n" +
-        "  docx — returns base64-encoded DOCX bytes")]
+        "  docx — returns base64-encoded DOCX bytes\n\n" +
+        "Set background=true for slow exports (large documents, pdf): the call returns an operation ID " +
+        "right away, and the export runs on a snapshot of the document. " +
+        "Poll it with get_operation (the result holds the exported content) or stop it with cancel_operation.")]
     public static async Task<string> Export(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
-        [Description("Export format: html, markdown, pdf, docx.")] string format)
+        OperationManager operations,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Export format: html, markdown, pdf, docx.")] string format,
+        [Description("Run as a background operation and return its ID. Default: false.")] bool background = false)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
-
-            return format.ToLowerInvariant() switch
+            var normalized = format.ToLowerInvariant() switch
             {
-                "html" => ExportHtml(session),
-                "markdown" or "md" => ExportMarkdown(session),
-                "pdf" => await ExportPdf(session),
-                "docx" => ExportDocx(session),
-                _ => throw new McpException(
-                    $"Unknown export format '{format}'. Supported: html, markdown, pdf, docx."),
+                "md" => "markdown",
+                var f => f
             };
+
+            if (normalized is not ("html" or "markdown" or "pdf" or "docx"))
+                throw new McpException(
+                    $"Unknown export format '{format}'. Supported: html, markdown, pdf, docx.");
+            if (normalized == "pdf")
+                tenant.Features.Require(FeatureFlags.PdfExport);
+
+            if (!background)
+                return await ExportAs(session, normalized, CancellationToken.None);
+
+            // Export a snapshot so edits made meanwhile don't race with the export
+            var snapshot = session.ToBytes();
+            var sourcePath = session.SourcePath;
+            var operation = await operations.StartAsync(
+                tenant.TenantId, $"export_{normalized}", $"Export {doc_id} to {normalized}", doc_id,
+                async ctx =>
+                {
+                    using var copy = DocxSession.FromBytes(snapshot, doc_id, sourcePath);
+                    await ctx.ReportAsync(0.1, $"Exporting to {normalized}");
+                    return await ExportAs(copy, normalized, ctx.CancellationToken);
+                });
+
+            return new JsonObject
+            {
+                ["operation_id"] = operation.Id,
+                ["state"] = "running",
+                ["message"] = $"Export to {normalized} started. Use get_operation to follow it."
+            }.ToJsonString();
         }
         catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"exporting '{doc_id}' to {format}"); }
         catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
@@ -46,6 +79,139 @@ public sealed class ExportTools
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
 
+    [McpServerTool(Name = "export_table"), Description(
+        "Export one table as CSV or JSON, for data pipelines.\n\n" +
+        "Merged cells are spread over the grid positions they cover: with merged='repeat' (default) every " +
+        "covered position repeats the merged cell's text, with merged='blank' only the top-left one has it. " +
+        "Paragraphs inside a cell are joined with newlines.\n\n" +
+        "Formats:\n" +
+        "  csv — RFC 4180 CSV, one line per row\n" +
+        "  json — with header=true, an array of objects keyed by the first row's texts; " +
+        "otherwise an array of row arrays\n\n" +
+        "Example:\n" +
+        "  export_table(doc_id, 0, \"json\")")]
+    public static string ExportTable(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("0-based index of the table in the body (-1 for the last one).")] int table_index,
+        [Description("Output format: csv or json. Default: csv.")] string format = "csv",
+        [Description("Treat the first row as column names (json only). Default: true.")] bool header = true,
+        [Description("How merged cells are exported: repeat or blank. Default: repeat.")] string merged = "repeat")
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            format = format.ToLowerInvariant();
+            if (format is not ("csv" or "json"))
+                return $"Error: Unknown format '{format}'. Use 'csv' or 'json'.";
+
+            var tables = session.GetBody().Elements<Table>().ToList();
+            var index = table_index < 0 ? tables.Count + table_index : table_index;
+            if (index < 0 || index >= tables.Count)
+                return $"Error: Table {table_index} not found; the document has {tables.Count} table(s).";
+
+            List<List<string>> grid;
+            try
+            {
+                grid = TableExportHelper.ToGrid(tables[index], merged);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            return format == "csv"
+                ? TableExportHelper.ToCsv(grid)
+                : TableExportHelper.ToJson(grid, header).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"exporting a table of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "export_images"), Description(
+        "Extract all images of a document, with a manifest.\n\n" +
+        "Images are saved once each, named by order of first appearance (image-001.png, image-002.jpeg, ...), " +
+        "along with manifest.json: one entry per place an image is shown, in document order, with its " +
+        "index, file, content type, SHA-256, pixel and displayed size, alt text and title, the part " +
+        "(body, header1, footer1, ...) and the heading of the section it is in. Images stored in the " +
+        "document but not shown anywhere are listed last, without a part.\n\n" +
+        "output is a directory (created if needed) or, when it ends with .zip, a ZIP archive.")]
+    public static string ExportImages(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Directory to write the images into, or a .zip file path.")] string output)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            if (string.IsNullOrWhiteSpace(output))
+                return "Error: output must be a directory or a .zip path.";
+
+            var (manifest, files) = ImageExportHelper.Collect(session.Document);
+            if (manifest.Count == 0)
+                return "Error: The document has no images.";
+
+            ImageExportHelper.Write(output, manifest, files);
+
+            return new JsonObject
+            {
+                ["output"] = output,
+                ["files"] = files.Count,
+                ["images"] = manifest.Count,
+                ["manifest"] = ImageExportHelper.ManifestJson(manifest)
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"exporting images of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "export_pptx_outline"), Description(
+        "Turn a document into a PPTX slide skeleton. Returns base64-encoded PPTX bytes.\n\n" +
+        "Each Heading 1 or Heading 2 becomes a slide titled with the heading. Its bullets are the list items " +
+        "and lower headings under it; a section without any gets the opening sentences of its paragraphs. " +
+        "A title slide is added when the document has a title (title property or Title-styled paragraph). " +
+        "The deck uses a plain layout, meant to be restyled in PowerPoint.")]
+    public static string ExportPptxOutline(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Most bullets per slide. Default: 6.")] int max_bullets = 6)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var slides = PptxOutlineHelper.BuildOutline(session.Document, Math.Clamp(max_bullets, 1, 20));
+            if (slides.Count == 0)
+                return "Error: The document has no Heading 1 or Heading 2 to make slides from.";
+
+            var deck = PptxOutlineHelper.CreateDeck(PptxOutlineHelper.DocumentTitle(session.Document), slides);
+            return Convert.ToBase64String(deck);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"exporting '{doc_id}' to pptx"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Export to a normalized format: html and markdown as text, pdf and docx as base64.
+    /// </summary>
+    internal static async Task<string> ExportAs(DocxSession session, string format, CancellationToken ct) => format switch
+    {
+        "html" => ExportHtml(session),
+        "markdown" => ExportMarkdown(session),
+        "pdf" => await ExportPdf(session, ct),
+        _ => ExportDocx(session),
+    };
+
     private static string ExportHtml(DocxSession session)
     {
         var body = session.GetBody();
@@ -98,9 +264,16 @@ public sealed class ExportTools
         return sb.ToString();
     }
 
-    private static async Task<string> ExportPdf(DocxSession session)
+    private static async Task<string> ExportPdf(DocxSession session, CancellationToken ct) =>
+        Convert.ToBase64String(await RenderPdf(session, ct));
+
+    /// <summary>
+    /// Convert the document to PDF with LibreOffice.
+    /// </summary>
+    internal static async Task<byte[]> RenderPdf(DocxSession session, CancellationToken ct)
     {
-        var tempDocx = Path.Combine(Path.GetTempPath(), $"docx-mcp-{session.Id}.docx");
+        // Unique name: a background export may run alongside a foreground one
+        var tempDocx = Path.Combine(Path.GetTempPath(), $"docx-mcp-{session.Id}-{Guid.NewGuid():N}.docx");
         var tempDir = Path.GetTempPath();
         try
         {
@@ -124,7 +297,15 @@ public sealed class ExportTools
             using var process = Process.Start(psi)
                 ?? throw new McpException("Failed to start LibreOffice.");
 
-            await process.WaitForExitAsync();
+            try
+            {
+                await process.WaitForExitAsync(ct);
+            }
+            catch (OperationCanceledException)
+            {
+                process.Kill(entireProcessTree: true);
+                throw;
+            }
 
             if (process.ExitCode != 0)
             {
@@ -141,7 +322,7 @@ public sealed class ExportTools
             var pdfBytes = await File.ReadAllBytesAsync(generatedPdf);
             File.Delete(generatedPdf);
 
-            return Convert.ToBase64String(pdfBytes);
+            return pdfBytes;
         }
         finally
         {
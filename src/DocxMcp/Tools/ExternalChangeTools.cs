@@ -33,13 +33,14 @@ public sealed class ExternalChangeTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID to check for external changes.")]
+        [Description("Session ID or alias to check for external changes.")]
         string doc_id,
         [Description("Set to true to acknowledge the changes and allow editing to continue.")]
         bool acknowledge = false)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var pending = gate.CheckForChanges(tenant.TenantId, tenant.Sessions, doc_id, sync);
 
             // No changes detected
@@ -111,11 +112,12 @@ public sealed class ExternalChangeTools
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID to sync.")]
+        [Description("Session ID or alias to sync.")]
         string doc_id)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var syncResult = PerformSync(tenant.Sessions, doc_id, isImport: false,
                 tenantId: tenant.TenantId, sync: sync);
 
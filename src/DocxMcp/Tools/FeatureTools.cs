@@ -0,0 +1,73 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class FeatureTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "get_unsupported_features"), Description(
+        "Report the content of a document this server keeps but can't model, and where it is — so you know " +
+        "which edits are safe before touching a document from Word.\n\n" +
+        "FEATURE KINDS:\n" +
+        "  equation        — Office Math (m:oMath)\n" +
+        "  smartart        — SmartArt diagram\n" +
+        "  chart           — chart with its embedded workbook\n" +
+        "  embedded_object — OLE object, e.g. an embedded spreadsheet (detail: its ProgID)\n" +
+        "  tracked_change  — pending revision (detail: ins, del, moveFrom, moveTo, rPrChange, pPrChange)\n\n" +
+        "All of it is preserved on save. Each location gives the part (body, header1, footer1, ...) and, in the " +
+        "body, the path of the paragraph or table holding it. Editing the text around such content with " +
+        "replace_text or adding elements next to it is safe. replace_element refuses to replace an element " +
+        "holding it, since the new element couldn't carry it over; pending revisions are only protected that " +
+        "way while track changes is off.")]
+    public static string GetUnsupportedFeatures(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var features = UnsupportedFeatureHelper.Report(session.Document);
+
+            var arr = new JsonArray();
+            foreach (var feature in features)
+            {
+                var locations = new JsonArray();
+                foreach (var location in feature.Locations)
+                {
+                    var obj = new JsonObject { ["part"] = location.Part, ["path"] = location.Path };
+                    if (location.Detail is not null)
+                        obj["detail"] = location.Detail;
+                    locations.Add((JsonNode)obj);
+                }
+
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["kind"] = feature.Kind,
+                    ["count"] = feature.Locations.Count,
+                    ["handling"] = feature.Handling,
+                    ["locations"] = locations
+                });
+            }
+
+            return new JsonObject
+            {
+                ["fully_supported"] = features.Count == 0,
+                ["features"] = arr
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"reporting unsupported features of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
@@ -0,0 +1,132 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class FieldTools
+{
+    [McpServerTool(Name = "insert_field"), Description(
+        "Append an auto-updating field to the end of a paragraph (body, header or footer).\n\n" +
+        "KINDS:\n" +
+        "  date       — today's date (DATE)\n" +
+        "  time       — the current time (TIME)\n" +
+        "  savedate   — when the document was last saved (SAVEDATE)\n" +
+        "  createdate — when the document was created (CREATEDATE)\n" +
+        "  filename   — the document's file name (FILENAME); format \"path\" includes the folder\n" +
+        "  toc        — a table of contents of the headings (TOC); format is the range of levels, default \"1-3\". " +
+        "Word builds it when the document is opened\n\n" +
+        "format is a Word date picture: d, dd, ddd, dddd, M, MM, MMM, MMMM, yy, yyyy, H, HH, h, hh, mm, ss, AM/PM " +
+        "(e.g. \"d MMMM yyyy\", \"HH:mm\"). Without one, the locale's short date or time pattern is used.\n" +
+        "locale (e.g. \"fr-FR\", \"ja-JP\") sets the language of month and day names; it defaults to the " +
+        "document's default language, then en-US.\n\n" +
+        "The field's current value is written as its cached result, so viewers that don't update fields " +
+        "still show it; Word refreshes the value when fields are updated.\n\n" +
+        "Examples:\n" +
+        "  insert_field(doc_id, \"/body/paragraph[0]\", \"date\", format=\"d MMMM yyyy\", locale=\"fr-FR\")\n" +
+        "  insert_field(doc_id, \"/footer/paragraph[0]\", \"filename\")")]
+    public static string InsertField(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Typed path to the paragraph (must resolve to exactly 1 paragraph).")] string path,
+        [Description("Field kind: date, time, savedate, createdate, filename or toc.")] string kind,
+        [Description("Word date picture, \"path\" for filename, or heading levels for toc.")] string? format = null,
+        [Description("Locale of the field, e.g. fr-FR. Default: the document's language.")] string? locale = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            FieldSpec field;
+            try
+            {
+                field = FieldHelper.Create(session.Document, kind, format, locale, session.SourcePath, DateTime.Now);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            // The cached result goes to the WAL so replay gives the same document
+            var walObj = new JsonObject
+            {
+                ["op"] = "insert_field",
+                ["path"] = path,
+                ["instruction"] = field.Instruction,
+                ["result"] = field.Result,
+                ["language"] = field.Language
+            };
+            var patch = JsonDocument.Parse(walObj.ToJsonString()).RootElement;
+
+            try
+            {
+                Apply(patch, session.Document);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return $"Field {field.Instruction} inserted in {path} (shows '{field.Result}').";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"inserting field in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an insert_field WAL operation.
+    /// </summary>
+    internal static void ReplayInsertField(JsonElement patch, WordprocessingDocument doc)
+    {
+        Apply(patch, doc);
+    }
+
+    private static void Apply(JsonElement patch, WordprocessingDocument doc)
+    {
+        var path = patch.GetProperty("path").GetString()
+            ?? throw new InvalidOperationException("insert_field must have a 'path' field.");
+
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements.Count != 1 || elements[0] is not Paragraph paragraph)
+            throw new InvalidOperationException($"Path '{path}' must resolve to exactly 1 paragraph.");
+
+        var field = new FieldSpec(
+            patch.GetProperty("instruction").GetString() ?? "",
+            patch.GetProperty("result").GetString() ?? "",
+            patch.GetProperty("language").GetString() ?? "en-US");
+        foreach (var run in FieldHelper.CreateRuns(field))
+            paragraph.AppendChild(run);
+
+        // A table of contents is only built by Word: ask it to update fields on open
+        if (field.Instruction.StartsWith("TOC", StringComparison.Ordinal))
+            HeadingHelper.RefreshTableOfContents(doc);
+    }
+}
@@ -0,0 +1,555 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class HeadingTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "promote_heading"), Description(
+        "Promote headings one level (Heading3 → Heading2), together with the headings nested under them " +
+        "so the outline keeps its shape. Changes the HeadingN style; text and formatting are untouched.\n\n" +
+        "Target the headings with 'path' (may match several, e.g. /body/heading[level=3]), or a range of " +
+        "top-level body elements with 'path' and 'end_path'. Fails without changes if a heading is already Heading1.\n\n" +
+        "Examples:\n" +
+        "  promote_heading(doc_id, \"/body/heading[text~='Appendix']\")\n" +
+        "  promote_heading(doc_id, \"/body/paragraph[id='1A2B3C4D']\", end_path=\"/body/paragraph[id='5E6F7A8B']\")")]
+    public static string PromoteHeading(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Path to the heading(s), or to the first element of a range.")] string path,
+        [Description("Optional path to the last element of the range.")] string? end_path = null,
+        [Description("Also move the headings nested under each target. Default: true.")] bool with_subheadings = true) =>
+        ShiftHeadings(tenant, sync, gate, doc_id, path, end_path, -1, with_subheadings);
+
+    [McpServerTool(Name = "demote_heading"), Description(
+        "Demote headings one level (Heading2 → Heading3), together with the headings nested under them " +
+        "so the outline keeps its shape. Changes the HeadingN style; text and formatting are untouched.\n\n" +
+        "Target the headings with 'path' (may match several), or a range of top-level body elements with " +
+        "'path' and 'end_path'. Fails without changes if a heading would go below Heading9.\n\n" +
+        "Example:\n" +
+        "  demote_heading(doc_id, \"/body/heading[text~='Background']\")")]
+    public static string DemoteHeading(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Path to the heading(s), or to the first element of a range.")] string path,
+        [Description("Optional path to the last element of the range.")] string? end_path = null,
+        [Description("Also move the headings nested under each target. Default: true.")] bool with_subheadings = true) =>
+        ShiftHeadings(tenant, sync, gate, doc_id, path, end_path, 1, with_subheadings);
+
+    [McpServerTool(Name = "normalize_heading_levels"), Description(
+        "Fix skipped heading levels: each heading becomes at most one level below the heading it falls under " +
+        "(Heading1 followed by Heading3 becomes Heading1, Heading2), and the outermost headings become Heading1. " +
+        "Relative nesting is kept. If the document has a table of contents, Word refreshes it on open.\n\n" +
+        "Pass dry_run=true to list the changes without applying them.")]
+    public static string NormalizeHeadingLevels(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("List the changes without applying them. Default: false.")] bool dry_run = false)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (!dry_run && gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var body = session.GetBody();
+
+            var changes = HeadingHelper.Normalize(body);
+            if (!dry_run)
+                Commit(tenant, sync, session, doc_id, changes);
+
+            return ChangesToJson(changes, dry_run).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"normalizing heading levels in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "apply_inferred_headings"), Description(
+        "Turn paragraphs formatted to look like headings (larger or bold text in the Normal style, typical of " +
+        "imported documents) into real Heading1-9 paragraphs, so outlines, sections and the table of contents see them.\n\n" +
+        "A paragraph is taken for a heading when it is short, doesn't end with punctuation, and has a larger font " +
+        "than the body text, is all bold, or starts with an outline number (\"2.\", \"2.3\", \"IV.\"). Numbered " +
+        "paragraphs get the level of their numbering depth; the others one level per distinct look, largest first. " +
+        "Missing Heading styles are added; direct formatting is kept, so the document looks the same.\n\n" +
+        "Pass dry_run=true to list the proposed headings first, then exclude the false positives by ID.")]
+    public static string ApplyInferredHeadings(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("List the proposed headings without applying them. Default: false.")] bool dry_run = false,
+        [Description("Element IDs of proposed headings to leave as they are, comma-separated.")] string? exclude = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (!dry_run && gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var excluded = (exclude ?? "")
+                .Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries)
+                .ToHashSet(StringComparer.OrdinalIgnoreCase);
+
+            var inference = HeadingInferenceHelper.Infer(session.Document);
+            var headings = inference.Headings.Where(h => !excluded.Contains(h.Id)).ToList();
+
+            if (!dry_run && headings.Count > 0)
+            {
+                HeadingInferenceHelper.Apply(session.Document, headings.Select(h => (h.Id, h.Level)).ToList());
+
+                var arr = new JsonArray();
+                foreach (var heading in headings)
+                    arr.Add((JsonNode)new JsonObject { ["id"] = heading.Id, ["level"] = heading.Level });
+                AppendWal(tenant, sync, session, doc_id, new JsonObject
+                {
+                    ["op"] = "apply_inferred_headings",
+                    ["headings"] = arr
+                });
+            }
+
+            var proposed = new JsonArray();
+            foreach (var heading in headings)
+            {
+                proposed.Add((JsonNode)new JsonObject
+                {
+                    ["path"] = $"/body/paragraph[id='{heading.Id}']",
+                    ["text"] = heading.Text,
+                    ["level"] = heading.Level,
+                    ["reason"] = heading.Reason
+                });
+            }
+
+            return new JsonObject
+            {
+                ["dry_run"] = dry_run,
+                ["body_font_size"] = inference.BodyFontSize,
+                ["changed"] = dry_run ? 0 : headings.Count,
+                ["headings"] = proposed
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"inferring headings in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "rename_heading"), Description(
+        "Replace the text of a heading, keeping its style and the formatting of its first run. " +
+        "Bookmarks on the heading stay, so internal links still point to it. " +
+        "If the document has a table of contents, Word refreshes it on open.\n\n" +
+        "heading_index is the zero-based index among all headings, as listed by read_heading_content.\n\n" +
+        "Example:\n" +
+        "  rename_heading(doc_id, 2, \"Pricing and Payment\")")]
+    public static string RenameHeading(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Zero-based index of the heading among all headings.")] int heading_index,
+        [Description("New heading text.")] string new_text)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var headings = Headings(session.GetBody());
+            if (heading_index < 0 || heading_index >= headings.Count)
+                return $"Error: Heading index {heading_index} out of range ({headings.Count} headings).";
+
+            var heading = headings[heading_index];
+            var id = ElementIdManager.GetId(heading);
+            if (id is null)
+                return $"Error: Heading {heading_index} has no element ID.";
+            var oldText = heading.InnerText;
+
+            HeadingHelper.Rename(session.Document, heading, new_text);
+            AppendWal(tenant, sync, session, doc_id, new JsonObject
+            {
+                ["op"] = "rename_heading",
+                ["id"] = id,
+                ["text"] = new_text
+            });
+
+            return $"Heading {heading_index} renamed from '{oldText}' to '{new_text}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"renaming heading in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "move_section"), Description(
+        "Move a heading together with all the content under it (up to the next heading of the same or " +
+        "a higher level, so sub-sections come along) to another place in the outline. " +
+        "Numbered headings and lists renumber themselves; if the document has a table of contents, " +
+        "Word refreshes it on open.\n\n" +
+        "heading_index and target_position are zero-based indexes among all headings, as listed by " +
+        "read_heading_content, taken before the move. The section is placed in front of the heading at " +
+        "target_position; pass the number of headings to move it to the end of the document.\n\n" +
+        "Examples:\n" +
+        "  move_section(doc_id, 4, 1)   — section of heading 4 now comes before heading 1\n" +
+        "  move_section(doc_id, 0, 7)   — with 7 headings, section of heading 0 moves to the end")]
+    public static string MoveSection(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Zero-based index of the heading whose section moves.")] int heading_index,
+        [Description("Zero-based index of the heading to move the section in front of, " +
+                     "or the number of headings to move it to the end.")] int target_position)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var headings = Headings(session.GetBody());
+            if (heading_index < 0 || heading_index >= headings.Count)
+                return $"Error: Heading index {heading_index} out of range ({headings.Count} headings).";
+            if (target_position < 0 || target_position > headings.Count)
+                return $"Error: Target position {target_position} out of range (0-{headings.Count}).";
+
+            var heading = headings[heading_index];
+            var id = ElementIdManager.GetId(heading);
+            var beforeId = target_position < headings.Count ? ElementIdManager.GetId(headings[target_position]) : null;
+            if (id is null || (target_position < headings.Count && beforeId is null))
+                return "Error: Headings must have element IDs.";
+
+            var section = HeadingHelper.SectionOf(session.GetBody(), heading);
+            try
+            {
+                HeadingHelper.MoveSection(session.Document, id, beforeId);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            AppendWal(tenant, sync, session, doc_id, new JsonObject
+            {
+                ["op"] = "move_section",
+                ["id"] = id,
+                ["before"] = beforeId
+            });
+
+            var newIndex = Headings(session.GetBody()).IndexOf(heading);
+            return $"Section '{heading.InnerText}' ({section.Count} elements) moved to heading position {newIndex}.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"moving section in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "get_anchor_links"), Description(
+        "List a deep-link identifier for every heading, so other systems can send users straight to a " +
+        "section: the link is '<doc_id>#<bookmark>', and the bookmark is in the document itself, so " +
+        "'report.docx#<bookmark>' links opened in Word jump to the heading too.\n\n" +
+        "Headings without a bookmark get one (a hidden _HeadingN bookmark), which is saved with the document. " +
+        "Bookmarks stay with their heading through edits, renames and moves, so links keep working.")]
+    public static string GetAnchorLinks(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            var body = session.GetBody();
+
+            var missing = Headings(body).Any(p => HeadingHelper.BookmarkOf(p) is null);
+            if (missing && gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var anchors = HeadingHelper.EnsureAnchors(body);
+
+            var added = new JsonArray();
+            foreach (var anchor in anchors.Where(a => a.Added && a.Id is not null))
+                added.Add((JsonNode)new JsonObject { ["id"] = anchor.Id, ["bookmark"] = anchor.Bookmark });
+            if (added.Count > 0)
+            {
+                AppendWal(tenant, sync, session, doc_id, new JsonObject
+                {
+                    ["op"] = "add_heading_bookmarks",
+                    ["bookmarks"] = added
+                });
+            }
+
+            var links = new JsonArray();
+            foreach (var anchor in anchors)
+            {
+                links.Add((JsonNode)new JsonObject
+                {
+                    ["text"] = anchor.Text,
+                    ["level"] = anchor.Level,
+                    ["bookmark"] = anchor.Bookmark,
+                    ["link"] = $"{doc_id}#{anchor.Bookmark}",
+                    ["path"] = anchor.Id is null ? null : $"/body/paragraph[id='{anchor.Id}']"
+                });
+            }
+
+            return new JsonObject
+            {
+                ["doc_id"] = doc_id,
+                ["added_bookmarks"] = added.Count,
+                ["anchors"] = links
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"listing anchor links of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    private static string ShiftHeadings(
+        TenantScope tenant, SyncManager sync, ExternalChangeGate gate,
+        string doc_id, string path, string? endPath, int delta, bool withSubheadings)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var body = session.GetBody();
+
+            List<HeadingChange> changes;
+            try
+            {
+                var targets = ResolveTargets(session.Document, body, path, endPath);
+                if (targets.Count == 0)
+                    return $"Error: No heading found at '{path}'{(endPath is null ? "" : $" through '{endPath}'")}.";
+                changes = HeadingHelper.Shift(body, targets, delta, withSubheadings);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            Commit(tenant, sync, session, doc_id, changes);
+            return ChangesToJson(changes, false).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"changing heading levels in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a set_heading_levels WAL operation.
+    /// </summary>
+    internal static void ReplaySetHeadingLevels(JsonElement patch, WordprocessingDocument doc)
+    {
+        var changes = new List<HeadingChange>();
+        foreach (var change in patch.GetProperty("changes").EnumerateArray())
+        {
+            changes.Add(new HeadingChange(
+                change.GetProperty("id").GetString() ?? "", "",
+                change.GetProperty("from").GetInt32(),
+                change.GetProperty("to").GetInt32()));
+        }
+        HeadingHelper.Apply(doc, changes);
+    }
+
+    /// <summary>
+    /// Replay an apply_inferred_headings WAL operation.
+    /// </summary>
+    internal static void ReplayApplyInferredHeadings(JsonElement patch, WordprocessingDocument doc)
+    {
+        var headings = new List<(string Id, int Level)>();
+        foreach (var heading in patch.GetProperty("headings").EnumerateArray())
+            headings.Add((heading.GetProperty("id").GetString() ?? "", heading.GetProperty("level").GetInt32()));
+        HeadingInferenceHelper.Apply(doc, headings);
+    }
+
+    /// <summary>
+    /// Replay a rename_heading WAL operation.
+    /// </summary>
+    internal static void ReplayRenameHeading(JsonElement patch, WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        var id = patch.GetProperty("id").GetString();
+        var heading = Headings(body).FirstOrDefault(p =>
+            string.Equals(ElementIdManager.GetId(p), id, StringComparison.OrdinalIgnoreCase));
+        if (heading is null) return;
+        HeadingHelper.Rename(doc, heading, patch.GetProperty("text").GetString() ?? "");
+    }
+
+    /// <summary>
+    /// Replay a move_section WAL operation.
+    /// </summary>
+    internal static void ReplayMoveSection(JsonElement patch, WordprocessingDocument doc)
+    {
+        var before = patch.TryGetProperty("before", out var b) && b.ValueKind == JsonValueKind.String
+            ? b.GetString()
+            : null;
+        HeadingHelper.MoveSection(doc, patch.GetProperty("id").GetString() ?? "", before);
+    }
+
+    /// <summary>
+    /// Replay an add_heading_bookmarks WAL operation.
+    /// </summary>
+    internal static void ReplayAddHeadingBookmarks(JsonElement patch, WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+        foreach (var entry in patch.GetProperty("bookmarks").EnumerateArray())
+        {
+            var id = entry.GetProperty("id").GetString();
+            var heading = Headings(body).FirstOrDefault(p =>
+                string.Equals(ElementIdManager.GetId(p), id, StringComparison.OrdinalIgnoreCase));
+            if (heading is not null)
+                HeadingHelper.AddBookmark(body, heading, entry.GetProperty("bookmark").GetString() ?? "");
+        }
+    }
+
+    private static List<Paragraph> Headings(Body body) =>
+        body.Elements<Paragraph>().Where(p => p.IsHeading()).ToList();
+
+    /// <summary>
+    /// Headings at <paramref name="path"/>, or the headings among the top-level
+    /// body elements from <paramref name="path"/> through <paramref name="endPath"/>.
+    /// </summary>
+    private static List<Paragraph> ResolveTargets(WordprocessingDocument doc, Body body, string path, string? endPath)
+    {
+        if (endPath is null)
+        {
+            return PathResolver.Resolve(DocxPath.Parse(path), doc)
+                .OfType<Paragraph>()
+                .Where(p => p.Parent == body && p.GetHeadingLevel() > 0)
+                .ToList();
+        }
+
+        var children = body.ChildElements.ToList();
+        var from = children.IndexOf(ResolveBodyChild(doc, body, path));
+        var to = children.IndexOf(ResolveBodyChild(doc, body, endPath));
+        if (from > to)
+            throw new ArgumentException($"end_path '{endPath}' comes before path '{path}'.");
+
+        return children.GetRange(from, to - from + 1)
+            .OfType<Paragraph>()
+            .Where(p => p.GetHeadingLevel() > 0)
+            .ToList();
+    }
+
+    private static OpenXmlElement ResolveBodyChild(WordprocessingDocument doc, Body body, string path)
+    {
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements.Count != 1)
+            throw new ArgumentException($"Path '{path}' must resolve to exactly 1 element, got {elements.Count}.");
+        if (elements[0].Parent != body)
+            throw new ArgumentException($"Path '{path}' must point to a top-level body element.");
+        return elements[0];
+    }
+
+    private static void Commit(TenantScope tenant, SyncManager sync, DocxSession session, string doc_id,
+        List<HeadingChange> changes)
+    {
+        if (changes.Count == 0) return;
+
+        HeadingHelper.Apply(session.Document, changes);
+
+        var arr = new JsonArray();
+        foreach (var change in changes)
+            arr.Add((JsonNode)new JsonObject { ["id"] = change.Id, ["from"] = change.From, ["to"] = change.To });
+
+        AppendWal(tenant, sync, session, doc_id, new JsonObject
+        {
+            ["op"] = "set_heading_levels",
+            ["changes"] = arr
+        });
+    }
+
+    private static void AppendWal(TenantScope tenant, SyncManager sync, DocxSession session, string doc_id,
+        JsonObject walObj)
+    {
+        var walEntry = new JsonArray();
+        walEntry.Add((JsonNode)walObj);
+        var bytes = session.ToBytes();
+        tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+        sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+    }
+
+    private static JsonObject ChangesToJson(List<HeadingChange> changes, bool dryRun)
+    {
+        var arr = new JsonArray();
+        foreach (var change in changes)
+        {
+            arr.Add((JsonNode)new JsonObject
+            {
+                ["path"] = $"/body/paragraph[id='{change.Id}']",
+                ["text"] = change.Text,
+                ["from"] = change.From,
+                ["to"] = change.To
+            });
+        }
+
+        return new JsonObject
+        {
+            ["dry_run"] = dryRun,
+            ["changed"] = changes.Count,
+            ["changes"] = arr
+        };
+    }
+}
@@ -1,4 +1,6 @@
 using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
 using Grpc.Core;
 using ModelContextProtocol;
 using ModelContextProtocol.Server;
@@ -9,6 +11,8 @@ namespace DocxMcp.Tools;
 [McpServerToolType]
 public sealed class HistoryTools
 {
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
     [McpServerTool(Name = "document_undo"), Description(
         "Undo N steps in the document's edit history. " +
         "Rebuilds the document from the nearest checkpoint. " +
@@ -16,11 +20,12 @@ public sealed class HistoryTools
     public static string DocumentUndo(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Number of steps to undo (default 1).")] int steps = 1)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var sessions = tenant.Sessions;
             var result = sessions.Undo(doc_id, steps);
             if (result.Steps > 0 && result.CurrentBytes is not null)
@@ -40,11 +45,12 @@ public sealed class HistoryTools
     public static string DocumentRedo(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Number of steps to redo (default 1).")] int steps = 1)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var sessions = tenant.Sessions;
             var result = sessions.Redo(doc_id, steps);
             if (result.Steps > 0 && result.CurrentBytes is not null)
@@ -64,12 +70,13 @@ public sealed class HistoryTools
         "Supports pagination with offset and limit.")]
     public static string DocumentHistory(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Start offset for pagination (default 0).")] int offset = 0,
         [Description("Maximum number of entries to return (default 20).")] int limit = 20)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var result = tenant.Sessions.GetHistory(doc_id, offset, limit);
 
             var lines = new List<string>
@@ -115,11 +122,12 @@ public sealed class HistoryTools
     public static string DocumentJumpTo(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("WAL position to jump to (0 = baseline).")] int position)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var sessions = tenant.Sessions;
             var result = sessions.JumpTo(doc_id, position);
             if (result.Steps > 0 && result.CurrentBytes is not null)
@@ -131,4 +139,98 @@ public sealed class HistoryTools
         catch (McpException) { throw; }
         catch (Exception ex) { throw new McpException(ex.Message, ex); }
     }
+
+    [McpServerTool(Name = "compare_statistics"), Description(
+        "Compare document statistics between two positions in the edit history: words, characters, " +
+        "paragraphs, headings, tables, images, comments and estimated pages, with the change for each. " +
+        "A quick measure of how much a document changed, without a full diff. Read-only; the cursor doesn't move.\n\n" +
+        "Position 0 is the baseline; to_position defaults to the current position. See document_history for positions.")]
+    public static string CompareStatistics(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Earlier WAL position (0 = baseline).")] int from_position,
+        [Description("Later WAL position. Default: the current position.")] int? to_position = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var sessions = tenant.Sessions;
+            to_position ??= sessions.GetHistory(doc_id, 0, 1).CursorPosition;
+
+            DocumentStatistics from, to;
+            try
+            {
+                using (var a = sessions.GetAtPosition(doc_id, from_position))
+                    from = StatisticsHelper.Compute(a.Document);
+                using (var b = sessions.GetAtPosition(doc_id, to_position.Value))
+                    to = StatisticsHelper.Compute(b.Document);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            JsonObject fromJson = new(), toJson = new(), delta = new();
+            foreach (var field in DocumentStatistics.Fields)
+            {
+                fromJson[field] = from[field];
+                toJson[field] = to[field];
+                delta[field] = to[field] - from[field];
+            }
+
+            return new JsonObject
+            {
+                ["from_position"] = from_position,
+                ["to_position"] = to_position.Value,
+                ["from"] = fromJson,
+                ["to"] = toJson,
+                ["delta"] = delta
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"comparing statistics for '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "generate_changelog"), Description(
+        "Summarize the edits between two positions in the edit history as a readable changelog: " +
+        "a summary line (e.g. \"Added section 'Pricing', edited 3 paragraphs in 'Terms', inserted 1 table\") " +
+        "followed by one bullet per change, ready for a commit-style message or an email notification. " +
+        "Changes are grouped by kind and by the section (heading) they fall in. Read-only; the cursor doesn't move.\n\n" +
+        "Position 0 is the baseline; to_position defaults to the current position. See document_history for positions.")]
+    public static string GenerateChangelog(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Earlier WAL position (0 = baseline). Default: 0.")] int from_position = 0,
+        [Description("Later WAL position. Default: the current position.")] int? to_position = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var sessions = tenant.Sessions;
+            to_position ??= sessions.GetHistory(doc_id, 0, 1).CursorPosition;
+
+            string changelog;
+            try
+            {
+                var entries = sessions.GetWalEntries(doc_id, from_position, to_position.Value);
+                using var before = sessions.GetAtPosition(doc_id, from_position);
+                using var after = sessions.GetAtPosition(doc_id, to_position.Value);
+                changelog = ChangelogHelper.Format(ChangelogHelper.Collect(entries, before.Document, after.Document));
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            return changelog.Length > 0
+                ? changelog
+                : $"No changes between positions {from_position} and {to_position.Value}.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"generating the changelog for '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
 }
@@ -0,0 +1,140 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class HyperlinkTools
+{
+    [McpServerTool(Name = "add_hyperlink_in_paragraph"), Description(
+        "Add a hyperlink inside an existing paragraph instead of as a paragraph of its own.\n\n" +
+        "With anchor_text, the first occurrence of that text in the paragraph becomes the link " +
+        "(supports cross-run matching; the runs keep their formatting). Otherwise text is appended " +
+        "to the end of the paragraph as a new link.\n\n" +
+        "TARGET (exactly one):\n" +
+        "  url      — external address\n" +
+        "  bookmark — name of an existing bookmark\n" +
+        "  heading  — text of a heading; the heading is bookmarked if needed\n\n" +
+        "Examples:\n" +
+        "  add_hyperlink_in_paragraph(doc_id, \"/body/paragraph[0]\", anchor_text=\"our website\", url=\"https://example.com\")\n" +
+        "  add_hyperlink_in_paragraph(doc_id, \"/body/paragraph[3]\", text=\" (see Pricing)\", heading=\"Pricing\", tooltip=\"Jump to pricing\")")]
+    public static string AddHyperlinkInParagraph(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Typed path to the paragraph (must resolve to exactly 1 paragraph).")] string path,
+        [Description("Existing text in the paragraph to turn into the link.")] string? anchor_text = null,
+        [Description("Link text to append to the paragraph, when anchor_text isn't given.")] string? text = null,
+        [Description("External URL to link to.")] string? url = null,
+        [Description("Bookmark to link to.")] string? bookmark = null,
+        [Description("Heading text to link to.")] string? heading = null,
+        [Description("Tooltip shown when hovering the link.")] string? tooltip = null,
+        [Description("Character style of the link. Default: Hyperlink.")] string? character_style = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if ((anchor_text is null) == (text is null))
+                return "Error: Provide exactly one of anchor_text or text.";
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "add_hyperlink",
+                ["path"] = path,
+                ["anchor_text"] = anchor_text,
+                ["text"] = text,
+                ["url"] = url,
+                ["bookmark"] = bookmark,
+                ["heading"] = heading,
+                ["tooltip"] = tooltip,
+                ["character_style"] = character_style
+            };
+            var patch = JsonDocument.Parse(walObj.ToJsonString()).RootElement;
+
+            Hyperlink hyperlink;
+            try
+            {
+                hyperlink = Apply(patch, session.Document);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or UriFormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return $"Hyperlink {ElementIdManager.GetId(hyperlink)} added to {path}.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"adding hyperlink to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an add_hyperlink WAL operation.
+    /// </summary>
+    internal static void ReplayAddHyperlink(JsonElement patch, WordprocessingDocument doc)
+    {
+        Apply(patch, doc);
+    }
+
+    private static Hyperlink Apply(JsonElement patch, WordprocessingDocument doc)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no MainDocumentPart.");
+        var path = patch.GetProperty("path").GetString()
+            ?? throw new InvalidOperationException("add_hyperlink must have a 'path' field.");
+
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements.Count != 1 || elements[0] is not Paragraph paragraph)
+            throw new InvalidOperationException($"Path '{path}' must resolve to exactly 1 paragraph.");
+
+        // Find the text before touching the package, so a miss leaves no stray relationship
+        List<Run>? runs = null;
+        if (patch.TryGetProperty("anchor_text", out var at) && at.ValueKind == JsonValueKind.String)
+        {
+            runs = CommentHelper.IsolateText(paragraph, at.GetString()!)
+                ?? throw new InvalidOperationException($"anchor_text '{at.GetString()}' not found in paragraph.");
+        }
+
+        var style = HyperlinkHelper.ResolveStyle(patch, mainPart);
+        var hyperlink = HyperlinkHelper.CreateLink(patch, mainPart);
+
+        if (runs is not null)
+        {
+            HyperlinkHelper.Wrap(runs, hyperlink, style);
+        }
+        else
+        {
+            hyperlink.AppendChild(HyperlinkHelper.CreateRun(patch.GetProperty("text").GetString() ?? "", style));
+            paragraph.AppendChild(hyperlink);
+        }
+
+        return hyperlink;
+    }
+}
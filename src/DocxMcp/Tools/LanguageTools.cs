@@ -0,0 +1,138 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class LanguageTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "detect_languages"), Description(
+        "Detect the languages a document is written in, per section, from the text itself — to route " +
+        "multilingual documents (e.g. bilingual contracts) to the right translation workflow, or to fix w:lang " +
+        "values that spell-check and hyphenation rely on.\n\n" +
+        "Sections are the ranges read_section lists, or with by='heading' the ranges starting at each heading. " +
+        "Each section reports its languages (ISO 639-1 codes) with their share of the sampled text and a " +
+        "confidence from 0 to 1, and the languages its runs declare now (w:lang). Paragraphs are detected one " +
+        "by one; long sections are sampled. Latin-script text is told apart by its common words, so very short " +
+        "or list-like sections may come out with low confidence or no language.\n\n" +
+        "With set_defaults=true, the dominant language becomes the document's default language, and the " +
+        "paragraphs of sections mainly in another language get that language on runs that declare none.")]
+    public static string DetectLanguages(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("'section' (default, section breaks) or 'heading'.")] string by = "section",
+        [Description("Set the document's default language and tag sections in other languages. Default: false.")]
+        bool set_defaults = false)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            if (by is not ("section" or "heading"))
+                return "Error: by must be 'section' or 'heading'.";
+
+            if (set_defaults && gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var sections = LanguageDetectionHelper.Analyze(session.Document, by == "heading");
+            var dominant = LanguageDetectionHelper.Dominant(sections);
+
+            var tagged = new JsonArray();
+            if (set_defaults && dominant is not null)
+            {
+                LanguageDetectionHelper.SetDefaultLanguage(session.Document, dominant);
+                foreach (var section in sections)
+                {
+                    var main = section.Languages.FirstOrDefault()?.Language;
+                    if (main is null || main == dominant || section.ParagraphIds.Count == 0) continue;
+
+                    LanguageDetectionHelper.TagParagraphs(session.Document, section.ParagraphIds, main);
+                    var ids = new JsonArray();
+                    foreach (var id in section.ParagraphIds)
+                        ids.Add((JsonNode)id);
+                    tagged.Add((JsonNode)new JsonObject { ["language"] = main, ["ids"] = ids });
+                }
+
+                var walEntry = new JsonArray();
+                walEntry.Add((JsonNode)new JsonObject
+                {
+                    ["op"] = "set_languages",
+                    ["default"] = dominant,
+                    ["paragraphs"] = tagged.DeepClone()
+                });
+                var bytes = session.ToBytes();
+                tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+                sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+            }
+
+            var arr = new JsonArray();
+            foreach (var section in sections)
+            {
+                var languages = new JsonArray();
+                foreach (var language in section.Languages)
+                {
+                    languages.Add((JsonNode)new JsonObject
+                    {
+                        ["language"] = language.Language,
+                        ["share"] = language.Share,
+                        ["confidence"] = language.Confidence
+                    });
+                }
+                var declared = new JsonArray();
+                foreach (var language in section.Declared)
+                    declared.Add((JsonNode)language);
+
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["index"] = section.Index,
+                    ["first_heading"] = section.FirstHeading,
+                    ["paragraphs"] = section.ParagraphIds.Count,
+                    ["sampled_chars"] = section.SampledChars,
+                    ["languages"] = languages,
+                    ["declared"] = declared
+                });
+            }
+
+            return new JsonObject
+            {
+                ["dominant"] = dominant,
+                ["multilingual"] = sections.SelectMany(s => s.Languages.Take(1)).Select(l => l.Language).Distinct().Count() > 1,
+                ["defaults_set"] = set_defaults && dominant is not null,
+                ["tagged_sections"] = tagged.Count,
+                ["sections"] = arr
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"detecting languages of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a set_languages WAL operation.
+    /// </summary>
+    internal static void ReplaySetLanguages(JsonElement patch, WordprocessingDocument doc)
+    {
+        LanguageDetectionHelper.SetDefaultLanguage(doc, patch.GetProperty("default").GetString() ?? "");
+        foreach (var section in patch.GetProperty("paragraphs").EnumerateArray())
+        {
+            var ids = section.GetProperty("ids").EnumerateArray().Select(id => id.GetString() ?? "");
+            LanguageDetectionHelper.TagParagraphs(doc, ids, section.GetProperty("language").GetString() ?? "");
+        }
+    }
+}
@@ -0,0 +1,126 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class OperationTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    /// <summary>
+    /// Longest a single get_operation call waits, to stay within client tool-call timeouts.
+    /// </summary>
+    private const int MaxWaitSeconds = 60;
+
+    [McpServerTool(Name = "get_operation"), Description(
+        "Get the state of a long-running operation started with background=true " +
+        "(e.g. export(..., background=true)).\n\n" +
+        "States: running, succeeded, failed, cancelled. Running operations report progress (0.0-1.0) " +
+        "and a message; succeeded operations carry the result, failed ones the error.\n" +
+        "Set wait_seconds to wait (up to 60s) for the operation to finish before returning.")]
+    public static async Task<string> GetOperation(
+        TenantScope tenant,
+        OperationManager operations,
+        [Description("Operation ID returned when the operation was started.")] string operation_id,
+        [Description("Seconds to wait for the operation to finish (0-60). Default: 0.")] int wait_seconds = 0)
+    {
+        try
+        {
+            var wait = TimeSpan.FromSeconds(Math.Clamp(wait_seconds, 0, MaxWaitSeconds));
+            var op = await operations.WaitAsync(tenant.TenantId, operation_id, wait)
+                ?? throw new McpException($"Operation '{operation_id}' not found. Finished operations expire after an hour.");
+
+            return ToJson(op).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"getting operation '{operation_id}'"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "list_operations"), Description(
+        "List long-running operations, newest first. Results are omitted; use get_operation to read one.")]
+    public static async Task<string> ListOperations(
+        TenantScope tenant,
+        OperationManager operations,
+        [Description("Also list finished operations (kept for an hour). Default: false.")] bool include_finished = false)
+    {
+        try
+        {
+            var list = await operations.ListAsync(tenant.TenantId, include_finished);
+
+            var arr = new JsonArray();
+            foreach (var op in list)
+            {
+                var json = ToJson(op);
+                json.Remove("result");
+                arr.Add((JsonNode)json);
+            }
+
+            return new JsonObject
+            {
+                ["count"] = list.Count,
+                ["operations"] = arr
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing operations"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "cancel_operation"), Description(
+        "Cancel a running operation. The operation stops at its next progress check; " +
+        "cancelling a finished operation has no effect.")]
+    public static async Task<string> CancelOperation(
+        TenantScope tenant,
+        OperationManager operations,
+        [Description("Operation ID to cancel.")] string operation_id)
+    {
+        try
+        {
+            var op = await operations.CancelAsync(tenant.TenantId, operation_id);
+            return ToJson(op).ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) when (ex.StatusCode == StatusCode.NotFound)
+        {
+            throw new McpException($"Operation '{operation_id}' not found. Finished operations expire after an hour.");
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"cancelling operation '{operation_id}'"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    internal static JsonObject ToJson(OperationDto op)
+    {
+        var json = new JsonObject
+        {
+            ["id"] = op.Id,
+            ["kind"] = op.Kind,
+            ["description"] = op.Description,
+            ["state"] = StateName(op.State),
+            ["progress"] = op.Progress,
+            ["created_at"] = op.CreatedAt.ToString("o"),
+            ["updated_at"] = op.UpdatedAt.ToString("o")
+        };
+        if (op.SessionId is not null) json["session_id"] = op.SessionId;
+        if (op.Message is not null) json["message"] = op.Message;
+        if (op.Result is not null) json["result"] = op.Result;
+        if (op.Error is not null) json["error"] = op.Error;
+        return json;
+    }
+
+    private static string StateName(OperationState state) => state switch
+    {
+        OperationState.Running => "running",
+        OperationState.Succeeded => "succeeded",
+        OperationState.Failed => "failed",
+        OperationState.Cancelled => "cancelled",
+        _ => "unknown"
+    };
+}
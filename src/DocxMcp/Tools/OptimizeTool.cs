@@ -0,0 +1,111 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class OptimizeTool
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "optimize_document"), Description(
+        "Shrink the document's file size without changing its content:\n" +
+        "  - remove styles nothing uses (default styles and styles used by fields are kept)\n" +
+        "  - remove numbering definitions no list uses\n" +
+        "  - remove images nothing shows, and merge identical images into one\n" +
+        "  - remove rsid attributes (revision save IDs Word adds on every save)\n" +
+        "  - recompress the package with maximum deflate compression\n\n" +
+        "Every step is on by default. Reports the size before and after and what was removed.\n" +
+        "Recompression applies to the saved output; later edits write the package with default compression again.\n\n" +
+        "Examples:\n" +
+        "  optimize_document(doc_id)\n" +
+        "  optimize_document(doc_id, strip_rsids=false)")]
+    public static string OptimizeDocument(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Remove unused styles. Default: true.")] bool strip_unused_styles = true,
+        [Description("Remove unused numbering definitions. Default: true.")] bool strip_unused_numbering = true,
+        [Description("Remove images that aren't referenced. Default: true.")] bool strip_unused_media = true,
+        [Description("Merge identical images into a single part. Default: true.")] bool dedupe_images = true,
+        [Description("Remove rsid attributes. Default: true.")] bool strip_rsids = true,
+        [Description("Recompress the package with maximum compression. Default: true.")] bool recompress = true)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var before = session.ToBytes().Length;
+
+            var options = new OptimizeOptions(strip_unused_styles, strip_unused_numbering,
+                strip_unused_media, dedupe_images, strip_rsids);
+            var report = DocumentOptimizer.Optimize(session.Document, options);
+
+            var bytes = session.ToBytes();
+            if (recompress)
+                bytes = DocumentOptimizer.Recompress(bytes);
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "optimize_document",
+                ["strip_unused_styles"] = strip_unused_styles,
+                ["strip_unused_numbering"] = strip_unused_numbering,
+                ["strip_unused_media"] = strip_unused_media,
+                ["dedupe_images"] = dedupe_images,
+                ["strip_rsids"] = strip_rsids
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return new JsonObject
+            {
+                ["size_before"] = before,
+                ["size_after"] = bytes.Length,
+                ["bytes_saved"] = before - bytes.Length,
+                ["styles_removed"] = report.StylesRemoved,
+                ["numbering_removed"] = report.NumberingRemoved,
+                ["media_removed"] = report.MediaRemoved,
+                ["images_deduplicated"] = report.ImagesDeduplicated,
+                ["rsids_removed"] = report.RsidsRemoved
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"optimizing '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an optimize_document WAL operation. Recompression only affects the bytes
+    /// written at the time, so there is nothing to replay for it.
+    /// </summary>
+    internal static void ReplayOptimizeDocument(JsonElement patch, WordprocessingDocument doc)
+    {
+        bool Flag(string name) => !patch.TryGetProperty(name, out var v) || v.GetBoolean();
+
+        DocumentOptimizer.Optimize(doc, new OptimizeOptions(
+            Flag("strip_unused_styles"),
+            Flag("strip_unused_numbering"),
+            Flag("strip_unused_media"),
+            Flag("dedupe_images"),
+            Flag("strip_rsids")));
+    }
+}
@@ -0,0 +1,69 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class PageLayoutTool
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "estimate_pages"), Description(
+        "Estimate the page count and page breaks of a document without rendering it. " +
+        "Uses each section's page size, margins and columns with font sizes, line spacing, " +
+        "paragraph spacing, table rows, images, page breaks and section breaks.\n\n" +
+        "Returns the estimated total and, per page, the first and last body element on it " +
+        "(ID paths usable with query) and the number of text lines. " +
+        "Elements split across pages appear on both. The estimate may differ from Word by a page " +
+        "or so on long documents.")]
+    public static string EstimatePages(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Number of pages to skip. Default: 0.")] int? offset = null,
+        [Description("Maximum number of pages to return (1-100). Default: 100.")] int? limit = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var estimate = PageLayoutEstimator.Estimate(session.Document);
+
+            var effectiveOffset = Math.Max(0, offset ?? 0);
+            var effectiveLimit = Math.Clamp(limit ?? 100, 1, 100);
+            var page = estimate.Ranges.Skip(effectiveOffset).Take(effectiveLimit).ToList();
+
+            var arr = new JsonArray();
+            foreach (var range in page)
+            {
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["page"] = range.Page,
+                    ["first"] = range.First,
+                    ["last"] = range.Last,
+                    ["lines"] = range.Lines
+                });
+            }
+
+            return new JsonObject
+            {
+                ["pages"] = estimate.Pages,
+                ["sections"] = estimate.Sections,
+                ["total"] = estimate.Ranges.Count,
+                ["offset"] = effectiveOffset,
+                ["limit"] = effectiveLimit,
+                ["count"] = page.Count,
+                ["items"] = arr
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"estimating pages of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
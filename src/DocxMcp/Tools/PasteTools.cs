@@ -0,0 +1,174 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class PasteTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "paste_html"), Description(
+        "Paste HTML copied from Word, Google Docs or a web page into a document.\n\n" +
+        "Clipboard HTML is cleaned up the way Word's 'merge formatting' paste does: paragraphs, headings, " +
+        "lists and tables are kept, runs keep bold, italic, underline, strike, super/subscript, colors, " +
+        "highlights and links, and mso- styles, Word's list bullets and Google Docs wrapper spans are dropped. " +
+        "Fonts and sizes are dropped too unless keep_fonts is set, so pasted text matches the document. " +
+        "Images are skipped. A full CF_HTML clipboard payload (with StartFragment markers) is accepted.\n\n" +
+        "range is either an insert position (a children path) or a path to body elements, which the pasted " +
+        "content replaces (like pasting over a selection).\n\n" +
+        "Examples:\n" +
+        "  paste_html(doc_id, \"/body/children/999\", \"<p>Hello <b>world</b></p>\")  — append at the end\n" +
+        "  paste_html(doc_id, \"/body/paragraph[id='1A2B3C4D']\", html)  — replace a paragraph")]
+    public static string PasteHtml(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")]
+        string doc_id,
+        [Description("Insert position (e.g. '/body/children/0') or path to the body elements to replace.")]
+        string range,
+        [Description("HTML from the clipboard.")]
+        string html,
+        [Description("Keep font families and sizes from the HTML. Default: false.")]
+        bool keep_fonts = false)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            var conversion = HtmlPasteHelper.Convert(html, keep_fonts);
+            if (conversion.Elements.Count == 0)
+                return "Error: the HTML has no text, lists or tables to paste.";
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            (List<OpenXmlElement> Inserted, int Replaced) pasted;
+            try
+            {
+                pasted = Paste(session.Document, conversion.Elements, range);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            // Log the converted elements rather than the HTML, so replay doesn't depend
+            // on how the conversion evolves
+            var walObj = new JsonObject
+            {
+                ["op"] = "paste_html",
+                ["range"] = range,
+                ["elements"] = conversion.Elements.DeepClone()
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var ids = new JsonArray();
+            foreach (var element in pasted.Inserted)
+                ids.Add((JsonNode?)ElementIdManager.GetId(element));
+
+            var result = new JsonObject
+            {
+                ["pasted"] = pasted.Inserted.Count,
+                ["ids"] = ids
+            };
+            if (pasted.Replaced > 0)
+                result["replaced"] = pasted.Replaced;
+            if (conversion.SkippedImages > 0)
+                result["skipped_images"] = conversion.SkippedImages;
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"pasting HTML into '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a paste_html WAL operation.
+    /// </summary>
+    internal static void ReplayPasteHtml(JsonElement patch, WordprocessingDocument doc)
+    {
+        var range = patch.GetProperty("range").GetString()
+            ?? throw new InvalidOperationException("paste_html must have a 'range' field.");
+        var elements = JsonNode.Parse(patch.GetProperty("elements").GetRawText())?.AsArray()
+            ?? throw new InvalidOperationException("paste_html must have an 'elements' field.");
+
+        Paste(doc, elements, range);
+    }
+
+    /// <summary>
+    /// Insert the elements at a children path, or in place of the elements a path resolves to.
+    /// </summary>
+    internal static (List<OpenXmlElement> Inserted, int Replaced) Paste(
+        WordprocessingDocument doc, JsonArray elements, string range)
+    {
+        var mainPart = doc.MainDocumentPart
+            ?? throw new InvalidOperationException("Document has no main part.");
+        var path = DocxPath.Parse(range);
+
+        OpenXmlElement parent;
+        int index;
+        List<OpenXmlElement> replaced = [];
+        if (path.IsChildrenPath)
+        {
+            (parent, index) = PathResolver.ResolveForInsert(path, doc);
+        }
+        else
+        {
+            replaced = PathResolver.Resolve(path, doc);
+            if (replaced.Count == 0)
+                throw new ArgumentException($"'{range}' matches no element.");
+            if (replaced.Any(e => e is not (Paragraph or Table)))
+                throw new ArgumentException("Only paragraphs and tables can be replaced by pasted content.");
+            parent = replaced[0].Parent
+                ?? throw new ArgumentException($"'{range}' cannot be replaced.");
+            if (replaced.Any(e => e.Parent != parent))
+                throw new ArgumentException($"The elements '{range}' matches must share a parent to be replaced.");
+            index = replaced.Min(e => parent.ChildElements.ToList().IndexOf(e));
+        }
+
+        var created = HtmlPasteHelper.CreateElements(elements, mainPart);
+        var trackChanges = RevisionHelper.IsTrackChangesEnabled(doc);
+
+        foreach (var element in replaced)
+        {
+            if (trackChanges)
+                RevisionHelper.DeleteElementWithTracking(doc, element);
+            else
+                element.Remove();
+        }
+        if (trackChanges && replaced.Count > 0)
+            index = parent.ChildElements.ToList().IndexOf(replaced[^1]) + 1;
+
+        foreach (var element in created)
+        {
+            if (trackChanges)
+                RevisionHelper.InsertElementWithTracking(doc, parent, element, index++);
+            else
+                parent.InsertChildAt(element, index++);
+        }
+
+        return (created, replaced.Count);
+    }
+}
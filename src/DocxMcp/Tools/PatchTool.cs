@@ -27,12 +27,15 @@ public sealed class PatchTool
         TenantScope tenant,
         SyncManager sync,
         ExternalChangeGate gate,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("JSON array of patch operations (max 10 per call).")] string patches,
-        [Description("If true, simulates operations without applying changes.")] bool dry_run = false)
+        [Description("If true, simulates operations without applying changes.")] bool dry_run = false,
+        [Description("If true, the response includes a structural delta and the updated outline.")] bool include_structure = false)
     {
         try
         {
+        doc_id = tenant.Sessions.ResolveId(doc_id);
+
         // Check for pending external changes — block edits until acknowledged
         if (!dry_run && gate.HasPendingChanges(tenant.TenantId, doc_id))
         {
@@ -92,22 +95,37 @@ public sealed class PatchTool
         };
 
         var succeededPatches = new List<string>();
+        var tracker = include_structure ? StructureTracker.Capture(session.GetBody()) : null;
+        // Tenant profile variables in section break headers and footers are resolved
+        // before the patch is applied, so the WAL replays the resolved text
+        var profile = patches.Contains("{{") ? sessions.LoadProfile() : null;
 
         foreach (var patchElement in patchArray.EnumerateArray())
         {
             PatchOperationResult opResult;
             PatchOperation? operation = null;
+            var patchJson = profile?.ResolveSectionBreak(patchElement.GetRawText()) ?? patchElement.GetRawText();
 
             try
             {
                 // Deserialize to typed operation
-                operation = JsonSerializer.Deserialize(patchElement.GetRawText(), DocxJsonContext.Default.PatchOperation);
+                operation = JsonSerializer.Deserialize(patchJson, DocxJsonContext.Default.PatchOperation);
                 if (operation is null)
                     throw new ArgumentException("Failed to parse patch operation.");
 
                 // Validate the operation
                 operation.Validate();
 
+                // Refuse replacements that would drop content the element factory can't rebuild
+                // (WAL replay goes through ExecuteReplace directly and isn't affected)
+                if (operation is ReplacePatchOperation guarded
+                    && DocxPath.Parse(guarded.Path) is { Leaf: not StyleSegment } guardedPath)
+                {
+                    var trackChanges = RevisionHelper.IsTrackChangesEnabled(wpDoc);
+                    foreach (var target in PathResolver.Resolve(guardedPath, wpDoc))
+                        UnsupportedFeatureHelper.EnsureReplaceable(target, trackChanges);
+                }
+
                 // Execute based on type
                 opResult = operation switch
                 {
@@ -125,7 +143,7 @@ public sealed class PatchTool
                 {
                     if (!dry_run)
                     {
-                        succeededPatches.Add(patchElement.GetRawText());
+                        succeededPatches.Add(patchJson);
                         result.Applied++;
                     }
                     else
@@ -160,12 +178,14 @@ public sealed class PatchTool
                 sessions.AppendWal(doc_id, walPatches, null, bytes);
                 sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
             }
-            catch { /* persistence is best-effort */ }
+            // Persistence is best-effort, but an edit the limits or the write policy refuse must be reported
+            catch (Exception ex) when (ex is not (EditLimitExceededException or WritePolicyViolationException)) { }
         }
 
         result.Success = dry_run
             ? result.Operations.All(o => o.Status is "would_succeed")
             : result.Applied == result.Total;
+        result.Structure = tracker?.Compare(session.GetBody());
 
         return result.ToJson();
         }
@@ -303,6 +323,7 @@ public sealed class PatchTool
                     RevisionHelper.InsertElementWithTracking(wpDoc, parent, element, index);
                 else
                     parent.InsertChildAt(element, index);
+                CompleteSectionBreak(element, op.Value, mainPart);
                 createdElement = element;
             }
         }
@@ -335,6 +356,7 @@ public sealed class PatchTool
                     RevisionHelper.InsertElementWithTracking(wpDoc, parent, element);
                 else
                     parent.AppendChild(element);
+                CompleteSectionBreak(element, op.Value, mainPart);
                 createdElement = element;
             }
         }
@@ -344,6 +366,17 @@ public sealed class PatchTool
         return result;
     }
 
+    /// <summary>
+    /// Give an inserted section break the layout of the section it splits, plus its own options.
+    /// </summary>
+    private static void CompleteSectionBreak(OpenXmlElement element, JsonElement value, MainDocumentPart mainPart)
+    {
+        if (element is Paragraph paragraph
+            && value.TryGetProperty("type", out var type)
+            && string.Equals(type.GetString(), "section_break", StringComparison.OrdinalIgnoreCase))
+            SectionHelper.CompleteSectionBreak(paragraph, value, mainPart);
+    }
+
     private static ReplaceOperationResult ExecuteReplace(ReplacePatchOperation op, WordprocessingDocument wpDoc,
         MainDocumentPart mainPart, bool dryRun)
     {
@@ -409,6 +442,7 @@ public sealed class PatchTool
                     RevisionHelper.ReplaceElementWithTracking(wpDoc, target, newElement);
                 else
                     parent.ReplaceChild(newElement, target);
+                CompleteSectionBreak(newElement, op.Value, mainPart);
             }
         }
 
@@ -562,7 +596,7 @@ public sealed class PatchTool
     /// <summary>
     /// Find and replace text within runs, preserving all run-level formatting.
     /// </summary>
-    private static ReplaceTextOperationResult ExecuteReplaceText(ReplaceTextPatchOperation op,
+    internal static ReplaceTextOperationResult ExecuteReplaceText(ReplaceTextPatchOperation op,
         WordprocessingDocument wpDoc, bool dryRun)
     {
         var result = new ReplaceTextOperationResult { Path = op.Path };
@@ -632,7 +666,7 @@ public sealed class PatchTool
         int count = 0;
         foreach (var para in paragraphs)
         {
-            var allText = string.Concat(para.Elements<Run>().Select(r => r.InnerText));
+            var allText = string.Concat(TextSegments(para).Select(t => t.Text));
             int idx = 0;
             while ((idx = allText.IndexOf(find, idx, StringComparison.Ordinal)) >= 0)
             {
@@ -645,6 +679,7 @@ public sealed class PatchTool
 
     /// <summary>
     /// Replace text within an element's runs, preserving formatting.
+    /// Matches may span runs; every run keeps its own formatting.
     /// Returns the number of replacements made.
     /// </summary>
     private static int ReplaceTextInElement(OpenXmlElement element, string find, string replace, int maxCount)
@@ -663,97 +698,80 @@ public sealed class PatchTool
             if (maxCount > 0 && totalReplaced >= maxCount)
                 break;
 
-            var runs = para.Elements<Run>().ToList();
-            if (runs.Count == 0) continue;
+            var segments = TextSegments(para);
+            if (segments.Count == 0) continue;
 
-            // Try simple per-run replacement first
-            bool foundInRun = false;
-            foreach (var run in runs)
+            var allText = string.Concat(segments.Select(t => t.Text));
+            var matches = new List<int>();
+            int idx = 0;
+            while ((idx = allText.IndexOf(find, idx, StringComparison.Ordinal)) >= 0)
             {
-                if (maxCount > 0 && totalReplaced >= maxCount)
+                if (maxCount > 0 && totalReplaced + matches.Count >= maxCount)
                     break;
-
-                var textElem = run.GetFirstChild<Text>();
-                if (textElem is null) continue;
-
-                var text = textElem.Text;
-                int idx = 0;
-                while ((idx = text.IndexOf(find, idx, StringComparison.Ordinal)) >= 0)
-                {
-                    text = text[..idx] + replace + text[(idx + find.Length)..];
-                    idx += replace.Length;
-                    totalReplaced++;
-                    foundInRun = true;
-
-                    if (maxCount > 0 && totalReplaced >= maxCount)
-                        break;
-                }
-                textElem.Text = text;
+                matches.Add(idx);
+                idx += find.Length;
             }
 
-            if (foundInRun) continue;
-
-            // Cross-run replacement: concatenate all run texts, find the match,
-            // then adjust the runs that contain the match
-            var allText = string.Concat(runs.Select(r => r.InnerText));
-            var matchIdx = allText.IndexOf(find, StringComparison.Ordinal);
-            if (matchIdx < 0) continue;
-
-            // Map character positions to runs
-            int pos = 0;
-            foreach (var run in runs)
-            {
-                if (maxCount > 0 && totalReplaced >= maxCount)
-                    break;
-
-                var textElem = run.GetFirstChild<Text>();
-                if (textElem is null)
-                {
-                    // Tab or break: count as 1 char (\t or empty)
-                    var runText = run.InnerText;
-                    pos += runText.Length;
-                    continue;
-                }
-
-                var runStart = pos;
-                var runEnd = pos + textElem.Text.Length;
-
-                // Check if this run overlaps with the find range
-                var findEnd = matchIdx + find.Length;
-
-                if (runEnd <= matchIdx || runStart >= findEnd)
-                {
-                    // No overlap
-                    pos = runEnd;
-                    continue;
-                }
+            // Right to left, so the positions of earlier matches stay valid
+            for (int i = matches.Count - 1; i >= 0; i--)
+                ReplaceSpan(segments, matches[i], find, replace);
 
-                // This run overlaps with the search text
-                var overlapStart = Math.Max(matchIdx, runStart) - runStart;
-                var overlapEnd = Math.Min(findEnd, runEnd) - runStart;
+            totalReplaced += matches.Count;
+        }
 
-                var before = textElem.Text[..overlapStart];
-                var after = textElem.Text[overlapEnd..];
+        return totalReplaced;
+    }
 
-                // First overlapping run gets the replacement text
-                if (runStart <= matchIdx)
-                {
-                    textElem.Text = before + replace + after;
-                    textElem.Space = SpaceProcessingModeValues.Preserve;
-                    totalReplaced++;
-                }
-                else
-                {
-                    // Subsequent overlapping runs: remove the overlapping portion
-                    textElem.Text = after;
-                    textElem.Space = SpaceProcessingModeValues.Preserve;
-                }
+    /// <summary>
+    /// The w:t elements of a paragraph's runs, in document order.
+    /// </summary>
+    private static List<Text> TextSegments(Paragraph para) =>
+        para.Elements<Run>().SelectMany(r => r.Elements<Text>()).ToList();
 
-                pos = runEnd;
-            }
+    /// <summary>
+    /// Replace the match of <paramref name="find"/> at <paramref name="start"/> in the
+    /// concatenated <paramref name="segments"/>. Characters find and replace share at either
+    /// end stay in the runs they are in, so "Acme Corp" → "Acme Corporation" leaves a bold
+    /// "Acme" bold; the changed middle takes the formatting of the run it starts in.
+    /// </summary>
+    private static void ReplaceSpan(List<Text> segments, int start, string find, string replace)
+    {
+        int prefix = 0;
+        while (prefix < find.Length && prefix < replace.Length && find[prefix] == replace[prefix])
+            prefix++;
+        int suffix = 0;
+        while (suffix < find.Length - prefix && suffix < replace.Length - prefix &&
+               find[find.Length - 1 - suffix] == replace[replace.Length - 1 - suffix])
+            suffix++;
+
+        // Characters [from, to) are removed and inserted goes in their place
+        var from = start + prefix;
+        var to = start + find.Length - suffix;
+        var inserted = replace[prefix..(replace.Length - suffix)];
+        if (from == to && inserted.Length == 0)
+            return;
+
+        // Inserted text joins the run of the first removed character, or for a pure
+        // insertion the run of the character before it, as typing would
+        var hostChar = from < to || prefix == 0 ? from : from - 1;
+
+        int pos = 0;
+        foreach (var segment in segments)
+        {
+            var text = segment.Text;
+            var segStart = pos;
+            var segEnd = pos + text.Length;
+            pos = segEnd;
+
+            var isHost = hostChar >= segStart && hostChar < segEnd;
+            var a = Math.Clamp(from - segStart, 0, text.Length);
+            var b = Math.Clamp(to - segStart, 0, text.Length);
+            if (a == b && !isHost)
+                continue;
+
+            segment.Text = text[..a] + (isHost ? inserted : "") + text[b..];
+            segment.Space = SpaceProcessingModeValues.Preserve;
         }
-
-        return totalReplaced;
     }
 
     /// <summary>
@@ -0,0 +1,548 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using Microsoft.Extensions.Logging.Abstractions;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+/// <summary>
+/// One parsed pipeline step: its kind, what to do when it fails, and its arguments.
+/// </summary>
+public sealed record PipelineStep(string Kind, string OnError, JsonObject Args);
+
+[McpServerToolType]
+public sealed partial class PipelineTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    public static readonly string[] StepKinds = ["open", "fill", "table", "patch", "style", "toc", "export", "save"];
+    public static readonly string[] ErrorModes = ["stop", "continue", "rollback"];
+
+    /// <summary>Longest pipeline accepted, to keep one operation bounded.</summary>
+    public const int MaxSteps = 50;
+
+    /// <summary>Patches per apply_patch call.</summary>
+    private const int PatchBatchSize = 10;
+
+    [GeneratedRegex(@"\{\{\s*([^{}]*?)\s*\}\}")]
+    private static partial Regex PlaceholderPattern();
+
+    [McpServerTool(Name = "run_pipeline"), Description(
+        "Run a multi-step document assembly pipeline on the server as one long-running operation, " +
+        "instead of one tool call per step. Returns an operation ID right away; follow it with get_operation " +
+        "(progress reports the current step, the result holds a report per step) or stop it with cancel_operation.\n\n" +
+        "The pipeline is a JSON object: {\"steps\": [...], \"on_error\": \"stop\"}. Each step has a \"step\" kind, " +
+        "its arguments, and optionally its own \"on_error\".\n\n" +
+        "STEPS:\n" +
+        "  open   — {\"template\": name} creates the document from a template (see template_list); " +
+        "without a template, a new empty document. Only as the first step, and only without doc_id.\n" +
+        "  fill   — {\"values\": {\"client\": \"ACME\", ...}} replaces {{client}} placeholders in the body, " +
+        "headers and footers. Values must not be empty. Tenant profile variables ({{company.name}}, ... see " +
+        "profile_set) are filled too unless given in values, so values can be omitted.\n" +
+        "  table  — {\"path\": \"/body/children/3\", \"csv\": \"...\", \"header\": true, \"properties\": {...}} inserts " +
+        "a table built from CSV; properties are add_element table properties (e.g. border_style).\n" +
+        "  patch  — {\"patches\": [...]} applies apply_patch operations.\n" +
+        "  style  — {\"target\": \"run\"|\"paragraph\"|\"table\", \"path\": ..., \"style\": {...}} as style_element, " +
+        "style_paragraph or style_table (which also take \"cell_style\" and \"row_style\").\n" +
+        "  toc    — {\"path\": paragraph path, \"levels\": \"1-3\"} appends a table of contents field.\n" +
+        "  export — {\"format\": \"pdf\"|\"html\"|\"markdown\"|\"docx\"}; the content is in the step's report.\n" +
+        "  save   — {\"path\": ..., \"source_type\": ..., \"connection_id\": ..., \"file_id\": ...} sets the save target " +
+        "as document_set_source, then saves (e.g. to Google Drive); without arguments, saves to the current target.\n\n" +
+        "ON ERROR:\n" +
+        "  stop     — (default) keep the edits made so far and skip the remaining steps\n" +
+        "  continue — record the failure and go on with the next step\n" +
+        "  rollback — undo every edit the pipeline made, then skip the remaining steps. " +
+        "Files already saved or exported stay as they are.\n\n" +
+        "Example:\n" +
+        "  run_pipeline(pipeline='{\"on_error\": \"rollback\", \"steps\": [" +
+        "{\"step\": \"open\", \"template\": \"proposal\"}, " +
+        "{\"step\": \"fill\", \"values\": {\"client\": \"ACME\"}}, " +
+        "{\"step\": \"toc\", \"path\": \"/body/paragraph[1]\"}, " +
+        "{\"step\": \"export\", \"format\": \"pdf\"}]}')")]
+    public static async Task<string> RunPipeline(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        OperationManager operations,
+        [Description("The pipeline as a JSON object with a steps array.")] string pipeline,
+        [Description("Session ID or alias to run on. Omit when the pipeline starts with an open step.")] string? doc_id = null)
+    {
+        try
+        {
+            List<PipelineStep> steps;
+            try
+            {
+                steps = Parse(pipeline);
+            }
+            catch (Exception ex) when (ex is JsonException or ArgumentException or InvalidOperationException)
+            {
+                return $"Error: Invalid pipeline — {ex.Message}";
+            }
+
+            var opens = steps[0].Kind == "open";
+            if (opens && doc_id is not null)
+                return "Error: doc_id cannot be combined with an open step.";
+            if (!opens && doc_id is null)
+                return "Error: Provide doc_id, or start the pipeline with an open step.";
+
+            if (doc_id is not null)
+            {
+                doc_id = tenant.Sessions.ResolveId(doc_id);
+                tenant.Sessions.Get(doc_id).Dispose();
+            }
+            if (steps.Any(s => s.Kind == "export" && Str(s, "format") == "pdf"))
+                tenant.Features.Require(FeatureFlags.PdfExport);
+
+            var description = doc_id is null
+                ? $"Run a {steps.Count}-step pipeline"
+                : $"Run a {steps.Count}-step pipeline on {doc_id}";
+            var operation = await operations.StartAsync(tenant.TenantId, "pipeline", description, doc_id,
+                ctx => RunAsync(tenant, sync, gate, doc_id, steps, ctx.ReportAsync, ctx.CancellationToken));
+
+            return new JsonObject
+            {
+                ["operation_id"] = operation.Id,
+                ["state"] = "running",
+                ["message"] = $"Pipeline of {steps.Count} step(s) started. Use get_operation to follow it."
+            }.ToJsonString();
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "starting a pipeline"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id ?? ""); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Parse and check a pipeline, so mistakes are reported before anything runs.
+    /// </summary>
+    public static List<PipelineStep> Parse(string pipeline)
+    {
+        if (JsonNode.Parse(pipeline) is not JsonObject root)
+            throw new ArgumentException("the pipeline must be a JSON object.");
+        if (root["steps"] is not JsonArray array || array.Count == 0)
+            throw new ArgumentException("'steps' must be a non-empty array.");
+        if (array.Count > MaxSteps)
+            throw new ArgumentException($"at most {MaxSteps} steps are allowed, got {array.Count}.");
+
+        var defaultOnError = OnError(root, "stop", "the pipeline");
+        var steps = new List<PipelineStep>();
+        for (var i = 0; i < array.Count; i++)
+        {
+            var where = $"step {i}";
+            if (array[i] is not JsonObject args)
+                throw new ArgumentException($"{where} must be an object.");
+            var kind = (args["step"] as JsonValue)?.ToString().ToLowerInvariant()
+                ?? throw new ArgumentException($"{where} has no 'step' kind.");
+            if (!StepKinds.Contains(kind))
+                throw new ArgumentException($"{where}: unknown step '{kind}'. Expected one of: {string.Join(", ", StepKinds)}.");
+
+            var step = new PipelineStep(kind, OnError(args, defaultOnError, where), args);
+            Check(step, i, where);
+            steps.Add(step);
+        }
+        return steps;
+    }
+
+    private static string OnError(JsonObject obj, string fallback, string where)
+    {
+        var mode = Str(obj, "on_error")?.ToLowerInvariant() ?? fallback;
+        return ErrorModes.Contains(mode)
+            ? mode
+            : throw new ArgumentException($"{where}: on_error must be one of: {string.Join(", ", ErrorModes)}.");
+    }
+
+    private static void Check(PipelineStep step, int index, string where)
+    {
+        switch (step.Kind)
+        {
+            case "open":
+                if (index > 0)
+                    throw new ArgumentException($"{where}: open can only be the first step.");
+                break;
+            case "fill":
+                if (step.Args["values"] is null)
+                    break;
+                if (step.Args["values"] is not JsonObject values)
+                    throw new ArgumentException($"{where}: 'values' must be an object.");
+                foreach (var (name, value) in values)
+                {
+                    if (value is not JsonValue || value.ToString().Length == 0)
+                        throw new ArgumentException($"{where}: the value of '{name}' must be a non-empty string or number.");
+                }
+                break;
+            case "table":
+                Require(step, "path", where);
+                Require(step, "csv", where);
+                if (step.Args["properties"] is not (null or JsonObject))
+                    throw new ArgumentException($"{where}: 'properties' must be an object.");
+                if (step.Args["header"] is JsonNode header
+                    && header.GetValueKind() is not (JsonValueKind.True or JsonValueKind.False))
+                    throw new ArgumentException($"{where}: 'header' must be true or false.");
+                break;
+            case "patch":
+                if (step.Args["patches"] is not JsonArray { Count: > 0 })
+                    throw new ArgumentException($"{where}: patch needs a non-empty 'patches' array.");
+                break;
+            case "style":
+                var target = Str(step, "target") ?? "run";
+                if (target is not ("run" or "paragraph" or "table"))
+                    throw new ArgumentException($"{where}: target must be run, paragraph or table.");
+                if (target != "table" && step.Args["style"] is not JsonObject)
+                    throw new ArgumentException($"{where}: style needs a 'style' object.");
+                break;
+            case "toc":
+                Require(step, "path", where);
+                break;
+            case "export":
+                if (Str(step, "format") is not ("html" or "markdown" or "pdf" or "docx"))
+                    throw new ArgumentException($"{where}: format must be html, markdown, pdf or docx.");
+                break;
+        }
+    }
+
+    private static void Require(PipelineStep step, string name, string where)
+    {
+        if (string.IsNullOrEmpty(Str(step, name)))
+            throw new ArgumentException($"{where}: {step.Kind} needs '{name}'.");
+    }
+
+    /// <summary>
+    /// Run the steps in order and report on each. A failed step is handled as its
+    /// on_error says; the report's status is completed, completed_with_errors,
+    /// stopped or rolled_back. Only cancellation ends the run with an exception.
+    /// </summary>
+    internal static async Task<string> RunAsync(
+        TenantScope tenant, SyncManager sync, ExternalChangeGate gate, string? docId,
+        IReadOnlyList<PipelineStep> steps, Func<double, string?, Task> report, CancellationToken ct)
+    {
+        var reports = new JsonArray();
+        int? start = docId is null ? null : tenant.Sessions.GetHistory(docId, 0, 1).CursorPosition;
+        var status = "completed";
+
+        for (var i = 0; i < steps.Count; i++)
+        {
+            var step = steps[i];
+            await report((double)i / steps.Count, $"Step {i + 1}/{steps.Count}: {step.Kind}");
+
+            var entry = new JsonObject { ["index"] = i, ["step"] = step.Kind };
+            reports.Add((JsonNode)entry);
+            try
+            {
+                var (message, output) = await RunStepAsync(tenant, sync, gate, docId, step, ct);
+                if (step.Kind == "open")
+                {
+                    docId = message;
+                    start = 0;
+                    message = $"Created document {docId}.";
+                }
+                entry["status"] = "succeeded";
+                entry["message"] = message;
+                if (output is not null)
+                    entry["output"] = output;
+                continue;
+            }
+            catch (OperationCanceledException) when (ct.IsCancellationRequested)
+            {
+                throw;
+            }
+            catch (Exception ex)
+            {
+                entry["status"] = "failed";
+                entry["error"] = ex is RpcException rpc ? rpc.Status.Detail : ex.Message;
+            }
+
+            if (step.OnError == "continue")
+            {
+                status = "completed_with_errors";
+                continue;
+            }
+
+            status = "stopped";
+            if (step.OnError == "rollback" && docId is not null && start is not null)
+            {
+                var undo = tenant.Sessions.JumpTo(docId, start.Value);
+                if (undo.Steps > 0 && undo.CurrentBytes is not null)
+                    sync.MaybeAutoSave(tenant.TenantId, docId, undo.CurrentBytes);
+                status = "rolled_back";
+            }
+            foreach (var skipped in steps.Skip(i + 1))
+                reports.Add((JsonNode)new JsonObject { ["index"] = reports.Count, ["step"] = skipped.Kind, ["status"] = "skipped" });
+            break;
+        }
+
+        var result = new JsonObject
+        {
+            ["doc_id"] = docId,
+            ["status"] = status,
+            ["steps"] = reports
+        };
+        return result.ToJsonString(JsonOpts);
+    }
+
+    /// <summary>
+    /// Run one step through the tool it stands for. Returns a message and the step's
+    /// output, if any; an open step returns the new session ID as its message.
+    /// Throws when the step fails.
+    /// </summary>
+    private static async Task<(string Message, JsonNode? Output)> RunStepAsync(
+        TenantScope tenant, SyncManager sync, ExternalChangeGate gate, string? docId,
+        PipelineStep step, CancellationToken ct)
+    {
+        if (step.Kind == "open")
+        {
+            var template = Str(step, "template");
+            using var created = template is null
+                ? tenant.Sessions.Create()
+                : tenant.Sessions.CreateFromTemplate(template);
+            return (created.Id, null);
+        }
+
+        var id = docId ?? throw new InvalidOperationException("No document to work on.");
+        if (step.Kind is not ("export" or "save") && gate.HasPendingChanges(tenant.TenantId, id))
+        {
+            throw new InvalidOperationException(
+                "External changes detected. Call get_external_changes to review and acknowledge them, " +
+                "or use sync_external_changes to reload the document, then run the pipeline again.");
+        }
+
+        switch (step.Kind)
+        {
+            case "fill":
+                return Fill(tenant, sync, gate, id, step.Args["values"] as JsonObject ?? new JsonObject());
+
+            case "table":
+            {
+                var value = new JsonObject { ["type"] = "table" };
+                if (step.Args["properties"] is JsonObject properties)
+                {
+                    foreach (var (name, property) in properties)
+                    {
+                        if (name is not ("type" or "headers" or "rows"))
+                            value[name] = property?.DeepClone();
+                    }
+                }
+
+                var rows = TableExportHelper.FromCsv(Str(step, "csv")!);
+                if (rows.Count == 0)
+                    throw new InvalidOperationException("The CSV has no rows.");
+                var width = rows.Max(r => r.Count);
+                var header = step.Args["header"]?.GetValue<bool>() ?? true;
+                var data = new JsonArray();
+                foreach (var row in rows.Skip(header ? 1 : 0))
+                    data.Add((JsonNode)Cells(row, width));
+                if (header)
+                    value["headers"] = Cells(rows[0], width);
+                value["rows"] = data;
+
+                var patch = new JsonObject { ["op"] = "add", ["path"] = Str(step, "path"), ["value"] = value };
+                ApplyPatches(tenant, sync, gate, id, [patch]);
+                return ($"Inserted a table of {rows.Count} row(s) and {width} column(s).", null);
+            }
+
+            case "patch":
+            {
+                var patches = ((JsonArray)step.Args["patches"]!).Select(p => p!.DeepClone()).ToList();
+                var applied = ApplyPatches(tenant, sync, gate, id, patches);
+                return ($"Applied {applied} patch operation(s).", null);
+            }
+
+            case "style":
+            {
+                var path = Str(step, "path");
+                var style = step.Args["style"]?.ToJsonString();
+                var result = (Str(step, "target") ?? "run") switch
+                {
+                    "paragraph" => StyleTools.StyleParagraph(tenant, sync, id, style!, path),
+                    "table" => StyleTools.StyleTable(tenant, sync, id, style,
+                        step.Args["cell_style"]?.ToJsonString(), step.Args["row_style"]?.ToJsonString(), path),
+                    _ => StyleTools.StyleElement(tenant, sync, id, style!, path)
+                };
+                return (Checked(result), null);
+            }
+
+            case "toc":
+                return (Checked(FieldTools.InsertField(tenant, sync, gate, id, Str(step, "path")!, "toc",
+                    Str(step, "levels"))), null);
+
+            case "export":
+            {
+                var format = Str(step, "format")!;
+                using var session = tenant.Sessions.Get(id);
+                var content = await ExportTools.ExportAs(session, format, ct);
+                return ($"Exported to {format}.", JsonValue.Create(content));
+            }
+
+            case "save":
+            {
+                var path = Str(step, "path");
+                if (path is not null)
+                {
+                    Checked(DocumentTools.DocumentSetSource(NullLogger<DocumentTools>.Instance, tenant, sync, id, path,
+                        Str(step, "source_type"), Str(step, "connection_id"), Str(step, "file_id")));
+                }
+                return (DocumentTools.DocumentSave(NullLogger<DocumentTools>.Instance, tenant, sync, id,
+                    cancellationToken: ct), null);
+            }
+
+            default:
+                throw new InvalidOperationException($"Unknown step '{step.Kind}'.");
+        }
+    }
+
+    /// <summary>
+    /// Replace each {{name}} placeholder as spelled in the document (inner spaces
+    /// included), in the body and in the headers and footers the document has.
+    /// Placeholders of tenant profile variables not in <paramref name="values"/> are
+    /// filled from the profile.
+    /// </summary>
+    private static (string Message, JsonNode? Output) Fill(
+        TenantScope tenant, SyncManager sync, ExternalChangeGate gate, string id, JsonObject values)
+    {
+        var roots = new List<string> { "/body" };
+        var spellings = new Dictionary<string, HashSet<string>>(StringComparer.Ordinal);
+        using (var session = tenant.Sessions.Get(id))
+        {
+            var main = session.Document.MainDocumentPart!;
+            if (main.HeaderParts.Any())
+                roots.Add("/header");
+            if (main.FooterParts.Any())
+                roots.Add("/footer");
+
+            var paragraphs = session.GetBody().Descendants<Paragraph>()
+                .Concat(main.HeaderParts.Where(h => h.Header is not null).SelectMany(h => h.Header!.Descendants<Paragraph>()))
+                .Concat(main.FooterParts.Where(f => f.Footer is not null).SelectMany(f => f.Footer!.Descendants<Paragraph>()));
+            foreach (var paragraph in paragraphs)
+            {
+                var text = string.Concat(paragraph.Descendants<Text>().Select(t => t.Text));
+                foreach (Match match in PlaceholderPattern().Matches(text))
+                {
+                    var name = match.Groups[1].Value;
+                    if (!spellings.TryGetValue(name, out var set))
+                        spellings[name] = set = new HashSet<string>(StringComparer.Ordinal);
+                    set.Add(match.Value);
+                }
+            }
+        }
+
+        var patches = new List<JsonNode>();
+        void Replace(HashSet<string> set, string value)
+        {
+            foreach (var spelling in set)
+            {
+                foreach (var root in roots)
+                {
+                    patches.Add(new JsonObject
+                    {
+                        ["op"] = "replace_text",
+                        ["path"] = root,
+                        ["find"] = spelling,
+                        ["replace"] = value,
+                        ["max_count"] = int.MaxValue
+                    });
+                }
+            }
+        }
+
+        var missing = new JsonArray();
+        foreach (var (name, value) in values)
+        {
+            if (spellings.TryGetValue(name, out var set))
+                Replace(set, value!.ToString());
+            else
+                missing.Add((JsonNode)JsonValue.Create(name)!);
+        }
+
+        var fromProfile = new JsonArray();
+        if (spellings.Keys.Any(n => !values.ContainsKey(n)))
+        {
+            foreach (var (name, value) in tenant.Sessions.LoadProfile().Variables())
+            {
+                if (values.ContainsKey(name) || !spellings.TryGetValue(name, out var set))
+                    continue;
+                Replace(set, value);
+                fromProfile.Add((JsonNode)JsonValue.Create(name)!);
+            }
+        }
+
+        if (patches.Count > 0)
+            ApplyPatches(tenant, sync, gate, id, patches);
+
+        var resolved = fromProfile.Select(n => n!.GetValue<string>()).ToHashSet(StringComparer.Ordinal);
+        var unfilled = new JsonArray();
+        foreach (var name in spellings.Keys.Where(n => !values.ContainsKey(n) && !resolved.Contains(n))
+                     .Order(StringComparer.Ordinal))
+            unfilled.Add((JsonNode)JsonValue.Create(name)!);
+
+        var filled = values.Count - missing.Count + fromProfile.Count;
+        var output = new JsonObject
+        {
+            ["filled"] = filled,
+            ["missing"] = missing,
+            ["unfilled"] = unfilled,
+            ["from_profile"] = fromProfile
+        };
+        return ($"Filled {filled} placeholder(s).", output);
+    }
+
+    /// <summary>
+    /// Apply patches through apply_patch in batches. Returns how many were applied;
+    /// throws with the first failure.
+    /// </summary>
+    private static int ApplyPatches(
+        TenantScope tenant, SyncManager sync, ExternalChangeGate gate, string id, IReadOnlyList<JsonNode> patches)
+    {
+        var applied = 0;
+        foreach (var batch in patches.Chunk(PatchBatchSize))
+        {
+            var array = new JsonArray();
+            foreach (var patch in batch)
+                array.Add(patch.DeepClone());
+
+            using var result = JsonDocument.Parse(PatchTool.ApplyPatch(tenant, sync, gate, id, array.ToJsonString()));
+            var root = result.RootElement;
+            if (root.TryGetProperty("applied", out var count))
+                applied += count.GetInt32();
+            if (root.GetProperty("success").GetBoolean())
+                continue;
+
+            var error = root.TryGetProperty("error", out var e) && e.ValueKind == JsonValueKind.String
+                ? e.GetString()
+                : null;
+            if (error is null && root.TryGetProperty("operations", out var ops))
+            {
+                error = ops.EnumerateArray()
+                    .Where(o => o.TryGetProperty("error", out var oe) && oe.ValueKind == JsonValueKind.String)
+                    .Select(o => $"{o.GetProperty("path").GetString()}: {o.GetProperty("error").GetString()}")
+                    .FirstOrDefault();
+            }
+            throw new InvalidOperationException(
+                $"{error ?? "A patch failed."} ({applied} of {patches.Count} patch operation(s) were applied.)");
+        }
+        return applied;
+    }
+
+    private static JsonArray Cells(List<string> row, int width)
+    {
+        var cells = new JsonArray();
+        for (var c = 0; c < width; c++)
+            cells.Add((JsonNode)JsonValue.Create(c < row.Count ? row[c] : "")!);
+        return cells;
+    }
+
+    private static string Checked(string toolResult) =>
+        toolResult.StartsWith("Error: ", StringComparison.Ordinal)
+            ? throw new InvalidOperationException(toolResult["Error: ".Length..])
+            : toolResult;
+
+    private static string? Str(PipelineStep step, string name) => Str(step.Args, name);
+
+    private static string? Str(JsonObject obj, string name) =>
+        obj[name] is JsonValue value ? value.ToString() : null;
+}
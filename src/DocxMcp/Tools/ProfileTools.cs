@@ -0,0 +1,94 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class ProfileTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "profile_set"), Description(
+        "Set the tenant profile: organization data stored once and resolved automatically, " +
+        "so generation calls don't need to send it again.\n\n" +
+        "Each field is a template variable:\n" +
+        "  company_name    — {{company.name}}\n" +
+        "  company_address — {{company.address}}\n" +
+        "  company_logo    — {{company.logo}} (e.g. the logo's file path or URL)\n" +
+        "  default_author  — {{author.name}}; also the author of comments added without one\n\n" +
+        "Variables are resolved by run_pipeline's fill step (values given to the step win) and in the " +
+        "header and footer texts of section breaks. Omitted fields are kept; an empty string clears a field.")]
+    public static string ProfileSet(
+        TenantScope tenant,
+        [Description("Company name.")] string? company_name = null,
+        [Description("Company postal address.")] string? company_address = null,
+        [Description("Company logo (file path or URL).")] string? company_logo = null,
+        [Description("Default author name.")] string? default_author = null)
+    {
+        try
+        {
+            var profile = tenant.Sessions.LoadProfile();
+            foreach (var (field, value) in new[]
+                     {
+                         ("company_name", company_name), ("company_address", company_address),
+                         ("company_logo", company_logo), ("default_author", default_author),
+                     })
+            {
+                if (value is not null)
+                    profile.Set(field, value);
+            }
+
+            tenant.Sessions.SaveProfile(profile);
+            return Describe(profile);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "saving the tenant profile"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "profile_get"), Description(
+        "Get the tenant profile and the template variables it resolves.")]
+    public static string ProfileGet(TenantScope tenant)
+    {
+        try
+        {
+            return Describe(tenant.Sessions.LoadProfile());
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "loading the tenant profile"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "profile_delete"), Description(
+        "Delete the tenant profile. Documents already generated keep the values resolved into them.")]
+    public static string ProfileDelete(TenantScope tenant)
+    {
+        try
+        {
+            return tenant.Sessions.DeleteProfile()
+                ? "Tenant profile deleted."
+                : "No tenant profile was set.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "deleting the tenant profile"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    private static string Describe(TenantProfile profile)
+    {
+        var variables = new JsonObject();
+        foreach (var (name, value) in profile.Variables())
+            variables[name] = value;
+
+        return new JsonObject
+        {
+            ["profile"] = profile.ToJson(),
+            ["variables"] = variables
+        }.ToJsonString(JsonOpts);
+    }
+}
@@ -38,7 +38,7 @@ public sealed class QueryTool
         "Every element has a stable 'id' field in JSON output. Use [id='...'] selectors for precise targeting.")]
     public static string Query(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Typed path to query (e.g. /body/paragraph[0], /body/table[0]). Prefer direct indexed access.")] string path,
         [Description("Output format: json, text, or summary. Default: json.")] string? format = "json",
         [Description("Number of elements to skip. Negative values count from the end (e.g. -10 = last 10 elements). Default: 0.")] int? offset = null,
@@ -46,6 +46,7 @@ public sealed class QueryTool
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -298,6 +299,11 @@ public sealed class QueryTool
                     propsObj["line_spacing"] = int.TryParse(sl, out var v) ? v : 0;
                     hasProperties = true;
                 }
+                if (spacing.LineRule?.Value is LineSpacingRuleValues rule)
+                {
+                    propsObj["line_spacing_rule"] = rule == LineSpacingRuleValues.AtLeast ? "at_least" : spacing.LineRule.InnerText;
+                    hasProperties = true;
+                }
             }
 
             if (pp.Indentation is Indentation indent)
@@ -573,6 +579,17 @@ public sealed class QueryTool
                 hasProps = true;
             }
 
+            if (tcp.TextDirection?.Val is not null)
+            {
+                propsObj["text_direction"] = tcp.TextDirection.Val.InnerText switch
+                {
+                    "tbRl" => "top_to_bottom",
+                    "btLr" => "bottom_to_top",
+                    var other => other
+                };
+                hasProps = true;
+            }
+
             if (hasProps)
                 result["properties"] = propsObj;
         }
@@ -665,7 +682,13 @@ public sealed class QueryTool
         if (rp.Strike is not null) result["strike"] = true;
         if (rp.FontSize?.Val?.Value is string fs) result["font_size"] = int.Parse(fs) / 2;
         if (rp.RunFonts?.Ascii?.Value is string fn) result["font_name"] = fn;
+        if (rp.RunFonts?.EastAsia?.Value is string fe) result["font_name_east_asia"] = fe;
         if (rp.Color?.Val?.Value is string c) result["color"] = c;
+        if (StyleHelper.HighlightName(rp.Highlight?.Val?.Value) is string hl) result["highlight"] = hl;
+        if (StyleHelper.VerticalAlignName(rp.VerticalTextAlignment?.Val?.Value) is string va) result["vertical_align"] = va;
+        if (rp.SmallCaps is not null) result["small_caps"] = true;
+        if (rp.Spacing?.Val?.Value is int spacing) result["letter_spacing"] = spacing / 20.0;
+        if (CodeHelper.IsInlineCode(rp)) result["code"] = true;
 
         return result;
     }
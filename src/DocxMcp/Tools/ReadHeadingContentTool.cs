@@ -24,7 +24,7 @@ public sealed class ReadHeadingContentTool
         "Results are paginated: max 50 elements per call. Use offset to paginate within large heading blocks.")]
     public static string ReadHeadingContent(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Text to search for in heading content (case-insensitive partial match). " +
                      "Omit to list all headings.")] string? heading_text = null,
         [Description("Zero-based index of the heading among all headings (or among headings at the specified level). " +
@@ -38,6 +38,7 @@ public sealed class ReadHeadingContentTool
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
             var body = session.GetBody();
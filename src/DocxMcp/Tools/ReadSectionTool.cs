@@ -24,7 +24,7 @@ public sealed class ReadSectionTool
         "Results are paginated: max 50 elements per call. Use offset to paginate within large sections.")]
     public static string ReadSection(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Zero-based section index. Omit or use -1 to list all sections.")] int? section_index = null,
         [Description("Output format: json, text, or summary. Default: json.")] string? format = "json",
         [Description("Number of elements to skip. Negative values count from the end (e.g. -10 = last 10 elements). Default: 0.")] int? offset = null,
@@ -32,6 +32,7 @@ public sealed class ReadSectionTool
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
             var body = doc.MainDocumentPart?.Document?.Body
@@ -0,0 +1,123 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class ReaderTools
+{
+    /// <summary>Elements returned by one read_next call, like query's page size cap.</summary>
+    private const int MaxElementsPerRead = 50;
+
+    [McpServerTool(Name = "open_reader"), Description(
+        "Open a reading cursor at the start of a document and return its reader_id. " +
+        "Then call read_next repeatedly to walk the document a few paragraphs or sections at a time, " +
+        "without tracking offsets: the position is kept by the server.\n\n" +
+        "The cursor follows element IDs, so edits made between reads don't make it skip or repeat content. " +
+        "Readers are forgotten when the document is closed or the server restarts.")]
+    public static string OpenReader(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            var total = session.GetBody().ChildElements.Count(e => e is not SectionProperties);
+            var reader = tenant.Sessions.Readers.Open(doc_id);
+
+            var result = new JsonObject
+            {
+                ["reader_id"] = reader.Id,
+                ["doc_id"] = doc_id,
+                ["total"] = total,
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"opening a reader on '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "read_next"), Description(
+        "Read the next elements of a document from a reader opened with open_reader, and move the reader past them.\n\n" +
+        "unit=paragraph reads `count` body elements (a table counts as one). " +
+        "unit=section reads `count` sections: a heading and everything up to the next heading. " +
+        "At most 50 elements are returned per call; a longer section continues on the next call.\n\n" +
+        "Returns the elements (same format as query), the position reached, the total, and done=true at the end.")]
+    public static string ReadNext(
+        TenantScope tenant,
+        [Description("Reader ID returned by open_reader.")] string reader_id,
+        [Description("What to read: paragraph (default) or section.")] string? unit = null,
+        [Description("Number of units to read (default 10 paragraphs or 1 section).")] int? count = null)
+    {
+        try
+        {
+            unit = (unit ?? "paragraph").ToLowerInvariant();
+            if (unit is not ("paragraph" or "section"))
+                throw new McpException($"Unknown unit '{unit}'. Use paragraph or section.");
+            var units = count ?? (unit == "section" ? 1 : 10);
+            if (units < 1)
+                throw new McpException("count must be at least 1.");
+
+            var reader = tenant.Sessions.Readers.Get(reader_id);
+            DocxSession session;
+            try
+            {
+                session = tenant.Sessions.Get(reader.SessionId);
+            }
+            catch (KeyNotFoundException)
+            {
+                tenant.Sessions.Readers.Close(reader_id);
+                throw GrpcErrorHelper.WrapNotFound(reader.SessionId);
+            }
+
+            var body = session.GetBody();
+            var elements = ReaderRegistry.ReadNext(reader, body, unit, units, MaxElementsPerRead);
+            var total = body.ChildElements.Count(e => e is not SectionProperties);
+
+            var result = new JsonObject
+            {
+                ["reader_id"] = reader.Id,
+                ["doc_id"] = reader.SessionId,
+                ["unit"] = unit,
+                ["count"] = elements.Count,
+                ["position"] = reader.NextIndex,
+                ["total"] = total,
+                ["done"] = reader.NextIndex >= total,
+                ["next_id"] = reader.NextElementId,
+                ["items"] = JsonNode.Parse(QueryTool.FormatJsonArray(elements, session.Document)),
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"reading with reader '{reader_id}'"); }
+        catch (KeyNotFoundException ex) { throw new McpException(ex.Message, ex); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "close_reader"), Description(
+        "Close a reader opened with open_reader. Readers are also closed with their document.")]
+    public static string CloseReader(
+        TenantScope tenant,
+        [Description("Reader ID returned by open_reader.")] string reader_id)
+    {
+        return tenant.Sessions.Readers.Close(reader_id)
+            ? $"Reader '{reader_id}' closed."
+            : $"No reader with ID '{reader_id}'.";
+    }
+
+    private static readonly JsonSerializerOptions JsonOpts = new()
+    {
+        WriteIndented = true,
+    };
+}
@@ -0,0 +1,185 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class ReviewTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "import_review"), Description(
+        "Merge the comments and tracked changes a reviewer made in Word on a copy of the document back into " +
+        "the live session, without replacing edits made since the copy was taken.\n\n" +
+        "Each annotation is placed on the matching paragraph: by element ID when the copy kept it and the text " +
+        "still agrees, otherwise by content (the same text nearest the same position; for comments, the most " +
+        "similar wording). Comments keep their author, initials, date and anchored text. Tracked changes are " +
+        "reapplied as tracked changes by their reviewer, so they can be accepted or rejected as usual; a change " +
+        "is only imported when its paragraph still has the text the reviewer saw. Comments the document already " +
+        "has (same author and text) are skipped, so importing the same copy twice adds nothing.\n\n" +
+        "Reports what was imported and every annotation that couldn't be placed, with the reason. " +
+        "Paragraphs the reviewer added or removed and formatting changes aren't imported.")]
+    public static string ImportReview(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the live document.")] string doc_id,
+        [Description("The reviewed DOCX, base64-encoded.")] string reviewed_docx)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            byte[] reviewedBytes;
+            try
+            {
+                reviewedBytes = Convert.FromBase64String(reviewed_docx);
+            }
+            catch (FormatException)
+            {
+                return "Error: reviewed_docx is not valid base64.";
+            }
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            ReviewImport import;
+            try
+            {
+                using var stream = new MemoryStream(reviewedBytes);
+                using var reviewed = WordprocessingDocument.Open(stream, false);
+                import = ReviewImportHelper.Collect(doc, reviewed);
+            }
+            catch (Exception ex) when (ex is OpenXmlPackageException or InvalidDataException or FileFormatException)
+            {
+                return $"Error: reviewed_docx is not a readable DOCX — {ex.Message}";
+            }
+
+            // Logged as the add_comment and import_revision ops they amount to, so replay
+            // doesn't need the reviewed copy
+            var walEntry = new JsonArray();
+            var unmatched = new JsonArray();
+            foreach (var u in import.Unmatched)
+            {
+                unmatched.Add((JsonNode)new JsonObject
+                {
+                    ["kind"] = u.Kind,
+                    ["author"] = u.Author,
+                    ["text"] = u.Text,
+                    ["reason"] = u.Reason
+                });
+            }
+
+            foreach (var comment in import.Comments)
+            {
+                var commentId = CommentHelper.AllocateCommentId(doc);
+                var walObj = new JsonObject
+                {
+                    ["op"] = "add_comment",
+                    ["comment_id"] = commentId,
+                    ["path"] = comment.Path,
+                    ["text"] = comment.Text,
+                    ["author"] = comment.Author,
+                    ["initials"] = comment.Initials,
+                    ["date"] = comment.Date.ToUniversalTime().ToString("o"),
+                    ["anchor_text"] = comment.AnchorText is not null ? JsonValue.Create(comment.AnchorText) : null
+                };
+                CommentTools.ReplayAddComment(JsonDocument.Parse(walObj.ToJsonString()).RootElement, doc);
+                walEntry.Add((JsonNode)walObj);
+            }
+
+            var changesApplied = 0;
+            foreach (var change in import.Changes)
+            {
+                try
+                {
+                    ReviewImportHelper.ApplyChange(doc, ResolveParagraph(change.Path, doc), change);
+                }
+                catch (InvalidOperationException ex)
+                {
+                    unmatched.Add((JsonNode)new JsonObject
+                    {
+                        ["kind"] = "change",
+                        ["author"] = change.Author,
+                        ["text"] = change.Insert.Length == 0 ? $"delete '{change.Delete}'" : $"insert '{change.Insert}'",
+                        ["reason"] = ex.Message
+                    });
+                    continue;
+                }
+                changesApplied++;
+                walEntry.Add((JsonNode)new JsonObject
+                {
+                    ["op"] = "import_revision",
+                    ["path"] = change.Path,
+                    ["offset"] = change.Offset,
+                    ["delete"] = change.Delete,
+                    ["insert"] = change.Insert,
+                    ["author"] = change.Author,
+                    ["date"] = change.Date.ToUniversalTime().ToString("o")
+                });
+            }
+
+            if (walEntry.Count > 0)
+            {
+                var bytes = session.ToBytes();
+                tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), "import_review", bytes);
+                sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+            }
+
+            var result = new JsonObject
+            {
+                ["comments_imported"] = import.Comments.Count,
+                ["changes_imported"] = changesApplied,
+                ["comments_already_present"] = import.AlreadyPresent,
+                ["unmatched"] = unmatched
+            };
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"importing a review into '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an import_revision WAL operation.
+    /// </summary>
+    internal static void ReplayImportRevision(JsonElement patch, WordprocessingDocument doc)
+    {
+        var path = patch.GetProperty("path").GetString()
+            ?? throw new InvalidOperationException("import_revision must have a 'path' field.");
+        var change = new ImportedChange(
+            path,
+            patch.GetProperty("offset").GetInt32(),
+            patch.GetProperty("delete").GetString() ?? "",
+            patch.GetProperty("insert").GetString() ?? "",
+            patch.GetProperty("author").GetString() ?? "Reviewer",
+            DateTime.Parse(patch.GetProperty("date").GetString()!).ToUniversalTime());
+
+        ReviewImportHelper.ApplyChange(doc, ResolveParagraph(path, doc), change);
+    }
+
+    private static Paragraph ResolveParagraph(string path, WordprocessingDocument doc)
+    {
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        return elements.Count == 1 && elements[0] is Paragraph paragraph
+            ? paragraph
+            : throw new InvalidOperationException($"'{path}' doesn't resolve to one paragraph.");
+    }
+}
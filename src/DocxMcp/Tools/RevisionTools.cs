@@ -20,7 +20,7 @@ public sealed class RevisionTools
         "paragraph_insertion, section_change, table_change, row_change, cell_change")]
     public static string RevisionList(
         TenantScope tenant,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Filter by author name (case-insensitive).")] string? author = null,
         [Description("Filter by revision type.")] string? type = null,
         [Description("Number of revisions to skip. Default: 0.")] int? offset = null,
@@ -28,6 +28,7 @@ public sealed class RevisionTools
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -89,11 +90,13 @@ public sealed class RevisionTools
     public static string RevisionAccept(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Revision ID to accept.")] int revision_id)
     {
         try
         {
+            tenant.Features.Require(FeatureFlags.TrackedChanges);
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -129,11 +132,13 @@ public sealed class RevisionTools
     public static string RevisionReject(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("Revision ID to reject.")] int revision_id)
     {
         try
         {
+            tenant.Features.Require(FeatureFlags.TrackedChanges);
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
@@ -166,11 +171,13 @@ public sealed class RevisionTools
     public static string TrackChangesEnable(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("True to enable, false to disable Track Changes.")] bool enabled)
     {
         try
         {
+            tenant.Features.Require(FeatureFlags.TrackedChanges);
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
 
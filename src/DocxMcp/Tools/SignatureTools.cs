@@ -0,0 +1,162 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class SignatureTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "add_signature_block"), Description(
+        "Add signature blocks for one or more signers: an optional company line, a 'By:' signature line, " +
+        "the signer's name and title, and a date line. Each block is kept together on one page.\n\n" +
+        "ANCHORS (optional e-sign text tags, written in white so they stay invisible):\n" +
+        "  none       — no anchors (default)\n" +
+        "  docusign   — \\s1\\ on the signature line, \\d1\\ on the date line (use them as anchor strings)\n" +
+        "  adobe_sign — {{Sig_es_:signer1:signature}} and {{Dte_es_:signer1:date}} text tags\n" +
+        "Signers are numbered in the order given.\n\n" +
+        "LAYOUTS: 'stacked' (one block under another) or 'columns' (side by side, two per row).\n\n" +
+        "Example:\n" +
+        "  add_signature_block(doc_id, '[{\"name\":\"Jane Doe\",\"title\":\"CEO\",\"company\":\"Acme Inc.\"}," +
+        "{\"name\":\"John Roe\"}]', anchors=\"docusign\", layout=\"columns\")")]
+    public static string AddSignatureBlock(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("JSON array of {name, title?, company?, date?} objects. date (default true) adds a date line.")] string signers,
+        [Description("E-sign anchor tags: 'none', 'docusign' or 'adobe_sign'. Default: none.")] string anchors = "none",
+        [Description("'stacked' or 'columns'. Default: stacked.")] string layout = "stacked",
+        [Description("Insert position (a children path, e.g. '/body/children/5'). Default: end of the document.")] string? path = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (!SignatureHelper.Providers.Contains(anchors))
+                return $"Error: Unknown anchors '{anchors}'. Use one of: {string.Join(", ", SignatureHelper.Providers)}.";
+            if (!SignatureHelper.Layouts.Contains(layout))
+                return $"Error: Unknown layout '{layout}'. Use one of: {string.Join(", ", SignatureHelper.Layouts)}.";
+
+            List<Signer> parsed;
+            try
+            {
+                parsed = SignatureHelper.Parse(signers);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            List<OpenXmlElement> elements;
+            List<SignerAnchors> placed;
+            try
+            {
+                (elements, placed) = Insert(session.Document, parsed, anchors, layout, path);
+            }
+            catch (Exception ex) when (ex is InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walSigners = new JsonArray();
+            foreach (var s in parsed)
+            {
+                walSigners.Add((JsonNode)new JsonObject
+                {
+                    ["name"] = s.Name,
+                    ["title"] = s.Title,
+                    ["company"] = s.Company,
+                    ["date"] = s.Date
+                });
+            }
+            var walObj = new JsonObject
+            {
+                ["op"] = "add_signature_block",
+                ["signers"] = walSigners,
+                ["anchors"] = anchors,
+                ["layout"] = layout,
+                ["path"] = path
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var ids = new JsonArray();
+            foreach (var e in elements)
+                ids.Add((JsonNode?)ElementIdManager.GetId(e));
+
+            var anchorArr = new JsonArray();
+            foreach (var a in placed)
+            {
+                anchorArr.Add((JsonNode)new JsonObject
+                {
+                    ["signer"] = a.Signer,
+                    ["signature"] = a.Signature,
+                    ["date"] = a.Date
+                });
+            }
+
+            return new JsonObject
+            {
+                ["signers"] = parsed.Count,
+                ["ids"] = ids,
+                ["anchors"] = anchorArr
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"adding signature blocks to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an add_signature_block WAL operation.
+    /// </summary>
+    internal static void ReplayAddSignatureBlock(JsonElement patch, WordprocessingDocument doc)
+    {
+        var signers = patch.GetProperty("signers").EnumerateArray()
+            .Select(s => new Signer(
+                s.GetProperty("name").GetString() ?? "",
+                s.TryGetProperty("title", out var t) ? t.GetString() : null,
+                s.TryGetProperty("company", out var c) ? c.GetString() : null,
+                !s.TryGetProperty("date", out var d) || d.GetBoolean()))
+            .ToList();
+        var anchors = patch.TryGetProperty("anchors", out var a) ? a.GetString() ?? "none" : "none";
+        var layout = patch.TryGetProperty("layout", out var l) ? l.GetString() ?? "stacked" : "stacked";
+        var path = patch.TryGetProperty("path", out var p) ? p.GetString() : null;
+
+        Insert(doc, signers, anchors, layout, path);
+    }
+
+    private static (List<OpenXmlElement>, List<SignerAnchors>) Insert(
+        WordprocessingDocument doc, List<Signer> signers, string anchors, string layout, string? path)
+    {
+        if (path is null)
+            return SignatureHelper.Insert(doc, signers, anchors, layout, null, 0);
+
+        var (parent, index) = PathResolver.ResolveForInsert(DocxPath.Parse(path), doc);
+        return SignatureHelper.Insert(doc, signers, anchors, layout, parent, index);
+    }
+}
@@ -0,0 +1,99 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class SimilarityTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "find_similar_documents"), Description(
+        "Find the tenant's other open sessions whose text is close to a document's, to spot a near-duplicate " +
+        "before creating yet another copy of an existing document.\n\n" +
+        "Similarity is the estimated overlap (Jaccard, 0 to 1) of the documents' 5-word sequences, computed " +
+        "with MinHash on the displayed text: formatting, IDs, deleted text and case are ignored. " +
+        "1.0 means the same text; a few edited sentences in a page keep it around 0.8; unrelated documents " +
+        "are near 0. Returns the sessions at or above threshold, most similar first.\n\n" +
+        "Examples:\n" +
+        "  find_similar_documents(doc_id)\n" +
+        "  find_similar_documents(doc_id, threshold=0.5)")]
+    public static string FindSimilarDocuments(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document to compare.")] string doc_id,
+        [Description("Lowest similarity reported, 0 to 1. Default: 0.8.")] double threshold = 0.8,
+        [Description("Maximum number of sessions returned (1-100). Default: 10.")] int limit = 10)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            if (threshold is < 0 or > 1)
+                return "Error: threshold must be between 0 and 1.";
+
+            ulong[] target;
+            using (var session = tenant.Sessions.Get(doc_id))
+                target = SimilarityHelper.Signature(SimilarityHelper.ExtractText(session.Document));
+            if (target.Length == 0)
+                return $"Error: document '{doc_id}' has no text to compare.";
+
+            var matches = new List<(Persistence.SessionIndexEntry Entry, double Similarity)>();
+            var scanned = 0;
+            foreach (var entry in tenant.Sessions.ListEntries().Where(e => e.Id != doc_id))
+            {
+                ulong[] signature;
+                try
+                {
+                    using var other = tenant.Sessions.Get(entry.Id);
+                    signature = SimilarityHelper.Signature(SimilarityHelper.ExtractText(other.Document));
+                }
+                catch (KeyNotFoundException)
+                {
+                    // Closed since the index was read
+                    continue;
+                }
+                scanned++;
+
+                var similarity = SimilarityHelper.Similarity(target, signature);
+                if (similarity >= threshold)
+                    matches.Add((entry, similarity));
+            }
+
+            var arr = new JsonArray();
+            foreach (var (entry, similarity) in matches
+                         .OrderByDescending(m => m.Similarity)
+                         .ThenByDescending(m => m.Entry.LastModifiedAt)
+                         .Take(Math.Clamp(limit, 1, 100)))
+            {
+                var obj = new JsonObject
+                {
+                    ["id"] = entry.Id,
+                    ["similarity"] = Math.Round(similarity, 3)
+                };
+                if (entry.DisplayName is not null) obj["name"] = entry.DisplayName;
+                if (entry.Alias is not null) obj["alias"] = entry.Alias;
+                if (entry.SourcePath is not null) obj["path"] = entry.SourcePath;
+                obj["last_modified_at"] = entry.LastModifiedAt.ToUniversalTime().ToString("o");
+                arr.Add((JsonNode)obj);
+            }
+
+            var result = new JsonObject
+            {
+                ["doc_id"] = doc_id,
+                ["threshold"] = threshold,
+                ["scanned"] = scanned,
+                ["count"] = matches.Count,
+                ["similar"] = arr
+            };
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"comparing '{doc_id}' with other documents"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
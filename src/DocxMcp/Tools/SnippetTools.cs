@@ -0,0 +1,236 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using Microsoft.Extensions.Logging;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class SnippetTools
+{
+    [McpServerTool(Name = "snippet_save"), Description(
+        "Save a range of body elements as a named snippet in the snippet library (boilerplate clauses, " +
+        "signature blocks, ...). Formatting, styles, numbering and images are kept with the snippet. " +
+        "Saving under an existing name replaces that snippet.\n\n" +
+        "Examples:\n" +
+        "  snippet_save(doc_id, \"signature\", \"/body/table[id='1A2B3C4D']\")\n" +
+        "  snippet_save(doc_id, \"nda-clause\", \"/body/paragraph[3]\", end_path=\"/body/paragraph[7]\")")]
+    public static string SnippetSave(
+        ILogger<SnippetTools> logger,
+        TenantScope tenant,
+        [Description("Session ID or alias of the source document.")]
+        string doc_id,
+        [Description("Snippet name (e.g. 'signature').")]
+        string name,
+        [Description("Path to the first body element of the snippet (must resolve to exactly 1 element).")]
+        string path,
+        [Description("Optional path to the last body element of the snippet. Default: same as path.")]
+        string? end_path = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            logger.LogDebug("snippet_save: doc_id={DocId}, name={Name}, path={Path}, end_path={EndPath}",
+                doc_id, name, path, end_path);
+
+            using var session = tenant.Sessions.Get(doc_id);
+
+            // Work on a copy: element IDs are preserved, so the paths resolve the same
+            using var copy = DocxSession.FromBytes(session.ToBytes(), name, sourcePath: null);
+            var range = ResolveRange(copy.Document, path, end_path ?? path);
+            SnippetHelper.Extract(copy.Document, range);
+            tenant.Sessions.SaveSnippet(name, copy.ToBytes());
+
+            return $"Snippet '{name}' saved from session '{doc_id}' ({range.Count} element(s)).";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "saving snippet"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "snippet_insert"), Description(
+        "Insert a snippet from the snippet library into a document. " +
+        "Styles and numbering the document lacks are copied from the snippet, and images are embedded.\n\n" +
+        "Examples:\n" +
+        "  snippet_insert(doc_id, \"signature\", \"/body/children/999\")  — append at the end\n" +
+        "  snippet_insert(doc_id, \"nda-clause\", \"/body/children/0\")  — insert at the start")]
+    public static string SnippetInsert(
+        TenantScope tenant,
+        SyncManager sync,
+        [Description("Session ID or alias of the target document.")]
+        string doc_id,
+        [Description("Snippet name.")]
+        string name,
+        [Description("Insert position (a children path, e.g. '/body/children/0').")]
+        string path)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            byte[] snippetBytes;
+            try
+            {
+                snippetBytes = tenant.Sessions.LoadSnippet(name);
+            }
+            catch (KeyNotFoundException ex)
+            {
+                throw new McpException($"{ex.Message} Use snippet_list to see available snippets.");
+            }
+
+            // Give the snippet fresh IDs up front and log those bytes, so replay
+            // recreates the same element IDs
+            using (var snippet = DocxSession.FromBytes(snippetBytes, name, sourcePath: null))
+            {
+                ElementIdManager.ReassignIds(snippet.GetBody(), doc);
+                snippetBytes = snippet.ToBytes();
+            }
+
+            List<string> ids;
+            try
+            {
+                ids = Insert(doc, snippetBytes, path);
+            }
+            catch (Exception ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "insert_snippet",
+                ["name"] = name,
+                ["path"] = path,
+                ["snippet"] = Convert.ToBase64String(snippetBytes)
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            return $"Snippet '{name}' inserted at {path} ({ids.Count} element(s): {string.Join(", ", ids)}).";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"inserting snippet into '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "snippet_list"), Description(
+        "List the snippets in the snippet library.")]
+    public static string SnippetList(TenantScope tenant)
+    {
+        try
+        {
+            var snippets = tenant.Sessions.ListLibraryItems(LibraryKind.Snippet);
+
+            var arr = new JsonArray();
+            foreach (var s in snippets)
+            {
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["name"] = s.Name,
+                    ["modified_at"] = s.ModifiedAt.ToString("o"),
+                    ["size_bytes"] = s.SizeBytes
+                });
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = snippets.Count,
+                ["snippets"] = arr
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing snippets"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "snippet_delete"), Description(
+        "Delete a snippet from the snippet library. Documents it was inserted into are not affected.")]
+    public static string SnippetDelete(
+        TenantScope tenant,
+        [Description("Snippet name.")]
+        string name)
+    {
+        try
+        {
+            return tenant.Sessions.DeleteLibraryItem(LibraryKind.Snippet, name)
+                ? $"Snippet '{name}' deleted."
+                : $"No snippet named '{name}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "deleting snippet"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an insert_snippet WAL operation.
+    /// </summary>
+    internal static void ReplayInsertSnippet(JsonElement patch, WordprocessingDocument doc)
+    {
+        var path = patch.GetProperty("path").GetString()
+            ?? throw new InvalidOperationException("insert_snippet must have a 'path' field.");
+        var snippetBytes = Convert.FromBase64String(patch.GetProperty("snippet").GetString()
+            ?? throw new InvalidOperationException("insert_snippet must have a 'snippet' field."));
+
+        Insert(doc, snippetBytes, path);
+    }
+
+    private static List<string> Insert(WordprocessingDocument doc, byte[] snippetBytes, string path)
+    {
+        var (parent, index) = PathResolver.ResolveForInsert(DocxPath.Parse(path), doc);
+
+        using var snippet = DocxSession.FromBytes(snippetBytes, "snippet", sourcePath: null);
+        var elements = SnippetHelper.Import(snippet.Document, doc);
+
+        foreach (var element in elements)
+            parent.InsertChildAt(element, index++);
+
+        return elements.Select(e => ElementIdManager.GetId(e) ?? "?").ToList();
+    }
+
+    /// <summary>
+    /// The body elements from <paramref name="startPath"/> to <paramref name="endPath"/>, inclusive.
+    /// </summary>
+    private static List<OpenXmlElement> ResolveRange(WordprocessingDocument doc, string startPath, string endPath)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body
+            ?? throw new InvalidOperationException("Document has no body.");
+
+        var start = ResolveBodyChild(doc, body, startPath);
+        var end = ResolveBodyChild(doc, body, endPath);
+
+        var children = body.ChildElements.ToList();
+        var (from, to) = (children.IndexOf(start), children.IndexOf(end));
+        if (from > to)
+            throw new McpException($"end_path '{endPath}' comes before path '{startPath}'.");
+
+        return children.GetRange(from, to - from + 1);
+    }
+
+    private static OpenXmlElement ResolveBodyChild(WordprocessingDocument doc, Body body, string path)
+    {
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements.Count != 1)
+            throw new McpException($"Path '{path}' must resolve to exactly 1 element, got {elements.Count}.");
+        if (elements[0].Parent != body || elements[0] is SectionProperties)
+            throw new McpException($"Path '{path}' must point to a top-level body element.");
+        return elements[0];
+    }
+}
@@ -0,0 +1,244 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class SortTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "sort_table"), Description(
+        "Sort a table's rows by a column, optionally removing duplicate rows. Rows are moved, not rewritten, " +
+        "so cell formatting and element IDs are kept. Header rows stay on top.\n\n" +
+        "Keys that are all numbers (currency, % and thousands separators allowed) or all dates sort by value; " +
+        "otherwise they sort as text, ignoring case. Empty cells go last. " +
+        "Tables with vertically merged cells can't be sorted.\n\n" +
+        "dedupe: none, rows (drop rows identical to an earlier row) or key (drop rows whose sort column repeats). " +
+        "The first occurrence is kept.\n\n" +
+        "Examples:\n" +
+        "  sort_table(doc_id, \"/body/table[0]\", \"Amount\", order=\"desc\")\n" +
+        "  sort_table(doc_id, \"/body/table[1]\", \"0\", has_header=false, dedupe=\"rows\")")]
+    public static string SortTable(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Path to the table (e.g. /body/table[0]).")] string path,
+        [Description("Column to sort by: a 0-based index or a header cell's text.")] string by_col,
+        [Description("asc or desc. Default: asc.")] string order = "asc",
+        [Description("Keep the first row in place as a header. Default: true.")] bool has_header = true,
+        [Description("Duplicate removal: none, rows or key. Default: none.")] string dedupe = "none")
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            if (order is not ("asc" or "desc"))
+                return "Error: order must be 'asc' or 'desc'.";
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            SortResult result;
+            try
+            {
+                result = ApplySortTable(session.Document, path, by_col, order, has_header, dedupe);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "sort_table",
+                ["path"] = path,
+                ["by_col"] = by_col,
+                ["order"] = order,
+                ["has_header"] = has_header,
+                ["dedupe"] = dedupe
+            };
+            Commit(tenant, sync, session, doc_id, walObj);
+
+            return ResultToJson(result, "rows").ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"sorting a table in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "sort_list"), Description(
+        "Sort the items of a bulleted or numbered list, optionally removing duplicate items. Paragraphs are moved, " +
+        "not rewritten, so formatting and numbering are kept. Nested items move with their parent item.\n\n" +
+        "Pass 'path' to any item to sort the whole list it belongs to, or 'path' and 'end_path' to sort a range " +
+        "of consecutive list paragraphs (it must start at an outermost item). " +
+        "Keys sort by value when they are all numbers or all dates, otherwise as text.\n\n" +
+        "Example:\n" +
+        "  sort_list(doc_id, \"/body/paragraph[text~='Apples']\", dedupe=true)")]
+    public static string SortList(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Path to a list item, or to the first paragraph of the range.")] string path,
+        [Description("Optional path to the last paragraph of the range.")] string? end_path = null,
+        [Description("asc or desc. Default: asc.")] string order = "asc",
+        [Description("Remove items whose text repeats an earlier item. Default: false.")] bool dedupe = false)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            if (order is not ("asc" or "desc"))
+                return "Error: order must be 'asc' or 'desc'.";
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            SortResult result;
+            try
+            {
+                result = ApplySortList(session.Document, path, end_path, order, dedupe);
+            }
+            catch (Exception ex) when (ex is ArgumentException or InvalidOperationException or FormatException)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "sort_list",
+                ["path"] = path,
+                ["end_path"] = end_path,
+                ["order"] = order,
+                ["dedupe"] = dedupe
+            };
+            Commit(tenant, sync, session, doc_id, walObj);
+
+            return ResultToJson(result, "items").ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"sorting a list in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a sort_table WAL operation.
+    /// </summary>
+    internal static void ReplaySortTable(JsonElement patch, WordprocessingDocument doc)
+    {
+        ApplySortTable(doc,
+            patch.GetProperty("path").GetString() ?? "",
+            patch.GetProperty("by_col").GetString() ?? "0",
+            patch.GetProperty("order").GetString() ?? "asc",
+            patch.GetProperty("has_header").GetBoolean(),
+            patch.GetProperty("dedupe").GetString() ?? "none");
+    }
+
+    /// <summary>
+    /// Replay a sort_list WAL operation.
+    /// </summary>
+    internal static void ReplaySortList(JsonElement patch, WordprocessingDocument doc)
+    {
+        ApplySortList(doc,
+            patch.GetProperty("path").GetString() ?? "",
+            patch.TryGetProperty("end_path", out var e) ? e.GetString() : null,
+            patch.GetProperty("order").GetString() ?? "asc",
+            patch.GetProperty("dedupe").GetBoolean());
+    }
+
+    private static SortResult ApplySortTable(
+        WordprocessingDocument doc, string path, string byCol, string order, bool hasHeader, string dedupe)
+    {
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements is not [Table table])
+            throw new ArgumentException($"Path '{path}' must resolve to exactly 1 table.");
+
+        return SortHelper.SortTable(table, byCol, order == "desc", hasHeader, dedupe);
+    }
+
+    private static SortResult ApplySortList(
+        WordprocessingDocument doc, string path, string? endPath, string order, bool dedupe)
+    {
+        var start = ResolveParagraph(doc, path);
+        List<Paragraph> paragraphs;
+        if (endPath is null)
+        {
+            paragraphs = SortHelper.ListAround(start);
+        }
+        else
+        {
+            var end = ResolveParagraph(doc, endPath);
+            if (end.Parent != start.Parent)
+                throw new ArgumentException($"'{path}' and '{endPath}' must be in the same container.");
+
+            var siblings = start.Parent!.ChildElements.ToList();
+            var (from, to) = (siblings.IndexOf(start), siblings.IndexOf(end));
+            if (from > to)
+                throw new ArgumentException($"end_path '{endPath}' comes before path '{path}'.");
+
+            var range = siblings.GetRange(from, to - from + 1);
+            if (range.Any(e => e is not Paragraph))
+                throw new ArgumentException("The list range must contain only paragraphs.");
+            paragraphs = range.Cast<Paragraph>().ToList();
+        }
+
+        return SortHelper.SortList(paragraphs, order == "desc", dedupe);
+    }
+
+    private static Paragraph ResolveParagraph(WordprocessingDocument doc, string path)
+    {
+        var elements = PathResolver.Resolve(DocxPath.Parse(path), doc);
+        if (elements is not [Paragraph paragraph])
+            throw new ArgumentException($"Path '{path}' must resolve to exactly 1 paragraph.");
+        return paragraph;
+    }
+
+    private static void Commit(TenantScope tenant, SyncManager sync, DocxSession session, string doc_id, JsonObject walObj)
+    {
+        var walEntry = new JsonArray();
+        walEntry.Add((JsonNode)walObj);
+        var bytes = session.ToBytes();
+        tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+        sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+    }
+
+    private static JsonObject ResultToJson(SortResult result, string unit)
+    {
+        var removed = new JsonArray();
+        foreach (var text in result.Removed)
+            removed.Add((JsonNode?)text);
+
+        return new JsonObject
+        {
+            ["sorted_" + unit] = result.Sorted,
+            ["key_type"] = result.KeyType,
+            ["removed_duplicates"] = result.Removed.Count,
+            ["removed"] = removed
+        };
+    }
+}
@@ -20,10 +20,18 @@ public sealed class StyleTools
         "Properties (set value to apply, true/false for toggles, JSON null to remove):\n" +
         "  bold, italic, underline, strike — true sets, false removes\n" +
         "  font_size — integer in points (e.g. 14)\n" +
-        "  font_name — string (e.g. \"Arial\")\n" +
+        "  font_name — string (e.g. \"Arial\"), for Latin text\n" +
+        "  font_name_east_asia — string (e.g. \"MS Mincho\"), for East Asian text\n" +
         "  color — hex string (e.g. \"FF0000\")\n" +
-        "  highlight — color name (yellow, green, cyan, etc.)\n" +
-        "  vertical_align — superscript, subscript, baseline\n\n" +
+        "  highlight — color name: yellow, green, cyan, magenta, blue, red, dark_blue, dark_cyan, dark_green,\n" +
+        "    dark_magenta, dark_red, dark_yellow, light_gray, dark_gray, black, white (none removes it)\n" +
+        "  vertical_align — superscript, subscript, baseline\n" +
+        "  small_caps — true sets, false removes\n" +
+        "  letter_spacing — number in points added between characters (e.g. 1.5; negative condenses)\n" +
+        "  code — true formats the run as inline code (monospaced, lightly shaded), false removes it\n" +
+        "  language — language tag (e.g. \"ar-SA\", \"he-IL\", \"fr-FR\"); right-to-left languages also set rtl\n" +
+        "  rtl — true marks the run as right-to-left text (bold, size and font also apply to Arabic/Hebrew script)\n\n" +
+        "Invalid values are rejected; properties that aren't run properties are ignored and listed in the result.\n" +
         "Omit path to style ALL runs in the document (including inside tables).\n" +
         "With path, styles all runs within the resolved element(s).\n" +
         "Use [id='...'] for stable targeting (e.g. /body/paragraph[id='1A2B3C4D']/run[id='5E6F7A8B']).\n" +
@@ -31,12 +39,13 @@ public sealed class StyleTools
     public static string StyleElement(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("JSON object of run-level style properties to merge.")] string style,
         [Description("Optional typed path. Omit to style all runs in the document.")] string? path = null)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
             var body = doc.MainDocumentPart?.Document?.Body
@@ -55,6 +64,9 @@ public sealed class StyleTools
             if (styleEl.ValueKind != JsonValueKind.Object)
                 return "Error: style must be a JSON object.";
 
+            if (StyleHelper.CheckRunStyle(styleEl, out var ignored) is { } styleError)
+                return $"Error: {styleError}";
+
             List<Run> runs;
             if (path is null)
             {
@@ -111,7 +123,9 @@ public sealed class StyleTools
             tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
             sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
 
-            return $"Styled {runs.Count} run(s).";
+            return ignored.Count == 0
+                ? $"Styled {runs.Count} run(s)."
+                : $"Styled {runs.Count} run(s). Ignored unsupported properties: {string.Join(", ", ignored)}.";
         }
         catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"styling element in '{doc_id}'"); }
         catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
@@ -122,10 +136,14 @@ public sealed class StyleTools
     [McpServerTool(Name = "style_paragraph"), Description(
         "Apply paragraph-level formatting using merge semantics — only specified properties change, all others are preserved.\n\n" +
         "Properties (set value to apply, JSON null to remove):\n" +
-        "  alignment — left, center, right, justify\n" +
+        "  alignment — left, center, right, justify (or both), distribute, start, end\n" +
+        "    (left/right are visual, also on RTL paragraphs)\n" +
+        "  bidi — true lays the paragraph out right to left\n" +
+        "  language — language tag for the paragraph and its runs; right-to-left languages also set bidi\n" +
         "  style — paragraph style name (e.g. \"Heading1\")\n" +
         "  spacing_before, spacing_after — integer in twips\n" +
-        "  line_spacing — integer in twips\n" +
+        "  line_spacing — integer; 240ths of a line (240 single, 360 1.5, 480 double) unless line_spacing_rule says otherwise\n" +
+        "  line_spacing_rule — auto (default), exact or at_least (the latter two read line_spacing in twips)\n" +
         "  indent_left, indent_right — integer in twips\n" +
         "  indent_first_line, indent_hanging — integer in twips\n" +
         "  shading — hex color string for background (e.g. \"FFFF00\")\n\n" +
@@ -136,12 +154,13 @@ public sealed class StyleTools
     public static string StyleParagraph(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("JSON object of paragraph-level style properties to merge.")] string style,
         [Description("Optional typed path. Omit to style all paragraphs in the document.")] string? path = null)
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             var session = tenant.Sessions.Get(doc_id);
             var doc = session.Document;
             var body = doc.MainDocumentPart?.Document?.Body
@@ -231,10 +250,12 @@ public sealed class StyleTools
         "  border_size — integer (default 4)\n" +
         "  width — integer, width_type — pct/dxa/auto\n" +
         "  table_style — style name\n" +
-        "  table_alignment — left, center, right\n\n" +
+        "  table_alignment — left, center, right, start, end\n" +
+        "  rtl — true orders the columns right to left\n\n" +
         "Cell style (applied to ALL cells in matched tables):\n" +
         "  shading — hex color, vertical_align — top/center/bottom\n" +
-        "  width — integer, borders — {top, bottom, left, right}\n\n" +
+        "  width — integer, borders — {top, bottom, left, right}\n" +
+        "  text_direction — top_to_bottom or bottom_to_top for rotated (vertical) text, horizontal to reset\n\n" +
         "Row style (applied to ALL rows in matched tables):\n" +
         "  height — integer, is_header — true/false\n\n" +
         "At least one of style, cell_style, or row_style must be provided.\n" +
@@ -243,7 +264,7 @@ public sealed class StyleTools
     public static string StyleTable(
         TenantScope tenant,
         SyncManager sync,
-        [Description("Session ID of the document.")] string doc_id,
+        [Description("Session ID or alias of the document.")] string doc_id,
         [Description("JSON object of table-level style properties to merge.")] string? style = null,
         [Description("JSON object of cell-level style properties to merge (applied to ALL cells).")] string? cell_style = null,
         [Description("JSON object of row-level style properties to merge (applied to ALL rows).")] string? row_style = null,
@@ -251,6 +272,7 @@ public sealed class StyleTools
     {
         try
         {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
             if (style is null && cell_style is null && row_style is null)
                 return "Error: At least one of style, cell_style, or row_style must be provided.";
 
@@ -0,0 +1,146 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using Microsoft.Extensions.Logging;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+using DocxMcp.Summaries;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class SummaryTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "generate_summary"), Description(
+        "Generate a summary (or abstract) of a document and save it into the document.\n\n" +
+        "The document text is split into chunks and sent to the configured summary endpoint " +
+        "(SUMMARY_ENDPOINT) or, when none is configured, to the client's model through MCP sampling.\n\n" +
+        "TARGETS:\n" +
+        "  property — a core document property: description (default), subject, title or keywords\n" +
+        "  section  — the content under the heading matching 'heading' (default 'Summary'), " +
+        "replaced by the summary; the heading is created at the start of the document if missing\n\n" +
+        "Examples:\n" +
+        "  generate_summary(doc_id, \"property\")\n" +
+        "  generate_summary(doc_id, \"section\", heading=\"Abstract\", max_words=150)")]
+    public static async Task<string> GenerateSummary(
+        McpServer server,
+        ILogger<SummaryTools> logger,
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        SummaryOptions options,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Where to save the summary: 'property' or 'section'.")] string target = "property",
+        [Description("Property to write when target is 'property'. Default: description.")] string property = "description",
+        [Description("Heading text of the section when target is 'section'. Default: 'Summary'.")] string heading = "Summary",
+        [Description("Maximum summary length in words. Default: 200.")] int max_words = 200,
+        [Description("Language to write the summary in (e.g. 'French'). Default: the document's language.")] string? language = null,
+        [Description("Extra instructions for the summarizer (e.g. 'Focus on obligations').")] string? instructions = null,
+        CancellationToken cancellationToken = default)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (target is not ("property" or "section"))
+                return $"Error: Unknown target '{target}'. Use 'property' or 'section'.";
+            if (target == "property" && !SummaryHelper.Properties.Contains(property.ToLowerInvariant()))
+                return $"Error: Unknown property '{property}'. Use one of: {string.Join(", ", SummaryHelper.Properties)}.";
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            ISummaryProvider provider;
+            if (options.Endpoint is not null)
+                provider = new HttpSummaryProvider(options);
+            else if (SamplingSummaryProvider.IsSupported(server))
+                provider = new SamplingSummaryProvider(server);
+            else
+                return "Error: No summary provider available. Configure SUMMARY_ENDPOINT " +
+                       "or use a client that supports sampling.";
+
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            var chunks = SummaryHelper.Chunk(doc, options.ChunkChars);
+            if (chunks.Count == 0)
+                return "Error: The document has no text to summarize.";
+
+            logger.LogDebug("generate_summary: doc_id={DocId}, provider={Provider}, chunks={Chunks}",
+                doc_id, provider.Name, chunks.Count);
+
+            var summary = await provider.SummarizeAsync(
+                new SummaryRequest(chunks, Math.Clamp(max_words, 20, 2000), language, instructions),
+                cancellationToken);
+
+            var walObj = new JsonObject
+            {
+                ["op"] = "set_summary",
+                ["target"] = target,
+                ["text"] = summary
+            };
+            if (target == "property")
+                walObj["property"] = property.ToLowerInvariant();
+            else
+                walObj["heading"] = heading;
+
+            Apply(doc, target, property, heading, summary);
+
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var result = new JsonObject
+            {
+                ["target"] = target,
+                ["provider"] = provider.Name,
+                ["chunks"] = chunks.Count,
+                ["summary"] = summary
+            };
+            if (target == "property")
+                result["property"] = property.ToLowerInvariant();
+            else
+                result["heading"] = heading;
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"summarizing '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay a set_summary WAL operation.
+    /// </summary>
+    internal static void ReplaySetSummary(JsonElement patch, WordprocessingDocument doc)
+    {
+        var target = patch.GetProperty("target").GetString()
+            ?? throw new InvalidOperationException("set_summary must have a 'target' field.");
+        var text = patch.GetProperty("text").GetString() ?? "";
+        var property = patch.TryGetProperty("property", out var p) ? p.GetString() ?? "description" : "description";
+        var heading = patch.TryGetProperty("heading", out var h) ? h.GetString() ?? "Summary" : "Summary";
+
+        Apply(doc, target, property, heading, text);
+    }
+
+    private static void Apply(WordprocessingDocument doc, string target, string property, string heading, string summary)
+    {
+        if (target == "property")
+            SummaryHelper.WriteProperty(doc, property, summary);
+        else
+            SummaryHelper.WriteSection(doc, heading, summary);
+    }
+}
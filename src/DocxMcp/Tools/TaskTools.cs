@@ -0,0 +1,93 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class TaskTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "extract_tasks"), Description(
+        "Extract the action items of a document (meeting notes, minutes, plans) as structured tasks, " +
+        "e.g. to sync them to a project-management tool.\n\n" +
+        "TASK SOURCES:\n" +
+        "  checkbox — checkbox list item: checkbox content control, checkbox bullet, ☐/☑ or [ ]/[x]\n" +
+        "  todo     — text after 'TODO:' or 'FIXME:'\n" +
+        "  action   — text after 'Action:', 'Action item:' or 'Follow-up:'\n\n" +
+        "A paragraph with several markers gives one task per marker. Each task has its text, the owner " +
+        "('@alice', 'Owner: Alice Martin', or a leading 'Alice:' / 'Alice will'), the due date as written " +
+        "('due March 5, 2026', 'by Friday', or an ISO date) with due_date in ISO form when the date is " +
+        "unambiguous, done for checkboxes, the heading of its section, and its range: the paragraph path " +
+        "with the offset and length of the task text, usable with replace_text.")]
+    public static string ExtractTasks(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Only return open tasks (unchecked checkboxes and markers). Default: false.")] bool? open_only = null,
+        [Description("Number of tasks to skip. Default: 0.")] int? offset = null,
+        [Description("Maximum number of tasks to return (1-100). Default: 100.")] int? limit = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var tasks = TaskHelper.Extract(session.Document);
+            var done = tasks.Count(t => t.Done == true);
+            if (open_only == true)
+                tasks = tasks.Where(t => t.Done != true).ToList();
+
+            var effectiveOffset = Math.Max(0, offset ?? 0);
+            var effectiveLimit = Math.Clamp(limit ?? 100, 1, 100);
+            var page = tasks.Skip(effectiveOffset).Take(effectiveLimit).ToList();
+
+            var arr = new JsonArray();
+            foreach (var task in page)
+            {
+                var obj = new JsonObject
+                {
+                    ["text"] = task.Text,
+                    ["source"] = task.Source
+                };
+                if (task.Done is not null)
+                    obj["done"] = task.Done;
+                if (task.Assignee is not null)
+                    obj["assignee"] = task.Assignee;
+                if (task.Due is not null)
+                    obj["due"] = task.Due;
+                if (task.DueDate is not null)
+                    obj["due_date"] = task.DueDate;
+                if (task.Section is not null)
+                    obj["section"] = task.Section;
+                obj["range"] = new JsonObject
+                {
+                    ["path"] = task.Path,
+                    ["offset"] = task.Offset,
+                    ["length"] = task.Length
+                };
+                arr.Add((JsonNode)obj);
+            }
+
+            var result = new JsonObject
+            {
+                ["total"] = tasks.Count,
+                ["done"] = open_only == true ? 0 : done,
+                ["offset"] = effectiveOffset,
+                ["limit"] = effectiveLimit,
+                ["count"] = page.Count,
+                ["tasks"] = arr
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"extracting tasks from '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
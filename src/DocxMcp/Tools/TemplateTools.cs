@@ -0,0 +1,223 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using Microsoft.Extensions.Logging;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+using DocxMcp.Grpc;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class TemplateTools
+{
+    [McpServerTool(Name = "template_save"), Description(
+        "Save an open document as a named template in the template library (letterheads, report skeletons, ...). " +
+        "Saving under an existing name replaces that template. " +
+        "Create documents from a template with document_open(template=...).")]
+    public static string TemplateSave(
+        ILogger<TemplateTools> logger,
+        TenantScope tenant,
+        [Description("Session ID or alias of the document to save as a template.")]
+        string doc_id,
+        [Description("Template name (e.g. 'letterhead').")]
+        string name)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            logger.LogDebug("template_save: doc_id={DocId}, name={Name}", doc_id, name);
+
+            using var session = tenant.Sessions.Get(doc_id);
+            tenant.Sessions.SaveTemplate(name, session.ToBytes());
+
+            return $"Template '{name}' saved from session '{doc_id}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "saving template"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "template_list"), Description(
+        "List the templates in the template library.")]
+    public static string TemplateList(TenantScope tenant)
+    {
+        try
+        {
+            var templates = tenant.Sessions.ListLibraryItems(LibraryKind.Template);
+
+            var arr = new JsonArray();
+            foreach (var t in templates)
+            {
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["name"] = t.Name,
+                    ["modified_at"] = t.ModifiedAt.ToString("o"),
+                    ["size_bytes"] = t.SizeBytes
+                });
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = templates.Count,
+                ["templates"] = arr
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing templates"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "lint_template"), Description(
+        "Check a template's {{placeholders}} and MERGEFIELD fields before generating documents from it. " +
+        "Read-only. Reports, with the paragraph path and the offset and length of the text in the paragraph:\n" +
+        "  - errors: unclosed '{{', stray '}}', empty placeholders, merge fields without a name, " +
+        "fields that never end or end without beginning\n" +
+        "  - warnings: placeholders split across runs (formatting changes or spell-check marks in the middle " +
+        "of a token, which break engines that replace text run by run), names that aren't plain identifiers, " +
+        "names that differ only in case\n" +
+        "  - info: field names used more than once\n\n" +
+        "Also lists every field name with its number of uses. Headers and footers are checked too " +
+        "(their issues have no path).")]
+    public static string LintTemplate(
+        TenantScope tenant,
+        [Description("Session ID or alias of the template document.")]
+        string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            using var session = tenant.Sessions.Get(doc_id);
+
+            var (issues, fields) = TemplateLintHelper.Lint(session.Document);
+
+            var issueArr = new JsonArray();
+            foreach (var issue in issues)
+            {
+                var obj = new JsonObject
+                {
+                    ["severity"] = issue.Severity,
+                    ["kind"] = issue.Kind,
+                    ["message"] = issue.Message,
+                    ["part"] = issue.Part,
+                    ["path"] = issue.Path,
+                    ["offset"] = issue.Offset,
+                    ["length"] = issue.Length,
+                    ["text"] = issue.Text
+                };
+                if (issue.Field is not null)
+                    obj["field"] = issue.Field;
+                issueArr.Add((JsonNode)obj);
+            }
+
+            var fieldArr = new JsonArray();
+            foreach (var (name, count) in fields)
+                fieldArr.Add((JsonNode)new JsonObject { ["name"] = name, ["count"] = count });
+
+            var result = new JsonObject
+            {
+                ["valid"] = issues.All(i => i.Severity != "error"),
+                ["errors"] = issues.Count(i => i.Severity == "error"),
+                ["warnings"] = issues.Count(i => i.Severity == "warning"),
+                ["issues"] = issueArr,
+                ["fields"] = fieldArr
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"linting template '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "list_fields"), Description(
+        "List the fillable fields of a document, including templates opened from disk or a connection: " +
+        "{{placeholder}} tokens, MERGEFIELD fields and DOCPROPERTY fields, in the body, headers and footers. Read-only.\n\n" +
+        "Field codes are read across runs, as Word stores them, and placeholders from the paragraph's text, so " +
+        "tokens split by formatting or spell-check marks are found (split_across_runs tells which). Each use has " +
+        "its kind, name, paragraph path (null in headers and footers), offset in the paragraph text, and the text " +
+        "it shows now; 'names' lists each distinct name with its kinds and number of uses.")]
+    public static string ListFields(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")]
+        string doc_id)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            using var session = tenant.Sessions.Get(doc_id);
+
+            var fields = TemplateLintHelper.ListFields(session.Document);
+
+            var uses = new JsonArray();
+            foreach (var field in fields)
+            {
+                var obj = new JsonObject
+                {
+                    ["kind"] = field.Kind,
+                    ["name"] = field.Name,
+                    ["part"] = field.Part,
+                    ["path"] = field.Path,
+                    ["offset"] = field.Offset,
+                    ["text"] = field.Text
+                };
+                if (field.Instruction is not null)
+                    obj["instruction"] = field.Instruction;
+                if (field.SplitAcrossRuns)
+                    obj["split_across_runs"] = true;
+                uses.Add((JsonNode)obj);
+            }
+
+            var names = new JsonArray();
+            foreach (var group in fields.GroupBy(f => f.Name))
+            {
+                var kinds = new JsonArray();
+                foreach (var kind in group.Select(f => f.Kind).Distinct())
+                    kinds.Add((JsonNode)kind);
+                names.Add((JsonNode)new JsonObject
+                {
+                    ["name"] = group.Key,
+                    ["kinds"] = kinds,
+                    ["count"] = group.Count()
+                });
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = fields.Count,
+                ["names"] = names,
+                ["fields"] = uses
+            };
+
+            return result.ToJsonString(new JsonSerializerOptions { WriteIndented = true });
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"listing fields of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "template_delete"), Description(
+        "Delete a template from the template library. Documents created from it are not affected.")]
+    public static string TemplateDelete(
+        TenantScope tenant,
+        [Description("Template name.")]
+        string name)
+    {
+        try
+        {
+            return tenant.Sessions.DeleteLibraryItem(LibraryKind.Template, name)
+                ? $"Template '{name}' deleted."
+                : $"No template named '{name}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "deleting template"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
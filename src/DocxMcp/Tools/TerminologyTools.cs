@@ -0,0 +1,333 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+using DocxMcp.Models;
+using DocxMcp.Paths;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class TerminologyTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "glossary_set"), Description(
+        "Create or replace a glossary (terminology rules) in the tenant's library.\n\n" +
+        "entries is a JSON array of objects:\n" +
+        "  term            — approved term (required)\n" +
+        "  variants        — unapproved spellings to replace with the term\n" +
+        "  translations    — approved translations by language, e.g. {\"fr\": \"contrat\"}\n" +
+        "  forbidden       — true to flag the term itself; 'replacement' is suggested if given\n" +
+        "  case_sensitive  — match case exactly (default: false)\n" +
+        "  note            — shown with findings\n\n" +
+        "Example:\n" +
+        "  [{\"term\": \"email\", \"variants\": [\"e-mail\"]},\n" +
+        "   {\"term\": \"agreement\", \"translations\": {\"fr\": \"contrat\"}},\n" +
+        "   {\"term\": \"guarantee\", \"forbidden\": true, \"replacement\": \"warranty\", \"note\": \"Legal\"}]")]
+    public static string GlossarySet(
+        TenantScope tenant,
+        [Description("JSON array of glossary entries.")] string entries,
+        [Description("Glossary name. Default: 'default'.")] string name = "default")
+    {
+        try
+        {
+            List<GlossaryEntry> parsed;
+            try
+            {
+                parsed = TerminologyHelper.Parse(entries);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            tenant.Sessions.SaveGlossary(name, TerminologyHelper.ToJson(parsed));
+            return $"Glossary '{name}' saved ({parsed.Count} entries).";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "saving glossary"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "glossary_get"), Description(
+        "Get a glossary's entries as JSON.")]
+    public static string GlossaryGet(
+        TenantScope tenant,
+        [Description("Glossary name. Default: 'default'.")] string name = "default")
+    {
+        try
+        {
+            return LoadGlossaryJson(tenant, name);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "loading glossary"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "glossary_list"), Description(
+        "List the glossaries in the tenant's library.")]
+    public static string GlossaryList(TenantScope tenant)
+    {
+        try
+        {
+            var glossaries = tenant.Sessions.ListLibraryItems(LibraryKind.Glossary);
+
+            var arr = new JsonArray();
+            foreach (var g in glossaries)
+            {
+                arr.Add((JsonNode)new JsonObject
+                {
+                    ["name"] = g.Name,
+                    ["modified_at"] = g.ModifiedAt.ToString("o"),
+                    ["size_bytes"] = g.SizeBytes
+                });
+            }
+
+            var result = new JsonObject
+            {
+                ["count"] = glossaries.Count,
+                ["glossaries"] = arr
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "listing glossaries"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "glossary_delete"), Description(
+        "Delete a glossary from the tenant's library.")]
+    public static string GlossaryDelete(
+        TenantScope tenant,
+        [Description("Glossary name.")] string name)
+    {
+        try
+        {
+            return tenant.Sessions.DeleteLibraryItem(LibraryKind.Glossary, name)
+                ? $"Glossary '{name}' deleted."
+                : $"No glossary named '{name}'.";
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, "deleting glossary"); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "check_terminology"), Description(
+        "Check a document against a glossary. Returns findings in document order, each with the " +
+        "paragraph path, the character offset and text of the match within the paragraph, " +
+        "the kind ('variant', 'untranslated' or 'forbidden'), the approved term and a suggested replacement.\n\n" +
+        "Pass language (e.g. 'fr') to check a translated document: terms with an approved translation " +
+        "in that language are flagged in favour of the translation.")]
+    public static string CheckTerminology(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Glossary name. Default: 'default'.")] string glossary = "default",
+        [Description("Language of the document for translation checks (e.g. 'fr').")] string? language = null,
+        [Description("Number of findings to skip. Default: 0.")] int? offset = null,
+        [Description("Maximum number of findings to return (1-100). Default: 100.")] int? limit = null)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var entries = TerminologyHelper.Parse(LoadGlossaryJson(tenant, glossary));
+            var session = tenant.Sessions.Get(doc_id);
+
+            var findings = TerminologyHelper.Check(session.Document, entries, language);
+
+            var effectiveOffset = Math.Max(0, offset ?? 0);
+            var effectiveLimit = Math.Clamp(limit ?? 100, 1, 100);
+            var page = findings.Skip(effectiveOffset).Take(effectiveLimit).ToList();
+
+            var arr = new JsonArray();
+            foreach (var f in page)
+                arr.Add((JsonNode)FindingToJson(f));
+
+            var result = new JsonObject
+            {
+                ["total"] = findings.Count,
+                ["offset"] = effectiveOffset,
+                ["limit"] = effectiveLimit,
+                ["count"] = page.Count,
+                ["findings"] = arr
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"checking terminology in '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "apply_terminology"), Description(
+        "Replace non-compliant terms in a document with the glossary's suggestions, preserving formatting. " +
+        "Findings without a suggestion (forbidden terms with no replacement) are left for manual review, " +
+        "as are matches that replace_text can't target precisely (the same text also occurs inside a longer word " +
+        "in that paragraph). Respects track changes.\n\n" +
+        "Run check_terminology first to review the findings.")]
+    public static string ApplyTerminology(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Glossary name. Default: 'default'.")] string glossary = "default",
+        [Description("Language of the document for translation checks (e.g. 'fr').")] string? language = null,
+        [Description("If true, reports what would be replaced without changing the document.")] bool dry_run = false)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (!dry_run && gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var entries = TerminologyHelper.Parse(LoadGlossaryJson(tenant, glossary));
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            var findings = TerminologyHelper.Check(doc, entries, language);
+
+            // One replace_text per (paragraph, matched text, suggestion), replacing every occurrence
+            var groups = findings
+                .GroupBy(f => (f.Path, f.Text, f.Suggestion))
+                .ToList();
+
+            var applied = new JsonArray();
+            var skipped = new JsonArray();
+            var walEntry = new JsonArray();
+            int replacements = 0;
+
+            foreach (var group in groups)
+            {
+                var (path, text, suggestion) = group.Key;
+                var count = group.Count();
+
+                string? reason = null;
+                if (suggestion is null)
+                    reason = "no suggestion";
+                else if (CountExact(doc, path, text) != count)
+                    reason = "text also occurs inside another word";
+
+                if (reason is not null)
+                {
+                    foreach (var f in group)
+                    {
+                        var obj = FindingToJson(f);
+                        obj["reason"] = reason;
+                        skipped.Add((JsonNode)obj);
+                    }
+                    continue;
+                }
+
+                var op = new ReplaceTextPatchOperation { Path = path, Find = text, Replace = suggestion!, MaxCount = count };
+                var made = count;
+                if (!dry_run)
+                {
+                    made = PatchTool.ExecuteReplaceText(op, doc, false).ReplacementsMade;
+                    walEntry.Add((JsonNode)new JsonObject
+                    {
+                        ["op"] = "replace_text",
+                        ["path"] = path,
+                        ["find"] = text,
+                        ["replace"] = suggestion,
+                        ["max_count"] = count
+                    });
+                }
+                replacements += made;
+
+                applied.Add((JsonNode)new JsonObject
+                {
+                    ["path"] = path,
+                    ["text"] = text,
+                    ["replacement"] = suggestion,
+                    ["count"] = made
+                });
+            }
+
+            if (walEntry.Count > 0)
+            {
+                var bytes = session.ToBytes();
+                tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+                sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+            }
+
+            var result = new JsonObject
+            {
+                ["dry_run"] = dry_run,
+                ["findings"] = findings.Count,
+                [dry_run ? "would_replace" : "replaced"] = replacements,
+                ["applied"] = applied,
+                ["skipped"] = skipped
+            };
+
+            return result.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"applying terminology to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Load a glossary, reporting a missing one as a tool error (not as a missing document).
+    /// </summary>
+    private static string LoadGlossaryJson(TenantScope tenant, string name)
+    {
+        try
+        {
+            return tenant.Sessions.LoadGlossary(name);
+        }
+        catch (KeyNotFoundException ex)
+        {
+            throw new McpException($"{ex.Message} Create one with glossary_set.");
+        }
+    }
+
+    /// <summary>
+    /// Occurrences of the exact text in a paragraph's runs. When this equals the
+    /// number of findings, replace_text on the paragraph touches only the findings.
+    /// </summary>
+    private static int CountExact(WordprocessingDocument doc, string path, string text)
+    {
+        var paragraph = (Paragraph)PathResolver.Resolve(DocxPath.Parse(path), doc).Single();
+        var runText = TerminologyHelper.RunText(paragraph);
+
+        int count = 0, idx = 0;
+        while ((idx = runText.IndexOf(text, idx, StringComparison.Ordinal)) >= 0)
+        {
+            count++;
+            idx += text.Length;
+        }
+        return count;
+    }
+
+    private static JsonObject FindingToJson(TerminologyFinding f)
+    {
+        var obj = new JsonObject
+        {
+            ["path"] = f.Path,
+            ["offset"] = f.Offset,
+            ["length"] = f.Text.Length,
+            ["text"] = f.Text,
+            ["kind"] = f.Kind,
+            ["term"] = f.Term,
+            ["suggestion"] = f.Suggestion
+        };
+        if (f.Note is not null)
+            obj["note"] = f.Note;
+        return obj;
+    }
+}
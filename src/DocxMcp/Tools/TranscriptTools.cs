@@ -0,0 +1,127 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class TranscriptTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "append_transcript_entries"), Description(
+        "Append speech/transcript entries (e.g. meeting minutes) to a document with consistent formatting.\n\n" +
+        "Each entry becomes a 'Transcript Entry' paragraph: the timestamp, then the speaker name aligned on a " +
+        "tab stop and colored per speaker, then the text with a hanging indent. Entries are added after the last " +
+        "transcript entry, or at the end of the document.\n\n" +
+        "Timestamps accept hh:mm:ss, mm:ss, a number of seconds or an ISO date-time, and are shown as hh:mm:ss.\n\n" +
+        "A speaker index (entry count and first timestamp per speaker) is kept under the index heading, " +
+        "created before the transcript if missing. Pass an empty index_heading to skip it.\n\n" +
+        "Example:\n" +
+        "  append_transcript_entries(doc_id, '[{\"speaker\":\"Alice\",\"timestamp\":\"00:01:05\",\"text\":\"Let's start.\"}]')")]
+    public static string AppendTranscriptEntries(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("JSON array of {speaker, timestamp, text} objects.")] string entries,
+        [Description("Heading of the speaker index. Default: 'Speakers'. Empty to skip the index.")] string index_heading = "Speakers")
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            List<TranscriptEntry> parsed;
+            try
+            {
+                parsed = TranscriptHelper.Parse(entries);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+            if (parsed.Count == 0)
+                return "Error: entries is empty.";
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+            var added = TranscriptHelper.Append(session.Document, parsed, index_heading);
+
+            var walEntries = new JsonArray();
+            foreach (var e in parsed)
+            {
+                walEntries.Add((JsonNode)new JsonObject
+                {
+                    ["speaker"] = e.Speaker,
+                    ["timestamp"] = e.Timestamp,
+                    ["text"] = e.Text
+                });
+            }
+            var walObj = new JsonObject
+            {
+                ["op"] = "append_transcript",
+                ["entries"] = walEntries,
+                ["index_heading"] = index_heading
+            };
+            var walEntry = new JsonArray();
+            walEntry.Add((JsonNode)walObj);
+            var bytes = session.ToBytes();
+            tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+            sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+
+            var ids = new JsonArray();
+            foreach (var p in added)
+                ids.Add((JsonNode?)ElementIdManager.GetId(p));
+
+            var speakers = new JsonArray();
+            foreach (var s in TranscriptHelper.Speakers(TranscriptHelper.ReadEntries(session.GetBody())))
+            {
+                speakers.Add((JsonNode)new JsonObject
+                {
+                    ["speaker"] = s.Name,
+                    ["entries"] = s.Entries,
+                    ["first_timestamp"] = s.FirstTimestamp
+                });
+            }
+
+            return new JsonObject
+            {
+                ["appended"] = added.Count,
+                ["ids"] = ids,
+                ["speakers"] = speakers
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"appending transcript entries to '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an append_transcript WAL operation.
+    /// </summary>
+    internal static void ReplayAppendTranscript(JsonElement patch, WordprocessingDocument doc)
+    {
+        var entries = patch.GetProperty("entries").EnumerateArray()
+            .Select(e => new TranscriptEntry(
+                e.GetProperty("speaker").GetString() ?? "",
+                e.GetProperty("timestamp").GetString() ?? "",
+                e.GetProperty("text").GetString() ?? ""))
+            .ToList();
+        var indexHeading = patch.TryGetProperty("index_heading", out var h) ? h.GetString() : null;
+
+        TranscriptHelper.Append(doc, entries, indexHeading);
+    }
+}
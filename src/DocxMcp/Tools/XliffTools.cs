@@ -0,0 +1,159 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Packaging;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.ExternalChanges;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class XliffTools
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "export_xliff"), Description(
+        "Export a document's translatable text as XLIFF 1.2 for translation tools (CAT tools, TMS).\n\n" +
+        "Each paragraph becomes a trans-unit keyed by its element ID. Inline formatting is protected: " +
+        "differently formatted runs are wrapped in <g id=\"gN\"> and non-text content (tabs, breaks, images, " +
+        "hyperlinks, bookmarks) becomes <x id=\"xN\"/> placeholders. Send the translated file back with import_xliff.")]
+    public static string ExportXliff(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Source language code (e.g. 'en-US').")] string source_lang,
+        [Description("Target language code (e.g. 'fr-FR').")] string target_lang)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+
+            var xliff = XliffHelper.Export(session.Document, doc_id, source_lang, target_lang);
+            return xliff.Declaration + Environment.NewLine + xliff.ToString();
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"exporting '{doc_id}' to XLIFF"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "import_xliff"), Description(
+        "Apply a translated XLIFF 1.2 file (from export_xliff) to a document. Each trans-unit's target " +
+        "replaces its paragraph's text, keeping the formatting of its <g> groups and restoring <x/> placeholders " +
+        "(placeholders missing from the target are appended to the paragraph).\n\n" +
+        "Units without a target, whose paragraph was deleted, or whose source text changed since the export are skipped.")]
+    public static string ImportXliff(
+        TenantScope tenant,
+        SyncManager sync,
+        ExternalChangeGate gate,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("XLIFF 1.2 document content.")] string xliff)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+
+            if (gate.HasPendingChanges(tenant.TenantId, doc_id))
+            {
+                return "Error: External changes detected. " +
+                       "Call get_external_changes to review and acknowledge before editing, " +
+                       "or use sync_external_changes to reload the document.";
+            }
+
+            var session = tenant.Sessions.Get(doc_id);
+
+            XliffImportResult result;
+            try
+            {
+                result = XliffHelper.Import(session.Document, xliff);
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (result.Updated > 0)
+            {
+                var walObj = new JsonObject
+                {
+                    ["op"] = "import_xliff",
+                    ["xliff"] = xliff
+                };
+                var walEntry = new JsonArray();
+                walEntry.Add((JsonNode)walObj);
+                var bytes = session.ToBytes();
+                tenant.Sessions.AppendWal(doc_id, walEntry.ToJsonString(), null, bytes);
+                sync.MaybeAutoSave(tenant.TenantId, doc_id, bytes);
+            }
+
+            var skipped = new JsonArray();
+            foreach (var (id, reason) in result.Skipped)
+                skipped.Add((JsonNode)new JsonObject { ["id"] = id, ["reason"] = reason });
+
+            return new JsonObject
+            {
+                ["units"] = result.Units,
+                ["updated"] = result.Updated,
+                ["skipped"] = skipped
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"importing XLIFF into '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    [McpServerTool(Name = "export_aligned_segments"), Description(
+        "Align two language versions of a document and export the pairs as a TMX 1.4 translation memory, " +
+        "to seed a translation memory from documents translated outside a CAT tool.\n\n" +
+        "Paragraphs are aligned using the document structure: headings only pair with headings of the same level, " +
+        "table cells with the cell at the same position, list items with list items, and lengths must be in " +
+        "proportion. A paragraph added or dropped in one version is left out without shifting the others; one " +
+        "split in two (or two merged) in the translation becomes one unit. With granularity 'sentence', aligned " +
+        "paragraphs with as many sentences on both sides are split into sentence pairs.\n\n" +
+        "Each <tu> records the paragraph IDs (x-source-id, x-target-id) and structural role (x-role); the header " +
+        "counts the paragraphs left unaligned (x-unaligned-source, x-unaligned-target).")]
+    public static string ExportAlignedSegments(
+        TenantScope tenant,
+        [Description("Session ID or alias of the source language version.")] string doc_id_a,
+        [Description("Session ID or alias of the translated version.")] string doc_id_b,
+        [Description("Source language code (e.g. 'en-US').")] string source_lang,
+        [Description("Target language code (e.g. 'fr-FR').")] string target_lang,
+        [Description("'sentence' (default) or 'paragraph'.")] string granularity = "sentence")
+    {
+        var current = doc_id_a;
+        try
+        {
+            doc_id_a = tenant.Sessions.ResolveId(doc_id_a);
+            doc_id_b = tenant.Sessions.ResolveId(doc_id_b);
+            if (granularity is not ("sentence" or "paragraph"))
+                return "Error: granularity must be 'sentence' or 'paragraph'.";
+            var sentences = granularity == "sentence";
+
+            current = doc_id_a;
+            var source = tenant.Sessions.Get(doc_id_a);
+            current = doc_id_b;
+            var target = tenant.Sessions.Get(doc_id_b);
+            var alignment = TranslationMemoryHelper.Align(source.Document, target.Document, sentences);
+            var tmx = TranslationMemoryHelper.ToTmx(alignment, source_lang, target_lang, sentences);
+            return tmx.Declaration + Environment.NewLine + tmx.ToString();
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"aligning '{doc_id_a}' with '{doc_id_b}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(current); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+
+    /// <summary>
+    /// Replay an import_xliff WAL operation.
+    /// </summary>
+    internal static void ReplayImportXliff(JsonElement patch, WordprocessingDocument doc)
+    {
+        var xliff = patch.GetProperty("xliff").GetString()
+            ?? throw new InvalidOperationException("import_xliff must have an 'xliff' field.");
+        XliffHelper.Import(doc, xliff);
+    }
+}
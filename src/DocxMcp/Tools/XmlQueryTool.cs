@@ -0,0 +1,116 @@
+using System.ComponentModel;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Grpc.Core;
+using ModelContextProtocol;
+using ModelContextProtocol.Server;
+using DocxMcp.Helpers;
+
+namespace DocxMcp.Tools;
+
+[McpServerToolType]
+public sealed class XmlQueryTool
+{
+    private static readonly JsonSerializerOptions JsonOpts = new() { WriteIndented = true };
+
+    [McpServerTool(Name = "query_document_xml"), Description(
+        "Run an XPath 1.0 query over the raw XML of a document part, for structural questions the typed " +
+        "query tool can't answer. Read-only; reflects unsaved edits.\n\n" +
+        "PARTS: document, styles, numbering, settings, comments, footnotes, endnotes, fonts, theme, " +
+        "header1..N, footer1..N, or a part URI (e.g. /docProps/core.xml). Pass part=\"list\" to list the parts.\n\n" +
+        "PREFIXES: w, r, wp, a, pic, m, v, mc, w14, w15, cp, dc, dcterms and dmcp (element IDs) are always bound, " +
+        "plus any prefix declared on the part's root element.\n\n" +
+        "Each match has its absolute location (e.g. /w:document[1]/w:body[1]/w:p[3]), line and column, " +
+        "its outer XML (elements), value (attributes, text) and dmcp:id when present. " +
+        "Expressions returning a number, string or boolean (e.g. count(//w:p)) return a single result.\n\n" +
+        "Examples:\n" +
+        "  query_document_xml(doc_id, \"document\", \"//w:tbl[.//w:vMerge]\")\n" +
+        "  query_document_xml(doc_id, \"styles\", \"//w:style[@w:type='paragraph']/@w:styleId\")\n" +
+        "  query_document_xml(doc_id, \"document\", \"count(//w:p[not(.//w:t)])\")")]
+    public static string QueryDocumentXml(
+        TenantScope tenant,
+        [Description("Session ID or alias of the document.")] string doc_id,
+        [Description("Part alias or URI, or 'list' to list the queryable parts.")] string part,
+        [Description("XPath 1.0 expression.")] string? xpath = null,
+        [Description("Number of matches to skip. Default: 0.")] int? offset = null,
+        [Description("Maximum number of matches to return (1-50). Default: 50.")] int? limit = null,
+        [Description("Maximum characters of XML per match. Default: 2000.")] int max_chars = 2000)
+    {
+        try
+        {
+            doc_id = tenant.Sessions.ResolveId(doc_id);
+            var session = tenant.Sessions.Get(doc_id);
+            var doc = session.Document;
+
+            if (part == "list")
+            {
+                var names = new JsonArray();
+                foreach (var name in XmlQueryHelper.ListParts(doc))
+                    names.Add((JsonNode?)name);
+                return new JsonObject { ["parts"] = names }.ToJsonString(JsonOpts);
+            }
+
+            if (string.IsNullOrWhiteSpace(xpath))
+                return "Error: xpath is required.";
+
+            List<XmlQueryMatch> matches;
+            object? scalar;
+            try
+            {
+                var target = XmlQueryHelper.ResolvePart(doc, part);
+                (matches, scalar) = XmlQueryHelper.Query(target, xpath, Math.Max(100, max_chars));
+            }
+            catch (ArgumentException ex)
+            {
+                return $"Error: {ex.Message}";
+            }
+
+            if (scalar is not null)
+            {
+                var value = scalar switch
+                {
+                    bool b => (JsonNode)b,
+                    double d => (JsonNode)d,
+                    _ => (JsonNode?)scalar.ToString()
+                };
+                return new JsonObject { ["part"] = part, ["result"] = value }.ToJsonString(JsonOpts);
+            }
+
+            var effectiveOffset = Math.Max(0, offset ?? 0);
+            var effectiveLimit = Math.Clamp(limit ?? 50, 1, 50);
+            var page = matches.Skip(effectiveOffset).Take(effectiveLimit).ToList();
+
+            var items = new JsonArray();
+            foreach (var m in page)
+            {
+                var item = new JsonObject
+                {
+                    ["type"] = m.Type,
+                    ["location"] = m.Location,
+                    ["line"] = m.Line,
+                    ["column"] = m.Column
+                };
+                if (m.Name is not null)
+                    item["name"] = m.Name;
+                if (m.Id is not null)
+                    item["id"] = m.Id;
+                item[m.Type == "element" ? "xml" : "value"] = m.Value;
+                items.Add((JsonNode)item);
+            }
+
+            return new JsonObject
+            {
+                ["part"] = part,
+                ["total"] = matches.Count,
+                ["offset"] = effectiveOffset,
+                ["limit"] = effectiveLimit,
+                ["count"] = page.Count,
+                ["items"] = items
+            }.ToJsonString(JsonOpts);
+        }
+        catch (RpcException ex) { throw GrpcErrorHelper.Wrap(ex, $"querying the XML of '{doc_id}'"); }
+        catch (KeyNotFoundException) { throw GrpcErrorHelper.WrapNotFound(doc_id); }
+        catch (McpException) { throw; }
+        catch (Exception ex) { throw new McpException(ex.Message, ex); }
+    }
+}
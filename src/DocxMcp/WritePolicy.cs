@@ -0,0 +1,146 @@
+using System.Collections.Concurrent;
+using DocumentFormat.OpenXml.Packaging;
+using ModelContextProtocol;
+
+namespace DocxMcp;
+
+/// <summary>
+/// One way a document breaks a write policy rule. Part is "body", "header1", "footer2"...;
+/// Path is the typed path of the offending element in the body, when it has one.
+/// </summary>
+public sealed record PolicyViolation(string Rule, string Message, string Part = "body", string? Path = null);
+
+/// <summary>
+/// A rule documents must follow to be written. Deployments add their own by registering
+/// implementations in the service collection; <see cref="WritePolicy"/> runs them before
+/// every edit is persisted.
+/// </summary>
+public interface IWriteValidator
+{
+    /// <summary>Name of the rule, reported with its violations.</summary>
+    string Rule { get; }
+
+    /// <summary>Every violation of the rule in the document, in document order.</summary>
+    IEnumerable<PolicyViolation> Validate(WordprocessingDocument doc);
+}
+
+/// <summary>
+/// An edit refused by <see cref="WritePolicy"/>: the violations it would introduce.
+/// </summary>
+public sealed class WritePolicyViolationException(IReadOnlyList<PolicyViolation> violations)
+    : McpException(Describe(violations))
+{
+    public IReadOnlyList<PolicyViolation> Violations { get; } = violations;
+
+    private static string Describe(IReadOnlyList<PolicyViolation> violations)
+    {
+        var lines = violations.Select(v =>
+            $"- [{v.Rule}] {(v.Path ?? v.Part)}: {v.Message}");
+        return $"Edit rejected: it breaks the write policy in {violations.Count} place(s). " +
+               "Nothing was saved.\n" + string.Join("\n", lines);
+    }
+}
+
+/// <summary>
+/// Policy enforcement at write time: validators run on the document an edit produces,
+/// and the edit is refused when it introduces violations. Violations the document already
+/// had when it was loaded don't block edits, so a non-compliant document can still be
+/// fixed step by step. The state a session was loaded in is kept in memory, per session.
+/// </summary>
+public sealed class WritePolicy
+{
+    private readonly ConcurrentDictionary<string, List<PolicyViolation>> _known = new();
+
+    public IReadOnlyList<IWriteValidator> Validators { get; }
+
+    public WritePolicy(IEnumerable<IWriteValidator> validators)
+    {
+        Validators = validators.ToList();
+    }
+
+    /// <summary>
+    /// The built-in validators enabled by DOCX_POLICY_FORBID_EXTERNAL_LINKS,
+    /// DOCX_POLICY_REQUIRE_ALT_TEXT, DOCX_POLICY_MAX_TABLE_ROWS and
+    /// DOCX_POLICY_MAX_TABLE_COLUMNS, followed by <paramref name="custom"/>.
+    /// </summary>
+    public static WritePolicy FromEnvironment(IEnumerable<IWriteValidator>? custom = null)
+    {
+        var validators = new List<IWriteValidator>();
+        if (IsEnabled("DOCX_POLICY_FORBID_EXTERNAL_LINKS"))
+            validators.Add(new ExternalLinkValidator());
+        if (IsEnabled("DOCX_POLICY_REQUIRE_ALT_TEXT"))
+            validators.Add(new AltTextValidator());
+
+        var rows = Environment.GetEnvironmentVariable("DOCX_POLICY_MAX_TABLE_ROWS");
+        var columns = Environment.GetEnvironmentVariable("DOCX_POLICY_MAX_TABLE_COLUMNS");
+        var maxRows = int.TryParse(rows, out var r) && r > 0 ? r : 0;
+        var maxColumns = int.TryParse(columns, out var c) && c > 0 ? c : 0;
+        if (maxRows > 0 || maxColumns > 0)
+            validators.Add(new TableSizeValidator(maxRows, maxColumns));
+
+        if (custom is not null)
+            validators.AddRange(custom);
+        return new WritePolicy(validators);
+    }
+
+    /// <summary>
+    /// Every violation of every validator in the document.
+    /// </summary>
+    public List<PolicyViolation> Validate(WordprocessingDocument doc) =>
+        Validators.SelectMany(v => v.Validate(doc)).ToList();
+
+    /// <summary>
+    /// Record the violations of a session's document as loaded, so edits are only
+    /// refused for the ones they add.
+    /// </summary>
+    public void Observe(string sessionId, WordprocessingDocument doc)
+    {
+        if (Validators.Count > 0)
+            _known[sessionId] = Validate(doc);
+    }
+
+    /// <summary>
+    /// Check the document an edit produced, or throw <see cref="WritePolicyViolationException"/>
+    /// with the violations the edit introduced. Violations are matched by rule and message,
+    /// since paths shift as content moves.
+    /// </summary>
+    public void Check(string sessionId, byte[] documentBytes)
+    {
+        if (Validators.Count == 0)
+            return;
+
+        List<PolicyViolation> after;
+        using (var stream = new MemoryStream(documentBytes))
+        using (var doc = WordprocessingDocument.Open(stream, false))
+            after = Validate(doc);
+
+        List<PolicyViolation> before = _known.TryGetValue(sessionId, out var known) ? known : [];
+        var remaining = before
+            .GroupBy(v => (v.Rule, v.Message))
+            .ToDictionary(g => g.Key, g => g.Count());
+        var introduced = new List<PolicyViolation>();
+        foreach (var violation in after)
+        {
+            var key = (violation.Rule, violation.Message);
+            if (remaining.TryGetValue(key, out var count) && count > 0)
+                remaining[key] = count - 1;
+            else
+                introduced.Add(violation);
+        }
+
+        if (introduced.Count > 0)
+            throw new WritePolicyViolationException(introduced);
+        _known[sessionId] = after;
+    }
+
+    /// <summary>
+    /// Drop what is tracked for a closed session.
+    /// </summary>
+    public void Forget(string sessionId) => _known.TryRemove(sessionId, out _);
+
+    private static bool IsEnabled(string name)
+    {
+        var value = Environment.GetEnvironmentVariable(name);
+        return value is "1" || string.Equals(value, "true", StringComparison.OrdinalIgnoreCase);
+    }
+}
@@ -0,0 +1,161 @@
+using System.Text.RegularExpressions;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DW = DocumentFormat.OpenXml.Drawing.Wordprocessing;
+
+namespace DocxMcp;
+
+/// <summary>
+/// The body, headers and footers of a document, with the names violations report them by.
+/// </summary>
+internal static class PolicyParts
+{
+    public static IEnumerable<(OpenXmlPart Part, OpenXmlElement Root, string Name)> Of(WordprocessingDocument doc)
+    {
+        var main = doc.MainDocumentPart;
+        if (main?.Document is null)
+            yield break;
+
+        yield return (main, main.Document, "body");
+        var i = 0;
+        foreach (var header in main.HeaderParts)
+        {
+            i++;
+            if (header.Header is not null)
+                yield return (header, header.Header, $"header{i}");
+        }
+        i = 0;
+        foreach (var footer in main.FooterParts)
+        {
+            i++;
+            if (footer.Footer is not null)
+                yield return (footer, footer.Footer, $"footer{i}");
+        }
+    }
+
+    /// <summary>
+    /// Typed path of the body element holding <paramref name="element"/>, or null outside the body.
+    /// </summary>
+    public static string? PathOf(OpenXmlElement element, WordprocessingDocument doc)
+    {
+        var body = doc.MainDocumentPart?.Document?.Body;
+        OpenXmlElement? holder = element as Table;
+        holder ??= element.Ancestors<Paragraph>().FirstOrDefault() ?? element as Paragraph;
+        return body is null || holder is null ? null : TerminologyHelper.PathOf(holder, body);
+    }
+}
+
+/// <summary>
+/// Forbids links out of the document: external hyperlinks, HYPERLINK fields to URLs, and
+/// external relationships such as linked images or attached templates.
+/// </summary>
+public sealed partial class ExternalLinkValidator : IWriteValidator
+{
+    public string Rule => "no_external_links";
+
+    [GeneratedRegex("""^\s*HYPERLINK\s+"?(?<target>[^"\s]+)""", RegexOptions.IgnoreCase)]
+    private static partial Regex HyperlinkField();
+
+    public IEnumerable<PolicyViolation> Validate(WordprocessingDocument doc)
+    {
+        foreach (var (part, root, name) in PolicyParts.Of(doc))
+        {
+            foreach (var link in root.Descendants<Hyperlink>())
+            {
+                if (link.Id?.Value is not { } id)
+                    continue;
+                var rel = part.HyperlinkRelationships.FirstOrDefault(r => r.Id == id);
+                if (rel is { IsExternal: true })
+                    yield return new PolicyViolation(Rule, $"Link to '{rel.Uri}'.", name, PolicyParts.PathOf(link, doc));
+            }
+
+            var instructions = root.Descendants<FieldCode>().Select(f => ((OpenXmlElement)f, f.Text))
+                .Concat(root.Descendants<SimpleField>().Select(f => ((OpenXmlElement)f, f.Instruction?.Value ?? "")));
+            foreach (var (field, instruction) in instructions)
+            {
+                var match = HyperlinkField().Match(instruction);
+                // HYPERLINK \l "bookmark" stays inside the document
+                if (match.Success && !match.Groups["target"].Value.StartsWith('\\'))
+                {
+                    yield return new PolicyViolation(Rule, $"HYPERLINK field to '{match.Groups["target"].Value}'.",
+                        name, PolicyParts.PathOf(field, doc));
+                }
+            }
+
+            foreach (var rel in part.ExternalRelationships)
+                yield return new PolicyViolation(Rule, $"External reference to '{rel.Uri}' ({ShortType(rel.RelationshipType)}).", name);
+        }
+
+        var settings = doc.MainDocumentPart?.DocumentSettingsPart;
+        if (settings is not null)
+        {
+            foreach (var rel in settings.ExternalRelationships)
+                yield return new PolicyViolation(Rule, $"External reference to '{rel.Uri}' ({ShortType(rel.RelationshipType)}).", "settings");
+        }
+    }
+
+    private static string ShortType(string relationshipType) =>
+        relationshipType[(relationshipType.LastIndexOf('/') + 1)..];
+}
+
+/// <summary>
+/// Requires a description (alt text) on every picture, chart and shape drawing.
+/// Drawings Word marks as decorative are exempt.
+/// </summary>
+public sealed class AltTextValidator : IWriteValidator
+{
+    /// <summary>URI of Word's decorative-image extension on wp:docPr.</summary>
+    private const string DecorativeExtension = "{C183D7F6-B498-43B3-948B-1728B52AA6E4}";
+
+    public string Rule => "require_alt_text";
+
+    public IEnumerable<PolicyViolation> Validate(WordprocessingDocument doc)
+    {
+        foreach (var (_, root, name) in PolicyParts.Of(doc))
+        {
+            foreach (var drawing in root.Descendants<Drawing>())
+            {
+                var docPr = drawing.Descendants<DW.DocProperties>().FirstOrDefault();
+                if (docPr is null || !string.IsNullOrWhiteSpace(docPr.Description?.Value))
+                    continue;
+                if (docPr.Descendants<DocumentFormat.OpenXml.Drawing.NonVisualDrawingPropertiesExtension>().Any(e => e.Uri?.Value == DecorativeExtension))
+                    continue;
+
+                var label = docPr.Name?.Value is { Length: > 0 } drawingName ? $"'{drawingName}'" : "A drawing";
+                yield return new PolicyViolation(Rule, $"{label} has no alt text.", name, PolicyParts.PathOf(drawing, doc));
+            }
+        }
+    }
+}
+
+/// <summary>
+/// Caps the number of rows and columns of every table, nested ones included
+/// (0 leaves a dimension unlimited). Columns are counted on the table grid.
+/// </summary>
+public sealed class TableSizeValidator(int maxRows, int maxColumns) : IWriteValidator
+{
+    public int MaxRows { get; } = Math.Max(0, maxRows);
+    public int MaxColumns { get; } = Math.Max(0, maxColumns);
+
+    public string Rule => "max_table_size";
+
+    public IEnumerable<PolicyViolation> Validate(WordprocessingDocument doc)
+    {
+        foreach (var (_, root, name) in PolicyParts.Of(doc))
+        {
+            foreach (var table in root.Descendants<Table>())
+            {
+                var rows = table.Elements<TableRow>().Count();
+                var columns = table.GetFirstChild<TableGrid>()?.Elements<GridColumn>().Count()
+                    ?? table.Elements<TableRow>().Select(r => r.Elements<TableCell>().Count()).DefaultIfEmpty(0).Max();
+
+                if (MaxRows > 0 && rows > MaxRows)
+                    yield return new PolicyViolation(Rule, $"Table has {rows} rows (at most {MaxRows}).", name, PolicyParts.PathOf(table, doc));
+                if (MaxColumns > 0 && columns > MaxColumns)
+                    yield return new PolicyViolation(Rule, $"Table has {columns} columns (at most {MaxColumns}).", name, PolicyParts.PathOf(table, doc));
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class AutofitTests
+{
+    private static (DocxSession Session, Table Table) CreateDoc(params string[][] rows)
+    {
+        var session = DocxSession.Create();
+        var table = new Table(rows.Select(cells => new TableRow(cells.Select(text =>
+            new TableCell(new Paragraph(new Run(new Text(text))))))));
+        session.GetBody().AppendChild(table);
+        return (session, table);
+    }
+
+    private static int[] Grid(Table table) =>
+        table.GetFirstChild<TableGrid>()!.Elements<GridColumn>().Select(g => int.Parse(g.Width!.Value!)).ToArray();
+
+    [Fact]
+    public void Contents_SizesColumnsToTheirText()
+    {
+        var (session, table) = CreateDoc(["1", "A fairly long description of the item"], ["2", "Short"]);
+        using var _ = session;
+
+        var fit = TableFitHelper.Autofit(session.Document, table, "contents");
+
+        // 10pt text: 100 twips per character, plus 216 twips of cell margins
+        Assert.Equal([360, 3916], Grid(table));
+        Assert.Equal(4276, fit.Width);
+        Assert.Equal("auto", table.GetFirstChild<TableProperties>()!.TableWidth!.Type!.InnerText);
+        Assert.Equal("3916", table.Descendants<TableCell>().ElementAt(1).TableCellProperties!.TableCellWidth!.Width!.Value);
+    }
+
+    [Fact]
+    public void WindowAndFixed_FillOrLockTheWidth()
+    {
+        var (session, table) = CreateDoc(["Name", "Notes"], ["Alice", "Joined in 2021"]);
+        using var _ = session;
+
+        TableFitHelper.Autofit(session.Document, table, "Window");
+        Assert.Equal(9360, Grid(table).Sum()); // Letter with 1in margins
+
+        TableFitHelper.Autofit(session.Document, table, "fixed");
+        var tblPr = table.GetFirstChild<TableProperties>()!;
+        Assert.Equal("fixed", tblPr.TableLayout!.Type!.InnerText);
+        Assert.Equal(Grid(table).Sum().ToString(), tblPr.TableWidth!.Width!.Value);
+    }
+
+    [Fact]
+    public void Autofit_ShrinksToTheTextWidth()
+    {
+        var (session, table) = CreateDoc([new string('x', 80), new string('y', 80)]);
+        using var _ = session;
+
+        TableFitHelper.Autofit(session.Document, table, "contents");
+
+        Assert.Equal(9360, Grid(table).Sum());
+        Assert.Throws<ArgumentException>(() => TableFitHelper.Autofit(session.Document, table, "stretch"));
+    }
+}
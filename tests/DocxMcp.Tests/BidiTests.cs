@@ -0,0 +1,73 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class BidiTests
+{
+    private static JsonElement Json(string json) => JsonDocument.Parse(json).RootElement;
+
+    [Theory]
+    [InlineData("ar-SA", true)]
+    [InlineData("he", true)]
+    [InlineData("fa-IR", true)]
+    [InlineData("uz-Arab-AF", true)]
+    [InlineData("fr-FR", false)]
+    [InlineData("en", false)]
+    public void IsRtlLanguage_RecognisesScripts(string language, bool expected)
+    {
+        Assert.Equal(expected, BidiHelper.IsRtlLanguage(language));
+    }
+
+    [Fact]
+    public void CreateParagraphProperties_MirrorsAlignmentForRtlLanguage()
+    {
+        var props = ElementFactory.CreateParagraphProperties(Json("{\"language\":\"ar-SA\",\"alignment\":\"right\"}"));
+
+        Assert.True(BidiHelper.IsBidi(props));
+        Assert.Equal("left", props.Justification!.Val!.InnerText);
+    }
+
+    [Fact]
+    public void CreateRunProperties_RtlRunGetsComplexScriptProperties()
+    {
+        var props = ElementFactory.CreateRunProperties(
+            Json("{\"language\":\"he-IL\",\"bold\":true,\"font_size\":14,\"font_name\":\"David\"}"));
+
+        Assert.NotNull(props.RightToLeftText);
+        Assert.Equal("he-IL", props.Languages!.Bidi!.Value);
+        Assert.NotNull(props.BoldComplexScript);
+        Assert.Equal("28", props.FontSizeComplexScript!.Val!.Value);
+        Assert.Equal("David", props.RunFonts!.ComplexScript!.Value);
+    }
+
+    [Fact]
+    public void MergeParagraphProperties_LanguageAppliesToRuns()
+    {
+        var paragraph = new Paragraph(
+            new Run(new RunProperties(new Languages { Val = "en-US" }), new Text("שלום")));
+
+        StyleHelper.MergeParagraphProperties(paragraph, Json("{\"language\":\"he-IL\",\"alignment\":\"start\"}"));
+
+        Assert.True(BidiHelper.IsBidi(paragraph.ParagraphProperties));
+        Assert.Equal("left", paragraph.ParagraphProperties!.Justification!.Val!.InnerText);
+        Assert.NotNull(paragraph.GetFirstChild<Run>()!.RunProperties!.RightToLeftText);
+
+        StyleHelper.MergeParagraphProperties(paragraph, Json("{\"bidi\":false}"));
+        Assert.False(BidiHelper.IsBidi(paragraph.ParagraphProperties));
+    }
+
+    [Fact]
+    public void MergeTableProperties_SetsVisualDirection()
+    {
+        var table = new Table(new TableRow(new TableCell(new Paragraph())));
+
+        StyleHelper.MergeTableProperties(table, Json("{\"rtl\":true,\"table_alignment\":\"right\"}"));
+
+        var tblPr = table.GetFirstChild<TableProperties>()!;
+        Assert.True(BidiHelper.IsRtl(tblPr));
+        Assert.Equal("left", tblPr.TableJustification!.Val!.InnerText);
+    }
+}
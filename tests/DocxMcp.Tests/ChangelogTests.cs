@@ -0,0 +1,78 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Persistence;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ChangelogTests
+{
+    private static Paragraph Heading(string text) =>
+        new(new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }), new Run(new Text(text)));
+
+    private static Paragraph Para(string text) => new(new Run(new Text(text)));
+
+    private static WalEntry Entry(string patches) => new() { Patches = patches, Timestamp = DateTime.UtcNow };
+
+    [Fact]
+    public void Collect_GroupsChangesBySection()
+    {
+        using var before = DocxSession.Create();
+        before.GetBody().Append(Heading("Terms"), Para("a"), Para("b"), Para("c"));
+        using var after = DocxSession.Create();
+        after.GetBody().Append(
+            Heading("Terms"), Para("a2"), Para("b2"), Para("c2"),
+            new Table(new TableRow(new TableCell(Para("x")))),
+            Heading("Pricing"), Para("Fees"));
+
+        var entries = new[]
+        {
+            Entry("""
+                [{"op":"replace","path":"/body/paragraph[1]","value":{"type":"paragraph","text":"a2"}},
+                 {"op":"replace","path":"/body/paragraph[2]","value":{"type":"paragraph","text":"b2"}}]
+                """),
+            Entry("""[{"op":"replace","path":"/body/paragraph[3]","value":{"type":"paragraph","text":"c2"}}]"""),
+            Entry("""[{"op":"add","path":"/body/children/4","value":{"type":"table","rows":[["x"]]}}]"""),
+            Entry("""[{"op":"add","path":"/body/children/5","value":{"type":"heading","level":1,"text":"Pricing"}}]"""),
+            Entry("""[{"op":"add","path":"/body/children/6","value":{"type":"paragraph","text":"Fees"}}]"""),
+        };
+
+        var changes = ChangelogHelper.Collect(entries, before.Document, after.Document);
+        var text = ChangelogHelper.Format(changes);
+
+        Assert.Equal(
+            """
+            Edited 3 paragraphs in 'Terms', inserted 1 table in 'Terms', added section 'Pricing' and 1 more change
+
+            - Edited 3 paragraphs in 'Terms'
+            - Inserted 1 table in 'Terms'
+            - Added section 'Pricing'
+            - Added 1 paragraph in 'Pricing'
+            """.ReplaceLineEndings("\n"),
+            text.ReplaceLineEndings("\n"));
+    }
+
+    [Fact]
+    public void Collect_RemovedElementsAreNamedFromTheEarlierDocument()
+    {
+        using var before = DocxSession.Create();
+        before.GetBody().Append(Heading("Scope"), Para("gone"), Para("kept"));
+        using var after = DocxSession.Create();
+        after.GetBody().Append(Heading("Scope"), Para("kept"));
+
+        var changes = ChangelogHelper.Collect(
+            [Entry("""[{"op":"remove","path":"/body/paragraph[1]"},{"op":"sort_table","path":"/body/table[0]"}]""")],
+            before.Document, after.Document);
+
+        Assert.Equal(
+            [(new ChangelogItem("removed", "paragraph", "Scope"), 1),
+             (new ChangelogItem("applied", "sort table", null, Named: true), 1)],
+            changes);
+    }
+
+    [Fact]
+    public void Format_NoChanges_IsEmpty()
+    {
+        Assert.Equal("", ChangelogHelper.Format([]));
+    }
+}
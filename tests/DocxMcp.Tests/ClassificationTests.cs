@@ -0,0 +1,102 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.CustomProperties;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ClassificationTests
+{
+    private static readonly ClassificationPolicy Policy = new();
+
+    private static AppliedLabel Applied(string name) =>
+        new(Policy.Find(name)!, "Privileged", new DateTime(2026, 3, 1, 12, 0, 0, DateTimeKind.Utc), "action-1");
+
+    private static List<string> Markings(DocxSession session, string tag) =>
+        session.Document.MainDocumentPart!.HeaderParts.Select(h => (DocumentFormat.OpenXml.OpenXmlElement)h.Header!)
+            .Concat(session.Document.MainDocumentPart.FooterParts.Select(f => f.Footer!))
+            .SelectMany(root => root.Descendants<SdtBlock>())
+            .Where(s => s.SdtProperties?.GetFirstChild<Tag>()?.Val?.Value == tag)
+            .Select(s => s.InnerText)
+            .ToList();
+
+    [Fact]
+    public void Apply_WritesMsipPropertiesAndMarkings()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().Append(new Paragraph(new Run(new Text("Body"))));
+
+        ClassificationHelper.Apply(session.Document, Applied("Highly Confidential"));
+
+        var label = Assert.Single(ClassificationHelper.Read(session.Document));
+        Assert.Equal("Highly Confidential", label.Name);
+        Assert.True(label.Enabled);
+        Assert.Equal("Privileged", label.Method);
+        Assert.Equal("2026-03-01T12:00:00Z", label.SetDate);
+        Assert.Equal(7, label.ContentBits);
+        Assert.Contains(session.Document.CustomFilePropertiesPart!.Properties!.Elements<CustomDocumentProperty>(),
+            p => p.Name!.Value == $"MSIP_Label_{label.Id}_Enabled");
+
+        Assert.Equal(["HIGHLY CONFIDENTIAL"], Markings(session, ClassificationHelper.HeaderTag));
+        Assert.Equal(["Highly Confidential"], Markings(session, ClassificationHelper.FooterTag));
+        Assert.Single(Markings(session, ClassificationHelper.WatermarkTag));
+    }
+
+    [Fact]
+    public void Apply_ReplacesThePreviousLabelAndMarkings()
+    {
+        using var session = DocxSession.Create();
+        ClassificationHelper.Apply(session.Document, Applied("Highly Confidential"));
+
+        ClassificationHelper.Apply(session.Document, Applied("General"));
+
+        var label = Assert.Single(ClassificationHelper.Read(session.Document));
+        Assert.Equal("General", label.Name);
+        Assert.Empty(Markings(session, ClassificationHelper.HeaderTag));
+        Assert.Empty(Markings(session, ClassificationHelper.WatermarkTag));
+        Assert.Equal(["General"], Markings(session, ClassificationHelper.FooterTag));
+
+        ClassificationHelper.Apply(session.Document, null);
+
+        Assert.Empty(ClassificationHelper.Read(session.Document));
+        Assert.Empty(Markings(session, ClassificationHelper.FooterTag));
+    }
+
+    [Fact]
+    public void Policy_ParseRequiresGuidIds()
+    {
+        var policy = ClassificationPolicy.Parse(
+            """{"labels": [{"name": "Internal", "id": "{0E5A1C2B-3D4F-4A5B-8C6D-7E8F9A0B1C2D}", "footer": "Internal"}]}""");
+
+        var label = Assert.Single(policy.Labels);
+        Assert.Equal("0e5a1c2b-3d4f-4a5b-8c6d-7e8f9a0b1c2d", label.Id);
+        Assert.Same(label, policy.Find("internal"));
+        Assert.Equal(2, label.ContentBits);
+        Assert.Throws<FormatException>(() => ClassificationPolicy.Parse("""{"labels": [{"name": "X", "id": "nope"}]}"""));
+    }
+
+    [Fact]
+    public void SetClassification_IsLoggedForReplay()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        TestHelpers.PersistBaseline(mgr, session);
+
+        var result = ClassificationTools.SetClassification(
+            mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), Policy, session.Id, "confidential");
+        Assert.Contains("\"Confidential\"", result);
+        Assert.StartsWith("Error:", ClassificationTools.SetClassification(
+            mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), Policy, session.Id, "Secret"));
+
+        var patches = JsonDocument.Parse(mgr.GetWalEntries(session.Id, 0, 1).Single().Patches);
+        using var replayed = DocxSession.Create();
+        ClassificationTools.ReplaySetClassification(patches.RootElement[0], replayed.Document);
+
+        var original = Assert.Single(ClassificationHelper.Read(session.Document));
+        var copy = Assert.Single(ClassificationHelper.Read(replayed.Document));
+        Assert.Equal(original, copy with { Sources = original.Sources });
+        Assert.Equal(["CONFIDENTIAL"], Markings(replayed, ClassificationHelper.HeaderTag));
+    }
+}
@@ -0,0 +1,84 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ClauseTests
+{
+    private static DocxSession CreateDoc(params string[] paragraphs)
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        foreach (var text in paragraphs)
+            body.AppendChild(new Paragraph(new Run(new Text(text))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Check_FindsDanglingReferencesAndTitleMismatches()
+    {
+        using var session = CreateDoc(
+            "1. Definitions",
+            "1.1 Terms are defined in Section 2.1 and Section 4.",
+            "2. Fees",
+            "2.1 Payment. See Section 1.1 (\"Definitions\") and Section 1.1 (\"Terms\").");
+
+        var report = ClauseHelper.Check(session.Document);
+
+        Assert.Equal(["1", "1.1", "2", "2.1"], report.Clauses.Select(c => c.Number));
+        Assert.Equal("Fees", report.Clauses[2].Title);
+
+        var dangling = Assert.Single(report.Findings, f => f.Kind == "dangling_reference");
+        Assert.Equal("Section 4", dangling.Reference);
+
+        var mismatch = Assert.Single(report.Findings, f => f.Kind == "title_mismatch");
+        Assert.Contains("Definitions", mismatch.Reference);
+    }
+
+    [Fact]
+    public void Check_ReportsDuplicatesAndGaps()
+    {
+        using var session = CreateDoc(
+            "1. Scope",
+            "1.1 First",
+            "1.3 Third",
+            "2. Term",
+            "2. Termination");
+
+        var report = ClauseHelper.Check(session.Document);
+
+        var gap = Assert.Single(report.Findings, f => f.Kind == "numbering_gap");
+        Assert.Equal("1.3", gap.Reference);
+        Assert.Contains("1.2 is missing", gap.Message);
+
+        Assert.Single(report.Findings, f => f.Kind == "duplicate_number");
+    }
+
+    [Fact]
+    public void Check_IgnoresPlainNumbersThatAreNotClauses()
+    {
+        using var session = CreateDoc("2024 was a good year.", "Revenue grew 12 percent.");
+
+        var report = ClauseHelper.Check(session.Document);
+
+        Assert.Empty(report.Clauses);
+        Assert.Empty(report.Findings);
+    }
+
+    [Fact]
+    public void Check_FlagsRefFieldsToMissingBookmarks()
+    {
+        using var session = CreateDoc("1. Scope");
+        session.GetBody().AppendChild(new Paragraph(
+            new Run(new Text("As set out in clause ") { Space = SpaceProcessingModeValues.Preserve }),
+            new SimpleField(new Run(new Text("1"))) { Instruction = " REF _Ref123 \\r \\h " }));
+
+        var report = ClauseHelper.Check(session.Document);
+
+        var broken = Assert.Single(report.Findings);
+        Assert.Equal("broken_field", broken.Kind);
+        Assert.Equal("REF _Ref123", broken.Reference);
+    }
+}
@@ -0,0 +1,51 @@
+using DocxMcp.Grpc;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class CloudLinkTests
+{
+    private const string DocId = "1AbCdEfGhIjKlMnOpQrStUvWxYz0123456789_-xyz";
+
+    [Theory]
+    [InlineData("https://docs.google.com/document/d/" + DocId + "/edit?usp=sharing", true)]
+    [InlineData("https://docs.google.com/document/u/1/d/" + DocId + "/edit#heading=h.1", true)]
+    [InlineData("https://drive.google.com/file/d/" + DocId + "/view?usp=drive_link", false)]
+    [InlineData("https://drive.google.com/open?id=" + DocId, false)]
+    [InlineData("https://drive.google.com/uc?id=" + DocId + "&export=download", false)]
+    public void Parse_GoogleLinks(string url, bool isGoogleDoc)
+    {
+        var link = CloudLink.Parse(url);
+
+        Assert.Equal(SourceType.GoogleDrive, link.Type);
+        Assert.Equal(DocId, link.FileId);
+        Assert.Equal(isGoogleDoc, link.IsGoogleDoc);
+    }
+
+    [Fact]
+    public void Parse_MicrosoftLinksUseGraphSharingIds()
+    {
+        // Example from the Graph documentation for /shares/{id}
+        var onedrive = CloudLink.Parse("https://onedrive.live.com/redir?resid=1231244193912!12&authKey=1201919!12921!1");
+        Assert.Equal(SourceType.Onedrive, onedrive.Type);
+        Assert.Equal("u!aHR0cHM6Ly9vbmVkcml2ZS5saXZlLmNvbS9yZWRpcj9yZXNpZD0xMjMxMjQ0MTkzOTEyITEyJmF1dGhLZXk9MTIwMTkxOSExMjkyMSEx",
+            onedrive.FileId);
+
+        Assert.Equal(SourceType.Onedrive,
+            CloudLink.Parse("https://contoso-my.sharepoint.com/:w:/g/personal/alex_contoso_com/EabC?e=x1").Type);
+        Assert.Equal(SourceType.Sharepoint,
+            CloudLink.Parse("https://contoso.sharepoint.com/:w:/r/sites/legal/Shared%20Documents/nda.docx?d=w1").Type);
+    }
+
+    [Theory]
+    [InlineData("https://docs.google.com/spreadsheets/d/" + DocId + "/edit", "Sheets spreadsheet")]
+    [InlineData("https://drive.google.com/drive/folders/" + DocId, "folder")]
+    [InlineData("https://example.com/report.docx", "document_open_url")]
+    [InlineData("http://docs.google.com/document/d/" + DocId + "/edit", "HTTPS")]
+    public void Parse_RejectsOtherLinks(string url, string message)
+    {
+        var ex = Assert.Throws<ArgumentException>(() => CloudLink.Parse(url));
+        Assert.Contains(message, ex.Message);
+    }
+}
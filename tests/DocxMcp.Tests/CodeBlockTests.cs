@@ -0,0 +1,127 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class CodeBlockTests
+{
+    private const string Python = "def greet(name):\n    # say hello\n    return \"hi \" + name * 2\n";
+
+    private static (SessionManager Mgr, string Id) CreateDocument()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Example:"))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        TestHelpers.PersistBaseline(mgr, session);
+        return (mgr, session.Id);
+    }
+
+    private static string Visible(Paragraph paragraph) =>
+        string.Concat(paragraph.Descendants().Select(e => e switch
+        {
+            Text t => t.Text,
+            TabChar => "\t",
+            _ => ""
+        }));
+
+    [Fact]
+    public void Tokenize_FindsKeywordsStringsCommentsAndNumbers()
+    {
+        var tokens = CodeHelper.Tokenize(Python, "py");
+
+        Assert.Contains(new CodeToken(CodeTokenKind.Keyword, "def"), tokens);
+        Assert.Contains(new CodeToken(CodeTokenKind.Keyword, "return"), tokens);
+        Assert.Contains(new CodeToken(CodeTokenKind.Comment, "# say hello"), tokens);
+        Assert.Contains(new CodeToken(CodeTokenKind.String, "\"hi \""), tokens);
+        Assert.Contains(new CodeToken(CodeTokenKind.Number, "2"), tokens);
+        Assert.DoesNotContain(tokens, t => t.Kind == CodeTokenKind.Keyword && t.Text == "greet");
+        Assert.Equal(Python, string.Concat(tokens.Select(t => t.Text)));
+
+        // SQL keywords match in any case; unknown languages stay plain
+        Assert.Contains(new CodeToken(CodeTokenKind.Keyword, "Select"), CodeHelper.Tokenize("Select 1", "sql"));
+        Assert.Equal([new CodeToken(CodeTokenKind.Plain, "def x")], CodeHelper.Tokenize("def x", "cobol"));
+    }
+
+    [Fact]
+    public void AddCodeBlock_ShadesLinesAndPreservesWhitespace()
+    {
+        var (mgr, id) = CreateDocument();
+
+        var json = CodeTools.AddCodeBlock(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, Python + "\tindented", "python", "dark");
+        Assert.Contains("\"lines\": 4", json);
+        Assert.Contains("\"highlighted\": true", json);
+
+        var paragraphs = mgr.Get(id).GetBody().Elements<Paragraph>().ToList();
+        Assert.Equal("Example:", Visible(paragraphs[0]));
+        var block = paragraphs.Skip(1).ToList();
+        Assert.Equal(
+            ["def greet(name):", "    # say hello", "    return \"hi \" + name * 2", "\tindented"],
+            block.Select(Visible));
+        Assert.All(block, p =>
+        {
+            Assert.Equal(CodeHelper.BlockStyle, p.ParagraphProperties!.ParagraphStyleId!.Val!.Value);
+            Assert.Equal("1E1E1E", p.ParagraphProperties.Shading!.Fill!.Value);
+        });
+        Assert.All(block.SelectMany(p => p.Descendants<Text>()),
+            t => Assert.Equal(SpaceProcessingModeValues.Preserve, t.Space!.Value));
+
+        var keyword = block[0].Elements<Run>().First();
+        Assert.Equal("def", keyword.InnerText);
+        Assert.Equal("569CD6", keyword.RunProperties!.Color!.Val!.Value);
+        var comment = block[1].Elements<Run>().Single(r => r.InnerText.StartsWith('#'));
+        Assert.NotNull(comment.RunProperties!.Italic);
+    }
+
+    [Fact]
+    public void AddCodeBlock_ReplaysAfterUndo()
+    {
+        var (mgr, id) = CreateDocument();
+        CodeTools.AddCodeBlock(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, "let x = 1;\nlet y = 2;", "rust");
+
+        mgr.Undo(id);
+        Assert.Single(mgr.Get(id).GetBody().Elements<Paragraph>());
+
+        mgr.Redo(id);
+        Assert.Equal(["Example:", "let x = 1;", "let y = 2;"],
+            mgr.Get(id).GetBody().Elements<Paragraph>().Select(Visible));
+    }
+
+    [Fact]
+    public void AddCodeBlock_UnknownLanguageIsPlainButUnknownThemeFails()
+    {
+        var (mgr, id) = CreateDocument();
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        Assert.StartsWith("Error: Unknown theme 'neon'",
+            CodeTools.AddCodeBlock(mgr, sync, gate, id, "x", "python", "neon"));
+
+        var json = CodeTools.AddCodeBlock(mgr, sync, gate, id, "IDENTIFICATION DIVISION.", "cobol");
+        Assert.Contains("\"highlighted\": false", json);
+        var run = mgr.Get(id).GetBody().Elements<Paragraph>().Last().Elements<Run>().Single();
+        Assert.Equal(CodeHelper.Theme("light").Foreground, run.RunProperties!.Color!.Val!.Value);
+    }
+
+    [Fact]
+    public void StyleElement_InlineCodeSetsAndRemovesMonospace()
+    {
+        var (mgr, id) = CreateDocument();
+        var sync = TestHelpers.CreateSyncManager();
+
+        StyleTools.StyleElement(mgr, sync, id, "{\"code\":true}", "/body/paragraph[0]");
+        var props = mgr.Get(id).GetBody().Descendants<Run>().Single().RunProperties!;
+        Assert.Equal(CodeHelper.MonospaceFont, props.RunFonts!.Ascii!.Value);
+        Assert.Equal(CodeHelper.InlineShading, props.Shading!.Fill!.Value);
+        Assert.Contains("\"code\": true", QueryTool.Query(mgr, id, "/body/paragraph[0]/run[0]", "json"));
+
+        StyleTools.StyleElement(mgr, sync, id, "{\"code\":false}", "/body/paragraph[0]");
+        props = mgr.Get(id).GetBody().Descendants<Run>().Single().RunProperties!;
+        Assert.Null(props.RunFonts);
+        Assert.Null(props.Shading);
+    }
+}
@@ -48,6 +48,7 @@ public class CountToolTests : IDisposable
         Assert.Equal(6, doc.RootElement.GetProperty("paragraphs").GetInt32()); // heading + 5 paragraphs
         Assert.Equal(1, doc.RootElement.GetProperty("tables").GetInt32());
         Assert.Equal(1, doc.RootElement.GetProperty("headings").GetInt32());
+        Assert.Equal(1, doc.RootElement.GetProperty("estimated_pages").GetInt32());
     }
 
     [Fact]
@@ -0,0 +1,60 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class CustomXmlTests
+{
+    private const string Data = """<contract xmlns="urn:acme"><party>Acme Inc.</party></contract>""";
+
+    [Fact]
+    public void Add_CreatesPartWithDatastoreItem()
+    {
+        using var session = DocxSession.Create();
+
+        var info = CustomXmlHelper.Add(session.Document, Data);
+
+        Assert.StartsWith("{", info.ItemId);
+        Assert.Equal("contract", info.Root);
+        Assert.Equal("urn:acme", info.Namespace);
+        Assert.NotNull(CustomXmlHelper.Find(session.Document, info.ItemId.Trim('{', '}').ToLowerInvariant()));
+        Assert.Single(CustomXmlHelper.List(session.Document));
+
+        Assert.Throws<ArgumentException>(() => CustomXmlHelper.Add(session.Document, "<broken"));
+    }
+
+    [Fact]
+    public void Bind_WrapsParagraphAndShowsValue()
+    {
+        using var session = DocxSession.Create();
+        var paragraph = new Paragraph(new Run(new RunProperties(new Bold()), new Text("placeholder")));
+        session.GetBody().AppendChild(paragraph);
+        var info = CustomXmlHelper.Add(session.Document, Data);
+        var part = CustomXmlHelper.Find(session.Document, info.ItemId)!;
+
+        var mappings = CustomXmlHelper.DefaultPrefixMappings(part);
+        Assert.Equal("xmlns:ns0='urn:acme'", mappings);
+
+        var value = CustomXmlHelper.Evaluate(part, "/ns0:contract/ns0:party", mappings);
+        var control = CustomXmlHelper.WrapParagraph(paragraph, "party");
+        CustomXmlHelper.Bind(control, info.ItemId, "/ns0:contract/ns0:party", mappings, value);
+
+        Assert.Equal("Acme Inc.", paragraph.InnerText);
+        var binding = control.SdtProperties!.GetFirstChild<DataBinding>()!;
+        Assert.Equal(info.ItemId, binding.StoreItemId!.Value);
+        Assert.NotNull(control.Descendants<Run>().Single().RunProperties?.Bold);
+        Assert.Single(CustomXmlHelper.FindControls(session.Document, "party"));
+    }
+
+    [Fact]
+    public void Evaluate_ReturnsNullWhenNothingMatches()
+    {
+        using var session = DocxSession.Create();
+        var info = CustomXmlHelper.Add(session.Document, Data);
+        var part = CustomXmlHelper.Find(session.Document, info.ItemId)!;
+
+        Assert.Null(CustomXmlHelper.Evaluate(part, "/ns0:contract/ns0:date", "xmlns:ns0='urn:acme'"));
+        Assert.Throws<ArgumentException>(() => CustomXmlHelper.Evaluate(part, "/x:contract", ""));
+    }
+}
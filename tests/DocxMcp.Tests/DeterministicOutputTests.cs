@@ -0,0 +1,91 @@
+using System.IO.Compression;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class DeterministicOutputTests
+{
+    private static byte[] NewDocument()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Original"))));
+        return session.ToBytes();
+    }
+
+    /// <summary>Add a paragraph with a hyperlink and a comment.</summary>
+    private static byte[] Edit(byte[] bytes)
+    {
+        using var session = DocxSession.FromBytes(bytes, "s1", sourcePath: null);
+        var mainPart = session.Document.MainDocumentPart!;
+
+        var rel = mainPart.AddHyperlinkRelationship(new Uri("https://example.com"), true,
+            DeterministicOutput.NextRelationshipId(mainPart));
+        var run = new Run(new Text("Added"));
+        var hyperlink = new Hyperlink(run) { Id = rel.Id };
+        var paragraph = new Paragraph(hyperlink);
+        ElementIdManager.AssignId(run);
+        ElementIdManager.AssignId(hyperlink);
+        ElementIdManager.AssignId(paragraph);
+        session.GetBody().AppendChild(paragraph);
+
+        CommentHelper.AddCommentToElement(session.Document, paragraph,
+            CommentHelper.AllocateCommentId(session.Document), "Check this", "Tester", "T",
+            DeterministicOutput.UtcNow);
+
+        return session.ToBytes();
+    }
+
+    [Fact]
+    public void SameOperations_ProduceIdenticalBytes()
+    {
+        using var _ = DeterministicOutput.Force();
+
+        var original = NewDocument();
+        Assert.Equal(original, NewDocument());
+
+        var first = Edit(original);
+        var second = Edit(original);
+        Assert.Equal(first, second);
+        Assert.Equal(Edit(first), Edit(second));
+    }
+
+    [Fact]
+    public void Disabled_KeepsRandomIds()
+    {
+        using var _ = DeterministicOutput.Force(false);
+
+        var original = NewDocument();
+        Assert.NotEqual(Edit(original), Edit(original));
+    }
+
+    [Fact]
+    public void NormalizePackage_OrdersEntriesWithFixedTimestamp()
+    {
+        using var _ = DeterministicOutput.Force();
+
+        using var archive = new ZipArchive(new MemoryStream(Edit(NewDocument())), ZipArchiveMode.Read);
+        var names = archive.Entries.Select(e => e.FullName).ToList();
+
+        Assert.Equal("[Content_Types].xml", names[0]);
+        Assert.Equal("_rels/.rels", names[1]);
+        Assert.Equal(names.Skip(2).OrderBy(n => n, StringComparer.Ordinal), names.Skip(2));
+        Assert.All(archive.Entries, e =>
+            Assert.Equal(DeterministicOutput.FixedTimestamp.DateTime, e.LastWriteTime.DateTime));
+
+        using var rels = new StreamReader(archive.GetEntry("_rels/.rels")!.Open());
+        Assert.Contains("Id=\"rId1\"", rels.ReadToEnd());
+    }
+
+    [Fact]
+    public void NextRelationshipId_FollowsHighestRId()
+    {
+        using var session = DocxSession.Create();
+        var mainPart = session.Document.MainDocumentPart!;
+        Assert.Equal("rId1", DeterministicOutput.NextRelationshipId(mainPart));
+
+        mainPart.AddHyperlinkRelationship(new Uri("https://example.com"), true, "rId7");
+        Assert.Equal("rId8", DeterministicOutput.NextRelationshipId(mainPart));
+    }
+}
@@ -0,0 +1,76 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using Microsoft.Extensions.Logging.Abstractions;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class EditGuardTests
+{
+    private const string Patch = "[{\"op\":\"add\",\"path\":\"/body/children/0\",\"value\":{\"type\":\"paragraph\",\"text\":\"x\"}}]";
+
+    [Fact]
+    public void Admit_RejectsEditsOverThePerMinuteLimit_UntilTheWindowPasses()
+    {
+        var now = new DateTime(2026, 1, 1, 12, 0, 0, DateTimeKind.Utc);
+        var guard = new EditGuard(maxEditsPerMinute: 2, maxGrowthBytes: 0, () => now);
+
+        guard.Admit("s1", 100);
+        now = now.AddSeconds(20);
+        guard.Admit("s1", 100);
+
+        var ex = Assert.Throws<EditLimitExceededException>(() => guard.Admit("s1", 100));
+        Assert.Equal("edits_per_minute", ex.Limit);
+        Assert.Equal(TimeSpan.FromSeconds(40), ex.RetryAfter);
+
+        // Other sessions have their own count
+        guard.Admit("s2", 100);
+
+        now = now.AddSeconds(40);
+        guard.Admit("s1", 100);
+    }
+
+    [Fact]
+    public void Admit_RejectsAnEditThatGrowsTheDocumentTooMuch()
+    {
+        var guard = new EditGuard(maxEditsPerMinute: 0, maxGrowthBytes: 1000);
+        guard.Observe("s1", 5000);
+
+        guard.Admit("s1", 5900);
+        var ex = Assert.Throws<EditLimitExceededException>(() => guard.Admit("s1", 7000));
+        Assert.Equal("growth_per_edit", ex.Limit);
+        Assert.Null(ex.RetryAfter);
+
+        // The refused edit isn't counted: the next edit is compared to 5900 bytes
+        guard.Admit("s1", 6800);
+        guard.Admit("s1", 1000);
+    }
+
+    [Fact]
+    public void Admit_UnknownSize_TakesTheFirstEditAsTheBaseline()
+    {
+        var guard = new EditGuard(maxEditsPerMinute: 0, maxGrowthBytes: 10);
+
+        guard.Admit("s1", 1_000_000);
+        Assert.Throws<EditLimitExceededException>(() => guard.Admit("s1", 1_000_100));
+    }
+
+    [Fact]
+    public void AppendWal_OverTheLimit_IsNotLogged()
+    {
+        var mgr = new SessionManager(TestHelpers.GetOrCreateHistoryStorage(), NullLogger<SessionManager>.Instance,
+            $"test-{Guid.NewGuid():N}", new EditGuard(maxEditsPerMinute: 2, maxGrowthBytes: 0));
+        var session = mgr.Create();
+
+        for (var i = 0; i < 2; i++)
+        {
+            session.GetBody().PrependChild(new Paragraph(new Run(new Text("x"))));
+            mgr.AppendWal(session.Id, Patch, null, session.ToBytes());
+        }
+        session.GetBody().PrependChild(new Paragraph(new Run(new Text("x"))));
+        var ex = Assert.Throws<EditLimitExceededException>(
+            () => mgr.AppendWal(session.Id, Patch, null, session.ToBytes()));
+
+        Assert.Contains("Retry in", ex.Message);
+        Assert.Equal(2, mgr.GetHistory(session.Id).CursorPosition);
+    }
+}
@@ -0,0 +1,157 @@
+using System.Net;
+using System.Security.Cryptography;
+using System.Text.Json.Nodes;
+using DocxMcp.Email;
+using DocxMcp.Tools;
+using Microsoft.Extensions.Logging.Abstractions;
+using ModelContextProtocol;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class EmailTests : IDisposable
+{
+    private readonly string _auditPath = Path.Combine(Path.GetTempPath(), $"email-audit-{Guid.NewGuid():N}.jsonl");
+
+    public void Dispose()
+    {
+        if (File.Exists(_auditPath))
+            File.Delete(_auditPath);
+    }
+
+    /// <summary>Records messages instead of sending them, or fails with <c>error</c>.</summary>
+    private sealed class FakeSender(string? error = null) : IEmailSender
+    {
+        public List<EmailMessage> Sent { get; } = [];
+
+        public string Name => "fake";
+
+        public Task<string?> SendAsync(EmailMessage message, CancellationToken cancellationToken = default)
+        {
+            if (error is not null)
+                throw new InvalidOperationException(error);
+            Sent.Add(message);
+            return Task.FromResult<string?>("msg-1");
+        }
+    }
+
+    private sealed class FakeHandler(Func<HttpRequestMessage, HttpResponseMessage> respond) : HttpMessageHandler
+    {
+        public List<string> Bodies { get; } = [];
+
+        protected override async Task<HttpResponseMessage> SendAsync(HttpRequestMessage request, CancellationToken cancellationToken)
+        {
+            Bodies.Add(await request.Content!.ReadAsStringAsync(cancellationToken));
+            return respond(request);
+        }
+    }
+
+    private static EmailSettings Settings(params string[] allowedDomains) => new()
+    {
+        From = "docs@example.com",
+        SmtpHost = "smtp.example.com",
+        AllowedDomains = [.. allowedDomains]
+    };
+
+    private EmailAuditLog Audit() =>
+        new(new EmailOptions { AuditLogPath = _auditPath }, NullLogger<EmailAuditLog>.Instance);
+
+    private List<JsonNode> AuditLines() =>
+        File.ReadAllLines(_auditPath).Select(l => JsonNode.Parse(l)!).ToList();
+
+    [Fact]
+    public void ParseTenants_FillsMissingFieldsFromDefaults()
+    {
+        var defaults = Settings();
+        var tenants = EmailOptions.ParseTenants(
+            """{"acme": {"provider": "HTTP", "endpoint": "https://mail.example.com/send", "allowed_domains": ["acme.com"]}}""",
+            defaults);
+        var options = new EmailOptions { Default = new EmailSettings(), Tenants = tenants };
+
+        var acme = options.ForTenant("acme")!;
+        Assert.Equal("http", acme.Provider);
+        Assert.Equal("docs@example.com", acme.From);
+        Assert.True(acme.AllowsRecipient("Jane@ACME.com"));
+        Assert.False(acme.AllowsRecipient("jane@other.com"));
+        // The server-wide settings have no sender, so other tenants can't send
+        Assert.Null(options.ForTenant("other"));
+    }
+
+    [Fact]
+    public async Task HttpSender_PostsAttachmentAsBase64()
+    {
+        var handler = new FakeHandler(_ => new HttpResponseMessage(HttpStatusCode.OK)
+        {
+            Content = new StringContent("""{"id": "abc123"}""")
+        });
+        var sender = new HttpEmailSender(
+            new EmailSettings { Endpoint = "https://mail.example.com/send", ApiKey = "k" },
+            TimeSpan.FromSeconds(5), new HttpClient(handler));
+
+        var id = await sender.SendAsync(new EmailMessage(
+            "docs@example.com", ["a@example.com"], "Report", "Attached.",
+            new EmailAttachment("report.docx", "application/octet-stream", [1, 2, 3])));
+
+        Assert.Equal("abc123", id);
+        var payload = JsonNode.Parse(Assert.Single(handler.Bodies))!;
+        Assert.Equal("a@example.com", payload["to"]![0]!.GetValue<string>());
+        Assert.Equal("AQID", payload["attachments"]![0]!["content"]!.GetValue<string>());
+    }
+
+    [Fact]
+    public async Task Send_AttachesTheDocumentAndAuditsIt()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var sender = new FakeSender();
+
+        var result = await EmailTools.Send(mgr, Settings("example.com"), sender, Audit(), session.Id,
+            "Jane <jane@example.com>; bob@example.com", "Draft", "See attached.", "docx", "Q3/report",
+            CancellationToken.None);
+
+        var message = Assert.Single(sender.Sent);
+        Assert.Equal(["jane@example.com", "bob@example.com"], message.To);
+        Assert.Equal("Q3_report.docx", message.Attachment.FileName);
+        Assert.Equal("PK"u8.ToArray(), message.Attachment.Content[..2]);
+        Assert.True(JsonNode.Parse(result)!["sent"]!.GetValue<bool>());
+
+        var line = Assert.Single(AuditLines());
+        Assert.True(line["sent"]!.GetValue<bool>());
+        Assert.Equal("msg-1", line["message_id"]!.GetValue<string>());
+        Assert.Equal(
+            Convert.ToHexString(SHA256.HashData(message.Attachment.Content)).ToLowerInvariant(),
+            line["sha256"]!.GetValue<string>());
+    }
+
+    [Fact]
+    public async Task Send_RefusesRecipientsOutsideAllowedDomains()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var sender = new FakeSender();
+
+        var result = await EmailTools.Send(mgr, Settings("example.com"), sender, Audit(), session.Id,
+            "jane@example.com, eve@elsewhere.net", "Draft", "", "docx", null, CancellationToken.None);
+
+        Assert.StartsWith("Error:", result);
+        Assert.Contains("eve@elsewhere.net", result);
+        Assert.Empty(sender.Sent);
+        Assert.False(File.Exists(_auditPath));
+    }
+
+    [Fact]
+    public async Task Send_FailureIsAuditedAndReported()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+
+        var ex = await Assert.ThrowsAsync<McpException>(() => EmailTools.Send(
+            mgr, Settings(), new FakeSender("relay refused"), Audit(), session.Id,
+            "jane@example.com", "Draft", "", "docx", null, CancellationToken.None));
+
+        Assert.Contains("relay refused", ex.Message);
+        var line = Assert.Single(AuditLines());
+        Assert.False(line["sent"]!.GetValue<bool>());
+        Assert.Equal("relay refused", line["error"]!.GetValue<string>());
+    }
+}
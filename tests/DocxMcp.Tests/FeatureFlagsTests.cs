@@ -0,0 +1,54 @@
+using DocxMcp.Tools;
+using ModelContextProtocol;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class FeatureFlagsTests
+{
+    [Fact]
+    public void Parse_ReadsValuesAndIgnoresGarbage()
+    {
+        var flags = FeatureFlags.Parse("pdf_export=0, hifi_toc, tracked_changes=off, broken=maybe, =1");
+
+        Assert.False(flags.IsEnabled(FeatureFlags.PdfExport));
+        Assert.False(flags.IsEnabled(FeatureFlags.TrackedChanges));
+        Assert.True(flags.IsEnabled("hifi_toc"));
+        Assert.False(flags.IsEnabled("broken"));
+    }
+
+    [Fact]
+    public void UnsetFlags_FallBackToServerThenDefaults()
+    {
+        var server = FeatureFlags.Parse("hifi_toc=1,pdf_export=0");
+        var tenant = FeatureFlags.Parse("pdf_export=1", server);
+
+        Assert.True(tenant.IsEnabled(FeatureFlags.PdfExport));
+        Assert.True(tenant.IsEnabled("hifi_toc"));
+        Assert.True(tenant.IsEnabled(FeatureFlags.TrackedChanges));
+        Assert.False(tenant.IsEnabled("unknown"));
+    }
+
+    [Fact]
+    public void DisabledFlag_RejectsGatedTools()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var tenant = new TenantScope(mgr, FeatureFlags.Parse("tracked_changes=0"));
+
+        var ex = Assert.Throws<McpException>(() =>
+            RevisionTools.TrackChangesEnable(tenant, TestHelpers.CreateSyncManager(), session.Id, true));
+        Assert.Contains("tracked_changes", ex.Message);
+    }
+
+    [Fact]
+    public void EnabledFlag_AllowsGatedTools()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var tenant = new TenantScope(mgr, FeatureFlags.Parse("tracked_changes=1"));
+
+        var result = RevisionTools.TrackChangesEnable(tenant, TestHelpers.CreateSyncManager(), session.Id, true);
+        Assert.Contains("enabled", result);
+    }
+}
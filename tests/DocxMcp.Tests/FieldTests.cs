@@ -0,0 +1,81 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class FieldTests
+{
+    private static readonly DateTime Now = new(2024, 3, 5, 14, 7, 0);
+
+    [Fact]
+    public void DateField_UsesLocaleForPictureAndCachedValue()
+    {
+        using var session = DocxSession.Create();
+
+        var field = FieldHelper.Create(session.Document, "date", "d MMMM yyyy", "fr-FR", null, Now);
+
+        Assert.Equal("DATE \\@ \"d MMMM yyyy\"", field.Instruction);
+        Assert.Equal("5 mars 2024", field.Result);
+        Assert.Equal("fr-FR", field.Language);
+    }
+
+    [Fact]
+    public void TimeField_ConvertsAmPm()
+    {
+        using var session = DocxSession.Create();
+
+        var field = FieldHelper.Create(session.Document, "time", "h:mm AM/PM", "en-US", null, Now);
+
+        Assert.Equal("2:07 PM", field.Result);
+        Assert.Contains("AM/PM", field.Instruction);
+    }
+
+    [Fact]
+    public void FilenameField_WithPath()
+    {
+        using var session = DocxSession.Create();
+
+        Assert.Equal("report.docx",
+            FieldHelper.Create(session.Document, "filename", null, null, "/tmp/report.docx", Now).Result);
+        var withPath = FieldHelper.Create(session.Document, "filename", "path", null, "/tmp/report.docx", Now);
+        Assert.Equal("FILENAME \\p", withPath.Instruction);
+        Assert.Equal("/tmp/report.docx", withPath.Result);
+        Assert.Throws<ArgumentException>(() =>
+            FieldHelper.Create(session.Document, "pagecount", null, null, null, Now));
+    }
+
+    [Fact]
+    public void TocField_ListsHeadingLevels()
+    {
+        using var session = DocxSession.Create();
+
+        var field = FieldHelper.Create(session.Document, "toc", null, null, null, Now);
+
+        Assert.Equal("TOC \\o \"1-3\" \\h \\z \\u", field.Instruction);
+        Assert.StartsWith("TOC \\o \"2-4\"", FieldHelper.Create(session.Document, "toc", "2-4", null, null, Now).Instruction);
+        Assert.Throws<ArgumentException>(() => FieldHelper.Create(session.Document, "toc", "3-1", null, null, Now));
+        Assert.Throws<ArgumentException>(() => FieldHelper.Create(session.Document, "toc", "all", null, null, Now));
+    }
+
+    [Fact]
+    public void InsertField_WritesComplexFieldAndReplays()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Printed on ") { Space = SpaceProcessingModeValues.Preserve })));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        var result = FieldTools.InsertField(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), session.Id, "/body/paragraph[0]", "date",
+            format: "yyyy-MM-dd", locale: "de-DE");
+        Assert.StartsWith("Field DATE", result);
+
+        var paragraph = mgr.Get(session.Id).GetBody().Elements<Paragraph>().Single();
+        Assert.Equal(" DATE \\@ \"yyyy-MM-dd\" ", paragraph.Descendants<FieldCode>().Single().Text);
+        Assert.Equal(3, paragraph.Descendants<FieldChar>().Count());
+        Assert.All(paragraph.Elements<Run>().Skip(1), r => Assert.Equal("de-DE", r.RunProperties!.Languages!.Val!.Value));
+        Assert.Matches(@"^Printed on \d{4}-\d{2}-\d{2}$", paragraph.InnerText);
+    }
+}
@@ -0,0 +1,253 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class HeadingTests
+{
+    private static DocxSession CreateDoc(params int[] levels)
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        for (int i = 0; i < levels.Length; i++)
+        {
+            body.AppendChild(new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = $"Heading{levels[i]}" }),
+                new Run(new Text($"H{i}"))));
+            body.AppendChild(new Paragraph(new Run(new Text($"Body {i}"))));
+        }
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    private static int[] Levels(DocxSession session) =>
+        session.GetBody().Elements<Paragraph>()
+            .Select(p => p.GetHeadingLevel())
+            .Where(l => l > 0)
+            .ToArray();
+
+    [Fact]
+    public void Shift_MovesSubheadingsAlong()
+    {
+        using var session = CreateDoc(1, 2, 3, 2, 1);
+        var body = session.GetBody();
+        var target = body.Elements<Paragraph>().First(p => p.InnerText == "H1");
+
+        var changes = HeadingHelper.Shift(body, [target], 1, withSubheadings: true);
+        HeadingHelper.Apply(session.Document, changes);
+
+        Assert.Equal([1, 3, 4, 2, 1], Levels(session));
+    }
+
+    [Fact]
+    public void Shift_RejectsLevelsOutOfRange()
+    {
+        using var session = CreateDoc(1, 2);
+        var body = session.GetBody();
+        var target = body.Elements<Paragraph>().First(p => p.InnerText == "H0");
+
+        Assert.Throws<ArgumentException>(() => HeadingHelper.Shift(body, [target], -1, withSubheadings: true));
+    }
+
+    [Fact]
+    public void Normalize_CollapsesSkippedLevels()
+    {
+        using var session = CreateDoc(2, 4, 4, 3, 1, 3);
+
+        var changes = HeadingHelper.Normalize(session.GetBody());
+        HeadingHelper.Apply(session.Document, changes);
+
+        Assert.Equal([1, 2, 2, 2, 1, 2], Levels(session));
+        Assert.Empty(HeadingHelper.Normalize(session.GetBody()));
+    }
+
+    private static string[] Texts(DocxSession session) =>
+        session.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToArray();
+
+    [Fact]
+    public void MoveSection_CarriesContentAndSubsections()
+    {
+        using var session = CreateDoc(1, 2, 1);
+        var headings = session.GetBody().Elements<Paragraph>().Where(p => p.IsHeading()).ToList();
+
+        HeadingHelper.MoveSection(session.Document, ElementIdManager.GetId(headings[0])!, null);
+
+        Assert.Equal(["H2", "Body 2", "H0", "Body 0", "H1", "Body 1"], Texts(session));
+    }
+
+    [Fact]
+    public void MoveSection_RejectsTargetInsideItsOwnSection()
+    {
+        using var session = CreateDoc(1, 2, 1);
+        var headings = session.GetBody().Elements<Paragraph>().Where(p => p.IsHeading()).ToList();
+
+        Assert.Throws<ArgumentException>(() => HeadingHelper.MoveSection(session.Document,
+            ElementIdManager.GetId(headings[0])!, ElementIdManager.GetId(headings[1])));
+    }
+
+    [Fact]
+    public void Rename_KeepsFirstRunFormattingAndBookmarks()
+    {
+        using var session = DocxSession.Create();
+        var heading = session.GetBody().AppendChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new BookmarkStart { Id = "1", Name = "intro" },
+            new Run(new RunProperties(new Italic()), new Text("Intro")),
+            new Run(new Text("duction")),
+            new BookmarkEnd { Id = "1" }));
+
+        HeadingHelper.Rename(session.Document, heading, "Overview");
+
+        Assert.Equal("Overview", heading.InnerText);
+        Assert.NotNull(heading.Elements<Run>().Single().RunProperties!.Italic);
+        Assert.Single(heading.Elements<BookmarkStart>());
+    }
+
+    [Fact]
+    public void EnsureAnchors_KeepsExistingBookmarksAndAddsMissingOnes()
+    {
+        using var session = CreateDoc(1, 2);
+        var body = session.GetBody();
+        var first = body.Elements<Paragraph>().First();
+        first.InsertAfter(new BookmarkStart { Id = "7", Name = "_Toc123" }, first.ParagraphProperties);
+        first.AppendChild(new BookmarkEnd { Id = "7" });
+
+        var anchors = HeadingHelper.EnsureAnchors(body);
+
+        Assert.Equal(["_Toc123", "_Heading8"], anchors.Select(a => a.Bookmark));
+        Assert.Equal([false, true], anchors.Select(a => a.Added));
+        Assert.Equal(anchors.Select(a => a.Bookmark), HeadingHelper.EnsureAnchors(body).Select(a => a.Bookmark));
+        Assert.All(HeadingHelper.EnsureAnchors(body), a => Assert.False(a.Added));
+    }
+
+    [Fact]
+    public void Tools_GetAnchorLinks_BookmarksSurviveReplay()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        foreach (var text in new[] { "Intro", "Pricing" })
+        {
+            session.GetBody().AppendChild(new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+                new Run(new Text(text))));
+        }
+        ElementIdManager.EnsureAllIds(session.Document);
+        TestHelpers.PersistBaseline(mgr, session);
+        var id = session.Id;
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        var result = System.Text.Json.Nodes.JsonNode.Parse(HeadingTools.GetAnchorLinks(mgr, sync, gate, id))!;
+        Assert.Equal(2, result["added_bookmarks"]!.GetValue<int>());
+        var links = result["anchors"]!.AsArray().Select(a => a!["link"]!.GetValue<string>()).ToList();
+        Assert.Equal([$"{id}#_Heading1", $"{id}#_Heading2"], links);
+
+        HeadingTools.RenameHeading(mgr, sync, gate, id, 0, "Overview");
+        mgr.Undo(id);
+        Assert.Equal(["_Heading1", "_Heading2"],
+            mgr.Get(id).GetBody().Descendants<BookmarkStart>().Select(b => b.Name!.Value!));
+
+        // Nothing left to add: the second call doesn't touch the document
+        var again = System.Text.Json.Nodes.JsonNode.Parse(HeadingTools.GetAnchorLinks(mgr, sync, gate, id))!;
+        Assert.Equal(0, again["added_bookmarks"]!.GetValue<int>());
+    }
+
+    [Fact]
+    public void Tools_RenameAndMove_ReplayAfterUndoRedo()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+        foreach (var (text, level) in new[] { ("Intro", 1), ("Scope", 2), ("Pricing", 1) })
+        {
+            body.AppendChild(new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = $"Heading{level}" }),
+                new Run(new Text(text))));
+            body.AppendChild(new Paragraph(new Run(new Text($"{text} body"))));
+        }
+        TestHelpers.PersistBaseline(mgr, session);
+        var id = session.Id;
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        Assert.StartsWith("Heading 2", HeadingTools.RenameHeading(mgr, sync, gate, id, 2, "Fees"));
+        Assert.StartsWith("Section", HeadingTools.MoveSection(mgr, sync, gate, id, 2, 0));
+        Assert.StartsWith("Error", HeadingTools.MoveSection(mgr, sync, gate, id, 1, 2));
+
+        string[] expected = ["Fees", "Fees body", "Intro", "Intro body", "Scope", "Scope body"];
+        Assert.Equal(expected, Texts(mgr.Get(id)));
+
+        mgr.Undo(id);
+        Assert.Equal(["Intro", "Intro body", "Scope", "Scope body", "Fees", "Fees body"], Texts(mgr.Get(id)));
+
+        mgr.Redo(id);
+        Assert.Equal(expected, Texts(mgr.Get(id)));
+    }
+
+    private static Paragraph Formatted(string text, int? size = null, bool bold = false)
+    {
+        var props = new RunProperties();
+        if (bold) props.AppendChild(new Bold());
+        if (size is int s) props.AppendChild(new FontSize { Val = s.ToString() });
+        return new Paragraph(new Run(props, new Text(text)));
+    }
+
+    [Fact]
+    public void Infer_RanksLooksAndUsesNumberingDepth()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        foreach (var paragraph in new[]
+        {
+            Formatted("Annual Report", size: 32, bold: true),
+            Formatted("This report covers the activity of the company over the past year."),
+            Formatted("Highlights", bold: true),
+            Formatted("Revenue grew and costs stayed flat for the third year in a row."),
+            Formatted("2.1 Outlook", bold: true),
+            Formatted("Next year should be similar."),
+            Formatted("Signed by the board.", bold: true)
+        })
+            body.AppendChild(paragraph);
+        ElementIdManager.EnsureAllIds(session.Document);
+
+        var inference = HeadingInferenceHelper.Infer(session.Document);
+
+        Assert.Equal(11, inference.BodyFontSize);
+        Assert.Equal(
+            [("Annual Report", 1), ("Highlights", 2), ("2.1 Outlook", 2)],
+            inference.Headings.Select(h => (h.Text, h.Level)));
+    }
+
+    [Fact]
+    public void ApplyInferredHeadings_SetsStylesAndReplays()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+        body.AppendChild(Formatted("Background", size: 28, bold: true));
+        body.AppendChild(Formatted("The project started in spring and ran until the end of the year."));
+        body.AppendChild(Formatted("Results", size: 28, bold: true));
+        body.AppendChild(Formatted("Most goals were met."));
+        TestHelpers.PersistBaseline(mgr, session);
+        var id = session.Id;
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        var dryRun = HeadingTools.ApplyInferredHeadings(mgr, sync, gate, id, dry_run: true);
+        Assert.Contains("\"Results\"", dryRun);
+        Assert.Empty(Levels(mgr.Get(id)));
+
+        var resultsId = ElementIdManager.GetId(mgr.Get(id).GetBody().Elements<Paragraph>().ElementAt(2))!;
+        HeadingTools.ApplyInferredHeadings(mgr, sync, gate, id, exclude: resultsId);
+        Assert.Equal([1], Levels(mgr.Get(id)));
+
+        mgr.Undo(id);
+        Assert.Empty(Levels(mgr.Get(id)));
+        mgr.Redo(id);
+        Assert.Equal([1], Levels(mgr.Get(id)));
+        Assert.Equal("Heading1", mgr.Get(id).GetBody().Elements<Paragraph>().First()
+            .ParagraphProperties?.ParagraphStyleId?.Val?.Value);
+    }
+}
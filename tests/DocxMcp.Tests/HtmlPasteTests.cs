@@ -0,0 +1,198 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class HtmlPasteTests
+{
+    // Trimmed from what Word for Windows puts on the clipboard
+    private const string WordHtml = """
+        Version:0.9
+        StartHTML:0000000105
+        EndHTML:0000003000
+        <html xmlns:o="urn:schemas-microsoft-com:office:office">
+        <head><style><!-- p.MsoNormal {margin:0in;} --></style></head>
+        <body lang=EN-US style='tab-interval:.5in'>
+        <!--StartFragment-->
+        <p class=MsoNormal><span style='font-size:12.0pt;font-family:"Calibri",sans-serif;
+        mso-ascii-theme-font:minor-latin'>Quarterly <b>results</b> are <span
+        style='color:#C00000'>up</span>&nbsp;<span style='background:yellow;mso-highlight:yellow'>12%</span><o:p></o:p></span></p>
+        <p class=MsoListParagraphCxSpFirst style='text-indent:-.25in;mso-list:l0 level1 lfo1'><![if !supportLists]><span
+        style='font-family:Symbol'><span style='mso-list:Ignore'>·<span style='font:7.0pt "Times New Roman"'>&nbsp;&nbsp;
+        </span></span></span><![endif]>First point<o:p></o:p></p>
+        <p class=MsoListParagraphCxSpLast style='text-indent:-.25in;mso-list:l0 level1 lfo1'><![if !supportLists]><span
+        style='font-family:Symbol'><span style='mso-list:Ignore'>·<span style='font:7.0pt "Times New Roman"'>&nbsp;&nbsp;
+        </span></span></span><![endif]>Second <i>point</i><o:p></o:p></p>
+        <p class=MsoListParagraph style='mso-list:l1 level1 lfo2'><![if !supportLists]><span
+        style='mso-list:Ignore'>1.<span>&nbsp;</span></span><![endif]>Numbered<o:p></o:p></p>
+        <!--EndFragment-->
+        </body>
+        </html>
+        """;
+
+    // Trimmed from what Google Docs puts on the clipboard
+    private const string GoogleDocsHtml = """
+        <meta charset="utf-8"><b style="font-weight:normal;" id="docs-internal-guid-1234"><h2 dir="ltr" style="line-height:1.38;"><span style="font-size:16pt;font-family:Arial;color:#000000;font-weight:400;">Overview</span></h2><p dir="ltr" style="line-height:1.38;text-align:center;"><span style="font-size:11pt;font-family:Arial;color:#000000;font-weight:700;">Bold</span><span style="font-size:11pt;font-family:Arial;color:#000000;font-weight:400;"> and </span><a href="https://example.com/x" style="text-decoration:none;"><span style="font-size:11pt;font-family:Arial;color:#1155cc;text-decoration:underline;">a link</span></a></p><br /><ul style="margin-top:0;"><li dir="ltr" style="list-style-type:disc;"><p dir="ltr" role="presentation"><span style="font-size:11pt;">One</span></p></li><ul><li dir="ltr"><p dir="ltr" role="presentation"><span style="font-size:11pt;">Nested</span></p></li></ul></ul><div dir="ltr" align="left"><table style="border:none;border-collapse:collapse;"><colgroup><col width="100" /><col width="100" /></colgroup><tbody><tr style="height:0pt"><td style="background-color:#d9ead3;padding:5pt;"><p dir="ltr"><span style="font-size:11pt;font-weight:700;">Name</span></p></td><td style="padding:5pt;"><p dir="ltr"><span style="font-size:11pt;">Value</span></p></td></tr><tr><td colspan="2"><p dir="ltr"><span style="vertical-align:super;font-size:8pt;">1</span><span>Merged</span></p></td></tr></tbody></table></div></b>
+        """;
+
+    private static JsonObject Element(HtmlPasteHelper.Conversion conversion, int index) =>
+        conversion.Elements[index]!.AsObject();
+
+    private static List<(string Text, JsonObject? Style)> Runs(JsonNode element) =>
+        element["runs"]!.AsArray()
+            .Select(r => (r!["text"]?.GetValue<string>() ?? "\n", r["style"]?.AsObject()))
+            .ToList();
+
+    [Fact]
+    public void Convert_WordHtml_KeepsEmphasisAndDropsMsoNoise()
+    {
+        var conversion = HtmlPasteHelper.Convert(WordHtml);
+
+        Assert.Equal(3, conversion.Elements.Count);
+
+        var runs = Runs(Element(conversion, 0));
+        Assert.Equal("Quarterly results are up 12%", string.Concat(runs.Select(r => r.Text)));
+        Assert.True(runs.Single(r => r.Text == "results").Style!["bold"]!.GetValue<bool>());
+        Assert.Equal("C00000", runs.Single(r => r.Text == "up").Style!["color"]!.GetValue<string>());
+        Assert.Equal("yellow", runs.Single(r => r.Text == "12%").Style!["highlight"]!.GetValue<string>());
+        // Fonts and sizes are dropped by default
+        Assert.DoesNotContain(runs, r => r.Style?["font_size"] is not null || r.Style?["font_name"] is not null);
+
+        var bullets = Element(conversion, 1);
+        Assert.Equal("list", bullets["type"]!.GetValue<string>());
+        Assert.False(bullets["ordered"]!.GetValue<bool>());
+        Assert.Equal(["First point", "Second point"],
+            bullets["items"]!.AsArray().Select(i => string.Concat(Runs(i!).Select(r => r.Text))));
+
+        var numbered = Element(conversion, 2);
+        Assert.True(numbered["ordered"]!.GetValue<bool>());
+        Assert.Equal("Numbered", string.Concat(Runs(numbered["items"]![0]!).Select(r => r.Text)));
+    }
+
+    [Fact]
+    public void Convert_GoogleDocsHtml_MapsHeadingsListsAndTables()
+    {
+        var conversion = HtmlPasteHelper.Convert(GoogleDocsHtml);
+        var types = conversion.Elements.Select(e => e!["type"]!.GetValue<string>()).ToList();
+        Assert.Equal(["heading", "paragraph", "paragraph", "list", "table"], types);
+
+        var heading = Element(conversion, 0);
+        Assert.Equal(2, heading["level"]!.GetValue<int>());
+        var headingRun = Assert.Single(Runs(heading));
+        Assert.Equal("Overview", headingRun.Text);
+        Assert.Null(headingRun.Style);
+
+        // The font-weight:normal wrapper doesn't make everything bold
+        var paragraph = Element(conversion, 1);
+        Assert.Equal("center", paragraph["properties"]!["alignment"]!.GetValue<string>());
+        var runs = paragraph["runs"]!.AsArray();
+        Assert.True(runs[0]!["style"]!["bold"]!.GetValue<bool>());
+        Assert.Null(runs[1]!["style"]);
+        Assert.Equal("https://example.com/x", runs[2]!["url"]!.GetValue<string>());
+
+        // The bare <br> between blocks is an empty paragraph
+        Assert.Empty(Element(conversion, 2)["runs"]!.AsArray());
+
+        Assert.Equal(["One", "Nested"],
+            Element(conversion, 3)["items"]!.AsArray().Select(i => string.Concat(Runs(i!).Select(r => r.Text))));
+
+        var rows = Element(conversion, 4)["rows"]!.AsArray();
+        Assert.Equal("D9EAD3", rows[0]!["cells"]![0]!["shading"]!.GetValue<string>());
+        var merged = rows[1]!["cells"]!.AsArray().Single()!;
+        Assert.Equal(2, merged["col_span"]!.GetValue<int>());
+        var mergedRuns = merged["paragraphs"]![0]!["runs"]!.AsArray();
+        Assert.Equal("superscript", mergedRuns[0]!["style"]!["vertical_align"]!.GetValue<string>());
+    }
+
+    [Fact]
+    public void Convert_KeepFonts_KeepsFamilyAndPointSize()
+    {
+        var conversion = HtmlPasteHelper.Convert(
+            "<p><span style=\"font-family:'Times New Roman',serif;font-size:16px\">Text</span></p>", keepFonts: true);
+
+        var style = Element(conversion, 0)["runs"]![0]!["style"]!;
+        Assert.Equal("Times New Roman", style["font_name"]!.GetValue<string>());
+        Assert.Equal(12, style["font_size"]!.GetValue<int>());
+    }
+
+    [Fact]
+    public void Convert_TableRowSpan_AddsContinuationCells()
+    {
+        var conversion = HtmlPasteHelper.Convert(
+            "<table><tr><th>A<th>B<tr><td rowspan=2>x<td>1<tr><td>2</table>");
+
+        var rows = Element(conversion, 0)["rows"]!.AsArray();
+        Assert.True(rows[0]!["is_header"]!.GetValue<bool>());
+        Assert.Equal("restart", rows[1]!["cells"]![0]!["row_span"]!.GetValue<string>());
+        Assert.Equal("continue", rows[2]!["cells"]![0]!["row_span"]!.GetValue<string>());
+        Assert.Equal(2, rows[2]!["cells"]!.AsArray().Count);
+    }
+
+    [Theory]
+    [InlineData("#1155cc", "1155CC")]
+    [InlineData("#abc", "AABBCC")]
+    [InlineData("rgb(255, 0, 0)", "FF0000")]
+    [InlineData("rgba(0, 0, 0, 0)", null)]
+    [InlineData("windowtext", null)]
+    public void ParseColor_ReadsCssColors(string value, string? expected)
+    {
+        Assert.Equal(expected, HtmlPasteHelper.ParseColor(value));
+    }
+
+    [Fact]
+    public void Paste_CreatesElementsAndLinks()
+    {
+        using var session = DocxSession.Create();
+        var conversion = HtmlPasteHelper.Convert(GoogleDocsHtml);
+
+        var (inserted, replaced) = PasteTools.Paste(session.Document, conversion.Elements, "/body/children/999");
+
+        Assert.Equal(0, replaced);
+        Assert.Equal(6, inserted.Count);
+        var body = session.GetBody();
+        Assert.Equal("Heading2", body.Elements<Paragraph>().First().ParagraphProperties?.ParagraphStyleId?.Val?.Value);
+        var link = Assert.Single(body.Descendants<Hyperlink>());
+        Assert.Equal("a link", link.InnerText);
+        Assert.Single(body.Elements<Table>());
+    }
+
+    [Fact]
+    public void Paste_OverParagraph_ReplacesIt()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text("old"))));
+        body.AppendChild(new Paragraph(new Run(new Text("kept"))));
+
+        var conversion = HtmlPasteHelper.Convert("<p>new <b>one</b></p><p>new two</p>");
+        var (_, replaced) = PasteTools.Paste(session.Document, conversion.Elements, "/body/paragraph[0]");
+
+        Assert.Equal(1, replaced);
+        Assert.Equal(["new one", "new two", "kept"], body.Elements<Paragraph>().Select(p => p.InnerText));
+    }
+
+    [Fact]
+    public void ReplayPasteHtml_RecreatesTheSameContent()
+    {
+        using var original = DocxSession.Create();
+        using var replayed = DocxSession.Create();
+        var conversion = HtmlPasteHelper.Convert(WordHtml);
+
+        PasteTools.Paste(original.Document, conversion.Elements, "/body/children/0");
+        var walObj = new JsonObject
+        {
+            ["op"] = "paste_html",
+            ["range"] = "/body/children/0",
+            ["elements"] = conversion.Elements.DeepClone()
+        };
+        PasteTools.ReplayPasteHtml(JsonDocument.Parse(walObj.ToJsonString()).RootElement, replayed.Document);
+
+        Assert.Equal(
+            original.GetBody().Elements<Paragraph>().Select(p => p.InnerText),
+            replayed.GetBody().Elements<Paragraph>().Select(p => p.InnerText));
+    }
+}
@@ -0,0 +1,126 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class HyperlinkTests
+{
+    private static SessionManager CreateDocument(out string id)
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        id = session.Id;
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(
+            new Run(new Text("Read ") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new RunProperties(new Bold()), new Text("our terms") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new Text(" first.") { Space = SpaceProcessingModeValues.Preserve })));
+        body.AppendChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new Run(new Text("Pricing"))));
+        TestHelpers.PersistBaseline(mgr, session);
+        return mgr;
+    }
+
+    [Fact]
+    public void HyperlinkElement_UsesCharacterStyleAndTooltip()
+    {
+        using var session = DocxSession.Create();
+        var mainPart = session.Document.MainDocumentPart!;
+        var value = JsonDocument.Parse("""
+            {"type": "hyperlink", "text": "Example", "url": "https://example.com", "tooltip": "Open example"}
+        """).RootElement;
+
+        var paragraph = (Paragraph)ElementFactory.CreateFromJson(value, mainPart);
+
+        var hyperlink = paragraph.Elements<Hyperlink>().Single();
+        Assert.Equal("Open example", hyperlink.Tooltip!.Value);
+        var run = hyperlink.Elements<Run>().Single();
+        Assert.Equal("Hyperlink", run.RunProperties!.RunStyle!.Val!.Value);
+        Assert.Null(run.RunProperties.Color);
+        Assert.Contains(mainPart.StyleDefinitionsPart!.Styles!.Elements<Style>(),
+            s => s.StyleId?.Value == "Hyperlink" && s.Type!.Value == StyleValues.Character);
+    }
+
+    [Fact]
+    public void HyperlinkElement_RequiresOneTarget()
+    {
+        using var session = DocxSession.Create();
+        var mainPart = session.Document.MainDocumentPart!;
+
+        Assert.Throws<ArgumentException>(() => ElementFactory.CreateFromJson(
+            JsonDocument.Parse("""{"type": "hyperlink", "text": "x"}""").RootElement, mainPart));
+        Assert.Throws<ArgumentException>(() => ElementFactory.CreateFromJson(
+            JsonDocument.Parse("""{"type": "hyperlink", "bookmark": "missing"}""").RootElement, mainPart));
+    }
+
+    [Fact]
+    public void InParagraph_WrapsExistingTextAcrossRuns()
+    {
+        var mgr = CreateDocument(out var id);
+
+        var result = HyperlinkTools.AddHyperlinkInParagraph(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, "/body/paragraph[0]",
+            anchor_text: "d our", url: "https://example.com/terms");
+        Assert.StartsWith("Hyperlink", result);
+
+        var paragraph = mgr.Get(id).GetBody().Elements<Paragraph>().First();
+        Assert.Equal("Read our terms first.", paragraph.InnerText);
+        var hyperlink = paragraph.Elements<Hyperlink>().Single();
+        Assert.Equal("d our", hyperlink.InnerText);
+        var runs = hyperlink.Elements<Run>().ToList();
+        Assert.Equal(2, runs.Count);
+        Assert.NotNull(runs[1].RunProperties!.Bold);
+        Assert.All(runs, r => Assert.Equal("Hyperlink", r.RunProperties!.RunStyle!.Val!.Value));
+    }
+
+    [Fact]
+    public void InParagraph_LinksToHeadingThroughBookmark()
+    {
+        var mgr = CreateDocument(out var id);
+
+        HyperlinkTools.AddHyperlinkInParagraph(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, "/body/paragraph[0]",
+            text: " See pricing.", heading: "pricing");
+
+        var body = mgr.Get(id).GetBody();
+        var bookmark = body.Elements<Paragraph>().Last().Elements<BookmarkStart>().Single();
+        var hyperlink = body.Elements<Paragraph>().First().Elements<Hyperlink>().Single();
+        Assert.Equal(bookmark.Name!.Value, hyperlink.Anchor!.Value);
+        Assert.Null(hyperlink.Id);
+        Assert.Equal("Read our terms first. See pricing.", body.Elements<Paragraph>().First().InnerText);
+    }
+
+    [Fact]
+    public void InParagraph_MissingTextIsAnError()
+    {
+        var mgr = CreateDocument(out var id);
+
+        var result = HyperlinkTools.AddHyperlinkInParagraph(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, "/body/paragraph[0]",
+            anchor_text: "nowhere", url: "https://example.com");
+
+        Assert.StartsWith("Error", result);
+        Assert.Empty(mgr.Get(id).Document.MainDocumentPart!.HyperlinkRelationships);
+    }
+
+    [Fact]
+    public void InParagraph_Undo_Redo()
+    {
+        var mgr = CreateDocument(out var id);
+        HyperlinkTools.AddHyperlinkInParagraph(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id, "/body/paragraph[0]",
+            anchor_text: "our terms", url: "https://example.com/terms", tooltip: "Terms");
+
+        mgr.Undo(id);
+        Assert.Empty(mgr.Get(id).GetBody().Descendants<Hyperlink>());
+
+        mgr.Redo(id);
+        var hyperlink = mgr.Get(id).GetBody().Descendants<Hyperlink>().Single();
+        Assert.Equal("our terms", hyperlink.InnerText);
+        Assert.Equal("Terms", hyperlink.Tooltip!.Value);
+    }
+}
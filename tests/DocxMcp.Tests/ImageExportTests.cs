@@ -0,0 +1,83 @@
+using System.IO.Compression;
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ImageExportTests : IDisposable
+{
+    // 1x1 transparent PNG
+    private static readonly byte[] Png = Convert.FromBase64String(
+        "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=");
+
+    private readonly string _dir = Path.Combine(Path.GetTempPath(), $"docx-mcp-images-{Guid.NewGuid():N}");
+    private readonly string _imagePath;
+
+    public ImageExportTests()
+    {
+        Directory.CreateDirectory(_dir);
+        _imagePath = Path.Combine(_dir, "source.png");
+        File.WriteAllBytes(_imagePath, Png);
+    }
+
+    public void Dispose() => Directory.Delete(_dir, recursive: true);
+
+    private Paragraph Image(DocxSession session, string alt)
+    {
+        var json = $$"""{"type": "image", "path": {{JsonSerializer.Serialize(_imagePath)}}, "alt": "{{alt}}", "width": 40, "height": 20}""";
+        return (Paragraph)ElementFactory.CreateFromJson(JsonDocument.Parse(json).RootElement, session.Document.MainDocumentPart!);
+    }
+
+    private static Paragraph Heading(string text) =>
+        new(new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }), new Run(new Text(text)));
+
+    [Fact]
+    public void Collect_ListsEachPlacementWithSectionAndAltText()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().Append(Image(session, "Logo"), Heading("Results"), Image(session, "Chart"));
+
+        var (manifest, files) = ImageExportHelper.Collect(session.Document);
+
+        Assert.Equal(2, manifest.Count);
+        Assert.Equal(["image-001.png", "image-002.png"], files.Keys.Order());
+        Assert.Null(manifest[0].Section);
+        Assert.Equal("Logo", manifest[0].AltText);
+        Assert.Equal("Results", manifest[1].Section);
+        Assert.Equal("Chart", manifest[1].AltText);
+        Assert.Equal(1, manifest[1].PixelWidth);
+        Assert.Equal(1, manifest[1].PixelHeight);
+        Assert.Equal(40 * 9525L, manifest[1].DisplayWidthEmu);
+    }
+
+    [Fact]
+    public void ExportImages_WritesZipWithManifest()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().Append(Heading("Intro"), Image(session, "Diagram"));
+        var zipPath = Path.Combine(_dir, "out", "images.zip");
+
+        var result = JsonNode.Parse(ExportTools.ExportImages(mgr, session.Id, zipPath))!;
+
+        Assert.Equal(1, result["images"]!.GetValue<int>());
+        using var zip = ZipFile.OpenRead(zipPath);
+        Assert.Equal(["image-001.png", "manifest.json"], zip.Entries.Select(e => e.FullName));
+        using var reader = new StreamReader(zip.GetEntry("manifest.json")!.Open());
+        var entry = JsonNode.Parse(reader.ReadToEnd())![0]!;
+        Assert.Equal("Intro", entry["section"]!.GetValue<string>());
+        Assert.Equal("body", entry["part"]!.GetValue<string>());
+    }
+
+    [Fact]
+    public void PixelSize_ReadsGifAndUnknownFormats()
+    {
+        byte[] gif = [.. "GIF89a"u8, 3, 0, 2, 0];
+        Assert.Equal(((int?)3, (int?)2), ImageExportHelper.PixelSize(gif));
+        Assert.Equal(((int?)null, (int?)null), ImageExportHelper.PixelSize([1, 2, 3]));
+    }
+}
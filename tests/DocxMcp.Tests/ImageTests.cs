@@ -0,0 +1,66 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+using DW = DocumentFormat.OpenXml.Drawing.Wordprocessing;
+using PIC = DocumentFormat.OpenXml.Drawing.Pictures;
+
+namespace DocxMcp.Tests;
+
+public class ImageTests : IDisposable
+{
+    // 1x1 transparent PNG
+    private static readonly byte[] Png = Convert.FromBase64String(
+        "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=");
+
+    private readonly DocxSession _session;
+    private readonly string _imagePath;
+
+    public ImageTests()
+    {
+        _session = DocxSession.Create();
+        _imagePath = Path.Combine(Path.GetTempPath(), $"docx-mcp-image-{Guid.NewGuid():N}.png");
+        File.WriteAllBytes(_imagePath, Png);
+    }
+
+    public void Dispose()
+    {
+        _session.Dispose();
+        File.Delete(_imagePath);
+    }
+
+    private Paragraph AddImage(string extra)
+    {
+        var json = $$"""{"type": "image", "path": {{JsonSerializer.Serialize(_imagePath)}}{{extra}}}""";
+        var paragraph = (Paragraph)ElementFactory.CreateFromJson(
+            JsonDocument.Parse(json).RootElement, _session.Document.MainDocumentPart!);
+        _session.GetBody().AppendChild(paragraph);
+        return paragraph;
+    }
+
+    [Fact]
+    public void AltText_IsWrittenToDocPr()
+    {
+        var paragraph = AddImage(""", "alt": "Revenue \"by\" quarter", "title": "Revenue", "name": "Chart" """);
+
+        var docPr = paragraph.Descendants<DW.DocProperties>().Single();
+        Assert.Equal("Revenue \"by\" quarter", docPr.Description!.Value);
+        Assert.Equal("Revenue", docPr.Title!.Value);
+        Assert.Equal("Chart", docPr.Name!.Value);
+
+        var cNvPr = paragraph.Descendants<PIC.NonVisualDrawingProperties>().Single();
+        Assert.Equal("Revenue \"by\" quarter", cNvPr.Description!.Value);
+    }
+
+    [Fact]
+    public void EachImage_GetsItsOwnDocPrId()
+    {
+        var first = AddImage("").Descendants<DW.DocProperties>().Single();
+        var second = AddImage("").Descendants<DW.DocProperties>().Single();
+
+        Assert.Equal(1u, first.Id!.Value);
+        Assert.Equal(2u, second.Id!.Value);
+        Assert.Equal("Picture 2", second.Name!.Value);
+        Assert.Null(second.Title);
+    }
+}
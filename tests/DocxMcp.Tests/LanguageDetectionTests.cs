@@ -0,0 +1,90 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class LanguageDetectionTests
+{
+    private static Paragraph Heading(string text) => new(
+        new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+        new Run(new Text(text)));
+
+    private static Paragraph Para(string text) => new(new Run(new Text(text)));
+
+    private static void Fill(DocxSession session)
+    {
+        var body = session.GetBody();
+        foreach (var paragraph in new[]
+        {
+            Heading("Terms and Conditions"),
+            Para("The supplier shall deliver the goods to the address given by the customer within thirty days."),
+            Para("Payment is due on receipt of the invoice and the customer is responsible for all taxes."),
+            Para("Either party may terminate this agreement with thirty days of written notice to the other party."),
+            Heading("Conditions générales"),
+            Para("Le fournisseur livre les marchandises à l'adresse indiquée par le client dans un délai de trente jours."),
+            Para("Le paiement est dû à la réception de la facture et le client est responsable des taxes.")
+        })
+            body.AppendChild(paragraph);
+        ElementIdManager.EnsureAllIds(session.Document);
+    }
+
+    [Theory]
+    [InlineData("The goods are delivered to the customer at the address in the order.", "en")]
+    [InlineData("Die Ware wird dem Kunden an die in der Bestellung angegebene Adresse geliefert und ist nicht für den Wiederverkauf bestimmt.", "de")]
+    [InlineData("La mercancía se entrega al cliente en la dirección indicada en el pedido y el pago se realiza con tarjeta.", "es")]
+    [InlineData("Товар доставляется покупателю по адресу, указанному в заказе.", "ru")]
+    [InlineData("商品は注文に記載された住所にお届けします。", "ja")]
+    public void Detect_RecognizesCommonLanguages(string text, string expected)
+    {
+        var detected = LanguageDetectionHelper.Detect(text);
+
+        Assert.NotNull(detected);
+        Assert.Equal(expected, detected.Value.Language);
+        Assert.InRange(detected.Value.Confidence, 0.3, 1);
+    }
+
+    [Fact]
+    public void Analyze_ByHeading_ReportsEachSectionsLanguage()
+    {
+        using var session = DocxSession.Create();
+        Fill(session);
+
+        var sections = LanguageDetectionHelper.Analyze(session.Document, byHeading: true)
+            .Where(s => s.SampledChars > 0)
+            .ToList();
+
+        Assert.Equal(["en", "fr"], sections.Select(s => s.Languages[0].Language));
+        Assert.Equal("Conditions générales", sections[1].FirstHeading);
+        Assert.Equal(1, sections[1].Languages[0].Share);
+    }
+
+    [Fact]
+    public void DetectLanguages_SetDefaults_TagsOtherLanguageAndReplays()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        Fill(session);
+        TestHelpers.PersistBaseline(mgr, session);
+        var id = session.Id;
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        var json = LanguageTools.DetectLanguages(mgr, sync, gate, id, by: "heading", set_defaults: true);
+        Assert.Contains("\"multilingual\": true", json);
+
+        string? Lang(string text) => mgr.Get(id).GetBody().Elements<Paragraph>()
+            .First(p => p.InnerText.StartsWith(text)).Descendants<Languages>().FirstOrDefault()?.Val?.Value;
+
+        Assert.Equal("fr", Lang("Le paiement"));
+        Assert.Null(Lang("Payment"));
+
+        mgr.Undo(id);
+        Assert.Null(Lang("Le paiement"));
+        mgr.Redo(id);
+        Assert.Equal("fr", Lang("Le paiement"));
+        Assert.Equal("en", mgr.Get(id).Document.MainDocumentPart!.StyleDefinitionsPart!.Styles!.DocDefaults!
+            .RunPropertiesDefault!.RunPropertiesBaseStyle!.GetFirstChild<Languages>()!.Val!.Value);
+    }
+}
@@ -0,0 +1,95 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Paths;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class MarkdownImportTests
+{
+    private const string Markdown = """
+        # Release notes
+
+        The **new** engine is *faster*
+        and ~~slower~~ `never` [documented](https://example.com/a?b=1&c=2).
+
+        - First
+        - Second
+          continued
+
+        1. One
+        2. Two
+
+        > Quoted
+        > text
+
+        ```rust
+        let x = 1 < 2;
+        ```
+
+        | Name | Value |
+        |------|------:|
+        | a \| b | 1 |
+        """;
+
+    [Fact]
+    public void ToHtml_MapsBlocksAndInlineSpans()
+    {
+        var html = MarkdownImportHelper.ToHtml(Markdown);
+
+        Assert.Equal(
+            "<h1>Release notes</h1>\n" +
+            "<p>The <strong>new</strong> engine is <em>faster</em> and <del>slower</del> <code>never</code> " +
+            "<a href=\"https://example.com/a?b=1&amp;c=2\">documented</a>.</p>\n" +
+            "<ul><li>First</li><li>Second continued</li></ul>\n" +
+            "<ol><li>One</li><li>Two</li></ol>\n" +
+            "<blockquote>Quoted text</blockquote>\n" +
+            "<pre>let x = 1 &lt; 2;</pre>\n" +
+            "<table><tr><th>Name</th><th>Value</th></tr><tr><td>a | b</td><td>1</td></tr></table>\n",
+            html);
+    }
+
+    [Fact]
+    public void ToHtml_LeavesCodeSpansAndIntrawordUnderscoresAlone()
+    {
+        var html = MarkdownImportHelper.ToHtml("Call `**not bold**` on snake_case_name");
+
+        Assert.Equal("<p>Call <code>**not bold**</code> on snake_case_name</p>\n", html);
+    }
+
+    [Fact]
+    public void ToHtml_EscapesRawHtml()
+    {
+        var html = MarkdownImportHelper.ToHtml("<script>alert(1)</script>");
+
+        Assert.Equal("<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n", html);
+    }
+
+    [Fact]
+    public void ToHtml_ConvertsToHeadingsListsAndTables()
+    {
+        var conversion = HtmlPasteHelper.Convert(MarkdownImportHelper.ToHtml(Markdown));
+
+        var types = conversion.Elements.Select(e => e!["type"]!.GetValue<string>()).ToList();
+        Assert.Equal(["heading", "paragraph", "list", "list", "paragraph", "paragraph", "table"], types);
+        Assert.Equal(1, conversion.Elements[0]!["level"]!.GetValue<int>());
+        Assert.False(conversion.Elements[2]!["ordered"]!.GetValue<bool>());
+        Assert.True(conversion.Elements[3]!["ordered"]!.GetValue<bool>());
+    }
+
+    [Fact]
+    public void PasteAtBodyAppendPath_AddsAfterExistingContent()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text("Existing"))));
+        body.AppendChild(new SectionProperties());
+
+        var conversion = HtmlPasteHelper.Convert(MarkdownImportHelper.ToHtml("# Added\n\nBody text"));
+        PasteTools.Paste(session.Document, conversion.Elements, PathResolver.BodyAppendPath(session.Document));
+
+        Assert.Equal(["Existing", "Added", "Body text"], body.Elements<Paragraph>().Select(p => p.InnerText));
+        Assert.IsType<SectionProperties>(body.LastChild);
+    }
+}
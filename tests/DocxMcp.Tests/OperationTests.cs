@@ -0,0 +1,93 @@
+using DocxMcp.Grpc;
+using Microsoft.Extensions.Logging.Abstractions;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class OperationTests
+{
+    private static OperationManager CreateManager() =>
+        new(TestHelpers.GetOrCreateHistoryStorage(), NullLogger<OperationManager>.Instance);
+
+    private static string NewTenant() => $"test-{Guid.NewGuid():N}";
+
+    [Fact]
+    public async Task Operation_ReportsProgressAndResult()
+    {
+        var manager = CreateManager();
+        var tenant = NewTenant();
+        var release = new TaskCompletionSource();
+
+        var started = await manager.StartAsync(tenant, "export_pdf", "Export report", "s1", async ctx =>
+        {
+            await ctx.ReportAsync(0.5, "Halfway");
+            await release.Task;
+            return "done";
+        });
+        Assert.Equal(OperationState.Running, started.State);
+
+        var running = await manager.ListAsync(tenant, includeFinished: false);
+        Assert.Equal(started.Id, Assert.Single(running).Id);
+
+        release.SetResult();
+        var finished = await manager.WaitAsync(tenant, started.Id, TimeSpan.FromSeconds(10));
+
+        Assert.NotNull(finished);
+        Assert.Equal(OperationState.Succeeded, finished.State);
+        Assert.Equal("done", finished.Result);
+        Assert.Equal(1.0, finished.Progress);
+        Assert.Equal("Halfway", finished.Message);
+        Assert.Empty(await manager.ListAsync(tenant, includeFinished: false));
+    }
+
+    [Fact]
+    public async Task Operation_FailureRecordsError()
+    {
+        var manager = CreateManager();
+        var tenant = NewTenant();
+
+        var started = await manager.StartAsync(tenant, "export_pdf", "Export report", null,
+            _ => throw new InvalidOperationException("LibreOffice not found"));
+        var finished = await manager.WaitAsync(tenant, started.Id, TimeSpan.FromSeconds(10));
+
+        Assert.Equal(OperationState.Failed, finished!.State);
+        Assert.Equal("LibreOffice not found", finished.Error);
+    }
+
+    [Fact]
+    public async Task Operation_CancelStopsTheWork()
+    {
+        var manager = CreateManager();
+        var tenant = NewTenant();
+        var stopped = new TaskCompletionSource<bool>();
+
+        var started = await manager.StartAsync(tenant, "export_pdf", "Export report", null, async ctx =>
+        {
+            try
+            {
+                await Task.Delay(Timeout.Infinite, ctx.CancellationToken);
+            }
+            catch (OperationCanceledException)
+            {
+                stopped.SetResult(true);
+                throw;
+            }
+            return "unreachable";
+        });
+
+        var cancelled = await manager.CancelAsync(tenant, started.Id);
+        Assert.Equal(OperationState.Cancelled, cancelled.State);
+
+        Assert.True(await stopped.Task.WaitAsync(TimeSpan.FromSeconds(10)));
+        var op = await manager.GetAsync(tenant, started.Id);
+        Assert.Equal(OperationState.Cancelled, op!.State);
+    }
+
+    [Fact]
+    public async Task Operation_UnknownIdReturnsNull()
+    {
+        var manager = CreateManager();
+
+        Assert.Null(await manager.GetAsync(NewTenant(), "op_missing"));
+    }
+}
@@ -0,0 +1,145 @@
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+using A = DocumentFormat.OpenXml.Drawing;
+
+namespace DocxMcp.Tests;
+
+public class OptimizeTests
+{
+    private static readonly byte[] Logo = [0x89, 0x50, 0x4E, 0x47, 1, 2, 3];
+    private static readonly byte[] Other = [0x89, 0x50, 0x4E, 0x47, 4, 5, 6];
+
+    private static DocxSession CreateBloatedSession()
+    {
+        var session = DocxSession.Create();
+        var mainPart = session.Document.MainDocumentPart!;
+
+        var stylesPart = mainPart.AddNewPart<StyleDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        stylesPart.Styles = new Styles(
+            new Style(new StyleName { Val = "Normal" }, new Rsid { Val = "00A1B2C3" })
+                { Type = StyleValues.Paragraph, StyleId = "Normal", Default = true },
+            new Style(new StyleName { Val = "heading 1" }, new BasedOn { Val = "Normal" },
+                    new LinkedStyle { Val = "Heading1Char" })
+                { Type = StyleValues.Paragraph, StyleId = "Heading1" },
+            new Style(new StyleName { Val = "Heading 1 Char" })
+                { Type = StyleValues.Character, StyleId = "Heading1Char" },
+            new Style(new StyleName { Val = "caption" })
+                { Type = StyleValues.Paragraph, StyleId = "Caption" },
+            new Style(new StyleName { Val = "Unused" })
+                { Type = StyleValues.Paragraph, StyleId = "Unused" });
+
+        var numberingPart = mainPart.AddNewPart<NumberingDefinitionsPart>(DeterministicOutput.NextRelationshipId(mainPart));
+        numberingPart.Numbering = new Numbering(
+            new AbstractNum(new Level { LevelIndex = 0 }) { AbstractNumberId = 1 },
+            new AbstractNum(new Level { LevelIndex = 0 }) { AbstractNumberId = 2 },
+            new NumberingInstance(new AbstractNumId { Val = 1 }) { NumberID = 1 },
+            new NumberingInstance(new AbstractNumId { Val = 2 }) { NumberID = 2 });
+
+        string AddImage(byte[] data)
+        {
+            var id = DeterministicOutput.NextRelationshipId(mainPart);
+            var part = mainPart.AddImagePart(ImagePartType.Png, id);
+            using var stream = new MemoryStream(data);
+            part.FeedData(stream);
+            return id;
+        }
+        var first = AddImage(Logo);
+        var duplicate = AddImage(Logo);
+        AddImage(Other);
+
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new Run(new Text("Title"))) { RsidParagraphAddition = "00A1B2C3" });
+        body.AppendChild(new Paragraph(
+            new ParagraphProperties(new NumberingProperties(
+                new NumberingLevelReference { Val = 0 }, new NumberingId { Val = 1 })),
+            new Run(new Text("Item")) { RsidRunAddition = "00A1B2C3" }));
+        body.AppendChild(new Paragraph(
+            new Run(new FieldCode(" TOC \\t \"caption,1\" ")),
+            new Run(new A.Blip { Embed = first }),
+            new Run(new A.Blip { Embed = duplicate })));
+
+        return session;
+    }
+
+    [Fact]
+    public void Optimize_RemovesUnusedStylesAndKeepsTheirDependencies()
+    {
+        using var session = CreateBloatedSession();
+        var report = DocumentOptimizer.Optimize(session.Document, new OptimizeOptions());
+
+        var ids = session.Document.MainDocumentPart!.StyleDefinitionsPart!.Styles!
+            .Elements<Style>().Select(s => s.StyleId!.Value).ToList();
+        Assert.Equal(new[] { "Normal", "Heading1", "Heading1Char", "Caption" }, ids);
+        Assert.Equal(1, report.StylesRemoved);
+    }
+
+    [Fact]
+    public void Optimize_RemovesUnusedNumbering()
+    {
+        using var session = CreateBloatedSession();
+        var report = DocumentOptimizer.Optimize(session.Document, new OptimizeOptions());
+
+        var numbering = session.Document.MainDocumentPart!.NumberingDefinitionsPart!.Numbering!;
+        Assert.Equal(new[] { 1 }, numbering.Elements<NumberingInstance>().Select(n => n.NumberID!.Value));
+        Assert.Equal(new[] { 1 }, numbering.Elements<AbstractNum>().Select(a => a.AbstractNumberId!.Value));
+        Assert.Equal(2, report.NumberingRemoved);
+    }
+
+    [Fact]
+    public void Optimize_DedupesAndRemovesImages()
+    {
+        using var session = CreateBloatedSession();
+        var report = DocumentOptimizer.Optimize(session.Document, new OptimizeOptions());
+
+        var mainPart = session.Document.MainDocumentPart!;
+        var image = Assert.Single(mainPart.ImageParts);
+        var id = mainPart.GetIdOfPart(image);
+        Assert.All(mainPart.Document!.Descendants<A.Blip>(), b => Assert.Equal(id, b.Embed!.Value));
+        Assert.Equal(1, report.ImagesDeduplicated);
+        Assert.Equal(1, report.MediaRemoved);
+    }
+
+    [Fact]
+    public void Optimize_StripsRsids()
+    {
+        using var session = CreateBloatedSession();
+        var report = DocumentOptimizer.Optimize(session.Document, new OptimizeOptions());
+
+        var mainPart = session.Document.MainDocumentPart!;
+        Assert.DoesNotContain("rsid", mainPart.Document!.OuterXml);
+        Assert.DoesNotContain("rsid", mainPart.StyleDefinitionsPart!.Styles!.OuterXml);
+        Assert.Equal(3, report.RsidsRemoved);
+    }
+
+    [Fact]
+    public void Optimize_DisabledStepsLeaveDocumentAlone()
+    {
+        using var session = CreateBloatedSession();
+        var report = DocumentOptimizer.Optimize(session.Document,
+            new OptimizeOptions(false, false, false, false, false));
+
+        Assert.Equal(0, report.StylesRemoved + report.NumberingRemoved + report.MediaRemoved
+            + report.ImagesDeduplicated + report.RsidsRemoved);
+        Assert.Equal(3, session.Document.MainDocumentPart!.ImageParts.Count());
+    }
+
+    [Fact]
+    public void Recompress_ShrinksAndKeepsDocumentReadable()
+    {
+        using var session = CreateBloatedSession();
+        var body = session.GetBody();
+        for (var i = 0; i < 200; i++)
+            body.AppendChild(new Paragraph(new Run(new Text($"Repeated paragraph {i} with the same words"))));
+
+        var bytes = session.ToBytes();
+        var recompressed = DocumentOptimizer.Recompress(bytes);
+        Assert.True(recompressed.Length <= bytes.Length);
+
+        using var reopened = DocxSession.FromBytes(recompressed, "s1", sourcePath: null);
+        Assert.Equal(body.Elements<Paragraph>().Count(), reopened.GetBody().Elements<Paragraph>().Count());
+    }
+}
@@ -0,0 +1,70 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class PageLayoutTests
+{
+    private static DocxSession CreateDoc(int paragraphs, string text = "Lorem ipsum dolor sit amet.")
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        for (int i = 0; i < paragraphs; i++)
+            body.AppendChild(new Paragraph(new Run(new Text(text))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Estimate_EmptyAndShortDocumentsFitOnOnePage()
+    {
+        using var empty = DocxSession.Create();
+        Assert.Equal(1, PageLayoutEstimator.Estimate(empty.Document).Pages);
+
+        using var session = CreateDoc(10);
+        var estimate = PageLayoutEstimator.Estimate(session.Document);
+        Assert.Equal(1, estimate.Pages);
+        Assert.Equal(10, estimate.Ranges[0].Lines);
+    }
+
+    [Fact]
+    public void Estimate_OverflowsOntoNextPages()
+    {
+        // Letter page with 1in margins: 12960 twips, 12pt lines (10pt × 1.2) -> 54 lines per page
+        using var session = CreateDoc(120);
+
+        var estimate = PageLayoutEstimator.Estimate(session.Document);
+
+        Assert.Equal(3, estimate.Pages);
+        Assert.Equal(54, estimate.Ranges[0].Lines);
+        var paragraphs = session.GetBody().Elements<Paragraph>().ToList();
+        Assert.Equal($"/body/paragraph[id='{ElementIdManager.GetId(paragraphs[54])}']", estimate.Ranges[1].First);
+    }
+
+    [Fact]
+    public void Estimate_HonoursPageBreaksAndSections()
+    {
+        using var session = CreateDoc(2);
+        var body = session.GetBody();
+        body.InsertAt(new Paragraph(new Run(new Break { Type = BreakValues.Page })), 1);
+        body.InsertAt(new Paragraph(
+            new ParagraphProperties(new SectionProperties(new SectionType { Val = SectionMarkValues.NextPage }))), 0);
+
+        var estimate = PageLayoutEstimator.Estimate(session.Document);
+
+        Assert.Equal(2, estimate.Sections);
+        Assert.Equal(3, estimate.Pages);
+    }
+
+    [Fact]
+    public void Estimate_WrapsLongParagraphsByWidth()
+    {
+        // 6.5in text width at 10pt: about 93 characters per line
+        using var session = CreateDoc(1, new string('x', 930));
+
+        var estimate = PageLayoutEstimator.Estimate(session.Document);
+
+        Assert.Equal(10, estimate.Ranges[0].Lines);
+    }
+}
@@ -174,6 +174,30 @@ public class PatchEngineTests : IDisposable
         }
     }
 
+    [Fact]
+    public void CreateListItems_RichMultiParagraphAndCheckboxItems()
+    {
+        var value = JsonDocument.Parse("""
+            {"type": "list", "ordered": true, "items": [
+                {"runs": [{"text": "Must", "style": {"bold": true}}, {"text": " support SSO"}],
+                 "paragraphs": ["Details.", {"text": "More", "style": {"italic": true}}]},
+                {"text": "Write docs", "checked": false},
+                {"text": "Ship", "checked": true}
+            ]}
+        """).RootElement;
+
+        var items = ElementFactory.CreateListItems(value).Cast<Paragraph>().ToList();
+
+        Assert.Equal(
+            ["ListNumber", "ListContinue", "ListContinue", "ListNumber", "ListNumber"],
+            items.Select(p => p.ParagraphProperties?.ParagraphStyleId?.Val?.Value));
+        Assert.Equal("Must support SSO", items[0].InnerText);
+        Assert.NotNull(items[0].Elements<Run>().First().RunProperties?.Bold);
+        Assert.NotNull(items[2].Elements<Run>().Single().RunProperties?.Italic);
+        Assert.Equal("\u2610 Write docs", items[3].InnerText);
+        Assert.Equal("\u2612 Ship", items[4].InnerText);
+    }
+
     public void Dispose()
     {
         _session.Dispose();
@@ -381,6 +381,43 @@ public class PatchResultTests : IDisposable
 
     #endregion
 
+    #region Structure Delta Tests
+
+    [Fact]
+    public void IncludeStructure_ReportsDeltaAndOutline()
+    {
+        var json = """
+            [{"op": "add", "path": "/body/children/0", "value": {"type": "heading", "level": 1, "text": "Intro"}},
+             {"op": "replace_text", "path": "/body/paragraph[2]", "find": "Second", "replace": "2nd"},
+             {"op": "remove", "path": "/body/paragraph[1]"}]
+            """;
+        var result = DocxMcp.Tools.PatchTool.ApplyPatch(_sessions, _sync, _gate, _session.Id, json,
+            include_structure: true);
+
+        var structure = JsonDocument.Parse(result).RootElement.GetProperty("structure");
+        var added = structure.GetProperty("added");
+        Assert.Equal(1, added.GetArrayLength());
+        Assert.Equal(0, added[0].GetProperty("start").GetInt32());
+        Assert.Equal(1, structure.GetProperty("changed")[0].GetProperty("start").GetInt32());
+        Assert.Equal(1, structure.GetProperty("removed").GetArrayLength());
+
+        var outline = structure.GetProperty("outline");
+        Assert.Equal(1, outline.GetArrayLength());
+        Assert.Equal("Intro", outline[0].GetProperty("text").GetString());
+        Assert.Equal(added[0].GetProperty("ids")[0].GetString(), outline[0].GetProperty("id").GetString());
+    }
+
+    [Fact]
+    public void Structure_OmittedByDefault()
+    {
+        var json = """[{"op": "add", "path": "/body/children/0", "value": {"type": "paragraph", "text": "New"}}]""";
+        var result = DocxMcp.Tools.PatchTool.ApplyPatch(_sessions, _sync, _gate, _session.Id, json);
+
+        Assert.False(JsonDocument.Parse(result).RootElement.TryGetProperty("structure", out _));
+    }
+
+    #endregion
+
     public void Dispose()
     {
         _sessions.Close(_session.Id);
@@ -156,6 +156,26 @@ public class PathResolverTests : IDisposable
         Assert.Throws<InvalidOperationException>(() => PathResolver.Resolve(path, _doc));
     }
 
+    [Fact]
+    public void BodyAppendPath_WithoutSectionProperties_PointsPastTheLastChild()
+    {
+        Assert.Equal("/body/children/4", PathResolver.BodyAppendPath(_doc));
+    }
+
+    [Fact]
+    public void BodyAppendPath_KeepsSectionPropertiesLast()
+    {
+        var body = _doc.MainDocumentPart!.Document!.Body!;
+        body.AppendChild(new SectionProperties());
+
+        var path = DocxPath.Parse(PathResolver.BodyAppendPath(_doc));
+        var (parent, index) = PathResolver.ResolveForInsert(path, _doc);
+        parent.InsertChildAt(new Paragraph(new Run(new Text("Appended"))), index);
+
+        Assert.IsType<SectionProperties>(body.LastChild);
+        Assert.Equal("Appended", body.ChildElements[^2].InnerText);
+    }
+
     public void Dispose()
     {
         _doc.Dispose();
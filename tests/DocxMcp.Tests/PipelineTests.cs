@@ -0,0 +1,139 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Grpc;
+using DocxMcp.Tools;
+using Microsoft.Extensions.Logging.Abstractions;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class PipelineTests
+{
+    private static Task NoProgress(double progress, string? message) => Task.CompletedTask;
+
+    private static (SessionManager Mgr, DocxSession Session) Proposal()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+        body.PrependChild(new Paragraph(new Run(new Text("Prepared for {{ client }} by {{author}}.") { Space = SpaceProcessingModeValues.Preserve })));
+        body.PrependChild(new Paragraph());
+        TestHelpers.PersistBaseline(mgr, session);
+        return (mgr, session);
+    }
+
+    private static async Task<JsonElement> Run(SessionManager mgr, string docId, string pipeline)
+    {
+        var json = await PipelineTools.RunAsync(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            docId, PipelineTools.Parse(pipeline), NoProgress, CancellationToken.None);
+        return JsonDocument.Parse(json).RootElement;
+    }
+
+    private static string[] Statuses(JsonElement result) =>
+        result.GetProperty("steps").EnumerateArray().Select(s => s.GetProperty("status").GetString()!).ToArray();
+
+    [Theory]
+    [InlineData("""{"steps": []}""")]
+    [InlineData("""{"steps": [{"step": "fill", "values": {"a": "b"}}, {"step": "open"}]}""")]
+    [InlineData("""{"steps": [{"step": "render"}]}""")]
+    [InlineData("""{"steps": [{"step": "fill", "values": {"a": ""}}]}""")]
+    [InlineData("""{"steps": [{"step": "export", "format": "odt"}]}""")]
+    [InlineData("""{"on_error": "retry", "steps": [{"step": "toc", "path": "/body/paragraph[0]"}]}""")]
+    public void Parse_RejectsInvalidPipelines(string pipeline)
+    {
+        Assert.Throws<ArgumentException>(() => PipelineTools.Parse(pipeline));
+    }
+
+    [Fact]
+    public async Task Run_FillsInsertsTableAndToc()
+    {
+        var (mgr, session) = Proposal();
+
+        var result = await Run(mgr, session.Id, """
+            {"steps": [
+              {"step": "fill", "values": {"client": "ACME", "author": "Dana", "date": "today"}},
+              {"step": "table", "path": "/body/children/2", "csv": "Item,Price\r\nSetup,\"1,200\"\r\nSupport,300\r\n"},
+              {"step": "toc", "path": "/body/paragraph[0]", "levels": "1-2"},
+              {"step": "export", "format": "markdown"}
+            ]}
+            """);
+
+        Assert.Equal("completed", result.GetProperty("status").GetString());
+        Assert.Equal(["succeeded", "succeeded", "succeeded", "succeeded"], Statuses(result));
+        var fill = result.GetProperty("steps")[0].GetProperty("output");
+        Assert.Equal(2, fill.GetProperty("filled").GetInt32());
+        Assert.Equal("date", fill.GetProperty("missing")[0].GetString());
+        Assert.Contains("Prepared for ACME by Dana.", result.GetProperty("steps")[3].GetProperty("output").GetString());
+
+        using var filled = mgr.Get(session.Id);
+        var body = filled.GetBody();
+        Assert.Contains(" TOC \\o \"1-2\"", body.Descendants<FieldCode>().Single().Text);
+        var table = body.Elements<Table>().Single();
+        Assert.Equal(3, table.Elements<TableRow>().Count());
+        Assert.Equal("1,200", table.Elements<TableRow>().ElementAt(1).Elements<TableCell>().Last().InnerText);
+    }
+
+    [Fact]
+    public async Task Run_FailedStepWithRollback_UndoesThePipelineEdits()
+    {
+        var (mgr, session) = Proposal();
+
+        var result = await Run(mgr, session.Id, """
+            {"on_error": "rollback", "steps": [
+              {"step": "fill", "values": {"client": "ACME"}},
+              {"step": "toc", "path": "/body/paragraph[9]"},
+              {"step": "export", "format": "html"}
+            ]}
+            """);
+
+        Assert.Equal("rolled_back", result.GetProperty("status").GetString());
+        Assert.Equal(["succeeded", "failed", "skipped"], Statuses(result));
+        using var restored = mgr.Get(session.Id);
+        Assert.Contains("{{ client }}", restored.GetBody().InnerText);
+    }
+
+    [Fact]
+    public async Task Run_FailedStepWithContinue_RunsTheRest()
+    {
+        var (mgr, session) = Proposal();
+
+        var result = await Run(mgr, session.Id, """
+            {"steps": [
+              {"step": "style", "target": "paragraph", "path": "/body/table[0]", "style": {"alignment": "center"}, "on_error": "continue"},
+              {"step": "fill", "values": {"client": "ACME"}}
+            ]}
+            """);
+
+        Assert.Equal("completed_with_errors", result.GetProperty("status").GetString());
+        Assert.Equal(["failed", "succeeded"], Statuses(result));
+        using var filled = mgr.Get(session.Id);
+        Assert.Contains("Prepared for ACME", filled.GetBody().InnerText);
+    }
+
+    [Fact]
+    public async Task RunPipeline_RunsAsAnOperationFromANewDocument()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var operations = new OperationManager(TestHelpers.GetOrCreateHistoryStorage(), NullLogger<OperationManager>.Instance);
+
+        var started = await PipelineTools.RunPipeline(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), operations, """
+            {"steps": [
+              {"step": "open"},
+              {"step": "patch", "patches": [{"op": "add", "path": "/body/children/0", "value": {"type": "heading", "level": 1, "text": "Offer"}}]},
+              {"step": "export", "format": "markdown"}
+            ]}
+            """);
+        var operationId = JsonDocument.Parse(started).RootElement.GetProperty("operation_id").GetString()!;
+
+        var finished = await operations.WaitAsync(mgr.TenantId, operationId, TimeSpan.FromSeconds(30));
+
+        Assert.Equal(OperationState.Succeeded, finished!.State);
+        var result = JsonDocument.Parse(finished.Result!).RootElement;
+        Assert.Equal("completed", result.GetProperty("status").GetString());
+        Assert.Contains("# Offer", result.GetProperty("steps")[2].GetProperty("output").GetString());
+        using var created = mgr.Get(result.GetProperty("doc_id").GetString()!);
+        Assert.Equal("Offer", created.GetBody().Elements<Paragraph>().First().InnerText);
+    }
+}
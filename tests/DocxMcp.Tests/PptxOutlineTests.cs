@@ -0,0 +1,100 @@
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Validation;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+using D = DocumentFormat.OpenXml.Drawing;
+
+namespace DocxMcp.Tests;
+
+public class PptxOutlineTests
+{
+    private static Paragraph Styled(string style, string text) =>
+        new(new ParagraphProperties(new ParagraphStyleId { Val = style }), new Run(new Text(text)));
+
+    private static Paragraph Bullet(string text, int level = 0) =>
+        new(new ParagraphProperties(new NumberingProperties(
+            new NumberingLevelReference { Val = level }, new NumberingId { Val = 1 })),
+            new Run(new Text(text)));
+
+    private static DocxSession Report()
+    {
+        var session = DocxSession.Create();
+        session.GetBody().Append(
+            Styled("Title", "Annual Report"),
+            new Paragraph(new Run(new Text("Before any heading."))),
+            Styled("Heading1", "Results"),
+            new Paragraph(new Run(new Text("Revenue grew. It grew a lot."))),
+            new Paragraph(new Run(new Text("Costs fell!"))),
+            Styled("Heading2", "Highlights"),
+            Bullet("New markets"),
+            Bullet("Three countries", 1),
+            Styled("Heading3", "Risks"),
+            Bullet("Currency"));
+        return session;
+    }
+
+    [Fact]
+    public void BuildOutline_OneSlidePerTopHeading()
+    {
+        using var session = Report();
+
+        var slides = PptxOutlineHelper.BuildOutline(session.Document, 6);
+
+        Assert.Equal(["Results", "Highlights"], slides.Select(s => s.Title));
+        // No list items under Results: its opening sentences are used instead
+        Assert.Equal(
+            [new OutlineBullet("Revenue grew.", 0), new OutlineBullet("Costs fell!", 0)],
+            slides[0].Bullets);
+        Assert.Equal(
+            [new OutlineBullet("New markets", 0), new OutlineBullet("Three countries", 1),
+             new OutlineBullet("Risks", 0), new OutlineBullet("Currency", 1)],
+            slides[1].Bullets);
+        Assert.Equal("Annual Report", PptxOutlineHelper.DocumentTitle(session.Document));
+    }
+
+    [Fact]
+    public void BuildOutline_CapsBulletsPerSlide()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().Append(Styled("Heading1", "List"));
+        for (int i = 0; i < 10; i++)
+            session.GetBody().Append(Bullet($"Item {i}"));
+
+        var slide = Assert.Single(PptxOutlineHelper.BuildOutline(session.Document, 4));
+
+        Assert.Equal(4, slide.Bullets.Count);
+    }
+
+    [Fact]
+    public void ExportPptxOutline_ProducesAValidDeck()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        using (var report = Report())
+            session.GetBody().Append(report.GetBody().ChildElements.Select(e => e.CloneNode(true)));
+
+        var result = ExportTools.ExportPptxOutline(mgr, session.Id);
+
+        using var pptx = PresentationDocument.Open(new MemoryStream(Convert.FromBase64String(result)), false);
+        Assert.Empty(new OpenXmlValidator().Validate(pptx));
+        var slides = pptx.PresentationPart!.Presentation.SlideIdList!.Elements<DocumentFormat.OpenXml.Presentation.SlideId>()
+            .Select(id => (SlidePart)pptx.PresentationPart.GetPartById(id.RelationshipId!))
+            .ToList();
+        Assert.Equal(3, slides.Count);
+        Assert.Equal("Annual Report", slides[0].Slide.Descendants<D.Text>().First().Text);
+        Assert.Equal(["Highlights", "New markets", "Three countries", "Risks", "Currency"],
+            slides[2].Slide.Descendants<D.Text>().Select(t => t.Text));
+    }
+
+    [Fact]
+    public void ExportPptxOutline_WithoutHeadings_IsAnError()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().Append(new Paragraph(new Run(new Text("Just text."))));
+
+        Assert.StartsWith("Error:", ExportTools.ExportPptxOutline(mgr, session.Id));
+    }
+}
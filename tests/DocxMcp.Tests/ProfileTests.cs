@@ -0,0 +1,92 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ProfileTests
+{
+    private static TenantProfile Acme()
+    {
+        var profile = new TenantProfile();
+        profile.Set("company_name", "ACME");
+        profile.Set("default_author", "Jane Doe");
+        return profile;
+    }
+
+    [Fact]
+    public void Resolve_ReplacesOnlyVariablesTheProfileSets()
+    {
+        var profile = Acme();
+
+        Assert.Equal("ACME by Jane Doe, {{company.address}}",
+            profile.Resolve("{{ company.name }} by {{author.name}}, {{company.address}}"));
+        Assert.Equal("ACME", TenantProfile.Parse(profile.ToJson().ToJsonString()).CompanyName);
+        Assert.Throws<ArgumentException>(() => profile.Set("company_phone", "555"));
+    }
+
+    [Fact]
+    public void ResolveSectionBreak_OnlyTouchesHeaderAndFooterTexts()
+    {
+        var profile = Acme();
+
+        var resolved = JsonDocument.Parse(profile.ResolveSectionBreak("""
+            {"op": "add", "path": "/body/children/1", "value": {"type": "section_break",
+             "header": "{{company.name}}", "footer": {"first": "© {{company.name}}"}}}
+            """)).RootElement.GetProperty("value");
+        Assert.Equal("ACME", resolved.GetProperty("header").GetString());
+        Assert.Equal("© ACME", resolved.GetProperty("footer").GetProperty("first").GetString());
+
+        const string paragraph = """{"op": "add", "path": "/body/children/0", "value": {"type": "paragraph", "text": "{{company.name}}"}}""";
+        Assert.Equal(paragraph, profile.ResolveSectionBreak(paragraph));
+    }
+
+    [Fact]
+    public async Task Fill_UsesProfileVariablesUnlessGiven()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        ProfileTools.ProfileSet(mgr, company_name: "ACME", company_address: "1 Main St");
+        var session = mgr.Create();
+        session.GetBody().PrependChild(new Paragraph(new Run(
+            new Text("{{company.name}}, {{company.address}}") { Space = SpaceProcessingModeValues.Preserve })));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        var json = await PipelineTools.RunAsync(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            session.Id, PipelineTools.Parse("""{"steps": [{"step": "fill", "values": {"company.address": "2 High St"}}]}"""),
+            (_, _) => Task.CompletedTask, CancellationToken.None);
+
+        var fill = JsonDocument.Parse(json).RootElement.GetProperty("steps")[0].GetProperty("output");
+        Assert.Equal(2, fill.GetProperty("filled").GetInt32());
+        Assert.Equal("company.name", fill.GetProperty("from_profile")[0].GetString());
+        using var filled = mgr.Get(session.Id);
+        Assert.Equal("ACME, 2 High St", filled.GetBody().Elements<Paragraph>().First().InnerText);
+    }
+
+    [Fact]
+    public void SectionBreakHeaderAndComments_UseTheProfile()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        ProfileTools.ProfileSet(mgr, company_name: "ACME", default_author: "Jane Doe");
+        var session = mgr.Create();
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Intro"))));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        PatchTool.ApplyPatch(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), session.Id, """
+            [{"op": "add", "path": "/body/children/1", "value": {"type": "section_break", "header": "{{company.name}} proposal"}}]
+            """);
+        CommentTools.CommentAdd(mgr, TestHelpers.CreateSyncManager(), session.Id, "/body/paragraph[0]", "Check");
+
+        using var edited = mgr.Get(session.Id);
+        var main = edited.Document.MainDocumentPart!;
+        Assert.Contains(main.HeaderParts, h => h.Header!.InnerText == "ACME proposal");
+        var comment = main.WordprocessingCommentsPart!.Comments!.Elements<Comment>().Single();
+        Assert.Equal("Jane Doe", comment.Author?.Value);
+        Assert.Equal("JD", comment.Initials?.Value);
+
+        Assert.True(mgr.DeleteProfile());
+        Assert.True(mgr.LoadProfile().IsEmpty);
+    }
+}
@@ -0,0 +1,86 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using System.Text.Json;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ReaderTests : IDisposable
+{
+    private readonly DocxSession _session;
+    private readonly SessionManager _sessions;
+
+    public ReaderTests()
+    {
+        _sessions = TestHelpers.CreateSessionManager();
+        _session = _sessions.Create();
+
+        var body = _session.GetBody();
+        foreach (var title in new[] { "Intro", "Scope" })
+        {
+            body.AppendChild(new Paragraph(
+                new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+                new Run(new Text(title))));
+            for (var i = 0; i < 3; i++)
+                body.AppendChild(new Paragraph(new Run(new Text($"{title} paragraph {i}"))));
+        }
+
+        ElementIdManager.EnsureAllIds(_session.Document);
+        TestHelpers.PersistBaseline(_sessions, _session);
+    }
+
+    private static string[] Texts(JsonElement result) =>
+        result.GetProperty("items").EnumerateArray().Select(i => i.GetProperty("text").GetString()!).ToArray();
+
+    [Fact]
+    public void ReadNext_WalksParagraphsThenSections()
+    {
+        using var opened = JsonDocument.Parse(ReaderTools.OpenReader(_sessions, _session.Id));
+        var readerId = opened.RootElement.GetProperty("reader_id").GetString()!;
+        Assert.Equal(8, opened.RootElement.GetProperty("total").GetInt32());
+
+        using var first = JsonDocument.Parse(ReaderTools.ReadNext(_sessions, readerId, "paragraph", 2));
+        Assert.Equal(["Intro", "Intro paragraph 0"], Texts(first.RootElement));
+        Assert.Equal(2, first.RootElement.GetProperty("position").GetInt32());
+        Assert.False(first.RootElement.GetProperty("done").GetBoolean());
+
+        // A section read from mid-section finishes it, stopping before the next heading
+        using var rest = JsonDocument.Parse(ReaderTools.ReadNext(_sessions, readerId, "section"));
+        Assert.Equal(["Intro paragraph 1", "Intro paragraph 2"], Texts(rest.RootElement));
+
+        using var scope = JsonDocument.Parse(ReaderTools.ReadNext(_sessions, readerId, "section", 5));
+        Assert.Equal(4, scope.RootElement.GetProperty("count").GetInt32());
+        Assert.Equal("Scope", Texts(scope.RootElement)[0]);
+        Assert.True(scope.RootElement.GetProperty("done").GetBoolean());
+    }
+
+    [Fact]
+    public void ReadNext_FollowsElementIdsAcrossEdits()
+    {
+        using var opened = JsonDocument.Parse(ReaderTools.OpenReader(_sessions, _session.Id));
+        var readerId = opened.RootElement.GetProperty("reader_id").GetString()!;
+        ReaderTools.ReadNext(_sessions, readerId, "paragraph", 3);
+
+        // Removing content already read doesn't make the reader skip ahead
+        _session.GetBody().Elements<Paragraph>().First().Remove();
+        TestHelpers.PersistBaseline(_sessions, _session);
+
+        using var next = JsonDocument.Parse(ReaderTools.ReadNext(_sessions, readerId, "paragraph", 1));
+        Assert.Equal(["Intro paragraph 2"], Texts(next.RootElement));
+    }
+
+    [Fact]
+    public void Close_ForgetsReadersOfTheDocument()
+    {
+        var reader = _sessions.Readers.Open(_session.Id);
+        _sessions.Close(_session.Id);
+
+        Assert.Throws<KeyNotFoundException>(() => _sessions.Readers.Get(reader.Id));
+    }
+
+    public void Dispose()
+    {
+        _session.Dispose();
+    }
+}
@@ -0,0 +1,87 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.ExternalChanges;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+/// <summary>
+/// replace_text keeps each run's formatting, including when a match spans runs.
+/// </summary>
+public class ReplaceTextFormattingTests : IDisposable
+{
+    private readonly DocxSession _session;
+    private readonly SessionManager _sessions;
+    private readonly SyncManager _sync;
+    private readonly ExternalChangeGate _gate = TestHelpers.CreateExternalChangeGate();
+
+    public ReplaceTextFormattingTests()
+    {
+        _sessions = TestHelpers.CreateSessionManager();
+        _sync = TestHelpers.CreateSyncManager();
+        _session = _sessions.Create();
+
+        // "Sold to Acme Corp for the price. Acme Corp again." with the first "Acme" bold and "price" italic
+        _session.GetBody().AppendChild(new Paragraph(
+            new Run(new Text("Sold to ") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new RunProperties(new Bold()), new Text("Acme")),
+            new Run(new Text(" Corp for the ") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new RunProperties(new Italic()), new Text("price")),
+            new Run(new Text(". Acme Corp again.") { Space = SpaceProcessingModeValues.Preserve })));
+
+        TestHelpers.PersistBaseline(_sessions, _session);
+    }
+
+    public void Dispose()
+    {
+        _sessions.Close(_session.Id);
+    }
+
+    private List<(string Text, bool Bold, bool Italic)> Replace(string find, string replace, int maxCount = 1)
+    {
+        var json = $$"""[{"op": "replace_text", "path": "/body/paragraph[0]", "find": "{{find}}", "replace": "{{replace}}", "max_count": {{maxCount}}}]""";
+        DocxMcp.Tools.PatchTool.ApplyPatch(_sessions, _sync, _gate, _session.Id, json);
+
+        return _sessions.Get(_session.Id).GetBody().Elements<Paragraph>().First()
+            .Elements<Run>()
+            .Where(r => r.InnerText.Length > 0)
+            .Select(r => (r.InnerText, r.RunProperties?.Bold is not null, r.RunProperties?.Italic is not null))
+            .ToList();
+    }
+
+    [Fact]
+    public void ReplacementInsideBoldSpan_StaysBold()
+    {
+        var runs = Replace("Acme", "Globex");
+
+        Assert.Contains(("Globex", true, false), runs);
+        Assert.Contains(("price", false, true), runs);
+    }
+
+    [Fact]
+    public void MatchAcrossRuns_KeepsSharedTextInItsRun()
+    {
+        var runs = Replace("Acme Corp", "Acme Corporation");
+
+        Assert.Equal(("Acme", true, false), runs[1]);
+        Assert.Equal((" Corporation for the ", false, false), runs[2]);
+    }
+
+    [Fact]
+    public void MatchAcrossRuns_ChangedTextTakesFormattingWhereItStarts()
+    {
+        var runs = Replace("Corp for the price", "Inc at the cost");
+
+        Assert.Equal("Sold to Acme Inc at the cost. Acme Corp again.", string.Concat(runs.Select(r => r.Text)));
+        Assert.Equal(("Acme", true, false), runs[1]);
+        Assert.DoesNotContain(runs, r => r.Italic);
+    }
+
+    [Fact]
+    public void SingleRunAndCrossRunMatches_AreAllReplaced()
+    {
+        var runs = Replace("Acme Corp", "ACME", maxCount: 10);
+
+        Assert.Equal("Sold to ACME for the price. ACME again.", string.Concat(runs.Select(r => r.Text)));
+        Assert.Equal(("ACME", true, false), runs[1]);
+    }
+}
@@ -0,0 +1,138 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class ReviewImportTests
+{
+    private static readonly DateTime ReviewDate = new(2026, 3, 2, 9, 30, 0, DateTimeKind.Utc);
+
+    private static List<Paragraph> AddParagraphs(Body body, params string[] texts)
+    {
+        var paragraphs = texts.Select(t => new Paragraph(new Run(new Text(t) { Space = SpaceProcessingModeValues.Preserve }))).ToList();
+        foreach (var paragraph in Enumerable.Reverse(paragraphs))
+            body.PrependChild(paragraph);
+        return paragraphs;
+    }
+
+    private static void Review(WordprocessingDocument reviewed, IReadOnlyList<Paragraph> paragraphs)
+    {
+        RevisionHelper.ReplaceTextWithTracking(reviewed, paragraphs[0], "thirty", "fifteen", "Alice");
+        CommentHelper.AddCommentToText(reviewed, paragraphs[1], 0, "Too long?", "Bob", "B", ReviewDate, "sixty days");
+    }
+
+    [Fact]
+    public void Collect_WithoutIds_AlignsByContent()
+    {
+        using var live = DocxSession.Create();
+        var liveParagraphs = AddParagraphs(live.GetBody(),
+            "Added after the copy was sent.", "Delivery within thirty days.", "Payment within sixty days.");
+        ElementIdManager.EnsureAllIds(live.Document);
+
+        using var reviewed = DocxSession.Create();
+        Review(reviewed.Document, AddParagraphs(reviewed.GetBody(),
+            "Delivery within thirty days.", "Payment within sixty days."));
+
+        var import = ReviewImportHelper.Collect(live.Document, reviewed.Document);
+
+        var comment = Assert.Single(import.Comments);
+        Assert.Equal(TerminologyHelper.PathOf(liveParagraphs[2], live.GetBody()), comment.Path);
+        Assert.Equal(("Too long?", "Bob", "sixty days"), (comment.Text, comment.Author, comment.AnchorText));
+        Assert.Equal(ReviewDate, comment.Date);
+
+        var change = Assert.Single(import.Changes);
+        Assert.Equal(TerminologyHelper.PathOf(liveParagraphs[1], live.GetBody()), change.Path);
+        Assert.Equal((16, "thirty", "fifteen", "Alice"), (change.Offset, change.Delete, change.Insert, change.Author));
+        Assert.Empty(import.Unmatched);
+    }
+
+    [Fact]
+    public void Collect_ParagraphEditedSinceTheCopy_LeavesTheChangeUnmatched()
+    {
+        using var live = DocxSession.Create();
+        AddParagraphs(live.GetBody(), "Delivery within 30 days.");
+        ElementIdManager.EnsureAllIds(live.Document);
+
+        using var reviewed = DocxSession.Create();
+        var paragraphs = AddParagraphs(reviewed.GetBody(), "Delivery within thirty days.");
+        RevisionHelper.ReplaceTextWithTracking(reviewed.Document, paragraphs[0], "thirty", "fifteen", "Alice");
+
+        var import = ReviewImportHelper.Collect(live.Document, reviewed.Document);
+
+        Assert.Empty(import.Changes);
+        var unmatched = Assert.Single(import.Unmatched);
+        Assert.Equal("change", unmatched.Kind);
+        Assert.Equal("replace 'thirty' with 'fifteen'", unmatched.Text);
+    }
+
+    [Fact]
+    public void ApplyChange_Insertion_KeepsFormattingOfTheTextBefore()
+    {
+        using var session = DocxSession.Create();
+        var paragraph = new Paragraph(
+            new Run(new RunProperties(new Bold()), new Text("Net") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new Text(" price.") { Space = SpaceProcessingModeValues.Preserve }));
+        session.GetBody().PrependChild(paragraph);
+
+        ReviewImportHelper.ApplyChange(session.Document, paragraph,
+            new ImportedChange("", 3, "", " unit", "Alice", ReviewDate));
+
+        var inserted = Assert.Single(paragraph.Elements<InsertedRun>());
+        Assert.Equal(" unit", inserted.InnerText);
+        Assert.Equal("Alice", inserted.Author?.Value);
+        Assert.NotNull(inserted.GetFirstChild<Run>()?.RunProperties?.Bold);
+        Assert.Equal("Net unit price.", string.Concat(paragraph.Descendants<Text>().Select(t => t.Text)));
+    }
+
+    [Fact]
+    public void ImportReview_MergesAnnotationsOnce()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        AddParagraphs(session.GetBody(), "Delivery within thirty days.", "Payment within sixty days.");
+        ElementIdManager.EnsureAllIds(session.Document);
+        TestHelpers.PersistBaseline(mgr, session);
+
+        // The reviewer's copy keeps the element IDs of the exported document
+        using var stream = new MemoryStream();
+        stream.Write(session.ToBytes());
+        stream.Position = 0;
+        using (var reviewed = WordprocessingDocument.Open(stream, true))
+        {
+            var body = reviewed.MainDocumentPart!.Document.Body!;
+            Review(reviewed, body.Elements<Paragraph>().Take(2).ToList());
+        }
+        var copy = Convert.ToBase64String(stream.ToArray());
+
+        var json = ReviewTools.ImportReview(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            session.Id, copy);
+        using (var result = JsonDocument.Parse(json))
+        {
+            Assert.Equal(1, result.RootElement.GetProperty("comments_imported").GetInt32());
+            Assert.Equal(1, result.RootElement.GetProperty("changes_imported").GetInt32());
+        }
+
+        using (var merged = mgr.Get(session.Id))
+        {
+            var comment = Assert.Single(CommentHelper.ListComments(merged.Document));
+            Assert.Equal(("Bob", "sixty days"), (comment.Author, comment.AnchoredText));
+            var first = merged.GetBody().Elements<Paragraph>().First();
+            Assert.Equal("thirty", first.Descendants<DeletedText>().Single().Text);
+            Assert.Equal("fifteen", first.Elements<InsertedRun>().Single().InnerText);
+        }
+
+        json = ReviewTools.ImportReview(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            session.Id, copy);
+        using (var again = JsonDocument.Parse(json))
+        {
+            Assert.Equal(0, again.RootElement.GetProperty("comments_imported").GetInt32());
+            Assert.Equal(0, again.RootElement.GetProperty("changes_imported").GetInt32());
+            Assert.Equal(1, again.RootElement.GetProperty("comments_already_present").GetInt32());
+        }
+    }
+}
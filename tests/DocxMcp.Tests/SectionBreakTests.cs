@@ -0,0 +1,143 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SectionBreakTests : IDisposable
+{
+    private readonly DocxSession _session;
+
+    public SectionBreakTests()
+    {
+        _session = DocxSession.Create();
+        var body = _session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text("Intro"))));
+        body.AppendChild(new Paragraph(new Run(new Text("Appendix"))));
+        body.AppendChild(new SectionProperties(
+            new PageSize { Width = 11906, Height = 16838 },
+            new PageMargin { Top = 1000, Bottom = 1000, Left = 1200, Right = 1200, Header = 500, Footer = 500, Gutter = 0 }));
+    }
+
+    public void Dispose() => _session.Dispose();
+
+    private Paragraph InsertBreak(string json, int index = 1)
+    {
+        var body = _session.GetBody();
+        var mainPart = _session.Document.MainDocumentPart!;
+        var value = JsonDocument.Parse(json).RootElement;
+        var paragraph = (Paragraph)ElementFactory.CreateFromJson(value, mainPart);
+        body.InsertChildAt(paragraph, index);
+        SectionHelper.CompleteSectionBreak(paragraph, value, mainPart);
+        return paragraph;
+    }
+
+    [Fact]
+    public void PlainBreak_KeepsLayoutOfTheSectionItSplits()
+    {
+        var sectPr = InsertBreak("""{"type": "section_break"}""").ParagraphProperties!.SectionProperties!;
+
+        Assert.Equal(11906u, sectPr.GetFirstChild<PageSize>()!.Width!.Value);
+        Assert.Equal(1200u, sectPr.GetFirstChild<PageMargin>()!.Left!.Value);
+        Assert.Equal(SectionMarkValues.NextPage, sectPr.GetFirstChild<SectionType>()!.Val!.Value);
+    }
+
+    [Fact]
+    public void BreakOptions_ApplyToTheSectionBeforeIt()
+    {
+        var sectPr = InsertBreak("""
+            {"type": "section_break", "break_type": "continuous", "orientation": "landscape",
+             "margins": {"left": 720, "right": 720}}
+        """).ParagraphProperties!.SectionProperties!;
+
+        var pageSize = sectPr.GetFirstChild<PageSize>()!;
+        Assert.Equal(16838u, pageSize.Width!.Value);
+        Assert.Equal(11906u, pageSize.Height!.Value);
+        Assert.Equal(PageOrientationValues.Landscape, pageSize.Orient!.Value);
+        var margins = sectPr.GetFirstChild<PageMargin>()!;
+        Assert.Equal(720u, margins.Left!.Value);
+        Assert.Equal(1000, margins.Top!.Value);
+        Assert.Equal(SectionMarkValues.Continuous, sectPr.GetFirstChild<SectionType>()!.Val!.Value);
+
+        // The final section is untouched
+        var last = _session.GetBody().Elements<SectionProperties>().Single();
+        Assert.Equal(11906u, last.GetFirstChild<PageSize>()!.Width!.Value);
+        Assert.Null(last.GetFirstChild<PageSize>()!.Orient);
+    }
+
+    [Fact]
+    public void NamedPageSize_IsUsed()
+    {
+        var sectPr = InsertBreak("""{"type": "section_break", "page_size": "letter"}""")
+            .ParagraphProperties!.SectionProperties!;
+
+        Assert.Equal(12240u, sectPr.GetFirstChild<PageSize>()!.Width!.Value);
+        Assert.Throws<ArgumentException>(() => InsertBreak("""{"type": "section_break", "page_size": "b7"}"""));
+    }
+
+    [Fact]
+    public void Header_DiffersPerSection()
+    {
+        var sectPr = InsertBreak("""
+            {"type": "section_break", "header": {"default": "Intro header", "first": "Cover"}, "footer": "Intro footer"}
+        """).ParagraphProperties!.SectionProperties!;
+
+        var mainPart = _session.Document.MainDocumentPart!;
+        string HeaderText(SectionProperties s, HeaderFooterValues type)
+        {
+            var id = s.Elements<HeaderReference>().Single(r => r.Type!.Value == type).Id!.Value!;
+            return ((HeaderPart)mainPart.GetPartById(id)).Header!.InnerText;
+        }
+
+        Assert.Equal("Intro header", HeaderText(sectPr, HeaderFooterValues.Default));
+        Assert.Equal("Cover", HeaderText(sectPr, HeaderFooterValues.First));
+        Assert.NotNull(sectPr.GetFirstChild<TitlePage>());
+        Assert.Single(sectPr.Elements<FooterReference>());
+
+        // The section after the break gets its own (empty) header instead of inheriting
+        var last = _session.GetBody().Elements<SectionProperties>().Single();
+        Assert.Equal("", HeaderText(last, HeaderFooterValues.Default));
+        Assert.Single(last.Elements<FooterReference>());
+
+        // Header references come before the other section properties
+        Assert.IsType<HeaderReference>(sectPr.FirstChild);
+    }
+
+    [Fact]
+    public void Break_OutsideTheBody_IsRejected()
+    {
+        var body = _session.GetBody();
+        var cell = new TableCell();
+        body.InsertAt(new Table(new TableRow(cell)), 0);
+
+        var mainPart = _session.Document.MainDocumentPart!;
+        var value = JsonDocument.Parse("""{"type": "section_break"}""").RootElement;
+        var paragraph = (Paragraph)ElementFactory.CreateFromJson(value, mainPart);
+        cell.AppendChild(paragraph);
+
+        Assert.Throws<InvalidOperationException>(() =>
+            SectionHelper.CompleteSectionBreak(paragraph, value, mainPart));
+        Assert.Null(paragraph.Parent);
+    }
+
+    [Fact]
+    public void AddElement_CompletesTheBreak()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        ElementTools.AddElement(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            session.Id, "/body/children/0", """{"type": "paragraph", "text": "Page one"}""");
+        ElementTools.AddElement(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(),
+            session.Id, "/body/children/1", """{"type": "section_break", "orientation": "landscape"}""");
+
+        var body = mgr.Get(session.Id).GetBody();
+        var sectPr = body.Descendants<Paragraph>()
+            .Select(p => p.ParagraphProperties?.SectionProperties)
+            .Single(s => s is not null)!;
+        Assert.Equal(PageOrientationValues.Landscape, sectPr.GetFirstChild<PageSize>()!.Orient!.Value);
+        Assert.Single(body.Elements<SectionProperties>());
+    }
+}
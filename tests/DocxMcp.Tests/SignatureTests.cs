@@ -0,0 +1,67 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SignatureTests
+{
+    private static readonly List<Signer> Signers =
+    [
+        new("Jane Doe", "CEO", "Acme Inc.", true),
+        new("John Roe", null, null, false)
+    ];
+
+    [Fact]
+    public void Parse_DefaultsDateLineAndRequiresName()
+    {
+        var signers = SignatureHelper.Parse("""[{"name":"Jane Doe","title":" "}]""");
+        Assert.Equal(new Signer("Jane Doe", null, null, true), Assert.Single(signers));
+
+        Assert.Throws<ArgumentException>(() => SignatureHelper.Parse("""[{"title":"CEO"}]"""));
+        Assert.Throws<ArgumentException>(() => SignatureHelper.Parse("[]"));
+    }
+
+    [Fact]
+    public void Insert_StackedBlocksWithDocuSignAnchors()
+    {
+        using var session = DocxSession.Create();
+
+        var (elements, anchors) = SignatureHelper.Insert(session.Document, Signers, "docusign", "stacked", null, 0);
+
+        var texts = session.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToList();
+        Assert.Equal(
+        [
+            "ACME INC.",
+            "By: \\s1\\______________________________",
+            "Name: Jane Doe",
+            "Title: CEO",
+            "Date: \\d1\\________________",
+            "By: \\s2\\______________________________",
+            "Name: John Roe"
+        ], texts);
+        Assert.Equal(7, elements.Count);
+        Assert.Null(anchors[1].Date);
+
+        // Anchors are invisible and blocks stay together
+        var anchorRun = session.GetBody().Descendants<Run>().First(r => r.InnerText == "\\s1\\");
+        Assert.Equal("FFFFFF", anchorRun.RunProperties?.Color?.Val?.Value);
+        Assert.NotNull(((Paragraph)elements[0]).ParagraphProperties?.KeepNext);
+        Assert.Null(((Paragraph)elements[4]).ParagraphProperties?.KeepNext);
+    }
+
+    [Fact]
+    public void Insert_ColumnsLayoutUsesBorderlessTable()
+    {
+        using var session = DocxSession.Create();
+
+        var (elements, anchors) = SignatureHelper.Insert(session.Document, Signers, "adobe_sign", "columns", null, 0);
+
+        var table = Assert.IsType<Table>(Assert.Single(elements));
+        var cells = table.Elements<TableRow>().Single().Elements<TableCell>().ToList();
+        Assert.Equal(2, cells.Count);
+        Assert.Contains("{{Sig_es_:signer2:signature}}", cells[1].InnerText);
+        Assert.Equal("{{Dte_es_:signer1:date}}", anchors[0].Date);
+        Assert.NotNull(ElementIdManager.GetId(table));
+    }
+}
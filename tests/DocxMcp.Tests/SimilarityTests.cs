@@ -0,0 +1,78 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SimilarityTests
+{
+    private const string Contract =
+        "This agreement is made between the supplier and the customer for the delivery of office furniture. " +
+        "The supplier shall deliver the goods within thirty days of the order and the customer shall pay " +
+        "the invoice within sixty days of delivery. Either party may terminate this agreement with three " +
+        "months written notice. Disputes shall be settled by the courts of the place where the supplier " +
+        "has its registered office.";
+
+    private const string Recipe =
+        "Preheat the oven, whisk the eggs with the sugar until pale, fold in the flour and bake the sponge " +
+        "for twenty five minutes before letting it cool on a rack.";
+
+    [Fact]
+    public void Similarity_IgnoresCaseAndPunctuation()
+    {
+        var a = SimilarityHelper.Signature(Contract);
+        var b = SimilarityHelper.Signature(Contract.ToUpperInvariant().Replace(".", ";"));
+
+        Assert.Equal(1.0, SimilarityHelper.Similarity(a, b));
+    }
+
+    [Fact]
+    public void Similarity_SmallEditStaysHigh_UnrelatedTextIsLow()
+    {
+        var original = SimilarityHelper.Signature(Contract);
+        var edited = SimilarityHelper.Signature(Contract.Replace("thirty days", "fifteen days"));
+        var unrelated = SimilarityHelper.Signature(Recipe);
+
+        Assert.InRange(SimilarityHelper.Similarity(original, edited), 0.7, 0.99);
+        Assert.InRange(SimilarityHelper.Similarity(original, unrelated), 0.0, 0.1);
+    }
+
+    [Fact]
+    public void Signature_EmptyText_IsNeverSimilar()
+    {
+        var empty = SimilarityHelper.Signature("  \n ");
+
+        Assert.Empty(empty);
+        Assert.Equal(0, SimilarityHelper.Similarity(empty, empty));
+        // Texts shorter than a shingle still compare
+        Assert.Equal(1.0, SimilarityHelper.Similarity(
+            SimilarityHelper.Signature("Short note"), SimilarityHelper.Signature("short NOTE")));
+    }
+
+    [Fact]
+    public void FindSimilarDocuments_ReportsNearDuplicatesOnly()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        DocxSession Create(string text)
+        {
+            var session = mgr.Create();
+            session.GetBody().PrependChild(new Paragraph(new Run(new Text(text))));
+            TestHelpers.PersistBaseline(mgr, session);
+            return session;
+        }
+        var draft = Create(Contract);
+        var copy = Create(Contract.Replace("sixty days", "ninety days"));
+        Create(Recipe);
+
+        var json = SimilarityTools.FindSimilarDocuments(mgr, draft.Id, threshold: 0.5);
+        using var result = JsonDocument.Parse(json);
+        var root = result.RootElement;
+
+        Assert.Equal(2, root.GetProperty("scanned").GetInt32());
+        var match = Assert.Single(root.GetProperty("similar").EnumerateArray());
+        Assert.Equal(copy.Id, match.GetProperty("id").GetString());
+        Assert.True(match.GetProperty("similarity").GetDouble() >= 0.5);
+    }
+}
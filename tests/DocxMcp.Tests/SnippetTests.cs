@@ -0,0 +1,88 @@
+using DocumentFormat.OpenXml.Packaging;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SnippetTests
+{
+    private static DocxSession CreateSource()
+    {
+        var session = DocxSession.Create();
+        var mainPart = session.Document.MainDocumentPart!;
+
+        var stylesPart = mainPart.StyleDefinitionsPart ?? mainPart.AddNewPart<StyleDefinitionsPart>();
+        stylesPart.Styles ??= new Styles();
+        stylesPart.Styles.AppendChild(new Style(
+            new StyleName { Val = "Clause" },
+            new BasedOn { Val = "ClauseBase" }) { StyleId = "Clause", Type = StyleValues.Paragraph });
+        stylesPart.Styles.AppendChild(new Style(
+            new StyleName { Val = "Clause Base" }) { StyleId = "ClauseBase", Type = StyleValues.Paragraph });
+
+        var body = session.GetBody();
+        body.PrependChild(new Paragraph(new Run(new Text("Intro"))));
+        body.InsertAfter(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Clause" }),
+            new Run(new Text("Clause one"))), body.GetFirstChild<Paragraph>());
+        body.InsertAfter(new Paragraph(new Run(new Text("Clause two"))),
+            body.Elements<Paragraph>().ElementAt(1));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    private static byte[] ExtractClauses(DocxSession source)
+    {
+        using var copy = DocxSession.FromBytes(source.ToBytes(), "snippet", sourcePath: null);
+        var keep = copy.GetBody().Elements<Paragraph>().Skip(1).Take(2).ToList();
+        SnippetHelper.Extract(copy.Document, keep);
+        return copy.ToBytes();
+    }
+
+    [Fact]
+    public void Extract_KeepsOnlySelectedElements()
+    {
+        using var source = CreateSource();
+        using var snippet = DocxSession.FromBytes(ExtractClauses(source), "snippet", sourcePath: null);
+
+        var texts = snippet.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToList();
+        Assert.Equal(["Clause one", "Clause two"], texts);
+    }
+
+    [Fact]
+    public void Import_CopiesMissingStylesWithBaseStyles()
+    {
+        using var source = CreateSource();
+        using var snippet = DocxSession.FromBytes(ExtractClauses(source), "snippet", sourcePath: null);
+        using var target = DocxSession.Create();
+
+        var elements = SnippetHelper.Import(snippet.Document, target.Document);
+        foreach (var element in elements)
+            target.GetBody().PrependChild(element);
+
+        var styleIds = target.Document.MainDocumentPart!.StyleDefinitionsPart!.Styles!
+            .Elements<Style>().Select(s => s.StyleId?.Value).ToList();
+        Assert.Contains("Clause", styleIds);
+        Assert.Contains("ClauseBase", styleIds);
+    }
+
+    [Fact]
+    public void ReassignIds_AvoidsTargetIds()
+    {
+        using var source = CreateSource();
+        using var target = DocxSession.FromBytes(source.ToBytes(), "target", sourcePath: null);
+        using var snippet = DocxSession.FromBytes(ExtractClauses(source), "snippet", sourcePath: null);
+
+        var targetIds = target.GetBody().Descendants<Paragraph>()
+            .Select(p => ElementIdManager.GetId(p)).ToHashSet();
+
+        ElementIdManager.ReassignIds(snippet.GetBody(), target.Document);
+
+        foreach (var p in snippet.GetBody().Descendants<Paragraph>())
+        {
+            var id = ElementIdManager.GetId(p);
+            Assert.NotNull(id);
+            Assert.DoesNotContain(id, targetIds);
+        }
+    }
+}
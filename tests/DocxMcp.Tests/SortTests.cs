@@ -0,0 +1,61 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SortTests
+{
+    private static Table CreateTable(params string[][] rows) =>
+        new(rows.Select(cells => new TableRow(cells.Select(text =>
+            new TableCell(new Paragraph(new Run(new Text(text))))))));
+
+    private static string[] Column(Table table, int column) =>
+        table.Elements<TableRow>().Select(r => r.Elements<TableCell>().ElementAt(column).InnerText).ToArray();
+
+    private static Paragraph Item(string text, int level = 0) =>
+        new(new ParagraphProperties(new NumberingProperties(
+                new NumberingLevelReference { Val = level }, new NumberingId { Val = 1 })),
+            new Run(new Text(text)));
+
+    [Fact]
+    public void SortTable_SortsNumbersByValueAndKeepsHeader()
+    {
+        var table = CreateTable(
+            ["Item", "Amount"], ["Pens", "$1,200"], ["Ink", "90"], ["Paper", ""], ["Desk", "(15)"]);
+
+        var result = SortHelper.SortTable(table, "Amount", descending: true, hasHeader: true);
+
+        Assert.Equal("number", result.KeyType);
+        Assert.Equal(["Item", "Pens", "Ink", "Desk", "Paper"], Column(table, 0));
+    }
+
+    [Fact]
+    public void SortTable_DedupesRowsAndKeys()
+    {
+        var table = CreateTable(["b", "1"], ["a", "2"], ["b", "1"], ["a", "3"]);
+
+        var rows = SortHelper.SortTable((Table)table.CloneNode(true), "0", false, false, "rows");
+        Assert.Single(rows.Removed);
+
+        SortHelper.SortTable(table, "0", false, false, "key");
+        Assert.Equal(["a", "b"], Column(table, 0));
+        Assert.Equal(["2", "1"], Column(table, 1));
+    }
+
+    [Fact]
+    public void SortList_MovesNestedItemsWithTheirParent()
+    {
+        var body = new Body(
+            new Paragraph(new Run(new Text("Intro"))),
+            Item("Pears"), Item("ripe", 1), Item("Apples"), Item("Pears"), Item("ripe", 1),
+            new Paragraph(new Run(new Text("Outro"))));
+
+        var list = SortHelper.ListAround(body.Elements<Paragraph>().ElementAt(3));
+        var result = SortHelper.SortList(list, descending: false, dedupe: true);
+
+        Assert.Single(result.Removed);
+        Assert.Equal(["Intro", "Apples", "Pears", "ripe", "Outro"],
+            body.Elements<Paragraph>().Select(p => p.InnerText).ToArray());
+    }
+}
@@ -0,0 +1,82 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class StatisticsTests
+{
+    private static string AddParagraphPatch(string text) =>
+        $"[{{\"op\":\"add\",\"path\":\"/body/children/0\",\"value\":{{\"type\":\"paragraph\",\"text\":\"{text}\"}}}}]";
+
+    [Fact]
+    public void Compute_CountsWordsParagraphsAndTables()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new Run(new Text("Scope"))));
+        body.AppendChild(new Paragraph(new Run(new Text("The quick brown fox."))));
+        body.AppendChild(new Paragraph());
+        body.AppendChild(new Table(new TableRow(new TableCell(new Paragraph(new Run(new Text("two words")))))));
+
+        var stats = StatisticsHelper.Compute(session.Document);
+
+        Assert.Equal(7, stats.Words);
+        Assert.Equal(3, stats.Paragraphs);
+        Assert.Equal(1, stats.Headings);
+        Assert.Equal(1, stats.Tables);
+        Assert.Equal(1, stats.Pages);
+    }
+
+    [Fact]
+    public void ComputeBySection_SplitsAtTopLevelHeadings()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        Paragraph Heading(string style, string text) => new(
+            new ParagraphProperties(new ParagraphStyleId { Val = style }),
+            new Run(new Text(text)));
+        body.AppendChild(new Paragraph(new Run(new Text("Draft notice"))));
+        body.AppendChild(Heading("Heading2", "Scope"));
+        body.AppendChild(Heading("Heading3", "In scope"));
+        body.AppendChild(new Paragraph(new Run(new Text("Everything listed below."))));
+        body.AppendChild(new Table(new TableRow(new TableCell(new Paragraph(new Run(new Text("cell")))))));
+        body.AppendChild(Heading("Heading2", "Terms"));
+        ElementIdManager.EnsureAllIds(session.Document);
+
+        var sections = StatisticsHelper.ComputeBySection(session.Document);
+
+        Assert.Equal(3, sections.Count);
+        Assert.Null(sections[0].Heading);
+        Assert.Equal(2, sections[0].Words);
+        Assert.Equal("Scope", sections[1].Heading);
+        Assert.Equal(2, sections[1].Level);
+        Assert.Equal(1, sections[1].Subheadings);
+        Assert.Equal(1, sections[1].Tables);
+        Assert.Equal(7, sections[1].Words);
+        Assert.Equal(ElementIdManager.GetId(body.Elements<Paragraph>().ElementAt(1)), sections[1].FirstId);
+        Assert.Equal(ElementIdManager.GetId(body.Elements<Table>().Single()), sections[1].LastId);
+        Assert.Equal(1, sections[2].Words);
+        Assert.Equal(sections[2].FirstId, sections[2].LastId);
+    }
+
+    [Fact]
+    public void GetAtPosition_RebuildsWithoutMovingTheCursor()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var id = mgr.Create().Id;
+        PatchTool.ApplyPatch(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), id, AddParagraphPatch("One two"));
+        PatchTool.ApplyPatch(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), id, AddParagraphPatch("three"));
+
+        using (var baseline = mgr.GetAtPosition(id, 0))
+            Assert.Equal(0, StatisticsHelper.Compute(baseline.Document).Words);
+        using (var first = mgr.GetAtPosition(id, 1))
+            Assert.Equal(2, StatisticsHelper.Compute(first.Document).Words);
+
+        Assert.Equal(2, mgr.GetHistory(id).CursorPosition);
+        Assert.Throws<ArgumentException>(() => mgr.GetAtPosition(id, 5));
+    }
+}
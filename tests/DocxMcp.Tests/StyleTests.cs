@@ -106,6 +106,53 @@ public class StyleTests
         Assert.Equal("Arial", run.RunProperties?.RunFonts?.Ascii?.Value);
     }
 
+    [Fact]
+    public void StyleElement_FontNameCoversLatinAndEastAsianText()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        // A theme font would win over an explicit one
+        session.GetBody().AppendChild(new Paragraph(new Run(
+            new RunProperties(new RunFonts { AsciiTheme = ThemeFontValues.MinorHighAnsi }),
+            new Text("test"))));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        StyleTools.StyleElement(mgr, CreateSyncManager(), id, "{\"font_name\":\"Garamond\",\"font_name_east_asia\":\"MS Mincho\"}");
+
+        var xml = mgr.Get(id).GetBody().Descendants<Run>().First().RunProperties!.RunFonts!.OuterXml;
+        Assert.Contains("w:ascii=\"Garamond\"", xml);
+        Assert.Contains("w:hAnsi=\"Garamond\"", xml);
+        Assert.Contains("w:eastAsia=\"MS Mincho\"", xml);
+        Assert.DoesNotContain("asciiTheme", xml);
+
+        // Removing the Latin font keeps the East Asian one
+        StyleTools.StyleElement(mgr, CreateSyncManager(), id, "{\"font_name\":null}");
+        var fonts = mgr.Get(id).GetBody().Descendants<Run>().First().RunProperties!.RunFonts!;
+        Assert.Null(fonts.Ascii);
+        Assert.Equal("MS Mincho", fonts.EastAsia!.Value);
+    }
+
+    [Fact]
+    public void AddParagraph_FontNameInPropertiesAppliesToRuns()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id,
+            "[{\"op\":\"add\",\"path\":\"/body/children/0\",\"value\":{\"type\":\"paragraph\"," +
+            "\"properties\":{\"font_name\":\"Garamond\"}," +
+            "\"runs\":[{\"text\":\"a\"},{\"text\":\"b\",\"style\":{\"font_name\":\"Arial\"}}]}}]");
+
+        var paragraph = mgr.Get(id).GetBody().Descendants<Paragraph>().First();
+        var runs = paragraph.Elements<Run>().ToList();
+        Assert.Equal("Garamond", runs[0].RunProperties?.RunFonts?.HighAnsi?.Value);
+        Assert.Equal("Arial", runs[1].RunProperties?.RunFonts?.Ascii?.Value);
+        Assert.Equal("Garamond", paragraph.ParagraphProperties?.ParagraphMarkRunProperties?.GetFirstChild<RunFonts>()?.Ascii?.Value);
+    }
+
     [Fact]
     public void StyleElement_SetHighlight()
     {
@@ -152,6 +199,81 @@ public class StyleTests
         Assert.NotNull(run.RunProperties?.Strike);
     }
 
+    [Fact]
+    public void StyleElement_SmallCapsAndLetterSpacing()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddParagraphPatch("test"));
+
+        StyleTools.StyleElement(mgr, CreateSyncManager(), id, "{\"small_caps\":true,\"letter_spacing\":1.5}");
+
+        var run = mgr.Get(id).GetBody().Descendants<Run>().First();
+        Assert.NotNull(run.RunProperties?.SmallCaps);
+        Assert.Equal(30, run.RunProperties?.Spacing?.Val?.Value);
+
+        StyleTools.StyleElement(mgr, CreateSyncManager(), id, "{\"small_caps\":false,\"letter_spacing\":null}");
+
+        run = mgr.Get(id).GetBody().Descendants<Run>().First();
+        Assert.Null(run.RunProperties?.SmallCaps);
+        Assert.Null(run.RunProperties?.Spacing);
+    }
+
+    [Fact]
+    public void StyleElement_UnknownHighlight_ReturnsError()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddParagraphPatch("test"));
+
+        var result = StyleTools.StyleElement(mgr, CreateSyncManager(), id, "{\"highlight\":\"orange\"}");
+
+        Assert.StartsWith("Error: Unknown highlight 'orange'", result);
+        Assert.Null(mgr.Get(id).GetBody().Descendants<Run>().First().RunProperties?.Highlight);
+    }
+
+    [Fact]
+    public void StyleElement_ListsIgnoredProperties()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddParagraphPatch("test"));
+
+        var result = StyleTools.StyleElement(mgr, CreateSyncManager(), id,
+            "{\"highlight\":\"none\",\"vertical_align\":\"subscript\",\"alignment\":\"center\"}");
+
+        Assert.Equal("Styled 1 run(s). Ignored unsupported properties: alignment.", result);
+        var run = mgr.Get(id).GetBody().Descendants<Run>().First();
+        Assert.Equal(VerticalPositionValues.Subscript, run.RunProperties?.VerticalTextAlignment?.Val?.Value);
+    }
+
+    [Fact]
+    public void AddParagraph_NewRunPropertiesShowInQuery()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddStyledParagraphPatch("test",
+            "{\"highlight\":\"dark_blue\",\"vertical_align\":\"superscript\",\"small_caps\":true,\"letter_spacing\":-0.5}"));
+
+        var rp = mgr.Get(id).GetBody().Descendants<Run>().First().RunProperties!;
+        Assert.Equal(HighlightColorValues.DarkBlue, rp.Highlight?.Val?.Value);
+        Assert.Equal(-10, rp.Spacing?.Val?.Value);
+
+        var json = QueryTool.Query(mgr, id, "/body/paragraph[0]/run[0]", "json");
+        Assert.Contains("\"highlight\": \"dark_blue\"", json);
+        Assert.Contains("\"vertical_align\": \"superscript\"", json);
+        Assert.Contains("\"small_caps\": true", json);
+        Assert.Contains("\"letter_spacing\": -0.5", json);
+    }
+
     // =========================
     // Paragraph merge tests
     // =========================
@@ -246,6 +368,63 @@ public class StyleTests
         Assert.Equal("360", para.ParagraphProperties?.Indentation?.FirstLine?.Value);
     }
 
+    [Fact]
+    public void StyleParagraph_JustifyAndDistribute()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddParagraphPatch("test"));
+
+        // "both" is what query reports for justified paragraphs, so it must round-trip
+        StyleTools.StyleParagraph(mgr, CreateSyncManager(), id, "{\"alignment\":\"both\"}", "/body/paragraph[0]");
+        var para = mgr.Get(id).GetBody().Descendants<Paragraph>().First();
+        Assert.Equal(JustificationValues.Both, para.ParagraphProperties?.Justification?.Val?.Value);
+
+        StyleTools.StyleParagraph(mgr, CreateSyncManager(), id, "{\"alignment\":\"distribute\"}", "/body/paragraph[0]");
+        para = mgr.Get(id).GetBody().Descendants<Paragraph>().First();
+        Assert.Equal(JustificationValues.Distribute, para.ParagraphProperties?.Justification?.Val?.Value);
+    }
+
+    [Fact]
+    public void StyleParagraph_LineSpacingRule()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id, AddParagraphPatch("test"));
+
+        StyleTools.StyleParagraph(mgr, CreateSyncManager(), id,
+            "{\"line_spacing\":300,\"line_spacing_rule\":\"exact\"}", "/body/paragraph[0]");
+        var spacing = mgr.Get(id).GetBody().Descendants<Paragraph>().First().ParagraphProperties?.SpacingBetweenLines;
+        Assert.Equal("300", spacing?.Line?.Value);
+        Assert.Equal(LineSpacingRuleValues.Exact, spacing?.LineRule?.Value);
+
+        // Removing the line value drops the rule with it
+        StyleTools.StyleParagraph(mgr, CreateSyncManager(), id, "{\"line_spacing\":null}", "/body/paragraph[0]");
+        Assert.Null(mgr.Get(id).GetBody().Descendants<Paragraph>().First().ParagraphProperties?.SpacingBetweenLines);
+    }
+
+    [Fact]
+    public void AddParagraph_AppliesAlignmentSpacingAndIndent()
+    {
+        var mgr = CreateManager();
+        var session = mgr.Create();
+        var id = session.Id;
+
+        PatchTool.ApplyPatch(mgr, CreateSyncManager(), CreateGate(), id,
+            "[{\"op\":\"add\",\"path\":\"/body/children/0\",\"value\":{\"type\":\"paragraph\",\"text\":\"t\"," +
+            "\"properties\":{\"alignment\":\"justify\",\"line_spacing\":360,\"line_spacing_rule\":\"auto\",\"indent_hanging\":360}}}]");
+
+        var props = mgr.Get(id).GetBody().Descendants<Paragraph>().First().ParagraphProperties;
+        Assert.Equal(JustificationValues.Both, props?.Justification?.Val?.Value);
+        Assert.Equal("360", props?.SpacingBetweenLines?.Line?.Value);
+        Assert.Equal(LineSpacingRuleValues.Auto, props?.SpacingBetweenLines?.LineRule?.Value);
+        Assert.Equal("360", props?.Indentation?.Hanging?.Value);
+    }
+
     // =========================
     // Table merge tests
     // =========================
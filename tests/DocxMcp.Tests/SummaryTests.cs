@@ -0,0 +1,68 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class SummaryTests
+{
+    private static DocxSession CreateDoc()
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.PrependChild(new Paragraph(new Run(new Text("Body text."))));
+        body.PrependChild(new Paragraph(new Run(new Text("Old summary."))));
+        body.PrependChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new Run(new Text("Abstract"))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Chunk_SplitsBetweenParagraphsAndMarksHeadings()
+    {
+        using var session = CreateDoc();
+
+        var single = SummaryHelper.Chunk(session.Document, 1000);
+        Assert.Equal(["# Abstract\nOld summary.\nBody text.".ReplaceLineEndings()], single.Select(c => c.ReplaceLineEndings()));
+
+        var split = SummaryHelper.Chunk(session.Document, 15);
+        Assert.Equal(3, split.Count);
+        Assert.All(split, c => Assert.True(c.Length <= 15));
+    }
+
+    [Fact]
+    public void WriteSection_ReplacesContentUnderExistingHeading()
+    {
+        using var session = CreateDoc();
+
+        SummaryHelper.WriteSection(session.Document, "abstract", "First.\n\nSecond.");
+
+        var texts = session.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToList();
+        Assert.Equal(["Abstract", "First.", "Second."], texts);
+    }
+
+    [Fact]
+    public void WriteSection_CreatesMissingHeading()
+    {
+        using var session = CreateDoc();
+
+        var added = SummaryHelper.WriteSection(session.Document, "Summary", "Short.");
+
+        var texts = session.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToList();
+        Assert.Equal(["Summary", "Short.", "Abstract", "Old summary.", "Body text."], texts);
+        Assert.NotNull(ElementIdManager.GetId(Assert.Single(added)));
+    }
+
+    [Fact]
+    public void WriteProperty_SetsCoreProperty()
+    {
+        using var session = CreateDoc();
+
+        SummaryHelper.WriteProperty(session.Document, "Description", "A summary.");
+        Assert.Equal("A summary.", session.Document.PackageProperties.Description);
+
+        Assert.Throws<ArgumentException>(() => SummaryHelper.WriteProperty(session.Document, "author", "x"));
+    }
+}
@@ -0,0 +1,97 @@
+using System.Text.Json.Nodes;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TableExportTests
+{
+    private static TableCell Cell(string text, int span = 1, MergedCellValues? vMerge = null)
+    {
+        var props = new TableCellProperties();
+        if (span > 1)
+            props.Append(new GridSpan { Val = span });
+        if (vMerge is { } merge)
+            props.Append(new VerticalMerge { Val = merge });
+        return new TableCell(props, new Paragraph(new Run(new Text(text))));
+    }
+
+    // | Region      | Q1 | Q2 |
+    // | North (2 rows) | 1  | 2  |
+    // |             | 3  | 4  |
+    // | Total (2 cols)  | 10 |
+    private static Table Sales() => new(
+        new TableRow(Cell("Region"), Cell("Q1"), Cell("Q2")),
+        new TableRow(Cell("North", vMerge: MergedCellValues.Restart), Cell("1"), Cell("2")),
+        new TableRow(Cell("", vMerge: MergedCellValues.Continue), Cell("3"), Cell("4")),
+        new TableRow(Cell("Total", span: 2), Cell("10")));
+
+    [Fact]
+    public void ToGrid_RepeatsMergedCells()
+    {
+        var grid = TableExportHelper.ToGrid(Sales());
+
+        Assert.Equal(
+            [
+                ["Region", "Q1", "Q2"],
+                ["North", "1", "2"],
+                ["North", "3", "4"],
+                ["Total", "Total", "10"],
+            ],
+            grid);
+    }
+
+    [Fact]
+    public void ToGrid_BlankMode_KeepsOnlyTheTopLeftCell()
+    {
+        var grid = TableExportHelper.ToGrid(Sales(), "blank");
+
+        Assert.Equal(["", "3", "4"], grid[2]);
+        Assert.Equal(["Total", "", "10"], grid[3]);
+    }
+
+    [Fact]
+    public void ToCsv_QuotesSpecialCharacters()
+    {
+        var csv = TableExportHelper.ToCsv([["a,b", "say \"hi\""], ["two\nlines", "plain"]]);
+
+        Assert.Equal("\"a,b\",\"say \"\"hi\"\"\"\r\n\"two\nlines\",plain\r\n", csv);
+    }
+
+    [Fact]
+    public void FromCsv_ReadsBackWhatToCsvWrites()
+    {
+        List<List<string>> grid = [["a,b", "say \"hi\""], ["two\nlines", ""], ["plain", "x"]];
+
+        Assert.Equal(grid, TableExportHelper.FromCsv(TableExportHelper.ToCsv(grid)));
+        List<List<string>> unquoted = [["a", "b"], ["c", "d"]];
+        Assert.Equal(unquoted, TableExportHelper.FromCsv("a,b\nc,d"));
+        Assert.Throws<FormatException>(() => TableExportHelper.FromCsv("\"open,field"));
+    }
+
+    [Fact]
+    public void ToJson_MakesColumnNamesUnique()
+    {
+        var json = TableExportHelper.ToJson([["Name", "", "Name"], ["x", "y", "z"]], header: true);
+
+        var row = Assert.Single(json)!.AsObject();
+        Assert.Equal(["Name", "column_2", "Name_2"], row.Select(p => p.Key));
+    }
+
+    [Fact]
+    public void ExportTable_ReturnsJsonRows()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().Append(new Paragraph(), Sales());
+
+        var rows = JsonNode.Parse(ExportTools.ExportTable(mgr, session.Id, -1, "json"))!.AsArray();
+
+        Assert.Equal(3, rows.Count);
+        Assert.Equal("North", rows[1]!["Region"]!.GetValue<string>());
+        Assert.Equal("10", rows[2]!["Q2"]!.GetValue<string>());
+        Assert.StartsWith("Error:", ExportTools.ExportTable(mgr, session.Id, 1));
+    }
+}
@@ -366,6 +366,22 @@ public class TableModificationTests : IDisposable
         Assert.Equal("Col1", cells[0].InnerText);
     }
 
+    [Fact]
+    public void CreateHeaderRow_BoldsPlainCells()
+    {
+        var mainPart = _session.Document.MainDocumentPart!;
+        var value = JsonDocument.Parse("""
+        {"type": "row", "is_header": true, "height": 400, "cells": ["Name", {"text": "Age"}]}
+        """).RootElement;
+
+        var row = Assert.IsType<TableRow>(ElementFactory.CreateFromJson(value, mainPart));
+
+        Assert.NotNull(row.TableRowProperties?.GetFirstChild<TableHeader>());
+        Assert.NotNull(row.TableRowProperties?.GetFirstChild<TableRowHeight>());
+        Assert.All(row.Descendants<Run>(), r => Assert.NotNull(r.RunProperties?.Bold));
+        Assert.Empty(row.Descendants<ParagraphStyleId>());
+    }
+
     [Fact]
     public void CreateCellAsTopLevelType()
     {
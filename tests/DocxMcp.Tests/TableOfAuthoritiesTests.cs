@@ -0,0 +1,119 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TableOfAuthoritiesTests
+{
+    private const string Brown = "Brown v. Board of Education, 347 U.S. 483 (1954)";
+
+    private static (SessionManager Mgr, string Id) CreateBrief()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text($"As held in {Brown}, separate is not equal."))));
+        body.AppendChild(new Paragraph(new Run(new Text("See Brown, 347 U.S. at 495."))));
+        body.AppendChild(new Paragraph(new Run(new Text("The claim arises under 42 U.S.C. § 1983."))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        TestHelpers.PersistBaseline(mgr, session);
+        return (mgr, session.Id);
+    }
+
+    private static void MarkAll(SessionManager mgr, string id)
+    {
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+        Assert.StartsWith("Citation marked", AuthorityTools.MarkCitation(mgr, sync, gate, id,
+            "/body/paragraph[0]", Brown, "Cases", short_citation: "Brown"));
+        Assert.StartsWith("Citation marked", AuthorityTools.MarkCitation(mgr, sync, gate, id,
+            "/body/paragraph[1]", "Brown, 347 U.S. at 495", "cases", short_citation: "Brown"));
+        Assert.StartsWith("Citation marked", AuthorityTools.MarkCitation(mgr, sync, gate, id,
+            "/body/paragraph[2]", "42 U.S.C. § 1983", "2"));
+    }
+
+    private static string Visible(Paragraph paragraph) =>
+        string.Concat(paragraph.Descendants().Select(e => e switch
+        {
+            Text t => t.Text,
+            TabChar => "\t",
+            _ => ""
+        }));
+
+    [Fact]
+    public void MarkCitation_LaterMarksReferToTheFirst()
+    {
+        var (mgr, id) = CreateBrief();
+
+        MarkAll(mgr, id);
+
+        var body = mgr.Get(id).GetBody();
+        Assert.Equal(
+            [
+                $" TA \\l \"{Brown}\" \\s \"Brown\" \\c 1 ",
+                " TA \\s \"Brown\" ",
+                " TA \\l \"42 U.S.C. § 1983\" \\s \"42 U.S.C. § 1983\" \\c 2 "
+            ],
+            body.Descendants<FieldCode>().Select(f => f.Text));
+        // The field follows the cited text, which is left as it was
+        Assert.Equal("See Brown, 347 U.S. at 495.", Visible(body.Elements<Paragraph>().ElementAt(1)));
+
+        var authorities = AuthorityHelper.Collect(body);
+        Assert.Equal([Brown, "42 U.S.C. § 1983"], authorities.Select(a => a.LongCitation));
+        Assert.Equal([2, 1], authorities.Select(a => a.Citing.Count));
+        Assert.Equal([1, 2], authorities.Select(a => a.Category));
+    }
+
+    [Fact]
+    public void InsertTableOfAuthorities_WritesStaticEntriesAndReplays()
+    {
+        var (mgr, id) = CreateBrief();
+        MarkAll(mgr, id);
+
+        var json = AuthorityTools.InsertTableOfAuthorities(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id);
+        Assert.Contains("\"pages_estimated\": true", json);
+
+        string[] expected = ["Cases", $"{Brown}\t1", "Statutes", "42 U.S.C. § 1983\t1"];
+        var session = mgr.Get(id);
+        var body = session.GetBody();
+        Assert.Equal(expected, body.Elements<Paragraph>().Take(4).Select(Visible));
+        Assert.Equal(
+            [" TOA \\h \\c \"1\" \\p ", " TOA \\h \\c \"2\" \\p "],
+            body.Descendants<FieldCode>().Where(f => f.Text.Contains("TOA")).Select(f => f.Text));
+        Assert.Equal(AuthorityHelper.EntryStyle,
+            body.Elements<Paragraph>().ElementAt(1).ParagraphProperties!.ParagraphStyleId!.Val!.Value);
+        Assert.NotNull(session.Document.MainDocumentPart!.DocumentSettingsPart!.Settings
+            .GetFirstChild<UpdateFieldsOnOpen>());
+
+        mgr.Undo(id);
+        Assert.DoesNotContain(mgr.Get(id).GetBody().Descendants<FieldCode>(), f => f.Text.Contains("TOA"));
+
+        mgr.Redo(id);
+        Assert.Equal(expected, mgr.Get(id).GetBody().Elements<Paragraph>().Take(4).Select(Visible));
+    }
+
+    [Fact]
+    public void InsertTableOfAuthorities_NeedsMarkedCitations()
+    {
+        var (mgr, id) = CreateBrief();
+
+        var result = AuthorityTools.InsertTableOfAuthorities(mgr, TestHelpers.CreateSyncManager(),
+            TestHelpers.CreateExternalChangeGate(), id);
+
+        Assert.StartsWith("Error: No citations marked", result);
+    }
+
+    [Fact]
+    public void ParseCategory_AcceptsNamesAndNumbers()
+    {
+        Assert.Equal(1, AuthorityHelper.ParseCategory("Cases"));
+        Assert.Equal(7, AuthorityHelper.ParseCategory("constitutional provisions"));
+        Assert.Equal(12, AuthorityHelper.ParseCategory("12"));
+        Assert.Equal("Category 12", AuthorityHelper.CategoryName(12));
+        Assert.Throws<ArgumentException>(() => AuthorityHelper.ParseCategory("17"));
+        Assert.Throws<ArgumentException>(() => AuthorityHelper.ParseCategory("Briefs"));
+    }
+}
@@ -0,0 +1,96 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+using W14 = DocumentFormat.OpenXml.Office2010.Word;
+
+namespace DocxMcp.Tests;
+
+public class TaskExtractionTests
+{
+    private static DocxSession CreateNotes(SessionManager mgr)
+    {
+        var session = mgr.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(
+            new ParagraphProperties(new ParagraphStyleId { Val = "Heading1" }),
+            new Run(new Text("Action items"))));
+        body.AppendChild(new Paragraph(new Run(new Text("[ ] @alice send the deck by 2026-03-05"))));
+        body.AppendChild(new Paragraph(new Run(new Text("☑ Book the room"))));
+        body.AppendChild(new Paragraph(new Run(new Text(
+            "Budget approved. TODO: update the roadmap; Action: Bob Stone will call the vendor due March 5, 2026"))));
+        body.AppendChild(new Paragraph(
+            new SdtRun(
+                new SdtProperties(new W14.SdtContentCheckBox(
+                    new W14.Checked { Val = W14.OnOffValues.One })),
+                new SdtContentRun(new Run(new Text("☒")))),
+            new Run(new Text(" Sign the contract (owner: Carol)"))));
+        body.AppendChild(new Paragraph(new Run(new Text("The team discussed next steps."))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Extract_FindsCheckboxesAndMarkers()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = CreateNotes(mgr);
+
+        var tasks = TaskHelper.Extract(session.Document);
+
+        Assert.Equal(
+            [
+                "@alice send the deck by 2026-03-05",
+                "Book the room",
+                "update the roadmap",
+                "Bob Stone will call the vendor due March 5, 2026",
+                "Sign the contract (owner: Carol)"
+            ],
+            tasks.Select(t => t.Text));
+        Assert.Equal(["checkbox", "checkbox", "todo", "action", "checkbox"], tasks.Select(t => t.Source));
+        Assert.Equal([false, true, null, null, true], tasks.Select(t => t.Done));
+        Assert.Equal(["alice", null, null, "Bob Stone", "Carol"], tasks.Select(t => t.Assignee));
+        Assert.Equal(["2026-03-05", null, null, "2026-03-05", null], tasks.Select(t => t.DueDate));
+        Assert.Equal("March 5, 2026", tasks[3].Due);
+        Assert.All(tasks, t => Assert.Equal("Action items", t.Section));
+    }
+
+    [Fact]
+    public void Extract_RangesCoverTheTaskText()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = CreateNotes(mgr);
+        var body = session.GetBody();
+
+        foreach (var task in TaskHelper.Extract(session.Document))
+        {
+            var paragraph = body.Descendants<Paragraph>()
+                .Single(p => TerminologyHelper.PathOf(p, body) == task.Path);
+            Assert.Equal(task.Text, TerminologyHelper.RunText(paragraph).Substring(task.Offset, task.Length));
+        }
+    }
+
+    [Fact]
+    public void ExtractTasks_OpenOnlySkipsCheckedItems()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = CreateNotes(mgr);
+
+        var json = TaskTools.ExtractTasks(mgr, session.Id, open_only: true);
+
+        Assert.Contains("\"total\": 3", json);
+        Assert.DoesNotContain("Book the room", json);
+        Assert.Contains("\"due_date\": \"2026-03-05\"", json);
+        Assert.Contains("\"path\": \"/body/paragraph[id=", json);
+    }
+
+    [Theory]
+    [InlineData("March 5, 2026", "2026-03-05")]
+    [InlineData("5th Mar 2026", "2026-03-05")]
+    [InlineData("03/05/2026", null)]
+    [InlineData("Friday", null)]
+    public void ToIsoDate_OnlyResolvesUnambiguousDates(string due, string? expected)
+    {
+        Assert.Equal(expected, TaskHelper.ToIsoDate(due));
+    }
+}
@@ -0,0 +1,191 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TemplateLintTests
+{
+    private static Paragraph Para(params string[] runs)
+    {
+        var paragraph = new Paragraph();
+        foreach (var text in runs)
+            paragraph.Append(new Run(new Text(text) { Space = SpaceProcessingModeValues.Preserve }));
+        return paragraph;
+    }
+
+    private static Run CharRun(FieldCharValues type) => new(new FieldChar { FieldCharType = type });
+
+    [Fact]
+    public void Lint_CleanTemplate_HasNoIssues()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().AppendChild(Para("Dear {{first_name}} {{last_name}},"));
+
+        var (issues, fields) = TemplateLintHelper.Lint(session.Document);
+
+        Assert.Empty(issues);
+        Assert.Equal(["first_name", "last_name"], fields.Select(f => f.Name));
+    }
+
+    [Fact]
+    public void Lint_UnclosedAndStrayBraces_AreErrorsWithRanges()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().AppendChild(Para("Total: {{amount and {{currency}}, then }}"));
+        ElementIdManager.EnsureAllIds(session.Document);
+
+        var (issues, _) = TemplateLintHelper.Lint(session.Document);
+
+        var unclosed = Assert.Single(issues, i => i.Kind == "unclosed_placeholder");
+        Assert.Equal("error", unclosed.Severity);
+        Assert.Equal(7, unclosed.Offset);
+        Assert.Equal("{{amount and ", unclosed.Text);
+        Assert.StartsWith("/body/paragraph[id='", unclosed.Path);
+
+        var stray = Assert.Single(issues, i => i.Kind == "stray_close");
+        Assert.Equal(39, stray.Offset);
+        Assert.Equal(2, stray.Length);
+    }
+
+    [Fact]
+    public void Lint_PlaceholderSplitAcrossRuns_IsWarned()
+    {
+        using var session = DocxSession.Create();
+        session.GetBody().AppendChild(Para("Hello {{cust", "omer}}!"));
+
+        var (issues, fields) = TemplateLintHelper.Lint(session.Document);
+
+        var split = Assert.Single(issues);
+        Assert.Equal("split_placeholder", split.Kind);
+        Assert.Equal("warning", split.Severity);
+        Assert.Equal("customer", split.Field);
+        Assert.Equal(6, split.Offset);
+        Assert.Equal("{{customer}}", split.Text);
+        Assert.Equal("customer", Assert.Single(fields).Name);
+    }
+
+    [Fact]
+    public void Lint_DuplicateAndCaseConflictingNames()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(Para("{{name}} and {{ name }}"));
+        body.AppendChild(Para("{{Name}} and {{full name}} and {{}}"));
+
+        var (issues, fields) = TemplateLintHelper.Lint(session.Document);
+
+        var duplicate = Assert.Single(issues, i => i.Kind == "duplicate_field");
+        Assert.Equal("info", duplicate.Severity);
+        Assert.Equal("name", duplicate.Field);
+        Assert.Equal("{{ name }}", duplicate.Text);
+
+        var conflict = Assert.Single(issues, i => i.Kind == "conflicting_case");
+        Assert.Equal("Name", conflict.Field);
+
+        Assert.Equal("full name", Assert.Single(issues, i => i.Kind == "invalid_name").Field);
+        Assert.Single(issues, i => i.Kind == "empty_placeholder");
+        Assert.Equal(2, fields.Single(f => f.Name == "name").Count);
+    }
+
+    [Fact]
+    public void Lint_MergeFields_CountedAndCheckedForStructure()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new SimpleField(new Run(new Text("«City»"))) { Instruction = " MERGEFIELD City " }));
+        body.AppendChild(new Paragraph(
+            CharRun(FieldCharValues.Begin),
+            new Run(new FieldCode(" MERGEFIELD ")),
+            new Run(new FieldCode("\"Zip Code\" \\* MERGEFORMAT ")),
+            CharRun(FieldCharValues.Separate),
+            new Run(new Text("«Zip»")),
+            CharRun(FieldCharValues.End)));
+        body.AppendChild(new Paragraph(
+            CharRun(FieldCharValues.Begin),
+            new Run(new FieldCode(" MERGEFIELD \\* MERGEFORMAT ")),
+            CharRun(FieldCharValues.End),
+            CharRun(FieldCharValues.End)));
+        body.AppendChild(new Paragraph(
+            CharRun(FieldCharValues.Begin),
+            new Run(new FieldCode(" MERGEFIELD Country "))));
+
+        var (issues, fields) = TemplateLintHelper.Lint(session.Document);
+
+        Assert.Equal(["City", "Zip Code"], fields.Select(f => f.Name));
+        Assert.Single(issues, i => i.Kind == "empty_merge_field");
+        Assert.Single(issues, i => i.Kind == "stray_field_end");
+        var unclosed = Assert.Single(issues, i => i.Kind == "unclosed_field");
+        Assert.Equal("Country", unclosed.Field);
+        Assert.All(issues, i => Assert.Equal("error", i.Severity));
+    }
+
+    [Fact]
+    public void Lint_FieldSpanningParagraphs_IsNotAnIssue()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(
+            CharRun(FieldCharValues.Begin),
+            new Run(new FieldCode(" TOC \\o \"1-3\" ")),
+            CharRun(FieldCharValues.Separate),
+            new Run(new Text("Introduction"))));
+        body.AppendChild(new Paragraph(new Run(new Text("Terms")), CharRun(FieldCharValues.End)));
+
+        var (issues, _) = TemplateLintHelper.Lint(session.Document);
+
+        Assert.Empty(issues);
+    }
+
+    [Fact]
+    public void LintTemplate_Tool_ReportsValidityAndFields()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().AppendChild(Para("Dear {{name}}, see {{date"));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        var json = TemplateTools.LintTemplate(mgr, session.Id);
+        using var result = JsonDocument.Parse(json);
+        var root = result.RootElement;
+
+        Assert.False(root.GetProperty("valid").GetBoolean());
+        Assert.Equal(1, root.GetProperty("errors").GetInt32());
+        var issue = root.GetProperty("issues")[0];
+        Assert.Equal("unclosed_placeholder", issue.GetProperty("kind").GetString());
+        Assert.Equal("body", issue.GetProperty("part").GetString());
+        Assert.Equal("{{date", issue.GetProperty("text").GetString());
+        Assert.Equal("name", root.GetProperty("fields")[0].GetProperty("name").GetString());
+    }
+
+    [Fact]
+    public void ListFields_FindsPlaceholdersAndFieldsSplitAcrossRuns()
+    {
+        using var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(Para("Dear {{first", "_name}},"));
+        body.AppendChild(new Paragraph(
+            CharRun(FieldCharValues.Begin),
+            new Run(new FieldCode(" MERGE")),
+            new Run(new FieldCode("FIELD \"Zip Code\" \\* MERGEFORMAT ")),
+            CharRun(FieldCharValues.Separate),
+            new Run(new Text("«Zip Code»")),
+            CharRun(FieldCharValues.End)));
+        body.AppendChild(new Paragraph(
+            new SimpleField(new Run(new Text("ACME"))) { Instruction = " DOCPROPERTY Company \\* MERGEFORMAT " },
+            new Run(new Text(" {{ }} and a PAGE field"))));
+
+        var fields = TemplateLintHelper.ListFields(session.Document);
+
+        Assert.Equal(
+            [("placeholder", "first_name"), ("merge_field", "Zip Code"), ("doc_property", "Company")],
+            fields.Select(f => (f.Kind, f.Name)));
+        Assert.True(fields[0].SplitAcrossRuns);
+        Assert.Equal(5, fields[0].Offset);
+        Assert.Equal("«Zip Code»", fields[1].Text);
+        Assert.Equal("ACME", fields[2].Text);
+    }
+}
@@ -0,0 +1,83 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TerminologyTests
+{
+    private const string Glossary = """
+        {"entries": [
+          {"term": "email", "variants": ["e-mail"]},
+          {"term": "agreement", "translations": {"fr": "contrat"}},
+          {"term": "guarantee", "forbidden": true, "replacement": "warranty", "note": "Legal"}
+        ]}
+        """;
+
+    private static DocxSession CreateDoc(params string[] paragraphs)
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        foreach (var text in paragraphs.Reverse())
+            body.PrependChild(new Paragraph(new Run(new Text(text))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Parse_RoundTrips()
+    {
+        var entries = TerminologyHelper.Parse(Glossary);
+        var reparsed = TerminologyHelper.Parse(TerminologyHelper.ToJson(entries));
+
+        Assert.Equal(3, reparsed.Count);
+        Assert.Equal(["e-mail"], reparsed[0].Variants);
+        Assert.Equal("contrat", reparsed[1].Translations["fr"]);
+        Assert.True(reparsed[2].Forbidden);
+        Assert.Equal("warranty", reparsed[2].Replacement);
+    }
+
+    [Fact]
+    public void Parse_RejectsEntryWithoutTerm()
+    {
+        Assert.Throws<ArgumentException>(() => TerminologyHelper.Parse("""[{"variants": ["x"]}]"""));
+    }
+
+    [Fact]
+    public void Check_FlagsVariantsAndForbiddenTermsWithOffsets()
+    {
+        using var session = CreateDoc("E-mail us.", "We guarantee delivery by e-mail.");
+        var entries = TerminologyHelper.Parse(Glossary);
+
+        var findings = TerminologyHelper.Check(session.Document, entries, language: null);
+
+        Assert.Equal(3, findings.Count);
+        Assert.Equal(("variant", 0, "E-mail", "Email"),
+            (findings[0].Kind, findings[0].Offset, findings[0].Text, findings[0].Suggestion));
+        Assert.Equal(("forbidden", 3, "warranty", "Legal"),
+            (findings[1].Kind, findings[1].Offset, findings[1].Suggestion, findings[1].Note));
+        Assert.Equal(("variant", 25), (findings[2].Kind, findings[2].Offset));
+        Assert.StartsWith("/body/paragraph[id='", findings[0].Path);
+    }
+
+    [Fact]
+    public void Check_MatchesWholeWordsOnly()
+    {
+        using var session = CreateDoc("Guaranteed e-mails.");
+        var findings = TerminologyHelper.Check(session.Document, TerminologyHelper.Parse(Glossary), language: null);
+
+        Assert.Empty(findings);
+    }
+
+    [Fact]
+    public void Check_WithLanguage_FlagsUntranslatedTerms()
+    {
+        using var session = CreateDoc("Le agreement est signé.");
+        var entries = TerminologyHelper.Parse(Glossary);
+
+        Assert.Empty(TerminologyHelper.Check(session.Document, entries, language: null));
+
+        var finding = Assert.Single(TerminologyHelper.Check(session.Document, entries, language: "FR"));
+        Assert.Equal(("untranslated", "contrat"), (finding.Kind, finding.Suggestion));
+    }
+}
@@ -0,0 +1,31 @@
+using System.Text.Json;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TextDirectionTests
+{
+    private static JsonElement Json(string json) => JsonDocument.Parse(json).RootElement;
+
+    [Fact]
+    public void CreateRichTableCell_SetsVerticalTextDirection()
+    {
+        var cell = ElementFactory.CreateRichTableCell(Json("{\"text\":\"Name\",\"text_direction\":\"bottom_to_top\"}"), false);
+
+        Assert.Equal("btLr", cell.TableCellProperties!.TextDirection!.Val!.InnerText);
+    }
+
+    [Fact]
+    public void MergeTableCellProperties_RotatesAndResetsText()
+    {
+        var cell = new TableCell(new Paragraph(new Run(new Text("Name"))));
+
+        StyleHelper.MergeTableCellProperties(cell, Json("{\"text_direction\":\"top_to_bottom\"}"));
+        Assert.Equal("tbRl", cell.TableCellProperties!.TextDirection!.Val!.InnerText);
+
+        StyleHelper.MergeTableCellProperties(cell, Json("{\"text_direction\":\"horizontal\"}"));
+        Assert.Null(cell.TableCellProperties!.TextDirection);
+    }
+}
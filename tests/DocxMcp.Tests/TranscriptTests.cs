@@ -0,0 +1,80 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TranscriptTests
+{
+    private static List<string> Texts(DocxSession session) =>
+        session.GetBody().Elements<Paragraph>().Select(p => p.InnerText).ToList();
+
+    [Theory]
+    [InlineData("1:05", "00:01:05")]
+    [InlineData("01:02:03.750", "01:02:03")]
+    [InlineData("3725", "01:02:05")]
+    [InlineData("2025-03-01T14:30:00Z", "14:30:00")]
+    public void NormalizeTimestamp_FormatsAsHoursMinutesSeconds(string input, string expected)
+    {
+        Assert.Equal(expected, TranscriptHelper.NormalizeTimestamp(input));
+    }
+
+    [Fact]
+    public void Parse_AcceptsNumericTimestampsAndRejectsMissingSpeaker()
+    {
+        var entries = TranscriptHelper.Parse("""[{"speaker":" Alice ","timestamp":65,"text":"Hi"}]""");
+        Assert.Equal(new TranscriptEntry("Alice", "00:01:05", "Hi"), Assert.Single(entries));
+
+        Assert.Throws<ArgumentException>(() => TranscriptHelper.Parse("""[{"timestamp":"1:00","text":"x"}]"""));
+        Assert.Throws<ArgumentException>(() => TranscriptHelper.Parse("""{"speaker":"A"}"""));
+    }
+
+    [Fact]
+    public void Append_FormatsEntriesAndBuildsSpeakerIndex()
+    {
+        using var session = DocxSession.Create();
+
+        TranscriptHelper.Append(session.Document,
+        [
+            new("Alice", "00:00:05", "Let's start."),
+            new("Bob", "00:00:12", "Agreed."),
+            new("Alice", "00:01:00", "Next item.")
+        ], "Speakers");
+
+        Assert.Equal(
+        [
+            "Speakers",
+            "Alice — 2 entries, first at 00:00:05",
+            "Bob — 1 entry, first at 00:00:12",
+            "[00:00:05]Alice: Let's start.",
+            "[00:00:12]Bob: Agreed.",
+            "[00:01:00]Alice: Next item."
+        ], Texts(session));
+
+        var styles = session.Document.MainDocumentPart!.StyleDefinitionsPart!.Styles!;
+        Assert.Contains(styles.Elements<Style>(), s => s.StyleId?.Value == TranscriptHelper.EntryStyle);
+    }
+
+    [Fact]
+    public void Append_ContinuesAfterLastEntryAndRefreshesIndex()
+    {
+        using var session = DocxSession.Create();
+        TranscriptHelper.Append(session.Document, [new("Alice", "00:00:05", "One.")], "Speakers");
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Action items"))));
+
+        TranscriptHelper.Append(session.Document, [new("Carol", "00:02:00", "Two.")], "Speakers");
+
+        Assert.Equal(
+        [
+            "Speakers",
+            "Alice — 1 entry, first at 00:00:05",
+            "Carol — 1 entry, first at 00:02:00",
+            "[00:00:05]Alice: One.",
+            "[00:02:00]Carol: Two.",
+            "Action items"
+        ], Texts(session));
+
+        var entries = TranscriptHelper.ReadEntries(session.GetBody());
+        Assert.Equal(["Alice", "Carol"], entries.Select(e => e.Speaker));
+    }
+}
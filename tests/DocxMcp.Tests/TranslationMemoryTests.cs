@@ -0,0 +1,97 @@
+using System.Xml.Linq;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class TranslationMemoryTests
+{
+    private static Paragraph Heading(int level, string text) => new(
+        new ParagraphProperties(new ParagraphStyleId { Val = $"Heading{level}" }),
+        new Run(new Text(text)));
+
+    private static Paragraph Para(string text) => new(new Run(new Text(text)));
+
+    private static DocxSession CreateDoc(params Paragraph[] paragraphs)
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        foreach (var paragraph in paragraphs.Reverse())
+            body.PrependChild(paragraph);
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Align_PairsByStructureAndSkipsAddedParagraphs()
+    {
+        using var english = CreateDoc(
+            Heading(1, "Terms"),
+            Para("The supplier delivers the goods. The customer pays the invoice."),
+            Heading(2, "Termination"),
+            Para("Either party may terminate with notice."));
+        using var french = CreateDoc(
+            Heading(1, "Conditions"),
+            Para("Le fournisseur livre les marchandises. Le client paie la facture."),
+            Para("Note du traducteur : ce paragraphe n'existe pas dans l'original."),
+            Heading(2, "Résiliation"),
+            Para("Chaque partie peut résilier avec un préavis."));
+
+        var result = TranslationMemoryHelper.Align(english.Document, french.Document, sentences: true);
+
+        Assert.Equal(0, result.UnalignedSource);
+        Assert.Equal(1, result.UnalignedTarget);
+        Assert.Equal(
+            [
+                ("Terms", "Conditions"),
+                ("The supplier delivers the goods.", "Le fournisseur livre les marchandises."),
+                ("The customer pays the invoice.", "Le client paie la facture."),
+                ("Termination", "Résiliation"),
+                ("Either party may terminate with notice.", "Chaque partie peut résilier avec un préavis.")
+            ],
+            result.Segments.Select(s => (s.Source, s.Target)));
+        Assert.Equal("h2", result.Segments[3].Role);
+    }
+
+    [Fact]
+    public void Align_JoinsAParagraphSplitInTheTranslation()
+    {
+        using var english = CreateDoc(
+            Heading(1, "Scope"),
+            Para("This agreement covers the delivery, installation and maintenance of the office furniture."));
+        using var german = CreateDoc(
+            Heading(1, "Geltungsbereich"),
+            Para("Dieser Vertrag umfasst die Lieferung und die Installation"),
+            Para("sowie die Wartung der Büromöbel."));
+
+        var result = TranslationMemoryHelper.Align(english.Document, german.Document, sentences: false);
+
+        Assert.Equal(2, result.Segments.Count);
+        Assert.Equal(
+            "Dieser Vertrag umfasst die Lieferung und die Installation sowie die Wartung der Büromöbel.",
+            result.Segments[1].Target);
+        Assert.Equal(0, result.UnalignedTarget);
+    }
+
+    [Fact]
+    public void ToTmx_RecordsLanguagesIdsAndRoles()
+    {
+        using var english = CreateDoc(Heading(1, "Terms"));
+        using var french = CreateDoc(Heading(1, "Conditions"));
+        var result = TranslationMemoryHelper.Align(english.Document, french.Document, sentences: true);
+
+        var tmx = TranslationMemoryHelper.ToTmx(result, "en-US", "fr-FR", sentences: true);
+
+        Assert.Equal("1.4", tmx.Root!.Attribute("version")!.Value);
+        Assert.Equal("en-US", tmx.Root.Element("header")!.Attribute("srclang")!.Value);
+        var tu = Assert.Single(tmx.Descendants("tu"));
+        var props = tu.Elements("prop").ToDictionary(p => p.Attribute("type")!.Value, p => p.Value);
+        Assert.Equal("h1", props["x-role"]);
+        Assert.Equal(ElementIdManager.GetId(english.GetBody().Elements<Paragraph>().First()), props["x-source-id"]);
+        Assert.Equal(
+            ["en-US", "fr-FR"],
+            tu.Elements("tuv").Select(v => v.Attribute(XNamespace.Xml + "lang")!.Value));
+        Assert.Equal("Conditions", tu.Elements("tuv").Last().Element("seg")!.Value);
+    }
+}
@@ -0,0 +1,90 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Xunit;
+using M = DocumentFormat.OpenXml.Math;
+using O = DocumentFormat.OpenXml.Vml.Office;
+
+namespace DocxMcp.Tests;
+
+public class UnsupportedFeatureTests
+{
+    private static void Fill(DocxSession session)
+    {
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text("Plain text"))));
+        body.AppendChild(new Paragraph(
+            new Run(new Text("Energy: ")),
+            new M.OfficeMath(new M.Run(new M.Text("E=mc²")))));
+        body.AppendChild(new Paragraph(
+            new Run(new EmbeddedObject(new O.OleObject { ProgId = "Excel.Sheet.12" }))));
+        body.AppendChild(new Paragraph(
+            new InsertedRun(new Run(new Text("Added"))) { Id = "1", Author = "Reviewer" }));
+        ElementIdManager.EnsureAllIds(session.Document);
+    }
+
+    [Fact]
+    public void Report_ListsEachFeatureWithItsLocation()
+    {
+        using var session = DocxSession.Create();
+        Fill(session);
+
+        var features = UnsupportedFeatureHelper.Report(session.Document);
+
+        Assert.Equal(["equation", "embedded_object", "tracked_change"], features.Select(f => f.Kind));
+        var equation = Assert.Single(features[0].Locations);
+        Assert.Equal("body", equation.Part);
+        Assert.StartsWith("/body/paragraph[id=", equation.Path);
+        Assert.Equal("Excel.Sheet.12", features[1].Locations[0].Detail);
+        Assert.Equal("ins", features[2].Locations[0].Detail);
+    }
+
+    [Fact]
+    public void Report_PlainDocumentIsFullySupported()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().AppendChild(new Paragraph(new Run(new Text("Plain text"))));
+
+        var json = FeatureTools.GetUnsupportedFeatures(mgr, session.Id);
+
+        Assert.Contains("\"fully_supported\": true", json);
+    }
+
+    [Fact]
+    public void ReplaceElement_RefusesToDropAnEquation()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        Fill(session);
+        TestHelpers.PersistBaseline(mgr, session);
+        var sync = TestHelpers.CreateSyncManager();
+        var gate = TestHelpers.CreateExternalChangeGate();
+
+        var json = ElementTools.ReplaceElement(mgr, sync, gate, session.Id,
+            "/body/paragraph[text~='Energy']", """{"type": "paragraph", "text": "Energy"}""");
+
+        Assert.Contains("equation", json);
+        Assert.Contains("\"success\": false", json);
+        Assert.Single(mgr.Get(session.Id).GetBody().Descendants<M.OfficeMath>());
+
+        // Text around the equation stays editable
+        json = ElementTools.ReplaceText(mgr, sync, gate, session.Id,
+            "/body/paragraph[text~='Energy']", "Energy", "Mass-energy");
+        Assert.Contains("\"success\": true", json);
+        Assert.Single(mgr.Get(session.Id).GetBody().Descendants<M.OfficeMath>());
+    }
+
+    [Fact]
+    public void ToBytes_KeepsUnsupportedContent()
+    {
+        using var session = DocxSession.Create();
+        Fill(session);
+
+        using var reopened = DocxSession.FromBytes(session.ToBytes(), "reopened", null);
+
+        Assert.Equal(
+            UnsupportedFeatureHelper.Report(session.Document).Select(f => (f.Kind, f.Locations.Count)),
+            UnsupportedFeatureHelper.Report(reopened.Document).Select(f => (f.Kind, f.Locations.Count)));
+    }
+}
@@ -0,0 +1,113 @@
+using System.Net;
+using System.Net.Http.Headers;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class UrlDocumentTests
+{
+    private static readonly byte[] Docx = [(byte)'P', (byte)'K', 3, 4, 1, 2, 3];
+
+    /// <summary>Answers every request with <c>respond</c>, recording them.</summary>
+    private sealed class FakeHandler(Func<HttpRequestMessage, HttpResponseMessage> respond) : HttpMessageHandler
+    {
+        public List<HttpRequestMessage> Requests { get; } = [];
+
+        protected override Task<HttpResponseMessage> SendAsync(HttpRequestMessage request, CancellationToken cancellationToken)
+        {
+            Requests.Add(request);
+            return Task.FromResult(respond(request));
+        }
+    }
+
+    private static HttpResponseMessage Served(byte[] data, string contentType = "application/octet-stream")
+    {
+        var content = new ByteArrayContent(data);
+        content.Headers.ContentType = new MediaTypeHeaderValue(contentType);
+        return new HttpResponseMessage(HttpStatusCode.OK) { Content = content };
+    }
+
+    private static HttpResponseMessage Redirect(string location) =>
+        new(HttpStatusCode.Found) { Headers = { Location = new Uri(location) } };
+
+    [Fact]
+    public async Task Download_ReturnsDocxBytes()
+    {
+        var handler = new FakeHandler(_ => Served(Docx));
+        var fetcher = new UrlDocumentFetcher(new UrlFetchOptions(), handler);
+
+        var data = await fetcher.DownloadAsync("https://example.com/report.docx", null);
+
+        Assert.Equal(Docx, data);
+    }
+
+    [Fact]
+    public async Task Download_RefusesPlainHttpAndHtmlPages()
+    {
+        var handler = new FakeHandler(_ => Served("<html></html>"u8.ToArray(), "text/html"));
+        var fetcher = new UrlDocumentFetcher(new UrlFetchOptions(), handler);
+
+        await Assert.ThrowsAsync<ArgumentException>(
+            () => fetcher.DownloadAsync("http://example.com/report.docx", null));
+        var ex = await Assert.ThrowsAsync<InvalidOperationException>(
+            () => fetcher.DownloadAsync("https://example.com/share/abc", null));
+        Assert.Contains("text/html", ex.Message);
+    }
+
+    [Fact]
+    public async Task Download_EnforcesSizeLimitAndZipSignature()
+    {
+        var large = new FakeHandler(_ => Served(new byte[2048]));
+        var fetcher = new UrlDocumentFetcher(new UrlFetchOptions { MaxBytes = 1024 }, large);
+        await Assert.ThrowsAsync<InvalidOperationException>(
+            () => fetcher.DownloadAsync("https://example.com/big.docx", null));
+
+        var notZip = new FakeHandler(_ => Served("plain text"u8.ToArray()));
+        fetcher = new UrlDocumentFetcher(new UrlFetchOptions(), notZip);
+        var ex = await Assert.ThrowsAsync<InvalidOperationException>(
+            () => fetcher.DownloadAsync("https://example.com/fake.docx", null));
+        Assert.Contains("ZIP", ex.Message);
+    }
+
+    [Fact]
+    public async Task Download_SendsAuthOnlyToTheLinksHost()
+    {
+        var handler = new FakeHandler(request => request.RequestUri!.Host == "example.com"
+            ? Redirect("https://cdn.example.net/blob")
+            : Served(Docx));
+        var fetcher = new UrlDocumentFetcher(new UrlFetchOptions(), handler);
+
+        await fetcher.DownloadAsync("https://example.com/report.docx", "Bearer secret");
+
+        Assert.Equal(2, handler.Requests.Count);
+        Assert.Equal("Bearer secret", handler.Requests[0].Headers.Authorization?.ToString());
+        Assert.Null(handler.Requests[1].Headers.Authorization);
+    }
+
+    [Fact]
+    public async Task Download_ChecksRedirectTargets()
+    {
+        var handler = new FakeHandler(_ => Redirect("http://example.com/insecure.docx"));
+        var fetcher = new UrlDocumentFetcher(new UrlFetchOptions(), handler);
+
+        await Assert.ThrowsAsync<ArgumentException>(
+            () => fetcher.DownloadAsync("https://example.com/report.docx", null));
+    }
+
+    [Theory]
+    [InlineData("8.8.8.8", true)]
+    [InlineData("127.0.0.1", false)]
+    [InlineData("10.1.2.3", false)]
+    [InlineData("172.20.0.1", false)]
+    [InlineData("192.168.1.10", false)]
+    [InlineData("169.254.169.254", false)]
+    [InlineData("::1", false)]
+    [InlineData("fd00::1", false)]
+    [InlineData("::ffff:10.0.0.1", false)]
+    [InlineData("2606:4700::1111", true)]
+    public void IsPublic_RejectsLocalAndPrivateAddresses(string address, bool expected)
+    {
+        Assert.Equal(expected, UrlDocumentFetcher.IsPublic(IPAddress.Parse(address)));
+    }
+}
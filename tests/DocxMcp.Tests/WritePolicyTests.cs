@@ -0,0 +1,118 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using DocxMcp.Tools;
+using Microsoft.Extensions.Logging.Abstractions;
+using Xunit;
+using DW = DocumentFormat.OpenXml.Drawing.Wordprocessing;
+
+namespace DocxMcp.Tests;
+
+public class WritePolicyTests
+{
+    private const string AddTable = """[{"op":"add","path":"/body/children/0","value":{"type":"table","rows":[["a"],["b"],["c"]]}}]""";
+    private const string AddParagraph = """[{"op":"add","path":"/body/children/0","value":{"type":"paragraph","text":"x"}}]""";
+
+    private static SessionManager CreateSessionManager(params IWriteValidator[] validators) =>
+        new(TestHelpers.GetOrCreateHistoryStorage(), NullLogger<SessionManager>.Instance,
+            $"test-{Guid.NewGuid():N}", writePolicy: new WritePolicy(validators));
+
+    private static string Patch(SessionManager mgr, string docId, string patches) =>
+        PatchTool.ApplyPatch(mgr, TestHelpers.CreateSyncManager(), TestHelpers.CreateExternalChangeGate(), docId, patches);
+
+    private static Table TableOf(int rows)
+    {
+        var table = new Table();
+        for (var i = 0; i < rows; i++)
+            table.AppendChild(new TableRow(new TableCell(new Paragraph(new Run(new Text($"r{i}"))))));
+        ElementIdManager.AssignId(table);
+        return table;
+    }
+
+    [Fact]
+    public void TableSize_ReportsTablesOverTheLimitWithTheirPath()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        session.GetBody().PrependChild(TableOf(3));
+        session.GetBody().PrependChild(TableOf(2));
+
+        var violations = new TableSizeValidator(2, 0).Validate(session.Document).ToList();
+
+        var violation = Assert.Single(violations);
+        Assert.Equal("max_table_size", violation.Rule);
+        Assert.Equal("Table has 3 rows (at most 2).", violation.Message);
+        Assert.Equal($"/body/table[id='{ElementIdManager.GetId(session.GetBody().Elements<Table>().Last())}']", violation.Path);
+    }
+
+    [Fact]
+    public void AltText_SkipsDescribedAndDecorativeDrawings()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+
+        static Paragraph WithDrawing(DW.DocProperties docPr) =>
+            new(new Run(new Drawing(new DW.Inline(docPr))));
+
+        var decorative = new DW.DocProperties { Id = 3U, Name = "Rule" };
+        var extensions = new DocumentFormat.OpenXml.Drawing.NonVisualDrawingPropertiesExtensionList();
+        extensions.AppendChild(new DocumentFormat.OpenXml.Drawing.NonVisualDrawingPropertiesExtension
+        {
+            Uri = "{C183D7F6-B498-43B3-948B-1728B52AA6E4}"
+        });
+        decorative.AppendChild(extensions);
+
+        body.PrependChild(WithDrawing(decorative));
+        body.PrependChild(WithDrawing(new DW.DocProperties { Id = 2U, Name = "Logo", Description = "Company logo" }));
+        body.PrependChild(WithDrawing(new DW.DocProperties { Id = 1U, Name = "Chart 1" }));
+
+        var violation = Assert.Single(new AltTextValidator().Validate(session.Document));
+        Assert.Equal("'Chart 1' has no alt text.", violation.Message);
+    }
+
+    [Fact]
+    public void ExternalLinks_FlagsUrlFieldsButNotBookmarkLinks()
+    {
+        var mgr = TestHelpers.CreateSessionManager();
+        var session = mgr.Create();
+        var body = session.GetBody();
+        body.PrependChild(new Paragraph(new SimpleField { Instruction = " HYPERLINK \\l \"intro\" " }));
+        body.PrependChild(new Paragraph(new SimpleField { Instruction = " HYPERLINK \"https://example.com\" " }));
+
+        var violation = Assert.Single(new ExternalLinkValidator().Validate(session.Document));
+        Assert.Equal("no_external_links", violation.Rule);
+        Assert.Equal("HYPERLINK field to 'https://example.com'.", violation.Message);
+    }
+
+    [Fact]
+    public void ApplyPatch_EditAddingAViolation_IsRejectedAndNotSaved()
+    {
+        var mgr = CreateSessionManager(new TableSizeValidator(2, 0));
+        var session = mgr.Create();
+        TestHelpers.PersistBaseline(mgr, session);
+
+        var ex = Assert.Throws<WritePolicyViolationException>(() => Patch(mgr, session.Id, AddTable));
+
+        var violation = Assert.Single(ex.Violations);
+        Assert.Equal("max_table_size", violation.Rule);
+        Assert.Contains("Nothing was saved", ex.Message);
+        Assert.Equal(0, mgr.GetHistory(session.Id).CursorPosition);
+        using var reloaded = mgr.Get(session.Id);
+        Assert.Empty(reloaded.GetBody().Elements<Table>());
+    }
+
+    [Fact]
+    public void ApplyPatch_ExistingViolations_DoNotBlockOtherEdits()
+    {
+        var mgr = CreateSessionManager(new TableSizeValidator(2, 0));
+        var session = mgr.Create();
+        session.GetBody().PrependChild(TableOf(3));
+        TestHelpers.PersistBaseline(mgr, session);
+
+        Patch(mgr, session.Id, AddParagraph);
+        Assert.Equal(1, mgr.GetHistory(session.Id).CursorPosition);
+
+        // A second oversized table is new
+        Assert.Throws<WritePolicyViolationException>(() => Patch(mgr, session.Id, AddTable));
+    }
+}
@@ -0,0 +1,101 @@
+using System.Xml.Linq;
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class XliffTests
+{
+    private static readonly XNamespace Ns = XliffHelper.Ns;
+
+    private static DocxSession CreateDoc()
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.PrependChild(new Paragraph(new Run(new Text("Plain text"))));
+        body.PrependChild(new Paragraph(
+            new Run(new Text("Hello ") { Space = SpaceProcessingModeValues.Preserve }),
+            new Run(new RunProperties(new Bold()), new Text("world"), new TabChar()),
+            new Run(new Text("!"))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    private static Paragraph P(DocxSession session, int i) => session.GetBody().Elements<Paragraph>().ElementAt(i);
+
+    [Fact]
+    public void Export_ProtectsFormattingWithGroupsAndPlaceholders()
+    {
+        using var session = CreateDoc();
+
+        var xliff = XliffHelper.Export(session.Document, "doc", "en", "fr");
+
+        var file = xliff.Root!.Element(Ns + "file")!;
+        Assert.Equal("fr", file.Attribute("target-language")!.Value);
+
+        var units = xliff.Descendants(Ns + "trans-unit").ToList();
+        Assert.Equal(2, units.Count);
+        Assert.Equal(ElementIdManager.GetId(P(session, 0)), units[0].Attribute("id")!.Value);
+
+        var source = units[0].Element(Ns + "source")!;
+        Assert.Equal(["g1", "g2", "g3"], source.Elements(Ns + "g").Select(g => g.Attribute("id")!.Value));
+        Assert.Single(source.Descendants(Ns + "x"));
+
+        // A uniformly formatted paragraph is plain text
+        Assert.Empty(units[1].Element(Ns + "source")!.Elements());
+    }
+
+    [Fact]
+    public void Import_RebuildsParagraphsKeepingFormatting()
+    {
+        using var session = CreateDoc();
+        var xliff = XliffHelper.Export(session.Document, "doc", "en", "fr");
+        var units = xliff.Descendants(Ns + "trans-unit").ToList();
+
+        units[0].Add(new XElement(Ns + "target",
+            new XElement(Ns + "g", new XAttribute("id", "g1"), "Bonjour "),
+            new XElement(Ns + "g", new XAttribute("id", "g2"), "le monde", new XElement(Ns + "x", new XAttribute("id", "x1"))),
+            new XElement(Ns + "g", new XAttribute("id", "g3"), " !")));
+        units[1].Add(new XElement(Ns + "target", "Texte brut"));
+
+        var result = XliffHelper.Import(session.Document, xliff.ToString());
+
+        Assert.Equal(2, result.Updated);
+        Assert.Empty(result.Skipped);
+        Assert.Equal("Bonjour le monde !", P(session, 0).InnerText);
+        Assert.Equal("Texte brut", P(session, 1).InnerText);
+
+        var bold = P(session, 0).Elements<Run>().Where(r => r.RunProperties?.Bold is not null).ToList();
+        Assert.Equal("le monde", string.Concat(bold.Select(r => r.InnerText)));
+        Assert.Single(P(session, 0).Descendants<TabChar>());
+    }
+
+    [Fact]
+    public void Import_KeepsPlaceholdersMissingFromTarget()
+    {
+        using var session = CreateDoc();
+        var xliff = XliffHelper.Export(session.Document, "doc", "en", "fr");
+        xliff.Descendants(Ns + "trans-unit").First().Add(new XElement(Ns + "target", "Salut"));
+
+        XliffHelper.Import(session.Document, xliff.ToString());
+
+        Assert.Single(P(session, 0).Descendants<TabChar>());
+    }
+
+    [Fact]
+    public void Import_SkipsUnitsWhoseSourceChanged()
+    {
+        using var session = CreateDoc();
+        var xliff = XliffHelper.Export(session.Document, "doc", "en", "fr");
+        xliff.Descendants(Ns + "trans-unit").Last().Add(new XElement(Ns + "target", "Texte brut"));
+
+        P(session, 1).GetFirstChild<Run>()!.GetFirstChild<Text>()!.Text = "Edited";
+
+        var result = XliffHelper.Import(session.Document, xliff.ToString());
+
+        Assert.Equal(0, result.Updated);
+        Assert.Contains(result.Skipped, s => s.Reason.Contains("source text changed"));
+        Assert.Equal("Edited", P(session, 1).InnerText);
+    }
+}
@@ -0,0 +1,61 @@
+using DocumentFormat.OpenXml.Wordprocessing;
+using DocxMcp.Helpers;
+using Xunit;
+
+namespace DocxMcp.Tests;
+
+public class XmlQueryTests
+{
+    private static DocxSession CreateDoc()
+    {
+        var session = DocxSession.Create();
+        var body = session.GetBody();
+        body.AppendChild(new Paragraph(new Run(new Text("First"))));
+        body.AppendChild(new Paragraph(new Run(new RunProperties(new Bold()), new Text("Second"))));
+        ElementIdManager.EnsureAllIds(session.Document);
+        return session;
+    }
+
+    [Fact]
+    public void Query_ReturnsElementsWithLocationsAndIds()
+    {
+        using var session = CreateDoc();
+        var part = XmlQueryHelper.ResolvePart(session.Document, "document");
+
+        var (matches, scalar) = XmlQueryHelper.Query(part, "//w:p[.//w:b]", 2000);
+
+        Assert.Null(scalar);
+        var match = Assert.Single(matches);
+        Assert.Equal("element", match.Type);
+        Assert.Equal("/w:document[1]/w:body[1]/w:p[2]", match.Location);
+        Assert.Equal(ElementIdManager.GetId(session.GetBody().Elements<Paragraph>().Last()), match.Id);
+        Assert.Contains("Second", match.Value);
+    }
+
+    [Fact]
+    public void Query_ReturnsScalarsAndTextNodes()
+    {
+        using var session = CreateDoc();
+        var part = XmlQueryHelper.ResolvePart(session.Document, "/word/document.xml");
+
+        var (_, count) = XmlQueryHelper.Query(part, "count(//w:p)", 2000);
+        Assert.Equal(2.0, count);
+
+        var (texts, _) = XmlQueryHelper.Query(part, "//w:t/text()", 2000);
+        Assert.Equal(["First", "Second"], texts.Select(t => t.Value));
+        Assert.EndsWith("/w:t[1]/text()[1]", texts[0].Location);
+    }
+
+    [Fact]
+    public void ResolvePart_RejectsUnknownPartsAndBadXPath()
+    {
+        using var session = CreateDoc();
+
+        Assert.Throws<ArgumentException>(() => XmlQueryHelper.ResolvePart(session.Document, "styles"));
+        Assert.Throws<ArgumentException>(() => XmlQueryHelper.ResolvePart(session.Document, "nonsense"));
+
+        var part = XmlQueryHelper.ResolvePart(session.Document, "document");
+        Assert.Throws<ArgumentException>(() => XmlQueryHelper.Query(part, "//w:p[", 2000));
+        Assert.Contains("document", XmlQueryHelper.ListParts(session.Document));
+    }
+}
@@ -0,0 +1,18 @@
+-- Recently opened/synced files and favorites, per tenant.
+-- Written by docx-storage-gdrive; local deployments keep the list in index.json.
+-- Favorites are never evicted; other entries are pruned to the most recent 50.
+
+CREATE TABLE IF NOT EXISTS "recent_file" (
+    "tenantId" TEXT NOT NULL,
+    "connectionId" TEXT NOT NULL,
+    "fileId" TEXT NOT NULL,
+    "path" TEXT NOT NULL,
+    "displayName" TEXT NOT NULL,
+    "lastUsedAt" INTEGER NOT NULL,
+    "favorite" INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY ("tenantId", "connectionId", "fileId"),
+    FOREIGN KEY ("tenantId") REFERENCES "tenant"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_recent_file_tenant"
+    ON "recent_file"("tenantId", "lastUsedAt");
@@ -0,0 +1,11 @@
+-- Unacknowledged external change events, per tenant.
+-- Written by docx-storage-gdrive so queued notifications survive a restart;
+-- local deployments keep the queue in {storage_dir}/{tenant}/watch_queue.json.
+-- "queue" is the JSON-serialized ChangeQueue (at most one event per session).
+
+CREATE TABLE IF NOT EXISTS "watch_change_queue" (
+    "tenantId" TEXT NOT NULL PRIMARY KEY,
+    "queue" TEXT NOT NULL,
+    "updatedAt" TEXT NOT NULL,
+    FOREIGN KEY ("tenantId") REFERENCES "tenant"("id") ON DELETE CASCADE
+);
@@ -0,0 +1,39 @@
+-- Scheduled jobs, per tenant, run by docx-mcp-sse-proxy.
+-- "cron" is a five-field expression (or @daily etc.), evaluated in UTC.
+-- "action" is JSON: {"type":"tool","tool":"...","arguments":{...}}
+-- or {"type":"sync","doc_id":"...","direction":"push"|"pull"}.
+-- "nextRunAt" is NULL until the scheduler first picks the job up.
+
+CREATE TABLE IF NOT EXISTS "scheduled_job" (
+    "id" TEXT PRIMARY KEY NOT NULL,
+    "tenantId" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "cron" TEXT NOT NULL,
+    "action" TEXT NOT NULL,
+    "enabled" INTEGER NOT NULL DEFAULT 1,
+    "alertWebhook" TEXT,
+    "nextRunAt" TEXT,
+    "lastRunAt" TEXT,
+    "lastStatus" TEXT,
+    "consecutiveFailures" INTEGER NOT NULL DEFAULT 0,
+    "createdAt" TEXT NOT NULL,
+    "updatedAt" TEXT NOT NULL,
+    FOREIGN KEY ("tenantId") REFERENCES "tenant"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_scheduled_job_tenantId" ON "scheduled_job"("tenantId");
+CREATE INDEX IF NOT EXISTS "idx_scheduled_job_due" ON "scheduled_job"("enabled", "nextRunAt");
+
+-- Run history; the scheduler keeps the most recent runs of each job.
+CREATE TABLE IF NOT EXISTS "scheduled_job_run" (
+    "id" TEXT PRIMARY KEY NOT NULL,
+    "jobId" TEXT NOT NULL,
+    "tenantId" TEXT NOT NULL,
+    "startedAt" TEXT NOT NULL,
+    "finishedAt" TEXT NOT NULL,
+    "status" TEXT NOT NULL,
+    "output" TEXT,
+    FOREIGN KEY ("jobId") REFERENCES "scheduled_job"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_scheduled_job_run_jobId" ON "scheduled_job_run"("jobId", "startedAt");
@@ -0,0 +1,32 @@
+-- Feature flags gating new or risky document capabilities, read by
+-- docx-mcp-sse-proxy and passed to the .NET backend per request.
+-- A flag is on for a tenant when "tenant_feature_flag" says so, else when
+-- "enabled" is set, else when the tenant's stable bucket (0-99, hashed from
+-- the flag name and tenant ID) is below "rolloutPercent".
+-- Flags absent from this table use the backend's built-in defaults.
+
+CREATE TABLE IF NOT EXISTS "feature_flag" (
+    "name" TEXT PRIMARY KEY NOT NULL,
+    "description" TEXT,
+    "enabled" INTEGER NOT NULL DEFAULT 0,
+    "rolloutPercent" INTEGER NOT NULL DEFAULT 0,
+    "createdAt" TEXT NOT NULL,
+    "updatedAt" TEXT NOT NULL
+);
+
+-- Per-tenant overrides, e.g. early access or a kill switch for one tenant.
+CREATE TABLE IF NOT EXISTS "tenant_feature_flag" (
+    "tenantId" TEXT NOT NULL,
+    "flag" TEXT NOT NULL,
+    "enabled" INTEGER NOT NULL,
+    "updatedAt" TEXT NOT NULL,
+    PRIMARY KEY ("tenantId", "flag"),
+    FOREIGN KEY ("tenantId") REFERENCES "tenant"("id") ON DELETE CASCADE,
+    FOREIGN KEY ("flag") REFERENCES "feature_flag"("name") ON DELETE CASCADE
+);
+
+-- Capabilities that already shipped stay on; new ones start off.
+INSERT OR IGNORE INTO "feature_flag" ("name", "description", "enabled", "rolloutPercent", "createdAt", "updatedAt") VALUES
+    ('pdf_export', 'Export documents to PDF (LibreOffice)', 1, 100, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    ('tracked_changes', 'Enable track changes and accept or reject revisions', 1, 100, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    ('hifi_toc', 'High-fidelity table of contents generation', 0, 0, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
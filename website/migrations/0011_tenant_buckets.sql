@@ -0,0 +1,19 @@
+-- Customer-owned buckets: a tenant registering S3-compatible credentials has
+-- its sessions, WAL and checkpoints stored in its own bucket by
+-- docx-storage-cloudflare instead of the shared R2 bucket.
+-- "secretAccessKey" is sealed with AES-256-GCM under BUCKET_CREDENTIALS_KEY
+-- (shared by the website and the storage server), with the tenant ID as
+-- associated data: base64(12-byte nonce || ciphertext || tag).
+-- "region" is the bucket's S3 signing region ("auto" for R2).
+
+CREATE TABLE IF NOT EXISTS "tenant_bucket" (
+    "tenantId" TEXT PRIMARY KEY NOT NULL,
+    "endpoint" TEXT NOT NULL,
+    "region" TEXT NOT NULL,
+    "bucket" TEXT NOT NULL,
+    "accessKeyId" TEXT NOT NULL,
+    "secretAccessKey" TEXT NOT NULL,
+    "createdAt" TEXT NOT NULL,
+    "updatedAt" TEXT NOT NULL,
+    FOREIGN KEY ("tenantId") REFERENCES "tenant"("id") ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Where a tenant's own bucket keeps its data ("eu", "us", ...), checked by
+-- docx-storage-cloudflare against its allowed regions. Buckets registered
+-- before this column existed have none and are refused until re-registered.
+
+ALTER TABLE "tenant_bucket" ADD COLUMN "dataRegion" TEXT NOT NULL DEFAULT '';
@@ -17,6 +17,7 @@ interface Env {
   OAUTH_MICROSOFT_TENANT_ID: string;
   GCS_SERVICE_ACCOUNT_KEY: string;
   GCS_BUCKET_NAME: string;
+  BUCKET_CREDENTIALS_KEY?: string;
 }
 
 declare namespace App {
@@ -0,0 +1,246 @@
+import { Kysely } from 'kysely';
+import { D1Dialect } from 'kysely-d1';
+
+// Jobs are executed by docx-mcp-sse-proxy, which owns the full cron parser.
+// Here we only check the shape so obvious typos are rejected at creation.
+const CRON_SHORTHANDS = ['@hourly', '@daily', '@midnight', '@weekly', '@monthly', '@yearly', '@annually'];
+const CRON_FIELD = /^[0-9a-zA-Z*,\-/]+$/;
+
+interface JobRecord {
+  id: string;
+  tenantId: string;
+  name: string;
+  cron: string;
+  action: string;
+  enabled: number;
+  alertWebhook: string | null;
+  nextRunAt: string | null;
+  lastRunAt: string | null;
+  lastStatus: string | null;
+  consecutiveFailures: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
+interface JobRunRecord {
+  id: string;
+  jobId: string;
+  tenantId: string;
+  startedAt: string;
+  finishedAt: string;
+  status: string;
+  output: string | null;
+}
+
+export type JobAction =
+  | { type: 'tool'; tool: string; arguments?: Record<string, unknown> }
+  | { type: 'sync'; doc_id: string; direction?: 'push' | 'pull' };
+
+export interface JobInfo {
+  id: string;
+  name: string;
+  cron: string;
+  action: JobAction;
+  enabled: boolean;
+  alertWebhook: string | null;
+  nextRunAt: string | null;
+  lastRunAt: string | null;
+  lastStatus: string | null;
+  consecutiveFailures: number;
+  createdAt: string;
+}
+
+export interface JobRunInfo {
+  id: string;
+  startedAt: string;
+  finishedAt: string;
+  status: string;
+  output: string | null;
+}
+
+export interface CreateJobInput {
+  name: string;
+  cron: string;
+  action: JobAction;
+  alertWebhook?: string | null;
+}
+
+function getKysely(db: D1Database) {
+  return new Kysely<{ scheduled_job: JobRecord; scheduled_job_run: JobRunRecord }>({
+    dialect: new D1Dialect({ database: db }),
+  });
+}
+
+function toInfo(record: JobRecord): JobInfo {
+  return {
+    id: record.id,
+    name: record.name,
+    cron: record.cron,
+    action: JSON.parse(record.action),
+    enabled: record.enabled === 1,
+    alertWebhook: record.alertWebhook,
+    nextRunAt: record.nextRunAt,
+    lastRunAt: record.lastRunAt,
+    lastStatus: record.lastStatus,
+    consecutiveFailures: record.consecutiveFailures,
+    createdAt: record.createdAt,
+  };
+}
+
+export function validateCron(cron: string): string | null {
+  const expr = cron.trim();
+  if (expr.startsWith('@')) {
+    return CRON_SHORTHANDS.includes(expr.toLowerCase()) ? null : `Unknown cron shorthand '${expr}'`;
+  }
+  const fields = expr.split(/\s+/);
+  if (fields.length !== 5) {
+    return 'Cron expression must have 5 fields (minute hour day month weekday)';
+  }
+  return fields.every((f) => CRON_FIELD.test(f)) ? null : `Invalid cron expression '${expr}'`;
+}
+
+export function validateAction(action: unknown): string | null {
+  if (!action || typeof action !== 'object') {
+    return 'Action is required';
+  }
+  const a = action as Record<string, unknown>;
+  if (a.type === 'tool') {
+    if (typeof a.tool !== 'string' || !a.tool) return 'Action tool is required';
+    if (a.arguments !== undefined && (typeof a.arguments !== 'object' || a.arguments === null || Array.isArray(a.arguments))) {
+      return 'Action arguments must be an object';
+    }
+    return null;
+  }
+  if (a.type === 'sync') {
+    if (typeof a.doc_id !== 'string' || !a.doc_id) return 'Action doc_id is required';
+    if (a.direction !== undefined && a.direction !== 'push' && a.direction !== 'pull') {
+      return "Action direction must be 'push' or 'pull'";
+    }
+    return null;
+  }
+  return "Action type must be 'tool' or 'sync'";
+}
+
+export async function createJob(
+  db: D1Database,
+  tenantId: string,
+  input: CreateJobInput,
+): Promise<JobInfo> {
+  const kysely = getKysely(db);
+  const now = new Date().toISOString();
+
+  const record: JobRecord = {
+    id: crypto.randomUUID(),
+    tenantId,
+    name: input.name,
+    cron: input.cron.trim(),
+    action: JSON.stringify(input.action),
+    enabled: 1,
+    alertWebhook: input.alertWebhook ?? null,
+    nextRunAt: null, // computed by the scheduler on its next poll
+    lastRunAt: null,
+    lastStatus: null,
+    consecutiveFailures: 0,
+    createdAt: now,
+    updatedAt: now,
+  };
+
+  await kysely.insertInto('scheduled_job').values(record).execute();
+
+  return toInfo(record);
+}
+
+export async function listJobs(db: D1Database, tenantId: string): Promise<JobInfo[]> {
+  const kysely = getKysely(db);
+
+  const records = await kysely
+    .selectFrom('scheduled_job')
+    .selectAll()
+    .where('tenantId', '=', tenantId)
+    .orderBy('createdAt', 'desc')
+    .execute();
+
+  return records.map(toInfo);
+}
+
+export async function listJobRuns(
+  db: D1Database,
+  tenantId: string,
+  jobId: string,
+): Promise<JobRunInfo[]> {
+  const kysely = getKysely(db);
+
+  return kysely
+    .selectFrom('scheduled_job_run')
+    .select(['id', 'startedAt', 'finishedAt', 'status', 'output'])
+    .where('jobId', '=', jobId)
+    .where('tenantId', '=', tenantId)
+    .orderBy('startedAt', 'desc')
+    .execute();
+}
+
+export async function getJob(
+  db: D1Database,
+  tenantId: string,
+  jobId: string,
+): Promise<JobInfo | null> {
+  const kysely = getKysely(db);
+
+  const record = await kysely
+    .selectFrom('scheduled_job')
+    .selectAll()
+    .where('id', '=', jobId)
+    .where('tenantId', '=', tenantId)
+    .executeTakeFirst();
+
+  return record ? toInfo(record) : null;
+}
+
+export async function updateJob(
+  db: D1Database,
+  tenantId: string,
+  jobId: string,
+  changes: { name?: string; cron?: string; enabled?: boolean; alertWebhook?: string | null },
+): Promise<boolean> {
+  const kysely = getKysely(db);
+
+  const set: Partial<JobRecord> = { updatedAt: new Date().toISOString() };
+  if (changes.name !== undefined) set.name = changes.name;
+  if (changes.alertWebhook !== undefined) set.alertWebhook = changes.alertWebhook;
+  if (changes.cron !== undefined) {
+    set.cron = changes.cron.trim();
+    set.nextRunAt = null; // rescheduled on the next poll
+  }
+  if (changes.enabled !== undefined) {
+    set.enabled = changes.enabled ? 1 : 0;
+    if (changes.enabled) {
+      set.nextRunAt = null;
+      set.consecutiveFailures = 0;
+    }
+  }
+
+  const result = await kysely
+    .updateTable('scheduled_job')
+    .set(set)
+    .where('id', '=', jobId)
+    .where('tenantId', '=', tenantId)
+    .executeTakeFirst();
+
+  return (result.numUpdatedRows ?? 0) > 0;
+}
+
+export async function deleteJob(
+  db: D1Database,
+  tenantId: string,
+  jobId: string,
+): Promise<boolean> {
+  const kysely = getKysely(db);
+
+  const result = await kysely
+    .deleteFrom('scheduled_job')
+    .where('id', '=', jobId)
+    .where('tenantId', '=', tenantId)
+    .executeTakeFirst();
+
+  return (result.numDeletedRows ?? 0) > 0;
+}
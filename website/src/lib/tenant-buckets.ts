@@ -0,0 +1,205 @@
+import { Kysely } from 'kysely';
+import { D1Dialect } from 'kysely-d1';
+
+// docx-storage-cloudflare reads this table and decrypts the secret with the
+// same key, so the sealed format must match storage/tenant_buckets.rs there.
+const NONCE_BYTES = 12;
+
+interface TenantBucketRecord {
+  tenantId: string;
+  endpoint: string;
+  region: string;
+  dataRegion: string;
+  bucket: string;
+  accessKeyId: string;
+  secretAccessKey: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface TenantBucketInfo {
+  endpoint: string;
+  region: string;
+  dataRegion: string;
+  bucket: string;
+  accessKeyId: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface TenantBucketInput {
+  endpoint: string;
+  region?: string;
+  dataRegion: string;
+  bucket: string;
+  accessKeyId: string;
+  secretAccessKey: string;
+}
+
+function getKysely(db: D1Database) {
+  return new Kysely<{ tenant_bucket: TenantBucketRecord }>({
+    dialect: new D1Dialect({ database: db }),
+  });
+}
+
+function toInfo(record: TenantBucketRecord): TenantBucketInfo {
+  // The secret never leaves the server
+  return {
+    endpoint: record.endpoint,
+    region: record.region,
+    dataRegion: record.dataRegion,
+    bucket: record.bucket,
+    accessKeyId: record.accessKeyId,
+    createdAt: record.createdAt,
+    updatedAt: record.updatedAt,
+  };
+}
+
+/**
+ * Whether `hostname` is a public host. The storage server sends signed
+ * requests to the endpoint from inside the deployment, so local names and
+ * private, loopback or link-local addresses are refused; the server checks
+ * again on the addresses it connects to.
+ */
+function isPublicHost(hostname: string): boolean {
+  const host = hostname.toLowerCase().replace(/\.$/, '');
+
+  // URL normalizes IPv6 literals, e.g. mapped IPv4 becomes [::ffff:c0a8:101]
+  if (host.startsWith('[')) {
+    const ip = host.slice(1, -1);
+    return !(
+      ip === '::' ||
+      ip === '::1' ||
+      ip.startsWith('::ffff:') ||
+      /^f[c-f]/.test(ip) ||
+      /^ff/.test(ip)
+    );
+  }
+  if (/^\d+\.\d+\.\d+\.\d+$/.test(host)) return isPublicIPv4(host);
+
+  if (host === 'localhost' || !host.includes('.')) return false;
+  return !/\.(localhost|local|internal|localdomain)$/.test(host);
+}
+
+function isPublicIPv4(ip: string): boolean {
+  const [a, b] = ip.split('.').map(Number);
+  return !(
+    a === 0 ||
+    a === 10 ||
+    a === 127 ||
+    a >= 224 ||
+    (a === 100 && b >= 64 && b <= 127) ||
+    (a === 169 && b === 254) ||
+    (a === 172 && b >= 16 && b <= 31) ||
+    (a === 192 && b === 168)
+  );
+}
+
+export function validateBucket(input: Partial<TenantBucketInput>): string | null {
+  if (!input.endpoint) return 'Endpoint is required';
+  let url: URL;
+  try {
+    url = new URL(input.endpoint);
+  } catch {
+    return `Invalid endpoint '${input.endpoint}'`;
+  }
+  if (url.protocol !== 'https:') return 'Endpoint must use https';
+  if (!isPublicHost(url.hostname)) return `Endpoint host '${url.hostname}' is not public`;
+  if (!input.dataRegion || !/^[a-z0-9-]+$/.test(input.dataRegion)) {
+    return 'Data region is required (e.g. "eu")';
+  }
+  if (!input.bucket?.trim()) return 'Bucket is required';
+  if (!input.accessKeyId?.trim()) return 'Access key ID is required';
+  if (!input.secretAccessKey) return 'Secret access key is required';
+  return null;
+}
+
+/**
+ * Seal a secret with AES-256-GCM under `keyBase64` (32 bytes), bound to the
+ * tenant so a sealed value copied to another tenant's row doesn't decrypt.
+ */
+async function sealSecret(keyBase64: string, tenantId: string, secret: string): Promise<string> {
+  const rawKey = Uint8Array.from(atob(keyBase64), (c) => c.charCodeAt(0));
+  if (rawKey.length !== 32) {
+    throw new Error('BUCKET_CREDENTIALS_KEY must be 32 bytes, base64-encoded');
+  }
+  const key = await crypto.subtle.importKey('raw', rawKey, 'AES-GCM', false, ['encrypt']);
+  const nonce = crypto.getRandomValues(new Uint8Array(NONCE_BYTES));
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: 'AES-GCM', iv: nonce, additionalData: new TextEncoder().encode(tenantId) },
+    key,
+    new TextEncoder().encode(secret),
+  );
+
+  const sealed = new Uint8Array(NONCE_BYTES + ciphertext.byteLength);
+  sealed.set(nonce);
+  sealed.set(new Uint8Array(ciphertext), NONCE_BYTES);
+  return btoa(String.fromCharCode(...sealed));
+}
+
+export async function getTenantBucket(
+  db: D1Database,
+  tenantId: string,
+): Promise<TenantBucketInfo | null> {
+  const kysely = getKysely(db);
+
+  const record = await kysely
+    .selectFrom('tenant_bucket')
+    .selectAll()
+    .where('tenantId', '=', tenantId)
+    .executeTakeFirst();
+
+  return record ? toInfo(record) : null;
+}
+
+export async function setTenantBucket(
+  db: D1Database,
+  keyBase64: string,
+  tenantId: string,
+  input: TenantBucketInput,
+): Promise<TenantBucketInfo> {
+  const kysely = getKysely(db);
+  const now = new Date().toISOString();
+
+  const record: TenantBucketRecord = {
+    tenantId,
+    endpoint: input.endpoint.replace(/\/+$/, ''),
+    region: input.region?.trim() || 'auto',
+    dataRegion: input.dataRegion,
+    bucket: input.bucket.trim(),
+    accessKeyId: input.accessKeyId.trim(),
+    secretAccessKey: await sealSecret(keyBase64, tenantId, input.secretAccessKey),
+    createdAt: now,
+    updatedAt: now,
+  };
+
+  // Replacing the credentials keeps the original creation date
+  await kysely
+    .insertInto('tenant_bucket')
+    .values(record)
+    .onConflict((oc) =>
+      oc.column('tenantId').doUpdateSet({
+        endpoint: record.endpoint,
+        region: record.region,
+        dataRegion: record.dataRegion,
+        bucket: record.bucket,
+        accessKeyId: record.accessKeyId,
+        secretAccessKey: record.secretAccessKey,
+        updatedAt: now,
+      }),
+    )
+    .execute();
+
+  return (await getTenantBucket(db, tenantId)) ?? toInfo(record);
+}
+
+export async function deleteTenantBucket(db: D1Database, tenantId: string): Promise<boolean> {
+  const kysely = getKysely(db);
+
+  const result = await kysely
+    .deleteFrom('tenant_bucket')
+    .where('tenantId', '=', tenantId)
+    .executeTakeFirst();
+
+  return (result.numDeletedRows ?? 0) > 0;
+}
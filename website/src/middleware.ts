@@ -8,6 +8,8 @@ export const onRequest = defineMiddleware(async (context, next) => {
     url.pathname.startsWith('/en/dashboard');
   const isPatRoute = url.pathname.startsWith('/api/pat');
   const isPreferencesRoute = url.pathname.startsWith('/api/preferences');
+  const isJobsRoute = url.pathname.startsWith('/api/jobs');
+  const isBucketRoute = url.pathname.startsWith('/api/bucket');
   const isAuthRoute = url.pathname.startsWith('/api/auth');
   const isConsentRoute =
     url.pathname === '/consent' || url.pathname === '/en/consent';
@@ -29,6 +31,8 @@ export const onRequest = defineMiddleware(async (context, next) => {
     !isAuthRoute &&
     !isPatRoute &&
     !isPreferencesRoute &&
+    !isJobsRoute &&
+    !isBucketRoute &&
     !isOAuthConnectionRoute &&
     !isConsentRoute &&
     !isOAuthServerPublicRoute
@@ -71,8 +75,8 @@ export const onRequest = defineMiddleware(async (context, next) => {
     return context.redirect(`${loginPath}?return_to=${returnTo}`);
   }
 
-  // Return 401 for API routes without auth (PAT, preferences, OAuth connections)
-  if ((isPatRoute || isPreferencesRoute || (isOAuthConnectionRoute && !isOAuthAuthorizeRoute)) && !session) {
+  // Return 401 for API routes without auth (PAT, preferences, jobs, bucket, OAuth connections)
+  if ((isPatRoute || isPreferencesRoute || isJobsRoute || isBucketRoute || (isOAuthConnectionRoute && !isOAuthAuthorizeRoute)) && !session) {
     return new Response(JSON.stringify({ error: 'Unauthorized' }), {
       status: 401,
       headers: { 'Content-Type': 'application/json' },
@@ -84,6 +88,8 @@ export const onRequest = defineMiddleware(async (context, next) => {
     (isProtectedRoute ||
       isPatRoute ||
       isPreferencesRoute ||
+      isJobsRoute ||
+      isBucketRoute ||
       isOAuthConnectionRoute ||
       isConsentRoute) &&
     session
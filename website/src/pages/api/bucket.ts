@@ -0,0 +1,105 @@
+import type { APIRoute } from 'astro';
+import {
+  deleteTenantBucket,
+  getTenantBucket,
+  setTenantBucket,
+  validateBucket,
+  type TenantBucketInput,
+} from '../../lib/tenant-buckets';
+
+export const prerender = false;
+
+// GET /api/bucket - Get the tenant's own bucket, without its secret
+export const GET: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const bucket = await getTenantBucket((env as unknown as Env).DB, tenant.id);
+
+  return new Response(JSON.stringify({ bucket }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
+
+// PUT /api/bucket - Register or replace the tenant's own bucket credentials
+export const PUT: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  let body: Partial<TenantBucketInput>;
+  try {
+    body = await context.request.json();
+  } catch {
+    return new Response(JSON.stringify({ error: 'Invalid JSON' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const error = validateBucket(body);
+  if (error) {
+    return new Response(JSON.stringify({ error }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const typedEnv = env as unknown as Env;
+  if (!typedEnv.BUCKET_CREDENTIALS_KEY) {
+    return new Response(JSON.stringify({ error: 'Customer-owned buckets are not enabled' }), {
+      status: 501,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const bucket = await setTenantBucket(
+    typedEnv.DB,
+    typedEnv.BUCKET_CREDENTIALS_KEY,
+    tenant.id,
+    body as TenantBucketInput,
+  );
+
+  return new Response(JSON.stringify({ bucket }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
+
+// DELETE /api/bucket - Go back to the shared bucket
+export const DELETE: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const deleted = await deleteTenantBucket((env as unknown as Env).DB, tenant.id);
+
+  if (!deleted) {
+    return new Response(JSON.stringify({ error: 'No bucket registered' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  return new Response(JSON.stringify({ success: true }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
@@ -0,0 +1,140 @@
+import type { APIRoute } from 'astro';
+import { deleteJob, getJob, listJobRuns, updateJob, validateCron } from '../../../lib/scheduled-jobs';
+
+export const prerender = false;
+
+// GET /api/jobs/:id - Get a scheduled job with its run history
+export const GET: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const jobId = context.params.id;
+  if (!jobId) {
+    return new Response(JSON.stringify({ error: 'Job ID required' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const db = (env as unknown as Env).DB;
+  const job = await getJob(db, tenant.id, jobId);
+
+  if (!job) {
+    return new Response(JSON.stringify({ error: 'Job not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const runs = await listJobRuns(db, tenant.id, jobId);
+
+  return new Response(JSON.stringify({ job, runs }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
+
+// PATCH /api/jobs/:id - Rename, reschedule, enable or disable a job
+export const PATCH: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const jobId = context.params.id;
+  if (!jobId) {
+    return new Response(JSON.stringify({ error: 'Job ID required' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  let body: { name?: string; cron?: string; enabled?: boolean; alertWebhook?: string | null };
+  try {
+    body = await context.request.json();
+  } catch {
+    return new Response(JSON.stringify({ error: 'Invalid JSON' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const name = body.name?.trim();
+  if (body.name !== undefined && !name) {
+    return new Response(JSON.stringify({ error: 'Name cannot be empty' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const cronError = body.cron !== undefined ? validateCron(body.cron) : null;
+  if (cronError) {
+    return new Response(JSON.stringify({ error: cronError }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const updated = await updateJob((env as unknown as Env).DB, tenant.id, jobId, {
+    name,
+    cron: body.cron,
+    enabled: body.enabled,
+    alertWebhook: body.alertWebhook,
+  });
+
+  if (!updated) {
+    return new Response(JSON.stringify({ error: 'Job not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  return new Response(JSON.stringify({ success: true }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
+
+// DELETE /api/jobs/:id - Delete a job and its run history
+export const DELETE: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const jobId = context.params.id;
+  if (!jobId) {
+    return new Response(JSON.stringify({ error: 'Job ID required' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const deleted = await deleteJob((env as unknown as Env).DB, tenant.id, jobId);
+
+  if (!deleted) {
+    return new Response(JSON.stringify({ error: 'Job not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  return new Response(JSON.stringify({ success: true }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
@@ -0,0 +1,73 @@
+import type { APIRoute } from 'astro';
+import { createJob, listJobs, validateAction, validateCron, type JobAction } from '../../../lib/scheduled-jobs';
+
+export const prerender = false;
+
+// GET /api/jobs - List the scheduled jobs of the current tenant
+export const GET: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const jobs = await listJobs((env as unknown as Env).DB, tenant.id);
+
+  return new Response(JSON.stringify({ jobs }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
+
+// POST /api/jobs - Register a scheduled job
+export const POST: APIRoute = async (context) => {
+  const tenant = context.locals.tenant;
+  if (!tenant) {
+    return new Response(JSON.stringify({ error: 'Tenant not found' }), {
+      status: 404,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  let body: { name?: string; cron?: string; action?: unknown; alertWebhook?: string | null };
+  try {
+    body = await context.request.json();
+  } catch {
+    return new Response(JSON.stringify({ error: 'Invalid JSON' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const name = body.name?.trim();
+  if (!name) {
+    return new Response(JSON.stringify({ error: 'Name is required' }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const error = validateCron(body.cron ?? '') ?? validateAction(body.action);
+  if (error) {
+    return new Response(JSON.stringify({ error }), {
+      status: 400,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const { env } = await import('cloudflare:workers');
+  const job = await createJob((env as unknown as Env).DB, tenant.id, {
+    name,
+    cron: body.cron!,
+    action: body.action as JobAction,
+    alertWebhook: body.alertWebhook,
+  });
+
+  return new Response(JSON.stringify({ job }), {
+    status: 201,
+    headers: { 'Content-Type': 'application/json' },
+  });
+};
@@ -0,0 +1,65 @@
+import assert from 'node:assert/strict';
+import { beforeEach, test } from 'node:test';
+
+import { reset, state } from './stubs.mjs';
+
+const { onRequest } = await import('../src/middleware.ts');
+const bucketRoute = await import('../src/pages/api/bucket.ts');
+
+// Request /api/bucket through the middleware, as Astro would
+async function request(method, body) {
+  const context = {
+    request: new Request('https://docx.example/api/bucket', {
+      method,
+      body: body && JSON.stringify(body),
+    }),
+    locals: {},
+    redirect: (path) => Response.redirect(new URL(path, 'https://docx.example'), 302),
+  };
+  const response = await onRequest(context, () => bucketRoute[method](context));
+  return { context, response };
+}
+
+function signIn() {
+  state.session = {
+    user: { id: 'user-1', name: 'Ada' },
+    session: { id: 'session-1' },
+  };
+}
+
+beforeEach(reset);
+
+test('bucket routes refuse requests without a session', async () => {
+  for (const method of ['GET', 'PUT', 'DELETE']) {
+    const { response } = await request(method, method === 'PUT' ? { bucket: 'b' } : undefined);
+    assert.equal(response.status, 401);
+  }
+  assert.deepEqual(state.bucketCalls, []);
+  assert.deepEqual(state.provisioned, []);
+});
+
+test('GET /api/bucket provisions the tenant and reads its bucket', async () => {
+  signIn();
+  const { context, response } = await request('GET');
+
+  assert.equal(response.status, 200);
+  assert.deepEqual(await response.json(), {
+    bucket: { tenantId: 'tenant-user-1', bucket: 'acme-docs' },
+  });
+  assert.deepEqual(state.provisioned, ['user-1']);
+  assert.equal(context.locals.tenant.id, 'tenant-user-1');
+  assert.deepEqual(state.bucketCalls, [['get', 'tenant-user-1']]);
+});
+
+test('PUT and DELETE /api/bucket act on the session tenant', async () => {
+  signIn();
+  const put = await request('PUT', { bucket: 'own-docs' });
+  const del = await request('DELETE');
+
+  assert.equal(put.response.status, 200);
+  assert.equal(del.response.status, 200);
+  assert.deepEqual(state.bucketCalls, [
+    ['set', 'tenant-user-1'],
+    ['delete', 'tenant-user-1'],
+  ]);
+});
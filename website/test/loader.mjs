@@ -0,0 +1,19 @@
+// Resolve hook pointing the modules only Astro and Workers provide, and the
+// libraries that reach D1, at test/stubs.mjs
+const stubs = new URL('./stubs.mjs', import.meta.url).href;
+
+const stubbedSpecifiers = new Set(['astro:middleware', 'cloudflare:workers']);
+const stubbedModules = ['/src/lib/auth', '/src/lib/tenant', '/src/lib/tenant-buckets'];
+
+export async function resolve(specifier, context, nextResolve) {
+  if (stubbedSpecifiers.has(specifier)) {
+    return { url: stubs, shortCircuit: true };
+  }
+  if (specifier.startsWith('.') && context.parentURL) {
+    const { pathname } = new URL(specifier, context.parentURL);
+    if (stubbedModules.some((module) => pathname.endsWith(module))) {
+      return { url: stubs, shortCircuit: true };
+    }
+  }
+  return nextResolve(specifier, context);
+}
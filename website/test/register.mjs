@@ -0,0 +1,4 @@
+// Loaded with --import: swaps the Astro and Cloudflare runtime modules for stubs
+import { register } from 'node:module';
+
+register('./loader.mjs', import.meta.url);
@@ -0,0 +1,48 @@
+// Stand-ins for astro:middleware, cloudflare:workers, lib/auth, lib/tenant
+// and lib/tenant-buckets; tests arrange and inspect them through `state`
+export const state = {
+  session: null,
+  provisioned: [],
+  bucketCalls: [],
+};
+
+export function reset() {
+  state.session = null;
+  state.provisioned = [];
+  state.bucketCalls = [];
+}
+
+// astro:middleware
+export const defineMiddleware = (middleware) => middleware;
+
+// cloudflare:workers
+export const env = { DB: {}, BUCKET_CREDENTIALS_KEY: 'test-key' };
+
+// lib/auth
+export const createAuth = () => ({
+  api: { getSession: async () => state.session },
+});
+
+// lib/tenant
+export async function getOrCreateTenant(_db, userId, name) {
+  state.provisioned.push(userId);
+  return { id: `tenant-${userId}`, name };
+}
+
+// lib/tenant-buckets
+export const validateBucket = () => null;
+
+export async function getTenantBucket(_db, tenantId) {
+  state.bucketCalls.push(['get', tenantId]);
+  return { tenantId, bucket: 'acme-docs' };
+}
+
+export async function setTenantBucket(_db, _key, tenantId, input) {
+  state.bucketCalls.push(['set', tenantId]);
+  return { tenantId, bucket: input.bucket };
+}
+
+export async function deleteTenantBucket(_db, tenantId) {
+  state.bucketCalls.push(['delete', tenantId]);
+  return true;
+}